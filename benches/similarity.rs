@@ -0,0 +1,65 @@
+//! SIMD vs scalar cosine similarity on 1024-dimensional embeddings
+//!
+//! ```bash
+//! cargo bench --bench similarity
+//! ```
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+use kodegen_candle_agent::memory::core::ops::similarity::{
+    cosine, cosine_scalar, top_k_by_similarity,
+};
+
+const DIMENSION: usize = 1024;
+const CANDIDATES: usize = 200;
+
+/// Deterministic pseudo-random vector
+fn vector(seed: u32) -> Vec<f32> {
+    let mut state = seed.wrapping_mul(2_654_435_761).max(1);
+    (0..DIMENSION)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state as f32 / u32::MAX as f32) * 2.0 - 1.0
+        })
+        .collect()
+}
+
+fn bench_pair(c: &mut Criterion) {
+    let (a, b) = (vector(1), vector(2));
+    let mut group = c.benchmark_group("cosine_1024");
+    group.bench_function("simd", |bench| {
+        bench.iter(|| cosine(black_box(&a), black_box(&b)))
+    });
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| cosine_scalar(black_box(&a), black_box(&b)))
+    });
+    group.finish();
+}
+
+fn bench_rerank(c: &mut Criterion) {
+    let query = vector(0);
+    let candidates: Vec<Vec<f32>> = (1..=CANDIDATES as u32).map(vector).collect();
+    let mut group = c.benchmark_group("rerank_200x1024");
+    group.bench_function("simd", |bench| {
+        bench.iter(|| top_k_by_similarity(black_box(&query), black_box(&candidates), 10))
+    });
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| {
+            let mut scored: Vec<(usize, f32)> = candidates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i, cosine_scalar(black_box(&query), c)))
+                .collect();
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+            scored.truncate(10);
+            scored
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_pair, bench_rerank);
+criterion_main!(benches);
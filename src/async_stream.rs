@@ -5,6 +5,9 @@
 
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::Instrument;
+
+use crate::runtime::spawn_with_request_id;
 
 // Re-export commonly used tokio_stream types
 pub use tokio_stream::wrappers::ReceiverStream;
@@ -12,6 +15,8 @@ pub use tokio_stream::{Stream, StreamExt};
 
 /// Create a stream from a spawned async task
 ///
+/// The task keeps the caller's tracing span and request ID.
+///
 /// # Example
 /// ```rust
 /// let stream = spawn_stream(|tx| async move {
@@ -27,7 +32,7 @@ where
     Fut: std::future::Future<Output = ()> + Send + 'static,
 {
     let (tx, rx) = mpsc::unbounded_channel();
-    tokio::spawn(f(tx));
+    spawn_with_request_id(f(tx).instrument(tracing::Span::current()));
     UnboundedReceiverStream::new(rx)
 }
 
@@ -148,22 +148,7 @@ impl<const TOOLS_CAPACITY: usize> AgentBuilder<TOOLS_CAPACITY> {
                 // Create memory tool
                 let memory_tool = crate::memory::MemoryTool::new(memory.clone());
 
-                // Convert tools with zero-allocation
-                let tools = match self.tools.len() {
-                    0 => crate::ZeroOneOrMany::None,
-                    1 => match self.tools.into_iter().next() {
-                        Some(tool) => crate::ZeroOneOrMany::One(tool),
-                        None => {
-                            return Err(AgentError::InitializationError(
-                                "Expected one tool but found none".to_string(),
-                            ));
-                        }
-                    },
-                    _ => {
-                        let tools_vec: Vec<_> = self.tools.into_iter().collect();
-                        crate::ZeroOneOrMany::Many(tools_vec)
-                    }
-                };
+                let tools: crate::ZeroOneOrMany<_> = self.tools.into_iter().collect();
 
                 Ok(Agent {
                     model,
@@ -5,6 +5,12 @@ use crate::domain::chat::config::{
     CandleBehaviorConfig, CandleChatConfig, CandleModelConfig, CandleModelPerformanceConfig,
     CandleModelRetryConfig, CandlePersonalityConfig, CandleUIConfig,
 };
+use crate::domain::agent::prompt_guard::{PromptGuard, ProtectedPrompt};
+use crate::domain::completion::SamplingSettings;
+use crate::domain::model::defaults::{
+    GenerationOverrides, ResolvedGeneration, apply_system_prompt_prefix, resolve_generation,
+};
+use crate::domain::model::CandleModelError;
 use crate::domain::model::traits::CandleModel;
 use std::time::Duration;
 
@@ -20,15 +26,25 @@ pub struct CandleAgentBuilderImpl {
     pub(super) name: String,
     pub(super) text_to_text_model: TextToTextModel,
     pub(super) text_embedding_model: Option<TextEmbeddingModel>,
-    pub(super) temperature: f64,
-    pub(super) max_tokens: u64,
+    /// Unset values fall back to model defaults (see `resolve_generation`)
+    pub(super) temperature: Option<f64>,
+    pub(super) max_tokens: Option<u64>,
     pub(super) memory_read_timeout: u64,
+    pub(super) memory_read: bool,
+    pub(super) memory_write: bool,
+    /// Shared libraries read on every turn, in addition to the agent's memory
+    pub(super) recall_libraries: Vec<RecallLibrary>,
+    /// Where recalled context goes in the prompt, and its relevance cutoff
+    pub(super) memory_injection: MemoryInjection,
+    /// Attach the memories that informed each answer to its `Complete` chunk
+    pub(super) cite_memories: bool,
     pub(super) system_prompt: String,
     pub(super) tools: ZeroOneOrMany<ToolInfo>,
     pub(super) context_file: Option<CandleContext<CandleFile>>,
     pub(super) context_files: Option<CandleContext<CandleFiles>>,
     pub(super) context_directory: Option<CandleContext<CandleDirectory>>,
     pub(super) context_github: Option<CandleContext<CandleGithub>>,
+    pub(super) context_sql: Option<CandleContext<CandleSql>>,
     pub(super) additional_params: std::collections::HashMap<String, String>,
     pub(super) metadata: std::collections::HashMap<String, String>,
     pub(super) on_chunk_handler: Option<OnChunkHandler>,
@@ -36,6 +52,22 @@ pub struct CandleAgentBuilderImpl {
     pub(super) on_conversation_turn_handler: Option<OnConversationTurnHandler>,
     pub(super) conversation_history: ZeroOneOrMany<(CandleMessageRole, String)>,
     pub(super) stop_sequences: Vec<String>,
+    pub(super) protect_system_prompt: bool,
+    pub(super) injection_policy: Option<InjectionPolicy>,
+    /// Stops the turn mid-stream when triggered by the caller
+    pub(super) interrupt: Option<TurnInterrupt>,
+    /// Per-turn trace chunks sent before each reply
+    pub(super) trace_verbosity: Verbosity,
+    /// User profile learned from messages and applied to every system prompt
+    pub(super) profile_memory: Option<ProfileMemory>,
+    /// Confidence scoring attached to each answer
+    pub(super) confidence: Option<ConfidenceEstimator>,
+    /// Text every reply starts with
+    pub(super) assistant_prefix: Option<String>,
+    /// JSON schema every reply is constrained to
+    pub(super) response_format: Option<ResponseFormat>,
+    /// Why the model passed to `.model(...)` could not be resolved
+    pub(super) model_error: Option<CandleModelError>,
 }
 
 impl std::fmt::Debug for CandleAgentBuilderImpl {
@@ -47,6 +79,11 @@ impl std::fmt::Debug for CandleAgentBuilderImpl {
             .field("temperature", &self.temperature)
             .field("max_tokens", &self.max_tokens)
             .field("memory_read_timeout", &self.memory_read_timeout)
+            .field("memory_read", &self.memory_read)
+            .field("memory_write", &self.memory_write)
+            .field("recall_libraries", &self.recall_libraries)
+            .field("memory_injection", &self.memory_injection)
+            .field("cite_memories", &self.cite_memories)
             .field(
                 "system_prompt",
                 &format!(
@@ -57,6 +94,15 @@ impl std::fmt::Debug for CandleAgentBuilderImpl {
             .field("tools", &self.tools)
             .field("additional_params", &self.additional_params)
             .field("metadata", &self.metadata)
+            .field("protect_system_prompt", &self.protect_system_prompt)
+            .field("injection_policy", &self.injection_policy)
+            .field("interrupt", &self.interrupt)
+            .field("trace_verbosity", &self.trace_verbosity)
+            .field("profile_memory", &self.profile_memory)
+            .field("confidence", &self.confidence)
+            .field("assistant_prefix", &self.assistant_prefix)
+            .field("response_format", &self.response_format)
+            .field("model_error", &self.model_error)
             .finish()
     }
 }
@@ -67,8 +113,14 @@ impl CandleAgentRoleBuilder for CandleAgentBuilderImpl {
         CandleAgentRoleBuilderImpl::new(name)
     }
 
-    fn model(mut self, model: TextToTextModel) -> impl CandleAgentRoleBuilder {
-        self.text_to_text_model = model;
+    fn model(mut self, model: impl IntoTextToTextModel) -> impl CandleAgentRoleBuilder {
+        match model.into_text_to_text_model() {
+            Ok(model) => {
+                self.text_to_text_model = model;
+                self.model_error = None;
+            }
+            Err(e) => self.model_error = Some(e),
+        }
         self
     }
 
@@ -78,12 +130,12 @@ impl CandleAgentRoleBuilder for CandleAgentBuilderImpl {
     }
 
     fn temperature(mut self, temp: f64) -> impl CandleAgentRoleBuilder {
-        self.temperature = temp;
+        self.temperature = Some(temp);
         self
     }
 
     fn max_tokens(mut self, max: u64) -> impl CandleAgentRoleBuilder {
-        self.max_tokens = max;
+        self.max_tokens = Some(max);
         self
     }
 
@@ -92,6 +144,35 @@ impl CandleAgentRoleBuilder for CandleAgentBuilderImpl {
         self
     }
 
+    fn memory_read(mut self, enabled: bool) -> impl CandleAgentRoleBuilder {
+        self.memory_read = enabled;
+        self
+    }
+
+    fn memory_write(mut self, enabled: bool) -> impl CandleAgentRoleBuilder {
+        self.memory_write = enabled;
+        self
+    }
+
+    fn recall_libraries<L, I>(mut self, libraries: I) -> impl CandleAgentRoleBuilder
+    where
+        L: Into<RecallLibrary>,
+        I: IntoIterator<Item = L>,
+    {
+        self.recall_libraries = libraries.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn memory_injection(mut self, injection: impl Into<MemoryInjection>) -> impl CandleAgentRoleBuilder {
+        self.memory_injection = injection.into();
+        self
+    }
+
+    fn cite_memories(mut self, enabled: bool) -> impl CandleAgentRoleBuilder {
+        self.cite_memories = enabled;
+        self
+    }
+
     fn system_prompt(mut self, prompt: impl Into<String>) -> impl CandleAgentRoleBuilder {
         self.system_prompt = prompt.into();
         self
@@ -135,6 +216,11 @@ impl CandleAgentRoleBuilder for CandleAgentBuilderImpl {
         self
     }
 
+    fn context_sql(mut self, context: CandleContext<CandleSql>) -> impl CandleAgentRoleBuilder {
+        self.context_sql = Some(context);
+        self
+    }
+
     fn tools<T>(mut self, tools: T) -> impl CandleAgentRoleBuilder
     where
         T: Into<ZeroOneOrMany<ToolInfo>>,
@@ -212,7 +298,7 @@ impl CandleAgentRoleBuilder for CandleAgentBuilderImpl {
         mut self,
         history: impl ConversationHistoryArgs,
     ) -> impl CandleAgentRoleBuilder {
-        self.conversation_history = history.into_history();
+        self.conversation_history = history.into_history().into();
         self
     }
 
@@ -250,13 +336,104 @@ impl CandleAgentRoleBuilder for CandleAgentBuilderImpl {
     }
 
     fn into_agent(self) -> Result<impl CandleAgentBuilder, AgentError> {
+        self.validate_model()?;
+        self.validate_sampling()?;
+        self.validate_response_format()?;
         Ok(self)
     }
 }
 
 impl CandleAgentBuilderImpl {
+    /// Validate temperature and sampling `additional_params` before chatting
+    ///
+    /// Ignored settings are logged as warnings; contradictory or out-of-range
+    /// settings are rejected so they never reach the sampler.
+    pub(crate) fn validate_sampling(&self) -> Result<(), AgentError> {
+        let param = |key: &str| self.additional_params.get(key).map(|v| v.trim());
+        let generation = self.resolve_generation()?;
+
+        let settings = SamplingSettings {
+            temperature: generation.temperature,
+            top_k: param("top_k").and_then(|v| v.parse().ok()),
+            top_p: param("top_p").and_then(|v| v.parse().ok()),
+            repeat_penalty: param("repeat_penalty").and_then(|v| v.parse().ok()),
+            repeat_last_n: param("repeat_last_n").and_then(|v| v.parse().ok()),
+        };
+        let context_length = self
+            .text_to_text_model
+            .info()
+            .max_input_tokens
+            .map(std::num::NonZeroU32::get);
+
+        let warnings = settings
+            .validate(context_length)
+            .map_err(|e| AgentError::Config(e.to_string()))?;
+        for warning in warnings {
+            log::warn!("Agent '{}': {}", self.name, warning.message);
+        }
+        Ok(())
+    }
+
+    /// Report a model passed to `.model(...)` that is not registered
+    pub(crate) fn validate_model(&self) -> Result<(), AgentError> {
+        match &self.model_error {
+            Some(e) => Err(AgentError::Config(e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Reject a response format on an agent that offers tools
+    ///
+    /// The schema mask blocks `<tool_call>`, so the tools could never be
+    /// called. A tool-result handler brings in the kodegen tools too.
+    pub(crate) fn validate_response_format(&self) -> Result<(), AgentError> {
+        let offers_tools = !self.tools.is_empty() || self.on_tool_result_handler.is_some();
+        if self.response_format.is_some() && offers_tools {
+            return Err(AgentError::Config(
+                "response_format cannot be combined with tools".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resolve unset generation parameters from the model's defaults
+    ///
+    /// `top_k` and `top_p` may be set through `additional_params`.
+    ///
+    /// # Errors
+    /// Returns `AgentError::Config` if the model requires `max_tokens` and
+    /// none was set
+    pub(crate) fn resolve_generation(&self) -> Result<ResolvedGeneration, AgentError> {
+        let param = |key: &str| self.additional_params.get(key).map(|v| v.trim());
+        let overrides = GenerationOverrides {
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            top_k: param("top_k").and_then(|v| v.parse().ok()),
+            top_p: param("top_p").and_then(|v| v.parse().ok()),
+        };
+        resolve_generation(self.text_to_text_model.info(), &overrides)
+            .map_err(|e| AgentError::Config(e.to_string()))
+    }
+
+    /// Build the prompt guard from the protection options
+    ///
+    /// The system prompt digest is taken here, from the builder's own prompt
+    /// rather than the session's model config, so it is an independent record
+    /// that each turn's assembled prompt is checked against.
+    pub(crate) fn build_prompt_guard(&self) -> PromptGuard {
+        let system_prompt =
+            apply_system_prompt_prefix(self.text_to_text_model.info(), &self.system_prompt)
+                .unwrap_or_default();
+        PromptGuard {
+            protected: self
+                .protect_system_prompt
+                .then(|| ProtectedPrompt::new(system_prompt)),
+            injection: self.injection_policy.clone(),
+        }
+    }
+
     /// Build CandleModelConfig by merging model defaults with builder overrides
-    pub(crate) fn build_model_config(&self) -> CandleModelConfig {
+    pub(crate) fn build_model_config(&self, generation: &ResolvedGeneration) -> CandleModelConfig {
         // Get model info which contains defaults
         let model_info = self.text_to_text_model.info();
 
@@ -266,26 +443,22 @@ impl CandleAgentBuilderImpl {
             registry_key: model_info.registry_key.to_string(),
             model_version: model_info.real_name.as_ref().map(|s| s.to_string()),
 
-            // Temperature: builder override > model default > fallback 0.7
-            temperature: self.temperature as f32,
+            // Temperature: builder override > model default > greedy fallback
+            temperature: generation.temperature as f32,
 
-            // Max tokens: builder value > model max_output_tokens > fallback 2048
-            max_tokens: Some(self.max_tokens as u32),
+            // Max tokens: builder value > model max_output_tokens > fallback 2000
+            max_tokens: Some(u32::try_from(generation.max_tokens).unwrap_or(u32::MAX)),
 
-            // Sampling parameters (not in builder, use model defaults or standard)
-            top_p: model_info.default_top_p.map(|p| p as f32).or(Some(1.0)),
-            top_k: model_info.default_top_k,
+            // Sampling parameters: additional_params > model defaults
+            top_p: generation.top_p.map(|p| p as f32),
+            top_k: generation.top_k,
             frequency_penalty: Some(0.0),
             presence_penalty: Some(0.0),
 
             stop_sequences: self.stop_sequences.clone(),
 
-            // System prompt from builder
-            system_prompt: if self.system_prompt.is_empty() {
-                model_info.system_prompt_prefix.clone()
-            } else {
-                Some(self.system_prompt.clone())
-            },
+            // System prompt from builder, behind the model's required prefix
+            system_prompt: apply_system_prompt_prefix(model_info, &self.system_prompt),
 
             // Function calling: enabled if tools present OR model supports it
             enable_functions: !self.tools.is_empty() || model_info.supports_function_calling,
@@ -1,12 +1,19 @@
 //! Simple builder setter methods for CandleAgentBuilder
 
 use super::super::*;
+use crate::domain::model::CandleModelError;
 
 pub(super) fn set_model(
     mut builder: CandleAgentBuilderImpl,
-    model: TextToTextModel,
+    model: Result<TextToTextModel, CandleModelError>,
 ) -> CandleAgentBuilderImpl {
-    builder.text_to_text_model = model;
+    match model {
+        Ok(model) => {
+            builder.text_to_text_model = model;
+            builder.model_error = None;
+        }
+        Err(e) => builder.model_error = Some(e),
+    }
     builder
 }
 
@@ -22,14 +29,14 @@ pub(super) fn set_temperature(
     mut builder: CandleAgentBuilderImpl,
     temp: f64,
 ) -> CandleAgentBuilderImpl {
-    builder.temperature = temp;
+    builder.temperature = Some(temp);
     builder
 }
 pub(super) fn set_max_tokens(
     mut builder: CandleAgentBuilderImpl,
     max: u64,
 ) -> CandleAgentBuilderImpl {
-    builder.max_tokens = max;
+    builder.max_tokens = Some(max);
     builder
 }
 
@@ -41,6 +48,46 @@ pub(super) fn set_memory_read_timeout(
     builder
 }
 
+pub(super) fn set_memory_read(
+    mut builder: CandleAgentBuilderImpl,
+    enabled: bool,
+) -> CandleAgentBuilderImpl {
+    builder.memory_read = enabled;
+    builder
+}
+
+pub(super) fn set_memory_write(
+    mut builder: CandleAgentBuilderImpl,
+    enabled: bool,
+) -> CandleAgentBuilderImpl {
+    builder.memory_write = enabled;
+    builder
+}
+
+pub(super) fn set_recall_libraries(
+    mut builder: CandleAgentBuilderImpl,
+    libraries: Vec<RecallLibrary>,
+) -> CandleAgentBuilderImpl {
+    builder.recall_libraries = libraries;
+    builder
+}
+
+pub(super) fn set_memory_injection(
+    mut builder: CandleAgentBuilderImpl,
+    injection: MemoryInjection,
+) -> CandleAgentBuilderImpl {
+    builder.memory_injection = injection;
+    builder
+}
+
+pub(super) fn set_cite_memories(
+    mut builder: CandleAgentBuilderImpl,
+    enabled: bool,
+) -> CandleAgentBuilderImpl {
+    builder.cite_memories = enabled;
+    builder
+}
+
 pub(super) fn set_system_prompt(
     mut builder: CandleAgentBuilderImpl,
     prompt: String,
@@ -92,6 +139,14 @@ pub(super) fn set_context(
     builder
 }
 
+pub(super) fn set_context_sql(
+    mut builder: CandleAgentBuilderImpl,
+    context: CandleContext<CandleSql>,
+) -> CandleAgentBuilderImpl {
+    builder.context_sql = Some(context);
+    builder
+}
+
 pub(super) fn set_tools<T>(mut builder: CandleAgentBuilderImpl, tools: T) -> CandleAgentBuilderImpl
 where
     T: Into<ZeroOneOrMany<ToolInfo>>,
@@ -116,6 +171,80 @@ pub(super) fn set_stop_sequences(
     builder
 }
 
+pub(super) fn set_protect_system_prompt(
+    mut builder: CandleAgentBuilderImpl,
+) -> CandleAgentBuilderImpl {
+    builder.protect_system_prompt = true;
+    builder
+}
+
+pub(super) fn set_injection_policy(
+    mut builder: CandleAgentBuilderImpl,
+    policy: InjectionPolicy,
+) -> CandleAgentBuilderImpl {
+    builder.injection_policy = Some(policy);
+    builder
+}
+
+pub(super) fn set_interrupt(
+    mut builder: CandleAgentBuilderImpl,
+    interrupt: TurnInterrupt,
+) -> CandleAgentBuilderImpl {
+    builder.interrupt = Some(interrupt);
+    builder
+}
+
+pub(super) fn set_session_id(
+    mut builder: CandleAgentBuilderImpl,
+    session_id: String,
+) -> CandleAgentBuilderImpl {
+    builder.metadata.insert(
+        crate::domain::chat::session::SESSION_ID_KEY.to_string(),
+        session_id,
+    );
+    builder
+}
+
+pub(super) fn set_trace_verbosity(
+    mut builder: CandleAgentBuilderImpl,
+    verbosity: Verbosity,
+) -> CandleAgentBuilderImpl {
+    builder.trace_verbosity = verbosity;
+    builder
+}
+
+pub(super) fn set_profile_memory(
+    mut builder: CandleAgentBuilderImpl,
+    profile: ProfileMemory,
+) -> CandleAgentBuilderImpl {
+    builder.profile_memory = Some(profile);
+    builder
+}
+
+pub(super) fn set_confidence(
+    mut builder: CandleAgentBuilderImpl,
+    estimator: ConfidenceEstimator,
+) -> CandleAgentBuilderImpl {
+    builder.confidence = Some(estimator);
+    builder
+}
+
+pub(super) fn set_assistant_prefix(
+    mut builder: CandleAgentBuilderImpl,
+    prefix: String,
+) -> CandleAgentBuilderImpl {
+    builder.assistant_prefix = (!prefix.is_empty()).then_some(prefix);
+    builder
+}
+
+pub(super) fn set_response_format(
+    mut builder: CandleAgentBuilderImpl,
+    format: ResponseFormat,
+) -> CandleAgentBuilderImpl {
+    builder.response_format = Some(format);
+    builder
+}
+
 pub(super) fn add_stop_sequence_impl(
     mut builder: CandleAgentBuilderImpl,
     sequence: String,
@@ -2,10 +2,55 @@
 
 use super::super::*;
 use crate::memory::core::manager::coordinator::MemoryCoordinator;
+use crate::memory::core::manager::pool::CoordinatorPool;
 use crate::memory::core::manager::surreal::SurrealDBMemoryManager;
+use crate::domain::model::traits::CandleModel;
 use surrealdb::engine::any::connect;
 
-pub(super) async fn initialize_memory_coordinator(
+/// Coordinators already opened in this process, by embedding model
+///
+/// Callers such as the CLI build a new agent for every turn; reusing the
+/// coordinator avoids reopening the database each time.
+static COORDINATORS: std::sync::LazyLock<
+    tokio::sync::Mutex<std::collections::HashMap<String, Arc<MemoryCoordinator>>>,
+> = std::sync::LazyLock::new(Default::default);
+
+/// Library pools already created in this process, by embedding model
+///
+/// Shared recall and the profile open their libraries through these, so
+/// later turns reuse the databases and models the first one opened.
+static POOLS: std::sync::LazyLock<
+    std::sync::Mutex<std::collections::HashMap<String, Arc<CoordinatorPool>>>,
+> = std::sync::LazyLock::new(Default::default);
+
+/// The process-wide library pool for `emb_model`
+pub(crate) fn shared_coordinator_pool(emb_model: &TextEmbeddingModel) -> Arc<CoordinatorPool> {
+    let key = emb_model.info().registry_key.to_string();
+    let mut pools = POOLS.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    pools
+        .entry(key)
+        .or_insert_with(|| Arc::new(CoordinatorPool::new(emb_model.clone())))
+        .clone()
+}
+
+/// Coordinator for the chat memory database, `agent.db` under the data dir
+///
+/// Chat sessions store turns and audit tool calls here; tools reading those
+/// back must open the same database rather than a pool library.
+pub(crate) async fn initialize_memory_coordinator(
+    emb_model: &TextEmbeddingModel,
+) -> Result<Arc<MemoryCoordinator>, String> {
+    let key = emb_model.info().registry_key.to_string();
+    let mut coordinators = COORDINATORS.lock().await;
+    if let Some(coordinator) = coordinators.get(&key) {
+        return Ok(coordinator.clone());
+    }
+    let coordinator = open_memory_coordinator(emb_model).await?;
+    coordinators.insert(key, coordinator.clone());
+    Ok(coordinator)
+}
+
+async fn open_memory_coordinator(
     emb_model: &TextEmbeddingModel,
 ) -> Result<Arc<MemoryCoordinator>, String> {
     let db_path = kodegen_config::KodegenConfig::data_dir()
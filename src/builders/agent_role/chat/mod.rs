@@ -9,15 +9,23 @@
 
 mod builder_methods;
 mod handler_registration;
+#[cfg(feature = "memory")]
 mod memory_ops;
 
+#[cfg(feature = "memory")]
+pub(crate) use memory_ops::initialize_memory_coordinator as chat_memory_coordinator;
+
 use super::*;
+#[cfg(feature = "memory")]
+use crate::domain::chat::profile::ProfileStore;
+#[cfg(feature = "memory")]
+use crate::domain::chat::recall::SharedRecall;
 use std::sync::Arc;
 use tokio_stream::StreamExt;
 
 impl CandleAgentBuilder for CandleAgentBuilderImpl {
-    fn model(self, model: TextToTextModel) -> impl CandleAgentBuilder {
-        builder_methods::set_model(self, model)
+    fn model(self, model: impl IntoTextToTextModel) -> impl CandleAgentBuilder {
+        builder_methods::set_model(self, model.into_text_to_text_model())
     }
 
     fn embedding_model(self, model: TextEmbeddingModel) -> impl CandleAgentBuilder {
@@ -44,10 +52,70 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
         builder_methods::set_memory_read_timeout(self, timeout_ms)
     }
 
+    fn memory_read(self, enabled: bool) -> impl CandleAgentBuilder {
+        builder_methods::set_memory_read(self, enabled)
+    }
+
+    fn memory_write(self, enabled: bool) -> impl CandleAgentBuilder {
+        builder_methods::set_memory_write(self, enabled)
+    }
+
+    fn recall_libraries<L, I>(self, libraries: I) -> impl CandleAgentBuilder
+    where
+        L: Into<RecallLibrary>,
+        I: IntoIterator<Item = L>,
+    {
+        builder_methods::set_recall_libraries(self, libraries.into_iter().map(Into::into).collect())
+    }
+
+    fn memory_injection(self, injection: impl Into<MemoryInjection>) -> impl CandleAgentBuilder {
+        builder_methods::set_memory_injection(self, injection.into())
+    }
+
+    fn cite_memories(self, enabled: bool) -> impl CandleAgentBuilder {
+        builder_methods::set_cite_memories(self, enabled)
+    }
+
     fn system_prompt(self, prompt: impl Into<String>) -> impl CandleAgentBuilder {
         builder_methods::set_system_prompt(self, prompt.into())
     }
 
+    fn protect_system_prompt(self) -> impl CandleAgentBuilder {
+        builder_methods::set_protect_system_prompt(self)
+    }
+
+    fn reject_prompt_injection(self, policy: InjectionPolicy) -> impl CandleAgentBuilder {
+        builder_methods::set_injection_policy(self, policy)
+    }
+
+    fn interruptible(self, interrupt: TurnInterrupt) -> impl CandleAgentBuilder {
+        builder_methods::set_interrupt(self, interrupt)
+    }
+
+    fn session_id(self, session_id: impl Into<String>) -> impl CandleAgentBuilder {
+        builder_methods::set_session_id(self, session_id.into())
+    }
+
+    fn trace_verbosity(self, verbosity: Verbosity) -> impl CandleAgentBuilder {
+        builder_methods::set_trace_verbosity(self, verbosity)
+    }
+
+    fn profile_memory(self, profile: ProfileMemory) -> impl CandleAgentBuilder {
+        builder_methods::set_profile_memory(self, profile)
+    }
+
+    fn confidence(self, estimator: ConfidenceEstimator) -> impl CandleAgentBuilder {
+        builder_methods::set_confidence(self, estimator)
+    }
+
+    fn assistant_prefix(self, prefix: impl Into<String>) -> impl CandleAgentBuilder {
+        builder_methods::set_assistant_prefix(self, prefix.into())
+    }
+
+    fn response_format<T: schemars::JsonSchema>(self) -> impl CandleAgentBuilder {
+        builder_methods::set_response_format(self, ResponseFormat::for_type::<T>())
+    }
+
     fn additional_params<P2>(self, params: P2) -> impl CandleAgentBuilder
     where
         P2: IntoIterator<Item = (&'static str, &'static str)>,
@@ -72,6 +140,10 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
         builder_methods::set_context(self, context1, context2, context3, context4)
     }
 
+    fn context_sql(self, context: CandleContext<CandleSql>) -> impl CandleAgentBuilder {
+        builder_methods::set_context_sql(self, context)
+    }
+
     fn tools<T>(self, tools: T) -> impl CandleAgentBuilder
     where
         T: Into<ZeroOneOrMany<ToolInfo>>,
@@ -125,9 +197,10 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
     }
 
     fn conversation_history(
-        self,
-        _history: impl ConversationHistoryArgs,
+        mut self,
+        history: impl ConversationHistoryArgs,
     ) -> impl CandleAgentBuilder {
+        self.conversation_history = history.into_history().into();
         self
     }
 
@@ -139,9 +212,15 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
         F: FnOnce(&CandleAgentConversation) -> Fut + Send + 'static,
         Fut: std::future::Future<Output = CandleChatLoop> + Send + 'static,
     {
+        self.validate_model()?;
+        self.validate_sampling()?;
+        self.validate_response_format()?;
+        let generation = self.resolve_generation()?;
+
         // Build configurations
-        let model_config = self.build_model_config();
+        let model_config = self.build_model_config(&generation);
         let chat_config = self.build_chat_config();
+        let prompt_guard = self.build_prompt_guard();
 
         // Extract all state from builder
         let provider = self.text_to_text_model;
@@ -149,6 +228,17 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
         let tools: Arc<[ToolInfo]> = Vec::from(self.tools).into();
         let metadata = self.metadata;
         let conversation_history = self.conversation_history;
+        let memory_read = self.memory_read;
+        let memory_write = self.memory_write;
+        let recall_libraries = self.recall_libraries;
+        let memory_injection = self.memory_injection;
+        let cite_memories = self.cite_memories;
+        let interrupt = self.interrupt;
+        let trace_verbosity = self.trace_verbosity;
+        let profile_memory = self.profile_memory;
+        let confidence = self.confidence;
+        let assistant_prefix = self.assistant_prefix;
+        let response_format = self.response_format;
 
         // Extract handlers
         let on_chunk_handler = self.on_chunk_handler;
@@ -160,10 +250,12 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
         let context_files = self.context_files;
         let context_directory = self.context_directory;
         let context_github = self.context_github;
+        let context_sql = self.context_sql;
 
         Ok(Box::pin(crate::async_stream::spawn_stream(
             move |sender| async move {
                 // Initialize memory manager if embedding model available
+                #[cfg(feature = "memory")]
                 let memory = if let Some(ref emb_model) = embedding_model {
                     match memory_ops::initialize_memory_coordinator(emb_model).await {
                         Ok(mgr) => mgr,
@@ -179,6 +271,35 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
                     return;
                 };
 
+                // Shared libraries and the profile are opened lazily through
+                // the process-wide pool, so each call reuses what earlier ones opened
+                #[cfg(feature = "memory")]
+                let pool = if recall_libraries.is_empty() && profile_memory.is_none() {
+                    None
+                } else {
+                    embedding_model
+                        .as_ref()
+                        .map(memory_ops::shared_coordinator_pool)
+                };
+                #[cfg(feature = "memory")]
+                let shared_recall = match &pool {
+                    Some(pool) if !recall_libraries.is_empty() => {
+                        Some(SharedRecall::new(Arc::clone(pool), recall_libraries))
+                    }
+                    _ => None,
+                };
+                #[cfg(feature = "memory")]
+                let profile = profile_memory
+                    .zip(pool)
+                    .map(|(config, pool)| ProfileStore::new(config, pool));
+
+                // Built without the memory core, the session runs without memory
+                #[cfg(not(feature = "memory"))]
+                let (memory, shared_recall, profile) = {
+                    let _ = (embedding_model, recall_libraries, profile_memory);
+                    ((), None, None)
+                };
+
                 // DELEGATE to domain::chat::session with raw context sources
                 let config = crate::domain::chat::session::ChatSessionConfig {
                     model_config,
@@ -187,12 +308,25 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
                     memory,
                     tools,
                     metadata,
+                    memory_read,
+                    shared_recall,
+                    memory_injection,
+                    cite_memories,
+                    memory_write,
+                    prompt_guard,
+                    interrupt,
+                    trace_verbosity,
+                    profile,
+                    confidence,
+                    assistant_prefix,
+                    response_format,
                 };
                 let contexts = crate::domain::chat::session::ChatSessionContexts {
                     context_file,
                     context_files,
                     context_directory,
                     context_github,
+                    context_sql,
                 };
                 let handlers = crate::domain::chat::session::ChatSessionHandlers {
                     on_chunk_handler,
@@ -237,4 +371,22 @@ impl CandleAgentBuilder for CandleAgentBuilderImpl {
             )),
         }
     }
+
+    fn handoff(
+        self,
+        mut document: HandoffDocument,
+    ) -> Pin<Box<dyn Stream<Item = CandleMessageChunk> + Send>> {
+        document.to = self.name.clone();
+        log::info!("Handoff from '{}' to '{}'", document.from, document.to);
+
+        let mut reply = CandleAgentBuilder::chat_with_message(self, document.brief());
+        Box::pin(crate::async_stream::spawn_stream(
+            move |sender| async move {
+                let _ = sender.send(CandleMessageChunk::Handoff(document));
+                while let Some(chunk) = reply.next().await {
+                    let _ = sender.send(chunk);
+                }
+            },
+        ))
+    }
 }
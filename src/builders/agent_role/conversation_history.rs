@@ -0,0 +1,175 @@
+//! Conversation history for `.conversation_history(...)`
+//!
+//! [`ConversationHistory`] is an ordered list of `(role, content)` messages.
+//! Anything implementing [`ConversationHistoryArgs`] converts into one: a
+//! single `(role, content)` pair, a `Vec` or array of pairs, or a tuple of
+//! up to twelve of those. Build longer histories with `push`/`extend` or the
+//! [`conversation_history!`](crate::conversation_history) macro.
+
+use super::{CandleMessageRole, ZeroOneOrMany};
+
+/// Ordered `(role, content)` messages seeding an agent conversation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversationHistory {
+    messages: Vec<(CandleMessageRole, String)>,
+}
+
+impl ConversationHistory {
+    /// Empty history
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a message
+    pub fn push(&mut self, role: CandleMessageRole, content: impl Into<String>) {
+        self.messages.push((role, content.into()));
+    }
+
+    /// History with a message appended
+    #[must_use]
+    pub fn with_message(mut self, role: CandleMessageRole, content: impl Into<String>) -> Self {
+        self.push(role, content);
+        self
+    }
+
+    /// Messages, oldest first
+    pub fn messages(&self) -> &[(CandleMessageRole, String)] {
+        &self.messages
+    }
+
+    /// Iterate over the messages, oldest first
+    pub fn iter(&self) -> std::slice::Iter<'_, (CandleMessageRole, String)> {
+        self.messages.iter()
+    }
+
+    /// Number of messages
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Whether there are no messages
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Take the messages, oldest first
+    pub fn into_messages(self) -> Vec<(CandleMessageRole, String)> {
+        self.messages
+    }
+}
+
+impl<S: Into<String>> Extend<(CandleMessageRole, S)> for ConversationHistory {
+    fn extend<I: IntoIterator<Item = (CandleMessageRole, S)>>(&mut self, iter: I) {
+        self.messages.extend(
+            iter.into_iter()
+                .map(|(role, content)| (role, content.into())),
+        );
+    }
+}
+
+impl<S: Into<String>> FromIterator<(CandleMessageRole, S)> for ConversationHistory {
+    fn from_iter<I: IntoIterator<Item = (CandleMessageRole, S)>>(iter: I) -> Self {
+        let mut history = Self::new();
+        history.extend(iter);
+        history
+    }
+}
+
+impl IntoIterator for ConversationHistory {
+    type Item = (CandleMessageRole, String);
+    type IntoIter = std::vec::IntoIter<(CandleMessageRole, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ConversationHistory {
+    type Item = &'a (CandleMessageRole, String);
+    type IntoIter = std::slice::Iter<'a, (CandleMessageRole, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<ConversationHistory> for ZeroOneOrMany<(CandleMessageRole, String)> {
+    fn from(history: ConversationHistory) -> Self {
+        ZeroOneOrMany::from(history.messages)
+    }
+}
+
+impl From<ZeroOneOrMany<(CandleMessageRole, String)>> for ConversationHistory {
+    fn from(messages: ZeroOneOrMany<(CandleMessageRole, String)>) -> Self {
+        Self {
+            messages: Vec::from(messages),
+        }
+    }
+}
+
+/// Values accepted by `.conversation_history(...)`
+pub trait ConversationHistoryArgs {
+    /// Convert this into conversation history
+    fn into_history(self) -> ConversationHistory;
+}
+
+impl ConversationHistoryArgs for ConversationHistory {
+    fn into_history(self) -> ConversationHistory {
+        self
+    }
+}
+
+impl ConversationHistoryArgs for (CandleMessageRole, &str) {
+    fn into_history(self) -> ConversationHistory {
+        ConversationHistory::new().with_message(self.0, self.1)
+    }
+}
+
+impl ConversationHistoryArgs for (CandleMessageRole, String) {
+    fn into_history(self) -> ConversationHistory {
+        ConversationHistory::new().with_message(self.0, self.1)
+    }
+}
+
+impl<S: Into<String>> ConversationHistoryArgs for Vec<(CandleMessageRole, S)> {
+    fn into_history(self) -> ConversationHistory {
+        self.into_iter().collect()
+    }
+}
+
+impl<S: Into<String>, const N: usize> ConversationHistoryArgs for [(CandleMessageRole, S); N] {
+    fn into_history(self) -> ConversationHistory {
+        self.into_iter().collect()
+    }
+}
+
+/// Implement [`ConversationHistoryArgs`] for a tuple by concatenating its parts in order
+macro_rules! impl_history_tuple {
+    ($($part:ident),+) => {
+        impl<$($part),+> ConversationHistoryArgs for ($($part,)+)
+        where
+            $($part: ConversationHistoryArgs,)+
+        {
+            #[allow(non_snake_case)]
+            fn into_history(self) -> ConversationHistory {
+                let ($($part,)+) = self;
+                let mut history = ConversationHistory::new();
+                $(history.extend($part.into_history());)+
+                history
+            }
+        }
+    };
+}
+
+impl_history_tuple!(T1, T2);
+impl_history_tuple!(T1, T2, T3);
+impl_history_tuple!(T1, T2, T3, T4);
+impl_history_tuple!(T1, T2, T3, T4, T5);
+impl_history_tuple!(T1, T2, T3, T4, T5, T6);
+impl_history_tuple!(T1, T2, T3, T4, T5, T6, T7);
+impl_history_tuple!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_history_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_history_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_history_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_history_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
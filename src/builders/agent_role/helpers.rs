@@ -2,10 +2,12 @@
 
 use super::*;
 use crate::capability::registry::TextToTextModel;
-use crate::capability::text_to_text::qwen3_quantized::LoadedQwen3QuantizedModel;
 use crate::domain::agent::core::AGENT_STATS;
+#[cfg(feature = "mcp")]
 use crate::domain::completion::types::ToolInfo;
-use crate::domain::tool::ToolSelector;
+#[cfg(feature = "mcp")]
+use crate::domain::tool::{ToolSelector, call_succeeded, tool_analytics};
+#[cfg(feature = "mcp")]
 use kodegen_mcp_client::create_stdio_client;
 
 pub struct CandleAgentRoleAgent {
@@ -32,6 +34,8 @@ impl CandleAgentRoleAgent {
                         token_count: None,
                         elapsed_secs: None,
                         tokens_per_sec: None,
+                        citations: Vec::new(),
+                        confidence: None,
                     };
                     let _ = sender.send(final_chunk);
                 }))
@@ -39,6 +43,14 @@ impl CandleAgentRoleAgent {
             CandleChatLoop::UserPrompt(user_message) | CandleChatLoop::Reprompt(user_message) => {
                 self.run_inference_cycle(user_message)
             }
+            // Turns run here are not recorded, so there is no previous turn to re-run
+            CandleChatLoop::Regenerate(_) => {
+                Box::pin(crate::async_stream::spawn_stream(|sender| async move {
+                    let _ = sender.send(CandleMessageChunk::Error(
+                        "Regenerate is only available in chat sessions".to_string(),
+                    ));
+                }))
+            }
         }
     }
 
@@ -57,7 +69,13 @@ impl CandleAgentRoleAgent {
                 // Only spawn kodegen for tool execution if tools are expected to be used
                 // Skip for pure inference use cases (like browser-agent) to avoid slow connection timeouts
                 let needs_tools = !state.tools.is_empty() || state.on_tool_result_handler.is_some();
-                
+
+                #[cfg(not(feature = "mcp"))]
+                if needs_tools {
+                    log::warn!("Built without the `mcp` feature - Agent will work without tools");
+                }
+
+                #[cfg(feature = "mcp")]
                 let mcp_client = if needs_tools {
                     match create_stdio_client("kodegen", &[]).await {
                         Ok((client, _connection)) => {
@@ -95,6 +113,7 @@ impl CandleAgentRoleAgent {
 
                 // Call provider
                 let prompt = CandlePrompt::new(full_prompt);
+                #[cfg_attr(not(feature = "mcp"), allow(unused_mut))]
                 let mut params = crate::domain::completion::CandleCompletionParams {
                     temperature: state.temperature,
                     max_tokens: std::num::NonZeroU64::new(state.max_tokens),
@@ -102,6 +121,7 @@ impl CandleAgentRoleAgent {
                 };
 
                 // Add tools
+                #[cfg(feature = "mcp")]
                 if let Some(ref client) = mcp_client {
                     let mut all_tools: Vec<ToolInfo> = state.tools.clone().into();
 
@@ -114,15 +134,20 @@ impl CandleAgentRoleAgent {
                         // ═══════════════════════════════════════════════════════════
                         // TOOL SELECTION: Filter to 2-3 most relevant tools
                         // ═══════════════════════════════════════════════════════════
-                        let final_tools = if all_tools.len() > 3 {
-                            // Extract model from enum
-                            let TextToTextModel::Qwen3Quantized(base_model) =
-                                &state.text_to_text_model;
-
-                            // Load model for tool selection
-                            match LoadedQwen3QuantizedModel::load(base_model).await {
+                        // Constrained selection runs on Qwen3; other models get every tool
+                        let final_tools = if all_tools.len() > 3
+                            && let TextToTextModel::Qwen3Quantized(base_model) =
+                                &state.text_to_text_model
+                        {
+                            // Reuse the shared loaded model (loads once on first use)
+                            match state.loaded_model.get_or_load(base_model).await {
                                 Ok(loaded_model) => {
-                                    let selector = ToolSelector::new(Arc::new(loaded_model));
+                                    let mut selector = ToolSelector::new(loaded_model)
+                                        .with_usage_hints(tool_analytics());
+                                    if let Some(ref embedding_model) = state.text_embedding_model {
+                                        selector = selector
+                                            .with_embedding_prefilter(embedding_model.clone());
+                                    }
                                     match selector.select_tools(&user_message, &all_tools).await {
                                         Ok(selected_names) => {
                                             // Filter to selected tools only
@@ -153,7 +178,7 @@ impl CandleAgentRoleAgent {
                                 }
                             }
                         } else {
-                            // 3 or fewer tools - no selection needed
+                            // 3 or fewer tools (or no Qwen3) - no selection needed
                             all_tools
                         };
 
@@ -179,6 +204,7 @@ impl CandleAgentRoleAgent {
                             token_count,
                             elapsed_secs,
                             tokens_per_sec,
+                            ..
                         } => {
                             assistant_response.push_str(text);
 
@@ -201,6 +227,8 @@ impl CandleAgentRoleAgent {
                                 token_count,
                                 elapsed_secs,
                                 tokens_per_sec,
+                                citations: Vec::new(),
+                                confidence: None,
                             }
                         }
                         CandleCompletionChunk::ToolCallStart { id, name } => {
@@ -215,12 +243,27 @@ impl CandleAgentRoleAgent {
                             name,
                             partial_input,
                         },
+                        #[cfg(not(feature = "mcp"))]
+                        CandleCompletionChunk::ToolCallComplete { name, .. } => {
+                            CandleMessageChunk::Error(format!(
+                                "Tool '{name}' not available: built without the `mcp` feature"
+                            ))
+                        }
+                        #[cfg(feature = "mcp")]
                         CandleCompletionChunk::ToolCallComplete { id: _, name, input } => {
                             // Execute tool via MCP client
                             if let Some(ref client) = mcp_client {
                                 match serde_json::from_str::<serde_json::Value>(&input) {
                                     Ok(args_json) => {
-                                        match client.call_tool(&name, args_json).await {
+                                        let start = std::time::Instant::now();
+                                        let result = client.call_tool(&name, args_json).await;
+                                        tool_analytics().record(
+                                            &name,
+                                            start.elapsed(),
+                                            call_succeeded(&result),
+                                        );
+
+                                        match result {
                                             Ok(result) => {
                                                 // Format tool result as text for LLM to see
                                                 let result_str = serde_json::to_string_pretty(&result)
@@ -277,100 +320,6 @@ impl CandleAgentRoleAgent {
     }
 }
 
-pub trait ConversationHistoryArgs {
-    /// Convert this into conversation history format
-    fn into_history(self) -> ZeroOneOrMany<(CandleMessageRole, String)>;
-}
-
-impl ConversationHistoryArgs for (CandleMessageRole, &str) {
-    fn into_history(self) -> ZeroOneOrMany<(CandleMessageRole, String)> {
-        ZeroOneOrMany::one((self.0, self.1.to_string()))
-    }
-}
-
-impl ConversationHistoryArgs for (CandleMessageRole, String) {
-    fn into_history(self) -> ZeroOneOrMany<(CandleMessageRole, String)> {
-        ZeroOneOrMany::one(self)
-    }
-}
-
-impl<T1, T2> ConversationHistoryArgs for (T1, T2)
-where
-    T1: ConversationHistoryArgs,
-    T2: ConversationHistoryArgs,
-{
-    fn into_history(self) -> ZeroOneOrMany<(CandleMessageRole, String)> {
-        let h1 = self.0.into_history();
-        let h2 = self.1.into_history();
-
-        match (h1, h2) {
-            (ZeroOneOrMany::None, h) | (h, ZeroOneOrMany::None) => h,
-            (ZeroOneOrMany::One(m1), ZeroOneOrMany::One(m2)) => ZeroOneOrMany::Many(vec![m1, m2]),
-            (ZeroOneOrMany::One(m), ZeroOneOrMany::Many(mut msgs)) => {
-                msgs.insert(0, m);
-                ZeroOneOrMany::Many(msgs)
-            }
-            (ZeroOneOrMany::Many(mut msgs), ZeroOneOrMany::One(m)) => {
-                msgs.push(m);
-                ZeroOneOrMany::Many(msgs)
-            }
-            (ZeroOneOrMany::Many(mut msgs1), ZeroOneOrMany::Many(msgs2)) => {
-                msgs1.extend(msgs2);
-                ZeroOneOrMany::Many(msgs1)
-            }
-        }
-    }
-}
-
-impl<T1, T2, T3> ConversationHistoryArgs for (T1, T2, T3)
-where
-    T1: ConversationHistoryArgs,
-    T2: ConversationHistoryArgs,
-    T3: ConversationHistoryArgs,
-{
-    fn into_history(self) -> ZeroOneOrMany<(CandleMessageRole, String)> {
-        let h1 = self.0.into_history();
-        let h2 = self.1.into_history();
-        let h3 = self.2.into_history();
-
-        // Merge all three by first merging h1 and h2, then merging result with h3
-        let combined_12 = match (h1, h2) {
-            (ZeroOneOrMany::None, h) | (h, ZeroOneOrMany::None) => h,
-            (ZeroOneOrMany::One(m1), ZeroOneOrMany::One(m2)) => ZeroOneOrMany::Many(vec![m1, m2]),
-            (ZeroOneOrMany::One(m), ZeroOneOrMany::Many(mut msgs)) => {
-                msgs.insert(0, m);
-                ZeroOneOrMany::Many(msgs)
-            }
-            (ZeroOneOrMany::Many(mut msgs), ZeroOneOrMany::One(m)) => {
-                msgs.push(m);
-                ZeroOneOrMany::Many(msgs)
-            }
-            (ZeroOneOrMany::Many(mut msgs1), ZeroOneOrMany::Many(msgs2)) => {
-                msgs1.extend(msgs2);
-                ZeroOneOrMany::Many(msgs1)
-            }
-        };
-
-        // Now merge combined_12 with h3
-        match (combined_12, h3) {
-            (ZeroOneOrMany::None, h) | (h, ZeroOneOrMany::None) => h,
-            (ZeroOneOrMany::One(m1), ZeroOneOrMany::One(m2)) => ZeroOneOrMany::Many(vec![m1, m2]),
-            (ZeroOneOrMany::One(m), ZeroOneOrMany::Many(mut msgs)) => {
-                msgs.insert(0, m);
-                ZeroOneOrMany::Many(msgs)
-            }
-            (ZeroOneOrMany::Many(mut msgs), ZeroOneOrMany::One(m)) => {
-                msgs.push(m);
-                ZeroOneOrMany::Many(msgs)
-            }
-            (ZeroOneOrMany::Many(mut msgs1), ZeroOneOrMany::Many(msgs2)) => {
-                msgs1.extend(msgs2);
-                ZeroOneOrMany::Many(msgs1)
-            }
-        }
-    }
-}
-
 /// CandleFluentAi entry point for creating agent roles
 pub struct CandleFluentAi;
 
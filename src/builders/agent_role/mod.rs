@@ -2,26 +2,41 @@
 
 mod agent_builder;
 mod chat;
+mod conversation_history;
 mod helpers;
 mod role_builder;
 mod role_builder_impl;
 mod traits;
 
-pub(crate) use crate::capability::registry::{TextEmbeddingModel, TextToTextModel};
+pub(crate) use crate::capability::registry::{
+    IntoTextToTextModel, TextEmbeddingModel, TextToTextModel,
+};
+pub(crate) use crate::capability::text_to_text::LoadedModelHandle;
 pub(crate) use crate::capability::traits::TextToTextCapable;
 pub(crate) use crate::domain::agent::core::AgentError;
+pub(crate) use crate::domain::agent::prompt_guard::InjectionPolicy;
 pub(crate) use crate::domain::agent::role::CandleAgentConversation;
 pub(crate) use crate::domain::chat::CandleChatLoop;
+pub(crate) use crate::domain::chat::confidence::ConfidenceEstimator;
+pub(crate) use crate::domain::chat::handoff::HandoffDocument;
+pub(crate) use crate::domain::chat::injection::MemoryInjection;
+pub(crate) use crate::domain::chat::interrupt::TurnInterrupt;
+pub(crate) use crate::domain::chat::profile::ProfileMemory;
+pub(crate) use crate::domain::chat::recall::RecallLibrary;
+pub(crate) use crate::domain::chat::trace::Verbosity;
 pub(crate) use crate::domain::chat::message::{CandleMessageChunk, CandleMessageRole};
-pub(crate) use crate::domain::completion::CandleCompletionChunk;
+pub(crate) use crate::domain::completion::{CandleCompletionChunk, ResponseFormat};
 pub(crate) use crate::domain::completion::types::ToolInfo;
 pub(crate) use crate::domain::context::provider::{
-    CandleContext, CandleDirectory, CandleFile, CandleFiles, CandleGithub,
+    CandleContext, CandleDirectory, CandleFile, CandleFiles, CandleGithub, CandleSql,
 };
 pub(crate) use crate::domain::prompt::CandlePrompt;
 pub use agent_builder::{AgentDebugInfo, CandleAgentBuilderImpl};
+#[cfg(feature = "memory")]
+pub(crate) use chat::chat_memory_coordinator;
 pub(crate) use cyrup_sugars::ZeroOneOrMany;
-pub use helpers::{CandleAgentRoleAgent, CandleFluentAi, ConversationHistoryArgs};
+pub use conversation_history::{ConversationHistory, ConversationHistoryArgs};
+pub use helpers::{CandleAgentRoleAgent, CandleFluentAi};
 pub use role_builder::CandleAgentRoleBuilderImpl;
 pub use role_builder_impl::{CandleMcpServerBuilderImpl, McpServerConfig};
 pub(crate) use serde_json;
@@ -57,9 +72,13 @@ pub struct AgentBuilderState {
     pub name: String,
     pub text_to_text_model: TextToTextModel,
     pub text_embedding_model: Option<TextEmbeddingModel>,
+    /// Shared loaded instance of `text_to_text_model` used for tool selection
+    pub loaded_model: LoadedModelHandle,
     pub temperature: f64,
     pub max_tokens: u64,
     pub memory_read_timeout: u64,
+    pub memory_read: bool,
+    pub memory_write: bool,
     pub system_prompt: String,
     pub tools: ZeroOneOrMany<ToolInfo>,
     pub context_file: Option<CandleContext<CandleFile>>,
@@ -7,15 +7,24 @@ pub struct CandleAgentRoleBuilderImpl {
     pub(super) name: String,
     pub(super) text_to_text_model: Option<TextToTextModel>,
     pub(super) text_embedding_model: Option<TextEmbeddingModel>,
-    pub(super) temperature: f64,
+    pub(super) temperature: Option<f64>,
     pub(super) max_tokens: Option<u64>,
     pub(super) memory_read_timeout: u64,
+    pub(super) memory_read: bool,
+    pub(super) memory_write: bool,
+    /// Shared libraries read on every turn, in addition to the agent's memory
+    pub(super) recall_libraries: Vec<RecallLibrary>,
+    /// Where recalled context goes in the prompt, and its relevance cutoff
+    pub(super) memory_injection: MemoryInjection,
+    /// Attach the memories that informed each answer to its `Complete` chunk
+    pub(super) cite_memories: bool,
     pub(super) system_prompt: String,
     pub(super) tools: ZeroOneOrMany<ToolInfo>,
     pub(super) context_file: Option<CandleContext<CandleFile>>,
     pub(super) context_files: Option<CandleContext<CandleFiles>>,
     pub(super) context_directory: Option<CandleContext<CandleDirectory>>,
     pub(super) context_github: Option<CandleContext<CandleGithub>>,
+    pub(super) context_sql: Option<CandleContext<CandleSql>>,
     pub(super) additional_params: std::collections::HashMap<String, String>,
     pub(super) metadata: std::collections::HashMap<String, String>,
     pub(super) on_chunk_handler: Option<OnChunkHandler>,
@@ -32,6 +41,11 @@ impl std::fmt::Debug for CandleAgentRoleBuilderImpl {
             .field("temperature", &self.temperature)
             .field("max_tokens", &self.max_tokens)
             .field("memory_read_timeout", &self.memory_read_timeout)
+            .field("memory_read", &self.memory_read)
+            .field("memory_write", &self.memory_write)
+            .field("recall_libraries", &self.recall_libraries)
+            .field("memory_injection", &self.memory_injection)
+            .field("cite_memories", &self.cite_memories)
             .field(
                 "system_prompt",
                 &format!(
@@ -51,9 +65,14 @@ impl CandleAgentRoleBuilderImpl {
             name: name.into(),
             text_to_text_model: None,
             text_embedding_model: None,
-            temperature: 0.0,
+            temperature: None,
             max_tokens: None,
             memory_read_timeout: 5000,
+            memory_read: true,
+            memory_write: true,
+            recall_libraries: Vec::new(),
+            memory_injection: MemoryInjection::default(),
+            cite_memories: false,
             system_prompt: r#"# Well-Informed Software Architect
 
 You think out loud as you work through problems, sharing your process in addition to the solutions.
@@ -69,6 +88,7 @@ You are a master at refactoring code, remembering to check for code that ALREADY
             context_files: None,
             context_directory: None,
             context_github: None,
+            context_sql: None,
             additional_params: std::collections::HashMap::new(),
             metadata: std::collections::HashMap::new(),
             on_chunk_handler: None,
@@ -37,16 +37,15 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
         CandleAgentRoleBuilderImpl::new(name)
     }
 
-    fn model(self, model: TextToTextModel) -> impl CandleAgentRoleBuilder {
+    fn model(self, model: impl IntoTextToTextModel) -> impl CandleAgentRoleBuilder {
         use crate::capability::registry;
-        use crate::domain::model::traits::CandleModel;
 
-        // Get max_tokens from model's ModelInfo
-        let model_max_tokens = model
-            .info()
-            .max_output_tokens
-            .map(|t| t.get().into())
-            .unwrap_or(2000);
+        // An unknown model is reported by `into_agent` and `chat`; until
+        // then the default model stands in
+        let (model, model_error) = match model.into_text_to_text_model() {
+            Ok(model) => (model, None),
+            Err(e) => (TextToTextModel::Qwen3Quantized(Arc::default()), Some(e)),
+        };
 
         // Get default embedding model from registry (if available)
         let default_embedding_model =
@@ -57,14 +56,20 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
             text_to_text_model: model,
             text_embedding_model: self.text_embedding_model.or(default_embedding_model),
             temperature: self.temperature,
-            max_tokens: self.max_tokens.unwrap_or(model_max_tokens),
+            max_tokens: self.max_tokens,
             memory_read_timeout: self.memory_read_timeout,
+            memory_read: self.memory_read,
+            memory_write: self.memory_write,
+            recall_libraries: self.recall_libraries,
+            memory_injection: self.memory_injection,
+            cite_memories: self.cite_memories,
             system_prompt: self.system_prompt,
             tools: self.tools,
             context_file: self.context_file,
             context_files: self.context_files,
             context_directory: self.context_directory,
             context_github: self.context_github,
+            context_sql: self.context_sql,
             additional_params: self.additional_params,
             metadata: self.metadata,
             on_chunk_handler: self.on_chunk_handler,
@@ -72,6 +77,15 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
             on_conversation_turn_handler: self.on_conversation_turn_handler,
             conversation_history: self.conversation_history,
             stop_sequences: self.stop_sequences,
+            protect_system_prompt: false,
+            injection_policy: None,
+            interrupt: None,
+            trace_verbosity: Verbosity::default(),
+            profile_memory: None,
+            confidence: None,
+            assistant_prefix: None,
+            response_format: None,
+            model_error,
         }
     }
 
@@ -83,7 +97,7 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
 
     /// Set temperature - EXACT syntax: .temperature(1.0)
     fn temperature(mut self, temp: f64) -> impl CandleAgentRoleBuilder {
-        self.temperature = temp;
+        self.temperature = Some(temp);
         self
     }
 
@@ -99,6 +113,40 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
         self
     }
 
+    /// Enable or disable memory recall - EXACT syntax: .memory_read(false)
+    fn memory_read(mut self, enabled: bool) -> impl CandleAgentRoleBuilder {
+        self.memory_read = enabled;
+        self
+    }
+
+    /// Enable or disable memory storage - EXACT syntax: .memory_write(false)
+    fn memory_write(mut self, enabled: bool) -> impl CandleAgentRoleBuilder {
+        self.memory_write = enabled;
+        self
+    }
+
+    /// Read shared libraries on every turn - EXACT syntax: .recall_libraries([("docs", 1.0), ("notes", 0.5)])
+    fn recall_libraries<L, I>(mut self, libraries: I) -> impl CandleAgentRoleBuilder
+    where
+        L: Into<RecallLibrary>,
+        I: IntoIterator<Item = L>,
+    {
+        self.recall_libraries = libraries.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Place recalled context - EXACT syntax: .memory_injection(ContextPlacement::AfterQuestion)
+    fn memory_injection(mut self, injection: impl Into<MemoryInjection>) -> impl CandleAgentRoleBuilder {
+        self.memory_injection = injection.into();
+        self
+    }
+
+    /// Cite recalled memories in answers - EXACT syntax: .cite_memories(true)
+    fn cite_memories(mut self, enabled: bool) -> impl CandleAgentRoleBuilder {
+        self.cite_memories = enabled;
+        self
+    }
+
     /// Set system prompt - EXACT syntax: .system_prompt("...")
     fn system_prompt(mut self, prompt: impl Into<String>) -> impl CandleAgentRoleBuilder {
         self.system_prompt = prompt.into();
@@ -146,6 +194,12 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
         self
     }
 
+    /// Add a database context - EXACT syntax: .context_sql(CandleContext::<CandleSql>::database("sqlite:app.db"))
+    fn context_sql(mut self, context: CandleContext<CandleSql>) -> impl CandleAgentRoleBuilder {
+        self.context_sql = Some(context);
+        self
+    }
+
     /// Set tools - EXACT syntax: .tools(tool1, tool2, tool3)
     fn tools<T>(mut self, tools: T) -> impl CandleAgentRoleBuilder
     where
@@ -210,7 +264,7 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
         mut self,
         history: impl ConversationHistoryArgs,
     ) -> impl CandleAgentRoleBuilder {
-        self.conversation_history = history.into_history();
+        self.conversation_history = history.into_history().into();
         self
     }
 
@@ -250,7 +304,6 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
     /// Convert to agent - EXACT syntax: .into_agent()
     fn into_agent(self) -> Result<impl CandleAgentBuilder, AgentError> {
         use crate::capability::registry;
-        use crate::domain::model::traits::CandleModel;
 
         // Get default text-to-text model if not set
         let text_model = match self.text_to_text_model {
@@ -264,31 +317,30 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
             }
         };
 
-        // Get max_tokens from model's ModelInfo
-        let model_max_tokens = text_model
-            .info()
-            .max_output_tokens
-            .map(|t| t.get().into())
-            .unwrap_or(2000);
-
         // Get default embedding model from registry (if available)
         let embedding_model = self
             .text_embedding_model
             .or_else(|| registry::get::<TextEmbeddingModel>("dunzhang/stella_en_400M_v5"));
 
-        Ok(CandleAgentBuilderImpl {
+        let builder = CandleAgentBuilderImpl {
             name: self.name,
             text_to_text_model: text_model,
             text_embedding_model: embedding_model,
             temperature: self.temperature,
-            max_tokens: self.max_tokens.unwrap_or(model_max_tokens),
+            max_tokens: self.max_tokens,
             memory_read_timeout: self.memory_read_timeout,
+            memory_read: self.memory_read,
+            memory_write: self.memory_write,
+            recall_libraries: self.recall_libraries,
+            memory_injection: self.memory_injection,
+            cite_memories: self.cite_memories,
             system_prompt: self.system_prompt,
             tools: self.tools,
             context_file: self.context_file,
             context_files: self.context_files,
             context_directory: self.context_directory,
             context_github: self.context_github,
+            context_sql: self.context_sql,
             additional_params: self.additional_params,
             metadata: self.metadata,
             on_chunk_handler: self.on_chunk_handler,
@@ -296,6 +348,19 @@ impl CandleAgentRoleBuilder for CandleAgentRoleBuilderImpl {
             on_conversation_turn_handler: self.on_conversation_turn_handler,
             conversation_history: self.conversation_history,
             stop_sequences: self.stop_sequences,
-        })
+            protect_system_prompt: false,
+            injection_policy: None,
+            interrupt: None,
+            trace_verbosity: Verbosity::default(),
+            profile_memory: None,
+            confidence: None,
+            assistant_prefix: None,
+            response_format: None,
+            model_error: None,
+        };
+
+        // Fail here rather than at the first chat turn
+        builder.validate_sampling()?;
+        Ok(builder)
     }
 }
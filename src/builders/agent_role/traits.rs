@@ -6,9 +6,12 @@ pub trait CandleAgentRoleBuilder: Sized + Send {
     /// Create a new agent role builder - EXACT syntax: CandleFluentAi::agent_role("name")
     fn new(name: impl Into<String>) -> impl CandleAgentRoleBuilder;
 
-    /// Set text-to-text model - EXACT syntax: .model(registry::get_text_to_text("key").unwrap())
+    /// Set text-to-text model - EXACT syntax: .model("llama-3.2-3b") or .model(registry::get::<TextToTextModel>("key").unwrap())
+    ///
+    /// A model name or registry key that is not registered fails
+    /// `into_agent` and `chat`.
     #[must_use]
-    fn model(self, model: TextToTextModel) -> impl CandleAgentRoleBuilder;
+    fn model(self, model: impl IntoTextToTextModel) -> impl CandleAgentRoleBuilder;
 
     /// Set text embedding model - EXACT syntax: .embedding_model(registry::get_text_embedding("key").unwrap())
     #[must_use]
@@ -26,6 +29,46 @@ pub trait CandleAgentRoleBuilder: Sized + Send {
     #[must_use]
     fn memory_read_timeout(self, timeout_ms: u64) -> impl CandleAgentRoleBuilder;
 
+    /// Enable or disable memory recall for this agent - EXACT syntax: .memory_read(false)
+    ///
+    /// When disabled, prompts are built without consulting long-term memory.
+    #[must_use]
+    fn memory_read(self, enabled: bool) -> impl CandleAgentRoleBuilder;
+
+    /// Enable or disable memory storage for this agent - EXACT syntax: .memory_write(false)
+    ///
+    /// When disabled, conversation turns and context documents are not stored.
+    #[must_use]
+    fn memory_write(self, enabled: bool) -> impl CandleAgentRoleBuilder;
+
+    /// Read shared libraries on every turn - EXACT syntax: .recall_libraries([("docs", 1.0), ("team_notes", 0.5)])
+    ///
+    /// Each library is searched alongside the agent's own memory and its hits
+    /// are merged into the prompt context, scored by the library's weight
+    /// (1.0 when only names are given, e.g. `["docs", "team_notes"]`).
+    /// Writes still go to the agent's own memory.
+    #[must_use]
+    fn recall_libraries<L, I>(self, libraries: I) -> impl CandleAgentRoleBuilder
+    where
+        L: Into<RecallLibrary>,
+        I: IntoIterator<Item = L>;
+
+    /// Place recalled context - EXACT syntax: .memory_injection(ContextPlacement::AfterQuestion)
+    ///
+    /// Context goes between the system prompt and the question by default.
+    /// Pass a [`MemoryInjection`] to also drop hits below a relevance cutoff,
+    /// e.g. `MemoryInjection::new(ContextPlacement::ToolMessage).with_min_score(0.3)`.
+    #[must_use]
+    fn memory_injection(self, injection: impl Into<MemoryInjection>) -> impl CandleAgentRoleBuilder;
+
+    /// Cite recalled memories in answers - EXACT syntax: .cite_memories(true)
+    ///
+    /// After each answer, the memories in its prompt context that are similar
+    /// to the answer are listed in the `citations` of the `Complete` chunk.
+    /// Costs one extra embedding batch per turn.
+    #[must_use]
+    fn cite_memories(self, enabled: bool) -> impl CandleAgentRoleBuilder;
+
     /// Set system prompt - EXACT syntax: .system_prompt("...")
     #[must_use]
     fn system_prompt(self, prompt: impl Into<String>) -> impl CandleAgentRoleBuilder;
@@ -52,6 +95,10 @@ pub trait CandleAgentRoleBuilder: Sized + Send {
         context4: CandleContext<CandleGithub>,
     ) -> impl CandleAgentRoleBuilder;
 
+    /// Add a read-only database context - EXACT syntax: .context_sql(CandleContext::<CandleSql>::database("sqlite:app.db"))
+    #[must_use]
+    fn context_sql(self, context: CandleContext<CandleSql>) -> impl CandleAgentRoleBuilder;
+
     /// Set tools - EXACT syntax: .tools(tool1, tool2, tool3)
     #[must_use]
     fn tools<T>(self, tools: T) -> impl CandleAgentRoleBuilder
@@ -133,9 +180,12 @@ pub trait CandleMcpServerBuilder: Sized + Send {
 
 /// Agent builder trait (PUBLIC API)
 pub trait CandleAgentBuilder: Sized + Send + Sync {
-    /// Set text-to-text model - EXACT syntax: .model(TextToTextModel)
+    /// Set text-to-text model - EXACT syntax: .model(TextToTextModel) or .model("llama-3.2-3b")
+    ///
+    /// A model name or registry key that is not registered fails
+    /// `into_agent` and `chat`.
     #[must_use]
-    fn model(self, model: TextToTextModel) -> impl CandleAgentBuilder;
+    fn model(self, model: impl IntoTextToTextModel) -> impl CandleAgentBuilder;
 
     /// Set text embedding model - EXACT syntax: .embedding_model(TextEmbeddingModel)
     #[must_use]
@@ -161,10 +211,121 @@ pub trait CandleAgentBuilder: Sized + Send + Sync {
     #[must_use]
     fn memory_read_timeout(self, timeout_ms: u64) -> impl CandleAgentBuilder;
 
+    /// Enable or disable memory recall for this agent - EXACT syntax: .memory_read(false)
+    ///
+    /// When disabled, prompts are built without consulting long-term memory.
+    #[must_use]
+    fn memory_read(self, enabled: bool) -> impl CandleAgentBuilder;
+
+    /// Enable or disable memory storage for this agent - EXACT syntax: .memory_write(false)
+    ///
+    /// When disabled, conversation turns and context documents are not stored.
+    #[must_use]
+    fn memory_write(self, enabled: bool) -> impl CandleAgentBuilder;
+
+    /// Read shared libraries on every turn - EXACT syntax: .recall_libraries([("docs", 1.0), ("team_notes", 0.5)])
+    ///
+    /// Each library is searched alongside the agent's own memory and its hits
+    /// are merged into the prompt context, scored by the library's weight
+    /// (1.0 when only names are given, e.g. `["docs", "team_notes"]`).
+    /// Writes still go to the agent's own memory.
+    #[must_use]
+    fn recall_libraries<L, I>(self, libraries: I) -> impl CandleAgentBuilder
+    where
+        L: Into<RecallLibrary>,
+        I: IntoIterator<Item = L>;
+
+    /// Place recalled context - EXACT syntax: .memory_injection(ContextPlacement::AfterQuestion)
+    ///
+    /// Context goes between the system prompt and the question by default.
+    /// Pass a [`MemoryInjection`] to also drop hits below a relevance cutoff,
+    /// e.g. `MemoryInjection::new(ContextPlacement::ToolMessage).with_min_score(0.3)`.
+    #[must_use]
+    fn memory_injection(self, injection: impl Into<MemoryInjection>) -> impl CandleAgentBuilder;
+
+    /// Cite recalled memories in answers - EXACT syntax: .cite_memories(true)
+    ///
+    /// After each answer, the memories in its prompt context that are similar
+    /// to the answer are listed in the `citations` of the `Complete` chunk.
+    /// Costs one extra embedding batch per turn.
+    #[must_use]
+    fn cite_memories(self, enabled: bool) -> impl CandleAgentBuilder;
+
     /// Set system prompt - EXACT syntax: .system_prompt("...")
     #[must_use]
     fn system_prompt(self, prompt: impl Into<String>) -> impl CandleAgentBuilder;
 
+    /// Protect the system prompt from modification - EXACT syntax: .protect_system_prompt()
+    ///
+    /// Its SHA-256 digest is recorded when chatting starts and checked on every
+    /// turn; a turn whose assembled prompt no longer begins with the original is
+    /// refused.
+    #[must_use]
+    fn protect_system_prompt(self) -> impl CandleAgentBuilder;
+
+    /// Refuse user messages with prompt-injection markers - EXACT syntax: .reject_prompt_injection(InjectionPolicy::default())
+    #[must_use]
+    fn reject_prompt_injection(self, policy: InjectionPolicy) -> impl CandleAgentBuilder;
+
+    /// Let the caller stop the reply mid-stream - EXACT syntax: .interruptible(interrupt.clone())
+    ///
+    /// After `interrupt.interrupt()`, the turn ends with a `Complete` chunk whose
+    /// `finish_reason` is `"interrupted"`, and the partial reply is stored in
+    /// memory tagged `turn.interrupted`.
+    #[must_use]
+    fn interruptible(self, interrupt: TurnInterrupt) -> impl CandleAgentBuilder;
+
+    /// Group turns under one session - EXACT syntax: .session_id("session-id")
+    ///
+    /// Stored messages, tool audits and regeneration are keyed by this ID;
+    /// without it every turn starts a session of its own.
+    #[must_use]
+    fn session_id(self, session_id: impl Into<String>) -> impl CandleAgentBuilder;
+
+    /// Trace what the model sees each turn - EXACT syntax: .trace_verbosity(Verbosity::Full)
+    ///
+    /// Before each reply, a `Trace` chunk lists the tools offered, the memory
+    /// hits in the prompt context with their scores, and the sampling
+    /// parameters; `Verbosity::Full` adds the fully rendered prompt.
+    #[must_use]
+    fn trace_verbosity(self, verbosity: Verbosity) -> impl CandleAgentBuilder;
+
+    /// Remember facts about the user - EXACT syntax: .profile_memory(ProfileMemory::new())
+    ///
+    /// Statements like "my timezone is Europe/Berlin" are stored in the
+    /// profile library (after the confirmation policy approves them) and the
+    /// stored facts are appended to every system prompt within a small token
+    /// budget. Nothing is learned while memory writes are disabled.
+    #[must_use]
+    fn profile_memory(self, profile: ProfileMemory) -> impl CandleAgentBuilder;
+
+    /// Score each answer's confidence - EXACT syntax: .confidence(ConfidenceEstimator::new())
+    ///
+    /// The `Complete` chunk carries a 0–1 `confidence` combining the mean
+    /// token logprob, hedging phrases and, with `with_self_evaluation(true)`,
+    /// the model's rating of its own answer. With `with_threshold`, answers
+    /// below it are qualified or backed by a memory lookup.
+    #[must_use]
+    fn confidence(self, estimator: ConfidenceEstimator) -> impl CandleAgentBuilder;
+
+    /// Pre-fill the start of every reply - EXACT syntax: .assistant_prefix("```json\n")
+    ///
+    /// The prefix is placed after the assistant header, so the model
+    /// continues from it. It is streamed as the first text of the reply and
+    /// counts against `max_tokens`.
+    #[must_use]
+    fn assistant_prefix(self, prefix: impl Into<String>) -> impl CandleAgentBuilder;
+
+    /// Constrain every reply to JSON of type `T` - EXACT syntax: .response_format::<Verdict>()
+    ///
+    /// Tokens that would leave `T`'s JSON schema are masked during
+    /// generation, and the schema is added to the system prompt, so each
+    /// reply parses with `serde_json::from_str::<T>`. An assistant prefix
+    /// must be a valid start of the document. The mask would block tool
+    /// calls, so an agent with tools is rejected when it starts chatting.
+    #[must_use]
+    fn response_format<T: schemars::JsonSchema>(self) -> impl CandleAgentBuilder;
+
     /// Set additional params - EXACT syntax: .additional_params([("key", "value")])
     #[must_use]
     fn additional_params<P2>(self, params: P2) -> impl CandleAgentBuilder
@@ -187,6 +348,10 @@ pub trait CandleAgentBuilder: Sized + Send + Sync {
         context4: CandleContext<CandleGithub>,
     ) -> impl CandleAgentBuilder;
 
+    /// Add a read-only database context - EXACT syntax: .context_sql(...)
+    #[must_use]
+    fn context_sql(self, context: CandleContext<CandleSql>) -> impl CandleAgentBuilder;
+
     /// Set tools - EXACT syntax: .tools(tool1, tool2, tool3)
     #[must_use]
     fn tools<T>(self, tools: T) -> impl CandleAgentBuilder
@@ -246,4 +411,13 @@ pub trait CandleAgentBuilder: Sized + Send + Sync {
         self,
         message: impl Into<String>,
     ) -> Pin<Box<dyn Stream<Item = CandleMessageChunk> + Send>>;
+
+    /// Take over a conversation from another agent - EXACT syntax: .handoff(document)
+    ///
+    /// The agent answers the document's brief. The stream starts with a
+    /// `Handoff` chunk carrying the document, addressed to this agent.
+    fn handoff(
+        self,
+        document: HandoffDocument,
+    ) -> Pin<Box<dyn Stream<Item = CandleMessageChunk> + Send>>;
 }
@@ -64,26 +64,26 @@ impl CompletionRequestBuilder {
     }
 
     /// Set the chat history
-    pub fn chat_history(mut self, history: ZeroOneOrMany<ChatMessage>) -> Self {
-        self.chat_history = history;
+    pub fn chat_history(mut self, history: impl IntoIterator<Item = ChatMessage>) -> Self {
+        self.chat_history = history.into_iter().collect();
         self
     }
 
     /// Set the documents
-    pub fn documents(mut self, docs: ZeroOneOrMany<Document>) -> Self {
-        self.documents = docs;
+    pub fn documents(mut self, docs: impl IntoIterator<Item = Document>) -> Self {
+        self.documents = docs.into_iter().collect();
         self
     }
 
     /// Set the memories
-    pub fn memories(mut self, memories: ZeroOneOrMany<RetrievalResult>) -> Self {
-        self.memories = memories;
+    pub fn memories(mut self, memories: impl IntoIterator<Item = RetrievalResult>) -> Self {
+        self.memories = memories.into_iter().collect();
         self
     }
 
     /// Set the tools
-    pub fn tools(mut self, tools: ZeroOneOrMany<ToolInfo>) -> Self {
-        self.tools = tools;
+    pub fn tools(mut self, tools: impl IntoIterator<Item = ToolInfo>) -> Self {
+        self.tools = tools.into_iter().collect();
         self
     }
 
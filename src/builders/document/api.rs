@@ -1,5 +1,6 @@
 //! Document builder API - factory methods for creating DocumentBuilders
 
+use super::batch::{DocumentBatch, DocumentSource};
 use super::trait_def::DocumentBuilder;
 use super::types::{DocumentBuilderData, DocumentBuilderImpl};
 use crate::domain::context::{
@@ -23,6 +24,12 @@ impl Document {
             .timeout(30000) // 30s default
     }
 
+    /// Load many URLs and paths in parallel - EXACT syntax: Document::batch(["https://a", "docs/b.md"])
+    #[inline]
+    pub fn batch<S: Into<DocumentSource>>(sources: impl IntoIterator<Item = S>) -> DocumentBatch {
+        DocumentBatch::new(sources)
+    }
+
     /// Create document from GitHub - EXACT syntax: Document::from_github("owner/repo", "path/to/file.md")
     #[inline]
     pub fn from_github(repo: impl Into<String>, path: impl Into<String>) -> impl DocumentBuilder {
@@ -0,0 +1,424 @@
+//! Batch document loading with bounded concurrency
+//!
+//! `Document::from_url` loads one document at a time and reports failures
+//! only through an error handler. A [`DocumentBatch`] loads many URLs and
+//! paths in parallel, retries transient HTTP failures with exponential
+//! backoff, and yields `(source, Result<Document, DocumentLoadError>)` in
+//! completion order so callers can tell exactly which source failed and why.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::stream::{self, Stream, StreamExt};
+use serde_json::Value;
+use tokio::io::AsyncReadExt;
+
+use super::types::{DocumentBuilderData, DocumentBuilderImpl};
+use crate::domain::context::CandleDocument as Document;
+
+/// Sources loaded at once unless configured otherwise
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Retries after a transient failure unless configured otherwise
+pub const DEFAULT_BATCH_RETRIES: u8 = 3;
+
+/// Redirects followed per request
+const MAX_REDIRECTS: usize = 10;
+
+/// Where a batch document comes from
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DocumentSource {
+    /// HTTP or HTTPS URL
+    Url(String),
+    /// Local file
+    File(PathBuf),
+}
+
+impl DocumentSource {
+    /// `http(s)://` inputs are URLs, anything else is a file path
+    pub fn parse(input: &str) -> Self {
+        if input.starts_with("http://") || input.starts_with("https://") {
+            Self::Url(input.to_string())
+        } else {
+            Self::File(PathBuf::from(input))
+        }
+    }
+
+    fn data(&self) -> DocumentBuilderData {
+        match self {
+            Self::Url(url) => DocumentBuilderData::Url(url.clone()),
+            Self::File(path) => DocumentBuilderData::File(path.clone()),
+        }
+    }
+}
+
+impl std::fmt::Display for DocumentSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Url(url) => f.write_str(url),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl From<&str> for DocumentSource {
+    fn from(input: &str) -> Self {
+        Self::parse(input)
+    }
+}
+
+impl From<String> for DocumentSource {
+    fn from(input: String) -> Self {
+        Self::parse(&input)
+    }
+}
+
+impl From<PathBuf> for DocumentSource {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+/// Why a batch document failed to load
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DocumentLoadError {
+    #[error("HTTP {status}")]
+    Status { status: u16 },
+
+    #[error("Request failed: {0}")]
+    Request(String),
+
+    #[error("Failed to read file: {0}")]
+    Io(String),
+
+    #[error("Document is {size} bytes, over the {max} byte limit")]
+    TooLarge { size: usize, max: usize },
+
+    #[error("URL not allowed: {0}")]
+    NotAllowed(String),
+}
+
+impl DocumentLoadError {
+    /// Whether retrying may succeed: timeouts, connection errors, 408, 429 and 5xx
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Status { status } => *status == 408 || *status == 429 || *status >= 500,
+            Self::Request(_) => true,
+            Self::Io(_) | Self::TooLarge { .. } | Self::NotAllowed(_) => false,
+        }
+    }
+}
+
+/// Batch progress, reported after each source finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// Sources finished so far, successfully or not
+    pub completed: usize,
+    /// Sources that failed so far
+    pub failed: usize,
+    /// Sources in the batch
+    pub total: usize,
+}
+
+type ProgressHandler = Arc<dyn Fn(BatchProgress) + Send + Sync>;
+
+type UrlCheck = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Loads many documents in parallel
+#[derive(Clone)]
+pub struct DocumentBatch {
+    sources: Vec<DocumentSource>,
+    concurrency: usize,
+    retries: u8,
+    timeout: Duration,
+    max_size: usize,
+    on_progress: Option<ProgressHandler>,
+    url_check: Option<UrlCheck>,
+}
+
+impl std::fmt::Debug for DocumentBatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DocumentBatch")
+            .field("sources", &self.sources)
+            .field("concurrency", &self.concurrency)
+            .field("retries", &self.retries)
+            .field("timeout", &self.timeout)
+            .field("max_size", &self.max_size)
+            .field("url_check", &self.url_check.is_some())
+            .finish()
+    }
+}
+
+impl DocumentBatch {
+    /// Batch over `sources` with the same defaults as `Document::from_url`
+    pub fn new<S: Into<DocumentSource>>(sources: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            sources: sources.into_iter().map(Into::into).collect(),
+            concurrency: DEFAULT_BATCH_CONCURRENCY,
+            retries: DEFAULT_BATCH_RETRIES,
+            timeout: Duration::from_secs(30),
+            max_size: 10 * 1024 * 1024,
+            on_progress: None,
+            url_check: None,
+        }
+    }
+
+    /// Sources in load order
+    pub fn sources(&self) -> &[DocumentSource] {
+        &self.sources
+    }
+
+    /// Most sources loaded at once (at least 1)
+    #[must_use]
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Retries after a transient failure
+    #[must_use]
+    pub fn retries(mut self, retries: u8) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Per-request timeout in milliseconds
+    #[must_use]
+    pub fn timeout(mut self, timeout_ms: u64) -> Self {
+        self.timeout = Duration::from_millis(timeout_ms);
+        self
+    }
+
+    /// Largest document accepted, in bytes
+    ///
+    /// Files and responses announcing a larger size are refused before they
+    /// are read, and other bodies stop being read at the limit.
+    #[must_use]
+    pub fn max_size(mut self, bytes: usize) -> Self {
+        self.max_size = bytes;
+        self
+    }
+
+    /// Called after each source finishes
+    #[must_use]
+    pub fn on_progress<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(BatchProgress) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Arc::new(handler));
+        self
+    }
+
+    /// Called on each URL before it is requested, and on every redirect
+    /// target before it is followed; an error fails the source
+    #[must_use]
+    pub fn check_urls<F>(mut self, check: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.url_check = Some(Arc::new(check));
+        self
+    }
+
+    /// Load every source, yielding each result as it completes
+    pub fn stream(
+        self,
+    ) -> impl Stream<Item = (DocumentSource, Result<Document, DocumentLoadError>)> + Send {
+        let total = self.sources.len();
+        let completed = Arc::new(AtomicUsize::new(0));
+        let failed = Arc::new(AtomicUsize::new(0));
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .redirect(redirect_policy(self.url_check.clone()))
+            .build();
+        let Self {
+            sources,
+            concurrency,
+            retries,
+            max_size,
+            on_progress,
+            url_check,
+            ..
+        } = self;
+
+        stream::iter(sources)
+            .map(move |source| {
+                let client = client.as_ref().map_err(|e| e.to_string()).cloned();
+                let (completed, failed) = (completed.clone(), failed.clone());
+                let on_progress = on_progress.clone();
+                let url_check = url_check.clone();
+                async move {
+                    let result = async {
+                        if let (DocumentSource::Url(url), Some(check)) = (&source, &url_check) {
+                            check(url).map_err(DocumentLoadError::NotAllowed)?;
+                        }
+                        let client = client.map_err(DocumentLoadError::Request)?;
+                        load_source(&client, &source, retries, max_size).await
+                    }
+                    .await;
+
+                    let failed = if result.is_err() {
+                        failed.fetch_add(1, Ordering::Relaxed) + 1
+                    } else {
+                        failed.load(Ordering::Relaxed)
+                    };
+                    let completed = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                    if let Some(handler) = &on_progress {
+                        handler(BatchProgress {
+                            completed,
+                            failed,
+                            total,
+                        });
+                    }
+                    (source, result)
+                }
+            })
+            .buffer_unordered(concurrency)
+    }
+
+    /// Load every source and collect the results in completion order
+    pub async fn load_all(self) -> Vec<(DocumentSource, Result<Document, DocumentLoadError>)> {
+        self.stream().collect().await
+    }
+}
+
+/// Load one source, retrying transient failures with exponential backoff
+async fn load_source(
+    client: &reqwest::Client,
+    source: &DocumentSource,
+    retries: u8,
+    max_size: usize,
+) -> Result<Document, DocumentLoadError> {
+    let mut attempt = 0u8;
+    loop {
+        let result = match source {
+            DocumentSource::Url(url) => fetch_url(client, url, max_size).await,
+            DocumentSource::File(path) => read_file(path, max_size).await,
+        };
+
+        match result {
+            Ok(content) => return Ok(document(source, content)),
+            Err(e) if e.is_transient() && attempt < retries => {
+                log::debug!("Retrying {} after attempt {}: {}", source, attempt + 1, e);
+                tokio::time::sleep(Duration::from_millis(100 * (1 << attempt.min(10)))).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Read `path`, refusing files over `max_size` bytes without reading past it
+async fn read_file(path: &Path, max_size: usize) -> Result<String, DocumentLoadError> {
+    let io = |e: std::io::Error| DocumentLoadError::Io(e.to_string());
+    let file = tokio::fs::File::open(path).await.map_err(io)?;
+    let size = file.metadata().await.map_err(io)?.len();
+    if size > max_size as u64 {
+        return Err(DocumentLoadError::TooLarge {
+            size: usize::try_from(size).unwrap_or(usize::MAX),
+            max: max_size,
+        });
+    }
+    // The file may have grown since
+    let mut content = String::new();
+    file.take((max_size as u64).saturating_add(1))
+        .read_to_string(&mut content)
+        .await
+        .map_err(io)?;
+    if content.len() > max_size {
+        return Err(DocumentLoadError::TooLarge {
+            size: content.len(),
+            max: max_size,
+        });
+    }
+    Ok(content)
+}
+
+/// Follow redirects whose target passes `url_check`, up to [`MAX_REDIRECTS`]
+fn redirect_policy(url_check: Option<UrlCheck>) -> reqwest::redirect::Policy {
+    let Some(check) = url_check else {
+        return reqwest::redirect::Policy::limited(MAX_REDIRECTS);
+    };
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            return attempt.error("too many redirects");
+        }
+        match check(attempt.url().as_str()) {
+            Ok(()) => attempt.follow(),
+            Err(e) => attempt.error(e),
+        }
+    })
+}
+
+/// GET `url`, refusing bodies over `max_size` bytes without reading past it
+async fn fetch_url(
+    client: &reqwest::Client,
+    url: &str,
+    max_size: usize,
+) -> Result<String, DocumentLoadError> {
+    let mut response = client.get(url).send().await.map_err(|e| {
+        // Refused redirects are not worth retrying
+        if e.is_redirect() {
+            let reason =
+                std::error::Error::source(&e).map_or_else(|| e.to_string(), ToString::to_string);
+            DocumentLoadError::NotAllowed(reason)
+        } else {
+            DocumentLoadError::Request(e.to_string())
+        }
+    })?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(DocumentLoadError::Status {
+            status: status.as_u16(),
+        });
+    }
+    if let Some(length) = response.content_length()
+        && length > max_size as u64
+    {
+        return Err(DocumentLoadError::TooLarge {
+            size: usize::try_from(length).unwrap_or(usize::MAX),
+            max: max_size,
+        });
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| DocumentLoadError::Request(e.to_string()))?
+    {
+        if body.len() + chunk.len() > max_size {
+            return Err(DocumentLoadError::TooLarge {
+                size: body.len() + chunk.len(),
+                max: max_size,
+            });
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Build a document with the same format detection as the single loaders
+fn document(source: &DocumentSource, content: String) -> Document {
+    let data = source.data();
+    let format = <DocumentBuilderImpl>::detect_format(&content, &data);
+    let media_type = <DocumentBuilderImpl>::detect_media_type(&format, &data);
+
+    let mut metadata = HashMap::with_capacity(2);
+    let key = match source {
+        DocumentSource::Url(_) => "url",
+        DocumentSource::File(_) => "path",
+    };
+    metadata.insert(key.to_string(), Value::String(source.to_string()));
+    metadata.insert("size".to_string(), Value::Number(content.len().into()));
+
+    Document {
+        data: content,
+        format: Some(format),
+        media_type: Some(media_type),
+        additional_props: metadata,
+    }
+}
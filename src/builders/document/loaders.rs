@@ -163,19 +163,7 @@ where
                     }
                 }
 
-                let result = match documents.len() {
-                    0 => ZeroOneOrMany::None,
-                    1 => {
-                        let mut iter = documents.into_iter();
-                        match iter.next() {
-                            Some(doc) => ZeroOneOrMany::One(doc),
-                            None => ZeroOneOrMany::None,
-                        }
-                    }
-                    _ => ZeroOneOrMany::Many(documents),
-                };
-
-                let _ = sender.send(CandleZeroOneOrManyChunk(result));
+                let _ = sender.send(CandleZeroOneOrManyChunk(ZeroOneOrMany::from(documents)));
             },
         ))
     }
@@ -268,20 +256,17 @@ where
                     let response_result = client.get(&url).send().await;
 
                     match response_result {
-                        Ok(response) => {
-                            // Try to get text content
-                            match response.text().await {
-                                Ok(content) => {
-                                    // Check size if max_size is set
-                                    if let Some(max_size) = builder.max_size
-                                        && content.len() > max_size
-                                    {
-                                        return; // Skip sending - content too large
-                                    }
-
+                        Ok(mut response) => {
+                            // Try to get text content, stopping past max_size
+                            match read_capped(&mut response, builder.max_size).await {
+                                Ok(Some(content)) => {
                                     let _ = sender.send(CandleStringChunk::text(content));
                                     return;
                                 }
+                                Ok(None) => {
+                                    log::warn!("Skipping {}: over the size limit", url);
+                                    return; // Skip sending - content too large
+                                }
                                 Err(e) => {
                                     last_error = format!("Failed to read response body: {}", e);
                                     if attempt < builder.retry_attempts {
@@ -333,3 +318,26 @@ where
         Self::load_url_content(&url, builder)
     }
 }
+
+/// Read a response body as text; `None` once it is over `max_size` bytes,
+/// checked against `Content-Length` before reading and while streaming
+async fn read_capped(
+    response: &mut reqwest::Response,
+    max_size: Option<usize>,
+) -> Result<Option<String>, reqwest::Error> {
+    let max_size = max_size.unwrap_or(usize::MAX);
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_size as u64)
+    {
+        return Ok(None);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > max_size {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+}
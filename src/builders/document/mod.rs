@@ -6,10 +6,12 @@
 //! - GitHub repositories
 //! - Glob patterns
 //! - Direct text/data
+//! - Batches of URLs and paths, loaded in parallel
 //!
 //! All loading operations support streaming, chunking, error handling, and retry logic.
 
 mod api;
+mod batch;
 mod builder_impl;
 mod detection;
 mod loaders;
@@ -18,6 +20,10 @@ mod types;
 
 // Re-export public API
 pub use api::document;
+pub use batch::{
+    BatchProgress, DEFAULT_BATCH_CONCURRENCY, DEFAULT_BATCH_RETRIES, DocumentBatch,
+    DocumentLoadError, DocumentSource,
+};
 pub use trait_def::DocumentBuilder;
 
 // Note: The impl Document blocks in api.rs are automatically available
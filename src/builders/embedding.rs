@@ -5,6 +5,7 @@
 
 use crate::capability::registry::{self, TextEmbeddingModel};
 use crate::capability::traits::TextEmbeddingCapable;
+use crate::domain::embedding::matryoshka;
 use crate::domain::embedding_result::Embedding;
 use cylo::{AsyncTask, async_task::AsyncTaskBuilder};
 
@@ -19,6 +20,9 @@ pub trait EmbeddingBuilder: Sized {
     /// Set dimensions (for validation only) - EXACT syntax: .with_dims(512)
     fn with_dims(self, dims: usize) -> impl EmbeddingBuilder;
 
+    /// Truncate to a supported Matryoshka dimension and re-normalize - EXACT syntax: .dimension(256)
+    fn dimension(self, dims: usize) -> impl EmbeddingBuilder;
+
     /// Generate embedding - EXACT syntax: .embed()
     fn embed(self) -> AsyncTask<Result<Embedding, Box<dyn std::error::Error + Send + Sync>>>;
 }
@@ -29,6 +33,7 @@ struct EmbeddingBuilderImpl {
     model_key: Option<String>,
     task: Option<String>,
     expected_dims: Option<usize>,
+    dimension: Option<usize>,
 }
 
 impl Embedding {
@@ -39,6 +44,7 @@ impl Embedding {
             model_key: None,
             task: None,
             expected_dims: None,
+            dimension: None,
         }
     }
 }
@@ -62,6 +68,12 @@ impl EmbeddingBuilder for EmbeddingBuilderImpl {
         self
     }
 
+    /// Set the output dimension (Matryoshka truncation)
+    fn dimension(mut self, dims: usize) -> impl EmbeddingBuilder {
+        self.dimension = Some(dims);
+        self
+    }
+
     /// Generate embedding - EXACT syntax: .embed()
     fn embed(self) -> AsyncTask<Result<Embedding, Box<dyn std::error::Error + Send + Sync>>> {
         AsyncTaskBuilder::new(async move {
@@ -85,8 +97,15 @@ impl EmbeddingBuilder for EmbeddingBuilderImpl {
                 }
             }
 
+            if let Some(dimension) = self.dimension {
+                model.validate_dimension_request(dimension)?;
+            }
+
             // Generate embedding via capability trait
-            let vec = model.embed(&self.document, self.task).await?;
+            let mut vec = model.embed(&self.document, self.task).await?;
+            if let Some(dimension) = self.dimension {
+                vec = matryoshka::truncate(&vec, dimension);
+            }
 
             Ok(Embedding::new(self.document, vec))
         })
@@ -0,0 +1,416 @@
+//! A/B comparison of two agent configurations on the same conversation
+//!
+//! An [`Experiment`] answers the same user turns with two agents, A and B,
+//! which may differ in model, sampling, memory settings or anything else a
+//! builder configures. The two run in parallel, and the [`ExperimentReport`]
+//! puts their replies, latency and token counts side by side.
+//!
+//! A recorded conversation is replayed turn by turn: each user message is
+//! answered given the recorded history before it, so both variants always
+//! see the same context. [`ExperimentReport::transcript`] renders one
+//! variant's replies in a fixed layout without timings, so diffing the two
+//! transcripts shows only where the answers differ.
+//!
+//! Each variant builds a fresh agent for every turn. Variants that write to
+//! memory should use separate libraries, or `.memory_write(false)`, so one
+//! variant's replies do not turn up in the other's recall.
+
+use std::fmt::{self, Write};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tokio_stream::{Stream, StreamExt};
+
+use super::agent_role::{CandleAgentBuilder, ConversationHistory};
+use crate::domain::chat::message::{CandleMessageChunk, CandleMessageRole};
+
+/// One of the two configurations compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Arm {
+    A,
+    B,
+}
+
+/// A labelled agent configuration
+///
+/// `configure` returns a configured builder; it is called once per turn.
+pub struct ExperimentVariant<F> {
+    label: String,
+    configure: F,
+}
+
+impl<F> fmt::Debug for ExperimentVariant<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExperimentVariant")
+            .field("label", &self.label)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, B> ExperimentVariant<F>
+where
+    F: Fn() -> B,
+    B: CandleAgentBuilder,
+{
+    /// Variant named `label`, built by `configure`
+    pub fn new(label: impl Into<String>, configure: F) -> Self {
+        Self {
+            label: label.into(),
+            configure,
+        }
+    }
+
+    /// Name shown in reports
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Answer `message` after `history`
+    async fn answer(&self, history: ConversationHistory, message: String) -> TurnOutcome {
+        let stream = (self.configure)()
+            .conversation_history(history)
+            .chat_with_message(message);
+        TurnOutcome::collect(stream).await
+    }
+}
+
+/// Two agent configurations to compare
+#[derive(Debug)]
+pub struct Experiment<FA, FB> {
+    a: ExperimentVariant<FA>,
+    b: ExperimentVariant<FB>,
+}
+
+impl<FA, BA, FB, BB> Experiment<FA, FB>
+where
+    FA: Fn() -> BA,
+    BA: CandleAgentBuilder,
+    FB: Fn() -> BB,
+    BB: CandleAgentBuilder,
+{
+    /// Compare variant `a` with variant `b`
+    pub fn new(a: ExperimentVariant<FA>, b: ExperimentVariant<FB>) -> Self {
+        Self { a, b }
+    }
+
+    /// Answer one live turn with both variants
+    pub async fn run_turn(
+        &self,
+        history: ConversationHistory,
+        message: impl Into<String>,
+    ) -> ExperimentReport {
+        let turn = history
+            .iter()
+            .filter(|(role, _)| *role == CandleMessageRole::User)
+            .count()
+            + 1;
+        let comparison = self.compare(turn, history, message.into()).await;
+        self.report(vec![comparison])
+    }
+
+    /// Replay every user turn of a recorded conversation with both variants
+    pub async fn replay(&self, conversation: &ConversationHistory) -> ExperimentReport {
+        let mut turns = Vec::new();
+        for (index, (history, message)) in replay_turns(conversation).into_iter().enumerate() {
+            log::info!(
+                "Experiment {} vs {}: turn {}",
+                self.a.label,
+                self.b.label,
+                index + 1
+            );
+            turns.push(self.compare(index + 1, history, message).await);
+        }
+        self.report(turns)
+    }
+
+    async fn compare(
+        &self,
+        turn: usize,
+        history: ConversationHistory,
+        message: String,
+    ) -> TurnComparison {
+        let (a, b) = tokio::join!(
+            self.a.answer(history.clone(), message.clone()),
+            self.b.answer(history, message.clone())
+        );
+        TurnComparison {
+            turn,
+            user_message: message,
+            a,
+            b,
+        }
+    }
+
+    fn report(&self, turns: Vec<TurnComparison>) -> ExperimentReport {
+        ExperimentReport {
+            a_label: self.a.label.clone(),
+            b_label: self.b.label.clone(),
+            turns,
+        }
+    }
+}
+
+/// The user turns of `conversation`, each with the history before it
+pub fn replay_turns(conversation: &ConversationHistory) -> Vec<(ConversationHistory, String)> {
+    let messages = conversation.messages();
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, (role, _))| *role == CandleMessageRole::User)
+        .map(|(index, (_, content))| (messages[..index].iter().cloned().collect(), content.clone()))
+        .collect()
+}
+
+/// What one variant did on one turn
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TurnOutcome {
+    /// Reply text
+    pub reply: String,
+    /// Tools called, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<String>,
+    /// Tokens generated, as reported by the model
+    pub token_count: Option<u32>,
+    pub tokens_per_sec: Option<f64>,
+    pub finish_reason: Option<String>,
+    /// Seconds until the first reply text
+    pub first_token_secs: Option<f64>,
+    /// Seconds until the reply ended
+    pub latency_secs: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+impl TurnOutcome {
+    /// Drain a reply stream, timing it from the first poll
+    pub async fn collect(stream: impl Stream<Item = CandleMessageChunk>) -> Self {
+        let started = Instant::now();
+        let mut stream = std::pin::pin!(stream);
+        let mut outcome = Self::default();
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                CandleMessageChunk::Text(text) => outcome.push_text(&text, started),
+                CandleMessageChunk::ToolCallStart { name, .. } => outcome.tool_calls.push(name),
+                CandleMessageChunk::Complete {
+                    text,
+                    finish_reason,
+                    token_count,
+                    tokens_per_sec,
+                    ..
+                } => {
+                    outcome.push_text(&text, started);
+                    outcome.finish_reason = finish_reason;
+                    outcome.token_count = token_count;
+                    outcome.tokens_per_sec = tokens_per_sec;
+                }
+                CandleMessageChunk::Error(error) => outcome.errors.push(error),
+                CandleMessageChunk::ToolCall { .. }
+                | CandleMessageChunk::ToolCallComplete { .. }
+                | CandleMessageChunk::ProgressNotification { .. }
+                | CandleMessageChunk::Trace(_)
+                | CandleMessageChunk::Handoff(_) => {}
+            }
+        }
+        outcome.latency_secs = started.elapsed().as_secs_f64();
+        outcome
+    }
+
+    fn push_text(&mut self, text: &str, started: Instant) {
+        if text.is_empty() {
+            return;
+        }
+        if self.first_token_secs.is_none() {
+            self.first_token_secs = Some(started.elapsed().as_secs_f64());
+        }
+        self.reply.push_str(text);
+    }
+
+    /// Whether the turn ended in an error
+    pub fn failed(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+/// Both variants' answers to one user message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnComparison {
+    /// 1-based position among the conversation's user turns
+    pub turn: usize,
+    pub user_message: String,
+    pub a: TurnOutcome,
+    pub b: TurnOutcome,
+}
+
+impl TurnComparison {
+    /// The outcome of `arm`
+    pub fn outcome(&self, arm: Arm) -> &TurnOutcome {
+        match arm {
+            Arm::A => &self.a,
+            Arm::B => &self.b,
+        }
+    }
+
+    /// Whether both variants replied with the same text
+    pub fn same_reply(&self) -> bool {
+        self.a.reply.trim() == self.b.reply.trim()
+    }
+}
+
+/// Totals for one variant over an experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArmSummary {
+    pub label: String,
+    pub turns: usize,
+    /// Turns that ended in an error
+    pub failed_turns: usize,
+    /// Tokens generated over all turns that reported a count
+    pub total_tokens: u64,
+    pub mean_latency_secs: f64,
+    /// Mean over turns that produced text
+    pub mean_first_token_secs: Option<f64>,
+}
+
+/// Side-by-side results of an experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentReport {
+    pub a_label: String,
+    pub b_label: String,
+    pub turns: Vec<TurnComparison>,
+}
+
+impl ExperimentReport {
+    /// Label of `arm`
+    pub fn label(&self, arm: Arm) -> &str {
+        match arm {
+            Arm::A => &self.a_label,
+            Arm::B => &self.b_label,
+        }
+    }
+
+    /// Totals for `arm`
+    pub fn summary(&self, arm: Arm) -> ArmSummary {
+        let outcomes: Vec<&TurnOutcome> = self.turns.iter().map(|t| t.outcome(arm)).collect();
+        let first_tokens: Vec<f64> = outcomes.iter().filter_map(|o| o.first_token_secs).collect();
+        ArmSummary {
+            label: self.label(arm).to_string(),
+            turns: outcomes.len(),
+            failed_turns: outcomes.iter().filter(|o| o.failed()).count(),
+            total_tokens: outcomes
+                .iter()
+                .filter_map(|o| o.token_count)
+                .map(u64::from)
+                .sum(),
+            mean_latency_secs: mean(outcomes.iter().map(|o| o.latency_secs)).unwrap_or(0.0),
+            mean_first_token_secs: mean(first_tokens.into_iter()),
+        }
+    }
+
+    /// Turns where the variants replied differently
+    pub fn differing_turns(&self) -> impl Iterator<Item = &TurnComparison> {
+        self.turns.iter().filter(|turn| !turn.same_reply())
+    }
+
+    /// The user messages and `arm`'s replies, without timings
+    ///
+    /// Both arms' transcripts share their layout, so a line diff of the two
+    /// shows only differences in the answers.
+    pub fn transcript(&self, arm: Arm) -> String {
+        let mut out = String::new();
+        for turn in &self.turns {
+            let outcome = turn.outcome(arm);
+            let _ = writeln!(out, "## Turn {}", turn.turn);
+            for line in turn.user_message.lines() {
+                let _ = writeln!(out, "> {line}");
+            }
+            out.push('\n');
+            if !outcome.tool_calls.is_empty() {
+                let _ = writeln!(out, "[tools: {}]", outcome.tool_calls.join(", "));
+            }
+            let _ = writeln!(out, "{}", outcome.reply.trim());
+            for error in &outcome.errors {
+                let _ = writeln!(out, "[error: {error}]");
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl fmt::Display for ExperimentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<24} {:>6} {:>7} {:>10} {:>10} {:>8}",
+            "variant", "turns", "failed", "latency", "first tok", "tokens"
+        )?;
+        for arm in [Arm::A, Arm::B] {
+            let summary = self.summary(arm);
+            writeln!(
+                f,
+                "{:<24} {:>6} {:>7} {:>9.2}s {:>10} {:>8}",
+                format!("{}: {}", arm_name(arm), summary.label),
+                summary.turns,
+                summary.failed_turns,
+                summary.mean_latency_secs,
+                summary
+                    .mean_first_token_secs
+                    .map_or_else(|| "-".to_string(), |secs| format!("{secs:.2}s")),
+                summary.total_tokens
+            )?;
+        }
+        writeln!(
+            f,
+            "{} of {} turn(s) differ",
+            self.differing_turns().count(),
+            self.turns.len()
+        )?;
+        for turn in &self.turns {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "## Turn {}: {}",
+                turn.turn,
+                first_line(&turn.user_message)
+            )?;
+            if turn.same_reply() {
+                writeln!(f, "(same reply)")?;
+            }
+            for arm in [Arm::A, Arm::B] {
+                let outcome = turn.outcome(arm);
+                writeln!(
+                    f,
+                    "--- {} ({:.2}s, {} tokens)",
+                    arm_name(arm),
+                    outcome.latency_secs,
+                    outcome
+                        .token_count
+                        .map_or_else(|| "?".to_string(), |n| n.to_string())
+                )?;
+                if !turn.same_reply() || arm == Arm::A {
+                    writeln!(f, "{}", outcome.reply.trim())?;
+                }
+                for error in &outcome.errors {
+                    writeln!(f, "error: {error}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn arm_name(arm: Arm) -> &'static str {
+    match arm {
+        Arm::A => "A",
+        Arm::B => "B",
+    }
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
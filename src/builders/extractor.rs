@@ -4,12 +4,15 @@
 
 use std::fmt;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use cyrup_sugars::prelude::MessageChunk;
 use serde::de::DeserializeOwned;
 
 use crate::capability::registry::TextToTextModel;
-use crate::domain::context::extraction::{Extractor, ExtractorImpl};
+use crate::domain::context::extraction::{
+    DEFAULT_MAX_REPAIRS, ExtractionValidator, Extractor, ExtractorImpl,
+};
 
 /// Extractor builder trait - elegant zero-allocation builder pattern
 pub trait ExtractorBuilder<T>: Sized
@@ -22,6 +25,14 @@ where
     /// Set instructions (alias for system_prompt) - EXACT syntax: .instructions("...")
     fn instructions(self, instructions: impl Into<String>) -> Self;
 
+    /// Set domain validator - EXACT syntax: .validator(|data| errors)
+    fn validator<F>(self, validator: F) -> Self
+    where
+        F: Fn(&T) -> Vec<String> + Send + Sync + 'static;
+
+    /// Set repair prompt limit (repair is off by default) - EXACT syntax: .max_repairs(3)
+    fn max_repairs(self, max_repairs: usize) -> Self;
+
     /// Build extractor - EXACT syntax: .build()
     fn build(self) -> ExtractorImpl<T, TextToTextModel>;
 }
@@ -33,6 +44,8 @@ where
 {
     model: TextToTextModel,
     system_prompt: Option<String>,
+    validator: Option<ExtractionValidator<T>>,
+    max_repairs: usize,
     _marker: PhantomData<T>,
 }
 
@@ -50,11 +63,28 @@ where
         self
     }
 
+    fn validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&T) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    fn max_repairs(mut self, max_repairs: usize) -> Self {
+        self.max_repairs = max_repairs;
+        self
+    }
+
     fn build(self) -> ExtractorImpl<T, TextToTextModel> {
-        let mut extractor = ExtractorImpl::new_with_provider(self.model);
+        let mut extractor =
+            ExtractorImpl::new_with_provider(self.model).with_max_repairs(self.max_repairs);
         if let Some(prompt) = self.system_prompt {
             extractor = extractor.with_system_prompt(prompt);
         }
+        if let Some(validator) = self.validator {
+            extractor = extractor.with_validator(move |data: &T| validator(data));
+        }
         extractor
     }
 }
@@ -78,6 +108,11 @@ where
 ///
 /// let extractor = extractor::<Person>(model)
 ///     .system_prompt("Extract person information as JSON")
+///     .validator(|person: &Person| match person.age {
+///         0..=130 => Vec::new(),
+///         age => vec![format!("age {age} is not a plausible human age")],
+///     })
+///     .max_repairs(2)
 ///     .build();
 /// # Ok(())
 /// # }
@@ -89,6 +124,8 @@ where
     ExtractorBuilderImpl {
         model,
         system_prompt: None,
+        validator: None,
+        max_repairs: DEFAULT_MAX_REPAIRS,
         _marker: PhantomData,
     }
 }
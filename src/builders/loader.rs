@@ -307,14 +307,8 @@ where
         tokio::spawn(async move {
             if let Some(items_result) = load_task.try_next() {
                 if let Ok(items) = items_result {
-                    match items {
-                        ZeroOneOrMany::None => {}
-                        ZeroOneOrMany::One(item) => handler(&item),
-                        ZeroOneOrMany::Many(items) => {
-                            for item in &items {
-                                handler(item);
-                            }
-                        }
+                    for item in items.iter() {
+                        handler(item);
                     }
                 }
             }
@@ -8,13 +8,18 @@ pub mod agent_role;
 pub mod completion;
 pub mod document;
 pub mod embedding;
+pub mod experiment;
 pub mod extractor;
 pub mod image;
 pub mod vision;
 
 // Re-export main builder types for public API
-pub use agent_role::{CandleAgentBuilder, CandleAgentRoleBuilder, CandleFluentAi};
+pub use agent_role::{
+    CandleAgentBuilder, CandleAgentRoleBuilder, CandleFluentAi, ConversationHistory,
+    ConversationHistoryArgs,
+};
 pub use embedding::EmbeddingBuilder;
+pub use experiment::{Experiment, ExperimentReport, ExperimentVariant};
 pub use extractor::{ExtractorBuilder, extractor};
 pub use image::ResizeFilter;
 pub use vision::CandleVisionBuilder;
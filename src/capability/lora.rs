@@ -0,0 +1,383 @@
+//! LoRA adapters for text-to-text models
+//!
+//! Adapters are PEFT-format safetensors files (`adapter_model.safetensors`)
+//! with their `adapter_config.json` alongside, registered per base model with
+//! [`registry::register_lora_adapter`](super::registry::register_lora_adapter)
+//! and selected per request through the `lora_adapter` completion parameter.
+//!
+//! Quantized GGUF weights cannot take a low-rank update in place, so adapters
+//! are merged at load time: each targeted weight is dequantized, receives
+//! `scale * B·A`, and is re-quantized to its original format. The merged
+//! model runs in its own pool workers, next to the unadapted base model.
+//!
+//! Projection weights are assumed to be stored unpermuted, which holds for
+//! the Qwen architectures served here.
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+
+use candle_core::quantized::{QTensor, gguf_file};
+use candle_core::{DType, Device, Tensor};
+
+/// Completion parameter selecting adapters by name
+///
+/// Accepts a single name or an array of names; adapters are merged in order.
+pub const LORA_ADAPTER_PARAM: &str = "lora_adapter";
+
+/// PEFT adapter configuration file, stored next to the adapter weights
+pub const ADAPTER_CONFIG_FILE: &str = "adapter_config.json";
+
+/// LoRA loading and merging errors
+#[derive(Debug, thiserror::Error)]
+pub enum LoraError {
+    #[error("Unknown LoRA adapter '{name}' for model {registry_key}")]
+    UnknownAdapter { registry_key: String, name: String },
+    #[error("LoRA adapter '{0}' targets no tensor in the base model")]
+    NoMatchingTensors(String),
+    #[error("LoRA adapter '{adapter}' has mismatched shapes for {tensor}: {detail}")]
+    ShapeMismatch {
+        adapter: String,
+        tensor: String,
+        detail: String,
+    },
+    #[error("Invalid LoRA adapter config {path}: {detail}")]
+    Config { path: String, detail: String },
+    #[error("Failed to read LoRA adapter: {0}")]
+    Io(String),
+    #[error("Tensor operation failed: {0}")]
+    Candle(#[from] candle_core::Error),
+}
+
+/// Where an adapter's safetensors file lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoraSource {
+    /// Local file path
+    Local(PathBuf),
+    /// File in a HuggingFace repository, downloaded on first use
+    HuggingFace { repo: String, file: String },
+}
+
+/// A named LoRA adapter for a base model
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoraAdapter {
+    /// Name used to select the adapter per request
+    pub name: String,
+    /// Adapter weights
+    pub source: LoraSource,
+    /// Update scale; defaults to the one given by `adapter_config.json`
+    pub scale: Option<f32>,
+}
+
+impl LoraAdapter {
+    /// Adapter loaded from a local safetensors file
+    pub fn local(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            source: LoraSource::Local(path.into()),
+            scale: None,
+        }
+    }
+
+    /// Adapter downloaded from a HuggingFace repository
+    pub fn huggingface(
+        name: impl Into<String>,
+        repo: impl Into<String>,
+        file: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            source: LoraSource::HuggingFace {
+                repo: repo.into(),
+                file: file.into(),
+            },
+            scale: None,
+        }
+    }
+
+    /// Override the update scale
+    #[must_use]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+}
+
+/// The parts of a PEFT `adapter_config.json` that shape the update
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct LoraConfig {
+    /// Rank of every factor pair
+    pub r: usize,
+    /// Update numerator
+    pub lora_alpha: f64,
+    /// Rank-stabilized scaling: `lora_alpha / sqrt(r)`
+    #[serde(default)]
+    pub use_rslora: bool,
+    /// Per-module rank overrides
+    #[serde(default)]
+    pub rank_pattern: HashMap<String, serde_json::Value>,
+    /// Per-module alpha overrides
+    #[serde(default)]
+    pub alpha_pattern: HashMap<String, serde_json::Value>,
+}
+
+impl LoraConfig {
+    /// Read and validate an `adapter_config.json`
+    ///
+    /// # Errors
+    /// Returns [`LoraError::Config`] if the file is missing, malformed, has a
+    /// zero rank, or uses per-module rank or alpha overrides, which are not
+    /// supported
+    pub fn read(path: &Path) -> Result<Self, LoraError> {
+        let invalid = |detail: String| LoraError::Config {
+            path: path.display().to_string(),
+            detail,
+        };
+        let text = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        let config: Self = serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+
+        if config.r == 0 {
+            return Err(invalid("rank r is 0".to_string()));
+        }
+        if !config.rank_pattern.is_empty() || !config.alpha_pattern.is_empty() {
+            return Err(invalid(
+                "per-module rank_pattern and alpha_pattern are not supported".to_string(),
+            ));
+        }
+        Ok(config)
+    }
+
+    /// Update scale: `lora_alpha / r`, or `lora_alpha / sqrt(r)` with rsLoRA
+    pub fn scale(&self) -> f32 {
+        let rank = self.r as f64;
+        let divisor = if self.use_rslora { rank.sqrt() } else { rank };
+        (self.lora_alpha / divisor) as f32
+    }
+}
+
+/// Repository path of the `adapter_config.json` next to an adapter file
+///
+/// `sql/adapter_model.safetensors` → `sql/adapter_config.json`
+pub fn adapter_config_file(weights_file: &str) -> String {
+    match weights_file.rsplit_once('/') {
+        Some((dir, _)) => format!("{dir}/{ADAPTER_CONFIG_FILE}"),
+        None => ADAPTER_CONFIG_FILE.to_string(),
+    }
+}
+
+/// Low-rank factors of one adapter, keyed by GGUF tensor name
+#[derive(Debug)]
+pub struct LoraWeights {
+    name: String,
+    scale: f32,
+    /// GGUF tensor name → (A `[r, in]`, B `[out, r]`)
+    factors: HashMap<String, (Tensor, Tensor)>,
+}
+
+impl LoraWeights {
+    /// Group PEFT tensors into per-weight factor pairs
+    ///
+    /// Tensors that do not map to a GGUF weight are ignored.
+    pub fn from_tensors(
+        name: impl Into<String>,
+        tensors: HashMap<String, Tensor>,
+        scale: f32,
+    ) -> Result<Self, LoraError> {
+        let name = name.into();
+        let mut a_factors = HashMap::new();
+        let mut b_factors = HashMap::new();
+
+        for (key, tensor) in tensors {
+            let tensor = tensor.to_dtype(DType::F32)?;
+            if let Some(target) = key.strip_suffix(".lora_A.weight").and_then(gguf_tensor_name) {
+                a_factors.insert(target, tensor);
+            } else if let Some(target) =
+                key.strip_suffix(".lora_B.weight").and_then(gguf_tensor_name)
+            {
+                b_factors.insert(target, tensor);
+            }
+        }
+
+        let factors: HashMap<_, _> = a_factors
+            .into_iter()
+            .filter_map(|(target, a)| b_factors.remove(&target).map(|b| (target, (a, b))))
+            .collect();
+
+        if factors.is_empty() {
+            return Err(LoraError::NoMatchingTensors(name));
+        }
+
+        Ok(Self {
+            name,
+            scale,
+            factors,
+        })
+    }
+
+    /// Read a PEFT safetensors file and its sibling `adapter_config.json`
+    ///
+    /// The scale comes from the config unless the adapter overrides it.
+    ///
+    /// # Errors
+    /// Returns [`LoraError::Config`] if the config cannot be read, and
+    /// [`LoraError::ShapeMismatch`] if a factor's rank differs from the
+    /// config's `r`
+    pub fn load(adapter: &LoraAdapter, path: &Path) -> Result<Self, LoraError> {
+        let config = LoraConfig::read(&path.with_file_name(ADAPTER_CONFIG_FILE))?;
+        let tensors = candle_core::safetensors::load(path, &Device::Cpu)?;
+        let scale = adapter.scale.unwrap_or_else(|| config.scale());
+        let weights = Self::from_tensors(adapter.name.clone(), tensors, scale)?;
+        weights.check_rank(config.r)?;
+        Ok(weights)
+    }
+
+    /// Check every factor pair against the configured rank
+    fn check_rank(&self, rank: usize) -> Result<(), LoraError> {
+        for (tensor, (a, b)) in &self.factors {
+            let (a_rank, b_rank) = (a.dims().first(), b.dims().get(1));
+            if a_rank != Some(&rank) || b_rank != Some(&rank) {
+                return Err(LoraError::ShapeMismatch {
+                    adapter: self.name.clone(),
+                    tensor: tensor.clone(),
+                    detail: format!(
+                        "A {:?} and B {:?} vs configured rank {}",
+                        a.dims(),
+                        b.dims(),
+                        rank
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Adapter name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of base weights this adapter updates
+    pub fn target_count(&self) -> usize {
+        self.factors.len()
+    }
+
+    /// Scale applied to `B·A`
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// `scale * B·A` for a GGUF tensor, if this adapter targets it
+    fn delta(&self, tensor_name: &str, shape: &[usize]) -> Result<Option<Tensor>, LoraError> {
+        let Some((a, b)) = self.factors.get(tensor_name) else {
+            return Ok(None);
+        };
+
+        let delta = b.matmul(a)?;
+        if delta.dims() != shape {
+            return Err(LoraError::ShapeMismatch {
+                adapter: self.name.clone(),
+                tensor: tensor_name.to_string(),
+                detail: format!("delta {:?} vs weight {:?}", delta.dims(), shape),
+            });
+        }
+        Ok(Some((delta * f64::from(self.scale))?))
+    }
+}
+
+/// Map a PEFT module path to its GGUF tensor name
+///
+/// `base_model.model.model.layers.3.self_attn.q_proj` → `blk.3.attn_q.weight`
+pub fn gguf_tensor_name(peft_module: &str) -> Option<String> {
+    let rest = &peft_module[peft_module.find("layers.")? + "layers.".len()..];
+    let (layer, module) = rest.split_once('.')?;
+    let layer: usize = layer.parse().ok()?;
+
+    let target = match module {
+        "self_attn.q_proj" => "attn_q",
+        "self_attn.k_proj" => "attn_k",
+        "self_attn.v_proj" => "attn_v",
+        "self_attn.o_proj" => "attn_output",
+        "mlp.gate_proj" => "ffn_gate",
+        "mlp.up_proj" => "ffn_up",
+        "mlp.down_proj" => "ffn_down",
+        _ => return None,
+    };
+    Some(format!("blk.{layer}.{target}.weight"))
+}
+
+/// Merge adapters into a GGUF model and return the merged file in memory
+///
+/// Untargeted tensors are copied unchanged. The result can be read with
+/// [`gguf_file::Content::read`] and loaded like the original file.
+pub fn merge_into_gguf<R: Read + Seek>(
+    content: &gguf_file::Content,
+    reader: &mut R,
+    adapters: &[LoraWeights],
+) -> Result<Cursor<Vec<u8>>, LoraError> {
+    let mut names: Vec<&String> = content.tensor_infos.keys().collect();
+    names.sort();
+
+    let mut tensors = Vec::with_capacity(names.len());
+    let mut merged = 0usize;
+    for name in names {
+        let original = content.tensor(reader, name, &Device::Cpu)?;
+        let shape = original.shape().dims().to_vec();
+
+        let mut weight: Option<Tensor> = None;
+        for adapter in adapters {
+            if let Some(delta) = adapter.delta(name, &shape)? {
+                let base = match weight.take() {
+                    Some(w) => w,
+                    None => original.dequantize(&Device::Cpu)?,
+                };
+                weight = Some((base + delta)?);
+            }
+        }
+
+        let tensor = match weight {
+            Some(w) => {
+                merged += 1;
+                QTensor::quantize(&w, original.dtype())?
+            }
+            None => original,
+        };
+        tensors.push((name.as_str(), tensor));
+    }
+
+    log::info!(
+        "Merged {} LoRA adapter(s) into {} tensors",
+        adapters.len(),
+        merged
+    );
+
+    let metadata: Vec<(&str, &gguf_file::Value)> = content
+        .metadata
+        .iter()
+        .map(|(k, v)| (k.as_str(), v))
+        .collect();
+    let tensor_refs: Vec<(&str, &QTensor)> = tensors.iter().map(|(n, t)| (*n, t)).collect();
+
+    let mut out = Cursor::new(Vec::new());
+    gguf_file::write(&mut out, &metadata, &tensor_refs)?;
+    out.set_position(0);
+    Ok(out)
+}
+
+/// Adapter names requested by completion parameters
+pub fn requested_adapters(additional_params: Option<&serde_json::Value>) -> Vec<String> {
+    match additional_params.and_then(|p| p.get(LORA_ADAPTER_PARAM)) {
+        Some(serde_json::Value::String(name)) => vec![name.clone()],
+        Some(serde_json::Value::Array(names)) => names
+            .iter()
+            .filter_map(|n| n.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Map a std I/O error into a [`LoraError`]
+impl From<std::io::Error> for LoraError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
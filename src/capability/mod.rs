@@ -3,10 +3,13 @@
 //! Models organized by what they CAN DO rather than who created them.
 //! See GLOSSARY.md for architecture details.
 
+pub mod lora;
 pub mod registry;
+pub mod tiny;
 pub mod traits;
 
 pub mod image_embedding;
+pub mod rerank;
 pub mod text_embedding;
 pub mod text_to_image;
 pub mod text_to_text;
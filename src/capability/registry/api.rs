@@ -47,8 +47,15 @@ pub trait FromRegistry: Sized {
 impl FromRegistry for TextToTextModel {
     fn from_registry(registry_key: &str) -> Option<Self> {
         // Acquire read lock - sync, fast, no contention
-        let registry = TEXT_TO_TEXT_UNIFIED.read();
-        registry.get(registry_key).cloned()
+        if let Some(model) = TEXT_TO_TEXT_UNIFIED.read().get(registry_key).cloned() {
+            return Some(model);
+        }
+
+        // GGUF repository keys, and `base:VARIANT` keys that register the
+        // quantization variant on first use
+        super::quantization::resolve_variant(registry_key)
+            // Short model names, e.g. `llama-3.2-3b`
+            .or_else(|| TextToTextModel::by_name(registry_key))
     }
 }
 
@@ -200,7 +207,7 @@ impl FromRegistry for AnyModel {
 /// }
 /// ```
 pub fn get_text_to_text(registry_key: &str) -> Option<impl TextToTextCapable> {
-    TEXT_TO_TEXT_UNIFIED.read().get(registry_key).cloned()
+    TextToTextModel::from_registry(registry_key)
 }
 
 /// Get a text embedding model by registry_key
@@ -6,9 +6,9 @@ use std::sync::Arc;
 
 // Import all model types
 use crate::capability::image_embedding::ClipVisionEmbeddingModel;
-use crate::capability::text_embedding::StellaEmbeddingModel;
+use crate::capability::text_embedding::{EncoderEmbeddingModel, StellaEmbeddingModel};
 use crate::capability::text_to_image::{FluxSchnell, StableDiffusion35Turbo};
-use crate::capability::text_to_text::CandleQwen3QuantizedModel;
+use crate::capability::text_to_text::{CandleGgufChatModel, CandleQwen3QuantizedModel};
 use crate::capability::vision::LLaVAModel;
 
 //==============================================================================
@@ -19,12 +19,16 @@ use crate::capability::vision::LLaVAModel;
 #[derive(Clone, Debug)]
 pub enum TextToTextModel {
     Qwen3Quantized(Arc<CandleQwen3QuantizedModel>),
+    /// Llama 3.x, Mistral and Phi-3 GGUF models
+    Gguf(Arc<CandleGgufChatModel>),
 }
 
 /// Enum for all text embedding models
 #[derive(Clone, Debug)]
 pub enum TextEmbeddingModel {
     Stella(Arc<StellaEmbeddingModel>),
+    /// BGE-M3, GTE-large and nomic-embed-text
+    Encoder(Arc<EncoderEmbeddingModel>),
 }
 
 /// Enum for all image embedding models
@@ -65,6 +69,7 @@ impl CandleModel for TextToTextModel {
     fn info(&self) -> &'static CandleModelInfo {
         match self {
             Self::Qwen3Quantized(m) => m.info(),
+            Self::Gguf(m) => m.info(),
         }
     }
 }
@@ -74,6 +79,7 @@ impl CandleModel for TextEmbeddingModel {
     fn info(&self) -> &'static CandleModelInfo {
         match self {
             Self::Stella(m) => m.info(),
+            Self::Encoder(m) => m.info(),
         }
     }
 }
@@ -0,0 +1,51 @@
+//! Shared loaded-model handles
+//!
+//! Callers that need direct access to a loaded model (constrained tool
+//! selection) share one handle per model here instead of loading a fresh copy
+//! on every turn. Base-model pool workers load through the same handle, so
+//! the model is resident once.
+
+use super::storage::LOADED_MODEL_HANDLES;
+use crate::capability::text_to_text::LoadedModelHandle;
+use crate::domain::model::traits::CandleModel;
+
+/// Get the shared loaded-model handle for a text-to-text model
+///
+/// `model` is the registry entry or the concrete model behind it; both share
+/// one handle.
+///
+/// The handle is created empty on first request; the model itself loads on
+/// first [`LoadedModelHandle::get_or_load`].
+pub fn loaded_model_handle(model: &impl CandleModel) -> LoadedModelHandle {
+    let key = model.info().registry_key;
+
+    if let Some(handle) = LOADED_MODEL_HANDLES.read().get(key) {
+        return handle.clone();
+    }
+
+    LOADED_MODEL_HANDLES
+        .write()
+        .entry(key.to_string())
+        .or_default()
+        .clone()
+}
+
+/// Unload the model behind the shared handle of `registry_key`
+///
+/// Every clone of the handle, such as those held by agents, loses the model
+/// too. Returns whether a model was loaded.
+pub fn unload_loaded_model(registry_key: &str) -> bool {
+    LOADED_MODEL_HANDLES
+        .read()
+        .get(registry_key)
+        .is_some_and(LoadedModelHandle::unload)
+}
+
+/// Drop all shared loaded-model handles, unloading their models
+///
+/// Clones held elsewhere lose their models too.
+pub fn clear_loaded_model_handles() {
+    for (_, handle) in LOADED_MODEL_HANDLES.write().drain() {
+        handle.unload();
+    }
+}
@@ -0,0 +1,57 @@
+//! LoRA adapter registration
+//!
+//! Adapters are registered against a base model's registry_key and selected
+//! per request by name (see [`LORA_ADAPTER_PARAM`](crate::capability::lora::LORA_ADAPTER_PARAM)).
+
+use super::storage::LORA_ADAPTERS;
+use crate::capability::lora::{LoraAdapter, LoraError};
+
+/// Register an adapter for a base model, replacing one with the same name
+pub fn register_lora_adapter(registry_key: &str, adapter: LoraAdapter) {
+    let mut adapters = LORA_ADAPTERS.write();
+    let entry = adapters.entry(registry_key.to_string()).or_default();
+    entry.retain(|a| a.name != adapter.name);
+    entry.push(adapter);
+}
+
+/// Remove an adapter; returns it if it was registered
+pub fn unregister_lora_adapter(registry_key: &str, name: &str) -> Option<LoraAdapter> {
+    let mut adapters = LORA_ADAPTERS.write();
+    let entry = adapters.get_mut(registry_key)?;
+    let index = entry.iter().position(|a| a.name == name)?;
+    Some(entry.remove(index))
+}
+
+/// Adapters registered for a base model, in registration order
+pub fn lora_adapters(registry_key: &str) -> Vec<LoraAdapter> {
+    LORA_ADAPTERS
+        .read()
+        .get(registry_key)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Resolve adapter names for a base model, preserving the requested order
+///
+/// # Errors
+/// Returns [`LoraError::UnknownAdapter`] for the first unregistered name
+pub fn resolve_lora_adapters(
+    registry_key: &str,
+    names: &[String],
+) -> Result<Vec<LoraAdapter>, LoraError> {
+    let registered = LORA_ADAPTERS.read();
+    let available = registered.get(registry_key);
+
+    names
+        .iter()
+        .map(|name| {
+            available
+                .and_then(|adapters| adapters.iter().find(|a| &a.name == name))
+                .cloned()
+                .ok_or_else(|| LoraError::UnknownAdapter {
+                    registry_key: registry_key.to_string(),
+                    name: name.clone(),
+                })
+        })
+        .collect()
+}
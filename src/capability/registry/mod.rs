@@ -103,11 +103,16 @@
 mod api;
 mod enums;
 mod image_embedding;
+mod loaded_models;
+mod lora_adapters;
+mod models;
+mod quantization;
 mod runtime;
 pub(crate) mod storage;
 mod text_embedding;
 mod text_to_image;
 mod text_to_text;
+mod tool_embeddings;
 mod vision;
 
 // Pool is an integral part of registry - registry IS ALWAYS POOLED
@@ -134,4 +139,24 @@ pub use runtime::{
     unregister_text_to_text,
 };
 
+// Re-export registry introspection
+pub use models::{DownloadStatus, ModelCapability, ModelDescriptor, models};
+pub use text_to_text::{IntoTextToTextModel, chat_template_families};
+
+// Re-export quantization variant selection
+pub use quantization::{QuantVariant, split_variant};
+
+// Re-export LoRA adapter registration
+pub use lora_adapters::{
+    lora_adapters, register_lora_adapter, resolve_lora_adapters, unregister_lora_adapter,
+};
+
+// Re-export shared loaded-model handles
+pub use loaded_models::{clear_loaded_model_handles, loaded_model_handle, unload_loaded_model};
+
+// Re-export tool embedding cache
+pub use tool_embeddings::{
+    cache_tool_embedding, clear_tool_embeddings, get_tool_embedding, tool_embedding_count,
+};
+
 // Test module
@@ -0,0 +1,120 @@
+//! Registry introspection
+//!
+//! Describes every registered model (capability, quantization, limits, memory
+//! estimate, and whether its weights are already in the local HuggingFace
+//! cache) so clients can discover what this process can serve before they
+//! configure an agent.
+
+use super::storage::{
+    IMAGE_EMBEDDING_UNIFIED, TEXT_EMBEDDING_UNIFIED, TEXT_TO_IMAGE_UNIFIED, TEXT_TO_TEXT_UNIFIED,
+    VISION_UNIFIED,
+};
+use crate::domain::model::CandleModelInfo;
+use crate::domain::model::traits::CandleModel;
+
+pub use kodegen_mcp_schema::candle::{DownloadStatus, ModelCapability, ModelDescriptor};
+
+/// Describe a registered model under the capability it is registered for
+fn describe(capability: ModelCapability, info: &CandleModelInfo) -> ModelDescriptor {
+    ModelDescriptor {
+        registry_key: info.registry_key.to_string(),
+        model_id: info.model_id.to_string(),
+        provider: info.provider_str().to_string(),
+        capability,
+        quantization: info.quantization.to_string(),
+        context_length: info.max_input_tokens.map(std::num::NonZeroU32::get),
+        embedding_dimension: info.embedding_dimension,
+        download_status: download_status(info),
+        est_memory_mb: info.est_memory_allocation_mb,
+    }
+}
+
+/// Describe every registered model, sorted by capability then registry key
+///
+/// Models registered lazily on first lookup (CLIP vision, FLUX, SD 3.5) only
+/// appear once they have been requested. Every quantization variant of a
+/// text-to-text model is listed, selectable as `base:VARIANT`.
+pub fn models() -> impl Iterator<Item = ModelDescriptor> {
+    let mut all = Vec::new();
+
+    all.extend(
+        TEXT_TO_TEXT_UNIFIED
+            .read()
+            .values()
+            .flat_map(|m| {
+                let mut infos = m.quantization_variants();
+                if !infos.iter().any(|info| info.registry_key == m.info().registry_key) {
+                    infos.push(m.info());
+                }
+                infos
+            })
+            .map(|info| describe(ModelCapability::TextToText, info)),
+    );
+    all.extend(
+        TEXT_EMBEDDING_UNIFIED
+            .read()
+            .values()
+            .map(|m| describe(ModelCapability::TextEmbedding, m.info())),
+    );
+    all.extend(
+        IMAGE_EMBEDDING_UNIFIED
+            .read()
+            .values()
+            .map(|m| describe(ModelCapability::ImageEmbedding, m.info())),
+    );
+    all.extend(
+        TEXT_TO_IMAGE_UNIFIED
+            .read()
+            .values()
+            .map(|m| describe(ModelCapability::TextToImage, m.info())),
+    );
+    all.extend(
+        VISION_UNIFIED
+            .read()
+            .values()
+            .map(|m| describe(ModelCapability::Vision, m.info())),
+    );
+
+    all.sort_by(|a, b| {
+        (a.capability as u8, &a.registry_key).cmp(&(b.capability as u8, &b.registry_key))
+    });
+    // Registered variants are also listed by their base model
+    all.dedup_by(|a, b| a.capability == b.capability && a.registry_key == b.registry_key);
+    all.into_iter()
+}
+
+/// Check the HuggingFace cache for a model's weights
+///
+/// Quantized models name their weight file in `quantization_url`
+/// (`org/repo/file.gguf`); other models count as cached once any snapshot of
+/// their repository exists.
+fn download_status(info: &CandleModelInfo) -> DownloadStatus {
+    use hf_hub::{Cache, Repo};
+
+    let cache = Cache::from_env();
+
+    let cached = match info
+        .quantization_url
+        .and_then(|url| url.splitn(3, '/').nth(2).map(|file| (url, file)))
+    {
+        Some((url, file)) => {
+            let repo = &url[..url.len() - file.len() - 1];
+            cache.model(repo.to_string()).get(file).is_some()
+        }
+        None => {
+            let snapshots = cache
+                .path()
+                .join(Repo::model(info.registry_key.to_string()).folder_name())
+                .join("snapshots");
+            std::fs::read_dir(snapshots)
+                .map(|mut entries| entries.next().is_some())
+                .unwrap_or(false)
+        }
+    };
+
+    if cached {
+        DownloadStatus::Cached
+    } else {
+        DownloadStatus::NotCached
+    }
+}
@@ -1,12 +1,12 @@
 use once_cell::sync::Lazy;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{mpsc, oneshot};
 
 use crate::capability::registry::pool::core::memory_governor::AllocationGuard;
 use crate::capability::registry::pool::core::types::{
-    HealthPing, HealthPong, PendingRequestsGuard, select_worker_power_of_two,
+    HealthPing, HealthPong, PendingRequestsGuard, WorkerStats, select_worker_power_of_two,
 };
 use crate::capability::registry::pool::core::{Pool, PoolConfig, PoolError, WorkerHandle};
 use crate::capability::traits::ImageEmbeddingCapable;
@@ -257,6 +257,8 @@ impl Pool<ImageEmbeddingWorkerHandle> {
         use std::sync::atomic::AtomicU32;
         let state = Arc::new(AtomicU32::new(0)); // Spawning state
         let state_clone = Arc::clone(&state);
+        let stats = Arc::new(WorkerStats::default());
+        let stats_for_task = Arc::clone(&stats);
 
         // Spawn worker task
         tokio::spawn(async move {
@@ -292,6 +294,7 @@ impl Pool<ImageEmbeddingWorkerHandle> {
                         WorkerState::Failed as u32,
                         std::sync::atomic::Ordering::Release,
                     );
+                    stats_for_task.set_last_error(&e);
 
                     // Clean up memory tracking (CRITICAL FIX)
                     // This prevents memory leak when model loading fails
@@ -340,12 +343,14 @@ impl Pool<ImageEmbeddingWorkerHandle> {
             core: WorkerHandle {
                 pending_requests,
                 last_used,
+                last_request: Arc::new(AtomicU64::new(now)),
                 worker_id,
                 shutdown_tx: shutdown_tx.clone(),
                 per_worker_mb,
                 health_tx: health_tx_main,
                 health_rx: Arc::new(tokio::sync::Mutex::new(health_rx_main)),
                 state,
+                stats,
             },
             embed_image_tx,
             embed_image_url_tx,
@@ -408,6 +413,7 @@ impl Pool<ImageEmbeddingWorkerHandle> {
         worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
         let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
         worker.core.touch();
+        let started = Instant::now();
 
         let (response_tx, response_rx) = oneshot::channel();
         worker
@@ -425,12 +431,21 @@ impl Pool<ImageEmbeddingWorkerHandle> {
             .map_err(|_| {
                 // Record timeout as failure
                 circuit.record_failure();
+                worker.core.stats.record_failure(started.elapsed(), "Request timed out");
                 self.metrics()
                     .total_timeouts
                     .fetch_add(1, Ordering::Relaxed);
                 PoolError::Timeout("Request timed out".to_string())
             })?
-            .map_err(|_| PoolError::RecvError("Response channel closed".to_string()))?;
+            .map_err(|_| {
+                worker
+                    .core
+                    .stats
+                    .record_failure(started.elapsed(), "Response channel closed");
+                PoolError::RecvError("Response channel closed".to_string())
+            })?;
+
+        worker.core.stats.record(started.elapsed(), &result);
 
         // Record success or failure based on result
         match &result {
@@ -488,6 +503,7 @@ impl Pool<ImageEmbeddingWorkerHandle> {
         worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
         let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
         worker.core.touch();
+        let started = Instant::now();
 
         let (response_tx, response_rx) = oneshot::channel();
         worker
@@ -505,12 +521,21 @@ impl Pool<ImageEmbeddingWorkerHandle> {
             .map_err(|_| {
                 // Record timeout as failure
                 circuit.record_failure();
+                worker.core.stats.record_failure(started.elapsed(), "Request timed out");
                 self.metrics()
                     .total_timeouts
                     .fetch_add(1, Ordering::Relaxed);
                 PoolError::Timeout("Request timed out".to_string())
             })?
-            .map_err(|_| PoolError::RecvError("Response channel closed".to_string()))?;
+            .map_err(|_| {
+                worker
+                    .core
+                    .stats
+                    .record_failure(started.elapsed(), "Response channel closed");
+                PoolError::RecvError("Response channel closed".to_string())
+            })?;
+
+        worker.core.stats.record(started.elapsed(), &result);
 
         // Record success or failure based on result
         match &result {
@@ -568,6 +593,7 @@ impl Pool<ImageEmbeddingWorkerHandle> {
         worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
         let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
         worker.core.touch();
+        let started = Instant::now();
 
         let (response_tx, response_rx) = oneshot::channel();
         worker
@@ -585,12 +611,21 @@ impl Pool<ImageEmbeddingWorkerHandle> {
             .map_err(|_| {
                 // Record timeout as failure
                 circuit.record_failure();
+                worker.core.stats.record_failure(started.elapsed(), "Request timed out");
                 self.metrics()
                     .total_timeouts
                     .fetch_add(1, Ordering::Relaxed);
                 PoolError::Timeout("Request timed out".to_string())
             })?
-            .map_err(|_| PoolError::RecvError("Response channel closed".to_string()))?;
+            .map_err(|_| {
+                worker
+                    .core
+                    .stats
+                    .record_failure(started.elapsed(), "Response channel closed");
+                PoolError::RecvError("Response channel closed".to_string())
+            })?;
+
+        worker.core.stats.record(started.elapsed(), &result);
 
         // Record success or failure based on result
         match &result {
@@ -648,6 +683,7 @@ impl Pool<ImageEmbeddingWorkerHandle> {
         worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
         let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
         worker.core.touch();
+        let started = Instant::now();
 
         let (response_tx, response_rx) = oneshot::channel();
         worker
@@ -665,12 +701,21 @@ impl Pool<ImageEmbeddingWorkerHandle> {
             .map_err(|_| {
                 // Record timeout as failure
                 circuit.record_failure();
+                worker.core.stats.record_failure(started.elapsed(), "Request timed out");
                 self.metrics()
                     .total_timeouts
                     .fetch_add(1, Ordering::Relaxed);
                 PoolError::Timeout("Request timed out".to_string())
             })?
-            .map_err(|_| PoolError::RecvError("Response channel closed".to_string()))?;
+            .map_err(|_| {
+                worker
+                    .core
+                    .stats
+                    .record_failure(started.elapsed(), "Response channel closed");
+                PoolError::RecvError("Response channel closed".to_string())
+            })?;
+
+        worker.core.stats.record(started.elapsed(), &result);
 
         // Record success or failure based on result
         match &result {
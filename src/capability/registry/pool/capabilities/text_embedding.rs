@@ -8,15 +8,18 @@ use tokio::time::{Instant, sleep};
 use crate::capability::registry::pool::WorkerState;
 use crate::capability::registry::pool::core::memory_governor::AllocationGuard;
 use crate::capability::registry::pool::core::types::{
-    HealthPing, HealthPong, PendingRequestsGuard, select_worker_power_of_two,
+    HealthPing, HealthPong, PendingRequestsGuard, WorkerStats, select_worker_power_of_two,
 };
 use crate::capability::registry::pool::core::{Pool, PoolConfig, PoolError, WorkerHandle};
 use crate::capability::traits::TextEmbeddingCapable;
+use crate::runtime::{RequestId, current_request_id, with_optional_request_id};
 
 /// Request for embed() operation
 pub struct EmbedRequest {
     pub text: Arc<str>,
     pub task: Option<String>,
+    /// Request the embedding was made under, for correlating worker logs
+    pub request_id: Option<RequestId>,
     pub response: oneshot::Sender<Result<Vec<f32>, PoolError>>,
 }
 
@@ -24,6 +27,8 @@ pub struct EmbedRequest {
 pub struct BatchEmbedRequest {
     pub texts: Arc<[String]>,
     pub task: Option<String>,
+    /// Request the embeddings were made under, for correlating worker logs
+    pub request_id: Option<RequestId>,
     pub response: oneshot::Sender<Result<Vec<Vec<f32>>, PoolError>>,
 }
 
@@ -121,12 +126,17 @@ pub async fn text_embedding_worker<T: TextEmbeddingCapable>(
                 timeout.as_mut().reset(Instant::now() + idle_threshold);
             }
             Some(req) = embed_rx.recv() => {
-                log::info!("Worker {}: Received embed request, text length: {}", worker_id, req.text.len());
+                log::info!(
+                    "Worker {}: Received embed request, text length: {}, request_id: {}",
+                    worker_id,
+                    req.text.len(),
+                    req.request_id.as_ref().map_or("-", RequestId::as_str)
+                );
                 // Transition: Ready/Idle → Processing
                 state.store(WorkerState::Processing as u32, std::sync::atomic::Ordering::Release);
 
                 log::info!("Worker {}: Calling model.embed()", worker_id);
-                let result = model.embed(&req.text, req.task)
+                let result = with_optional_request_id(req.request_id, model.embed(&req.text, req.task))
                     .await
                     .map_err(|e| PoolError::ModelError(e.to_string()));
                 log::info!("Worker {}: model.embed() returned", worker_id);
@@ -146,7 +156,13 @@ pub async fn text_embedding_worker<T: TextEmbeddingCapable>(
                 // Transition: Ready/Idle → Processing
                 state.store(WorkerState::Processing as u32, std::sync::atomic::Ordering::Release);
 
-                let result = model.batch_embed(&req.texts, req.task)
+                log::debug!(
+                    "Worker {}: Received batch embed request, {} texts, request_id: {}",
+                    worker_id,
+                    req.texts.len(),
+                    req.request_id.as_ref().map_or("-", RequestId::as_str)
+                );
+                let result = with_optional_request_id(req.request_id, model.batch_embed(&req.texts, req.task))
                     .await
                     .map_err(|e| PoolError::ModelError(e.to_string()));
                 if let Err(e) = req.response.send(result) {
@@ -240,6 +256,8 @@ impl Pool<TextEmbeddingWorkerHandle> {
         // Create state before spawning thread so we can clone it
         let state = Arc::new(AtomicU32::new(0)); // Spawning state
         let state_for_task = Arc::clone(&state);
+        let stats = Arc::new(WorkerStats::default());
+        let stats_for_task = Arc::clone(&stats);
 
         // Create worker handle BEFORE spawning (so wait_for_workers can see it)
         let now = SystemTime::now()
@@ -254,12 +272,14 @@ impl Pool<TextEmbeddingWorkerHandle> {
             core: WorkerHandle {
                 pending_requests,
                 last_used,
+                last_request: Arc::new(AtomicU64::new(now)),
                 worker_id,
                 shutdown_tx: shutdown_tx.clone(),
                 per_worker_mb,
                 health_tx: health_tx_main.clone(),
                 health_rx: Arc::new(tokio::sync::Mutex::new(health_rx_main)),
                 state: Arc::clone(&state),
+                stats: Arc::clone(&stats),
             },
             embed_tx: embed_tx.clone(),
             batch_embed_tx: batch_embed_tx.clone(),
@@ -304,6 +324,7 @@ impl Pool<TextEmbeddingWorkerHandle> {
                         WorkerState::Failed as u32,
                         std::sync::atomic::Ordering::Release,
                     );
+                    stats_for_task.set_last_error(&e);
 
                     // Worker already registered - will be cleaned up when state → Failed
                     // AllocationGuard will auto-release memory on return
@@ -385,6 +406,7 @@ impl Pool<TextEmbeddingWorkerHandle> {
         worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
         let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
         worker.core.touch();
+        let started = Instant::now();
 
         let (response_tx, response_rx) = oneshot::channel();
         worker
@@ -392,6 +414,7 @@ impl Pool<TextEmbeddingWorkerHandle> {
             .try_send(EmbedRequest {
                 text: Arc::from(text),
                 task,
+                request_id: current_request_id(),
                 response: response_tx,
             })
             .map_err(|e| PoolError::SendError(format!("Worker queue full or closed: {:?}", e)))?;
@@ -414,6 +437,8 @@ impl Pool<TextEmbeddingWorkerHandle> {
             Ok(Ok(res)) => res,
         };
 
+        worker.core.stats.record(started.elapsed(), &result);
+
         // Record success or failure based on result
         match &result {
             Ok(_) => circuit.record_success(),
@@ -471,6 +496,7 @@ impl Pool<TextEmbeddingWorkerHandle> {
         worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
         let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
         worker.core.touch();
+        let started = Instant::now();
 
         let (response_tx, response_rx) = oneshot::channel();
         worker
@@ -478,6 +504,7 @@ impl Pool<TextEmbeddingWorkerHandle> {
             .try_send(BatchEmbedRequest {
                 texts: Arc::from(texts),
                 task,
+                request_id: current_request_id(),
                 response: response_tx,
             })
             .map_err(|e| PoolError::SendError(format!("Worker queue full or closed: {:?}", e)))?;
@@ -500,6 +527,8 @@ impl Pool<TextEmbeddingWorkerHandle> {
             Ok(Ok(res)) => res,
         };
 
+        worker.core.stats.record(started.elapsed(), &result);
+
         // Record success or failure based on result
         match &result {
             Ok(_) => circuit.record_success(),
@@ -2,13 +2,13 @@ use once_cell::sync::Lazy;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{mpsc, oneshot};
 use tokio_stream::Stream;
 
 use crate::capability::registry::pool::core::memory_governor::AllocationGuard;
 use crate::capability::registry::pool::core::types::{
-    HealthPing, HealthPong, PendingRequestsGuard, select_worker_power_of_two,
+    HealthPing, HealthPong, PendingRequestsGuard, WorkerStats, select_worker_power_of_two,
 };
 use crate::capability::registry::pool::core::{Pool, PoolConfig, PoolError, WorkerHandle};
 use crate::capability::traits::TextToTextCapable;
@@ -197,6 +197,8 @@ impl Pool<TextToTextWorkerHandle> {
         use std::sync::atomic::AtomicU32;
         let state = Arc::new(AtomicU32::new(0)); // Spawning state
         let state_clone = Arc::clone(&state);
+        let stats = Arc::new(WorkerStats::default());
+        let stats_for_task = Arc::clone(&stats);
 
         // Spawn worker task
         tokio::spawn(async move {
@@ -229,6 +231,7 @@ impl Pool<TextToTextWorkerHandle> {
                         WorkerState::Failed as u32,
                         std::sync::atomic::Ordering::Release,
                     );
+                    stats_for_task.set_last_error(&e);
 
                     // Clean up memory tracking
                     // This prevents memory leak when model loading fails
@@ -275,12 +278,14 @@ impl Pool<TextToTextWorkerHandle> {
             core: WorkerHandle {
                 pending_requests,
                 last_used,
+                last_request: Arc::new(AtomicU64::new(now)),
                 worker_id,
                 shutdown_tx: shutdown_tx.clone(),
                 per_worker_mb,
                 health_tx: health_tx_main,
                 health_rx: Arc::new(tokio::sync::Mutex::new(health_rx_main)),
                 state,
+                stats,
             },
             prompt_tx,
             shutdown_tx,
@@ -377,6 +382,7 @@ impl Pool<TextToTextWorkerHandle> {
             worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
             let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
             worker.core.touch();
+            let started = Instant::now();
 
             // Send request to worker
             let (response_tx, response_rx) = oneshot::channel();
@@ -398,11 +404,13 @@ impl Pool<TextToTextWorkerHandle> {
                 Ok(Ok(Ok(stream))) => {
                     // timeout Ok, recv Ok, result Ok
                     circuit.record_success();
+                    worker.core.stats.record_success(started.elapsed());
                     stream
                 }
                 Ok(Ok(Err(e))) => {
                     // timeout Ok, recv Ok, result Err
                     circuit.record_failure();
+                    worker.core.stats.record_failure(started.elapsed(), &e);
                     pool.metrics().total_errors.fetch_add(1, Ordering::Relaxed);
 
                     let _ = tx.send(CandleCompletionChunk::Error(format!("Worker error: {}", e)));
@@ -411,6 +419,10 @@ impl Pool<TextToTextWorkerHandle> {
                 Ok(Err(_)) => {
                     // timeout Ok, recv Err (channel closed)
                     circuit.record_failure();
+                    worker
+                        .core
+                        .stats
+                        .record_failure(started.elapsed(), "Response channel closed");
                     pool.metrics().total_errors.fetch_add(1, Ordering::Relaxed);
 
                     let _ = tx.send(CandleCompletionChunk::Error(
@@ -421,6 +433,10 @@ impl Pool<TextToTextWorkerHandle> {
                 Err(_) => {
                     // timeout Err
                     circuit.record_failure();
+                    worker
+                        .core
+                        .stats
+                        .record_failure(started.elapsed(), "Request timeout");
                     pool.metrics()
                         .total_timeouts
                         .fetch_add(1, Ordering::Relaxed);
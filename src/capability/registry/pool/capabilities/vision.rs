@@ -2,13 +2,13 @@ use once_cell::sync::Lazy;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{mpsc, oneshot};
 use tokio_stream::Stream;
 
 use crate::capability::registry::pool::core::memory_governor::AllocationGuard;
 use crate::capability::registry::pool::core::types::{
-    HealthPing, HealthPong, PendingRequestsGuard, select_worker_power_of_two,
+    HealthPing, HealthPong, PendingRequestsGuard, WorkerStats, select_worker_power_of_two,
 };
 use crate::capability::registry::pool::core::{Pool, PoolConfig, PoolError, WorkerHandle};
 use crate::capability::traits::VisionCapable;
@@ -204,6 +204,8 @@ impl Pool<VisionWorkerHandle> {
         use std::sync::atomic::AtomicU32;
         let state = Arc::new(AtomicU32::new(0)); // Spawning state
         let state_clone = Arc::clone(&state);
+        let stats = Arc::new(WorkerStats::default());
+        let stats_for_task = Arc::clone(&stats);
 
         // Spawn worker task
         tokio::spawn(async move {
@@ -235,6 +237,7 @@ impl Pool<VisionWorkerHandle> {
                         WorkerState::Failed as u32,
                         std::sync::atomic::Ordering::Release,
                     );
+                    stats_for_task.set_last_error(&e);
 
                     // Clean up memory tracking (CRITICAL FIX)
                     // This prevents memory leak when model loading fails
@@ -281,12 +284,14 @@ impl Pool<VisionWorkerHandle> {
             core: WorkerHandle {
                 pending_requests,
                 last_used,
+                last_request: Arc::new(AtomicU64::new(now)),
                 worker_id,
                 shutdown_tx: shutdown_tx.clone(),
                 per_worker_mb,
                 health_tx: health_tx_main,
                 health_rx: Arc::new(tokio::sync::Mutex::new(health_rx_main)),
                 state,
+                stats,
             },
             describe_image_tx,
             describe_url_tx,
@@ -375,6 +380,7 @@ impl Pool<VisionWorkerHandle> {
             worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
             let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
             worker.core.touch();
+            let started = Instant::now();
 
             // Send request to worker
             let (response_tx, response_rx) = oneshot::channel();
@@ -396,11 +402,13 @@ impl Pool<VisionWorkerHandle> {
                 Ok(Ok(Ok(stream))) => {
                     // timeout Ok, recv Ok, result Ok
                     circuit.record_success();
+                    worker.core.stats.record_success(started.elapsed());
                     stream
                 }
                 Ok(Ok(Err(e))) => {
                     // timeout Ok, recv Ok, result Err
                     circuit.record_failure();
+                    worker.core.stats.record_failure(started.elapsed(), &e);
                     pool.metrics().total_errors.fetch_add(1, Ordering::Relaxed);
 
                     let _ = tx.send(CandleStringChunk::text(format!("Worker error: {}", e)));
@@ -409,6 +417,10 @@ impl Pool<VisionWorkerHandle> {
                 Ok(Err(_)) => {
                     // timeout Ok, recv Err (channel closed)
                     circuit.record_failure();
+                    worker
+                        .core
+                        .stats
+                        .record_failure(started.elapsed(), "Response channel closed");
                     pool.metrics().total_errors.fetch_add(1, Ordering::Relaxed);
 
                     let _ = tx.send(CandleStringChunk::text(
@@ -419,6 +431,10 @@ impl Pool<VisionWorkerHandle> {
                 Err(_) => {
                     // timeout Err
                     circuit.record_failure();
+                    worker
+                        .core
+                        .stats
+                        .record_failure(started.elapsed(), "Request timeout");
                     pool.metrics()
                         .total_timeouts
                         .fetch_add(1, Ordering::Relaxed);
@@ -510,6 +526,7 @@ impl Pool<VisionWorkerHandle> {
             worker.core.pending_requests.fetch_add(1, Ordering::Relaxed);
             let _guard = PendingRequestsGuard::new(&worker.core.pending_requests);
             worker.core.touch();
+            let started = Instant::now();
 
             // Send request to worker
             let (response_tx, response_rx) = oneshot::channel();
@@ -531,11 +548,13 @@ impl Pool<VisionWorkerHandle> {
                 Ok(Ok(Ok(stream))) => {
                     // timeout Ok, recv Ok, result Ok
                     circuit.record_success();
+                    worker.core.stats.record_success(started.elapsed());
                     stream
                 }
                 Ok(Ok(Err(e))) => {
                     // timeout Ok, recv Ok, result Err
                     circuit.record_failure();
+                    worker.core.stats.record_failure(started.elapsed(), &e);
                     pool.metrics().total_errors.fetch_add(1, Ordering::Relaxed);
 
                     let _ = tx.send(CandleStringChunk::text(format!("Worker error: {}", e)));
@@ -544,6 +563,10 @@ impl Pool<VisionWorkerHandle> {
                 Ok(Err(_)) => {
                     // timeout Ok, recv Err (channel closed)
                     circuit.record_failure();
+                    worker
+                        .core
+                        .stats
+                        .record_failure(started.elapsed(), "Response channel closed");
                     pool.metrics().total_errors.fetch_add(1, Ordering::Relaxed);
 
                     let _ = tx.send(CandleStringChunk::text(
@@ -554,6 +577,10 @@ impl Pool<VisionWorkerHandle> {
                 Err(_) => {
                     // timeout Err
                     circuit.record_failure();
+                    worker
+                        .core
+                        .stats
+                        .record_failure(started.elapsed(), "Request timeout");
                     pool.metrics()
                         .total_timeouts
                         .fetch_add(1, Ordering::Relaxed);
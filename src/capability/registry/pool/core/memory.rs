@@ -6,3 +6,22 @@ pub fn query_system_memory_mb() -> usize {
     sys.refresh_memory();
     (sys.total_memory() / 1024 / 1024) as usize
 }
+
+/// Current system memory pressure, from the share of memory not available
+///
+/// Available memory counts reclaimable caches as free, unlike used memory.
+pub fn query_memory_pressure() -> super::MemoryPressure {
+    let mut sys = System::new();
+    sys.refresh_memory();
+    let total = sys.total_memory();
+    if total == 0 {
+        return super::MemoryPressure::Low;
+    }
+    let available = sys.available_memory().min(total);
+    match 1.0 - available as f64 / total as f64 {
+        p if p < 0.50 => super::MemoryPressure::Low,
+        p if p < 0.70 => super::MemoryPressure::Normal,
+        p if p < 0.85 => super::MemoryPressure::High,
+        _ => super::MemoryPressure::Critical,
+    }
+}
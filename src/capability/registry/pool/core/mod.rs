@@ -8,7 +8,7 @@ pub mod worker;
 pub mod worker_state;
 
 pub use error::PoolError;
-pub use memory::query_system_memory_mb;
+pub use memory::{query_memory_pressure, query_system_memory_mb};
 pub use memory_governor::{
     AllocationGuard, EvictionCandidate, MemoryError, MemoryGovernor, MemoryPressure,
 };
@@ -17,7 +17,10 @@ pub use spawn::{
     HasWorkers, MemoryGovernorAccess, SpawnLock, WorkerMetrics, ensure_workers_spawned,
     ensure_workers_spawned_adaptive,
 };
-pub use types::{PoolConfig, PoolMetrics, PoolWorkerHandle, SpawnGuard, WorkerHandle};
+pub use types::{
+    PoolConfig, PoolMetrics, PoolWorkerHandle, SpawnGuard, WorkerActivity, WorkerHandle,
+    WorkerStats, WorkerStatus, worker_activity,
+};
 pub use worker::{check_memory_available, spawn_worker_thread};
 pub use worker_state::{
     CircuitBreaker, HealthCheck, HealthStatus, UnifiedWorkerHandle, WorkerState,
@@ -8,7 +8,7 @@ use super::error::PoolError;
 use super::memory_governor::MemoryGovernor;
 use super::types::{
     HealthStatusLevel, MemoryHealth, ModelHealth, PoolConfig, PoolHealth, PoolMetrics,
-    PoolWorkerHandle, SpawnGuard, WorkerHealthStats,
+    PoolWorkerHandle, SpawnGuard, WorkerHealthStats, WorkerStatus,
 };
 use super::worker_state::{CircuitBreaker, CircuitBreakerConfig};
 
@@ -408,4 +408,27 @@ impl<W: PoolWorkerHandle> Pool<W> {
                 .unwrap_or(0),
         }
     }
+
+    /// Per-worker status: activity, requests served, average latency and last error
+    ///
+    /// Ordered by registry key, then worker ID.
+    pub fn status(&self) -> Vec<WorkerStatus> {
+        let mut status: Vec<WorkerStatus> = self
+            .workers
+            .iter()
+            .flat_map(|entry| {
+                entry
+                    .value()
+                    .iter()
+                    .map(|w| w.core().status(entry.key()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        status.sort_by(|a, b| {
+            a.registry_key
+                .cmp(&b.registry_key)
+                .then(a.worker_id.cmp(&b.worker_id))
+        });
+        status
+    }
 }
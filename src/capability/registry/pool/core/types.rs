@@ -276,11 +276,74 @@ impl PoolMetrics {
     }
 }
 
+/// Request outcomes for one worker
+///
+/// Shared between the worker handle and its spawn task, so load failures are
+/// recorded as well as request results.
+/// For streaming capabilities, latency is measured until the worker starts
+/// streaming.
+#[derive(Debug, Default)]
+pub struct WorkerStats {
+    pub requests_served: AtomicU64,
+    pub requests_failed: AtomicU64,
+    pub latency_sum_ms: AtomicU64,
+    last_error: std::sync::Mutex<Option<String>>,
+}
+
+impl WorkerStats {
+    /// Record a successful request
+    pub fn record_success(&self, elapsed: Duration) {
+        self.requests_served.fetch_add(1, Ordering::Relaxed);
+        self.latency_sum_ms
+            .fetch_add(elapsed.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Record a failed request and remember its error
+    pub fn record_failure(&self, elapsed: Duration, error: impl std::fmt::Display) {
+        self.requests_failed.fetch_add(1, Ordering::Relaxed);
+        self.latency_sum_ms
+            .fetch_add(elapsed.as_millis() as u64, Ordering::Relaxed);
+        self.set_last_error(error);
+    }
+
+    /// Record a request result
+    pub fn record<T, E: std::fmt::Display>(&self, elapsed: Duration, result: &Result<T, E>) {
+        match result {
+            Ok(_) => self.record_success(elapsed),
+            Err(e) => self.record_failure(elapsed, e),
+        }
+    }
+
+    /// Remember an error that was not a request (e.g. model load failure)
+    pub fn set_last_error(&self, error: impl std::fmt::Display) {
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = Some(error.to_string());
+        }
+    }
+
+    /// Most recent error, if any
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok().and_then(|e| e.clone())
+    }
+
+    /// Mean latency over completed requests; `None` before the first one
+    pub fn avg_latency_ms(&self) -> Option<f64> {
+        let count = self.requests_served.load(Ordering::Relaxed)
+            + self.requests_failed.load(Ordering::Relaxed);
+        (count > 0).then(|| self.latency_sum_ms.load(Ordering::Relaxed) as f64 / count as f64)
+    }
+}
+
 /// Handle to a worker thread (capability-specific channels defined in capabilities/)
 #[derive(Debug, Clone)]
 pub struct WorkerHandle {
     pub pending_requests: Arc<AtomicUsize>,
     pub last_used: Arc<AtomicU64>,
+    /// Last request routed to the worker (Unix seconds)
+    ///
+    /// Unlike `last_used`, health checks leave this alone, so it measures how
+    /// long the model has gone unused for keep-alive.
+    pub last_request: Arc<AtomicU64>,
     pub worker_id: usize,
     pub shutdown_tx: mpsc::UnboundedSender<()>,
     pub per_worker_mb: usize,
@@ -289,6 +352,9 @@ pub struct WorkerHandle {
 
     // NEW: Add state tracking
     pub state: Arc<AtomicU32>, // WorkerState as u32
+
+    /// Requests served, failures and latency
+    pub stats: Arc<WorkerStats>,
 }
 
 impl WorkerHandle {
@@ -308,12 +374,14 @@ impl WorkerHandle {
         Self {
             pending_requests: Arc::new(AtomicUsize::new(0)),
             last_used: Arc::new(AtomicU64::new(now)),
+            last_request: Arc::new(AtomicU64::new(now)),
             worker_id,
             shutdown_tx,
             per_worker_mb,
             health_tx,
             health_rx: Arc::new(tokio::sync::Mutex::new(health_rx)),
             state: Arc::new(AtomicU32::new(0)), // Start in Spawning state
+            stats: Arc::new(WorkerStats::default()),
         }
     }
 
@@ -325,6 +393,8 @@ impl WorkerHandle {
             .unwrap_or(0);
         self.last_used
             .store(now, std::sync::atomic::Ordering::Release);
+        self.last_request
+            .store(now, std::sync::atomic::Ordering::Release);
     }
 
     /// Check if worker is alive by sending health ping
@@ -436,6 +506,23 @@ impl WorkerHandle {
         use super::worker_state::WorkerState;
         matches!(self.get_state(), WorkerState::Ready | WorkerState::Idle)
     }
+
+    /// Snapshot of this worker for status reporting
+    pub fn status(&self, registry_key: &str) -> WorkerStatus {
+        let pending_requests = self.pending_requests.load(Ordering::Acquire);
+        WorkerStatus {
+            registry_key: registry_key.to_string(),
+            worker_id: self.worker_id,
+            state: worker_activity(self.get_state(), pending_requests),
+            pending_requests,
+            requests_served: self.stats.requests_served.load(Ordering::Relaxed),
+            requests_failed: self.stats.requests_failed.load(Ordering::Relaxed),
+            avg_latency_ms: self.stats.avg_latency_ms(),
+            last_error: self.stats.last_error(),
+            last_used: self.last_used.load(Ordering::Acquire),
+            memory_mb: self.per_worker_mb,
+        }
+    }
 }
 
 /// Select worker using Power of Two Choices algorithm (O(1) instead of O(n))
@@ -599,3 +686,25 @@ pub struct MemoryHealth {
     pub pressure: String,
     pub utilization: f64,
 }
+
+pub use kodegen_mcp_schema::candle::{WorkerActivity, WorkerStatus};
+
+/// Map a lifecycle state and in-flight request count to an activity
+pub fn worker_activity(
+    state: super::worker_state::WorkerState,
+    pending_requests: usize,
+) -> WorkerActivity {
+    use super::worker_state::WorkerState;
+    match state {
+        WorkerState::Spawning | WorkerState::Loading => WorkerActivity::Loading,
+        WorkerState::Ready | WorkerState::Processing | WorkerState::Idle => {
+            if pending_requests > 0 {
+                WorkerActivity::Busy
+            } else {
+                WorkerActivity::Idle
+            }
+        }
+        WorkerState::Evicting | WorkerState::Dead => WorkerActivity::Stopped,
+        WorkerState::Failed => WorkerActivity::Failed,
+    }
+}
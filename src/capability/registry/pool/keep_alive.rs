@@ -0,0 +1,161 @@
+//! Keep-alive: unloading models that have gone unused
+//!
+//! Each pool keeps its models loaded for a keep-alive period after the last
+//! request, then the maintenance thread shuts down every worker of the model
+//! to give the memory back. The next request loads the model again, and
+//! while that happens the model reports [`ModelResidency::Loading`].
+//!
+//! Durations use ollama's format: `30s`, `5m`, `1h`, a bare number of
+//! seconds, `0` to unload as soon as the model is idle, or any negative value
+//! to keep it loaded forever. Chat models default to [`DEFAULT_KEEP_ALIVE`]
+//! (override with `KODEGEN_KEEP_ALIVE`); embedding models stay loaded unless
+//! `KODEGEN_EMBEDDING_KEEP_ALIVE` is set, since they are needed for every
+//! memory lookup.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::capability::registry::ModelCapability;
+
+/// Keep-alive of chat, vision and image models when nothing is configured
+pub const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(5 * 60);
+
+/// Keep-alive of every pool except text embedding
+pub const KEEP_ALIVE_ENV: &str = "KODEGEN_KEEP_ALIVE";
+
+/// Keep-alive of the text embedding pool
+pub const EMBEDDING_KEEP_ALIVE_ENV: &str = "KODEGEN_EMBEDDING_KEEP_ALIVE";
+
+/// How long a model stays loaded after its last request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeepAlive {
+    /// Never unload
+    Forever,
+    /// Unload after this long without requests
+    Idle(Duration),
+}
+
+impl KeepAlive {
+    /// Idle time after which the model is unloaded, `None` for [`KeepAlive::Forever`]
+    pub fn idle_secs(self) -> Option<u64> {
+        match self {
+            Self::Forever => None,
+            Self::Idle(duration) => Some(duration.as_secs()),
+        }
+    }
+}
+
+/// Errors parsing a keep-alive duration
+#[derive(Debug, thiserror::Error)]
+pub enum KeepAliveError {
+    #[error("Invalid keep-alive '{0}': expected e.g. 30s, 5m, 1h, 0 or -1")]
+    Invalid(String),
+}
+
+impl FromStr for KeepAlive {
+    type Err = KeepAliveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let invalid = || KeepAliveError::Invalid(s.to_string());
+        if let Some(magnitude) = value.strip_prefix('-') {
+            // Any negative duration, ollama style
+            magnitude
+                .trim_end_matches(['s', 'm', 'h'])
+                .parse::<f64>()
+                .map_err(|_| invalid())?;
+            return Ok(Self::Forever);
+        }
+
+        let (number, unit_secs) = match value.char_indices().last() {
+            Some((i, 's')) => (&value[..i], 1.0),
+            Some((i, 'm')) => (&value[..i], 60.0),
+            Some((i, 'h')) => (&value[..i], 3600.0),
+            _ => (value, 1.0),
+        };
+        let amount: f64 = number.parse().map_err(|_| invalid())?;
+        if !amount.is_finite() {
+            return Err(invalid());
+        }
+        Ok(Self::Idle(Duration::from_secs_f64(amount * unit_secs)))
+    }
+}
+
+impl fmt::Display for KeepAlive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Forever => write!(f, "forever"),
+            Self::Idle(duration) => write!(f, "{}s", duration.as_secs()),
+        }
+    }
+}
+
+pub use kodegen_mcp_schema::candle::ModelResidency;
+
+static KEEP_ALIVE: LazyLock<RwLock<HashMap<ModelCapability, KeepAlive>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Models unloaded by keep-alive, with the Unix time they were unloaded
+static UNLOADED: LazyLock<Mutex<HashMap<(ModelCapability, String), u64>>> =
+    LazyLock::new(Default::default);
+
+/// Keep-alive from the environment, or the default for `capability`
+fn configured_keep_alive(capability: ModelCapability) -> KeepAlive {
+    let (env, default) = match capability {
+        ModelCapability::TextEmbedding => (EMBEDDING_KEEP_ALIVE_ENV, KeepAlive::Forever),
+        _ => (KEEP_ALIVE_ENV, KeepAlive::Idle(DEFAULT_KEEP_ALIVE)),
+    };
+    match std::env::var(env) {
+        Ok(value) => value.parse().unwrap_or_else(|e| {
+            log::warn!("{e} in {env}, using {default}");
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+/// Keep-alive for models in the `capability` pool
+pub fn keep_alive(capability: ModelCapability) -> KeepAlive {
+    if let Some(keep_alive) = KEEP_ALIVE.read().get(&capability) {
+        return *keep_alive;
+    }
+    *KEEP_ALIVE
+        .write()
+        .entry(capability)
+        .or_insert_with(|| configured_keep_alive(capability))
+}
+
+/// Override the keep-alive for models in the `capability` pool
+///
+/// Takes effect at the next maintenance pass.
+pub fn set_keep_alive(capability: ModelCapability, keep_alive: KeepAlive) {
+    KEEP_ALIVE.write().insert(capability, keep_alive);
+}
+
+/// Record that keep-alive unloaded `registry_key`
+pub(crate) fn record_unload(capability: ModelCapability, registry_key: &str) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    UNLOADED
+        .lock()
+        .insert((capability, registry_key.to_string()), now);
+}
+
+/// Models unloaded by keep-alive, with the Unix time they were unloaded
+///
+/// Includes models that have since been reloaded; callers filter those out
+/// against the pools.
+pub(crate) fn unloaded_models() -> Vec<(ModelCapability, String, u64)> {
+    UNLOADED
+        .lock()
+        .iter()
+        .map(|((capability, key), at)| (*capability, key.clone(), *at))
+        .collect()
+}
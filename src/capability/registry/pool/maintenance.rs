@@ -6,12 +6,14 @@ use super::capabilities::{
     image_embedding_pool, text_embedding_pool, text_to_image_pool, text_to_text_pool, vision_pool,
 };
 use super::core::Pool;
+use super::keep_alive::{keep_alive, record_unload};
+use crate::capability::registry::ModelCapability;
 
 /// Check if all workers for a model are idle
 ///
 /// A worker is considered idle if:
 /// - It has no pending requests (pending_requests == 0)
-/// - It hasn't been used for at least idle_threshold_secs seconds
+/// - It hasn't received a request for at least idle_threshold_secs seconds
 /// - It's in an evictable state (Ready or Idle, not Loading or Processing)
 fn all_workers_idle<W: super::core::types::PoolWorkerHandle>(
     workers: &[W],
@@ -40,8 +42,9 @@ fn all_workers_idle<W: super::core::types::PoolWorkerHandle>(
         }
 
         let pending = core.pending_requests.load(Ordering::Acquire);
-        let last_used = core.last_used.load(Ordering::Acquire);
-        let idle_duration = now.saturating_sub(last_used);
+        // Health checks refresh last_used, so measure idleness by requests
+        let last_request = core.last_request.load(Ordering::Acquire);
+        let idle_duration = now.saturating_sub(last_request);
 
         pending == 0 && idle_duration >= idle_threshold_secs
     })
@@ -49,13 +52,13 @@ fn all_workers_idle<W: super::core::types::PoolWorkerHandle>(
 
 /// Find least recently used (LRU) worker
 ///
-/// Returns the index of the worker with the oldest last_used timestamp.
+/// Returns the index of the worker with the oldest last_request timestamp.
 /// Returns None if workers vector is empty.
 fn find_lru_worker<W: super::core::types::PoolWorkerHandle>(workers: &[W]) -> Option<usize> {
     workers
         .iter()
         .enumerate()
-        .min_by_key(|(_, w)| w.core().last_used.load(Ordering::Acquire))
+        .min_by_key(|(_, w)| w.core().last_request.load(Ordering::Acquire))
         .map(|(idx, _)| idx)
 }
 
@@ -189,19 +192,68 @@ fn validate_pool_health<T: super::core::types::PoolWorkerHandle>(
     }
 }
 
+/// Unload a model: shut down all of its workers and drop the copy behind
+/// its shared loaded-model handle
+///
+/// Called when the model has been idle for longer than its keep-alive. The
+/// next request for the model spawns workers again, and the next use of the
+/// handle reloads it.
+#[instrument(skip(pool), fields(registry_key = %registry_key))]
+fn unload_model<W: super::core::types::PoolWorkerHandle>(
+    pool: &Pool<W>,
+    registry_key: &str,
+    capability: ModelCapability,
+) {
+    let Some(workers) = pool.workers().remove(registry_key).map(|(_, workers)| workers) else {
+        return;
+    };
+
+    let mut freed_mb = 0;
+    for worker in &workers {
+        if let Err(e) = worker.core().shutdown_tx.send(()) {
+            warn!(
+                worker_id = worker.core().worker_id,
+                error = %e,
+                "Failed to send shutdown signal"
+            );
+        }
+        pool.remove_memory(worker.core().per_worker_mb);
+        freed_mb += worker.core().per_worker_mb;
+    }
+
+    pool.metrics()
+        .workers_evicted
+        .fetch_add(workers.len(), Ordering::Release);
+    if crate::capability::registry::unload_loaded_model(registry_key) {
+        debug!("Dropped the shared loaded copy of the model");
+    }
+    record_unload(capability, registry_key);
+
+    info!(
+        workers = workers.len(),
+        freed_mb = freed_mb,
+        "Unloaded model (keep-alive expired)"
+    );
+}
+
 /// Process maintenance for one pool
 ///
-/// Iterates over all models in the pool and evicts one LRU worker
-/// per idle model.
+/// Iterates over all models in the pool. A model idle for longer than its
+/// keep-alive is unloaded entirely; otherwise an idle model with more than
+/// one worker is scaled down by evicting its LRU worker.
 fn process_pool_maintenance<W: super::core::types::PoolWorkerHandle>(
     pool: &'static Pool<W>,
     idle_threshold_secs: u64,
+    capability: ModelCapability,
     pool_name: &str,
 ) {
     // FIRST: Clean up dead/failed workers
     cleanup_dead_workers(pool);
 
+    let keep_alive_secs = keep_alive(capability).idle_secs();
+
     // Collect models that need eviction (to avoid holding locks)
+    let mut models_to_unload = Vec::new();
     let mut models_to_evict = Vec::new();
 
     // Scan all models in pool
@@ -209,15 +261,26 @@ fn process_pool_maintenance<W: super::core::types::PoolWorkerHandle>(
         let registry_key = entry.key().clone();
         let workers = entry.value();
 
-        // Check if all workers are idle and there's at least one worker
-        if all_workers_idle(workers, idle_threshold_secs) && !workers.is_empty() {
-            // Find LRU worker index
+        if keep_alive_secs.is_some_and(|secs| all_workers_idle(workers, secs)) {
+            models_to_unload.push(registry_key);
+        } else if workers.len() > 1 && all_workers_idle(workers, idle_threshold_secs) {
+            // Scale down, keeping the last worker for keep-alive
             if let Some(lru_idx) = find_lru_worker(workers) {
                 models_to_evict.push((registry_key, lru_idx));
             }
         }
     }
 
+    for registry_key in models_to_unload {
+        debug!(
+            pool_name = %pool_name,
+            registry_key = %registry_key,
+            keep_alive_secs = ?keep_alive_secs,
+            "Keep-alive expired, unloading model"
+        );
+        unload_model(pool, &registry_key, capability);
+    }
+
     // Perform evictions (after releasing iterator locks)
     for (registry_key, lru_idx) in models_to_evict {
         // Get per_worker_mb from the worker handle
@@ -272,7 +335,8 @@ fn log_memory_usage() {
 ///
 /// Runs every 1 minute (configurable via pool config):
 /// - Check each pool for idle workers
-/// - Evict 1 LRU worker per idle model
+/// - Unload models idle for longer than their keep-alive
+/// - Evict 1 LRU worker per other idle model with more than one worker
 /// - Monitor system memory pressure
 /// - Log eviction events
 ///
@@ -312,11 +376,36 @@ pub fn start_maintenance_thread() -> Result<tokio::task::JoinHandle<()>, String>
             validate_pool_health(text_to_image_pool(), "TextToImage");
 
             // Process each pool (evict idle workers)
-            process_pool_maintenance(text_embedding_pool(), idle_threshold, "TextEmbedding");
-            process_pool_maintenance(text_to_text_pool(), idle_threshold, "TextToText");
-            process_pool_maintenance(image_embedding_pool(), idle_threshold, "ImageEmbedding");
-            process_pool_maintenance(vision_pool(), idle_threshold, "Vision");
-            process_pool_maintenance(text_to_image_pool(), idle_threshold, "TextToImage");
+            process_pool_maintenance(
+                text_embedding_pool(),
+                idle_threshold,
+                ModelCapability::TextEmbedding,
+                "TextEmbedding",
+            );
+            process_pool_maintenance(
+                text_to_text_pool(),
+                idle_threshold,
+                ModelCapability::TextToText,
+                "TextToText",
+            );
+            process_pool_maintenance(
+                image_embedding_pool(),
+                idle_threshold,
+                ModelCapability::ImageEmbedding,
+                "ImageEmbedding",
+            );
+            process_pool_maintenance(
+                vision_pool(),
+                idle_threshold,
+                ModelCapability::Vision,
+                "Vision",
+            );
+            process_pool_maintenance(
+                text_to_image_pool(),
+                idle_threshold,
+                ModelCapability::TextToImage,
+                "TextToImage",
+            );
 
             // Log memory usage
             log_memory_usage();
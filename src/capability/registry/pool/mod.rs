@@ -90,10 +90,16 @@
 //!
 //! 1. Scans all 5 pools for idle models
 //! 2. For each idle model:
-//!    - Check: **ALL workers** idle? (pending == 0, idle >= 5 minutes)
-//!    - If yes: Find LRU worker, send shutdown signal
+//!    - Check: **ALL workers** idle for longer than the pool's keep-alive?
+//!      If yes: unload the model, shutting down every worker (see [`keep_alive`])
+//!    - Otherwise, more than one worker and all idle >= cooldown? Find LRU
+//!      worker, send shutdown signal
 //!    - Worker loop receives signal and exits
 //!    - Update memory tracking: `total_memory_mb -= per_worker_mb`
+//!
+//! Idleness is measured from the last request, not the last health check.
+//! An unloaded model is reloaded by its next request and reports
+//! [`ModelResidency::Loading`] in [`all_model_status`] until a worker is ready.
 //! 3. Log memory usage across all pools
 //!
 //! ## Memory Management
@@ -217,19 +223,30 @@
 //!     vision.rs               - Vision pool + worker loop
 //!     text_to_image.rs        - TextToImage pool + worker loop
 //!   maintenance.rs            - Background eviction thread
+//!   status.rs                 - Per-worker status across all pools
 //! ```
 
 pub mod capabilities;
 pub mod core;
+pub mod keep_alive;
 pub mod maintenance;
 pub mod shutdown;
+pub mod status;
 
 pub use capabilities::{
     image_embedding_pool, text_embedding_pool, text_to_image_pool, text_to_text_pool, vision_pool,
 };
-pub use core::{Pool, PoolConfig, PoolError, WorkerHandle, WorkerState};
+pub use core::{
+    Pool, PoolConfig, PoolError, WorkerActivity, WorkerHandle, WorkerState, WorkerStatus,
+    worker_activity,
+};
+pub use keep_alive::{KeepAlive, KeepAliveError, ModelResidency, keep_alive, set_keep_alive};
 pub use maintenance::start_maintenance_thread;
 pub use shutdown::begin_shutdown;
+pub use status::{
+    ModelLoadStatus, PoolWorkerStatus, all_model_status, all_worker_status,
+    prometheus_model_status, prometheus_worker_status,
+};
 
 use once_cell::sync::Lazy;
 
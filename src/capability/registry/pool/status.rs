@@ -0,0 +1,200 @@
+//! Per-worker status across the 5 global pools
+//!
+//! [`Pool::status`](super::Pool::status) reports each worker's activity,
+//! requests served, average latency and last error. [`all_worker_status`]
+//! collects it from every pool for the `candle_pool_status` tool, and
+//! [`prometheus_worker_status`] renders it for the `/metrics` endpoint.
+//! [`all_model_status`] summarizes the same per model, including models
+//! unloaded by keep-alive, and [`prometheus_model_status`] renders that.
+
+use super::core::WorkerActivity;
+use super::keep_alive::{ModelResidency, keep_alive, unloaded_models};
+use super::{
+    image_embedding_pool, text_embedding_pool, text_to_image_pool, text_to_text_pool, vision_pool,
+};
+use crate::capability::registry::ModelCapability;
+
+pub use kodegen_mcp_schema::candle::{ModelLoadStatus, PoolWorkerStatus};
+
+/// Status of every worker in every pool
+pub fn all_worker_status() -> Vec<PoolWorkerStatus> {
+    let pools = [
+        (ModelCapability::TextToText, text_to_text_pool().status()),
+        (ModelCapability::TextEmbedding, text_embedding_pool().status()),
+        (ModelCapability::ImageEmbedding, image_embedding_pool().status()),
+        (ModelCapability::TextToImage, text_to_image_pool().status()),
+        (ModelCapability::Vision, vision_pool().status()),
+    ];
+    pools
+        .into_iter()
+        .flat_map(|(capability, workers)| {
+            workers
+                .into_iter()
+                .map(move |worker| PoolWorkerStatus { capability, worker })
+        })
+        .collect()
+}
+
+
+/// Residency of every loaded, loading or keep-alive-unloaded model
+///
+/// Ordered by capability name, then registry key.
+pub fn all_model_status() -> Vec<ModelLoadStatus> {
+    let workers = all_worker_status();
+    let mut models: Vec<ModelLoadStatus> = Vec::new();
+
+    for w in &workers {
+        let state = match w.worker.state {
+            WorkerActivity::Idle | WorkerActivity::Busy => ModelResidency::Loaded,
+            WorkerActivity::Loading => ModelResidency::Loading,
+            WorkerActivity::Stopped | WorkerActivity::Failed => ModelResidency::Unloaded,
+        };
+        match models
+            .iter_mut()
+            .find(|m| m.capability == w.capability && m.registry_key == w.worker.registry_key)
+        {
+            Some(model) => {
+                model.workers += 1;
+                model.state = model.state.max(state);
+            }
+            None => models.push(ModelLoadStatus {
+                capability: w.capability,
+                registry_key: w.worker.registry_key.clone(),
+                state,
+                workers: 1,
+                keep_alive_secs: keep_alive(w.capability).idle_secs(),
+                unloaded_at: None,
+            }),
+        }
+    }
+
+    for (capability, registry_key, unloaded_at) in unloaded_models() {
+        if let Some(model) = models
+            .iter_mut()
+            .find(|m| m.capability == capability && m.registry_key == registry_key)
+        {
+            model.unloaded_at = Some(unloaded_at);
+        } else {
+            models.push(ModelLoadStatus {
+                capability,
+                registry_key,
+                state: ModelResidency::Unloaded,
+                workers: 0,
+                keep_alive_secs: keep_alive(capability).idle_secs(),
+                unloaded_at: Some(unloaded_at),
+            });
+        }
+    }
+
+    models.sort_by(|a, b| {
+        a.capability
+            .as_str()
+            .cmp(b.capability.as_str())
+            .then_with(|| a.registry_key.cmp(&b.registry_key))
+    });
+    models
+}
+
+/// Render worker status in Prometheus text format
+pub fn prometheus_worker_status(workers: &[PoolWorkerStatus]) -> String {
+    const STATES: [(WorkerActivity, &str); 5] = [
+        (WorkerActivity::Loading, "loading"),
+        (WorkerActivity::Idle, "idle"),
+        (WorkerActivity::Busy, "busy"),
+        (WorkerActivity::Stopped, "stopped"),
+        (WorkerActivity::Failed, "failed"),
+    ];
+
+    let labels = |w: &PoolWorkerStatus| {
+        format!(
+            "capability=\"{}\",model=\"{}\",worker=\"{}\"",
+            w.capability.as_str(),
+            w.worker.registry_key,
+            w.worker.worker_id
+        )
+    };
+
+    let mut output = String::with_capacity(256 + workers.len() * 512);
+
+    output.push_str("# HELP pool_worker_state Current worker state (1 for the active state)\n");
+    output.push_str("# TYPE pool_worker_state gauge\n");
+    for w in workers {
+        for (state, name) in STATES {
+            output.push_str(&format!(
+                "pool_worker_state{{{},state=\"{}\"}} {}\n",
+                labels(w),
+                name,
+                u8::from(w.worker.state == state)
+            ));
+        }
+    }
+
+    output.push_str("# HELP pool_worker_pending_requests Requests in flight per worker\n");
+    output.push_str("# TYPE pool_worker_pending_requests gauge\n");
+    for w in workers {
+        output.push_str(&format!(
+            "pool_worker_pending_requests{{{}}} {}\n",
+            labels(w),
+            w.worker.pending_requests
+        ));
+    }
+
+    output.push_str("# HELP pool_worker_requests_served_total Requests completed per worker\n");
+    output.push_str("# TYPE pool_worker_requests_served_total counter\n");
+    for w in workers {
+        output.push_str(&format!(
+            "pool_worker_requests_served_total{{{}}} {}\n",
+            labels(w),
+            w.worker.requests_served
+        ));
+    }
+
+    output.push_str("# HELP pool_worker_requests_failed_total Requests failed per worker\n");
+    output.push_str("# TYPE pool_worker_requests_failed_total counter\n");
+    for w in workers {
+        output.push_str(&format!(
+            "pool_worker_requests_failed_total{{{}}} {}\n",
+            labels(w),
+            w.worker.requests_failed
+        ));
+    }
+
+    output.push_str("# HELP pool_worker_latency_avg_ms Average request latency per worker\n");
+    output.push_str("# TYPE pool_worker_latency_avg_ms gauge\n");
+    for w in workers {
+        if let Some(latency) = w.worker.avg_latency_ms {
+            output.push_str(&format!(
+                "pool_worker_latency_avg_ms{{{}}} {:.2}\n",
+                labels(w),
+                latency
+            ));
+        }
+    }
+
+    output
+}
+
+/// Render model residency in Prometheus text format
+pub fn prometheus_model_status(models: &[ModelLoadStatus]) -> String {
+    const STATES: [(ModelResidency, &str); 3] = [
+        (ModelResidency::Loaded, "loaded"),
+        (ModelResidency::Loading, "loading"),
+        (ModelResidency::Unloaded, "unloaded"),
+    ];
+
+    let mut output = String::with_capacity(128 + models.len() * 256);
+    output.push_str("# HELP pool_model_state Current model residency (1 for the active state)\n");
+    output.push_str("# TYPE pool_model_state gauge\n");
+    for m in models {
+        for (state, name) in STATES {
+            output.push_str(&format!(
+                "pool_model_state{{capability=\"{}\",model=\"{}\",state=\"{}\"}} {}\n",
+                m.capability.as_str(),
+                m.registry_key,
+                name,
+                u8::from(m.state == state)
+            ));
+        }
+    }
+    output
+}
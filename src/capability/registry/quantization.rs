@@ -0,0 +1,140 @@
+//! Quantization variants selectable per registry key
+//!
+//! A text-to-text model can also be looked up by the GGUF repository its
+//! weights come from, and that key may end in `:VARIANT` to pick a
+//! quantization other than the model's default, e.g.
+//! `"unsloth/Qwen3-1.7B-GGUF:Q8_0"`. Variant models are registered on first
+//! lookup under their own key, so they get their own pool workers, memory
+//! estimate and weight file.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::enums::TextToTextModel;
+use super::storage::TEXT_TO_TEXT_UNIFIED;
+use crate::domain::model::CandleModelInfo;
+use crate::domain::model::traits::CandleModel;
+
+/// GGUF quantization of a text-to-text model
+#[allow(non_camel_case_types)]
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum QuantVariant {
+    /// 4-bit k-quant, medium: smallest footprint (default)
+    #[default]
+    Q4_K_M,
+    /// 5-bit k-quant, medium: better quality for ~15% more memory
+    Q5_K_M,
+    /// 8-bit: near-lossless, roughly 1.6x the memory of `Q4_K_M`
+    Q8_0,
+}
+
+impl QuantVariant {
+    /// Every selectable variant, smallest first
+    pub const ALL: [Self; 3] = [Self::Q4_K_M, Self::Q5_K_M, Self::Q8_0];
+
+    /// Name as used in GGUF filenames and registry keys
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Q4_K_M => "Q4_K_M",
+            Self::Q5_K_M => "Q5_K_M",
+            Self::Q8_0 => "Q8_0",
+        }
+    }
+
+    /// Variant for a name, ignoring case (`q8_0` and `Q8_0` both work)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+impl std::fmt::Display for QuantVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Split a registry key into its base key and requested variant
+///
+/// Suffixes that are not a known variant (such as `phi4:latest`) are left as
+/// part of the key.
+pub fn split_variant(registry_key: &str) -> (&str, Option<QuantVariant>) {
+    match registry_key.rsplit_once(':') {
+        Some((base, suffix)) => match QuantVariant::from_name(suffix) {
+            Some(variant) => (base, Some(variant)),
+            None => (registry_key, None),
+        },
+        None => (registry_key, None),
+    }
+}
+
+impl TextToTextModel {
+    /// The same model at another quantization
+    ///
+    /// Returns `None` if the model does not offer `variant`.
+    pub fn with_quantization(&self, variant: QuantVariant) -> Option<Self> {
+        match self {
+            Self::Qwen3Quantized(m) => Some(Self::Qwen3Quantized(std::sync::Arc::new(
+                (**m).clone().with_variant(variant),
+            ))),
+            Self::Gguf(m) => (m.info().quantization == variant.as_str()).then(|| self.clone()),
+        }
+    }
+
+    /// Model info of every quantization the model offers
+    pub fn quantization_variants(&self) -> Vec<&'static CandleModelInfo> {
+        match self {
+            Self::Qwen3Quantized(_) => QuantVariant::ALL
+                .into_iter()
+                .map(crate::capability::text_to_text::CandleQwen3QuantizedModel::variant_info)
+                .collect(),
+            Self::Gguf(m) => vec![m.info()],
+        }
+    }
+}
+
+/// GGUF repository a model's weights are downloaded from (`org/repo`)
+fn gguf_repo(info: &CandleModelInfo) -> Option<&'static str> {
+    info.quantization_url?
+        .rsplit_once('/')
+        .map(|(repo, _)| repo)
+}
+
+/// Registered model for a base key: its registry key or its GGUF repository
+fn find_base(base: &str) -> Option<TextToTextModel> {
+    let registry = TEXT_TO_TEXT_UNIFIED.read();
+    if let Some(model) = registry.get(base) {
+        return Some(model.clone());
+    }
+    registry
+        .iter()
+        .find(|(key, model)| {
+            split_variant(key).1.is_none() && gguf_repo(model.info()) == Some(base)
+        })
+        .map(|(_, model)| model.clone())
+}
+
+/// Resolve a GGUF repository or `base:VARIANT` key, registering the variant
+/// on first use
+///
+/// The default variant resolves to the base model itself.
+pub(super) fn resolve_variant(registry_key: &str) -> Option<TextToTextModel> {
+    let (base, variant) = split_variant(registry_key);
+    let base_model = find_base(base)?;
+    let Some(variant) = variant else {
+        return Some(base_model);
+    };
+    let model = base_model.with_quantization(variant)?;
+
+    let key = model.info().registry_key.to_string();
+    if key == base_model.info().registry_key {
+        return Some(base_model);
+    }
+
+    log::info!("Registering {} quantization of '{}' as '{}'", variant, base, key);
+    let mut registry = TEXT_TO_TEXT_UNIFIED.write();
+    Some(registry.entry(key).or_insert(model).clone())
+}
@@ -5,8 +5,12 @@ use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
 use super::enums::*;
-use crate::capability::text_embedding::StellaEmbeddingModel;
-use crate::capability::text_to_text::CandleQwen3QuantizedModel;
+use crate::capability::lora::LoraAdapter;
+use crate::capability::text_embedding::encoder::ENCODER_EMBEDDING_MODELS;
+use crate::capability::text_embedding::{EncoderEmbeddingModel, StellaEmbeddingModel};
+use crate::capability::text_to_text::{
+    CandleGgufChatModel, CandleQwen3QuantizedModel, GGUF_CHAT_MODELS, LoadedModelHandle,
+};
 use crate::capability::vision::LLaVAModel;
 use crate::domain::model::traits::CandleModel;
 
@@ -19,8 +23,9 @@ use crate::domain::model::traits::CandleModel;
 
 /// Unified text-to-text model registry
 ///
-/// Initialized with Qwen3Quantized model and supports runtime registration
-/// for models requiring async initialization.
+/// Initialized with Qwen3Quantized and the other GGUF chat models (Llama 3.x,
+/// Mistral, Phi-3), and supports runtime registration for models requiring
+/// async initialization.
 pub(super) static TEXT_TO_TEXT_UNIFIED: LazyLock<RwLock<HashMap<String, TextToTextModel>>> =
     LazyLock::new(|| {
         let mut map = HashMap::new();
@@ -29,19 +34,38 @@ pub(super) static TEXT_TO_TEXT_UNIFIED: LazyLock<RwLock<HashMap<String, TextToTe
         let key = model.info().registry_key.to_string();
         map.insert(key, TextToTextModel::Qwen3Quantized(model));
 
+        for spec in GGUF_CHAT_MODELS {
+            match CandleGgufChatModel::new(spec) {
+                Ok(model) => {
+                    let key = model.info().registry_key.to_string();
+                    map.insert(key, TextToTextModel::Gguf(Arc::new(model)));
+                }
+                Err(e) => log::warn!("Failed to register {}: {}", spec.info.name, e),
+            }
+        }
+
         RwLock::new(map)
     });
 
 /// Unified text embedding model registry
 ///
-/// Initialized with Stella embedding model.
+/// Initialized with the Stella 400M and 1.5B embedding models and the
+/// encoder models (BGE-M3, GTE-large, nomic-embed-text).
 pub(super) static TEXT_EMBEDDING_UNIFIED: LazyLock<RwLock<HashMap<String, TextEmbeddingModel>>> =
     LazyLock::new(|| {
         let mut map = HashMap::new();
 
-        let model = Arc::new(StellaEmbeddingModel::default());
-        let key = model.info().registry_key.to_string();
-        map.insert(key, TextEmbeddingModel::Stella(model));
+        for model in [StellaEmbeddingModel::new(), StellaEmbeddingModel::large()] {
+            let model = Arc::new(model);
+            let key = model.info().registry_key.to_string();
+            map.insert(key, TextEmbeddingModel::Stella(model));
+        }
+
+        for spec in ENCODER_EMBEDDING_MODELS {
+            let model = Arc::new(EncoderEmbeddingModel::new(spec));
+            let key = model.info().registry_key.to_string();
+            map.insert(key, TextEmbeddingModel::Encoder(model));
+        }
 
         RwLock::new(map)
     });
@@ -73,3 +97,22 @@ pub(crate) static VISION_UNIFIED: LazyLock<RwLock<HashMap<String, VisionModel>>>
 
         RwLock::new(map)
     });
+
+/// Cached tool-description embeddings
+///
+/// Keyed by (embedding model registry_key, tool name, description hash) so a
+/// changed description or a different embedding model never reuses a stale vector.
+pub(super) static TOOL_EMBEDDING_CACHE: LazyLock<RwLock<HashMap<(String, String, u64), Arc<[f32]>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Shared loaded-model handles for in-process inference outside the pool
+///
+/// Keyed by text-to-text registry_key so every agent and turn reuses one load.
+pub(super) static LOADED_MODEL_HANDLES: LazyLock<RwLock<HashMap<String, LoadedModelHandle>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// LoRA adapters registered per base model
+///
+/// Keyed by text-to-text registry_key; adapters keep registration order.
+pub(super) static LORA_ADAPTERS: LazyLock<RwLock<HashMap<String, Vec<LoraAdapter>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
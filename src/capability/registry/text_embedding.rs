@@ -1,12 +1,15 @@
 //! TextEmbeddingCapable trait implementation for TextEmbeddingModel
 
 use super::pool::capabilities::text_embedding_pool;
-use super::pool::core::{PoolError, ensure_workers_spawned_adaptive};
+use super::pool::core::{PoolError, ensure_workers_spawned_adaptive, query_memory_pressure};
+use crate::capability::text_embedding::adaptive_batch::batch_sizer;
 use crate::capability::traits::TextEmbeddingCapable;
 use crate::domain::model::traits::CandleModel;
 use std::sync::Arc;
+use std::time::Instant;
 
 // LoadedModel imports
+use crate::capability::text_embedding::encoder::LoadedEncoderModel;
 use crate::capability::text_embedding::stella::LoadedStellaModel;
 
 use super::enums::TextEmbeddingModel;
@@ -31,6 +34,7 @@ impl TextEmbeddingCapable for TextEmbeddingModel {
         Box::pin(async move {
             match self {
                 Self::Stella(m) => spawn_embed_stella(m, &text, task).await,
+                Self::Encoder(m) => spawn_embed_encoder(m, &text, task).await,
             }
         })
     }
@@ -54,6 +58,7 @@ impl TextEmbeddingCapable for TextEmbeddingModel {
         Box::pin(async move {
             match self {
                 Self::Stella(m) => spawn_batch_embed_stella(m, &texts, task).await,
+                Self::Encoder(m) => spawn_batch_embed_encoder(m, &texts, task).await,
             }
         })
     }
@@ -61,6 +66,28 @@ impl TextEmbeddingCapable for TextEmbeddingModel {
     fn embedding_dimension(&self) -> usize {
         match self {
             Self::Stella(m) => m.embedding_dimension(),
+            Self::Encoder(m) => m.embedding_dimension(),
+        }
+    }
+
+    fn supported_dimensions(&self) -> Vec<usize> {
+        match self {
+            Self::Stella(m) => m.supported_dimensions(),
+            Self::Encoder(m) => m.supported_dimensions(),
+        }
+    }
+
+    fn recommended_batch_size(&self) -> usize {
+        match self {
+            Self::Stella(m) => m.batch_sizes().0,
+            Self::Encoder(m) => m.batch_sizes().0,
+        }
+    }
+
+    fn max_batch_size(&self) -> usize {
+        match self {
+            Self::Stella(m) => m.batch_sizes().1,
+            Self::Encoder(m) => m.batch_sizes().1,
         }
     }
 }
@@ -140,9 +167,23 @@ macro_rules! impl_text_embedding_spawn {
             .await
             .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
 
-            pool.batch_embed_text(registry_key, texts, task)
-                .await
-                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+            // Split into batches sized by observed throughput on this device
+            let (recommended, max) = model.batch_sizes();
+            let sizer = batch_sizer(registry_key, recommended, max);
+            let mut embeddings = Vec::with_capacity(texts.len());
+            let mut start = 0;
+            while start < texts.len() {
+                let end = (start + sizer.batch_size()).min(texts.len());
+                let began = Instant::now();
+                let batch = pool
+                    .batch_embed_text(registry_key, &texts[start..end], task.clone())
+                    .await
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+                sizer.record(end - start, began.elapsed(), query_memory_pressure());
+                embeddings.extend(batch);
+                start = end;
+            }
+            Ok(embeddings)
         }
     };
 }
@@ -154,3 +195,10 @@ impl_text_embedding_spawn!(
     crate::capability::text_embedding::stella::StellaEmbeddingModel,
     LoadedStellaModel
 );
+
+impl_text_embedding_spawn!(
+    spawn_embed_encoder,
+    spawn_batch_embed_encoder,
+    crate::capability::text_embedding::encoder::EncoderEmbeddingModel,
+    LoadedEncoderModel
+);
@@ -4,16 +4,30 @@ use super::pool::capabilities::text_to_text_pool;
 use super::pool::core::{PoolError, ensure_workers_spawned_adaptive};
 use crate::capability::traits::TextToTextCapable;
 use crate::domain::completion::{CandleCompletionChunk, types::CandleCompletionParams};
+use crate::domain::model::CandleModelError;
 use crate::domain::model::traits::CandleModel;
 use crate::domain::prompt::CandlePrompt;
+use std::collections::HashMap;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use tokio_stream::Stream;
 
 // LoadedModel imports
-use crate::capability::text_to_text::qwen3_quantized::LoadedQwen3QuantizedModel;
+use crate::capability::text_to_text::chat_template::ChatTemplateFamily;
+use crate::capability::text_to_text::gguf_chat::LoadedGgufChatModel;
+use crate::capability::text_to_text::qwen3_quantized::{
+    CandleQwen3QuantizedModel, LoadedQwen3QuantizedModel,
+};
+use crate::domain::chat::templates::{ModelChatTemplate, TemplateResult};
 
+use super::api::FromRegistry;
 use super::enums::TextToTextModel;
+use super::quantization::split_variant;
+use super::storage::TEXT_TO_TEXT_UNIFIED;
+use super::loaded_models::loaded_model_handle;
+use super::lora_adapters::resolve_lora_adapters;
+use crate::capability::lora::LoraAdapter;
+use crate::capability::lora::requested_adapters;
 
 impl TextToTextCapable for TextToTextModel {
     fn prompt(
@@ -25,49 +39,162 @@ impl TextToTextCapable for TextToTextModel {
             Self::Qwen3Quantized(m) => {
                 spawn_stream_qwen3_quantized(m.clone(), prompt, params.clone())
             }
+            Self::Gguf(m) => spawn_stream_gguf_chat(m.clone(), prompt, params.clone()),
         }
     }
 }
 
+impl TextToTextModel {
+    /// The registered model called `name` (its `CandleModelInfo::name`, e.g.
+    /// `llama-3.2-3b`), ignoring case
+    ///
+    /// Quantization variants share their base model's name; the base model
+    /// is returned.
+    pub fn by_name(name: &str) -> Option<Self> {
+        TEXT_TO_TEXT_UNIFIED
+            .read()
+            .iter()
+            .find(|(key, model)| {
+                split_variant(key).1.is_none() && model.info().name.eq_ignore_ascii_case(name)
+            })
+            .map(|(_, model)| model.clone())
+    }
+
+    /// Built-in prompt markup for the model; `None` when it uses the
+    /// template shipped in its `tokenizer_config.json`
+    pub fn chat_template(&self) -> Option<ChatTemplateFamily> {
+        match self {
+            Self::Qwen3Quantized(_) => Some(ChatTemplateFamily::ChatMl),
+            Self::Gguf(m) => m.chat_template(),
+        }
+    }
+}
+
+/// A text-to-text model, or the registry key or name of one
+///
+/// Lets `.model(...)` on the agent builders take either a
+/// [`TextToTextModel`] or a string such as `"llama-3.2-3b"`.
+pub trait IntoTextToTextModel {
+    /// Resolve to a registered model
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput`, listing the registered models, if a key or
+    /// name matches no registered model.
+    fn into_text_to_text_model(self) -> Result<TextToTextModel, CandleModelError>;
+}
+
+impl IntoTextToTextModel for TextToTextModel {
+    fn into_text_to_text_model(self) -> Result<TextToTextModel, CandleModelError> {
+        Ok(self)
+    }
+}
+
+impl IntoTextToTextModel for &str {
+    fn into_text_to_text_model(self) -> Result<TextToTextModel, CandleModelError> {
+        TextToTextModel::from_registry(self).ok_or_else(|| {
+            let mut names: Vec<&str> = TEXT_TO_TEXT_UNIFIED
+                .read()
+                .values()
+                .map(|model| model.info().name)
+                .collect();
+            names.sort_unstable();
+            names.dedup();
+            CandleModelError::InvalidInput(
+                format!(
+                    "unknown text-to-text model '{}'; registered models: {}",
+                    self,
+                    names.join(", ")
+                )
+                .into(),
+            )
+        })
+    }
+}
+
+impl IntoTextToTextModel for String {
+    fn into_text_to_text_model(self) -> Result<TextToTextModel, CandleModelError> {
+        self.as_str().into_text_to_text_model()
+    }
+}
+
+/// Template presets used by the registered text-to-text models, without duplicates
+pub fn chat_template_families() -> Vec<ChatTemplateFamily> {
+    let mut families: Vec<ChatTemplateFamily> = TEXT_TO_TEXT_UNIFIED
+        .read()
+        .values()
+        .filter_map(TextToTextModel::chat_template)
+        .collect();
+    families.sort();
+    families.dedup();
+    families
+}
+
 // Helper macro to eliminate duplication in streaming worker spawning
 macro_rules! impl_text_to_text_spawn {
-    ($fn_name:ident, $model_ty:ty, $loaded_ty:ty) => {
+    ($fn_name:ident, $ensure_name:ident, $model_ty:ty, $load:path) => {
+        /// Make sure pool workers holding `model` (with `adapters` merged)
+        /// run under `worker_key`, loading the weights if none do
+        async fn $ensure_name(
+            model: &Arc<$model_ty>,
+            worker_key: &str,
+            adapters: &[LoraAdapter],
+        ) -> Result<(), PoolError> {
+            let per_worker_mb = model.info().est_memory_allocation_mb;
+            let pool = text_to_text_pool();
+            ensure_workers_spawned_adaptive(
+                pool,
+                worker_key,
+                per_worker_mb,
+                pool.config().max_workers_per_model,
+                |_, allocation_guard| {
+                    let m_clone = model.clone();
+                    let adapters = adapters.to_vec();
+                    pool.spawn_text_to_text_worker(
+                        worker_key,
+                        move || async move {
+                            $load(&m_clone, &adapters)
+                                .await
+                                .map_err(|e| PoolError::SpawnFailed(e.to_string()))
+                        },
+                        per_worker_mb,
+                        allocation_guard,
+                    )
+                },
+            )
+            .await
+        }
+
         fn $fn_name(
             model: Arc<$model_ty>,
             prompt: CandlePrompt,
             params: CandleCompletionParams,
         ) -> Pin<Box<dyn Stream<Item = CandleCompletionChunk> + Send>> {
             let registry_key = model.info().registry_key;
-            let per_worker_mb = model.info().est_memory_allocation_mb;
             let pool = text_to_text_pool();
 
             Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
-                if let Err(e) = ensure_workers_spawned_adaptive(
-                    pool,
-                    registry_key,
-                    per_worker_mb,
-                    pool.config().max_workers_per_model,
-                    |_, allocation_guard| {
-                        let m_clone = model.clone();
-                        pool.spawn_text_to_text_worker(
-                            registry_key,
-                            move || async move {
-                                <$loaded_ty>::load(&m_clone)
-                                    .await
-                                    .map_err(|e| PoolError::SpawnFailed(e.to_string()))
-                            },
-                            per_worker_mb,
-                            allocation_guard,
-                        )
-                    },
-                )
-                .await
-                {
+                // Adapter-merged variants run in their own workers, keyed apart from the base model
+                let adapter_names = requested_adapters(params.additional_params.as_ref());
+                let adapters = match resolve_lora_adapters(registry_key, &adapter_names) {
+                    Ok(adapters) => adapters,
+                    Err(e) => {
+                        let _ = tx.send(CandleCompletionChunk::Error(e.to_string()));
+                        return;
+                    }
+                };
+                let worker_key = if adapter_names.is_empty() {
+                    registry_key.to_string()
+                } else {
+                    format!("{}+lora:{}", registry_key, adapter_names.join(","))
+                };
+
+                if let Err(e) = $ensure_name(&model, &worker_key, &adapters).await {
                     let _ = tx.send(CandleCompletionChunk::Error(e.to_string()));
                     return;
                 }
 
-                let mut stream = pool.prompt(registry_key, prompt, params);
+                let mut stream = pool.prompt(&worker_key, prompt, params);
                 use tokio_stream::StreamExt;
                 while let Some(chunk) = stream.next().await {
                     if tx.send(chunk).is_err() {
@@ -79,9 +206,99 @@ macro_rules! impl_text_to_text_spawn {
     };
 }
 
+impl TextToTextModel {
+    /// Load the model's weights into pool workers now rather than on the
+    /// first prompt
+    ///
+    /// Returns at once if workers already hold it.
+    ///
+    /// # Errors
+    ///
+    /// [`PoolError`] if memory is exhausted or the weights fail to load.
+    pub async fn load(&self) -> Result<(), PoolError> {
+        let registry_key = self.info().registry_key;
+        match self {
+            Self::Qwen3Quantized(m) => ensure_qwen3_quantized_workers(m, registry_key, &[]).await,
+            Self::Gguf(m) => ensure_gguf_chat_workers(m, registry_key, &[]).await,
+        }
+    }
+
+    /// `prompt` as the model sees it: `params`' conversation rendered through
+    /// the model's chat template, ending where the reply starts
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template cannot be loaded or fails to render.
+    pub async fn render_prompt(
+        &self,
+        prompt: String,
+        params: &CandleCompletionParams,
+    ) -> TemplateResult<String> {
+        let template = self.model_template().await?;
+        let mut conversation = params.conversation(prompt);
+        if !self.info().supports_function_calling {
+            conversation.tools.clear();
+        }
+        template.render(&conversation)
+    }
+
+    /// The template the model's workers render prompts with, loaded once
+    async fn model_template(&self) -> TemplateResult<ModelChatTemplate> {
+        static TEMPLATES: LazyLock<parking_lot::Mutex<HashMap<String, ModelChatTemplate>>> =
+            LazyLock::new(Default::default);
+
+        let registry_key = self.info().registry_key;
+        if let Some(template) = TEMPLATES.lock().get(registry_key) {
+            return Ok(template.clone());
+        }
+        let template = match self {
+            Self::Qwen3Quantized(m) => {
+                let checkpoint = crate::capability::tiny::GgufCheckpoint::qwen3_for(m.variant());
+                ModelChatTemplate::load(
+                    m.as_ref(),
+                    &checkpoint.tokenizer_repo,
+                    Some(ChatTemplateFamily::ChatMl),
+                )
+                .await?
+            }
+            Self::Gguf(m) => {
+                let spec = m.spec();
+                ModelChatTemplate::load(m.as_ref(), spec.tokenizer_repo, spec.template).await?
+            }
+        };
+        TEMPLATES
+            .lock()
+            .insert(registry_key.to_string(), template.clone());
+        Ok(template)
+    }
+}
+
+/// Load the model of a Qwen3 pool worker
+///
+/// Base-model workers take the instance behind the model's shared handle,
+/// so tool selection and the pool keep a single copy resident.
+async fn load_qwen3_quantized_worker(
+    model: &CandleQwen3QuantizedModel,
+    adapters: &[LoraAdapter],
+) -> Result<Arc<LoadedQwen3QuantizedModel>, Box<dyn std::error::Error + Send + Sync>> {
+    if adapters.is_empty() {
+        return loaded_model_handle(model).get_or_load(model).await;
+    }
+    LoadedQwen3QuantizedModel::load_with_adapters(model, adapters)
+        .await
+        .map(Arc::new)
+}
+
 // Generate functions for each model type
 impl_text_to_text_spawn!(
     spawn_stream_qwen3_quantized,
+    ensure_qwen3_quantized_workers,
     crate::capability::text_to_text::qwen3_quantized::CandleQwen3QuantizedModel,
-    LoadedQwen3QuantizedModel
+    load_qwen3_quantized_worker
+);
+impl_text_to_text_spawn!(
+    spawn_stream_gguf_chat,
+    ensure_gguf_chat_workers,
+    crate::capability::text_to_text::gguf_chat::CandleGgufChatModel,
+    LoadedGgufChatModel::load_with_adapters
 );
@@ -0,0 +1,59 @@
+//! Tool description embedding cache
+//!
+//! Tool descriptions rarely change, so their embeddings are computed once per
+//! embedding model and kept alongside the models that produced them.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use super::storage::TOOL_EMBEDDING_CACHE;
+
+/// Hash a tool description for use in a cache key
+fn description_hash(description: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    description.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Look up a cached tool embedding
+pub fn get_tool_embedding(
+    embedding_model_key: &str,
+    tool_name: &str,
+    description: &str,
+) -> Option<Arc<[f32]>> {
+    TOOL_EMBEDDING_CACHE
+        .read()
+        .get(&(
+            embedding_model_key.to_string(),
+            tool_name.to_string(),
+            description_hash(description),
+        ))
+        .cloned()
+}
+
+/// Cache a tool embedding
+pub fn cache_tool_embedding(
+    embedding_model_key: &str,
+    tool_name: &str,
+    description: &str,
+    embedding: Arc<[f32]>,
+) {
+    TOOL_EMBEDDING_CACHE.write().insert(
+        (
+            embedding_model_key.to_string(),
+            tool_name.to_string(),
+            description_hash(description),
+        ),
+        embedding,
+    );
+}
+
+/// Number of cached tool embeddings
+pub fn tool_embedding_count() -> usize {
+    TOOL_EMBEDDING_CACHE.read().len()
+}
+
+/// Drop every cached tool embedding
+pub fn clear_tool_embeddings() {
+    TOOL_EMBEDDING_CACHE.write().clear();
+}
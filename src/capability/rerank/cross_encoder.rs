@@ -0,0 +1,262 @@
+//! BERT cross-encoder reranker
+//!
+//! Uses `cross-encoder/ms-marco-MiniLM-L-6-v2`, a 6-layer MiniLM trained on
+//! MS MARCO passage ranking: small enough to score 50 candidates per query on
+//! CPU. Each (query, document) pair is encoded as one sequence; the pooled
+//! `[CLS]` state goes through the classification head, and the sigmoid of its
+//! logit is the relevance score.
+//!
+//! The model is downloaded and loaded on first use and then kept for the
+//! life of the process.
+
+use std::num::NonZeroU32;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use anyhow::{Context, anyhow};
+use candle_core::{DType, Device, IndexOp, Tensor};
+use candle_nn::{Linear, Module, VarBuilder};
+use candle_transformers::models::bert::{BertModel, Config};
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+use tokio::sync::OnceCell;
+
+use crate::capability::text_embedding::safetensors_validation::validate_safetensors_file;
+use crate::capability::traits::{RerankCapable, RerankFuture};
+use crate::core::device_util::{DevicePreference, EMBEDDING_DEVICE_ENV, select_device};
+use crate::domain::model::CandleModelInfo;
+use crate::domain::model::traits::CandleModel;
+
+/// Pairs scored per forward pass
+const RERANK_BATCH_SIZE: usize = 16;
+
+/// Static model info for the MiniLM cross-encoder
+pub(crate) static CROSS_ENCODER_MODEL_INFO: CandleModelInfo = CandleModelInfo {
+    provider: crate::domain::model::CandleProvider::Community,
+    name: "ms-marco-MiniLM-L-6-v2",
+    registry_key: "cross-encoder/ms-marco-MiniLM-L-6-v2",
+    quantization_url: None,
+    max_input_tokens: NonZeroU32::new(512),
+    max_output_tokens: None,
+    input_price: None,
+    output_price: None,
+    supports_vision: false,
+    supports_function_calling: false,
+    supports_streaming: false,
+    supports_embeddings: false,
+    requires_max_tokens: false,
+    supports_thinking: false,
+    optimal_thinking_budget: None,
+    system_prompt_prefix: None,
+    real_name: None,
+    model_type: None,
+    model_id: "ms-marco-minilm-l-6-v2",
+    quantization: "none",
+    patch: None,
+    embedding_dimension: None,
+    vocab_size: None,
+    image_size: None,
+    image_mean: None,
+    image_std: None,
+    default_temperature: None,
+    default_top_k: None,
+    default_top_p: None,
+    supports_kv_cache: false,
+    supports_flash_attention: false,
+    use_bf16: false,
+    default_steps: None,
+    default_guidance_scale: None,
+    time_shift: None,
+    est_memory_allocation_mb: 150, // 22M params × 4 bytes/param + overhead
+};
+
+static RERANKER: LazyLock<CrossEncoderReranker> = LazyLock::new(CrossEncoderReranker::new);
+
+/// Process-wide cross-encoder, loaded on first use
+pub fn reranker() -> &'static CrossEncoderReranker {
+    &RERANKER
+}
+
+/// Cross-encoder reranker that loads its model lazily
+#[derive(Debug, Default)]
+pub struct CrossEncoderReranker {
+    loaded: OnceCell<LoadedCrossEncoder>,
+}
+
+impl CrossEncoderReranker {
+    /// Reranker whose model is loaded on the first [`RerankCapable::rerank`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The loaded model, downloading and loading it if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files cannot be downloaded or loaded.
+    pub async fn loaded(
+        &self,
+    ) -> std::result::Result<&LoadedCrossEncoder, Box<dyn std::error::Error + Send + Sync>> {
+        self.loaded
+            .get_or_try_init(|| LoadedCrossEncoder::load(self))
+            .await
+    }
+}
+
+impl CandleModel for CrossEncoderReranker {
+    fn info(&self) -> &'static CandleModelInfo {
+        &CROSS_ENCODER_MODEL_INFO
+    }
+}
+
+impl RerankCapable for CrossEncoderReranker {
+    fn rerank(&self, query: &str, documents: &[String]) -> RerankFuture<'_> {
+        let query = query.to_string();
+        let documents = documents.to_vec();
+        Box::pin(async move {
+            if documents.is_empty() {
+                return Ok(Vec::new());
+            }
+            let model = self.loaded().await?.clone();
+            let scores =
+                tokio::task::spawn_blocking(move || model.score(&query, &documents)).await??;
+            Ok(scores)
+        })
+    }
+}
+
+/// Cross-encoder with tokenizer and weights in memory
+#[derive(Clone)]
+pub struct LoadedCrossEncoder {
+    tokenizer: Arc<Tokenizer>,
+    model: Arc<Mutex<BertModel>>,
+    pooler: Linear,
+    classifier: Linear,
+    device: Device,
+}
+
+impl std::fmt::Debug for LoadedCrossEncoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadedCrossEncoder")
+            .field("device", &self.device)
+            .field("model", &"Arc<Mutex<BertModel>>")
+            .finish()
+    }
+}
+
+impl LoadedCrossEncoder {
+    /// Download (if needed) and load the model of `base`
+    pub async fn load(
+        base: &CrossEncoderReranker,
+    ) -> std::result::Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let info = base.info();
+        let max_length = info
+            .max_input_tokens
+            .ok_or_else(|| anyhow!("max_input_tokens missing in ModelInfo"))?
+            .get() as usize;
+
+        let config_path = base
+            .huggingface_file(info.registry_key, "config.json")
+            .await?;
+        let weights_path = base
+            .huggingface_file(info.registry_key, "model.safetensors")
+            .await?;
+        let tokenizer_path = base
+            .huggingface_file(info.registry_key, "tokenizer.json")
+            .await?;
+
+        // Shares the embedding model's device setting
+        let device = select_device(DevicePreference::from_env(EMBEDDING_DEVICE_ENV))
+            .context("Failed to select compute device")?;
+
+        let config: Config = serde_json::from_str(
+            &std::fs::read_to_string(&config_path).context("Failed to read config.json")?,
+        )
+        .context("Failed to parse cross-encoder config")?;
+
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| anyhow!("Failed to load tokenizer: {}", e))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length,
+                strategy: tokenizers::TruncationStrategy::LongestFirst,
+                stride: 0,
+                direction: tokenizers::TruncationDirection::Right,
+            }))
+            .map_err(|e| anyhow!("Failed to set truncation: {}", e))?;
+
+        validate_safetensors_file(&weights_path)?;
+        let vb = unsafe {
+            VarBuilder::from_mmaped_safetensors(&[weights_path], DType::F32, &device)
+                .context("Failed to load cross-encoder weights")?
+        };
+        let model = BertModel::load(vb.pp("bert"), &config)
+            .context("Failed to create cross-encoder model")?;
+        let pooler = candle_nn::linear(
+            config.hidden_size,
+            config.hidden_size,
+            vb.pp("bert.pooler.dense"),
+        )
+        .context("Failed to load pooler")?;
+        let classifier = candle_nn::linear(config.hidden_size, 1, vb.pp("classifier"))
+            .context("Failed to load classification head")?;
+
+        Ok(Self {
+            tokenizer: Arc::new(tokenizer),
+            model: Arc::new(Mutex::new(model)),
+            pooler,
+            classifier,
+            device,
+        })
+    }
+
+    /// Relevance of each document to `query` in `0..=1`, in document order
+    ///
+    /// Blocking; run it in `spawn_blocking`.
+    pub fn score(&self, query: &str, documents: &[String]) -> anyhow::Result<Vec<f32>> {
+        let mut scores = Vec::with_capacity(documents.len());
+        for batch in documents.chunks(RERANK_BATCH_SIZE) {
+            let pairs: Vec<(String, String)> = batch
+                .iter()
+                .map(|document| (query.to_string(), document.clone()))
+                .collect();
+            let encodings = self
+                .tokenizer
+                .encode_batch(pairs, true)
+                .map_err(|e| anyhow!("Tokenization failed: {}", e))?;
+
+            let ids = stack(&encodings, |e| e.get_ids(), &self.device)?;
+            let type_ids = stack(&encodings, |e| e.get_type_ids(), &self.device)?;
+            let mask = stack(&encodings, |e| e.get_attention_mask(), &self.device)?;
+
+            let hidden = {
+                let model = self
+                    .model
+                    .lock()
+                    .map_err(|_| anyhow!("Cross-encoder lock poisoned"))?;
+                model.forward(&ids, &type_ids, Some(&mask))?
+            };
+            let cls = hidden.i((.., 0))?;
+            let pooled = self.pooler.forward(&cls)?.tanh()?;
+            let logits = self.classifier.forward(&pooled)?.squeeze(1)?;
+            let probabilities = candle_nn::ops::sigmoid(&logits)?;
+            scores.extend(probabilities.to_vec1::<f32>()?);
+        }
+        Ok(scores)
+    }
+}
+
+/// One row per encoding, as a `[batch, seq]` tensor
+fn stack(
+    encodings: &[tokenizers::Encoding],
+    field: impl Fn(&tokenizers::Encoding) -> &[u32],
+    device: &Device,
+) -> anyhow::Result<Tensor> {
+    let rows = encodings
+        .iter()
+        .map(|encoding| Tensor::new(field(encoding), device))
+        .collect::<candle_core::Result<Vec<_>>>()?;
+    Ok(Tensor::stack(&rows, 0)?)
+}
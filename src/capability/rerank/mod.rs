@@ -0,0 +1,10 @@
+//! Reranking Capability
+//!
+//! A cross-encoder reads a query and a candidate together and scores how well
+//! the candidate answers it. That is far too slow to run over a whole library
+//! but much sharper than vector similarity, so it serves as a second stage
+//! over the top vector hits (see [`rerank_memories`](crate::memory::core::ops::rerank::rerank_memories)).
+
+pub mod cross_encoder;
+
+pub use cross_encoder::{CrossEncoderReranker, LoadedCrossEncoder, reranker};
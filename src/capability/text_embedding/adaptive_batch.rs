@@ -0,0 +1,127 @@
+//! Adaptive batch sizing for embedding requests
+//!
+//! `recommended_batch_size` is a static guess per variant, but the batch size
+//! that maximizes throughput differs widely between CPU, Metal and CUDA. An
+//! [`AdaptiveBatchSizer`] starts at the recommendation and hill-climbs on
+//! observed throughput: it keeps stepping in the direction that made batches
+//! faster per text, and turns around when throughput drops. Slow batches and
+//! memory pressure shrink it immediately. The size always stays between 1 and
+//! the model's `max_batch_size`.
+//!
+//! One sizer is kept per registry key for the lifetime of the process, see
+//! [`batch_sizer`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::capability::registry::pool::core::MemoryPressure;
+
+/// Batches slower than this shrink the batch size regardless of throughput
+pub const MAX_BATCH_LATENCY: Duration = Duration::from_secs(5);
+
+/// Relative throughput change treated as noise
+const THROUGHPUT_TOLERANCE: f64 = 0.05;
+
+#[derive(Debug)]
+struct SizerState {
+    size: usize,
+    /// Whether the next step grows the batch
+    growing: bool,
+    /// Texts per second of the last full batch
+    last_throughput: Option<f64>,
+}
+
+/// Batch size controller for one embedding model
+#[derive(Debug)]
+pub struct AdaptiveBatchSizer {
+    min: usize,
+    max: usize,
+    state: Mutex<SizerState>,
+}
+
+impl AdaptiveBatchSizer {
+    /// Start at `initial`, adjusting within `min..=max`
+    pub fn new(initial: usize, min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        let max = max.max(min);
+        Self {
+            min,
+            max,
+            state: Mutex::new(SizerState {
+                size: initial.clamp(min, max),
+                growing: true,
+                last_throughput: None,
+            }),
+        }
+    }
+
+    /// Batch size to use next
+    pub fn batch_size(&self) -> usize {
+        self.state.lock().size
+    }
+
+    /// Feed back one batch of `items` texts that took `elapsed`
+    ///
+    /// Batches smaller than the current size (the tail of a request) only
+    /// count for latency and memory pressure, not for throughput.
+    pub fn record(&self, items: usize, elapsed: Duration, pressure: MemoryPressure) {
+        let mut state = self.state.lock();
+
+        match pressure {
+            MemoryPressure::Critical => return self.shrink(&mut state, 2),
+            MemoryPressure::High => return self.shrink(&mut state, 4),
+            MemoryPressure::Low | MemoryPressure::Normal => {}
+        }
+        if elapsed > MAX_BATCH_LATENCY {
+            return self.shrink(&mut state, 4);
+        }
+        if items == 0 || items < state.size || elapsed.is_zero() {
+            return;
+        }
+
+        let throughput = items as f64 / elapsed.as_secs_f64();
+        if let Some(last) = state.last_throughput
+            && throughput < last * (1.0 - THROUGHPUT_TOLERANCE)
+        {
+            state.growing = !state.growing;
+        }
+        state.last_throughput = Some(throughput);
+
+        let step = (state.size / 4).max(1);
+        let next = if state.growing {
+            state.size.saturating_add(step)
+        } else {
+            state.size.saturating_sub(step)
+        }
+        .clamp(self.min, self.max);
+
+        // Bounce off the bounds instead of sticking to them
+        if next == state.size {
+            state.growing = !state.growing;
+        }
+        state.size = next;
+    }
+
+    /// Cut the size by `1/divisor` of itself and restart the search downwards
+    fn shrink(&self, state: &mut SizerState, divisor: usize) {
+        let cut = (state.size / divisor).max(1);
+        state.size = state.size.saturating_sub(cut).clamp(self.min, self.max);
+        state.growing = false;
+        state.last_throughput = None;
+    }
+}
+
+static SIZERS: LazyLock<Mutex<HashMap<String, Arc<AdaptiveBatchSizer>>>> =
+    LazyLock::new(Default::default);
+
+/// Shared sizer for `registry_key`, created at `recommended` on first use
+pub fn batch_sizer(registry_key: &str, recommended: usize, max: usize) -> Arc<AdaptiveBatchSizer> {
+    SIZERS
+        .lock()
+        .entry(registry_key.to_string())
+        .or_insert_with(|| Arc::new(AdaptiveBatchSizer::new(recommended, 1, max)))
+        .clone()
+}
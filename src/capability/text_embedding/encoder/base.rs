@@ -0,0 +1,50 @@
+//! Base encoder embedding model implementation
+
+use super::spec::EncoderEmbeddingSpec;
+use crate::domain::model::CandleModelInfo;
+use crate::domain::model::traits::CandleModel;
+
+/// Encoder embedding provider - registry holder only
+///
+/// Describes one [`EncoderEmbeddingSpec`]; inference runs in
+/// `LoadedEncoderModel` via the worker pool, as with Stella.
+#[derive(Debug, Clone)]
+pub struct EncoderEmbeddingModel {
+    spec: &'static EncoderEmbeddingSpec,
+}
+
+impl EncoderEmbeddingModel {
+    /// Create a provider for `spec` (no downloads)
+    #[inline]
+    pub fn new(spec: &'static EncoderEmbeddingSpec) -> Self {
+        Self { spec }
+    }
+
+    /// The model this provider loads
+    pub fn spec(&self) -> &'static EncoderEmbeddingSpec {
+        self.spec
+    }
+
+    /// Get the embedding output dimension from model info
+    pub fn embedding_dimension(&self) -> usize {
+        self.info().embedding_dimension.unwrap_or(768) as usize
+    }
+
+    /// Matryoshka dimensions the output can be truncated to, then the full dimension
+    pub fn supported_dimensions(&self) -> Vec<usize> {
+        let mut dimensions = self.spec.mrl_dimensions.to_vec();
+        dimensions.push(self.embedding_dimension());
+        dimensions
+    }
+
+    /// Recommended and maximum batch sizes of this model
+    pub fn batch_sizes(&self) -> (usize, usize) {
+        self.spec.batch_sizes
+    }
+}
+
+impl CandleModel for EncoderEmbeddingModel {
+    fn info(&self) -> &'static CandleModelInfo {
+        &self.spec.info
+    }
+}
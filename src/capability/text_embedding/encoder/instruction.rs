@@ -0,0 +1,58 @@
+//! Task prefixes for encoder embeddings
+//!
+//! Encoders take no free-form instructions; tasks map to the few prefixes
+//! a model was trained with (see [`TaskPrefixes`]):
+//!
+//! - `"document"`, `"search_document"`: stored passages
+//! - `"classification"`: classification inputs
+//! - `"clustering"`, `"s2s"`: clustering and symmetric similarity
+//! - anything else, including `None`, `"code:<language>"` and
+//!   `"instruct:<instruction>"`: search queries
+
+use super::spec::TaskPrefixes;
+use crate::capability::text_embedding::stella::instruction::{
+    CODE_TASK_PREFIX, INSTRUCT_TASK_PREFIX,
+};
+
+/// Tasks with a meaning for encoders
+const KNOWN_TASKS: &[&str] = &[
+    "s2p",
+    "s2s",
+    "search_query",
+    "search_document",
+    "document",
+    "classification",
+    "clustering",
+    "retrieval",
+];
+
+/// Prefix `prefixes` gives inputs of `task`
+pub fn task_prefix(prefixes: &TaskPrefixes, task: Option<&str>) -> &'static str {
+    match task {
+        Some("document" | "search_document") => prefixes.document,
+        Some(t) if t.starts_with(CODE_TASK_PREFIX) => prefixes.query,
+        Some("classification") => prefixes.classification,
+        Some("clustering" | "s2s") => prefixes.clustering,
+        Some(t) => {
+            if !KNOWN_TASKS.contains(&t) && !t.starts_with(INSTRUCT_TASK_PREFIX) {
+                log::warn!(
+                    "Unknown embedding task '{}'. Embedding as a search query. Valid tasks: {}",
+                    t,
+                    KNOWN_TASKS.join(", ")
+                );
+            }
+            prefixes.query
+        }
+        None => prefixes.query,
+    }
+}
+
+/// `texts` with the prefix of `task`
+pub fn format_with_prefix(
+    prefixes: &TaskPrefixes,
+    texts: &[String],
+    task: Option<&str>,
+) -> Vec<String> {
+    let prefix = task_prefix(prefixes, task);
+    texts.iter().map(|text| format!("{prefix}{text}")).collect()
+}
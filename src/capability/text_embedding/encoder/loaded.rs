@@ -0,0 +1,301 @@
+//! Loaded encoder model wrapper with thread-safe interior mutability
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, anyhow};
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::{bert, xlm_roberta};
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+use super::base::EncoderEmbeddingModel;
+use super::instruction::format_with_prefix;
+use super::nomic_bert::{NomicBertConfig, NomicBertModel};
+use super::spec::{EncoderArchitecture, EncoderEmbeddingSpec, Pooling};
+use crate::capability::text_embedding::safetensors_validation::validate_safetensors_file;
+use crate::capability::traits::{BatchEmbeddingFuture, EmbeddingFuture, TextEmbeddingCapable};
+use crate::core::device_util::{DevicePreference, EMBEDDING_DEVICE_ENV, select_device};
+use crate::domain::model::CandleModelInfo;
+use crate::domain::model::traits::CandleModel;
+
+/// Network of a loaded encoder
+enum EncoderNetwork {
+    Bert(bert::BertModel),
+    XlmRoberta(xlm_roberta::XLMRobertaModel),
+    NomicBert(NomicBertModel),
+}
+
+impl EncoderNetwork {
+    /// Last hidden states `(batch, seq, hidden)`
+    fn forward(
+        &self,
+        input_ids: &Tensor,
+        token_type_ids: &Tensor,
+        attention_mask: &Tensor,
+    ) -> candle_core::Result<Tensor> {
+        match self {
+            Self::Bert(model) => model.forward(input_ids, token_type_ids, Some(attention_mask)),
+            Self::XlmRoberta(model) => {
+                model.forward(input_ids, attention_mask, token_type_ids, None, None, None)
+            }
+            Self::NomicBert(model) => model.forward(input_ids, token_type_ids, attention_mask),
+        }
+    }
+}
+
+/// Loaded encoder that keeps model/tokenizer in memory
+///
+/// Loaded once per pool worker, like `LoadedStellaModel`. Inputs get the
+/// spec's task prefix, are padded on the right, and the pooled states are
+/// L2-normalized.
+#[derive(Clone)]
+pub struct LoadedEncoderModel {
+    spec: &'static EncoderEmbeddingSpec,
+    tokenizer: Arc<Tokenizer>,
+    model: Arc<Mutex<EncoderNetwork>>,
+    device: Device,
+}
+
+impl std::fmt::Debug for LoadedEncoderModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadedEncoderModel")
+            .field("model", &self.spec.info.registry_key)
+            .field("architecture", &self.spec.architecture)
+            .field("device", &self.device)
+            .finish()
+    }
+}
+
+impl CandleModel for LoadedEncoderModel {
+    fn info(&self) -> &'static CandleModelInfo {
+        &self.spec.info
+    }
+}
+
+impl LoadedEncoderModel {
+    /// Download (if needed) and load the model of `base`
+    pub async fn load(
+        base: &EncoderEmbeddingModel,
+    ) -> std::result::Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let spec = base.spec();
+        let repo = spec.info.registry_key;
+        let max_length = spec
+            .info
+            .max_input_tokens
+            .ok_or_else(|| anyhow!("max_input_tokens missing in ModelInfo"))?
+            .get() as usize;
+
+        let config_path = base.huggingface_file(repo, "config.json").await?;
+        let tokenizer_path = base.huggingface_file(repo, "tokenizer.json").await?;
+        // Some checkpoints only ship PyTorch weights
+        let weights = match base.huggingface_file(repo, "model.safetensors").await {
+            Ok(path) => Weights::SafeTensors(path),
+            Err(e) => {
+                log::info!(
+                    "{} has no model.safetensors ({}), using pytorch_model.bin",
+                    repo,
+                    e
+                );
+                Weights::Pth(base.huggingface_file(repo, "pytorch_model.bin").await?)
+            }
+        };
+
+        // Device from KODEGEN_EMBEDDING_DEVICE, else the best available one.
+        // GPU work is confined to spawn_blocking and serialized by the model lock
+        let device = select_device(DevicePreference::from_env(EMBEDDING_DEVICE_ENV))
+            .context("Failed to select compute device")?;
+
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| anyhow!("Failed to load tokenizer: {}", e))?;
+        configure_tokenizer(&mut tokenizer, max_length)?;
+
+        let config = std::fs::read_to_string(&config_path).context("Failed to read config.json")?;
+        let vb = weights.var_builder(&device)?;
+        let model = match spec.architecture {
+            EncoderArchitecture::Bert => {
+                let config: bert::Config =
+                    serde_json::from_str(&config).context("Failed to parse BERT config")?;
+                EncoderNetwork::Bert(
+                    bert::BertModel::load(vb, &config).context("Failed to create BERT model")?,
+                )
+            }
+            EncoderArchitecture::XlmRoberta => {
+                let config: xlm_roberta::Config =
+                    serde_json::from_str(&config).context("Failed to parse XLM-RoBERTa config")?;
+                EncoderNetwork::XlmRoberta(
+                    xlm_roberta::XLMRobertaModel::new(&config, vb)
+                        .context("Failed to create XLM-RoBERTa model")?,
+                )
+            }
+            EncoderArchitecture::NomicBert => {
+                let config: NomicBertConfig =
+                    serde_json::from_str(&config).context("Failed to parse NomicBERT config")?;
+                EncoderNetwork::NomicBert(
+                    NomicBertModel::load(vb, &config)
+                        .context("Failed to create NomicBERT model")?,
+                )
+            }
+        };
+
+        Ok(Self {
+            spec,
+            tokenizer: Arc::new(tokenizer),
+            model: Arc::new(Mutex::new(model)),
+            device,
+        })
+    }
+
+    /// Embed `texts`, already prefixed, in one forward pass
+    fn encode(&self, texts: Vec<String>) -> anyhow::Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts, true)
+            .map_err(|e| anyhow!("Batch tokenization failed: {}", e))?;
+
+        let ids: Vec<Vec<u32>> = encodings.iter().map(|e| e.get_ids().to_vec()).collect();
+        let type_ids: Vec<Vec<u32>> = encodings
+            .iter()
+            .map(|e| e.get_type_ids().to_vec())
+            .collect();
+        let mask: Vec<Vec<u32>> = encodings
+            .iter()
+            .map(|e| e.get_attention_mask().to_vec())
+            .collect();
+
+        let input_ids = Tensor::new(ids, &self.device).context("Failed to create input tensor")?;
+        let token_type_ids =
+            Tensor::new(type_ids, &self.device).context("Failed to create token type tensor")?;
+        let attention_mask =
+            Tensor::new(mask, &self.device).context("Failed to create attention mask")?;
+
+        // Lock held until the result is read back, so GPU command buffers of
+        // concurrent calls never interleave
+        let model = self
+            .model
+            .lock()
+            .map_err(|e| anyhow!("Model mutex poisoned (thread panic): {}", e))?;
+        let hidden = model
+            .forward(&input_ids, &token_type_ids, &attention_mask)
+            .context("Encoder forward pass failed")?;
+        let embeddings = pool(&hidden, &attention_mask, self.spec.pooling)
+            .context("Failed to pool encoder states")?;
+
+        if !self.device.is_cpu() {
+            self.device
+                .synchronize()
+                .context("Failed to synchronize compute device")?;
+        }
+        embeddings
+            .to_vec2::<f32>()
+            .context("Failed to convert embeddings to vec")
+    }
+}
+
+impl TextEmbeddingCapable for LoadedEncoderModel {
+    fn embed(&self, text: &str, task: Option<String>) -> EmbeddingFuture<'_> {
+        let texts = vec![text.to_string()];
+        Box::pin(async move {
+            self.batch_embed(&texts, task)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("Encoder returned no embedding").into())
+        })
+    }
+
+    fn batch_embed(&self, texts: &[String], task: Option<String>) -> BatchEmbeddingFuture<'_> {
+        let texts = format_with_prefix(&self.spec.prefixes, texts, task.as_deref());
+        let model = self.clone();
+        Box::pin(async move {
+            if texts.is_empty() {
+                return Ok(Vec::new());
+            }
+            let embeddings = tokio::task::spawn_blocking(move || model.encode(texts))
+                .await
+                .context("spawn_blocking join failed")??;
+            Ok(embeddings)
+        })
+    }
+
+    fn embedding_dimension(&self) -> usize {
+        self.spec.info.embedding_dimension.unwrap_or(768) as usize
+    }
+
+    fn supported_dimensions(&self) -> Vec<usize> {
+        EncoderEmbeddingModel::new(self.spec).supported_dimensions()
+    }
+
+    fn recommended_batch_size(&self) -> usize {
+        self.spec.batch_sizes.0
+    }
+
+    fn max_batch_size(&self) -> usize {
+        self.spec.batch_sizes.1
+    }
+}
+
+/// Weight file of a checkpoint
+enum Weights {
+    SafeTensors(PathBuf),
+    Pth(PathBuf),
+}
+
+impl Weights {
+    fn var_builder(self, device: &Device) -> anyhow::Result<VarBuilder<'static>> {
+        match self {
+            Self::SafeTensors(path) => {
+                // Validate before unsafe mmap
+                validate_safetensors_file(&path)?;
+                unsafe {
+                    VarBuilder::from_mmaped_safetensors(&[path], DType::F32, device)
+                        .context("Failed to load encoder weights")
+                }
+            }
+            Self::Pth(path) => VarBuilder::from_pth(&path, DType::F32, device)
+                .context("Failed to load encoder weights"),
+        }
+    }
+}
+
+/// Pad on the right with the tokenizer's pad token and truncate to `max_length`
+fn configure_tokenizer(tokenizer: &mut Tokenizer, max_length: usize) -> anyhow::Result<()> {
+    // XLM-RoBERTa pads with <pad>, BERT vocabularies with [PAD]
+    let (pad_id, pad_token) = ["<pad>", "[PAD]"]
+        .into_iter()
+        .find_map(|token| tokenizer.token_to_id(token).map(|id| (id, token)))
+        .unwrap_or((0, "[PAD]"));
+    tokenizer.with_padding(Some(PaddingParams {
+        strategy: PaddingStrategy::BatchLongest,
+        pad_id,
+        pad_token: pad_token.to_string(),
+        ..Default::default()
+    }));
+    tokenizer
+        .with_truncation(Some(TruncationParams {
+            max_length,
+            strategy: tokenizers::TruncationStrategy::LongestFirst,
+            stride: 0,
+            direction: tokenizers::TruncationDirection::Right,
+        }))
+        .map_err(|e| anyhow!("Failed to set truncation: {}", e))?;
+    Ok(())
+}
+
+/// Pool `hidden` `(batch, seq, dim)` per `pooling`, then L2-normalize
+fn pool(hidden: &Tensor, attention_mask: &Tensor, pooling: Pooling) -> candle_core::Result<Tensor> {
+    let pooled = match pooling {
+        Pooling::Cls => hidden.narrow(1, 0, 1)?.squeeze(1)?,
+        Pooling::Mean => {
+            let mask = attention_mask.to_dtype(DType::F32)?.unsqueeze(2)?;
+            let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+            let counts = mask.sum(1)?.clamp(1f32, f32::MAX)?;
+            summed.broadcast_div(&counts)?
+        }
+    };
+    let norms = pooled
+        .sqr()?
+        .sum_keepdim(1)?
+        .sqrt()?
+        .clamp(1e-12f32, f32::MAX)?;
+    pooled.broadcast_div(&norms)
+}
@@ -0,0 +1,21 @@
+//! Encoder embedding providers: BGE-M3, GTE-large and nomic-embed-text
+//!
+//! Bidirectional encoders that share one loader. Each model is described by
+//! an [`EncoderEmbeddingSpec`]: the network it runs on (candle-transformers
+//! `bert` or `xlm_roberta`, or the NomicBERT implementation here), how token
+//! states are pooled, and the task prefixes it was trained with. Outputs are
+//! L2-normalized like Stella's.
+
+mod base;
+pub mod instruction;
+mod loaded;
+mod nomic_bert;
+mod spec;
+
+pub use base::EncoderEmbeddingModel;
+pub use loaded::LoadedEncoderModel;
+pub use nomic_bert::{NomicBertConfig, NomicBertModel};
+pub use spec::{
+    BGE_M3, ENCODER_EMBEDDING_MODELS, EncoderArchitecture, EncoderEmbeddingSpec, GTE_LARGE,
+    NO_PREFIXES, NOMIC_EMBED_TEXT, Pooling, TaskPrefixes,
+};
@@ -0,0 +1,281 @@
+//! NomicBERT encoder
+//!
+//! BERT without learned positions: rotary embeddings on queries and keys, a
+//! fused QKV projection, post-norm blocks and SwiGLU feed-forward layers.
+//! candle-transformers has no implementation; weight and config names
+//! follow `nomic-ai/nomic-embed-text-v1.5`.
+
+use candle_core::{D, DType, Device, Result, Tensor};
+use candle_nn::{Embedding, LayerNorm, Linear, Module, VarBuilder};
+use serde::Deserialize;
+
+/// `config.json` of a NomicBERT checkpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct NomicBertConfig {
+    pub vocab_size: usize,
+    pub n_embd: usize,
+    pub n_head: usize,
+    pub n_layer: usize,
+    /// Feed-forward width; 4 × `n_embd` if unset
+    #[serde(default)]
+    pub n_inner: Option<usize>,
+    #[serde(default = "default_type_vocab_size")]
+    pub type_vocab_size: usize,
+    #[serde(default = "default_layer_norm_epsilon")]
+    pub layer_norm_epsilon: f64,
+    #[serde(default = "default_rotary_emb_base")]
+    pub rotary_emb_base: f64,
+    /// Share of each head's dimensions that are rotated
+    #[serde(default = "default_rotary_emb_fraction")]
+    pub rotary_emb_fraction: f64,
+    #[serde(default)]
+    pub qkv_proj_bias: bool,
+    #[serde(default)]
+    pub mlp_fc1_bias: bool,
+    #[serde(default)]
+    pub mlp_fc2_bias: bool,
+}
+
+fn default_type_vocab_size() -> usize {
+    2
+}
+
+fn default_layer_norm_epsilon() -> f64 {
+    1e-12
+}
+
+fn default_rotary_emb_base() -> f64 {
+    10_000.0
+}
+
+fn default_rotary_emb_fraction() -> f64 {
+    1.0
+}
+
+/// Non-interleaved rotary embedding over the first `dim` dimensions of a head
+#[derive(Debug, Clone)]
+struct RotaryEmbedding {
+    inv_freq: Tensor,
+    dim: usize,
+}
+
+impl RotaryEmbedding {
+    fn new(dim: usize, base: f64, device: &Device) -> Result<Self> {
+        let inv_freq: Vec<f32> = (0..dim)
+            .step_by(2)
+            .map(|i| 1.0 / base.powf(i as f64 / dim as f64) as f32)
+            .collect();
+        let len = inv_freq.len();
+        Ok(Self {
+            inv_freq: Tensor::from_vec(inv_freq, (1, len), device)?,
+            dim,
+        })
+    }
+
+    /// Cosines and sines for positions `0..seq_len`, each `(seq_len, dim / 2)`
+    fn cos_sin(&self, seq_len: usize) -> Result<(Tensor, Tensor)> {
+        let positions = Tensor::arange(0u32, seq_len as u32, self.inv_freq.device())?
+            .to_dtype(DType::F32)?
+            .reshape((seq_len, 1))?;
+        let freqs = positions.matmul(&self.inv_freq)?;
+        Ok((freqs.cos()?, freqs.sin()?))
+    }
+
+    /// Rotate `xs` of shape `(batch, heads, seq, head_dim)`
+    fn apply(&self, xs: &Tensor, cos: &Tensor, sin: &Tensor) -> Result<Tensor> {
+        let head_dim = xs.dim(D::Minus1)?;
+        if self.dim == head_dim {
+            return candle_nn::rotary_emb::rope(&xs.contiguous()?, cos, sin);
+        }
+        let rotated = candle_nn::rotary_emb::rope(
+            &xs.narrow(D::Minus1, 0, self.dim)?.contiguous()?,
+            cos,
+            sin,
+        )?;
+        let rest = xs.narrow(D::Minus1, self.dim, head_dim - self.dim)?;
+        Tensor::cat(&[&rotated, &rest], D::Minus1)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NomicBertAttention {
+    wqkv: Linear,
+    out_proj: Linear,
+    n_head: usize,
+    head_dim: usize,
+}
+
+impl NomicBertAttention {
+    fn load(vb: VarBuilder, config: &NomicBertConfig) -> Result<Self> {
+        let n_embd = config.n_embd;
+        Ok(Self {
+            wqkv: candle_nn::linear_b(n_embd, 3 * n_embd, config.qkv_proj_bias, vb.pp("Wqkv"))?,
+            out_proj: candle_nn::linear_b(n_embd, n_embd, config.qkv_proj_bias, vb.pp("out_proj"))?,
+            n_head: config.n_head,
+            head_dim: n_embd / config.n_head,
+        })
+    }
+
+    fn forward(
+        &self,
+        xs: &Tensor,
+        attention_mask: &Tensor,
+        rotary: &RotaryEmbedding,
+        cos: &Tensor,
+        sin: &Tensor,
+    ) -> Result<Tensor> {
+        let (batch, seq_len, n_embd) = xs.dims3()?;
+        let qkv =
+            self.wqkv
+                .forward(xs)?
+                .reshape((batch, seq_len, 3, self.n_head, self.head_dim))?;
+        let head = |i: usize| qkv.narrow(2, i, 1)?.squeeze(2)?.transpose(1, 2);
+        let q = rotary.apply(&head(0)?, cos, sin)?;
+        let k = rotary.apply(&head(1)?, cos, sin)?;
+        let v = head(2)?.contiguous()?;
+
+        let scale = (self.head_dim as f64).sqrt();
+        let scores = (q.matmul(&k.t()?.contiguous()?)? / scale)?.broadcast_add(attention_mask)?;
+        let probs = candle_nn::ops::softmax_last_dim(&scores)?;
+        let context = probs
+            .matmul(&v)?
+            .transpose(1, 2)?
+            .reshape((batch, seq_len, n_embd))?;
+        self.out_proj.forward(&context)
+    }
+}
+
+/// SwiGLU feed-forward: `fc2(fc11(x) * silu(fc12(x)))`
+#[derive(Debug, Clone)]
+struct NomicBertMlp {
+    fc11: Linear,
+    fc12: Linear,
+    fc2: Linear,
+}
+
+impl NomicBertMlp {
+    fn load(vb: VarBuilder, config: &NomicBertConfig) -> Result<Self> {
+        let n_embd = config.n_embd;
+        let n_inner = config.n_inner.unwrap_or(4 * n_embd);
+        Ok(Self {
+            fc11: candle_nn::linear_b(n_embd, n_inner, config.mlp_fc1_bias, vb.pp("fc11"))?,
+            fc12: candle_nn::linear_b(n_embd, n_inner, config.mlp_fc1_bias, vb.pp("fc12"))?,
+            fc2: candle_nn::linear_b(n_inner, n_embd, config.mlp_fc2_bias, vb.pp("fc2"))?,
+        })
+    }
+}
+
+impl Module for NomicBertMlp {
+    fn forward(&self, xs: &Tensor) -> Result<Tensor> {
+        let gate = candle_nn::ops::silu(&self.fc12.forward(xs)?)?;
+        self.fc2.forward(&(self.fc11.forward(xs)? * gate)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NomicBertLayer {
+    attn: NomicBertAttention,
+    mlp: NomicBertMlp,
+    norm1: LayerNorm,
+    norm2: LayerNorm,
+}
+
+impl NomicBertLayer {
+    fn load(vb: VarBuilder, config: &NomicBertConfig) -> Result<Self> {
+        let eps = config.layer_norm_epsilon;
+        Ok(Self {
+            attn: NomicBertAttention::load(vb.pp("attn"), config)?,
+            mlp: NomicBertMlp::load(vb.pp("mlp"), config)?,
+            norm1: candle_nn::layer_norm(config.n_embd, eps, vb.pp("norm1"))?,
+            norm2: candle_nn::layer_norm(config.n_embd, eps, vb.pp("norm2"))?,
+        })
+    }
+
+    fn forward(
+        &self,
+        xs: &Tensor,
+        attention_mask: &Tensor,
+        rotary: &RotaryEmbedding,
+        cos: &Tensor,
+        sin: &Tensor,
+    ) -> Result<Tensor> {
+        let attn = self.attn.forward(xs, attention_mask, rotary, cos, sin)?;
+        let xs = self.norm1.forward(&(attn + xs)?)?;
+        let mlp = self.mlp.forward(&xs)?;
+        self.norm2.forward(&(mlp + xs)?)
+    }
+}
+
+/// NomicBERT encoder returning the last hidden states
+#[derive(Debug, Clone)]
+pub struct NomicBertModel {
+    word_embeddings: Embedding,
+    token_type_embeddings: Option<Embedding>,
+    emb_ln: LayerNorm,
+    layers: Vec<NomicBertLayer>,
+    rotary: RotaryEmbedding,
+}
+
+impl NomicBertModel {
+    /// Load the weights under `vb`
+    pub fn load(vb: VarBuilder, config: &NomicBertConfig) -> Result<Self> {
+        let n_embd = config.n_embd;
+        let word_embeddings = candle_nn::embedding(
+            config.vocab_size,
+            n_embd,
+            vb.pp("embeddings.word_embeddings"),
+        )?;
+        let token_type_embeddings = if config.type_vocab_size > 0 {
+            Some(candle_nn::embedding(
+                config.type_vocab_size,
+                n_embd,
+                vb.pp("embeddings.token_type_embeddings"),
+            )?)
+        } else {
+            None
+        };
+        let emb_ln = candle_nn::layer_norm(n_embd, config.layer_norm_epsilon, vb.pp("emb_ln"))?;
+        let layers = (0..config.n_layer)
+            .map(|i| NomicBertLayer::load(vb.pp(format!("encoder.layers.{i}")), config))
+            .collect::<Result<Vec<_>>>()?;
+
+        let head_dim = n_embd / config.n_head;
+        // Rotated dimensions come in pairs
+        let rotary_dim = ((head_dim as f64 * config.rotary_emb_fraction) as usize) & !1;
+        let rotary = RotaryEmbedding::new(rotary_dim, config.rotary_emb_base, vb.device())?;
+
+        Ok(Self {
+            word_embeddings,
+            token_type_embeddings,
+            emb_ln,
+            layers,
+            rotary,
+        })
+    }
+
+    /// Hidden states `(batch, seq, n_embd)` of `input_ids`
+    ///
+    /// `attention_mask` is 1 for tokens and 0 for padding.
+    pub fn forward(
+        &self,
+        input_ids: &Tensor,
+        token_type_ids: &Tensor,
+        attention_mask: &Tensor,
+    ) -> Result<Tensor> {
+        let (batch, seq_len) = input_ids.dims2()?;
+        let mut xs = self.word_embeddings.forward(input_ids)?;
+        if let Some(token_type_embeddings) = &self.token_type_embeddings {
+            xs = (xs + token_type_embeddings.forward(token_type_ids)?)?;
+        }
+        let mut xs = self.emb_ln.forward(&xs)?;
+
+        // 0 for tokens, a large negative score for padding
+        let attention_mask = ((attention_mask.to_dtype(DType::F32)? - 1.0)? * f64::from(f32::MAX))?
+            .reshape((batch, 1, 1, seq_len))?;
+        let (cos, sin) = self.rotary.cos_sin(seq_len)?;
+        for layer in &self.layers {
+            xs = layer.forward(&xs, &attention_mask, &self.rotary, &cos, &sin)?;
+        }
+        Ok(xs)
+    }
+}
@@ -0,0 +1,171 @@
+//! Encoder embedding model specs
+//!
+//! Each [`EncoderEmbeddingSpec`] names a checkpoint, the network that runs
+//! it, how its token states are pooled and the prefixes it was trained with.
+
+use std::num::NonZeroU32;
+
+use crate::domain::model::CandleModelInfo;
+
+/// Network an encoder checkpoint runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncoderArchitecture {
+    /// candle-transformers `bert`
+    Bert,
+    /// candle-transformers `xlm_roberta`
+    XlmRoberta,
+    /// BERT with rotary positions and SwiGLU feed-forward layers
+    NomicBert,
+}
+
+/// How token states become one embedding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pooling {
+    /// State of the first (`[CLS]` / `<s>`) token
+    Cls,
+    /// Mean of the non-padding token states
+    Mean,
+}
+
+/// Text prepended to inputs, by kind of task
+///
+/// Models trained without prefixes leave them all empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskPrefixes {
+    pub query: &'static str,
+    pub document: &'static str,
+    pub classification: &'static str,
+    pub clustering: &'static str,
+}
+
+/// Prefixes of models trained without any
+pub const NO_PREFIXES: TaskPrefixes = TaskPrefixes {
+    query: "",
+    document: "",
+    classification: "",
+    clustering: "",
+};
+
+/// An encoder embedding model this crate can load
+#[derive(Debug)]
+pub struct EncoderEmbeddingSpec {
+    /// Model info; `registry_key` is the HuggingFace repository
+    pub info: CandleModelInfo,
+    pub architecture: EncoderArchitecture,
+    pub pooling: Pooling,
+    pub prefixes: TaskPrefixes,
+    /// Matryoshka dimensions below the full one the model is trained for
+    pub mrl_dimensions: &'static [usize],
+    /// Recommended and maximum batch sizes
+    pub batch_sizes: (usize, usize),
+}
+
+/// Every encoder embedding model, in registration order
+pub static ENCODER_EMBEDDING_MODELS: [&EncoderEmbeddingSpec; 3] =
+    [&BGE_M3, &GTE_LARGE, &NOMIC_EMBED_TEXT];
+
+/// BGE-M3: multilingual XLM-RoBERTa large, dense retrieval head
+pub static BGE_M3: EncoderEmbeddingSpec = EncoderEmbeddingSpec {
+    info: CandleModelInfo {
+        provider: crate::domain::model::CandleProvider::BAAI,
+        name: "bge-m3",
+        registry_key: "BAAI/bge-m3",
+        max_input_tokens: NonZeroU32::new(8192),
+        model_id: "bge-m3",
+        embedding_dimension: Some(1024),
+        vocab_size: Some(250002),
+        est_memory_allocation_mb: 2600, // 568M params × 4 bytes/param + overhead
+        ..ENCODER_BASE_INFO
+    },
+    architecture: EncoderArchitecture::XlmRoberta,
+    pooling: Pooling::Cls,
+    prefixes: NO_PREFIXES,
+    mrl_dimensions: &[],
+    batch_sizes: (8, 32),
+};
+
+/// GTE-large: English BERT large
+pub static GTE_LARGE: EncoderEmbeddingSpec = EncoderEmbeddingSpec {
+    info: CandleModelInfo {
+        provider: crate::domain::model::CandleProvider::AlibabaNLP,
+        name: "gte-large",
+        registry_key: "thenlper/gte-large",
+        max_input_tokens: NonZeroU32::new(512),
+        model_id: "gte-large",
+        embedding_dimension: Some(1024),
+        vocab_size: Some(30522),
+        est_memory_allocation_mb: 1500, // 335M params × 4 bytes/param + overhead
+        ..ENCODER_BASE_INFO
+    },
+    architecture: EncoderArchitecture::Bert,
+    pooling: Pooling::Mean,
+    prefixes: NO_PREFIXES,
+    mrl_dimensions: &[],
+    batch_sizes: (16, 64),
+};
+
+/// nomic-embed-text v1.5: English NomicBERT with Matryoshka dimensions
+pub static NOMIC_EMBED_TEXT: EncoderEmbeddingSpec = EncoderEmbeddingSpec {
+    info: CandleModelInfo {
+        provider: crate::domain::model::CandleProvider::NomicAI,
+        name: "nomic-embed-text-v1.5",
+        registry_key: "nomic-ai/nomic-embed-text-v1.5",
+        // Trained context; longer inputs need rotary scaling
+        max_input_tokens: NonZeroU32::new(2048),
+        model_id: "nomic-embed-text-v1.5",
+        embedding_dimension: Some(768),
+        vocab_size: Some(30528),
+        est_memory_allocation_mb: 700, // 137M params × 4 bytes/param + overhead
+        ..ENCODER_BASE_INFO
+    },
+    architecture: EncoderArchitecture::NomicBert,
+    pooling: Pooling::Mean,
+    prefixes: TaskPrefixes {
+        query: "search_query: ",
+        document: "search_document: ",
+        classification: "classification: ",
+        clustering: "clustering: ",
+    },
+    mrl_dimensions: &[64, 128, 256, 512],
+    batch_sizes: (32, 128),
+};
+
+// Fields shared by every encoder embedding model
+const ENCODER_BASE_INFO: CandleModelInfo = CandleModelInfo {
+    provider: crate::domain::model::CandleProvider::Community,
+    name: "",
+    registry_key: "",
+    quantization_url: None,
+    max_input_tokens: None,
+    max_output_tokens: None,
+    input_price: None,
+    output_price: None,
+    supports_vision: false,
+    supports_function_calling: false,
+    supports_streaming: false,
+    supports_embeddings: true,
+    requires_max_tokens: false,
+    supports_thinking: false,
+    optimal_thinking_budget: None,
+    system_prompt_prefix: None,
+    real_name: None,
+    model_type: None,
+    model_id: "",
+    quantization: "none",
+    patch: None,
+    embedding_dimension: None,
+    vocab_size: None,
+    image_size: None,
+    image_mean: None,
+    image_std: None,
+    default_temperature: None,
+    default_top_k: None,
+    default_top_p: None,
+    supports_kv_cache: false,
+    supports_flash_attention: false,
+    use_bf16: false,
+    default_steps: None,
+    default_guidance_scale: None,
+    time_shift: None,
+    est_memory_allocation_mb: 0,
+};
@@ -2,9 +2,12 @@
 //!
 //! Providers that implement text embedding using EmbeddingModel trait.
 
+pub mod adaptive_batch;
 pub mod safetensors_validation;
 
+pub mod encoder;
 pub mod stella;
 
 // Re-exports for convenience
+pub(crate) use encoder::EncoderEmbeddingModel;
 pub(crate) use stella::StellaEmbeddingModel;
@@ -1,6 +1,8 @@
 //! Base Stella embedding model implementation
 
-use super::config::STELLA_400M_MODEL_INFO;
+use super::config::{
+    MRL_DIMENSIONS, STELLA_1_5B_MODEL_INFO, STELLA_400M_MODEL_INFO, batch_sizes, detect_variant,
+};
 use crate::domain::model::CandleModelInfo;
 use crate::domain::model::traits::CandleModel;
 
@@ -20,7 +22,9 @@ use crate::domain::model::traits::CandleModel;
 /// model.embed("text", None).await?;  // ← Compile error!
 /// ```
 #[derive(Debug, Clone)]
-pub struct StellaEmbeddingModel {}
+pub struct StellaEmbeddingModel {
+    info: &'static CandleModelInfo,
+}
 
 impl Default for StellaEmbeddingModel {
     fn default() -> Self {
@@ -29,24 +33,43 @@ impl Default for StellaEmbeddingModel {
 }
 
 impl StellaEmbeddingModel {
-    /// Create new Stella embedding provider
+    /// Create new Stella embedding provider (400M variant)
     #[inline]
     pub fn new() -> Self {
-        Self {}
+        Self {
+            info: &STELLA_400M_MODEL_INFO,
+        }
+    }
+
+    /// Stella provider of the 1.5B variant: slower, better retrieval quality
+    #[inline]
+    pub fn large() -> Self {
+        Self {
+            info: &STELLA_1_5B_MODEL_INFO,
+        }
     }
 
     /// Get the embedding output dimension from model info
     pub fn embedding_dimension(&self) -> usize {
         self.info().embedding_dimension.unwrap_or(1024) as usize
     }
+
+    /// MRL dimensions the output can be truncated to, up to the full dimension
+    pub fn supported_dimensions(&self) -> Vec<usize> {
+        let full = self.embedding_dimension();
+        MRL_DIMENSIONS.into_iter().filter(|&dim| dim <= full).collect()
+    }
+
+    /// Recommended and maximum batch sizes of this variant
+    pub fn batch_sizes(&self) -> (usize, usize) {
+        batch_sizes(detect_variant(self.info().registry_key))
+    }
 }
 
 impl CandleModel for StellaEmbeddingModel {
     fn info(&self) -> &'static CandleModelInfo {
-        // Default to 400M variant
-        // Note: This is only used for registry lookup. Actual variant is detected
-        // from registry_key during model loading.
-        &STELLA_400M_MODEL_INFO
+        // The loader detects the variant from this info's registry_key
+        self.info
     }
 }
 
@@ -4,6 +4,9 @@ use crate::domain::model::CandleModelInfo;
 use candle_transformers::models::stella_en_v5::{EmbedDim, ModelVariant};
 use std::num::NonZeroU32;
 
+/// Matryoshka (MRL) dimensions Stella is trained to be truncated to
+pub(crate) const MRL_DIMENSIONS: [usize; 7] = [256, 768, 1024, 2048, 4096, 6144, 8192];
+
 /// Static model info for Stella 400M variant
 pub(crate) static STELLA_400M_MODEL_INFO: CandleModelInfo = CandleModelInfo {
     provider: crate::domain::model::CandleProvider::Dunzhang,
@@ -93,6 +96,14 @@ pub(crate) fn detect_variant(registry_key: &str) -> ModelVariant {
     }
 }
 
+/// Recommended and maximum batch sizes of a variant
+pub(crate) fn batch_sizes(variant: ModelVariant) -> (usize, usize) {
+    match variant {
+        ModelVariant::Large => (8, 32),
+        ModelVariant::Small => (16, 64),
+    }
+}
+
 /// Convert dimension to EmbedDim enum
 pub(crate) fn embed_dim(
     dimension: u32,
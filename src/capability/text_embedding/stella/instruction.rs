@@ -12,12 +12,58 @@ const VALID_TASKS: &[&str] = &[
     "retrieval",
 ];
 
+/// Prefix of per-language code tasks, e.g. `"code:rust"`
+pub const CODE_TASK_PREFIX: &str = "code:";
+
+/// Prefix of tasks that carry their own instruction, e.g. `"instruct:Retrieve matching legal clauses."`
+pub const INSTRUCT_TASK_PREFIX: &str = "instruct:";
+
+/// Instructions for code search queries; stored code is embedded as a plain document
+const CODE_INSTRUCTIONS: &[(&str, &str)] = &[
+    ("code:rust", "Given a question about Rust code, retrieve the functions, types, traits and impls that answer it."),
+    ("code:python", "Given a question about Python code, retrieve the functions, classes and methods that answer it."),
+    ("code:javascript", "Given a question about JavaScript code, retrieve the functions, classes and modules that answer it."),
+    ("code:typescript", "Given a question about TypeScript code, retrieve the functions, classes, interfaces and types that answer it."),
+    ("code:go", "Given a question about Go code, retrieve the functions, methods and types that answer it."),
+    ("code:java", "Given a question about Java code, retrieve the classes, interfaces and methods that answer it."),
+    ("code:kotlin", "Given a question about Kotlin code, retrieve the classes, objects and functions that answer it."),
+    ("code:c", "Given a question about C code, retrieve the functions, structs and macros that answer it."),
+    ("code:cpp", "Given a question about C++ code, retrieve the functions, classes and templates that answer it."),
+    ("code:csharp", "Given a question about C# code, retrieve the classes, interfaces and methods that answer it."),
+    ("code:ruby", "Given a question about Ruby code, retrieve the modules, classes and methods that answer it."),
+    ("code:swift", "Given a question about Swift code, retrieve the types, protocols and functions that answer it."),
+    ("code:php", "Given a question about PHP code, retrieve the classes, traits and functions that answer it."),
+];
+
+/// Instruction for a per-language code task, if the language is known
+pub fn code_instruction(task: &str) -> Option<&'static str> {
+    CODE_INSTRUCTIONS
+        .iter()
+        .find(|(name, _)| *name == task)
+        .map(|(_, instruction)| *instruction)
+}
+
+/// Instruction carried by an `instruct:` task, if it is one and not blank
+pub fn custom_instruction(task: &str) -> Option<&str> {
+    task.strip_prefix(INSTRUCT_TASK_PREFIX)
+        .map(str::trim)
+        .filter(|instruction| !instruction.is_empty())
+}
+
 /// Get the instruction string for a given task (or default)
 ///
 /// Validates the task parameter and logs a warning if invalid.
 /// Returns the appropriate instruction text for the task.
-fn get_instruction(task: Option<&str>) -> &'static str {
+fn get_instruction(task: Option<&str>) -> &str {
+    if let Some(instruction) = task.and_then(custom_instruction) {
+        return instruction;
+    }
+
     // Validate task parameter and warn if invalid
+    if let Some(instruction) = task.and_then(code_instruction) {
+        return instruction;
+    }
+
     if let Some(t) = task
         && !VALID_TASKS.contains(&t)
     {
@@ -60,6 +106,8 @@ fn get_instruction(task: Option<&str>) -> &'static str {
 ///   - Instruction: "Given a web search query, retrieve relevant passages that answer the query."
 /// - `"s2s"`, `"classification"`, or `"clustering"`: Semantic similarity
 ///   - Instruction: "Retrieve semantically similar text."
+/// - `"code:<language>"` (e.g. `"code:rust"`): Per-language code search instruction
+/// - `"instruct:<instruction>"`: The given instruction
 /// - `None`: Defaults to search query mode (`"s2p"`)
 ///
 /// # Validation
@@ -95,6 +143,8 @@ pub fn format_single_with_instruction(text: &str, task: Option<&str>) -> String
 ///   - Instruction: "Given a web search query, retrieve relevant passages that answer the query."
 /// - `"s2s"`, `"classification"`, or `"clustering"`: Semantic similarity
 ///   - Instruction: "Retrieve semantically similar text."
+/// - `"code:<language>"` (e.g. `"code:rust"`): Per-language code search instruction
+/// - `"instruct:<instruction>"`: The given instruction
 /// - `None`: Defaults to search query mode (`"s2p"`)
 ///
 /// # Validation
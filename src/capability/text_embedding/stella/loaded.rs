@@ -1,12 +1,15 @@
 //! Loaded Stella model wrapper with thread-safe interior mutability
 
-use super::config::{STELLA_1_5B_MODEL_INFO, STELLA_400M_MODEL_INFO, detect_variant, embed_dim};
+use super::config::{
+    MRL_DIMENSIONS, STELLA_1_5B_MODEL_INFO, STELLA_400M_MODEL_INFO, batch_sizes, detect_variant,
+    embed_dim,
+};
 use super::instruction::{format_single_with_instruction, format_with_instruction};
 use super::utils::{
     configure_stella_tokenizer, create_stella_config, load_stella_weights,
 };
 use crate::capability::traits::TextEmbeddingCapable;
-use crate::core::device_util::detect_best_device;
+use crate::core::device_util::{DevicePreference, EMBEDDING_DEVICE_ENV, select_device};
 use crate::domain::model::CandleModelInfo;
 use crate::domain::model::traits::CandleModel;
 use anyhow::{Context, anyhow};
@@ -85,9 +88,10 @@ impl LoadedStellaModel {
         let variant = detect_variant(base_model.info().registry_key);
         let embed_dim = embed_dim(dimension as u32)?;
 
-        // Use best available device (Metal/CUDA/CPU) - async issues handled by spawn_blocking in embed()
-        let device = detect_best_device()
-            .context("Failed to detect compute device")?;
+        // Device from KODEGEN_EMBEDDING_DEVICE, else the best available one.
+        // GPU work is confined to spawn_blocking and serialized by the model lock
+        let device = select_device(DevicePreference::from_env(EMBEDDING_DEVICE_ENV))
+            .context("Failed to select compute device")?;
         let dtype = DType::F32;
 
         // Load files from HuggingFace
@@ -134,8 +138,20 @@ impl LoadedStellaModel {
 
     /// Get supported MRL dimensions (Matryoshka Representation Learning)
     pub fn supported_dimensions(&self) -> Vec<usize> {
-        vec![256, 768, 1024, 2048, 4096, 6144, 8192]
+        MRL_DIMENSIONS.to_vec()
+    }
+}
+
+/// Wait for queued GPU work on `device`, then copy a result to host memory
+///
+/// CPU devices have nothing to wait for.
+fn read_back<T>(device: &Device, read: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    if !device.is_cpu() {
+        device
+            .synchronize()
+            .context("Failed to synchronize compute device")?;
     }
+    read()
 }
 
 impl TextEmbeddingCapable for LoadedStellaModel {
@@ -184,28 +200,27 @@ impl TextEmbeddingCapable for LoadedStellaModel {
                     .context("Failed to create attention mask")?;
                 log::info!("embed: Created attention_mask tensor");
 
-                // Forward pass - lock std::sync::Mutex in blocking context
+                // Forward pass - lock std::sync::Mutex in blocking context.
+                // The lock is held until the result is read back, so GPU
+                // command buffers of concurrent calls never interleave
                 log::info!("embed: About to lock model");
-                let embeddings = {
-                    let mut model_guard = model.lock()
-                        .map_err(|e| anyhow!("Model mutex poisoned (thread panic): {}", e))?;
-                    log::info!("embed: Model locked, calling forward_norm");
-                    model_guard
-                        .forward_norm(&input_ids, &attention_mask)
-                        .context("Stella forward pass failed")?
-                };
+                let mut model_guard = model.lock()
+                    .map_err(|e| anyhow!("Model mutex poisoned (thread panic): {}", e))?;
+                log::info!("embed: Model locked, calling forward_norm");
+                let embeddings = model_guard
+                    .forward_norm(&input_ids, &attention_mask)
+                    .context("Stella forward pass failed")?;
                 log::info!("embed: forward_norm completed");
 
                 // Extract first embedding - squeeze batch dimension then to_vec1
-                log::info!("embed: About to squeeze");
-                let squeezed = embeddings
-                    .squeeze(0)
-                    .context("Failed to squeeze batch dimension")?;
-                log::info!("embed: Squeezed, about to to_vec1");
-                
-                let vec = squeezed
-                    .to_vec1::<f32>()
-                    .context("Failed to convert embedding to vec")?;
+                let vec = read_back(&device, || {
+                    embeddings
+                        .squeeze(0)
+                        .context("Failed to squeeze batch dimension")?
+                        .to_vec1::<f32>()
+                        .context("Failed to convert embedding to vec")
+                })?;
+                drop(model_guard);
                 log::info!("embed: Converted to vec, length: {}", vec.len());
 
                 Ok(vec)
@@ -265,19 +280,20 @@ impl TextEmbeddingCapable for LoadedStellaModel {
                     .to_dtype(DType::U8)
                     .context("Failed to convert mask dtype")?;
 
-                // Forward pass - lock std::sync::Mutex in blocking context
-                let embeddings = {
-                    let mut model_guard = model.lock()
-                        .map_err(|e| anyhow!("Model mutex poisoned (thread panic): {}", e))?;
-                    model_guard
-                        .forward_norm(&input_ids, &attention_mask)
-                        .context("Stella batch forward pass failed")?
-                };
+                // Forward pass - lock held until read back, as in embed()
+                let mut model_guard = model.lock()
+                    .map_err(|e| anyhow!("Model mutex poisoned (thread panic): {}", e))?;
+                let embeddings = model_guard
+                    .forward_norm(&input_ids, &attention_mask)
+                    .context("Stella batch forward pass failed")?;
 
                 // Convert to Vec<Vec<f32>>
-                let vec = embeddings
-                    .to_vec2::<f32>()
-                    .context("Failed to convert batch embeddings to vec")?;
+                let vec = read_back(&device, || {
+                    embeddings
+                        .to_vec2::<f32>()
+                        .context("Failed to convert batch embeddings to vec")
+                })?;
+                drop(model_guard);
 
                 Ok(vec)
             })
@@ -293,16 +309,10 @@ impl TextEmbeddingCapable for LoadedStellaModel {
     }
 
     fn recommended_batch_size(&self) -> usize {
-        match self.variant {
-            ModelVariant::Large => 8,
-            ModelVariant::Small => 16,
-        }
+        batch_sizes(self.variant).0
     }
 
     fn max_batch_size(&self) -> usize {
-        match self.variant {
-            ModelVariant::Large => 32,
-            ModelVariant::Small => 64,
-        }
+        batch_sizes(self.variant).1
     }
 }
@@ -0,0 +1,348 @@
+//! Chat templates: how a conversation becomes model input
+//!
+//! Each text-to-text model family expects its own markup around system,
+//! user, assistant and tool turns. A wrong newline or a missing end-of-turn
+//! token does not fail loudly, it just makes generation worse, so templates
+//! are rendered here in one place and covered by golden files (see
+//! [`template_goldens`](super::template_goldens)).
+
+use crate::domain::completion::format_tools_for_qwen3;
+use crate::domain::completion::types::ToolInfo;
+
+/// System instructions added when tools are offered to a ChatML model
+pub const CHATML_TOOL_PREAMBLE: &str = "You are a helpful AI assistant with access to tools. When you need to use a tool, output <tool_call>{\"name\": \"tool_name\", \"arguments\": {...}}</tool_call>";
+
+/// Who a turn is from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+    /// Result of a tool call, fed back to the model
+    Tool,
+}
+
+/// One turn of a conversation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatTurn {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatTurn {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::System,
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::User,
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::Assistant,
+            content: content.into(),
+        }
+    }
+
+    pub fn tool(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::Tool,
+            content: content.into(),
+        }
+    }
+}
+
+/// A conversation to render, ending where the assistant should reply
+#[derive(Debug, Clone)]
+pub struct ChatConversation {
+    pub turns: Vec<ChatTurn>,
+    /// Tools offered to the model
+    pub tools: Vec<ToolInfo>,
+    /// Let the model reason before answering; `false` pre-fills an empty
+    /// reasoning block on models that support one
+    pub enable_thinking: bool,
+    /// Text the reply starts with
+    pub assistant_prefix: Option<String>,
+}
+
+impl ChatConversation {
+    pub fn new(turns: Vec<ChatTurn>) -> Self {
+        Self {
+            turns,
+            tools: Vec::new(),
+            enable_thinking: true,
+            assistant_prefix: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_tools(mut self, tools: Vec<ToolInfo>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    #[must_use]
+    pub fn with_thinking(mut self, enabled: bool) -> Self {
+        self.enable_thinking = enabled;
+        self
+    }
+
+    #[must_use]
+    pub fn with_assistant_prefix(mut self, prefix: Option<String>) -> Self {
+        self.assistant_prefix = prefix.filter(|p| !p.is_empty());
+        self
+    }
+}
+
+/// Prompt markup used by a model family
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ChatTemplateFamily {
+    /// `<|im_start|>role ... <|im_end|>` turns, as used by Qwen3
+    ChatMl,
+    /// `<|start_header_id|>role<|end_header_id|> ... <|eot_id|>` turns, as
+    /// used by Llama 3.x
+    Llama3,
+    /// `[INST] ... [/INST]` turns, as used by Mistral Instruct
+    Mistral,
+    /// `<|role|> ... <|end|>` turns, as used by Phi-3
+    Phi3,
+}
+
+impl ChatTemplateFamily {
+    /// Every template family, in a stable order
+    pub const ALL: [Self; 4] = [Self::ChatMl, Self::Llama3, Self::Mistral, Self::Phi3];
+
+    /// Short name, used for golden file directories
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ChatMl => "chatml",
+            Self::Llama3 => "llama3",
+            Self::Mistral => "mistral",
+            Self::Phi3 => "phi3",
+        }
+    }
+
+    /// Token that ends an assistant turn, where generation should stop
+    pub fn end_of_turn(self) -> &'static str {
+        match self {
+            Self::ChatMl => "<|im_end|>",
+            Self::Llama3 => "<|eot_id|>",
+            Self::Mistral => "</s>",
+            Self::Phi3 => "<|end|>",
+        }
+    }
+
+    /// Render `conversation` into model input, ending with the assistant header
+    ///
+    /// A beginning-of-sequence token, where the model uses one, is left to the
+    /// tokenizer.
+    pub fn render(self, conversation: &ChatConversation) -> String {
+        match self {
+            Self::ChatMl => render_chatml(conversation),
+            Self::Llama3 => render_llama3(conversation),
+            Self::Mistral => render_mistral(conversation),
+            Self::Phi3 => render_phi3(conversation),
+        }
+    }
+}
+
+/// System turns merged into one message, followed by the tool definitions
+fn system_prompt(conversation: &ChatConversation) -> Option<String> {
+    let mut system: Vec<&str> = conversation
+        .turns
+        .iter()
+        .filter(|turn| turn.role == ChatRole::System)
+        .map(|turn| turn.content.as_str())
+        .collect();
+    let tool_block = (!conversation.tools.is_empty()).then(|| {
+        format!(
+            "{CHATML_TOOL_PREAMBLE}\n\n{}",
+            format_tools_for_qwen3(&conversation.tools)
+        )
+    });
+    if let Some(tool_block) = &tool_block {
+        system.push(tool_block);
+    }
+    (!system.is_empty()).then(|| system.join("\n\n"))
+}
+
+/// Qwen3 ChatML rendering
+///
+/// System turns are merged into one leading system message, followed by the
+/// tool definitions. Reasoning is dropped from earlier assistant turns and
+/// tool results are passed back as user turns in `<tool_response>` tags.
+fn render_chatml(conversation: &ChatConversation) -> String {
+    let mut out = String::new();
+    if let Some(system) = system_prompt(conversation) {
+        out.push_str(&format!("<|im_start|>system\n{system}<|im_end|>\n"));
+    }
+
+    for turn in &conversation.turns {
+        match turn.role {
+            ChatRole::System => {}
+            ChatRole::User => {
+                out.push_str(&format!("<|im_start|>user\n{}<|im_end|>\n", turn.content));
+            }
+            ChatRole::Assistant => {
+                out.push_str(&format!(
+                    "<|im_start|>assistant\n{}<|im_end|>\n",
+                    strip_reasoning(&turn.content)
+                ));
+            }
+            ChatRole::Tool => {
+                out.push_str(&format!(
+                    "<|im_start|>user\n<tool_response>\n{}\n</tool_response><|im_end|>\n",
+                    turn.content
+                ));
+            }
+        }
+    }
+
+    out.push_str("<|im_start|>assistant\n");
+    if !conversation.enable_thinking {
+        out.push_str("<think>\n\n</think>\n\n");
+    }
+    if let Some(prefix) = &conversation.assistant_prefix {
+        out.push_str(prefix);
+    }
+    out
+}
+
+/// Llama 3.x rendering
+///
+/// Tools are offered in the system message with the same `<tool_call>`
+/// instructions as ChatML; tool results come back in `ipython` turns.
+fn render_llama3(conversation: &ChatConversation) -> String {
+    let header = |role: &str| format!("<|start_header_id|>{role}<|end_header_id|>\n\n");
+
+    let mut out = String::new();
+    if let Some(system) = system_prompt(conversation) {
+        out.push_str(&format!("{}{system}<|eot_id|>", header("system")));
+    }
+
+    for turn in &conversation.turns {
+        match turn.role {
+            ChatRole::System => {}
+            ChatRole::User => {
+                out.push_str(&format!("{}{}<|eot_id|>", header("user"), turn.content));
+            }
+            ChatRole::Assistant => {
+                out.push_str(&format!(
+                    "{}{}<|eot_id|>",
+                    header("assistant"),
+                    strip_reasoning(&turn.content)
+                ));
+            }
+            ChatRole::Tool => {
+                out.push_str(&format!("{}{}<|eot_id|>", header("ipython"), turn.content));
+            }
+        }
+    }
+
+    out.push_str(&header("assistant"));
+    if let Some(prefix) = &conversation.assistant_prefix {
+        out.push_str(prefix);
+    }
+    out
+}
+
+/// Mistral Instruct (v0.3) rendering
+///
+/// Mistral has no system role: as in its own template, the system message
+/// and tool definitions are prepended to the last user turn. Tool results
+/// are passed back as instructions in `<tool_response>` tags.
+fn render_mistral(conversation: &ChatConversation) -> String {
+    let system = system_prompt(conversation);
+    let last_user = conversation
+        .turns
+        .iter()
+        .rposition(|turn| turn.role == ChatRole::User);
+
+    let mut out = String::new();
+    if last_user.is_none()
+        && let Some(system) = &system
+    {
+        out.push_str(&format!("[INST] {system}[/INST]"));
+    }
+
+    for (index, turn) in conversation.turns.iter().enumerate() {
+        match turn.role {
+            ChatRole::System => {}
+            ChatRole::User => match &system {
+                Some(system) if Some(index) == last_user => {
+                    out.push_str(&format!("[INST] {system}\n\n{}[/INST]", turn.content));
+                }
+                _ => out.push_str(&format!("[INST] {}[/INST]", turn.content)),
+            },
+            ChatRole::Assistant => {
+                out.push_str(&format!(" {}</s>", strip_reasoning(&turn.content).trim()));
+            }
+            ChatRole::Tool => {
+                out.push_str(&format!(
+                    "[INST] <tool_response>\n{}\n</tool_response>[/INST]",
+                    turn.content
+                ));
+            }
+        }
+    }
+
+    if let Some(prefix) = &conversation.assistant_prefix {
+        out.push_str(prefix);
+    }
+    out
+}
+
+/// Phi-3 rendering
+///
+/// Laid out like ChatML with `<|role|>` headers; tool results are passed
+/// back as user turns in `<tool_response>` tags.
+fn render_phi3(conversation: &ChatConversation) -> String {
+    let mut out = String::new();
+    if let Some(system) = system_prompt(conversation) {
+        out.push_str(&format!("<|system|>\n{system}<|end|>\n"));
+    }
+
+    for turn in &conversation.turns {
+        match turn.role {
+            ChatRole::System => {}
+            ChatRole::User => {
+                out.push_str(&format!("<|user|>\n{}<|end|>\n", turn.content));
+            }
+            ChatRole::Assistant => {
+                out.push_str(&format!(
+                    "<|assistant|>\n{}<|end|>\n",
+                    strip_reasoning(&turn.content)
+                ));
+            }
+            ChatRole::Tool => {
+                out.push_str(&format!(
+                    "<|user|>\n<tool_response>\n{}\n</tool_response><|end|>\n",
+                    turn.content
+                ));
+            }
+        }
+    }
+
+    out.push_str("<|assistant|>\n");
+    if let Some(prefix) = &conversation.assistant_prefix {
+        out.push_str(prefix);
+    }
+    out
+}
+
+/// Remove a leading `<think>...</think>` block from an earlier reply
+fn strip_reasoning(content: &str) -> &str {
+    match content.split_once("</think>") {
+        Some((before, after)) if before.trim_start().starts_with("<think>") => {
+            after.trim_start_matches('\n')
+        }
+        _ => content,
+    }
+}
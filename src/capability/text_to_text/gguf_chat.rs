@@ -0,0 +1,743 @@
+//! Streaming completion with Llama 3.x, Mistral and Phi-3 GGUF models
+//!
+//! These families share one loader and generation loop. Each model is
+//! described by a [`GgufChatSpec`]: its checkpoint, the candle-transformers
+//! implementation that runs it and its chat template preset, if any; without
+//! one, prompts go through the model's own template (see
+//! [`ModelChatTemplate`]). Llama and Mistral both run on `quantized_llama`;
+//! Phi-3 has its own fused-attention layout in `quantized_phi3`.
+
+use std::num::NonZeroU32;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+use candle_core::quantized::gguf_file;
+use candle_core::{Device, Tensor};
+use candle_transformers::generation::{LogitsProcessor, Sampling};
+use candle_transformers::models::{quantized_llama, quantized_phi3};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_stream::Stream;
+use tokio_util::sync::CancellationToken;
+use kodegen_simd::logits::constraints::GenerationConstraint;
+use uuid::Uuid;
+
+use super::chat_template::ChatTemplateFamily;
+use crate::capability::lora::{self, LoraAdapter, LoraSource, LoraWeights};
+use crate::core::generation::TokenOutputStream;
+use crate::core::{Engine, EngineConfig};
+use crate::domain::chat::templates::ModelChatTemplate;
+use crate::core::generation::constraints::mask_logits;
+use crate::domain::completion::{ConstraintCache, ResponseFormat, ToolCallParser};
+use crate::domain::completion::{CandleCompletionChunk, CandleCompletionParams};
+use crate::domain::context::chunks::FinishReason;
+use crate::domain::model::CandleUsage;
+use crate::domain::model::{info::CandleModelInfo, traits::CandleModel};
+use crate::domain::prompt::CandlePrompt;
+
+/// candle-transformers implementation a GGUF checkpoint runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GgufArchitecture {
+    /// `quantized_llama`: Llama 2/3 and Mistral
+    Llama,
+    /// `quantized_phi3`: Phi-3 and Phi-4
+    Phi3,
+}
+
+/// A GGUF text-to-text model this crate can load
+#[derive(Debug)]
+pub struct GgufChatSpec {
+    /// Model info; `quantization_url` names the weight file (`org/repo/file.gguf`)
+    pub info: CandleModelInfo,
+    /// Repository holding `tokenizer.json`
+    pub tokenizer_repo: &'static str,
+    pub architecture: GgufArchitecture,
+    /// Built-in template; `None` uses the `chat_template` in the tokenizer
+    /// repository's `tokenizer_config.json`
+    pub template: Option<ChatTemplateFamily>,
+}
+
+impl GgufChatSpec {
+    /// Repository and filename of the GGUF weights
+    pub fn checkpoint(&self) -> Option<(&'static str, &'static str)> {
+        self.info.quantization_url?.rsplit_once('/')
+    }
+}
+
+/// Every GGUF model besides Qwen3, in registration order
+pub static GGUF_CHAT_MODELS: [&GgufChatSpec; 3] =
+    [&LLAMA_3_2_3B, &MISTRAL_7B_INSTRUCT, &PHI_3_MINI];
+
+/// Llama 3.2 3B Instruct, Q4_K_M
+pub static LLAMA_3_2_3B: GgufChatSpec = GgufChatSpec {
+    info: CandleModelInfo {
+        provider: crate::domain::model::CandleProvider::Unsloth,
+        name: "llama-3.2-3b",
+        registry_key: "unsloth/Llama-3.2-3B-Instruct-GGUF",
+        quantization_url: Some(
+            "unsloth/Llama-3.2-3B-Instruct-GGUF/Llama-3.2-3B-Instruct-Q4_K_M.gguf",
+        ),
+        max_input_tokens: NonZeroU32::new(131072), // 128K context window
+        model_id: "llama-3.2",
+        vocab_size: Some(128256),
+        est_memory_allocation_mb: 2300, // ~2.0GB weights + KV cache
+        ..GGUF_CHAT_BASE_INFO
+    },
+    tokenizer_repo: "unsloth/Llama-3.2-3B-Instruct",
+    architecture: GgufArchitecture::Llama,
+    template: Some(ChatTemplateFamily::Llama3),
+};
+
+/// Mistral 7B Instruct v0.3, Q4_K_M
+pub static MISTRAL_7B_INSTRUCT: GgufChatSpec = GgufChatSpec {
+    info: CandleModelInfo {
+        provider: crate::domain::model::CandleProvider::Community,
+        name: "mistral-7b-instruct",
+        registry_key: "bartowski/Mistral-7B-Instruct-v0.3-GGUF",
+        quantization_url: Some(
+            "bartowski/Mistral-7B-Instruct-v0.3-GGUF/Mistral-7B-Instruct-v0.3-Q4_K_M.gguf",
+        ),
+        max_input_tokens: NonZeroU32::new(32768), // 32K context window
+        model_id: "mistral-7b",
+        vocab_size: Some(32768),
+        est_memory_allocation_mb: 4800, // ~4.4GB weights + KV cache
+        ..GGUF_CHAT_BASE_INFO
+    },
+    tokenizer_repo: "unsloth/mistral-7b-instruct-v0.3",
+    architecture: GgufArchitecture::Llama,
+    template: Some(ChatTemplateFamily::Mistral),
+};
+
+/// Phi-3 Mini 4K Instruct, 4-bit
+pub static PHI_3_MINI: GgufChatSpec = GgufChatSpec {
+    info: CandleModelInfo {
+        provider: crate::domain::model::CandleProvider::Microsoft,
+        name: "phi-3-mini",
+        registry_key: "microsoft/Phi-3-mini-4k-instruct-gguf",
+        quantization_url: Some(
+            "microsoft/Phi-3-mini-4k-instruct-gguf/Phi-3-mini-4k-instruct-q4.gguf",
+        ),
+        max_input_tokens: NonZeroU32::new(4096), // 4K context window
+        max_output_tokens: NonZeroU32::new(4096),
+        supports_function_calling: false,
+        model_id: "phi-3",
+        vocab_size: Some(32064),
+        est_memory_allocation_mb: 2600, // ~2.2GB weights + KV cache
+        supports_flash_attention: true, // quantized_phi3 takes a flash-attn flag
+        ..GGUF_CHAT_BASE_INFO
+    },
+    tokenizer_repo: "microsoft/Phi-3-mini-4k-instruct",
+    architecture: GgufArchitecture::Phi3,
+    template: Some(ChatTemplateFamily::Phi3),
+};
+
+// Fields shared by every GGUF chat model
+const GGUF_CHAT_BASE_INFO: CandleModelInfo = CandleModelInfo {
+    provider: crate::domain::model::CandleProvider::Community,
+    name: "",
+    registry_key: "",
+    quantization_url: None,
+    max_input_tokens: None,
+    max_output_tokens: NonZeroU32::new(8192),
+    input_price: None,
+    output_price: None,
+    supports_vision: false,
+    supports_function_calling: true,
+    supports_streaming: true,
+    supports_embeddings: false,
+    requires_max_tokens: false,
+    supports_thinking: false,
+    optimal_thinking_budget: None,
+    system_prompt_prefix: None,
+    real_name: None,
+    model_type: None,
+    model_id: "",
+    quantization: "Q4_K_M",
+    patch: None,
+    embedding_dimension: None,
+    vocab_size: None,
+    image_size: None,
+    image_mean: None,
+    image_std: None,
+    default_temperature: Some(0.0), // Greedy sampling for deterministic output
+    default_top_k: Some(50),
+    default_top_p: Some(0.9),
+    supports_kv_cache: true,
+    supports_flash_attention: false,
+    use_bf16: false,
+    default_steps: None,
+    default_guidance_scale: None,
+    time_shift: None,
+    est_memory_allocation_mb: 0,
+};
+
+/// GGUF chat model provider for local inference using Candle
+///
+/// Lightweight: weights are downloaded and loaded by the worker pool on
+/// first use.
+#[derive(Debug, Clone)]
+pub struct CandleGgufChatModel {
+    /// Engine for orchestration and stream conversion
+    engine: Arc<Engine>,
+    spec: &'static GgufChatSpec,
+    /// Request flash attention when the weights are loaded
+    flash_attn: bool,
+}
+
+impl CandleGgufChatModel {
+    /// Create a provider for `spec` (no downloads)
+    ///
+    /// # Errors
+    /// Returns error if engine creation fails
+    pub fn new(
+        spec: &'static GgufChatSpec,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let engine_config = EngineConfig::new(spec.info.name, "candle-gguf")
+            .with_streaming()
+            .with_max_tokens(spec.info.max_output_tokens.map_or(8192, NonZeroU32::get))
+            .with_temperature(0.0); // Greedy sampling for deterministic output
+
+        Ok(Self {
+            engine: Arc::new(Engine::new(engine_config)?),
+            spec,
+            flash_attn: crate::core::device_util::flash_attention_requested(),
+        })
+    }
+
+    /// Request flash attention instead of following `KODEGEN_FLASH_ATTN`
+    ///
+    /// Honoured only for Phi-3 weights on devices with flash-attn kernels;
+    /// otherwise the model loads with standard attention.
+    #[must_use]
+    pub fn with_flash_attention(mut self, enabled: bool) -> Self {
+        self.flash_attn = enabled;
+        self
+    }
+
+    /// The model this provider loads
+    pub fn spec(&self) -> &'static GgufChatSpec {
+        self.spec
+    }
+
+    /// Built-in prompt markup for the model, if it has a preset
+    pub fn chat_template(&self) -> Option<ChatTemplateFamily> {
+        self.spec.template
+    }
+}
+
+impl CandleModel for CandleGgufChatModel {
+    #[inline]
+    fn info(&self) -> &'static CandleModelInfo {
+        &self.spec.info
+    }
+}
+
+/// Weights of a loaded GGUF model
+enum GgufWeights {
+    Llama(quantized_llama::ModelWeights),
+    Phi3(quantized_phi3::ModelWeights),
+}
+
+impl GgufWeights {
+    /// Logits for the token after `input`, which starts at `index_pos`
+    ///
+    /// Position 0 resets the KV cache.
+    fn forward(&mut self, input: &Tensor, index_pos: usize) -> candle_core::Result<Tensor> {
+        match self {
+            Self::Llama(model) => model.forward(input, index_pos),
+            Self::Phi3(model) => model.forward(input, index_pos),
+        }
+    }
+}
+
+/// Loaded GGUF chat model that keeps resources in memory for worker threads
+#[derive(Clone)]
+pub struct LoadedGgufChatModel {
+    model: Arc<tokio::sync::Mutex<GgufWeights>>,
+    /// Shared with other models using the same `tokenizer.json`
+    tokenizer: Arc<tokenizers::Tokenizer>,
+    device: Device,
+    engine: Arc<Engine>,
+    /// EOS from GGUF metadata and the template's end-of-turn token
+    stop_tokens: Vec<u32>,
+    /// Whether flash attention was resolved on at load time
+    flash_attn: bool,
+    spec: &'static GgufChatSpec,
+    /// Prompt format
+    template: ModelChatTemplate,
+    /// Response-format constraints built so far
+    constraints: ConstraintCache,
+}
+
+impl LoadedGgufChatModel {
+    /// Load model resources into memory (called once per worker)
+    pub async fn load(
+        base: &CandleGgufChatModel,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::load_with_adapters(base, &[]).await
+    }
+
+    /// Load model resources with LoRA adapters merged into the weights
+    ///
+    /// Adapters are applied in order; an empty slice loads the base model.
+    pub async fn load_with_adapters(
+        base: &CandleGgufChatModel,
+        adapters: &[LoraAdapter],
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let spec = base.spec;
+        let (repo, file) = spec
+            .checkpoint()
+            .ok_or_else(|| format!("{} has no GGUF checkpoint", spec.info.name))?;
+        log::info!("Loading {} from {}/{}", spec.info.name, repo, file);
+
+        let gguf_file_path = base.huggingface_file(repo, file).await?;
+        let tokenizer_path = base
+            .huggingface_file(spec.tokenizer_repo, "tokenizer.json")
+            .await?;
+
+        let device = crate::core::device_util::detect_best_device().unwrap_or_else(|e| {
+            log::warn!("Device detection failed: {}. Using CPU.", e);
+            Device::Cpu
+        });
+
+        let flash_attn = crate::core::device_util::resolve_flash_attention(
+            spec.info.name,
+            base.flash_attn,
+            spec.info.supports_flash_attention,
+            &device,
+        );
+
+        let mut file = std::fs::File::open(&gguf_file_path)
+            .map_err(|e| format!("Failed to open GGUF file: {}", e))?;
+        let content = gguf_file::Content::read(&mut file)
+            .map_err(|e| format!("Failed to read GGUF content: {}", e))?;
+        let eos_token_id = content
+            .metadata
+            .get("tokenizer.ggml.eos_token_id")
+            .and_then(|v| v.to_u32().ok());
+
+        let model = if adapters.is_empty() {
+            from_gguf(spec.architecture, content, &mut file, &device, flash_attn)
+        } else {
+            let mut weights = Vec::with_capacity(adapters.len());
+            for adapter in adapters {
+                let path = match &adapter.source {
+                    LoraSource::Local(path) => path.clone(),
+                    LoraSource::HuggingFace {
+                        repo,
+                        file: filename,
+                    } => {
+                        // Lands next to the weights, where LoraWeights::load reads it
+                        base.huggingface_file(repo, &lora::adapter_config_file(filename)).await?;
+                        base.huggingface_file(repo, filename).await?
+                    }
+                };
+                log::info!(
+                    "Loading LoRA adapter '{}' from {}",
+                    adapter.name,
+                    path.display()
+                );
+                weights.push(LoraWeights::load(adapter, &path)?);
+            }
+
+            let mut merged = lora::merge_into_gguf(&content, &mut file, &weights)?;
+            let merged_content = gguf_file::Content::read(&mut merged)
+                .map_err(|e| format!("Failed to read merged GGUF content: {}", e))?;
+            from_gguf(spec.architecture, merged_content, &mut merged, &device, flash_attn)
+        }
+        .map_err(|e| format!("Failed to create model: {}", e))?;
+
+        let tokenizer = crate::core::tokenizer::shared_tokenizer_cache()
+            .get_or_load(&tokenizer_path)
+            .map_err(|e| Box::from(e.to_string()) as Box<dyn std::error::Error + Send + Sync>)?;
+
+        let template = ModelChatTemplate::load(base, spec.tokenizer_repo, spec.template).await?;
+
+        let mut stop_tokens: Vec<u32> = eos_token_id.into_iter().collect();
+        stop_tokens.extend(tokenizer.token_to_id(template.end_of_turn()));
+        stop_tokens.dedup();
+        if stop_tokens.is_empty() {
+            return Err(format!("{} has no end-of-sequence token", spec.info.name).into());
+        }
+        log::info!("{} loaded, stop tokens {:?}", spec.info.name, stop_tokens);
+
+        Ok(Self {
+            model: Arc::new(tokio::sync::Mutex::new(model)),
+            tokenizer,
+            device,
+            engine: Arc::clone(&base.engine),
+            stop_tokens,
+            flash_attn,
+            spec,
+            template,
+            constraints: ConstraintCache::new(),
+        })
+    }
+}
+
+fn from_gguf<R: std::io::Read + std::io::Seek>(
+    architecture: GgufArchitecture,
+    content: gguf_file::Content,
+    reader: &mut R,
+    device: &Device,
+    flash_attn: bool,
+) -> candle_core::Result<GgufWeights> {
+    match architecture {
+        GgufArchitecture::Llama => {
+            quantized_llama::ModelWeights::from_gguf(content, reader, device)
+                .map(GgufWeights::Llama)
+        }
+        GgufArchitecture::Phi3 => {
+            quantized_phi3::ModelWeights::from_gguf(flash_attn, content, reader, device)
+                .map(GgufWeights::Phi3)
+        }
+    }
+}
+
+/// Limits and penalties of one request; temperature and top-k/p are in its
+/// `LogitsProcessor`
+struct GenerationSettings {
+    repeat_penalty: f32,
+    repeat_last_n: usize,
+    max_tokens: u64,
+    /// Schema the reply must match, starting with `assistant_prefix`
+    response_format: Option<ResponseFormat>,
+    assistant_prefix: Option<String>,
+    /// Text that ends the reply
+    stop: Vec<String>,
+    /// Stops the token loop when cancelled
+    cancellation: CancellationToken,
+}
+
+impl crate::capability::traits::TextToTextCapable for LoadedGgufChatModel {
+    fn prompt(
+        &self,
+        prompt: CandlePrompt,
+        params: &CandleCompletionParams,
+    ) -> Pin<Box<dyn Stream<Item = CandleCompletionChunk> + Send>> {
+        let loaded = self.clone();
+        let info = &self.spec.info;
+        let additional = |key: &str| {
+            params
+                .additional_params
+                .as_ref()
+                .and_then(|p| p.get(key))
+                .cloned()
+        };
+
+        let top_k = additional("top_k")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+        let top_p = additional("top_p")
+            .and_then(|v| v.as_f64())
+            .or(info.default_top_p);
+        // Fixed default keeps sampling reproducible; regenerated turns pass their own
+        let seed = additional("seed")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(299792458);
+        let settings = GenerationSettings {
+            repeat_penalty: additional("repeat_penalty")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(1.0) as f32,
+            repeat_last_n: additional("repeat_last_n")
+                .and_then(|v| v.as_u64())
+                .map_or(64, |v| v as usize),
+            max_tokens: params.max_tokens.map_or(1000, |n| n.get()),
+            response_format: params.response_format.clone(),
+            assistant_prefix: params.assistant_prefix.clone(),
+            stop: params.stop_sequences(),
+            cancellation: params.cancellation.clone(),
+        };
+
+        let sampling_check = params
+            .sampling_settings()
+            .validate(info.max_input_tokens.map(NonZeroU32::get));
+        if let Ok(warnings) = &sampling_check {
+            for warning in warnings {
+                log::warn!("Sampling parameters: {}", warning.message);
+            }
+        }
+        let format_check = params.validate_response_format();
+
+        let mut conversation = params.conversation(prompt.content);
+        // A model without function calling would only echo tool markup
+        if !info.supports_function_calling && !conversation.tools.is_empty() {
+            log::warn!("{} does not support function calling; tools not offered", info.name);
+            conversation.tools.clear();
+        }
+        let prompt_text = self.template.render(&conversation);
+        let assistant_prefix = params.assistant_prefix.clone();
+
+        let temperature = params.temperature;
+        let sampling = if temperature <= 0.0 {
+            Sampling::ArgMax
+        } else {
+            match (top_k, top_p) {
+                (None, None) => Sampling::All { temperature },
+                (Some(k), None) => Sampling::TopK { k, temperature },
+                (None, Some(p)) => Sampling::TopP { p, temperature },
+                (Some(k), Some(p)) => Sampling::TopKThenTopP { k, p, temperature },
+            }
+        };
+
+        let cancellation = params.cancellation.clone();
+        Box::pin(self.engine.coordinate_completion(cancellation, move || {
+            crate::async_stream::spawn_stream(move |tx| async move {
+                if let Err(e) = sampling_check {
+                    let _ = tx.send(CandleCompletionChunk::Error(format!(
+                        "Invalid sampling parameters: {}",
+                        e
+                    )));
+                    return;
+                }
+                if let Err(e) = format_check {
+                    let _ = tx.send(CandleCompletionChunk::Error(format!(
+                        "Invalid completion parameters: {}",
+                        e
+                    )));
+                    return;
+                }
+                let prompt_text = match prompt_text {
+                    Ok(text) => text,
+                    Err(e) => {
+                        let _ = tx.send(CandleCompletionChunk::Error(format!(
+                            "Failed to render chat template: {}",
+                            e
+                        )));
+                        return;
+                    }
+                };
+                let mut settings = settings;
+                // The pre-filled prefix is part of the reply, so it uses up reply budget
+                if let Some(prefix) = &assistant_prefix {
+                    let prefix_tokens = loaded
+                        .tokenizer
+                        .encode(prefix.as_str(), false)
+                        .map_or(0, |encoding| encoding.len() as u64);
+                    settings.max_tokens = settings.max_tokens.saturating_sub(prefix_tokens);
+                }
+                let logits_processor = LogitsProcessor::from_sampling(seed, sampling);
+                if let Err(e) = loaded
+                    .generate(&prompt_text, &settings, logits_processor, &tx)
+                    .await
+                {
+                    let _ = tx.send(CandleCompletionChunk::Error(e));
+                }
+            })
+        }))
+    }
+}
+
+impl LoadedGgufChatModel {
+    /// Run the prompt and stream the reply into `tx`
+    async fn generate(
+        &self,
+        prompt_text: &str,
+        settings: &GenerationSettings,
+        mut logits_processor: LogitsProcessor,
+        tx: &UnboundedSender<CandleCompletionChunk>,
+    ) -> Result<(), String> {
+        let tokens = self
+            .tokenizer
+            .encode(prompt_text, true)
+            .map_err(|e| format!("Failed to encode prompt: {}", e))?
+            .get_ids()
+            .to_vec();
+
+        // A response format masks tokens leaving its schema; the pre-filled
+        // prefix is the start of the document
+        let mut response_constraint = match &settings.response_format {
+            Some(format) => {
+                let constraint = self
+                    .constraints
+                    .get_or_build(format, &self.tokenizer)
+                    .map_err(|e| format!("Invalid response format: {}", e))?;
+                let state = constraint.new_state();
+                Some((constraint, state))
+            }
+            None => None,
+        };
+        if let (Some((constraint, state)), Some(prefix)) =
+            (&mut response_constraint, &settings.assistant_prefix)
+        {
+            let prefix_tokens = self
+                .tokenizer
+                .encode(prefix.as_str(), false)
+                .map_err(|e| format!("Failed to encode assistant prefix: {}", e))?;
+            for &token in prefix_tokens.get_ids() {
+                if !matches!(constraint.update(state, token), Ok(true)) {
+                    return Err("Assistant prefix does not match the response format".to_string());
+                }
+            }
+        }
+
+        let mut tos = TokenOutputStream::new(Arc::clone(&self.tokenizer))
+            .with_stop_sequences(settings.stop.clone());
+        let mut tool_parser = ToolCallParser::new();
+        let mut all_tokens = tokens.clone();
+        // A request cancelled while queued for the model never takes it
+        let mut model = tokio::select! {
+            model = self.model.lock() => model,
+            () = settings.cancellation.cancelled() => return Ok(()),
+        };
+
+        // The whole prompt runs from position 0, which resets the KV cache
+        let prompt_tokens = tokens.len();
+        let started = Instant::now();
+        let mut finish_reason = FinishReason::Length;
+        let mut input = tokens;
+        for generated in 0..settings.max_tokens {
+            // Stop promptly, releasing the model, once nobody wants the rest
+            if settings.cancellation.is_cancelled() || tx.is_closed() {
+                log::info!("Generation stopped after {} tokens", generated);
+                return Ok(());
+            }
+            let position = all_tokens.len() - input.len();
+            let allowed = response_constraint.as_ref().map(|(constraint, state)| {
+                move |token: u32| constraint.try_next(state, token).unwrap_or(false)
+            });
+            let next_token = self
+                .next_token(
+                    &mut model,
+                    &input,
+                    position,
+                    &all_tokens,
+                    settings,
+                    &mut logits_processor,
+                    allowed.as_ref().map(|f| f as &dyn Fn(u32) -> bool),
+                )
+                .map_err(|e| format!("Generation failed: {}", e))?;
+            if self.stop_tokens.contains(&next_token) {
+                finish_reason = FinishReason::Stop;
+                break;
+            }
+            let mut document_done = false;
+            if let Some((constraint, state)) = &mut response_constraint {
+                document_done = !constraint
+                    .update(state, next_token)
+                    .map_err(|e| format!("Response format violated: {}", e))?
+                    || constraint.is_done(state);
+            }
+            all_tokens.push(next_token);
+            input = vec![next_token];
+
+            if let Some(text) = tos.next_token(next_token).ok().flatten() {
+                send_text(tx, &mut tool_parser, text);
+            }
+            if document_done || tos.stopped() {
+                finish_reason = FinishReason::Stop;
+                break;
+            }
+        }
+
+        if let Ok(Some(text)) = tos.decode_rest()
+            && !text.is_empty()
+        {
+            send_text(tx, &mut tool_parser, text);
+        }
+        let generated = all_tokens.len() - prompt_tokens;
+        let _ = tx.send(complete_chunk(
+            prompt_tokens,
+            generated,
+            started,
+            finish_reason,
+            self.flash_attn,
+        ));
+        Ok(())
+    }
+
+    /// Forward `input` at `position` and sample the following token, out of
+    /// the `allowed` ones if given
+    #[allow(clippy::too_many_arguments)]
+    fn next_token(
+        &self,
+        model: &mut GgufWeights,
+        input: &[u32],
+        position: usize,
+        all_tokens: &[u32],
+        settings: &GenerationSettings,
+        logits_processor: &mut LogitsProcessor,
+        allowed: Option<&dyn Fn(u32) -> bool>,
+    ) -> candle_core::Result<u32> {
+        let input = Tensor::new(input, &self.device)?.unsqueeze(0)?;
+        let mut logits = model.forward(&input, position)?.squeeze(0)?;
+        if settings.repeat_penalty != 1.0 {
+            let start_at = all_tokens.len().saturating_sub(settings.repeat_last_n);
+            logits = candle_transformers::utils::apply_repeat_penalty(
+                &logits,
+                settings.repeat_penalty,
+                &all_tokens[start_at..],
+            )?;
+        }
+        if let Some(allowed) = allowed {
+            logits = mask_logits(&logits, allowed)?;
+        }
+        logits_processor.sample(&logits)
+    }
+}
+
+/// Send decoded text, as a tool call once one is complete
+fn send_text(
+    tx: &UnboundedSender<CandleCompletionChunk>,
+    tool_parser: &mut ToolCallParser,
+    text: String,
+) {
+    let chunk = match tool_parser.process_token(&text) {
+        Some(tool_call) => {
+            log::info!("🔧 Tool call detected: {}", tool_call.name);
+            CandleCompletionChunk::ToolCallComplete {
+                id: Uuid::new_v4().to_string(),
+                name: tool_call.name,
+                input: tool_call.arguments,
+            }
+        }
+        None => CandleCompletionChunk::Text(text),
+    };
+    let _ = tx.send(chunk);
+}
+
+/// Final chunk of a reply: why it ended, token usage and timing
+pub(super) fn complete_chunk(
+    prompt_tokens: usize,
+    generated: usize,
+    started: Instant,
+    finish_reason: FinishReason,
+    flash_attention: bool,
+) -> CandleCompletionChunk {
+    let input_tokens = u32::try_from(prompt_tokens).unwrap_or(u32::MAX);
+    let output_tokens = u32::try_from(generated).unwrap_or(u32::MAX);
+    let elapsed_secs = started.elapsed().as_secs_f64();
+    CandleCompletionChunk::Complete {
+        text: String::new(),
+        finish_reason: Some(finish_reason),
+        usage: Some(CandleUsage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens.saturating_add(output_tokens),
+        }),
+        token_count: Some(output_tokens),
+        elapsed_secs: Some(elapsed_secs),
+        tokens_per_sec: (elapsed_secs > 0.0).then(|| f64::from(output_tokens) / elapsed_secs),
+        mean_logprob: None,
+        flash_attention: Some(flash_attention),
+    }
+}
+
+impl std::fmt::Debug for LoadedGgufChatModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadedGgufChatModel")
+            .field("name", &self.spec.info.name)
+            .field("device", &self.device)
+            .field("stop_tokens", &self.stop_tokens)
+            .field("flash_attn", &self.flash_attn)
+            .field("template", &self.template)
+            .finish()
+    }
+}
+
+impl CandleModel for LoadedGgufChatModel {
+    #[inline]
+    fn info(&self) -> &'static CandleModelInfo {
+        &self.spec.info
+    }
+}
@@ -2,7 +2,14 @@
 //!
 //! Models capable of generating text completions from text prompts.
 
+pub mod chat_template;
+pub mod gguf_chat;
+pub mod prefix_cache;
 pub mod qwen3_quantized;
+pub mod template_goldens;
 
 // Re-exports for convenience
-pub use qwen3_quantized::CandleQwen3QuantizedModel;
+pub use chat_template::{ChatConversation, ChatRole, ChatTemplateFamily, ChatTurn};
+pub use gguf_chat::{CandleGgufChatModel, GGUF_CHAT_MODELS, GgufArchitecture, GgufChatSpec};
+pub use prefix_cache::PrefixCache;
+pub use qwen3_quantized::{CandleQwen3QuantizedModel, LoadedModelHandle};
@@ -0,0 +1,173 @@
+//! Reuse of prompt prefixes across generations
+//!
+//! Chat prompts repeat themselves: every turn starts with the same system
+//! prompt, often followed by the same memory context, and only the tail
+//! changes. A [`PrefixCache`] keeps snapshots of the model state (its KV
+//! cache) after such shared prefixes, so a later prompt starting with one
+//! only runs its new suffix through the model.
+//!
+//! Checkpoints are placed where a prompt diverges from the one before it,
+//! which is where the stable part of a conversation ends. Snapshots are
+//! keyed by their exact tokens, so they are safe to share between sessions;
+//! the least recently used is evicted past `KODEGEN_PREFIX_CACHE_ENTRIES`
+//! (default 4, `0` disables the cache) or once the snapshots together cover
+//! more than `KODEGEN_PREFIX_CACHE_TOKENS` tokens (default 16384). A
+//! snapshot's KV cache grows with its prefix, so the token budget is what
+//! bounds their memory.
+
+use std::collections::VecDeque;
+
+/// Number of prefix snapshots kept per loaded model
+pub const PREFIX_CACHE_ENTRIES_ENV: &str = "KODEGEN_PREFIX_CACHE_ENTRIES";
+
+/// Default number of prefix snapshots
+pub const DEFAULT_PREFIX_CACHE_ENTRIES: usize = 4;
+
+/// Prefix tokens all snapshots of a loaded model may cover together
+pub const PREFIX_CACHE_TOKENS_ENV: &str = "KODEGEN_PREFIX_CACHE_TOKENS";
+
+/// Default token budget of the prefix snapshots
+pub const DEFAULT_PREFIX_CACHE_TOKENS: usize = 16_384;
+
+/// Shortest prefix worth a snapshot
+pub const MIN_PREFIX_TOKENS: usize = 32;
+
+/// Snapshots of model state `S` after prompt prefixes, most recent first
+#[derive(Debug, Clone)]
+pub struct PrefixCache<S> {
+    entries: VecDeque<(Vec<u32>, S)>,
+    capacity: usize,
+    /// Most prefix tokens held across all entries
+    max_tokens: usize,
+    /// Prefix tokens held across all entries
+    tokens: usize,
+    /// Tokens of the last prompt seen, to find where the next one diverges
+    last_prompt: Vec<u32>,
+}
+
+impl<S: Clone> PrefixCache<S> {
+    /// Cache holding at most `capacity` snapshots, within the default token
+    /// budget
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            max_tokens: DEFAULT_PREFIX_CACHE_TOKENS,
+            tokens: 0,
+            last_prompt: Vec::new(),
+        }
+    }
+
+    /// Cache sized from [`PREFIX_CACHE_ENTRIES_ENV`] and
+    /// [`PREFIX_CACHE_TOKENS_ENV`]
+    pub fn from_env() -> Self {
+        let capacity = env_usize(PREFIX_CACHE_ENTRIES_ENV, DEFAULT_PREFIX_CACHE_ENTRIES);
+        let max_tokens = env_usize(PREFIX_CACHE_TOKENS_ENV, DEFAULT_PREFIX_CACHE_TOKENS);
+        Self::new(capacity).with_max_tokens(max_tokens)
+    }
+
+    /// Evict snapshots once together they cover more than `max_tokens`
+    /// prefix tokens
+    #[must_use]
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self.evict();
+        self
+    }
+
+    /// Number of snapshots held
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Prefix tokens covered by the snapshots held
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    /// Whether no snapshot is held
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The snapshot of the longest cached prefix of `tokens`, with its length
+    ///
+    /// At least one token is always left to run, since generation needs the
+    /// logits of the last prompt token.
+    pub fn lookup(&mut self, tokens: &[u32]) -> Option<(usize, S)> {
+        let index = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (prefix, _))| prefix.len() < tokens.len() && tokens.starts_with(prefix))
+            .max_by_key(|(_, (prefix, _))| prefix.len())
+            .map(|(index, _)| index)?;
+        let entry = self.entries.remove(index)?;
+        let found = (entry.0.len(), entry.1.clone());
+        self.entries.push_front(entry);
+        Some(found)
+    }
+
+    /// Where to snapshot while running `tokens` from position `reused`
+    ///
+    /// That is the end of the prefix shared with the previous prompt, when
+    /// it lies past `reused`, leaves a suffix to run and is long enough to
+    /// be worth keeping. Records `tokens` as the previous prompt.
+    pub fn checkpoint_at(&mut self, tokens: &[u32], reused: usize) -> Option<usize> {
+        let shared = common_prefix_len(&self.last_prompt, tokens);
+        self.last_prompt = tokens.to_vec();
+        (self.capacity > 0
+            && shared > reused
+            && shared < tokens.len()
+            && shared >= MIN_PREFIX_TOKENS)
+            .then_some(shared)
+    }
+
+    /// Keep `state`, the model after running `prefix`
+    ///
+    /// Prefixes longer than the whole token budget are not kept.
+    pub fn insert(&mut self, prefix: Vec<u32>, state: S) {
+        if self.capacity == 0 || prefix.len() > self.max_tokens {
+            return;
+        }
+        if let Some(index) = self.entries.iter().position(|(cached, _)| *cached == prefix) {
+            self.entries.remove(index);
+            self.tokens -= prefix.len();
+        }
+        self.tokens += prefix.len();
+        self.entries.push_front((prefix, state));
+        self.evict();
+    }
+
+    /// Drop every snapshot
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.tokens = 0;
+        self.last_prompt.clear();
+    }
+
+    /// Drop least recently used snapshots until within both limits
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity || self.tokens > self.max_tokens {
+            match self.entries.pop_back() {
+                Some((prefix, _)) => self.tokens -= prefix.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+fn env_usize(name: &str, default: usize) -> usize {
+    match std::env::var(name) {
+        Ok(value) => value.trim().parse().unwrap_or_else(|_| {
+            log::warn!("Invalid {name}={value:?}, using the default");
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+/// Number of leading tokens `a` and `b` have in common
+pub fn common_prefix_len(a: &[u32], b: &[u32]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
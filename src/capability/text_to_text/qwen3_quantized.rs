@@ -7,6 +7,7 @@
 use std::num::NonZeroU32;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::async_stream;
 use crate::core::generation::TokenOutputStream;
@@ -16,11 +17,18 @@ use candle_transformers::generation::{LogitsProcessor, Sampling};
 use candle_transformers::models::quantized_qwen3::ModelWeights as Qwen3Model;
 use tokio_stream::Stream;
 
+use super::chat_template::ChatTemplateFamily;
+use super::gguf_chat::complete_chunk;
+use super::prefix_cache::PrefixCache;
+use crate::capability::lora::{self, LoraAdapter, LoraSource, LoraWeights};
+use crate::capability::registry::QuantVariant;
 use crate::core::{Engine, EngineConfig};
 
-use crate::domain::completion::ToolCallParser;
-use crate::domain::completion::format_tools_for_qwen3;
+use crate::core::generation::constraints::mask_logits;
+use crate::domain::completion::{ConstraintCache, ToolCallParser};
+use crate::domain::chat::templates::ModelChatTemplate;
 use crate::domain::completion::{CandleCompletionChunk, CandleCompletionParams};
+use crate::domain::context::chunks::FinishReason;
 use crate::domain::model::{info::CandleModelInfo, traits::CandleModel};
 use crate::domain::prompt::CandlePrompt;
 use kodegen_simd::logits::constraints::GenerationConstraint;
@@ -39,6 +47,8 @@ pub trait BuilderCandleQwen3QuantizedModel: Send + Sync + 'static {
 pub struct CandleQwen3QuantizedModel {
     /// Engine for orchestration and stream conversion
     engine: Arc<Engine>,
+    /// GGUF quantization to load
+    variant: QuantVariant,
 }
 
 impl CandleQwen3QuantizedModel {
@@ -62,16 +72,64 @@ impl CandleQwen3QuantizedModel {
 
         let engine = Arc::new(Engine::new(engine_config)?);
 
-        Ok(Self { engine })
+        Ok(Self {
+            engine,
+            variant: QuantVariant::default(),
+        })
+    }
+
+    /// Use another GGUF quantization
+    ///
+    /// Each variant has its own registry key
+    /// (`unsloth/Qwen3-1.7B-GGUF:VARIANT`), weight file and memory estimate.
+    #[must_use]
+    pub fn with_variant(mut self, variant: QuantVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// GGUF quantization this model loads
+    pub fn variant(&self) -> QuantVariant {
+        self.variant
+    }
+
+    /// Model info for a quantization variant
+    pub fn variant_info(variant: QuantVariant) -> &'static CandleModelInfo {
+        match variant {
+            QuantVariant::Q4_K_M => &QWEN3_QUANTIZED_MODEL_INFO,
+            QuantVariant::Q5_K_M => &QWEN3_QUANTIZED_Q5_K_M_MODEL_INFO,
+            QuantVariant::Q8_0 => &QWEN3_QUANTIZED_Q8_0_MODEL_INFO,
+        }
     }
 }
 
 // Static model info for Qwen3 1.7B Quantized
-pub static QWEN3_QUANTIZED_MODEL_INFO: CandleModelInfo = CandleModelInfo {
+pub static QWEN3_QUANTIZED_MODEL_INFO: CandleModelInfo = QWEN3_QUANTIZED_BASE_INFO;
+
+// Q5_K_M variant: better quality for ~15% more memory
+pub static QWEN3_QUANTIZED_Q5_K_M_MODEL_INFO: CandleModelInfo = CandleModelInfo {
+    registry_key: "unsloth/Qwen3-1.7B-GGUF:Q5_K_M",
+    quantization: "Q5_K_M",
+    quantization_url: Some("unsloth/Qwen3-1.7B-GGUF/Qwen3-1.7B-Q5_K_M.gguf"),
+    est_memory_allocation_mb: 1700, // ~1.7GB for Q5_K_M quantized
+    ..QWEN3_QUANTIZED_BASE_INFO
+};
+
+// Q8_0 variant: near-lossless, largest footprint
+pub static QWEN3_QUANTIZED_Q8_0_MODEL_INFO: CandleModelInfo = CandleModelInfo {
+    registry_key: "unsloth/Qwen3-1.7B-GGUF:Q8_0",
+    quantization: "Q8_0",
+    quantization_url: Some("unsloth/Qwen3-1.7B-GGUF/Qwen3-1.7B-Q8_0.gguf"),
+    est_memory_allocation_mb: 2400, // ~2.4GB for Q8_0
+    ..QWEN3_QUANTIZED_BASE_INFO
+};
+
+// Default (Q4_K_M) model info shared by every variant
+const QWEN3_QUANTIZED_BASE_INFO: CandleModelInfo = CandleModelInfo {
     provider: crate::domain::model::CandleProvider::Unsloth,
     name: "qwen3-1.7b-quantized",
     registry_key: "Qwen/Qwen2.5-Coder-3B-Instruct-GGUF",
-    quantization_url: None,
+    quantization_url: Some("unsloth/Qwen3-1.7B-GGUF/Qwen3-1.7B-Q4_K_M.gguf"),
     max_input_tokens: NonZeroU32::new(32768), // 32K context window
     max_output_tokens: NonZeroU32::new(8192),
     input_price: None,
@@ -109,7 +167,7 @@ pub static QWEN3_QUANTIZED_MODEL_INFO: CandleModelInfo = CandleModelInfo {
 impl CandleModel for CandleQwen3QuantizedModel {
     #[inline]
     fn info(&self) -> &'static CandleModelInfo {
-        &QWEN3_QUANTIZED_MODEL_INFO
+        Self::variant_info(self.variant)
     }
 }
 
@@ -117,16 +175,35 @@ impl CandleModel for CandleQwen3QuantizedModel {
 ///
 /// This model pre-loads the actual model into memory with safe async mutable access,
 /// avoiding disk I/O on every request.
+/// Prompts starting with a prefix seen before resume from a snapshot of the
+/// model after it instead of re-running it (see [`PrefixCache`]).
+///
+/// Concurrent requests take turns on the model lock; they are not batched.
+/// Candle's quantized Qwen3 builds its causal mask for a single row and keeps
+/// one KV cache per model, so a padded `[batch, len]` forward pass needs a
+/// model implementation of our own first.
 #[derive(Clone)]
 pub struct LoadedQwen3QuantizedModel {
     /// The loaded Qwen3 model using Candle's native quantized implementation
     /// Wrapped in Arc<Mutex> for safe sharing in async context
     model: Arc<tokio::sync::Mutex<Qwen3Model>>,
-    tokenizer: tokenizers::Tokenizer,
+    /// Model snapshots after shared prompt prefixes, taken and restored
+    /// while `model` is locked
+    prefixes: Arc<parking_lot::Mutex<PrefixCache<Qwen3Model>>>,
+    /// Shared with other models using the same `tokenizer.json`
+    tokenizer: Arc<tokenizers::Tokenizer>,
     device: Device,
     engine: Arc<Engine>,
     /// EOS token ID extracted from GGUF metadata
     eos_token_id: Option<u32>,
+    /// Whether flash attention was resolved on at load time
+    flash_attention: bool,
+    /// GGUF quantization that was loaded
+    variant: QuantVariant,
+    /// Prompt format
+    template: ModelChatTemplate,
+    /// Response-format constraints built so far
+    constraints: ConstraintCache,
 }
 
 impl LoadedQwen3QuantizedModel {
@@ -136,15 +213,46 @@ impl LoadedQwen3QuantizedModel {
     /// The model stays in memory for all subsequent requests.
     pub async fn load(
         base: &CandleQwen3QuantizedModel,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::load_with_adapters(base, &[]).await
+    }
+
+    /// Load model resources with LoRA adapters merged into the weights
+    ///
+    /// Adapters are applied in order; an empty slice loads the base model.
+    pub async fn load_with_adapters(
+        base: &CandleQwen3QuantizedModel,
+        adapters: &[LoraAdapter],
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         log::info!("Loading Qwen3 model using Candle's native quantized implementation");
 
+        // Resolve checkpoint (tiny mode swaps in a smaller Qwen3-architecture GGUF)
+        let checkpoint = crate::capability::tiny::GgufCheckpoint::qwen3_for(base.variant);
+        log::info!("Using checkpoint {}/{}", checkpoint.repo, checkpoint.file);
+
         // Download files using huggingface_file()
-        let gguf_file_path = base
-            .huggingface_file("unsloth/Qwen3-1.7B-GGUF", "Qwen3-1.7B-Q4_K_M.gguf")
-            .await?;
+        let gguf_file_path = match &checkpoint.generated {
+            Some(path) => {
+                let target = path.clone();
+                tokio::task::spawn_blocking(move || {
+                    crate::capability::tiny::write_tiny_qwen3_gguf(&target)
+                })
+                .await?
+                .map_err(|e| format!("Failed to write tiny checkpoint: {}", e))?;
+                path.clone()
+            }
+            None => base
+                .huggingface_file(&checkpoint.repo, &checkpoint.file)
+                .await
+                .map_err(|e| {
+                    Box::from(format!(
+                        "{} quantization unavailable: {}/{} could not be fetched: {}",
+                        base.variant, checkpoint.repo, checkpoint.file, e
+                    )) as Box<dyn std::error::Error + Send + Sync>
+                })?,
+        };
         let tokenizer_path = base
-            .huggingface_file("Qwen/Qwen3-1.7B", "tokenizer.json")
+            .huggingface_file(&checkpoint.tokenizer_repo, "tokenizer.json")
             .await?;
 
         if !tokenizer_path.exists() {
@@ -159,6 +267,13 @@ impl LoadedQwen3QuantizedModel {
             log::warn!("Device detection failed: {}. Using CPU.", e);
             Device::Cpu
         });
+        // quantized_qwen3 has no flash-attn kernels; a request is only logged
+        let flash_attention = crate::core::device_util::resolve_flash_attention(
+            "qwen3-quantized",
+            crate::core::device_util::flash_attention_requested(),
+            QWEN3_QUANTIZED_BASE_INFO.supports_flash_attention,
+            &device,
+        );
 
         // Load GGUF file - simple and direct (no spawn_blocking)
         log::info!("Loading model from {}", gguf_file_path.display());
@@ -181,28 +296,64 @@ impl LoadedQwen3QuantizedModel {
         log::info!("EOS token ID from GGUF: {:?}", eos_token_id);
 
         // Create model using Candle's native implementation - simple and fast!
-        let model = Qwen3Model::from_gguf(content, &mut file, &device).map_err(|e| {
+        let model = if adapters.is_empty() {
+            Qwen3Model::from_gguf(content, &mut file, &device)
+        } else {
+            let mut weights = Vec::with_capacity(adapters.len());
+            for adapter in adapters {
+                let path = match &adapter.source {
+                    LoraSource::Local(path) => path.clone(),
+                    LoraSource::HuggingFace { repo, file: filename } => {
+                        // Lands next to the weights, where LoraWeights::load reads it
+                        base.huggingface_file(repo, &lora::adapter_config_file(filename)).await?;
+                        base.huggingface_file(repo, filename).await?
+                    }
+                };
+                log::info!("Loading LoRA adapter '{}' from {}", adapter.name, path.display());
+                weights.push(LoraWeights::load(adapter, &path)?);
+            }
+
+            let mut merged = lora::merge_into_gguf(&content, &mut file, &weights)?;
+            let merged_content = gguf_file::Content::read(&mut merged).map_err(|e| {
+                Box::from(format!("Failed to read merged GGUF content: {}", e))
+                    as Box<dyn std::error::Error + Send + Sync>
+            })?;
+            Qwen3Model::from_gguf(merged_content, &mut merged, &device)
+        }
+        .map_err(|e| {
             Box::from(format!("Failed to create model: {}", e))
                 as Box<dyn std::error::Error + Send + Sync>
         })?;
 
         log::info!("Model loaded successfully");
 
-        // Load tokenizer - direct synchronous loading (no spawn_blocking)
+        // Load tokenizer through the shared cache, so a draft model of the
+        // same family reuses this one
         log::info!("Loading tokenizer from {}", tokenizer_path.display());
-        let tokenizer = tokenizers::Tokenizer::from_file(&tokenizer_path).map_err(|e| {
-            Box::from(format!("Failed to load tokenizer: {}", e))
-                as Box<dyn std::error::Error + Send + Sync>
-        })?;
+        let tokenizer = crate::core::tokenizer::shared_tokenizer_cache()
+            .get_or_load(&tokenizer_path)
+            .map_err(|e| Box::from(e.to_string()) as Box<dyn std::error::Error + Send + Sync>)?;
 
         log::info!("Tokenizer loaded successfully");
 
+        let template = ModelChatTemplate::load(
+            base,
+            &checkpoint.tokenizer_repo,
+            Some(ChatTemplateFamily::ChatMl),
+        )
+        .await?;
+
         Ok(Self {
             model: Arc::new(tokio::sync::Mutex::new(model)),
+            prefixes: Arc::new(parking_lot::Mutex::new(PrefixCache::from_env())),
             tokenizer,
             device,
             engine: Arc::clone(&base.engine),
             eos_token_id,
+            flash_attention,
+            variant: base.variant,
+            template,
+            constraints: ConstraintCache::new(),
         })
     }
 
@@ -222,7 +373,7 @@ impl LoadedQwen3QuantizedModel {
     ///
     /// # Arguments
     /// * `prompt` - The prompt to generate from
-    /// * `type_constraint` - Schema constraint created via `constraint_for_type()`
+    /// * `type_constraint` - Constraint built with [`crate::constraints`]
     ///
     /// # Returns
     /// * `Ok(String)` - Generated text guaranteed to match schema
@@ -253,6 +404,7 @@ impl LoadedQwen3QuantizedModel {
             let input_ids = Tensor::new(&all_tokens[..], &self.device)?;
             let logits = {
                 let mut model = self.model.lock().await;
+                model.clear_kv_cache();
                 model.forward(&input_ids.unsqueeze(0)?, 0)?
             };
 
@@ -365,9 +517,15 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
         // Clone pre-loaded resources for the generation closure
         let engine = self.engine.clone();
         let model = self.model.clone(); // ✅ Use CACHED model
+        let prefixes = Arc::clone(&self.prefixes);
         let device = self.device.clone();
-        let tokenizer = self.tokenizer.clone(); // ✅ Clone pre-loaded tokenizer
+        let tokenizer = Arc::clone(&self.tokenizer); // ✅ Share pre-loaded tokenizer
         let eos_token_id = self.eos_token_id.unwrap_or(151645);
+        let flash_attention = self.flash_attention;
+        let constraints = self.constraints.clone();
+        let response_format = params.response_format.clone();
+        let stop = params.stop_sequences();
+        let cancellation = params.cancellation.clone();
 
         log::info!("🚀 Using CACHED model from memory - no loading needed!");
 
@@ -404,42 +562,66 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
             .map(|v| v as usize)
             .unwrap_or(64);
 
-        // Format prompt using Qwen3 chat template with optional tool support
-        let prompt_text = if let Some(ref tools) = params.tools {
-            // Convert ZeroOneOrMany to Vec using Into trait
-            let tools_vec: Vec<_> = tools.clone().into();
-
-            if tools_vec.is_empty() {
-                // No tools available - standard user prompt
-                format!(
-                    "<|im_start|>user\n{}<|im_end|>\n<|im_start|>assistant\n",
-                    prompt.content
-                )
-            } else {
-                // Tools available - add system message with tool definitions
-                let tool_defs = format_tools_for_qwen3(&tools_vec);
-
-                log::debug!("Generated prompt with {} tool(s)", tools_vec.len());
-
-                format!(
-                    "<|im_start|>system\nYou are a helpful AI assistant with access to tools. When you need to use a tool, output <tool_call>{{\"name\": \"tool_name\", \"arguments\": {{...}}}}</tool_call>\n\n{}<|im_end|>\n<|im_start|>user\n{}<|im_end|>\n<|im_start|>assistant\n",
-                    tool_defs, prompt.content
-                )
+        // Fixed default keeps sampling reproducible; regenerated turns pass their own
+        let seed = params
+            .additional_params
+            .as_ref()
+            .and_then(|p| p.get("seed"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(299792458);
+
+        // Catch nonsensical sampling combinations before they reach the sampler
+        let sampling_check = params
+            .sampling_settings()
+            .validate(QWEN3_QUANTIZED_MODEL_INFO.max_input_tokens.map(NonZeroU32::get));
+        if let Ok(warnings) = &sampling_check {
+            for warning in warnings {
+                log::warn!("Sampling parameters: {}", warning.message);
             }
-        } else {
-            // No tools parameter provided - standard user prompt
-            format!(
-                "<|im_start|>user\n{}<|im_end|>\n<|im_start|>assistant\n",
-                prompt.content
-            )
-        };
+        }
+        let format_check = params.validate_response_format();
+
+        // Format prompt through the model's chat template with optional tool
+        // support; a pre-filled reply start goes right after the assistant header
+        let conversation = params.conversation(prompt.content);
+        if !conversation.tools.is_empty() {
+            log::debug!("Generated prompt with {} tool(s)", conversation.tools.len());
+        }
+        let prompt_text = self.template.render(&conversation);
+        let assistant_prefix = params.assistant_prefix.clone();
         let max_tokens = params.max_tokens.map(|n| n.get()).unwrap_or(1000);
 
         // Use Engine's coordinate_completion for automatic metrics and stream conversion
-        Box::pin(engine.coordinate_completion(move || {
+        Box::pin(engine.coordinate_completion(cancellation.clone(), move || {
             async_stream::spawn_stream(move |tx| async move {
                 log::info!("✅ Using cached model from memory - no disk I/O!");
 
+                if let Err(e) = sampling_check {
+                    let _ = tx.send(CandleCompletionChunk::Error(format!(
+                        "Invalid sampling parameters: {}",
+                        e
+                    )));
+                    return;
+                }
+                if let Err(e) = format_check {
+                    let _ = tx.send(CandleCompletionChunk::Error(format!(
+                        "Invalid completion parameters: {}",
+                        e
+                    )));
+                    return;
+                }
+
+                let prompt_text = match prompt_text {
+                    Ok(text) => text,
+                    Err(e) => {
+                        let _ = tx.send(CandleCompletionChunk::Error(format!(
+                            "Failed to render chat template: {}",
+                            e
+                        )));
+                        return;
+                    }
+                };
+
                 // Encode the prompt
                 let tokens = match tokenizer.encode(prompt_text.as_str(), true) {
                     Ok(encoding) => encoding.get_ids().to_vec(),
@@ -452,8 +634,55 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                     }
                 };
 
+                // The pre-filled prefix is part of the reply, so it uses up reply budget
+                let max_tokens = match &assistant_prefix {
+                    Some(prefix) => {
+                        let prefix_tokens = tokenizer
+                            .encode(prefix.as_str(), false)
+                            .map(|encoding| encoding.len() as u64)
+                            .unwrap_or(0);
+                        max_tokens.saturating_sub(prefix_tokens)
+                    }
+                    None => max_tokens,
+                };
+
+                // A response format masks tokens leaving its schema; the
+                // pre-filled prefix is the start of the document
+                let mut response_constraint = match &response_format {
+                    Some(format) => match constraints.get_or_build(format, &tokenizer) {
+                        Ok(constraint) => {
+                            let state = constraint.new_state();
+                            Some((constraint, state))
+                        }
+                        Err(e) => {
+                            let _ = tx.send(CandleCompletionChunk::Error(format!(
+                                "Invalid response format: {}",
+                                e
+                            )));
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                if let (Some((constraint, state)), Some(prefix)) =
+                    (&mut response_constraint, &assistant_prefix)
+                {
+                    let prefix_tokens = tokenizer
+                        .encode(prefix.as_str(), false)
+                        .map(|encoding| encoding.get_ids().to_vec())
+                        .unwrap_or_default();
+                    for token in prefix_tokens {
+                        if !matches!(constraint.update(state, token), Ok(true)) {
+                            let _ = tx.send(CandleCompletionChunk::Error(
+                                "Assistant prefix does not match the response format".to_string(),
+                            ));
+                            return;
+                        }
+                    }
+                }
+                let mut constraint_done = false;
+
                 // Create LogitsProcessor for sampling
-                let seed = 299792458;
                 let mut logits_processor = {
                     let sampling = if temperature <= 0.0 {
                         Sampling::ArgMax
@@ -469,7 +698,7 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                 };
 
                 // Create TokenOutputStream for efficient decoding
-                let mut tos = TokenOutputStream::new(tokenizer.clone());
+                let mut tos = TokenOutputStream::new(tokenizer).with_stop_sequences(stop);
 
                 // Create tool call parser for detecting function calls in output
                 let mut tool_parser = ToolCallParser::new();
@@ -478,11 +707,63 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                 let mut all_tokens = Vec::with_capacity(tokens.len() + max_tokens as usize);
                 all_tokens.extend_from_slice(&tokens);
 
-                // Lock the model for generation
-                let mut model = model.lock().await;
+                // Lock the model for generation; a request cancelled while
+                // queued for it never takes it
+                let mut model = tokio::select! {
+                    model = model.lock() => model,
+                    () = cancellation.cancelled() => return,
+                };
 
-                // Initial forward pass
-                let input = match Tensor::new(&tokens[..], &device) {
+                // Resume from the longest cached prefix of the prompt, or
+                // start from an empty KV cache
+                let (mut prefilled, checkpoint) = {
+                    let mut prefixes = prefixes.lock();
+                    let reused = match prefixes.lookup(&tokens) {
+                        Some((len, snapshot)) => {
+                            *model = snapshot;
+                            len
+                        }
+                        None => {
+                            model.clear_kv_cache();
+                            0
+                        }
+                    };
+                    (reused, prefixes.checkpoint_at(&tokens, reused))
+                };
+                if prefilled > 0 {
+                    log::debug!("Reusing {} cached prompt tokens of {}", prefilled, tokens.len());
+                }
+                let started = Instant::now();
+
+                // Run the prefix shared with the previous prompt first and
+                // keep a snapshot, so the next prompt starting with it can
+                // skip it
+                if let Some(end) = checkpoint {
+                    let input = match Tensor::new(&tokens[prefilled..end], &device)
+                        .and_then(|t| t.unsqueeze(0))
+                    {
+                        Ok(t) => t,
+                        Err(e) => {
+                            let _ = tx.send(CandleCompletionChunk::Error(format!(
+                                "Failed to create input tensor: {}",
+                                e
+                            )));
+                            return;
+                        }
+                    };
+                    if let Err(e) = model.forward(&input, prefilled) {
+                        let _ = tx.send(CandleCompletionChunk::Error(format!(
+                            "Forward pass failed: {}",
+                            e
+                        )));
+                        return;
+                    }
+                    prefixes.lock().insert(tokens[..end].to_vec(), model.clone());
+                    prefilled = end;
+                }
+
+                // Initial forward pass over the rest of the prompt
+                let input = match Tensor::new(&tokens[prefilled..], &device) {
                     Ok(t) => match t.unsqueeze(0) {
                         Ok(t) => t,
                         Err(e) => {
@@ -502,7 +783,7 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                     }
                 };
 
-                let logits = match model.forward(&input, 0) {
+                let logits = match model.forward(&input, prefilled) {
                     Ok(l) => l,
                     Err(e) => {
                         let _ = tx.send(CandleCompletionChunk::Error(format!(
@@ -561,6 +842,22 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                     logits // Skip expensive operation when not needed
                 };
 
+                let logits = match &response_constraint {
+                    Some((constraint, state)) => match mask_logits(&logits, |token| {
+                        constraint.try_next(state, token).unwrap_or(false)
+                    }) {
+                        Ok(l) => l,
+                        Err(e) => {
+                            let _ = tx.send(CandleCompletionChunk::Error(format!(
+                                "Response format masking failed: {}",
+                                e
+                            )));
+                            return;
+                        }
+                    },
+                    None => logits,
+                };
+
                 let mut next_token = match logits_processor.sample(&logits) {
                     Ok(t) => t,
                     Err(e) => {
@@ -572,6 +869,21 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                     }
                 };
 
+                if let Some((constraint, state)) = &mut response_constraint
+                    && next_token != eos_token_id
+                {
+                    match constraint.update(state, next_token) {
+                        Ok(more) => constraint_done = !more || constraint.is_done(state),
+                        Err(e) => {
+                            let _ = tx.send(CandleCompletionChunk::Error(format!(
+                                "Response format violated: {}",
+                                e
+                            )));
+                            return;
+                        }
+                    }
+                }
+
                 all_tokens.push(next_token);
 
                 // Send first token (check for tool calls)
@@ -593,10 +905,17 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
 
                 // Continue generation
                 for index in 0..max_tokens {
-                    if next_token == eos_token_id {
+                    // A complete document or a stop sequence ends the reply
+                    if next_token == eos_token_id || constraint_done || tos.stopped() {
                         break;
                     }
 
+                    // Stop promptly, releasing the model, once nobody wants the rest
+                    if cancellation.is_cancelled() || tx.is_closed() {
+                        log::info!("Generation stopped after {} tokens", index + 1);
+                        return;
+                    }
+
                     let input = match Tensor::new(&[next_token], &device) {
                         Ok(t) => match t.unsqueeze(0) {
                             Ok(t) => t,
@@ -676,6 +995,22 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                         logits // Skip expensive operation when not needed
                     };
 
+                    let logits = match &response_constraint {
+                        Some((constraint, state)) => match mask_logits(&logits, |token| {
+                            constraint.try_next(state, token).unwrap_or(false)
+                        }) {
+                            Ok(l) => l,
+                            Err(e) => {
+                                let _ = tx.send(CandleCompletionChunk::Error(format!(
+                                    "Response format masking failed: {}",
+                                    e
+                                )));
+                                return;
+                            }
+                        },
+                        None => logits,
+                    };
+
                     next_token = match logits_processor.sample(&logits) {
                         Ok(t) => t,
                         Err(e) => {
@@ -687,6 +1022,21 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                         }
                     };
 
+                    if let Some((constraint, state)) = &mut response_constraint
+                        && next_token != eos_token_id
+                    {
+                        match constraint.update(state, next_token) {
+                            Ok(more) => constraint_done = !more || constraint.is_done(state),
+                            Err(e) => {
+                                let _ = tx.send(CandleCompletionChunk::Error(format!(
+                                    "Response format violated: {}",
+                                    e
+                                )));
+                                return;
+                            }
+                        }
+                    }
+
                     all_tokens.push(next_token);
 
                     // Send token through stream (check for tool calls)
@@ -725,6 +1075,21 @@ impl crate::capability::traits::TextToTextCapable for LoadedQwen3QuantizedModel
                         let _ = tx.send(CandleCompletionChunk::Text(text));
                     }
                 }
+
+                // Whatever ended the loop other than the token budget is a natural stop
+                let finish_reason = if next_token == eos_token_id || constraint_done || tos.stopped() {
+                    FinishReason::Stop
+                } else {
+                    FinishReason::Length
+                };
+                let generated = all_tokens.len() - tokens.len();
+                let _ = tx.send(complete_chunk(
+                    tokens.len(),
+                    generated,
+                    started,
+                    finish_reason,
+                    flash_attention,
+                ));
             })
         }))
     }
@@ -736,6 +1101,9 @@ impl std::fmt::Debug for LoadedQwen3QuantizedModel {
             .field("device", &self.device)
             .field("model", &"Arc<Mutex<Qwen3Model>>")
             .field("eos_token_id", &self.eos_token_id)
+            .field("flash_attention", &self.flash_attention)
+            .field("variant", &self.variant)
+            .field("template", &self.template)
             .finish()
     }
 }
@@ -743,7 +1111,71 @@ impl std::fmt::Debug for LoadedQwen3QuantizedModel {
 impl CandleModel for LoadedQwen3QuantizedModel {
     #[inline]
     fn info(&self) -> &'static CandleModelInfo {
-        &QWEN3_QUANTIZED_MODEL_INFO
+        CandleQwen3QuantizedModel::variant_info(self.variant)
+    }
+}
+
+/// Load slot of a [`LoadedModelHandle`]
+type ModelCell = Arc<tokio::sync::OnceCell<Arc<LoadedQwen3QuantizedModel>>>;
+
+/// Shared, lazily loaded [`LoadedQwen3QuantizedModel`]
+///
+/// Clones share one instance: the GGUF is loaded on first use and every later
+/// caller (e.g. tool selection on each turn) reuses it instead of reloading.
+/// [`unload`](Self::unload) drops it for every clone at once.
+#[derive(Clone, Default)]
+pub struct LoadedModelHandle {
+    cell: Arc<parking_lot::RwLock<ModelCell>>,
+}
+
+impl LoadedModelHandle {
+    /// Create an empty handle; the model loads on first [`get_or_load`](Self::get_or_load)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wrap an already loaded model
+    pub fn from_loaded(model: Arc<LoadedQwen3QuantizedModel>) -> Self {
+        Self {
+            cell: Arc::new(parking_lot::RwLock::new(Arc::new(
+                tokio::sync::OnceCell::new_with(Some(model)),
+            ))),
+        }
+    }
+
+    /// Whether the model has been loaded
+    pub fn is_loaded(&self) -> bool {
+        self.cell.read().initialized()
+    }
+
+    /// Get the shared model, loading it from `base` on first use
+    ///
+    /// Concurrent callers wait for a single load; a failed load is not cached.
+    pub async fn get_or_load(
+        &self,
+        base: &CandleQwen3QuantizedModel,
+    ) -> Result<Arc<LoadedQwen3QuantizedModel>, Box<dyn std::error::Error + Send + Sync>> {
+        let cell = Arc::clone(&self.cell.read());
+        cell.get_or_try_init(|| async { LoadedQwen3QuantizedModel::load(base).await.map(Arc::new) })
+            .await
+            .cloned()
+    }
+
+    /// Drop the loaded model for every clone of this handle
+    ///
+    /// Its memory is freed once callers still using it are done; the next
+    /// [`get_or_load`](Self::get_or_load) loads it again. Returns whether a
+    /// model was loaded.
+    pub fn unload(&self) -> bool {
+        std::mem::take(&mut *self.cell.write()).initialized()
+    }
+}
+
+impl std::fmt::Debug for LoadedModelHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadedModelHandle")
+            .field("loaded", &self.is_loaded())
+            .finish()
     }
 }
 
@@ -0,0 +1,181 @@
+//! Golden rendering tests for chat templates
+//!
+//! [`canonical_conversations`] is a fixed set of conversations — system plus
+//! user, multi-turn, tools, thinking disabled — that every template family is
+//! rendered against. The renders are checked into
+//! `tests/goldens/chat_templates/<family>/<case>.txt`; [`check_goldens`]
+//! reports every render that no longer matches, and [`write_goldens`]
+//! regenerates them after an intentional template change:
+//!
+//! ```text
+//! kodegen-candle-agent templates goldens tests/goldens/chat_templates
+//! ```
+//!
+//! Review the diff of the regenerated files like any other code change.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use super::chat_template::{ChatConversation, ChatTemplateFamily, ChatTurn};
+use crate::capability::registry::chat_template_families;
+use crate::domain::completion::types::ToolInfo;
+
+/// Golden directory, relative to the crate root
+pub const GOLDEN_DIR: &str = "tests/goldens/chat_templates";
+
+/// A render that differs from its golden file
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenMismatch {
+    pub path: PathBuf,
+    /// Golden contents; `None` when the file is missing
+    pub expected: Option<String>,
+    pub actual: String,
+}
+
+impl fmt::Display for GoldenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(expected) = &self.expected else {
+            return write!(f, "{}: golden file missing", self.path.display());
+        };
+        let mut expected_lines = expected.split('\n');
+        let mut actual_lines = self.actual.split('\n');
+        for line in 1.. {
+            match (expected_lines.next(), actual_lines.next()) {
+                (None, None) => break,
+                (e, a) if e == a => {}
+                (e, a) => {
+                    return write!(
+                        f,
+                        "{}:{line}: expected {:?}, rendered {:?}",
+                        self.path.display(),
+                        e.unwrap_or("<end>"),
+                        a.unwrap_or("<end>")
+                    );
+                }
+            }
+        }
+        write!(f, "{}: renders differ", self.path.display())
+    }
+}
+
+fn weather_tool() -> ToolInfo {
+    let schema = json!({
+        "properties": {
+            "city": {"description": "City name", "type": "string"}
+        },
+        "required": ["city"],
+        "type": "object"
+    });
+    ToolInfo {
+        name: "get_weather".into(),
+        title: None,
+        description: Some("Current weather for a city".into()),
+        input_schema: std::sync::Arc::new(schema.as_object().cloned().unwrap_or_default()),
+        output_schema: None,
+        annotations: None,
+        icons: None,
+        meta: None,
+    }
+}
+
+/// The conversations every template is rendered against, by case name
+pub fn canonical_conversations() -> Vec<(&'static str, ChatConversation)> {
+    vec![
+        (
+            "system_user",
+            ChatConversation::new(vec![
+                ChatTurn::system("You are a helpful assistant."),
+                ChatTurn::user("What is the capital of France?"),
+            ]),
+        ),
+        (
+            "multi_turn",
+            ChatConversation::new(vec![
+                ChatTurn::system("You are a helpful assistant."),
+                ChatTurn::user("Name a prime number."),
+                ChatTurn::assistant("7"),
+                ChatTurn::user("And one larger than 100?"),
+            ]),
+        ),
+        (
+            "tools",
+            ChatConversation::new(vec![
+                ChatTurn::user("What's the weather in Paris?"),
+                ChatTurn::assistant(
+                    "<tool_call>{\"name\": \"get_weather\", \"arguments\": {\"city\": \"Paris\"}}</tool_call>",
+                ),
+                ChatTurn::tool("{\"temperature_c\": 18, \"sky\": \"cloudy\"}"),
+            ])
+            .with_tools(vec![weather_tool()]),
+        ),
+        (
+            "thinking",
+            ChatConversation::new(vec![
+                ChatTurn::user("Is 91 prime?"),
+                ChatTurn::assistant("<think>\n91 = 7 * 13.\n</think>\n\nNo, 91 = 7 × 13."),
+                ChatTurn::user("Is 97 prime?"),
+            ])
+            .with_thinking(false),
+        ),
+        (
+            "assistant_prefix",
+            ChatConversation::new(vec![ChatTurn::user("List two colors as JSON.")])
+                .with_assistant_prefix(Some("```json\n".to_string())),
+        ),
+    ]
+}
+
+/// Golden file for `case` rendered by `family`
+pub fn golden_path(dir: &Path, family: ChatTemplateFamily, case: &str) -> PathBuf {
+    dir.join(family.name()).join(format!("{case}.txt"))
+}
+
+/// Every (golden path, render) pair for the registered model families
+pub fn render_all(dir: &Path) -> Vec<(PathBuf, String)> {
+    let conversations = canonical_conversations();
+    chat_template_families()
+        .into_iter()
+        .flat_map(|family| {
+            conversations
+                .iter()
+                .map(move |(case, conversation)| {
+                    (golden_path(dir, family, case), family.render(conversation))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Renders that differ from their golden files under `dir`
+pub fn check_goldens(dir: &Path) -> Vec<GoldenMismatch> {
+    render_all(dir)
+        .into_iter()
+        .filter_map(|(path, actual)| {
+            let expected = std::fs::read_to_string(&path).ok();
+            (expected.as_deref() != Some(actual.as_str())).then_some(GoldenMismatch {
+                path,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+/// Write every render to its golden file under `dir`, returning the files written
+///
+/// # Errors
+///
+/// Returns the first I/O error creating a directory or writing a file.
+pub fn write_goldens(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for (path, render) in render_all(dir) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, render)?;
+        written.push(path);
+    }
+    Ok(written)
+}
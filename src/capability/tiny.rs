@@ -0,0 +1,233 @@
+//! Tiny model mode for reproducible end-to-end tests
+//!
+//! Production checkpoints are too heavy for CI, so integration coverage of the
+//! memorize → recall → chat → tool-call path has historically been compile-only.
+//! Tiny mode keeps every registry key stable but swaps the checkpoint files the
+//! loaders download for tiny checkpoints that share each architecture.
+//!
+//! The published Qwen3 GGUFs start at 0.6B parameters, so the tiny Qwen3
+//! checkpoint is generated instead: a two-layer, 64-wide model with fixed
+//! pseudo-random weights (about 10 MB), written once to the temp directory.
+//! Only its tokenizer is downloaded. It produces noise, which is all an
+//! end-to-end test of the plumbing needs.
+//!
+//! Enable it with the `tiny-models` feature or at runtime:
+//!
+//! ```bash
+//! KODEGEN_TINY_MODELS=1 cargo test --test tiny_models
+//! ```
+//!
+//! The text checkpoint can be pointed at a published GGUF instead (for example
+//! a locally trained Qwen3-architecture model) via `KODEGEN_TINY_TEXT_REPO`,
+//! `KODEGEN_TINY_TEXT_FILE` and `KODEGEN_TINY_TOKENIZER_REPO`.
+//!
+//! Embeddings use [`TINY_EMBEDDING_MODEL`], nomic-embed-text v1.5: at 137M
+//! parameters the smallest registered embedding model. Libraries record the
+//! dimension they were built for, so its 768-dimensional vectors need no
+//! schema changes.
+
+use std::path::{Path, PathBuf};
+
+use candle_core::quantized::gguf_file::{self, Value};
+use candle_core::quantized::{GgmlDType, QTensor};
+use candle_core::{DType, Device, Tensor};
+
+use crate::capability::registry::QuantVariant;
+
+/// Environment variable that enables tiny model mode at runtime
+pub const TINY_MODELS_ENV: &str = "KODEGEN_TINY_MODELS";
+
+/// Override for the tiny text-to-text GGUF repository
+pub const TINY_TEXT_REPO_ENV: &str = "KODEGEN_TINY_TEXT_REPO";
+
+/// Override for the tiny text-to-text GGUF filename
+pub const TINY_TEXT_FILE_ENV: &str = "KODEGEN_TINY_TEXT_FILE";
+
+/// Override for the repository providing the tiny model's tokenizer.json
+pub const TINY_TOKENIZER_REPO_ENV: &str = "KODEGEN_TINY_TOKENIZER_REPO";
+
+/// Registry key of the embedding model end-to-end tests use in tiny mode
+pub const TINY_EMBEDDING_MODEL: &str = "nomic-ai/nomic-embed-text-v1.5";
+
+/// Rows of the generated model's embedding: the Qwen3 tokenizer's vocabulary
+const TINY_QWEN3_VOCAB: usize = 151_936;
+
+/// Hidden size of the generated model
+const TINY_QWEN3_HIDDEN: usize = 64;
+
+/// Transformer blocks of the generated model
+const TINY_QWEN3_LAYERS: usize = 2;
+
+/// Attention heads of the generated model
+const TINY_QWEN3_HEADS: usize = 4;
+
+/// Key/value heads of the generated model
+const TINY_QWEN3_KV_HEADS: usize = 2;
+
+/// Width of each attention head of the generated model
+const TINY_QWEN3_HEAD_DIM: usize = 16;
+
+/// Feed-forward width of the generated model
+const TINY_QWEN3_FFN: usize = 128;
+
+/// Returns `true` when tiny model mode is active
+///
+/// Active when built with the `tiny-models` feature, or when
+/// `KODEGEN_TINY_MODELS` is set to `1`, `true`, `yes` or `on`.
+pub fn tiny_models_enabled() -> bool {
+    cfg!(feature = "tiny-models")
+        || std::env::var(TINY_MODELS_ENV)
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(false)
+}
+
+/// HuggingFace locations for a GGUF text-to-text checkpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GgufCheckpoint {
+    /// Repository holding the GGUF weights
+    pub repo: String,
+    /// GGUF filename inside `repo`
+    pub file: String,
+    /// Repository holding `tokenizer.json`
+    pub tokenizer_repo: String,
+    /// Generated GGUF loaded instead of downloading `repo`/`file`
+    ///
+    /// Loaders create it with [`write_tiny_qwen3_gguf`] if it is missing.
+    pub generated: Option<PathBuf>,
+}
+
+impl GgufCheckpoint {
+    /// Production Qwen3 checkpoint
+    pub fn qwen3_default() -> Self {
+        Self::qwen3_quantized(QuantVariant::default())
+    }
+
+    /// Production Qwen3 checkpoint at a given quantization
+    pub fn qwen3_quantized(variant: QuantVariant) -> Self {
+        Self {
+            repo: "unsloth/Qwen3-1.7B-GGUF".to_string(),
+            file: format!("Qwen3-1.7B-{variant}.gguf"),
+            tokenizer_repo: "Qwen/Qwen3-1.7B".to_string(),
+            generated: None,
+        }
+    }
+
+    /// Generated Qwen3-architecture checkpoint using the Qwen3 tokenizer
+    pub fn qwen3_tiny() -> Self {
+        let file = "kodegen-tiny-qwen3.gguf";
+        Self {
+            repo: "Qwen/Qwen3-0.6B".to_string(),
+            file: file.to_string(),
+            tokenizer_repo: "Qwen/Qwen3-0.6B".to_string(),
+            generated: Some(std::env::temp_dir().join(file)),
+        }
+    }
+
+    /// Select the Qwen3 checkpoint for the current mode
+    ///
+    /// In tiny mode `KODEGEN_TINY_TEXT_REPO` and `KODEGEN_TINY_TEXT_FILE`
+    /// replace the generated checkpoint with a published one, and
+    /// `KODEGEN_TINY_TOKENIZER_REPO` replaces the tokenizer.
+    pub fn qwen3() -> Self {
+        Self::qwen3_for(QuantVariant::default())
+    }
+
+    /// Select the Qwen3 checkpoint for the current mode and a quantization
+    ///
+    /// Tiny mode ignores `variant`: every variant loads the same tiny
+    /// checkpoint so tests stay small.
+    pub fn qwen3_for(variant: QuantVariant) -> Self {
+        if !tiny_models_enabled() {
+            return Self::qwen3_quantized(variant);
+        }
+
+        let tiny = Self::qwen3_tiny();
+        let repo = std::env::var(TINY_TEXT_REPO_ENV).ok();
+        let file = std::env::var(TINY_TEXT_FILE_ENV).ok();
+        let generated = if repo.is_none() && file.is_none() {
+            tiny.generated
+        } else {
+            None
+        };
+        Self {
+            repo: repo.unwrap_or(tiny.repo),
+            file: file.unwrap_or(tiny.file),
+            tokenizer_repo: std::env::var(TINY_TOKENIZER_REPO_ENV).unwrap_or(tiny.tokenizer_repo),
+            generated,
+        }
+    }
+}
+
+/// Write the generated tiny Qwen3 checkpoint to `path`, unless it exists
+///
+/// Weights are a fixed function of their position, so every run writes the
+/// same model. The file is written next to `path` and renamed into place,
+/// so concurrent loaders never read a partial file.
+pub fn write_tiny_qwen3_gguf(path: &Path) -> candle_core::Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+
+    let device = Device::Cpu;
+    let matrix = |rows: usize, cols: usize, seed: f64| -> candle_core::Result<QTensor> {
+        let positions =
+            Tensor::arange(0u32, (rows * cols) as u32, &device)?.to_dtype(DType::F32)?;
+        let weights = ((positions * 0.618)? + seed)?.sin()?.affine(0.02, 0.0)?;
+        QTensor::quantize(&weights.reshape((rows, cols))?, GgmlDType::Q8_0)
+    };
+    let norm = |size: usize| -> candle_core::Result<QTensor> {
+        QTensor::quantize(&Tensor::ones(size, DType::F32, &device)?, GgmlDType::F32)
+    };
+
+    let hidden = TINY_QWEN3_HIDDEN;
+    let ffn = TINY_QWEN3_FFN;
+    let attention = TINY_QWEN3_HEADS * TINY_QWEN3_HEAD_DIM;
+    let kv = TINY_QWEN3_KV_HEADS * TINY_QWEN3_HEAD_DIM;
+    let mut tensors = vec![
+        (
+            "token_embd.weight".to_string(),
+            matrix(TINY_QWEN3_VOCAB, hidden, 0.0)?,
+        ),
+        ("output_norm.weight".to_string(), norm(hidden)?),
+    ];
+    for layer in 0..TINY_QWEN3_LAYERS {
+        let seed = layer as f64 + 1.0;
+        let blk = |name: &str| format!("blk.{layer}.{name}.weight");
+        tensors.extend([
+            (blk("attn_norm"), norm(hidden)?),
+            (blk("ffn_norm"), norm(hidden)?),
+            (blk("attn_q_norm"), norm(TINY_QWEN3_HEAD_DIM)?),
+            (blk("attn_k_norm"), norm(TINY_QWEN3_HEAD_DIM)?),
+            (blk("attn_q"), matrix(attention, hidden, seed + 0.1)?),
+            (blk("attn_k"), matrix(kv, hidden, seed + 0.2)?),
+            (blk("attn_v"), matrix(kv, hidden, seed + 0.3)?),
+            (blk("attn_output"), matrix(hidden, attention, seed + 0.4)?),
+            (blk("ffn_gate"), matrix(ffn, hidden, seed + 0.5)?),
+            (blk("ffn_up"), matrix(ffn, hidden, seed + 0.6)?),
+            (blk("ffn_down"), matrix(hidden, ffn, seed + 0.7)?),
+        ]);
+    }
+
+    let count = |n: usize| Value::U32(n as u32);
+    let metadata = [
+        ("general.architecture", Value::String("qwen3".to_string())),
+        ("general.dtype", Value::U32(0)),
+        ("qwen3.attention.head_count", count(TINY_QWEN3_HEADS)),
+        ("qwen3.attention.head_count_kv", count(TINY_QWEN3_KV_HEADS)),
+        ("qwen3.attention.key_length", count(TINY_QWEN3_HEAD_DIM)),
+        ("qwen3.block_count", count(TINY_QWEN3_LAYERS)),
+        ("qwen3.embedding_length", count(hidden)),
+        ("qwen3.context_length", Value::U32(4096)),
+        ("qwen3.attention.layer_norm_rms_epsilon", Value::F32(1e-6)),
+        ("qwen3.rope.freq_base", Value::F32(1_000_000.0)),
+    ];
+    let metadata: Vec<(&str, &Value)> = metadata.iter().map(|(k, v)| (*k, v)).collect();
+    let tensors: Vec<(&str, &QTensor)> = tensors.iter().map(|(n, t)| (n.as_str(), t)).collect();
+
+    let partial = path.with_extension(format!("{}.partial", std::process::id()));
+    let mut file = std::fs::File::create(&partial)?;
+    gguf_file::write(&mut file, &metadata, &tensors)?;
+    drop(file);
+    std::fs::rename(&partial, path)?;
+    Ok(())
+}
@@ -5,6 +5,7 @@
 //! - TextToImage  
 //! - TextEmbedding
 //! - ImageEmbedding
+//! - Rerank
 //! - TextToSpeech
 //! - SpeechToText
 //! - Vision
@@ -63,6 +64,25 @@ pub trait TextToTextCapable: CandleModel {
     }
 }
 
+/// Lets pool workers run a model instance that is also used elsewhere
+impl<T: TextToTextCapable> TextToTextCapable for std::sync::Arc<T> {
+    fn prompt(
+        &self,
+        prompt: CandlePrompt,
+        params: &CandleCompletionParams,
+    ) -> Pin<Box<dyn Stream<Item = CandleCompletionChunk> + Send>> {
+        (**self).prompt(prompt, params)
+    }
+
+    fn default_generation_params(&self) -> GenerationParams {
+        (**self).default_generation_params()
+    }
+
+    fn max_context_length(&self) -> Option<usize> {
+        (**self).max_context_length()
+    }
+}
+
 /// Trait for models capable of text embedding
 pub trait TextEmbeddingCapable: CandleModel {
     /// Generate embedding for a single text
@@ -199,6 +219,23 @@ pub trait TextEmbeddingCapable: CandleModel {
     }
 }
 
+/// Type alias for relevance scores future
+pub type RerankFuture<'a> = Pin<
+    Box<
+        dyn std::future::Future<
+                Output = std::result::Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>>,
+            > + Send
+            + 'a,
+    >,
+>;
+
+/// Trait for models that score how relevant documents are to a query
+pub trait RerankCapable: CandleModel {
+    /// Relevance of each document to `query`, in document order; higher is
+    /// more relevant
+    fn rerank(&self, query: &str, documents: &[String]) -> RerankFuture<'_>;
+}
+
 /// Trait for models capable of image embedding
 pub trait ImageEmbeddingCapable: CandleModel {
     /// Generate embedding for an image from file path
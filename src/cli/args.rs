@@ -46,6 +46,36 @@ pub struct CliArgs {
 
     /// Verbose logging
     pub verbose: bool,
+
+    /// Asked for the operator dashboard, which runs in the server instead
+    pub dashboard: bool,
+
+    /// Print what the model was shown (prompt, tools, memory hits, sampling) each turn
+    pub trace: bool,
+
+    /// Workflow definition to run (`workflow run <file>`) instead of chatting
+    pub workflow: Option<PathBuf>,
+
+    /// Workflow inputs: `key=value` pairs or JSON objects (`--input`, repeatable)
+    pub workflow_inputs: Vec<String>,
+
+    /// Directory to regenerate chat template goldens in (`templates goldens <dir>`)
+    pub template_goldens: Option<PathBuf>,
+
+    /// Memory library to check (`memory fsck <library>`)
+    pub fsck_library: Option<String>,
+
+    /// Repair the issues `memory fsck` finds (`--repair`)
+    pub fsck_repair: bool,
+
+    /// Report the size of the model cache (`models cache`)
+    pub model_cache: bool,
+
+    /// Prune the model cache (`models prune`)
+    pub model_prune: bool,
+
+    /// Size to prune the model cache to (`--max-size`, e.g. `20G`)
+    pub prune_max_size: Option<String>,
 }
 
 impl Default for CliArgs {
@@ -64,6 +94,16 @@ impl Default for CliArgs {
             message: None,
             config: None,
             verbose: false,
+            dashboard: false,
+            trace: false,
+            workflow: None,
+            workflow_inputs: Vec::new(),
+            template_goldens: None,
+            fsck_library: None,
+            fsck_repair: false,
+            model_cache: false,
+            model_prune: false,
+            prune_max_size: None,
         }
     }
 }
@@ -166,6 +206,58 @@ impl CliArgs {
                 "--no-interactive" => {
                     cli_args.interactive = false;
                 }
+                "--dashboard" => {
+                    cli_args.dashboard = true;
+                }
+                "--trace" => {
+                    cli_args.trace = true;
+                }
+                "workflow" if args.get(i + 1).map(String::as_str) == Some("run") => {
+                    i += 2;
+                    if i < args.len() {
+                        cli_args.workflow = Some(PathBuf::from(&args[i]));
+                        cli_args.interactive = false;
+                    }
+                }
+                "templates" if args.get(i + 1).map(String::as_str) == Some("goldens") => {
+                    i += 2;
+                    // An empty path records a missing directory for `validate` to reject
+                    cli_args.template_goldens =
+                        Some(args.get(i).map(PathBuf::from).unwrap_or_default());
+                    cli_args.interactive = false;
+                }
+                "memory" if args.get(i + 1).map(String::as_str) == Some("fsck") => {
+                    i += 2;
+                    if i < args.len() {
+                        cli_args.fsck_library = Some(args[i].clone());
+                        cli_args.interactive = false;
+                    }
+                }
+                "models" if args.get(i + 1).map(String::as_str) == Some("cache") => {
+                    i += 1;
+                    cli_args.model_cache = true;
+                    cli_args.interactive = false;
+                }
+                "models" if args.get(i + 1).map(String::as_str) == Some("prune") => {
+                    i += 1;
+                    cli_args.model_prune = true;
+                    cli_args.interactive = false;
+                }
+                "--max-size" => {
+                    i += 1;
+                    if i < args.len() {
+                        cli_args.prune_max_size = Some(args[i].clone());
+                    }
+                }
+                "--repair" => {
+                    cli_args.fsck_repair = true;
+                }
+                "--input" => {
+                    i += 1;
+                    if i < args.len() {
+                        cli_args.workflow_inputs.push(args[i].clone());
+                    }
+                }
                 _ => {
                     // Treat unknown args as documents
                     if !args[i].starts_with('-') {
@@ -198,6 +290,15 @@ impl CliArgs {
             return Err("Memory read timeout must be greater than 0".to_string());
         }
 
+        if let Some(dir) = &self.template_goldens
+            && dir.as_os_str().is_empty()
+        {
+            return Err(
+                "templates goldens requires a directory, e.g. tests/goldens/chat_templates"
+                    .to_string(),
+            );
+        }
+
         Ok(())
     }
 }
@@ -21,6 +21,7 @@ pub const CHAT_COMMANDS: &[&str] = &[
     "/history",
     "/export",
     "/import",
+    "/regenerate",
 ];
 
 /// Model completer with fuzzy matching
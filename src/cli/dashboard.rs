@@ -0,0 +1,395 @@
+//! Live operator dashboard (`--dashboard`)
+//!
+//! Renders a full-screen ratatui view refreshed on an interval:
+//! - Worker pools: loaded models, worker counts, busy/idle split, average latency
+//! - Memory: governor allocation against its limit and per-pool footprint
+//! - Memorize sessions with stage and progress
+//! - Recent recalls with latency
+//!
+//! Every panel is backed by an existing stats API; [`Dashboard::snapshot`]
+//! gathers them so the data can also be consumed without a terminal. Those
+//! stats live in the process that serves requests, so the dashboard runs
+//! inside the server (`kodegen-candle-agent --dashboard`) and lists the
+//! sessions of the tools it serves.
+
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::capability::registry::pool::core::types::{HealthStatusLevel, PoolHealth};
+use crate::capability::registry::pool::{
+    image_embedding_pool, text_embedding_pool, text_to_image_pool, text_to_text_pool, vision_pool,
+};
+use crate::domain::agent::core::{AgentStatsSnapshot, get_agent_stats};
+use crate::memory::monitoring::{RecallRecord, recent_recalls};
+use crate::tools::memorize_manager::{MemorizeStatus, MemorizeStatusResponse};
+use crate::tools::{MemorizeSessionManager, served_memorize_manager};
+
+/// Default refresh interval
+const DEFAULT_REFRESH: Duration = Duration::from_millis(500);
+
+/// Number of recalls shown
+const RECALL_ROWS: usize = 10;
+
+/// Health of one capability pool
+#[derive(Debug, Clone)]
+pub struct PoolSummary {
+    /// Capability served by the pool
+    pub capability: &'static str,
+    /// Model and worker health
+    pub health: PoolHealth,
+    /// Memory attributed to the pool's workers
+    pub memory_mb: usize,
+}
+
+/// Point-in-time view of everything the dashboard renders
+#[derive(Debug, Clone)]
+pub struct DashboardSnapshot {
+    /// One entry per capability pool
+    pub pools: Vec<PoolSummary>,
+    /// Memorize sessions, oldest first
+    pub sessions: Vec<MemorizeStatusResponse>,
+    /// Recent recalls, newest first
+    pub recalls: Vec<RecallRecord>,
+    /// Agent completion counters
+    pub agent: AgentStatsSnapshot,
+    /// Supervised background tasks still running
+    pub background_tasks: usize,
+}
+
+impl DashboardSnapshot {
+    /// Total memory attributed to pool workers
+    pub fn total_pool_memory_mb(&self) -> usize {
+        self.pools.iter().map(|p| p.memory_mb).sum()
+    }
+
+    /// Number of models with at least one worker
+    pub fn loaded_models(&self) -> usize {
+        self.pools
+            .iter()
+            .flat_map(|p| &p.health.models)
+            .filter(|m| m.workers.total > 0)
+            .count()
+    }
+}
+
+/// Full-screen dashboard
+#[derive(Clone)]
+pub struct Dashboard {
+    memorize: Option<Arc<MemorizeSessionManager>>,
+    refresh: Duration,
+    started: Instant,
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dashboard {
+    /// Create a dashboard with the default refresh interval
+    pub fn new() -> Self {
+        Self {
+            memorize: None,
+            refresh: DEFAULT_REFRESH,
+            started: Instant::now(),
+        }
+    }
+
+    /// Show sessions from `manager` instead of the served tools' manager
+    #[must_use]
+    pub fn with_memorize_manager(mut self, manager: Arc<MemorizeSessionManager>) -> Self {
+        self.memorize = Some(manager);
+        self
+    }
+
+    /// Set the refresh interval
+    #[must_use]
+    pub fn with_refresh_interval(mut self, refresh: Duration) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Gather current stats from every source
+    pub async fn snapshot(&self) -> DashboardSnapshot {
+        let pools = vec![
+            PoolSummary {
+                capability: "TextToText",
+                health: text_to_text_pool().get_health().await,
+                memory_mb: text_to_text_pool().total_memory_mb(),
+            },
+            PoolSummary {
+                capability: "TextEmbedding",
+                health: text_embedding_pool().get_health().await,
+                memory_mb: text_embedding_pool().total_memory_mb(),
+            },
+            PoolSummary {
+                capability: "ImageEmbedding",
+                health: image_embedding_pool().get_health().await,
+                memory_mb: image_embedding_pool().total_memory_mb(),
+            },
+            PoolSummary {
+                capability: "Vision",
+                health: vision_pool().get_health().await,
+                memory_mb: vision_pool().total_memory_mb(),
+            },
+            PoolSummary {
+                capability: "TextToImage",
+                health: text_to_image_pool().get_health().await,
+                memory_mb: text_to_image_pool().total_memory_mb(),
+            },
+        ];
+
+        let manager = self.memorize.clone().or_else(served_memorize_manager);
+        let sessions = match manager {
+            Some(manager) => manager.list_sessions().await,
+            None => Vec::new(),
+        };
+
+        DashboardSnapshot {
+            pools,
+            sessions,
+            recalls: recent_recalls(RECALL_ROWS),
+            agent: get_agent_stats(),
+            background_tasks: crate::runtime::supervisor().active_tasks(),
+        }
+    }
+
+    /// Run until the user presses `q`, `Esc` or `Ctrl+C`
+    pub async fn run(self) -> Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal).await;
+        ratatui::restore();
+        result
+    }
+
+    async fn event_loop(&self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            let snapshot = self.snapshot().await;
+            let uptime = self.started.elapsed();
+            terminal.draw(|frame| draw(frame, &snapshot, uptime))?;
+
+            let refresh = self.refresh;
+            let quit = tokio::task::spawn_blocking(move || -> io::Result<bool> {
+                if !event::poll(refresh)? {
+                    return Ok(false);
+                }
+                Ok(match event::read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Press => {
+                        matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                            || (key.code == KeyCode::Char('c')
+                                && key.modifiers.contains(KeyModifiers::CONTROL))
+                    }
+                    _ => false,
+                })
+            })
+            .await??;
+
+            if quit {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, snapshot: &DashboardSnapshot, uptime: Duration) {
+    let [header, top, sessions, recalls, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(8),
+        Constraint::Length(8),
+        Constraint::Length(RECALL_ROWS as u16 + 3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let [pools, memory] =
+        Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(top);
+
+    draw_header(frame, header, snapshot, uptime);
+    draw_pools(frame, pools, snapshot);
+    draw_memory(frame, memory, snapshot);
+    draw_sessions(frame, sessions, snapshot);
+    draw_recalls(frame, recalls, snapshot);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(" q quit ").dark_gray()),
+        footer,
+    );
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot, uptime: Duration) {
+    let agent = &snapshot.agent;
+    let line = Line::from(format!(
+        "uptime {}s │ models loaded {} │ completions {} │ tokens {} │ avg completion {} ms │ background tasks {}",
+        uptime.as_secs(),
+        snapshot.loaded_models(),
+        agent.completions_total,
+        agent.tokens_total,
+        agent.avg_completion_time_us / 1000,
+        snapshot.background_tasks,
+    ));
+    frame.render_widget(
+        Paragraph::new(line).block(Block::bordered().title(" kodegen candle agent ".bold())),
+        area,
+    );
+}
+
+fn draw_pools(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let rows = snapshot.pools.iter().flat_map(|pool| {
+        pool.health.models.iter().map(move |model| {
+            Row::new(vec![
+                pool.capability.to_string(),
+                model.registry_key.clone(),
+                model.workers.total.to_string(),
+                model.workers.busy.to_string(),
+                model.workers.idle.to_string(),
+                model
+                    .avg_latency_ms
+                    .map_or_else(|| "-".to_string(), |ms| format!("{ms:.0} ms")),
+            ])
+            .style(Style::new().fg(status_color(model.status)))
+        })
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(15),
+            Constraint::Min(20),
+            Constraint::Length(7),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(10),
+        ],
+    )
+    .header(Row::new(["Pool", "Model", "Workers", "Busy", "Idle", "Latency"]).bold())
+    .block(Block::bordered().title(" Pools "));
+
+    frame.render_widget(table, area);
+}
+
+fn draw_memory(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let [gauge_area, pools_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+
+    // Every pool reports the same system-wide governor view; use the first
+    let (used, limit, pressure) = snapshot
+        .pools
+        .first()
+        .map(|p| {
+            (
+                p.health.memory.used_mb,
+                p.health.memory.limit_mb,
+                p.health.memory.pressure.clone(),
+            )
+        })
+        .unwrap_or((0, 0, "Unknown".to_string()));
+
+    let ratio = if limit == 0 {
+        0.0
+    } else {
+        (used as f64 / limit as f64).clamp(0.0, 1.0)
+    };
+
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::bordered().title(format!(" Memory ({pressure}) ")))
+            .gauge_style(Style::new().fg(Color::Cyan))
+            .ratio(ratio)
+            .label(format!("{used} / {limit} MB")),
+        gauge_area,
+    );
+
+    let mut lines: Vec<Line> = snapshot
+        .pools
+        .iter()
+        .map(|p| Line::from(format!("{:<15} {:>7} MB", p.capability, p.memory_mb)))
+        .collect();
+    lines.push(Line::from(format!("{:<15} {:>7} MB", "Total", snapshot.total_pool_memory_mb())).bold());
+
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Worker footprint ")),
+        pools_area,
+    );
+}
+
+fn draw_sessions(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let rows = snapshot.sessions.iter().map(|s| {
+        let color = match s.status {
+            MemorizeStatus::InProgress => Color::Yellow,
+            MemorizeStatus::Completed => Color::Green,
+            MemorizeStatus::Failed => Color::Red,
+        };
+        Row::new(vec![
+            s.session_id.chars().take(8).collect::<String>(),
+            s.library.clone(),
+            format!("{:?}", s.status),
+            s.progress.stage.clone(),
+            s.progress.files_loaded.to_string(),
+            format!("{} KB", s.progress.total_size_bytes / 1024),
+            format!("{:.1}s", s.runtime_ms as f64 / 1000.0),
+        ])
+        .style(Style::new().fg(color))
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(9),
+            Constraint::Length(16),
+            Constraint::Length(11),
+            Constraint::Min(16),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(8),
+        ],
+    )
+    .header(Row::new(["Session", "Library", "Status", "Stage", "Files", "Size", "Runtime"]).bold())
+    .block(Block::bordered().title(" Memorize sessions "));
+
+    frame.render_widget(table, area);
+}
+
+fn draw_recalls(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let rows = snapshot.recalls.iter().map(|r| {
+        Row::new(vec![
+            r.at.format("%H:%M:%S").to_string(),
+            r.library.clone(),
+            r.query.chars().take(60).collect::<String>(),
+            r.results.to_string(),
+            format!("{:.0} ms", r.latency_ms),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(9),
+            Constraint::Length(16),
+            Constraint::Min(20),
+            Constraint::Length(7),
+            Constraint::Length(9),
+        ],
+    )
+    .header(Row::new(["Time", "Library", "Query", "Results", "Latency"]).bold())
+    .block(Block::bordered().title(" Recent recalls "));
+
+    frame.render_widget(table, area);
+}
+
+fn status_color(status: HealthStatusLevel) -> Color {
+    match status {
+        HealthStatusLevel::Healthy => Color::Green,
+        HealthStatusLevel::Degraded => Color::Yellow,
+        HealthStatusLevel::Unhealthy => Color::Red,
+    }
+}
@@ -5,7 +5,8 @@
 
 use super::completion::CommandCompleter;
 use super::config::CliConfig;
-use crate::domain::chat::CandleChatLoop;
+use crate::domain::chat::{CandleChatLoop, RegenerateOptions};
+use crate::domain::chat::search::parse_query;
 use std::fs;
 use std::path::Path;
 
@@ -15,6 +16,9 @@ pub enum InputHandlerResult {
     /// Continue with chat message
     Chat(String),
 
+    /// Answer the previous message again with different sampling
+    Regenerate(RegenerateOptions),
+
     /// Execute command
     Command(CommandResult),
 
@@ -99,6 +103,7 @@ impl InputHandler {
             "/tokens" => self.handle_tokens(&args),
             "/export" => self.handle_export(&args),
             "/import" => self.handle_import(&args),
+            "/regenerate" => self.handle_regenerate(&args),
             _ => InputHandlerResult::Command(CommandResult::Error(format!(
                 "Unknown command: {}",
                 command
@@ -114,6 +119,7 @@ Available Commands:
   /exit, /quit    - Exit the application
   /save <file>    - Save conversation to file
   /search <query> - Search conversation history
+                    (e.g. error AND (timeout OR retry) -draft)
   /clear          - Clear conversation history
   /history        - Show conversation history
   /model <name>   - Change current model
@@ -122,6 +128,7 @@ Available Commands:
   /tokens <n>     - Set max tokens
   /export <file>  - Export configuration
   /import <file>  - Import configuration
+  /regenerate [t] - Answer the last message again (optionally at temperature t)
 
 Chat Commands:
   Type any message to chat with the AI
@@ -153,6 +160,10 @@ Chat Commands:
     }
 
     /// Handle /search command
+    ///
+    /// Accepts the search query language, e.g. `error AND (timeout OR retry)`.
+    /// Input history has no tags, roles or timestamps, so only the boolean
+    /// expression is applied here.
     fn handle_search(&self, args: &[String]) -> InputHandlerResult {
         if args.is_empty() {
             return InputHandlerResult::Command(CommandResult::Error(
@@ -160,12 +171,25 @@ Chat Commands:
             ));
         }
 
-        let query = args.join(" ");
+        let input = args.join(" ");
+        let query = match parse_query(&input) {
+            Ok(query) => query,
+            Err(e) => {
+                return InputHandlerResult::Command(CommandResult::Error(format!(
+                    "Invalid search query: {}\n{}",
+                    e.message,
+                    e.pointer(&input)
+                )));
+            }
+        };
+        let Some(expression) = query.expression else {
+            return InputHandlerResult::Command(CommandResult::SearchResults(Vec::new()));
+        };
         let history = self.config.get_history();
 
         let results: Vec<String> = history
             .iter()
-            .filter(|msg| msg.to_lowercase().contains(&query.to_lowercase()))
+            .filter(|msg| expression.matches(msg))
             .cloned()
             .collect();
 
@@ -286,6 +310,27 @@ Chat Commands:
         }
     }
 
+    /// Handle /regenerate command
+    fn handle_regenerate(&self, args: &[String]) -> InputHandlerResult {
+        let Some(arg) = args.first() else {
+            return InputHandlerResult::Regenerate(RegenerateOptions::new());
+        };
+
+        match arg.parse::<f64>() {
+            Ok(temp) if (0.0..=2.0).contains(&temp) => {
+                InputHandlerResult::Regenerate(RegenerateOptions::new().with_temperature(temp))
+            }
+            Ok(temp) => InputHandlerResult::Command(CommandResult::Error(format!(
+                "Temperature must be between 0.0 and 2.0, got {}",
+                temp
+            ))),
+            Err(e) => InputHandlerResult::Command(CommandResult::Error(format!(
+                "Invalid temperature: {}",
+                e
+            ))),
+        }
+    }
+
     /// Handle /import command
     fn handle_import(&mut self, args: &[String]) -> InputHandlerResult {
         if args.is_empty() {
@@ -322,6 +367,7 @@ Chat Commands:
     pub fn to_chat_loop(&self, input: InputHandlerResult) -> Option<CandleChatLoop> {
         match input {
             InputHandlerResult::Chat(message) => Some(CandleChatLoop::UserPrompt(message)),
+            InputHandlerResult::Regenerate(options) => Some(CandleChatLoop::Regenerate(options)),
             InputHandlerResult::Exit => Some(CandleChatLoop::Break),
             _ => None,
         }
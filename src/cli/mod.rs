@@ -7,14 +7,20 @@
 pub mod args;
 pub mod completion;
 pub mod config;
+#[cfg(feature = "tools")]
+pub mod dashboard;
 pub mod handler;
 pub mod prompt;
+pub mod render;
 pub mod runner;
 
 // Re-export main types for convenience
 pub use args::CliArgs;
 pub use completion::{CommandCompleter, ModelCompleter};
 pub use config::CliConfig;
+#[cfg(feature = "tools")]
+pub use dashboard::{Dashboard, DashboardSnapshot};
 pub use handler::{CommandResult, InputHandler, InputHandlerResult};
 pub use prompt::PromptBuilder;
+pub use render::{MarkdownStreamRenderer, ToolSpinner};
 pub use runner::CliRunner;
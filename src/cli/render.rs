@@ -0,0 +1,323 @@
+//! Streaming output rendering for the interactive CLI
+//!
+//! Tokens arrive a few characters at a time, so markdown cannot be parsed as a
+//! whole document. [`MarkdownStreamRenderer`] is a small state machine that
+//! styles code fences, inline code, bold spans and headings as tokens stream
+//! in, holding back only the characters needed to disambiguate a marker.
+//! [`ToolSpinner`] animates tool-call activity and [`format_turn_stats`]
+//! summarizes a completed turn.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+/// Code fence marker
+const FENCE: &str = "```";
+
+/// Spinner animation frames
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Delay between spinner frames
+const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+/// Color choice for a stream: colors only when attached to a terminal
+///
+/// `NO_COLOR` is honored by termcolor itself.
+pub fn color_choice(is_terminal: bool) -> ColorChoice {
+    if is_terminal {
+        ColorChoice::Auto
+    } else {
+        ColorChoice::Never
+    }
+}
+
+/// Standard output configured for streamed rendering
+pub fn stdout_stream() -> StandardStream {
+    StandardStream::stdout(color_choice(io::stdout().is_terminal()))
+}
+
+/// Incremental markdown renderer for streamed tokens
+#[derive(Debug, Default)]
+pub struct MarkdownStreamRenderer {
+    pending: String,
+    line_start: bool,
+    in_code_block: bool,
+    inline_code: bool,
+    bold: bool,
+    heading: bool,
+}
+
+impl MarkdownStreamRenderer {
+    /// Create a renderer positioned at the start of a line
+    pub fn new() -> Self {
+        Self {
+            line_start: true,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the renderer is inside a fenced code block
+    pub fn in_code_block(&self) -> bool {
+        self.in_code_block
+    }
+
+    /// Render a streamed token
+    ///
+    /// Characters that may begin a marker (`` ` ``, `*`, `#` at line start)
+    /// are held back until the next token resolves them.
+    pub fn push<W: WriteColor>(&mut self, out: &mut W, token: &str) -> io::Result<()> {
+        self.pending.push_str(token);
+        self.drain(out, false)?;
+        out.flush()
+    }
+
+    /// Flush held-back characters and reset all styles
+    ///
+    /// Call at the end of each turn; an unterminated code fence or bold span
+    /// does not leak into the next turn.
+    pub fn finish<W: WriteColor>(&mut self, out: &mut W) -> io::Result<()> {
+        self.drain(out, true)?;
+        out.reset()?;
+        out.flush()?;
+        *self = Self::new();
+        Ok(())
+    }
+
+    fn drain<W: WriteColor>(&mut self, out: &mut W, eof: bool) -> io::Result<()> {
+        while !self.pending.is_empty() {
+            let consumed = if self.line_start {
+                self.render_line_start(out, eof)?
+            } else if self.in_code_block {
+                self.render_code(out)?
+            } else {
+                self.render_inline(out, eof)?
+            };
+
+            match consumed {
+                Some(n) => {
+                    self.pending.drain(..n);
+                }
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle constructs only recognized at the start of a line
+    ///
+    /// Returns the number of bytes consumed, or `None` to wait for more input.
+    fn render_line_start<W: WriteColor>(&mut self, out: &mut W, eof: bool) -> io::Result<Option<usize>> {
+        let pending = self.pending.as_str();
+
+        if pending.starts_with(FENCE) {
+            let Some(newline) = pending.find('\n') else {
+                if !eof {
+                    return Ok(None);
+                }
+                self.write_fence(out, pending)?;
+                self.in_code_block = !self.in_code_block;
+                return Ok(Some(pending.len()));
+            };
+            let line = &pending[..newline];
+            self.write_fence(out, line)?;
+            writeln!(out)?;
+            self.in_code_block = !self.in_code_block;
+            return Ok(Some(newline + 1));
+        }
+
+        if !eof && FENCE.starts_with(pending) {
+            return Ok(None);
+        }
+
+        if !self.in_code_block && pending.starts_with('#') {
+            let hashes = pending.bytes().take_while(|&b| b == b'#').count();
+            match pending.as_bytes().get(hashes) {
+                Some(b' ') => {
+                    self.heading = true;
+                    self.line_start = false;
+                    self.apply_style(out)?;
+                    return Ok(Some(hashes + 1));
+                }
+                None if !eof => return Ok(None),
+                _ => {}
+            }
+        }
+
+        self.line_start = false;
+        Ok(Some(0))
+    }
+
+    fn render_code<W: WriteColor>(&mut self, out: &mut W) -> io::Result<Option<usize>> {
+        self.apply_style(out)?;
+        let end = self.pending.find('\n').map_or(self.pending.len(), |i| i + 1);
+        let chunk = &self.pending[..end];
+        write!(out, "{chunk}")?;
+        if chunk.ends_with('\n') {
+            self.line_start = true;
+        }
+        Ok(Some(end))
+    }
+
+    fn render_inline<W: WriteColor>(&mut self, out: &mut W, eof: bool) -> io::Result<Option<usize>> {
+        let Some(c) = self.pending.chars().next() else {
+            return Ok(None);
+        };
+
+        match c {
+            '`' => {
+                self.inline_code = !self.inline_code;
+                self.apply_style(out)?;
+                Ok(Some(1))
+            }
+            '*' if !self.inline_code => {
+                if self.pending.starts_with("**") {
+                    self.bold = !self.bold;
+                    self.apply_style(out)?;
+                    Ok(Some(2))
+                } else if self.pending.len() == 1 && !eof {
+                    Ok(None)
+                } else {
+                    write!(out, "*")?;
+                    Ok(Some(1))
+                }
+            }
+            '\n' => {
+                self.heading = false;
+                self.inline_code = false;
+                self.apply_style(out)?;
+                writeln!(out)?;
+                self.line_start = true;
+                Ok(Some(1))
+            }
+            _ => {
+                // Write the run of characters that need no interpretation
+                let end = self
+                    .pending
+                    .find(['`', '*', '\n'])
+                    .unwrap_or(self.pending.len());
+                let end = if end == 0 { c.len_utf8() } else { end };
+                write!(out, "{}", &self.pending[..end])?;
+                Ok(Some(end))
+            }
+        }
+    }
+
+    fn write_fence<W: WriteColor>(&self, out: &mut W, line: &str) -> io::Result<()> {
+        out.set_color(ColorSpec::new().set_fg(Some(Color::Black)).set_intense(true))?;
+        write!(out, "{line}")?;
+        out.reset()
+    }
+
+    /// Apply the color spec for the current state
+    fn apply_style<W: WriteColor>(&self, out: &mut W) -> io::Result<()> {
+        let mut spec = ColorSpec::new();
+        if self.in_code_block {
+            spec.set_fg(Some(Color::Green));
+        } else if self.inline_code {
+            spec.set_fg(Some(Color::Yellow));
+        } else if self.heading {
+            spec.set_fg(Some(Color::Cyan)).set_bold(true);
+        }
+        if self.bold {
+            spec.set_bold(true);
+        }
+
+        if spec.is_none() {
+            out.reset()
+        } else {
+            out.set_color(&spec)
+        }
+    }
+}
+
+/// Animated stderr indicator shown while a tool call runs
+#[derive(Debug)]
+pub struct ToolSpinner {
+    running: Arc<AtomicBool>,
+    handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl ToolSpinner {
+    /// Start animating for `tool_name`
+    ///
+    /// Renders nothing when stderr is not a terminal.
+    pub fn start(tool_name: &str) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+
+        if !io::stderr().is_terminal() {
+            eprintln!("🔧 {tool_name}");
+            return Self {
+                running,
+                handle: None,
+            };
+        }
+
+        let flag = running.clone();
+        let label = tool_name.to_string();
+        let handle = tokio::spawn(async move {
+            let mut frame = 0usize;
+            while flag.load(Ordering::Relaxed) {
+                let mut stderr = io::stderr();
+                let _ = write!(
+                    stderr,
+                    "\r{} running {label}",
+                    SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]
+                );
+                let _ = stderr.flush();
+                frame += 1;
+                tokio::time::sleep(SPINNER_INTERVAL).await;
+            }
+        });
+
+        Self {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop animating and clear the spinner line
+    pub async fn stop(mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.await;
+            let mut stderr = io::stderr();
+            let _ = write!(stderr, "\r\x1b[2K");
+            let _ = stderr.flush();
+        }
+    }
+}
+
+impl Drop for ToolSpinner {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Summarize a completed turn: token count, throughput and time to first token
+///
+/// Returns `None` when no statistics are available.
+pub fn format_turn_stats(
+    token_count: Option<u32>,
+    tokens_per_sec: Option<f64>,
+    time_to_first_token: Option<Duration>,
+) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(count) = token_count {
+        parts.push(format!("{count} tokens"));
+    }
+    if let Some(rate) = tokens_per_sec {
+        parts.push(format!("{rate:.1} tok/s"));
+    }
+    if let Some(ttft) = time_to_first_token {
+        parts.push(format!("TTFT {} ms", ttft.as_millis()));
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" · "))
+    }
+}
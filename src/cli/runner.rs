@@ -4,15 +4,27 @@
 //! with proper defaults, smart input resolution, and all CLI flags wired to builder methods.
 
 use anyhow::{Context, Result};
+use futures::future::BoxFuture;
 use std::io::Write;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_stream::{Stream, StreamExt};
 
 use super::args::CliArgs;
 use super::config::CliConfig;
 use super::handler::{CommandResult, InputHandler, InputHandlerResult};
+use super::render::{self, MarkdownStreamRenderer, ToolSpinner};
 
 use crate::builders::agent_role::{CandleAgentBuilder, CandleAgentRoleBuilder, CandleFluentAi};
+use crate::domain::agent::role::CandleAgentConversation;
+use crate::domain::chat::interrupt::{INTERRUPTED_FINISH_REASON, TurnInterrupt};
+use crate::domain::chat::message::CandleMessageChunk;
+use crate::domain::chat::trace::Verbosity;
 use crate::domain::chat::CandleChatLoop;
 use crate::util::input_resolver::resolve_input;
+use crate::util::output::print_info;
 
 /// CLI runner for interactive chat
 pub struct CliRunner {
@@ -46,17 +58,215 @@ impl CliRunner {
         })
     }
 
+    /// Run a workflow definition file and print its output as JSON
+    ///
+    /// Memory steps use the same per-library databases as the MCP server;
+    /// tool steps call the tools of a spawned kodegen process.
+    async fn run_workflow(&self, path: &std::path::Path) -> Result<()> {
+        #[cfg(feature = "memory")]
+        use crate::capability::registry::{FromRegistry, TextEmbeddingModel};
+        use crate::domain::context::WorkflowDataChunk;
+        #[cfg(feature = "memory")]
+        use crate::memory::core::manager::pool::CoordinatorPool;
+        use crate::workflow::{WorkflowDefinition, WorkflowSteps, definition::parse_inputs};
+
+        let definition = WorkflowDefinition::load(path)?;
+        let input = parse_inputs(self.args.workflow_inputs.iter().map(String::as_str))
+            .map_err(|e| anyhow::anyhow!("Invalid workflow input: {}", e))?;
+
+        let steps = WorkflowSteps::new();
+        #[cfg(feature = "memory")]
+        let steps = {
+            let embedding_model = TextEmbeddingModel::from_registry("dunzhang/stella_en_400M_v5")
+                .context("Stella embedding model not found in registry")?;
+            steps.with_memory(Arc::new(CoordinatorPool::new(embedding_model)))
+        };
+        let steps = if definition.uses_tools() {
+            steps.with_kodegen_tools().await
+        } else {
+            steps
+        };
+        let workflow = definition.compile(&steps)?;
+
+        let _ = print_info(&format!("Running workflow '{}'", definition.name));
+        let mut stream = workflow.execute(WorkflowDataChunk::from(input));
+        let mut result = WorkflowDataChunk::default();
+        while let Some(chunk) = stream.next().await {
+            result = chunk;
+        }
+
+        if let Some(error) = result.error_message {
+            return Err(anyhow::anyhow!(
+                "Workflow '{}' failed at {}: {}",
+                definition.name,
+                result.step_name.as_deref().unwrap_or("start"),
+                error
+            ));
+        }
+        println!("{}", serde_json::to_string_pretty(&result.data)?);
+        Ok(())
+    }
+
+    /// Regenerate the chat template golden files and list what was written
+    fn write_template_goldens(&self, dir: &std::path::Path) -> Result<()> {
+        use crate::capability::text_to_text::template_goldens::write_goldens;
+
+        // A mistyped directory would otherwise get a fresh golden tree
+        if !dir.is_dir() {
+            anyhow::bail!("Golden directory {} does not exist", dir.display());
+        }
+
+        let written = write_goldens(dir)
+            .with_context(|| format!("Failed to write goldens to {}", dir.display()))?;
+        for path in &written {
+            println!("{}", path.display());
+        }
+        let _ = print_info(&format!("Wrote {} chat template goldens", written.len()));
+        Ok(())
+    }
+
+    /// Check a memory library's integrity, repairing it with `--repair`
+    ///
+    /// Fails when issues remain, so scripts can tell a clean library apart.
+    #[cfg(feature = "memory")]
+    async fn run_fsck(&self, library: &str) -> Result<()> {
+        use crate::capability::registry::{FromRegistry, TextEmbeddingModel};
+        use crate::domain::model::traits::CandleModel;
+        use crate::memory::core::manager::coordinator::MemoryCoordinator;
+        use crate::memory::core::manager::pool::CoordinatorPool;
+        use crate::util::output::{print_success, print_warning};
+
+        // Only a library with nothing recorded uses the requested model;
+        // any other is checked with the model it records
+        let embedding_model = match self.args.embedding_model.as_deref() {
+            Some(model_key) => TextEmbeddingModel::from_registry(model_key)
+                .with_context(|| format!("Embedding model '{model_key}' not found in registry"))?,
+            None => CoordinatorPool::from_env()
+                .context("Failed to read the embedding model configuration")?
+                .library_embedding_model(library)
+                .clone(),
+        };
+        let coordinator = MemoryCoordinator::from_library(library, embedding_model)
+            .await
+            .with_context(|| format!("Failed to open memory library '{library}'"))?;
+        let _ = print_info(&format!(
+            "Library '{library}' is embedded with {}",
+            coordinator.embedding_model().info().registry_key
+        ));
+
+        let report = coordinator
+            .fsck(self.args.fsck_repair)
+            .await
+            .with_context(|| format!("Failed to check memory library '{library}'"))?;
+        println!("{report}");
+
+        match report.unrepaired() {
+            0 if report.is_clean() => {
+                let _ = print_success(&format!("Library '{library}' is consistent"));
+                Ok(())
+            }
+            0 => {
+                let _ = print_success(&format!("Repaired {} issue(s)", report.issues.len()));
+                Ok(())
+            }
+            remaining => {
+                if !self.args.fsck_repair {
+                    let _ = print_warning("Run again with --repair to fix them");
+                }
+                Err(anyhow::anyhow!(
+                    "{remaining} issue(s) remain in memory library '{library}'"
+                ))
+            }
+        }
+    }
+
+    /// Print the size of the model cache, repo by repo
+    fn report_model_cache(&self) -> Result<()> {
+        use crate::domain::model::hf_cache::cache_report;
+
+        let report = cache_report().context("Failed to read the model cache")?;
+        print!("{report}");
+        Ok(())
+    }
+
+    /// Prune the model cache to `--max-size`, or the configured cap
+    ///
+    /// Repos leased by other running processes, such as a server with the
+    /// model loaded, are kept.
+    fn prune_model_cache(&self) -> Result<()> {
+        use crate::domain::model::hf_cache::{
+            MODEL_CACHE_MAX_BYTES_ENV, max_cache_bytes, parse_size, prune,
+        };
+
+        let max_bytes = match self.args.prune_max_size.as_deref() {
+            Some(size) => parse_size(size)
+                .with_context(|| format!("Invalid --max-size '{size}'"))?,
+            None => max_cache_bytes().with_context(|| {
+                format!("Pass --max-size or set {MODEL_CACHE_MAX_BYTES_ENV}")
+            })?,
+        };
+        let report = prune(max_bytes).context("Failed to prune the model cache")?;
+        print!("{report}");
+        Ok(())
+    }
+
     /// Run the CLI application using fluent API
     pub async fn run(&mut self) -> Result<()> {
-        use tokio_stream::StreamExt;
+        crate::runtime::crash::install();
+        let result = self.run_command().await;
+        if let Err(e) = &result {
+            crate::runtime::crash::report_error("CLI stopped", e);
+        }
+        result
+    }
 
+    async fn run_command(&mut self) -> Result<()> {
         // Initialize pool maintenance thread (lazy init)
         crate::capability::registry::pool::init_maintenance();
 
-        // Setup Ctrl+C handler for IMMEDIATE exit
+        // Pools and sessions live in the server, so the dashboard has to run there
+        if self.args.dashboard {
+            anyhow::bail!(
+                "The dashboard shows the server's pools and sessions; start it with \
+                 `kodegen-candle-agent --dashboard`"
+            );
+        }
+
+        if let Some(path) = self.args.workflow.clone() {
+            return self.run_workflow(&path).await;
+        }
+
+        if let Some(dir) = self.args.template_goldens.clone() {
+            return self.write_template_goldens(&dir);
+        }
+
+        if let Some(library) = self.args.fsck_library.clone() {
+            #[cfg(feature = "memory")]
+            return self.run_fsck(&library).await;
+            #[cfg(not(feature = "memory"))]
+            anyhow::bail!("`memory fsck {library}` requires the `memory` feature");
+        }
+
+        if self.args.model_cache {
+            return self.report_model_cache();
+        }
+
+        if self.args.model_prune {
+            return self.prune_model_cache();
+        }
+
+        // One Ctrl+C stops the reply being streamed; at the prompt it exits
+        let streaming: Arc<Mutex<Option<TurnInterrupt>>> = Arc::new(Mutex::new(None));
+        let ctrlc_streaming = streaming.clone();
         ctrlc::set_handler(move || {
-            eprintln!("\n\nExiting...");
-            std::process::exit(0);
+            let active = ctrlc_streaming.lock().ok().and_then(|s| s.clone());
+            match active {
+                Some(interrupt) if !interrupt.is_interrupted() => interrupt.interrupt(),
+                _ => {
+                    eprintln!("\n\nExiting...");
+                    std::process::exit(0);
+                }
+            }
         })
         .map_err(|e| anyhow::anyhow!("Failed to set Ctrl-C handler: {}", e))?;
 
@@ -64,7 +274,7 @@ impl CliRunner {
         println!("\n╭─────────────────────────────────────╮");
         println!("│  🤖  Interactive AI Chat           │");
         println!("╰─────────────────────────────────────╯");
-        println!("\nType /help for commands • Ctrl+C to exit\n");
+        println!("\nType /help for commands • Ctrl+C stops a reply, or exits at the prompt\n");
 
         // Resolve system prompt using smart input resolution
         let system_prompt = if let Some(ref prompt_input) = self.args.system_prompt {
@@ -88,176 +298,242 @@ You are a master at refactoring code, remembering to check for code that ALREADY
             )
         };
 
-        // Use async closure with direct tokio stdin reading (prepare handler first)
-        let handler = std::sync::Arc::new(std::sync::Mutex::new(self.handler.clone()));
-
-        // Build agent and compute stream directly in each branch to avoid opaque type mismatch
-        let stream = if let Some(registry_key) = &self.args.model {
-            use crate::capability::registry::{self, TextToTextModel};
-
-            let text_model = registry::get::<TextToTextModel>(registry_key)
-                .ok_or_else(|| anyhow::anyhow!("Model not found in registry: {}", registry_key))?;
-
-            CandleFluentAi::agent_role(&self.args.agent_role)
-                .into_agent()?
-                .model(text_model)
-                .temperature(self.args.temperature)
-                .system_prompt(system_prompt.clone())
-                .memory_read_timeout(self.args.memory_read_timeout)
-                .max_tokens(self.args.max_tokens.unwrap_or(2000))
-                .on_chunk(|chunk| async move {
-                    use crate::domain::chat::message::CandleMessageChunk;
-                    if let CandleMessageChunk::Text(ref text) = chunk {
-                        print!("{}", text);
-                        let _ = std::io::stdout().flush();
+        let handler = Arc::new(Mutex::new(self.handler.clone()));
+
+        // Shared with the chat closure so the consumer can measure time to first token
+        let turn_started: Arc<Mutex<Option<Instant>>> = Arc::new(Mutex::new(None));
+        let exit_requested = Arc::new(AtomicBool::new(false));
+        let trace_verbosity = if self.args.trace {
+            Verbosity::Full
+        } else {
+            Verbosity::Off
+        };
+
+        // Every turn of this run is stored under one conversation
+        let conversation_id = uuid::Uuid::new_v4().to_string();
+
+        while !exit_requested.load(Ordering::Relaxed) {
+            let interrupt = TurnInterrupt::new();
+            let read_turn = Self::read_turn(
+                handler.clone(),
+                turn_started.clone(),
+                exit_requested.clone(),
+                streaming.clone(),
+                interrupt.clone(),
+            );
+
+            // Build agent and compute stream directly in each branch to avoid opaque type mismatch
+            let stream = if let Some(registry_key) = &self.args.model {
+                use crate::capability::registry::{self, TextToTextModel};
+
+                let text_model = registry::get::<TextToTextModel>(registry_key).ok_or_else(|| {
+                    anyhow::anyhow!("Model not found in registry: {}", registry_key)
+                })?;
+
+                CandleFluentAi::agent_role(&self.args.agent_role)
+                    .into_agent()?
+                    .model(text_model)
+                    .temperature(self.args.temperature)
+                    .system_prompt(system_prompt.clone())
+                    .memory_read_timeout(self.args.memory_read_timeout)
+                    .max_tokens(self.args.max_tokens.unwrap_or(2000))
+                    .interruptible(interrupt)
+                    .trace_verbosity(trace_verbosity)
+                    .session_id(conversation_id.clone())
+                    .chat(read_turn)?
+            } else {
+                CandleFluentAi::agent_role(&self.args.agent_role)
+                    .into_agent()?
+                    .temperature(self.args.temperature)
+                    .system_prompt(system_prompt.clone())
+                    .memory_read_timeout(self.args.memory_read_timeout)
+                    .max_tokens(self.args.max_tokens.unwrap_or(2000))
+                    .interruptible(interrupt)
+                    .trace_verbosity(trace_verbosity)
+                    .session_id(conversation_id.clone())
+                    .chat(read_turn)?
+            };
+            Self::render_turn(stream, &turn_started).await?;
+
+            // Back at the prompt: Ctrl+C exits again
+            if let Ok(mut active) = streaming.lock() {
+                *active = None;
+            }
+        }
+
+        // Save config on exit
+        self.save_config()?;
+
+        Ok(())
+    }
+
+    /// Read the next line from stdin and turn it into a chat loop action
+    ///
+    /// A chat message arms `interrupt`, so Ctrl+C stops its reply instead of
+    /// exiting; `/exit`, EOF and input errors end the session.
+    fn read_turn(
+        handler: Arc<Mutex<InputHandler>>,
+        turn_started: Arc<Mutex<Option<Instant>>>,
+        exit_requested: Arc<AtomicBool>,
+        streaming: Arc<Mutex<Option<TurnInterrupt>>>,
+        interrupt: TurnInterrupt,
+    ) -> impl Fn(&CandleAgentConversation) -> BoxFuture<'static, CandleChatLoop>
+    + Send
+    + Sync
+    + 'static {
+        move |_conversation| {
+            let handler = Arc::clone(&handler);
+            let turn_started = Arc::clone(&turn_started);
+            let exit_requested = Arc::clone(&exit_requested);
+            let streaming = Arc::clone(&streaming);
+            let interrupt = interrupt.clone();
+            Box::pin(async move {
+                use tokio::io::{AsyncBufReadExt, BufReader};
+
+                print!("\n> You: ");
+                let _ = std::io::stdout().flush();
+
+                let stdin = tokio::io::stdin();
+                let mut reader = BufReader::new(stdin);
+                let mut input = String::new();
+
+                let handler_result = match reader.read_line(&mut input).await {
+                    Ok(0) => InputHandlerResult::Exit, // EOF
+                    Ok(_) => match handler.lock() {
+                        Ok(mut h) => h.handle(input.trim()),
+                        Err(_) => InputHandlerResult::Exit,
+                    },
+                    Err(e) => {
+                        eprintln!("Input error: {}", e);
+                        InputHandlerResult::Exit
                     }
-                    chunk
-                })
-                .chat(move |_conversation| {
-                    let handler = handler.clone();
-                    async move {
-                        use tokio::io::{AsyncBufReadExt, BufReader};
-
-                        print!("\n> You: ");
-                        let _ = std::io::stdout().flush();
-
-                        let stdin = tokio::io::stdin();
-                        let mut reader = BufReader::new(stdin);
-                        let mut input = String::new();
-
-                        match reader.read_line(&mut input).await {
-                            Ok(0) => CandleChatLoop::Break, // EOF
-                            Ok(_) => {
-                                let input = input.trim();
-
-                                // Handle input via InputHandler
-                                let handler_result = match handler.lock() {
-                                    Ok(mut h) => h.handle(input),
-                                    Err(_) => InputHandlerResult::Exit,
-                                };
-
-                                match handler_result {
-                                    InputHandlerResult::Exit => {
-                                        println!("Goodbye!");
-                                        CandleChatLoop::Break
-                                    }
-                                    InputHandlerResult::Command(cmd_result) => {
-                                        let output = Self::format_command_result(&cmd_result);
-                                        println!("{}", output);
-                                        CandleChatLoop::Reprompt(String::new())
-                                    }
-                                    InputHandlerResult::None => {
-                                        CandleChatLoop::Reprompt(String::new())
-                                    }
-                                    InputHandlerResult::Chat(message) => {
-                                        CandleChatLoop::UserPrompt(message)
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Input error: {}", e);
-                                CandleChatLoop::Break
-                            }
-                        }
+                };
+
+                match handler_result {
+                    InputHandlerResult::Exit => {
+                        println!("Goodbye!");
+                        exit_requested.store(true, Ordering::Relaxed);
+                        CandleChatLoop::Break
                     }
-                })?
-        } else {
-            CandleFluentAi::agent_role(&self.args.agent_role)
-                .into_agent()?
-                .temperature(self.args.temperature)
-                .system_prompt(system_prompt.clone())
-                .memory_read_timeout(self.args.memory_read_timeout)
-                .max_tokens(self.args.max_tokens.unwrap_or(2000))
-                .on_chunk(|chunk| async move {
-                    use crate::domain::chat::message::CandleMessageChunk;
-                    if let CandleMessageChunk::Text(ref text) = chunk {
-                        print!("{}", text);
-                        let _ = std::io::stdout().flush();
+                    InputHandlerResult::Command(cmd_result) => {
+                        let output = Self::format_command_result(&cmd_result);
+                        println!("{}", output);
+                        CandleChatLoop::Reprompt(String::new())
                     }
-                    chunk
-                })
-                .chat(move |_conversation| {
-                    let handler = handler.clone();
-                    async move {
-                        use tokio::io::{AsyncBufReadExt, BufReader};
-
-                        print!("\n> You: ");
-                        let _ = std::io::stdout().flush();
-
-                        let stdin = tokio::io::stdin();
-                        let mut reader = BufReader::new(stdin);
-                        let mut input = String::new();
-
-                        match reader.read_line(&mut input).await {
-                            Ok(0) => CandleChatLoop::Break, // EOF
-                            Ok(_) => {
-                                let input = input.trim();
-
-                                // Handle input via InputHandler
-                                let handler_result = match handler.lock() {
-                                    Ok(mut h) => h.handle(input),
-                                    Err(_) => InputHandlerResult::Exit,
-                                };
-
-                                match handler_result {
-                                    InputHandlerResult::Exit => {
-                                        println!("Goodbye!");
-                                        CandleChatLoop::Break
-                                    }
-                                    InputHandlerResult::Command(cmd_result) => {
-                                        let output = Self::format_command_result(&cmd_result);
-                                        println!("{}", output);
-                                        CandleChatLoop::Reprompt(String::new())
-                                    }
-                                    InputHandlerResult::None => {
-                                        CandleChatLoop::Reprompt(String::new())
-                                    }
-                                    InputHandlerResult::Chat(message) => {
-                                        CandleChatLoop::UserPrompt(message)
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Input error: {}", e);
-                                CandleChatLoop::Break
-                            }
-                        }
+                    InputHandlerResult::None => CandleChatLoop::Reprompt(String::new()),
+                    InputHandlerResult::Chat(message) => {
+                        Self::arm_turn(&turn_started, &streaming, interrupt);
+                        CandleChatLoop::UserPrompt(message)
                     }
-                })?
-        };
-        tokio::pin!(stream);
+                    InputHandlerResult::Regenerate(options) => {
+                        Self::arm_turn(&turn_started, &streaming, interrupt);
+                        CandleChatLoop::Regenerate(options)
+                    }
+                }
+            })
+        }
+    }
+
+    /// Start timing a submitted turn and let Ctrl+C stop it
+    fn arm_turn(
+        turn_started: &Mutex<Option<Instant>>,
+        streaming: &Mutex<Option<TurnInterrupt>>,
+        interrupt: TurnInterrupt,
+    ) {
+        if let Ok(mut started) = turn_started.lock() {
+            *started = Some(Instant::now());
+        }
+        if let Ok(mut active) = streaming.lock() {
+            *active = Some(interrupt);
+        }
+    }
+
+    /// Consume one turn's stream, rendering markdown incrementally as tokens arrive
+    async fn render_turn(
+        mut stream: Pin<Box<dyn Stream<Item = CandleMessageChunk> + Send>>,
+        turn_started: &Mutex<Option<Instant>>,
+    ) -> Result<()> {
+        let mut out = render::stdout_stream();
+        let mut renderer = MarkdownStreamRenderer::new();
+        let mut spinner: Option<ToolSpinner> = None;
+        let mut first_token: Option<Duration> = None;
 
-        // Consume stream
         println!("\n💭 ");
         while let Some(chunk) = stream.next().await {
-            use crate::domain::chat::message::CandleMessageChunk;
+            if !matches!(
+                chunk,
+                CandleMessageChunk::ToolCallStart { .. } | CandleMessageChunk::ToolCall { .. }
+            ) && let Some(active) = spinner.take()
+            {
+                active.stop().await;
+            }
+
             match chunk {
-                CandleMessageChunk::Text(_) => {
-                    // Text already printed via on_chunk handler
+                CandleMessageChunk::Text(text) => {
+                    if first_token.is_none() {
+                        first_token = Self::elapsed_since(turn_started);
+                    }
+                    renderer.push(&mut out, &text)?;
+                }
+                CandleMessageChunk::Complete { finish_reason, .. }
+                    if finish_reason.as_deref() == Some(INTERRUPTED_FINISH_REASON) =>
+                {
+                    renderer.finish(&mut out)?;
+                    println!();
+                    let _ = print_info("  ⏹ Stopped");
+                    println!();
                 }
-                CandleMessageChunk::Complete { text, .. } => {
+                CandleMessageChunk::Complete {
+                    text,
+                    token_count,
+                    tokens_per_sec,
+                    ..
+                } => {
                     if !text.is_empty() {
-                        print!("{}", text);
+                        if first_token.is_none() {
+                            first_token = Self::elapsed_since(turn_started);
+                        }
+                        renderer.push(&mut out, &text)?;
+                    }
+                    renderer.finish(&mut out)?;
+                    println!();
+                    if let Some(stats) =
+                        render::format_turn_stats(token_count, tokens_per_sec, first_token.take())
+                    {
+                        let _ = print_info(&format!("  {}", stats));
+                    }
+                    println!();
+                }
+                CandleMessageChunk::Trace(trace) => {
+                    renderer.finish(&mut out)?;
+                    for line in trace.to_string().lines() {
+                        let _ = print_info(&format!("  🔍 {}", line));
                     }
-                    println!("\n");
                 }
                 CandleMessageChunk::Error(err) => {
+                    renderer.finish(&mut out)?;
                     eprintln!("\n❌ {}", err);
                 }
                 CandleMessageChunk::ToolCallStart { name, .. } => {
-                    println!("\n🔧 {}", name);
+                    renderer.finish(&mut out)?;
+                    println!();
+                    spinner = Some(ToolSpinner::start(&name));
                 }
-                CandleMessageChunk::ToolCallComplete { .. } => {}
                 _ => {}
             }
         }
-
-        // Save config on exit
-        self.save_config()?;
-
+        if let Some(active) = spinner.take() {
+            active.stop().await;
+        }
+        renderer.finish(&mut out)?;
         Ok(())
     }
 
+    /// Time elapsed since the current turn was submitted
+    fn elapsed_since(started: &Mutex<Option<Instant>>) -> Option<Duration> {
+        started.lock().ok().and_then(|s| s.map(|t| t.elapsed()))
+    }
+
     /// Format command result for display
     fn format_command_result(result: &CommandResult) -> String {
         match result {
@@ -64,3 +64,162 @@ pub fn detect_best_device() -> candle_core::Result<Device> {
     info!("Using CPU for inference (no GPU available or GPU initialization failed)");
     Ok(Device::Cpu)
 }
+
+/// Environment variable choosing the device Stella embeddings run on
+///
+/// Accepts `auto` (default), `cpu`, `cuda`, `cuda:N`, `metal` or `metal:N`.
+pub const EMBEDDING_DEVICE_ENV: &str = "KODEGEN_EMBEDDING_DEVICE";
+
+/// Requested compute device
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DevicePreference {
+    /// Best available device, as [`detect_best_device`]
+    #[default]
+    Auto,
+    /// Always the CPU
+    Cpu,
+    /// CUDA GPU with this ordinal
+    Cuda(usize),
+    /// Metal GPU with this ordinal
+    Metal(usize),
+}
+
+/// A device preference string was not recognized
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown device '{0}' (expected auto, cpu, cuda[:N] or metal[:N])")]
+pub struct UnknownDevice(pub String);
+
+impl std::str::FromStr for DevicePreference {
+    type Err = UnknownDevice;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        let (kind, ordinal) = match s.split_once(':') {
+            Some((kind, ordinal)) => (
+                kind,
+                ordinal
+                    .parse::<usize>()
+                    .map_err(|_| UnknownDevice(s.clone()))?,
+            ),
+            None => (s.as_str(), 0),
+        };
+        match kind {
+            "" | "auto" => Ok(Self::Auto),
+            "cpu" => Ok(Self::Cpu),
+            "cuda" => Ok(Self::Cuda(ordinal)),
+            "metal" => Ok(Self::Metal(ordinal)),
+            _ => Err(UnknownDevice(s.clone())),
+        }
+    }
+}
+
+impl DevicePreference {
+    /// Preference set in the environment variable `var`, else [`Self::Auto`]
+    ///
+    /// An unrecognized value is logged and treated as `Auto`.
+    pub fn from_env(var: &str) -> Self {
+        match std::env::var(var) {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                log::warn!("Ignoring {}: {}", var, e);
+                Self::Auto
+            }),
+            Err(_) => Self::Auto,
+        }
+    }
+}
+
+/// Returns the device matching `preference`.
+///
+/// A GPU that is not compiled in (`cuda` / `metal` features) or fails to
+/// initialize falls back to the CPU with a warning, so a misconfigured
+/// machine still runs.
+pub fn select_device(preference: DevicePreference) -> candle_core::Result<Device> {
+    let requested = match preference {
+        DevicePreference::Auto => return detect_best_device(),
+        DevicePreference::Cpu => return Ok(Device::Cpu),
+        DevicePreference::Cuda(ordinal) => Device::new_cuda(ordinal),
+        DevicePreference::Metal(ordinal) => Device::new_metal(ordinal),
+    };
+    match requested {
+        Ok(device) => {
+            info!("Using requested device {:?} for inference", preference);
+            Ok(device)
+        }
+        Err(e) => {
+            log::warn!(
+                "Requested device {:?} unavailable: {}. Using CPU.",
+                preference,
+                e
+            );
+            Ok(Device::Cpu)
+        }
+    }
+}
+
+/// Environment variable requesting flash attention at model load time
+///
+/// Accepts `1`/`true`/`yes`/`on`; anything else (or unset) leaves it off.
+/// Only architectures with flash-attention kernels honour it, and only on
+/// devices where [`flash_attention_available`] holds.
+pub const FLASH_ATTN_ENV: &str = "KODEGEN_FLASH_ATTN";
+
+/// Whether [`FLASH_ATTN_ENV`] requests flash attention
+pub fn flash_attention_requested() -> bool {
+    std::env::var(FLASH_ATTN_ENV).is_ok_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    })
+}
+
+/// Whether `model` loads with flash attention on `device`
+///
+/// `has_kernels` says whether the model's architecture implements flash
+/// attention at all. A request that cannot be honoured is logged and falls
+/// back to standard attention.
+pub fn resolve_flash_attention(
+    model: &str,
+    requested: bool,
+    has_kernels: bool,
+    device: &Device,
+) -> bool {
+    if !requested {
+        return false;
+    }
+    if !has_kernels {
+        log::warn!(
+            "Flash attention requested for {} but its architecture has no flash-attn kernels; using standard attention",
+            model
+        );
+        return false;
+    }
+    if !flash_attention_available(device) {
+        log::warn!(
+            "Flash attention requested for {} but unsupported on {:?}; using standard attention",
+            model,
+            device
+        );
+        return false;
+    }
+    info!("Loading {} with flash attention", model);
+    true
+}
+
+/// Returns `true` when flash-attention kernels can run on `device`.
+///
+/// Candle only ships flash-attn kernels for CUDA, so this requires both the
+/// `flash-attn` feature and a CUDA device. Callers should treat a `false`
+/// result as "fall back to standard attention", never as an error.
+pub fn flash_attention_available(device: &Device) -> bool {
+    #[cfg(feature = "flash-attn")]
+    {
+        device.is_cuda()
+    }
+
+    #[cfg(not(feature = "flash-attn"))]
+    {
+        let _ = device;
+        false
+    }
+}
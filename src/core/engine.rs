@@ -13,7 +13,9 @@ use crate::async_stream;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio_stream::Stream;
+use tokio_util::sync::CancellationToken;
 
+use super::model_manager::{ManagedModel, ModelManager, shared_model_manager};
 use crate::domain::context::chunks::{CandleCompletionChunk, CandleStringChunk};
 use crate::domain::model::CandleUsage;
 
@@ -58,6 +60,15 @@ pub enum EngineError {
     /// The provided input is invalid or malformed
     InvalidInput,
 
+    #[error("Model needs {required_mb} MB but only {available_mb} MB of the budget is free")]
+    /// Loading the model would exceed the model manager's memory budget
+    MemoryBudgetExceeded {
+        /// Estimated memory of the model, in MB
+        required_mb: usize,
+        /// Budget left for new models, in MB
+        available_mb: usize,
+    },
+
     #[error("Service unavailable")]
     /// The engine service is temporarily unavailable
     ServiceUnavailable,
@@ -208,6 +219,7 @@ pub struct Engine {
     successful_requests: Arc<AtomicU64>,
     failed_requests: Arc<AtomicU64>,
     is_healthy: Arc<AtomicBool>,
+    models: Arc<ModelManager>,
 }
 
 impl Engine {
@@ -223,15 +235,43 @@ impl Engine {
             successful_requests: Arc::new(AtomicU64::new(0)),
             failed_requests: Arc::new(AtomicU64::new(0)),
             is_healthy: Arc::new(AtomicBool::new(true)),
+            models: shared_model_manager(),
         })
     }
 
+    /// Route prompts through `models` instead of the process-wide manager
+    #[must_use]
+    pub fn with_model_manager(mut self, models: Arc<ModelManager>) -> Self {
+        self.models = models;
+        self
+    }
+
     /// Get immutable reference to configuration
     #[inline]
     pub fn config(&self) -> &EngineConfig {
         &self.config
     }
 
+    /// Models loaded alongside the configured one
+    #[inline]
+    pub fn models(&self) -> &Arc<ModelManager> {
+        &self.models
+    }
+
+    /// The chat model a prompt should run on
+    ///
+    /// See [`ModelManager::route_model`]; the configured `registry_key` is
+    /// the fallback.
+    ///
+    /// # Errors
+    ///
+    /// As for [`ModelManager::load`].
+    pub async fn route_model(&self, registry_key: Option<&str>) -> EngineResult<ManagedModel> {
+        self.models
+            .route_model(registry_key, &self.config.registry_key)
+            .await
+    }
+
     /// Get current request count (atomic read)
     #[inline]
     pub fn request_count(&self) -> u64 {
@@ -301,8 +341,14 @@ impl Engine {
     /// - Custom completion logic requiring direct control over chunk types
     ///
     /// This bypasses the text-to-completion conversion and provides metrics tracking only.
+    /// Forwarding stops as soon as `cancellation` is cancelled or the returned
+    /// stream is dropped, and the provider's stream is dropped with it. A
+    /// provider stream that ends without an error or a final
+    /// [`CandleCompletionChunk::Complete`] gets one, so consumers can rely on
+    /// it to mark the end of the reply.
     pub fn coordinate_completion<F, S>(
         &self,
+        cancellation: CancellationToken,
         generation_fn: F,
     ) -> impl Stream<Item = CandleCompletionChunk> + use<F, S>
     where
@@ -324,12 +370,32 @@ impl Engine {
         async_stream::spawn_stream(move |tx| async move {
             use tokio_stream::StreamExt;
             let mut has_error = false;
+            let mut completed = false;
+            let mut cancelled = false;
             let mut stream = Box::pin(completion_stream);
 
-            while let Some(chunk) = stream.next().await {
+            loop {
+                let chunk = tokio::select! {
+                    biased;
+                    () = cancellation.cancelled() => {
+                        log::debug!("Completion cancelled");
+                        cancelled = true;
+                        break;
+                    }
+                    () = tx.closed() => {
+                        // Client disconnected
+                        has_error = true;
+                        break;
+                    }
+                    chunk = stream.next() => chunk,
+                };
+                let Some(chunk) = chunk else { break };
+
                 // Check for error chunks
-                if matches!(chunk, CandleCompletionChunk::Error(_)) {
-                    has_error = true;
+                match chunk {
+                    CandleCompletionChunk::Error(_) => has_error = true,
+                    CandleCompletionChunk::Complete { .. } => completed = true,
+                    _ => {}
                 }
 
                 if tx.send(chunk).is_err() {
@@ -339,6 +405,19 @@ impl Engine {
                 }
             }
 
+            if !completed && !has_error && !cancelled {
+                let _ = tx.send(CandleCompletionChunk::Complete {
+                    text: String::new(),
+                    finish_reason: Some(crate::domain::context::chunks::FinishReason::Stop),
+                    usage: None,
+                    token_count: None,
+                    elapsed_secs: None,
+                    tokens_per_sec: None,
+                    mean_logprob: None,
+                    flash_attention: None,
+                });
+            }
+
             // Update completion metrics
             active_requests.fetch_sub(1, Ordering::Relaxed);
             if has_error {
@@ -406,6 +485,8 @@ impl Engine {
                             token_count: Some(gen_stats.tokens_generated),
                             elapsed_secs: Some(gen_stats.elapsed_secs),
                             tokens_per_sec: Some(gen_stats.tokens_per_sec),
+                            mean_logprob: gen_stats.mean_logprob,
+                            flash_attention: Some(gen_stats.flash_attention),
                         }
                     }
                     CandleStringChunk {
@@ -426,6 +507,8 @@ impl Engine {
                             token_count: None,
                             elapsed_secs: None,
                             tokens_per_sec: None,
+                            mean_logprob: None,
+                            flash_attention: None,
                         }
                     }
                 };
@@ -507,6 +590,7 @@ impl Default for Engine {
             successful_requests: Arc::new(AtomicU64::new(0)),
             failed_requests: Arc::new(AtomicU64::new(0)),
             is_healthy: Arc::new(AtomicBool::new(true)),
+            models: shared_model_manager(),
         }
     }
 }
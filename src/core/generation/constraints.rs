@@ -0,0 +1,125 @@
+//! Public constructors for generation constraints
+//!
+//! A [`SchemaConstraint`] masks every token that would take the output off a
+//! JSON schema or regular expression. Pass one to
+//! `LoadedQwen3QuantizedModel::prompt_with_context` together with the model's
+//! `tokenizer()` to get structured output without the extractor builder.
+//! Agents get the same for every reply with `.response_format::<T>()`.
+//!
+//! ```rust,no_run
+//! use kodegen_candle_agent::capability::text_to_text::qwen3_quantized::LoadedQwen3QuantizedModel;
+//! use kodegen_candle_agent::constraints::constraint_for_type;
+//!
+//! #[derive(serde::Deserialize, schemars::JsonSchema)]
+//! struct Verdict {
+//!     approved: bool,
+//!     reason: String,
+//! }
+//!
+//! async fn review(model: &LoadedQwen3QuantizedModel, prompt: String) -> anyhow::Result<Verdict> {
+//!     let constraint = constraint_for_type::<Verdict>(model.tokenizer())?;
+//!     let json = model.prompt_with_context(prompt, constraint).await?;
+//!     Ok(serde_json::from_str(&json)?)
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use kodegen_simd::logits::constraints::SchemaVocabulary;
+use schemars::JsonSchema;
+use serde_json::Value;
+use tokenizers::Tokenizer;
+
+pub use kodegen_simd::logits::constraints::SchemaConstraint;
+
+/// Errors building a generation constraint
+#[derive(Debug, thiserror::Error)]
+pub enum ConstraintError {
+    /// Schema is not a JSON object or boolean
+    #[error("Invalid JSON schema: {0}")]
+    InvalidSchema(String),
+    /// Pattern does not compile
+    #[error("Invalid regex: {0}")]
+    InvalidRegex(#[from] regex::Error),
+    /// Constraint index could not be built for this vocabulary
+    #[error("Failed to build constraint: {0}")]
+    Build(String),
+}
+
+/// Result alias for constraint construction
+pub type ConstraintResult<T> = Result<T, ConstraintError>;
+
+/// Constraint matching the JSON schema derived from `T`
+pub fn constraint_for_type<T: JsonSchema>(tokenizer: &Tokenizer) -> ConstraintResult<SchemaConstraint> {
+    let schema = serde_json::to_value(schemars::schema_for!(T))
+        .map_err(|e| ConstraintError::InvalidSchema(e.to_string()))?;
+    constraint_from_schema(&schema, tokenizer)
+}
+
+/// Constraint matching an arbitrary JSON schema
+pub fn constraint_from_schema(schema: &Value, tokenizer: &Tokenizer) -> ConstraintResult<SchemaConstraint> {
+    validate_schema(schema)?;
+    kodegen_simd::serde_constraints::constraint_for_schema(&schema.to_string(), tokenizer)
+        .map_err(|e| ConstraintError::Build(e.to_string()))
+}
+
+/// Constraint matching a regular expression over the whole output
+///
+/// The pattern is implicitly anchored at both ends.
+pub fn constraint_from_regex(pattern: &str, tokenizer: &Tokenizer) -> ConstraintResult<SchemaConstraint> {
+    validate_regex(pattern)?;
+    let vocabulary = SchemaVocabulary::from_tokenizer(tokenizer)
+        .map_err(|e| ConstraintError::Build(e.to_string()))?;
+    SchemaConstraint::new(pattern, Arc::new(vocabulary), false)
+        .map_err(|e| ConstraintError::Build(e.to_string()))
+}
+
+/// Constraint accepting exactly one of `choices`
+pub fn constraint_for_choice<S: AsRef<str>>(
+    choices: &[S],
+    tokenizer: &Tokenizer,
+) -> ConstraintResult<SchemaConstraint> {
+    if choices.is_empty() {
+        return Err(ConstraintError::Build("No choices given".to_string()));
+    }
+    constraint_from_regex(&choice_pattern(choices), tokenizer)
+}
+
+/// Check that `schema` can describe a document before building an index for it
+pub fn validate_schema(schema: &Value) -> ConstraintResult<()> {
+    match schema {
+        Value::Object(_) | Value::Bool(_) => Ok(()),
+        other => Err(ConstraintError::InvalidSchema(format!(
+            "expected an object or boolean, got {other}"
+        ))),
+    }
+}
+
+/// Check that `pattern` compiles
+pub fn validate_regex(pattern: &str) -> ConstraintResult<()> {
+    regex::Regex::new(pattern)?;
+    Ok(())
+}
+
+/// `logits` with every token `allowed` rejects set to negative infinity
+///
+/// Applied after temperature and penalties, right before sampling, so the
+/// sampler only ever picks a token the constraint accepts.
+pub fn mask_logits(
+    logits: &candle_core::Tensor,
+    allowed: impl Fn(u32) -> bool,
+) -> candle_core::Result<candle_core::Tensor> {
+    let mut values = logits.to_dtype(candle_core::DType::F32)?.to_vec1::<f32>()?;
+    for (token, value) in values.iter_mut().enumerate() {
+        if !allowed(token as u32) {
+            *value = f32::NEG_INFINITY;
+        }
+    }
+    candle_core::Tensor::from_vec(values, logits.shape(), logits.device())?.to_dtype(logits.dtype())
+}
+
+/// Alternation matching any of `choices` literally
+pub fn choice_pattern<S: AsRef<str>>(choices: &[S]) -> String {
+    let alternatives: Vec<String> = choices.iter().map(|c| regex::escape(c.as_ref())).collect();
+    format!("({})", alternatives.join("|"))
+}
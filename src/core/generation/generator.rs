@@ -56,6 +56,12 @@ pub struct TextGenerator {
 
     /// Current JSON constraint state
     pub constraint_state: Option<JsonState>,
+
+    /// Sum of the log probabilities of the sampled tokens
+    logprob_sum: f64,
+
+    /// Tokens counted in `logprob_sum`
+    logprob_count: u32,
 }
 impl TextGenerator {
     /// Create new TextGenerator
@@ -66,6 +72,8 @@ impl TextGenerator {
         config: SamplingConfig,
     ) -> Self {
         let max_history = config.repetition_context_length;
+        let mut stats = GenerationStatistics::new();
+        stats.set_flash_attention_active(model.flash_attention_active());
 
         Self {
             model,
@@ -73,10 +81,12 @@ impl TextGenerator {
             device,
             config,
             token_history: TokenHistory::new(max_history),
-            stats: GenerationStatistics::new(),
+            stats,
             simd_metrics: SimdMetrics::new(),
             constraint: None,
             constraint_state: None,
+            logprob_sum: 0.0,
+            logprob_count: 0,
         }
     }
 
@@ -92,6 +102,8 @@ impl TextGenerator {
             tokens_generated: self.stats.total_tokens as u32,
             elapsed_secs: self.stats.total_duration.as_secs_f64(),
             tokens_per_sec: self.stats.tokens_per_second(),
+            mean_logprob: self.mean_logprob(),
+            flash_attention: self.stats.flash_attention_active,
         };
 
         log::debug!(
@@ -338,7 +350,7 @@ impl TextGenerator {
         let constraint_state = self.constraint_state.clone();
 
         // Wrap all SIMD operations in spawn_blocking for CPU-intensive work
-        let result = tokio::task::spawn_blocking(move || -> CandleResult<u32> {
+        let (result, probability) = tokio::task::spawn_blocking(move || -> CandleResult<(u32, f32)> {
             let mut logits = logits_owned;
 
             // Apply temperature scaling with SIMD
@@ -425,7 +437,7 @@ impl TextGenerator {
                 })?
             };
 
-            Ok(token as u32)
+            Ok((token as u32, probs.get(token).copied().unwrap_or(0.0)))
         })
         .await
         .map_err(|e| {
@@ -449,9 +461,20 @@ impl TextGenerator {
         self.simd_metrics.record_argmax_op();
         self.stats.record_simd_operation();
 
+        // Zero-probability picks (forced by constraints) would make the mean -inf
+        if probability > 0.0 {
+            self.logprob_sum += f64::from(probability).ln();
+            self.logprob_count += 1;
+        }
+
         Ok(result)
     }
 
+    /// Mean log probability of the tokens sampled so far
+    pub fn mean_logprob(&self) -> Option<f64> {
+        (self.logprob_count > 0).then(|| self.logprob_sum / f64::from(self.logprob_count))
+    }
+
     /// Check if generation should stop
     pub fn should_stop(&self, token: u32, special_tokens: &SpecialTokens) -> bool {
         special_tokens.is_eos(token)
@@ -472,6 +495,8 @@ impl TextGenerator {
         self.stats.reset();
         self.simd_metrics.reset();
         self.token_history.clear();
+        self.logprob_sum = 0.0;
+        self.logprob_count = 0;
     }
 
     /// Set JSON constraint for structured generation
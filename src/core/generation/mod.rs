@@ -10,6 +10,7 @@
 //!
 //! - [`types`] - Core types, aliases and constants
 //! - [`tokens`] - Token management and special token handling
+//! - [`constraints`] - Schema and regex constraints for structured output
 //! - [`config`] - Sampling configuration and parameter management
 //! - [`stats`] - Generation statistics and performance monitoring
 //! - [`metrics`] - SIMD-specific performance metrics
@@ -47,6 +48,7 @@
 
 // Public module declarations
 pub mod config;
+pub mod constraints;
 pub mod generator;
 pub mod metrics;
 pub mod models;
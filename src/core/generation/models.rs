@@ -68,6 +68,11 @@ pub trait CandleModel: Send + Sync {
     fn config(&self) -> Option<&CandleConfig> {
         None
     }
+
+    /// Whether the model was loaded with flash-attention kernels
+    fn flash_attention_active(&self) -> bool {
+        false
+    }
 }
 
 /// Llama model wrapper for Candle integration
@@ -90,6 +95,9 @@ pub struct CandleLlamaModel {
 
     /// Model vocabulary size
     vocab_size: usize,
+
+    /// Whether flash attention was enabled at load time
+    flash_attn: bool,
 }
 impl CandleLlamaModel {
     /// Create new CandleLlamaModel
@@ -106,6 +114,7 @@ impl CandleLlamaModel {
             device,
             config,
             vocab_size,
+            flash_attn: false,
         }
     }
 
@@ -118,8 +127,8 @@ impl CandleLlamaModel {
         let model_path = model_path.as_ref();
 
         // Extract the LlamaConfig from ModelConfig's architecture
-        let llama_config = match &config.architecture {
-            ModelArchitecture::Llama(llama_cfg) => llama_cfg,
+        let mut llama_config = match &config.architecture {
+            ModelArchitecture::Llama(llama_cfg) => llama_cfg.clone(),
             _ => {
                 return Err(CandleModelError::InvalidConfiguration(
                     "Expected Llama architecture in config".into(),
@@ -127,6 +136,15 @@ impl CandleLlamaModel {
             }
         };
 
+        // Flash attention is opt-in and only honoured where kernels exist
+        let use_flash_attn = crate::core::device_util::resolve_flash_attention(
+            &config.registry_key,
+            config.use_flash_attn,
+            true,
+            &device,
+        );
+        llama_config.use_flash_attn = use_flash_attn;
+
         // Determine if single or multi-file model
         let safetensors_files = if model_path.is_file() {
             // Single file provided directly
@@ -165,12 +183,12 @@ impl CandleLlamaModel {
         };
 
         // Create KV cache for efficient inference
-        let cache = Cache::new(true, config.dtype, llama_config, &device).map_err(|e| {
+        let cache = Cache::new(true, config.dtype, &llama_config, &device).map_err(|e| {
             CandleModelError::InvalidConfiguration(format!("Failed to create cache: {}", e).into())
         })?;
 
         // Load the Llama model from weights
-        let model = Llama::load(vb, llama_config).map_err(|e| {
+        let model = Llama::load(vb, &llama_config).map_err(|e| {
             CandleModelError::InvalidConfiguration(
                 format!("Failed to load Llama model: {}", e).into(),
             )
@@ -178,7 +196,9 @@ impl CandleLlamaModel {
 
         let vocab_size = config.vocab_size;
 
-        Ok(Self::new(model, cache, device, config, vocab_size))
+        let mut loaded = Self::new(model, cache, device, config, vocab_size);
+        loaded.flash_attn = use_flash_attn;
+        Ok(loaded)
     }
 
     /// Get the underlying Llama model
@@ -236,6 +256,10 @@ impl CandleModel for CandleLlamaModel {
     fn config(&self) -> Option<&CandleConfig> {
         Some(&self.config)
     }
+
+    fn flash_attention_active(&self) -> bool {
+        self.flash_attn
+    }
 }
 
 /// Quantized Llama model wrapper for GGUF models
@@ -555,6 +579,8 @@ pub struct CandleQuantizedPhiModel {
     vocab_size: usize,
     /// End-of-sequence token ID from GGUF metadata
     eos_token_id: Option<u32>,
+    /// Whether flash attention was enabled at load time
+    flash_attn: bool,
 }
 
 impl CandleQuantizedPhiModel {
@@ -570,6 +596,7 @@ impl CandleQuantizedPhiModel {
             device,
             vocab_size,
             eos_token_id,
+            flash_attn: false,
         }
     }
 
@@ -579,12 +606,24 @@ impl CandleQuantizedPhiModel {
     }
 
     /// Load a quantized Phi model from a GGUF file
+    ///
+    /// Flash attention is used when [`FLASH_ATTN_ENV`] requests it and the
+    /// device supports it.
+    ///
+    /// [`FLASH_ATTN_ENV`]: crate::core::device_util::FLASH_ATTN_ENV
     pub async fn from_gguf_path<P: AsRef<std::path::Path>>(
         model_path: P,
         device: Device,
     ) -> CandleResult<Self> {
         use candle_core::quantized::gguf_file;
 
+        let use_flash_attn = crate::core::device_util::resolve_flash_attention(
+            "quantized Phi",
+            crate::core::device_util::flash_attention_requested(),
+            true,
+            &device,
+        );
+
         // Open GGUF file
         let file = tokio::fs::File::open(model_path.as_ref())
             .await
@@ -635,8 +674,6 @@ impl CandleQuantizedPhiModel {
                 }
 
                 // Create model using quantized_phi3 (Phi-4 uses phi3.* metadata)
-                // Phi-4 does not support flash attention according to model docs
-                let use_flash_attn = false;
                 log::info!(
                     "Loading quantized_phi3 model with flash_attn={}",
                     use_flash_attn
@@ -664,7 +701,9 @@ impl CandleQuantizedPhiModel {
             })?;
 
         log::info!("✅ Phi model loaded successfully!");
-        Ok(Self::new(model, device, vocab_size, eos_token_id))
+        let mut loaded = Self::new(model, device, vocab_size, eos_token_id);
+        loaded.flash_attn = use_flash_attn;
+        Ok(loaded)
     }
 }
 
@@ -708,6 +747,10 @@ impl CandleModel for CandleQuantizedPhiModel {
     fn vocab_size(&self) -> usize {
         self.vocab_size
     }
+
+    fn flash_attention_active(&self) -> bool {
+        self.flash_attn
+    }
 }
 
 /// Helper function to discover model files from a multi-file model index
@@ -46,6 +46,9 @@ pub struct GenerationStatistics {
     /// Number of cache misses during generation
     pub cache_misses: u64,
 
+    /// Whether the model ran with flash-attention kernels
+    pub flash_attention_active: bool,
+
     /// Start time of generation for ongoing tracking
     generation_start: Option<Instant>,
 }
@@ -64,6 +67,7 @@ impl GenerationStatistics {
             peak_memory_bytes: 0,
             cache_hits: 0,
             cache_misses: 0,
+            flash_attention_active: false,
             generation_start: None,
         }
     }
@@ -131,6 +135,11 @@ impl GenerationStatistics {
         self.cache_misses += 1;
     }
 
+    /// Record whether flash attention is active for this generation
+    pub fn set_flash_attention_active(&mut self, active: bool) {
+        self.flash_attention_active = active;
+    }
+
     /// Calculate tokens per second for total generation
     pub fn tokens_per_second(&self) -> f64 {
         if self.total_duration.as_secs_f64() > 0.0 {
@@ -190,8 +199,13 @@ impl GenerationStatistics {
     }
 
     /// Reset all statistics
+    ///
+    /// Flash-attention state describes the loaded model rather than a single
+    /// run, so it survives the reset.
     pub fn reset(&mut self) {
+        let flash_attention_active = self.flash_attention_active;
         *self = Self::new();
+        self.flash_attention_active = flash_attention_active;
     }
 }
 impl Default for GenerationStatistics {
@@ -192,6 +192,9 @@ pub mod engine;
 /// Advanced constrained generation with sampling strategies
 pub mod generation;
 
+/// Several concurrently loaded models with shared tokenizers
+pub mod model_manager;
+
 /// Unified model configuration system for hundreds of models
 pub mod model_config;
 
@@ -205,8 +208,11 @@ pub mod tokenizer;
 pub use engine::*;
 pub use generation::*;
 pub use model_config::*;
+pub use model_manager::{
+    ManagedModel, ModelManager, ModelManagerStats, ModelRole, shared_model_manager,
+};
 pub use simd_adapters::{
     should_use_simd, simd_argmax_with_bounds, simd_error_to_fallback_strategy,
     simd_softmax_with_cache, simd_temperature_scale,
 };
-pub use tokenizer::{CandleTokenizer, CandleTokenizerConfig};
+pub use tokenizer::{CandleTokenizer, CandleTokenizerConfig, TokenizerCache, shared_tokenizer_cache};
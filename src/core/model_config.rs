@@ -32,6 +32,12 @@ pub struct ModelConfig {
     pub registry_key: String,
     /// Model provider identifier
     pub provider_name: String,
+    /// Request flash attention at load time (honoured only when the
+    /// device and build support it, see [`Self::flash_attention_enabled`]);
+    /// defaults to [`FLASH_ATTN_ENV`]
+    ///
+    /// [`FLASH_ATTN_ENV`]: crate::core::device_util::FLASH_ATTN_ENV
+    pub use_flash_attn: bool,
 }
 
 impl ModelConfig {
@@ -55,6 +61,7 @@ impl ModelConfig {
             dtype: DType::F16, // Default to F16 for efficiency
             registry_key: registry_key.into(),
             provider_name: provider_name.into(),
+            use_flash_attn: crate::core::device_util::flash_attention_requested(),
         }
     }
 
@@ -82,6 +89,20 @@ impl ModelConfig {
         self
     }
 
+    /// Request flash attention when the backend provides kernels for it
+    pub fn with_flash_attention(mut self, enabled: bool) -> Self {
+        self.use_flash_attn = enabled;
+        self
+    }
+
+    /// Whether flash attention will actually be used on `device`
+    ///
+    /// Combines the requested setting with a runtime capability check so
+    /// loaders can fall back to standard attention on unsupported backends.
+    pub fn flash_attention_enabled(&self, device: &candle_core::Device) -> bool {
+        self.use_flash_attn && crate::core::device_util::flash_attention_available(device)
+    }
+
     /// Validate the model configuration
     pub fn validate(&self) -> Result<(), ModelConfigError> {
         if self.registry_key.is_empty() {
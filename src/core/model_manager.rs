@@ -0,0 +1,451 @@
+//! Multi-model manager
+//!
+//! An [`Engine`](super::Engine) serves one chat model by default. Speculative
+//! decoding, reranking and per-request model selection need several models
+//! resident at once, so [`ModelManager`] holds them side by side, keyed by
+//! registry key:
+//!
+//! - each model is loaded for a [`ModelRole`] (chat, draft, reranker, vision,
+//!   embedding) and the first model of a role becomes its default;
+//! - prompts are routed by registry key, falling back to the role's default
+//!   ([`ModelManager::route_model`] loads a requested chat model on demand,
+//!   which is how the OpenAI-compatible endpoint picks its model);
+//! - loading a chat model spawns its pool workers, so the weights are in
+//!   memory before the first prompt; other models load on first use;
+//! - the estimated memory of every loaded model counts against one budget,
+//!   so adding a draft model cannot silently push the chat model out of
+//!   memory;
+//! - tokenizers come from one [`TokenizerCache`], so models of the same
+//!   family share a single copy.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tokenizers::Tokenizer;
+
+use super::engine::{EngineError, EngineResult};
+use super::tokenizer::{TokenizerCache, shared_tokenizer_cache};
+use crate::capability::registry::{AnyModel, FromRegistry, TextToTextModel};
+use crate::domain::model::traits::CandleModel;
+
+/// Process-wide manager engines route through
+static SHARED_MODELS: LazyLock<Arc<ModelManager>> = LazyLock::new(|| Arc::new(ModelManager::new()));
+
+/// The model manager shared by every engine in this process
+pub fn shared_model_manager() -> Arc<ModelManager> {
+    Arc::clone(&SHARED_MODELS)
+}
+
+/// What a loaded model is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelRole {
+    /// Generates replies
+    Chat,
+    /// Proposes tokens for a chat model to verify (speculative decoding)
+    Draft,
+    /// Scores candidates against a query
+    Reranker,
+    /// Answers prompts about images
+    Vision,
+    /// Embeds text
+    Embedding,
+}
+
+impl ModelRole {
+    /// Lowercase name of the role
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Draft => "draft",
+            Self::Reranker => "reranker",
+            Self::Vision => "vision",
+            Self::Embedding => "embedding",
+        }
+    }
+
+    /// Whether `model` has the capability this role needs
+    pub fn accepts(self, model: &AnyModel) -> bool {
+        match self {
+            Self::Chat | Self::Draft => matches!(model, AnyModel::TextToText(_)),
+            Self::Reranker => matches!(model, AnyModel::TextToText(_) | AnyModel::TextEmbedding(_)),
+            Self::Vision => matches!(model, AnyModel::Vision(_)),
+            Self::Embedding => matches!(model, AnyModel::TextEmbedding(_)),
+        }
+    }
+}
+
+impl std::fmt::Display for ModelRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A model held by a [`ModelManager`]
+#[derive(Debug, Clone)]
+pub struct ManagedModel {
+    registry_key: String,
+    model: AnyModel,
+    role: ModelRole,
+    memory_mb: usize,
+    tokenizer: Option<Arc<Tokenizer>>,
+}
+
+impl ManagedModel {
+    /// The registry model
+    pub fn model(&self) -> &AnyModel {
+        &self.model
+    }
+
+    /// Registry key the model was loaded under
+    pub fn registry_key(&self) -> &str {
+        &self.registry_key
+    }
+
+    /// Role the model was loaded for
+    pub fn role(&self) -> ModelRole {
+        self.role
+    }
+
+    /// Memory charged to the manager's budget, in MB
+    pub fn memory_mb(&self) -> usize {
+        self.memory_mb
+    }
+
+    /// The model as a text-to-text model, if it is one
+    pub fn text_to_text(&self) -> Option<&TextToTextModel> {
+        match &self.model {
+            AnyModel::TextToText(model) => Some(model),
+            _ => None,
+        }
+    }
+
+    /// Tokenizer attached with [`ModelManager::attach_tokenizer`]
+    pub fn tokenizer(&self) -> Option<&Arc<Tokenizer>> {
+        self.tokenizer.as_ref()
+    }
+}
+
+/// Snapshot of a [`ModelManager`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelManagerStats {
+    /// Loaded models as `(registry_key, role, memory_mb)`, sorted by key
+    pub models: Vec<(String, ModelRole, usize)>,
+    /// Memory charged by all loaded models, in MB
+    pub memory_used_mb: usize,
+    /// Memory budget, if one is set
+    pub memory_budget_mb: Option<usize>,
+    /// Tokenizers in the shared cache
+    pub cached_tokenizers: usize,
+}
+
+/// Several loaded models with shared tokenizers and one memory budget
+#[derive(Debug)]
+pub struct ModelManager {
+    models: RwLock<HashMap<String, ManagedModel>>,
+    defaults: RwLock<HashMap<ModelRole, String>>,
+    tokenizers: Arc<TokenizerCache>,
+    memory_budget_mb: Option<usize>,
+}
+
+impl Default for ModelManager {
+    fn default() -> Self {
+        Self {
+            models: RwLock::new(HashMap::new()),
+            defaults: RwLock::new(HashMap::new()),
+            tokenizers: shared_tokenizer_cache(),
+            memory_budget_mb: None,
+        }
+    }
+}
+
+impl ModelManager {
+    /// Manager with no budget using the process-wide tokenizer cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse loads that would take the loaded models past `budget_mb`
+    #[must_use]
+    pub fn with_memory_budget_mb(mut self, budget_mb: usize) -> Self {
+        self.memory_budget_mb = Some(budget_mb);
+        self
+    }
+
+    /// Share tokenizers through `cache` instead of the process-wide cache
+    #[must_use]
+    pub fn with_tokenizer_cache(mut self, cache: Arc<TokenizerCache>) -> Self {
+        self.tokenizers = cache;
+        self
+    }
+
+    /// The tokenizer cache models share
+    pub fn tokenizers(&self) -> &Arc<TokenizerCache> {
+        &self.tokenizers
+    }
+
+    /// Load the registry model `registry_key` for `role`
+    ///
+    /// Text-to-text models get their pool workers spawned, so this returns
+    /// once the weights are in memory; other models load on first use. The
+    /// model becomes the role's default if the role has none. Loading a key
+    /// that is already loaded returns it unchanged.
+    ///
+    /// # Errors
+    ///
+    /// [`EngineError::ModelNotFound`] if the key is not registered,
+    /// [`EngineError::ConfigurationError`] if the model lacks the role's
+    /// capability, [`EngineError::MemoryBudgetExceeded`] if it does not fit
+    /// in the remaining budget, and [`EngineError::InternalError`] if the
+    /// weights fail to load.
+    pub async fn load(&self, registry_key: &str, role: ModelRole) -> EngineResult<ManagedModel> {
+        if let Some(existing) = self.get(registry_key) {
+            return Ok(existing);
+        }
+        let model = AnyModel::from_registry(registry_key).ok_or(EngineError::ModelNotFound)?;
+        check_role(registry_key, &model, role)?;
+        self.check_budget(model.info().est_memory_allocation_mb)?;
+
+        if let AnyModel::TextToText(text_model) = &model {
+            text_model.load().await.map_err(|e| {
+                EngineError::InternalError(format!("Failed to load '{}': {}", registry_key, e))
+            })?;
+        }
+        self.insert(registry_key, model, role)
+    }
+
+    /// The chat model a prompt should run on
+    ///
+    /// `registry_key` selects a model for this request; without one the
+    /// default chat model is used, then `fallback`. Models not yet held are
+    /// loaded as chat models.
+    ///
+    /// # Errors
+    ///
+    /// As for [`load`](Self::load).
+    pub async fn route_model(
+        &self,
+        registry_key: Option<&str>,
+        fallback: &str,
+    ) -> EngineResult<ManagedModel> {
+        if let Ok(model) = self.route(registry_key, ModelRole::Chat) {
+            return Ok(model);
+        }
+        self.load(registry_key.unwrap_or(fallback), ModelRole::Chat)
+            .await
+    }
+
+    /// Fail if `memory_mb` more would exceed the budget
+    fn check_budget(&self, memory_mb: usize) -> EngineResult<()> {
+        let Some(budget_mb) = self.memory_budget_mb else {
+            return Ok(());
+        };
+        let used_mb = self.memory_used_mb();
+        if used_mb + memory_mb > budget_mb {
+            return Err(EngineError::MemoryBudgetExceeded {
+                required_mb: memory_mb,
+                available_mb: budget_mb.saturating_sub(used_mb),
+            });
+        }
+        Ok(())
+    }
+
+    /// Add an already resolved model for `role`, charging its estimated memory
+    ///
+    /// # Errors
+    ///
+    /// As for [`load`](Self::load), except for registry lookup.
+    pub fn insert(
+        &self,
+        registry_key: &str,
+        model: AnyModel,
+        role: ModelRole,
+    ) -> EngineResult<ManagedModel> {
+        check_role(registry_key, &model, role)?;
+
+        let mut models = self.models.write();
+        if let Some(existing) = models.get(registry_key) {
+            return Ok(existing.clone());
+        }
+
+        let memory_mb = model.info().est_memory_allocation_mb;
+        if let Some(budget_mb) = self.memory_budget_mb {
+            let used_mb: usize = models.values().map(|m| m.memory_mb).sum();
+            if used_mb + memory_mb > budget_mb {
+                return Err(EngineError::MemoryBudgetExceeded {
+                    required_mb: memory_mb,
+                    available_mb: budget_mb.saturating_sub(used_mb),
+                });
+            }
+        }
+
+        let managed = ManagedModel {
+            registry_key: registry_key.to_string(),
+            model,
+            role,
+            memory_mb,
+            tokenizer: None,
+        };
+        models.insert(registry_key.to_string(), managed.clone());
+        self.defaults
+            .write()
+            .entry(role)
+            .or_insert_with(|| registry_key.to_string());
+
+        log::info!(
+            "Loaded {} model '{}' ({} MB)",
+            role,
+            registry_key,
+            memory_mb
+        );
+        Ok(managed)
+    }
+
+    /// Attach the tokenizer at `path` to a loaded model, via the shared cache
+    ///
+    /// # Errors
+    ///
+    /// [`EngineError::ModelNotFound`] if the model is not loaded, or
+    /// [`EngineError::ConfigurationError`] if the tokenizer cannot be read.
+    pub fn attach_tokenizer(
+        &self,
+        registry_key: &str,
+        path: impl AsRef<Path>,
+    ) -> EngineResult<Arc<Tokenizer>> {
+        let tokenizer = self
+            .tokenizers
+            .get_or_load(path)
+            .map_err(|e| EngineError::ConfigurationError(e.to_string()))?;
+        let mut models = self.models.write();
+        let managed = models
+            .get_mut(registry_key)
+            .ok_or(EngineError::ModelNotFound)?;
+        managed.tokenizer = Some(Arc::clone(&tokenizer));
+        Ok(tokenizer)
+    }
+
+    /// Make the loaded model `registry_key` the default for its role
+    ///
+    /// # Errors
+    ///
+    /// [`EngineError::ModelNotFound`] if the model is not loaded.
+    pub fn set_default(&self, registry_key: &str) -> EngineResult<()> {
+        let role = self
+            .models
+            .read()
+            .get(registry_key)
+            .map(ManagedModel::role)
+            .ok_or(EngineError::ModelNotFound)?;
+        self.defaults.write().insert(role, registry_key.to_string());
+        Ok(())
+    }
+
+    /// Unload a model, releasing its memory and its role default
+    ///
+    /// Another loaded model of the same role, if any, becomes the default.
+    /// Tokenizers no other model holds are dropped from the cache. Returns
+    /// whether the model was loaded.
+    pub fn unload(&self, registry_key: &str) -> bool {
+        let (role, replacement) = {
+            let mut models = self.models.write();
+            let Some(removed) = models.remove(registry_key) else {
+                return false;
+            };
+            let replacement = models
+                .iter()
+                .filter(|(_, m)| m.role == removed.role)
+                .map(|(key, _)| key.clone())
+                .min();
+            (removed.role, replacement)
+        };
+
+        let mut defaults = self.defaults.write();
+        if defaults.get(&role).map(String::as_str) == Some(registry_key) {
+            match replacement {
+                Some(key) => defaults.insert(role, key),
+                None => defaults.remove(&role),
+            };
+        }
+        drop(defaults);
+
+        self.tokenizers.evict_unused();
+        log::info!("Unloaded model '{}'", registry_key);
+        true
+    }
+
+    /// The loaded model `registry_key`, if any
+    pub fn get(&self, registry_key: &str) -> Option<ManagedModel> {
+        self.models.read().get(registry_key).cloned()
+    }
+
+    /// The default model for `role`, if one is loaded
+    pub fn default_for(&self, role: ModelRole) -> Option<ManagedModel> {
+        let key = self.defaults.read().get(&role).cloned()?;
+        self.get(&key)
+    }
+
+    /// The model a prompt should run on
+    ///
+    /// A registry key selects that model, which must be loaded; without
+    /// one the role's default is used.
+    ///
+    /// # Errors
+    ///
+    /// [`EngineError::ModelNotFound`] if the key is not loaded or the role
+    /// has no model.
+    pub fn route(&self, registry_key: Option<&str>, role: ModelRole) -> EngineResult<ManagedModel> {
+        match registry_key {
+            Some(key) => self.get(key),
+            None => self.default_for(role),
+        }
+        .ok_or(EngineError::ModelNotFound)
+    }
+
+    /// Whether `registry_key` is loaded
+    pub fn is_loaded(&self, registry_key: &str) -> bool {
+        self.models.read().contains_key(registry_key)
+    }
+
+    /// Memory charged by all loaded models, in MB
+    pub fn memory_used_mb(&self) -> usize {
+        self.models.read().values().map(|m| m.memory_mb).sum()
+    }
+
+    /// Memory left in the budget, in MB, if one is set
+    pub fn memory_available_mb(&self) -> Option<usize> {
+        self.memory_budget_mb
+            .map(|budget| budget.saturating_sub(self.memory_used_mb()))
+    }
+
+    /// Snapshot of loaded models and memory use
+    pub fn stats(&self) -> ModelManagerStats {
+        let mut models: Vec<_> = self
+            .models
+            .read()
+            .iter()
+            .map(|(key, m)| (key.clone(), m.role, m.memory_mb))
+            .collect();
+        models.sort_by(|a, b| a.0.cmp(&b.0));
+        ModelManagerStats {
+            memory_used_mb: models.iter().map(|(_, _, mb)| mb).sum(),
+            models,
+            memory_budget_mb: self.memory_budget_mb,
+            cached_tokenizers: self.tokenizers.len(),
+        }
+    }
+}
+
+/// Fail unless `model` has the capability `role` needs
+fn check_role(registry_key: &str, model: &AnyModel, role: ModelRole) -> EngineResult<()> {
+    if role.accepts(model) {
+        Ok(())
+    } else {
+        Err(EngineError::ConfigurationError(format!(
+            "Model '{}' cannot be used as a {} model",
+            registry_key, role
+        )))
+    }
+}
@@ -0,0 +1,104 @@
+//! Shared tokenizer cache
+//!
+//! Models from one family ship the same `tokenizer.json`: a chat model and
+//! its draft model, or several quantizations of one checkpoint. Loading them
+//! through [`TokenizerCache`] parses each file once and hands every model the
+//! same `Arc<Tokenizer>`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use parking_lot::RwLock;
+use tokenizers::Tokenizer;
+
+use super::core::CandleTokenizerError;
+
+/// Process-wide cache used by model loaders
+static SHARED_TOKENIZERS: LazyLock<Arc<TokenizerCache>> =
+    LazyLock::new(|| Arc::new(TokenizerCache::new()));
+
+/// The tokenizer cache shared by every model loaded in this process
+pub fn shared_tokenizer_cache() -> Arc<TokenizerCache> {
+    Arc::clone(&SHARED_TOKENIZERS)
+}
+
+/// Canonical form of `path`, or `path` itself if it cannot be resolved
+fn cache_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Tokenizers keyed by the canonical path of their `tokenizer.json`
+#[derive(Debug, Default)]
+pub struct TokenizerCache {
+    tokenizers: RwLock<HashMap<PathBuf, Arc<Tokenizer>>>,
+}
+
+impl TokenizerCache {
+    /// Empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The tokenizer at `path`, loading it on first use
+    ///
+    /// Paths are canonicalized, so symlinked snapshots of one file share an
+    /// entry. A failed load is not cached.
+    pub fn get_or_load(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Arc<Tokenizer>, CandleTokenizerError> {
+        let key = cache_key(path.as_ref());
+
+        if let Some(tokenizer) = self.tokenizers.read().get(&key) {
+            return Ok(Arc::clone(tokenizer));
+        }
+
+        // Parse outside the lock; a concurrent load of the same file keeps
+        // whichever finished first
+        let tokenizer = Tokenizer::from_file(&key)
+            .map(Arc::new)
+            .map_err(|e| CandleTokenizerError::LoadFailed(format!("{}: {}", key.display(), e)))?;
+
+        Ok(Arc::clone(
+            self.tokenizers.write().entry(key).or_insert(tokenizer),
+        ))
+    }
+
+    /// Add an already loaded tokenizer under `path`
+    pub fn insert(&self, path: impl AsRef<Path>, tokenizer: Arc<Tokenizer>) {
+        let key = cache_key(path.as_ref());
+        self.tokenizers.write().insert(key, tokenizer);
+    }
+
+    /// Whether the tokenizer at `path` is cached
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        let key = cache_key(path.as_ref());
+        self.tokenizers.read().contains_key(&key)
+    }
+
+    /// Number of cached tokenizers
+    pub fn len(&self) -> usize {
+        self.tokenizers.read().len()
+    }
+
+    /// Whether nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.tokenizers.read().is_empty()
+    }
+
+    /// Drop cached tokenizers no model still holds
+    ///
+    /// Returns how many were dropped.
+    pub fn evict_unused(&self) -> usize {
+        let mut tokenizers = self.tokenizers.write();
+        let before = tokenizers.len();
+        tokenizers.retain(|_, tokenizer| Arc::strong_count(tokenizer) > 1);
+        before - tokenizers.len()
+    }
+
+    /// Drop every cached tokenizer
+    pub fn clear(&self) {
+        self.tokenizers.write().clear();
+    }
+}
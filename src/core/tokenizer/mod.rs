@@ -4,9 +4,11 @@
 //! This module contains shared tokenization infrastructure used across
 //! text generation and embedding capabilities.
 
+pub mod cache;
 pub mod core;
 
 // Re-export main tokenizer types for convenient access
+pub use cache::{TokenizerCache, shared_tokenizer_cache};
 pub use core::{
     CandlePaddingStrategy, CandleStreamingTokenizer, CandleTokenizer, CandleTokenizerConfig,
     CandleTokenizerError, CandleTokenizerStats,
@@ -1,9 +1,8 @@
 //! Core agent data structures with automatic memory tool injection
 
-use std::sync::{
-    Arc,
-    atomic::{AtomicU64, AtomicUsize, Ordering},
-};
+#[cfg(feature = "memory")]
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
 use rmcp::model::Tool as ToolInfo;
 use serde_json::Value;
@@ -12,10 +11,15 @@ use tokio_stream::Stream;
 
 use crate::core::EngineError;
 use crate::domain::context::CandleDocument as Document;
+#[cfg(feature = "memory")]
 use crate::domain::memory::MemoryConfig;
+#[cfg(feature = "memory")]
+use crate::domain::memory::MemoryTool;
+#[cfg(feature = "memory")]
 use crate::domain::memory::config::memory::MemoryConfig as ComprehensiveMemoryConfig;
-use crate::domain::memory::{Error as MemoryError, MemoryTool, MemoryToolError};
+use crate::domain::memory::{Error as MemoryError, MemoryToolError};
 use crate::domain::model::CandleModel as Model;
+#[cfg(feature = "memory")]
 use crate::memory::core::SurrealDBMemoryManager;
 // Tool data now comes from SweetMCP ToolInfo directly
 use cyrup_sugars::ZeroOneOrMany;
@@ -203,8 +207,10 @@ pub struct Agent<M: Model> {
     /// MCP tools available to the agent for function calling
     pub tools: ZeroOneOrMany<ToolInfo>,
     /// Optional memory system for storing and retrieving conversation context
+    #[cfg(feature = "memory")]
     pub memory: Option<Arc<SurrealDBMemoryManager>>,
     /// Memory tool for automated memory management operations
+    #[cfg(feature = "memory")]
     pub memory_tool: Option<MemoryTool>,
     /// Temperature setting for response randomness (0.0 to 2.0)
     pub temperature: Option<f64>,
@@ -228,6 +234,7 @@ impl<M: Model + Clone + Send + 'static + Default> Agent<M> {
     ///
     /// # Performance
     /// Zero allocation agent construction with lock-free memory manager sharing
+    #[cfg(feature = "memory")]
     #[inline]
     pub fn new(
         model: M,
@@ -297,6 +304,32 @@ impl<M: Model + Clone + Send + 'static + Default> Agent<M> {
         }))
     }
 
+    /// Create a new agent without long-term memory
+    ///
+    /// Builds without the `memory` feature have no memory system to inject,
+    /// so the agent only carries its model and system prompt.
+    #[cfg(not(feature = "memory"))]
+    #[inline]
+    pub fn new(
+        model: M,
+        system_prompt: impl Into<String>,
+    ) -> Pin<
+        Box<
+            dyn Stream<Item = crate::domain::context::chunks::CandleResult<Self, AgentError>>
+                + Send,
+        >,
+    > {
+        let agent = Self {
+            model,
+            system_prompt: system_prompt.into(),
+            ..Self::default()
+        };
+        AGENT_STATS.record_agent_created();
+        Box::pin(crate::async_stream::once(crate::domain::context::chunks::CandleResult {
+            result: Ok(agent),
+        }))
+    }
+
     /// Create a new agent with custom memory configuration
     ///
     /// # Arguments
@@ -309,6 +342,7 @@ impl<M: Model + Clone + Send + 'static + Default> Agent<M> {
     ///
     /// # Performance
     /// Zero allocation with custom cognitive settings
+    #[cfg(feature = "memory")]
     #[inline]
     pub fn with_memory_config(
         model: M,
@@ -420,6 +454,7 @@ impl<M: Model + Clone + Send + 'static + Default> Agent<M> {
     ///
     /// # Performance
     /// Zero allocation with lock-free memory sharing between agents
+    #[cfg(feature = "memory")]
     #[inline]
     pub fn with_shared_memory(
         model: M,
@@ -463,6 +498,7 @@ impl<M: Model + Clone + Send + 'static + Default> Agent<M> {
     ///
     /// # Performance
     /// Zero cost abstraction with direct tool access
+    #[cfg(feature = "memory")]
     #[inline]
     pub fn memory_tool(&self) -> Option<&MemoryTool> {
         self.memory_tool.as_ref()
@@ -475,6 +511,7 @@ impl<M: Model + Clone + Send + 'static + Default> Agent<M> {
     ///
     /// # Performance
     /// Zero cost abstraction with direct memory access
+    #[cfg(feature = "memory")]
     #[inline]
     pub fn memory(&self) -> Option<&Arc<SurrealDBMemoryManager>> {
         self.memory.as_ref()
@@ -508,6 +545,21 @@ impl<M: Model + Clone + Send + 'static + Default> Agent<M> {
         self
     }
 
+    /// Add several tools to the agent
+    ///
+    /// # Arguments
+    /// * `tools` - Tools to add, from a `Vec`, an array, an iterator or a `ZeroOneOrMany`
+    ///
+    /// # Returns
+    /// Updated agent instance
+    #[must_use]
+    pub fn add_tools(mut self, tools: impl IntoIterator<Item = ToolInfo>) -> Self {
+        let mut all = Vec::from(std::mem::replace(&mut self.tools, ZeroOneOrMany::None));
+        all.extend(tools);
+        self.tools = ZeroOneOrMany::from(all);
+        self
+    }
+
     /// Set agent temperature
     ///
     /// # Arguments
@@ -550,7 +602,9 @@ impl<M: Model + Clone + Send + 'static + Default> Default for Agent<M> {
             system_prompt: String::new(),
             context: ZeroOneOrMany::None,
             tools: ZeroOneOrMany::None,
+            #[cfg(feature = "memory")]
             memory: None,
+            #[cfg(feature = "memory")]
             memory_tool: None,
             temperature: None,
             max_tokens: None,
@@ -571,7 +625,9 @@ impl<M: Model + Clone + Send + 'static + Default> MessageChunk for Agent<M> {
             system_prompt: error, // Keep error string in system_prompt for error() lifetime
             context: ZeroOneOrMany::None,
             tools: ZeroOneOrMany::None,
+            #[cfg(feature = "memory")]
             memory: None,
+            #[cfg(feature = "memory")]
             memory_tool: None,
             temperature: None,
             max_tokens: None,
@@ -4,13 +4,19 @@
 
 pub mod chat;
 pub mod core;
+pub mod prompt_guard;
 pub mod role;
 pub mod types;
+pub mod untrusted_content;
 
 // Re-export commonly used types with explicit imports to avoid conflicts
+pub use prompt_guard::{InjectionPolicy, PromptGuard, PromptGuardError, ProtectedPrompt};
 pub use role::McpServerConfig as CandleMcpServer;
 pub use role::{CandleAgentConversation, CandleAgentConversationMessage};
 pub use role::{CandleAgentRole, McpServerConfig};
 // Canonical agent handle for conversation turn callbacks comes from builder layer
 pub use crate::builders::agent_role::CandleAgentRoleAgent;
 pub use types::{AgentConfig, CandleAdditionalParams, CandleAgent};
+pub use untrusted_content::{
+    InjectionFinding, InjectionFindingKind, SanitizedContent, sanitize_untrusted,
+};
@@ -0,0 +1,185 @@
+//! System prompt protection and prompt-injection screening
+//!
+//! An agent built with `.protect_system_prompt()` records the SHA-256 digest
+//! of its configured system prompt when the builder is consumed. Every turn
+//! re-hashes the prefix of the prompt actually sent to the model and refuses
+//! to run if it no longer matches, so nothing between the builder and the
+//! provider can rewrite the agent's instructions. The digest is attached to
+//! the turn's tracing span as `system_prompt_sha256`.
+//!
+//! `.reject_prompt_injection(policy)` additionally refuses user messages that
+//! contain one of the policy's markers, such as "ignore previous instructions"
+//! or raw chat-template control tokens.
+
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+
+/// Markers rejected by [`InjectionPolicy::default`]
+pub const DEFAULT_INJECTION_MARKERS: &[&str] = &[
+    "ignore previous instructions",
+    "ignore all previous instructions",
+    "ignore the above instructions",
+    "disregard the system prompt",
+    "disregard previous instructions",
+    "forget your instructions",
+    "you are no longer bound by",
+    "new system prompt:",
+    "<|im_start|>system",
+    "<|system|>",
+    "[inst]",
+    "<<sys>>",
+];
+
+/// Prompt guard violations
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PromptGuardError {
+    #[error("System prompt was modified after the agent was built (expected sha256 {expected})")]
+    PromptMutated { expected: String },
+    #[error("Message rejected: contains prompt-injection marker '{marker}'")]
+    InjectionDetected { marker: String },
+}
+
+/// System prompt with the digest recorded when the agent was built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectedPrompt {
+    text: Arc<str>,
+    digest: String,
+}
+
+impl ProtectedPrompt {
+    /// Record `text` and its SHA-256 digest
+    pub fn new(text: impl Into<String>) -> Self {
+        let text: Arc<str> = text.into().into();
+        let digest = sha256_hex(&text);
+        Self { text, digest }
+    }
+
+    /// The protected prompt
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Hex-encoded SHA-256 digest of the protected prompt
+    pub fn digest(&self) -> &str {
+        &self.digest
+    }
+
+    /// Check that `assembled` still begins with the protected prompt
+    ///
+    /// Content appended after the prefix (personality, memory context, the
+    /// user turn) is allowed; any change to the prefix itself is not.
+    pub fn verify(&self, assembled: &str) -> Result<(), PromptGuardError> {
+        let prefix = assembled.get(..self.text.len()).unwrap_or(assembled);
+        if prefix.len() == self.text.len() && sha256_hex(prefix) == self.digest {
+            Ok(())
+        } else {
+            Err(PromptGuardError::PromptMutated {
+                expected: self.digest.clone(),
+            })
+        }
+    }
+}
+
+/// Case-insensitive markers that cause a user message to be refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InjectionPolicy {
+    markers: Vec<String>,
+}
+
+impl Default for InjectionPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_INJECTION_MARKERS.iter().copied())
+    }
+}
+
+impl InjectionPolicy {
+    /// Policy rejecting exactly `markers`
+    pub fn new<I, S>(markers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            markers: markers
+                .into_iter()
+                .map(|m| m.into().to_lowercase())
+                .filter(|m| !m.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Add markers to the policy
+    #[must_use]
+    pub fn with_markers<I, S>(mut self, markers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.markers.extend(Self::new(markers).markers);
+        self
+    }
+
+    /// Markers this policy rejects (lowercased)
+    pub fn markers(&self) -> &[String] {
+        &self.markers
+    }
+
+    /// Refuse `message` if it contains any marker
+    ///
+    /// Matching ignores case and collapses runs of whitespace, so
+    /// "Ignore   previous\ninstructions" is caught as well.
+    pub fn check(&self, message: &str) -> Result<(), PromptGuardError> {
+        let normalized = message
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+
+        match self.markers.iter().find(|m| normalized.contains(m.as_str())) {
+            Some(marker) => Err(PromptGuardError::InjectionDetected {
+                marker: marker.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Per-agent prompt protection settings
+#[derive(Debug, Clone, Default)]
+pub struct PromptGuard {
+    /// Protected system prompt, if `.protect_system_prompt()` was set
+    pub protected: Option<ProtectedPrompt>,
+    /// Injection policy, if `.reject_prompt_injection()` was set
+    pub injection: Option<InjectionPolicy>,
+}
+
+impl PromptGuard {
+    /// Digest recorded for the system prompt, if protected
+    pub fn digest(&self) -> Option<&str> {
+        self.protected.as_ref().map(ProtectedPrompt::digest)
+    }
+
+    /// Screen a user message against the injection policy
+    pub fn check_message(&self, message: &str) -> Result<(), PromptGuardError> {
+        match &self.injection {
+            Some(policy) => policy.check(message),
+            None => Ok(()),
+        }
+    }
+
+    /// Verify the assembled system prompt against the protected prefix
+    pub fn verify_system_prompt(&self, assembled: &str) -> Result<(), PromptGuardError> {
+        match &self.protected {
+            Some(protected) => protected.verify(assembled),
+            None => Ok(()),
+        }
+    }
+}
+
+fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
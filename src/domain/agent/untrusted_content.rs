@@ -0,0 +1,174 @@
+//! Screening of retrieved memories and tool results before prompt assembly
+//!
+//! Recalled memories, loaded documents and tool output are not written by the
+//! user, yet they land in the prompt next to the agent's instructions.
+//! [`sanitize_untrusted`] runs over all of it before the prompt is assembled:
+//!
+//! - chat-template control tokens (`<|im_start|>`, `[INST]`, `<<SYS>>`,
+//!   `<tool_response>`, ...) are removed until none are left, so content
+//!   cannot open a turn, not even with markers nested in markers;
+//! - lines starting with a role label (`System:`, `User:`, `Assistant:`,
+//!   `Tool result:`) are quoted, so they cannot pass for a turn of a plain
+//!   text transcript;
+//! - instruction-like phrases aimed at the model ("ignore previous
+//!   instructions", "from now on you", ...) are kept but reported.
+//!
+//! Every change and match is returned as an [`InjectionFinding`]; chat turns
+//! list them in their trace.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Longest matched text kept in a finding
+const MAX_FINDING_CHARS: usize = 80;
+
+/// Chat-template control tokens of the supported model families
+static TEMPLATE_MARKERS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)<\|[a-z0-9_]{1,32}\|>|\[/?INST\]|<</?SYS>>|</?tool_(?:call|response)>")
+        .unwrap_or_else(|e| panic!("Invalid template marker pattern: {e}"))
+});
+
+/// Role labels opening a line, as used by plain text transcripts
+static ROLE_LABELS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?im)^[ \t]*(?:system|developer|user|assistant|tool(?: result)?)(?: \([^)\n]{0,64}\))?[ \t]*:",
+    )
+    .unwrap_or_else(|e| panic!("Invalid role label pattern: {e}"))
+});
+
+/// Phrases that address the model with instructions
+static INSTRUCTION_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"(?i)\b(?:ignore|disregard|forget|override)\s+(?:all\s+|any\s+)?(?:of\s+)?(?:the\s+|your\s+|my\s+)?(?:previous|prior|above|earlier|system|original)\s+(?:instructions|prompts?|rules|messages|directions)",
+        r"(?i)\byou\s+(?:must|should|will)\s+now\b",
+        r"(?i)\bfrom\s+now\s+on,?\s+(?:you|always|never)\b",
+        r"(?i)\byou\s+are\s+no\s+longer\b",
+        r"(?i)\bnew\s+(?:system\s+)?(?:instructions?|prompt|rules)\s*:",
+        r"(?i)\b(?:reveal|print|repeat|output|show)\s+(?:me\s+)?(?:your|the)\s+(?:system\s+prompt|instructions|hidden\s+prompt)",
+        r"(?i)\bdo\s+not\s+(?:tell|inform|mention\s+(?:this\s+)?to)\s+the\s+user\b",
+        r"(?i)\b(?:call|invoke|run|execute)\s+the\s+\S+\s+tool\b",
+    ]
+    .into_iter()
+    .filter_map(|pattern| match Regex::new(pattern) {
+        Ok(regex) => Some(regex),
+        Err(e) => {
+            log::error!("Invalid instruction pattern {pattern}: {e}");
+            None
+        }
+    })
+    .collect()
+});
+
+/// What was found in untrusted content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionFindingKind {
+    /// A chat-template control token, removed
+    TemplateMarker,
+    /// A line opening with a role label, quoted
+    RoleLabel,
+    /// An instruction aimed at the model, kept
+    Instruction,
+}
+
+impl InjectionFindingKind {
+    /// Short name used in traces
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TemplateMarker => "template marker removed",
+            Self::RoleLabel => "role label quoted",
+            Self::Instruction => "instruction",
+        }
+    }
+}
+
+/// One suspicious span of untrusted content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InjectionFinding {
+    /// Where the content came from (memory source, `tool:<name>`, ...)
+    pub source: String,
+    pub kind: InjectionFindingKind,
+    /// The matched text, shortened if long
+    pub matched: String,
+}
+
+impl fmt::Display for InjectionFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} {:?}",
+            self.source,
+            self.kind.as_str(),
+            self.matched
+        )
+    }
+}
+
+/// Untrusted content made safe to place in a prompt
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SanitizedContent {
+    /// Content with control tokens removed and role labels quoted
+    pub text: String,
+    /// Everything that was changed or flagged, in order of kind
+    pub findings: Vec<InjectionFinding>,
+}
+
+impl SanitizedContent {
+    /// Whether nothing was changed or flagged
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// Sanitize content not written by the user before it goes into a prompt
+///
+/// `source` labels the findings, e.g. the memory's source or `tool:<name>`.
+pub fn sanitize_untrusted(source: &str, text: &str) -> SanitizedContent {
+    let mut findings = Vec::new();
+    let mut finding = |kind, matched: &str| {
+        findings.push(InjectionFinding {
+            source: source.to_string(),
+            kind,
+            matched: shorten(matched.trim()),
+        });
+    };
+
+    // Removing `<|im_start|>` from `<|im_<|im_start|>start|>` leaves another
+    let mut stripped = text.to_string();
+    loop {
+        let pass = TEMPLATE_MARKERS.replace_all(&stripped, |caps: &regex::Captures<'_>| {
+            finding(InjectionFindingKind::TemplateMarker, &caps[0]);
+            String::new()
+        });
+        match pass {
+            Cow::Borrowed(_) => break,
+            Cow::Owned(next) => stripped = next,
+        }
+    }
+
+    let quoted = ROLE_LABELS.replace_all(&stripped, |caps: &regex::Captures<'_>| {
+        finding(InjectionFindingKind::RoleLabel, &caps[0]);
+        format!("> {}", caps[0].trim_start())
+    });
+
+    for pattern in INSTRUCTION_PATTERNS.iter() {
+        for m in pattern.find_iter(&quoted) {
+            finding(InjectionFindingKind::Instruction, m.as_str());
+        }
+    }
+
+    let text = quoted.into_owned();
+    SanitizedContent { text, findings }
+}
+
+/// `text` cut to [`MAX_FINDING_CHARS`] characters
+fn shorten(text: &str) -> String {
+    match text.char_indices().nth(MAX_FINDING_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
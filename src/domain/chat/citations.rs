@@ -0,0 +1,126 @@
+//! Post-hoc citation of recalled memories
+//!
+//! With `.cite_memories(true)`, once an answer is complete it is embedded
+//! together with the memory entries that went into its prompt context. Entries
+//! whose similarity to the answer clears [`CITATION_THRESHOLD`] are attached
+//! to the `Complete` chunk, best match first, so callers can see which
+//! memories informed the answer.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "memory")]
+use crate::domain::context::builder::BuiltContext;
+use crate::domain::context::builder::{ContextItem, ContextSourceKind};
+use crate::domain::tool::selector::cosine_similarity;
+#[cfg(feature = "memory")]
+use crate::memory::core::manager::coordinator::MemoryCoordinator;
+
+/// Lowest answer similarity for a memory to be cited
+pub const CITATION_THRESHOLD: f32 = 0.6;
+
+/// Most citations attached to one answer
+pub const MAX_CITATIONS: usize = 5;
+
+/// A memory that informed an answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// Memory ID, when the entry came from a memory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_id: Option<String>,
+    /// Source shown in the prompt context, e.g. `team_notes:src/lib.rs:10-24`
+    pub source: String,
+    /// Cosine similarity between the memory and the answer
+    pub similarity: f32,
+}
+
+/// Memory entries whose embeddings are close enough to the answer's
+///
+/// `entries` pairs each context item with its embedding. Returns at most
+/// [`MAX_CITATIONS`] citations, most similar first.
+pub fn rank_citations(
+    answer: &[f32],
+    entries: &[(&ContextItem, Vec<f32>)],
+    threshold: f32,
+) -> Vec<Citation> {
+    let mut citations: Vec<Citation> = entries
+        .iter()
+        .filter(|(item, _)| item.kind == ContextSourceKind::Memory)
+        .filter_map(|(item, embedding)| {
+            let similarity = cosine_similarity(answer, embedding);
+            (similarity >= threshold).then(|| Citation {
+                memory_id: item.memory_id.clone(),
+                source: item.source.clone(),
+                similarity,
+            })
+        })
+        .collect();
+
+    citations.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    citations.truncate(MAX_CITATIONS);
+    citations
+}
+
+/// Cite the memories in `context` that overlap `answer`
+///
+/// Embedding failures are logged and produce no citations rather than
+/// failing the turn.
+#[cfg(feature = "memory")]
+pub async fn cite_memories(
+    memory: &MemoryCoordinator,
+    context: &BuiltContext,
+    answer: &str,
+) -> Vec<Citation> {
+    let items: Vec<&ContextItem> = context
+        .included
+        .iter()
+        .filter(|item| item.kind == ContextSourceKind::Memory)
+        .collect();
+    if items.is_empty() || answer.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let mut texts = Vec::with_capacity(items.len() + 1);
+    texts.push(answer.to_string());
+    texts.extend(items.iter().map(|item| item.content.clone()));
+
+    let mut embeddings = match memory.embed_for_similarity(&texts).await {
+        Ok(embeddings) => embeddings.into_iter(),
+        Err(e) => {
+            log::warn!("Citation embedding failed: {e:?}");
+            return Vec::new();
+        }
+    };
+    let Some(answer_embedding) = embeddings.next() else {
+        return Vec::new();
+    };
+    let entries: Vec<(&ContextItem, Vec<f32>)> = items.into_iter().zip(embeddings).collect();
+
+    rank_citations(&answer_embedding, &entries, CITATION_THRESHOLD)
+}
+
+/// Cite the memories closest to `query`, for answers whose prompt carried none
+///
+/// `similarity` is the memory's search similarity to `query`. Search failures
+/// are logged and produce no citations.
+#[cfg(feature = "memory")]
+pub async fn recall_citations(memory: &MemoryCoordinator, query: &str) -> Vec<Citation> {
+    let memories = match memory.search_memories(query, MAX_CITATIONS, None).await {
+        Ok(memories) => memories,
+        Err(e) => {
+            log::warn!("Citation recall failed: {e:?}");
+            return Vec::new();
+        }
+    };
+    memories
+        .iter()
+        .filter_map(|node| {
+            let similarity = node.metadata.custom.get("similarity")?.as_f64()? as f32;
+            let item = ContextItem::from_memory(node);
+            (similarity >= CITATION_THRESHOLD).then(|| Citation {
+                memory_id: item.memory_id,
+                source: item.source,
+                similarity,
+            })
+        })
+        .collect()
+}
@@ -19,8 +19,13 @@ use super::types::metadata::ResourceUsage;
 use crate::domain::chat::export::{ChatExporter, ExportConfig, ExportData, ExportFormat};
 use crate::domain::chat::message::{CandleMessage, CandleMessageRole};
 use crate::domain::util::unix_timestamp_micros;
+#[cfg(feature = "memory")]
 use crate::memory::core::manager::coordinator::MemoryCoordinator;
 
+/// Builds without the `memory` feature have no memory to export from
+#[cfg(not(feature = "memory"))]
+type MemoryCoordinator = std::convert::Infallible;
+
 /// Get current timestamp in microseconds since Unix epoch, with fallback for clock errors
 fn current_timestamp_us() -> u64 {
     unix_timestamp_micros()
@@ -84,6 +89,7 @@ impl CommandExecutor {
     }
 
     /// Create a new command executor with memory access
+    #[cfg(feature = "memory")]
     #[must_use]
     pub fn with_memory(memory: Arc<MemoryCoordinator>) -> Self {
         Self {
@@ -645,6 +651,7 @@ async fn execute_export_workflow(
 }
 
 /// Retrieve conversation messages from memory coordinator using public API
+#[cfg(feature = "memory")]
 async fn retrieve_conversation_messages(
     memory: &MemoryCoordinator,
 ) -> Result<Vec<CandleMessage>, String> {
@@ -711,6 +718,14 @@ async fn retrieve_conversation_messages(
     Ok(messages)
 }
 
+/// Without the `memory` feature there is no coordinator to read from
+#[cfg(not(feature = "memory"))]
+async fn retrieve_conversation_messages(
+    memory: &MemoryCoordinator,
+) -> Result<Vec<CandleMessage>, String> {
+    match *memory {}
+}
+
 /// Send progress event for export operation
 fn send_export_progress(
     sender: &tokio::sync::mpsc::UnboundedSender<CommandEvent>,
@@ -0,0 +1,210 @@
+//! Confidence estimates for answers
+//!
+//! With `.confidence(ConfidenceEstimator::new())`, every answer's `Complete`
+//! chunk carries a 0–1 `confidence` built from up to three signals:
+//!
+//! - the mean log probability of the generated tokens, as reported by the
+//!   model (a geometric-mean token probability),
+//! - hedging phrases in the answer ("I'm not sure", "probably", ...),
+//! - optionally, a short self-evaluation pass in which the model rates its
+//!   own answer.
+//!
+//! Missing signals are left out of the weighted average. With a threshold
+//! set, answers scoring below it are qualified with [`LOW_CONFIDENCE_NOTE`]
+//! or backed by a memory lookup, depending on the [`LowConfidenceAction`].
+
+use std::num::NonZeroU64;
+
+use tokio_stream::StreamExt;
+
+use crate::capability::registry::TextToTextModel;
+use crate::capability::traits::TextToTextCapable;
+use crate::domain::completion::{CandleCompletionChunk, CandleCompletionParams};
+use crate::domain::prompt::CandlePrompt;
+
+/// Threshold used by [`ConfidenceEstimator::with_threshold`] callers that have no better value
+pub const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.5;
+
+/// Appended to answers qualified by [`LowConfidenceAction::Qualify`]
+pub const LOW_CONFIDENCE_NOTE: &str = "I'm not sure about this answer, so please double-check it.";
+
+/// Phrases that signal an uncertain answer (matched case-insensitively)
+pub const HEDGING_PHRASES: &[&str] = &[
+    "i'm not sure",
+    "i am not sure",
+    "not certain",
+    "i don't know",
+    "i do not know",
+    "i think",
+    "i believe",
+    "as far as i know",
+    "if i recall",
+    "it seems",
+    "might be",
+    "may be",
+    "probably",
+    "possibly",
+    "perhaps",
+    "unclear",
+];
+
+/// Confidence lost per hedging phrase
+const HEDGE_PENALTY: f32 = 0.25;
+
+const LOGPROB_WEIGHT: f32 = 0.5;
+const HEDGING_WEIGHT: f32 = 0.2;
+const SELF_EVAL_WEIGHT: f32 = 0.3;
+
+/// Tokens allowed for the self-evaluation reply
+const SELF_EVAL_MAX_TOKENS: u64 = 8;
+
+/// What happens to an answer scoring below the threshold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LowConfidenceAction {
+    /// Append [`LOW_CONFIDENCE_NOTE`] to the answer
+    #[default]
+    Qualify,
+    /// Search memory for the question and attach the hits as citations
+    RecallMemory,
+}
+
+/// Confidence settings, passed to `.confidence(...)`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfidenceEstimator {
+    /// Ask the model to rate its own answer (one extra short completion per turn)
+    pub self_evaluation: bool,
+    /// Score below which `action` is taken
+    pub threshold: Option<f32>,
+    pub action: LowConfidenceAction,
+}
+
+impl ConfidenceEstimator {
+    /// Logprob and hedging signals only, no threshold
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the self-evaluation pass
+    #[must_use]
+    pub fn with_self_evaluation(mut self, enabled: bool) -> Self {
+        self.self_evaluation = enabled;
+        self
+    }
+
+    /// Take `action` when an answer scores below `threshold`
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: f32, action: LowConfidenceAction) -> Self {
+        self.threshold = Some(threshold.clamp(0.0, 1.0));
+        self.action = action;
+        self
+    }
+
+    /// Action to take for `confidence`, if it is below the threshold
+    pub fn action_for(&self, confidence: f32) -> Option<LowConfidenceAction> {
+        self.threshold
+            .filter(|threshold| confidence < *threshold)
+            .map(|_| self.action)
+    }
+
+    /// Score `answer` to `question`
+    ///
+    /// A failed self-evaluation is logged and left out of the score.
+    pub async fn estimate(
+        &self,
+        provider: &TextToTextModel,
+        question: &str,
+        answer: &str,
+        mean_logprob: Option<f64>,
+    ) -> f32 {
+        let self_eval = if self.self_evaluation {
+            self_evaluate(provider, question, answer).await
+        } else {
+            None
+        };
+        combine_confidence(mean_logprob, answer, self_eval)
+    }
+}
+
+/// Number of hedging phrases in `text`
+pub fn hedging_count(text: &str) -> usize {
+    let text = text.to_lowercase().replace('’', "'");
+    HEDGING_PHRASES
+        .iter()
+        .map(|phrase| text.matches(phrase).count())
+        .sum()
+}
+
+/// Hedging signal: 1.0 for a plain answer, lower for each hedge
+pub fn hedging_score(text: &str) -> f32 {
+    (1.0 - hedging_count(text) as f32 * HEDGE_PENALTY).max(0.0)
+}
+
+/// Logprob signal: the geometric-mean token probability
+pub fn logprob_score(mean_logprob: f64) -> f32 {
+    (mean_logprob.exp() as f32).clamp(0.0, 1.0)
+}
+
+/// Weighted average of the available signals
+///
+/// The hedging signal is always present; the others count only when given.
+pub fn combine_confidence(mean_logprob: Option<f64>, answer: &str, self_eval: Option<f32>) -> f32 {
+    let mut weighted = HEDGING_WEIGHT * hedging_score(answer);
+    let mut weights = HEDGING_WEIGHT;
+    if let Some(mean_logprob) = mean_logprob.filter(|m| m.is_finite()) {
+        weighted += LOGPROB_WEIGHT * logprob_score(mean_logprob);
+        weights += LOGPROB_WEIGHT;
+    }
+    if let Some(self_eval) = self_eval {
+        weighted += SELF_EVAL_WEIGHT * self_eval.clamp(0.0, 1.0);
+        weights += SELF_EVAL_WEIGHT;
+    }
+    weighted / weights
+}
+
+/// Prompt asking the model to rate an answer from 0 to 10
+pub fn self_eval_prompt(question: &str, answer: &str) -> String {
+    format!(
+        "Question: {question}\n\nAnswer: {answer}\n\n\
+         On a scale from 0 (certainly wrong) to 10 (certainly right), how likely is \
+         this answer to be correct? Reply with a single number."
+    )
+}
+
+/// Rating in a self-evaluation reply, scaled to 0–1
+pub fn parse_self_eval(reply: &str) -> Option<f32> {
+    let number: String = reply
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let rating: f32 = number.trim_end_matches('.').parse().ok()?;
+    (0.0..=10.0).contains(&rating).then_some(rating / 10.0)
+}
+
+/// Ask `provider` to rate `answer`
+async fn self_evaluate(provider: &TextToTextModel, question: &str, answer: &str) -> Option<f32> {
+    let params = CandleCompletionParams {
+        temperature: 0.0,
+        max_tokens: NonZeroU64::new(SELF_EVAL_MAX_TOKENS),
+        ..Default::default()
+    };
+    let mut stream = provider.prompt(CandlePrompt::new(self_eval_prompt(question, answer)), &params);
+    let mut reply = String::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            CandleCompletionChunk::Text(text) | CandleCompletionChunk::Complete { text, .. } => {
+                reply.push_str(&text);
+            }
+            CandleCompletionChunk::Error(e) => {
+                log::warn!("Confidence self-evaluation failed: {e}");
+                return None;
+            }
+            _ => {}
+        }
+    }
+    let rating = parse_self_eval(&reply);
+    if rating.is_none() {
+        log::debug!("Unparseable self-evaluation reply: {reply:?}");
+    }
+    rating
+}
@@ -0,0 +1,259 @@
+//! Handing a conversation from one agent to another
+//!
+//! Multi-specialist workflows (researcher → coder → reviewer) pass work
+//! between agents with different personas or models. A [`HandoffDocument`]
+//! packages what the next agent needs: a summary of the conversation so far,
+//! the task it is being handed, the memories that matter and the tool
+//! outputs gathered along the way. `.handoff(document)` on the receiving
+//! agent's builder starts it with the document as its brief; the stream
+//! opens with a `CandleMessageChunk::Handoff` carrying the document, so
+//! callers can log or display the transfer before the new agent replies.
+//!
+//! ```ignore
+//! let document = HandoffDocument::from_history("researcher", &history)
+//!     .with_memories(&findings)
+//!     .with_pending_task("Implement the parser described above");
+//! let stream = CandleFluentAi::agent_role("coder")
+//!     .model(coder_model)
+//!     .into_agent()?
+//!     .handoff(document);
+//! ```
+
+use std::fmt::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::builders::agent_role::ConversationHistory;
+use crate::domain::chat::message::CandleMessageRole;
+use crate::domain::memory::primitives::node::MemoryNode;
+
+/// Messages of the conversation kept in the default summary
+pub const HANDOFF_SUMMARY_MESSAGES: usize = 12;
+
+/// Characters kept of each message in the default summary
+pub const HANDOFF_MESSAGE_CHARS: usize = 400;
+
+/// Characters the default summary may take
+pub const HANDOFF_SUMMARY_CHARS: usize = 4000;
+
+/// Tool outputs kept by [`HandoffDocument::from_history`], the most recent
+pub const HANDOFF_TOOL_OUTPUTS: usize = 8;
+
+/// Characters kept of each tool output
+pub const HANDOFF_TOOL_OUTPUT_CHARS: usize = 2000;
+
+/// A memory passed to the next agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoffMemory {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_id: Option<String>,
+    pub content: String,
+}
+
+/// A tool result passed to the next agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoffToolOutput {
+    /// Tool that produced the output, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    pub output: String,
+}
+
+/// Conversation state handed from one agent to another
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HandoffDocument {
+    /// Agent handing the conversation off
+    pub from: String,
+    /// Agent receiving it (set when the handoff starts)
+    #[serde(default)]
+    pub to: String,
+    /// What has happened so far
+    pub summary: String,
+    /// What the receiving agent should do
+    pub pending_task: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memories: Vec<HandoffMemory>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_outputs: Vec<HandoffToolOutput>,
+}
+
+impl HandoffDocument {
+    /// Empty document from agent `from`
+    pub fn new(from: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Package `history` as agent `from` leaves it
+    ///
+    /// The summary keeps the opening message, which usually states the
+    /// goal, and as many of the latest messages as fit
+    /// [`HANDOFF_SUMMARY_MESSAGES`] and [`HANDOFF_SUMMARY_CHARS`], each
+    /// clipped to [`HANDOFF_MESSAGE_CHARS`], noting how many were left out
+    /// between them. Tool messages become tool outputs, the last
+    /// [`HANDOFF_TOOL_OUTPUTS`] clipped to [`HANDOFF_TOOL_OUTPUT_CHARS`], and
+    /// the pending task is the last user message. Replace any of them with
+    /// the `with_*` methods, e.g. with a model-written summary.
+    pub fn from_history(from: impl Into<String>, history: &ConversationHistory) -> Self {
+        let mut document = Self::new(from);
+        let messages = history.messages();
+
+        let lines: Vec<String> = messages
+            .iter()
+            .filter(|(role, _)| *role != CandleMessageRole::Tool)
+            .map(|(role, content)| {
+                format!("{role}: {}", clip(content.trim(), HANDOFF_MESSAGE_CHARS))
+            })
+            .collect();
+        document.summary = summarize(&lines);
+
+        let tool_outputs: Vec<&String> = messages
+            .iter()
+            .filter(|(role, _)| *role == CandleMessageRole::Tool)
+            .map(|(_, output)| output)
+            .collect();
+        let skip = tool_outputs.len().saturating_sub(HANDOFF_TOOL_OUTPUTS);
+        document.tool_outputs = tool_outputs[skip..]
+            .iter()
+            .map(|output| HandoffToolOutput {
+                tool: None,
+                output: clip(output.trim(), HANDOFF_TOOL_OUTPUT_CHARS),
+            })
+            .collect();
+
+        document.pending_task = messages
+            .iter()
+            .rev()
+            .find(|(role, _)| *role == CandleMessageRole::User)
+            .map(|(_, content)| content.trim().to_string())
+            .unwrap_or_default();
+        document
+    }
+
+    /// Replace the summary
+    #[must_use]
+    pub fn with_summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = summary.into();
+        self
+    }
+
+    /// Replace the pending task
+    #[must_use]
+    pub fn with_pending_task(mut self, task: impl Into<String>) -> Self {
+        self.pending_task = task.into();
+        self
+    }
+
+    /// Add a memory by its text
+    #[must_use]
+    pub fn with_memory(mut self, content: impl Into<String>) -> Self {
+        self.memories.push(HandoffMemory {
+            memory_id: None,
+            content: content.into(),
+        });
+        self
+    }
+
+    /// Add recalled memories
+    #[must_use]
+    pub fn with_memories<'a>(mut self, memories: impl IntoIterator<Item = &'a MemoryNode>) -> Self {
+        self.memories
+            .extend(memories.into_iter().map(|memory| HandoffMemory {
+                memory_id: Some(memory.id().to_string()),
+                content: memory.content().to_string(),
+            }));
+        self
+    }
+
+    /// Add the output of `tool`
+    #[must_use]
+    pub fn with_tool_output(mut self, tool: impl Into<String>, output: impl Into<String>) -> Self {
+        self.tool_outputs.push(HandoffToolOutput {
+            tool: Some(tool.into()),
+            output: output.into(),
+        });
+        self
+    }
+
+    /// The message that starts the receiving agent
+    ///
+    /// Empty sections are left out; the pending task comes last, so it is
+    /// what the agent answers.
+    pub fn brief(&self) -> String {
+        let mut brief = format!(
+            "You are taking over this conversation from {}.\n",
+            self.from
+        );
+        if !self.summary.is_empty() {
+            let _ = write!(brief, "\n## Conversation so far\n{}\n", self.summary);
+        }
+        if !self.memories.is_empty() {
+            brief.push_str("\n## Relevant memories\n");
+            for memory in &self.memories {
+                let _ = writeln!(brief, "- {}", memory.content.trim());
+            }
+        }
+        if !self.tool_outputs.is_empty() {
+            brief.push_str("\n## Tool outputs\n");
+            for output in &self.tool_outputs {
+                let tool = output.tool.as_deref().unwrap_or("tool");
+                let _ = writeln!(brief, "### {tool}\n{}", output.output.trim());
+            }
+        }
+        let _ = write!(brief, "\n## Your task\n{}", self.pending_task);
+        brief
+    }
+}
+
+impl fmt::Display for HandoffDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} → {} ({} memories, {} tool outputs): {}",
+            self.from,
+            self.to,
+            self.memories.len(),
+            self.tool_outputs.len(),
+            clip(&self.pending_task, HANDOFF_MESSAGE_CHARS)
+        )
+    }
+}
+
+/// The first of `lines` and as many of the last as fit the summary limits,
+/// with a note of how many were left out between them
+fn summarize(lines: &[String]) -> String {
+    let Some((first, rest)) = lines.split_first() else {
+        return String::new();
+    };
+    let mut budget = HANDOFF_SUMMARY_CHARS.saturating_sub(first.chars().count());
+    let mut recent = 0;
+    for line in rest.iter().rev().take(HANDOFF_SUMMARY_MESSAGES - 1) {
+        let len = line.chars().count() + 1;
+        if len > budget {
+            break;
+        }
+        budget -= len;
+        recent += 1;
+    }
+
+    let mut summary = first.clone();
+    let omitted = rest.len() - recent;
+    if omitted > 0 {
+        let _ = write!(summary, "\n[{omitted} earlier messages omitted]");
+    }
+    for line in &rest[omitted..] {
+        summary.push('\n');
+        summary.push_str(line);
+    }
+    summary
+}
+
+/// `text` cut to `max_chars` characters
+fn clip(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
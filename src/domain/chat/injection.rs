@@ -0,0 +1,125 @@
+//! Placement of recalled memory context in the prompt
+//!
+//! Where recalled context sits relative to the system prompt and the question
+//! changes how much weight small models give it, so the placement is set per
+//! agent with `.memory_injection(...)`. A relevance cutoff drops weak matches
+//! entirely instead of letting them fill the budget.
+//!
+//! ```ignore
+//! let agent = CandleFluentAi::agent_role("support")
+//!     .memory_injection(MemoryInjection::new(ContextPlacement::AfterQuestion).with_min_score(0.3))
+//!     .into_agent()?;
+//! ```
+
+use crate::domain::context::builder::{ContextBuilder, DEFAULT_CONTEXT_HEADING};
+
+/// Heading for context placed in the system prompt
+pub const BACKGROUND_CONTEXT_HEADING: &str = "## Background Knowledge";
+
+/// Tool name the pseudo-tool message is attributed to
+pub const MEMORY_TOOL_NAME: &str = "memory_recall";
+
+/// Where recalled context goes in the prompt
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContextPlacement {
+    /// Part of the system instructions, framed as standing background
+    /// knowledge, ahead of the earlier turns of the conversation
+    SystemPrompt,
+    /// Its own block after the earlier turns, just before the question
+    #[default]
+    BeforeQuestion,
+    /// A tool result turn (`Tool (memory_recall): ...`) ahead of the question
+    ToolMessage,
+    /// After the question, closest to where the answer starts
+    AfterQuestion,
+}
+
+impl ContextPlacement {
+    /// Heading placed above the context entries
+    pub fn heading(self) -> &'static str {
+        match self {
+            Self::SystemPrompt => BACKGROUND_CONTEXT_HEADING,
+            Self::BeforeQuestion | Self::AfterQuestion => DEFAULT_CONTEXT_HEADING,
+            Self::ToolMessage => "",
+        }
+    }
+}
+
+/// How recalled context is injected into the prompt
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemoryInjection {
+    /// Where the context goes
+    pub placement: ContextPlacement,
+    /// Hits scoring below this are dropped (no cutoff when unset)
+    pub min_score: Option<f32>,
+}
+
+impl MemoryInjection {
+    /// Injection at `placement` without a relevance cutoff
+    pub fn new(placement: ContextPlacement) -> Self {
+        Self {
+            placement,
+            min_score: None,
+        }
+    }
+
+    /// Drop hits scoring below `min_score` (similarity × importance × weight)
+    #[must_use]
+    pub fn with_min_score(mut self, min_score: f32) -> Self {
+        self.min_score = Some(min_score);
+        self
+    }
+
+    /// Context builder with this placement's heading and cutoff
+    pub fn context_builder(&self, token_budget: usize) -> ContextBuilder {
+        let builder = ContextBuilder::new(token_budget).with_heading(self.placement.heading());
+        match self.min_score {
+            Some(min_score) => builder.with_min_score(min_score),
+            None => builder,
+        }
+    }
+
+    /// Full prompt with `context` placed per the configured placement
+    ///
+    /// `transcript` holds the earlier turns and follows the system prompt;
+    /// only [`ContextPlacement::SystemPrompt`] puts the context ahead of it.
+    pub fn assemble(
+        &self,
+        system_prompt: &str,
+        transcript: &str,
+        context: &str,
+        user_message: &str,
+    ) -> String {
+        let context = context.trim_end();
+        let mut prompt = system_prompt.to_string();
+        if self.placement == ContextPlacement::SystemPrompt && !context.is_empty() {
+            prompt.push_str("\n\n");
+            prompt.push_str(context);
+        }
+        if !transcript.is_empty() {
+            prompt.push_str("\n\n");
+            prompt.push_str(transcript);
+        }
+        if context.is_empty() {
+            return format!("{prompt}\n\nUser: {user_message}");
+        }
+        match self.placement {
+            ContextPlacement::SystemPrompt => format!("{prompt}\n\nUser: {user_message}"),
+            ContextPlacement::BeforeQuestion => {
+                format!("{prompt}\n\n{context}\n\nUser: {user_message}")
+            }
+            ContextPlacement::ToolMessage => format!(
+                "{prompt}\n\nTool ({MEMORY_TOOL_NAME}):\n{context}\n\nUser: {user_message}"
+            ),
+            ContextPlacement::AfterQuestion => {
+                format!("{prompt}\n\nUser: {user_message}\n\n{context}")
+            }
+        }
+    }
+}
+
+impl From<ContextPlacement> for MemoryInjection {
+    fn from(placement: ContextPlacement) -> Self {
+        Self::new(placement)
+    }
+}
@@ -0,0 +1,53 @@
+//! Stopping a turn while the reply is still streaming
+//!
+//! A [`TurnInterrupt`] is handed to the agent with `.interruptible(handle)`
+//! and kept by the caller. Calling [`TurnInterrupt::interrupt`] stops the
+//! in-flight completion: the session sends a `Complete` chunk whose
+//! `finish_reason` is [`INTERRUPTED_FINISH_REASON`], and stores the turn with
+//! the partial reply, tagged [`INTERRUPTED_TAG`]. The provider sees the same
+//! signal through the completion's cancellation token, so its token loop
+//! stops and releases the model right away.
+//!
+//! A handle covers one turn; create a new one for the next turn.
+
+use tokio_util::sync::CancellationToken;
+
+/// `finish_reason` of the `Complete` chunk ending an interrupted turn
+pub const INTERRUPTED_FINISH_REASON: &str = "interrupted";
+
+/// Memory tag on the assistant message of an interrupted turn
+pub const INTERRUPTED_TAG: &str = "turn.interrupted";
+
+/// Signals "stop generating" for one in-flight turn
+#[derive(Debug, Clone, Default)]
+pub struct TurnInterrupt {
+    token: CancellationToken,
+}
+
+impl TurnInterrupt {
+    /// Handle that has not been interrupted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the turn; later calls have no effect
+    pub fn interrupt(&self) {
+        self.token.cancel();
+    }
+
+    /// Whether [`interrupt`](Self::interrupt) has been called
+    pub fn is_interrupted(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once the turn is interrupted
+    pub async fn interrupted(&self) {
+        self.token.cancelled().await;
+    }
+
+    /// Token cancelled by [`interrupt`](Self::interrupt), for
+    /// `CandleCompletionParams::with_cancellation`
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
@@ -2,6 +2,8 @@
 
 use std::fmt;
 
+use super::regenerate::RegenerateOptions;
+
 /// Controls the flow of a chat conversation in the unified domain system.
 ///
 /// This enum is used to control the flow of a chat conversation, allowing for breaking out of loops,
@@ -19,6 +21,10 @@ pub enum CandleChatLoop {
     /// Prompt the user for input and continue the conversation.
     /// The String contains the prompt message.
     UserPrompt(String),
+
+    /// Answer the previous user message again with different sampling.
+    /// The message is not added to history a second time.
+    Regenerate(RegenerateOptions),
 }
 
 impl fmt::Display for CandleChatLoop {
@@ -29,6 +35,9 @@ impl fmt::Display for CandleChatLoop {
             CandleChatLoop::UserPrompt(prompt) => {
                 write!(f, "CandleChatLoop::UserPrompt({prompt:?})")
             }
+            CandleChatLoop::Regenerate(options) => {
+                write!(f, "CandleChatLoop::Regenerate({options:?})")
+            }
         }
     }
 }
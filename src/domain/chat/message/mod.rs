@@ -153,6 +153,8 @@ pub mod types {
                 token_count: None,
                 elapsed_secs: None,
                 tokens_per_sec: None,
+                citations: Vec::new(),
+                confidence: None,
             }
         }
 
@@ -238,8 +240,20 @@ pub mod types {
             token_count: Option<u32>,
             elapsed_secs: Option<f64>,
             tokens_per_sec: Option<f64>,
+            /// Memories that informed the answer (when citations are enabled)
+            #[serde(default, skip_serializing_if = "Vec::is_empty")]
+            citations: Vec<crate::domain::chat::citations::Citation>,
+            /// Estimated probability (0–1) that the answer is right (when confidence is enabled)
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            confidence: Option<f32>,
         },
 
+        /// What the model was shown this turn (when tracing is enabled)
+        Trace(crate::domain::chat::trace::TurnTrace),
+
+        /// Conversation handed to a new agent, sent before its reply
+        Handoff(crate::domain::chat::handoff::HandoffDocument),
+
         /// Error occurred during streaming
         Error(String),
     }
@@ -296,6 +310,12 @@ pub mod types {
                     }
                     write!(f, "{output}")
                 }
+                CandleMessageChunk::Trace(trace) => {
+                    write!(f, "🔍 Trace: {trace}")
+                }
+                CandleMessageChunk::Handoff(document) => {
+                    write!(f, "🤝 Handoff: {document}")
+                }
                 CandleMessageChunk::Error(error) => {
                     write!(f, "❌ Error: {error}")
                 }
@@ -18,20 +18,29 @@
 //! crossbeam-skiplist for lock-free data structures, and atomic operations
 //! for thread-safe state management.
 
+pub mod citations;
 pub mod commands;
+pub mod confidence;
 pub mod config;
 pub mod conversation;
 pub mod export;
 pub mod formatting;
+pub mod handoff;
+pub mod injection;
+pub mod interrupt;
 pub mod orchestration;
+pub mod profile;
 
 pub mod r#loop;
 pub mod macros;
 pub mod message;
 pub mod realtime;
+pub mod recall;
+pub mod regenerate;
 pub mod search;
 pub mod session;
 pub mod templates;
+pub mod trace;
 pub mod types;
 
 // Re-export types with corrected names to avoid ambiguous glob re-exports
@@ -39,6 +48,7 @@ pub use commands::{
     CommandExecutor as CandleCommandExecutor, CommandRegistry as CandleCommandRegistry,
     ImmutableChatCommand as CandleImmutableChatCommand,
 };
+pub use confidence::{ConfidenceEstimator, LowConfidenceAction};
 pub use config::{CandleChatConfig, CandlePersonalityConfig};
 pub use conversation::CandleConversationEvent as CandleConversation;
 pub use export::{ExportData as CandleExportData, ExportFormat as CandleExportFormat};
@@ -46,6 +56,9 @@ pub use formatting::{
     FormatStyle as CandleFormatStyle, StreamingMessageFormatter as CandleStreamingMessageFormatter,
 };
 
+pub use handoff::HandoffDocument;
+pub use injection::{ContextPlacement, MemoryInjection};
+pub use interrupt::TurnInterrupt;
 pub use r#loop::CandleChatLoop;
 pub use macros::{
     ChatMacro as CandleChatMacro, MacroAction as CandleMacroAction,
@@ -59,6 +72,13 @@ pub use message::message_processing::{
 };
 pub use message::types::{CandleMessage, CandleMessageChunk, CandleMessageRole};
 pub use realtime::RealTimeSystem as CandleRealTimeSystem;
+pub use profile::{ProfileFact, ProfileMemory};
+#[cfg(feature = "memory")]
+pub use profile::ProfileStore;
+pub use recall::RecallLibrary;
+#[cfg(feature = "memory")]
+pub use recall::SharedRecall;
+pub use regenerate::RegenerateOptions;
 pub use search::{
     CandleConversationTag, CandleConversationTagger, CandleEnhancedHistoryManager,
     CandleTaggingStatistics, ChatSearchIndex as CandleChatSearchIndex,
@@ -73,6 +93,7 @@ pub use templates::{
     ChatTemplate as CandleChatTemplate, TemplateCategory as CandleTemplateCategory,
     TemplateManager as CandleTemplateManager,
 };
+pub use trace::{TurnTrace, Verbosity};
 pub use types::responses::{
     FinalResponse as CandleFinalResponse, FunctionCall as CandleFunctionCall,
     OpenAIFunctionCallResponse as CandleOpenAIFunctionCallResponse, ToolCall as CandleToolCall,
@@ -0,0 +1,562 @@
+//! Persistent user profile applied to every turn
+//!
+//! With `.profile_memory(ProfileMemory::new())`, statements about the user —
+//! "my timezone is Europe/Berlin", "I prefer to write in Rust", "always use tabs" —
+//! are picked out of each user message, passed through the confirmation
+//! policy and stored in a dedicated library under the [`PROFILE_CATEGORY`]
+//! category, one memory per [`ProfileKey`]. A newer value replaces the old
+//! one. On every turn the stored facts are appended to the system prompt as a
+//! short block trimmed to a small token budget.
+//!
+//! ```ignore
+//! let agent = CandleFluentAi::agent_role("assistant")
+//!     .into_agent()?
+//!     .profile_memory(ProfileMemory::new().with_confirmation(|fact| async move {
+//!         ask_user(&format!("Remember that your {fact}?")).await
+//!     }));
+//! ```
+
+#[cfg(feature = "memory")]
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+use futures::future::BoxFuture;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::context::builder::estimate_tokens;
+use crate::domain::memory::primitives::node::MemoryNode;
+#[cfg(feature = "memory")]
+use crate::domain::memory::primitives::types::MemoryTypeEnum;
+#[cfg(feature = "memory")]
+use crate::memory::core::manager::coordinator::NewMemory;
+#[cfg(feature = "memory")]
+use crate::memory::core::manager::pool::CoordinatorPool;
+#[cfg(feature = "memory")]
+use crate::memory::core::ops::filter::MemoryFilter;
+#[cfg(feature = "memory")]
+use crate::memory::core::primitives::metadata::MemoryMetadata;
+
+/// Memory category of stored profile facts
+pub const PROFILE_CATEGORY: &str = "profile";
+
+/// Library profile facts are stored in unless configured otherwise
+pub const DEFAULT_PROFILE_LIBRARY: &str = "profile";
+
+/// Default budget of the profile block, in estimated tokens
+pub const DEFAULT_PROFILE_TOKENS: usize = 150;
+
+/// Tag on every stored profile fact
+pub const PROFILE_TAG: &str = "profile";
+
+/// Custom metadata key holding the fact's [`ProfileKey`]
+pub const PROFILE_KEY: &str = "profile_key";
+
+/// Custom metadata key holding the fact's value
+pub const PROFILE_VALUE: &str = "profile_value";
+
+/// Heading placed above the profile block
+pub const PROFILE_HEADING: &str = "## About the User";
+
+/// Most stored facts read per turn
+const MAX_PROFILE_FACTS: usize = 200;
+
+/// Longest value accepted from a message
+const MAX_VALUE_CHARS: usize = 60;
+
+/// What a profile fact describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileKey {
+    /// How the user wants to be addressed
+    Name,
+    /// IANA zone or UTC offset
+    Timezone,
+    /// Preferred programming language
+    ProgrammingLanguage,
+    /// Language answers should be written in
+    ResponseLanguage,
+    /// Tabs or N spaces
+    Indentation,
+    /// snake_case, camelCase, ...
+    NamingConvention,
+}
+
+impl ProfileKey {
+    /// Stable identifier stored in memory metadata
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Timezone => "timezone",
+            Self::ProgrammingLanguage => "programming_language",
+            Self::ResponseLanguage => "response_language",
+            Self::Indentation => "indentation",
+            Self::NamingConvention => "naming_convention",
+        }
+    }
+
+    /// Key for a stored identifier
+    pub fn parse(key: &str) -> Option<Self> {
+        [
+            Self::Name,
+            Self::Timezone,
+            Self::ProgrammingLanguage,
+            Self::ResponseLanguage,
+            Self::Indentation,
+            Self::NamingConvention,
+        ]
+        .into_iter()
+        .find(|k| k.as_str() == key)
+    }
+
+    /// Label used in the prompt block
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Timezone => "timezone",
+            Self::ProgrammingLanguage => "preferred programming language",
+            Self::ResponseLanguage => "preferred response language",
+            Self::Indentation => "indentation",
+            Self::NamingConvention => "naming convention",
+        }
+    }
+}
+
+/// One thing known about the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileFact {
+    pub key: ProfileKey,
+    pub value: String,
+}
+
+impl ProfileFact {
+    pub fn new(key: ProfileKey, value: impl Into<String>) -> Self {
+        Self {
+            key,
+            value: value.into(),
+        }
+    }
+
+    /// Fact stored in `memory`, if it is a profile fact
+    pub fn from_memory(memory: &MemoryNode) -> Option<Self> {
+        let custom = &memory.metadata.custom;
+        let key = ProfileKey::parse(custom.get(PROFILE_KEY)?.as_str()?)?;
+        Some(Self::new(key, custom.get(PROFILE_VALUE)?.as_str()?))
+    }
+}
+
+impl fmt::Display for ProfileFact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is {}", self.key.label(), self.value)
+    }
+}
+
+/// Languages accepted as a preferred programming language
+const PROGRAMMING_LANGUAGES: &[&str] = &[
+    "Bash", "C", "C#", "C++", "Clojure", "Dart", "Elixir", "Erlang", "F#", "Go", "Haskell",
+    "Java", "JavaScript", "Julia", "Kotlin", "Lua", "OCaml", "Perl", "PHP", "Python", "R",
+    "Ruby", "Rust", "Scala", "SQL", "Swift", "TypeScript", "Zig",
+];
+
+/// Languages accepted as a preferred response language
+const RESPONSE_LANGUAGES: &[&str] = &[
+    "English", "German", "French", "Spanish", "Italian", "Portuguese", "Dutch", "Polish",
+    "Russian", "Ukrainian", "Turkish", "Japanese", "Chinese", "Korean", "Hindi", "Arabic",
+];
+
+/// A pattern that reads one fact out of a sentence
+struct ProfileRule {
+    key: ProfileKey,
+    regex: Regex,
+    /// Value stored for the captured text, or `None` to reject it
+    accept: fn(&str) -> Option<String>,
+}
+
+/// Accept the captured text as it is
+fn as_captured(value: &str) -> Option<String> {
+    Some(value.to_string())
+}
+
+/// Canonical spelling of a known programming language
+fn programming_language(value: &str) -> Option<String> {
+    known(PROGRAMMING_LANGUAGES, value)
+}
+
+/// Canonical spelling of a known response language
+fn response_language(value: &str) -> Option<String> {
+    known(RESPONSE_LANGUAGES, value)
+}
+
+fn known(names: &[&str], value: &str) -> Option<String> {
+    names
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(value))
+        .map(|name| name.to_string())
+}
+
+/// IANA zone (`Europe/Berlin`), UTC/GMT offset (`UTC+2`) or abbreviation (`CET`)
+const TIMEZONE: &str = r"(\p{Lu}[\w-]*(?:/[\w+-]+)+|(?:UTC|GMT)(?:[+-]\d{1,2}(?::?\d{2})?)?|\p{Lu}{2,5})\b";
+
+/// Tabs or a number of spaces
+const INDENT: &str = r"(tabs|\d+[- ]spaces?)";
+
+static PROFILE_RULES: LazyLock<Vec<ProfileRule>> = LazyLock::new(|| {
+    let rules: [(ProfileKey, String, fn(&str) -> Option<String>); 10] = [
+        // Names must be capitalized, so "call me back" is not a name
+        (ProfileKey::Name, r"(?i:\bmy name is)\s+(\p{Lu}[\p{L}'-]*)".into(), as_captured),
+        (ProfileKey::Name, r"^(?i:(?:please\s+)?call me)\s+(\p{Lu}[\p{L}'-]*)".into(), as_captured),
+        (ProfileKey::Timezone, format!(r"(?i:\bmy time ?zone is)\s+{TIMEZONE}"), as_captured),
+        (
+            ProfileKey::Timezone,
+            format!(r"(?i:\bI(?:'m| am) in (?:the )?){TIMEZONE}(?i:\s+time ?zone)"),
+            as_captured,
+        ),
+        (
+            ProfileKey::ProgrammingLanguage,
+            r"(?i)\bI prefer (?:to (?:write|code|program) in|writing in|coding in|programming in)\s+([\w#+.-]*\w[#+]*)".into(),
+            programming_language,
+        ),
+        (
+            ProfileKey::ProgrammingLanguage,
+            r"(?i)\bmy (?:preferred|favou?rite|main) (?:programming )?language is\s+([\w#+.-]*\w[#+]*)".into(),
+            programming_language,
+        ),
+        // A one-off "answer in French" is a request, not a preference
+        (
+            ProfileKey::ResponseLanguage,
+            r"(?i)\b(?:always|from now on,?)\s+(?:answer|reply|respond|talk to me|write to me)(?: to me)? in\s+(\p{L}+)\b".into(),
+            response_language,
+        ),
+        (
+            ProfileKey::ResponseLanguage,
+            r"(?i)\bI prefer (?:answers|replies|responses) in\s+(\p{L}+)\b".into(),
+            response_language,
+        ),
+        // Indentation must be named, so "I use 2 spaces after a period" is ignored
+        (
+            ProfileKey::Indentation,
+            format!(r"(?i)\b(?:I (?:prefer|like|use)|please use|always use)\s+{INDENT}\s+(?:for\s+)?indent(?:ation|ing)?\b"),
+            as_captured,
+        ),
+        (
+            ProfileKey::NamingConvention,
+            r"(?i)\b(?:I (?:prefer|like|use)|please use|always use)\s+(snake_case|camelCase|PascalCase|kebab-case|SCREAMING_SNAKE_CASE)\b".into(),
+            as_captured,
+        ),
+    ];
+    rules
+        .into_iter()
+        .filter_map(|(key, pattern, accept)| match Regex::new(&pattern) {
+            Ok(regex) => Some(ProfileRule { key, regex, accept }),
+            Err(e) => {
+                log::error!("Invalid profile rule for {}: {e}", key.as_str());
+                None
+            }
+        })
+        .collect()
+});
+
+/// Sentences of `message` that are not questions
+///
+/// A sentence ends at a newline, or at `.`, `!` or `?` followed by whitespace.
+fn statements(message: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut chars = message.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_break = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        if c == '\n' || (matches!(c, '.' | '!' | '?') && at_break) {
+            let sentence = message[start..i].trim();
+            if c != '?' && !sentence.is_empty() {
+                statements.push(sentence);
+            }
+            start = i + c.len_utf8();
+        }
+    }
+    let rest = message[start..].trim();
+    if !rest.is_empty() {
+        statements.push(rest);
+    }
+    statements
+}
+
+/// Profile facts stated in a user message, at most one per key
+///
+/// Only first-person statements are recognized and questions are skipped,
+/// so "what is Ana's timezone?" does not change the profile. Names must be
+/// capitalized, languages must be known ones and timezones must look like
+/// zones.
+pub fn extract_profile_facts(message: &str) -> Vec<ProfileFact> {
+    let statements = statements(message);
+    let mut facts: Vec<ProfileFact> = Vec::new();
+    for rule in PROFILE_RULES.iter() {
+        if facts.iter().any(|f| f.key == rule.key) {
+            continue;
+        }
+        let value = statements.iter().find_map(|sentence| {
+            let captured = rule.regex.captures(sentence)?.get(1)?.as_str();
+            let captured = captured.trim_end_matches(['.', ',', '!', '?', ';', ':']);
+            if captured.is_empty() || captured.chars().count() > MAX_VALUE_CHARS {
+                return None;
+            }
+            (rule.accept)(captured)
+        });
+        if let Some(value) = value {
+            facts.push(ProfileFact::new(rule.key, value));
+        }
+    }
+    facts
+}
+
+/// Profile block for the system prompt, trimmed to `token_budget`
+///
+/// Facts are kept in the given order until the budget runs out. Returns an
+/// empty string when there is nothing to add.
+pub fn render_profile(facts: &[ProfileFact], token_budget: usize) -> String {
+    let mut block = PROFILE_HEADING.to_string();
+    let mut used = estimate_tokens(&block);
+    let mut included = 0;
+    for fact in facts {
+        let line = format!("\n- {fact}");
+        let tokens = estimate_tokens(&line);
+        if used + tokens > token_budget {
+            break;
+        }
+        used += tokens;
+        block.push_str(&line);
+        included += 1;
+    }
+    if included == 0 { String::new() } else { block }
+}
+
+/// Callback asked before a newly learned fact is stored
+pub type ProfileConfirmation = Arc<dyn Fn(&ProfileFact) -> BoxFuture<'static, bool> + Send + Sync>;
+
+/// How learned facts get into the profile
+#[derive(Clone, Default)]
+pub enum ProfileConfirmationPolicy {
+    /// Store every recognized fact
+    #[default]
+    Automatic,
+    /// Store a fact only if the callback approves it
+    Confirm(ProfileConfirmation),
+    /// Never learn; only apply facts already stored
+    ReadOnly,
+}
+
+impl fmt::Debug for ProfileConfirmationPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Automatic => write!(f, "Automatic"),
+            Self::Confirm(_) => write!(f, "Confirm"),
+            Self::ReadOnly => write!(f, "ReadOnly"),
+        }
+    }
+}
+
+/// Profile memory settings, passed to `.profile_memory(...)`
+#[derive(Debug, Clone)]
+pub struct ProfileMemory {
+    /// Library the facts are stored in
+    pub library: String,
+    /// Budget of the profile block, in estimated tokens
+    pub token_budget: usize,
+    /// How learned facts are approved
+    pub policy: ProfileConfirmationPolicy,
+}
+
+impl Default for ProfileMemory {
+    fn default() -> Self {
+        Self {
+            library: DEFAULT_PROFILE_LIBRARY.to_string(),
+            token_budget: DEFAULT_PROFILE_TOKENS,
+            policy: ProfileConfirmationPolicy::default(),
+        }
+    }
+}
+
+impl ProfileMemory {
+    /// Default library, budget and automatic learning
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store facts in `library`
+    #[must_use]
+    pub fn with_library(mut self, library: impl Into<String>) -> Self {
+        self.library = library.into();
+        self
+    }
+
+    /// Set the profile block budget
+    #[must_use]
+    pub fn with_token_budget(mut self, tokens: usize) -> Self {
+        self.token_budget = tokens;
+        self
+    }
+
+    /// Ask `callback` before storing each learned fact
+    #[must_use]
+    pub fn with_confirmation<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ProfileFact) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = bool> + Send + 'static,
+    {
+        self.policy = ProfileConfirmationPolicy::Confirm(Arc::new(move |fact| Box::pin(callback(fact))));
+        self
+    }
+
+    /// Apply stored facts without learning new ones
+    #[must_use]
+    pub fn read_only(mut self) -> Self {
+        self.policy = ProfileConfirmationPolicy::ReadOnly;
+        self
+    }
+}
+
+/// Profile memory opened for a chat session
+#[cfg(feature = "memory")]
+#[derive(Clone)]
+pub struct ProfileStore {
+    config: ProfileMemory,
+    pool: Arc<CoordinatorPool>,
+}
+
+#[cfg(feature = "memory")]
+impl fmt::Debug for ProfileStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProfileStore")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "memory")]
+impl ProfileStore {
+    /// Read and write `config.library` through `pool`
+    pub fn new(config: ProfileMemory, pool: Arc<CoordinatorPool>) -> Self {
+        Self { config, pool }
+    }
+
+    /// Settings this store was opened with
+    pub fn config(&self) -> &ProfileMemory {
+        &self.config
+    }
+
+    /// Stored facts, newest value per key
+    ///
+    /// An unavailable library yields no facts rather than failing the turn.
+    pub async fn facts(&self) -> Vec<ProfileFact> {
+        let mut stored = match self.stored().await {
+            Ok(stored) => stored,
+            Err(e) => {
+                log::warn!("Profile library '{}' unavailable: {e}", self.config.library);
+                return Vec::new();
+            }
+        };
+        stored.sort_by_key(|m| std::cmp::Reverse(m.creation_time().into_inner()));
+
+        let mut facts: Vec<ProfileFact> = Vec::new();
+        for memory in &stored {
+            if let Some(fact) = ProfileFact::from_memory(memory)
+                && !facts.iter().any(|f| f.key == fact.key)
+            {
+                facts.push(fact);
+            }
+        }
+        facts
+    }
+
+    /// Profile block for the system prompt (empty when nothing is stored)
+    pub async fn render(&self) -> String {
+        render_profile(&self.facts().await, self.config.token_budget)
+    }
+
+    /// Learn the facts stated in `message`
+    ///
+    /// Facts already stored with the same value are skipped; the rest go
+    /// through the confirmation policy and replace any older value for their
+    /// key. Returns the facts that were stored.
+    pub async fn learn(&self, message: &str) -> Vec<ProfileFact> {
+        if matches!(self.config.policy, ProfileConfirmationPolicy::ReadOnly) {
+            return Vec::new();
+        }
+        let candidates = extract_profile_facts(message);
+        if candidates.is_empty() {
+            return Vec::new();
+        }
+
+        let stored = match self.stored().await {
+            Ok(stored) => stored,
+            Err(e) => {
+                log::warn!("Profile library '{}' unavailable: {e}", self.config.library);
+                return Vec::new();
+            }
+        };
+        let mut by_key: HashMap<ProfileKey, Vec<(String, String)>> = HashMap::new();
+        for memory in &stored {
+            if let Some(fact) = ProfileFact::from_memory(memory) {
+                by_key
+                    .entry(fact.key)
+                    .or_default()
+                    .push((memory.id().to_string(), fact.value));
+            }
+        }
+
+        let mut learned = Vec::new();
+        for fact in candidates {
+            let previous = by_key.remove(&fact.key).unwrap_or_default();
+            if previous.iter().any(|(_, value)| value.eq_ignore_ascii_case(&fact.value)) {
+                continue;
+            }
+            if let ProfileConfirmationPolicy::Confirm(confirm) = &self.config.policy
+                && !confirm(&fact).await
+            {
+                continue;
+            }
+            match self.replace(&fact, &previous).await {
+                Ok(()) => learned.push(fact),
+                Err(e) => log::warn!("Failed to store profile fact '{fact}': {e}"),
+            }
+        }
+        learned
+    }
+
+    /// Every stored profile memory
+    async fn stored(&self) -> anyhow::Result<Vec<MemoryNode>> {
+        let coordinator = self.pool.get_coordinator(&self.config.library).await?;
+        let filter = MemoryFilter::new()
+            .with_tags(vec![PROFILE_TAG.to_string()])
+            .with_limit(MAX_PROFILE_FACTS);
+        Ok(coordinator.get_memories(filter).await?)
+    }
+
+    /// Store `fact` and delete the memories holding its previous values
+    async fn replace(&self, fact: &ProfileFact, previous: &[(String, String)]) -> anyhow::Result<()> {
+        let coordinator = self.pool.get_coordinator(&self.config.library).await?;
+        let metadata = MemoryMetadata {
+            context: "profile".to_string(),
+            category: PROFILE_CATEGORY.to_string(),
+            tags: vec![PROFILE_TAG.to_string(), format!("profile.{}", fact.key.as_str())],
+            importance: 0.9,
+            source: Some("chat".to_string()),
+            custom: serde_json::json!({
+                PROFILE_KEY: fact.key.as_str(),
+                PROFILE_VALUE: fact.value,
+            }),
+            ..MemoryMetadata::new()
+        };
+        coordinator
+            .add_memories(vec![NewMemory::new(
+                format!("The user's {fact}"),
+                MemoryTypeEnum::Semantic,
+                Some(metadata),
+            )])
+            .await?;
+        for (memory_id, _) in previous {
+            coordinator.delete_memory(memory_id).await?;
+        }
+        Ok(())
+    }
+}
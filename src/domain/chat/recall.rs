@@ -0,0 +1,194 @@
+//! Read-through recall across shared memory libraries
+//!
+//! An agent writes to its own memory but may also read from shared knowledge
+//! bases. Libraries configured with `.recall_libraries([...])` are searched on
+//! every turn alongside the agent's memory; their hits are merged into one
+//! context block, scored by each library's ranker (similarity × importance
+//! unless one was registered, see [`MemoryRanker`]) × the library's weight.
+//!
+//! ```ignore
+//! let agent = CandleFluentAi::agent_role("support")
+//!     .recall_libraries([("product_docs", 1.0), ("team_notes", 0.5)])
+//!     .into_agent()?;
+//! ```
+//!
+//! [`MemoryRanker`]: crate::memory::core::ops::ranking::MemoryRanker
+
+#[cfg(feature = "memory")]
+use std::sync::Arc;
+
+#[cfg(feature = "memory")]
+use crate::domain::context::builder::{BuiltContext, ContextBuilder};
+#[cfg(feature = "memory")]
+use crate::memory::core::manager::coordinator::MemoryCoordinator;
+#[cfg(feature = "memory")]
+use crate::memory::core::manager::pool::CoordinatorPool;
+#[cfg(feature = "memory")]
+use crate::memory::core::ops::ranking::ScoredMemory;
+
+/// Weight of a library listed without one (same as the agent's own memory)
+pub const DEFAULT_RECALL_WEIGHT: f32 = 1.0;
+
+/// Hits fetched per library on each turn
+pub const RECALL_TOP_K: usize = 10;
+
+/// A shared library consulted on every turn
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallLibrary {
+    /// Library name, as passed to `memorize`/`recall`
+    pub name: String,
+    /// Multiplier applied to the library's hit scores
+    pub weight: f32,
+}
+
+impl RecallLibrary {
+    /// Library with the default weight
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            weight: DEFAULT_RECALL_WEIGHT,
+        }
+    }
+
+    /// Set the score multiplier (negative weights are treated as 0)
+    #[must_use]
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.max(0.0);
+        self
+    }
+}
+
+impl From<&str> for RecallLibrary {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for RecallLibrary {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<(&str, f32)> for RecallLibrary {
+    fn from((name, weight): (&str, f32)) -> Self {
+        Self::new(name).with_weight(weight)
+    }
+}
+
+impl From<(String, f32)> for RecallLibrary {
+    fn from((name, weight): (String, f32)) -> Self {
+        Self::new(name).with_weight(weight)
+    }
+}
+
+/// Shared libraries and the pool used to open them
+#[cfg(feature = "memory")]
+#[derive(Clone)]
+pub struct SharedRecall {
+    pool: Arc<CoordinatorPool>,
+    libraries: Arc<[RecallLibrary]>,
+}
+
+#[cfg(feature = "memory")]
+impl SharedRecall {
+    /// Read `libraries` through `pool`
+    pub fn new(pool: Arc<CoordinatorPool>, libraries: Vec<RecallLibrary>) -> Self {
+        Self {
+            pool,
+            libraries: libraries.into(),
+        }
+    }
+
+    /// Configured libraries
+    pub fn libraries(&self) -> &[RecallLibrary] {
+        &self.libraries
+    }
+
+    /// Search every library concurrently, ranking each library's hits with
+    /// its own ranker
+    ///
+    /// Each library is read through a snapshot taken when its search starts,
+    /// so chunks from a memorize session still in progress are left out.
+    /// Libraries that cannot be opened or searched are skipped with a warning
+    /// so one unavailable knowledge base does not block the turn.
+    pub async fn search(&self, query: &str) -> Vec<(RecallLibrary, Vec<ScoredMemory>)> {
+        let searches = self.libraries.iter().map(|library| async move {
+            let coordinator = match self.pool.get_coordinator(&library.name).await {
+                Ok(coordinator) => coordinator,
+                Err(e) => {
+                    log::warn!("Recall library '{}' unavailable: {e:?}", library.name);
+                    return None;
+                }
+            };
+            let snapshot = coordinator.read_snapshot();
+            match coordinator
+                .search_ranked_at(query, RECALL_TOP_K, None, &snapshot, None)
+                .await
+            {
+                Ok(memories) => Some((library.clone(), memories)),
+                Err(e) => {
+                    log::warn!("Recall from library '{}' failed: {e:?}", library.name);
+                    None
+                }
+            }
+        });
+
+        futures::future::join_all(searches)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(feature = "memory")]
+impl std::fmt::Debug for SharedRecall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedRecall")
+            .field("libraries", &self.libraries)
+            .finish()
+    }
+}
+
+/// Search the agent's memory and any shared libraries, merged into one block
+///
+/// `builder` sets the budget, heading and relevance cutoff. Every source is
+/// read through a snapshot, so writes still in progress stay out of the turn.
+/// Returns the built context and the number of hits found across all sources.
+#[cfg(feature = "memory")]
+pub async fn recall_context(
+    memory: &Arc<MemoryCoordinator>,
+    shared: Option<&SharedRecall>,
+    query: &str,
+    builder: ContextBuilder,
+) -> (BuiltContext, usize) {
+    let own = async {
+        let snapshot = memory.read_snapshot();
+        match memory
+            .search_ranked_at(query, RECALL_TOP_K, None, &snapshot, None)
+            .await
+        {
+            Ok(memories) => memories,
+            Err(e) => {
+                log::warn!("Memory search failed: {e:?}");
+                Vec::new()
+            }
+        }
+    };
+    let shared_search = async {
+        match shared {
+            Some(shared) => shared.search(query).await,
+            None => Vec::new(),
+        }
+    };
+    let (own, libraries) = tokio::join!(own, shared_search);
+
+    let mut hits = own.len();
+    let mut builder = builder.add_ranked_memories(None, &own);
+    for (library, memories) in &libraries {
+        hits += memories.len();
+        builder = builder.add_ranked_memories(Some((&library.name, library.weight)), memories);
+    }
+    (builder.build(), hits)
+}
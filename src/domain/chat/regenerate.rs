@@ -0,0 +1,126 @@
+//! Re-running the last turn with different sampling
+//!
+//! Returning [`CandleChatLoop::Regenerate`](super::CandleChatLoop::Regenerate)
+//! from a chat handler answers the previous user message again without adding
+//! it to history a second time. The new answer replaces the previous assistant
+//! memory of that turn, keeping its `session_id` and `turn_id`.
+//!
+//! Turns are remembered per session (the agent's `session_id` metadata);
+//! chats without a session ID cannot regenerate. At most
+//! [`MAX_REMEMBERED_SESSIONS`] sessions are remembered, and a session idle
+//! for [`LAST_TURN_IDLE`] is forgotten.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use moka::ops::compute::Op;
+use moka::sync::Cache;
+
+/// Temperature added to the previous turn's when none is given
+pub const DEFAULT_TEMPERATURE_STEP: f64 = 0.2;
+
+/// Highest temperature a regenerated turn is sampled at
+pub const MAX_REGENERATE_TEMPERATURE: f64 = 2.0;
+
+/// Memory tag on a regenerated assistant message
+pub const REGENERATED_TAG: &str = "turn.regenerated";
+
+/// Sessions whose last turn is remembered; the least recently used go first
+pub const MAX_REMEMBERED_SESSIONS: u64 = 1024;
+
+/// How long the last turn of an idle session is remembered
+pub const LAST_TURN_IDLE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Sampling changes for a regenerated turn
+///
+/// Unset fields pick a fresh random seed and a temperature
+/// [`DEFAULT_TEMPERATURE_STEP`] above the previous turn's.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RegenerateOptions {
+    /// Sampling seed
+    pub seed: Option<u64>,
+    /// Sampling temperature
+    pub temperature: Option<f64>,
+}
+
+impl RegenerateOptions {
+    /// Fresh seed, slightly higher temperature
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sample with `seed`
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sample at `temperature` instead of raising the previous one
+    #[must_use]
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Temperature for the regenerated turn, given the previous turn's
+    pub fn resolve_temperature(&self, previous: f64) -> f64 {
+        self.temperature
+            .unwrap_or(previous + DEFAULT_TEMPERATURE_STEP)
+            .clamp(0.0, MAX_REGENERATE_TEMPERATURE)
+    }
+
+    /// Seed for the regenerated turn
+    pub fn resolve_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random)
+    }
+}
+
+/// The most recent turn of a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastTurn {
+    /// User message the turn answered
+    pub user_message: String,
+    /// Temperature the answer was sampled at
+    pub temperature: f64,
+    /// Session the turn's memories are stored under
+    pub session_id: String,
+    /// Turn the memories are stored under
+    pub turn_id: String,
+    /// Stored assistant message, once the write has finished
+    pub assistant_memory_id: Option<String>,
+}
+
+static LAST_TURNS: LazyLock<Cache<String, LastTurn>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(MAX_REMEMBERED_SESSIONS)
+        .time_to_idle(LAST_TURN_IDLE)
+        .build()
+});
+
+/// Remember `turn` as the latest of session `key`
+///
+/// Turns without a session (empty `key`) are not remembered.
+pub fn record_last_turn(key: &str, turn: LastTurn) {
+    if !key.is_empty() {
+        LAST_TURNS.insert(key.to_string(), turn);
+    }
+}
+
+/// Latest turn of session `key`
+pub fn last_turn(key: &str) -> Option<LastTurn> {
+    LAST_TURNS.get(key)
+}
+
+/// Attach the stored assistant memory to turn `turn_id`, if still the latest
+pub fn set_assistant_memory(key: &str, turn_id: &str, memory_id: String) {
+    LAST_TURNS
+        .entry(key.to_string())
+        .and_compute_with(|entry| match entry {
+            Some(entry) if entry.value().turn_id == turn_id => Op::Put(LastTurn {
+                assistant_memory_id: Some(memory_id),
+                ..entry.into_value()
+            }),
+            _ => Op::Nop,
+        });
+}
@@ -0,0 +1,554 @@
+//! User-facing query language for chat history search
+//!
+//! ```text
+//! error AND (timeout OR retry) tag:bug after:2024-06-01 user:assistant
+//! ```
+//!
+//! - Bare words are terms; adjacent terms must all match (implicit `AND`)
+//! - `AND`, `OR` and `NOT` (upper case) combine terms; `-term` means `NOT term`
+//! - Parentheses group; `OR` binds looser than `AND`
+//! - `"quoted text"` matches an exact phrase
+//! - Filters apply to the whole query: `tag:` (repeatable, comma-separated),
+//!   `user:`, `session:`, `type:`, `after:`/`before:` (`YYYY-MM-DD`, UTC),
+//!   `sort:`, `limit:` and `offset:`
+//!
+//! [`parse_query`] turns a string into a [`SearchQuery`] whose `expression`
+//! holds the boolean tree. Syntax errors report the column they were found at
+//! and, where possible, what was expected.
+
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use super::types::{DateRange, QueryExpr, QueryOperator, SearchError, SearchQuery, SortOrder};
+
+/// Filter names recognised before a `:`
+pub const FILTER_NAMES: &[&str] = &[
+    "tag", "user", "session", "type", "after", "before", "sort", "limit", "offset",
+];
+
+const ROLES: &[&str] = &["user", "assistant", "system", "tool"];
+
+const SORT_ORDERS: &str = "relevance, newest, oldest, user or user_desc";
+
+/// Query syntax error at a 1-based column
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} (column {column})")]
+pub struct QuerySyntaxError {
+    /// What went wrong and, where possible, what was expected
+    pub message: String,
+    /// Column (in characters) where the problem starts
+    pub column: usize,
+}
+
+impl QuerySyntaxError {
+    fn new(message: impl Into<String>, column: usize) -> Self {
+        Self {
+            message: message.into(),
+            column,
+        }
+    }
+
+    /// The query with a caret under the offending column
+    #[must_use]
+    pub fn pointer(&self, query: &str) -> String {
+        format!("{query}\n{}^", " ".repeat(self.column.saturating_sub(1)))
+    }
+}
+
+impl From<QuerySyntaxError> for SearchError {
+    fn from(error: QuerySyntaxError) -> Self {
+        SearchError::QueryError {
+            reason: error.to_string(),
+        }
+    }
+}
+
+/// Parse a query string into a [`SearchQuery`]
+///
+/// A query with only filters matches every message that passes them.
+///
+/// # Errors
+///
+/// Returns [`QuerySyntaxError`] for empty queries, unbalanced parentheses or
+/// quotes, operators missing an operand, misplaced or malformed filters, and
+/// likely misspelled filter names
+pub fn parse_query(input: &str) -> Result<SearchQuery, QuerySyntaxError> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err(QuerySyntaxError::new(
+            "Empty query; expected a term, \"phrase\" or filter such as tag:bug",
+            1,
+        ));
+    }
+
+    let mut query = SearchQuery::default();
+    let mut filters = FilterState::default();
+    let mut depth = 0usize;
+    let mut terms = Vec::with_capacity(tokens.len());
+    for (token, column) in tokens {
+        match token {
+            Token::Filter { name, value } => {
+                if depth > 0 {
+                    return Err(QuerySyntaxError::new(
+                        format!(
+                            "Filters apply to the whole query; move {name}:{value} outside the parentheses"
+                        ),
+                        column,
+                    ));
+                }
+                filters.apply(&mut query, &name, &value, column)?;
+            }
+            token => {
+                match token {
+                    Token::LParen => depth += 1,
+                    Token::RParen => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                terms.push((token, column));
+            }
+        }
+    }
+    filters.finish(&mut query)?;
+
+    let expression = if terms.is_empty() {
+        QueryExpr::And(Vec::new())
+    } else {
+        let end_column = input.chars().count() + 1;
+        let mut parser = Parser {
+            tokens: terms,
+            pos: 0,
+            end_column,
+        };
+        let expression = parser.parse_or()?;
+        if let Some((_, column)) = parser.tokens.get(parser.pos) {
+            return Err(QuerySyntaxError::new(
+                "Unexpected ')' with no matching '('",
+                *column,
+            ));
+        }
+        expression
+    };
+
+    query.terms = expression.positive_terms();
+    query.operator = match &expression {
+        QueryExpr::Phrase(_) => QueryOperator::Phrase,
+        QueryExpr::Not(_) => QueryOperator::Not,
+        QueryExpr::Or(exprs) if exprs.iter().all(|e| matches!(e, QueryExpr::Term(_))) => {
+            QueryOperator::Or
+        }
+        _ => QueryOperator::And,
+    };
+    query.expression = Some(expression);
+    Ok(query)
+}
+
+impl FromStr for SearchQuery {
+    type Err = QuerySyntaxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_query(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word(String),
+    Phrase(String),
+    Filter { name: String, value: String },
+}
+
+impl Token {
+    fn starts_operand(&self) -> bool {
+        matches!(
+            self,
+            Token::LParen | Token::Not | Token::Word(_) | Token::Phrase(_)
+        )
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, QuerySyntaxError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let column = i + 1;
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push((Token::LParen, column));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((Token::RParen, column));
+                i += 1;
+            }
+            '"' => {
+                let (phrase, next) = read_quoted(&chars, i)?;
+                tokens.push((Token::Phrase(phrase), column));
+                i = next;
+            }
+            '-' if chars.get(i + 1).is_some_and(|c| !c.is_whitespace() && *c != ')') => {
+                tokens.push((Token::Not, column));
+                i += 1;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '(' | ')' | '"') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+
+                // Filter with a quoted value, e.g. tag:"needs review"
+                if let Some(name) = word.strip_suffix(':')
+                    && FILTER_NAMES.contains(&name.to_lowercase().as_str())
+                    && chars.get(i) == Some(&'"')
+                {
+                    let (value, next) = read_quoted(&chars, i)?;
+                    tokens.push((
+                        Token::Filter {
+                            name: name.to_lowercase(),
+                            value,
+                        },
+                        column,
+                    ));
+                    i = next;
+                    continue;
+                }
+
+                tokens.push((classify(word, column)?, column));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Read a `"..."` string starting at the opening quote
+fn read_quoted(chars: &[char], start: usize) -> Result<(String, usize), QuerySyntaxError> {
+    let close = chars[start + 1..]
+        .iter()
+        .position(|c| *c == '"')
+        .map(|offset| start + 1 + offset)
+        .ok_or_else(|| QuerySyntaxError::new("Unclosed quote; add a closing '\"'", start + 1))?;
+
+    let text: String = chars[start + 1..close].iter().collect();
+    if text.trim().is_empty() {
+        return Err(QuerySyntaxError::new("Empty quoted phrase", start + 1));
+    }
+    Ok((text, close + 1))
+}
+
+fn classify(word: String, column: usize) -> Result<Token, QuerySyntaxError> {
+    match word.as_str() {
+        "AND" => return Ok(Token::And),
+        "OR" => return Ok(Token::Or),
+        "NOT" => return Ok(Token::Not),
+        _ => {}
+    }
+
+    if let Some((name, value)) = word.split_once(':') {
+        let name = name.to_lowercase();
+        if FILTER_NAMES.contains(&name.as_str()) {
+            if value.is_empty() {
+                return Err(QuerySyntaxError::new(
+                    format!("{name}: needs a value, e.g. {}", filter_example(&name)),
+                    column,
+                ));
+            }
+            return Ok(Token::Filter {
+                name,
+                value: value.to_string(),
+            });
+        }
+        if name.len() >= 3
+            && name.chars().all(|c| c.is_ascii_alphabetic())
+            && let Some(suggestion) = FILTER_NAMES.iter().find(|f| is_likely_typo(&name, f))
+        {
+            return Err(QuerySyntaxError::new(
+                format!("Unknown filter '{name}:'; did you mean '{suggestion}:'?"),
+                column,
+            ));
+        }
+    }
+
+    Ok(Token::Word(word))
+}
+
+fn filter_example(name: &str) -> &'static str {
+    match name {
+        "tag" => "tag:bug",
+        "user" => "user:assistant",
+        "session" => "session:abc123",
+        "type" => "type:code",
+        "after" => "after:2024-06-01",
+        "before" => "before:2024-07-01",
+        "sort" => "sort:newest",
+        "limit" => "limit:20",
+        _ => "offset:20",
+    }
+}
+
+/// One edit away, or two adjacent letters swapped
+fn is_likely_typo(word: &str, filter: &str) -> bool {
+    if edit_distance(word, filter) <= 1 {
+        return true;
+    }
+    let (a, b): (Vec<char>, Vec<char>) = (word.chars().collect(), filter.chars().collect());
+    a.len() == b.len()
+        && (0..a.len().saturating_sub(1)).any(|i| {
+            a[i] == b[i + 1]
+                && a[i + 1] == b[i]
+                && a[..i] == b[..i]
+                && a[i + 2..] == b[i + 2..]
+        })
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Filters seen so far, to reject repeats and check the date range
+#[derive(Default)]
+struct FilterState {
+    seen: Vec<String>,
+    after: Option<u64>,
+    before: Option<(u64, usize)>,
+}
+
+impl FilterState {
+    fn apply(
+        &mut self,
+        query: &mut SearchQuery,
+        name: &str,
+        value: &str,
+        column: usize,
+    ) -> Result<(), QuerySyntaxError> {
+        if name != "tag" {
+            if self.seen.iter().any(|s| s == name) {
+                return Err(QuerySyntaxError::new(
+                    format!("{name}: is given more than once"),
+                    column,
+                ));
+            }
+            self.seen.push(name.to_string());
+        }
+
+        match name {
+            "tag" => query.tag_filter.get_or_insert_with(Vec::new).extend(
+                value
+                    .split(',')
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string),
+            ),
+            "user" => {
+                let role = value.to_lowercase();
+                if !ROLES.contains(&role.as_str()) {
+                    return Err(QuerySyntaxError::new(
+                        format!("Unknown role '{value}' for user:; expected user, assistant, system or tool"),
+                        column,
+                    ));
+                }
+                query.user_filter = Some(role);
+            }
+            "session" => query.session_filter = Some(value.to_string()),
+            "type" => query.content_type_filter = Some(value.to_string()),
+            "after" => self.after = Some(parse_date(name, value, column)?),
+            "before" => self.before = Some((parse_date(name, value, column)?, column)),
+            "sort" => {
+                query.sort_order = match value.to_lowercase().as_str() {
+                    "relevance" => SortOrder::Relevance,
+                    "newest" | "date" => SortOrder::DateDescending,
+                    "oldest" => SortOrder::DateAscending,
+                    "user" => SortOrder::UserAscending,
+                    "user_desc" => SortOrder::UserDescending,
+                    _ => {
+                        return Err(QuerySyntaxError::new(
+                            format!("Unknown sort order '{value}'; expected {SORT_ORDERS}"),
+                            column,
+                        ));
+                    }
+                }
+            }
+            "limit" => {
+                query.max_results = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| {
+                        QuerySyntaxError::new(
+                            format!("limit: needs a positive number, got '{value}'"),
+                            column,
+                        )
+                    })?;
+            }
+            _ => {
+                query.offset = value.parse::<usize>().map_err(|_| {
+                    QuerySyntaxError::new(
+                        format!("offset: needs a number, got '{value}'"),
+                        column,
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self, query: &mut SearchQuery) -> Result<(), QuerySyntaxError> {
+        let start = self.after.unwrap_or(0);
+        let end = match self.before {
+            Some((before, column)) => {
+                if before <= start {
+                    return Err(QuerySyntaxError::new(
+                        "before: date must be later than the after: date",
+                        column,
+                    ));
+                }
+                before - 1
+            }
+            None => u64::MAX,
+        };
+        if self.after.is_some() || self.before.is_some() {
+            query.date_range = Some(DateRange { start, end });
+        }
+        Ok(())
+    }
+}
+
+/// Midnight UTC at the start of a `YYYY-MM-DD` date, as Unix seconds
+fn parse_date(name: &str, value: &str, column: usize) -> Result<u64, QuerySyntaxError> {
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        QuerySyntaxError::new(
+            format!("Invalid date '{value}' for {name}:; expected YYYY-MM-DD"),
+            column,
+        )
+    })?;
+    let timestamp = date
+        .and_hms_opt(0, 0, 0)
+        .map_or(0, |dt| dt.and_utc().timestamp());
+    Ok(u64::try_from(timestamp).unwrap_or(0))
+}
+
+/// Recursive-descent parser over the non-filter tokens
+///
+/// ```text
+/// or    := and ("OR" and)*
+/// and   := unary (["AND"] unary)*
+/// unary := "NOT" unary | "(" or ")" | word | phrase
+/// ```
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    end_column: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn expect_operand(&self, operator: &str, column: usize) -> Result<(), QuerySyntaxError> {
+        if self.peek().is_some_and(Token::starts_operand) {
+            Ok(())
+        } else {
+            Err(QuerySyntaxError::new(
+                format!("{operator} needs a term after it"),
+                column,
+            ))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<QueryExpr, QuerySyntaxError> {
+        let mut exprs = vec![self.parse_and()?];
+        while let Some((Token::Or, column)) = self.tokens.get(self.pos) {
+            let column = *column;
+            self.pos += 1;
+            self.expect_operand("OR", column)?;
+            exprs.push(self.parse_and()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            QueryExpr::Or(exprs)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<QueryExpr, QuerySyntaxError> {
+        let mut exprs = vec![self.parse_unary()?];
+        loop {
+            match self.tokens.get(self.pos) {
+                Some((Token::And, column)) => {
+                    let column = *column;
+                    self.pos += 1;
+                    self.expect_operand("AND", column)?;
+                    exprs.push(self.parse_unary()?);
+                }
+                Some((token, _)) if token.starts_operand() => exprs.push(self.parse_unary()?),
+                _ => break,
+            }
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            QueryExpr::And(exprs)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryExpr, QuerySyntaxError> {
+        let Some((token, column)) = self.tokens.get(self.pos).cloned() else {
+            return Err(QuerySyntaxError::new(
+                "Query ends early; expected a term",
+                self.end_column,
+            ));
+        };
+        self.pos += 1;
+
+        match token {
+            Token::Not => {
+                self.expect_operand("NOT", column)?;
+                Ok(QueryExpr::Not(Box::new(self.parse_unary()?)))
+            }
+            Token::LParen => {
+                if self.peek() == Some(&Token::RParen) {
+                    return Err(QuerySyntaxError::new("Empty parentheses", column));
+                }
+                let expr = self.parse_or()?;
+                match self.peek() {
+                    Some(Token::RParen) => {
+                        self.pos += 1;
+                        Ok(expr)
+                    }
+                    _ => Err(QuerySyntaxError::new(
+                        "Unclosed '('; add a matching ')'",
+                        column,
+                    )),
+                }
+            }
+            Token::Word(word) => Ok(QueryExpr::Term(word.to_lowercase())),
+            Token::Phrase(phrase) => Ok(QueryExpr::Phrase(phrase.to_lowercase())),
+            Token::RParen => Err(QuerySyntaxError::new(
+                "Unexpected ')' with no matching '('",
+                column,
+            )),
+            Token::And => Err(QuerySyntaxError::new("AND needs a term before it", column)),
+            Token::Or => Err(QuerySyntaxError::new("OR needs a term before it", column)),
+            Token::Filter { .. } => Err(QuerySyntaxError::new("Unexpected filter", column)),
+        }
+    }
+}
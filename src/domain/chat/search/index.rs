@@ -254,6 +254,48 @@ impl ChatSearchIndex {
         }
     }
 
+    /// Remove a message and every posting that points at it
+    ///
+    /// Terms left without postings are dropped from the index. Returns the
+    /// number of postings removed, or `None` if `doc_id` is not indexed.
+    pub fn remove_message(&self, doc_id: &str) -> Option<usize> {
+        let entry = self.document_store.remove(doc_id)?;
+        self.document_count.fetch_sub(1, Ordering::Relaxed);
+
+        let mut removed = 0;
+        for term in self.tokenize_with_simd(&entry.value().message.content) {
+            let Some(postings) = self.inverted_index.get(&term) else {
+                continue;
+            };
+            let remaining: Vec<IndexEntry> = postings
+                .value()
+                .iter()
+                .filter(|posting| posting.doc_id != doc_id)
+                .cloned()
+                .collect();
+            let dropped = postings.value().len() - remaining.len();
+            if dropped == 0 {
+                continue;
+            }
+            removed += dropped;
+
+            if remaining.is_empty() {
+                self.inverted_index.remove(&term);
+                self.term_frequencies.remove(&term);
+            } else {
+                self.inverted_index.insert(term.clone(), remaining);
+                if let Some(tf_entry) = self.term_frequencies.get(&term) {
+                    let mut tf_entry = tf_entry.value().clone();
+                    tf_entry.tf = (tf_entry.tf - 1.0).max(0.0);
+                    self.term_frequencies.insert(term, tf_entry);
+                }
+            }
+        }
+
+        self.index_update_counter.inc();
+        Some(removed)
+    }
+
     /// Tokenize text with SIMD optimization
     pub fn tokenize_with_simd(&self, text: &str) -> Vec<String> {
         text.split_whitespace()
@@ -12,6 +12,7 @@ use tokio_stream::{Stream, StreamExt};
 
 // Submodules
 pub mod algorithms;
+pub mod dsl;
 pub mod export;
 pub mod index;
 pub mod manager;
@@ -21,6 +22,7 @@ pub mod tagger;
 pub mod types;
 
 // Re-export public types
+pub use dsl::{QuerySyntaxError, parse_query};
 pub use export::HistoryExporter as CandleHistoryExporter;
 pub use export::{HistoryExporter, SearchExporter};
 pub use index::ChatSearchIndex;
@@ -73,63 +75,69 @@ impl ChatSearcher {
             // START TIMING
             let start_time = std::time::Instant::now();
             
-            let results = match query_operator {
-                QueryOperator::And => {
-                    let stream = self_clone
-                        .index
-                        .search_and_stream(&query_terms, query_fuzzy_matching);
-                    tokio::pin!(stream);
-                    let mut vec = Vec::new();
-                    while let Some(result) = stream.next().await {
-                        vec.push(result);
+            let results = if let Some(expression) = &query.expression {
+                self_clone
+                    .collect_expression_matches(&query_terms, expression, query_fuzzy_matching)
+                    .await
+            } else {
+                match query_operator {
+                    QueryOperator::And => {
+                        let stream = self_clone
+                            .index
+                            .search_and_stream(&query_terms, query_fuzzy_matching);
+                        tokio::pin!(stream);
+                        let mut vec = Vec::new();
+                        while let Some(result) = stream.next().await {
+                            vec.push(result);
+                        }
+                        vec
                     }
-                    vec
-                }
-                QueryOperator::Or => {
-                    let stream = self_clone
-                        .index
-                        .search_or_stream(&query_terms, query_fuzzy_matching);
-                    tokio::pin!(stream);
-                    let mut vec = Vec::new();
-                    while let Some(result) = stream.next().await {
-                        vec.push(result);
+                    QueryOperator::Or => {
+                        let stream = self_clone
+                            .index
+                            .search_or_stream(&query_terms, query_fuzzy_matching);
+                        tokio::pin!(stream);
+                        let mut vec = Vec::new();
+                        while let Some(result) = stream.next().await {
+                            vec.push(result);
+                        }
+                        vec
                     }
-                    vec
-                }
-                QueryOperator::Not => {
-                    let stream = self_clone
-                        .index
-                        .search_not_stream(&query_terms, query_fuzzy_matching);
-                    tokio::pin!(stream);
-                    let mut vec = Vec::new();
-                    while let Some(result) = stream.next().await {
-                        vec.push(result);
+                    QueryOperator::Not => {
+                        let stream = self_clone
+                            .index
+                            .search_not_stream(&query_terms, query_fuzzy_matching);
+                        tokio::pin!(stream);
+                        let mut vec = Vec::new();
+                        while let Some(result) = stream.next().await {
+                            vec.push(result);
+                        }
+                        vec
                     }
-                    vec
-                }
-                QueryOperator::Phrase => {
-                    let stream = self_clone
-                        .index
-                        .search_phrase_stream(&query_terms, query_fuzzy_matching);
-                    tokio::pin!(stream);
-                    let mut vec = Vec::new();
-                    while let Some(result) = stream.next().await {
-                        vec.push(result);
+                    QueryOperator::Phrase => {
+                        let stream = self_clone
+                            .index
+                            .search_phrase_stream(&query_terms, query_fuzzy_matching);
+                        tokio::pin!(stream);
+                        let mut vec = Vec::new();
+                        while let Some(result) = stream.next().await {
+                            vec.push(result);
+                        }
+                        vec
                     }
-                    vec
-                }
-                QueryOperator::Proximity { distance } => {
-                    let stream = self_clone.index.search_proximity_stream(
-                        &query_terms,
-                        distance,
-                        query_fuzzy_matching,
-                    );
-                    tokio::pin!(stream);
-                    let mut vec = Vec::new();
-                    while let Some(result) = stream.next().await {
-                        vec.push(result);
+                    QueryOperator::Proximity { distance } => {
+                        let stream = self_clone.index.search_proximity_stream(
+                            &query_terms,
+                            distance,
+                            query_fuzzy_matching,
+                        );
+                        tokio::pin!(stream);
+                        let mut vec = Vec::new();
+                        while let Some(result) = stream.next().await {
+                            vec.push(result);
+                        }
+                        vec
                     }
-                    vec
                 }
             };
 
@@ -146,10 +154,37 @@ impl ChatSearcher {
 
             // END TIMING - Update query statistics
             let query_duration_ms = start_time.elapsed().as_secs_f64() * 1000.0;
-            self_clone.update_query_statistics(query_duration_ms);
+            self_clone.update_query_statistics(query_duration_ms).await;
         }))
     }
 
+    /// Messages matching a parsed query expression
+    ///
+    /// Candidates come from an OR search over the expression's positive terms,
+    /// or from every indexed message when the expression has none or negates
+    /// something; the expression then decides which candidates match.
+    async fn collect_expression_matches(
+        &self,
+        positive_terms: &[String],
+        expression: &QueryExpr,
+        fuzzy_matching: bool,
+    ) -> Vec<SearchResult> {
+        let candidates = if positive_terms.is_empty() || expression.has_negation() {
+            self.index.search_not_stream(&[], fuzzy_matching)
+        } else {
+            self.index.search_or_stream(positive_terms, fuzzy_matching)
+        };
+        tokio::pin!(candidates);
+
+        let mut results = Vec::new();
+        while let Some(result) = candidates.next().await {
+            if expression.matches(&result.message.message.content) {
+                results.push(result);
+            }
+        }
+        results
+    }
+
     /// Apply comprehensive filtering system (date, user, session, tag, content)
     fn apply_filters(results: Vec<SearchResult>, query: &SearchQuery) -> Vec<SearchResult> {
         let mut filtered = results;
@@ -278,12 +313,12 @@ impl ChatSearcher {
     }
 
     /// Update query statistics with performance tracking
-    fn update_query_statistics(&self, query_duration_ms: f64) {
+    async fn update_query_statistics(&self, query_duration_ms: f64) {
         // Increment query counter atomically
         self.index.increment_query_counter();
         
         // Update statistics including average query time
-        let mut stats = self.index.statistics.blocking_write();
+        let mut stats = self.index.statistics.write().await;
         
         // Calculate running average: new_avg = old_avg + (new_value - old_avg) / count
         let query_count = self.index.query_counter.get();
@@ -35,6 +35,108 @@ pub struct SearchQuery {
     pub offset: usize,
     /// Sort order
     pub sort_order: SortOrder,
+    /// Boolean expression from the query language; when set it decides which
+    /// messages match, and `terms` only seed candidate retrieval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<QueryExpr>,
+}
+
+/// Results returned when a query does not set a limit
+pub const DEFAULT_MAX_RESULTS: usize = 50;
+
+impl Default for SearchQuery {
+    fn default() -> Self {
+        Self {
+            terms: Vec::new(),
+            operator: QueryOperator::And,
+            date_range: None,
+            user_filter: None,
+            session_filter: None,
+            tag_filter: None,
+            content_type_filter: None,
+            fuzzy_matching: false,
+            max_results: DEFAULT_MAX_RESULTS,
+            offset: 0,
+            sort_order: SortOrder::Relevance,
+            expression: None,
+        }
+    }
+}
+
+/// Boolean search expression, as parsed from the query language
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryExpr {
+    /// Lowercased term
+    Term(String),
+    /// Lowercased exact phrase
+    Phrase(String),
+    /// Every sub-expression matches (an empty list matches every message)
+    And(Vec<QueryExpr>),
+    /// At least one sub-expression matches
+    Or(Vec<QueryExpr>),
+    /// The sub-expression does not match
+    Not(Box<QueryExpr>),
+}
+
+impl QueryExpr {
+    /// Whether `text` satisfies the expression (case-insensitive)
+    #[must_use]
+    pub fn matches(&self, text: &str) -> bool {
+        self.matches_lowercase(&text.to_lowercase())
+    }
+
+    fn matches_lowercase(&self, text: &str) -> bool {
+        match self {
+            Self::Term(term) | Self::Phrase(term) => text.contains(term.as_str()),
+            Self::And(exprs) => exprs.iter().all(|e| e.matches_lowercase(text)),
+            Self::Or(exprs) => exprs.iter().any(|e| e.matches_lowercase(text)),
+            Self::Not(expr) => !expr.matches_lowercase(text),
+        }
+    }
+
+    /// Whether the expression negates anything
+    ///
+    /// Negated expressions can match messages that contain none of the
+    /// [`positive_terms`](Self::positive_terms).
+    #[must_use]
+    pub fn has_negation(&self) -> bool {
+        match self {
+            Self::Term(_) | Self::Phrase(_) => false,
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().any(Self::has_negation),
+            Self::Not(_) => true,
+        }
+    }
+
+    /// Words that appear outside any `Not`, deduplicated, in query order
+    #[must_use]
+    pub fn positive_terms(&self) -> Vec<String> {
+        let mut terms = Vec::new();
+        self.collect_positive_terms(&mut terms);
+        terms
+    }
+
+    fn collect_positive_terms(&self, terms: &mut Vec<String>) {
+        match self {
+            Self::Term(term) => {
+                if !terms.contains(term) {
+                    terms.push(term.clone());
+                }
+            }
+            Self::Phrase(phrase) => {
+                for word in phrase.split_whitespace() {
+                    if !terms.iter().any(|t| t == word) {
+                        terms.push(word.to_string());
+                    }
+                }
+            }
+            Self::And(exprs) | Self::Or(exprs) => {
+                for expr in exprs {
+                    expr.collect_positive_terms(terms);
+                }
+            }
+            Self::Not(_) => {}
+        }
+    }
 }
 
 /// Query operator enumeration
@@ -5,18 +5,43 @@ use std::collections::HashMap;
 use std::fmt::Write;
 use std::pin::Pin;
 use std::sync::Arc;
+#[cfg(feature = "memory")]
 use surrealdb_types::Datetime;
 use tokio_stream::{Stream, StreamExt};
+use tracing::Instrument;
 
 // Context types (use provider:: to get the concrete struct, not the trait)
 use crate::domain::context::provider::{
-    CandleContext, CandleDirectory, CandleFile, CandleFiles, CandleGithub,
+    CandleContext, CandleContextLoadReport, CandleDirectory, CandleFile, CandleFiles,
+    CandleGithub, CandleSql,
 };
+#[cfg(feature = "memory")]
+use crate::domain::context::provider::CandleContextStream;
+use crate::domain::context::builder::BuiltContext;
+#[cfg(feature = "memory")]
+use crate::domain::context::builder::DEFAULT_CONTEXT_TOKENS;
+#[cfg(feature = "memory")]
+use crate::domain::chat::citations::{cite_memories, recall_citations};
+use crate::domain::chat::confidence::{ConfidenceEstimator, LOW_CONFIDENCE_NOTE, LowConfidenceAction};
+use crate::domain::chat::injection::MemoryInjection;
+use crate::domain::chat::interrupt::{INTERRUPTED_FINISH_REASON, INTERRUPTED_TAG, TurnInterrupt};
+#[cfg(feature = "memory")]
+use crate::domain::chat::profile::ProfileStore;
+#[cfg(feature = "memory")]
+use crate::domain::chat::recall::{SharedRecall, recall_context};
+use crate::domain::chat::regenerate::{self, LastTurn, RegenerateOptions};
+#[cfg(feature = "memory")]
+use crate::domain::chat::regenerate::REGENERATED_TAG;
+use crate::domain::chat::trace::{TurnTrace, Verbosity};
 
 // Memory helper functions (copied from builders since they're not publicly exported)
 
 // Import domain types
 use crate::builders::agent_role::CandleAgentRoleAgent;
+use crate::domain::agent::prompt_guard::PromptGuard;
+use crate::domain::agent::untrusted_content::InjectionFinding;
+#[cfg(feature = "mcp")]
+use crate::domain::agent::untrusted_content::sanitize_untrusted;
 use crate::domain::agent::core::AGENT_STATS;
 use crate::domain::agent::role::CandleAgentConversation;
 use crate::domain::chat::{
@@ -25,25 +50,37 @@ use crate::domain::chat::{
     message::{CandleMessageChunk, CandleMessageRole},
 };
 use crate::domain::completion::CandleCompletionChunk;
-use crate::domain::completion::CandleCompletionParams;
+use crate::domain::completion::{CandleCompletionParams, ResponseFormat};
 use crate::domain::prompt::CandlePrompt;
 
 
 use crate::builders::agent_role::AgentBuilderState;
 use crate::capability::registry::TextToTextModel;
 use crate::capability::traits::TextToTextCapable;
-use crate::domain::memory::primitives::node::MemoryNode as DomainMemoryNode;
+#[cfg(feature = "memory")]
 use crate::domain::memory::primitives::types::MemoryTypeEnum as DomainMemoryTypeEnum;
+#[cfg(feature = "memory")]
 use crate::memory::MemoryMetadata;
-use crate::memory::core::manager::coordinator::MemoryCoordinator;
+#[cfg(feature = "memory")]
+use crate::memory::core::manager::coordinator::{MemoryCoordinator, NewMemory, ToolCallRecord};
+#[cfg(feature = "memory")]
 use crate::memory::core::manager::surreal::MemoryManager; // Trait must be in scope
+#[cfg(feature = "memory")]
 use crate::memory::primitives::node::MemoryNode as CoreMemoryNode;
+#[cfg(feature = "memory")]
 use crate::memory::primitives::types::{MemoryContent, MemoryTypeEnum as CoreMemoryTypeEnum};
+#[cfg(feature = "mcp")]
 use kodegen_mcp_client::create_stdio_client;
 
 use crate::domain::completion::types::ToolInfo;
 use cyrup_sugars::collections::ZeroOneOrMany;
 
+/// Metadata key grouping stored conversation turns into one session
+pub const SESSION_ID_KEY: &str = "session_id";
+
+/// Custom metadata key identifying the turn a stored message belongs to
+pub const TURN_ID_KEY: &str = "turn_id";
+
 // Type aliases for complex callback types
 type OnChunkHandler =
     Arc<dyn Fn(CandleMessageChunk) -> BoxFuture<'static, CandleMessageChunk> + Send + Sync>;
@@ -57,14 +94,60 @@ type OnConversationTurnHandler = Arc<
         + Sync,
 >;
 
+// Without the `memory` feature a session has no long-term memory: nothing is
+// recalled, cited, learned or stored
+#[cfg(not(feature = "memory"))]
+type MemoryCoordinator = std::convert::Infallible;
+#[cfg(not(feature = "memory"))]
+type SharedRecall = std::convert::Infallible;
+#[cfg(not(feature = "memory"))]
+type ProfileStore = std::convert::Infallible;
+
+/// Long-term memory of a session
+#[cfg(feature = "memory")]
+pub type SessionMemory = Arc<MemoryCoordinator>;
+/// Long-term memory of a session; none without the `memory` feature
+#[cfg(not(feature = "memory"))]
+pub type SessionMemory = ();
+
+// Without the `mcp` feature no kodegen client is spawned and tool calls fail
+#[cfg(feature = "mcp")]
+type McpClient = kodegen_mcp_client::KodegenClient;
+#[cfg(not(feature = "mcp"))]
+type McpClient = std::convert::Infallible;
+
 /// Configuration bundle for chat session execution
 pub struct ChatSessionConfig<S> {
     pub model_config: CandleModelConfig,
     pub chat_config: CandleChatConfig,
     pub provider: TextToTextModel,
-    pub memory: Arc<MemoryCoordinator>,
+    pub memory: SessionMemory,
     pub tools: Arc<[ToolInfo]>,
     pub metadata: HashMap<String, String, S>,
+    /// Consult long-term memory when building prompts
+    pub memory_read: bool,
+    /// Shared libraries read alongside `memory` when `memory_read` is set
+    pub shared_recall: Option<SharedRecall>,
+    /// Where recalled context goes in the prompt, and its relevance cutoff
+    pub memory_injection: MemoryInjection,
+    /// Attach the recalled memories that informed each answer to its `Complete` chunk
+    pub cite_memories: bool,
+    /// Store conversation turns and context documents in long-term memory
+    pub memory_write: bool,
+    /// System prompt protection and injection screening
+    pub prompt_guard: PromptGuard,
+    /// Stops the turn mid-stream when triggered
+    pub interrupt: Option<TurnInterrupt>,
+    /// Per-turn trace chunks sent before the reply
+    pub trace_verbosity: Verbosity,
+    /// User profile applied to the system prompt and learned from each message
+    pub profile: Option<ProfileStore>,
+    /// Confidence scoring of each answer
+    pub confidence: Option<ConfidenceEstimator>,
+    /// Text every reply starts with
+    pub assistant_prefix: Option<String>,
+    /// JSON schema every reply is constrained to
+    pub response_format: Option<ResponseFormat>,
 }
 
 /// Context sources bundle for chat session
@@ -73,6 +156,7 @@ pub struct ChatSessionContexts {
     pub context_files: Option<CandleContext<CandleFiles>>,
     pub context_directory: Option<CandleContext<CandleDirectory>>,
     pub context_github: Option<CandleContext<CandleGithub>>,
+    pub context_sql: Option<CandleContext<CandleSql>>,
 }
 
 /// Callback handlers bundle for chat session
@@ -84,40 +168,22 @@ pub struct ChatSessionHandlers {
 
 // Helper functions for memory operations
 
-fn format_memory_context(memories: &[DomainMemoryNode], max_chars: usize) -> String {
-    let mut result = String::from("## Relevant Context\n\n");
-    let mut current_len = result.len();
-
-    for memory in memories {
-        let content = memory.content().to_string();
-        let source = memory
-            .metadata
-            .custom
-            .get("source")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        let entry = format!("- [{source}]: {content}\n");
-
-        if current_len + entry.len() > max_chars {
-            break;
-        }
-
-        result.push_str(&entry);
-        current_len += entry.len();
-    }
-
-    result
-}
-
 /// Load documents from a context stream into memory using `MemoryManager` API
+///
+/// Documents that failed to load are counted in the returned report.
+#[cfg(feature = "memory")]
 async fn load_context_stream(
-    stream: Pin<Box<dyn Stream<Item = crate::domain::context::CandleDocument> + Send>>,
+    stream: CandleContextStream,
     memory: Arc<MemoryCoordinator>,
     metadata: HashMap<String, String>,
     context_tag: &str,
-) {
+) -> CandleContextLoadReport {
+    let mut report = CandleContextLoadReport::new(context_tag);
     tokio::pin!(stream);
-    while let Some(doc) = stream.next().await {
+    while let Some(item) = stream.next().await {
+        let Some(doc) = report.record(item) else {
+            continue;
+        };
         // Create CoreMemoryNode following MemoryManager pattern
         let content = MemoryContent::new(&doc.data);
         let mut node = CoreMemoryNode::new(CoreMemoryTypeEnum::Semantic, content);
@@ -141,6 +207,10 @@ async fn load_context_stream(
             log::warn!("Failed to load context document from {context_tag}: {e:?}");
         }
     }
+    if report.has_failures() {
+        log::warn!("Context partially loaded: {report}");
+    }
+    report
 }
 
 /// Handle Break loop case
@@ -152,6 +222,38 @@ fn process_break_loop() -> CandleMessageChunk {
         token_count: None,
         elapsed_secs: None,
         tokens_per_sec: None,
+        citations: Vec::new(),
+        confidence: None,
+    }
+}
+
+/// Earlier messages of the conversation as labelled prompt lines
+fn render_history(history: &[(CandleMessageRole, String)]) -> String {
+    let mut transcript = String::new();
+    for (role, content) in history {
+        let speaker = match role {
+            CandleMessageRole::User => "User",
+            CandleMessageRole::Assistant => "Assistant",
+            CandleMessageRole::System => "System",
+            CandleMessageRole::Tool => "Tool",
+        };
+        transcript.push_str(&format!("{speaker}: {content}\n\n"));
+    }
+    transcript.truncate(transcript.trim_end().len());
+    transcript
+}
+
+/// `Complete` chunk ending an interrupted turn
+fn interrupted_chunk() -> CandleMessageChunk {
+    CandleMessageChunk::Complete {
+        text: String::new(),
+        finish_reason: Some(INTERRUPTED_FINISH_REASON.to_string()),
+        usage: None,
+        token_count: None,
+        elapsed_secs: None,
+        tokens_per_sec: None,
+        citations: Vec::new(),
+        confidence: None,
     }
 }
 
@@ -159,10 +261,11 @@ fn process_break_loop() -> CandleMessageChunk {
 /// 
 /// Only spawns kodegen if tools are configured. Pure inference use cases
 /// (like browser-agent) skip the spawn to avoid slow connection timeouts.
+#[cfg(feature = "mcp")]
 async fn initialize_mcp_client(
     tools: &Arc<[ToolInfo]>,
     on_tool_result_handler: Option<&OnToolResultHandler>,
-) -> Option<kodegen_mcp_client::KodegenClient> {
+) -> Option<McpClient> {
     // Only spawn kodegen for tool execution if tools are expected to be used
     // Skip for pure inference use cases to avoid slow connection timeouts
     let needs_tools = !tools.is_empty() || on_tool_result_handler.is_some();
@@ -185,21 +288,48 @@ async fn initialize_mcp_client(
     }
 }
 
-/// Search memory and format context
-async fn search_and_format_memory(memory: &Arc<MemoryCoordinator>, user_message: &str) -> String {
-    match memory.search_memories(user_message, 10, None).await {
-        Ok(memories) => {
-            if memories.is_empty() {
-                String::new()
-            } else {
-                format_memory_context(&memories, 2000)
-            }
-        }
-        Err(e) => {
-            log::warn!("Memory search failed: {e:?}");
-            String::new()
-        }
+/// Without the `mcp` feature there is no kodegen to spawn
+#[cfg(not(feature = "mcp"))]
+async fn initialize_mcp_client(
+    tools: &Arc<[ToolInfo]>,
+    on_tool_result_handler: Option<&OnToolResultHandler>,
+) -> Option<McpClient> {
+    if !tools.is_empty() || on_tool_result_handler.is_some() {
+        log::warn!("Built without the `mcp` feature - Tools will not be available");
+    }
+    None
+}
+
+/// Tools offered by the kodegen process
+#[cfg(feature = "mcp")]
+async fn list_mcp_tools(client: &McpClient) -> Vec<ToolInfo> {
+    client.list_tools().await.unwrap_or_default()
+}
+
+#[cfg(not(feature = "mcp"))]
+async fn list_mcp_tools(client: &McpClient) -> Vec<ToolInfo> {
+    match *client {}
+}
+
+/// Search memory (and any shared recall libraries) and build the context block
+#[cfg(feature = "memory")]
+async fn search_and_format_memory(
+    memory: &Arc<MemoryCoordinator>,
+    shared_recall: Option<&SharedRecall>,
+    injection: &MemoryInjection,
+    user_message: &str,
+) -> BuiltContext {
+    let start = std::time::Instant::now();
+    let builder = injection.context_builder(DEFAULT_CONTEXT_TOKENS);
+    let (context, hits) = recall_context(memory, shared_recall, user_message, builder).await;
+    if context.below_cutoff > 0 {
+        log::debug!("Dropped {} recalled memories below the relevance cutoff", context.below_cutoff);
+    }
+    for finding in &context.findings {
+        log::warn!("Prompt injection in recalled context: {finding}");
     }
+    crate::memory::monitoring::record_recall("chat", user_message, hits, start.elapsed());
+    context
 }
 
 /// Build system prompt with personality traits and custom instructions
@@ -223,23 +353,20 @@ fn build_system_prompt(model_config: &CandleModelConfig, chat_config: &CandleCha
     system_prompt
 }
 
-/// Build prompt with personality and memory context
-fn build_prompt_with_context(
-    model_config: &CandleModelConfig,
-    chat_config: &CandleChatConfig,
-    memory_context: &str,
-    user_message: &str,
-) -> String {
-    let system_prompt = build_system_prompt(model_config, chat_config);
-
-    if memory_context.is_empty() {
-        format!("{system_prompt}\n\nUser: {user_message}")
-    } else {
-        format!("{system_prompt}\n\n{memory_context}\n\nUser: {user_message}")
+/// Resolved top-k/top-p as provider `additional_params`, if any are set
+fn sampling_params(model_config: &CandleModelConfig) -> Option<serde_json::Value> {
+    let mut params = serde_json::Map::new();
+    if let Some(top_k) = model_config.top_k {
+        params.insert("top_k".to_string(), top_k.into());
+    }
+    if let Some(top_p) = model_config.top_p {
+        params.insert("top_p".to_string(), f64::from(top_p).into());
     }
+    (!params.is_empty()).then_some(serde_json::Value::Object(params))
 }
 
 /// Load all context sources in parallel
+#[cfg(feature = "memory")]
 fn load_all_contexts<S>(
     memory: &Arc<MemoryCoordinator>,
     metadata: &HashMap<String, String, S>,
@@ -247,7 +374,8 @@ fn load_all_contexts<S>(
     context_files: Option<CandleContext<CandleFiles>>,
     context_directory: Option<CandleContext<CandleDirectory>>,
     context_github: Option<CandleContext<CandleGithub>>,
-) -> Vec<tokio::task::JoinHandle<()>>
+    context_sql: Option<CandleContext<CandleSql>>,
+) -> Vec<tokio::task::JoinHandle<CandleContextLoadReport>>
 where
     S: std::hash::BuildHasher,
 {
@@ -261,7 +389,7 @@ where
         let mem = memory.clone();
         let user_id = user_id.clone();
         let agent_id = agent_id.clone();
-        load_tasks.push(tokio::spawn(async move {
+        load_tasks.push(crate::runtime::spawn_with_request_id(async move {
             let mut meta = HashMap::new();
             if let Some(uid) = user_id {
                 meta.insert("user_id".to_string(), uid);
@@ -269,7 +397,7 @@ where
             if let Some(aid) = agent_id {
                 meta.insert("agent_id".to_string(), aid);
             }
-            load_context_stream(ctx.load(), mem, meta, "context_file").await;
+            load_context_stream(ctx.load(), mem, meta, "context_file").await
         }));
     }
 
@@ -277,7 +405,7 @@ where
         let mem = memory.clone();
         let user_id = user_id.clone();
         let agent_id = agent_id.clone();
-        load_tasks.push(tokio::spawn(async move {
+        load_tasks.push(crate::runtime::spawn_with_request_id(async move {
             let mut meta = HashMap::new();
             if let Some(uid) = user_id {
                 meta.insert("user_id".to_string(), uid);
@@ -285,7 +413,7 @@ where
             if let Some(aid) = agent_id {
                 meta.insert("agent_id".to_string(), aid);
             }
-            load_context_stream(ctx.load(), mem, meta, "context_files").await;
+            load_context_stream(ctx.load(), mem, meta, "context_files").await
         }));
     }
 
@@ -293,7 +421,7 @@ where
         let mem = memory.clone();
         let user_id = user_id.clone();
         let agent_id = agent_id.clone();
-        load_tasks.push(tokio::spawn(async move {
+        load_tasks.push(crate::runtime::spawn_with_request_id(async move {
             let mut meta = HashMap::new();
             if let Some(uid) = user_id {
                 meta.insert("user_id".to_string(), uid);
@@ -301,7 +429,7 @@ where
             if let Some(aid) = agent_id {
                 meta.insert("agent_id".to_string(), aid);
             }
-            load_context_stream(ctx.load(), mem, meta, "context_directory").await;
+            load_context_stream(ctx.load(), mem, meta, "context_directory").await
         }));
     }
 
@@ -309,7 +437,23 @@ where
         let mem = memory.clone();
         let user_id = user_id.clone();
         let agent_id = agent_id.clone();
-        load_tasks.push(tokio::spawn(async move {
+        load_tasks.push(crate::runtime::spawn_with_request_id(async move {
+            let mut meta = HashMap::new();
+            if let Some(uid) = user_id {
+                meta.insert("user_id".to_string(), uid);
+            }
+            if let Some(aid) = agent_id {
+                meta.insert("agent_id".to_string(), aid);
+            }
+            load_context_stream(ctx.load(), mem, meta, "context_github").await
+        }));
+    }
+
+    if let Some(ctx) = context_sql {
+        let mem = memory.clone();
+        let user_id = user_id.clone();
+        let agent_id = agent_id.clone();
+        load_tasks.push(crate::runtime::spawn_with_request_id(async move {
             let mut meta = HashMap::new();
             if let Some(uid) = user_id {
                 meta.insert("user_id".to_string(), uid);
@@ -317,27 +461,110 @@ where
             if let Some(aid) = agent_id {
                 meta.insert("agent_id".to_string(), aid);
             }
-            load_context_stream(ctx.load(), mem, meta, "context_github").await;
+            load_context_stream(ctx.load(), mem, meta, "context_sql").await
         }));
     }
 
     load_tasks
 }
 
+/// What an answer's confidence is estimated against
+struct ConfidenceCheck<'a> {
+    estimator: &'a ConfidenceEstimator,
+    provider: &'a TextToTextModel,
+    question: &'a str,
+    #[cfg(feature = "memory")]
+    memory: &'a MemoryCoordinator,
+}
+
+/// Where a turn's tool calls are audited
+#[cfg_attr(not(all(feature = "memory", feature = "mcp")), allow(dead_code))]
+struct ToolAuditTarget<'a> {
+    memory: &'a SessionMemory,
+    session_id: &'a str,
+    turn_id: &'a str,
+}
+
+#[cfg(feature = "mcp")]
+impl ToolAuditTarget<'_> {
+    /// Write the call of `tool` to the audit log in the background
+    #[cfg(feature = "memory")]
+    fn record(
+        &self,
+        tool: &str,
+        args: &str,
+        elapsed: std::time::Duration,
+        result: Result<&str, &str>,
+    ) {
+        let record =
+            ToolCallRecord::new(tool, args, elapsed, result).in_turn(self.session_id, self.turn_id);
+        let memory = self.memory.clone();
+        crate::runtime::supervisor().spawn("tool call audit", async move {
+            if let Err(e) = memory.record_tool_call(&record).await {
+                log::warn!("Failed to audit call to tool '{}': {e:?}", record.tool);
+            }
+        });
+    }
+
+    /// The audit log is kept in memory; without it calls go unrecorded
+    #[cfg(not(feature = "memory"))]
+    fn record(
+        &self,
+        _tool: &str,
+        _args: &str,
+        _elapsed: std::time::Duration,
+        _result: Result<&str, &str>,
+    ) {
+    }
+}
+
+/// Resolves once `interrupt` fires; never without one
+async fn wait_for_interrupt(interrupt: Option<&TurnInterrupt>) {
+    match interrupt {
+        Some(interrupt) => interrupt.interrupted().await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Stream completion chunks and process them with handlers
+///
+/// Returns the assistant response and whether it was stopped early, by
+/// `interrupt` or by the caller dropping the chat stream; a stopped response
+/// holds the text streamed so far. Injection findings in tool results are
+/// added to `trace`, which is sent again.
 #[allow(clippy::too_many_arguments)]
 async fn stream_and_process_chunks(
     completion_stream: Pin<Box<dyn Stream<Item = CandleCompletionChunk> + Send>>,
     sender: &tokio::sync::mpsc::UnboundedSender<CandleMessageChunk>,
     chat_config: &CandleChatConfig,
-    mcp_client: Option<&kodegen_mcp_client::KodegenClient>,
+    mcp_client: Option<&McpClient>,
+    audit: ToolAuditTarget<'_>,
+    citation_context: Option<(&MemoryCoordinator, &BuiltContext)>,
+    confidence_check: Option<ConfidenceCheck<'_>>,
+    mut trace: Option<&mut TurnTrace>,
+    interrupt: Option<&TurnInterrupt>,
     on_chunk_handler: Option<&OnChunkHandler>,
     on_tool_result_handler: Option<&OnToolResultHandler>,
-) -> String {
+) -> (String, bool) {
     tokio::pin!(completion_stream);
     let mut assistant_response = String::new();
 
-    while let Some(completion_chunk) = completion_stream.next().await {
+    loop {
+        // Dropping the completion stream on return stops the provider
+        let next = tokio::select! {
+            biased;
+            () = wait_for_interrupt(interrupt) => {
+                let _ = sender.send(interrupted_chunk());
+                return (assistant_response, true);
+            }
+            () = sender.closed() => {
+                log::info!("Chat stream dropped, stopping generation");
+                return (assistant_response, true);
+            }
+            chunk = completion_stream.next() => chunk,
+        };
+        let Some(completion_chunk) = next else { break };
+
         let message_chunk = match completion_chunk {
             CandleCompletionChunk::Text(ref text) => {
                 assistant_response.push_str(text);
@@ -350,6 +577,8 @@ async fn stream_and_process_chunks(
                 token_count,
                 elapsed_secs,
                 tokens_per_sec,
+                mean_logprob,
+                flash_attention: _,
             } => {
                 assistant_response.push_str(text);
 
@@ -364,13 +593,48 @@ async fn stream_and_process_chunks(
                     AGENT_STATS.record_completion(u64::from(usage.total_tokens), duration_us);
                 }
 
+                // The answer is final here, so cite the memories it drew on
+                #[cfg_attr(not(feature = "memory"), allow(unused_mut))]
+                let mut citations = match citation_context {
+                    #[cfg(feature = "memory")]
+                    Some((memory, context)) => cite_memories(memory, context, &assistant_response).await,
+                    #[cfg(not(feature = "memory"))]
+                    Some((memory, _)) => match *memory {},
+                    None => Vec::new(),
+                };
+
+                // Score the answer and back up a doubtful one
+                let mut text = text.clone();
+                let mut confidence = None;
+                if let Some(check) = &confidence_check {
+                    let score = check
+                        .estimator
+                        .estimate(check.provider, check.question, &assistant_response, mean_logprob)
+                        .await;
+                    match check.estimator.action_for(score) {
+                        Some(LowConfidenceAction::Qualify) => {
+                            let note = format!("\n\n{LOW_CONFIDENCE_NOTE}");
+                            text.push_str(&note);
+                            assistant_response.push_str(&note);
+                        }
+                        #[cfg(feature = "memory")]
+                        Some(LowConfidenceAction::RecallMemory) if citations.is_empty() => {
+                            citations = recall_citations(check.memory, check.question).await;
+                        }
+                        Some(LowConfidenceAction::RecallMemory) | None => {}
+                    }
+                    confidence = Some(score);
+                }
+
                 CandleMessageChunk::Complete {
-                    text: text.clone(),
+                    text,
                     finish_reason: finish_reason.map(|f| format!("{f:?}")),
                     usage: usage.map(|u| format!("{u:?}")),
                     token_count,
                     elapsed_secs,
                     tokens_per_sec,
+                    citations,
+                    confidence,
                 }
             }
             CandleCompletionChunk::ToolCallStart { id, name } => {
@@ -386,7 +650,14 @@ async fn stream_and_process_chunks(
                 partial_input,
             },
             CandleCompletionChunk::ToolCallComplete { id: _, name, input } => {
-                execute_tool_call(&name, &input, mcp_client, sender, on_tool_result_handler).await
+                let (chunk, findings) =
+                    execute_tool_call(&name, &input, mcp_client, &audit, on_tool_result_handler)
+                        .await;
+                if let Some(trace) = trace.as_deref_mut().filter(|_| !findings.is_empty()) {
+                    trace.injection_findings.extend(findings);
+                    let _ = sender.send(CandleMessageChunk::Trace(trace.clone()));
+                }
+                chunk
             }
             CandleCompletionChunk::Error(error) => CandleMessageChunk::Error(error),
         };
@@ -403,19 +674,17 @@ async fn stream_and_process_chunks(
         let _ = sender.send(final_chunk);
     }
 
-    assistant_response
+    (assistant_response, false)
 }
 
-/// Store conversation turn in memory
-fn store_conversation_in_memory<S: std::hash::BuildHasher>(
-    system_prompt: &str,
-    user_message: &str,
-    assistant_response: &str,
-    memory: &Arc<MemoryCoordinator>,
+/// Metadata shared by every message of one stored turn
+#[cfg(feature = "memory")]
+fn turn_metadata<S: std::hash::BuildHasher>(
     metadata: &HashMap<String, String, S>,
-) {
-    // Base metadata template
-    let base_meta = MemoryMetadata {
+    session_id: &str,
+    turn_id: &str,
+) -> MemoryMetadata {
+    MemoryMetadata {
         user_id: metadata.get("user_id").cloned(),
         agent_id: metadata.get("agent_id").cloned(),
         context: "chat".to_string(),
@@ -426,68 +695,141 @@ fn store_conversation_in_memory<S: std::hash::BuildHasher>(
         created_at: Datetime::now(),
         last_accessed_at: None,
         embedding: None,
-        custom: serde_json::Value::Object(serde_json::Map::new()),
-        tags: vec![], // Set per message type below
-    };
-
-    // Store SYSTEM message
-    if !system_prompt.is_empty() {
-        let system_meta = MemoryMetadata {
-            tags: vec!["message_type.system".to_string()],
-            ..base_meta.clone()
-        };
+        custom: serde_json::json!({
+            SESSION_ID_KEY: session_id,
+            TURN_ID_KEY: turn_id,
+        }),
+        tags: vec![], // Set per message type by the caller
+    }
+}
 
-        let memory_clone = memory.clone();
-        let system_msg = system_prompt.to_string();
-        tokio::spawn(async move {
-            if let Err(e) = memory_clone
-                .add_memory(
-                    system_msg,
-                    DomainMemoryTypeEnum::Semantic,
-                    Some(system_meta),
-                )
-                .await
-            {
-                log::error!("Failed to store system memory: {e:?}");
-            }
-        });
+/// Tags of a stored assistant message
+#[cfg(feature = "memory")]
+fn assistant_tags(interrupted: bool, regenerated: bool) -> Vec<String> {
+    let mut tags = vec!["message_type.assistant".to_string()];
+    if interrupted {
+        tags.push(INTERRUPTED_TAG.to_string());
+    }
+    if regenerated {
+        tags.push(REGENERATED_TAG.to_string());
     }
+    tags
+}
 
-    // Store USER message
-    let user_meta = MemoryMetadata {
-        tags: vec!["message_type.user".to_string()],
-        ..base_meta.clone()
-    };
+/// Store conversation turn in memory
+///
+/// The turn is written by one supervised background task (drained on
+/// shutdown) that embeds all messages in a single batch and stores them
+/// atomically. Every message carries the turn's `turn_id` and `session_id` in
+/// its custom metadata so history search can point back to the conversation.
+/// The assistant message of an interrupted turn is also tagged
+/// [`INTERRUPTED_TAG`]. Once stored, the assistant memory is attached to the
+/// session's last turn so a regenerated answer can replace it.
+#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "memory")]
+fn store_conversation_in_memory<S: std::hash::BuildHasher>(
+    system_prompt: &str,
+    user_message: &str,
+    assistant_response: &str,
+    interrupted: bool,
+    turn: &LastTurn,
+    session_key: &str,
+    memory: &Arc<MemoryCoordinator>,
+    metadata: &HashMap<String, String, S>,
+) {
+    let base_meta = turn_metadata(metadata, &turn.session_id, &turn.turn_id);
+
+    // Queue SYSTEM, USER and ASSISTANT messages as one batch
+    let mut messages = Vec::with_capacity(3);
 
+    if !system_prompt.is_empty() {
+        messages.push(NewMemory::new(
+            system_prompt,
+            DomainMemoryTypeEnum::Semantic,
+            Some(MemoryMetadata {
+                tags: vec!["message_type.system".to_string()],
+                ..base_meta.clone()
+            }),
+        ));
+    }
+
+    messages.push(NewMemory::new(
+        user_message,
+        DomainMemoryTypeEnum::Episodic,
+        Some(MemoryMetadata {
+            tags: vec!["message_type.user".to_string()],
+            ..base_meta.clone()
+        }),
+    ));
+
+    messages.push(NewMemory::new(
+        assistant_response,
+        DomainMemoryTypeEnum::Episodic,
+        Some(MemoryMetadata {
+            tags: assistant_tags(interrupted, false),
+            ..base_meta
+        }),
+    ));
+
+    // One embedding batch and one transaction for the whole turn
     let memory_clone = memory.clone();
-    let user_msg = user_message.to_string();
-    tokio::spawn(async move {
-        if let Err(e) = memory_clone
-            .add_memory(user_msg, DomainMemoryTypeEnum::Episodic, Some(user_meta))
-            .await
-        {
-            log::error!("Failed to store user memory: {e:?}");
+    let session_key = session_key.to_string();
+    let turn_id = turn.turn_id.clone();
+    crate::runtime::supervisor().spawn("store conversation memory", async move {
+        match memory_clone.add_memories(messages).await {
+            Ok(stored) => {
+                if let Some(assistant) = stored.last() {
+                    regenerate::set_assistant_memory(&session_key, &turn_id, assistant.id().to_string());
+                }
+            }
+            Err(e) => log::error!("Failed to store conversation memory: {e:?}"),
         }
     });
+}
 
-    // Store ASSISTANT message
-    let assistant_meta = MemoryMetadata {
-        tags: vec!["message_type.assistant".to_string()],
-        ..base_meta.clone()
-    };
+/// Replace the assistant message of a regenerated turn
+///
+/// The user message stays as stored; the previous answer is deleted and the
+/// new one stored under the same `session_id` and `turn_id`, tagged
+/// [`REGENERATED_TAG`].
+#[cfg(feature = "memory")]
+fn replace_assistant_memory<S: std::hash::BuildHasher>(
+    previous: &LastTurn,
+    assistant_response: &str,
+    interrupted: bool,
+    session_key: &str,
+    memory: &Arc<MemoryCoordinator>,
+    metadata: &HashMap<String, String, S>,
+) {
+    let replacement = NewMemory::new(
+        assistant_response,
+        DomainMemoryTypeEnum::Episodic,
+        Some(MemoryMetadata {
+            tags: assistant_tags(interrupted, true),
+            ..turn_metadata(metadata, &previous.session_id, &previous.turn_id)
+        }),
+    );
 
     let memory_clone = memory.clone();
-    let assistant_msg = assistant_response.to_string();
-    tokio::spawn(async move {
-        if let Err(e) = memory_clone
-            .add_memory(
-                assistant_msg,
-                DomainMemoryTypeEnum::Episodic,
-                Some(assistant_meta),
-            )
-            .await
-        {
-            log::error!("Failed to store assistant memory: {e:?}");
+    let session_key = session_key.to_string();
+    let turn_id = previous.turn_id.clone();
+    let old_id = previous.assistant_memory_id.clone();
+    crate::runtime::supervisor().spawn("replace regenerated memory", async move {
+        match &old_id {
+            Some(old_id) => {
+                if let Err(e) = memory_clone.delete_memory(old_id).await {
+                    log::warn!("Failed to delete replaced answer {old_id}: {e:?}");
+                }
+            }
+            None => log::debug!("Previous answer of turn {turn_id} not stored yet; keeping it"),
+        }
+        match memory_clone.add_memories(vec![replacement]).await {
+            Ok(stored) => {
+                if let Some(assistant) = stored.last() {
+                    regenerate::set_assistant_memory(&session_key, &turn_id, assistant.id().to_string());
+                }
+            }
+            Err(e) => log::error!("Failed to store regenerated answer: {e:?}"),
         }
     });
 }
@@ -512,9 +854,12 @@ async fn invoke_turn_handler_if_configured(
             name: String::from("agent"),
             text_to_text_model: provider.clone(),
             text_embedding_model: None,
+            loaded_model: crate::capability::registry::loaded_model_handle(provider),
             temperature: f64::from(model_config.temperature),
             max_tokens: u64::from(model_config.max_tokens.unwrap_or(4096)),
             memory_read_timeout: model_config.timeout_ms,
+            memory_read: true,
+            memory_write: true,
             system_prompt: model_config.system_prompt.clone().unwrap_or_default(),
             tools: tools.to_vec().into(),
             context_file: None,
@@ -538,20 +883,31 @@ async fn invoke_turn_handler_if_configured(
     }
 }
 
-/// Execute a tool call and return the result as a message chunk
+/// Execute a tool call and return the result as a message chunk, with the
+/// injection findings in its output
 ///
-/// Executes tool calls via MCP client.
+/// Executes tool calls via MCP client; each executed call is audited.
+#[cfg(feature = "mcp")]
 async fn execute_tool_call(
     name: &str,
     input: &str,
-    mcp_client: Option<&kodegen_mcp_client::KodegenClient>,
-    _sender: &tokio::sync::mpsc::UnboundedSender<CandleMessageChunk>,
+    mcp_client: Option<&McpClient>,
+    audit: &ToolAuditTarget<'_>,
     on_tool_result_handler: Option<&OnToolResultHandler>,
-) -> CandleMessageChunk {
+) -> (CandleMessageChunk, Vec<InjectionFinding>) {
     if let Some(client) = mcp_client {
         match serde_json::from_str::<serde_json::Value>(input) {
             Ok(args_json) => {
-                match client.call_tool(name, args_json).await {
+                let start = std::time::Instant::now();
+                let result = client.call_tool(name, args_json).await;
+                let elapsed = start.elapsed();
+                crate::domain::tool::tool_analytics().record(
+                    name,
+                    elapsed,
+                    crate::domain::tool::call_succeeded(&result),
+                );
+
+                match result {
                     Ok(response) => {
                         if let Some(handler) = on_tool_result_handler {
                             let results = vec![format!("{response:?}")];
@@ -559,29 +915,79 @@ async fn execute_tool_call(
                         }
                         let result_str = serde_json::to_string_pretty(&response)
                             .unwrap_or_else(|_| format!("{response:?}"));
-                        CandleMessageChunk::Text(format!("\n[Tool: {name}]\n{result_str}\n"))
+                        audit.record(name, input, elapsed, Ok(&result_str));
+                        // Tool output joins the transcript, so it is screened like recalled memory
+                        let sanitized = sanitize_untrusted(&format!("tool:{name}"), &result_str);
+                        for finding in &sanitized.findings {
+                            log::warn!("Prompt injection in tool result: {finding}");
+                        }
+                        let chunk = CandleMessageChunk::Text(format!(
+                            "\n[Tool: {name}]\n{}\n",
+                            sanitized.text
+                        ));
+                        (chunk, sanitized.findings)
+                    }
+                    Err(e) => {
+                        let error = e.to_string();
+                        audit.record(name, input, elapsed, Err(&error));
+                        let chunk =
+                            CandleMessageChunk::Error(format!("Tool '{name}' failed: {error}"));
+                        (chunk, Vec::new())
                     }
-                    Err(e) => CandleMessageChunk::Error(format!("Tool '{name}' failed: {e}")),
                 }
             }
-            Err(e) => CandleMessageChunk::Error(format!("Invalid JSON: {e}")),
+            Err(e) => (CandleMessageChunk::Error(format!("Invalid JSON: {e}")), Vec::new()),
         }
     } else {
-        CandleMessageChunk::Error("MCP client not available".to_string())
+        let chunk = CandleMessageChunk::Error("MCP client not available".to_string());
+        (chunk, Vec::new())
+    }
+}
+
+/// Without the `mcp` feature no tool can be executed
+#[cfg(not(feature = "mcp"))]
+async fn execute_tool_call(
+    name: &str,
+    _input: &str,
+    mcp_client: Option<&McpClient>,
+    _audit: &ToolAuditTarget<'_>,
+    _on_tool_result_handler: Option<&OnToolResultHandler>,
+) -> (CandleMessageChunk, Vec<InjectionFinding>) {
+    if let Some(client) = mcp_client {
+        match *client {}
     }
+    let chunk = CandleMessageChunk::Error(format!(
+        "Tool '{name}' not available: built without the `mcp` feature"
+    ));
+    (chunk, Vec::new())
 }
 
 /// Handle user prompt/reprompt processing with full conversation flow
 #[allow(clippy::too_many_arguments)]
 async fn handle_user_prompt<S: std::hash::BuildHasher>(
     user_message: String,
+    history: &[(CandleMessageRole, String)],
     sender: &tokio::sync::mpsc::UnboundedSender<CandleMessageChunk>,
     chat_config: &CandleChatConfig,
     model_config: &CandleModelConfig,
     provider: &TextToTextModel,
-    memory: &Arc<MemoryCoordinator>,
+    memory: &SessionMemory,
     tools: &Arc<[ToolInfo]>,
     metadata: &HashMap<String, String, S>,
+    memory_read: bool,
+    shared_recall: Option<&SharedRecall>,
+    memory_injection: &MemoryInjection,
+    cite: bool,
+    memory_write: bool,
+    prompt_guard: &PromptGuard,
+    interrupt: Option<&TurnInterrupt>,
+    trace_verbosity: Verbosity,
+    load_warnings: &[String],
+    profile: Option<&ProfileStore>,
+    confidence: Option<&ConfidenceEstimator>,
+    assistant_prefix: Option<&str>,
+    response_format: Option<&ResponseFormat>,
+    regeneration: Option<(RegenerateOptions, LastTurn)>,
     on_chunk_handler: Option<&OnChunkHandler>,
     on_tool_result_handler: Option<&OnToolResultHandler>,
     on_conversation_turn_handler: Option<&OnConversationTurnHandler>,
@@ -597,60 +1003,233 @@ async fn handle_user_prompt<S: std::hash::BuildHasher>(
         return;
     }
 
+    // Refuse messages carrying prompt-injection markers
+    if let Err(e) = prompt_guard.check_message(&user_message) {
+        log::warn!("{e}");
+        let _ = sender.send(CandleMessageChunk::Error(e.to_string()));
+        return;
+    }
+
+    let system_prompt = build_system_prompt(model_config, chat_config);
+
     // Initialize MCP client for tool execution (only if tools are configured)
     let mcp_client = initialize_mcp_client(tools, on_tool_result_handler).await;
 
-    // Search memory and build prompt
-    let memory_context = search_and_format_memory(memory, &user_message).await;
-    let full_prompt =
-        build_prompt_with_context(model_config, chat_config, &memory_context, &user_message);
+    // Search memory (unless recall is disabled for this session) and build prompt
+    #[cfg(feature = "memory")]
+    let memory_context = if memory_read {
+        search_and_format_memory(memory, shared_recall, memory_injection, &user_message).await
+    } else {
+        BuiltContext::default()
+    };
+    #[cfg(not(feature = "memory"))]
+    let memory_context = {
+        // Built without the memory core: nothing to recall, cite or store
+        let _ = (memory, memory_read, shared_recall, cite, memory_write);
+        BuiltContext::default()
+    };
+    // The profile goes after the system prompt, so a protected prompt still matches
+    let profile_block = match profile {
+        #[cfg(feature = "memory")]
+        Some(profile) => profile.render().await,
+        #[cfg(not(feature = "memory"))]
+        Some(profile) => match *profile {},
+        None => String::new(),
+    };
+    let mut turn_system_prompt = if profile_block.is_empty() {
+        system_prompt.clone()
+    } else {
+        format!("{system_prompt}\n\n{profile_block}")
+    };
+    if let Some(format) = response_format {
+        turn_system_prompt.push_str("\n\n");
+        turn_system_prompt.push_str(&format.instructions());
+    }
+    // Earlier turns of the conversation come before this turn's context,
+    // unless the context is part of the system prompt
+    let transcript = render_history(history);
+    let full_prompt = memory_injection.assemble(
+        &turn_system_prompt,
+        &transcript,
+        &memory_context.text,
+        &user_message,
+    );
+
+    // Refuse the turn if the prompt about to be sent no longer begins with
+    // the protected system prompt
+    if let Err(e) = prompt_guard.verify_system_prompt(&full_prompt) {
+        log::error!("{e}");
+        let _ = sender.send(CandleMessageChunk::Error(e.to_string()));
+        return;
+    }
 
     // Call provider
-    let prompt = CandlePrompt::new(full_prompt);
+    let prompt = CandlePrompt::new(full_prompt.clone());
     let mut params = CandleCompletionParams {
         temperature: f64::from(model_config.temperature),
         max_tokens: model_config
             .max_tokens
             .and_then(|t| std::num::NonZeroU64::new(u64::from(t))),
+        additional_params: sampling_params(model_config),
+        assistant_prefix: assistant_prefix.map(str::to_string),
+        response_format: response_format.cloned(),
+        cancellation: interrupt.map(TurnInterrupt::cancellation_token).unwrap_or_default(),
         ..Default::default()
     };
 
+    // A regenerated turn is sampled differently from the answer it replaces
+    if let Some((options, previous)) = &regeneration {
+        params.temperature = options.resolve_temperature(previous.temperature);
+        let mut extra = match params.additional_params.take() {
+            Some(serde_json::Value::Object(extra)) => extra,
+            _ => serde_json::Map::new(),
+        };
+        extra.insert("seed".to_string(), options.resolve_seed().into());
+        params.additional_params = Some(serde_json::Value::Object(extra));
+    }
+
     // Add tools
+    let mut tool_names = Vec::new();
     if let Some(ref client) = mcp_client {
         let mut all_tools: Vec<ToolInfo> = tools.to_vec();
         
         // Get tools from kodegen via MCP
-        if let Ok(kodegen_tools) = client.list_tools().await {
-            all_tools.extend(kodegen_tools);
-        }
+        all_tools.extend(list_mcp_tools(client).await);
 
         if !all_tools.is_empty() {
+            tool_names = all_tools.iter().map(|t| t.name.to_string()).collect();
             params.tools = Some(ZeroOneOrMany::from(all_tools));
         }
     }
 
-    // Stream and process completion chunks
+    // Show what the model is about to see, before its first token. The
+    // prefix is traced on its own, so the prompt stops at the reply header
+    let rendered_prompt = if trace_verbosity.is_enabled() {
+        let unprefixed = params.clone().with_assistant_prefix(None);
+        match provider.render_prompt(full_prompt.clone(), &unprefixed).await {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                log::warn!("Tracing the unrendered prompt: {}", e);
+                full_prompt.clone()
+            }
+        }
+    } else {
+        String::new()
+    };
+    let mut trace =
+        TurnTrace::capture(trace_verbosity, &rendered_prompt, &params, &tool_names, &memory_context)
+            .map(|trace| trace.with_warnings(load_warnings.to_vec()));
+    if let Some(trace) = &trace {
+        let _ = sender.send(CandleMessageChunk::Trace(trace.clone()));
+    }
+
+    // A regenerated turn keeps its IDs; tool calls are audited under them
+    let session_key = metadata.get(SESSION_ID_KEY).map_or("", String::as_str);
+    let turn = match &regeneration {
+        Some((_, previous)) => LastTurn {
+            temperature: params.temperature,
+            ..previous.clone()
+        },
+        None => LastTurn {
+            user_message: user_message.clone(),
+            temperature: params.temperature,
+            session_id: metadata
+                .get(SESSION_ID_KEY)
+                .cloned()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            turn_id: uuid::Uuid::new_v4().to_string(),
+            assistant_memory_id: None,
+        },
+    };
+
+    // Listed in crash dumps while the turn generates
+    let _generation = crate::runtime::crash::track_generation(&turn.turn_id);
+
+    // Stream and process completion chunks; the model continues after the
+    // prefix, so the reply is sent and stored with the prefix in front
     let completion_stream = provider.prompt(prompt, &params);
-    let assistant_response = stream_and_process_chunks(
+    let completion_stream: Pin<Box<dyn Stream<Item = CandleCompletionChunk> + Send>> =
+        match assistant_prefix {
+            Some(prefix) => Box::pin(
+                tokio_stream::once(CandleCompletionChunk::Text(prefix.to_string()))
+                    .chain(completion_stream),
+            ),
+            None => completion_stream,
+        };
+    #[cfg(feature = "memory")]
+    let citation_context =
+        (cite && !memory_context.is_empty()).then_some((memory.as_ref(), &memory_context));
+    #[cfg(not(feature = "memory"))]
+    let citation_context = None;
+    let (assistant_response, interrupted) = stream_and_process_chunks(
         completion_stream,
         sender,
         chat_config,
         mcp_client.as_ref(),
+        ToolAuditTarget {
+            memory,
+            session_id: &turn.session_id,
+            turn_id: &turn.turn_id,
+        },
+        citation_context,
+        confidence.map(|estimator| ConfidenceCheck {
+            estimator,
+            provider,
+            question: &user_message,
+            #[cfg(feature = "memory")]
+            memory: memory.as_ref(),
+        }),
+        trace.as_mut(),
+        interrupt,
         on_chunk_handler,
         on_tool_result_handler,
     )
     .await;
 
+    // Remember the turn so it can be regenerated
+    if !user_message.trim().is_empty() {
+        regenerate::record_last_turn(session_key, turn.clone());
+    }
+
     // Store conversation in memory including system prompt
-    if !assistant_response.is_empty() {
-        let system_prompt = build_system_prompt(model_config, chat_config);
-        store_conversation_in_memory(
-            &system_prompt,
-            &user_message,
-            &assistant_response,
-            memory,
-            metadata,
-        );
+    #[cfg(feature = "memory")]
+    if memory_write && !assistant_response.is_empty() {
+        match &regeneration {
+            Some((_, previous)) => replace_assistant_memory(
+                previous,
+                &assistant_response,
+                interrupted,
+                session_key,
+                memory,
+                metadata,
+            ),
+            None => store_conversation_in_memory(
+                &system_prompt,
+                &user_message,
+                &assistant_response,
+                interrupted,
+                &turn,
+                session_key,
+                memory,
+                metadata,
+            ),
+        }
+    }
+
+    // Learn what the user said about themselves; a regenerated turn was already seen
+    #[cfg(feature = "memory")]
+    if memory_write
+        && regeneration.is_none()
+        && let Some(profile) = profile
+    {
+        for fact in profile.learn(&user_message).await {
+            log::info!("Profile updated: {fact}");
+        }
+    }
+
+    // An interrupted turn ends here; the caller decides what comes next
+    if interrupted {
+        return;
     }
 
     // Invoke conversation turn handler if configured
@@ -688,76 +1267,156 @@ where
                 memory,
                 tools,
                 metadata,
+                memory_read,
+                shared_recall,
+                memory_injection,
+                cite_memories,
+                memory_write,
+                prompt_guard,
+                interrupt,
+                trace_verbosity,
+                profile,
+                confidence,
+                assistant_prefix,
+                response_format,
             } = config;
             let ChatSessionContexts {
                 context_file,
                 context_files,
                 context_directory,
                 context_github,
+                context_sql,
             } = contexts;
             let ChatSessionHandlers {
                 on_chunk_handler,
                 on_tool_result_handler,
                 on_conversation_turn_handler,
             } = handlers;
-
-            // Load context documents from all sources in parallel using tokio::spawn
-            let load_tasks = load_all_contexts(
-                &memory,
-                &metadata,
-                context_file,
-                context_files,
-                context_directory,
-                context_github,
+            let _session = crate::runtime::crash::track_session(
+                metadata.get(SESSION_ID_KEY).map_or("anonymous", String::as_str),
             );
 
-            // Wait for all context loading tasks to complete
+            // Load context documents from all sources in parallel using tokio::spawn.
+            // Context documents are stored as memories, so skip them when writes are disabled.
+            #[cfg(feature = "memory")]
+            let load_tasks = if memory_write {
+                load_all_contexts(
+                    &memory,
+                    &metadata,
+                    context_file,
+                    context_files,
+                    context_directory,
+                    context_github,
+                    context_sql,
+                )
+            } else {
+                Vec::new()
+            };
+
+            #[cfg(not(feature = "memory"))]
+            let load_tasks: Vec<tokio::task::JoinHandle<CandleContextLoadReport>> = Vec::new();
+
+            // Wait for all context loading tasks to complete, keeping what failed
+            let mut load_warnings = Vec::new();
+            #[cfg(not(feature = "memory"))]
+            if context_file.is_some()
+                || context_files.is_some()
+                || context_directory.is_some()
+                || context_github.is_some()
+                || context_sql.is_some()
+            {
+                load_warnings.push(
+                    "context documents not loaded: they are stored as memories, which needs the `memory` feature"
+                        .to_string(),
+                );
+            }
             for task in load_tasks {
-                if let Err(e) = task.await {
-                    log::warn!("Context loading task panicked: {e:?}");
+                match task.await {
+                    Ok(report) => load_warnings.extend(report.warnings()),
+                    Err(e) => {
+                        log::warn!("Context loading task panicked: {e:?}");
+                        load_warnings.push(format!("context loading task panicked: {e}"));
+                    }
                 }
             }
 
             // Create conversation and ALWAYS populate with history (history is not optional)
+            let history = Vec::from(conversation_history);
             let mut initial_conversation = CandleAgentConversation::new();
 
-            // Convert ZeroOneOrMany to vec for iteration
-            let history_vec: Vec<(CandleMessageRole, String)> = match conversation_history {
-                ZeroOneOrMany::None => vec![],
-                ZeroOneOrMany::One(item) => vec![item],
-                ZeroOneOrMany::Many(items) => items,
-            };
-
-            for (role, message) in history_vec {
-                initial_conversation.add_message(message, role);
+            for (role, message) in &history {
+                initial_conversation.add_message(message.clone(), *role);
             }
 
             // Execute async handler to get CandleChatLoop result
             let chat_loop_result = handler(&initial_conversation).await;
 
             // Process CandleChatLoop result
-            match chat_loop_result {
+            let (user_message, regeneration) = match chat_loop_result {
                 CandleChatLoop::Break => {
                     let _ = sender.send(process_break_loop());
+                    return;
                 }
                 CandleChatLoop::UserPrompt(user_message)
-                | CandleChatLoop::Reprompt(user_message) => {
-                    handle_user_prompt(
-                        user_message,
-                        &sender,
-                        &chat_config,
-                        &model_config,
-                        &provider,
-                        &memory,
-                        &tools,
-                        &metadata,
-                        on_chunk_handler.as_ref(),
-                        on_tool_result_handler.as_ref(),
-                        on_conversation_turn_handler.as_ref(),
-                    )
-                    .await;
+                | CandleChatLoop::Reprompt(user_message) => (user_message, None),
+                CandleChatLoop::Regenerate(options) => {
+                    let session_key = metadata.get(SESSION_ID_KEY).map_or("", String::as_str);
+                    match regenerate::last_turn(session_key) {
+                        Some(previous) => (previous.user_message.clone(), Some((options, previous))),
+                        None if session_key.is_empty() => {
+                            let _ = sender.send(CandleMessageChunk::Error(
+                                "Nothing to regenerate: set a session_id to regenerate turns".to_string(),
+                            ));
+                            return;
+                        }
+                        None => {
+                            let _ = sender.send(CandleMessageChunk::Error(
+                                "Nothing to regenerate: this session has no previous turn".to_string(),
+                            ));
+                            return;
+                        }
+                    }
                 }
+            };
+
+            let span = tracing::info_span!(
+                "chat_turn",
+                system_prompt_sha256 = tracing::field::Empty,
+                regenerate = regeneration.is_some()
+            );
+            if let Some(digest) = prompt_guard.digest() {
+                span.record("system_prompt_sha256", digest);
             }
+            handle_user_prompt(
+                user_message,
+                &history,
+                &sender,
+                &chat_config,
+                &model_config,
+                &provider,
+                &memory,
+                &tools,
+                &metadata,
+                memory_read,
+                shared_recall.as_ref(),
+                &memory_injection,
+                cite_memories,
+                memory_write,
+                &prompt_guard,
+                interrupt.as_ref(),
+                trace_verbosity,
+                &load_warnings,
+                profile.as_ref(),
+                confidence.as_ref(),
+                assistant_prefix.as_deref(),
+                response_format.as_ref(),
+                regeneration,
+                on_chunk_handler.as_ref(),
+                on_tool_result_handler.as_ref(),
+                on_conversation_turn_handler.as_ref(),
+            )
+            .instrument(span)
+            .await;
         },
     ))
 }
@@ -11,6 +11,7 @@ pub mod core;
 pub mod engines;
 pub mod filters;
 pub mod manager;
+pub mod model_template;
 pub mod parser;
 
 // Re-export core types for convenience
@@ -32,6 +33,9 @@ use std::sync::Arc;
 pub use compiler::TemplateCompiler;
 // Re-export other important types
 pub use manager::TemplateManager;
+pub use model_template::{
+    CHAT_TEMPLATE_ENV, JinjaChatTemplate, ModelChatTemplate, TemplatePreference,
+};
 // Candle-prefixed aliases for managers and other components
 pub use manager::TemplateManager as CandleTemplateManager;
 pub use parser::TemplateParser;
@@ -0,0 +1,338 @@
+//! Model chat templates: the prompt format a text-to-text model expects
+//!
+//! Models publish their prompt format as a Jinja template in the
+//! `chat_template` field of their `tokenizer_config.json`. A
+//! [`ModelChatTemplate`] renders a [`ChatConversation`] either through such a
+//! template or through one of the built-in presets ([`ChatTemplateFamily`]),
+//! whose output is pinned by golden files.
+//!
+//! Models with a preset use it unless `KODEGEN_CHAT_TEMPLATE=model` asks for
+//! the model's own template. Models without one always use theirs, so adding
+//! a model takes a `tokenizer_config.json` rather than prompt-building code.
+//!
+//! Jinja templates get the variables Hugging Face `transformers` passes them
+//! (`messages`, `tools`, `add_generation_prompt`, `enable_thinking`,
+//! `bos_token`, `eos_token`) and its `raise_exception` and `strftime_now`
+//! functions; Python string methods such as `.strip()` work too.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use minijinja::{Environment, Error, ErrorKind};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::core::{TemplateError, TemplateResult};
+use crate::capability::text_to_text::{ChatConversation, ChatRole, ChatTemplateFamily};
+use crate::domain::completion::tool_schemas;
+use crate::domain::model::traits::CandleModel;
+
+/// `preset` (default) or `model`: which template a model with both uses
+pub const CHAT_TEMPLATE_ENV: &str = "KODEGEN_CHAT_TEMPLATE";
+
+/// Name of the template inside its environment
+const TEMPLATE_NAME: &str = "chat_template";
+
+/// Which template a model with both a preset and its own template uses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TemplatePreference {
+    /// The built-in preset
+    #[default]
+    Preset,
+    /// The template from the model's `tokenizer_config.json`
+    Model,
+}
+
+impl TemplatePreference {
+    /// Preference from [`CHAT_TEMPLATE_ENV`]
+    pub fn from_env() -> Self {
+        match std::env::var(CHAT_TEMPLATE_ENV) {
+            Ok(value) if value.eq_ignore_ascii_case("model") => Self::Model,
+            Ok(value) if value.is_empty() || value.eq_ignore_ascii_case("preset") => Self::Preset,
+            Ok(value) => {
+                log::warn!("Invalid {CHAT_TEMPLATE_ENV}={value:?}, using presets");
+                Self::Preset
+            }
+            Err(_) => Self::Preset,
+        }
+    }
+}
+
+/// The prompt format of a model
+#[derive(Debug, Clone)]
+pub enum ModelChatTemplate {
+    /// Built-in rendering
+    Preset(ChatTemplateFamily),
+    /// The model's own Jinja template
+    Jinja(Arc<JinjaChatTemplate>),
+}
+
+impl ModelChatTemplate {
+    /// Choose between a model's preset and the template in its
+    /// `tokenizer_config.json`
+    ///
+    /// A model with a preset falls back to it when its own template is
+    /// preferred but cannot be loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no preset and no usable template.
+    pub fn resolve(
+        preset: Option<ChatTemplateFamily>,
+        tokenizer_config: Option<&Path>,
+        preference: TemplatePreference,
+    ) -> TemplateResult<Self> {
+        match (preset, tokenizer_config) {
+            (Some(family), Some(path)) if preference == TemplatePreference::Model => {
+                match JinjaChatTemplate::from_tokenizer_config(path) {
+                    Ok(template) => Ok(Self::Jinja(Arc::new(template))),
+                    Err(e) => {
+                        log::warn!("Using the {} preset: {}", family.name(), e);
+                        Ok(Self::Preset(family))
+                    }
+                }
+            }
+            (Some(family), _) => Ok(Self::Preset(family)),
+            (None, Some(path)) => {
+                JinjaChatTemplate::from_tokenizer_config(path).map(|t| Self::Jinja(Arc::new(t)))
+            }
+            (None, None) => Err(TemplateError::NotFound {
+                name: "tokenizer_config.json".to_string(),
+            }),
+        }
+    }
+
+    /// The template for `model`, whose tokenizer lives in `tokenizer_repo`
+    ///
+    /// `tokenizer_config.json` is only downloaded when it would be used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no preset and no usable template.
+    pub async fn load<M: CandleModel>(
+        model: &M,
+        tokenizer_repo: &str,
+        preset: Option<ChatTemplateFamily>,
+    ) -> TemplateResult<Self> {
+        let preference = TemplatePreference::from_env();
+        let tokenizer_config = if preset.is_none() || preference == TemplatePreference::Model {
+            match model
+                .huggingface_file(tokenizer_repo, "tokenizer_config.json")
+                .await
+            {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    log::warn!("No tokenizer_config.json for {}: {}", tokenizer_repo, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        Self::resolve(preset, tokenizer_config.as_deref(), preference)
+    }
+
+    /// Render `conversation` into model input, ending where the reply starts
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a Jinja template fails to render.
+    pub fn render(&self, conversation: &ChatConversation) -> TemplateResult<String> {
+        match self {
+            Self::Preset(family) => Ok(family.render(conversation)),
+            Self::Jinja(template) => template.render(conversation),
+        }
+    }
+
+    /// Token that ends an assistant turn
+    pub fn end_of_turn(&self) -> &str {
+        match self {
+            Self::Preset(family) => family.end_of_turn(),
+            Self::Jinja(template) => template.eos_token(),
+        }
+    }
+}
+
+/// A Jinja chat template, compiled once
+pub struct JinjaChatTemplate {
+    env: Environment<'static>,
+    bos_token: String,
+    eos_token: String,
+}
+
+impl std::fmt::Debug for JinjaChatTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JinjaChatTemplate")
+            .field("bos_token", &self.bos_token)
+            .field("eos_token", &self.eos_token)
+            .finish()
+    }
+}
+
+impl JinjaChatTemplate {
+    /// Compile `source` with the model's special tokens
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TemplateError::ParseError`] if the template does not compile.
+    pub fn new(
+        source: impl Into<String>,
+        bos_token: impl Into<String>,
+        eos_token: impl Into<String>,
+    ) -> TemplateResult<Self> {
+        // Block handling as in `transformers`
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_unknown_method_callback(minijinja_contrib::pycompat::unknown_method_callback);
+        env.add_function(
+            "raise_exception",
+            |message: String| -> Result<String, Error> {
+                Err(Error::new(ErrorKind::InvalidOperation, message))
+            },
+        );
+        env.add_function("strftime_now", |format: String| -> Result<String, Error> {
+            let mut out = String::new();
+            write!(out, "{}", chrono::Local::now().format(&format)).map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidOperation,
+                    format!("invalid strftime format {format:?}"),
+                )
+            })?;
+            Ok(out)
+        });
+        env.add_template_owned(TEMPLATE_NAME, source.into())
+            .map_err(|e| TemplateError::ParseError {
+                message: e.to_string(),
+            })?;
+        Ok(Self {
+            env,
+            bos_token: bos_token.into(),
+            eos_token: eos_token.into(),
+        })
+    }
+
+    /// The chat template in a `tokenizer_config.json` file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or has no
+    /// chat template.
+    pub fn from_tokenizer_config(path: &Path) -> TemplateResult<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| TemplateError::StorageError {
+            message: format!("Failed to read {}: {}", path.display(), e),
+        })?;
+        let config: Value = serde_json::from_str(&text).map_err(|e| TemplateError::ParseError {
+            message: format!("Invalid {}: {}", path.display(), e),
+        })?;
+        Self::from_tokenizer_config_value(&config)
+    }
+
+    /// The chat template in parsed `tokenizer_config.json` contents
+    ///
+    /// When several named templates are given, the one named `default` is
+    /// used, else the first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no chat template or it does not compile.
+    pub fn from_tokenizer_config_value(config: &Value) -> TemplateResult<Self> {
+        let source = match config.get("chat_template") {
+            Some(Value::String(source)) => Some(source.as_str()),
+            Some(Value::Array(named)) => named
+                .iter()
+                .find(|t| t.get("name").and_then(Value::as_str) == Some("default"))
+                .or_else(|| named.first())
+                .and_then(|t| t.get("template"))
+                .and_then(Value::as_str),
+            _ => None,
+        }
+        .ok_or_else(|| TemplateError::NotFound {
+            name: "chat_template".to_string(),
+        })?;
+        Self::new(
+            source,
+            special_token(config, "bos_token"),
+            special_token(config, "eos_token"),
+        )
+    }
+
+    /// End-of-sequence token the template was given
+    pub fn eos_token(&self) -> &str {
+        &self.eos_token
+    }
+
+    /// Render `conversation`
+    ///
+    /// A leading beginning-of-sequence token is dropped, since the tokenizer
+    /// adds it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TemplateError::RenderError`] if rendering fails, including
+    /// when the template raises an exception.
+    pub fn render(&self, conversation: &ChatConversation) -> TemplateResult<String> {
+        let messages: Vec<Message<'_>> = conversation
+            .turns
+            .iter()
+            .map(|turn| Message {
+                role: match turn.role {
+                    ChatRole::System => "system",
+                    ChatRole::User => "user",
+                    ChatRole::Assistant => "assistant",
+                    ChatRole::Tool => "tool",
+                },
+                content: &turn.content,
+            })
+            .collect();
+        let tools = (!conversation.tools.is_empty()).then(|| tool_schemas(&conversation.tools));
+
+        let render_error = |e: Error| TemplateError::RenderError {
+            message: e.to_string(),
+        };
+        let rendered = self
+            .env
+            .get_template(TEMPLATE_NAME)
+            .map_err(render_error)?
+            .render(minijinja::context! {
+                messages => messages,
+                tools => tools,
+                add_generation_prompt => true,
+                enable_thinking => conversation.enable_thinking,
+                bos_token => &self.bos_token,
+                eos_token => &self.eos_token,
+            })
+            .map_err(render_error)?;
+
+        let mut out = match rendered.strip_prefix(self.bos_token.as_str()) {
+            Some(rest) if !self.bos_token.is_empty() => rest.to_string(),
+            _ => rendered,
+        };
+        if let Some(prefix) = &conversation.assistant_prefix {
+            out.push_str(prefix);
+        }
+        Ok(out)
+    }
+}
+
+/// A conversation turn as chat templates expect it
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+/// A special token from `tokenizer_config.json`, given as a string or as an
+/// added-token object
+fn special_token(config: &Value, key: &str) -> String {
+    match config.get(key) {
+        Some(Value::String(token)) => token.clone(),
+        Some(token) => token
+            .get("content")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        None => String::new(),
+    }
+}
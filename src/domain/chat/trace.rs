@@ -0,0 +1,188 @@
+//! Per-turn traces of what the model was shown
+//!
+//! With `.trace_verbosity(Verbosity::Full)`, every turn sends a
+//! `CandleMessageChunk::Trace` before the first token: the fully rendered
+//! prompt, the tools offered, the memory hits that made it into the context
+//! with their scores, and the sampling parameters. [`Verbosity::Summary`]
+//! sends the same chunk without the prompt text, which keeps traces small
+//! enough to record for every turn. Context sources that failed to load for
+//! the session are listed as warnings, and prompt-injection findings in the
+//! recalled context are listed with what was done about them. Tool results
+//! are screened as they arrive, so a turn whose tool output has findings
+//! sends the trace again with them added.
+
+use std::fmt::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::agent::untrusted_content::InjectionFinding;
+use crate::domain::completion::CandleCompletionParams;
+use crate::domain::context::builder::{BuiltContext, ContextSourceKind, estimate_tokens};
+
+/// How much per-turn trace detail the session emits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verbosity {
+    /// No trace chunks
+    #[default]
+    Off,
+    /// Tools, memory hits and sampling parameters
+    Summary,
+    /// Everything in `Summary` plus the rendered prompt
+    Full,
+}
+
+impl Verbosity {
+    /// Whether a trace chunk is sent at all
+    pub fn is_enabled(self) -> bool {
+        self != Self::Off
+    }
+
+    /// Whether the trace carries the rendered prompt
+    pub fn includes_prompt(self) -> bool {
+        self == Self::Full
+    }
+}
+
+/// A memory entry that was placed in the prompt context
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceMemoryHit {
+    /// Memory ID, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_id: Option<String>,
+    /// Source shown in the prompt context
+    pub source: String,
+    /// Relevance score the context was ranked by
+    pub score: f32,
+}
+
+/// Sampling parameters the completion was requested with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceSampling {
+    pub temperature: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    /// Provider-specific parameters (top_k, top_p, seed, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional: Option<serde_json::Value>,
+}
+
+/// Everything the model saw for one turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnTrace {
+    /// Detail level this trace was captured at
+    pub verbosity: Verbosity,
+    /// Prompt as rendered by the model's chat template, up to where the reply
+    /// starts; at [`Verbosity::Full`] only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// Estimated prompt tokens, including any assistant prefix
+    pub prompt_tokens: usize,
+    /// Estimated tokens of the recalled context within the prompt
+    pub context_tokens: usize,
+    /// Names of the tools offered to the model
+    pub tools: Vec<String>,
+    /// Memory entries in the prompt context, in rank order
+    pub memory_hits: Vec<TraceMemoryHit>,
+    pub sampling: TraceSampling,
+    /// Pre-filled start of the reply, placed after the assistant header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assistant_prefix: Option<String>,
+    /// Context sources that failed to load for this session
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Prompt-injection findings in the recalled context and tool results
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub injection_findings: Vec<InjectionFinding>,
+}
+
+impl TurnTrace {
+    /// Trace of a turn about to be sent, or `None` when tracing is off
+    pub fn capture(
+        verbosity: Verbosity,
+        prompt: &str,
+        params: &CandleCompletionParams,
+        tools: &[String],
+        context: &BuiltContext,
+    ) -> Option<Self> {
+        if !verbosity.is_enabled() {
+            return None;
+        }
+
+        let memory_hits = context
+            .included
+            .iter()
+            .filter(|item| item.kind == ContextSourceKind::Memory)
+            .map(|item| TraceMemoryHit {
+                memory_id: item.memory_id.clone(),
+                source: item.source.clone(),
+                score: item.score,
+            })
+            .collect();
+
+        Some(Self {
+            verbosity,
+            prompt: verbosity.includes_prompt().then(|| prompt.to_string()),
+            prompt_tokens: estimate_tokens(prompt)
+                + params.assistant_prefix.as_deref().map_or(0, estimate_tokens),
+            context_tokens: context.tokens,
+            tools: tools.to_vec(),
+            memory_hits,
+            sampling: TraceSampling {
+                temperature: params.temperature,
+                max_tokens: params.max_tokens.map(std::num::NonZeroU64::get),
+                additional: params.additional_params.clone(),
+            },
+            assistant_prefix: params.assistant_prefix.clone(),
+            warnings: Vec::new(),
+            injection_findings: context.findings.clone(),
+        })
+    }
+
+    /// Attach context load failures
+    #[must_use]
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}
+
+impl fmt::Display for TurnTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!(
+            "~{} prompt tokens ({} context), temperature {}",
+            self.prompt_tokens, self.context_tokens, self.sampling.temperature
+        );
+        if let Some(max_tokens) = self.sampling.max_tokens {
+            write!(out, ", max_tokens {max_tokens}").ok();
+        }
+        if let Some(additional) = &self.sampling.additional {
+            write!(out, ", {additional}").ok();
+        }
+        if !self.tools.is_empty() {
+            write!(out, "\ntools: {}", self.tools.join(", ")).ok();
+        }
+        if let Some(prefix) = &self.assistant_prefix {
+            write!(out, "\nassistant prefix: {prefix:?}").ok();
+        }
+        for hit in &self.memory_hits {
+            write!(out, "\nmemory {:.3} {}", hit.score, hit.source).ok();
+        }
+        if !self.warnings.is_empty() {
+            write!(out, "\nwarnings:").ok();
+            for warning in &self.warnings {
+                write!(out, "\n- {warning}").ok();
+            }
+        }
+        if !self.injection_findings.is_empty() {
+            write!(out, "\ninjection findings:").ok();
+            for finding in &self.injection_findings {
+                write!(out, "\n- {finding}").ok();
+            }
+        }
+        if let Some(prompt) = &self.prompt {
+            write!(out, "\n--- prompt ---\n{prompt}\n--- end prompt ---").ok();
+        }
+        write!(f, "{out}")
+    }
+}
@@ -168,3 +168,16 @@ impl<T> From<Option<T>> for ZeroOneOrMany<T> {
         }
     }
 }
+
+impl<T> FromIterator<T> for ZeroOneOrMany<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::many(iter.into_iter().collect())
+    }
+}
+
+impl<T> Extend<T> for ZeroOneOrMany<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let items = std::mem::take(self).to_vec().into_iter().chain(iter);
+        *self = items.collect();
+    }
+}
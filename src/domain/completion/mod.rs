@@ -16,6 +16,7 @@ pub mod core;
 pub mod prompt_formatter;
 pub mod request;
 pub mod response;
+pub mod response_format;
 mod tool_formatter;
 mod tool_parser;
 pub mod types;
@@ -26,7 +27,8 @@ pub use candle::{
     StreamingCoreResponse,
 };
 pub use prompt_formatter::PromptFormatter;
-pub use tool_formatter::format_tools_for_qwen3;
+pub use response_format::{ConstraintCache, ResponseFormat};
+pub use tool_formatter::{format_tools_for_qwen3, tool_schemas};
 pub use tool_parser::{ToolCall, ToolCallParser};
 
 // Type aliases for convenience
@@ -38,7 +40,7 @@ pub type CandleCompletionRequestError = CompletionRequestError;
 pub use response::{CompactCompletionResponse, CompletionResponse};
 pub type CandleCompactCompletionResponse = CompactCompletionResponse;
 pub type CandleCompletionResponse<'a> = CompletionResponse<'a>;
-pub use types::{CandleCompletionParams, CandleModelParams};
+pub use types::{CandleCompletionParams, CandleModelParams, SamplingSettings};
 
 // Re-export CandleCompletionChunk from context/chunk.rs
 pub use crate::domain::context::chunks::CandleCompletionChunk;
@@ -120,11 +120,7 @@ impl PromptFormatter {
         &self,
         memories: &ZeroOneOrMany<RetrievalResult>,
     ) -> Option<String> {
-        let memory_items = match memories {
-            ZeroOneOrMany::None => return None,
-            ZeroOneOrMany::One(memory) => vec![memory],
-            ZeroOneOrMany::Many(memories) => memories.iter().collect(),
-        };
+        let memory_items: Vec<_> = memories.iter().collect();
 
         if memory_items.is_empty() {
             return None;
@@ -173,11 +169,7 @@ impl PromptFormatter {
 
     /// Format context section with static documents
     fn format_context_section(&self, documents: &ZeroOneOrMany<Document>) -> Option<String> {
-        let doc_items = match documents {
-            ZeroOneOrMany::None => return None,
-            ZeroOneOrMany::One(doc) => vec![doc],
-            ZeroOneOrMany::Many(docs) => docs.iter().collect(),
-        };
+        let doc_items: Vec<_> = documents.iter().collect();
 
         if doc_items.is_empty() {
             return None;
@@ -225,11 +217,7 @@ impl PromptFormatter {
 
     /// Format chat history section
     fn format_chat_history(&self, chat_history: &ZeroOneOrMany<ChatMessage>) -> Option<String> {
-        let history_items = match chat_history {
-            ZeroOneOrMany::None => return None,
-            ZeroOneOrMany::One(msg) => vec![msg],
-            ZeroOneOrMany::Many(msgs) => msgs.iter().collect(),
-        };
+        let history_items: Vec<_> = chat_history.iter().collect();
 
         if history_items.is_empty() {
             return None;
@@ -0,0 +1,108 @@
+//! Structured output: completions constrained to a JSON schema
+//!
+//! A [`ResponseFormat`] on [`CandleCompletionParams`] makes the provider mask,
+//! at every step of its generation loop, each token that would take the
+//! reply off the schema, so the reply is always a valid document. The schema
+//! is also shown to the model in the system prompt, since a model that knows
+//! the expected shape needs fewer forced tokens.
+//!
+//! Building a constraint indexes the tokenizer's vocabulary against the
+//! schema, so loaded models keep the ones they build in a
+//! [`ConstraintCache`].
+//!
+//! [`CandleCompletionParams`]: super::CandleCompletionParams
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokenizers::Tokenizer;
+
+use crate::core::generation::constraints::{
+    ConstraintResult, SchemaConstraint, constraint_from_schema, validate_schema,
+};
+
+/// JSON schema every reply must match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseFormat {
+    /// Name of the described type, shown to the model
+    pub name: String,
+    pub schema: Value,
+}
+
+impl ResponseFormat {
+    /// Replies that deserialize into `T`
+    pub fn for_type<T: JsonSchema>() -> Self {
+        Self {
+            name: T::schema_name().to_string(),
+            schema: serde_json::to_value(schemars::schema_for!(T)).unwrap_or(Value::Bool(true)),
+        }
+    }
+
+    /// Replies matching `schema`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `schema` is not a JSON object or boolean.
+    pub fn json_schema(name: impl Into<String>, schema: Value) -> ConstraintResult<Self> {
+        validate_schema(&schema)?;
+        Ok(Self {
+            name: name.into(),
+            schema,
+        })
+    }
+
+    /// System prompt text asking for the format
+    pub fn instructions(&self) -> String {
+        format!(
+            "Respond only with a JSON {} document matching this schema:\n{}",
+            self.name, self.schema
+        )
+    }
+}
+
+/// Constraints built by one model, by schema
+#[derive(Clone, Default)]
+pub struct ConstraintCache {
+    entries: Arc<Mutex<HashMap<String, Arc<SchemaConstraint>>>>,
+}
+
+impl std::fmt::Debug for ConstraintCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConstraintCache")
+            .field("entries", &self.entries.lock().len())
+            .finish()
+    }
+}
+
+impl ConstraintCache {
+    /// Empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The constraint for `format` over `tokenizer`'s vocabulary, built on
+    /// first use
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schema cannot be compiled into a constraint.
+    pub fn get_or_build(
+        &self,
+        format: &ResponseFormat,
+        tokenizer: &Tokenizer,
+    ) -> ConstraintResult<Arc<SchemaConstraint>> {
+        let key = format.schema.to_string();
+        let mut entries = self.entries.lock();
+        if let Some(constraint) = entries.get(&key) {
+            return Ok(Arc::clone(constraint));
+        }
+        log::info!("Building response constraint for {}", format.name);
+        let constraint = Arc::new(constraint_from_schema(&format.schema, tokenizer)?);
+        entries.insert(key, Arc::clone(&constraint));
+        Ok(constraint)
+    }
+}
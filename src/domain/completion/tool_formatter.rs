@@ -43,7 +43,16 @@ pub fn format_tools_for_qwen3(tools: &[ToolInfo]) -> String {
         return String::new();
     }
 
-    let tool_schemas: Vec<Value> = tools
+    let tools_json =
+        serde_json::to_string_pretty(&tool_schemas(tools)).unwrap_or_else(|_| "[]".to_string());
+
+    format!("<tools>\n{tools_json}\n</tools>")
+}
+
+/// `OpenAI`-style function schema of each tool, as passed to chat templates
+#[must_use]
+pub fn tool_schemas(tools: &[ToolInfo]) -> Vec<Value> {
+    tools
         .iter()
         .map(|tool| {
             json!({
@@ -55,10 +64,5 @@ pub fn format_tools_for_qwen3(tools: &[ToolInfo]) -> String {
                 }
             })
         })
-        .collect();
-
-    let tools_json =
-        serde_json::to_string_pretty(&tool_schemas).unwrap_or_else(|_| "[]".to_string());
-
-    format!("<tools>\n{tools_json}\n</tools>")
+        .collect()
 }
@@ -7,10 +7,14 @@ use std::ops::RangeInclusive;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio_util::sync::CancellationToken;
 
 use crate::domain::model::{
-    CandleValidationError as ValidationError, CandleValidationResult as ValidationResult,
+    CandleValidationError as ValidationError, CandleValidationIssue as ValidationIssue,
+    CandleValidationResult as ValidationResult, CandleValidationSeverity as ValidationSeverity,
 };
+use super::response_format::ResponseFormat;
+use crate::capability::text_to_text::{ChatConversation, ChatTurn};
 use cyrup_sugars::ZeroOneOrMany;
 
 /// Temperature range for generation (0.0 to 2.0)
@@ -38,6 +42,36 @@ pub struct CandleCompletionParams {
     pub tools: Option<ZeroOneOrMany<ToolInfo>>,
     /// Additional provider-specific parameters
     pub additional_params: Option<Value>,
+    /// Text the assistant response starts with
+    ///
+    /// Rendered after the assistant header so generation continues from it.
+    /// Its tokens count against `max_tokens`; it is not repeated in the
+    /// returned stream.
+    pub assistant_prefix: Option<String>,
+    /// Schema the response must match
+    ///
+    /// Tokens leaving the schema are masked during generation; an assistant
+    /// prefix counts as the start of the document.
+    pub response_format: Option<ResponseFormat>,
+    /// Text that ends the reply when generated
+    ///
+    /// The reply stops before the first stop sequence produced, which is not
+    /// included in the stream. Also read from `additional_params["stop"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Stops generation when cancelled
+    ///
+    /// Providers check it before every token and release the model as soon
+    /// as they see it; the stream then ends without a `Complete` chunk.
+    /// Generation also stops once the stream is dropped.
+    #[serde(skip)]
+    pub cancellation: CancellationToken,
+    /// Earlier turns of the conversation
+    ///
+    /// Rendered with their own roles before the prompt, which becomes the
+    /// last user turn.
+    #[serde(skip)]
+    pub history: Vec<ChatTurn>,
 }
 
 impl Default for CandleCompletionParams {
@@ -52,6 +86,11 @@ impl Default for CandleCompletionParams {
             stream: false,
             tools: None,
             additional_params: None,
+            assistant_prefix: None,
+            response_format: None,
+            stop: Vec::new(),
+            cancellation: CancellationToken::new(),
+            history: Vec::new(),
         }
     }
 }
@@ -97,6 +136,221 @@ impl CandleCompletionParams {
         self.additional_params = additional_params;
         self
     }
+
+    /// Pre-fill the start of the assistant response
+    #[must_use]
+    pub fn with_assistant_prefix(mut self, prefix: Option<String>) -> Self {
+        self.assistant_prefix = prefix.filter(|p| !p.is_empty());
+        self
+    }
+
+    /// Constrain the response to a JSON schema
+    #[must_use]
+    pub fn with_response_format(mut self, format: Option<ResponseFormat>) -> Self {
+        self.response_format = format;
+        self
+    }
+
+    /// Check that a response format is not combined with tools
+    ///
+    /// # Errors
+    ///
+    /// Returns `InconsistentData` when both are set: the schema mask blocks
+    /// `<tool_call>`, so the tools could never be called.
+    pub fn validate_response_format(&self) -> ValidationResult<()> {
+        let has_tools = self.tools.as_ref().is_some_and(|tools| !tools.is_empty());
+        if self.response_format.is_some() && has_tools {
+            return Err(ValidationError::InconsistentData {
+                description: "response_format cannot be combined with tools".into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// End the reply at any of `stop`
+    #[must_use]
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// Stop sequences from `stop` and `additional_params["stop"]`
+    ///
+    /// The parameter may be a string or an array of strings; empty
+    /// sequences are dropped.
+    #[must_use]
+    pub fn stop_sequences(&self) -> Vec<String> {
+        let extra = match self.additional_params.as_ref().and_then(|p| p.get("stop")) {
+            Some(Value::String(stop)) => vec![stop.clone()],
+            Some(Value::Array(stops)) => stops
+                .iter()
+                .filter_map(|stop| stop.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut stops: Vec<String> = Vec::new();
+        for stop in self.stop.iter().chain(&extra) {
+            if !stop.is_empty() && !stops.contains(stop) {
+                stops.push(stop.clone());
+            }
+        }
+        stops
+    }
+
+    /// Stop generation when `token` is cancelled
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Render `history` before the prompt
+    #[must_use]
+    pub fn with_history(mut self, history: Vec<ChatTurn>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Turns to render: the history, then `prompt` as a user turn
+    ///
+    /// An empty prompt adds no turn, so the history can end with an
+    /// assistant tool call or a tool result.
+    #[must_use]
+    pub fn conversation_turns(&self, prompt: String) -> Vec<ChatTurn> {
+        let mut turns = self.history.clone();
+        if !prompt.is_empty() {
+            turns.push(ChatTurn::user(prompt));
+        }
+        turns
+    }
+
+    /// The conversation providers render for `prompt`
+    ///
+    /// [`Self::conversation_turns`] with the tools offered and the assistant
+    /// prefix.
+    #[must_use]
+    pub fn conversation(&self, prompt: String) -> ChatConversation {
+        let tools = self.tools.clone().map(Into::into).unwrap_or_default();
+        ChatConversation::new(self.conversation_turns(prompt))
+            .with_tools(tools)
+            .with_assistant_prefix(self.assistant_prefix.clone())
+    }
+
+    /// Sampling settings: temperature plus the sampling keys of `additional_params`
+    #[must_use]
+    pub fn sampling_settings(&self) -> SamplingSettings {
+        let param = |key: &str| self.additional_params.as_ref().and_then(|p| p.get(key));
+
+        SamplingSettings {
+            temperature: self.temperature,
+            top_k: param("top_k").and_then(Value::as_u64),
+            top_p: param("top_p").and_then(Value::as_f64),
+            repeat_penalty: param("repeat_penalty").and_then(Value::as_f64),
+            repeat_last_n: param("repeat_last_n").and_then(Value::as_u64),
+        }
+    }
+}
+
+/// Sampling configuration checked before generation
+///
+/// `None` means the model default applies.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SamplingSettings {
+    /// Sampling temperature (0.0 selects greedy decoding)
+    pub temperature: f64,
+    /// Top-k cutoff
+    pub top_k: Option<u64>,
+    /// Nucleus sampling probability mass
+    pub top_p: Option<f64>,
+    /// Repetition penalty factor
+    pub repeat_penalty: Option<f64>,
+    /// Tokens considered by the repetition penalty
+    pub repeat_last_n: Option<u64>,
+}
+
+impl SamplingSettings {
+    /// Check for nonsensical sampling combinations
+    ///
+    /// `context_length` is the model's context window, bounding `repeat_last_n`.
+    /// Settings that are merely ignored (`top_p`/`top_k` with temperature 0)
+    /// are returned as warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ValidationError` for settings that cannot sample correctly:
+    /// `top_k` of 0, `top_p` outside (0, 1], a non-positive `repeat_penalty`
+    /// or `repeat_last_n` beyond the context window.
+    pub fn validate(&self, context_length: Option<u32>) -> ValidationResult<Vec<ValidationIssue>> {
+        if !TEMPERATURE_RANGE.contains(&self.temperature) {
+            return Err(ValidationError::InvalidRange {
+                field: "temperature".into(),
+                value: self.temperature.to_string(),
+                expected: format!(
+                    "between {:.1} and {:.1}",
+                    TEMPERATURE_RANGE.start(),
+                    TEMPERATURE_RANGE.end()
+                ),
+            });
+        }
+
+        if self.top_k == Some(0) {
+            return Err(ValidationError::InvalidRange {
+                field: "top_k".into(),
+                value: "0".into(),
+                expected: "at least 1".into(),
+            });
+        }
+
+        if let Some(top_p) = self.top_p
+            && !(top_p > 0.0 && top_p <= 1.0)
+        {
+            return Err(ValidationError::InvalidRange {
+                field: "top_p".into(),
+                value: top_p.to_string(),
+                expected: "greater than 0.0 and at most 1.0".into(),
+            });
+        }
+
+        if let Some(penalty) = self.repeat_penalty
+            && penalty <= 0.0
+        {
+            return Err(ValidationError::InvalidRange {
+                field: "repeat_penalty".into(),
+                value: penalty.to_string(),
+                expected: "greater than 0.0".into(),
+            });
+        }
+
+        if let (Some(last_n), Some(context)) = (self.repeat_last_n, context_length)
+            && last_n > u64::from(context)
+        {
+            return Err(ValidationError::InconsistentData {
+                description: format!(
+                    "repeat_last_n ({last_n}) exceeds the model context length ({context})"
+                ),
+            });
+        }
+
+        let mut warnings = Vec::new();
+        if self.temperature == 0.0 {
+            for (field, set) in [("top_p", self.top_p.is_some()), ("top_k", self.top_k.is_some())] {
+                if set {
+                    warnings.push(ValidationIssue {
+                        message: format!(
+                            "{field} is ignored with temperature 0.0 (greedy decoding)"
+                        ),
+                        severity: ValidationSeverity::Warning,
+                        field: Some(field.into()),
+                        suggestion: Some(format!(
+                            "raise temperature above 0.0 or remove {field}"
+                        )),
+                    });
+                }
+            }
+        }
+
+        Ok(warnings)
+    }
 }
 
 // Re-export existing tool definitions from the tool module
@@ -0,0 +1,384 @@
+//! Budgeted prompt context assembly
+//!
+//! [`ContextBuilder`] collects candidate context from several places — recall
+//! hits, loaded files, web results — and turns them into one block for the
+//! prompt: duplicates are dropped (keeping the better-scored copy), the rest
+//! is ranked by score and trimmed to a token budget, and every entry is
+//! annotated with where it came from. Entries are screened for prompt
+//! injection on the way in (see [`sanitize_untrusted`]) and the findings
+//! are returned with the block.
+//!
+//! ```ignore
+//! let context = ContextBuilder::new(500)
+//!     .add_memories(&memories)
+//!     .add_documents(&documents)
+//!     .build();
+//! let prompt = format!("{system}\n\n{}\n\nUser: {message}", context.text);
+//! ```
+
+use std::collections::HashMap;
+
+use super::CandleDocument;
+use crate::domain::agent::untrusted_content::{InjectionFinding, sanitize_untrusted};
+use crate::domain::memory::primitives::node::MemoryNode;
+use crate::memory::core::ops::grouping::memory_source;
+use crate::memory::core::ops::ranking::{ScoredMemory, similarity};
+use crate::memory::core::ops::similarity::boosted_score;
+use crate::memory::core::primitives::chunk::chunk_location;
+
+/// Default budget, in estimated tokens
+pub const DEFAULT_CONTEXT_TOKENS: usize = 500;
+
+/// Heading placed above the context entries
+pub const DEFAULT_CONTEXT_HEADING: &str = "## Relevant Context";
+
+/// Smallest remainder of the budget worth filling with a truncated entry
+const MIN_TRUNCATED_TOKENS: usize = 32;
+
+/// Rough token estimate used for budgeting (4 bytes per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+/// Where a context item came from
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ContextSourceKind {
+    /// Long-term memory recall
+    Memory,
+    /// Local file or directory context
+    File,
+    /// Web page or search result
+    Web,
+    /// Anything else, labelled by the caller
+    Other(String),
+}
+
+impl ContextSourceKind {
+    /// Label used in provenance annotations
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Memory => "memory",
+            Self::File => "file",
+            Self::Web => "web",
+            Self::Other(label) => label,
+        }
+    }
+}
+
+/// One candidate piece of context
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextItem {
+    /// Kind of source
+    pub kind: ContextSourceKind,
+    /// Path, URL, location or ID the content came from
+    pub source: String,
+    /// Text to include
+    pub content: String,
+    /// Relevance; higher ranks first
+    pub score: f32,
+    /// ID of the memory the item was recalled from
+    pub memory_id: Option<String>,
+}
+
+impl ContextItem {
+    /// Item with a neutral score of 0.5
+    pub fn new(kind: ContextSourceKind, source: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+            content: content.into(),
+            score: 0.5,
+            memory_id: None,
+        }
+    }
+
+    /// Set the relevance score
+    #[must_use]
+    pub fn with_score(mut self, score: f32) -> Self {
+        self.score = score;
+        self
+    }
+
+    /// Set the ID of the memory the item came from
+    #[must_use]
+    pub fn with_memory_id(mut self, memory_id: impl Into<String>) -> Self {
+        self.memory_id = Some(memory_id.into());
+        self
+    }
+
+    /// Item for a recalled memory, scored by similarity × importance
+    ///
+    /// Chunks are attributed to `path:line_start-line_end`.
+    pub fn from_memory(memory: &MemoryNode) -> Self {
+        Self::from_scored_memory(memory, boosted_score(similarity(memory), memory.importance()))
+    }
+
+    /// Item for a recalled memory with the score its ranker gave it
+    pub fn from_scored_memory(memory: &MemoryNode, score: f32) -> Self {
+        let custom = serde_json::Value::Object(
+            memory
+                .metadata
+                .custom
+                .iter()
+                .map(|(k, v)| (k.to_string(), (**v).clone()))
+                .collect(),
+        );
+        let source = match chunk_location(&custom) {
+            Some((path, span)) => format!("{}:{}-{}", path, span.line_start, span.line_end),
+            None => memory_source(memory),
+        };
+
+        Self::new(ContextSourceKind::Memory, source, memory.content().to_string())
+            .with_score(score)
+            .with_memory_id(memory.id().to_string())
+    }
+
+    /// Item for a loaded document, attributed to its `path` or `url` property
+    ///
+    /// Documents loaded from `http(s)` URLs are classed as web results.
+    pub fn from_document(document: &CandleDocument) -> Self {
+        let source = ["path", "url", "source"]
+            .iter()
+            .find_map(|key| document.additional_props.get(*key)?.as_str())
+            .unwrap_or("document")
+            .to_string();
+        let kind = if source.starts_with("http://") || source.starts_with("https://") {
+            ContextSourceKind::Web
+        } else {
+            ContextSourceKind::File
+        };
+        Self::new(kind, source, document.data.clone())
+    }
+
+    /// Formatted entry, e.g. `- [memory: src/lib.rs:10-24]: ...`
+    fn entry(&self, content: &str) -> String {
+        format!("- [{}: {}]: {}\n", self.kind.label(), self.source, content)
+    }
+}
+
+/// Assembled context block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuiltContext {
+    /// Context block ready to place in a prompt (empty when nothing was included)
+    pub text: String,
+    /// Items included, in rank order (the last may be truncated)
+    pub included: Vec<ContextItem>,
+    /// Items left out as duplicates
+    pub duplicates: usize,
+    /// Items left out for lack of budget
+    pub dropped: usize,
+    /// Items left out for scoring below the relevance cutoff
+    pub below_cutoff: usize,
+    /// Estimated tokens of `text`
+    pub tokens: usize,
+    /// Prompt-injection findings in the included items
+    pub findings: Vec<InjectionFinding>,
+}
+
+impl BuiltContext {
+    /// Whether no context was included
+    pub fn is_empty(&self) -> bool {
+        self.included.is_empty()
+    }
+}
+
+/// Composes context items into a budgeted block
+#[derive(Debug, Clone)]
+pub struct ContextBuilder {
+    items: Vec<ContextItem>,
+    token_budget: usize,
+    heading: String,
+    min_score: Option<f32>,
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONTEXT_TOKENS)
+    }
+}
+
+impl ContextBuilder {
+    /// Builder with a budget in estimated tokens
+    pub fn new(token_budget: usize) -> Self {
+        Self {
+            items: Vec::new(),
+            token_budget,
+            heading: DEFAULT_CONTEXT_HEADING.to_string(),
+            min_score: None,
+        }
+    }
+
+    /// Replace the heading (an empty heading omits it)
+    #[must_use]
+    pub fn with_heading(mut self, heading: impl Into<String>) -> Self {
+        self.heading = heading.into();
+        self
+    }
+
+    /// Drop items scoring below `min_score` before ranking
+    ///
+    /// Weak matches are left out entirely rather than filling the budget.
+    #[must_use]
+    pub fn with_min_score(mut self, min_score: f32) -> Self {
+        self.min_score = Some(min_score);
+        self
+    }
+
+    /// Add one item
+    #[must_use]
+    pub fn add(mut self, item: ContextItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Add recalled memories
+    #[must_use]
+    pub fn add_memories<'a>(mut self, memories: impl IntoIterator<Item = &'a MemoryNode>) -> Self {
+        self.items.extend(memories.into_iter().map(ContextItem::from_memory));
+        self
+    }
+
+    /// Add memories recalled from a shared library
+    ///
+    /// Scores are multiplied by `weight` and sources are prefixed with the
+    /// library name, e.g. `team_notes:src/lib.rs:10-24`.
+    #[must_use]
+    pub fn add_library_memories<'a>(
+        mut self,
+        library: &str,
+        weight: f32,
+        memories: impl IntoIterator<Item = &'a MemoryNode>,
+    ) -> Self {
+        self.items.extend(memories.into_iter().map(|memory| {
+            let item = ContextItem::from_memory(memory);
+            ContextItem {
+                source: format!("{library}:{}", item.source),
+                score: item.score * weight,
+                ..item
+            }
+        }));
+        self
+    }
+
+    /// Add memories ordered by a [`MemoryRanker`], keeping its scores
+    ///
+    /// With a `library`, scores are multiplied by `weight` and sources are
+    /// prefixed as in [`Self::add_library_memories`].
+    ///
+    /// [`MemoryRanker`]: crate::memory::core::ops::ranking::MemoryRanker
+    #[must_use]
+    pub fn add_ranked_memories<'a>(
+        mut self,
+        library: Option<(&str, f32)>,
+        ranked: impl IntoIterator<Item = &'a ScoredMemory>,
+    ) -> Self {
+        self.items.extend(ranked.into_iter().map(|scored| {
+            let item = ContextItem::from_scored_memory(&scored.memory, scored.score);
+            match library {
+                Some((library, weight)) => ContextItem {
+                    source: format!("{library}:{}", item.source),
+                    score: item.score * weight,
+                    ..item
+                },
+                None => item,
+            }
+        }));
+        self
+    }
+
+    /// Add loaded documents
+    #[must_use]
+    pub fn add_documents<'a>(
+        mut self,
+        documents: impl IntoIterator<Item = &'a CandleDocument>,
+    ) -> Self {
+        self.items.extend(documents.into_iter().map(ContextItem::from_document));
+        self
+    }
+
+    /// Deduplicate, rank and trim the items into one block
+    pub fn build(self) -> BuiltContext {
+        let mut built = BuiltContext::default();
+
+        // Deduplicate on normalized content, keeping the higher score
+        let mut unique: Vec<ContextItem> = Vec::with_capacity(self.items.len());
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for item in self.items {
+            let key = normalize(&item.content);
+            if key.is_empty() {
+                continue;
+            }
+            if self.min_score.is_some_and(|min| item.score < min) {
+                built.below_cutoff += 1;
+                continue;
+            }
+            match seen.get(&key) {
+                Some(&index) => {
+                    built.duplicates += 1;
+                    if item.score > unique[index].score {
+                        unique[index] = item;
+                    }
+                }
+                None => {
+                    seen.insert(key, unique.len());
+                    unique.push(item);
+                }
+            }
+        }
+
+        unique.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let mut text = if self.heading.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n\n", self.heading)
+        };
+        let mut remaining = self.token_budget.saturating_sub(estimate_tokens(&text));
+
+        for mut item in unique {
+            let sanitized = sanitize_untrusted(&item.source, &item.content);
+            item.content = sanitized.text;
+
+            let entry = item.entry(&item.content);
+            let cost = estimate_tokens(&entry);
+            if cost <= remaining {
+                remaining -= cost;
+                text.push_str(&entry);
+                built.included.push(item);
+                built.findings.extend(sanitized.findings);
+                continue;
+            }
+
+            let overhead = estimate_tokens(&item.entry("…"));
+            if remaining >= MIN_TRUNCATED_TOKENS.max(overhead + 1) {
+                let keep = item.content.floor_char_boundary((remaining - overhead) * 4);
+                let truncated = format!("{}…", item.content[..keep].trim_end());
+                let entry = item.entry(&truncated);
+                remaining = remaining.saturating_sub(estimate_tokens(&entry));
+                text.push_str(&entry);
+                built.included.push(ContextItem {
+                    content: truncated,
+                    ..item
+                });
+                built.findings.extend(sanitized.findings);
+            } else {
+                built.dropped += 1;
+            }
+        }
+
+        if !built.included.is_empty() {
+            built.tokens = estimate_tokens(&text);
+            built.text = text;
+        }
+        built
+    }
+}
+
+/// Lowercased content with whitespace runs collapsed
+fn normalize(content: &str) -> String {
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
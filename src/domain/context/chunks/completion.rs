@@ -58,6 +58,12 @@ pub enum CandleCompletionChunk {
         token_count: Option<u32>,
         elapsed_secs: Option<f64>,
         tokens_per_sec: Option<f64>,
+        /// Mean natural-log probability of the generated tokens
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mean_logprob: Option<f64>,
+        /// Whether the model ran with flash-attention kernels, when reported
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        flash_attention: Option<bool>,
     },
 
     /// Error occurred during streaming
@@ -46,6 +46,12 @@ pub struct GenerationStats {
     pub elapsed_secs: f64,
     /// Throughput: tokens generated per second
     pub tokens_per_sec: f64,
+    /// Mean natural-log probability of the sampled tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_logprob: Option<f64>,
+    /// Whether the model ran with flash-attention kernels
+    #[serde(default)]
+    pub flash_attention: bool,
 }
 
 /// Streaming text chunk from `TextGenerator` to Engine layer
@@ -4,6 +4,8 @@ use std::time::Duration;
 
 use thiserror::Error;
 
+use super::validation::ExtractionAttempt;
+
 /// Error types for extraction operations
 #[derive(Debug, Error)]
 pub enum ExtractionError {
@@ -31,6 +33,14 @@ pub enum ExtractionError {
     #[error("Validation failed: {reason}")]
     ValidationFailed { reason: String },
 
+    /// Every attempt, including repairs, failed parsing or validation
+    #[error(
+        "Extraction failed after {} attempts: {}",
+        .attempts.len(),
+        .attempts.last().map(|a| a.errors.join("; ")).unwrap_or_default()
+    )]
+    RepairsExhausted { attempts: Vec<ExtractionAttempt> },
+
     /// Generic error for other cases
     #[error("Extraction error: {0}")]
     Other(String),
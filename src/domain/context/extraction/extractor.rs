@@ -1,5 +1,6 @@
 use std::fmt;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use crate::async_stream;
 use cyrup_sugars::prelude::MessageChunk;
@@ -7,12 +8,16 @@ use serde::de::DeserializeOwned;
 use tokio_stream::{Stream, StreamExt};
 
 use super::error::{_ExtractionResult as ExtractionResult, ExtractionError};
+use super::model::ExtractionResult as Extraction;
+use super::validation::{
+    DEFAULT_MAX_REPAIRS, ExtractionAttempt, ExtractionValidator, repair_prompt,
+};
 use crate::builders::completion::CompletionRequestBuilder;
 use crate::capability::traits::TextToTextCapable;
 use crate::domain::{
     chat::message::types::CandleMessageRole as MessageRole,
     completion::types::CandleCompletionParams as CompletionParams,
-    context::chunks::{CandleCompletionChunk, FinishReason},
+    context::chunks::CandleCompletionChunk,
     prompt::CandlePrompt as Prompt,
 };
 
@@ -41,6 +46,8 @@ where
 {
     provider: P,
     system_prompt: Option<String>,
+    validator: Option<ExtractionValidator<T>>,
+    max_repairs: usize,
     _marker: PhantomData<T>,
 }
 
@@ -53,6 +60,8 @@ where
         f.debug_struct("ExtractorImpl")
             .field("provider", &"<TextToTextCapable>")
             .field("system_prompt", &self.system_prompt)
+            .field("validator", &self.validator.as_ref().map(|_| "<validator>"))
+            .field("max_repairs", &self.max_repairs)
             .finish()
     }
 }
@@ -73,85 +82,17 @@ where
 
     fn extract_from(&self, text: &str) -> impl Stream<Item = T> {
         let text = text.to_string();
-        let provider = self.provider.clone();
-        let system_prompt = self.system_prompt.clone().unwrap_or_else(|| {
-            format!("Extract structured data from the following text. Return ONLY valid JSON matching the expected schema. Text: {text}")
-        });
+        let extractor = self.clone();
 
         async_stream::spawn_stream(move |tx| async move {
-            let completion_request = match CompletionRequestBuilder::new()
-                .system_prompt(system_prompt.clone())
-                .build()
-            {
-                Ok(req) => req,
-                Err(_e) => {
-                    let _ = tx.send(T::default());
-                    return;
+            let data = match extractor.extract_validated(&text).await {
+                Ok(result) => result.data,
+                Err(e) => {
+                    log::warn!("Extraction failed: {e}");
+                    T::default()
                 }
             };
-
-            // Execute extraction asynchronously using tokio streams
-            let model = &provider;
-            let prompt = Prompt {
-                content: completion_request.system_prompt,
-                role: MessageRole::System,
-            };
-            let params = CompletionParams {
-                temperature: completion_request.temperature,
-                max_tokens: completion_request
-                    .max_tokens
-                    .and_then(|t| std::num::NonZeroU64::new(t.get())),
-                n: std::num::NonZeroU8::MIN,
-                stream: true,
-                tools: None,
-                additional_params: None,
-            };
-
-            // Get the stream and process chunks asynchronously
-            let stream = model.prompt(prompt, &params);
-            tokio::pin!(stream);
-
-            // Process chunks to build response
-            let mut full_response = String::new();
-            let mut finish_reason = None;
-
-            while let Some(chunk) = stream.next().await {
-                match chunk {
-                    CandleCompletionChunk::Text(text) => {
-                        full_response.push_str(&text);
-                    }
-                    CandleCompletionChunk::Complete {
-                        text,
-                        finish_reason: reason,
-                        ..
-                    } => {
-                        if !text.is_empty() {
-                            full_response.push_str(&text);
-                        }
-                        finish_reason = reason;
-                        break;
-                    }
-                    CandleCompletionChunk::Error(_err) => {
-                        let _ = tx.send(T::default());
-                        return;
-                    }
-                    _ => {}
-                }
-            }
-
-            // Parse and send result
-            if finish_reason == Some(FinishReason::Stop) || !full_response.is_empty() {
-                match Self::parse_json_response(&full_response) {
-                    Ok(result) => {
-                        let _ = tx.send(result);
-                    }
-                    Err(_e) => {
-                        let _ = tx.send(T::default());
-                    }
-                }
-            } else {
-                let _ = tx.send(T::default());
-            }
+            let _ = tx.send(data);
         })
     }
 }
@@ -166,6 +107,8 @@ where
         Self {
             provider,
             system_prompt: None,
+            validator: None,
+            max_repairs: DEFAULT_MAX_REPAIRS,
             _marker: PhantomData,
         }
     }
@@ -176,6 +119,131 @@ where
         &self.provider
     }
 
+    /// Check parsed results with `validator`, repairing those that fail
+    #[must_use]
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&T) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Re-prompt at most `max_repairs` times after the first attempt (0, the default, disables repair)
+    #[must_use]
+    pub fn with_max_repairs(mut self, max_repairs: usize) -> Self {
+        self.max_repairs = max_repairs;
+        self
+    }
+
+    /// Extract, feeding parse and validation errors back to the model until
+    /// the result is valid or the repairs run out
+    ///
+    /// The result records every attempt.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ExtractionError::CompletionError` if the model fails, or
+    /// `ExtractionError::RepairsExhausted` with every attempt if no response
+    /// passed.
+    pub async fn extract_validated(&self, text: &str) -> ExtractionResult<Extraction<T>> {
+        let base_prompt = self.system_prompt.clone().unwrap_or_else(|| {
+            format!("Extract structured data from the following text. Return ONLY valid JSON matching the expected schema. Text: {text}")
+        });
+
+        let mut prompt = base_prompt.clone();
+        let mut attempts = Vec::new();
+        let mut tokens_used = 0;
+
+        for number in 1..=self.max_repairs + 1 {
+            let (response, tokens) = Self::complete(&self.provider, prompt).await?;
+            tokens_used += tokens;
+
+            let errors = match Self::parse_json_response(&response) {
+                Ok(data) => {
+                    let errors = self
+                        .validator
+                        .as_ref()
+                        .map(|validate| validate(&data))
+                        .unwrap_or_default();
+                    if errors.is_empty() {
+                        attempts.push(ExtractionAttempt {
+                            number,
+                            response: response.clone(),
+                            errors,
+                        });
+                        return Ok(Extraction::new(data, response, tokens_used).with_attempts(attempts));
+                    }
+                    errors
+                }
+                Err(e) => vec![e.to_string()],
+            };
+
+            log::debug!("Extraction attempt {number} rejected: {}", errors.join("; "));
+            prompt = repair_prompt(&base_prompt, &response, &errors);
+            attempts.push(ExtractionAttempt {
+                number,
+                response,
+                errors,
+            });
+        }
+
+        Err(ExtractionError::RepairsExhausted { attempts })
+    }
+
+    /// Run one completion, returning the response text and tokens generated
+    async fn complete(provider: &P, system_prompt: String) -> ExtractionResult<(String, usize)> {
+        let completion_request = CompletionRequestBuilder::new()
+            .system_prompt(system_prompt)
+            .build()
+            .map_err(|e| ExtractionError::CompletionError(format!("{e:?}")))?;
+
+        let prompt = Prompt {
+            content: completion_request.system_prompt,
+            role: MessageRole::System,
+        };
+        let params = CompletionParams {
+            temperature: completion_request.temperature,
+            max_tokens: completion_request
+                .max_tokens
+                .and_then(|t| std::num::NonZeroU64::new(t.get())),
+            n: std::num::NonZeroU8::MIN,
+            stream: true,
+            tools: None,
+            additional_params: None,
+            ..Default::default()
+        };
+
+        // Get the stream and process chunks asynchronously
+        let stream = provider.prompt(prompt, &params);
+        tokio::pin!(stream);
+
+        let mut full_response = String::new();
+        let mut tokens = 0;
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                CandleCompletionChunk::Text(text) => {
+                    full_response.push_str(&text);
+                }
+                CandleCompletionChunk::Complete {
+                    text, token_count, ..
+                } => {
+                    if !text.is_empty() {
+                        full_response.push_str(&text);
+                    }
+                    tokens = token_count.unwrap_or_default() as usize;
+                    break;
+                }
+                CandleCompletionChunk::Error(err) => {
+                    return Err(ExtractionError::CompletionError(err));
+                }
+                _ => {}
+            }
+        }
+
+        Ok((full_response, tokens))
+    }
+
     /// Parse JSON response
     ///
     /// # Errors
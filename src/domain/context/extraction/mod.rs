@@ -6,11 +6,15 @@
 mod error;
 mod extractor;
 mod model;
+mod validation;
 
 // Re-export the main types
 pub use error::ExtractionError;
 pub use extractor::{Extractor, ExtractorImpl};
 pub use model::{ExtractionConfig, ExtractionRequest, ExtractionResult};
+pub use validation::{
+    DEFAULT_MAX_REPAIRS, ExtractionAttempt, ExtractionValidator, repair_prompt,
+};
 
 /// Result type for extraction operations
 pub type Result<T> = std::result::Result<T, ExtractionError>;
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::validation::ExtractionAttempt;
+
 /// Configuration for the extraction model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractionConfig {
@@ -74,6 +76,8 @@ pub struct ExtractionResult<T> {
     pub raw_response: String,
     /// The number of tokens used
     pub tokens_used: usize,
+    /// Every attempt made, including repairs, in order
+    pub attempts: Vec<ExtractionAttempt>,
 }
 
 impl<T> ExtractionResult<T> {
@@ -83,6 +87,19 @@ impl<T> ExtractionResult<T> {
             data,
             raw_response,
             tokens_used,
+            attempts: Vec::new(),
         }
     }
+
+    /// Record the attempts that produced this result
+    #[must_use]
+    pub fn with_attempts(mut self, attempts: Vec<ExtractionAttempt>) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Number of repair prompts that were needed
+    pub fn repairs(&self) -> usize {
+        self.attempts.len().saturating_sub(1)
+    }
 }
@@ -0,0 +1,54 @@
+//! Domain validation and error-guided repair for extracted data
+//!
+//! Valid JSON of the right shape can still break rules the schema cannot
+//! express: an end date before its start, a status outside the allowed set.
+//! An extractor given a validator checks every parsed result. Repair is
+//! opt-in: with a repair limit set, a response that fails to parse or
+//! validate is sent back to the model with the specific errors, up to that
+//! many times. Every attempt is kept so a failed or repaired extraction can
+//! be debugged afterwards.
+
+use std::sync::Arc;
+
+/// Checks extracted data, returning one message per violated rule
+///
+/// An empty list means the data is valid.
+pub type ExtractionValidator<T> = Arc<dyn Fn(&T) -> Vec<String> + Send + Sync>;
+
+/// Repair prompts sent after the first attempt, unless configured otherwise
+///
+/// None: the first response is final until repairs are asked for.
+pub const DEFAULT_MAX_REPAIRS: usize = 0;
+
+/// One model response and what was wrong with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractionAttempt {
+    /// 1 for the first attempt, 2 for the first repair, and so on
+    pub number: usize,
+    /// Raw model response
+    pub response: String,
+    /// Parse or validation errors; empty when the attempt succeeded
+    pub errors: Vec<String>,
+}
+
+impl ExtractionAttempt {
+    /// Whether the response parsed and passed validation
+    pub fn succeeded(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Prompt asking the model to fix `previous` given its `errors`
+pub fn repair_prompt(base_prompt: &str, previous: &str, errors: &[String]) -> String {
+    let errors = errors
+        .iter()
+        .map(|error| format!("- {error}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{base_prompt}\n\n\
+         Your previous answer was:\n{previous}\n\n\
+         It was rejected for these reasons:\n{errors}\n\n\
+         Return ONLY corrected JSON that fixes every problem listed above."
+    )
+}
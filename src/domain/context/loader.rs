@@ -152,19 +152,7 @@ impl Loader<PathBuf> for LoaderImpl<PathBuf> {
                 results.retain(|item| filter(item));
             }
 
-            // Convert Vec<PathBuf> to ZeroOneOrMany<PathBuf> without unwrap
-            match results.len() {
-                0 => ZeroOneOrMany::None,
-                1 => {
-                    let mut iter = results.into_iter();
-                    if let Some(item) = iter.next() {
-                        ZeroOneOrMany::One(item)
-                    } else {
-                        ZeroOneOrMany::None
-                    }
-                }
-                _ => ZeroOneOrMany::many(results),
-            }
+            ZeroOneOrMany::from(results)
         })
     }
 
@@ -208,25 +196,7 @@ impl Loader<PathBuf> for LoaderImpl<PathBuf> {
         let load_task = self.load_all();
         tokio::task::spawn(async move {
             let paths = load_task.await.unwrap_or(ZeroOneOrMany::None);
-            let results: Vec<U> = match paths {
-                ZeroOneOrMany::None => Vec::new(),
-                ZeroOneOrMany::One(path) => vec![processor(&path)],
-                ZeroOneOrMany::Many(paths) => paths.iter().map(processor).collect(),
-            };
-
-            // Convert Vec<U> to ZeroOneOrMany<U> without unwrap
-            match results.len() {
-                0 => ZeroOneOrMany::None,
-                1 => {
-                    let mut iter = results.into_iter();
-                    if let Some(item) = iter.next() {
-                        ZeroOneOrMany::One(item)
-                    } else {
-                        ZeroOneOrMany::None
-                    }
-                }
-                _ => ZeroOneOrMany::many(results),
-            }
+            paths.iter().map(processor).collect()
         })
     }
 
@@ -6,10 +6,12 @@
 //! - Production-ready context management with memory integration (from context.rs)
 //! - Core file loading interface and implementation (from loader.rs)
 //! - Structured data extraction from unstructured text (from extraction/)
+//! - Budgeted prompt context assembly with provenance (from builder.rs)
 //!
 //! The module provides a clean, unified interface for all context operations
 //! while maintaining high performance and memory integration capabilities.
 
+pub mod builder;
 pub mod chunks;
 pub mod document;
 pub mod extraction;
@@ -20,6 +22,7 @@ pub mod realtime;
 pub mod traits;
 
 // Re-export all types for easy access
+pub use builder::*;
 pub use chunks::*;
 pub use document::*;
 pub use extraction::*;
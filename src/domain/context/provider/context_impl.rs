@@ -1,8 +1,10 @@
 //! Context implementation module for Candle context provider system
 //!
 //! This module contains `CandleContext<T>` and all type-specific implementations
-//! for File, Files, Directory, and GitHub context operations.
+//! for File, Files, Directory, and GitHub context operations (SQL lives in
+//! [`super::sql`]).
 
+#[cfg(feature = "git")]
 use kodegen_tools_git::{
     CloneOpts, FetchOpts, GitError as GitGixError, MergeOpts, clone_repo, fetch, merge, open_repo,
 };
@@ -14,18 +16,20 @@ use std::time::SystemTime;
 use tokio_stream::Stream;
 use uuid::Uuid;
 
+use super::file_tree::{FileTreeEntry, file_tree_document};
 use super::processor::CandleStreamingContextProcessor;
 use super::types::{
-    CandleContextError, CandleContextEvent, CandleDirectory, CandleFile, CandleFiles, CandleGithub,
-    CandleImmutableDirectoryContext, CandleImmutableFileContext, CandleImmutableFilesContext,
-    CandleImmutableGithubContext,
+    CandleContextError, CandleContextEvent, CandleContextStream, CandleDirectory,
+    CandleDirectoryOutput, CandleFile,
+    CandleFiles, CandleGithub, CandleImmutableDirectoryContext, CandleImmutableFileContext,
+    CandleImmutableFilesContext, CandleImmutableGithubContext, CandleImmutableSqlContext,
 };
 use crate::domain::context::CandleDocument as Document;
 
 /// Context wrapper with zero Arc usage
 #[derive(Debug)]
 pub struct CandleContext<T> {
-    source: CandleContextSourceType,
+    pub(super) source: CandleContextSourceType,
     processor: CandleStreamingContextProcessor,
     _marker: PhantomData<T>,
 }
@@ -37,6 +41,7 @@ pub enum CandleContextSourceType {
     Files(CandleImmutableFilesContext),
     Directory(CandleImmutableDirectoryContext),
     Github(CandleImmutableGithubContext),
+    Sql(CandleImmutableSqlContext),
 }
 
 impl<T> Clone for CandleContext<T> {
@@ -135,16 +140,19 @@ impl CandleContext<CandleFile> {
         Self::new(CandleContextSourceType::File(file_context))
     }
 
-    /// Load documents asynchronously with streaming - returns unwrapped values
+    /// Load documents asynchronously with streaming - failures arrive as `Err` items
     #[inline]
-    pub fn load(self) -> Pin<Box<dyn Stream<Item = Document> + Send>> {
+    pub fn load(self) -> CandleContextStream {
         match self.source {
             CandleContextSourceType::File(file_context) => {
                 self.processor.process_file_context(file_context)
             }
-            _ => Box::pin(crate::async_stream::spawn_stream(move |_tx| async move {
+            _ => Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
                 // Invalid context type for file loading
                 log::error!("Invalid context type for file loading");
+                let _ = tx.send(Err(CandleContextError::ContextNotFound(
+                    "Invalid context type".to_string(),
+                )));
             })),
         }
     }
@@ -165,63 +173,79 @@ impl CandleContext<CandleFiles> {
         Self::new(CandleContextSourceType::Files(files_context))
     }
 
-    /// Load documents asynchronously with streaming - returns unwrapped values
+    /// Load documents asynchronously with streaming - failures arrive as `Err` items
     #[inline]
-    pub fn load(self) -> Pin<Box<dyn Stream<Item = Document> + Send>> {
+    pub fn load(self) -> CandleContextStream {
         Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
             match self.source {
                 CandleContextSourceType::Files(files_context) => {
                     // Expand glob pattern and load files
                     match glob::glob(&files_context.pattern) {
                         Ok(paths) => {
-                            for entry in paths.flatten() {
-                                if let Ok(content) = tokio::fs::read_to_string(&entry).await {
-                                    let document = Document {
-                                        data: content,
-                                        format: Some(
-                                            crate::domain::context::CandleContentFormat::Text,
-                                        ),
-                                        media_type: Some(
-                                            crate::domain::context::CandleDocumentMediaType::TXT,
-                                        ),
-                                        additional_props: {
-                                            let mut props = HashMap::new();
-                                            props.insert(
-                                                "id".to_string(),
-                                                serde_json::Value::String(
-                                                    Uuid::new_v4().to_string(),
-                                                ),
-                                            );
-                                            props.insert(
-                                                "path".to_string(),
-                                                serde_json::Value::String(
-                                                    entry.to_string_lossy().to_string(),
-                                                ),
-                                            );
-                                            props
-                                        },
-                                    };
-                                    let _ = tx.send(document);
+                            for entry in paths {
+                                let entry = match entry {
+                                    Ok(entry) => entry,
+                                    Err(e) => {
+                                        let _ = tx.send(Err(CandleContextError::IoError(e.to_string())));
+                                        continue;
+                                    }
+                                };
+                                match tokio::fs::read_to_string(&entry).await {
+                                    Ok(content) => {
+                                        let document = Document {
+                                            data: content,
+                                            format: Some(
+                                                crate::domain::context::CandleContentFormat::Text,
+                                            ),
+                                            media_type: Some(
+                                                crate::domain::context::CandleDocumentMediaType::TXT,
+                                            ),
+                                            additional_props: {
+                                                let mut props = HashMap::new();
+                                                props.insert(
+                                                    "id".to_string(),
+                                                    serde_json::Value::String(
+                                                        Uuid::new_v4().to_string(),
+                                                    ),
+                                                );
+                                                props.insert(
+                                                    "path".to_string(),
+                                                    serde_json::Value::String(
+                                                        entry.to_string_lossy().to_string(),
+                                                    ),
+                                                );
+                                                props
+                                            },
+                                        };
+                                        let _ = tx.send(Ok(document));
+                                    }
+                                    Err(e) => {
+                                        let _ = tx.send(Err(CandleContextError::IoError(format!(
+                                            "Failed to read {}: {e}",
+                                            entry.display()
+                                        ))));
+                                    }
                                 }
                             }
                         }
                         Err(e) => {
-                            log::error!(
-                                "Streaming error in {}: {:?}",
-                                "Glob pattern error",
-                                CandleContextError::ContextNotFound(format!(
-                                    "Glob pattern error: {e}"
-                                ))
-                            );
+                            let error = CandleContextError::PatternError(format!(
+                                "Glob pattern error for '{}': {e}",
+                                files_context.pattern
+                            ));
+                            log::error!("Streaming error in {}: {:?}", "Glob pattern error", error);
+                            let _ = tx.send(Err(error));
                         }
                     }
                 }
                 _ => {
+                    let error = CandleContextError::ContextNotFound("Invalid context type".to_string());
                     log::error!(
                         "Streaming error in {}: {:?}",
                         "Invalid context type for files loading",
-                        CandleContextError::ContextNotFound("Invalid context type".to_string())
+                        error
                     );
+                    let _ = tx.send(Err(error));
                 }
             }
         }))
@@ -239,14 +263,15 @@ impl CandleContext<CandleDirectory> {
             recursive: true,
             extensions: Vec::new(),
             max_depth: None,
+            output: CandleDirectoryOutput::default(),
             memory_integration: None,
         };
         Self::new(CandleContextSourceType::Directory(directory_context))
     }
 
-    /// Load documents asynchronously with streaming - returns unwrapped values
+    /// Load documents asynchronously with streaming - failures arrive as `Err` items
     #[inline]
-    pub fn load(self) -> Pin<Box<dyn Stream<Item = Document> + Send>> {
+    pub fn load(self) -> CandleContextStream {
         Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
             // Use spawn for async directory traversal
             let _ = tokio::task::spawn(async move {
@@ -260,7 +285,11 @@ impl CandleContext<CandleDirectory> {
                             extensions: Vec<String>,
                             max_depth: Option<usize>,
                             current_depth: usize,
-                            sender: tokio::sync::mpsc::UnboundedSender<Document>,
+                            sender: tokio::sync::mpsc::UnboundedSender<
+                                Result<Document, CandleContextError>,
+                            >,
+                            // Set to list files for the tree instead of reading them
+                            tree: Option<std::sync::Arc<parking_lot::Mutex<Vec<(PathBuf, u64)>>>>,
                         ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), std::io::Error>> + Send>> {
                             Box::pin(async move {
                             if let Some(max) = max_depth
@@ -282,10 +311,17 @@ impl CandleContext<CandleDirectory> {
                                         )
                                     };
 
-                                    if should_include
-                                        && let Ok(content) = tokio::fs::read_to_string(&path).await
-                                    {
-                                        let document = Document {
+                                    if !should_include {
+                                        continue;
+                                    }
+                                    if let Some(tree) = &tree {
+                                        let size = entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+                                        tree.lock().push((path, size));
+                                        continue;
+                                    }
+                                    match tokio::fs::read_to_string(&path).await {
+                                        Ok(content) => {
+                                            let document = Document {
                                                 data: content,
                                                 format: Some(crate::domain::context::CandleContentFormat::Text),
                                                 media_type: Some(
@@ -307,7 +343,13 @@ impl CandleContext<CandleDirectory> {
                                                     );
                                                     props
                                                 }};
-                                        let _ = sender.send(document);
+                                            let _ = sender.send(Ok(document));
+                                        }
+                                        Err(e) => {
+                                            let _ = sender.send(Err(CandleContextError::IoError(
+                                                format!("Failed to read {}: {e}", path.display()),
+                                            )));
+                                        }
                                     }
                                 } else if path.is_dir()
                                     && recursive
@@ -320,6 +362,7 @@ impl CandleContext<CandleDirectory> {
                                         max_depth,
                                         current_depth + 1,
                                         sender.clone(),
+                                        tree.clone(),
                                     ).await?;
                                 }
                             }
@@ -327,6 +370,43 @@ impl CandleContext<CandleDirectory> {
                             })
                         }
 
+                        let output = directory_context.output;
+
+                        // The tree comes first so it can guide what is read next
+                        if output.includes_tree() {
+                            let files = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+                            let listed = traverse_dir(
+                                directory_context.path.clone(),
+                                directory_context.recursive,
+                                directory_context.extensions.clone(),
+                                directory_context.max_depth,
+                                0,
+                                tx.clone(),
+                                Some(std::sync::Arc::clone(&files)),
+                            ).await;
+                            if let Err(e) = listed {
+                                log::warn!("File tree of {} is incomplete: {e}", directory_context.path);
+                            }
+                            let root = Path::new(&directory_context.path);
+                            let entries: Vec<FileTreeEntry> = std::mem::take(&mut *files.lock())
+                                .into_iter()
+                                .map(|(path, size_bytes)| FileTreeEntry {
+                                    path: path
+                                        .strip_prefix(root)
+                                        .unwrap_or(&path)
+                                        .components()
+                                        .map(|c| c.as_os_str().to_string_lossy())
+                                        .collect::<Vec<_>>()
+                                        .join("/"),
+                                    size_bytes,
+                                })
+                                .collect();
+                            let _ = tx.send(Ok(file_tree_document(&directory_context.path, &entries)));
+                        }
+                        if !output.includes_contents() {
+                            return;
+                        }
+
                         match traverse_dir(
                             directory_context.path.clone(),
                             directory_context.recursive,
@@ -334,19 +414,25 @@ impl CandleContext<CandleDirectory> {
                             directory_context.max_depth,
                             0,
                             tx.clone(),
+                            None,
                         ).await {
                             Ok(()) => {
                                 // Documents are sent directly by traverse_dir
                             }
                             Err(e) => {
-                                log::error!("Streaming error in {}: {:?}", "Directory traversal failed", CandleContextError::ContextNotFound(format!(
-                                        "Directory traversal error: {e}"
-                                    )));
+                                let error = CandleContextError::ContextNotFound(format!(
+                                    "Directory traversal error in {}: {e}",
+                                    directory_context.path
+                                ));
+                                log::error!("Streaming error in {}: {:?}", "Directory traversal failed", error);
+                                let _ = tx.send(Err(error));
                             }
                         }
                     }
                     _ => {
-                        log::error!("Streaming error in {}: {:?}", "Invalid context type for directory loading", CandleContextError::ContextNotFound("Invalid context type".to_string()));
+                        let error = CandleContextError::ContextNotFound("Invalid context type".to_string());
+                        log::error!("Streaming error in {}: {:?}", "Invalid context type for directory loading", error);
+                        let _ = tx.send(Err(error));
                     }
                 }
             }).await;
@@ -408,6 +494,7 @@ impl CandleContext<CandleGithub> {
     }
 
     /// Build authenticated URL by embedding token if provided
+    #[cfg(feature = "git")]
     fn build_auth_url(repo_url: &str, auth_token: Option<&String>) -> String {
         if let Some(token) = auth_token {
             // Inject token into HTTPS URL: https://github.com -> https://TOKEN@github.com
@@ -423,6 +510,7 @@ impl CandleContext<CandleGithub> {
     }
 
     /// Clone or update a git repository
+    #[cfg(feature = "git")]
     async fn get_or_clone_repo(
         repo_url: &str,
         branch: &str,
@@ -444,7 +532,19 @@ impl CandleContext<CandleGithub> {
         }
     }
 
+    /// Without the `git` feature repositories cannot be cloned
+    #[cfg(not(feature = "git"))]
+    async fn get_or_clone_repo(
+        _repo_url: &str,
+        _branch: &str,
+        _auth_token: Option<&String>,
+        _cache_dir: &Path,
+    ) -> Result<PathBuf, String> {
+        Err("built without the `git` feature".to_string())
+    }
+
     /// Update existing repository
+    #[cfg(feature = "git")]
     async fn update_repo(
         repo_path: &Path,
         branch: &str,
@@ -473,6 +573,7 @@ impl CandleContext<CandleGithub> {
     }
 
     /// Clone fresh repository
+    #[cfg(feature = "git")]
     async fn clone_repo(
         repo_url: &str,
         branch: &str,
@@ -497,21 +598,23 @@ impl CandleContext<CandleGithub> {
         Ok(repo_path.to_path_buf())
     }
 
-    /// Load documents asynchronously with streaming - returns unwrapped values
+    /// Load documents asynchronously with streaming - failures arrive as `Err` items
     #[inline]
-    pub fn load(self) -> Pin<Box<dyn Stream<Item = Document> + Send>> {
+    pub fn load(self) -> CandleContextStream {
         Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
             match self.source {
                 CandleContextSourceType::Github(github_context) => {
                     // Validate repository URL
                     if github_context.repository_url.is_empty() {
+                        let error = CandleContextError::ContextNotFound(
+                            "GitHub repository URL is required".to_string(),
+                        );
                         log::error!(
                             "Streaming error in {}: {:?}",
                             "GitHub repository URL missing",
-                            CandleContextError::ContextNotFound(
-                                "GitHub repository URL is required".to_string()
-                            )
+                            error
                         );
+                        let _ = tx.send(Err(error));
                         return;
                     }
 
@@ -535,57 +638,78 @@ impl CandleContext<CandleGithub> {
                             // Match files using glob pattern
                             match glob::glob(&glob_pattern) {
                                 Ok(paths) => {
-                                    for entry in paths.flatten() {
+                                    for entry in paths {
+                                        let entry = match entry {
+                                            Ok(entry) => entry,
+                                            Err(e) => {
+                                                let _ = tx.send(Err(CandleContextError::IoError(
+                                                    e.to_string(),
+                                                )));
+                                                continue;
+                                            }
+                                        };
+                                        let relative_path = entry
+                                            .strip_prefix(&repo_path)
+                                            .unwrap_or(&entry)
+                                            .to_string_lossy()
+                                            .to_string();
+
                                         // Read file content
-                                        if let Ok(content) = tokio::fs::read_to_string(&entry).await
-                                        {
-                                            let relative_path = entry
-                                                .strip_prefix(&repo_path)
-                                                .unwrap_or(&entry)
-                                                .to_string_lossy()
-                                                .to_string();
-
-                                            let document = Self::create_github_document(
-                                                content,
-                                                relative_path,
-                                                github_context.repository_url.clone(),
-                                                github_context.branch.clone(),
-                                            );
-
-                                            let _ = tx.send(document);
+                                        match tokio::fs::read_to_string(&entry).await {
+                                            Ok(content) => {
+                                                let document = Self::create_github_document(
+                                                    content,
+                                                    relative_path,
+                                                    github_context.repository_url.clone(),
+                                                    github_context.branch.clone(),
+                                                );
+
+                                                let _ = tx.send(Ok(document));
+                                            }
+                                            Err(e) => {
+                                                let _ = tx.send(Err(CandleContextError::IoError(
+                                                    format!("Failed to read {relative_path}: {e}"),
+                                                )));
+                                            }
                                         }
                                     }
                                 }
                                 Err(e) => {
+                                    let error = CandleContextError::PatternError(format!(
+                                        "Glob pattern error for '{}': {}",
+                                        github_context.pattern, e
+                                    ));
                                     log::error!(
                                         "Streaming error in {}: {:?}",
                                         "Glob pattern expansion failed",
-                                        CandleContextError::PatternError(format!(
-                                            "Glob pattern error for '{}': {}",
-                                            github_context.pattern, e
-                                        ))
+                                        error
                                     );
+                                    let _ = tx.send(Err(error));
                                 }
                             }
                         }
                         Err(e) => {
+                            let error = CandleContextError::ProviderUnavailable(format!(
+                                "Failed to clone/update repository '{}': {}",
+                                github_context.repository_url, e
+                            ));
                             log::error!(
                                 "Streaming error in {}: {:?}",
                                 "GitHub repository access failed",
-                                CandleContextError::ProviderUnavailable(format!(
-                                    "Failed to clone/update repository '{}': {}",
-                                    github_context.repository_url, e
-                                ))
+                                error
                             );
+                            let _ = tx.send(Err(error));
                         }
                     }
                 }
                 _ => {
+                    let error = CandleContextError::ContextNotFound("Invalid context type".to_string());
                     log::error!(
                         "Streaming error in {}: {:?}",
                         "Invalid context type for GitHub loading",
-                        CandleContextError::ContextNotFound("Invalid context type".to_string())
+                        error
                     );
+                    let _ = tx.send(Err(error));
                 }
             }
         }))
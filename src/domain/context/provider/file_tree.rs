@@ -0,0 +1,151 @@
+//! File-tree summaries for directory contexts
+//!
+//! Loading every file of a large repository floods the context window. A
+//! directory context can instead (or first) emit one compact document listing
+//! each file's path, size and language, so an agent reasons about the layout
+//! and then reads the files it needs through tools.
+//!
+//! ```ignore
+//! let tree = CandleContext::<CandleDirectory>::of("/repo").file_tree_only();
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use uuid::Uuid;
+
+use super::context_impl::{CandleContext, CandleContextSourceType};
+use super::types::{CandleDirectory, CandleDirectoryOutput};
+use crate::domain::context::CandleDocument as Document;
+use crate::memory::core::chunking::CodeLanguage;
+
+/// One file of a directory tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTreeEntry {
+    /// Path relative to the directory root, `/`-separated
+    pub path: String,
+    /// File size in bytes
+    pub size_bytes: u64,
+}
+
+impl FileTreeEntry {
+    /// Source language of the file, if it is a recognized source file
+    pub fn language(&self) -> Option<&'static str> {
+        CodeLanguage::from_path(&self.path).map(CodeLanguage::name)
+    }
+}
+
+/// Human-readable byte count
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f64 / (1 << 10) as f64),
+        b => format!("{b} B"),
+    }
+}
+
+/// Render a file tree: a totals line, then one indented line per file
+///
+/// Files are grouped under their directories, sorted by path.
+pub fn render_file_tree(root: &str, entries: &[FileTreeEntry]) -> String {
+    let total_bytes: u64 = entries.iter().map(|e| e.size_bytes).sum();
+    let mut languages: BTreeMap<&str, usize> = BTreeMap::new();
+    for language in entries.iter().filter_map(FileTreeEntry::language) {
+        *languages.entry(language).or_default() += 1;
+    }
+
+    let mut out = format!(
+        "File tree of {} ({} files, {})\n",
+        root,
+        entries.len(),
+        format_size(total_bytes)
+    );
+    if !languages.is_empty() {
+        let summary: Vec<String> = languages
+            .iter()
+            .map(|(language, count)| format!("{language} {count}"))
+            .collect();
+        out.push_str(&format!("Languages: {}\n", summary.join(", ")));
+    }
+    out.push('\n');
+
+    let mut sorted: Vec<&FileTreeEntry> = entries.iter().collect();
+    sorted.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut open_dirs: Vec<&str> = Vec::new();
+    for entry in sorted {
+        let mut parts: Vec<&str> = entry.path.split('/').collect();
+        let name = parts.pop().unwrap_or_default();
+
+        // Close directories this file is not in, then open its own
+        let shared = open_dirs
+            .iter()
+            .zip(&parts)
+            .take_while(|(open, part)| open == part)
+            .count();
+        open_dirs.truncate(shared);
+        for part in &parts[shared..] {
+            out.push_str(&format!("{}{}/\n", "  ".repeat(open_dirs.len()), part));
+            open_dirs.push(*part);
+        }
+
+        out.push_str(&format!(
+            "{}{} ({}",
+            "  ".repeat(open_dirs.len()),
+            name,
+            format_size(entry.size_bytes)
+        ));
+        if let Some(language) = entry.language() {
+            out.push_str(&format!(", {language}"));
+        }
+        out.push_str(")\n");
+    }
+    out
+}
+
+/// Document holding the rendered tree of `root`
+pub fn file_tree_document(root: &str, entries: &[FileTreeEntry]) -> Document {
+    let mut props = HashMap::new();
+    props.insert(
+        "id".to_string(),
+        serde_json::Value::String(Uuid::new_v4().to_string()),
+    );
+    props.insert(
+        "path".to_string(),
+        serde_json::Value::String(root.to_string()),
+    );
+    props.insert(
+        "kind".to_string(),
+        serde_json::Value::String("file_tree".to_string()),
+    );
+    props.insert("file_count".to_string(), serde_json::json!(entries.len()));
+
+    Document {
+        data: render_file_tree(root, entries),
+        format: Some(crate::domain::context::CandleContentFormat::Text),
+        media_type: Some(crate::domain::context::CandleDocumentMediaType::TXT),
+        additional_props: props,
+    }
+}
+
+impl CandleContext<CandleDirectory> {
+    /// Emit a file-tree summary document before the file contents
+    #[must_use]
+    pub fn with_file_tree(self) -> Self {
+        self.directory_output(CandleDirectoryOutput::TreeAndContents)
+    }
+
+    /// Emit only the file-tree summary, without reading any file
+    #[must_use]
+    pub fn file_tree_only(self) -> Self {
+        self.directory_output(CandleDirectoryOutput::TreeOnly)
+    }
+
+    /// Choose which documents the directory emits
+    #[must_use]
+    pub fn directory_output(mut self, output: CandleDirectoryOutput) -> Self {
+        if let CandleContextSourceType::Directory(directory_context) = &mut self.source {
+            directory_context.output = output;
+        }
+        self
+    }
+}
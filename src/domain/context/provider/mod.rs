@@ -8,10 +8,13 @@
 //! parallel processing, real-time event streaming, comprehensive error handling.
 
 pub mod context_impl;
+pub mod file_tree;
 pub mod processor;
+pub mod sql;
 pub mod types;
 
 // Re-export all public types to maintain API compatibility
 pub use context_impl::*;
+pub use file_tree::{FileTreeEntry, file_tree_document, render_file_tree};
 pub use processor::*;
 pub use types::*;
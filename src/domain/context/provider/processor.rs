@@ -15,10 +15,10 @@ use tokio_stream::Stream;
 use uuid::Uuid;
 
 use super::types::{
-    CandleContextError, CandleContextEvent, CandleImmutableFileContext, CandleValidationError,
+    CandleContextError, CandleContextEvent, CandleContextStream, CandleImmutableFileContext,
+    CandleValidationError,
 };
 use crate::domain::context::CandleDocument as Document;
-use cyrup_sugars::prelude::MessageChunk;
 
 /// Streaming context processor with atomic state tracking for Candle
 pub struct CandleStreamingContextProcessor {
@@ -137,7 +137,7 @@ impl CandleStreamingContextProcessor {
     pub fn process_file_context(
         &self,
         context: CandleImmutableFileContext,
-    ) -> Pin<Box<dyn Stream<Item = Document> + Send>> {
+    ) -> CandleContextStream {
         let _processor_id = self.processor_id.clone();
         let event_sender = self.event_sender.clone();
 
@@ -167,24 +167,34 @@ impl CandleStreamingContextProcessor {
                 }
 
                 log::error!("File context validation failed: {error}");
+                let _ = tx.send(Err(error));
                 return;
             }
 
             // Process file context
-            let document = Self::load_file_document(&context).await;
+            let result = Self::load_file_document(&context).await;
             let duration = start_time.elapsed().unwrap_or(Duration::ZERO);
-            let _ = tx.send(document);
 
-            // Emit context load completed event
+            // Emit context load completed (or failed) event
             if let Some(ref events) = event_sender {
-                let _ = events.send(CandleContextEvent::ContextLoadCompleted {
-                    context_type: "File".to_string(),
-                    source: context.path.clone(),
-                    documents_loaded: 1,
-                    duration,
-                    timestamp: SystemTime::now(),
-                });
+                let event = match &result {
+                    Ok(_) => CandleContextEvent::ContextLoadCompleted {
+                        context_type: "File".to_string(),
+                        source: context.path.clone(),
+                        documents_loaded: 1,
+                        duration,
+                        timestamp: SystemTime::now(),
+                    },
+                    Err(e) => CandleContextEvent::ContextLoadFailed {
+                        context_type: "File".to_string(),
+                        source: context.path.clone(),
+                        error: e.to_string(),
+                        timestamp: SystemTime::now(),
+                    },
+                };
+                let _ = events.send(event);
             }
+            let _ = tx.send(result);
         }))
     }
 
@@ -212,7 +222,9 @@ impl CandleStreamingContextProcessor {
     /// Load file document with production-quality file reading
     #[inline]
     #[allow(clippy::too_many_lines)]
-    pub(crate) async fn load_file_document(context: &CandleImmutableFileContext) -> Document {
+    pub(crate) async fn load_file_document(
+        context: &CandleImmutableFileContext,
+    ) -> Result<Document, CandleContextError> {
         const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024; // 100MB default
         const LARGE_FILE_WARNING_THRESHOLD: u64 = 10 * 1024 * 1024; // 10MB
 
@@ -226,13 +238,13 @@ impl CandleStreamingContextProcessor {
                         "File context validation failed: Path is not a file: {}",
                         context.path
                     );
-                    return Document::bad_chunk(format!("Path is not a file: {}", context.path));
+                    return Err(CandleContextError::InvalidPath(format!("Path is not a file: {}", context.path)));
                 }
                 meta
             }
             Err(e) => {
                 log::error!("Failed to read file metadata: {e}");
-                return Document::bad_chunk(format!("Failed to access file: {e}"));
+                return Err(CandleContextError::ContextNotFound(format!("Failed to access {}: {e}", context.path)));
             }
         };
 
@@ -243,11 +255,12 @@ impl CandleStreamingContextProcessor {
                 metadata.len(),
                 MAX_FILE_SIZE
             );
-            return Document::bad_chunk(format!(
-                "File too large: {} bytes (max {} bytes)",
+            return Err(CandleContextError::ValidationError(format!(
+                "File too large: {} ({} bytes, max {} bytes)",
+                context.path,
                 metadata.len(),
                 MAX_FILE_SIZE
-            ));
+            )));
         }
 
         // Warn for large files
@@ -321,7 +334,7 @@ impl CandleStreamingContextProcessor {
                     Ok(bytes) => general_purpose::STANDARD.encode(&bytes),
                     Err(e) => {
                         log::error!("Failed to read binary file: {e}");
-                        return Document::bad_chunk(format!("Failed to read file: {e}"));
+                        return Err(CandleContextError::IoError(format!("Failed to read {}: {e}", context.path)));
                     }
                 }
             }
@@ -338,20 +351,21 @@ impl CandleStreamingContextProcessor {
                                     "File is not valid UTF-8, encoding as base64: {}",
                                     context.path
                                 );
-                                return Document {
+                                return Ok(Document {
                                     data: general_purpose::STANDARD.encode(&bytes),
                                     format: Some(
                                         crate::domain::context::CandleContentFormat::Base64,
                                     ),
                                     media_type: Some(media_type),
                                     additional_props: Self::build_metadata_props(context),
-                                };
+                                });
                             }
                             Err(read_err) => {
                                 log::error!("Failed to read file as text or binary: {read_err}");
-                                return Document::bad_chunk(format!(
-                                    "Failed to read file: {read_err}"
-                                ));
+                                return Err(CandleContextError::IoError(format!(
+                                    "Failed to read {}: {read_err}",
+                                    context.path
+                                )));
                             }
                         }
                     }
@@ -360,12 +374,12 @@ impl CandleStreamingContextProcessor {
         };
 
         // Create the document with actual content
-        Document {
+        Ok(Document {
             data,
             format: Some(format),
             media_type: Some(media_type),
             additional_props: Self::build_metadata_props(context),
-        }
+        })
     }
 
     /// Helper function to detect format from file extension (case-insensitive)
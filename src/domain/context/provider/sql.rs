@@ -0,0 +1,544 @@
+//! SQL database context for Candle agents
+//!
+//! `CandleContext<CandleSql>` connects to Postgres or SQLite, loads table and
+//! view definitions as documents, and runs parameterized SELECTs whose rows
+//! become documents too, so "chat with my database" agents go through the same
+//! context loading and memory pipeline as files and repositories.
+//!
+//! Connections are read-only: Postgres sessions default to read-only
+//! transactions, SQLite databases are opened with `mode=ro` and
+//! `query_only`, and every query must be a single SELECT statement.
+//!
+//! ```ignore
+//! let sql = CandleContext::<CandleSql>::database("postgres://reader@localhost/shop")
+//!     .query("SELECT id, status FROM orders WHERE placed_at > $1", [json!("2024-06-01")])
+//!     .max_rows(50);
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use sqlx::AnyConnection;
+use sqlx::any::AnyRow;
+use sqlx::{Column, Connection, Executor, Row};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use super::context_impl::{CandleContext, CandleContextSourceType};
+use super::types::{
+    CandleContextError, CandleContextStream, CandleImmutableSqlContext, CandleProviderError,
+    CandleSql, CandleSqlQuery,
+};
+use crate::domain::context::CandleDocument as Document;
+
+/// Rows rendered per query unless `max_rows` is set
+pub const DEFAULT_SQL_MAX_ROWS: usize = 100;
+
+/// Statements a read-only query may not contain, even inside a CTE
+const WRITE_KEYWORDS: &[&str] = &[
+    "insert", "update", "delete", "merge", "upsert", "replace", "drop", "alter", "create",
+    "truncate", "grant", "revoke", "attach", "detach", "pragma", "copy", "vacuum", "reindex",
+    "call", "do", "lock", "set",
+];
+
+/// Write keywords that are also read-only functions, e.g. `replace(name, 'a', 'b')`
+const SQL_FUNCTIONS: &[&str] = &["replace"];
+
+/// Database engines the SQL context can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlBackend {
+    Postgres,
+    Sqlite,
+}
+
+impl SqlBackend {
+    /// Backend for a connection URL
+    pub fn from_url(url: &str) -> Option<Self> {
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            Some(Self::Postgres)
+        } else if url.starts_with("sqlite:") {
+            Some(Self::Sqlite)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reject anything but a single SELECT (or WITH ... SELECT) statement
+///
+/// The database connection is read-only as well; this check gives a clear
+/// error before a write is sent at all.
+///
+/// # Errors
+///
+/// Returns `CandleProviderError::SqlProvider` naming the problem.
+pub fn validate_read_only(sql: &str) -> Result<(), CandleProviderError> {
+    let code = strip_literals(sql);
+    let code = code.trim().trim_end_matches(';').trim_end();
+    if code.contains(';') {
+        return Err(CandleProviderError::SqlProvider(
+            "only one statement may be run per query".to_string(),
+        ));
+    }
+
+    let first = code
+        .split(|c: char| !is_word_char(c))
+        .find(|word| !word.is_empty())
+        .map(str::to_lowercase);
+    match first.as_deref() {
+        Some("select" | "with") => {}
+        Some(first) => {
+            return Err(CandleProviderError::SqlProvider(format!(
+                "only SELECT queries are allowed, found '{}'",
+                first.to_uppercase()
+            )));
+        }
+        None => {
+            return Err(CandleProviderError::SqlProvider("query is empty".to_string()));
+        }
+    }
+    if let Some(keyword) = find_write_keyword(code) {
+        return Err(CandleProviderError::SqlProvider(format!(
+            "query contains '{}', which is not allowed in a read-only query",
+            keyword.to_uppercase()
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `c` can be part of an SQL word
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// First write keyword in `code` that is used as a keyword
+///
+/// Only whole words match, so `created_at` or `offset` never count. A word
+/// qualified by a dot (`t.set`) or a call of one of [`SQL_FUNCTIONS`] is an
+/// identifier, not a statement.
+fn find_write_keyword(code: &str) -> Option<String> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_word_char(chars[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_word_char(chars[i]) {
+            i += 1;
+        }
+        let word: String = chars[start..i].iter().collect::<String>().to_lowercase();
+        if !WRITE_KEYWORDS.contains(&word.as_str()) {
+            continue;
+        }
+        let before = chars[..start].iter().rev().find(|c| !c.is_whitespace());
+        let after = chars[i..].iter().find(|c| !c.is_whitespace());
+        let qualified = before == Some(&'.') || after == Some(&'.');
+        let call = after == Some(&'(') && SQL_FUNCTIONS.contains(&word.as_str());
+        if !qualified && !call {
+            return Some(word);
+        }
+    }
+    None
+}
+
+/// SQL with string literals, quoted identifiers and comments blanked out
+fn strip_literals(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                for next in chars.by_ref() {
+                    if next == c {
+                        break;
+                    }
+                }
+                out.push(' ');
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+                out.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Open a connection that cannot write
+///
+/// SQLite URLs are opened with `mode=ro` unless they set a mode, and every
+/// connection runs with writes disabled (`query_only` on SQLite, a read-only
+/// session on Postgres).
+///
+/// # Errors
+///
+/// Returns `CandleProviderError::SqlProvider` for an unsupported URL or a
+/// failed connection.
+pub async fn connect_read_only(url: &str) -> Result<AnyConnection, CandleProviderError> {
+    sqlx::any::install_default_drivers();
+    let backend = SqlBackend::from_url(url).ok_or_else(|| {
+        CandleProviderError::SqlProvider(format!(
+            "unsupported database URL '{}' (expected postgres:// or sqlite:)",
+            redact_url(url)
+        ))
+    })?;
+
+    let url = match backend {
+        SqlBackend::Sqlite if !url.contains("mode=") => {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!("{url}{separator}mode=ro")
+        }
+        _ => url.to_string(),
+    };
+    let mut conn = AnyConnection::connect(&url)
+        .await
+        .map_err(|e| CandleProviderError::SqlProvider(format!("connection failed: {e}")))?;
+
+    let guard = match backend {
+        SqlBackend::Postgres => "SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY",
+        SqlBackend::Sqlite => "PRAGMA query_only = ON",
+    };
+    conn.execute(guard)
+        .await
+        .map_err(|e| CandleProviderError::SqlProvider(format!("could not make connection read-only: {e}")))?;
+    Ok(conn)
+}
+
+/// Connection URL with any password replaced by `***`
+pub fn redact_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let Some((credentials, host)) = rest.split_once('@') else {
+        return url.to_string();
+    };
+    match credentials.split_once(':') {
+        Some((user, _)) => format!("{scheme}://{user}:***@{host}"),
+        None => url.to_string(),
+    }
+}
+
+/// Column of a table, as read from `information_schema`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlColumn {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+}
+
+/// `CREATE TABLE` statement for a table's columns
+pub fn table_ddl(table: &str, columns: &[SqlColumn]) -> String {
+    let body = columns
+        .iter()
+        .map(|column| {
+            let mut line = format!("    {} {}", column.name, column.data_type);
+            if !column.nullable {
+                line.push_str(" NOT NULL");
+            }
+            if let Some(default) = &column.default {
+                line.push_str(" DEFAULT ");
+                line.push_str(default);
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!("CREATE TABLE {table} (\n{body}\n);")
+}
+
+/// Markdown table of query results
+///
+/// `total` is the number of rows the query returned; when it exceeds the
+/// rows given, a note says how many were left out.
+pub fn render_rows(columns: &[String], rows: &[Vec<String>], total: usize) -> String {
+    let escape = |cell: &str| cell.replace('|', "\\|").replace('\n', " ");
+    let mut out = format!(
+        "| {} |\n|{}|\n",
+        columns.iter().map(|c| escape(c)).collect::<Vec<_>>().join(" | "),
+        vec![" --- "; columns.len()].join("|")
+    );
+    for row in rows {
+        out.push_str("| ");
+        out.push_str(&row.iter().map(|c| escape(c)).collect::<Vec<_>>().join(" | "));
+        out.push_str(" |\n");
+    }
+    if total > rows.len() {
+        out.push_str(&format!("\n({} of {} rows shown)\n", rows.len(), total));
+    }
+    out
+}
+
+impl CandleContext<CandleSql> {
+    /// Read a database - EXACT syntax: `CandleContext<CandleSql>::database("postgres://user@host/db")`
+    #[inline]
+    pub fn database(url: impl Into<String>) -> Self {
+        let sql_context = CandleImmutableSqlContext {
+            url: url.into(),
+            include_schema: true,
+            queries: Vec::new(),
+            max_rows: DEFAULT_SQL_MAX_ROWS,
+            memory_integration: None,
+        };
+        Self::new(CandleContextSourceType::Sql(sql_context))
+    }
+
+    /// Load the rows of a SELECT as a document - EXACT syntax: `.query("SELECT ... WHERE id = $1", [json!(7)])`
+    #[must_use]
+    pub fn query<P>(mut self, sql: impl Into<String>, params: P) -> Self
+    where
+        P: IntoIterator<Item = serde_json::Value>,
+    {
+        if let CandleContextSourceType::Sql(sql_context) = &mut self.source {
+            sql_context.queries.push(CandleSqlQuery {
+                sql: sql.into(),
+                params: params.into_iter().collect(),
+            });
+        }
+        self
+    }
+
+    /// Skip loading table and view definitions
+    #[must_use]
+    pub fn without_schema(mut self) -> Self {
+        if let CandleContextSourceType::Sql(sql_context) = &mut self.source {
+            sql_context.include_schema = false;
+        }
+        self
+    }
+
+    /// Render at most `max_rows` rows per query
+    #[must_use]
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        if let CandleContextSourceType::Sql(sql_context) = &mut self.source {
+            sql_context.max_rows = max_rows.max(1);
+        }
+        self
+    }
+
+    /// Load documents asynchronously with streaming - failures arrive as `Err` items
+    #[inline]
+    pub fn load(self) -> CandleContextStream {
+        Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
+            let CandleContextSourceType::Sql(sql_context) = self.source else {
+                log::error!("Invalid context type for SQL loading");
+                let _ = tx.send(Err(CandleContextError::ContextNotFound(
+                    "Invalid context type".to_string(),
+                )));
+                return;
+            };
+            let database = redact_url(&sql_context.url);
+
+            let mut conn = match connect_read_only(&sql_context.url).await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    log::error!("Streaming error in SQL context '{database}': {e}");
+                    let _ = tx.send(Err(CandleContextError::ProviderUnavailable(e.to_string())));
+                    return;
+                }
+            };
+
+            if sql_context.include_schema {
+                let backend = SqlBackend::from_url(&sql_context.url);
+                match Self::load_schema(&mut conn, backend).await {
+                    Ok(objects) => {
+                        for (name, ddl) in objects {
+                            let _ = tx.send(Ok(Self::create_sql_document(ddl, &database, "schema", &name, None)));
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Streaming error in SQL context '{database}': {e}");
+                        let _ = tx.send(Err(CandleContextError::ProviderUnavailable(e.to_string())));
+                    }
+                }
+            }
+
+            for (index, query) in sql_context.queries.iter().enumerate() {
+                match Self::run_query(&mut conn, query, sql_context.max_rows).await {
+                    Ok((text, rows)) => {
+                        let name = format!("query{}", index + 1);
+                        let _ = tx.send(Ok(Self::create_sql_document(text, &database, "query", &name, Some((query, rows)))));
+                    }
+                    Err(e) => {
+                        log::error!("Streaming error in SQL context '{database}': {e}");
+                        let _ = tx.send(Err(CandleContextError::ProviderUnavailable(e.to_string())));
+                    }
+                }
+            }
+
+            let _ = conn.close().await;
+        }))
+    }
+
+    /// Table and view definitions as `(name, ddl)` pairs
+    async fn load_schema(
+        conn: &mut AnyConnection,
+        backend: Option<SqlBackend>,
+    ) -> Result<Vec<(String, String)>, CandleProviderError> {
+        let map_err = |e: sqlx::Error| CandleProviderError::SqlProvider(format!("schema query failed: {e}"));
+        match backend {
+            Some(SqlBackend::Sqlite) => {
+                let rows = sqlx::query(
+                    "SELECT name, sql FROM sqlite_master \
+                     WHERE type IN ('table', 'view', 'index') AND sql IS NOT NULL \
+                     AND name NOT LIKE 'sqlite_%' ORDER BY name",
+                )
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(map_err)?;
+                Ok(rows
+                    .iter()
+                    .filter_map(|row| {
+                        let name: String = row.try_get(0).ok()?;
+                        let sql: String = row.try_get(1).ok()?;
+                        Some((name, format!("{sql};")))
+                    })
+                    .collect())
+            }
+            Some(SqlBackend::Postgres) => {
+                let rows = sqlx::query(
+                    "SELECT table_schema || '.' || table_name, column_name, data_type, \
+                     is_nullable, column_default FROM information_schema.columns \
+                     WHERE table_schema NOT IN ('pg_catalog', 'information_schema') \
+                     ORDER BY table_schema, table_name, ordinal_position",
+                )
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(map_err)?;
+
+                let mut tables: BTreeMap<String, Vec<SqlColumn>> = BTreeMap::new();
+                for row in &rows {
+                    let (Ok(table), Ok(name), Ok(data_type)) = (
+                        row.try_get::<String, _>(0),
+                        row.try_get::<String, _>(1),
+                        row.try_get::<String, _>(2),
+                    ) else {
+                        continue;
+                    };
+                    tables.entry(table).or_default().push(SqlColumn {
+                        name,
+                        data_type,
+                        nullable: row.try_get::<String, _>(3).is_ok_and(|v| v == "YES"),
+                        default: row.try_get::<Option<String>, _>(4).ok().flatten(),
+                    });
+                }
+                Ok(tables
+                    .into_iter()
+                    .map(|(table, columns)| {
+                        let ddl = table_ddl(&table, &columns);
+                        (table, ddl)
+                    })
+                    .collect())
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Run one query, returning its rendered rows and how many were rendered
+    async fn run_query(
+        conn: &mut AnyConnection,
+        query: &CandleSqlQuery,
+        max_rows: usize,
+    ) -> Result<(String, usize), CandleProviderError> {
+        validate_read_only(&query.sql)?;
+
+        let mut statement = sqlx::query(&query.sql);
+        for param in &query.params {
+            statement = match param {
+                serde_json::Value::Null => statement.bind(None::<String>),
+                serde_json::Value::Bool(b) => statement.bind(*b),
+                serde_json::Value::Number(n) => match n.as_i64() {
+                    Some(i) => statement.bind(i),
+                    None => statement.bind(n.as_f64().unwrap_or_default()),
+                },
+                serde_json::Value::String(s) => statement.bind(s.clone()),
+                other => statement.bind(other.to_string()),
+            };
+        }
+
+        let mut columns: Vec<String> = Vec::new();
+        let mut rows = Vec::new();
+        let mut total = 0;
+        let mut stream = statement.fetch(&mut *conn);
+        while let Some(row) = stream.next().await {
+            let row = row.map_err(|e| CandleProviderError::SqlProvider(format!("query failed: {e}")))?;
+            if columns.is_empty() {
+                columns = row.columns().iter().map(|c| c.name().to_string()).collect();
+            }
+            total += 1;
+            if rows.len() < max_rows {
+                rows.push(Self::row_cells(&row));
+            }
+        }
+
+        Ok((render_rows(&columns, &rows, total), rows.len()))
+    }
+
+    /// Row values as display strings
+    fn row_cells(row: &AnyRow) -> Vec<String> {
+        (0..row.len())
+            .map(|i| {
+                if let Ok(value) = row.try_get::<Option<String>, _>(i) {
+                    value.unwrap_or_else(|| "NULL".to_string())
+                } else if let Ok(value) = row.try_get::<Option<i64>, _>(i) {
+                    value.map_or_else(|| "NULL".to_string(), |v| v.to_string())
+                } else if let Ok(value) = row.try_get::<Option<f64>, _>(i) {
+                    value.map_or_else(|| "NULL".to_string(), |v| v.to_string())
+                } else if let Ok(value) = row.try_get::<Option<bool>, _>(i) {
+                    value.map_or_else(|| "NULL".to_string(), |v| v.to_string())
+                } else {
+                    "?".to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Create document with SQL provenance metadata
+    fn create_sql_document(
+        data: String,
+        database: &str,
+        kind: &str,
+        name: &str,
+        query: Option<(&CandleSqlQuery, usize)>,
+    ) -> Document {
+        let mut props = HashMap::new();
+        props.insert(
+            "id".to_string(),
+            serde_json::Value::String(Uuid::new_v4().to_string()),
+        );
+        props.insert(
+            "source".to_string(),
+            serde_json::Value::String(format!("{database}#{name}")),
+        );
+        props.insert("database".to_string(), serde_json::Value::String(database.to_string()));
+        props.insert("sql_kind".to_string(), serde_json::Value::String(kind.to_string()));
+        if let Some((query, rows)) = query {
+            props.insert("query".to_string(), serde_json::Value::String(query.sql.clone()));
+            props.insert("row_count".to_string(), serde_json::Value::from(rows));
+        }
+
+        Document {
+            data,
+            format: Some(crate::domain::context::CandleContentFormat::Text),
+            media_type: Some(crate::domain::context::CandleDocumentMediaType::TXT),
+            additional_props: props,
+        }
+    }
+}
@@ -25,6 +25,9 @@ pub struct CandleDirectory;
 /// Marker type for GitHub repository Candle context integration. Enables GitHub API integration with rate limiting and authentication.
 #[derive(Debug, Clone)]
 pub struct CandleGithub;
+/// Marker type for SQL database Candle context. Loads schema DDL and read-only query results from Postgres or SQLite.
+#[derive(Debug, Clone)]
+pub struct CandleSql;
 
 /// Comprehensive error types for Candle context operations with zero allocations
 #[derive(Error, Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +70,9 @@ pub enum CandleProviderError {
     #[error("GitHub provider error: {0}")]
     /// GitHub API provider specific error. Handles authentication, rate limiting, and repository access issues.
     GithubProvider(String),
+    #[error("SQL provider error: {0}")]
+    /// SQL database provider specific error. Handles connection failures, rejected statements, and query errors.
+    SqlProvider(String),
     #[error("Embedding provider error: {0}")]
     /// Vector embedding provider error. Manages embedding generation, storage, and retrieval failures.
     EmbeddingProvider(String),
@@ -89,6 +95,74 @@ pub enum CandleValidationError {
     SizeLimitExceeded(String),
 }
 
+/// Stream of documents loaded from a Candle context source
+///
+/// A file that cannot be read, a failed clone or a rejected query yields an
+/// `Err` in place of its document, so callers can report partial loads.
+pub type CandleContextStream = Pin<
+    Box<dyn Stream<Item = Result<crate::domain::context::CandleDocument, CandleContextError>> + Send>,
+>;
+
+/// Outcome of loading one Candle context source
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CandleContextLoadReport {
+    /// Source label, e.g. `context_github`
+    pub source: String,
+    /// Documents loaded
+    pub loaded: usize,
+    /// Errors for everything that failed to load
+    pub failures: Vec<CandleContextError>,
+}
+
+impl CandleContextLoadReport {
+    /// Empty report for `source`
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Count one stream item, returning the document if it loaded
+    pub fn record<D>(&mut self, item: Result<D, CandleContextError>) -> Option<D> {
+        match item {
+            Ok(document) => {
+                self.loaded += 1;
+                Some(document)
+            }
+            Err(e) => {
+                self.failures.push(e);
+                None
+            }
+        }
+    }
+
+    /// Whether anything failed to load
+    pub fn has_failures(&self) -> bool {
+        !self.failures.is_empty()
+    }
+
+    /// One line per failure, prefixed with the source
+    pub fn warnings(&self) -> Vec<String> {
+        self.failures
+            .iter()
+            .map(|e| format!("{}: {e}", self.source))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for CandleContextLoadReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} of {} loaded",
+            self.source,
+            self.loaded,
+            self.loaded + self.failures.len()
+        )
+    }
+}
+
 /// Candle context events for real-time streaming monitoring
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CandleContextEvent {
@@ -268,10 +342,36 @@ pub struct CandleImmutableDirectoryContext {
     pub extensions: Vec<String>,
     /// Maximum depth for traversal
     pub max_depth: Option<usize>,
+    /// Whether to load file contents, a file-tree summary, or both
+    pub output: CandleDirectoryOutput,
     /// Memory integration layer
     pub memory_integration: Option<CandleMemoryIntegration>,
 }
 
+/// Documents a directory context emits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CandleDirectoryOutput {
+    /// One document per file
+    #[default]
+    Contents,
+    /// A file-tree summary document, then one document per file
+    TreeAndContents,
+    /// Only the file-tree summary document
+    TreeOnly,
+}
+
+impl CandleDirectoryOutput {
+    /// Whether a file-tree summary is emitted
+    pub fn includes_tree(self) -> bool {
+        matches!(self, Self::TreeAndContents | Self::TreeOnly)
+    }
+
+    /// Whether file contents are loaded
+    pub fn includes_contents(self) -> bool {
+        matches!(self, Self::Contents | Self::TreeAndContents)
+    }
+}
+
 /// Immutable GitHub context with owned strings for Candle
 #[derive(Debug, Clone)]
 pub struct CandleImmutableGithubContext {
@@ -287,6 +387,30 @@ pub struct CandleImmutableGithubContext {
     pub memory_integration: Option<CandleMemoryIntegration>,
 }
 
+/// Parameterized read-only query whose result rows become a context document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandleSqlQuery {
+    /// SELECT (or WITH ... SELECT) statement with positional placeholders (`$1` or `?`)
+    pub sql: String,
+    /// Values bound to the placeholders in order
+    pub params: Vec<serde_json::Value>,
+}
+
+/// Immutable SQL database context with owned strings for Candle
+#[derive(Debug, Clone)]
+pub struct CandleImmutableSqlContext {
+    /// Connection URL (`postgres://...` or `sqlite://...`)
+    pub url: String,
+    /// Load table and view definitions as documents
+    pub include_schema: bool,
+    /// Queries whose results are loaded as documents
+    pub queries: Vec<CandleSqlQuery>,
+    /// Largest number of rows rendered per query
+    pub max_rows: usize,
+    /// Memory integration layer
+    pub memory_integration: Option<CandleMemoryIntegration>,
+}
+
 /// Candle memory integration layer with atomic operations
 #[derive(Debug)]
 pub struct CandleMemoryIntegration {
@@ -0,0 +1,22 @@
+//! Matryoshka (MRL) truncation of embeddings
+//!
+//! Models trained with Matryoshka Representation Learning front-load their
+//! embeddings: the first `n` components are themselves a usable
+//! `n`-dimensional embedding. Truncating is only half of it, though; the
+//! prefix of a unit vector is shorter than one, so it must be re-normalized
+//! before cosine or dot-product comparison.
+
+/// First `dimension` components of `embedding`, scaled back to unit length
+///
+/// Returns the embedding unchanged (but normalized) when it has no more than
+/// `dimension` components; an all-zero prefix stays all zeros.
+pub fn truncate(embedding: &[f32], dimension: usize) -> Vec<f32> {
+    let mut prefix = embedding[..dimension.min(embedding.len())].to_vec();
+    let norm = prefix.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for x in &mut prefix {
+            *x /= norm;
+        }
+    }
+    prefix
+}
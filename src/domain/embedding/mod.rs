@@ -15,6 +15,9 @@ pub mod service;
 /// Similarity computation types and traits for vector comparisons
 pub mod similarity;
 
+/// Matryoshka truncation to smaller embedding dimensions
+pub mod matryoshka;
+
 pub use config::EmbeddingConfig as EmbeddingConfiguration;
 // Re-export configuration types
 pub use config::{EmbeddingConfig, IntoEmbeddingConfig};
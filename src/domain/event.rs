@@ -0,0 +1,289 @@
+//! One event type for everything an agent run produces
+//!
+//! Chat replies stream [`CandleMessageChunk`]s, providers stream
+//! [`CandleCompletionChunk`]s, JSON extraction yields [`CandleJsonChunk`]s,
+//! workflows pass [`WorkflowDataChunk`]s between steps and memorize sessions
+//! report their status separately. Each converts into an [`AgentEvent`], so
+//! UIs, logs and webhooks can consume a single stream per run:
+//!
+//! ```ignore
+//! let mut events = agent_events(agent.chat_with_message("hi"))
+//!     .merge(agent_events(workflow.execute(input)));
+//! while let Some(event) = events.next().await {
+//!     webhook.send(serde_json::to_string(&event)?).await?;
+//! }
+//! ```
+//!
+//! Events serialize with a `type` tag (`{"type":"text","text":"Hel"}`).
+//! New variants may be added, so matches need a wildcard arm.
+
+use std::pin::Pin;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::domain::chat::citations::Citation;
+use crate::domain::chat::handoff::HandoffDocument;
+use crate::domain::chat::message::CandleMessageChunk;
+use crate::domain::chat::trace::TurnTrace;
+use crate::domain::context::chunks::{CandleCompletionChunk, CandleJsonChunk, WorkflowDataChunk};
+use crate::domain::model::CandleUsage;
+
+/// State of a background session (e.g. memorize)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    InProgress,
+    Completed,
+    Failed,
+}
+
+/// Something that happened during an agent run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum AgentEvent {
+    /// Reply text
+    Text { text: String },
+
+    /// Tool call started
+    ToolCallStart { id: String, name: String },
+
+    /// Tool call input streamed so far
+    ToolCallDelta {
+        id: String,
+        name: String,
+        partial_input: String,
+    },
+
+    /// Tool call input complete
+    ToolCallComplete {
+        id: String,
+        name: String,
+        input: String,
+    },
+
+    /// Progress reported by a running tool
+    Progress {
+        progress: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        total: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+
+    /// Reply finished
+    Complete {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        finish_reason: Option<String>,
+        /// Token usage, when the source reports it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        usage: Option<CandleUsage>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token_count: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        elapsed_secs: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tokens_per_sec: Option<f64>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        citations: Vec<Citation>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        confidence: Option<f32>,
+    },
+
+    /// What the model was shown for a turn
+    Trace(TurnTrace),
+
+    /// Conversation handed to another agent
+    Handoff(HandoffDocument),
+
+    /// Structured output
+    Json { value: Value },
+
+    /// Output of a workflow step
+    WorkflowStep {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        step: Option<String>,
+        data: Value,
+    },
+
+    /// Status of a background session
+    Session {
+        session_id: String,
+        /// What the session works on, e.g. the memory library
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        target: Option<String>,
+        state: SessionState,
+        /// Current stage, e.g. "Generating embeddings"
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stage: Option<String>,
+        /// What the session produced, e.g. the memory ID
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+
+    /// Something failed
+    Error {
+        message: String,
+        /// Workflow step or subsystem that failed, when known
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+    },
+}
+
+impl AgentEvent {
+    /// Error event without a source
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::Error {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Whether this is an error
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error { .. })
+            || matches!(
+                self,
+                Self::Session {
+                    state: SessionState::Failed,
+                    ..
+                }
+            )
+    }
+
+    /// Whether this ends a reply
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        matches!(self, Self::Complete { .. })
+    }
+}
+
+impl From<CandleMessageChunk> for AgentEvent {
+    fn from(chunk: CandleMessageChunk) -> Self {
+        match chunk {
+            CandleMessageChunk::Text(text) => Self::Text { text },
+            CandleMessageChunk::ToolCallStart { id, name } => Self::ToolCallStart { id, name },
+            CandleMessageChunk::ToolCall {
+                id,
+                name,
+                partial_input,
+            } => Self::ToolCallDelta {
+                id,
+                name,
+                partial_input,
+            },
+            CandleMessageChunk::ToolCallComplete { id, name, input } => {
+                Self::ToolCallComplete { id, name, input }
+            }
+            CandleMessageChunk::ProgressNotification {
+                progress,
+                total,
+                message,
+            } => Self::Progress {
+                progress,
+                total,
+                message,
+            },
+            // Chat chunks carry usage as display text, so only the token count survives
+            CandleMessageChunk::Complete {
+                text,
+                finish_reason,
+                usage: _,
+                token_count,
+                elapsed_secs,
+                tokens_per_sec,
+                citations,
+                confidence,
+            } => Self::Complete {
+                text,
+                finish_reason,
+                usage: None,
+                token_count,
+                elapsed_secs,
+                tokens_per_sec,
+                citations,
+                confidence,
+            },
+            CandleMessageChunk::Trace(trace) => Self::Trace(trace),
+            CandleMessageChunk::Handoff(document) => Self::Handoff(document),
+            CandleMessageChunk::Error(message) => Self::error(message),
+        }
+    }
+}
+
+impl From<CandleCompletionChunk> for AgentEvent {
+    fn from(chunk: CandleCompletionChunk) -> Self {
+        match chunk {
+            CandleCompletionChunk::Text(text) => Self::Text { text },
+            CandleCompletionChunk::ToolCallStart { id, name } => Self::ToolCallStart { id, name },
+            CandleCompletionChunk::ToolCall {
+                id,
+                name,
+                partial_input,
+            } => Self::ToolCallDelta {
+                id,
+                name,
+                partial_input,
+            },
+            CandleCompletionChunk::ToolCallComplete { id, name, input } => {
+                Self::ToolCallComplete { id, name, input }
+            }
+            CandleCompletionChunk::Complete {
+                text,
+                finish_reason,
+                usage,
+                token_count,
+                elapsed_secs,
+                tokens_per_sec,
+                mean_logprob: _,
+                flash_attention: _,
+            } => Self::Complete {
+                text,
+                finish_reason: finish_reason.map(|reason| format!("{reason:?}")),
+                usage,
+                token_count,
+                elapsed_secs,
+                tokens_per_sec,
+                citations: Vec::new(),
+                confidence: None,
+            },
+            CandleCompletionChunk::Error(message) => Self::error(message),
+        }
+    }
+}
+
+impl From<CandleJsonChunk> for AgentEvent {
+    fn from(chunk: CandleJsonChunk) -> Self {
+        Self::Json { value: chunk.0 }
+    }
+}
+
+impl From<WorkflowDataChunk> for AgentEvent {
+    fn from(chunk: WorkflowDataChunk) -> Self {
+        match chunk.error_message {
+            Some(message) => Self::Error {
+                message,
+                source: chunk.step_name,
+            },
+            None => Self::WorkflowStep {
+                step: chunk.step_name,
+                data: chunk.data,
+            },
+        }
+    }
+}
+
+/// Adapt a stream of any chunk type into [`AgentEvent`]s
+pub fn agent_events<S, T>(stream: S) -> Pin<Box<dyn Stream<Item = AgentEvent> + Send>>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Into<AgentEvent> + 'static,
+{
+    Box::pin(stream.map(Into::into))
+}
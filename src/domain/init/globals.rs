@@ -83,6 +83,7 @@ use std::sync::{Arc, LazyLock, atomic::AtomicUsize};
 
 use arc_swap::ArcSwap;
 use atomic_counter::RelaxedCounter;
+#[cfg(feature = "memory")]
 use tokio::sync::{Mutex, mpsc};
 
 use crate::domain::error::CircuitBreaker;
@@ -92,9 +93,11 @@ use crate::domain::error::CircuitBreaker;
 
 // Production memory configuration management
 use crate::domain::memory::MemoryConfig;
+#[cfg(feature = "memory")]
 use crate::memory::core::manager::surreal::SurrealDBMemoryManager;
 
 /// Type alias for memory manager connection pool channel pair
+#[cfg(feature = "memory")]
 type MemoryManagerPool = (
     mpsc::UnboundedSender<Arc<SurrealDBMemoryManager>>,
     Arc<Mutex<mpsc::UnboundedReceiver<Arc<SurrealDBMemoryManager>>>>,
@@ -105,6 +108,7 @@ pub static CONFIG_CACHE: LazyLock<ArcSwap<MemoryConfig>> =
     LazyLock::new(|| ArcSwap::new(Arc::new(create_default_config())));
 
 /// Connection pool for zero-allocation connection management
+#[cfg(feature = "memory")]
 pub static CONNECTION_POOL: LazyLock<MemoryManagerPool> = LazyLock::new(|| {
     let (sender, receiver) = mpsc::unbounded_channel();
     (sender, Arc::new(Mutex::new(receiver)))
@@ -2,16 +2,22 @@
 
 pub mod globals;
 
+#[cfg(feature = "memory")]
 use std::sync::{Arc, LazyLock};
+#[cfg(feature = "memory")]
 use tokio::sync::{Mutex, mpsc};
 
 use crate::domain::memory::MemoryConfig;
+#[cfg(feature = "memory")]
 use crate::memory::core::manager::surreal::SurrealDBMemoryManager;
+#[cfg(feature = "memory")]
 use surrealdb::engine::any;
 
+#[cfg(feature = "memory")]
 use crate::domain::core::DomainInitError;
 
 /// Type alias for memory manager connection pool channel pair
+#[cfg(feature = "memory")]
 type MemoryManagerPool = (
     mpsc::UnboundedSender<Arc<SurrealDBMemoryManager>>,
     Arc<Mutex<mpsc::UnboundedReceiver<Arc<SurrealDBMemoryManager>>>>,
@@ -28,6 +34,7 @@ type MemoryManagerPool = (
 /// - Database connection cannot be established
 /// - Database initialization fails
 /// - Memory schema initialization fails
+#[cfg(feature = "memory")]
 pub async fn initialize_memory_service() -> Result<SurrealDBMemoryManager, DomainInitError> {
     let config = get_default_memory_config();
     initialize_memory_service_with_config(config).await
@@ -50,6 +57,7 @@ pub async fn initialize_memory_service() -> Result<SurrealDBMemoryManager, Domai
 ///
 /// # Performance
 /// Production-quality `SurrealDB` connection with cognitive memory features
+#[cfg(feature = "memory")]
 pub async fn initialize_memory_service_with_config(
     config: MemoryConfig,
 ) -> Result<SurrealDBMemoryManager, DomainInitError> {
@@ -88,6 +96,7 @@ pub fn get_default_memory_config() -> MemoryConfig {
 /// Memory service connection pool for efficient resource management
 ///
 /// Uses Arc<SurrealDBMemoryManager> for shared ownership across threads
+#[cfg(feature = "memory")]
 static MEMORY_SERVICE_POOL: LazyLock<MemoryManagerPool> = LazyLock::new(|| {
     let (sender, receiver) = mpsc::unbounded_channel();
     (sender, Arc::new(Mutex::new(receiver)))
@@ -97,6 +106,7 @@ static MEMORY_SERVICE_POOL: LazyLock<MemoryManagerPool> = LazyLock::new(|| {
 ///
 /// # Returns
 /// Shared reference to memory manager service, or None if pool is empty
+#[cfg(feature = "memory")]
 pub async fn get_from_pool() -> Option<Arc<SurrealDBMemoryManager>> {
     let (_sender, receiver) = &*MEMORY_SERVICE_POOL;
     let mut rx = receiver.lock().await;
@@ -107,6 +117,7 @@ pub async fn get_from_pool() -> Option<Arc<SurrealDBMemoryManager>> {
 ///
 /// # Arguments
 /// * `memory` - Memory manager service to return to pool
+#[cfg(feature = "memory")]
 pub fn return_to_pool(memory: Arc<SurrealDBMemoryManager>) {
     let (sender, _receiver) = &*MEMORY_SERVICE_POOL;
     let _ = sender.send(memory);
@@ -133,6 +144,7 @@ pub fn pool_size() -> usize {
 ///
 /// # Performance
 /// Async initialization with proper error handling
+#[cfg(feature = "memory")]
 pub async fn initialize_pool(
     pool_size: usize,
     config: MemoryConfig,
@@ -7,6 +7,7 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "memory")]
 use super::super::SurrealDBMemoryManager;
 use super::memory::MemoryConfig;
 use crate::domain::init::globals::{CONFIG_CACHE, LOCAL_CONFIG};
@@ -90,6 +91,7 @@ pub fn update_config_cache(new_config: MemoryConfig) {
 }
 
 /// Get memory from connection pool with lock-free access
+#[cfg(feature = "memory")]
 #[inline]
 pub async fn get_pooled_memory() -> Option<Arc<SurrealDBMemoryManager>> {
     use std::sync::atomic::Ordering;
@@ -106,6 +108,7 @@ pub async fn get_pooled_memory() -> Option<Arc<SurrealDBMemoryManager>> {
 }
 
 /// Return memory to connection pool
+#[cfg(feature = "memory")]
 #[inline]
 pub fn return_pooled_memory(memory: Arc<SurrealDBMemoryManager>) {
     use std::sync::atomic::Ordering;
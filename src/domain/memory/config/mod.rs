@@ -12,9 +12,9 @@ pub mod shared;
 pub mod vector;
 
 // Re-export specific types to avoid ambiguous glob re-exports
-pub use cache::{
-    get_cached_config, get_pool_stats, get_pooled_memory, return_pooled_memory, update_config_cache,
-};
+pub use cache::{get_cached_config, get_pool_stats, update_config_cache};
+#[cfg(feature = "memory")]
+pub use cache::{get_pooled_memory, return_pooled_memory};
 pub use database::DatabaseConfig;
 // LLM types removed - use generic models with CandleModel + TextToTextCapable instead
 pub use memory::MemoryConfig;
@@ -16,6 +16,7 @@ pub mod config;
 
 // Re-export real memory manager from packages/memory
 pub use crate::memory::core::manager::MemoryManager;
+#[cfg(feature = "memory")]
 pub use crate::memory::core::manager::surreal::SurrealDBMemoryManager;
 
 /// SIMD-optimized vector operations for high-performance memory processing
@@ -75,7 +76,9 @@ pub use kodegen_simd::{CpuFeatures, CpuInfo, get_cpu_features, get_cpu_info};
 pub use primitives::*;
 // Re-export commonly used primitives types
 pub use primitives::{MemoryContent, MemoryTypeEnum};
-pub use tool::{MemoryOperation, MemoryResult, MemoryTool, MemoryToolError, MemoryToolResult};
+#[cfg(feature = "memory")]
+pub use tool::MemoryTool;
+pub use tool::{MemoryOperation, MemoryResult, MemoryToolError, MemoryToolResult};
 // Re-export trait types for trait-backed architecture
 pub use traits::{CandleMemory, CandleMemoryStats};
 
@@ -59,14 +59,9 @@ impl AlignedEmbedding {
             return None;
         }
 
-        let dot = self.dot_product(other)?;
-        let norm_self = self.data.iter().map(|x| x * x).sum::<f32>().sqrt();
-        let norm_other = other.data.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-        if norm_self == 0.0 || norm_other == 0.0 {
-            Some(0.0)
-        } else {
-            Some(dot / (norm_self * norm_other))
-        }
+        Some(crate::memory::core::ops::similarity::cosine(
+            &self.data,
+            &other.data,
+        ))
     }
 }
@@ -5,6 +5,7 @@
 
 // Removed unused import: std::future::Future
 // Removed unused import: std::pin::Pin
+#[cfg(feature = "memory")]
 use std::sync::{
     Arc, LazyLock,
     atomic::{AtomicUsize, Ordering},
@@ -12,40 +13,51 @@ use std::sync::{
 
 // Ultra-high-performance zero-allocation imports
 // Removed unused import: arrayvec::ArrayVec
+#[cfg(feature = "memory")]
 use tokio::sync::Mutex;
+#[cfg(feature = "memory")]
 use tokio::sync::mpsc;
 
 use serde::{Deserialize, Serialize};
 
 // Removed unused import: serde_json::Value
 use super::Error as MemoryError;
-use crate::memory::core::{MemoryNode, MemoryType, SurrealDBMemoryManager};
+use crate::memory::core::{MemoryNode, MemoryType};
+#[cfg(feature = "memory")]
+use crate::memory::core::SurrealDBMemoryManager;
 // Removed unused imports: AsyncStream, AsyncTask, spawn_async
+#[cfg(feature = "memory")]
 use crate::domain::completion::types::ToolInfo;
 use crate::domain::error::ZeroAllocError;
 
 /// Type alias for memory node result queue channel pair
+#[cfg(feature = "memory")]
 type MemoryNodeQueue = (
     mpsc::UnboundedSender<MemoryNode>,
     Arc<Mutex<mpsc::UnboundedReceiver<MemoryNode>>>,
 );
 
 /// Maximum number of memory nodes in result collections
+#[cfg(feature = "memory")]
 const MAX_MEMORY_TOOL_RESULTS: usize = 1000;
 
 /// Maximum number of streaming results per operation
+#[cfg(feature = "memory")]
 const MAX_STREAMING_RESULTS: usize = 100;
 
 /// Global result aggregation statistics
+#[cfg(feature = "memory")]
 static TOOL_STATS: LazyLock<AtomicUsize> = LazyLock::new(|| AtomicUsize::new(0));
 
 /// Result queue for aggregation
+#[cfg(feature = "memory")]
 static RESULT_QUEUE: LazyLock<MemoryNodeQueue> = LazyLock::new(|| {
     let (sender, receiver) = mpsc::unbounded_channel();
     (sender, Arc::new(Mutex::new(receiver)))
 });
 
 /// Zero-allocation memory tool with lock-free cognitive search
+#[cfg(feature = "memory")]
 #[derive(Debug, Clone)]
 pub struct MemoryTool {
     /// Tool metadata
@@ -118,6 +130,7 @@ pub enum MemoryToolError {
 /// Zero-allocation result type for memory tool operations
 pub type MemoryToolResult<T> = Result<T, MemoryToolError>;
 
+#[cfg(feature = "memory")]
 impl MemoryTool {
     /// Create a new memory tool instance
     #[must_use]
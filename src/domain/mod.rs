@@ -21,6 +21,8 @@ pub mod core;
 pub mod embedding;
 pub mod embedding_result;
 pub mod error;
+/// Unified event stream for chat, tools, workflows and memorize sessions
+pub mod event;
 /// Image processing and vision model support
 pub mod image;
 /// Image generation domain types for text-to-image diffusion models
@@ -44,5 +46,8 @@ pub use tokio_stream::Stream;
 // Re-export embedding result type
 pub use embedding_result::Embedding;
 
+// Re-export the unified event type
+pub use event::{AgentEvent, SessionState, agent_events};
+
 // Re-export only from minimal working modules
 // Most re-exports temporarily disabled until import issues resolved
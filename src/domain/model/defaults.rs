@@ -0,0 +1,94 @@
+//! Resolution of generation parameters against model defaults
+//!
+//! Builders leave parameters unset unless the caller chose a value.
+//! [`resolve_generation`] fills the gaps from [`CandleModelInfo`] in a fixed
+//! order — caller value, then model default, then a crate-wide fallback — and
+//! refuses to continue when the model requires `max_tokens` and none was
+//! given. [`apply_system_prompt_prefix`] prepends the model's required
+//! system prompt prefix.
+
+use super::info::CandleModelInfo;
+
+/// Temperature used when neither the caller nor the model sets one (greedy)
+pub const FALLBACK_TEMPERATURE: f64 = 0.0;
+
+/// Output token limit used when neither the caller nor the model sets one
+pub const FALLBACK_MAX_TOKENS: u64 = 2000;
+
+/// Model defaults resolution errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ModelDefaultsError {
+    #[error("Model '{model}' requires max_tokens to be set explicitly")]
+    MaxTokensRequired { model: String },
+}
+
+/// Generation parameters chosen by the caller; `None` means unset
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GenerationOverrides {
+    pub temperature: Option<f64>,
+    pub max_tokens: Option<u64>,
+    pub top_k: Option<u32>,
+    pub top_p: Option<f64>,
+}
+
+/// Generation parameters after applying model defaults
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedGeneration {
+    pub temperature: f64,
+    pub max_tokens: u64,
+    /// Top-k, if the caller or the model sets one
+    pub top_k: Option<u32>,
+    /// Top-p, if the caller or the model sets one
+    pub top_p: Option<f64>,
+}
+
+/// Fill unset parameters from `info`
+///
+/// # Errors
+/// Returns [`ModelDefaultsError::MaxTokensRequired`] if the model sets
+/// `requires_max_tokens` and `overrides.max_tokens` is `None`
+pub fn resolve_generation(
+    info: &CandleModelInfo,
+    overrides: &GenerationOverrides,
+) -> Result<ResolvedGeneration, ModelDefaultsError> {
+    let max_tokens = match overrides.max_tokens {
+        Some(max_tokens) => max_tokens,
+        None if info.requires_max_tokens => {
+            return Err(ModelDefaultsError::MaxTokensRequired {
+                model: info.name.to_string(),
+            });
+        }
+        None => info
+            .max_output_tokens
+            .map_or(FALLBACK_MAX_TOKENS, |t| u64::from(t.get())),
+    };
+
+    Ok(ResolvedGeneration {
+        temperature: overrides
+            .temperature
+            .or(info.default_temperature)
+            .unwrap_or(FALLBACK_TEMPERATURE),
+        max_tokens,
+        top_k: overrides.top_k.or(info.default_top_k),
+        top_p: overrides.top_p.or(info.default_top_p),
+    })
+}
+
+/// System prompt with the model's prefix prepended
+///
+/// Returns `None` when both are empty. A prompt that already starts with the
+/// prefix is returned unchanged, so applying the prefix twice is harmless.
+pub fn apply_system_prompt_prefix(info: &CandleModelInfo, system_prompt: &str) -> Option<String> {
+    let prefix = info
+        .system_prompt_prefix
+        .as_deref()
+        .filter(|p| !p.trim().is_empty());
+
+    match prefix {
+        Some(prefix) if system_prompt.starts_with(prefix) => Some(system_prompt.to_string()),
+        Some(prefix) if system_prompt.is_empty() => Some(prefix.to_string()),
+        Some(prefix) => Some(format!("{prefix}\n\n{system_prompt}")),
+        None if system_prompt.is_empty() => None,
+        None => Some(system_prompt.to_string()),
+    }
+}
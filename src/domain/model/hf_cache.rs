@@ -0,0 +1,356 @@
+//! Size accounting and pruning of the HuggingFace model cache
+//!
+//! Every model file goes through [`CandleModel::huggingface_file`], which
+//! leases the file's HuggingFace repo and marks the file as used. The cache
+//! is measured per repo (`models--org--name` under the hub directory);
+//! `models prune` removes the least recently used repos until it fits under
+//! `--max-size` or `KODEGEN_MODEL_CACHE_MAX_BYTES` (e.g. `50G`).
+//!
+//! A lease is a shared lock on the repo's file under `.kodegen-leases` in
+//! the hub directory, held from the first download or use of the repo until
+//! the process exits. Pruning takes an exclusive lock on that file before
+//! removing the repo, so it never removes a repo that any process, this one
+//! or another, is downloading or has loaded; a process leasing a repo waits
+//! for a prune of it to finish, then downloads it again.
+//!
+//! [`CandleModel::huggingface_file`]: super::traits::CandleModel::huggingface_file
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::SystemTime;
+
+use dashmap::DashMap;
+
+/// Cap on the size of the model cache, in bytes or with a K/M/G/T suffix
+pub const MODEL_CACHE_MAX_BYTES_ENV: &str = "KODEGEN_MODEL_CACHE_MAX_BYTES";
+
+/// Prefix of model repo directories in the hub cache
+const REPO_DIR_PREFIX: &str = "models--";
+
+/// Directory of lease files in the hub cache
+const LEASE_DIR: &str = ".kodegen-leases";
+
+/// Repos this process holds leases on, for as long as it runs
+static LEASES: LazyLock<DashMap<String, Arc<RepoLease>>> = LazyLock::new(DashMap::new);
+
+/// A shared lock keeping a repo from being pruned by any process
+///
+/// Released when dropped.
+#[derive(Debug)]
+pub struct RepoLease {
+    _file: File,
+}
+
+/// Lease file of `repo` in the hub cache at `root`
+fn lease_path(root: &Path, repo: &str) -> PathBuf {
+    root.join(LEASE_DIR)
+        .join(format!("{REPO_DIR_PREFIX}{}.lock", repo.replace('/', "--")))
+}
+
+/// Open (creating it if needed) the lease file of `repo`
+fn open_lease_file(root: &Path, repo: &str) -> std::io::Result<File> {
+    let path = lease_path(root, repo);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+}
+
+/// Lease `repo` in the hub cache at `root`, waiting for a prune of it to
+/// finish
+///
+/// # Errors
+///
+/// Returns an error if the lease file cannot be created or locked.
+pub fn lease_repo_at(root: &Path, repo: &str) -> std::io::Result<RepoLease> {
+    let file = open_lease_file(root, repo)?;
+    file.lock_shared()?;
+    Ok(RepoLease { _file: file })
+}
+
+/// Lease `repo` until this process exits
+///
+/// Blocks while another process prunes the repo; call it off the async
+/// runtime. Failing to take the lease is logged, not fatal: the repo is
+/// then only protected from prunes in this process.
+pub fn lease_repo(repo: &str) {
+    if LEASES.contains_key(repo) {
+        return;
+    }
+    match lease_repo_at(&cache_root(), repo) {
+        Ok(lease) => {
+            LEASES.entry(repo.to_string()).or_insert_with(|| Arc::new(lease));
+        }
+        Err(e) => log::warn!("Could not lease model cache repo {repo}: {e}"),
+    }
+}
+
+/// Repos this process has leased
+pub fn leased_repos() -> HashSet<String> {
+    LEASES.iter().map(|entry| entry.key().clone()).collect()
+}
+
+/// Mark a cached file as just used, for least-recently-used pruning
+///
+/// Sets the modification time of the file behind the snapshot link.
+pub fn touch(path: &Path) {
+    let result = std::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+    if let Err(e) = result {
+        log::debug!("Could not mark {} as used: {}", path.display(), e);
+    }
+}
+
+/// Exclusive lock on the lease file of `repo`, or `None` if a process
+/// holds a lease on it
+fn lock_for_prune(root: &Path, repo: &str) -> std::io::Result<Option<File>> {
+    let file = open_lease_file(root, repo)?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
+}
+
+/// One model repo in the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedRepo {
+    /// Repo ID, e.g. `unsloth/Qwen3-1.7B-GGUF`
+    pub repo: String,
+    /// Directory holding the repo's files
+    pub path: PathBuf,
+    /// Bytes of its files
+    pub size_bytes: u64,
+    /// Most recent use of any of its files
+    pub last_used: SystemTime,
+}
+
+/// Size of the model cache, repo by repo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheReport {
+    /// Hub cache directory
+    pub root: PathBuf,
+    pub total_bytes: u64,
+    /// Repos, most recently used first
+    pub repos: Vec<CachedRepo>,
+}
+
+impl fmt::Display for CacheReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Model cache {}: {} in {} repo(s)",
+            self.root.display(),
+            format_bytes(self.total_bytes),
+            self.repos.len()
+        )?;
+        for repo in &self.repos {
+            writeln!(f, "  {:>10}  {}", format_bytes(repo.size_bytes), repo.repo)?;
+        }
+        Ok(())
+    }
+}
+
+/// What a prune removed and kept
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Repos removed, least recently used first
+    pub removed: Vec<CachedRepo>,
+    pub freed_bytes: u64,
+    /// Size of the cache afterwards
+    pub remaining_bytes: u64,
+    /// Repos that would have been removed but are in use
+    pub skipped_in_use: Vec<String>,
+}
+
+impl fmt::Display for PruneReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Removed {} repo(s), freed {}; cache is now {}",
+            self.removed.len(),
+            format_bytes(self.freed_bytes),
+            format_bytes(self.remaining_bytes)
+        )?;
+        for repo in &self.removed {
+            writeln!(
+                f,
+                "  removed {:>10}  {}",
+                format_bytes(repo.size_bytes),
+                repo.repo
+            )?;
+        }
+        for repo in &self.skipped_in_use {
+            writeln!(f, "  kept (in use)  {repo}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Hub cache directory, from `HF_HOME` or the default location
+pub fn cache_root() -> PathBuf {
+    hf_hub::Cache::from_env().path().clone()
+}
+
+/// Cap from [`MODEL_CACHE_MAX_BYTES_ENV`], if set and valid
+pub fn max_cache_bytes() -> Option<u64> {
+    let value = std::env::var(MODEL_CACHE_MAX_BYTES_ENV).ok()?;
+    let parsed = parse_size(&value);
+    if parsed.is_none() {
+        log::warn!("Invalid {MODEL_CACHE_MAX_BYTES_ENV}={value:?}, not pruning the model cache");
+    }
+    parsed
+}
+
+/// Parse a byte count with an optional K, M, G or T suffix (powers of 1024)
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let upper = value.to_ascii_uppercase();
+    let digits = upper.trim_end_matches('B').trim_end_matches('I');
+    let (number, shift) = match digits.chars().last()? {
+        'K' => (&digits[..digits.len() - 1], 10),
+        'M' => (&digits[..digits.len() - 1], 20),
+        'G' => (&digits[..digits.len() - 1], 30),
+        'T' => (&digits[..digits.len() - 1], 40),
+        _ => (digits, 0),
+    };
+    number.trim().parse::<u64>().ok()?.checked_mul(1 << shift)
+}
+
+/// Measure the model repos under `root`
+///
+/// # Errors
+///
+/// Returns an error if `root` exists but cannot be read.
+pub fn scan(root: &Path) -> std::io::Result<CacheReport> {
+    let mut repos = Vec::new();
+    if root.exists() {
+        for entry in std::fs::read_dir(root)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let Some(repo) = name.strip_prefix(REPO_DIR_PREFIX) else {
+                continue;
+            };
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            repos.push(measure(repo.replacen("--", "/", 1), entry.path()));
+        }
+    }
+    repos.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    Ok(CacheReport {
+        root: root.to_path_buf(),
+        total_bytes: repos.iter().map(|repo| repo.size_bytes).sum(),
+        repos,
+    })
+}
+
+/// Size and last use of a repo directory; snapshot links are not counted
+fn measure(repo: String, path: PathBuf) -> CachedRepo {
+    let mut size_bytes = 0;
+    let mut last_used = SystemTime::UNIX_EPOCH;
+    for entry in walkdir::WalkDir::new(&path).into_iter().flatten() {
+        let Ok(metadata) = entry.path().symlink_metadata() else {
+            continue;
+        };
+        if metadata.is_file() {
+            size_bytes += metadata.len();
+            if let Ok(modified) = metadata.modified() {
+                last_used = last_used.max(modified);
+            }
+        }
+    }
+    CachedRepo {
+        repo,
+        path,
+        size_bytes,
+        last_used,
+    }
+}
+
+/// Size of the model cache
+///
+/// # Errors
+///
+/// Returns an error if the cache directory cannot be read.
+pub fn cache_report() -> std::io::Result<CacheReport> {
+    scan(&cache_root())
+}
+
+/// Remove least recently used repos under `root` until it holds at most
+/// `max_bytes`, keeping the repos in `protected` and those any process
+/// holds a lease on
+///
+/// # Errors
+///
+/// Returns an error if the cache cannot be read or a repo cannot be removed.
+pub fn prune_to(
+    root: &Path,
+    max_bytes: u64,
+    protected: &HashSet<String>,
+) -> std::io::Result<PruneReport> {
+    let report = scan(root)?;
+    let mut pruned = PruneReport {
+        remaining_bytes: report.total_bytes,
+        ..PruneReport::default()
+    };
+    for repo in report.repos.into_iter().rev() {
+        if pruned.remaining_bytes <= max_bytes {
+            break;
+        }
+        if protected.contains(&repo.repo) {
+            pruned.skipped_in_use.push(repo.repo);
+            continue;
+        }
+        // Held until the repo is gone, so no process can lease it meanwhile
+        let Some(_lock) = lock_for_prune(root, &repo.repo)? else {
+            pruned.skipped_in_use.push(repo.repo);
+            continue;
+        };
+        std::fs::remove_dir_all(&repo.path)?;
+        log::info!(
+            "Pruned model cache repo {} ({})",
+            repo.repo,
+            format_bytes(repo.size_bytes)
+        );
+        pruned.remaining_bytes -= repo.size_bytes;
+        pruned.freed_bytes += repo.size_bytes;
+        pruned.removed.push(repo);
+    }
+    Ok(pruned)
+}
+
+/// Prune the model cache to `max_bytes`, keeping repos in use by any process
+///
+/// # Errors
+///
+/// Returns an error if the cache cannot be read or a repo cannot be removed.
+pub fn prune(max_bytes: u64) -> std::io::Result<PruneReport> {
+    prune_to(&cache_root(), max_bytes, &leased_repos())
+}
+
+/// `1.5 GiB`-style size
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
@@ -43,6 +43,12 @@ pub enum CandleProvider {
     /// Dunzhang (Stella models)
     #[serde(rename = "dunzhang")]
     Dunzhang,
+    /// Beijing Academy of Artificial Intelligence (BGE models)
+    #[serde(rename = "baai")]
+    BAAI,
+    /// Nomic AI (nomic-embed models)
+    #[serde(rename = "nomic-ai")]
+    NomicAI,
     /// `LLaVA` HF (`LLaVA` models)
     #[serde(rename = "llava-hf")]
     LLaVAHF,
@@ -75,6 +81,8 @@ impl CandleProvider {
             CandleProvider::JinaAI => "jina-ai",
             CandleProvider::Nvidia => "nvidia",
             CandleProvider::Dunzhang => "dunzhang",
+            CandleProvider::BAAI => "baai",
+            CandleProvider::NomicAI => "nomic-ai",
             CandleProvider::LLaVAHF => "llava-hf",
             CandleProvider::Unsloth => "unsloth",
             CandleProvider::LAION => "laion",
@@ -4,8 +4,10 @@
 //! including traits, information, registry, and error handling.
 
 pub mod capabilities;
+pub mod defaults;
 pub mod download_lock;
 pub mod error;
+pub mod hf_cache;
 pub mod info;
 pub mod traits;
 pub mod usage;
@@ -13,6 +15,7 @@ pub mod validation;
 
 // Re-export commonly used Candle types
 pub use capabilities::*;
+pub use defaults::{GenerationOverrides, ModelDefaultsError, ResolvedGeneration};
 pub use error::{CandleModelError, CandleResult};
 pub use info::{CandleModelInfo, CandleProvider};
 pub use traits::*;
@@ -82,6 +82,8 @@ pub trait CandleModel: Send + Sync + std::fmt::Debug + 'static {
     /// Get path to a file in a `HuggingFace` repository
     ///
     /// Downloads the file if not cached, returns cached path if available.
+    /// Leases the repo against cache pruning and marks the file as used
+    /// (see [`super::hf_cache`]).
     ///
     /// # Arguments
     /// * `repo_key` - Repository identifier (e.g., "org/model-name")
@@ -101,6 +103,7 @@ pub trait CandleModel: Send + Sync + std::fmt::Debug + 'static {
     {
         async move {
             use crate::domain::model::download_lock::acquire_download_lock;
+            use crate::domain::model::hf_cache;
             use hf_hub::Cache;
             use hf_hub::api::tokio::ApiBuilder;
 
@@ -109,6 +112,15 @@ pub trait CandleModel: Send + Sync + std::fmt::Debug + 'static {
             let lock = acquire_download_lock(repo_key, filename).await;
             let _guard = lock.lock().await;
 
+            // Keep the repo out of cache pruning, in every process, while
+            // this one downloads or uses it
+            let lease_repo = repo_key.to_string();
+            if let Err(e) =
+                tokio::task::spawn_blocking(move || hf_cache::lease_repo(&lease_repo)).await
+            {
+                log::warn!("Could not lease model cache repo {repo_key}: {e}");
+            }
+
             // Check cache first (file might be ready if we waited for lock)
             let cache = Cache::from_env();
             let cache_repo = cache.model(repo_key.to_string());
@@ -119,6 +131,7 @@ pub trait CandleModel: Send + Sync + std::fmt::Debug + 'static {
                     && metadata.len() > 0
                 {
                     log::info!("✅ Using cached file (available after lock wait): {filename}");
+                    hf_cache::touch(&cached_path);
                     return Ok(cached_path);
                 }
             }
@@ -144,6 +157,14 @@ pub trait CandleModel: Send + Sync + std::fmt::Debug + 'static {
     }
 }
 
+/// A shared model is the model it points to
+impl<T: CandleModel> CandleModel for std::sync::Arc<T> {
+    #[inline]
+    fn info(&self) -> &'static CandleModelInfo {
+        (**self).info()
+    }
+}
+
 /// A message in a chat conversation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "role", rename_all = "lowercase")]
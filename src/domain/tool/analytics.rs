@@ -0,0 +1,151 @@
+//! Tool usage analytics
+//!
+//! Per-tool call counts, success/failure rates and latency percentiles.
+//! [`CandleToolRouter`](super::CandleToolRouter) and the chat session record
+//! every call; [`ToolSelector`](super::ToolSelector) can turn the numbers into
+//! reliability hints so the selection model steers away from flaky tools.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use rmcp::model::CallToolResult;
+use serde::{Deserialize, Serialize};
+
+/// Latency samples retained per tool for percentile estimates
+const LATENCY_WINDOW: usize = 256;
+
+/// Process-wide analytics shared by routers and chat sessions
+static GLOBAL_TOOL_ANALYTICS: LazyLock<Arc<ToolAnalytics>> =
+    LazyLock::new(|| Arc::new(ToolAnalytics::new()));
+
+/// Get the process-wide tool analytics
+#[inline]
+pub fn tool_analytics() -> Arc<ToolAnalytics> {
+    GLOBAL_TOOL_ANALYTICS.clone()
+}
+
+/// Whether an MCP tool call succeeded
+///
+/// A call that returns a result flagged `is_error` failed as surely as one
+/// that never returned.
+pub fn call_succeeded<E>(result: &Result<CallToolResult, E>) -> bool {
+    matches!(result, Ok(result) if result.is_error != Some(true))
+}
+
+/// Running counters for a single tool
+#[derive(Debug, Default)]
+struct ToolUsage {
+    successes: u64,
+    failures: u64,
+    latencies_ms: VecDeque<f64>,
+}
+
+/// Point-in-time usage statistics for one tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolUsageSnapshot {
+    /// Tool name
+    pub name: String,
+    /// Total calls recorded
+    pub calls: u64,
+    /// Calls that returned a result
+    pub successes: u64,
+    /// Calls that returned an error
+    pub failures: u64,
+    /// `successes / calls`, 1.0 when no calls are recorded
+    pub success_rate: f64,
+    /// Median latency over the recent window
+    pub p50_ms: f64,
+    /// 95th percentile latency over the recent window
+    pub p95_ms: f64,
+    /// 99th percentile latency over the recent window
+    pub p99_ms: f64,
+}
+
+/// Per-tool usage tracker
+#[derive(Debug, Default)]
+pub struct ToolAnalytics {
+    tools: Mutex<HashMap<String, ToolUsage>>,
+}
+
+impl ToolAnalytics {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a completed tool call
+    pub fn record(&self, name: &str, latency: Duration, success: bool) {
+        let mut tools = self.tools.lock();
+        let usage = tools.entry(name.to_string()).or_default();
+
+        if success {
+            usage.successes += 1;
+        } else {
+            usage.failures += 1;
+        }
+
+        if usage.latencies_ms.len() == LATENCY_WINDOW {
+            usage.latencies_ms.pop_front();
+        }
+        usage.latencies_ms.push_back(latency.as_secs_f64() * 1000.0);
+    }
+
+    /// Statistics for one tool, if it has been called
+    pub fn snapshot(&self, name: &str) -> Option<ToolUsageSnapshot> {
+        self.tools
+            .lock()
+            .get(name)
+            .map(|usage| Self::summarize(name, usage))
+    }
+
+    /// Statistics for every tool, sorted by name
+    pub fn snapshots(&self) -> Vec<ToolUsageSnapshot> {
+        let mut all: Vec<_> = self
+            .tools
+            .lock()
+            .iter()
+            .map(|(name, usage)| Self::summarize(name, usage))
+            .collect();
+        all.sort_by(|a, b| a.name.cmp(&b.name));
+        all
+    }
+
+    /// Clear all recorded statistics
+    pub fn reset(&self) {
+        self.tools.lock().clear();
+    }
+
+    fn summarize(name: &str, usage: &ToolUsage) -> ToolUsageSnapshot {
+        let calls = usage.successes + usage.failures;
+        let success_rate = if calls == 0 {
+            1.0
+        } else {
+            usage.successes as f64 / calls as f64
+        };
+
+        let mut sorted: Vec<f64> = usage.latencies_ms.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+
+        ToolUsageSnapshot {
+            name: name.to_string(),
+            calls,
+            successes: usage.successes,
+            failures: usage.failures,
+            success_rate,
+            p50_ms: percentile(&sorted, 0.50),
+            p95_ms: percentile(&sorted, 0.95),
+            p99_ms: percentile(&sorted, 0.99),
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice (0.0 when empty)
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
@@ -7,17 +7,27 @@
 //!
 //! Key components:
 //! - `CandleToolRouter`: Unified tool routing (local, remote, Cylo)
+//! - `ToolAnalytics`: Per-tool success rates and latency percentiles
+//! - `SafetyPolicy`: Screening of shell and code-execution arguments
 //! - OpenAI-style function calling experience
 //! - Full `tokio_stream::Stream` compatibility
 
+pub mod analytics;
 pub mod router;
+pub mod safety;
 pub mod selector;
 
 // Re-export the router and error types
+pub use analytics::{ToolAnalytics, ToolUsageSnapshot, call_succeeded, tool_analytics};
 pub use router::{CandleToolRouter, CyloBackendConfig, RouterError};
+pub use safety::{
+    ArgumentClassifier, ModelArgumentClassifier, SAFETY_OVERRIDE_ARG, SafetyFlag, SafetyPolicy,
+    SafetyRule,
+};
 pub use selector::*;
 
 // Re-export workspace MCP types
+#[cfg(feature = "mcp")]
 pub use kodegen_mcp_client::KodegenClient;
 pub use kodegen_mcp_schema::Tool;
 pub use rmcp::model::Tool as ToolInfo; // Type alias for backwards compatibility
@@ -6,18 +6,27 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use parking_lot::RwLock;
 use serde_json::Value;
 use std::pin::Pin;
 use tokio_stream::Stream;
+use tracing::Instrument;
 
+use super::analytics::{ToolAnalytics, tool_analytics};
+use super::safety::SafetyPolicy;
 use crate::domain::context::chunks::CandleJsonChunk;
 use cylo::{BackendConfig, Cylo, ExecutionRequest, ExecutionResult, create_backend};
+#[cfg(feature = "mcp")]
 use kodegen_mcp_client::KodegenClient;
 use kodegen_mcp_schema::ToolResponse;
 use rmcp::model::{Tool as RmcpTool, Content};
 
+/// Without the `mcp` feature there is no remote MCP client to route to
+#[cfg(not(feature = "mcp"))]
+type KodegenClient = std::convert::Infallible;
+
 /// Candle Tool Router
 ///
 /// Provides transparent tool routing for the chat loop architecture.
@@ -35,6 +44,12 @@ pub struct CandleToolRouter {
 
     /// Tool routing map: `tool_name` -> execution strategy
     tool_routes: Arc<RwLock<HashMap<String, ToolRoute>>>,
+
+    /// Per-tool call counts, success rates and latency
+    analytics: Arc<ToolAnalytics>,
+
+    /// Argument screening run before shell and code-execution tools
+    safety: Option<Arc<SafetyPolicy>>,
 }
 
 /// Tool execution route strategy
@@ -71,6 +86,8 @@ pub enum RouterError {
     McpClientError(String),
     #[error("Tool error: {0}")]
     ToolError(String),
+    #[error("Rejected by safety policy: {0}")]
+    PolicyDenied(String),
 }
 
 /// Internal trait for executing tools with type erasure
@@ -161,9 +178,34 @@ impl CandleToolRouter {
             local_tools: Arc::new(RwLock::new(HashMap::new())),
             cylo_config: None,
             tool_routes: Arc::new(RwLock::new(HashMap::new())),
+            analytics: tool_analytics(),
+            safety: None,
         }
     }
 
+    /// Record usage into a dedicated tracker instead of the process-wide one
+    #[must_use]
+    pub fn with_analytics(mut self, analytics: Arc<ToolAnalytics>) -> Self {
+        self.analytics = analytics;
+        self
+    }
+
+    /// Usage analytics recorded by this router
+    #[must_use]
+    pub fn analytics(&self) -> &Arc<ToolAnalytics> {
+        &self.analytics
+    }
+
+    /// Screen shell and code-execution arguments before dispatch
+    ///
+    /// Flagged calls fail with [`RouterError::PolicyDenied`] unless approved
+    /// (see [`SafetyPolicy::screen`]).
+    #[must_use]
+    pub fn with_safety_policy(mut self, policy: SafetyPolicy) -> Self {
+        self.safety = Some(Arc::new(policy));
+        self
+    }
+
     /// Configure Cylo backend for code execution
     #[must_use]
     pub fn with_cylo(mut self, backend_type: String, config: String) -> Self {
@@ -208,6 +250,7 @@ impl CandleToolRouter {
         }
 
         // Add remote MCP tools
+        #[cfg(feature = "mcp")]
         if let Some(client) = &self.mcp_client
             && let Ok(remote_tools) = client.list_tools().await
         {
@@ -224,6 +267,11 @@ impl CandleToolRouter {
 
     /// Execute a tool by name
     ///
+    /// Every call that reaches a tool is recorded in [`Self::analytics`].
+    /// The call runs in a `tool_call` tracing span tagged with the current
+    /// request ID, so backend queries can be correlated with the request.
+    /// With a safety policy configured, arguments are screened first.
+    ///
     /// # Errors
     /// Returns error if tool not found, execution fails, or invalid arguments provided
     pub async fn call_tool(
@@ -232,6 +280,55 @@ impl CandleToolRouter {
         args: Value,
         ctx: Option<kodegen_mcp_schema::ToolExecutionContext>,
     ) -> Result<Value, RouterError> {
+        let request_id = crate::runtime::current_request_id();
+        let span = tracing::info_span!(
+            "tool_call",
+            tool = name,
+            request_id = request_id.as_ref().map(|id| id.as_str()),
+        );
+
+        let args = match &self.safety {
+            Some(policy) => policy
+                .screen(name, args)
+                .instrument(span.clone())
+                .await
+                .map_err(|flag| RouterError::PolicyDenied(flag.to_string()))?,
+            None => args,
+        };
+
+        let start = Instant::now();
+        let result = self
+            .dispatch_tool(name, args, ctx)
+            .instrument(span.clone())
+            .await;
+        // A result the tool flagged as an error is still handed back, but
+        // counts as a failure
+        let success = matches!(result, Ok((_, is_error)) if !is_error);
+        span.in_scope(|| {
+            tracing::debug!(
+                elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+                success,
+                "tool call finished"
+            );
+        });
+
+        // Unknown tools say nothing about any tool's reliability
+        if !matches!(result, Err(RouterError::ToolNotFound(_))) {
+            self.analytics.record(name, start.elapsed(), success);
+        }
+
+        result.map(|(value, _)| value)
+    }
+
+    /// Route a call to the local, remote or Cylo backend
+    ///
+    /// Returns the result and whether the tool flagged it as an error.
+    async fn dispatch_tool(
+        &self,
+        name: &str,
+        args: Value,
+        ctx: Option<kodegen_mcp_schema::ToolExecutionContext>,
+    ) -> Result<(Value, bool), RouterError> {
         // Try local tools first
         let executor = self.local_tools.read().get(name).cloned();
         if let Some(executor) = executor {
@@ -241,13 +338,21 @@ impl CandleToolRouter {
                 )
             })?;
             let contents = executor.execute(args, ctx).await?;
-            return Self::contents_to_value(&contents);
+            return Ok((Self::contents_to_value(&contents)?, false));
         }
 
         // Try remote MCP client
+        #[cfg(not(feature = "mcp"))]
+        if let Some(client) = &self.mcp_client {
+            match *client {}
+        }
+        #[cfg(feature = "mcp")]
         if let Some(client) = &self.mcp_client {
             match client.call_tool(name, args.clone()).await {
-                Ok(result) => return Self::call_result_to_json(&result),
+                Ok(result) => {
+                    let is_error = result.is_error == Some(true);
+                    return Ok((Self::call_result_to_json(&result)?, is_error));
+                }
                 Err(kodegen_mcp_client::ClientError::ServiceError(_)) => {
                     // Tool might not exist on remote - try Cylo
                 }
@@ -264,9 +369,10 @@ impl CandleToolRouter {
                 config,
             }) = route
             {
-                return self
+                let value = self
                     .execute_cylo_backend(&backend_type, &config, args)
-                    .await;
+                    .await?;
+                return Ok((value, false));
             }
         }
 
@@ -284,18 +390,17 @@ impl CandleToolRouter {
         let router = self.clone();
         let tool_name = tool_name.to_string();
 
+        // Runs in the stream's task, which keeps the caller's request ID
         Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
-            tokio::spawn(async move {
-                match router.call_tool(&tool_name, args, ctx).await {
-                    Ok(result) => {
-                        let _ = tx.send(CandleJsonChunk(result));
-                    }
-                    Err(e) => {
-                        let error = serde_json::json!({"error": e.to_string()});
-                        let _ = tx.send(CandleJsonChunk(error));
-                    }
+            match router.call_tool(&tool_name, args, ctx).await {
+                Ok(result) => {
+                    let _ = tx.send(CandleJsonChunk(result));
                 }
-            });
+                Err(e) => {
+                    let error = serde_json::json!({"error": e.to_string()});
+                    let _ = tx.send(CandleJsonChunk(error));
+                }
+            }
         }))
     }
 
@@ -0,0 +1,354 @@
+//! Safety screening of outbound tool arguments
+//!
+//! Before a shell or code-execution tool runs, [`SafetyPolicy`] checks its
+//! arguments against regex rules for obviously dangerous payloads (recursive
+//! deletes, disk wipes, credential exfiltration, pipe-to-shell installs) and,
+//! optionally, asks a local model for a second opinion. A flagged call only
+//! proceeds if the operator allowed dangerous calls on the policy
+//! ([`SafetyPolicy::allow_dangerous`]) or the configured confirmation
+//! callback approves it.
+//!
+//! Tool arguments are written by the model, so nothing in them can approve a
+//! call: [`SAFETY_OVERRIDE_ARG`] is stripped and ignored.
+//!
+//! The policy is installed on [`CandleToolRouter`](super::CandleToolRouter)
+//! with `with_safety_policy` and runs before every dispatch.
+
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use crate::constraints::constraint_for_type;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::capability::text_to_text::qwen3_quantized::LoadedQwen3QuantizedModel;
+
+/// Reserved argument stripped from every call before dispatch
+///
+/// Earlier versions let it approve a flagged call. Since the model writes the
+/// arguments, it is now ignored; approval comes from the policy alone.
+pub const SAFETY_OVERRIDE_ARG: &str = "allow_dangerous";
+
+/// Tool name fragments treated as shell or code execution
+const DEFAULT_GATED_TOOL_MARKERS: &[&str] =
+    &["exec", "shell", "bash", "terminal", "command", "run_code"];
+
+/// A call flagged as dangerous
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafetyFlag {
+    /// Tool being called
+    pub tool: String,
+    /// Rule (or `model`) that flagged the call
+    pub rule: String,
+    /// Human-readable reason
+    pub reason: String,
+}
+
+impl std::fmt::Display for SafetyFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "call to '{}' flagged by {}: {}",
+            self.tool, self.rule, self.reason
+        )
+    }
+}
+
+/// Regex rule over the string content of tool arguments
+#[derive(Debug, Clone)]
+pub struct SafetyRule {
+    name: String,
+    pattern: Regex,
+    reason: String,
+}
+
+impl SafetyRule {
+    /// Compile a rule
+    ///
+    /// # Errors
+    /// Returns an error if `pattern` is not a valid regex
+    pub fn new(
+        name: impl Into<String>,
+        pattern: &str,
+        reason: impl Into<String>,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.into(),
+            pattern: Regex::new(pattern)?,
+            reason: reason.into(),
+        })
+    }
+
+    /// Rule name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, text: &str) -> bool {
+        self.pattern.is_match(text)
+    }
+}
+
+/// Built-in rules for obviously dangerous shell payloads
+pub fn default_rules() -> Vec<SafetyRule> {
+    [
+        (
+            "recursive_delete",
+            r"(?i)\brm\s+(-[a-z]*r[a-z]*f|-[a-z]*f[a-z]*r|--recursive\s+--force|--force\s+--recursive)\b",
+            "recursive forced delete",
+        ),
+        (
+            "disk_wipe",
+            r"(?i)\b(mkfs(\.\w+)?|dd\s+if=\S+\s+of=/dev/|shred\s+\S*\s*/dev/)",
+            "overwrites a filesystem or block device",
+        ),
+        ("fork_bomb", r":\(\)\s*\{\s*:\|:&\s*\};:", "fork bomb"),
+        (
+            "pipe_to_shell",
+            r"(?i)\b(curl|wget)\b[^|\n]*\|\s*(sudo\s+)?(ba|z|da)?sh\b",
+            "executes a downloaded script",
+        ),
+        (
+            "credential_read",
+            r"(?i)(~|\$home|/root|/home/\w+)/\.(ssh|aws|gnupg|kube|docker)/|/etc/shadow\b|\.netrc\b",
+            "reads credential files",
+        ),
+        (
+            "credential_exfiltration",
+            r"(?i)\b(curl|wget|nc|ncat|scp)\b.*(\$\{?\w*(token|secret|key|password)\w*\}?|\bprintenv\b|\benv\b\s*\|)",
+            "sends secrets or environment variables over the network",
+        ),
+        (
+            "chmod_world",
+            r"(?i)\bchmod\s+(-R\s+)?0?777\s+/",
+            "makes system paths world-writable",
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, pattern, reason)| SafetyRule::new(name, pattern, reason).ok())
+    .collect()
+}
+
+/// Second-opinion check over tool arguments
+pub trait ArgumentClassifier: Send + Sync {
+    /// Return a flag if the call looks dangerous
+    fn classify<'a>(&'a self, tool: &'a str, args: &'a Value) -> BoxFuture<'a, Option<SafetyFlag>>;
+}
+
+/// Model verdict schema for [`ModelArgumentClassifier`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SafetyCheckResponse {
+    /// Whether the call could destroy data, leak credentials or compromise the host
+    pub dangerous: bool,
+    /// Brief reason (1 sentence)
+    pub reason: String,
+}
+
+/// Local-model classifier using constrained generation
+///
+/// Model failures never block a call; the regex rules remain authoritative.
+pub struct ModelArgumentClassifier {
+    model: Arc<LoadedQwen3QuantizedModel>,
+}
+
+impl ModelArgumentClassifier {
+    /// Classify with an already loaded model
+    pub fn new(model: Arc<LoadedQwen3QuantizedModel>) -> Self {
+        Self { model }
+    }
+}
+
+impl ArgumentClassifier for ModelArgumentClassifier {
+    fn classify<'a>(&'a self, tool: &'a str, args: &'a Value) -> BoxFuture<'a, Option<SafetyFlag>> {
+        Box::pin(async move {
+            let tokenizer = self.model.tokenizer();
+            let constraint = match constraint_for_type::<SafetyCheckResponse>(tokenizer) {
+                Ok(constraint) => constraint,
+                Err(e) => {
+                    log::warn!("Safety classifier constraint failed: {e}");
+                    return None;
+                }
+            };
+            let prompt = format!(
+                "Tool: {tool}\nArguments: {args}\n\nCould running this destroy data, leak \
+                 credentials or compromise the host? Answer as JSON:"
+            );
+
+            let response = match self.model.prompt_with_context(prompt, constraint).await {
+                Ok(response) => response,
+                Err(e) => {
+                    log::warn!("Safety classifier inference failed: {e}");
+                    return None;
+                }
+            };
+            let verdict: SafetyCheckResponse = serde_json::from_str(&response).ok()?;
+            verdict.dangerous.then(|| SafetyFlag {
+                tool: tool.to_string(),
+                rule: "model".to_string(),
+                reason: verdict.reason,
+            })
+        })
+    }
+}
+
+/// Callback asked to approve a flagged call
+pub type ConfirmationCallback = Arc<dyn Fn(&SafetyFlag) -> BoxFuture<'static, bool> + Send + Sync>;
+
+/// Policy stage run before tool dispatch
+#[derive(Clone)]
+pub struct SafetyPolicy {
+    rules: Vec<SafetyRule>,
+    gated_tools: Vec<String>,
+    classifier: Option<Arc<dyn ArgumentClassifier>>,
+    confirmation: Option<ConfirmationCallback>,
+    allow_dangerous: bool,
+}
+
+impl std::fmt::Debug for SafetyPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SafetyPolicy")
+            .field("rules", &self.rules.iter().map(SafetyRule::name).collect::<Vec<_>>())
+            .field("gated_tools", &self.gated_tools)
+            .field("classifier", &self.classifier.is_some())
+            .field("confirmation", &self.confirmation.is_some())
+            .field("allow_dangerous", &self.allow_dangerous)
+            .finish()
+    }
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            rules: default_rules(),
+            gated_tools: DEFAULT_GATED_TOOL_MARKERS
+                .iter()
+                .map(|m| (*m).to_string())
+                .collect(),
+            classifier: None,
+            confirmation: None,
+            allow_dangerous: false,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// Add a regex rule
+    #[must_use]
+    pub fn with_rule(mut self, rule: SafetyRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Also screen tools whose name contains `marker`
+    #[must_use]
+    pub fn with_gated_tool(mut self, marker: impl Into<String>) -> Self {
+        self.gated_tools.push(marker.into().to_lowercase());
+        self
+    }
+
+    /// Ask `classifier` about calls the regex rules let through
+    #[must_use]
+    pub fn with_classifier(mut self, classifier: Arc<dyn ArgumentClassifier>) -> Self {
+        self.classifier = Some(classifier);
+        self
+    }
+
+    /// Ask `callback` to approve flagged calls instead of rejecting them outright
+    #[must_use]
+    pub fn with_confirmation<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn(&SafetyFlag) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = bool> + Send + 'static,
+    {
+        self.confirmation = Some(Arc::new(move |flag| Box::pin(callback(flag))));
+        self
+    }
+
+    /// Let flagged calls through with a warning instead of rejecting them
+    ///
+    /// An operator setting (CLI flag, session configuration); the model cannot
+    /// turn it on from tool arguments.
+    #[must_use]
+    pub fn allow_dangerous(mut self, allow: bool) -> Self {
+        self.allow_dangerous = allow;
+        self
+    }
+
+    /// Whether calls to `tool` are screened
+    pub fn is_gated(&self, tool: &str) -> bool {
+        let tool = tool.to_lowercase();
+        self.gated_tools.iter().any(|m| tool.contains(m.as_str()))
+    }
+
+    /// Run the regex rules over every string in `args`
+    pub fn check_rules(&self, tool: &str, args: &Value) -> Option<SafetyFlag> {
+        let mut strings = Vec::new();
+        collect_strings(args, &mut strings);
+
+        self.rules.iter().find_map(|rule| {
+            strings.iter().any(|s| rule.matches(s)).then(|| SafetyFlag {
+                tool: tool.to_string(),
+                rule: rule.name.clone(),
+                reason: rule.reason.clone(),
+            })
+        })
+    }
+
+    /// Screen a call and return the arguments to dispatch
+    ///
+    /// [`SAFETY_OVERRIDE_ARG`] is stripped from the arguments and has no
+    /// effect. Flagged calls pass only if the policy allows dangerous calls or
+    /// the confirmation callback approves them.
+    ///
+    /// # Errors
+    /// Returns the flag when a dangerous call is not approved
+    pub async fn screen(&self, tool: &str, mut args: Value) -> Result<Value, SafetyFlag> {
+        if args
+            .as_object_mut()
+            .and_then(|map| map.remove(SAFETY_OVERRIDE_ARG))
+            .is_some()
+        {
+            log::warn!("Ignoring '{SAFETY_OVERRIDE_ARG}' in arguments of '{tool}'");
+        }
+
+        if !self.is_gated(tool) {
+            return Ok(args);
+        }
+
+        let flag = match self.check_rules(tool, &args) {
+            Some(flag) => Some(flag),
+            None => match &self.classifier {
+                Some(classifier) => classifier.classify(tool, &args).await,
+                None => None,
+            },
+        };
+        let Some(flag) = flag else {
+            return Ok(args);
+        };
+
+        if self.allow_dangerous {
+            log::warn!("Safety override: {flag}");
+            return Ok(args);
+        }
+        if let Some(confirm) = &self.confirmation
+            && confirm(&flag).await
+        {
+            log::warn!("Safety confirmation granted: {flag}");
+            return Ok(args);
+        }
+
+        log::warn!("Safety policy rejected {flag}");
+        Err(flag)
+    }
+}
+
+fn collect_strings<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) => out.push(s),
+        Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
@@ -3,16 +3,32 @@
 //! This module implements an AI-powered tool selection agent that uses structured
 //! generation to filter large tool lists down to the 2-3 most relevant tools for
 //! a given user query, achieving significant context efficiency gains.
+//!
+//! With an embedding pre-filter configured, tool descriptions are embedded once
+//! (cached in the capability registry) and ranked against the user message so
+//! the LLM only sees the closest candidates.
 
 use anyhow::{Context, Result as AnyResult};
-use kodegen_simd::serde_constraints::constraint_for_type;
+use crate::constraints::constraint_for_type;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::sync::Arc;
 
+use super::analytics::ToolAnalytics;
+use crate::capability::registry::{self, TextEmbeddingModel};
 use crate::capability::text_to_text::qwen3_quantized::LoadedQwen3QuantizedModel;
+use crate::capability::traits::TextEmbeddingCapable;
+use crate::domain::model::traits::CandleModel;
+use crate::memory::core::ops::similarity::top_k_by_similarity;
 use rmcp::model::Tool as ToolInfo;
 
+/// Candidates kept by the embedding pre-filter before LLM selection
+pub const PREFILTER_CANDIDATES: usize = 10;
+
+/// Minimum recorded calls before a tool's success rate is shown to the model
+const MIN_CALLS_FOR_HINT: u64 = 3;
+
 /// Tool selection response schema - constrains model output to valid JSON
 ///
 /// This struct defines the exact JSON structure that the model must generate,
@@ -32,12 +48,39 @@ pub struct ToolSelectionResponse {
 /// by 91% (from ~22,500 tokens to ~900 tokens).
 pub struct ToolSelector {
     model: Arc<LoadedQwen3QuantizedModel>,
+    usage_hints: Option<Arc<ToolAnalytics>>,
+    prefilter: Option<TextEmbeddingModel>,
 }
 
 impl ToolSelector {
     /// Create a new tool selector with the given model
     pub fn new(model: Arc<LoadedQwen3QuantizedModel>) -> Self {
-        Self { model }
+        Self {
+            model,
+            usage_hints: None,
+            prefilter: None,
+        }
+    }
+
+    /// Narrow large tool lists by embedding similarity before LLM selection
+    ///
+    /// Only the [`PREFILTER_CANDIDATES`] tools closest to the user message are
+    /// passed to the constrained LLM selection.
+    #[must_use]
+    pub fn with_embedding_prefilter(mut self, embedding_model: TextEmbeddingModel) -> Self {
+        self.prefilter = Some(embedding_model);
+        self
+    }
+
+    /// Annotate tools with observed success rates from `analytics`
+    ///
+    /// Tools with at least a few recorded calls are listed with their success
+    /// rate and median latency, and the prompt asks the model to prefer
+    /// reliable tools.
+    #[must_use]
+    pub fn with_usage_hints(mut self, analytics: Arc<ToolAnalytics>) -> Self {
+        self.usage_hints = Some(analytics);
+        self
     }
 
     /// Select 2-3 most relevant tools for user query
@@ -63,12 +106,31 @@ impl ToolSelector {
         user_query: &str,
         available_tools: &[ToolInfo],
     ) -> AnyResult<Vec<String>> {
+        // 0. Narrow candidates by embedding similarity (falls back to all tools)
+        let candidates = match &self.prefilter {
+            Some(embedding_model) if available_tools.len() > PREFILTER_CANDIDATES => {
+                match Self::prefilter_tools(embedding_model, user_query, available_tools).await {
+                    Ok(narrowed) => Cow::Owned(narrowed),
+                    Err(e) => {
+                        log::warn!("Tool pre-filter failed: {e}, using all tools");
+                        Cow::Borrowed(available_tools)
+                    }
+                }
+            }
+            _ => Cow::Borrowed(available_tools),
+        };
+
         // 1. Create abbreviated tool list (name + one-line description)
-        let tool_list = Self::create_abbreviated_list(available_tools);
+        let tool_list = Self::create_abbreviated_list(&candidates, self.usage_hints.as_deref());
 
         // 2. Build selection prompt
+        let reliability_note = if self.usage_hints.is_some() {
+            "\nBracketed figures are observed success rates; prefer reliable tools when relevance is similar.\n"
+        } else {
+            ""
+        };
         let prompt = format!(
-            "User query: {user_query}\n\nAvailable tools:\n{tool_list}\n\nSelect 2-3 most relevant tools:"
+            "User query: {user_query}\n\nAvailable tools:\n{tool_list}\n{reliability_note}\nSelect 2-3 most relevant tools:"
         );
 
         // 3. Create constraint from ToolSelectionResponse schema
@@ -90,11 +152,87 @@ impl ToolSelector {
         Ok(selection.selected_tools)
     }
 
+    /// Keep the [`PREFILTER_CANDIDATES`] tools most similar to `user_query`
+    ///
+    /// Tool embeddings missing from the registry cache are computed in one batch.
+    async fn prefilter_tools(
+        embedding_model: &TextEmbeddingModel,
+        user_query: &str,
+        tools: &[ToolInfo],
+    ) -> AnyResult<Vec<ToolInfo>> {
+        let model_key = embedding_model.info().registry_key;
+        let texts: Vec<String> = tools.iter().map(Self::embedding_text).collect();
+
+        let mut embeddings: Vec<Option<Arc<[f32]>>> = tools
+            .iter()
+            .zip(&texts)
+            .map(|(t, text)| registry::get_tool_embedding(model_key, &t.name, text))
+            .collect();
+
+        let missing: Vec<usize> = (0..tools.len()).filter(|&i| embeddings[i].is_none()).collect();
+        if !missing.is_empty() {
+            let batch: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+            let vectors = embedding_model
+                .batch_embed(&batch, Some("document".to_string()))
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to embed tool descriptions: {e}"))?;
+
+            for (&i, vector) in missing.iter().zip(vectors) {
+                let vector: Arc<[f32]> = vector.into();
+                registry::cache_tool_embedding(model_key, &tools[i].name, &texts[i], vector.clone());
+                embeddings[i] = Some(vector);
+            }
+        }
+
+        let query = embedding_model
+            .embed(user_query, Some("search_query".to_string()))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to embed user query: {e}"))?;
+
+        let tool_vectors: Vec<Option<&[f32]>> = embeddings.iter().map(Option::as_deref).collect();
+        let ranked = Self::rank_embedded(&query, &tool_vectors, PREFILTER_CANDIDATES);
+
+        Ok(ranked.into_iter().map(|i| tools[i].clone()).collect())
+    }
+
+    /// Text embedded for a tool: name plus full description
+    fn embedding_text(tool: &ToolInfo) -> String {
+        match &tool.description {
+            Some(description) => format!("{}: {}", tool.name, description),
+            None => tool.name.to_string(),
+        }
+    }
+
+    /// Indices into `embeddings` of the `k` most similar to `query`
+    ///
+    /// Tools without an embedding are skipped; the returned indices still
+    /// refer to their position in `embeddings`.
+    pub fn rank_embedded(query: &[f32], embeddings: &[Option<&[f32]>], k: usize) -> Vec<usize> {
+        let (indices, vectors): (Vec<usize>, Vec<&[f32]>) = embeddings
+            .iter()
+            .enumerate()
+            .filter_map(|(i, vector)| vector.map(|vector| (i, vector)))
+            .unzip();
+        Self::rank_by_similarity(query, &vectors, k)
+            .into_iter()
+            .map(|rank| indices[rank])
+            .collect()
+    }
+
+    /// Indices of the `k` candidates with the highest cosine similarity to `query`
+    pub fn rank_by_similarity(query: &[f32], candidates: &[&[f32]], k: usize) -> Vec<usize> {
+        top_k_by_similarity(query, candidates, k)
+            .into_iter()
+            .map(|(i, _)| i)
+            .collect()
+    }
+
     /// Create abbreviated tool list with name + one-line description
     ///
     /// This reduces token usage while retaining enough information for
-    /// the model to make informed selection decisions.
-    fn create_abbreviated_list(tools: &[ToolInfo]) -> String {
+    /// the model to make informed selection decisions. When `analytics` is
+    /// given, tools with enough history get a reliability suffix.
+    pub fn create_abbreviated_list(tools: &[ToolInfo], analytics: Option<&ToolAnalytics>) -> String {
         tools
             .iter()
             .map(|t| {
@@ -103,9 +241,26 @@ impl ToolSelector {
                     .as_ref()
                     .and_then(|d| d.lines().next())
                     .unwrap_or("");
-                format!("- {}: {}", t.name, first_line)
+                let hint = analytics
+                    .and_then(|a| a.snapshot(&t.name))
+                    .filter(|s| s.calls >= MIN_CALLS_FOR_HINT)
+                    .map(|s| {
+                        format!(
+                            " [{:.0}% success over {} calls, p50 {:.0}ms]",
+                            s.success_rate * 100.0,
+                            s.calls,
+                            s.p50_ms
+                        )
+                    })
+                    .unwrap_or_default();
+                format!("- {}: {}{}", t.name, first_line, hint)
             })
             .collect::<Vec<_>>()
             .join("\n")
     }
 }
+
+/// Cosine similarity, 0.0 for mismatched or zero-length vectors
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    crate::memory::core::ops::similarity::cosine(a, b)
+}
@@ -45,6 +45,8 @@ pub mod cli;
 /// Chat functionality is now available through domain::chat
 /// Core components (engine, generation, etc.)
 pub mod core;
+/// Schema and regex constraints for structured generation
+pub use core::generation::constraints;
 /// Candle domain types (replaces cyrup_domain dependency)
 pub mod domain;
 /// Extension integration for Raycast and Alfred (macOS)
@@ -54,10 +56,11 @@ pub mod image;
 /// Memory system with cognitive features and vector storage
 pub mod memory;
 /// MCP tools for memory operations
+#[cfg(feature = "tools")]
 pub mod tools;
 /// Prompt processing utilities
 pub mod prompt;
-/// Shared Tokio runtime for avoiding multiple runtime creation
+/// Runtime helpers, including the background task supervisor
 pub mod runtime;
 /// Utility modules for common operations
 pub mod util;
@@ -86,13 +89,16 @@ pub mod prelude {
     };
     pub use crate::domain::chat::CandleChatLoop;
     pub use crate::domain::chat::message::CandleMessageChunk;
+    pub use crate::domain::event::{AgentEvent, agent_events};
     pub use crate::domain::{
         agent::CandleAgent,
         chat::message::types::CandleMessageRole,
         context::{
             FinishReason,
             chunks::CandleStringChunk,
-            provider::{CandleContext, CandleDirectory, CandleFile, CandleFiles, CandleGithub},
+            provider::{
+                CandleContext, CandleDirectory, CandleFile, CandleFiles, CandleGithub, CandleSql,
+            },
         },
         image_generation::{
             ImageGenerationChunk, ImageGenerationConfig, ImageGenerationModel, tensor_to_image,
@@ -101,10 +107,14 @@ pub mod prelude {
     };
 
     // Re-export workspace MCP types for convenience
+    #[cfg(feature = "mcp")]
     pub use kodegen_mcp_client::KodegenClient;
     pub use kodegen_mcp_schema::Tool;
     pub use rmcp::model::Tool as ToolInfo;
 
+    // Zero/one/many collections and their conversions
+    pub use crate::util::ZeroOneOrMany;
+
     // Real workflow execution types - streams-only architecture
     pub use crate::workflow::{CandleExecutableWorkflow, CandleWorkflowStep, candle_workflow};
 
@@ -156,14 +166,18 @@ pub use capability::registry::pool::{Pool, PoolError, init_maintenance};
 ///
 /// # Returns
 /// ServerHandle for graceful shutdown, or error if startup fails
+#[cfg(feature = "server")]
 pub async fn start_server(
     addr: std::net::SocketAddr,
     tls_cert: Option<std::path::PathBuf>,
     tls_key: Option<std::path::PathBuf>,
 ) -> anyhow::Result<kodegen_server_http::ServerHandle> {
+    crate::runtime::crash::install();
+
     // Bind to the address first
     let listener = tokio::net::TcpListener::bind(addr).await
-        .map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", addr, e))?;
+        .map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", addr, e))
+        .inspect_err(|e| crate::runtime::crash::report_error("Server failed to start", e))?;
 
     // Convert separate cert/key into Option<(cert, key)> tuple
     let tls_config = match (tls_cert, tls_key) {
@@ -180,61 +194,64 @@ pub async fn start_server(
 /// This variant is used by kodegend to eliminate TOCTOU race conditions
 /// during port cleanup. The listener is already bound to a port.
 ///
+/// With the `api` feature, the memory REST API is served under `/api` on its
+/// own plain-HTTP listener at [`memory::api::api_addr`] (the next port by
+/// default), each request opening its library through the same coordinator
+/// pool as the MCP tools. The returned handle stops both servers.
+///
 /// # Arguments
 /// * `listener` - Pre-bound TcpListener (port already reserved)
 /// * `tls_config` - Optional (cert_path, key_path) for HTTPS
 ///
 /// # Returns
 /// ServerHandle for graceful shutdown, or error if startup fails
+#[cfg(feature = "server")]
 pub async fn start_server_with_listener(
     listener: tokio::net::TcpListener,
     tls_config: Option<(std::path::PathBuf, std::path::PathBuf)>,
 ) -> anyhow::Result<kodegen_server_http::ServerHandle> {
-    use kodegen_server_http::{ServerBuilder, Managers, RouterSet, register_tool};
+    crate::runtime::crash::install();
+
+    serve_with_listener(listener, tls_config)
+        .await
+        .inspect_err(|e| crate::runtime::crash::report_error("Server failed to start", e))
+}
+
+#[cfg(feature = "server")]
+async fn serve_with_listener(
+    listener: tokio::net::TcpListener,
+    tls_config: Option<(std::path::PathBuf, std::path::PathBuf)>,
+) -> anyhow::Result<kodegen_server_http::ServerHandle> {
+    use kodegen_server_http::{ServerBuilder, Managers, RouterSet};
     use rmcp::handler::server::router::{prompt::PromptRouter, tool::ToolRouter};
 
+    // Initialize CoordinatorPool (retrieves model from lazy registry, creates empty pool),
+    // shared by the MCP tools and the REST API
+    let pool = initialize_coordinator_pool().await?;
+
+    // Bind the REST API before starting MCP so a taken port fails startup cleanly
+    #[cfg(feature = "api")]
+    let api = {
+        let mcp_addr = listener
+            .local_addr()
+            .map_err(|e| anyhow::anyhow!("Failed to get listener address: {}", e))?;
+        crate::memory::api::PoolApiServer::start(
+            crate::memory::api::api_addr(mcp_addr)?,
+            pool.clone(),
+        )
+        .await?
+    };
+
     let mut builder = ServerBuilder::new()
         .category(kodegen_config::CATEGORY_CANDLE_AGENT)
-        .register_tools(|| async {
-            // Initialize CoordinatorPool (retrieves model from lazy registry, creates empty pool)
-            let pool = initialize_coordinator_pool().await?;
-
-            let mut tool_router = ToolRouter::new();
-            let mut prompt_router = PromptRouter::new();
-            let managers = Managers::new();
-
-            // Create memorize session manager
-            let memorize_manager = std::sync::Arc::new(crate::tools::MemorizeSessionManager::new(pool.clone()));
-
-            // Register memory tools (4 tools)
-            (tool_router, prompt_router) = register_tool(
-                tool_router,
-                prompt_router,
-                crate::tools::MemorizeTool::new(memorize_manager.clone()),
-            );
-
-            (tool_router, prompt_router) = register_tool(
-                tool_router,
-                prompt_router,
-                crate::tools::CheckMemorizeStatusTool::new(memorize_manager.clone()),
-            );
-
-            (tool_router, prompt_router) = register_tool(
-                tool_router,
-                prompt_router,
-                crate::tools::RecallTool::new(pool.clone()),
-            );
-
-            (tool_router, prompt_router) = register_tool(
-                tool_router,
-                prompt_router,
-                crate::tools::ListMemoryLibrariesTool::new(pool.clone()),
-            );
-
-            // Start cleanup task for memorize sessions
-            memorize_manager.start_cleanup_task();
-
-            Ok(RouterSet::new(tool_router, prompt_router, managers))
+        .register_tools(move || {
+            let pool = pool.clone();
+            async move {
+                let (tool_router, prompt_router) =
+                    crate::tools::register_all_tools(pool, ToolRouter::new(), PromptRouter::new());
+
+                Ok(RouterSet::new(tool_router, prompt_router, Managers::new()))
+            }
         })
         .with_listener(listener);
 
@@ -242,20 +259,80 @@ pub async fn start_server_with_listener(
         builder = builder.with_tls_config(cert, key);
     }
 
+    #[cfg(feature = "api")]
+    {
+        match builder.serve().await {
+            Ok(mcp) => Ok(with_api_server(mcp, api)),
+            Err(e) => {
+                api.shutdown().await;
+                Err(e)
+            }
+        }
+    }
+    #[cfg(not(feature = "api"))]
     builder.serve().await
 }
 
+/// Handle that shuts down the REST API together with the MCP server
+///
+/// Completion is signalled once both have stopped; the caller's timeout on
+/// [`kodegen_server_http::ServerHandle::wait_for_completion`] covers both.
+#[cfg(all(feature = "server", feature = "api"))]
+fn with_api_server(
+    mcp: kodegen_server_http::ServerHandle,
+    api: crate::memory::api::PoolApiServer,
+) -> kodegen_server_http::ServerHandle {
+    let cancel = tokio_util::sync::CancellationToken::new();
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+    let cancelled = cancel.clone();
+
+    tokio::spawn(async move {
+        cancelled.cancelled().await;
+        mcp.cancel();
+        let (mcp_result, ()) =
+            tokio::join!(mcp.wait_for_completion(std::time::Duration::MAX), api.shutdown());
+        match mcp_result {
+            Ok(()) => {
+                let _ = done_tx.send(());
+            }
+            // Dropping the sender reports the lost signal to the caller
+            Err(e) => log::error!("MCP server shutdown failed: {}", e),
+        }
+    });
+
+    kodegen_server_http::ServerHandle::new(cancel, done_rx)
+}
+
+/// Serve the candle-agent MCP tools over stdin/stdout
+///
+/// For editors and clients that launch MCP servers as child processes
+/// instead of connecting over HTTP. Serves the same tools as
+/// [`start_server`] and returns when the client disconnects.
+///
+/// # Errors
+///
+/// Returns an error if the embedding model is missing or the MCP session
+/// cannot be established.
+#[cfg(feature = "tools")]
+pub async fn run_stdio_server() -> anyhow::Result<()> {
+    crate::runtime::crash::install();
+    let result = async {
+        let pool = initialize_coordinator_pool().await?;
+        crate::tools::StdioServer::new(pool).serve_stdio().await
+    }
+    .await;
+    if let Err(e) = &result {
+        crate::runtime::crash::report_error("Stdio server stopped", e);
+    }
+    result
+}
+
 // Helper function for pool initialization
+#[cfg(feature = "tools")]
 async fn initialize_coordinator_pool() -> anyhow::Result<std::sync::Arc<crate::memory::core::manager::pool::CoordinatorPool>> {
-    use crate::capability::registry::FromRegistry;
-    use crate::capability::registry::TextEmbeddingModel;
-    
-    // Get embedding model from lazy static registry (Stella 400M variant)
-    let emb_model = TextEmbeddingModel::from_registry("dunzhang/stella_en_400M_v5")
-        .ok_or_else(|| anyhow::anyhow!("Stella embedding model not found in registry"))?;
-
-    // Create empty coordinator pool - coordinators created lazily per library
-    let pool = crate::memory::core::manager::pool::CoordinatorPool::new(emb_model);
+    // Create empty coordinator pool - coordinators created lazily per library, each
+    // with the embedding model chosen for it in the environment (Stella 400M by default)
+    let pool = crate::memory::core::manager::pool::CoordinatorPool::from_env()?;
 
     Ok(std::sync::Arc::new(pool))
 }
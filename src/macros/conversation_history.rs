@@ -10,6 +10,25 @@ macro_rules! __process_conversation_history {
     ($history:expr) => {{
         // The macro processes whatever ConversationHistoryArgs receives
         // and ensures it gets converted to the right format
-        $history.into_history()
+        $crate::builders::agent_role::ConversationHistoryArgs::into_history($history)
+    }};
+}
+
+/// Build a [`ConversationHistory`](crate::builders::agent_role::ConversationHistory)
+/// from any number of `role => content` messages
+///
+/// ```ignore
+/// let history = conversation_history![
+///     CandleMessageRole::System => "You are terse",
+///     CandleMessageRole::User => "Hi",
+/// ];
+/// ```
+#[macro_export]
+macro_rules! conversation_history {
+    ($($role:expr => $content:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut history = $crate::builders::agent_role::ConversationHistory::new();
+        $(history.push($role, $content);)*
+        history
     }};
 }
@@ -1,76 +1,138 @@
 //! Candle-Agent Category HTTP Server
 //!
-//! Serves memory tools via HTTP/HTTPS transport using kodegen_server_http.
+//! Serves memory tools via HTTP/HTTPS transport using kodegen_server_http,
+//! or over stdin/stdout with `--stdio` for editors that launch MCP servers
+//! as child processes. `--dashboard` serves over HTTP with the operator
+//! dashboard in the terminal; quitting the dashboard stops the server.
+//! Over HTTP, the memory REST API is served on the next port (or
+//! `KODEGEN_API_ADDR`), sharing the MCP tools' coordinator pool; `--help`
+//! says so after the MCP server's options.
 
-use anyhow::{Result, anyhow};
-use kodegen_server_http::{ServerBuilder, Managers, RouterSet, register_tool};
+use anyhow::Result;
+use kodegen_server_http::{ServerBuilder, Managers, RouterSet};
 use rmcp::handler::server::router::{prompt::PromptRouter, tool::ToolRouter};
 use std::sync::Arc;
+use std::time::Duration;
 
-use kodegen_candle_agent::capability::registry::TextEmbeddingModel;
+use kodegen_candle_agent::cli::Dashboard;
 use kodegen_candle_agent::memory::core::manager::pool::CoordinatorPool;
-use kodegen_candle_agent::tools::{
-    MemorizeTool, MemorizeSessionManager, CheckMemorizeStatusTool,
-    RecallTool, ListMemoryLibrariesTool
-};
+#[cfg(feature = "api")]
+use kodegen_candle_agent::memory::api::{PoolApiServer, api_addr};
+use kodegen_candle_agent::tools::register_all_tools;
+
+/// How long shutdown waits for in-flight background tasks (memorize, memory writes)
+const BACKGROUND_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shown after the MCP server's options in `--help`
+#[cfg(feature = "api")]
+const API_HELP: &str = "\
+The memory REST API listens separately from MCP, on plain HTTP under /api:
+on the --http port + 1 by default, or on KODEGEN_API_ADDR (e.g. 127.0.0.1:30438).
+Startup fails if that address is already in use.";
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    ServerBuilder::new()
+    kodegen_candle_agent::runtime::crash::install();
+
+    let has_flag = |flag: &str| std::env::args().skip(1).any(|arg| arg == flag);
+    if has_flag("--help") || has_flag("-h") {
+        return print_help();
+    }
+
+    let result = if has_flag("--stdio") {
+        kodegen_candle_agent::run_stdio_server().await
+    } else if has_flag("--dashboard") {
+        serve_with_dashboard().await
+    } else {
+        serve_http().await
+    };
+
+    if let Err(e) = &result {
+        kodegen_candle_agent::runtime::crash::report_error("Server stopped", e);
+    }
+
+    // Drain supervised background tasks before exiting
+    kodegen_candle_agent::runtime::supervisor()
+        .shutdown(BACKGROUND_DRAIN_TIMEOUT)
+        .await;
+
+    result
+}
+
+async fn serve_http() -> Result<()> {
+    // Initialize CoordinatorPool, shared by the MCP tools and the REST API
+    let pool = initialize_coordinator_pool().await?;
+
+    #[cfg(feature = "api")]
+    let api = match mcp_address() {
+        Some(mcp_addr) => Some(PoolApiServer::start(api_addr(mcp_addr)?, pool.clone()).await?),
+        // Leave reporting the missing or invalid address to the MCP server
+        None => None,
+    };
+
+    let result = ServerBuilder::new()
         .category(kodegen_config::CATEGORY_CANDLE_AGENT)
-        .register_tools(|| async {
-            // Initialize CoordinatorPool (async initialization)
-            let pool = initialize_coordinator_pool().await?;
-
-            let mut tool_router = ToolRouter::new();
-            let mut prompt_router = PromptRouter::new();
-            let managers = Managers::new();
-
-            // Create memorize session manager
-            let memorize_manager = Arc::new(MemorizeSessionManager::new(pool.clone()));
-
-            // Register memory tools (4 tools now - memorize and check_memorize_status use manager)
-            (tool_router, prompt_router) = register_tool(
-                tool_router,
-                prompt_router,
-                MemorizeTool::new(memorize_manager.clone()),
-            );
-
-            (tool_router, prompt_router) = register_tool(
-                tool_router,
-                prompt_router,
-                CheckMemorizeStatusTool::new(memorize_manager.clone()),
-            );
-
-            (tool_router, prompt_router) = register_tool(
-                tool_router,
-                prompt_router,
-                RecallTool::new(pool.clone()),
-            );
-
-            (tool_router, prompt_router) = register_tool(
-                tool_router,
-                prompt_router,
-                ListMemoryLibrariesTool::new(pool.clone()),
-            );
-
-            // CRITICAL: Start cleanup task after all tools registered
-            memorize_manager.start_cleanup_task();
-
-            Ok(RouterSet::new(tool_router, prompt_router, managers))
+        .register_tools(move || {
+            let pool = pool.clone();
+            async move {
+                // Same tools as the stdio transport
+                let (tool_router, prompt_router) =
+                    register_all_tools(pool, ToolRouter::new(), PromptRouter::new());
+
+                Ok(RouterSet::new(tool_router, prompt_router, Managers::new()))
+            }
         })
         .run()
-        .await
+        .await;
+
+    #[cfg(feature = "api")]
+    if let Some(api) = api {
+        api.shutdown().await;
+    }
+    result
 }
 
-async fn initialize_coordinator_pool() -> Result<Arc<CoordinatorPool>> {
-    // Get embedding model from registry (Stella 400M variant - registered by default)
-    use kodegen_candle_agent::capability::registry::FromRegistry;
-    let emb_model = TextEmbeddingModel::from_registry("dunzhang/stella_en_400M_v5")
-        .ok_or_else(|| anyhow!("Stella embedding model not found in registry"))?;
+/// MCP address given with `--http`, parsed as the server will parse it
+#[cfg(feature = "api")]
+fn mcp_address() -> Option<std::net::SocketAddr> {
+    use clap::Parser;
+
+    let args = std::env::args().filter(|arg| arg != "--dashboard");
+    kodegen_server_http::Cli::try_parse_from(args)
+        .ok()
+        .and_then(|cli| cli.http)
+}
+
+/// The MCP server's `--help`, followed by where the REST API listens
+fn print_help() -> Result<()> {
+    use clap::CommandFactory;
 
-    // Create coordinator pool - coordinators created lazily per library
-    let pool = CoordinatorPool::new(emb_model);
+    let command = kodegen_server_http::Cli::command();
+    #[cfg(feature = "api")]
+    let mut command = command.after_help(API_HELP);
+    #[cfg(not(feature = "api"))]
+    let mut command = command;
+    command.print_help()?;
+    Ok(())
+}
+
+/// Serve over HTTP while the dashboard shows this process's pools and sessions
+async fn serve_with_dashboard() -> Result<()> {
+    let server = tokio::spawn(serve_http());
+    let result = Dashboard::new().run().await;
+    if server.is_finished() {
+        // The server failing to start matters more than the dashboard
+        server.await??;
+    } else {
+        server.abort();
+    }
+    result
+}
+
+async fn initialize_coordinator_pool() -> Result<Arc<CoordinatorPool>> {
+    // Create coordinator pool - coordinators created lazily per library, each with
+    // the embedding model chosen for it in the environment (Stella 400M by default)
+    let pool = CoordinatorPool::from_env()?;
 
     Ok(Arc::new(pool))
 }
@@ -0,0 +1,218 @@
+//! Resumable chat turns over server-sent events
+//!
+//! `POST /v1/chat/sessions` opens a session (shared with the WebSocket
+//! endpoint) and `POST /v1/chat/sessions/{session_id}/turns` starts a turn,
+//! answering with an SSE stream. Each event's `id` is its offset within the
+//! turn: `chunk` events carry an agent event and a final `done` event
+//! reports whether the turn was cancelled.
+//!
+//! Generation keeps going when the client disconnects. Reconnecting to
+//! `GET /v1/chat/sessions/{session_id}/turns/{turn}/events` with the last
+//! offset received as `Last-Event-ID` (or `?after=`) replays the missed
+//! events and follows the turn live. Only the session's latest turn and its
+//! newest [`REPLAY_CAPACITY`](super::replay::REPLAY_CAPACITY) events are
+//! kept; a client further behind gets 410 Gone.
+
+use std::convert::Infallible;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        Json,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::routes::AppState;
+use super::ws::{StartTurnError, TurnEvent};
+
+/// Body of `POST /v1/chat/sessions`
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateSessionRequest {
+    /// Session to resume, if still alive
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Registry key of the model to chat with
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Session opened or resumed
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionResponse {
+    pub session_id: String,
+    pub resumed: bool,
+    /// Turns taken so far
+    pub turns: u64,
+}
+
+/// Body of `POST /v1/chat/sessions/{session_id}/turns`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TurnRequest {
+    /// User message
+    pub content: String,
+}
+
+/// Query parameters of the resume endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct ResumeQuery {
+    /// Last offset received, for clients that cannot set `Last-Event-ID`
+    pub after: Option<u64>,
+}
+
+/// Open or resume a chat session
+#[utoipa::path(
+    post,
+    path = "/v1/chat/sessions",
+    tag = "chat",
+    request_body = CreateSessionRequest,
+    responses((status = 200, description = "Session opened or resumed", body = SessionResponse))
+)]
+pub async fn create_session(
+    State(state): State<AppState>,
+    Json(request): Json<CreateSessionRequest>,
+) -> Json<SessionResponse> {
+    let (session_id, resumed, turns) = state
+        .chat_sessions
+        .open(request.session_id.as_deref(), request.model.as_deref());
+    Json(SessionResponse {
+        session_id,
+        resumed,
+        turns,
+    })
+}
+
+/// Start a turn and stream it as server-sent events
+///
+/// The `X-Chat-Turn` response header holds the turn number used to resume.
+#[utoipa::path(
+    post,
+    path = "/v1/chat/sessions/{session_id}/turns",
+    tag = "chat",
+    params(("session_id" = String, Path, description = "Chat session ID")),
+    request_body = TurnRequest,
+    responses(
+        (status = 200, description = "Event stream of the turn"),
+        (status = 404, description = "Session expired or model not registered"),
+        (status = 409, description = "A turn is already streaming"),
+    )
+)]
+pub async fn start_turn(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    Json(request): Json<TurnRequest>,
+) -> Result<
+    (
+        [(&'static str, String); 1],
+        Sse<impl Stream<Item = Result<Event, Infallible>>>,
+    ),
+    (StatusCode, String),
+> {
+    let (turn, buffer) = state
+        .chat_sessions
+        .start_turn(&session_id, request.content)
+        .map_err(|e| {
+            let status = match e {
+                StartTurnError::TurnStreaming => StatusCode::CONFLICT,
+                StartTurnError::SessionExpired(_) | StartTurnError::ModelNotFound { .. } => {
+                    StatusCode::NOT_FOUND
+                }
+            };
+            (status, e.to_string())
+        })?;
+
+    let events = buffer
+        .follow(0)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok((
+        [("x-chat-turn", turn.to_string())],
+        event_stream(turn, events),
+    ))
+}
+
+/// Replay and follow a turn from the client's last offset
+///
+/// The offset comes from `Last-Event-ID`, else `?after=`, else 0 (the whole
+/// turn).
+#[utoipa::path(
+    get,
+    path = "/v1/chat/sessions/{session_id}/turns/{turn}/events",
+    tag = "chat",
+    params(
+        ("session_id" = String, Path, description = "Chat session ID"),
+        ("turn" = u64, Path, description = "Turn number"),
+        ("after" = Option<u64>, Query, description = "Last offset received"),
+        ("Last-Event-ID" = Option<String>, Header, description = "Last offset received"),
+    ),
+    responses(
+        (status = 200, description = "Missed and live events of the turn"),
+        (status = 400, description = "Malformed Last-Event-ID"),
+        (status = 404, description = "Not the session's latest turn"),
+        (status = 410, description = "Events after the offset are no longer buffered"),
+    )
+)]
+pub async fn resume_turn(
+    State(state): State<AppState>,
+    Path((session_id, turn)): Path<(String, u64)>,
+    Query(query): Query<ResumeQuery>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let after = match headers.get("last-event-id") {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|id| id.trim().parse::<u64>().ok())
+            .ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    "Last-Event-ID must be an event offset".to_string(),
+                )
+            })?,
+        None => query.after.unwrap_or(0),
+    };
+
+    let buffer = state
+        .chat_sessions
+        .generation(&session_id, turn)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Turn {turn} of session '{session_id}' is not available"),
+            )
+        })?;
+    let events = buffer
+        .follow(after)
+        .map_err(|gap| (StatusCode::GONE, gap.to_string()))?;
+    Ok(event_stream(turn, events))
+}
+
+/// SSE event for one buffered turn event
+pub fn sse_event(turn: u64, offset: u64, event: &TurnEvent) -> Event {
+    let (name, data) = match event {
+        TurnEvent::Chunk { chunk } => ("chunk", serde_json::to_string(chunk)),
+        TurnEvent::Done { cancelled } => (
+            "done",
+            serde_json::to_string(&serde_json::json!({ "turn": turn, "cancelled": cancelled })),
+        ),
+    };
+    let data = data.unwrap_or_else(|e| {
+        log::error!("Failed to encode chat event: {}", e);
+        String::new()
+    });
+    Event::default()
+        .id(offset.to_string())
+        .event(name)
+        .data(data)
+}
+
+fn event_stream(
+    turn: u64,
+    events: impl Stream<Item = (u64, TurnEvent)> + Send + 'static,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    Sse::new(events.map(move |(offset, event)| Ok(sse_event(turn, offset, &event))))
+        .keep_alive(KeepAlive::default())
+}
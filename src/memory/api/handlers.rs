@@ -11,13 +11,31 @@ use futures_util::StreamExt;
 use surrealdb_types::{Datetime, Value};
 
 use super::models::{CreateMemoryRequest, HealthResponse, MemoryResponse, SearchRequest};
-use super::routes::AppState;
+use super::routes::{AppState, LibraryMemory};
+use crate::capability::registry::pool::{
+    all_model_status, all_worker_status, prometheus_model_status, prometheus_worker_status,
+};
 use crate::memory::core::primitives::node::MemoryNode;
 use crate::memory::manager::surreal::MemoryManager;
 
 /// Create a new memory
+#[utoipa::path(
+    post,
+    path = "/v1/memories",
+    tag = "memories",
+    params(
+        ("x-memory-library" = Option<String>, Header,
+            description = "Memory library, `default` when omitted"),
+    ),
+    request_body = CreateMemoryRequest,
+    responses(
+        (status = 200, description = "Memory created", body = MemoryResponse),
+        (status = 400, description = "Empty content"),
+        (status = 500, description = "Storage failure"),
+    )
+)]
 pub async fn create_memory(
-    State(state): State<AppState>,
+    LibraryMemory(memory_manager): LibraryMemory,
     JsonBody(request): JsonBody<CreateMemoryRequest>,
 ) -> Result<Json<MemoryResponse>, StatusCode> {
     // Validate request
@@ -30,7 +48,7 @@ pub async fn create_memory(
     let memory_node = MemoryNode::new(request.memory_type, content);
 
     // Create memory using the manager
-    let pending_memory = state.memory_manager.create_memory(memory_node);
+    let pending_memory = memory_manager.create_memory(memory_node);
     match pending_memory.await {
         Ok(memory) => {
             let response = MemoryResponse {
@@ -52,8 +70,23 @@ pub async fn create_memory(
 }
 
 /// Get a memory by ID
+#[utoipa::path(
+    get,
+    path = "/v1/memories/{id}",
+    tag = "memories",
+    params(
+        ("id" = String, Path, description = "Memory ID"),
+        ("x-memory-library" = Option<String>, Header,
+            description = "Memory library, `default` when omitted"),
+    ),
+    responses(
+        (status = 200, description = "Memory found", body = MemoryResponse),
+        (status = 404, description = "No memory with this ID"),
+        (status = 500, description = "Storage failure"),
+    )
+)]
 pub async fn get_memory(
-    State(state): State<AppState>,
+    LibraryMemory(memory_manager): LibraryMemory,
     Path(id): Path<String>,
 ) -> Result<Json<MemoryResponse>, StatusCode> {
     // Validate ID format
@@ -62,7 +95,7 @@ pub async fn get_memory(
     }
 
     // Retrieve memory using the manager
-    match state.memory_manager.get_memory(&id).await {
+    match memory_manager.get_memory(&id).await {
         Ok(Some(memory)) => {
             let response = MemoryResponse {
                 id: memory.id,
@@ -84,8 +117,24 @@ pub async fn get_memory(
 }
 
 /// Update a memory
+#[utoipa::path(
+    put,
+    path = "/v1/memories/{id}",
+    tag = "memories",
+    params(
+        ("id" = String, Path, description = "Memory ID"),
+        ("x-memory-library" = Option<String>, Header,
+            description = "Memory library, `default` when omitted"),
+    ),
+    request_body = CreateMemoryRequest,
+    responses(
+        (status = 200, description = "Memory updated", body = MemoryResponse),
+        (status = 400, description = "Empty ID or content"),
+        (status = 500, description = "Storage failure"),
+    )
+)]
 pub async fn update_memory(
-    State(state): State<AppState>,
+    LibraryMemory(memory_manager): LibraryMemory,
     Path(id): Path<String>,
     JsonBody(request): JsonBody<CreateMemoryRequest>,
 ) -> Result<Json<MemoryResponse>, StatusCode> {
@@ -99,7 +148,7 @@ pub async fn update_memory(
     let content = crate::memory::core::primitives::types::MemoryContent::new(&request.content);
     let updated_memory = MemoryNode::with_id(id.clone(), request.memory_type, content);
 
-    let pending_memory = state.memory_manager.update_memory(updated_memory);
+    let pending_memory = memory_manager.update_memory(updated_memory);
     match pending_memory.await {
         Ok(memory) => {
             let response = MemoryResponse {
@@ -121,8 +170,23 @@ pub async fn update_memory(
 }
 
 /// Delete a memory
+#[utoipa::path(
+    delete,
+    path = "/v1/memories/{id}",
+    tag = "memories",
+    params(
+        ("id" = String, Path, description = "Memory ID"),
+        ("x-memory-library" = Option<String>, Header,
+            description = "Memory library, `default` when omitted"),
+    ),
+    responses(
+        (status = 204, description = "Memory deleted"),
+        (status = 404, description = "No memory with this ID"),
+        (status = 500, description = "Storage failure"),
+    )
+)]
 pub async fn delete_memory(
-    State(state): State<AppState>,
+    LibraryMemory(memory_manager): LibraryMemory,
     Path(id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
     // Validate ID format
@@ -131,7 +195,7 @@ pub async fn delete_memory(
     }
 
     // Delete memory using the manager
-    match state.memory_manager.delete_memory(&id).await {
+    match memory_manager.delete_memory(&id).await {
         Ok(true) => Ok(StatusCode::NO_CONTENT),
         Ok(false) => Err(StatusCode::NOT_FOUND),
         Err(e) => {
@@ -142,8 +206,23 @@ pub async fn delete_memory(
 }
 
 /// Search memories
+#[utoipa::path(
+    post,
+    path = "/v1/memories/search",
+    tag = "memories",
+    params(
+        ("x-memory-library" = Option<String>, Header,
+            description = "Memory library, `default` when omitted"),
+    ),
+    request_body = SearchRequest,
+    responses(
+        (status = 200, description = "Matching memories", body = [MemoryResponse]),
+        (status = 400, description = "Empty query"),
+    )
+)]
 pub async fn search_memories(
     State(state): State<AppState>,
+    LibraryMemory(memory_manager): LibraryMemory,
     JsonBody(request): JsonBody<SearchRequest>,
 ) -> Result<Json<Vec<MemoryResponse>>, StatusCode> {
     // Track search latency for metrics
@@ -155,7 +234,7 @@ pub async fn search_memories(
     }
 
     // Perform search using the manager
-    let mut memory_stream = state.memory_manager.search_by_content(&request.query);
+    let mut memory_stream = memory_manager.search_by_content(&request.query);
 
     // Collect memories from stream
     let mut memories: Vec<MemoryNode> = vec![];
@@ -196,11 +275,21 @@ pub async fn search_memories(
 }
 
 /// Health check endpoint
+#[utoipa::path(
+    get,
+    path = "/v1/health",
+    tag = "health",
+    params(
+        ("x-memory-library" = Option<String>, Header,
+            description = "Memory library, `default` when omitted"),
+    ),
+    responses((status = 200, description = "Memory manager health", body = HealthResponse))
+)]
 pub async fn get_health(
-    State(state): State<AppState>,
+    LibraryMemory(memory_manager): LibraryMemory,
 ) -> Json<HealthResponse> {
     // Perform actual health check using the memory manager
-    let status = if state.memory_manager.health_check().await.is_ok() {
+    let status = if memory_manager.health_check().await.is_ok() {
         "healthy".to_string()
     } else {
         "unhealthy".to_string()
@@ -209,19 +298,34 @@ pub async fn get_health(
     Json(HealthResponse {
         status,
         timestamp: Datetime::now(),
+        models: all_model_status(),
     })
 }
 
 /// Metrics endpoint
+#[utoipa::path(
+    get,
+    path = "/v1/metrics",
+    tag = "health",
+    params(
+        ("x-memory-library" = Option<String>, Header,
+            description = "Memory library, `default` when omitted"),
+    ),
+    responses(
+        (status = 200, description = "Prometheus text exposition", body = String, content_type = "text/plain"),
+        (status = 500, description = "Metrics query failed"),
+    )
+)]
 pub async fn get_metrics(
     State(state): State<AppState>,
+    LibraryMemory(memory_manager): LibraryMemory,
 ) -> Result<String, StatusCode> {
     // Collect actual metrics from the memory manager
     let mut output = String::with_capacity(1024);
 
     // Get total memory count using efficient SurrealDB COUNT query
     let total_count: u64 = {
-        let mut query_result = state.memory_manager
+        let mut query_result = memory_manager
             .database()
             .query("SELECT count() AS total FROM memory")
             .await
@@ -240,7 +344,7 @@ pub async fn get_metrics(
         count_result.unwrap_or(0)
     };
 
-    let is_healthy = state.memory_manager.health_check().await.is_ok();
+    let is_healthy = memory_manager.health_check().await.is_ok();
 
     // Memory health status
     output.push_str(
@@ -270,7 +374,7 @@ pub async fn get_metrics(
         MemoryTypeEnum::LongTerm,
     ] {
         let type_str = memory_type.to_string();
-        let count: Option<u64> = state.memory_manager
+        let count: Option<u64> = memory_manager
             .database()
             .query(format!("SELECT count() AS total FROM memory WHERE memory_type = '{}'", type_str))
             .await
@@ -301,7 +405,7 @@ pub async fn get_metrics(
 
     // Get storage size from SurrealDB system information
     let storage_size_bytes: u64 = {
-        match state.memory_manager
+        match memory_manager
             .database()
             .query("INFO FOR ROOT")
             .await
@@ -338,5 +442,9 @@ pub async fn get_metrics(
     output.push_str("# TYPE memory_storage_size_bytes gauge\n");
     output.push_str(&format!("memory_storage_size_bytes {}\n", storage_size_bytes));
 
+    // Per-worker model pool status
+    output.push_str(&prometheus_worker_status(&all_worker_status()));
+    output.push_str(&prometheus_model_status(&all_model_status()));
+
     Ok(output)
 }
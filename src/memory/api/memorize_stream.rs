@@ -0,0 +1,85 @@
+//! Memorize progress over server-sent events
+//!
+//! `GET /v1/memorize/{session_id}/events` follows a memorize session started
+//! through the MCP tools. The stream opens with the session's current status
+//! as a `stage` event, sends another `stage` event for every stage change
+//! ("Loading content", "Generating embeddings", "Storing in database") as it
+//! happens, and ends with a `completed` or `failed` event. Each event's data
+//! is the session status returned by `check_memorize_status`.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::{Stream, StreamExt};
+use utoipa::OpenApi;
+
+use crate::tools::memorize_manager::{
+    MemorizeSessionManager, MemorizeStatus, MemorizeStatusResponse,
+};
+
+/// OpenAPI description of the memorize progress endpoint
+#[derive(OpenApi)]
+#[openapi(
+    paths(memorize_events),
+    tags((name = "memorize", description = "Memorize session progress")),
+)]
+pub struct MemorizeApiDoc;
+
+/// Follow a memorize session's stages as server-sent events
+#[utoipa::path(
+    get,
+    path = "/v1/memorize/{session_id}/events",
+    tag = "memorize",
+    params(("session_id" = String, Path, description = "Memorize session ID")),
+    responses(
+        (status = 200, description = "Stage events of the session until it finishes"),
+        (status = 404, description = "Session not found"),
+        (status = 503, description = "No memorize tools are served by this process"),
+    )
+)]
+pub async fn memorize_events(
+    Path(session_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let manager = crate::tools::served_memorize_manager().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No memorize tools are served by this process".to_string(),
+        )
+    })?;
+    follow_session(&manager, &session_id).await
+}
+
+/// Stage transitions of a session managed by `manager`, as server-sent events
+///
+/// # Errors
+///
+/// Returns 404 if the session does not exist (or was cleaned up).
+pub async fn follow_session(
+    manager: &Arc<MemorizeSessionManager>,
+    session_id: &str,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>> + use<>>, (StatusCode, String)> {
+    let updates = manager
+        .subscribe(session_id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+    Ok(Sse::new(updates.map(|status| Ok(sse_event(&status)))).keep_alive(KeepAlive::default()))
+}
+
+/// SSE event for one session status
+pub fn sse_event(status: &MemorizeStatusResponse) -> Event {
+    let name = match status.status {
+        MemorizeStatus::InProgress => "stage",
+        MemorizeStatus::Completed => "completed",
+        MemorizeStatus::Failed => "failed",
+    };
+    let data = serde_json::to_string(status).unwrap_or_else(|e| {
+        log::error!("Failed to encode memorize event: {}", e);
+        String::new()
+    });
+    Event::default().event(name).data(data)
+}
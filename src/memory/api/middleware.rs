@@ -17,6 +17,9 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::OnceCell;
 use tower_http::cors::{Any, CorsLayer};
+use tracing::Instrument;
+
+use crate::runtime::request_id::{REQUEST_ID_HEADER, RequestId, with_request_id};
 
 /// Security configuration errors
 #[derive(Debug, Error)]
@@ -281,6 +284,52 @@ pub async fn logging_middleware(request: Request<Body>, next: Next) -> impl Into
     response
 }
 
+/// Access logging middleware with request IDs
+///
+/// Assigns each request an ID (adopting a valid client `x-request-id`), runs
+/// the handler inside a tracing span and request ID scope, logs method, path,
+/// status and duration, and echoes the ID back in the response header.
+pub async fn access_log_middleware(request: Request<Body>, next: Next) -> Response {
+    let request_id = RequestId::from_header(
+        request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|h| h.to_str().ok()),
+    );
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let span = tracing::info_span!(
+        "http_request",
+        request_id = %request_id,
+        method = %method,
+        path = %path,
+    );
+    let start = Instant::now();
+
+    let mut response = with_request_id(request_id.clone(), next.run(request))
+        .instrument(span.clone())
+        .await;
+
+    let status = response.status().as_u16();
+    let duration_ms = start.elapsed().as_secs_f64() * 1000.0;
+    span.in_scope(|| {
+        tracing::info!(
+            target: "access",
+            request_id = %request_id,
+            method = %method,
+            path = %path,
+            status,
+            duration_ms,
+            "request completed"
+        );
+    });
+
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
 /// Production authentication middleware with JWT and API key support
 pub async fn auth_middleware(mut request: Request<Body>, next: Next) -> impl IntoResponse {
     // Extract authorization header
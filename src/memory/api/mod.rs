@@ -1,14 +1,26 @@
 //! API module for exposing memory system functionality
 //! This module is feature-gated with the "api" feature
 
+#[cfg(feature = "api")]
+pub mod chat_stream;
 #[cfg(feature = "api")]
 pub mod handlers;
+#[cfg(all(feature = "api", feature = "tools"))]
+pub mod memorize_stream;
 #[cfg(feature = "api")]
 pub mod middleware;
 #[cfg(feature = "api")]
 pub mod models;
 #[cfg(feature = "api")]
+pub mod openai;
+#[cfg(feature = "api")]
+pub mod openapi;
+#[cfg(feature = "api")]
+pub mod replay;
+#[cfg(feature = "api")]
 pub mod routes;
+#[cfg(feature = "api")]
+pub mod ws;
 
 #[cfg(feature = "api")]
 use std::net::SocketAddr;
@@ -18,9 +30,16 @@ use std::sync::Arc;
 #[cfg(feature = "api")]
 use axum::Router;
 
+#[cfg(feature = "api")]
+use tokio::task::JoinHandle;
+#[cfg(feature = "api")]
+use tokio_util::sync::CancellationToken;
+
 #[cfg(feature = "api")]
 use crate::memory::SurrealMemoryManager;
 #[cfg(feature = "api")]
+use crate::memory::core::manager::pool::CoordinatorPool;
+#[cfg(feature = "api")]
 use crate::memory::utils::config::APIConfig;
 
 /// API server for the memory system
@@ -49,6 +68,15 @@ impl APIServer {
 
     /// Start the API server
     pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
+        crate::runtime::crash::install();
+        let result = self.serve().await;
+        if let Err(e) = &result {
+            crate::runtime::crash::report_error("API server stopped", e);
+        }
+        result
+    }
+
+    async fn serve(&self) -> Result<(), Box<dyn std::error::Error>> {
         let addr = format!("{}:{}", self.config.host, self.config.port).parse::<SocketAddr>()?;
 
         log::info!("API server listening on {}", addr);
@@ -60,3 +88,113 @@ impl APIServer {
         Ok(())
     }
 }
+
+/// Address the REST API listens on next to the MCP server, e.g. `127.0.0.1:30438`
+#[cfg(feature = "api")]
+pub const API_ADDR_ENV: &str = "KODEGEN_API_ADDR";
+
+/// Address of the REST API served next to an MCP server bound to `mcp_addr`
+///
+/// [`API_ADDR_ENV`] if set, otherwise the MCP server's host on the next port.
+///
+/// # Errors
+///
+/// Returns an error if [`API_ADDR_ENV`] is not a socket address, or the MCP
+/// server is on the last port.
+#[cfg(feature = "api")]
+pub fn api_addr(mcp_addr: SocketAddr) -> anyhow::Result<SocketAddr> {
+    if let Ok(value) = std::env::var(API_ADDR_ENV) {
+        return value
+            .trim()
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid {}='{}': {}", API_ADDR_ENV, value, e));
+    }
+    let port = mcp_addr.port().checked_add(1).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No port after {} for the REST API; set {}",
+            mcp_addr.port(),
+            API_ADDR_ENV
+        )
+    })?;
+    Ok(SocketAddr::new(mcp_addr.ip(), port))
+}
+
+/// REST API served from a coordinator pool on its own listener
+///
+/// Started next to the MCP server so REST and MCP clients share the pool's
+/// libraries (see [`routes::create_pool_router`]). Served over plain HTTP.
+#[cfg(feature = "api")]
+pub struct PoolApiServer {
+    addr: SocketAddr,
+    cancel: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+#[cfg(feature = "api")]
+impl PoolApiServer {
+    /// Bind `addr` and serve the pool's REST API until shut down
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `addr` cannot be bound; one naming [`API_ADDR_ENV`]
+    /// if another process already listens there.
+    pub async fn start(addr: SocketAddr, pool: Arc<CoordinatorPool>) -> anyhow::Result<Self> {
+        let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::AddrInUse {
+                anyhow::anyhow!(
+                    "REST API address {} is already in use (it defaults to the MCP port + 1); \
+                     set {} to a free address",
+                    addr,
+                    API_ADDR_ENV
+                )
+            } else {
+                anyhow::anyhow!("Failed to bind REST API to {}: {}", addr, e)
+            }
+        })?;
+        Self::with_listener(listener, pool).map_err(Into::into)
+    }
+
+    /// Serve the pool's REST API on a pre-bound listener until shut down
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener's address cannot be read.
+    pub fn with_listener(
+        listener: tokio::net::TcpListener,
+        pool: Arc<CoordinatorPool>,
+    ) -> std::io::Result<Self> {
+        let addr = listener.local_addr()?;
+        let cancel = CancellationToken::new();
+        let stopped = cancel.clone();
+        let router = routes::create_pool_router(pool);
+
+        let task = tokio::spawn(async move {
+            let result = axum::serve(listener, router)
+                .with_graceful_shutdown(async move { stopped.cancelled().await })
+                .await;
+            if let Err(e) = result {
+                crate::runtime::crash::report_error("API server stopped", &e);
+            }
+        });
+        log::info!(
+            "Memory REST API listening on http://{}{}",
+            addr,
+            routes::API_MOUNT_PREFIX
+        );
+
+        Ok(Self { addr, cancel, task })
+    }
+
+    /// Address the API is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stop accepting connections and wait for open requests to finish
+    pub async fn shutdown(self) {
+        self.cancel.cancel();
+        if let Err(e) = self.task.await {
+            log::error!("REST API server task failed: {}", e);
+        }
+    }
+}
@@ -3,32 +3,38 @@
 
 use serde::{Deserialize, Serialize};
 use surrealdb_types::Datetime;
+use utoipa::ToSchema;
 
+use crate::capability::registry::pool::ModelLoadStatus;
 use crate::memory::primitives::types::MemoryTypeEnum;
 
 /// Request to create a new memory
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateMemoryRequest {
     pub content: String,
     pub memory_type: MemoryTypeEnum,
+    #[schema(value_type = Option<Object>)]
     pub metadata: Option<serde_json::Value>,
     pub user_id: Option<String>,
 }
 
 /// Response containing memory information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MemoryResponse {
     pub id: String,
     pub content: String,
     pub memory_type: MemoryTypeEnum,
+    #[schema(value_type = Option<Object>)]
     pub metadata: Option<serde_json::Value>,
     pub user_id: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
     pub created_at: Datetime,
+    #[schema(value_type = String, format = DateTime)]
     pub updated_at: Datetime,
 }
 
 /// Search request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SearchRequest {
     pub query: String,
     pub memory_type: Option<MemoryTypeEnum>,
@@ -38,16 +44,23 @@ pub struct SearchRequest {
 }
 
 /// Health check response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct HealthResponse {
     pub status: String,
+    #[schema(value_type = String, format = DateTime)]
     pub timestamp: Datetime,
+    /// Residency of each model: loaded, loading (e.g. reloading after a
+    /// keep-alive unload) or unloaded
+    #[serde(default)]
+    #[schema(value_type = Vec<Object>)]
+    pub models: Vec<ModelLoadStatus>,
 }
 
 /// Error response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
+    #[schema(value_type = String, format = DateTime)]
     pub timestamp: Datetime,
 }
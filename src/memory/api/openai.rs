@@ -0,0 +1,710 @@
+//! OpenAI-compatible endpoints
+//!
+//! `POST /v1/chat/completions` accepts the OpenAI request shape so agent
+//! frameworks written against that API can use a local model unchanged.
+//! Replies are returned whole; streaming chat goes over the WebSocket at
+//! `/v1/chat/ws` instead.
+//!
+//! `POST /v1/embeddings` embeds one string or an array of strings with a
+//! registered text embedding model (`model` is its registry key), so RAG
+//! stacks can use the local models through their OpenAI integrations.
+//! Token usage is counted with the model's own tokenizer.
+//!
+//! Chat messages keep their roles and are rendered through the model's chat
+//! template. `usage` and `finish_reason` come from the model; `"length"`
+//! means the reply hit `max_tokens`. Usage is estimated only when the model
+//! reports none.
+//!
+//! `tools` are offered to the model through its chat template and the tool
+//! calls it emits come back as `tool_calls`. `tool_choice` maps onto that as
+//! follows:
+//!
+//! - `"auto"` (default): every tool is offered and the model decides,
+//! - `"none"`: no tools are offered,
+//! - `"required"`: every tool is offered and the model is told to call one,
+//! - `{"type": "function", "function": {"name": ...}}`: only that tool is
+//!   offered and the model is told to call it.
+//!
+//! Local models can still ignore the instruction; such replies come back as
+//! plain text with `finish_reason: "stop"`.
+//!
+//! Errors use the OpenAI shape, `{"error": {"message", "type", "param", "code"}}`.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{Json as JsonBody, http::StatusCode, response::Json};
+use base64::Engine;
+use cyrup_sugars::ZeroOneOrMany;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use super::routes::LibraryMemory;
+use super::ws::DEFAULT_CHAT_MODEL;
+use crate::capability::registry::{self, TextEmbeddingModel};
+use crate::capability::text_to_text::ChatTurn;
+use crate::capability::traits::{TextEmbeddingCapable, TextToTextCapable};
+use crate::core::tokenizer::shared_tokenizer_cache;
+use crate::core::{EngineError, shared_model_manager};
+use crate::domain::agent::untrusted_content::sanitize_untrusted;
+use crate::domain::completion::types::ToolInfo;
+use crate::domain::completion::{CandleCompletionChunk, CandleCompletionParams};
+use crate::domain::context::FinishReason;
+use crate::domain::context::builder::estimate_tokens;
+use crate::domain::embedding::matryoshka;
+use crate::domain::model::traits::CandleModel;
+use crate::domain::prompt::CandlePrompt;
+use crate::memory::builder::DEFAULT_MEMORY_EMBEDDING_MODEL;
+
+/// One message of the conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ChatMessage {
+    /// `system`, `user`, `assistant` or `tool`
+    pub role: String,
+    /// Text, or an array of content parts of which the text parts are used
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub content: Option<Value>,
+    /// Tool calls made by an earlier assistant turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Call that a `tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    /// Text of the message, joining text content parts
+    pub fn text(&self) -> String {
+        match &self.content {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Array(parts)) => parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Function a tool exposes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FunctionDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON Schema of the arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub parameters: Option<Value>,
+}
+
+/// Tool offered to the model; only `function` tools exist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ChatTool {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: FunctionDefinition,
+}
+
+impl ChatTool {
+    fn to_tool_info(&self) -> ToolInfo {
+        let schema = match &self.function.parameters {
+            Some(Value::Object(schema)) => schema.clone(),
+            _ => serde_json::Map::from_iter([("type".to_string(), Value::from("object"))]),
+        };
+        ToolInfo {
+            name: self.function.name.clone().into(),
+            title: None,
+            description: self.function.description.clone().map(Into::into),
+            input_schema: Arc::new(schema),
+            output_schema: None,
+            annotations: None,
+            icons: None,
+            meta: None,
+        }
+    }
+}
+
+/// `tool_choice` keyword
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolChoiceMode {
+    None,
+    Auto,
+    Required,
+}
+
+/// Name of the function a forced `tool_choice` selects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FunctionName {
+    pub name: String,
+}
+
+/// Which tools the model may or must call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum ToolChoice {
+    Mode(ToolChoiceMode),
+    Function {
+        #[serde(rename = "type")]
+        kind: String,
+        function: FunctionName,
+    },
+}
+
+/// `POST /v1/chat/completions` request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChatCompletionRequest {
+    /// Registry key of the model; the chat default when omitted
+    #[serde(default)]
+    pub model: Option<String>,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub tools: Vec<ChatTool>,
+    #[serde(default)]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    #[serde(default, alias = "max_completion_tokens")]
+    pub max_tokens: Option<u64>,
+    /// Must be `false`; stream over `/v1/chat/ws`
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// Function call made by the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FunctionCall {
+    pub name: String,
+    /// Arguments as a JSON-encoded string
+    pub arguments: String,
+}
+
+/// Tool call in an assistant message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: FunctionCall,
+}
+
+/// Assistant reply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AssistantMessage {
+    pub role: String,
+    /// `null` when the reply is only tool calls
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// One generated reply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ChatChoice {
+    pub index: u32,
+    pub message: AssistantMessage,
+    /// `stop`, `length`, `tool_calls` or `content_filter`
+    pub finish_reason: String,
+}
+
+/// Token counts of a completion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Usage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// `POST /v1/chat/completions` response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ChatCompletionResponse {
+    pub id: String,
+    /// Always `chat.completion`
+    pub object: String,
+    /// Unix timestamp in seconds
+    pub created: u64,
+    pub model: String,
+    pub choices: Vec<ChatChoice>,
+    pub usage: Usage,
+}
+
+/// Error details in the OpenAI shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OpenAiErrorBody {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub param: Option<String>,
+    pub code: Option<String>,
+}
+
+/// Error response in the OpenAI shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OpenAiError {
+    pub error: OpenAiErrorBody,
+}
+
+/// Status and body of a failed OpenAI-compatible request
+pub type OpenAiErrorResponse = (StatusCode, Json<OpenAiError>);
+
+fn invalid_request(param: &str, message: impl Into<String>) -> OpenAiErrorResponse {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(OpenAiError {
+            error: OpenAiErrorBody {
+                message: message.into(),
+                kind: "invalid_request_error".to_string(),
+                param: Some(param.to_string()),
+                code: None,
+            },
+        }),
+    )
+}
+
+fn server_error(message: impl Into<String>) -> OpenAiErrorResponse {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(OpenAiError {
+            error: OpenAiErrorBody {
+                message: message.into(),
+                kind: "server_error".to_string(),
+                param: None,
+                code: None,
+            },
+        }),
+    )
+}
+
+/// Tools to offer the model under `choice`, and the instruction that forces a call
+///
+/// # Errors
+///
+/// Fails when `choice` names a function that is not among `tools`, or
+/// requires a call without any tools.
+pub fn resolve_tool_choice(
+    tools: &[ChatTool],
+    choice: Option<&ToolChoice>,
+) -> Result<(Vec<ToolInfo>, Option<String>), OpenAiErrorResponse> {
+    let all = || tools.iter().map(ChatTool::to_tool_info).collect::<Vec<_>>();
+    match choice {
+        None | Some(ToolChoice::Mode(ToolChoiceMode::Auto)) => Ok((all(), None)),
+        Some(ToolChoice::Mode(ToolChoiceMode::None)) => Ok((Vec::new(), None)),
+        Some(ToolChoice::Mode(ToolChoiceMode::Required)) => {
+            if tools.is_empty() {
+                return Err(invalid_request(
+                    "tool_choice",
+                    "tool_choice \"required\" needs at least one tool",
+                ));
+            }
+            Ok((all(), Some("You must call one of the available tools.".to_string())))
+        }
+        Some(ToolChoice::Function { function, .. }) => {
+            let tool = tools
+                .iter()
+                .find(|tool| tool.function.name == function.name)
+                .ok_or_else(|| {
+                    invalid_request(
+                        "tool_choice",
+                        format!("tool_choice names unknown function '{}'", function.name),
+                    )
+                })?;
+            Ok((
+                vec![tool.to_tool_info()],
+                Some(format!("You must call the `{}` tool.", function.name)),
+            ))
+        }
+    }
+}
+
+/// The conversation as chat turns, keeping each message's role
+///
+/// `developer` messages count as system messages and unknown roles as user
+/// messages. Tool calls made by the assistant are written back in the
+/// `<tool_call>` form the model emits them in, and tool results are screened
+/// for prompt injection. `instruction` is added as a system turn.
+pub fn conversation_turns(messages: &[ChatMessage], instruction: Option<&str>) -> Vec<ChatTurn> {
+    let mut turns: Vec<ChatTurn> = messages
+        .iter()
+        .map(|message| {
+            let mut text = message.text();
+            for call in message.tool_calls.iter().flatten() {
+                text.push_str(&format!(
+                    "<tool_call>{{\"name\": \"{}\", \"arguments\": {}}}</tool_call>",
+                    call.function.name, call.function.arguments
+                ));
+            }
+            match message.role.as_str() {
+                "system" | "developer" => ChatTurn::system(text),
+                "assistant" => ChatTurn::assistant(text),
+                // Tool results are not written by the caller's user; screen them
+                "tool" => {
+                    let sanitized = sanitize_untrusted("tool", &text);
+                    for finding in &sanitized.findings {
+                        log::warn!("Prompt injection in tool message: {finding}");
+                    }
+                    ChatTurn::tool(sanitized.text)
+                }
+                _ => ChatTurn::user(text),
+            }
+        })
+        .collect();
+    if let Some(instruction) = instruction {
+        turns.push(ChatTurn::system(instruction));
+    }
+    turns
+}
+
+/// Usage estimated from the text, for replies that ended without a count
+fn estimated_usage(turns: &[ChatTurn], completion: &str) -> Usage {
+    let prompt_tokens: usize = turns.iter().map(|turn| estimate_tokens(&turn.content)).sum();
+    let prompt_tokens = u32::try_from(prompt_tokens).unwrap_or(u32::MAX);
+    let completion_tokens = u32::try_from(estimate_tokens(completion)).unwrap_or(u32::MAX);
+    Usage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens.saturating_add(completion_tokens),
+    }
+}
+
+/// OpenAI name of a finish reason
+fn finish_reason_name(reason: FinishReason) -> &'static str {
+    match reason {
+        FinishReason::Length => "length",
+        FinishReason::ContentFilter => "content_filter",
+        FinishReason::ToolCalls => "tool_calls",
+        FinishReason::Stop | FinishReason::Error => "stop",
+    }
+}
+
+/// Create a chat completion
+#[utoipa::path(
+    post,
+    path = "/v1/chat/completions",
+    tag = "openai",
+    request_body = ChatCompletionRequest,
+    responses(
+        (status = 200, description = "Completion generated", body = ChatCompletionResponse),
+        (status = 400, description = "Invalid request", body = OpenAiError),
+        (status = 404, description = "Unknown model", body = OpenAiError),
+        (status = 500, description = "Generation failed", body = OpenAiError),
+    )
+)]
+pub async fn chat_completions(
+    JsonBody(request): JsonBody<ChatCompletionRequest>,
+) -> Result<Json<ChatCompletionResponse>, OpenAiErrorResponse> {
+    if request.stream {
+        return Err(invalid_request(
+            "stream",
+            "Streaming is not supported on this endpoint; use the /v1/chat/ws WebSocket",
+        ));
+    }
+    if request.messages.is_empty() {
+        return Err(invalid_request("messages", "messages must not be empty"));
+    }
+
+    // Requests without a model run on the default chat model
+    let managed = match shared_model_manager()
+        .route_model(request.model.as_deref(), DEFAULT_CHAT_MODEL)
+        .await
+    {
+        Ok(managed) => managed,
+        Err(EngineError::ModelNotFound) => {
+            let model_key = request.model.as_deref().unwrap_or(DEFAULT_CHAT_MODEL);
+            let message = format!("Model '{model_key}' not found in registry");
+            let (_, body) = invalid_request("model", message);
+            return Err((StatusCode::NOT_FOUND, body));
+        }
+        Err(e) => return Err(server_error(e.to_string())),
+    };
+    let model_key = managed.registry_key().to_string();
+    let Some(model) = managed.text_to_text().cloned() else {
+        return Err(invalid_request(
+            "model",
+            format!("Model '{model_key}' is not a chat model"),
+        ));
+    };
+
+    let (tools, instruction) = resolve_tool_choice(&request.tools, request.tool_choice.as_ref())?;
+    let turns = conversation_turns(&request.messages, instruction.as_deref());
+
+    let mut params = CandleCompletionParams::default();
+    if let Some(temperature) = request.temperature {
+        params.temperature = temperature;
+    }
+    params.max_tokens = request.max_tokens.and_then(std::num::NonZeroU64::new);
+    if !tools.is_empty() {
+        params.tools = Some(ZeroOneOrMany::from(tools));
+    }
+    // Every message is history; the empty prompt adds no further user turn
+    let params = params.with_history(turns.clone());
+
+    let mut stream = model.prompt(CandlePrompt::new(String::new()), &params);
+    let mut content = String::new();
+    let mut tool_calls = Vec::new();
+    let mut finish_reason = None;
+    let mut usage = None;
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            CandleCompletionChunk::Text(text) => content.push_str(&text),
+            CandleCompletionChunk::ToolCallComplete { id, name, input } => {
+                tool_calls.push(ToolCall {
+                    id: format!("call_{}", id.replace('-', "")),
+                    kind: "function".to_string(),
+                    function: FunctionCall {
+                        name,
+                        arguments: input,
+                    },
+                });
+            }
+            CandleCompletionChunk::Complete {
+                text,
+                finish_reason: reason,
+                usage: chunk_usage,
+                ..
+            } => {
+                content.push_str(&text);
+                finish_reason = reason;
+                usage = chunk_usage.map(|chunk_usage| Usage {
+                    prompt_tokens: chunk_usage.input_tokens,
+                    completion_tokens: chunk_usage.output_tokens,
+                    total_tokens: chunk_usage.total_tokens,
+                });
+            }
+            CandleCompletionChunk::Error(message) => {
+                log::error!("Chat completion with {} failed: {}", model_key, message);
+                return Err(server_error(message));
+            }
+            CandleCompletionChunk::ToolCallStart { .. } | CandleCompletionChunk::ToolCall { .. } => {}
+        }
+    }
+
+    let finish_reason = if tool_calls.is_empty() {
+        finish_reason_name(finish_reason.unwrap_or(FinishReason::Stop))
+    } else {
+        "tool_calls"
+    };
+    let usage = usage.unwrap_or_else(|| estimated_usage(&turns, &content));
+    let content = (!content.trim().is_empty() || tool_calls.is_empty()).then_some(content);
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    Ok(Json(ChatCompletionResponse {
+        id: format!("chatcmpl-{}", uuid::Uuid::new_v4().simple()),
+        object: "chat.completion".to_string(),
+        created,
+        model: model_key,
+        choices: vec![ChatChoice {
+            index: 0,
+            message: AssistantMessage {
+                role: "assistant".to_string(),
+                content,
+                tool_calls,
+            },
+            finish_reason: finish_reason.to_string(),
+        }],
+        usage,
+    }))
+}
+
+/// Text to embed: one string or a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl EmbeddingInput {
+    pub fn into_texts(self) -> Vec<String> {
+        match self {
+            Self::One(text) => vec![text],
+            Self::Many(texts) => texts,
+        }
+    }
+}
+
+/// How embedding vectors are encoded in the response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingFormat {
+    /// JSON array of floats
+    #[default]
+    Float,
+    /// Base64 of the little-endian `f32` bytes
+    Base64,
+}
+
+/// `POST /v1/embeddings` request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingRequest {
+    /// Registry key of the embedding model; the request's library's model when omitted
+    #[serde(default)]
+    pub model: Option<String>,
+    pub input: EmbeddingInput,
+    #[serde(default)]
+    pub encoding_format: EncodingFormat,
+    /// Truncate to this many dimensions and re-normalize; must be one of the
+    /// model's supported (Matryoshka) dimensions
+    #[serde(default)]
+    pub dimensions: Option<usize>,
+    /// Embedding task for models with task prompts, e.g. `query` or
+    /// `document` (not part of the OpenAI API)
+    #[serde(default)]
+    pub task: Option<String>,
+}
+
+/// Embedding vector, as floats or base64
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum EmbeddingVector {
+    Float(Vec<f32>),
+    Base64(String),
+}
+
+impl EmbeddingVector {
+    /// Encode `embedding` as `format` asks
+    pub fn encode(embedding: Vec<f32>, format: EncodingFormat) -> Self {
+        match format {
+            EncodingFormat::Float => Self::Float(embedding),
+            EncodingFormat::Base64 => {
+                let bytes: Vec<u8> = embedding.iter().flat_map(|x| x.to_le_bytes()).collect();
+                Self::Base64(base64::engine::general_purpose::STANDARD.encode(bytes))
+            }
+        }
+    }
+}
+
+/// Embedding of the input at `index`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingData {
+    /// Always `embedding`
+    pub object: String,
+    pub index: usize,
+    pub embedding: EmbeddingVector,
+}
+
+/// Token counts of an embedding request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingUsage {
+    pub prompt_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// `POST /v1/embeddings` response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingResponse {
+    /// Always `list`
+    pub object: String,
+    pub data: Vec<EmbeddingData>,
+    pub model: String,
+    pub usage: EmbeddingUsage,
+}
+
+/// Number of tokens the tokenizer of `model` splits `texts` into
+async fn count_tokens(model: &TextEmbeddingModel, texts: &[String]) -> Result<usize, String> {
+    let path = model
+        .huggingface_file(model.info().registry_key, "tokenizer.json")
+        .await
+        .map_err(|e| e.to_string())?;
+    let tokenizer = shared_tokenizer_cache()
+        .get_or_load(&path)
+        .map_err(|e| e.to_string())?;
+    let encodings = tokenizer
+        .encode_batch(texts.to_vec(), true)
+        .map_err(|e| e.to_string())?;
+    Ok(encodings.iter().map(|encoding| encoding.len()).sum())
+}
+
+/// Create embeddings
+#[utoipa::path(
+    post,
+    path = "/v1/embeddings",
+    tag = "openai",
+    params(
+        ("x-memory-library" = Option<String>, Header,
+            description = "Library whose model embeds when `model` is omitted"),
+    ),
+    request_body = EmbeddingRequest,
+    responses(
+        (status = 200, description = "Embeddings generated", body = EmbeddingResponse),
+        (status = 400, description = "Invalid request", body = OpenAiError),
+        (status = 404, description = "Unknown model", body = OpenAiError),
+        (status = 500, description = "Embedding failed", body = OpenAiError),
+    )
+)]
+pub async fn embeddings(
+    LibraryMemory(memory_manager): LibraryMemory,
+    JsonBody(request): JsonBody<EmbeddingRequest>,
+) -> Result<Json<EmbeddingResponse>, OpenAiErrorResponse> {
+    // Default to the model the request's library is embedded with
+    let library_model = memory_manager
+        .embedding_model()
+        .map(|model| model.info().registry_key);
+    let model_key = request
+        .model
+        .as_deref()
+        .or(library_model)
+        .unwrap_or(DEFAULT_MEMORY_EMBEDDING_MODEL)
+        .to_string();
+    let Some(model) = registry::get::<TextEmbeddingModel>(&model_key) else {
+        let message = format!("Embedding model '{model_key}' not found in registry");
+        let (_, body) = invalid_request("model", message);
+        return Err((StatusCode::NOT_FOUND, body));
+    };
+
+    let texts = request.input.into_texts();
+    if texts.is_empty() || texts.iter().any(String::is_empty) {
+        return Err(invalid_request("input", "input must not be empty"));
+    }
+    if let Some(dimensions) = request.dimensions
+        && let Err(e) = model.validate_dimension_request(dimensions)
+    {
+        return Err(invalid_request("dimensions", e.to_string()));
+    }
+
+    let embeddings = model
+        .chunked_batch_embed(&texts, request.task)
+        .await
+        .map_err(|e| {
+            log::error!("Embedding with {} failed: {}", model_key, e);
+            server_error(e.to_string())
+        })?;
+
+    let tokens = count_tokens(&model, &texts).await.map_err(|e| {
+        log::error!("Tokenizing with {} failed: {}", model_key, e);
+        server_error(e)
+    })?;
+    let tokens = u32::try_from(tokens).unwrap_or(u32::MAX);
+    let data = embeddings
+        .into_iter()
+        .enumerate()
+        .map(|(index, embedding)| EmbeddingData {
+            object: "embedding".to_string(),
+            index,
+            embedding: EmbeddingVector::encode(
+                match request.dimensions {
+                    Some(dimensions) => matryoshka::truncate(&embedding, dimensions),
+                    None => embedding,
+                },
+                request.encoding_format,
+            ),
+        })
+        .collect();
+
+    Ok(Json(EmbeddingResponse {
+        object: "list".to_string(),
+        data,
+        model: model_key,
+        usage: EmbeddingUsage {
+            prompt_tokens: tokens,
+            total_tokens: tokens,
+        },
+    }))
+}
@@ -0,0 +1,69 @@
+//! OpenAPI description of the memory API
+//! The spec is generated from the handler annotations and served at `/openapi.json`
+
+use axum::response::Json;
+use utoipa::OpenApi;
+
+use super::{chat_stream, handlers, openai, ws};
+use super::models::{
+    CreateMemoryRequest, ErrorResponse, HealthResponse, MemoryResponse, SearchRequest,
+};
+use crate::memory::primitives::types::MemoryTypeEnum;
+
+/// Path prefix of the current API version
+pub const API_V1_PREFIX: &str = "/v1";
+
+/// OpenAPI document for the versioned memory API
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "kodegen-candle-agent memory API", version = "1"),
+    paths(
+        handlers::create_memory,
+        handlers::get_memory,
+        handlers::update_memory,
+        handlers::delete_memory,
+        handlers::search_memories,
+        handlers::get_health,
+        handlers::get_metrics,
+        ws::interrupt_turn,
+        chat_stream::create_session,
+        chat_stream::start_turn,
+        chat_stream::resume_turn,
+        openai::chat_completions,
+        openai::embeddings,
+    ),
+    components(schemas(
+        CreateMemoryRequest,
+        MemoryResponse,
+        SearchRequest,
+        HealthResponse,
+        ErrorResponse,
+        MemoryTypeEnum,
+        chat_stream::CreateSessionRequest,
+        chat_stream::SessionResponse,
+        chat_stream::TurnRequest,
+        openai::ChatCompletionRequest,
+        openai::ChatCompletionResponse,
+        openai::EmbeddingRequest,
+        openai::EmbeddingResponse,
+        openai::OpenAiError,
+    )),
+    tags(
+        (name = "memories", description = "Memory storage and search"),
+        (name = "health", description = "Health and monitoring"),
+        (name = "chat", description = "Interactive chat sessions"),
+        (name = "openai", description = "OpenAI-compatible endpoints"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Serve the OpenAPI document
+///
+/// Includes the memorize progress endpoint when the MCP tools are built.
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    #[cfg(feature = "tools")]
+    let doc = ApiDoc::openapi().merge_from(super::memorize_stream::MemorizeApiDoc::openapi());
+    #[cfg(not(feature = "tools"))]
+    let doc = ApiDoc::openapi();
+    Json(doc)
+}
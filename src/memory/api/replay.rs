@@ -0,0 +1,195 @@
+//! Replay buffers for resumable streams
+//!
+//! A generation writes its events into a [`ReplayBuffer`], which numbers them
+//! with offsets starting at 1. A reader follows the buffer from the last
+//! offset it received, so a client that lost its connection reconnects with
+//! that offset (the SSE `Last-Event-ID`) and gets only what it missed. Only
+//! the newest events are kept; asking for older ones is a [`ReplayGap`].
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use futures::Stream;
+use parking_lot::Mutex;
+use tokio::sync::watch;
+
+use crate::async_stream::spawn_stream;
+
+/// Events kept per generation by [`ReplayBuffer::new`]
+pub const REPLAY_CAPACITY: usize = 4096;
+
+/// The events after an offset were dropped from the buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Events after offset {requested} are no longer buffered (oldest kept is {oldest})")]
+pub struct ReplayGap {
+    /// Offset the reader asked to resume after
+    pub requested: u64,
+    /// Oldest offset still buffered
+    pub oldest: u64,
+}
+
+struct ReplayState<T> {
+    events: VecDeque<(u64, T)>,
+    last_offset: u64,
+    finished: bool,
+}
+
+/// Bounded, offset-numbered log of one generation's events
+pub struct ReplayBuffer<T> {
+    state: Mutex<ReplayState<T>>,
+    capacity: usize,
+    /// Last offset written; bumped on every push and on finish
+    latest: watch::Sender<u64>,
+}
+
+impl<T> std::fmt::Debug for ReplayBuffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("ReplayBuffer")
+            .field("buffered", &state.events.len())
+            .field("last_offset", &state.last_offset)
+            .field("finished", &state.finished)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl<T> Default for ReplayBuffer<T> {
+    fn default() -> Self {
+        Self::with_capacity(REPLAY_CAPACITY)
+    }
+}
+
+impl<T> ReplayBuffer<T> {
+    /// Buffer keeping the last [`REPLAY_CAPACITY`] events
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer keeping the last `capacity` events (at least one)
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (latest, _) = watch::channel(0);
+        Self {
+            state: Mutex::new(ReplayState {
+                events: VecDeque::new(),
+                last_offset: 0,
+                finished: false,
+            }),
+            capacity: capacity.max(1),
+            latest,
+        }
+    }
+
+    /// Append an event, returning its offset
+    ///
+    /// Events pushed after [`finish`](Self::finish) are dropped and get the
+    /// last offset.
+    pub fn push(&self, event: T) -> u64 {
+        let mut state = self.state.lock();
+        if state.finished {
+            return state.last_offset;
+        }
+        state.last_offset += 1;
+        let offset = state.last_offset;
+        if state.events.len() == self.capacity {
+            state.events.pop_front();
+        }
+        state.events.push_back((offset, event));
+        drop(state);
+        self.latest.send_replace(offset);
+        offset
+    }
+
+    /// Mark the generation as complete; followers end after the last event
+    pub fn finish(&self) {
+        let mut state = self.state.lock();
+        state.finished = true;
+        let offset = state.last_offset;
+        drop(state);
+        self.latest.send_replace(offset);
+    }
+
+    /// Whether the generation is complete
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().finished
+    }
+
+    /// Offset of the newest event, 0 if none was pushed
+    pub fn last_offset(&self) -> u64 {
+        self.state.lock().last_offset
+    }
+}
+
+impl<T: Clone> ReplayBuffer<T> {
+    /// Buffered events with an offset above `offset`
+    ///
+    /// # Errors
+    ///
+    /// [`ReplayGap`] if some of those events were already dropped.
+    pub fn events_after(&self, offset: u64) -> Result<Vec<(u64, T)>, ReplayGap> {
+        let state = self.state.lock();
+        Self::collect_after(&state, offset)
+    }
+
+    fn collect_after(state: &ReplayState<T>, offset: u64) -> Result<Vec<(u64, T)>, ReplayGap> {
+        if let Some((oldest, _)) = state.events.front()
+            && offset + 1 < *oldest
+        {
+            return Err(ReplayGap {
+                requested: offset,
+                oldest: *oldest,
+            });
+        }
+        Ok(state
+            .events
+            .iter()
+            .filter(|(o, _)| *o > offset)
+            .cloned()
+            .collect())
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> ReplayBuffer<T> {
+    /// Events after `offset`, then live events until the generation finishes
+    ///
+    /// A reader that falls more than the buffer's capacity behind is ended
+    /// early; it can resume from the last offset it received.
+    ///
+    /// # Errors
+    ///
+    /// [`ReplayGap`] if events after `offset` were already dropped.
+    pub fn follow(
+        self: Arc<Self>,
+        offset: u64,
+    ) -> Result<impl Stream<Item = (u64, T)> + Send + 'static, ReplayGap> {
+        // Check up front so the caller can answer with an error status
+        self.events_after(offset)?;
+
+        let mut latest = self.latest.subscribe();
+        Ok(spawn_stream(move |tx| async move {
+            let mut offset = offset;
+            loop {
+                latest.borrow_and_update();
+                let (events, finished) = {
+                    let state = self.state.lock();
+                    match Self::collect_after(&state, offset) {
+                        Ok(events) => (events, state.finished),
+                        Err(gap) => {
+                            log::debug!("Replay reader fell behind: {}", gap);
+                            return;
+                        }
+                    }
+                };
+                for event in events {
+                    offset = event.0;
+                    if tx.send(event).is_err() {
+                        return;
+                    }
+                }
+                if finished || latest.changed().await.is_err() {
+                    return;
+                }
+            }
+        }))
+    }
+}
@@ -5,31 +5,109 @@ use std::sync::{Arc, RwLock};
 
 use axum::{
     Router,
+    extract::FromRequestParts,
+    http::{StatusCode, request::Parts},
+    middleware,
     routing::{delete, get, post, put},
 };
 
+use super::chat_stream::{create_session, resume_turn, start_turn};
 use super::handlers::{
     create_memory, delete_memory, get_health, get_memory, get_metrics, search_memories,
     update_memory,
 };
+use super::middleware::access_log_middleware;
+use super::openai::{chat_completions, embeddings};
+use super::openapi::{API_V1_PREFIX, openapi_json};
+use super::ws::{ChatSessions, chat_socket, interrupt_turn};
 use crate::memory::SurrealMemoryManager;
+use crate::memory::core::manager::pool::CoordinatorPool;
+use crate::memory::utils::Error;
+
+/// Header naming the memory library a request reads and writes
+pub const LIBRARY_HEADER: &str = "x-memory-library";
+
+/// Library of requests without a [`LIBRARY_HEADER`]
+pub const DEFAULT_API_LIBRARY: &str = "default";
+
+/// Path the REST API is nested under when served from the MCP server's
+/// coordinator pool
+pub const API_MOUNT_PREFIX: &str = "/api";
+
+/// Where requests get the storage of their memory library
+#[derive(Clone)]
+pub enum MemorySource {
+    /// One storage manager, whichever library a request names
+    Manager(Arc<SurrealMemoryManager>),
+    /// The library a request names, opened on first use through the pool
+    Pool(Arc<CoordinatorPool>),
+}
+
+impl MemorySource {
+    /// Storage manager of `library`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the library name is invalid or its coordinator
+    /// cannot be created.
+    pub async fn manager(
+        &self,
+        library: &str,
+    ) -> crate::memory::utils::Result<Arc<SurrealMemoryManager>> {
+        match self {
+            Self::Manager(manager) => Ok(Arc::clone(manager)),
+            Self::Pool(pool) => Ok(pool.get_coordinator(library).await?.memory_manager()),
+        }
+    }
+}
+
+/// Storage manager of the library named by the request's [`LIBRARY_HEADER`]
+pub struct LibraryMemory(pub Arc<SurrealMemoryManager>);
+
+impl FromRequestParts<AppState> for LibraryMemory {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let library = match parts.headers.get(LIBRARY_HEADER) {
+            Some(value) => value
+                .to_str()
+                .map(str::trim)
+                .map_err(|_| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        format!("{LIBRARY_HEADER} must be visible ASCII"),
+                    )
+                })?,
+            None => DEFAULT_API_LIBRARY,
+        };
+        match state.memory.manager(library).await {
+            Ok(manager) => Ok(Self(manager)),
+            Err(Error::InvalidInput(message)) => Err((StatusCode::BAD_REQUEST, message)),
+            Err(e) => {
+                log::error!("Failed to open memory library '{}': {}", library, e);
+                Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to open memory library '{library}'"),
+                ))
+            }
+        }
+    }
+}
 
 /// Combined application state
 #[derive(Clone)]
 pub struct AppState {
-    pub memory_manager: Arc<SurrealMemoryManager>,
+    pub memory: MemorySource,
     pub last_search_latency: Arc<RwLock<f64>>,
+    pub chat_sessions: ChatSessions,
 }
 
-/// Create the main API router
-pub fn create_router(memory_manager: Arc<SurrealMemoryManager>) -> Router {
-    // Create combined application state
-    let state = AppState {
-        memory_manager,
-        last_search_latency: Arc::new(RwLock::new(0.0_f64)),
-    };
-    
-    Router::new()
+/// Version 1 endpoints, mounted under [`API_V1_PREFIX`]
+fn v1_routes() -> Router<AppState> {
+    let routes = Router::new()
         // Memory operations
         .route("/memories", post(create_memory))
         .route("/memories/{id}", get(get_memory))
@@ -39,6 +117,67 @@ pub fn create_router(memory_manager: Arc<SurrealMemoryManager>) -> Router {
         // Health and monitoring
         .route("/health", get(get_health))
         .route("/metrics", get(get_metrics))
+        // Interactive chat
+        .route("/chat/ws", get(chat_socket))
+        .route("/chat/sessions", post(create_session))
+        .route("/chat/sessions/{session_id}/turns", post(start_turn))
+        .route(
+            "/chat/sessions/{session_id}/turns/{turn}/events",
+            get(resume_turn),
+        )
+        .route(
+            "/chat/sessions/{session_id}/turns/{turn}/interrupt",
+            post(interrupt_turn),
+        )
+        // OpenAI-compatible
+        .route("/chat/completions", post(chat_completions))
+        .route("/embeddings", post(embeddings));
+    // Progress of memorize sessions started through the MCP tools
+    #[cfg(feature = "tools")]
+    let routes = routes.route(
+        "/memorize/{session_id}/events",
+        get(super::memorize_stream::memorize_events),
+    );
+    routes
+}
+
+/// Create the main API router
+///
+/// Endpoints are served under `/v1`; the unversioned paths remain as
+/// aliases of v1 for existing clients. The OpenAPI spec is at `/openapi.json`.
+/// Every request gets an access log line and an `x-request-id`.
+/// Browser chat clients connect to the WebSocket at `/v1/chat/ws`; OpenAI
+/// clients can use `/v1/chat/completions` and `/v1/embeddings`.
+pub fn create_router(memory_manager: Arc<SurrealMemoryManager>) -> Router {
+    router_for(MemorySource::Manager(memory_manager))
+}
+
+/// API router whose requests get their storage from `memory`
+fn router_for(memory: MemorySource) -> Router {
+    // Create combined application state
+    let state = AppState {
+        memory,
+        last_search_latency: Arc::new(RwLock::new(0.0_f64)),
+        chat_sessions: ChatSessions::new(),
+    };
+
+    Router::new()
+        .nest(API_V1_PREFIX, v1_routes())
+        .merge(v1_routes())
+        .route("/openapi.json", get(openapi_json))
+        .layer(middleware::from_fn(access_log_middleware))
         // Inject combined application state
         .with_state(state)
 }
+
+/// Create the API router served from a shared coordinator pool
+///
+/// Each request reads and writes the library named by its
+/// [`LIBRARY_HEADER`] ([`DEFAULT_API_LIBRARY`] without one), through the
+/// library's pooled coordinator, so REST and MCP clients share databases
+/// without opening them twice. Libraries are opened on first use. Routes
+/// are nested under [`API_MOUNT_PREFIX`]; [`super::PoolApiServer`] serves
+/// them next to the MCP server.
+pub fn create_pool_router(pool: Arc<CoordinatorPool>) -> Router {
+    Router::new().nest(API_MOUNT_PREFIX, router_for(MemorySource::Pool(pool)))
+}
@@ -0,0 +1,609 @@
+//! WebSocket chat endpoint
+//!
+//! `GET /v1/chat/ws` upgrades to a WebSocket carrying JSON text frames in
+//! both directions. The client opens or resumes a session with `hello`,
+//! sends `message` frames, and may send `cancel` while a reply is streaming.
+//! The server answers with `session`, then one `chunk` frame per agent event
+//! (text, tool-call events, completion stats) and a closing `done`.
+//!
+//! Sessions outlive the socket for [`SESSION_TTL`], so a browser that drops
+//! its connection can reconnect with the same `session_id` and keep the
+//! conversation. The server pings every [`PING_INTERVAL`] and closes sockets
+//! that miss two pongs in a row.
+//!
+//! A streaming turn can also be stopped over HTTP with
+//! `POST /v1/chat/sessions/{session_id}/turns/{turn}/interrupt`; the socket
+//! then receives the partial reply's `done` frame with `cancelled: true`.
+//!
+//! Every turn streams into a [`ReplayBuffer`] kept until the session's next
+//! turn, which the resumable SSE endpoints in [`super::chat_stream`] read.
+//!
+//! Turns run through the agent's chat path, so they get memory recall, tool
+//! calls and storage like any other chat. The session's earlier turns are
+//! handed over as role-tagged conversation history, capped at the latest
+//! [`MAX_HISTORY_TURNS`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{
+        Path, Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::StatusCode,
+    response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::Instrument;
+
+use super::replay::ReplayBuffer;
+use super::routes::AppState;
+use crate::builders::agent_role::{CandleAgentBuilder, CandleAgentRoleBuilder, CandleFluentAi};
+use crate::capability::registry::{self, TextToTextModel};
+use crate::domain::chat::interrupt::TurnInterrupt;
+use crate::domain::chat::message::{CandleMessageChunk, CandleMessageRole};
+use crate::domain::event::AgentEvent;
+use crate::runtime::spawn_with_request_id;
+
+/// Model used when neither the query string nor `hello` names one
+pub const DEFAULT_CHAT_MODEL: &str = "Qwen/Qwen2.5-Coder-3B-Instruct-GGUF";
+
+/// How long an idle session is kept for resumption
+pub const SESSION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Interval between server pings
+pub const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Earlier turns of a session handed to the agent with each new turn
+pub const MAX_HISTORY_TURNS: usize = 32;
+
+/// Agent role that answers chat turns
+const CHAT_AGENT_ROLE: &str = "chat";
+
+/// Frame sent by the client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientFrame {
+    /// Open a new session, or resume `session_id` if it is still alive
+    Hello {
+        #[serde(default)]
+        session_id: Option<String>,
+        #[serde(default)]
+        model: Option<String>,
+    },
+    /// User message starting a new turn
+    Message { content: String },
+    /// Stop the turn that is currently streaming, or `turn` if given
+    Cancel {
+        #[serde(default)]
+        turn: Option<u64>,
+    },
+    /// Application-level keepalive for clients that cannot send ping frames
+    Ping,
+}
+
+/// Frame sent by the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerFrame {
+    /// Session opened or resumed
+    Session {
+        session_id: String,
+        resumed: bool,
+        turns: u64,
+    },
+    /// One event of `turn`'s reply
+    Chunk { turn: u64, chunk: AgentEvent },
+    /// `turn` finished, or was cancelled by the client
+    Done { turn: u64, cancelled: bool },
+    /// Protocol or model error; the socket stays open
+    Error { message: String },
+    /// Reply to a client `ping`
+    Pong,
+}
+
+/// One event of a turn, as kept in its replay buffer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TurnEvent {
+    /// One event of the reply
+    Chunk { chunk: AgentEvent },
+    /// The turn finished, or was cancelled
+    Done { cancelled: bool },
+}
+
+/// Why a turn could not start
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StartTurnError {
+    #[error("Session '{0}' has expired")]
+    SessionExpired(String),
+    #[error("A turn is already streaming; send cancel first")]
+    TurnStreaming,
+    /// The turn was recorded and its buffer holds a `done` event
+    #[error("Model '{model}' not found in registry")]
+    ModelNotFound { model: String, turn: u64 },
+}
+
+/// One recorded turn of the conversation
+struct Exchange {
+    turn: u64,
+    user: String,
+    assistant: String,
+    /// The reply was stopped before it finished; `assistant` is partial
+    interrupted: bool,
+}
+
+struct ChatSession {
+    model: String,
+    history: Vec<Exchange>,
+    turns: u64,
+    last_seen: Instant,
+    /// Turn currently streaming and the handle that stops it
+    active: Option<(u64, TurnInterrupt)>,
+    /// Events of the latest turn, kept for resumption until the next turn
+    generation: Option<(u64, Arc<ReplayBuffer<TurnEvent>>)>,
+}
+
+/// Chat sessions kept across WebSocket connections
+#[derive(Clone, Default)]
+pub struct ChatSessions {
+    sessions: Arc<Mutex<HashMap<String, ChatSession>>>,
+}
+
+impl ChatSessions {
+    /// Empty session store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resume `session_id` if it is still alive, otherwise start a new session
+    ///
+    /// Returns the session ID, whether it was resumed, and its turn count.
+    pub fn open(&self, session_id: Option<&str>, model: Option<&str>) -> (String, bool, u64) {
+        let mut sessions = self.sessions.lock();
+        sessions.retain(|_, session| session.last_seen.elapsed() < SESSION_TTL);
+
+        if let Some(id) = session_id
+            && let Some(session) = sessions.get_mut(id)
+        {
+            if let Some(model) = model {
+                session.model = model.to_string();
+            }
+            session.last_seen = Instant::now();
+            return (id.to_string(), true, session.turns);
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        sessions.insert(
+            id.clone(),
+            ChatSession {
+                model: model.unwrap_or(DEFAULT_CHAT_MODEL).to_string(),
+                history: Vec::new(),
+                turns: 0,
+                last_seen: Instant::now(),
+                active: None,
+                generation: None,
+            },
+        );
+        (id, false, 0)
+    }
+
+    /// Number of live sessions
+    pub fn len(&self) -> usize {
+        self.sessions.lock().len()
+    }
+
+    /// Whether no sessions are live
+    pub fn is_empty(&self) -> bool {
+        self.sessions.lock().is_empty()
+    }
+
+    /// Start a turn: returns its number, the model key, the earlier turns,
+    /// the handle that stops it and the buffer its events go to
+    #[allow(clippy::type_complexity)]
+    fn begin_turn(
+        &self,
+        session_id: &str,
+    ) -> Result<
+        (
+            u64,
+            String,
+            Vec<(CandleMessageRole, String)>,
+            TurnInterrupt,
+            Arc<ReplayBuffer<TurnEvent>>,
+        ),
+        StartTurnError,
+    > {
+        let mut sessions = self.sessions.lock();
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| StartTurnError::SessionExpired(session_id.to_string()))?;
+        if session.active.is_some() {
+            return Err(StartTurnError::TurnStreaming);
+        }
+        session.turns += 1;
+        session.last_seen = Instant::now();
+        let interrupt = TurnInterrupt::new();
+        session.active = Some((session.turns, interrupt.clone()));
+        let buffer = Arc::new(ReplayBuffer::new());
+        session.generation = Some((session.turns, Arc::clone(&buffer)));
+
+        let mut history = Vec::new();
+        for exchange in &session.history {
+            history.push((CandleMessageRole::User, exchange.user.clone()));
+            if !exchange.assistant.is_empty() {
+                history.push((CandleMessageRole::Assistant, exchange.assistant.clone()));
+            }
+        }
+
+        Ok((session.turns, session.model.clone(), history, interrupt, buffer))
+    }
+
+    /// Start a turn of `session_id` answering `content`
+    ///
+    /// The agent's reply streams into the returned buffer in a background
+    /// task that keeps running when readers go away; it ends with a
+    /// [`TurnEvent::Done`] and is recorded in the session history.
+    ///
+    /// # Errors
+    ///
+    /// [`StartTurnError`] if the session is gone, already streaming, or its
+    /// model is not registered.
+    pub fn start_turn(
+        &self,
+        session_id: &str,
+        content: String,
+    ) -> Result<(u64, Arc<ReplayBuffer<TurnEvent>>), StartTurnError> {
+        let (turn, model_key, history, interrupt, buffer) = self.begin_turn(session_id)?;
+
+        let Some(model) = registry::get::<TextToTextModel>(&model_key) else {
+            self.record_turn(session_id, &content, "", false);
+            buffer.push(TurnEvent::Done { cancelled: false });
+            buffer.finish();
+            return Err(StartTurnError::ModelNotFound {
+                model: model_key,
+                turn,
+            });
+        };
+
+        let sessions = self.clone();
+        let session_id = session_id.to_string();
+        let events = Arc::clone(&buffer);
+        spawn_with_request_id(async move {
+            let mut reply = String::new();
+            match CandleFluentAi::agent_role(CHAT_AGENT_ROLE).into_agent() {
+                Ok(agent) => {
+                    // The agent stops the stream itself once interrupted
+                    let mut stream = agent
+                        .model(model)
+                        .session_id(session_id.clone())
+                        .conversation_history(history)
+                        .interruptible(interrupt.clone())
+                        .chat_with_message(content.clone());
+                    while let Some(chunk) = stream.next().await {
+                        match &chunk {
+                            CandleMessageChunk::Text(text) => reply.push_str(text),
+                            CandleMessageChunk::Complete { text, .. } => reply.push_str(text),
+                            _ => {}
+                        }
+                        events.push(TurnEvent::Chunk {
+                            chunk: AgentEvent::from(chunk),
+                        });
+                    }
+                }
+                Err(e) => {
+                    events.push(TurnEvent::Chunk {
+                        chunk: AgentEvent::error(e.to_string()),
+                    });
+                }
+            }
+            let cancelled = interrupt.is_interrupted();
+            sessions.record_turn(&session_id, &content, &reply, cancelled);
+            events.push(TurnEvent::Done { cancelled });
+            events.finish();
+        });
+
+        Ok((turn, buffer))
+    }
+
+    /// Replay buffer of `turn`, if it is the latest turn of `session_id`
+    pub fn generation(&self, session_id: &str, turn: u64) -> Option<Arc<ReplayBuffer<TurnEvent>>> {
+        let mut sessions = self.sessions.lock();
+        let session = sessions.get_mut(session_id)?;
+        session.last_seen = Instant::now();
+        match &session.generation {
+            Some((latest, buffer)) if *latest == turn => Some(Arc::clone(buffer)),
+            _ => None,
+        }
+    }
+
+    /// Stop the streaming turn of `session_id`
+    ///
+    /// With `turn` set, only that turn is stopped. Returns whether a turn was
+    /// streaming and has now been interrupted.
+    pub fn interrupt(&self, session_id: &str, turn: Option<u64>) -> bool {
+        let sessions = self.sessions.lock();
+        match sessions.get(session_id).and_then(|s| s.active.as_ref()) {
+            Some((active, interrupt)) if turn.is_none_or(|t| t == *active) => {
+                let stopped = !interrupt.is_interrupted();
+                interrupt.interrupt();
+                stopped
+            }
+            _ => false,
+        }
+    }
+
+    /// Record a finished or interrupted turn in the session history
+    ///
+    /// An interrupted turn keeps the partial reply streamed before the stop
+    /// and is listed by [`interrupted_turns`](Self::interrupted_turns). Only
+    /// the latest [`MAX_HISTORY_TURNS`] turns are kept.
+    pub fn record_turn(&self, session_id: &str, user: &str, assistant: &str, interrupted: bool) {
+        if let Some(session) = self.sessions.lock().get_mut(session_id) {
+            let turn = session.active.take().map_or(session.turns, |(turn, _)| turn);
+            session.history.push(Exchange {
+                turn,
+                user: user.to_string(),
+                assistant: assistant.to_string(),
+                interrupted,
+            });
+            let excess = session.history.len().saturating_sub(MAX_HISTORY_TURNS);
+            session.history.drain(..excess);
+            session.last_seen = Instant::now();
+        }
+    }
+
+    /// Messages recorded for `session_id`, user and assistant interleaved
+    pub fn history(&self, session_id: &str) -> Vec<String> {
+        self.sessions
+            .lock()
+            .get(session_id)
+            .map(|session| {
+                session
+                    .history
+                    .iter()
+                    .flat_map(|exchange| [&exchange.user, &exchange.assistant])
+                    .filter(|text| !text.is_empty())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Turns of `session_id` whose reply was interrupted before it finished
+    pub fn interrupted_turns(&self, session_id: &str) -> Vec<u64> {
+        self.sessions
+            .lock()
+            .get(session_id)
+            .map(|session| {
+                session
+                    .history
+                    .iter()
+                    .filter(|exchange| exchange.interrupted)
+                    .map(|exchange| exchange.turn)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Query parameters accepted on the upgrade request
+#[derive(Debug, Default, Deserialize)]
+pub struct ChatSocketQuery {
+    /// Session to resume
+    pub session_id: Option<String>,
+    /// Registry key of the model to chat with
+    pub model: Option<String>,
+}
+
+/// Upgrade to a chat WebSocket
+pub async fn chat_socket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(query): Query<ChatSocketQuery>,
+) -> Response {
+    // The socket outlives the upgrade request; keep its request ID and span
+    let request_id = crate::runtime::current_request_id();
+    let span = tracing::Span::current();
+    ws.on_upgrade(move |socket| {
+        crate::runtime::with_optional_request_id(
+            request_id,
+            handle_socket(socket, state.chat_sessions, query),
+        )
+        .instrument(span)
+    })
+}
+
+/// Stop a streaming turn
+///
+/// Responds 204 when the turn was interrupted and 404 when it is not
+/// streaming (already finished, or unknown session).
+#[utoipa::path(
+    post,
+    path = "/v1/chat/sessions/{session_id}/turns/{turn}/interrupt",
+    tag = "chat",
+    params(
+        ("session_id" = String, Path, description = "Chat session ID"),
+        ("turn" = u64, Path, description = "Turn number from the session's frames"),
+    ),
+    responses(
+        (status = 204, description = "Turn interrupted"),
+        (status = 404, description = "No such turn streaming"),
+    )
+)]
+pub async fn interrupt_turn(
+    State(state): State<AppState>,
+    Path((session_id, turn)): Path<(String, u64)>,
+) -> StatusCode {
+    if state.chat_sessions.interrupt(&session_id, Some(turn)) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+async fn handle_socket(socket: WebSocket, sessions: ChatSessions, query: ChatSocketQuery) {
+    let (mut sink, mut incoming) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<ServerFrame>();
+
+    // A session is opened immediately; a later `hello` may switch to another one
+    let (mut session_id, resumed, turns) =
+        sessions.open(query.session_id.as_deref(), query.model.as_deref());
+    let _ = tx.send(ServerFrame::Session {
+        session_id: session_id.clone(),
+        resumed,
+        turns,
+    });
+
+    let mut active: Option<u64> = None;
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    ping.tick().await;
+    let mut missed_pongs = 0u8;
+
+    loop {
+        tokio::select! {
+            Some(frame) = rx.recv() => {
+                if let ServerFrame::Done { turn, .. } = &frame
+                    && active == Some(*turn)
+                {
+                    active = None;
+                }
+                let text = match serde_json::to_string(&frame) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        log::error!("Failed to encode chat frame: {}", e);
+                        continue;
+                    }
+                };
+                if sink.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            _ = ping.tick() => {
+                if missed_pongs >= 2 {
+                    log::debug!("Closing chat socket {} after missed pongs", session_id);
+                    break;
+                }
+                missed_pongs += 1;
+                if sink.send(Message::Ping(Vec::new().into())).await.is_err() {
+                    break;
+                }
+            }
+            message = incoming.next() => {
+                let Some(Ok(message)) = message else { break };
+                let text = match message {
+                    Message::Text(text) => text,
+                    Message::Pong(_) => {
+                        missed_pongs = 0;
+                        continue;
+                    }
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                missed_pongs = 0;
+
+                let frame = match serde_json::from_str::<ClientFrame>(&text) {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        let _ = tx.send(ServerFrame::Error { message: format!("Invalid frame: {e}") });
+                        continue;
+                    }
+                };
+
+                match frame {
+                    ClientFrame::Hello { session_id: requested, model } => {
+                        if active.is_some() {
+                            let _ = tx.send(ServerFrame::Error {
+                                message: "Cannot switch sessions while a turn is streaming".to_string(),
+                            });
+                            continue;
+                        }
+                        let (id, resumed, turns) =
+                            sessions.open(requested.as_deref(), model.as_deref());
+                        session_id = id.clone();
+                        let _ = tx.send(ServerFrame::Session { session_id: id, resumed, turns });
+                    }
+                    ClientFrame::Message { content } => {
+                        if active.is_some() {
+                            let _ = tx.send(ServerFrame::Error {
+                                message: "A turn is already streaming; send cancel first".to_string(),
+                            });
+                            continue;
+                        }
+                        active = start_turn(&sessions, &session_id, content, tx.clone());
+                    }
+                    ClientFrame::Cancel { turn } => {
+                        // The turn's task sends `done` once it has stopped
+                        if !sessions.interrupt(&session_id, turn) {
+                            let _ = tx.send(ServerFrame::Error {
+                                message: "No turn is streaming".to_string(),
+                            });
+                        }
+                    }
+                    ClientFrame::Ping => {
+                        let _ = tx.send(ServerFrame::Pong);
+                    }
+                }
+            }
+        }
+    }
+
+    // The session stays resumable; only the in-flight turn is stopped
+    if active.is_some() {
+        sessions.interrupt(&session_id, active);
+    }
+}
+
+/// Start a turn and forward its events into `tx`
+///
+/// Returns the turn number, or `None` when the turn could not start.
+fn start_turn(
+    sessions: &ChatSessions,
+    session_id: &str,
+    content: String,
+    tx: mpsc::UnboundedSender<ServerFrame>,
+) -> Option<u64> {
+    let (turn, buffer) = match sessions.start_turn(session_id, content) {
+        Ok(started) => started,
+        Err(e) => {
+            let _ = tx.send(ServerFrame::Error {
+                message: e.to_string(),
+            });
+            if let StartTurnError::ModelNotFound { turn, .. } = e {
+                let _ = tx.send(ServerFrame::Done { turn, cancelled: false });
+            }
+            return None;
+        }
+    };
+
+    let events = match buffer.follow(0) {
+        Ok(events) => events,
+        Err(e) => {
+            log::error!("Failed to follow turn {}: {}", turn, e);
+            return None;
+        }
+    };
+    let sessions = sessions.clone();
+    let session_id = session_id.to_string();
+    spawn_with_request_id(async move {
+        tokio::pin!(events);
+        while let Some((_, event)) = events.next().await {
+            let frame = match event {
+                TurnEvent::Chunk { chunk } => ServerFrame::Chunk { turn, chunk },
+                TurnEvent::Done { cancelled } => ServerFrame::Done { turn, cancelled },
+            };
+            // The socket is gone; stop the turn and keep what was streamed so far
+            if tx.send(frame).is_err() {
+                sessions.interrupt(&session_id, Some(turn));
+                break;
+            }
+        }
+    });
+
+    Some(turn)
+}
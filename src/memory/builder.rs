@@ -0,0 +1,120 @@
+//! Standalone memory without the MCP server
+//!
+//! The server opens libraries through a [`CoordinatorPool`] shared by every
+//! tool. A program that just wants local semantic memory can open one
+//! library directly:
+//!
+//! ```no_run
+//! use kodegen_candle_agent::memory::Memory;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let memory = Memory::library("notes")
+//!     .at_path("./data/notes.db")
+//!     .build()
+//!     .await?;
+//!
+//! let hits = memory.search_memories("release checklist", 5, None).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Without [`MemoryBuilder::at_path`] the library is stored where the server
+//! keeps it, so both see the same memories.
+//!
+//! [`CoordinatorPool`]: crate::memory::core::manager::pool::CoordinatorPool
+
+use std::path::PathBuf;
+
+use crate::capability::registry::{self, TextEmbeddingModel};
+use crate::memory::core::manager::coordinator::MemoryCoordinator;
+use crate::memory::utils::{Error, Result};
+
+/// Embedding model used unless [`MemoryBuilder::embedding_model`] is called
+pub const DEFAULT_MEMORY_EMBEDDING_MODEL: &str = "dunzhang/stella_en_400M_v5";
+
+/// Entry point for opening a memory library
+pub struct Memory;
+
+impl Memory {
+    /// Start configuring the library named `name`
+    ///
+    /// ```no_run
+    /// use kodegen_candle_agent::memory::Memory;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let notes = Memory::library("notes").build().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn library(name: impl Into<String>) -> MemoryBuilder {
+        MemoryBuilder {
+            library: name.into(),
+            embedding_model: DEFAULT_MEMORY_EMBEDDING_MODEL.to_string(),
+            path: None,
+        }
+    }
+}
+
+/// Options for a memory library, from [`Memory::library`]
+#[derive(Debug, Clone)]
+pub struct MemoryBuilder {
+    library: String,
+    embedding_model: String,
+    path: Option<PathBuf>,
+}
+
+impl MemoryBuilder {
+    /// Embed memories with the registered model `registry_key`
+    ///
+    /// A library must keep the model it was created with; switching models
+    /// leaves existing embeddings incomparable with new ones.
+    #[must_use]
+    pub fn embedding_model(mut self, registry_key: impl Into<String>) -> Self {
+        self.embedding_model = registry_key.into();
+        self
+    }
+
+    /// Store the library's database at `path` instead of the kodegen data directory
+    #[must_use]
+    pub fn at_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Open (or create) the library
+    ///
+    /// # Errors
+    ///
+    /// Fails when the embedding model is not registered, the library name is
+    /// invalid or the database cannot be opened.
+    ///
+    /// ```no_run
+    /// use kodegen_candle_agent::memory::Memory;
+    /// use kodegen_candle_agent::domain::memory::primitives::types::MemoryTypeEnum;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let memory = Memory::library("notes")
+    ///     .embedding_model("dunzhang/stella_en_400M_v5")
+    ///     .at_path("/tmp/notes.db")
+    ///     .build()
+    ///     .await?;
+    ///
+    /// let note = "Ship on Fridays only after the smoke tests pass".to_string();
+    /// memory.add_memory(note, MemoryTypeEnum::LongTerm, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn build(self) -> Result<MemoryCoordinator> {
+        let model = registry::get::<TextEmbeddingModel>(&self.embedding_model).ok_or_else(|| {
+            Error::Embedding(format!(
+                "Embedding model '{}' is not registered",
+                self.embedding_model
+            ))
+        })?;
+
+        match self.path {
+            Some(path) => MemoryCoordinator::from_library_at(&self.library, &path, model).await,
+            None => MemoryCoordinator::from_library(&self.library, model).await,
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! Code-aware splitting of source files
+//!
+//! Files are cut at top-level definitions (functions, classes, impls, ...)
+//! found with per-language line heuristics, keeping leading doc comments,
+//! attributes and decorators with the definition they describe. Definitions
+//! larger than the size budget are cut again at nested definitions, then at
+//! blank lines; small neighbours are merged so helpers don't become
+//! one-line memories. Chunks are embedded as plain documents; queries about
+//! code of a language can use its embedding task (see
+//! [`CodeLanguage::embedding_task`]).
+
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::memory::core::primitives::chunk::MemoryChunk;
+
+/// Default upper bound on chunk size in bytes (about 512 Stella tokens of code)
+pub const DEFAULT_MAX_CHUNK_BYTES: usize = 1_500;
+
+/// Default size below which a chunk is merged with its neighbour
+pub const DEFAULT_MIN_CHUNK_BYTES: usize = 200;
+
+/// Languages recognized by the code splitter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    Java,
+    Kotlin,
+    C,
+    Cpp,
+    CSharp,
+    Ruby,
+    Swift,
+    Php,
+}
+
+impl CodeLanguage {
+    /// All recognized languages
+    pub const ALL: &'static [CodeLanguage] = &[
+        Self::Rust,
+        Self::Python,
+        Self::JavaScript,
+        Self::TypeScript,
+        Self::Go,
+        Self::Java,
+        Self::Kotlin,
+        Self::C,
+        Self::Cpp,
+        Self::CSharp,
+        Self::Ruby,
+        Self::Swift,
+        Self::Php,
+    ];
+
+    /// Language for a file extension (without the dot, case-insensitive)
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let language = match extension.to_ascii_lowercase().as_str() {
+            "rs" => Self::Rust,
+            "py" | "pyi" => Self::Python,
+            "js" | "jsx" | "mjs" | "cjs" => Self::JavaScript,
+            "ts" | "tsx" | "mts" | "cts" => Self::TypeScript,
+            "go" => Self::Go,
+            "java" => Self::Java,
+            "kt" | "kts" => Self::Kotlin,
+            "c" | "h" => Self::C,
+            "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => Self::Cpp,
+            "cs" => Self::CSharp,
+            "rb" => Self::Ruby,
+            "swift" => Self::Swift,
+            "php" => Self::Php,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Language for a file path, by extension
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        path.as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "Rust",
+            Self::Python => "Python",
+            Self::JavaScript => "JavaScript",
+            Self::TypeScript => "TypeScript",
+            Self::Go => "Go",
+            Self::Java => "Java",
+            Self::Kotlin => "Kotlin",
+            Self::C => "C",
+            Self::Cpp => "C++",
+            Self::CSharp => "C#",
+            Self::Ruby => "Ruby",
+            Self::Swift => "Swift",
+            Self::Php => "PHP",
+        }
+    }
+
+    /// Embedding task for recall queries about code of this language
+    pub fn embedding_task(self) -> &'static str {
+        match self {
+            Self::Rust => "code:rust",
+            Self::Python => "code:python",
+            Self::JavaScript => "code:javascript",
+            Self::TypeScript => "code:typescript",
+            Self::Go => "code:go",
+            Self::Java => "code:java",
+            Self::Kotlin => "code:kotlin",
+            Self::C => "code:c",
+            Self::Cpp => "code:cpp",
+            Self::CSharp => "code:csharp",
+            Self::Ruby => "code:ruby",
+            Self::Swift => "code:swift",
+            Self::Php => "code:php",
+        }
+    }
+
+    /// Regex matching the start of a definition, applied to a line without
+    /// its indentation
+    fn definition_pattern(self) -> &'static str {
+        match self {
+            Self::Rust => {
+                r#"^(pub(\([^)]*\))?\s+)?((default|const|async|unsafe|extern(\s+"[^"]*")?)\s+)*(fn|struct|enum|union|trait|impl|mod|type|macro_rules!)\b"#
+            }
+            Self::Python => r"^(async\s+)?(def|class)\s",
+            Self::JavaScript | Self::TypeScript => {
+                r"^(export\s+(default\s+)?)?(declare\s+)?(abstract\s+)?((async\s+)?function\b|class\b|interface\b|enum\b|namespace\b|type\s+\w+|(const|let)\s+\w+\s*=\s*(async\s+)?(\(|function\b))"
+            }
+            Self::Go => r"^(func|type)\s",
+            Self::Java | Self::CSharp => {
+                r"^((public|private|protected|internal|static|final|abstract|sealed|partial|synchronized|async|virtual|override)\s+)*((class|interface|enum|record|struct)\s|[\w<>\[\],.?]+\s+\w+\s*\([^;]*$)"
+            }
+            Self::Kotlin => {
+                r"^((public|private|protected|internal|open|abstract|sealed|data|inline|suspend|override)\s+)*(class|interface|object|fun|enum\s+class)\b"
+            }
+            Self::C | Self::Cpp => {
+                r"^((template\s*<.*>\s*)?(class|struct|namespace|enum|union)\b|[A-Za-z_][\w\s\*&:<>,]*[\s\*&]([\w:~]+)\s*\([^;]*$)"
+            }
+            Self::Ruby => r"^(def|class|module)\s",
+            Self::Swift => {
+                r"^((public|private|fileprivate|internal|open|static|final|override|mutating)\s+)*(func|class|struct|enum|protocol|extension|actor)\b"
+            }
+            Self::Php => {
+                r"^((public|private|protected|static|abstract|final)\s+)*(function|class|interface|trait|enum)\b"
+            }
+        }
+    }
+
+    /// Whether `#` starts a comment (rather than an attribute or directive)
+    fn hash_comments(self) -> bool {
+        matches!(self, Self::Python | Self::Ruby | Self::Php)
+    }
+}
+
+static DEFINITION_PATTERNS: LazyLock<Vec<(CodeLanguage, Regex)>> = LazyLock::new(|| {
+    CodeLanguage::ALL
+        .iter()
+        .filter_map(|&language| {
+            Regex::new(language.definition_pattern())
+                .map(|regex| (language, regex))
+                .map_err(|e| log::warn!("Invalid {} definition pattern: {e}", language.name()))
+                .ok()
+        })
+        .collect()
+});
+
+fn definition_regex(language: CodeLanguage) -> Option<&'static Regex> {
+    DEFINITION_PATTERNS
+        .iter()
+        .find(|(l, _)| *l == language)
+        .map(|(_, regex)| regex)
+}
+
+/// Splits source files at definition boundaries
+#[derive(Debug, Clone, Copy)]
+pub struct CodeSplitter {
+    max_bytes: usize,
+    min_bytes: usize,
+}
+
+impl Default for CodeSplitter {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_CHUNK_BYTES,
+            min_bytes: DEFAULT_MIN_CHUNK_BYTES,
+        }
+    }
+}
+
+impl CodeSplitter {
+    /// Splitter with the default size budget
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Upper bound on chunk size in bytes (single over-long lines may exceed it)
+    #[must_use]
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes.max(1);
+        self
+    }
+
+    /// Size below which a chunk is merged with the next one
+    #[must_use]
+    pub fn with_min_bytes(mut self, min_bytes: usize) -> Self {
+        self.min_bytes = min_bytes;
+        self
+    }
+
+    /// Split `text`, read from `source`, into chunks
+    pub fn split(&self, source: &str, text: &str, language: CodeLanguage) -> Vec<MemoryChunk> {
+        let lines = line_ranges(text);
+        let segments = self.split_lines(text, &lines, 0..lines.len(), language, true);
+
+        self.merge(&lines, segments)
+            .into_iter()
+            .map(|range| lines[range.start].start..lines[range.end - 1].end)
+            .filter(|bytes| !text[bytes.clone()].trim().is_empty())
+            .map(|bytes| MemoryChunk::new(source, text, bytes))
+            .collect()
+    }
+
+    /// Cut a range of lines at definitions, recursing into oversized ones
+    fn split_lines(
+        &self,
+        text: &str,
+        lines: &[Range<usize>],
+        range: Range<usize>,
+        language: CodeLanguage,
+        top_level: bool,
+    ) -> Vec<Range<usize>> {
+        let mut starts = vec![range.start];
+        for index in range.clone() {
+            if index == range.start || !is_definition(&text[lines[index].clone()], language, top_level) {
+                continue;
+            }
+            // Keep doc comments, attributes and decorators with the definition
+            let mut start = index;
+            let floor = *starts.last().unwrap_or(&range.start);
+            while start > floor + 1 && is_lead_line(&text[lines[start - 1].clone()], language) {
+                start -= 1;
+            }
+            if start > floor {
+                starts.push(start);
+            }
+        }
+        starts.push(range.end);
+
+        let mut segments = Vec::new();
+        for pair in starts.windows(2) {
+            let segment = pair[0]..pair[1];
+            if segment.is_empty() {
+                continue;
+            }
+            let bytes = lines[segment.end - 1].end - lines[segment.start].start;
+            if bytes <= self.max_bytes {
+                segments.push(segment);
+            } else if top_level {
+                segments.extend(self.split_lines(text, lines, segment, language, false));
+            } else {
+                segments.extend(self.split_window(text, lines, segment));
+            }
+        }
+        segments
+    }
+
+    /// Cut an oversized range into windows, preferring blank lines
+    fn split_window(&self, text: &str, lines: &[Range<usize>], range: Range<usize>) -> Vec<Range<usize>> {
+        let mut segments = Vec::new();
+        let mut start = range.start;
+        while start < range.end {
+            let mut end = start + 1;
+            let mut last_blank = None;
+            while end < range.end && lines[end].end - lines[start].start <= self.max_bytes {
+                if text[lines[end].clone()].trim().is_empty() {
+                    last_blank = Some(end);
+                }
+                end += 1;
+            }
+            if end < range.end
+                && let Some(blank) = last_blank
+            {
+                end = blank + 1;
+            }
+            segments.push(start..end);
+            start = end;
+        }
+        segments
+    }
+
+    /// Merge neighbouring segments smaller than `min_bytes`
+    fn merge(&self, lines: &[Range<usize>], segments: Vec<Range<usize>>) -> Vec<Range<usize>> {
+        let size = |r: &Range<usize>| lines[r.end - 1].end - lines[r.start].start;
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(segments.len());
+        for segment in segments {
+            if let Some(last) = merged.last_mut()
+                && (size(last) < self.min_bytes || size(&segment) < self.min_bytes)
+                && size(&(last.start..segment.end)) <= self.max_bytes
+            {
+                last.end = segment.end;
+                continue;
+            }
+            merged.push(segment);
+        }
+        merged
+    }
+}
+
+/// Byte ranges of each line, including its newline
+fn line_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (index, _) in text.match_indices('\n') {
+        ranges.push(start..index + 1);
+        start = index + 1;
+    }
+    if start < text.len() {
+        ranges.push(start..text.len());
+    }
+    ranges
+}
+
+fn is_definition(line: &str, language: CodeLanguage, top_level: bool) -> bool {
+    let trimmed = line.trim_start();
+    if top_level && trimmed.len() != line.len() {
+        return false;
+    }
+    definition_regex(language).is_some_and(|regex| regex.is_match(trimmed.trim_end()))
+}
+
+fn is_lead_line(line: &str, language: CodeLanguage) -> bool {
+    let trimmed = line.trim_start();
+    ["//", "/*", "*", "#[", "@"]
+        .iter()
+        .any(|prefix| trimmed.starts_with(prefix))
+        || (language.hash_comments() && trimmed.starts_with('#'))
+}
@@ -0,0 +1,7 @@
+//! Splitting source documents into memory chunks
+
+pub mod code;
+pub mod text;
+
+pub use code::{CodeLanguage, CodeSplitter};
+pub use text::{ChunkStrategy, TextSplitter};
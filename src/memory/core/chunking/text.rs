@@ -0,0 +1,341 @@
+//! Splitting prose into overlapping chunks
+//!
+//! Long documents embedded whole produce one vector that matches everything
+//! a little and nothing well. [`TextSplitter`] cuts them into chunks of at
+//! most `max_tokens` tokens, either at sentence boundaries or at fixed token
+//! windows, repeating `overlap_tokens` tokens of each chunk at the start of
+//! the next so a passage cut in two is still found whole in one of them.
+//!
+//! Tokens are counted with the embedding model's tokenizer when one is
+//! given ([`TextSplitter::with_tokenizer`]); without one they are
+//! approximated as whitespace-separated words.
+//!
+//! Chunking is off unless `KODEGEN_CHUNK_STRATEGY` selects a strategy, so
+//! prose is stored as one memory by default.
+
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock};
+
+use regex::Regex;
+use tokenizers::Tokenizer;
+
+use crate::memory::core::primitives::chunk::MemoryChunk;
+
+/// Chunking strategy: `sentences`, `tokens` or `off` (the default)
+pub const CHUNK_STRATEGY_ENV: &str = "KODEGEN_CHUNK_STRATEGY";
+
+/// Tokens per chunk
+pub const CHUNK_TOKENS_ENV: &str = "KODEGEN_CHUNK_TOKENS";
+
+/// Tokens repeated between consecutive chunks
+pub const CHUNK_OVERLAP_ENV: &str = "KODEGEN_CHUNK_OVERLAP";
+
+/// Default upper bound on chunk size in tokens
+pub const DEFAULT_CHUNK_TOKENS: usize = 384;
+
+/// Default overlap between consecutive chunks in tokens
+pub const DEFAULT_CHUNK_OVERLAP: usize = 48;
+
+/// Words, as the unit chunk sizes are counted in without a tokenizer
+static WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\S+").expect("valid regex"));
+
+/// Sentence ends: terminal punctuation followed by whitespace, or a blank line
+static SENTENCE_END: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"[.!?]["')\]]*\s+|\n\s*\n"#).expect("valid regex"));
+
+/// Where chunks may be cut
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChunkStrategy {
+    /// Whole sentences, packed up to the size budget
+    #[default]
+    Sentences,
+    /// Fixed windows of tokens, regardless of sentences
+    Tokens,
+}
+
+impl FromStr for ChunkStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sentences" | "sentence" => Ok(Self::Sentences),
+            "tokens" | "token" => Ok(Self::Tokens),
+            other => Err(format!("unknown chunk strategy '{other}'")),
+        }
+    }
+}
+
+/// Splits prose into overlapping chunks
+#[derive(Clone)]
+pub struct TextSplitter {
+    strategy: ChunkStrategy,
+    max_tokens: usize,
+    overlap_tokens: usize,
+    /// Counts tokens; `None` counts words
+    tokenizer: Option<Arc<Tokenizer>>,
+}
+
+impl Default for TextSplitter {
+    fn default() -> Self {
+        Self {
+            strategy: ChunkStrategy::default(),
+            max_tokens: DEFAULT_CHUNK_TOKENS,
+            overlap_tokens: DEFAULT_CHUNK_OVERLAP,
+            tokenizer: None,
+        }
+    }
+}
+
+impl fmt::Debug for TextSplitter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextSplitter")
+            .field("strategy", &self.strategy)
+            .field("max_tokens", &self.max_tokens)
+            .field("overlap_tokens", &self.overlap_tokens)
+            .field("tokenizer", &self.tokenizer.is_some())
+            .finish()
+    }
+}
+
+impl TextSplitter {
+    /// Sentence splitter with the default budget
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Splitter configured from the environment, `None` when chunking is off
+    ///
+    /// Chunking is off unless [`CHUNK_STRATEGY_ENV`] names a strategy;
+    /// invalid sizes keep their defaults.
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(CHUNK_STRATEGY_ENV).ok()?;
+        if value.trim().eq_ignore_ascii_case("off") {
+            return None;
+        }
+        let strategy = match value.parse() {
+            Ok(strategy) => strategy,
+            Err(e) => {
+                log::warn!("Invalid {CHUNK_STRATEGY_ENV}: {e}, chunking stays off");
+                return None;
+            }
+        };
+        let mut splitter = Self::default().with_strategy(strategy);
+        if let Some(tokens) = env_usize(CHUNK_TOKENS_ENV) {
+            splitter = splitter.with_max_tokens(tokens);
+        }
+        if let Some(overlap) = env_usize(CHUNK_OVERLAP_ENV) {
+            splitter = splitter.with_overlap_tokens(overlap);
+        }
+        Some(splitter)
+    }
+
+    /// Where chunks may be cut
+    #[must_use]
+    pub fn with_strategy(mut self, strategy: ChunkStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Upper bound on chunk size in tokens (a single longer sentence is cut
+    /// into token windows)
+    #[must_use]
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens.max(1);
+        self.overlap_tokens = self.overlap_tokens.min(self.max_tokens - 1);
+        self
+    }
+
+    /// Tokens repeated at the start of the next chunk (less than the maximum)
+    #[must_use]
+    pub fn with_overlap_tokens(mut self, overlap_tokens: usize) -> Self {
+        self.overlap_tokens = overlap_tokens.min(self.max_tokens - 1);
+        self
+    }
+
+    /// Count tokens with `tokenizer` instead of words
+    ///
+    /// Truncation and padding set in `tokenizer.json` are dropped, so long
+    /// documents are counted whole.
+    #[must_use]
+    pub fn with_tokenizer(mut self, tokenizer: Arc<Tokenizer>) -> Self {
+        if tokenizer.get_truncation().is_none() && tokenizer.get_padding().is_none() {
+            self.tokenizer = Some(tokenizer);
+            return self;
+        }
+        let mut counting = Tokenizer::clone(&tokenizer);
+        counting.with_padding(None);
+        match counting.with_truncation(None) {
+            Ok(_) => self.tokenizer = Some(Arc::new(counting)),
+            Err(e) => log::warn!("Counting chunk sizes in words: {e}"),
+        }
+        self
+    }
+
+    /// Chunking strategy
+    pub fn strategy(&self) -> ChunkStrategy {
+        self.strategy
+    }
+
+    /// Upper bound on chunk size in tokens
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Tokens repeated between consecutive chunks
+    pub fn overlap_tokens(&self) -> usize {
+        self.overlap_tokens
+    }
+
+    /// Whether chunk sizes are counted with a tokenizer rather than in words
+    pub fn has_tokenizer(&self) -> bool {
+        self.tokenizer.is_some()
+    }
+
+    /// Split `text`, read from `source`, into chunks
+    ///
+    /// Text within the budget is one chunk; blank text is none.
+    pub fn split(&self, source: &str, text: &str) -> Vec<MemoryChunk> {
+        let words = self.tokens(text);
+        if words.is_empty() {
+            return Vec::new();
+        }
+        if words.len() <= self.max_tokens {
+            return vec![MemoryChunk::new(
+                source,
+                text,
+                words[0].start..words[words.len() - 1].end,
+            )];
+        }
+
+        let spans = match self.strategy {
+            ChunkStrategy::Tokens => self.token_windows(0..words.len()),
+            ChunkStrategy::Sentences => self.sentence_spans(text, &words),
+        };
+        spans
+            .into_iter()
+            .map(|span| {
+                MemoryChunk::new(
+                    source,
+                    text,
+                    words[span.start].start..words[span.end - 1].end,
+                )
+            })
+            .collect()
+    }
+
+    /// Byte ranges of the tokens of `text`, without surrounding whitespace
+    fn tokens(&self, text: &str) -> Vec<Range<usize>> {
+        let Some(tokenizer) = &self.tokenizer else {
+            return WORD.find_iter(text).map(|m| m.range()).collect();
+        };
+        match tokenizer.encode(text, false) {
+            Ok(encoding) => encoding
+                .get_offsets()
+                .iter()
+                .filter_map(|&(start, end)| trim_range(text, start..end))
+                .collect(),
+            Err(e) => {
+                log::warn!("Counting chunk sizes in words: {e}");
+                WORD.find_iter(text).map(|m| m.range()).collect()
+            }
+        }
+    }
+
+    /// Windows of `max_tokens` words over `range`, stepping by the budget
+    /// minus the overlap
+    fn token_windows(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let step = self.max_tokens - self.overlap_tokens;
+        let mut windows = Vec::new();
+        let mut start = range.start;
+        loop {
+            let end = (start + self.max_tokens).min(range.end);
+            windows.push(start..end);
+            if end == range.end {
+                return windows;
+            }
+            start += step;
+        }
+    }
+
+    /// Sentences packed into chunks, as ranges of word indices
+    fn sentence_spans(&self, text: &str, words: &[Range<usize>]) -> Vec<Range<usize>> {
+        let sentences = sentence_words(text, words);
+        let mut spans = Vec::new();
+        let mut first = 0;
+        while first < sentences.len() {
+            let start = sentences[first].start;
+            let mut last = first;
+            while last + 1 < sentences.len() && sentences[last + 1].end - start <= self.max_tokens {
+                last += 1;
+            }
+
+            let end = sentences[last].end;
+            if end - start > self.max_tokens {
+                // One sentence longer than the budget
+                spans.extend(self.token_windows(start..end));
+                first = last + 1;
+                continue;
+            }
+            spans.push(start..end);
+            if last + 1 == sentences.len() {
+                break;
+            }
+
+            // Start the next chunk with the trailing sentences that fit the
+            // overlap, as long as it still reaches the following sentence
+            let following = sentences[last + 1].end;
+            let mut next = last + 1;
+            while next > first + 1
+                && end - sentences[next - 1].start <= self.overlap_tokens
+                && following - sentences[next - 1].start <= self.max_tokens
+            {
+                next -= 1;
+            }
+            first = next;
+        }
+        spans
+    }
+}
+
+/// `range` of `text` without leading and trailing whitespace; `None` when
+/// nothing is left or the range does not fall on character boundaries
+fn trim_range(text: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let token = text.get(range.clone())?;
+    let trimmed = token.trim_start();
+    let start = range.start + (token.len() - trimmed.len());
+    let end = start + trimmed.trim_end().len();
+    (end > start).then_some(start..end)
+}
+
+/// Sentences of `text` as ranges of word indices
+fn sentence_words(text: &str, words: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut word = 0;
+    for boundary in SENTENCE_END.find_iter(text) {
+        while word < words.len() && words[word].start < boundary.end() {
+            word += 1;
+        }
+        if word > start {
+            sentences.push(start..word);
+            start = word;
+        }
+    }
+    if start < words.len() {
+        sentences.push(start..words.len());
+    }
+    sentences
+}
+
+fn env_usize(name: &str) -> Option<usize> {
+    let value = std::env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(n) => Some(n),
+        Err(_) => {
+            log::warn!("Invalid {name}={value:?}, using the default");
+            None
+        }
+    }
+}
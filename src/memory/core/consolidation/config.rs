@@ -0,0 +1,80 @@
+//! Consolidation configuration
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::utils::{Error, Result};
+
+/// What a consolidation pass does, and how often it runs
+///
+/// Each step is skipped when its setting is `None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsolidationConfig {
+    /// Seconds between scheduled passes; 0 disables the background job
+    pub interval_secs: u64,
+
+    /// Days without a recall over which importance halves
+    pub decay_half_life_days: Option<f64>,
+
+    /// Cosine similarity at or above which memories are merged
+    ///
+    /// Off by default: merging deletes memories, so a library opts in.
+    pub merge_threshold: Option<f32>,
+
+    /// Recalls after which an episodic memory is promoted to semantic
+    pub promote_min_recalls: Option<u64>,
+}
+
+impl Default for ConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 6 * 3600,          // 4 passes a day
+            decay_half_life_days: Some(30.0), // Unrecalled for a month: half as important
+            merge_threshold: None,            // Deletes memories, so opt-in
+            promote_min_recalls: Some(5),
+        }
+    }
+}
+
+impl ConsolidationConfig {
+    /// Config that never runs a pass
+    pub fn disabled() -> Self {
+        Self {
+            interval_secs: 0,
+            decay_half_life_days: None,
+            merge_threshold: None,
+            promote_min_recalls: None,
+        }
+    }
+
+    /// Time between scheduled passes, `None` if the job is disabled
+    pub fn interval(&self) -> Option<Duration> {
+        (self.interval_secs > 0).then(|| Duration::from_secs(self.interval_secs))
+    }
+
+    /// Check the settings are in range
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` for a half-life that is not positive or
+    /// a merge threshold outside `0.0..=1.0`.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(days) = self.decay_half_life_days
+            && !(days > 0.0 && days.is_finite())
+        {
+            return Err(Error::InvalidInput(format!(
+                "decay_half_life_days must be positive, got {days}"
+            )));
+        }
+        if let Some(threshold) = self.merge_threshold
+            && !(0.0..=1.0).contains(&threshold)
+        {
+            return Err(Error::InvalidInput(format!(
+                "merge_threshold must be between 0 and 1, got {threshold}"
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+//! Periodic consolidation of a memory library
+//!
+//! A pass over one library:
+//! - decays importance by how long each memory has gone unrecalled, halving
+//!   it every half-life (see [`decayed_importance`])
+//! - merges near-duplicate memories when a merge threshold is set (off by
+//!   default), keeping the most important one with every member's tags and
+//!   content
+//! - promotes episodic memories recalled often enough to semantic
+//!
+//! [`ConsolidationWorker`] runs passes on a schedule; the coordinator pool
+//! starts one per library with the config it was built with.
+
+mod config;
+#[cfg(feature = "memory")]
+mod worker;
+
+pub use config::ConsolidationConfig;
+#[cfg(feature = "memory")]
+pub use worker::ConsolidationWorker;
+
+/// Importance never decays below this
+pub const MIN_IMPORTANCE: f32 = 0.01;
+
+/// What one consolidation pass changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsolidationReport {
+    /// Memories whose importance was decayed
+    pub decayed: usize,
+    /// Memories deleted after being merged into a duplicate
+    pub merged: usize,
+    /// Episodic memories promoted to semantic
+    pub promoted: usize,
+}
+
+/// `importance` after `idle_days` without a recall
+///
+/// Halves every `half_life_days` and never drops below [`MIN_IMPORTANCE`]
+/// (nor rises, for importance already below it).
+pub fn decayed_importance(importance: f32, idle_days: f64, half_life_days: f64) -> f32 {
+    if idle_days <= 0.0 || importance <= MIN_IMPORTANCE {
+        return importance;
+    }
+    let factor = 0.5f64.powf(idle_days / half_life_days);
+    ((f64::from(importance) * factor) as f32).max(MIN_IMPORTANCE)
+}
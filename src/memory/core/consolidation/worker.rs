@@ -0,0 +1,64 @@
+//! Consolidation worker implementation
+//!
+//! Wakes every `interval_secs`, runs one pass over its library and sleeps
+//! again, until the coordinator's workers are shut down.
+
+use std::sync::Arc;
+
+use crate::memory::core::manager::coordinator::MemoryCoordinator;
+
+use super::config::ConsolidationConfig;
+
+/// Background worker running scheduled consolidation passes
+#[derive(Debug)]
+pub struct ConsolidationWorker {
+    coordinator: Arc<MemoryCoordinator>,
+    config: ConsolidationConfig,
+    shutdown_rx: tokio::sync::watch::Receiver<bool>,
+}
+
+impl ConsolidationWorker {
+    /// Create new consolidation worker
+    pub fn new(
+        coordinator: Arc<MemoryCoordinator>,
+        config: ConsolidationConfig,
+        shutdown_rx: tokio::sync::watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            coordinator,
+            config,
+            shutdown_rx,
+        }
+    }
+
+    /// Run the consolidation loop
+    ///
+    /// Returns immediately if the config has no schedule.
+    pub async fn run(mut self) {
+        let Some(interval) = self.config.interval() else {
+            return;
+        };
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {
+                    match self.coordinator.consolidate(&self.config).await {
+                        Ok(report) => log::info!(
+                            "Consolidation pass: {} decayed, {} merged, {} promoted",
+                            report.decayed,
+                            report.merged,
+                            report.promoted
+                        ),
+                        Err(e) => log::error!("Consolidation pass failed: {}", e),
+                    }
+                }
+                _ = self.shutdown_rx.changed() => {
+                    log::info!("Consolidation worker received shutdown signal");
+                    break;
+                }
+            }
+        }
+
+        log::info!("Consolidation worker stopped gracefully");
+    }
+}
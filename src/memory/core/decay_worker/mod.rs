@@ -1,15 +1,17 @@
 //! Background worker for proactive temporal decay
 //!
 //! Processes memories and their relationships in batches, applying exponential decay to:
-//! - Memory importance scores
-//! - Quantum coherence levels
 //! - Entanglement edge strengths
 //! - Causal edge strengths
 //!
+//! Memory importance is decayed by [consolidation](crate::memory::core::consolidation).
+//!
 //! This eliminates expensive on-read decay calculations from the hot path.
 
 mod config;
+#[cfg(feature = "memory")]
 mod worker;
 
 pub use config::DecayWorkerConfig;
+#[cfg(feature = "memory")]
 pub use worker::DecayWorker;
@@ -3,10 +3,9 @@
 //! Implements continuous batch processing:
 //! 1. Wake every N seconds
 //! 2. Query batch of memories using cursor pagination
-//! 3. Apply temporal decay to memory nodes (importance + quantum coherence)
-//! 4. Query and decay associated entanglement/causal edges
-//! 5. Persist changes to SurrealDB
-//! 6. Repeat with next batch
+//! 3. Query and decay associated entanglement/causal edges
+//! 4. Persist changes to SurrealDB
+//! 5. Repeat with next batch
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -132,33 +131,18 @@ impl DecayWorker {
         Ok(processed_count)
     }
 
-    /// Process a single memory: apply decay to node and edges
+    /// Process a single memory: apply decay to its edges
+    ///
+    /// Importance is decayed by consolidation passes
+    /// (see [`crate::memory::core::consolidation`]).
     async fn process_memory(
         &self,
         memory_node: &crate::memory::core::primitives::node::MemoryNode,
     ) -> Result<()> {
-        // Step 1: Apply temporal decay to memory node
-        let mut domain_memory = self
-            .coordinator
-            .convert_memory_to_domain_node(memory_node)?;
-
-        self.coordinator
-            .apply_temporal_decay(&mut domain_memory)
-            .await?;
-
-        // Convert back and persist
-        let updated_memory = self
-            .coordinator
-            .convert_domain_to_memory_node(&domain_memory);
-        self.coordinator
-            .surreal_manager
-            .update_memory(updated_memory)
-            .await?;
-
-        // Step 2: Apply decay to entanglement edges
+        // Step 1: Apply decay to entanglement edges
         self.decay_entanglement_edges(&memory_node.id).await?;
 
-        // Step 3: Apply decay to causal edges
+        // Step 2: Apply decay to causal edges
         self.decay_causal_edges(&memory_node.id).await?;
 
         Ok(())
@@ -0,0 +1,181 @@
+//! Source-anchored chunk writes
+//!
+//! Chunks are stored under deterministic IDs (see [`MemoryChunk::id`]) and are
+//! not deduplicated by content hash: two files with identical spans are still
+//! two locations. Writing a source again updates its chunks in place.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use tokio_stream::StreamExt;
+
+use crate::domain::memory::primitives::node::{AlignedEmbedding, MemoryNode};
+use crate::domain::memory::primitives::types::MemoryTypeEnum;
+use crate::memory::MemoryMetadata;
+use crate::memory::core::cognitive_queue::{CognitiveTask, CognitiveTaskType};
+use crate::memory::core::manager::surreal::trait_def::MemoryManager;
+use crate::memory::core::primitives::chunk::{CHUNK_SOURCE_KEY, MemoryChunk};
+use crate::memory::utils::Result;
+
+use super::lifecycle::MemoryCoordinator;
+
+/// Outcome of [`MemoryCoordinator::upsert_chunks`]
+#[derive(Debug, Default)]
+pub struct ChunkUpsert {
+    /// Stored chunks, in input order
+    pub memories: Vec<MemoryNode>,
+    /// Chunks stored for the first time
+    pub created: usize,
+    /// Existing chunks whose content changed and was re-embedded
+    pub updated: usize,
+    /// Existing chunks left as they were
+    pub unchanged: usize,
+    /// Chunks of the same sources that no longer exist and were deleted
+    pub removed: usize,
+}
+
+impl MemoryCoordinator {
+    /// Store chunks under their deterministic IDs
+    ///
+    /// New chunks are created, changed chunks are re-embedded and updated, and
+    /// unchanged chunks are kept as they are. Chunks previously stored for the
+    /// same sources but absent from `chunks` are deleted, so the library
+    /// mirrors the latest version of each source.
+    pub async fn upsert_chunks(
+        &self,
+        chunks: Vec<MemoryChunk>,
+        memory_type: MemoryTypeEnum,
+        metadata: Option<MemoryMetadata>,
+    ) -> Result<ChunkUpsert> {
+        let mut outcome = ChunkUpsert::default();
+        let mut results: Vec<Option<MemoryNode>> = (0..chunks.len()).map(|_| None).collect();
+        let mut to_embed = Vec::new();
+        let mut existing_ids = HashSet::new();
+
+        for (index, chunk) in chunks.iter().enumerate() {
+            let id = chunk.id().simple().to_string();
+            match self.surreal_manager.get_memory(&id).await? {
+                Some(stored) => {
+                    existing_ids.insert(id);
+                    let hash = crate::domain::memory::serialization::content_hash(&chunk.content);
+                    if stored.content_hash == hash {
+                        outcome.unchanged += 1;
+                        results[index] = Some(self.convert_memory_to_domain_node(&stored)?);
+                    } else {
+                        to_embed.push(index);
+                    }
+                }
+                None => to_embed.push(index),
+            }
+        }
+
+        if !to_embed.is_empty() {
+            // Chunks are documents, embedded with the library's document task
+            self.record_embedding_model().await?;
+            let document_task = self.embedding_tasks().document;
+            let texts: Vec<String> = to_embed.iter().map(|&i| chunks[i].content.clone()).collect();
+            let embeddings = self.generate_embeddings(&texts, Some(&document_task)).await?;
+
+            let mut creates = Vec::new();
+            for (&index, embedding) in to_embed.iter().zip(embeddings) {
+                let chunk = &chunks[index];
+                let mut node = Self::new_chunk_node(chunk, memory_type, metadata.as_ref());
+                node.embedding = Some(AlignedEmbedding::new(embedding));
+                let memory_node = self.convert_domain_to_memory_node(&node);
+
+                if existing_ids.contains(&memory_node.id) {
+                    let updated = self.surreal_manager.update_memory(memory_node).await?;
+                    outcome.updated += 1;
+                    results[index] = Some(self.convert_memory_to_domain_node(&updated)?);
+                } else {
+                    creates.push((index, memory_node));
+                }
+            }
+
+            if !creates.is_empty() {
+                let nodes = creates.iter().map(|(_, node)| node.clone()).collect();
+                let stored = self.surreal_manager.create_memories(nodes).await?;
+
+                {
+                    let mut repo = self.repository.write().await;
+                    for memory in &stored {
+                        repo.add(memory.clone());
+                    }
+                }
+
+                for ((index, _), stored_memory) in creates.iter().zip(stored) {
+                    let task = CognitiveTask::new(
+                        stored_memory.id.clone(),
+                        CognitiveTaskType::CommitteeEvaluation,
+                        5, // Default priority
+                    );
+                    self.cognitive_queue
+                        .enqueue(task)
+                        .map_err(crate::memory::utils::Error::Internal)?;
+                    outcome.created += 1;
+                    results[*index] = Some(self.convert_memory_to_domain_node(&stored_memory)?);
+                }
+            }
+        }
+
+        let keep: HashSet<String> = chunks.iter().map(|c| c.id().simple().to_string()).collect();
+        let sources: HashSet<&str> = chunks.iter().map(|c| c.source.as_str()).collect();
+        for source in sources {
+            outcome.removed += self.remove_stale_chunks(source, &keep).await?;
+        }
+
+        outcome.memories = results.into_iter().flatten().collect();
+        Ok(outcome)
+    }
+
+    /// Delete chunks of `source` whose IDs are not in `keep`
+    async fn remove_stale_chunks(&self, source: &str, keep: &HashSet<String>) -> Result<usize> {
+        let filters = HashMap::from([(
+            CHUNK_SOURCE_KEY.to_string(),
+            serde_json::Value::String(source.to_string()),
+        )]);
+        let mut stream = self.surreal_manager.query_by_metadata(filters).await?;
+
+        let mut stale = Vec::new();
+        while let Some(memory) = stream.next().await {
+            let memory = memory?;
+            if !keep.contains(&memory.id) {
+                stale.push(memory.id);
+            }
+        }
+
+        for id in &stale {
+            self.surreal_manager.delete_memory(id).await?;
+        }
+        if !stale.is_empty() {
+            log::debug!("Removed {} stale chunks of {}", stale.len(), source);
+        }
+        Ok(stale.len())
+    }
+
+    /// Unsaved domain node for a chunk, with its ID and location metadata
+    fn new_chunk_node(
+        chunk: &MemoryChunk,
+        memory_type: MemoryTypeEnum,
+        metadata: Option<&MemoryMetadata>,
+    ) -> MemoryNode {
+        let mut node = Self::new_domain_node_with_id(
+            chunk.id(),
+            &chunk.content,
+            memory_type,
+            metadata,
+        );
+
+        let mut node_metadata = (*node.metadata).clone();
+        node_metadata
+            .custom
+            .insert(Arc::from("source"), Arc::new(chunk.source.clone().into()));
+        for (key, value) in chunk.custom_metadata() {
+            node_metadata
+                .custom
+                .insert(Arc::from(key.as_str()), Arc::new(value));
+        }
+        node.metadata = Arc::new(node_metadata);
+        node
+    }
+}
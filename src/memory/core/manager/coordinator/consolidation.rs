@@ -0,0 +1,402 @@
+//! Consolidation passes over the coordinator's library
+//!
+//! See [`crate::memory::core::consolidation`] for what a pass does. Decay
+//! is measured from the later of a memory's last recall and the previous
+//! pass, whose time is stored in the library's database, so a memory is
+//! never decayed twice for the same stretch of time. A library's first pass
+//! decays nothing; it records where the next one starts counting from.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use futures_util::StreamExt;
+use serde_json::{Value, json};
+
+use crate::memory::core::consolidation::{
+    ConsolidationConfig, ConsolidationReport, ConsolidationWorker, decayed_importance,
+};
+use crate::memory::core::manager::surreal::trait_def::MemoryManager;
+use crate::memory::core::ops::duplicates::{SimHashIndex, complete_linkage};
+use crate::memory::core::ops::similarity::cosine;
+use crate::memory::core::primitives::chunk::CHUNK_SOURCE_KEY;
+use crate::memory::core::primitives::types::MemoryTypeEnum;
+use crate::memory::utils::{Error, Result};
+
+use super::lifecycle::MemoryCoordinator;
+
+/// Record holding the time of the library's last pass
+const CONSOLIDATION_RECORD: &str = "library_settings:consolidation";
+
+/// Memories read per query
+const PAGE_SIZE: usize = 500;
+
+/// Most embeddings held at once while comparing LSH buckets
+const EMBEDDING_CACHE_SIZE: usize = 4 * PAGE_SIZE;
+
+/// Custom metadata key on a merged memory listing the `{id, content}` of
+/// the duplicates merged into it
+pub const MERGED_MEMORIES_KEY: &str = "merged_memories";
+
+/// A memory considered for merging
+struct MergeCandidate {
+    id: String,
+    importance: f32,
+}
+
+impl MemoryCoordinator {
+    /// Run consolidation passes on `config`'s schedule
+    ///
+    /// The worker stops with the coordinator's other workers. Nothing is
+    /// started if the config has no schedule.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the config is out of range.
+    pub fn start_consolidation(&self, config: ConsolidationConfig) -> Result<()> {
+        config.validate()?;
+        let Some(shutdown_tx) = &self.decay_shutdown_tx else {
+            return Ok(());
+        };
+        if config.interval().is_none() {
+            return Ok(());
+        }
+
+        let worker =
+            ConsolidationWorker::new(Arc::new(self.clone()), config, shutdown_tx.subscribe());
+        crate::runtime::supervisor().spawn_cancellable("memory consolidation", worker.run());
+        log::info!("Consolidation worker started");
+        Ok(())
+    }
+
+    /// Run one consolidation pass now
+    ///
+    /// Merges duplicates, promotes recalled episodic memories, then decays
+    /// importance, skipping each step `config` leaves unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the config is out of range, or the
+    /// database error of the step that failed.
+    pub async fn consolidate(&self, config: &ConsolidationConfig) -> Result<ConsolidationReport> {
+        config.validate()?;
+
+        // Pending recalls count toward promotion and reset decay
+        if let Err(e) = self.flush_recall_hits().await {
+            log::warn!("Consolidation may miss recent recalls: {}", e);
+        }
+        let since_last_pass = self.secs_since_last_consolidation().await;
+
+        let mut report = ConsolidationReport::default();
+        if let Some(threshold) = config.merge_threshold {
+            report.merged = self.merge_duplicates(threshold).await?;
+        }
+        if let Some(min_recalls) = config.promote_min_recalls {
+            report.promoted = self.promote_recalled(min_recalls).await?;
+        }
+        // The first pass only marks where decay starts counting from
+        if let Some(half_life_days) = config.decay_half_life_days
+            && let Some(since_last_pass) = since_last_pass
+        {
+            report.decayed = self
+                .decay_importance(half_life_days, since_last_pass)
+                .await?;
+        }
+
+        self.surreal_manager
+            .execute_query(&format!(
+                "UPSERT {CONSOLIDATION_RECORD} SET last_run = time::now();"
+            ))
+            .await?;
+        Ok(report)
+    }
+
+    /// Seconds since the library's last pass, `None` if it never had one
+    async fn secs_since_last_consolidation(&self) -> Option<f64> {
+        let rows = self
+            .surreal_manager
+            .execute_query(&format!(
+                "SELECT duration::secs(time::now() - last_run) AS secs FROM {CONSOLIDATION_RECORD};"
+            ))
+            .await
+            .ok()?;
+        let row = match &rows {
+            Value::Array(rows) => rows.first()?,
+            row => row,
+        };
+        row.get("secs")?.as_f64()
+    }
+
+    /// Decay every memory's importance by the time since its last recall,
+    /// counted from the previous pass at the earliest
+    ///
+    /// Returns the number of memories whose importance dropped.
+    async fn decay_importance(&self, half_life_days: f64, since_last_pass: f64) -> Result<usize> {
+        let mut decayed = 0;
+        let mut offset = 0;
+        loop {
+            let page = match self
+                .surreal_manager
+                .execute_query(&format!(
+                    "SELECT meta::id(id) AS id, metadata.importance ?? 0.5 AS importance, \
+                     duration::secs(time::now() - (metadata.last_recalled_at ?? created_at)) AS idle_secs \
+                     FROM memory ORDER BY created_at LIMIT {PAGE_SIZE} START {offset}"
+                ))
+                .await?
+            {
+                Value::Array(rows) => rows,
+                _ => Vec::new(),
+            };
+
+            let updates: Vec<String> = page
+                .iter()
+                .filter_map(|row| {
+                    let id = row.get("id")?.as_str()?;
+                    let importance = row.get("importance")?.as_f64()? as f32;
+                    let idle_secs = row.get("idle_secs")?.as_f64()?;
+                    let elapsed = since_last_pass.min(idle_secs);
+                    let new_importance =
+                        decayed_importance(importance, elapsed / 86400.0, half_life_days);
+                    (new_importance < importance).then(|| {
+                        format!(
+                            "UPDATE type::thing('memory', {}) SET metadata.importance = {};",
+                            Value::from(id),
+                            Value::from(f64::from(new_importance))
+                        )
+                    })
+                })
+                .collect();
+            if !updates.is_empty() {
+                self.surreal_manager
+                    .execute_query(&updates.join("\n"))
+                    .await?;
+                decayed += updates.len();
+            }
+
+            if page.len() < PAGE_SIZE {
+                break;
+            }
+            offset += PAGE_SIZE;
+        }
+        Ok(decayed)
+    }
+
+    /// Merge memories at or above `threshold` similarity
+    ///
+    /// Each group keeps its most important memory, which gains every
+    /// member's tags and lists their IDs and content under
+    /// [`MERGED_MEMORIES_KEY`]; the others are deleted. Groups are formed by
+    /// complete linkage, so every member is a duplicate of every other.
+    /// Document chunks are left alone, since memorizing their source again
+    /// would recreate them. Returns the number of memories deleted.
+    ///
+    /// The library is paged through once to bucket embeddings by LSH band,
+    /// keeping only the band keys; embeddings are then loaded bucket by
+    /// bucket, at most [`EMBEDDING_CACHE_SIZE`] at a time.
+    async fn merge_duplicates(&self, threshold: f32) -> Result<usize> {
+        let mut candidates: Vec<MergeCandidate> = Vec::new();
+        let mut indexes: HashMap<usize, SimHashIndex> = HashMap::new();
+        let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+        let mut offset = 0;
+        loop {
+            let mut stream = self.surreal_manager.list_all_memories(PAGE_SIZE, offset);
+            let mut fetched = 0;
+            while let Some(memory) = stream.next().await {
+                let memory = memory?;
+                fetched += 1;
+                if memory.metadata.custom.get(CHUNK_SOURCE_KEY).is_some() {
+                    continue;
+                }
+                let Some(embedding) = memory.embedding.or(memory.metadata.embedding) else {
+                    continue;
+                };
+                let dimension = embedding.len();
+                if dimension == 0 {
+                    continue;
+                }
+                let index = indexes
+                    .entry(dimension)
+                    .or_insert_with(|| SimHashIndex::new(dimension));
+                for key in index.band_keys(&embedding) {
+                    buckets
+                        .entry((dimension, key))
+                        .or_default()
+                        .push(candidates.len());
+                }
+                candidates.push(MergeCandidate {
+                    id: memory.id,
+                    importance: memory.metadata.importance,
+                });
+            }
+            if fetched < PAGE_SIZE {
+                break;
+            }
+            offset += fetched;
+        }
+
+        // Compare within each bucket, loading embeddings as buckets need them
+        let mut cache: HashMap<usize, Vec<f32>> = HashMap::new();
+        let mut compared = HashSet::new();
+        let mut matches = Vec::new();
+        for bucket in buckets.values().filter(|bucket| bucket.len() > 1) {
+            let missing: Vec<usize> = bucket
+                .iter()
+                .copied()
+                .filter(|i| !cache.contains_key(i))
+                .collect();
+            if cache.len() + missing.len() > EMBEDDING_CACHE_SIZE {
+                cache.clear();
+                self.load_embeddings(&candidates, bucket, &mut cache).await?;
+            } else {
+                self.load_embeddings(&candidates, &missing, &mut cache).await?;
+            }
+
+            for (n, &a) in bucket.iter().enumerate() {
+                for &b in &bucket[n + 1..] {
+                    if !compared.insert((a.min(b), a.max(b))) {
+                        continue;
+                    }
+                    if let (Some(x), Some(y)) = (cache.get(&a), cache.get(&b)) {
+                        let similarity = cosine(x, y);
+                        if similarity >= threshold {
+                            matches.push((similarity, a, b));
+                        }
+                    }
+                }
+            }
+        }
+        drop(cache);
+
+        // Linkage checks every pair within a group, so keep the matched embeddings
+        let matched: Vec<usize> = matches
+            .iter()
+            .flat_map(|&(_, a, b)| [a, b])
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut embeddings = HashMap::new();
+        self.load_embeddings(&candidates, &matched, &mut embeddings)
+            .await?;
+        let groups = complete_linkage(candidates.len(), matches, threshold, |x, y| {
+            match (embeddings.get(&x), embeddings.get(&y)) {
+                (Some(x), Some(y)) => cosine(x, y),
+                _ => f32::MIN,
+            }
+        });
+
+        let mut removed = 0;
+        for (indices, _) in groups {
+            let mut members: Vec<&MergeCandidate> =
+                indices.iter().map(|&i| &candidates[i]).collect();
+            members.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+            let Some((keep, rest)) = members.split_first() else {
+                continue;
+            };
+            let Some(mut node) = self.get_memory(&keep.id).await? else {
+                continue;
+            };
+
+            let mut tags: HashSet<String> =
+                node.metadata.tags.iter().map(|t| t.to_string()).collect();
+            let mut merged = match node.metadata.custom.get(MERGED_MEMORIES_KEY) {
+                Some(value) => value.as_array().cloned().unwrap_or_default(),
+                None => Vec::new(),
+            };
+            let mut absorbed = Vec::new();
+            for member in rest {
+                let Some(duplicate) = self.get_memory(&member.id).await? else {
+                    continue;
+                };
+                for tag in &duplicate.metadata.tags {
+                    if tags.insert(tag.to_string()) {
+                        node.add_tag(tag.clone());
+                    }
+                }
+                merged.push(json!({
+                    "id": member.id,
+                    "content": duplicate.content().to_string(),
+                }));
+                absorbed.push(member.id.as_str());
+            }
+            if absorbed.is_empty() {
+                continue;
+            }
+            node.set_custom_metadata(MERGED_MEMORIES_KEY, Value::Array(merged));
+            self.update_memory(node).await?;
+
+            for id in &absorbed {
+                self.delete_memory(id).await?;
+                removed += 1;
+            }
+            log::debug!("Merged {} duplicates into memory {}", absorbed.len(), keep.id);
+        }
+        Ok(removed)
+    }
+
+    /// Load the embeddings of `indices` into `into`, keyed by index
+    async fn load_embeddings(
+        &self,
+        candidates: &[MergeCandidate],
+        indices: &[usize],
+        into: &mut HashMap<usize, Vec<f32>>,
+    ) -> Result<()> {
+        for page in indices.chunks(PAGE_SIZE) {
+            let by_id: HashMap<&str, usize> = page
+                .iter()
+                .map(|&i| (candidates[i].id.as_str(), i))
+                .collect();
+            let ids: Vec<String> = by_id.keys().map(|id| id.to_string()).collect();
+            let mut response = self
+                .surreal_manager
+                .db
+                .query(
+                    "SELECT meta::id(id) AS id, metadata.embedding AS embedding \
+                     FROM memory WHERE meta::id(id) IN $ids",
+                )
+                .bind(("ids", ids))
+                .await
+                .map_err(|e| Error::Database(format!("{:?}", e)))?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|e| Error::Database(format!("{:?}", e)))?;
+
+            for row in rows {
+                let (Some(id), Some(embedding)) = (
+                    row.get("id").and_then(Value::as_str),
+                    row.get("embedding").and_then(Value::as_array),
+                ) else {
+                    continue;
+                };
+                if let Some(&i) = by_id.get(id) {
+                    into.insert(
+                        i,
+                        embedding
+                            .iter()
+                            .filter_map(Value::as_f64)
+                            .map(|x| x as f32)
+                            .collect(),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Promote episodic memories recalled at least `min_recalls` times to
+    /// semantic, returning how many were promoted
+    async fn promote_recalled(&self, min_recalls: u64) -> Result<usize> {
+        let (episodic, semantic) = (MemoryTypeEnum::Episodic, MemoryTypeEnum::Semantic);
+        let promoted = self
+            .surreal_manager
+            .execute_query(&format!(
+                "UPDATE memory SET memory_type = '{semantic}', updated_at = time::now(), \
+                 metadata.category = IF metadata.category = '{episodic}' THEN '{semantic}' ELSE metadata.category END \
+                 WHERE memory_type = '{episodic}' AND (metadata.recall_count ?? 0) >= {min_recalls} \
+                 RETURN VALUE meta::id(id);"
+            ))
+            .await?;
+        Ok(match promoted {
+            Value::Array(ids) => ids.len(),
+            _ => 0,
+        })
+    }
+}
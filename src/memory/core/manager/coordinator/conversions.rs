@@ -287,6 +287,9 @@ impl MemoryCoordinator {
     ///   - `Some("search_query")` - Query instruction (for search queries)
     ///   - `Some("s2s")` - Similarity instruction (for semantic similarity)
     ///   - `None` - Defaults to query instruction
+    ///
+    /// Callers pass the tasks from [`Self::embedding_tasks`] so stored
+    /// memories and queries follow the library's setting.
     pub(super) async fn generate_embedding(&self, text: &str, task: Option<&str>) -> Result<Vec<f32>> {
         use crate::capability::traits::TextEmbeddingCapable;
 
@@ -298,4 +301,33 @@ impl MemoryCoordinator {
             .map_err(|e| Error::Internal(format!("Embedding generation failed: {}", e)))?;
         Ok(embedding)
     }
+
+    /// Generate embeddings for several texts in one batch call
+    pub(super) async fn generate_embeddings(
+        &self,
+        texts: &[String],
+        task: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>> {
+        use crate::capability::traits::TextEmbeddingCapable;
+
+        let embeddings = self
+            .embedding_model
+            .batch_embed(texts, task.map(|s| s.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Batch embedding generation failed: {}", e)))?;
+
+        if embeddings.len() != texts.len() {
+            return Err(Error::Internal(format!(
+                "Batch embedding returned {} vectors for {} texts",
+                embeddings.len(),
+                texts.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+
+    /// Embed texts for comparison with each other (semantic similarity task)
+    pub async fn embed_for_similarity(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.generate_embeddings(texts, Some("s2s")).await
+    }
 }
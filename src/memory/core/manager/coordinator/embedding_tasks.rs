@@ -0,0 +1,180 @@
+//! Embedding tasks used at memorize and recall time
+//!
+//! Instruction-tuned embedders such as Stella embed the same text differently
+//! per task (see `format_single_with_instruction`). By default memories are
+//! embedded as plain documents and queries with the search instruction
+//! (s2p). Libraries of short, like-for-like texts retrieve better when both
+//! sides use the similarity instruction (s2s), and a library can also carry
+//! its own instruction.
+//!
+//! A library's tasks are stored in its database and apply to every memorize
+//! and recall; [`MemoryCoordinator::with_embedding_task`] overrides them for
+//! one call. Document and query tasks always come from the same setting,
+//! since memories embedded for one task and queried with another rank
+//! poorly.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::capability::text_embedding::stella::instruction::{
+    CODE_TASK_PREFIX, INSTRUCT_TASK_PREFIX,
+};
+use crate::memory::core::manager::surreal::SurrealDBMemoryManager;
+use crate::memory::utils::{Error, Result};
+
+use super::lifecycle::MemoryCoordinator;
+
+/// Task for stored memories under the default (s2p) setting: no instruction prefix
+pub const DOCUMENT_TASK: &str = "document";
+
+/// Task for recall queries under the default (s2p) setting
+pub const QUERY_TASK: &str = "search_query";
+
+/// Record holding a library's embedding tasks
+const SETTINGS_RECORD: &str = "library_settings:embedding";
+
+/// Tasks passed to the embedding model for stored memories and for queries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingTasks {
+    /// Task for memory content
+    pub document: String,
+    /// Task for recall queries
+    pub query: String,
+}
+
+impl Default for EmbeddingTasks {
+    fn default() -> Self {
+        Self {
+            document: DOCUMENT_TASK.to_string(),
+            query: QUERY_TASK.to_string(),
+        }
+    }
+}
+
+impl EmbeddingTasks {
+    /// Document and query tasks for a task name or `instruct:` instruction
+    ///
+    /// - `s2p`, `search_query`, `search_document`, `retrieval`, `document`:
+    ///   plain documents, queries with the search instruction (the default)
+    /// - `code:<language>`: plain documents, queries with the language's
+    ///   code search instruction
+    /// - `s2s`, `classification`, `clustering`, `instruct:<instruction>` and
+    ///   any other task: the same task on both sides
+    pub fn for_task(task: &str) -> Self {
+        let task = task.trim();
+        match task {
+            "" | "s2p" | "search_query" | "search_document" | "retrieval" | DOCUMENT_TASK => {
+                Self::default()
+            }
+            code if code.starts_with(CODE_TASK_PREFIX) => Self {
+                document: DOCUMENT_TASK.to_string(),
+                query: code.to_string(),
+            },
+            _ => Self::symmetric(task),
+        }
+    }
+
+    /// The same task for documents and queries
+    pub fn symmetric(task: impl Into<String>) -> Self {
+        let task = task.into();
+        Self {
+            document: task.clone(),
+            query: task,
+        }
+    }
+
+    /// Both sides embedded with a custom instruction
+    pub fn with_instruction(instruction: &str) -> Self {
+        Self::symmetric(format!("{INSTRUCT_TASK_PREFIX}{}", instruction.trim()))
+    }
+}
+
+impl MemoryCoordinator {
+    /// Embedding tasks in effect: the per-call override, else the library's
+    pub fn embedding_tasks(&self) -> EmbeddingTasks {
+        match &self.embedding_task_override {
+            Some(tasks) => tasks.clone(),
+            None => self.embedding_tasks.read().clone(),
+        }
+    }
+
+    /// This coordinator with `task` overriding the library's tasks
+    ///
+    /// The override applies to memories written and queries embedded through
+    /// the returned handle only; `None` keeps the library's tasks.
+    #[must_use]
+    pub fn with_embedding_task(&self, task: Option<&str>) -> Self {
+        let mut coordinator = self.clone();
+        coordinator.embedding_task_override = task.map(EmbeddingTasks::for_task);
+        coordinator
+    }
+
+    /// Store `tasks` as the library's embedding tasks
+    ///
+    /// The tasks can only change while the library is empty: stored
+    /// memories keep the embeddings of the old tasks, which new queries would
+    /// no longer match. Setting the tasks already in effect always succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a task is empty, the tasks would change on a
+    /// library that has memories, or the setting cannot be written.
+    pub async fn set_embedding_tasks(&self, tasks: EmbeddingTasks) -> Result<()> {
+        if tasks.document.trim().is_empty() || tasks.query.trim().is_empty() {
+            return Err(Error::InvalidInput(
+                "Embedding tasks cannot be empty".into(),
+            ));
+        }
+        let current = self.embedding_tasks.read().clone();
+        if tasks != current {
+            let count = self.memory_count().await?;
+            if count > 0 {
+                return Err(Error::InvalidInput(format!(
+                    "library has {count} memories embedded with task '{}'; \
+                     re-memorize them into a new library to use '{}'",
+                    current.document, tasks.document
+                )));
+            }
+        }
+        self.surreal_manager
+            .execute_query(&format!(
+                "UPSERT {SETTINGS_RECORD} SET document_task = {}, query_task = {};",
+                Value::from(tasks.document.as_str()),
+                Value::from(tasks.query.as_str())
+            ))
+            .await?;
+        log::info!(
+            "Embedding tasks set: documents '{}', queries '{}'",
+            tasks.document,
+            tasks.query
+        );
+        *self.embedding_tasks.write() = tasks;
+        Ok(())
+    }
+
+    /// Read the library's stored embedding tasks, if any were set
+    pub(super) async fn load_embedding_tasks(
+        surreal_manager: &SurrealDBMemoryManager,
+    ) -> Option<EmbeddingTasks> {
+        let rows = match surreal_manager
+            .execute_query(&format!(
+                "SELECT document_task, query_task FROM {SETTINGS_RECORD};"
+            ))
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                log::warn!("Failed to read embedding tasks, using defaults: {}", e);
+                return None;
+            }
+        };
+        let row = match &rows {
+            Value::Array(rows) => rows.first()?,
+            row => row,
+        };
+        Some(EmbeddingTasks {
+            document: row.get("document_task")?.as_str()?.to_string(),
+            query: row.get("query_task")?.as_str()?.to_string(),
+        })
+    }
+}
@@ -0,0 +1,128 @@
+//! Latency-bounded search for interactive callers
+//!
+//! Autocomplete-style integrations need an answer within tens of
+//! milliseconds and cannot wait for the full ranking pipeline. A fast search
+//! embeds the query, reads a small number of MTREE candidates and stops at
+//! a deadline. Query routing, graph expansion of related memories, the
+//! entanglement/quality importance boost and the cognitive state update are
+//! all skipped, so hits keep the database's similarity × importance order.
+//!
+//! When the filter or the read snapshot leaves fewer than the requested hits,
+//! the rest are filled from the full search while the budget lasts. Work
+//! still running at the deadline is dropped, which cancels its query.
+
+use std::time::Duration;
+
+use crate::domain::memory::primitives::node::MemoryNode;
+use crate::memory::core::ops::filter::MemoryFilter;
+use crate::memory::utils::Result;
+
+use super::lifecycle::MemoryCoordinator;
+use super::snapshot::ReadSnapshot;
+
+/// Budget of a fast search when the caller gives none
+pub const DEFAULT_FAST_SEARCH_BUDGET: Duration = Duration::from_millis(50);
+
+/// MTREE candidates read per requested result (the full search reads 5)
+const FAST_CANDIDATE_FACTOR: usize = 2;
+
+/// Result of [`MemoryCoordinator::search_memories_fast`]
+#[derive(Debug)]
+pub struct FastSearch {
+    /// Hits in database order, at most `top_k`
+    pub memories: Vec<MemoryNode>,
+    /// The budget ran out before the search finished; `memories` are the
+    /// best found by then
+    pub approximate: bool,
+}
+
+impl MemoryCoordinator {
+    /// Search `snapshot` for `query`, returning within `budget`
+    ///
+    /// When embedding the query alone exceeds the budget the result is empty
+    /// and marked approximate.
+    pub async fn search_memories_fast(
+        &self,
+        query: &str,
+        top_k: usize,
+        filter: Option<MemoryFilter>,
+        snapshot: &ReadSnapshot,
+        budget: Duration,
+    ) -> Result<FastSearch> {
+        let deadline = tokio::time::Instant::now() + budget;
+        let query_task = self.embedding_tasks().query;
+        let Ok(embedding) = tokio::time::timeout_at(
+            deadline,
+            self.generate_embedding(query, Some(&query_task)),
+        )
+        .await
+        else {
+            log::debug!("Fast search budget of {budget:?} spent embedding the query");
+            return Ok(FastSearch {
+                memories: Vec::new(),
+                approximate: true,
+            });
+        };
+
+        let candidates = top_k.saturating_mul(FAST_CANDIDATE_FACTOR).max(1);
+        let Ok(nodes) = tokio::time::timeout_at(
+            deadline,
+            self.surreal_manager.vector_search(&embedding?, candidates),
+        )
+        .await
+        else {
+            log::debug!("Fast search budget of {budget:?} ran out in the vector search");
+            return Ok(FastSearch {
+                memories: Vec::new(),
+                approximate: true,
+            });
+        };
+        let nodes = nodes?;
+        let exhausted = nodes.len() < candidates;
+
+        let mut memories = Vec::with_capacity(top_k);
+        for node in nodes {
+            if memories.len() == top_k {
+                break;
+            }
+            if filter.as_ref().is_some_and(|filter| !filter.matches(&node)) {
+                continue;
+            }
+            let memory = self.convert_memory_to_domain_node(&node)?;
+            if snapshot.contains(&memory) {
+                memories.push(memory);
+            }
+        }
+
+        // Candidates were left out and the library has more: fill the rest
+        // from the full search
+        let mut approximate = false;
+        if memories.len() < top_k && !exhausted {
+            match tokio::time::timeout_at(
+                deadline,
+                self.search_memories_at(query, top_k, filter, snapshot),
+            )
+            .await
+            {
+                Ok(Ok(found)) => {
+                    for memory in found {
+                        if memories.len() == top_k {
+                            break;
+                        }
+                        if !memories.iter().any(|kept| kept.id() == memory.id()) {
+                            memories.push(memory);
+                        }
+                    }
+                }
+                Ok(Err(e)) => log::warn!("Fast search could not fill from the full search: {e}"),
+                Err(_) => approximate = true,
+            }
+        }
+
+        self.note_recall_hits(&memories);
+        Ok(FastSearch {
+            memories,
+            approximate,
+        })
+    }
+}
@@ -0,0 +1,304 @@
+//! Integrity check and repair for a library's database
+//!
+//! A crash mid-write can leave a library that opens fine but fails in
+//! confusing ways at query time. [`MemoryCoordinator::fsck`] walks every
+//! memory and the tables that refer to memories and reports:
+//!
+//! - memories without an embedding, or with one whose dimension differs from
+//!   that of the model recorded for the library (the MTREE index rejects or
+//!   mis-ranks them),
+//! - memories whose stored `content_hash` does not match their content
+//!   (deduplication then misses or wrongly merges them; hashes also change
+//!   across toolchain upgrades),
+//! - quantum signatures, relationships and entanglement/causal edges that
+//!   point at memories which no longer exist.
+//!
+//! With `repair`, embeddings are regenerated, hashes rewritten and orphans
+//! dropped. Each issue records whether its repair succeeded.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::capability::traits::TextEmbeddingCapable;
+use crate::domain::memory::serialization::content_hash;
+use crate::domain::model::traits::CandleModel;
+use crate::memory::utils::{Error, Result};
+
+use super::lifecycle::MemoryCoordinator;
+
+/// Memories read per query while checking
+const FSCK_PAGE_SIZE: usize = 500;
+
+/// Graph edge tables whose endpoints are memories
+const EDGE_TABLES: [&str; 2] = ["entangled", "caused"];
+
+/// What is wrong with a record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsckIssueKind {
+    /// Memory has no embedding
+    MissingEmbedding,
+    /// Memory's embedding has the wrong number of dimensions
+    EmbeddingDimension { expected: usize, actual: usize },
+    /// Stored content hash differs from the hash of the content
+    HashMismatch,
+    /// Quantum signature of a memory that no longer exists
+    OrphanedSignature,
+    /// Relationship with a source or target that no longer exists
+    OrphanedRelationship,
+    /// Entanglement or causal edge with a missing endpoint
+    OrphanedEdge,
+}
+
+impl fmt::Display for FsckIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingEmbedding => write!(f, "missing embedding"),
+            Self::EmbeddingDimension { expected, actual } => {
+                write!(f, "embedding has {actual} dimensions, expected {expected}")
+            }
+            Self::HashMismatch => write!(f, "content hash mismatch"),
+            Self::OrphanedSignature => write!(f, "orphaned quantum signature"),
+            Self::OrphanedRelationship => write!(f, "orphaned relationship"),
+            Self::OrphanedEdge => write!(f, "orphaned graph edge"),
+        }
+    }
+}
+
+/// One problem found by [`MemoryCoordinator::fsck`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsckIssue {
+    pub kind: FsckIssueKind,
+    /// Affected record, as `table:id`
+    pub record: String,
+    /// Whether a repair was made
+    pub repaired: bool,
+    /// Why the repair failed, when one was attempted
+    pub repair_error: Option<String>,
+}
+
+/// Outcome of an integrity check
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FsckReport {
+    /// Memories examined
+    pub memories_checked: usize,
+    /// Problems found, in check order
+    pub issues: Vec<FsckIssue>,
+}
+
+impl FsckReport {
+    /// No problems were found
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Problems left after any repairs
+    pub fn unrepaired(&self) -> usize {
+        self.issues.iter().filter(|issue| !issue.repaired).count()
+    }
+
+    /// Record an issue, awaiting its repair if one was started
+    async fn push(
+        &mut self,
+        kind: FsckIssueKind,
+        record: String,
+        repair: Option<impl Future<Output = Result<()>>>,
+    ) {
+        let (repaired, repair_error) = match repair {
+            Some(repair) => match repair.await {
+                Ok(()) => (true, None),
+                Err(e) => {
+                    log::warn!("fsck: failed to repair {record}: {e}");
+                    (false, Some(e.to_string()))
+                }
+            },
+            None => (false, None),
+        };
+        self.issues.push(FsckIssue {
+            kind,
+            record,
+            repaired,
+            repair_error,
+        });
+    }
+}
+
+impl fmt::Display for FsckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} memories checked, {} issue(s), {} unrepaired",
+            self.memories_checked,
+            self.issues.len(),
+            self.unrepaired()
+        )?;
+        for issue in &self.issues {
+            let status = match (&issue.repair_error, issue.repaired) {
+                (Some(error), _) => format!(" [repair failed: {error}]"),
+                (None, true) => " [repaired]".to_string(),
+                (None, false) => String::new(),
+            };
+            write!(f, "\n{}: {}{status}", issue.record, issue.kind)?;
+        }
+        Ok(())
+    }
+}
+
+fn str_field<'a>(row: &'a Value, field: &str) -> &'a str {
+    row.get(field).and_then(Value::as_str).unwrap_or_default()
+}
+
+impl MemoryCoordinator {
+    /// Check the library's integrity, repairing what can be repaired if `repair`
+    ///
+    /// Embeddings are checked against, and regenerated with, the model
+    /// recorded for the library.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the database cannot be read, or when the library
+    /// records a different embedding model than this coordinator's. Failed
+    /// repairs are recorded on their issue instead.
+    pub async fn fsck(&self, repair: bool) -> Result<FsckReport> {
+        let model_key = self.embedding_model.info().registry_key;
+        if let Some(recorded) = self.surreal_manager.library_embedding_model().await?
+            && recorded != model_key
+        {
+            return Err(Error::Config(format!(
+                "Library is embedded with '{recorded}', not '{model_key}'; \
+                 open it with MemoryCoordinator::from_library to check it"
+            )));
+        }
+
+        let mut report = FsckReport::default();
+        let mut memory_ids = HashSet::new();
+        let expected_dims = self.embedding_model.embedding_dimension();
+
+        let mut offset = 0;
+        loop {
+            let page = self
+                .fsck_rows(&format!(
+                    "SELECT meta::id(id) AS id, content, content_hash, \
+                     array::len(metadata.embedding ?? []) AS dims \
+                     FROM memory LIMIT {FSCK_PAGE_SIZE} START {offset}"
+                ))
+                .await?;
+            for row in &page {
+                let id = str_field(row, "id").to_string();
+                let content = str_field(row, "content");
+                let dims = row.get("dims").and_then(Value::as_u64).unwrap_or(0) as usize;
+
+                let dimension_issue = match dims {
+                    0 => Some(FsckIssueKind::MissingEmbedding),
+                    actual if actual != expected_dims => Some(FsckIssueKind::EmbeddingDimension {
+                        expected: expected_dims,
+                        actual,
+                    }),
+                    _ => None,
+                };
+                if let Some(kind) = dimension_issue {
+                    let outcome = repair.then(|| self.fsck_reembed(&id, content));
+                    report.push(kind, format!("memory:{id}"), outcome).await;
+                }
+
+                let hash = content_hash(content);
+                if row.get("content_hash").and_then(Value::as_i64) != Some(hash) {
+                    let outcome = repair.then(|| {
+                        self.fsck_execute(format!(
+                            "UPDATE type::thing('memory', {}) SET content_hash = {hash}",
+                            Value::from(id.as_str())
+                        ))
+                    });
+                    report
+                        .push(FsckIssueKind::HashMismatch, format!("memory:{id}"), outcome)
+                        .await;
+                }
+
+                memory_ids.insert(id);
+            }
+            report.memories_checked += page.len();
+            if page.len() < FSCK_PAGE_SIZE {
+                break;
+            }
+            offset += FSCK_PAGE_SIZE;
+        }
+
+        for row in self
+            .fsck_rows("SELECT meta::id(id) AS id, memory_id FROM quantum_signature")
+            .await?
+        {
+            if !memory_ids.contains(str_field(&row, "memory_id")) {
+                let id = str_field(&row, "id");
+                let record = format!("quantum_signature:{id}");
+                let outcome = repair.then(|| self.fsck_delete("quantum_signature", id));
+                report.push(FsckIssueKind::OrphanedSignature, record, outcome).await;
+            }
+        }
+
+        for row in self
+            .fsck_rows("SELECT meta::id(id) AS id, source_id, target_id FROM relationship")
+            .await?
+        {
+            let source = str_field(&row, "source_id");
+            let target = str_field(&row, "target_id");
+            if !memory_ids.contains(source) || !memory_ids.contains(target) {
+                let id = str_field(&row, "id");
+                let record = format!("relationship:{id}");
+                let outcome = repair.then(|| self.fsck_delete("relationship", id));
+                report.push(FsckIssueKind::OrphanedRelationship, record, outcome).await;
+            }
+        }
+
+        for table in EDGE_TABLES {
+            for row in self
+                .fsck_rows(&format!(
+                    "SELECT meta::id(id) AS id, meta::id(in) AS source, \
+                     meta::id(out) AS target FROM {table}"
+                ))
+                .await?
+            {
+                let source = str_field(&row, "source");
+                let target = str_field(&row, "target");
+                if !memory_ids.contains(source) || !memory_ids.contains(target) {
+                    let id = str_field(&row, "id");
+                    let outcome = repair.then(|| self.fsck_delete(table, id));
+                    report
+                        .push(FsckIssueKind::OrphanedEdge, format!("{table}:{id}"), outcome)
+                        .await;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Rows returned by `query`
+    async fn fsck_rows(&self, query: &str) -> Result<Vec<Value>> {
+        match self.surreal_manager.execute_query(query).await? {
+            Value::Array(rows) => Ok(rows),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    async fn fsck_execute(&self, query: String) -> Result<()> {
+        self.surreal_manager.execute_query(&query).await.map(|_| ())
+    }
+
+    async fn fsck_delete(&self, table: &str, id: &str) -> Result<()> {
+        self.fsck_execute(format!("DELETE type::thing('{table}', {})", Value::from(id)))
+            .await
+    }
+
+    /// Replace a memory's embedding with a fresh one
+    async fn fsck_reembed(&self, id: &str, content: &str) -> Result<()> {
+        let document_task = self.embedding_tasks().document;
+        let embedding = self.generate_embedding(content, Some(&document_task)).await?;
+        let embedding = serde_json::to_string(&embedding).unwrap_or_default();
+        self.fsck_execute(format!(
+            "UPDATE type::thing('memory', {}) SET metadata.embedding = {embedding}",
+            Value::from(id)
+        ))
+        .await
+    }
+}
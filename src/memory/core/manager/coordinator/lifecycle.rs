@@ -2,23 +2,35 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use moka::sync::Cache;
-use surrealdb::engine::any::connect;
+use surrealdb::Surreal;
+use surrealdb::engine::any::{Any, connect};
 use tokio::sync::RwLock;
 
-use crate::capability::registry::TextEmbeddingModel;
+use crate::capability::registry::{self, TextEmbeddingModel};
+use crate::capability::traits::TextEmbeddingCapable;
 use crate::domain::memory::cognitive::types::CognitiveState;
+use crate::domain::model::traits::CandleModel;
 use crate::memory::cognitive::committee::ModelCommitteeEvaluator;
 use crate::memory::cognitive::quantum::{QuantumRouter, QuantumState};
 use crate::memory::core::cognitive_queue::CognitiveProcessingQueue;
 use crate::memory::core::manager::surreal::SurrealDBMemoryManager;
+use crate::memory::core::ops::ranking::{MemoryRanker, default_ranker};
+use crate::memory::migration::LibrarySchema;
 use crate::memory::repository::MemoryRepository;
 use crate::memory::utils::{Error, Result};
 
+use super::embedding_tasks::EmbeddingTasks;
+use super::recall_stats::RecallHits;
+use super::snapshot::WriteGenerations;
 use super::types::LazyEvalStrategy;
 
+/// Embedding model of libraries written before the model was recorded
+pub const LEGACY_EMBEDDING_MODEL: &str = "dunzhang/stella_en_400M_v5";
+
 /// High-level memory manager that uses SurrealDB's native capabilities directly
 ///
 /// Note: cognitive_queue, committee_evaluator, quantum_router, and quantum_state
@@ -29,6 +41,8 @@ pub struct MemoryCoordinator {
     pub(in crate::memory::core) surreal_manager: Arc<SurrealDBMemoryManager>,
     pub(super) repository: Arc<RwLock<MemoryRepository>>,
     pub(super) embedding_model: TextEmbeddingModel,
+    /// Whether the library records `embedding_model` as its model
+    pub(super) embedding_model_recorded: Arc<AtomicBool>,
     // NEW COGNITIVE FIELDS:
     pub(super) cognitive_queue: Arc<CognitiveProcessingQueue>,
     pub(super) committee_evaluator: Arc<ModelCommitteeEvaluator>,
@@ -42,6 +56,16 @@ pub struct MemoryCoordinator {
     // TEMPORAL DECAY:
     pub(in crate::memory::core) decay_rate: f64,
     pub(super) decay_shutdown_tx: Option<tokio::sync::watch::Sender<bool>>,
+    // READ SNAPSHOTS:
+    pub(super) generations: Arc<WriteGenerations>,
+    // RECALL TRACKING:
+    pub(super) recall_hits: Arc<RecallHits>,
+    // EMBEDDING TASKS:
+    pub(super) embedding_tasks: Arc<parking_lot::RwLock<EmbeddingTasks>>,
+    pub(super) embedding_task_override: Option<EmbeddingTasks>,
+    // RECALL SETTINGS:
+    pub(super) recall_min_score: Arc<parking_lot::RwLock<Option<f32>>>,
+    pub(super) ranker: Arc<parking_lot::RwLock<Arc<dyn MemoryRanker>>>,
 }
 
 impl MemoryCoordinator {
@@ -110,6 +134,12 @@ impl MemoryCoordinator {
             measurement_count: 0,
         };
 
+        // Tasks set for this library, else the s2p defaults
+        let embedding_tasks = Self::load_embedding_tasks(&surreal_manager)
+            .await
+            .unwrap_or_default();
+        let recall_min_score = Self::load_recall_min_score(&surreal_manager).await;
+
         // Create shutdown channel for decay worker
         let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
 
@@ -117,6 +147,7 @@ impl MemoryCoordinator {
             surreal_manager,
             repository: Arc::new(RwLock::new(MemoryRepository::new())),
             embedding_model,
+            embedding_model_recorded: Arc::new(AtomicBool::new(false)),
             cognitive_queue,
             committee_evaluator,
             quantum_router,
@@ -130,8 +161,17 @@ impl MemoryCoordinator {
                 .build(),
             decay_rate: 0.1,
             decay_shutdown_tx: Some(shutdown_tx),
+            generations: Arc::new(WriteGenerations::default()),
+            recall_hits: Arc::new(RecallHits::default()),
+            embedding_tasks: Arc::new(parking_lot::RwLock::new(embedding_tasks)),
+            embedding_task_override: None,
+            recall_min_score: Arc::new(parking_lot::RwLock::new(recall_min_score)),
+            ranker: Arc::new(parking_lot::RwLock::new(default_ranker())),
         };
 
+        // Write recall hits back on an interval and when shutting down
+        coordinator.start_recall_flush(shutdown_rx.clone());
+
         // Spawn decay worker for background temporal decay processing
         let coordinator_arc = Arc::new(coordinator);
         let decay_config = crate::memory::core::decay_worker::DecayWorkerConfig::default();
@@ -158,7 +198,11 @@ impl MemoryCoordinator {
     ///
     /// # Arguments
     /// * `library_name` - Library identifier (e.g., "test", "production")
-    /// * `embedding_model` - Text embedding model for auto-embedding generation
+    /// * `embedding_model` - Text embedding model for auto-embedding generation.
+    ///   A library keeps the model it was first written with: once a memory
+    ///   is stored, later opens use the recorded model instead. Libraries
+    ///   written before models were recorded are taken to use
+    ///   [`LEGACY_EMBEDDING_MODEL`], which is recorded when they are opened.
     ///
     /// # Example
     /// ```no_run
@@ -183,16 +227,28 @@ impl MemoryCoordinator {
             ));
         }
 
-        if library_name.is_empty() {
-            return Err(Error::InvalidInput("Library name cannot be empty".into()));
-        }
-
         // Construct path: kodegen data dir + memory/{library}.db
         let db_path = kodegen_config::KodegenConfig::data_dir()
             .unwrap_or_else(|_| PathBuf::from("."))
             .join("memory")
             .join(format!("{}.db", library_name));
 
+        Self::from_library_at(library_name, &db_path, embedding_model).await
+    }
+
+    /// Create a MemoryCoordinator for `library_name` stored at `db_path`
+    ///
+    /// Like [`Self::from_library`], but the database lives at the given path
+    /// instead of the kodegen data directory. Parent directories are created.
+    pub async fn from_library_at(
+        library_name: &str,
+        db_path: &std::path::Path,
+        embedding_model: TextEmbeddingModel,
+    ) -> Result<Self> {
+        if library_name.is_empty() {
+            return Err(Error::InvalidInput("Library name cannot be empty".into()));
+        }
+
         log::info!("Initializing memory library '{}' at: {}", library_name, db_path.display());
 
         // Create directory if needed
@@ -214,16 +270,112 @@ impl MemoryCoordinator {
             .await
             .map_err(|e| Error::Database(format!("Failed to initialize namespace: {:?}", e)))?;
 
+        // Memories are only comparable with queries embedded by the same model,
+        // so a library sticks to the one recorded on its first write
+        let recorded_model = LibrarySchema::new(Arc::new(db.clone()))
+            .await
+            .map_err(|e| Error::Database(format!("Schema version tracking failed: {:?}", e)))?
+            .embedding_model()
+            .await
+            .map_err(|e| {
+                Error::Database(format!("Failed to read library embedding model: {:?}", e))
+            })?;
+        // Memories written before models were recorded all used the legacy model
+        let legacy = recorded_model.is_none() && Self::has_memories(&db).await?;
+        let recorded_model = if legacy {
+            log::warn!(
+                "Library '{}' has memories but no recorded embedding model; assuming {}",
+                library_name,
+                LEGACY_EMBEDDING_MODEL
+            );
+            Some(LEGACY_EMBEDDING_MODEL.to_string())
+        } else {
+            recorded_model
+        };
+        let embedding_model = match recorded_model.as_deref() {
+            Some(key) if key != embedding_model.info().registry_key => {
+                let recorded = registry::get::<TextEmbeddingModel>(key).ok_or_else(|| {
+                    Error::Config(format!(
+                        "Library '{}' is embedded with '{}', which is not in the registry",
+                        library_name, key
+                    ))
+                })?;
+                log::warn!(
+                    "Library '{}' is embedded with {}; ignoring requested model {}",
+                    library_name,
+                    key,
+                    embedding_model.info().registry_key
+                );
+                recorded
+            }
+            _ => embedding_model,
+        };
+
         // Create SurrealDBMemoryManager with embedding model
         let surreal_manager = SurrealDBMemoryManager::with_embedding_model(db, embedding_model.clone());
 
         // Initialize database schema and indexes
         surreal_manager.initialize().await?;
 
+        // Record the legacy model before the dimension check compares against it
+        if legacy {
+            surreal_manager
+                .set_library_embedding_model(LEGACY_EMBEDDING_MODEL)
+                .await?;
+        }
+
+        // Bring the library's recorded schema version up to date for this model
+        let schema = surreal_manager
+            .ensure_library_schema(embedding_model.embedding_dimension())
+            .await?;
+        log::debug!(
+            "Library '{}' at schema v{} ({} dimensions)",
+            library_name,
+            schema.version,
+            schema.embedding_dimension
+        );
+
         let surreal_arc = Arc::new(surreal_manager);
 
         // Delegate to existing new() method for coordinator setup
-        Self::new(surreal_arc, embedding_model).await
+        let coordinator = Self::new(surreal_arc, embedding_model).await?;
+        coordinator
+            .embedding_model_recorded
+            .store(recorded_model.is_some(), Ordering::Release);
+        Ok(coordinator)
+    }
+
+    /// Whether the library behind `db` holds any memory
+    async fn has_memories(db: &Surreal<Any>) -> Result<bool> {
+        let mut response = db
+            .query("SELECT count() AS total FROM memory GROUP ALL")
+            .await
+            .map_err(|e| Error::Database(format!("Failed to count memories: {:?}", e)))?;
+        let total: Option<u64> = response
+            .take("total")
+            .map_err(|e| Error::Database(format!("Failed to count memories: {:?}", e)))?;
+        Ok(total.unwrap_or(0) > 0)
+    }
+
+    /// Embedding model this coordinator's library is embedded with
+    pub fn embedding_model(&self) -> &TextEmbeddingModel {
+        &self.embedding_model
+    }
+
+    /// Record the embedding model in the library before its first write
+    ///
+    /// A no-op once recorded; see [`Self::from_library`].
+    pub(super) async fn record_embedding_model(&self) -> Result<()> {
+        if self.embedding_model_recorded.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let registry_key = self.embedding_model.info().registry_key;
+        self.surreal_manager
+            .set_library_embedding_model(registry_key)
+            .await?;
+        self.embedding_model_recorded.store(true, Ordering::Release);
+        log::info!("Library embedding model recorded: {}", registry_key);
+        Ok(())
     }
 
     /// Configure lazy evaluation strategy
@@ -254,6 +406,14 @@ impl MemoryCoordinator {
         self.decay_rate
     }
 
+    /// Storage manager of this coordinator's library
+    ///
+    /// For serving the library through interfaces built on the storage layer,
+    /// such as the REST API, without opening the database a second time.
+    pub fn memory_manager(&self) -> Arc<SurrealDBMemoryManager> {
+        Arc::clone(&self.surreal_manager)
+    }
+
     /// Shutdown all cognitive worker tasks gracefully
     pub fn shutdown_workers(&mut self) {
         // Flush any pending batches before shutdown
@@ -3,18 +3,58 @@
 //! This module was decomposed from a 1,331-line monolithic file
 //! into 9 focused modules for better maintainability.
 
+mod chunks;
+mod consolidation;
 mod conversions;
+mod embedding_tasks;
+mod fast_search;
+mod fsck;
 mod lifecycle;
 mod operations;
+mod recall_settings;
+mod recall_stats;
 mod relationships;
 mod search;
+mod snapshot;
 mod temporal;
+mod tool_audit;
 mod trait_impl;
+mod transaction;
 mod types;
 mod workers;
 
 // Re-export public types
 pub use types::*;
 
+// Re-export chunk write outcome
+pub use chunks::ChunkUpsert;
+
+// Re-export embedding task settings
+pub use embedding_tasks::{DOCUMENT_TASK, EmbeddingTasks, QUERY_TASK};
+
+// Re-export fast search types
+pub use fast_search::{DEFAULT_FAST_SEARCH_BUDGET, FastSearch};
+
+// Re-export integrity check types
+pub use fsck::{FsckIssue, FsckIssueKind, FsckReport};
+
 // Re-export the main coordinator struct
-pub use lifecycle::MemoryCoordinator;
+pub use lifecycle::{LEGACY_EMBEDDING_MODEL, MemoryCoordinator};
+
+// Re-export recall tracking types
+pub use recall_stats::{
+    DEFAULT_UNUSED_DAYS, RECALL_FLUSH_INTERVAL, RECALL_FLUSH_THRESHOLD, RecallHits, UnusedMemory,
+    UnusedMemoryReport,
+};
+
+// Re-export read snapshot types
+pub use snapshot::{GENERATION_KEY, ReadSnapshot, WriteGeneration, WriteGenerations};
+
+// Re-export tool-call audit types
+pub use tool_audit::{
+    DEFAULT_TOOL_AUDIT_LIMIT, TOOL_AUDIT_PREVIEW_CHARS, ToolAuditEntry, ToolAuditQuery,
+    ToolCallRecord,
+};
+
+// Re-export transaction types
+pub use transaction::{MemoryTransaction, TransactionOutcome};
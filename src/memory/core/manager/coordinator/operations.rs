@@ -1,16 +1,18 @@
 //! Core CRUD operations for memory management
 
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::domain::memory::primitives::node::MemoryNode;
+use crate::domain::memory::primitives::types::{MemoryContent, MemoryTypeEnum};
 use crate::memory::MemoryMetadata;
 use crate::memory::core::cognitive_queue::{CognitiveTask, CognitiveTaskType};
 use crate::memory::core::manager::surreal::trait_def::MemoryManager;
 use crate::memory::utils::Result;
 
 use super::lifecycle::MemoryCoordinator;
-use super::types::LazyEvalStrategy;
+use super::types::{ContentEdit, LazyEvalStrategy, NewMemory};
 
 impl MemoryCoordinator {
     /// Add a new memory to storage with deduplication and cognitive processing
@@ -31,11 +33,9 @@ impl MemoryCoordinator {
     pub async fn add_memory(
         &self,
         content: String,
-        memory_type: crate::domain::memory::primitives::types::MemoryTypeEnum,
+        memory_type: MemoryTypeEnum,
         metadata: Option<MemoryMetadata>,
     ) -> Result<MemoryNode> {
-        use crate::domain::memory::primitives::types::MemoryContent;
-
         // Calculate content hash for deduplication
         let content_hash = crate::domain::memory::serialization::content_hash(&content);
         log::debug!("add_memory: Calculated content_hash = {} for content: {}", 
@@ -130,55 +130,12 @@ impl MemoryCoordinator {
         }
 
         // Create new domain memory node
-        let memory_content = MemoryContent::text(&content);
-        let mut domain_memory = MemoryNode::new(memory_type, memory_content);
-
-        // Apply metadata if provided
-        if let Some(ref metadata) = metadata {
-            // Import user_id, agent_id, context into custom metadata
-            let mut custom_map = std::collections::HashMap::new();
-
-            if let Some(ref user_id) = metadata.user_id {
-                custom_map.insert(
-                    Arc::from("user_id"),
-                    Arc::new(serde_json::Value::String(user_id.clone())),
-                );
-            }
-
-            if let Some(ref agent_id) = metadata.agent_id {
-                custom_map.insert(
-                    Arc::from("agent_id"),
-                    Arc::new(serde_json::Value::String(agent_id.clone())),
-                );
-            }
-
-            custom_map.insert(
-                Arc::from("context"),
-                Arc::new(serde_json::Value::String(metadata.context.clone())),
-            );
-
-            // Apply metadata
-            domain_memory.metadata = Arc::new(
-                crate::domain::memory::primitives::node::MemoryNodeMetadata {
-                    importance: metadata.importance,
-                    keywords: metadata
-                        .keywords
-                        .iter()
-                        .map(|s| Arc::from(s.as_str()))
-                        .collect(),
-                    tags: metadata
-                        .tags
-                        .iter()
-                        .map(|s| Arc::from(s.as_str()))
-                        .collect(),
-                    custom: custom_map,
-                    version: 1,
-                },
-            );
-        }
+        let mut domain_memory = Self::new_domain_node(&content, memory_type, metadata.as_ref());
 
-        // Generate embedding for document (no instruction prefix per Stella's asymmetric design)
-        let embedding = self.generate_embedding(&content, Some("document")).await?;
+        // Embed with the library's document task (by default no instruction prefix)
+        self.record_embedding_model().await?;
+        let document_task = self.embedding_tasks().document;
+        let embedding = self.generate_embedding(&content, Some(&document_task)).await?;
         domain_memory.embedding =
             Some(crate::domain::memory::primitives::node::AlignedEmbedding::new(embedding));
 
@@ -261,6 +218,168 @@ impl MemoryCoordinator {
         Ok(final_domain_memory)
     }
 
+    /// Add several memories with one embedding batch and one transaction
+    ///
+    /// New text memories are embedded in a single batch call and stored
+    /// atomically. Entries whose content already exists (including repeats
+    /// within the batch) or that carry an `image_path` go through
+    /// [`add_memory`](Self::add_memory) afterwards, so duplicate refresh and
+    /// image embedding behave exactly as for single writes.
+    ///
+    /// # Returns
+    /// The created or refreshed memory nodes, in input order
+    pub async fn add_memories(&self, memories: Vec<NewMemory>) -> Result<Vec<MemoryNode>> {
+        let mut results: Vec<Option<MemoryNode>> = (0..memories.len()).map(|_| None).collect();
+        let mut batch = Vec::new();
+        let mut individual = Vec::new();
+        let mut seen_hashes = HashSet::new();
+
+        for (index, memory) in memories.into_iter().enumerate() {
+            let content_hash = crate::domain::memory::serialization::content_hash(&memory.content);
+            let has_image = memory
+                .metadata
+                .as_ref()
+                .is_some_and(|m| m.custom.get("image_path").is_some());
+            let duplicate = !seen_hashes.insert(content_hash)
+                || self
+                    .surreal_manager
+                    .document_exists_by_hash(content_hash)
+                    .await?;
+
+            if has_image || duplicate {
+                individual.push((index, memory));
+            } else {
+                batch.push((index, memory));
+            }
+        }
+
+        if !batch.is_empty() {
+            let texts: Vec<String> = batch.iter().map(|(_, m)| m.content.clone()).collect();
+
+            self.record_embedding_model().await?;
+            let document_task = self.embedding_tasks().document;
+            let embeddings = self.generate_embeddings(&texts, Some(&document_task)).await?;
+
+            let nodes = batch
+                .iter()
+                .zip(embeddings)
+                .map(|((_, memory), embedding)| {
+                    let mut domain_memory = Self::new_domain_node(
+                        &memory.content,
+                        memory.memory_type,
+                        memory.metadata.as_ref(),
+                    );
+                    domain_memory.embedding = Some(
+                        crate::domain::memory::primitives::node::AlignedEmbedding::new(embedding),
+                    );
+                    self.convert_domain_to_memory_node(&domain_memory)
+                })
+                .collect();
+
+            let stored = self.surreal_manager.create_memories(nodes).await?;
+
+            {
+                let mut repo = self.repository.write().await;
+                for memory in &stored {
+                    repo.add(memory.clone());
+                }
+            }
+
+            for ((index, _), stored_memory) in batch.iter().zip(stored) {
+                let task = CognitiveTask::new(
+                    stored_memory.id.clone(),
+                    CognitiveTaskType::CommitteeEvaluation,
+                    5, // Default priority
+                );
+                self.cognitive_queue
+                    .enqueue(task)
+                    .map_err(crate::memory::utils::Error::Internal)?;
+                results[*index] = Some(self.convert_memory_to_domain_node(&stored_memory)?);
+            }
+        }
+
+        for (index, memory) in individual {
+            results[index] = Some(
+                self.add_memory(memory.content, memory.memory_type, memory.metadata)
+                    .await?,
+            );
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Build an unsaved domain node with metadata applied
+    pub(super) fn new_domain_node(
+        content: &str,
+        memory_type: MemoryTypeEnum,
+        metadata: Option<&MemoryMetadata>,
+    ) -> MemoryNode {
+        Self::new_domain_node_with_id(uuid::Uuid::new_v4(), content, memory_type, metadata)
+    }
+
+    /// Build an unsaved domain node with a given ID and metadata applied
+    pub(super) fn new_domain_node_with_id(
+        id: uuid::Uuid,
+        content: &str,
+        memory_type: MemoryTypeEnum,
+        metadata: Option<&MemoryMetadata>,
+    ) -> MemoryNode {
+        let mut domain_memory = MemoryNode::with_id(id, memory_type, MemoryContent::text(content));
+
+        // Apply metadata if provided
+        if let Some(metadata) = metadata {
+            // Import user_id, agent_id, context into custom metadata
+            let mut custom_map = std::collections::HashMap::new();
+
+            // Caller-supplied custom fields (session IDs, write generation)
+            if let serde_json::Value::Object(ref custom) = metadata.custom {
+                for (key, value) in custom {
+                    custom_map.insert(Arc::from(key.as_str()), Arc::new(value.clone()));
+                }
+            }
+
+            if let Some(ref user_id) = metadata.user_id {
+                custom_map.insert(
+                    Arc::from("user_id"),
+                    Arc::new(serde_json::Value::String(user_id.clone())),
+                );
+            }
+
+            if let Some(ref agent_id) = metadata.agent_id {
+                custom_map.insert(
+                    Arc::from("agent_id"),
+                    Arc::new(serde_json::Value::String(agent_id.clone())),
+                );
+            }
+
+            custom_map.insert(
+                Arc::from("context"),
+                Arc::new(serde_json::Value::String(metadata.context.clone())),
+            );
+
+            // Apply metadata
+            domain_memory.metadata = Arc::new(
+                crate::domain::memory::primitives::node::MemoryNodeMetadata {
+                    importance: metadata.importance,
+                    keywords: metadata
+                        .keywords
+                        .iter()
+                        .map(|s| Arc::from(s.as_str()))
+                        .collect(),
+                    tags: metadata
+                        .tags
+                        .iter()
+                        .map(|s| Arc::from(s.as_str()))
+                        .collect(),
+                    custom: custom_map,
+                    version: 1,
+                },
+            );
+        }
+
+        domain_memory
+    }
+
     /// Retrieve a memory by ID with lazy evaluation support
     ///
     /// Supports three evaluation strategies:
@@ -354,7 +473,7 @@ impl MemoryCoordinator {
             }
         }
 
-        // NOTE: Temporal decay now applied by background DecayWorker
+        // NOTE: Importance decay now applied by background consolidation passes
         // Removed lazy evaluation from read path for performance
 
         // Handle lazy evaluation based on strategy
@@ -481,6 +600,83 @@ impl MemoryCoordinator {
         Ok(())
     }
 
+    /// Replace a memory's content with `placeholder` and drop its embedding
+    ///
+    /// The memory keeps its ID, metadata and relationships, and gains `tag`.
+    /// Returns `false` when no memory has that ID.
+    pub async fn redact_memory(&self, memory_id: &str, placeholder: &str, tag: &str) -> Result<bool> {
+        let Some(mut memory) = self.surreal_manager.get_memory(memory_id).await? else {
+            return Ok(false);
+        };
+
+        memory.content = crate::memory::core::primitives::types::MemoryContent::new(placeholder);
+        memory.content_hash = crate::domain::memory::serialization::content_hash(placeholder);
+        memory.embedding = None;
+        memory.metadata.embedding = None;
+        if !memory.metadata.tags.iter().any(|t| t == tag) {
+            memory.metadata.tags.push(tag.to_string());
+        }
+        memory.updated_at = surrealdb_types::Datetime::now();
+
+        let updated = self.surreal_manager.update_memory(memory).await?;
+        {
+            let mut repo = self.repository.write().await;
+            repo.update(updated);
+        }
+        self.evaluation_cache.invalidate(memory_id);
+
+        log::info!("Redacted memory: {}", memory_id);
+
+        Ok(true)
+    }
+
+    /// Replace or append to a memory's content and re-embed it
+    ///
+    /// The content hash is recomputed, the embedding regenerated with the
+    /// library's document task and `updated_at` bumped, so vector search
+    /// finds the memory by its new content; a cached evaluation of the old
+    /// content is dropped. Returns `None` when no memory has that ID.
+    ///
+    /// # Errors
+    ///
+    /// `AlreadyExists` if another memory already holds the new content.
+    pub async fn edit_memory(&self, memory_id: &str, edit: &ContentEdit) -> Result<Option<MemoryNode>> {
+        let Some(mut memory) = self.surreal_manager.get_memory(memory_id).await? else {
+            return Ok(None);
+        };
+
+        let content = edit.apply(&memory.content.text);
+        let hash = crate::domain::memory::serialization::content_hash(&content);
+        if let Some(existing) = self.surreal_manager.find_document_by_hash(hash).await?
+            && existing.id != memory.id
+        {
+            return Err(crate::memory::utils::Error::AlreadyExists(format!(
+                "memory {} already has this content",
+                existing.id
+            )));
+        }
+
+        let document_task = self.embedding_tasks().document;
+        let embedding = self.generate_embedding(&content, Some(&document_task)).await?;
+
+        memory.content_hash = hash;
+        memory.content = crate::memory::core::primitives::types::MemoryContent::new(&content);
+        memory.embedding = Some(embedding.clone());
+        memory.metadata.embedding = Some(embedding);
+        memory.updated_at = surrealdb_types::Datetime::now();
+
+        let updated = self.surreal_manager.update_memory(memory).await?;
+        {
+            let mut repo = self.repository.write().await;
+            repo.update(updated.clone());
+        }
+        self.evaluation_cache.invalidate(memory_id);
+
+        log::info!("Edited memory: {}", memory_id);
+
+        Ok(Some(self.convert_memory_to_domain_node(&updated)?))
+    }
+
     /// Get cognitive performance statistics
     ///
     /// Returns atomic counters for cognitive operations. All counters are
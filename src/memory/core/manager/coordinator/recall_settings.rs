@@ -0,0 +1,94 @@
+//! Per-library recall settings
+//!
+//! A library can carry a minimum relevance for recall: hits scoring below it
+//! are left out, so callers get no context rather than irrelevant context.
+//! Like the embedding tasks, the setting is stored in the library's database
+//! and a recall request can override it.
+//!
+//! A library can also be given a [`MemoryRanker`] that orders its recall
+//! hits. Rankers are code, so they are registered for the life of the
+//! process rather than stored.
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::memory::core::ops::ranking::MemoryRanker;
+use crate::memory::core::manager::surreal::SurrealDBMemoryManager;
+use crate::memory::utils::{Error, Result};
+
+use super::lifecycle::MemoryCoordinator;
+
+/// Record holding a library's recall settings
+const SETTINGS_RECORD: &str = "library_settings:recall";
+
+impl MemoryCoordinator {
+    /// The library's minimum recall relevance, if one is set
+    pub fn recall_min_score(&self) -> Option<f32> {
+        *self.recall_min_score.read()
+    }
+
+    /// Store `min_score` as the library's minimum recall relevance; `None`
+    /// removes it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the score is outside `0.0..=1.0` or the setting
+    /// cannot be written.
+    pub async fn set_recall_min_score(&self, min_score: Option<f32>) -> Result<()> {
+        if let Some(score) = min_score
+            && !(0.0..=1.0).contains(&score)
+        {
+            return Err(Error::InvalidInput(format!(
+                "min_score must be between 0 and 1, got {score}"
+            )));
+        }
+        let value = min_score.map_or_else(
+            || "NONE".to_string(),
+            |score| Value::from(f64::from(score)).to_string(),
+        );
+        self.surreal_manager
+            .execute_query(&format!(
+                "UPSERT {SETTINGS_RECORD} SET min_score = {value};"
+            ))
+            .await?;
+        log::info!("Recall min_score set to {:?}", min_score);
+        *self.recall_min_score.write() = min_score;
+        Ok(())
+    }
+
+    /// The ranker ordering the library's recall hits
+    pub fn ranker(&self) -> Arc<dyn MemoryRanker> {
+        Arc::clone(&self.ranker.read())
+    }
+
+    /// Order the library's recall hits with `ranker`
+    ///
+    /// Every handle to the library picks it up, including those opened
+    /// through the coordinator pool afterwards.
+    pub fn set_ranker(&self, ranker: Arc<dyn MemoryRanker>) {
+        log::info!("Recall ranker set to {}", ranker.name());
+        *self.ranker.write() = ranker;
+    }
+
+    /// Read the library's stored minimum recall relevance, if any was set
+    pub(super) async fn load_recall_min_score(
+        surreal_manager: &SurrealDBMemoryManager,
+    ) -> Option<f32> {
+        let rows = match surreal_manager
+            .execute_query(&format!("SELECT min_score FROM {SETTINGS_RECORD};"))
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                log::warn!("Failed to read recall settings, using defaults: {}", e);
+                return None;
+            }
+        };
+        let row = match &rows {
+            Value::Array(rows) => rows.first()?,
+            row => row,
+        };
+        row.get("min_score")?.as_f64().map(|score| score as f32)
+    }
+}
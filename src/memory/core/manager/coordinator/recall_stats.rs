@@ -0,0 +1,282 @@
+//! Recall hit tracking and the unused-memory report
+//!
+//! Every memory returned by a search counts as a recall hit. Hits are
+//! counted in memory and written back in batches, as
+//! `metadata.recall_count` and `metadata.last_recalled_at`: once
+//! [`RECALL_FLUSH_THRESHOLD`] distinct memories have pending hits, every
+//! [`RECALL_FLUSH_INTERVAL`], when the coordinator or the process shuts
+//! down, and when [`MemoryCoordinator::flush_recall_hits`] is called.
+//!
+//! [`MemoryCoordinator::unused_memories`] uses them to list memories that
+//! no search has returned in a number of days, with the storage each one
+//! takes, so a library can be pruned knowing what is actually used.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use tokio::sync::watch;
+
+use crate::domain::memory::primitives::node::MemoryNode;
+use crate::memory::core::manager::surreal::SurrealDBMemoryManager;
+use crate::memory::utils::Result;
+
+use super::lifecycle::MemoryCoordinator;
+
+/// Memories with pending hits that trigger a write-back
+pub const RECALL_FLUSH_THRESHOLD: usize = 128;
+
+/// Time between periodic write-backs of pending hits
+pub const RECALL_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Days without a recall after which a memory counts as unused
+pub const DEFAULT_UNUSED_DAYS: u32 = 30;
+
+/// Memories read per query while building the report
+const REPORT_PAGE_SIZE: usize = 500;
+
+/// Characters of content shown per memory
+const PREVIEW_CHARS: usize = 160;
+
+/// Recall hits not yet written to the database, by memory ID
+#[derive(Debug, Default)]
+pub struct RecallHits {
+    pending: Mutex<HashMap<String, u64>>,
+}
+
+impl RecallHits {
+    /// Count one hit for each of `ids`, returning how many memories have pending hits
+    pub fn record(&self, ids: impl IntoIterator<Item = String>) -> usize {
+        let mut pending = self.pending.lock();
+        for id in ids {
+            *pending.entry(id).or_default() += 1;
+        }
+        pending.len()
+    }
+
+    /// Memories with pending hits
+    pub fn pending(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    /// Remove and return every pending hit
+    pub fn take(&self) -> HashMap<String, u64> {
+        std::mem::take(&mut *self.pending.lock())
+    }
+
+    /// Put back hits whose write-back failed
+    pub fn restore(&self, hits: HashMap<String, u64>) {
+        let mut pending = self.pending.lock();
+        for (id, count) in hits {
+            *pending.entry(id).or_default() += count;
+        }
+    }
+}
+
+/// A memory no search has returned within the report window
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnusedMemory {
+    pub id: String,
+    /// Start of the content
+    pub preview: String,
+    pub created_at: String,
+    /// Last time a search returned it; `None` if never
+    pub last_recalled_at: Option<String>,
+    /// Searches that returned it since tracking began
+    pub recall_count: u64,
+    /// Content plus embedding size in bytes
+    pub bytes: usize,
+}
+
+/// Outcome of [`MemoryCoordinator::unused_memories`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnusedMemoryReport {
+    /// Window in days
+    pub days: u32,
+    /// Memories examined
+    pub memories_checked: usize,
+    /// Bytes taken by every memory examined
+    pub total_bytes: usize,
+    /// Memories not recalled within the window
+    pub unused_count: usize,
+    /// Bytes taken by those memories
+    pub unused_bytes: usize,
+    /// The largest unused memories, largest first
+    pub memories: Vec<UnusedMemory>,
+}
+
+impl MemoryCoordinator {
+    /// Count a recall hit for each returned memory
+    ///
+    /// Starts a background write-back once enough memories have pending hits.
+    pub(super) fn note_recall_hits(&self, memories: &[MemoryNode]) {
+        if memories.is_empty() {
+            return;
+        }
+        let ids = memories.iter().map(|memory| memory.id().simple().to_string());
+        let pending = self.recall_hits.record(ids);
+        if pending >= RECALL_FLUSH_THRESHOLD {
+            let coordinator = self.clone();
+            crate::runtime::supervisor().spawn("recall hit write-back", async move {
+                if let Err(e) = coordinator.flush_recall_hits().await {
+                    log::warn!("Failed to write back recall hits: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Write pending recall hits to the database, returning the memories updated
+    ///
+    /// # Errors
+    ///
+    /// Returns the database error; the hits stay pending for the next flush.
+    pub async fn flush_recall_hits(&self) -> Result<usize> {
+        write_back(&self.recall_hits, &self.surreal_manager).await
+    }
+
+    /// Write pending hits back every [`RECALL_FLUSH_INTERVAL`], and a last
+    /// time when `shutdown` fires, every coordinator clone is gone, or the
+    /// process shuts down
+    pub(super) fn start_recall_flush(&self, mut shutdown: watch::Receiver<bool>) {
+        let hits = self.recall_hits.clone();
+        let manager = self.surreal_manager.clone();
+        let supervisor = crate::runtime::supervisor();
+        let cancelled = supervisor.cancellation_token();
+        supervisor.spawn("recall hit flush", async move {
+            let mut interval = tokio::time::interval(RECALL_FLUSH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval.tick().await;
+            loop {
+                let stopping = tokio::select! {
+                    _ = interval.tick() => false,
+                    () = cancelled.cancelled() => true,
+                    changed = shutdown.changed() => changed.is_err() || *shutdown.borrow(),
+                };
+                if let Err(e) = write_back(&hits, &manager).await {
+                    log::warn!("Failed to write back recall hits: {}", e);
+                }
+                if stopping {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Memories older than `days` that no search has returned in `days`
+    ///
+    /// Pending hits are written back first. At most `limit` memories are
+    /// listed, largest first; the counts and byte totals cover all of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the database cannot be read.
+    pub async fn unused_memories(&self, days: u32, limit: usize) -> Result<UnusedMemoryReport> {
+        if let Err(e) = self.flush_recall_hits().await {
+            log::warn!("Unused-memory report may miss recent recalls: {}", e);
+        }
+
+        let mut report = UnusedMemoryReport {
+            days,
+            ..UnusedMemoryReport::default()
+        };
+        let mut offset = 0;
+        loop {
+            let page = match self
+                .surreal_manager
+                .execute_query(&format!(
+                    "SELECT meta::id(id) AS id, content, created_at, \
+                     array::len(metadata.embedding ?? []) AS dims, \
+                     metadata.recall_count ?? 0 AS recall_count, \
+                     metadata.last_recalled_at AS last_recalled_at, \
+                     created_at < time::now() - {days}d \
+                     AND (metadata.last_recalled_at IS NONE \
+                     OR metadata.last_recalled_at < time::now() - {days}d) AS unused \
+                     FROM memory ORDER BY created_at LIMIT {REPORT_PAGE_SIZE} START {offset}"
+                ))
+                .await?
+            {
+                Value::Array(rows) => rows,
+                _ => Vec::new(),
+            };
+
+            for row in &page {
+                let content = row.get("content").and_then(Value::as_str).unwrap_or_default();
+                let dims = row.get("dims").and_then(Value::as_u64).unwrap_or(0) as usize;
+                let bytes = content.len() + dims * std::mem::size_of::<f32>();
+                report.total_bytes += bytes;
+
+                if row.get("unused").and_then(Value::as_bool) != Some(true) {
+                    continue;
+                }
+                report.unused_count += 1;
+                report.unused_bytes += bytes;
+                report.memories.push(UnusedMemory {
+                    id: str_field(row, "id").to_string(),
+                    preview: content.chars().take(PREVIEW_CHARS).collect(),
+                    created_at: str_field(row, "created_at").to_string(),
+                    last_recalled_at: row
+                        .get("last_recalled_at")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                    recall_count: row.get("recall_count").and_then(Value::as_u64).unwrap_or(0),
+                    bytes,
+                });
+            }
+            // Keep memory bounded on large libraries
+            if report.memories.len() > limit * 2 {
+                largest_first(&mut report.memories, limit);
+            }
+
+            report.memories_checked += page.len();
+            if page.len() < REPORT_PAGE_SIZE {
+                break;
+            }
+            offset += REPORT_PAGE_SIZE;
+        }
+
+        largest_first(&mut report.memories, limit);
+        Ok(report)
+    }
+}
+
+/// Write `hits` to the database, returning the memories updated
+///
+/// On failure the hits are put back for the next write-back.
+async fn write_back(hits: &RecallHits, manager: &SurrealDBMemoryManager) -> Result<usize> {
+    let pending = hits.take();
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let query: String = pending
+        .iter()
+        .map(|(id, count)| {
+            format!(
+                "UPDATE type::thing('memory', {}) SET \
+                 metadata.recall_count = (metadata.recall_count ?? 0) + {count}, \
+                 metadata.last_recalled_at = time::now();",
+                Value::from(id.as_str())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match manager.execute_query(&query).await {
+        Ok(_) => Ok(pending.len()),
+        Err(e) => {
+            hits.restore(pending);
+            Err(e)
+        }
+    }
+}
+
+fn str_field<'a>(row: &'a Value, field: &str) -> &'a str {
+    row.get(field).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Keep the `limit` largest memories, largest first
+fn largest_first(memories: &mut Vec<UnusedMemory>, limit: usize) {
+    memories.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.id.cmp(&b.id)));
+    memories.truncate(limit);
+}
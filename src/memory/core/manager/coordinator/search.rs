@@ -58,6 +58,8 @@ impl MemoryCoordinator {
             routing_decision.confidence
         );
 
+        let query_task = self.embedding_tasks().query;
+
         // Dispatch based on strategy
         let memory_stream = match routing_decision.strategy {
             crate::memory::cognitive::quantum::types::RoutingStrategy::Attention => {
@@ -66,13 +68,13 @@ impl MemoryCoordinator {
             }
             crate::memory::cognitive::quantum::types::RoutingStrategy::Quantum => {
                 // Pure vector similarity search
-                let query_embedding = self.generate_embedding(query, Some("search_query")).await?;
+                let query_embedding = self.generate_embedding(query, Some(&query_task)).await?;
                 self.surreal_manager
                     .search_by_vector(query_embedding, top_k * 5)
             }
             crate::memory::cognitive::quantum::types::RoutingStrategy::Emergent => {
                 // Emergent pattern search: vector seeds + entanglement graph expansion
-                let query_embedding = self.generate_embedding(query, Some("search_query")).await?;
+                let query_embedding = self.generate_embedding(query, Some(&query_task)).await?;
                 self.surreal_manager.search_with_entanglement(
                     query_embedding,
                     top_k * 5,
@@ -81,7 +83,7 @@ impl MemoryCoordinator {
             }
             crate::memory::cognitive::quantum::types::RoutingStrategy::Causal => {
                 // Causal/temporal search: vector seeds + causal chain traversal via ->caused edges
-                let query_embedding = self.generate_embedding(query, Some("search_query")).await?;
+                let query_embedding = self.generate_embedding(query, Some(&query_task)).await?;
                 self.surreal_manager.search_with_causal_expansion(
                     query_embedding,
                     top_k * 5,
@@ -90,7 +92,7 @@ impl MemoryCoordinator {
             }
             crate::memory::cognitive::quantum::types::RoutingStrategy::Hybrid(ref strategies) => {
                 // Hybrid search: execute multiple strategies and merge results
-                let query_embedding = self.generate_embedding(query, Some("search_query")).await?;
+                let query_embedding = self.generate_embedding(query, Some(&query_task)).await?;
 
                 let mut all_results = Vec::new();
                 let mut seen_ids = std::collections::HashSet::new();
@@ -171,7 +173,7 @@ impl MemoryCoordinator {
             }
         }
 
-        // NOTE: Temporal decay now applied by background DecayWorker
+        // NOTE: Importance decay now applied by background consolidation passes
         // Removed lazy evaluation from read path for performance
 
         // Apply optional filter
@@ -309,6 +311,7 @@ impl MemoryCoordinator {
 
         // Apply top_k limit after sorting
         boosted_memories.truncate(top_k);
+        self.note_recall_hits(&boosted_memories);
 
         // Update cognitive state with query pattern for adaptive routing
         {
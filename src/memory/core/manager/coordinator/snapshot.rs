@@ -0,0 +1,260 @@
+//! Read snapshots over a library during bulk writes
+//!
+//! Bulk writers such as memorize sessions open a [`WriteGeneration`] and
+//! stamp every memory they store with its number under [`GENERATION_KEY`].
+//! A [`ReadSnapshot`] records the newest generation handed out and those still
+//! open when it was taken. Searches through a snapshot skip memories from
+//! open or later generations, so every recall made with it ranks the same
+//! library version even while thousands of chunks are being written.
+//!
+//! Generation numbers and snapshots both follow the wall clock in
+//! microseconds, so memories stamped by an earlier process always fall inside
+//! snapshots taken after a restart, before this process opens any generation.
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+
+use crate::domain::memory::primitives::node::MemoryNode;
+use crate::memory::MemoryMetadata;
+use crate::memory::core::ops::filter::MemoryFilter;
+use crate::memory::core::ops::ranking::{MemoryRanker, ScoredMemory, rank_memories};
+use crate::memory::utils::Result;
+
+use super::lifecycle::MemoryCoordinator;
+
+/// Custom metadata key holding the generation a memory was written in
+pub const GENERATION_KEY: &str = "write_generation";
+
+/// Candidates searched per requested result while writes are pending
+const SNAPSHOT_OVERFETCH: usize = 2;
+
+#[derive(Debug, Default)]
+struct GenerationState {
+    /// Newest generation handed out
+    last: u64,
+    /// Generations whose writer has not finished
+    open: BTreeSet<u64>,
+}
+
+/// Wall clock in microseconds since the Unix epoch
+fn now_micros() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_micros()).unwrap_or(u64::MAX))
+}
+
+/// Write generations of one library
+#[derive(Debug, Default)]
+pub struct WriteGenerations {
+    state: Mutex<GenerationState>,
+    closed: Notify,
+}
+
+impl WriteGenerations {
+    fn state(&self) -> std::sync::MutexGuard<'_, GenerationState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Open a generation; it closes when the returned handle is dropped
+    pub fn begin_write(self: &Arc<Self>) -> WriteGeneration {
+        let mut state = self.state();
+        let generation = now_micros().max(state.last + 1);
+        state.last = generation;
+        state.open.insert(generation);
+        WriteGeneration {
+            generation,
+            generations: Arc::clone(self),
+        }
+    }
+
+    fn end(&self, generation: u64) {
+        self.state().open.remove(&generation);
+        self.closed.notify_waiters();
+    }
+
+    /// Snapshot of everything written so far, excluding open generations
+    ///
+    /// The snapshot reaches up to the current time, so generations stamped
+    /// before this process started are included; generations opened after
+    /// it are numbered above it.
+    pub fn snapshot(&self) -> ReadSnapshot {
+        let mut state = self.state();
+        state.last = state.last.max(now_micros());
+        ReadSnapshot {
+            generation: state.last,
+            pending: state.open.iter().copied().collect(),
+        }
+    }
+
+    /// Wait until none of `generations` is open
+    async fn wait_closed(&self, generations: &[u64]) {
+        loop {
+            let notified = self.closed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if !generations.iter().any(|g| self.state().open.contains(g)) {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// An open bulk write; closes when dropped
+///
+/// Memories stamped with [`WriteGeneration::stamp`] stay out of snapshots
+/// taken before the write closes.
+#[derive(Debug)]
+#[must_use = "the generation closes as soon as it is dropped"]
+pub struct WriteGeneration {
+    generation: u64,
+    generations: Arc<WriteGenerations>,
+}
+
+impl WriteGeneration {
+    /// Generation number
+    pub fn id(&self) -> u64 {
+        self.generation
+    }
+
+    /// Mark `metadata` as written in this generation
+    pub fn stamp(&self, metadata: &mut MemoryMetadata) {
+        // Serializing a u64 cannot fail
+        let _ = metadata.set_custom(GENERATION_KEY, self.generation);
+    }
+}
+
+impl Drop for WriteGeneration {
+    fn drop(&mut self) {
+        self.generations.end(self.generation);
+    }
+}
+
+/// Consistent view of a library taken at one point in time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadSnapshot {
+    generation: u64,
+    pending: Vec<u64>,
+}
+
+impl Default for ReadSnapshot {
+    /// Snapshot that includes every memory
+    fn default() -> Self {
+        Self {
+            generation: u64::MAX,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl ReadSnapshot {
+    /// Snapshot at `generation` excluding the `pending` generations
+    pub fn new(generation: u64, pending: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            generation,
+            pending: pending.into_iter().collect(),
+        }
+    }
+
+    /// Newest generation the snapshot can include
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Generations that were still being written when the snapshot was taken
+    pub fn pending(&self) -> &[u64] {
+        &self.pending
+    }
+
+    /// Whether a memory written in `generation` is part of the snapshot
+    ///
+    /// Memories written outside any generation are always included.
+    pub fn includes(&self, generation: Option<u64>) -> bool {
+        generation.is_none_or(|g| g <= self.generation && !self.pending.contains(&g))
+    }
+
+    /// Whether `memory` is part of the snapshot
+    pub fn contains(&self, memory: &MemoryNode) -> bool {
+        self.includes(
+            memory
+                .metadata
+                .custom
+                .get(GENERATION_KEY)
+                .and_then(|v| v.as_u64()),
+        )
+    }
+}
+
+impl MemoryCoordinator {
+    /// Open a write generation for a bulk write
+    pub fn begin_write(&self) -> WriteGeneration {
+        self.generations.begin_write()
+    }
+
+    /// Snapshot of the library as of now, excluding writes still in progress
+    pub fn read_snapshot(&self) -> ReadSnapshot {
+        self.generations.snapshot()
+    }
+
+    /// Wait for the writes in progress now to finish, then take a snapshot
+    pub async fn snapshot_after_writes(&self) -> ReadSnapshot {
+        let pending = self.generations.snapshot().pending;
+        self.generations.wait_closed(&pending).await;
+        self.read_snapshot()
+    }
+
+    /// [`Self::search_memories`] restricted to the memories in `snapshot`
+    ///
+    /// While writes are pending, extra candidates are searched so hits
+    /// dropped from newer generations do not shrink the result set.
+    pub async fn search_memories_at(
+        &self,
+        query: &str,
+        top_k: usize,
+        filter: Option<MemoryFilter>,
+        snapshot: &ReadSnapshot,
+    ) -> Result<Vec<MemoryNode>> {
+        let current = self.generations.snapshot();
+        let unchanged = snapshot.pending.is_empty() && current.generation <= snapshot.generation;
+        let candidates = if unchanged {
+            top_k
+        } else {
+            top_k.saturating_mul(SNAPSHOT_OVERFETCH)
+        };
+
+        let mut memories = self.search_memories(query, candidates, filter).await?;
+        memories.retain(|memory| snapshot.contains(memory));
+        memories.truncate(top_k);
+        Ok(memories)
+    }
+
+    /// [`Self::search_memories_at`], ordered by `ranker`
+    ///
+    /// Without a ranker, the library's own ([`Self::ranker`]) is used. The
+    /// query is embedded again only for rankers that read the embedding.
+    pub async fn search_ranked_at(
+        &self,
+        query: &str,
+        top_k: usize,
+        filter: Option<MemoryFilter>,
+        snapshot: &ReadSnapshot,
+        ranker: Option<Arc<dyn MemoryRanker>>,
+    ) -> Result<Vec<ScoredMemory>> {
+        let ranker = ranker.unwrap_or_else(|| self.ranker());
+        let memories = self.search_memories_at(query, top_k, filter, snapshot).await?;
+        let query_embedding = if ranker.uses_query_embedding() {
+            self.query_embedding(query).await?
+        } else {
+            Vec::new()
+        };
+        Ok(rank_memories(&*ranker, &query_embedding, memories))
+    }
+
+    /// Embedding of `query` as searches compute it
+    pub async fn query_embedding(&self, query: &str) -> Result<Vec<f32>> {
+        let task = self.embedding_tasks().query;
+        self.generate_embedding(query, Some(&task)).await
+    }
+}
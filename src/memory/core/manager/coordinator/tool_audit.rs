@@ -0,0 +1,201 @@
+//! Tool-call audit log
+//!
+//! Every tool call a chat session executes is written to the library's
+//! `tool_call` table: the tool, a hash of its arguments, how long it took,
+//! whether it succeeded, the start of its result and the session and turn
+//! that made it. Unlike log lines, the records survive restarts, so a call
+//! can be traced back to the conversation that triggered it.
+//!
+//! Arguments are stored only as a hash; two calls with the same arguments
+//! share it, which is enough to spot repeats without keeping secrets. The
+//! hash is SHA-256, so it stays comparable across builds.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::memory::utils::{Error, Result};
+
+use super::lifecycle::MemoryCoordinator;
+
+/// Characters of the result kept per call
+pub const TOOL_AUDIT_PREVIEW_CHARS: usize = 500;
+
+/// Records returned when a query sets no limit
+pub const DEFAULT_TOOL_AUDIT_LIMIT: usize = 100;
+
+/// One executed tool call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    /// Tool name
+    pub tool: String,
+    /// First 16 hex digits of the SHA-256 of the raw arguments
+    pub args_hash: String,
+    /// Call duration in milliseconds
+    pub duration_ms: u64,
+    /// Whether the tool returned a result
+    pub success: bool,
+    /// Start of the result, or the error message
+    pub result_preview: String,
+    /// Chat session that made the call
+    pub session_id: Option<String>,
+    /// Turn within that session
+    pub turn_id: Option<String>,
+}
+
+impl ToolCallRecord {
+    /// Record of a call to `tool` with `args` that returned `result`
+    pub fn new(
+        tool: impl Into<String>,
+        args: &str,
+        duration: std::time::Duration,
+        result: std::result::Result<&str, &str>,
+    ) -> Self {
+        let (success, output) = match result {
+            Ok(output) => (true, output),
+            Err(error) => (false, error),
+        };
+        Self {
+            tool: tool.into(),
+            args_hash: Sha256::digest(args.as_bytes())[..8]
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            success,
+            result_preview: output.chars().take(TOOL_AUDIT_PREVIEW_CHARS).collect(),
+            session_id: None,
+            turn_id: None,
+        }
+    }
+
+    /// Attribute the call to a chat session and turn
+    #[must_use]
+    pub fn in_turn(mut self, session_id: impl Into<String>, turn_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self.turn_id = Some(turn_id.into());
+        self
+    }
+}
+
+/// A stored tool call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolAuditEntry {
+    /// Record ID
+    pub id: String,
+    /// When the call was recorded
+    pub called_at: String,
+    #[serde(flatten)]
+    pub call: ToolCallRecord,
+}
+
+/// Filter of [`MemoryCoordinator::tool_calls`]; unset fields match anything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolAuditQuery {
+    pub session_id: Option<String>,
+    pub turn_id: Option<String>,
+    pub tool: Option<String>,
+    /// Only calls that failed
+    pub failures_only: bool,
+    /// Most records to return, newest first (default 100)
+    pub limit: Option<usize>,
+}
+
+impl ToolAuditQuery {
+    /// SurrealQL selecting the matching records, newest first
+    ///
+    /// Filter values are parameters (`$session_id`, `$turn_id`, `$tool`,
+    /// `$limit`), bound by [`MemoryCoordinator::tool_calls`].
+    pub fn to_query(&self) -> String {
+        let mut conditions = Vec::new();
+        if self.session_id.is_some() {
+            conditions.push("session_id = $session_id".to_string());
+        }
+        if self.turn_id.is_some() {
+            conditions.push("turn_id = $turn_id".to_string());
+        }
+        if self.tool.is_some() {
+            conditions.push("tool = $tool".to_string());
+        }
+        if self.failures_only {
+            conditions.push("success = false".to_string());
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+
+        format!(
+            "SELECT meta::id(id) AS id, <string> called_at AS called_at, tool, args_hash, \
+             duration_ms, success, result_preview, session_id, turn_id \
+             FROM tool_call{filter} ORDER BY called_at DESC LIMIT $limit"
+        )
+    }
+}
+
+impl MemoryCoordinator {
+    /// Append a tool call to the library's audit log
+    ///
+    /// # Errors
+    ///
+    /// Returns the database error when the record cannot be written.
+    pub async fn record_tool_call(&self, record: &ToolCallRecord) -> Result<()> {
+        self.surreal_manager
+            .db
+            .query(
+                "CREATE tool_call SET tool = $tool, args_hash = $args_hash, \
+                 duration_ms = $duration_ms, success = $success, \
+                 result_preview = $result_preview, session_id = $session_id, \
+                 turn_id = $turn_id, called_at = time::now()",
+            )
+            .bind(("tool", record.tool.clone()))
+            .bind(("args_hash", record.args_hash.clone()))
+            .bind(("duration_ms", record.duration_ms))
+            .bind(("success", record.success))
+            .bind(("result_preview", record.result_preview.clone()))
+            .bind(("session_id", record.session_id.clone()))
+            .bind(("turn_id", record.turn_id.clone()))
+            .await
+            .and_then(|response| response.check())
+            .map_err(|e| Error::Database(format!("{:?}", e)))?;
+        Ok(())
+    }
+
+    /// Audited tool calls matching `query`, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the database cannot be read.
+    pub async fn tool_calls(&self, query: &ToolAuditQuery) -> Result<Vec<ToolAuditEntry>> {
+        let mut request = self
+            .surreal_manager
+            .db
+            .query(query.to_query())
+            .bind(("limit", query.limit.unwrap_or(DEFAULT_TOOL_AUDIT_LIMIT) as i64));
+        if let Some(session_id) = &query.session_id {
+            request = request.bind(("session_id", session_id.clone()));
+        }
+        if let Some(turn_id) = &query.turn_id {
+            request = request.bind(("turn_id", turn_id.clone()));
+        }
+        if let Some(tool) = &query.tool {
+            request = request.bind(("tool", tool.clone()));
+        }
+        let rows: Vec<Value> = request
+            .await
+            .map_err(|e| Error::Database(format!("{:?}", e)))?
+            .take(0)
+            .map_err(|e| Error::Database(format!("{:?}", e)))?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| match serde_json::from_value(row) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    log::warn!("Skipping malformed tool_call record: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,206 @@
+//! Transactional multi-operation memory writes
+//!
+//! Operations are buffered in a [`MemoryTransaction`] and applied in a single
+//! SurrealDB transaction on commit, so a failure part-way through a complex
+//! ingest leaves no partial state behind. Rolling back simply discards the
+//! buffer; nothing touches the database before commit.
+
+use std::future::Future;
+
+use crate::domain::memory::primitives::node::{AlignedEmbedding, MemoryNode};
+use crate::domain::memory::primitives::types::MemoryTypeEnum;
+use crate::memory::core::cognitive_queue::{CognitiveTask, CognitiveTaskType};
+use crate::memory::core::manager::surreal::{MetadataUpdate, WriteBatch, WriteOp};
+use crate::memory::utils::{Error, Result};
+use crate::memory::{MemoryMetadata, MemoryRelationship};
+
+use super::lifecycle::MemoryCoordinator;
+
+/// A buffered operation awaiting commit
+#[derive(Debug)]
+enum PendingOp {
+    Memory(MemoryNode),
+    Relationship(MemoryRelationship),
+    Update(String, MetadataUpdate),
+}
+
+/// Buffered memory operations applied atomically by [`MemoryCoordinator::commit`]
+///
+/// Operations are applied in the order they were added. Content-hash
+/// deduplication does not apply to inserts made through a transaction.
+#[derive(Debug, Default)]
+#[must_use = "a transaction does nothing until committed"]
+pub struct MemoryTransaction {
+    ops: Vec<PendingOp>,
+}
+
+impl MemoryTransaction {
+    /// Queue a new memory and return the ID it will be stored under
+    ///
+    /// The ID can be used by relationships and updates later in the same
+    /// transaction.
+    pub fn add_memory(
+        &mut self,
+        content: impl Into<String>,
+        memory_type: MemoryTypeEnum,
+        metadata: Option<MemoryMetadata>,
+    ) -> String {
+        let content = content.into();
+        let node = MemoryCoordinator::new_domain_node(&content, memory_type, metadata.as_ref());
+        // Stored IDs use the hyphen-free UUID form (see `convert_domain_to_memory_node`)
+        let id = node.id().simple().to_string();
+        self.ops.push(PendingOp::Memory(node));
+        id
+    }
+
+    /// Queue a relationship between two memories
+    pub fn add_relationship(
+        &mut self,
+        source_id: &str,
+        target_id: &str,
+        relationship_type: impl Into<String>,
+        metadata: Option<serde_json::Value>,
+    ) {
+        let mut relationship = MemoryRelationship::new(
+            source_id.to_string(),
+            target_id.to_string(),
+            relationship_type.into(),
+        );
+        if let Some(metadata) = metadata {
+            relationship = relationship.with_metadata(metadata);
+        }
+        self.ops.push(PendingOp::Relationship(relationship));
+    }
+
+    /// Queue a metadata update; the commit fails if the memory does not exist
+    pub fn update_metadata(&mut self, memory_id: &str, update: MetadataUpdate) {
+        if !update.is_empty() {
+            self.ops
+                .push(PendingOp::Update(memory_id.to_string(), update));
+        }
+    }
+
+    /// Number of queued operations
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Whether no operations are queued
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Discard all queued operations
+    pub fn rollback(self) {
+        log::debug!("Memory transaction rolled back ({} operations discarded)", self.ops.len());
+    }
+}
+
+/// Records written by a committed [`MemoryTransaction`], in operation order
+#[derive(Debug, Clone, Default)]
+pub struct TransactionOutcome {
+    /// Created memories
+    pub memories: Vec<MemoryNode>,
+    /// Created relationships
+    pub relationships: Vec<MemoryRelationship>,
+    /// Memories after their metadata update
+    pub updated: Vec<MemoryNode>,
+}
+
+impl MemoryCoordinator {
+    /// Start a new transaction
+    pub fn begin_transaction(&self) -> MemoryTransaction {
+        MemoryTransaction::default()
+    }
+
+    /// Apply every operation of `transaction` atomically
+    ///
+    /// New memories are embedded in one batch call before the transaction
+    /// runs; an embedding failure aborts the commit before anything is written.
+    pub async fn commit(&self, transaction: MemoryTransaction) -> Result<TransactionOutcome> {
+        if transaction.is_empty() {
+            return Ok(TransactionOutcome::default());
+        }
+
+        let texts: Vec<String> = transaction
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                PendingOp::Memory(node) => Some(node.content().to_string()),
+                _ => None,
+            })
+            .collect();
+        let mut embeddings = if texts.is_empty() {
+            Vec::new()
+        } else {
+            self.record_embedding_model().await?;
+            let document_task = self.embedding_tasks().document;
+            self.generate_embeddings(&texts, Some(&document_task)).await?
+        }
+        .into_iter();
+
+        let mut batch = WriteBatch::new();
+        for op in transaction.ops {
+            batch.push(match op {
+                PendingOp::Memory(mut node) => {
+                    let embedding = embeddings.next().ok_or_else(|| {
+                        Error::Internal("Missing embedding for transactional insert".to_string())
+                    })?;
+                    node.embedding = Some(AlignedEmbedding::new(embedding));
+                    WriteOp::CreateMemory(self.convert_domain_to_memory_node(&node))
+                }
+                PendingOp::Relationship(relationship) => WriteOp::CreateRelationship(relationship),
+                PendingOp::Update(memory_id, update) => WriteOp::UpdateMetadata { memory_id, update },
+            });
+        }
+
+        let written = self.surreal_manager.execute_batch(batch).await?;
+
+        // Only touch caches and queues once the transaction has committed
+        {
+            let mut repo = self.repository.write().await;
+            for memory in &written.memories {
+                repo.add(memory.clone());
+            }
+            for memory in &written.updated {
+                repo.update(memory.clone());
+            }
+        }
+
+        let mut outcome = TransactionOutcome {
+            relationships: written.relationships,
+            ..TransactionOutcome::default()
+        };
+        for memory in &written.memories {
+            let task = CognitiveTask::new(
+                memory.id.clone(),
+                CognitiveTaskType::CommitteeEvaluation,
+                5, // Default priority
+            );
+            self.cognitive_queue.enqueue(task).map_err(Error::Internal)?;
+            outcome
+                .memories
+                .push(self.convert_memory_to_domain_node(memory)?);
+        }
+        for memory in &written.updated {
+            outcome
+                .updated
+                .push(self.convert_memory_to_domain_node(memory)?);
+        }
+
+        Ok(outcome)
+    }
+
+    /// Build a transaction in `build` and commit it if `build` succeeds
+    ///
+    /// Returning an error from `build` rolls the transaction back; nothing is
+    /// written.
+    pub async fn with_transaction<F, Fut>(&self, build: F) -> Result<TransactionOutcome>
+    where
+        F: FnOnce(MemoryTransaction) -> Fut,
+        Fut: Future<Output = Result<MemoryTransaction>>,
+    {
+        let transaction = build(self.begin_transaction()).await?;
+        self.commit(transaction).await
+    }
+}
@@ -1,5 +1,8 @@
 //! Type definitions for memory coordinator
 
+use crate::domain::memory::primitives::types::MemoryTypeEnum;
+use crate::memory::MemoryMetadata;
+
 /// Strategy for handling memories with pending cognitive evaluation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LazyEvalStrategy {
@@ -11,3 +14,49 @@ pub enum LazyEvalStrategy {
     /// Trigger immediate evaluation and wait (bypasses queue)
     TriggerAndWait,
 }
+
+/// A memory queued for a batched write via `MemoryCoordinator::add_memories`
+#[derive(Debug, Clone)]
+pub struct NewMemory {
+    /// Text content of the memory
+    pub content: String,
+    /// Type classification of the memory
+    pub memory_type: MemoryTypeEnum,
+    /// Optional metadata (user_id, agent_id, etc.)
+    pub metadata: Option<MemoryMetadata>,
+}
+
+impl NewMemory {
+    /// Create a new memory entry
+    pub fn new(
+        content: impl Into<String>,
+        memory_type: MemoryTypeEnum,
+        metadata: Option<MemoryMetadata>,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            memory_type,
+            metadata,
+        }
+    }
+}
+
+/// A change to a memory's content via `MemoryCoordinator::edit_memory`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentEdit {
+    /// Replace the whole content
+    Replace(String),
+    /// Append to the existing content on a new line
+    Append(String),
+}
+
+impl ContentEdit {
+    /// Content after applying the edit to `current`
+    pub fn apply(&self, current: &str) -> String {
+        match self {
+            Self::Replace(content) => content.clone(),
+            Self::Append(content) if current.is_empty() => content.clone(),
+            Self::Append(content) => format!("{current}\n{content}"),
+        }
+    }
+}
@@ -1,9 +1,16 @@
 //! Memory management, coordination, and specific implementations
 
+#[cfg(feature = "memory")]
 pub mod coordinator;
 pub mod surreal;
+#[cfg(feature = "memory")]
 pub mod pool;
 
-pub use coordinator::MemoryCoordinator;
+#[cfg(feature = "memory")]
+pub use coordinator::{
+    MemoryCoordinator, MemoryTransaction, NewMemory, ReadSnapshot, TransactionOutcome,
+    WriteGeneration, WriteGenerations,
+};
+#[cfg(feature = "memory")]
 pub use pool::CoordinatorPool;
 pub use surreal::*;
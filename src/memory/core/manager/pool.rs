@@ -4,15 +4,57 @@
 //! one per library (physical .db file). This enables proper library isolation
 //! where each library is a separate database file rather than using tags for
 //! library separation.
+//!
+//! Libraries may use different embedding models, e.g. Stella 1.5B where
+//! recall quality matters and 400M where speed does. The model is chosen
+//! when a library is first opened and recorded on its first write; from then
+//! on the library keeps it. [`CoordinatorPool::from_env`] reads:
+//!
+//! - `KODEGEN_EMBEDDING_MODEL`: registry key of the default model
+//!   (default [`DEFAULT_EMBEDDING_MODEL`])
+//! - `KODEGEN_LIBRARY_EMBEDDING_MODELS`: comma-separated `library=registry_key`
+//!   overrides, e.g. `research=dunzhang/stella_en_1.5B_v5`
 
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{RwLock, Mutex};
 
-use crate::capability::registry::TextEmbeddingModel;
+use crate::capability::registry::{FromRegistry, TextEmbeddingModel};
+use crate::memory::core::consolidation::ConsolidationConfig;
 use crate::memory::core::manager::coordinator::MemoryCoordinator;
 use crate::memory::utils::{Error, Result};
 
+/// Registry key of the default embedding model
+pub const EMBEDDING_MODEL_ENV: &str = "KODEGEN_EMBEDDING_MODEL";
+
+/// Per-library embedding model overrides
+pub const LIBRARY_EMBEDDING_MODELS_ENV: &str = "KODEGEN_LIBRARY_EMBEDDING_MODELS";
+
+/// Embedding model of libraries without an override
+pub const DEFAULT_EMBEDDING_MODEL: &str = "dunzhang/stella_en_400M_v5";
+
+/// Parse `library=registry_key` pairs separated by commas
+///
+/// Blank entries are skipped.
+///
+/// # Errors
+/// Returns `Error::Config` for an entry without a library or a model
+pub fn parse_library_models(spec: &str) -> Result<Vec<(String, String)>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once('=') {
+            Some((library, model)) if !library.trim().is_empty() && !model.trim().is_empty() => {
+                Ok((library.trim().to_string(), model.trim().to_string()))
+            }
+            _ => Err(Error::Config(format!(
+                "Invalid {} entry '{}', expected library=registry_key",
+                LIBRARY_EMBEDDING_MODELS_ENV, entry
+            ))),
+        })
+        .collect()
+}
+
 /// Pool of MemoryCoordinators, one per library
 ///
 /// Each library corresponds to a physical database file at:
@@ -26,12 +68,18 @@ pub struct CoordinatorPool {
     /// Cache of coordinators by library name
     coordinators: Arc<RwLock<HashMap<String, Arc<MemoryCoordinator>>>>,
     
-    /// Shared embedding model (reused across all libraries)
+    /// Embedding model of libraries without an override
     embedding_model: TextEmbeddingModel,
+
+    /// Embedding models chosen for specific libraries
+    library_models: HashMap<String, TextEmbeddingModel>,
     
     /// Per-library initialization locks (prevents concurrent creation)
     /// Key: library_name, Value: Mutex guard for that library's initialization
     init_locks: Arc<RwLock<HashMap<String, Arc<Mutex<()>>>>>,
+
+    /// Consolidation run on every library the pool opens
+    consolidation: ConsolidationConfig,
 }
 
 impl CoordinatorPool {
@@ -56,10 +104,123 @@ impl CoordinatorPool {
         Self {
             coordinators: Arc::new(RwLock::new(HashMap::new())),
             embedding_model,
+            library_models: HashMap::new(),
             init_locks: Arc::new(RwLock::new(HashMap::new())),
+            consolidation: ConsolidationConfig::default(),
         }
     }
 
+    /// Create a pool whose libraries are consolidated per `consolidation`
+    ///
+    /// [`Self::new`] uses [`ConsolidationConfig::default`];
+    /// [`ConsolidationConfig::disabled`] turns the background job off.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidInput` if the config is out of range
+    ///
+    /// # Example
+    /// ```no_run
+    /// use kodegen_candle_agent::capability::registry::{FromRegistry, TextEmbeddingModel};
+    /// use kodegen_candle_agent::memory::core::ConsolidationConfig;
+    /// use kodegen_candle_agent::memory::core::manager::pool::CoordinatorPool;
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let emb_model = TextEmbeddingModel::from_registry("dunzhang/stella_en_400M_v5").unwrap();
+    /// let pool = CoordinatorPool::with_consolidation(
+    ///     emb_model,
+    ///     ConsolidationConfig {
+    ///         decay_half_life_days: Some(90.0),
+    ///         merge_threshold: Some(0.97),
+    ///         ..ConsolidationConfig::default()
+    ///     },
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_consolidation(
+        embedding_model: TextEmbeddingModel,
+        consolidation: ConsolidationConfig,
+    ) -> Result<Self> {
+        consolidation.validate()?;
+        Ok(Self {
+            consolidation,
+            ..Self::new(embedding_model)
+        })
+    }
+
+    /// Create a pool with the embedding models set in the environment
+    ///
+    /// See the module docs for the variables read.
+    ///
+    /// # Errors
+    /// Returns `Error::Config` if a variable is malformed or names a model
+    /// that is not in the registry
+    pub fn from_env() -> Result<Self> {
+        let default_key = std::env::var(EMBEDDING_MODEL_ENV)
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+        let mut pool = Self::new(Self::registry_model(default_key.trim())?);
+
+        if let Ok(spec) = std::env::var(LIBRARY_EMBEDDING_MODELS_ENV) {
+            for (library, key) in parse_library_models(&spec)? {
+                pool = pool.with_library_embedding_model(library, Self::registry_model(&key)?);
+            }
+        }
+        Ok(pool)
+    }
+
+    fn registry_model(registry_key: &str) -> Result<TextEmbeddingModel> {
+        TextEmbeddingModel::from_registry(registry_key).ok_or_else(|| {
+            Error::Config(format!(
+                "Embedding model '{}' not found in registry",
+                registry_key
+            ))
+        })
+    }
+
+    /// Embed `library` with `embedding_model` instead of the pool's default
+    ///
+    /// Takes effect for a library that has no memories yet; one that does
+    /// keeps the model it was written with.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use kodegen_candle_agent::capability::registry::{FromRegistry, TextEmbeddingModel};
+    /// use kodegen_candle_agent::memory::core::manager::pool::CoordinatorPool;
+    ///
+    /// # fn example() {
+    /// let fast = TextEmbeddingModel::from_registry("dunzhang/stella_en_400M_v5").unwrap();
+    /// let accurate = TextEmbeddingModel::from_registry("dunzhang/stella_en_1.5B_v5").unwrap();
+    /// let pool = CoordinatorPool::new(fast).with_library_embedding_model("research", accurate);
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_library_embedding_model(
+        mut self,
+        library_name: impl Into<String>,
+        embedding_model: TextEmbeddingModel,
+    ) -> Self {
+        self.library_models
+            .insert(library_name.into(), embedding_model);
+        self
+    }
+
+    /// Embedding model requested for `library_name`
+    ///
+    /// An open library may use another one, recorded before the request was
+    /// configured; see [`MemoryCoordinator::embedding_model`].
+    pub fn library_embedding_model(&self, library_name: &str) -> &TextEmbeddingModel {
+        self.library_models
+            .get(library_name)
+            .unwrap_or(&self.embedding_model)
+    }
+
+    /// Consolidation run on every library the pool opens
+    pub fn consolidation(&self) -> &ConsolidationConfig {
+        &self.consolidation
+    }
+
     /// Get a coordinator for the specified library, creating if needed
     ///
     /// If the coordinator already exists in the pool, returns the cached instance.
@@ -139,8 +300,12 @@ impl CoordinatorPool {
         // We hold the lock and cache is still empty - safe to create coordinator
         log::info!("Initializing coordinator for library '{}' with exclusive lock", library_name);
         
-        let coordinator =
-            MemoryCoordinator::from_library(library_name, self.embedding_model.clone()).await?;
+        let coordinator = MemoryCoordinator::from_library(
+            library_name,
+            self.library_embedding_model(library_name).clone(),
+        )
+        .await?;
+        coordinator.start_consolidation(self.consolidation.clone())?;
         let coordinator_arc = Arc::new(coordinator);
         
         // Cache it
@@ -254,6 +419,10 @@ impl CoordinatorPool {
         let count = coordinators.len();
 
         for (name, coordinator) in coordinators.drain() {
+            if let Err(e) = coordinator.flush_recall_hits().await {
+                log::warn!("Failed to write back recall hits of library '{}': {}", name, e);
+            }
+
             // Try to unwrap Arc to get mutable access
             // If Arc has multiple references, we can't shutdown (just log warning)
             match Arc::try_unwrap(coordinator) {
@@ -274,6 +443,11 @@ impl CoordinatorPool {
         log::info!("Shutdown complete ({} coordinators)", count);
     }
 
+    /// Embedding model of libraries without an override
+    pub fn embedding_model(&self) -> &TextEmbeddingModel {
+        &self.embedding_model
+    }
+
     /// Get the number of cached coordinators in the pool
     ///
     /// Useful for monitoring and debugging.
@@ -0,0 +1,279 @@
+//! Transactional write batches
+//!
+//! A [`WriteBatch`] collects memory inserts, relationship inserts and metadata
+//! updates and applies them in one SurrealDB transaction: either every
+//! operation lands or none does. Operations run in the order they were added,
+//! so a relationship may reference a memory created earlier in the same batch.
+
+use surrealdb_types::{RecordId, ToSql};
+
+use crate::memory::primitives::{MemoryNode, MemoryRelationship};
+use crate::memory::schema::memory_schema::MemoryNodeSchema;
+use crate::memory::schema::relationship_schema::Relationship;
+use crate::memory::utils::error::Error;
+use crate::runtime::annotate_query;
+
+use super::Result;
+use super::manager::SurrealDBMemoryManager;
+use super::types::{MemoryNodeCreateContent, RelationshipCreateContent};
+
+/// Partial metadata update applied to an existing memory
+#[derive(Debug, Clone, Default)]
+pub struct MetadataUpdate {
+    /// New importance score
+    pub importance: Option<f32>,
+    /// Tags to add (existing tags are kept)
+    pub add_tags: Vec<String>,
+    /// Custom fields merged into the existing custom metadata
+    pub custom: Option<serde_json::Value>,
+}
+
+impl MetadataUpdate {
+    /// Whether the update changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.importance.is_none() && self.add_tags.is_empty() && self.custom.is_none()
+    }
+}
+
+/// A single operation in a [`WriteBatch`]
+#[derive(Debug, Clone)]
+pub enum WriteOp {
+    /// Insert a memory (embedding must already be set)
+    CreateMemory(MemoryNode),
+    /// Insert a relationship
+    CreateRelationship(MemoryRelationship),
+    /// Update metadata of an existing memory; fails the batch if it does not exist
+    UpdateMetadata {
+        memory_id: String,
+        update: MetadataUpdate,
+    },
+}
+
+/// Ordered set of operations applied atomically
+#[derive(Debug, Clone, Default)]
+pub struct WriteBatch {
+    ops: Vec<WriteOp>,
+}
+
+impl WriteBatch {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an operation
+    pub fn push(&mut self, op: WriteOp) {
+        self.ops.push(op);
+    }
+
+    /// Number of queued operations
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Whether the batch is empty
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+/// Records written by a committed [`WriteBatch`], in operation order
+#[derive(Debug, Clone, Default)]
+pub struct WriteBatchOutcome {
+    /// Created memories
+    pub memories: Vec<MemoryNode>,
+    /// Created relationships
+    pub relationships: Vec<MemoryRelationship>,
+    /// Memories after their metadata update
+    pub updated: Vec<MemoryNode>,
+}
+
+/// Which result set holds an operation's record
+enum ResultSlot {
+    Memory(usize),
+    Relationship(usize),
+    Updated(usize),
+}
+
+impl SurrealDBMemoryManager {
+    /// Apply a batch of writes in a single transaction
+    ///
+    /// Unlike `create_memory`, inserts do not generate embeddings or
+    /// deduplicate by content hash.
+    pub async fn execute_batch(&self, batch: WriteBatch) -> Result<WriteBatchOutcome> {
+        if batch.is_empty() {
+            return Ok(WriteBatchOutcome::default());
+        }
+
+        let now = crate::memory::utils::current_timestamp_ms();
+        let mut statements = Vec::new();
+        let mut slots = Vec::with_capacity(batch.len());
+        let mut bindings: Vec<(String, serde_json::Value)> = Vec::new();
+        let mut memory_bindings = Vec::new();
+        let mut record_bindings: Vec<(String, RecordId)> = Vec::new();
+
+        for (i, op) in batch.ops.into_iter().enumerate() {
+            match op {
+                WriteOp::CreateMemory(memory) => {
+                    statements.push(format!(
+                        "CREATE memory:{id} CONTENT {{ content: $content_{i}, content_hash: $content_hash_{i}, memory_type: $memory_type_{i}, created_at: $created_at_{i}, updated_at: $updated_at_{i}, metadata: $metadata_{i} }};",
+                        id = memory.id
+                    ));
+                    slots.push(ResultSlot::Memory(statements.len() - 1));
+                    memory_bindings.push((i, memory));
+                }
+                WriteOp::CreateRelationship(relationship) => {
+                    let content = RelationshipCreateContent::from(&relationship);
+                    statements.push(format!(
+                        "CREATE relationship CONTENT {{ id: $rel_id_{i}, source_id: $source_id_{i}, target_id: $target_id_{i}, relationship_type: $relationship_type_{i}, created_at: $created_at_{i}, updated_at: $updated_at_{i}, strength: $strength_{i}, metadata: $metadata_{i} }};"
+                    ));
+                    slots.push(ResultSlot::Relationship(statements.len() - 1));
+                    bindings.extend([
+                        (format!("rel_id_{i}"), relationship.id.into()),
+                        (format!("source_id_{i}"), content.source_id.into()),
+                        (format!("target_id_{i}"), content.target_id.into()),
+                        (format!("relationship_type_{i}"), content.relationship_type.into()),
+                        (format!("created_at_{i}"), content.created_at.into()),
+                        (format!("updated_at_{i}"), content.updated_at.into()),
+                        (format!("strength_{i}"), content.strength.into()),
+                        (format!("metadata_{i}"), content.metadata),
+                    ]);
+                }
+                WriteOp::UpdateMetadata { memory_id, update } => {
+                    // Abort the whole transaction if the target is missing
+                    statements.push(format!(
+                        "IF !record::exists($memory_id_{i}) {{ THROW $missing_{i} }};"
+                    ));
+                    bindings.push((
+                        format!("missing_{i}"),
+                        format!("memory {memory_id} not found").into(),
+                    ));
+                    record_bindings.push((
+                        format!("memory_id_{i}"),
+                        RecordId::new("memory", memory_id.as_str()),
+                    ));
+
+                    let mut metadata_patch = serde_json::Map::new();
+                    if let Some(importance) = update.importance {
+                        metadata_patch.insert("importance".into(), importance.into());
+                    }
+                    if let Some(custom) = update.custom {
+                        metadata_patch.insert("custom".into(), custom);
+                    }
+                    statements.push(format!("UPDATE $memory_id_{i} MERGE $patch_{i};"));
+                    bindings.push((
+                        format!("patch_{i}"),
+                        serde_json::json!({ "updated_at": now, "metadata": metadata_patch }),
+                    ));
+
+                    if !update.add_tags.is_empty() {
+                        statements.push(format!(
+                            "UPDATE $memory_id_{i} SET metadata.tags = array::union(metadata.tags, $tags_{i});"
+                        ));
+                        bindings.push((format!("tags_{i}"), update.add_tags.into()));
+                    }
+                    slots.push(ResultSlot::Updated(statements.len() - 1));
+                }
+            }
+        }
+
+        let query = format!(
+            "BEGIN TRANSACTION; {} COMMIT TRANSACTION;",
+            statements.join(" ")
+        );
+
+        let mut query_builder = self.db.query(annotate_query(&query));
+        for (i, memory) in &memory_bindings {
+            let content = MemoryNodeCreateContent::from(memory);
+            query_builder = query_builder
+                .bind((format!("content_{i}"), content.content))
+                .bind((format!("content_hash_{i}"), content.content_hash))
+                .bind((format!("memory_type_{i}"), content.memory_type.to_string()))
+                .bind((format!("created_at_{i}"), memory.created_at))
+                .bind((format!("updated_at_{i}"), memory.updated_at))
+                .bind((format!("metadata_{i}"), content.metadata));
+        }
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+        for binding in record_bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        let mut response = query_builder
+            .await
+            .map_err(|e| Error::Database(format!("Write batch transaction failed: {:?}", e)))?;
+
+        let mut outcome = WriteBatchOutcome::default();
+        for slot in slots {
+            match slot {
+                ResultSlot::Memory(index) => {
+                    let records: Vec<MemoryNodeSchema> = response
+                        .take(index)
+                        .map_err(|e| statement_error(index, e))?;
+                    outcome
+                        .memories
+                        .push(Self::from_schema(first_record(records, index)?));
+                }
+                ResultSlot::Relationship(index) => {
+                    let records: Vec<Relationship> = response
+                        .take(index)
+                        .map_err(|e| statement_error(index, e))?;
+                    outcome
+                        .relationships
+                        .push(relationship_from_schema(first_record(records, index)?));
+                }
+                ResultSlot::Updated(index) => {
+                    let records: Vec<MemoryNodeSchema> = response
+                        .take(index)
+                        .map_err(|e| statement_error(index, e))?;
+                    outcome
+                        .updated
+                        .push(Self::from_schema(first_record(records, index)?));
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Create several memories in a single transaction
+    ///
+    /// Either every record is written or none is. Unlike `create_memory`, this
+    /// does not generate embeddings or deduplicate by content hash; callers
+    /// embed in one batch and filter duplicates beforehand.
+    pub async fn create_memories(&self, memories: Vec<MemoryNode>) -> Result<Vec<MemoryNode>> {
+        let mut batch = WriteBatch::new();
+        for memory in memories {
+            batch.push(WriteOp::CreateMemory(memory));
+        }
+        Ok(self.execute_batch(batch).await?.memories)
+    }
+}
+
+/// Error for a failed statement (a failed statement aborts the whole transaction)
+fn statement_error(index: usize, e: impl std::fmt::Debug) -> Error {
+    Error::Database(format!("Write batch statement {index} failed: {:?}", e))
+}
+
+/// First record of a statement's result set
+fn first_record<T>(records: Vec<T>, index: usize) -> Result<T> {
+    records
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Other(format!("Write batch statement {index} returned no record")))
+}
+
+/// Convert a stored relationship record to a [`MemoryRelationship`]
+pub(super) fn relationship_from_schema(r: Relationship) -> MemoryRelationship {
+    MemoryRelationship {
+        id: format!("relationship:{}", r.id.to_sql()),
+        source_id: r.source_id,
+        target_id: r.target_id,
+        relationship_type: r.relationship_type,
+        metadata: Some(r.metadata),
+        created_at: Some(r.created_at),
+        updated_at: Some(r.updated_at),
+        strength: Some(r.strength),
+    }
+}
@@ -9,12 +9,15 @@ use surrealdb::Surreal;
 use surrealdb::engine::any::Any;
 
 use crate::capability::registry::TextEmbeddingModel;
+use crate::capability::traits::TextEmbeddingCapable;
 use crate::memory::migration::{
-    BuiltinMigrations, DataExporter, DataImporter, ExportFormat, ImportFormat, MigrationManager,
+    BuiltinMigrations, DataExporter, DataImporter, ExportFormat, ImportFormat,
+    LEGACY_EMBEDDING_DIMENSION, LibrarySchema, LibrarySchemaVersion, MigrationManager,
 };
 use crate::memory::primitives::{MemoryNode, MemoryRelationship};
 use crate::memory::schema::memory_schema::MemoryNodeSchema;
 use crate::memory::utils::error::Error;
+use crate::runtime::annotate_query;
 use std::path::Path;
 
 use super::Result;
@@ -57,6 +60,11 @@ impl SurrealDBMemoryManager {
         &self.db
     }
 
+    /// Embedding model memories are embedded with, if one was given
+    pub fn embedding_model(&self) -> Option<&TextEmbeddingModel> {
+        self.embedding_model.as_ref()
+    }
+
     /// Initialize the database schema and indexes
     ///
     /// This method sets up:
@@ -84,6 +92,8 @@ impl SurrealDBMemoryManager {
                 DEFINE FIELD IF NOT EXISTS metadata.tags ON memory TYPE array<string>;
                 DEFINE FIELD IF NOT EXISTS metadata.keywords ON memory TYPE array<string>;
                 DEFINE FIELD IF NOT EXISTS metadata.custom ON memory TYPE option<object> FLEXIBLE;
+                DEFINE FIELD IF NOT EXISTS metadata.recall_count ON memory TYPE option<int>;
+                DEFINE FIELD IF NOT EXISTS metadata.last_recalled_at ON memory TYPE option<datetime>;
                 ",
             )
             .await
@@ -129,17 +139,23 @@ impl SurrealDBMemoryManager {
             })?;
 
         // Define MTREE index for vector similarity search (optional - may fail on SurrealDB v3)
-        // MTREE syntax changed in SurrealDB v3 - this is an optimization index, not required
+        // MTREE syntax changed in SurrealDB v3 - this is an optimization index, not required.
+        // Sized for this manager's model; ensure_library_schema rebuilds an existing
+        // index built for another dimension
+        let dimension = self
+            .embedding_model
+            .as_ref()
+            .map_or(LEGACY_EMBEDDING_DIMENSION, |model| model.embedding_dimension());
         if let Err(e) = self.db
-            .query(
+            .query(format!(
                 "
                 DEFINE INDEX IF NOT EXISTS memory_embedding_mtree ON memory 
                 FIELDS metadata.embedding 
-                MTREE DIMENSION 1024 
+                MTREE DIMENSION {dimension} 
                 DIST COSINE 
                 TYPE F32;
-                ",
-            )
+                "
+            ))
             .await
         {
             log::warn!("MTREE index creation skipped (SurrealDB v3 compatibility): {:?}", e);
@@ -266,6 +282,21 @@ impl SurrealDBMemoryManager {
             .await
             .map_err(|e| Error::Database(format!("Failed to define causal edges: {:?}", e)))?;
 
+        // Define per-library settings (embedding tasks, consolidation)
+        self.db
+            .query(
+                "
+                DEFINE TABLE IF NOT EXISTS library_settings SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS document_task ON library_settings TYPE string;
+                DEFINE FIELD IF NOT EXISTS query_task ON library_settings TYPE string;
+                DEFINE FIELD IF NOT EXISTS last_run ON library_settings TYPE option<datetime>;
+                ",
+            )
+            .await
+            .map_err(|e| {
+                Error::Database(format!("Failed to define library settings: {:?}", e))
+            })?;
+
         Ok(())
     }
 
@@ -275,7 +306,7 @@ impl SurrealDBMemoryManager {
     pub async fn execute_query(&self, query: &str) -> Result<serde_json::Value> {
         let mut response = self
             .db
-            .query(query)
+            .query(annotate_query(query))
             .await
             .map_err(|e| Error::Database(format!("{:?}", e)))?;
 
@@ -315,13 +346,51 @@ impl SurrealDBMemoryManager {
         Ok(())
     }
 
+    /// Bring this library's schema to the current version for embeddings of
+    /// `embedding_dimension`, as recorded in its `schema_version` record
+    pub async fn ensure_library_schema(
+        &self,
+        embedding_dimension: usize,
+    ) -> Result<LibrarySchemaVersion> {
+        self.library_schema()
+            .await?
+            .ensure(embedding_dimension)
+            .await
+            .map_err(|e| Error::Database(format!("Library migration failed: {:?}", e)))
+    }
+
+    /// Registry key of the embedding model recorded for this library, `None`
+    /// until its first memory is written
+    pub async fn library_embedding_model(&self) -> Result<Option<String>> {
+        self.library_schema()
+            .await?
+            .embedding_model()
+            .await
+            .map_err(|e| Error::Database(format!("Failed to read library embedding model: {:?}", e)))
+    }
+
+    /// Record `registry_key` as the embedding model of this library's memories
+    pub async fn set_library_embedding_model(&self, registry_key: &str) -> Result<()> {
+        self.library_schema()
+            .await?
+            .set_embedding_model(registry_key)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to record library embedding model: {:?}", e)))
+    }
+
+    async fn library_schema(&self) -> Result<LibrarySchema> {
+        LibrarySchema::new(Arc::new(self.db.clone()))
+            .await
+            .map_err(|e| Error::Database(format!("Schema version tracking failed: {:?}", e)))
+    }
+
     /// Export all memories and relationships to a file
     pub async fn export_memories(&self, path: &Path, format: ExportFormat) -> Result<()> {
         // Fetch all memories
         let query = "SELECT * FROM memory";
         let mut response = self
             .db
-            .query(query)
+            .query(annotate_query(query))
             .await
             .map_err(|e| Error::Database(format!("Export query failed: {:?}", e)))?;
 
@@ -335,7 +404,7 @@ impl SurrealDBMemoryManager {
         let query = "SELECT * FROM relationship";
         let mut response = self
             .db
-            .query(query)
+            .query(annotate_query(query))
             .await
             .map_err(|e| Error::Database(format!("Export query failed: {:?}", e)))?;
 
@@ -424,7 +493,7 @@ impl SurrealDBMemoryManager {
             ";
 
             self.db
-                .query(query)
+                .query(annotate_query(query))
                 .bind(("id", memory.id.clone()))
                 .bind(("content", content.content))
                 .bind(("content_hash", content.content_hash))
@@ -454,7 +523,7 @@ impl SurrealDBMemoryManager {
             ";
 
             self.db
-                .query(query)
+                .query(annotate_query(query))
                 .bind(("id", relationship.id))
                 .bind(("source_id", content.source_id))
                 .bind(("target_id", content.target_id))
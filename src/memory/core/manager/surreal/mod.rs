@@ -3,15 +3,23 @@
 //! This module was decomposed from a 2,062-line monolithic file into focused submodules
 //! for better maintainability and separation of concerns.
 
+#[cfg(feature = "memory")]
+pub mod batch;
 pub mod futures;
+#[cfg(feature = "memory")]
 pub mod manager;
+#[cfg(feature = "memory")]
 pub mod operations;
+#[cfg(feature = "memory")]
 pub mod queries;
 pub mod trait_def;
 pub mod types;
 
 // Re-export all public items to maintain API compatibility
+#[cfg(feature = "memory")]
+pub use batch::{MetadataUpdate, WriteBatch, WriteBatchOutcome, WriteOp};
 pub use futures::*;
+#[cfg(feature = "memory")]
 pub use manager::*;
 pub use trait_def::*;
 pub use types::*;
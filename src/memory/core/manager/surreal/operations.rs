@@ -12,8 +12,10 @@ use crate::memory::schema::memory_schema::MemoryNodeSchema;
 use crate::memory::schema::quantum_schema::QuantumSignatureSchema;
 use crate::memory::schema::relationship_schema::Relationship;
 use crate::memory::utils::error::Error;
+use crate::runtime::{annotate_query, spawn_with_request_id};
 use surrealdb_types::ToSql;
 
+use super::batch::relationship_from_schema;
 use super::futures::{
     MemoryQuery, MemoryStream, PendingCount, PendingDeletion, PendingEntanglementEdge,
     PendingMemory, PendingQuantumSignature, PendingQuantumUpdate, PendingRelationship,
@@ -29,7 +31,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let embedding_model = self.embedding_model.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let mut memory_with_embedding = memory.clone();
 
@@ -57,7 +59,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                 // Check for duplicate content_hash before CREATE
                 let check_query = "SELECT * FROM memory WHERE content_hash = $content_hash LIMIT 1";
                 let mut check_response = db
-                    .query(check_query)
+                    .query(annotate_query(check_query))
                     .bind(("content_hash", content.content_hash))
                     .await
                     .map_err(|e| Error::Database(format!("{:?}", e)))?;
@@ -86,7 +88,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                     );
 
                     let mut update_response = db
-                        .query(&update_query)
+                        .query(annotate_query(&update_query))
                         .bind(("importance", max_importance))
                         .bind(("timestamp", now))
                         .bind(("updated_at", now))
@@ -117,7 +119,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                 ", memory.id);
 
                 let mut response = db
-                    .query(&query)
+                    .query(annotate_query(&query))
                     .bind(("content", content.content))
                     .bind(("content_hash", content.content_hash))
                     .bind(("memory_type", content.memory_type.to_string()))
@@ -162,12 +164,12 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let id = id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let query = format!("SELECT * FROM memory:{}", id);
 
                 let mut response = db
-                    .query(&query)
+                    .query(annotate_query(&query))
                     .await
                     .map_err(|e| Error::Database(format!("{:?}", e)))?;
 
@@ -192,7 +194,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let db = self.db.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let content = MemoryNodeCreateContent::from(&memory);
 
@@ -207,7 +209,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                 ", memory.id);
 
                 let mut response = db
-                    .query(&query)
+                    .query(annotate_query(&query))
                     .bind(("content", content.content))
                     .bind(("content_hash", content.content_hash))
                     .bind(("memory_type", content.memory_type.to_string()))
@@ -239,12 +241,12 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let id = id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let query = format!("DELETE memory:{}", id);
 
                 let mut response = db
-                    .query(&query)
+                    .query(annotate_query(&query))
                     .await
                     .map_err(|e| Error::Database(format!("{:?}", e)))?;
 
@@ -266,41 +268,17 @@ impl MemoryManager for SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         let db = self.db.clone();
 
-        tokio::spawn(async move {
-            let vector_json = serde_json::to_string(&vector).unwrap_or_default();
-
-            // Use KNN operator <|k|> to leverage MTREE index with COSINE distance
-            // This is MUCH faster than brute-force similarity computation
-            // Return both raw similarity_score and importance-weighted vector_score
-            let query = format!(
-                "SELECT *,
-                        vector::similarity::cosine(metadata.embedding, {vector_json}) AS similarity_score,
-                        vector::similarity::cosine(metadata.embedding, {vector_json}) * metadata.importance AS vector_score
-                 FROM memory
-                 WHERE metadata.embedding <|{limit}|> {vector_json}
-                 ORDER BY vector_score DESC
-                 LIMIT {limit}",
-                vector_json = vector_json,
-                limit = limit
-            );
-
-            log::debug!("Executing vector search SQL:\n{}", query);
-
-            match db.query(&query).await {
-                Ok(mut response) => {
-                    let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
-
-                    log::info!("Vector search: {} results (limit {})", results.len(), limit);
-
-                    for schema in results {
-                        let memory = SurrealDBMemoryManager::from_schema(schema);
+        spawn_with_request_id(async move {
+            match Self::vector_query(&db, &vector, limit).await {
+                Ok(results) => {
+                    for memory in results {
                         if tx.send(Ok(memory)).await.is_err() {
                             break;
                         }
                     }
                 }
                 Err(e) => {
-                    let _ = tx.send(Err(Error::Database(format!("{:?}", e)))).await;
+                    let _ = tx.send(Err(e)).await;
                 }
             }
         });
@@ -313,13 +291,13 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let search_text = text.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let query = format!(
                 "SELECT * FROM memory WHERE content CONTAINS \"{}\" ORDER BY metadata.created_at DESC LIMIT 100",
                 search_text.replace("\"", "\\\"")
             );
 
-            match db.query(&query).await {
+            match db.query(annotate_query(&query)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -343,11 +321,11 @@ impl MemoryManager for SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         let db = self.db.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let memory_type_str = memory_type.to_string();
             let query = "SELECT * FROM memory WHERE memory_type = $memory_type ORDER BY metadata.created_at DESC LIMIT 100";
 
-            match db.query(query).bind(("memory_type", memory_type_str)).await {
+            match db.query(annotate_query(query)).bind(("memory_type", memory_type_str)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -371,13 +349,13 @@ impl MemoryManager for SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         let db = self.db.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let query = "SELECT * FROM memory ORDER BY created_at DESC START $offset LIMIT $limit";
 
             log::debug!("list_all_memories: offset={}, limit={}, query={}", offset, limit, query);
 
             match db
-                .query(query)
+                .query(annotate_query(query))
                 .bind(("offset", offset))
                 .bind(("limit", limit))
                 .await
@@ -420,12 +398,12 @@ impl MemoryManager for SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let db = self.db.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let query = "SELECT count() AS total FROM memory";
 
                 let mut response = db
-                    .query(query)
+                    .query(annotate_query(query))
                     .await
                     .map_err(|e| Error::Database(format!("{:?}", e)))?;
 
@@ -447,7 +425,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let db = self.db.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let content = RelationshipCreateContent::from(&relationship);
 
@@ -465,7 +443,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                 ";
 
                 let mut response = db
-                    .query(query)
+                    .query(annotate_query(query))
                     .bind(("id", relationship.id.clone()))
                     .bind(("source_id", content.source_id))
                     .bind(("target_id", content.target_id))
@@ -484,16 +462,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                 result
                     .into_iter()
                     .next()
-                    .map(|r| MemoryRelationship {
-                        id: format!("relationship:{}", r.id.to_sql()),
-                        source_id: r.source_id,
-                        target_id: r.target_id,
-                        relationship_type: r.relationship_type,
-                        metadata: Some(r.metadata),
-                        created_at: Some(r.created_at),
-                        updated_at: Some(r.updated_at),
-                        strength: Some(r.strength),
-                    })
+                    .map(relationship_from_schema)
                     .ok_or_else(|| Error::Other("Failed to create relationship".to_string()))
             }
             .await;
@@ -509,11 +478,11 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let memory_id = memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let query =
                 "SELECT * FROM relationship WHERE source_id = $memory_id OR target_id = $memory_id";
 
-            match db.query(query).bind(("memory_id", memory_id)).await {
+            match db.query(annotate_query(query)).bind(("memory_id", memory_id)).await {
                 Ok(mut response) => {
                     let results: Vec<Relationship> = response.take(0).unwrap_or_default();
 
@@ -548,12 +517,12 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let id = id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let query = "DELETE $id";
 
                 let mut response = db
-                    .query(query)
+                    .query(annotate_query(query))
                     .bind(("id", id))
                     .await
                     .map_err(|e| Error::Database(format!("{:?}", e)))?;
@@ -581,7 +550,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let memory_id = memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let schema = QuantumSignatureSchema::from_cognitive_state(&signature).await;
 
@@ -598,7 +567,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                     }
                 ";
 
-                db.query(query)
+                db.query(annotate_query(query))
                     .bind(("memory_id", memory_id))
                     .bind(("coherence_fingerprint", schema.coherence_fingerprint))
                     .bind(("entanglement_bonds", schema.entanglement_bonds))
@@ -625,12 +594,12 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let memory_id = memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let query = "SELECT * FROM quantum_signature WHERE memory_id = $memory_id LIMIT 1";
 
                 let mut response = db
-                    .query(query)
+                    .query(annotate_query(query))
                     .bind(("memory_id", memory_id))
                     .await
                     .map_err(|e| Error::Database(format!("{:?}", e)))?;
@@ -664,7 +633,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let source_id = source_id.to_string();
         let target_id = target_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let now = crate::memory::utils::current_timestamp_ms();
                 let entanglement_type_str = format!("{:?}", entanglement_type);
@@ -674,7 +643,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                     source_id, target_id
                 );
 
-                db.query(&query)
+                db.query(annotate_query(&query))
                     .bind(("entanglement_type", entanglement_type_str))
                     .bind(("strength", strength))
                     .bind(("created_at", now))
@@ -703,7 +672,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let source_id = source_id.to_string();
         let target_id = target_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let result = async {
                 let now = crate::memory::utils::current_timestamp_ms();
 
@@ -712,7 +681,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                     source_id, target_id
                 );
 
-                db.query(&query)
+                db.query(annotate_query(&query))
                     .bind(("strength", strength))
                     .bind(("temporal_distance", temporal_distance))
                     .bind(("created_at", now))
@@ -734,10 +703,10 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let memory_id = memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let query = format!("SELECT out.* FROM {}->entangled", memory_id);
 
-            match db.query(&query).await {
+            match db.query(annotate_query(&query)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -767,13 +736,13 @@ impl MemoryManager for SurrealDBMemoryManager {
         let memory_id = memory_id.to_string();
         let type_str = format!("{:?}", entanglement_type);
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let query = format!(
                 "SELECT out.* FROM {}->entangled WHERE entanglement_type = $entanglement_type",
                 memory_id
             );
 
-            match db.query(&query).bind(("entanglement_type", type_str)).await {
+            match db.query(annotate_query(&query)).bind(("entanglement_type", type_str)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -803,7 +772,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let start_id = start_memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let safe_depth = max_depth.min(5);
 
             let mut chain = String::from("->entangled");
@@ -816,7 +785,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                 start_id, chain
             );
 
-            match db.query(&query).bind(("min_strength", min_strength)).await {
+            match db.query(annotate_query(&query)).bind(("min_strength", min_strength)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -845,7 +814,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         let db = self.db.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let ids_json = serde_json::to_string(&seed_memory_ids).unwrap_or_default();
 
             let query = format!(
@@ -857,7 +826,7 @@ impl MemoryManager for SurrealDBMemoryManager {
                 ids_json, expansion_factor
             );
 
-            match db.query(&query).bind(("min_strength", min_strength)).await {
+            match db.query(annotate_query(&query)).bind(("min_strength", min_strength)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -882,12 +851,12 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let memory_id = memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             // Query: SELECT in.* FROM memory_id<-caused
             // This gets all memories that caused this one
             let query = format!("SELECT in.* FROM {}<-caused", memory_id);
 
-            match db.query(&query).await {
+            match db.query(annotate_query(&query)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -912,12 +881,12 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let memory_id = memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             // Query: SELECT out.* FROM memory_id->caused
             // This gets all memories that were caused by this one
             let query = format!("SELECT out.* FROM {}->caused", memory_id);
 
-            match db.query(&query).await {
+            match db.query(annotate_query(&query)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -942,7 +911,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let start_id = start_memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let safe_depth = max_depth.min(10); // Safety limit
 
             // Build chain: ->caused->memory->caused->memory...
@@ -953,7 +922,7 @@ impl MemoryManager for SurrealDBMemoryManager {
 
             let query = format!("SELECT DISTINCT out.* FROM {}{}", start_id, chain);
 
-            match db.query(&query).await {
+            match db.query(annotate_query(&query)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -978,7 +947,7 @@ impl MemoryManager for SurrealDBMemoryManager {
         let db = self.db.clone();
         let start_id = start_memory_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let safe_depth = max_depth.min(10); // Safety limit
 
             // Build chain: <-caused<-memory<-caused<-memory...
@@ -989,7 +958,7 @@ impl MemoryManager for SurrealDBMemoryManager {
 
             let query = format!("SELECT DISTINCT in.* FROM {}{}", start_id, chain);
 
-            match db.query(&query).await {
+            match db.query(annotate_query(&query)).await {
                 Ok(mut response) => {
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
 
@@ -7,7 +7,10 @@ use crate::capability::traits::TextEmbeddingCapable;
 use crate::memory::primitives::MemoryNode;
 use crate::memory::schema::memory_schema::MemoryNodeSchema;
 use crate::memory::utils::error::Error;
-use surrealdb::types::SurrealValue;
+use crate::runtime::{annotate_query, spawn_with_request_id};
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use surrealdb_types::SurrealValue;
 
 use super::Result;
 use super::futures::MemoryStream;
@@ -37,7 +40,7 @@ impl SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         let db = self.db.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let vector_json = serde_json::to_string(&query_vector).unwrap_or_default();
             let safe_depth = expansion_depth.min(5);
 
@@ -78,7 +81,7 @@ impl SurrealDBMemoryManager {
 
             log::debug!("Executing hybrid search SQL:\n{}", sql);
 
-            match db.query(&sql).await {
+            match db.query(annotate_query(&sql)).await {
                 Ok(mut response) => {
                     // Result ALWAYS at index 0 (no more CTE index math!)
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_else(|e| {
@@ -132,7 +135,7 @@ impl SurrealDBMemoryManager {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         let db = self.db.clone();
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let vector_json = serde_json::to_string(&query_vector).unwrap_or_default();
             let safe_depth = expansion_depth.min(5);
 
@@ -171,7 +174,7 @@ impl SurrealDBMemoryManager {
                 )
             };
 
-            match db.query(&sql).await {
+            match db.query(annotate_query(&sql)).await {
                 Ok(mut response) => {
                     // Result ALWAYS at index 0
                     let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
@@ -200,6 +203,49 @@ impl SurrealDBMemoryManager {
         MemoryStream::new(rx)
     }
 
+    /// The `limit` memories nearest to `vector`, best first
+    ///
+    /// Unlike [`MemoryManager::search_by_vector`], the query runs in the
+    /// caller's future, so dropping it (e.g. at a deadline) cancels the search.
+    pub async fn vector_search(&self, vector: &[f32], limit: usize) -> Result<Vec<MemoryNode>> {
+        Self::vector_query(&self.db, vector, limit).await
+    }
+
+    pub(super) async fn vector_query(
+        db: &Surreal<Any>,
+        vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<MemoryNode>> {
+        let vector_json = serde_json::to_string(vector).unwrap_or_default();
+
+        // Use KNN operator <|k|> to leverage MTREE index with COSINE distance
+        // This is MUCH faster than brute-force similarity computation
+        // Return both raw similarity_score and importance-weighted vector_score
+        let query = format!(
+            "SELECT *,
+                    vector::similarity::cosine(metadata.embedding, {vector_json}) AS similarity_score,
+                    vector::similarity::cosine(metadata.embedding, {vector_json}) * metadata.importance AS vector_score
+             FROM memory
+             WHERE metadata.embedding <|{limit}|> {vector_json}
+             ORDER BY vector_score DESC
+             LIMIT {limit}",
+            vector_json = vector_json,
+            limit = limit
+        );
+
+        log::debug!("Executing vector search SQL:\n{}", query);
+
+        let mut response = db
+            .query(annotate_query(&query))
+            .await
+            .map_err(|e| Error::Database(format!("{:?}", e)))?;
+        let results: Vec<MemoryNodeSchema> = response.take(0).unwrap_or_default();
+
+        log::info!("Vector search: {} results (limit {})", results.len(), limit);
+
+        Ok(results.into_iter().map(Self::from_schema).collect())
+    }
+
     /// Search memories by text with auto-embedding generation
     pub async fn search_by_text(&self, text: &str, limit: usize) -> Result<MemoryStream> {
         if let Some(ref embedding_model) = self.embedding_model {
@@ -224,7 +270,7 @@ impl SurrealDBMemoryManager {
 
         let (tx, rx) = tokio::sync::mpsc::channel(100);
 
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             let mut conditions = Vec::new();
             let mut bindings = Vec::new();
 
@@ -242,7 +288,7 @@ impl SurrealDBMemoryManager {
 
             let query_str = format!("SELECT * FROM memory{}", where_clause);
 
-            let mut query_builder = db.query(&query_str);
+            let mut query_builder = db.query(annotate_query(&query_str));
             for (param, value) in bindings {
                 query_builder = query_builder.bind((param, value));
             }
@@ -274,7 +320,7 @@ impl SurrealDBMemoryManager {
 
         let mut response = self
             .db
-            .query(query)
+            .query(annotate_query(query))
             .bind(("ids", ids))
             .await
             .map_err(|e| Error::Database(format!("{:?}", e)))?;
@@ -303,7 +349,7 @@ impl SurrealDBMemoryManager {
 
         let mut response = self
             .db
-            .query(query)
+            .query(annotate_query(query))
             .bind(("hash", hash))
             .await
             .map_err(|e| Error::Database(format!("Failed to query by content_hash: {:?}", e)))?;
@@ -333,7 +379,7 @@ impl SurrealDBMemoryManager {
 
         let mut response = self
             .db
-            .query(query)
+            .query(annotate_query(query))
             .bind(("hash", hash))
             .await
             .map_err(|e| Error::Database(format!("Failed to query by content_hash: {:?}", e)))?;
@@ -375,7 +421,7 @@ impl SurrealDBMemoryManager {
 
         let mut response = self
             .db
-            .query(query)
+            .query(annotate_query(query))
             .bind(("hash", hash))
             .bind(("timestamp", timestamp))
             .await
@@ -412,7 +458,7 @@ impl SurrealDBMemoryManager {
 
         let mut response = self
             .db
-            .query(entangled_query)
+            .query(annotate_query(entangled_query))
             .await
             .map_err(|e| Error::Database(format!("Failed to load entangled edges: {:?}", e)))?;
 
@@ -463,7 +509,7 @@ impl SurrealDBMemoryManager {
 
         let mut response = self
             .db
-            .query(causal_query)
+            .query(annotate_query(causal_query))
             .await
             .map_err(|e| Error::Database(format!("Failed to load causal edges: {:?}", e)))?;
 
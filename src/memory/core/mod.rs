@@ -1,8 +1,11 @@
 //! Memory module that provides the core memory functionality
 
 // New hierarchical module structure
+pub mod chunking;
 pub mod cognitive_queue;
+#[cfg(feature = "memory")]
 pub mod cognitive_worker;
+pub mod consolidation;
 pub mod decay_worker;
 pub mod manager;
 pub mod ops;
@@ -15,12 +18,18 @@ pub mod systems;
 
 // Module re-exports for backward compatibility (keeping internal imports working)
 // Manager types (explicit imports to avoid conflicts)
-pub use manager::coordinator::MemoryCoordinator;
+#[cfg(feature = "memory")]
+pub use manager::coordinator::{
+    MemoryCoordinator, MemoryTransaction, NewMemory, ReadSnapshot, TransactionOutcome,
+    WriteGeneration, WriteGenerations,
+};
 pub use manager::surreal::MemoryQuery as SurrealMemoryQuery; // Rename conflicting type
 pub use manager::surreal::{
     MemoryManager, MemoryStream, PendingDeletion, PendingMemory, PendingRelationship,
-    RelationshipStream, SurrealDBMemoryManager,
+    RelationshipStream,
 };
+#[cfg(feature = "memory")]
+pub use manager::surreal::SurrealDBMemoryManager;
 pub use ops::filter;
 pub use ops::filter::{MemoryFilter, MemoryFilterBuilder, TimeRange}; /* Keep ops versions as primary */
 // Main operations types - explicit to avoid conflicts
@@ -44,6 +53,13 @@ pub use schema::*;
 pub use systems::*;
 // Cognitive queue exports
 pub use cognitive_queue::{CognitiveProcessingQueue, CognitiveTask, CognitiveTaskType};
+#[cfg(feature = "memory")]
 pub use cognitive_worker::CognitiveWorker;
+// Consolidation exports
+pub use consolidation::{ConsolidationConfig, ConsolidationReport};
+#[cfg(feature = "memory")]
+pub use consolidation::ConsolidationWorker;
 // Decay worker exports
-pub use decay_worker::{DecayWorker, DecayWorkerConfig};
+pub use decay_worker::DecayWorkerConfig;
+#[cfg(feature = "memory")]
+pub use decay_worker::DecayWorker;
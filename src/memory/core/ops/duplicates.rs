@@ -0,0 +1,194 @@
+//! Near-duplicate detection over embeddings
+//!
+//! Embeddings are bucketed with random-hyperplane LSH (SimHash bands), so
+//! only embeddings sharing a bucket are compared. Pairs at or above the
+//! threshold are joined into groups by complete linkage, so every member of
+//! a group matches every other. Used by the duplicate-finding tool and by
+//! consolidation.
+
+use std::collections::{HashMap, HashSet};
+
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64;
+
+use super::similarity::cosine;
+
+/// LSH bands; a pair is compared when any band matches
+pub const LSH_BANDS: usize = 16;
+
+/// Hyperplanes per band
+pub const LSH_ROWS: usize = 8;
+
+/// Hyperplane seed, fixed so repeated scans bucket identically
+const LSH_SEED: u64 = 0x6b6f_6465_6765_6e;
+
+/// Result of [`duplicate_groups`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DuplicateScan {
+    /// Groups of indices into the scanned embeddings, each with the lowest
+    /// similarity between two of its members
+    pub groups: Vec<(Vec<usize>, f32)>,
+    /// Pairs compared
+    pub comparisons: usize,
+}
+
+/// Random-hyperplane LSH over embeddings of one dimension
+pub struct SimHashIndex {
+    planes: Vec<Vec<f32>>,
+}
+
+impl SimHashIndex {
+    /// [`LSH_BANDS`] × [`LSH_ROWS`] hyperplanes for `dimension`, from a fixed seed
+    pub fn new(dimension: usize) -> Self {
+        let mut rng = Pcg64::seed_from_u64(LSH_SEED);
+        let planes = (0..LSH_BANDS * LSH_ROWS)
+            .map(|_| {
+                (0..dimension)
+                    .map(|_| rng.random_range(-1.0f32..1.0))
+                    .collect()
+            })
+            .collect();
+        Self { planes }
+    }
+
+    /// Bucket key of `embedding` in each band
+    pub fn band_keys(&self, embedding: &[f32]) -> Vec<u64> {
+        self.planes
+            .chunks(LSH_ROWS)
+            .enumerate()
+            .map(|(band, planes)| {
+                let bits = planes.iter().enumerate().fold(0u64, |bits, (row, plane)| {
+                    let dot: f32 = plane.iter().zip(embedding).map(|(p, x)| p * x).sum();
+                    if dot >= 0.0 { bits | (1 << row) } else { bits }
+                });
+                ((band as u64) << LSH_ROWS) | bits
+            })
+            .collect()
+    }
+}
+
+/// Group embeddings whose cosine similarity is at least `threshold`
+///
+/// Only embeddings sharing an LSH bucket are candidates. Groups are built
+/// by complete linkage: matching pairs are taken most similar first, and two
+/// groups join only if every member of one matches every member of the
+/// other, so a chain of near-matches never lands in one group. Groups are
+/// returned largest first, members in index order.
+pub fn duplicate_groups<E: AsRef<[f32]>>(embeddings: &[E], threshold: f32) -> DuplicateScan {
+    let mut by_dimension: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, embedding) in embeddings.iter().enumerate() {
+        let dimension = embedding.as_ref().len();
+        if dimension > 0 {
+            by_dimension.entry(dimension).or_default().push(index);
+        }
+    }
+
+    let mut compared = HashSet::new();
+    let mut matches: Vec<(f32, usize, usize)> = Vec::new();
+
+    for (dimension, indices) in by_dimension {
+        let index = SimHashIndex::new(dimension);
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+        for &i in &indices {
+            for key in index.band_keys(embeddings[i].as_ref()) {
+                buckets.entry(key).or_default().push(i);
+            }
+        }
+
+        for bucket in buckets.values() {
+            for (n, &a) in bucket.iter().enumerate() {
+                for &b in &bucket[n + 1..] {
+                    if !compared.insert((a.min(b), a.max(b))) {
+                        continue;
+                    }
+                    let similarity = cosine(embeddings[a].as_ref(), embeddings[b].as_ref());
+                    if similarity >= threshold {
+                        matches.push((similarity, a, b));
+                    }
+                }
+            }
+        }
+    }
+
+    let groups = complete_linkage(embeddings.len(), matches, threshold, |x, y| {
+        compared.insert((x.min(y), x.max(y)));
+        cosine(embeddings[x].as_ref(), embeddings[y].as_ref())
+    });
+
+    DuplicateScan {
+        groups,
+        comparisons: compared.len(),
+    }
+}
+
+/// Join matching pairs into groups whose members all match each other
+///
+/// `matches` are `(similarity, a, b)` pairs at or above `threshold` among
+/// `count` items; `similarity` scores any other pair the linkage checks.
+/// Pairs are taken most similar first, and two groups join only if every
+/// cross pair reaches `threshold`. Returns groups of two or more, largest
+/// first with members in index order, each with its lowest pair similarity.
+pub fn complete_linkage(
+    count: usize,
+    mut matches: Vec<(f32, usize, usize)>,
+    threshold: f32,
+    mut similarity: impl FnMut(usize, usize) -> f32,
+) -> Vec<(Vec<usize>, f32)> {
+    matches.sort_by(|x, y| y.0.total_cmp(&x.0).then_with(|| (x.1, x.2).cmp(&(y.1, y.2))));
+
+    // Each item starts as its own group, named by its index
+    let mut group_of: Vec<usize> = (0..count).collect();
+    let mut members: Vec<Vec<usize>> = (0..count).map(|i| vec![i]).collect();
+    let mut weakest: HashMap<usize, f32> = HashMap::new();
+
+    for (pair_similarity, a, b) in matches {
+        let (group_a, group_b) = (group_of[a], group_of[b]);
+        if group_a == group_b {
+            continue;
+        }
+
+        let mut lowest = [weakest.get(&group_a), weakest.get(&group_b)]
+            .into_iter()
+            .flatten()
+            .fold(pair_similarity, |low, &s| low.min(s));
+        let linked = members[group_a].iter().all(|&x| {
+            members[group_b].iter().all(|&y| {
+                let s = if (x, y) == (a, b) || (x, y) == (b, a) {
+                    pair_similarity
+                } else {
+                    similarity(x, y)
+                };
+                lowest = lowest.min(s);
+                s >= threshold
+            })
+        });
+        if !linked {
+            continue;
+        }
+
+        let (keep, absorb) = if members[group_a].len() >= members[group_b].len() {
+            (group_a, group_b)
+        } else {
+            (group_b, group_a)
+        };
+        let moved = std::mem::take(&mut members[absorb]);
+        for &i in &moved {
+            group_of[i] = keep;
+        }
+        members[keep].extend(moved);
+        weakest.remove(&absorb);
+        weakest.insert(keep, lowest);
+    }
+
+    let mut groups: Vec<(Vec<usize>, f32)> = members
+        .into_iter()
+        .enumerate()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|(id, mut group)| {
+            group.sort_unstable();
+            (group, weakest.get(&id).copied().unwrap_or(1.0))
+        })
+        .collect();
+    groups.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+    groups
+}
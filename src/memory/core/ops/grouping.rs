@@ -0,0 +1,79 @@
+//! Grouping of search hits by source document
+//!
+//! Chunked libraries return several hits from the same file. Grouping keeps
+//! one entry per source, ranked by a combined score: the best hit counts in
+//! full and each further hit adds half as much weight as the one before it,
+//! so a source with several strong matches outranks one with a single match
+//! of the same strength without letting many weak chunks dominate.
+
+use crate::domain::memory::primitives::node::MemoryNode;
+use crate::memory::core::primitives::chunk::CHUNK_SOURCE_KEY;
+
+/// Hits from one source
+#[derive(Debug, Clone)]
+pub struct SourceGroup<T> {
+    /// Source path or URL, or the memory ID for memories without a source
+    pub source: String,
+    /// Combined score of all hits
+    pub score: f32,
+    /// Hits ordered best first
+    pub hits: Vec<T>,
+}
+
+impl<T> SourceGroup<T> {
+    /// Highest scoring hit
+    pub fn best(&self) -> &T {
+        &self.hits[0]
+    }
+}
+
+/// Group `hits` by `source`, returning at most `limit` groups best first
+///
+/// `score` is applied to each hit once; hits with equal source keys end up
+/// in the same group.
+pub fn group_by_source<T>(
+    hits: Vec<T>,
+    limit: usize,
+    source: impl Fn(&T) -> String,
+    score: impl Fn(&T) -> f32,
+) -> Vec<SourceGroup<T>> {
+    let mut groups: Vec<(String, Vec<(f32, T)>)> = Vec::new();
+    for hit in hits {
+        let key = source(&hit);
+        let hit_score = score(&hit);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push((hit_score, hit)),
+            None => groups.push((key, vec![(hit_score, hit)])),
+        }
+    }
+
+    let mut groups: Vec<SourceGroup<T>> = groups
+        .into_iter()
+        .map(|(source, mut members)| {
+            members.sort_by(|a, b| b.0.total_cmp(&a.0));
+            let score = members
+                .iter()
+                .enumerate()
+                .map(|(i, (s, _))| s / 2f32.powi(i as i32))
+                .sum();
+            SourceGroup {
+                source,
+                score,
+                hits: members.into_iter().map(|(_, hit)| hit).collect(),
+            }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| b.score.total_cmp(&a.score));
+    groups.truncate(limit);
+    groups
+}
+
+/// Source document of a memory: the chunk's source path, else its `source`
+/// metadata, else its own ID
+pub fn memory_source(memory: &MemoryNode) -> String {
+    [CHUNK_SOURCE_KEY, "source"]
+        .iter()
+        .find_map(|key| memory.metadata.custom.get(*key)?.as_str().map(str::to_string))
+        .unwrap_or_else(|| memory.id().to_string())
+}
@@ -1,17 +1,29 @@
 //! Memory operations
 
+pub mod duplicates;
 pub mod evolution;
 pub mod filter;
 pub mod graph;
+pub mod grouping;
 pub mod query;
+pub mod ranking;
 pub mod repository;
+pub mod rerank;
 pub mod retrieval;
+pub mod similarity;
 pub mod storage;
+pub mod threshold;
 
+pub use duplicates::*;
 pub use evolution::*;
 pub use filter::*;
 pub use graph::*;
+pub use grouping::*;
 pub use query::*;
+pub use ranking::*;
 pub use repository::*;
+pub use rerank::*;
 pub use retrieval::*;
+pub use similarity::*;
 pub use storage::*;
+pub use threshold::*;
@@ -0,0 +1,134 @@
+//! Pluggable ranking of recall candidates
+//!
+//! Search returns candidates with a relevance (the reranker's score for
+//! reranked hits, the cosine similarity otherwise). A [`MemoryRanker`] turns
+//! them into the final order: [`SimilarityImportanceRanker`], the default,
+//! scores each by relevance × importance. Other rankers can weigh recency,
+//! source or anything else in a memory's metadata.
+//!
+//! A ranker is registered per library with
+//! [`MemoryCoordinator::set_ranker`] or passed for one search to
+//! [`MemoryCoordinator::search_ranked_at`]:
+//!
+//! ```ignore
+//! struct Recent;
+//!
+//! impl MemoryRanker for Recent {
+//!     fn rank(&self, _query: &[f32], mut candidates: Vec<ScoredMemory>) -> Vec<ScoredMemory> {
+//!         candidates.sort_by_key(|c| std::cmp::Reverse(c.memory.creation_time()));
+//!         candidates
+//!     }
+//! }
+//!
+//! pool.get_coordinator("notes").await?.set_ranker(Arc::new(Recent));
+//! ```
+//!
+//! [`MemoryCoordinator::set_ranker`]: crate::memory::core::manager::coordinator::MemoryCoordinator::set_ranker
+//! [`MemoryCoordinator::search_ranked_at`]: crate::memory::core::manager::coordinator::MemoryCoordinator::search_ranked_at
+
+use std::sync::Arc;
+
+use crate::domain::memory::primitives::node::MemoryNode;
+use crate::memory::core::ops::rerank::rerank_score;
+use crate::memory::core::ops::similarity::boosted_score;
+
+/// A recall candidate and its score
+#[derive(Debug, Clone)]
+pub struct ScoredMemory {
+    pub memory: MemoryNode,
+    /// Relevance going into a ranker, ranking score coming out
+    pub score: f32,
+}
+
+impl ScoredMemory {
+    /// Candidate scored by its relevance
+    pub fn new(memory: MemoryNode) -> Self {
+        let score = relevance(&memory);
+        Self { memory, score }
+    }
+}
+
+/// Orders recall candidates
+pub trait MemoryRanker: Send + Sync {
+    /// Score and reorder `candidates`, best first
+    ///
+    /// `query_embedding` is empty when [`Self::uses_query_embedding`] is
+    /// false. Rankers may drop candidates but should not add any.
+    fn rank(&self, query_embedding: &[f32], candidates: Vec<ScoredMemory>) -> Vec<ScoredMemory>;
+
+    /// Whether [`Self::rank`] reads the query embedding
+    ///
+    /// Searches only embed the query for rankers that need it.
+    fn uses_query_embedding(&self) -> bool {
+        true
+    }
+
+    /// Name shown in logs
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+impl std::fmt::Debug for dyn MemoryRanker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MemoryRanker").field(&self.name()).finish()
+    }
+}
+
+/// The default ranking: relevance × importance
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimilarityImportanceRanker;
+
+impl MemoryRanker for SimilarityImportanceRanker {
+    fn rank(
+        &self,
+        _query_embedding: &[f32],
+        mut candidates: Vec<ScoredMemory>,
+    ) -> Vec<ScoredMemory> {
+        for candidate in &mut candidates {
+            candidate.score = boosted_score(candidate.score, candidate.memory.importance());
+        }
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        candidates
+    }
+
+    fn uses_query_embedding(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> &str {
+        "similarity×importance"
+    }
+}
+
+/// A shared [`SimilarityImportanceRanker`]
+pub fn default_ranker() -> Arc<dyn MemoryRanker> {
+    Arc::new(SimilarityImportanceRanker)
+}
+
+/// Raw cosine similarity stored in `metadata.custom` by the search
+pub fn similarity(memory: &MemoryNode) -> f32 {
+    memory
+        .metadata
+        .custom
+        .get("similarity")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0) as f32
+}
+
+/// The reranker's score for reranked hits, the similarity otherwise
+pub fn relevance(memory: &MemoryNode) -> f32 {
+    rerank_score(memory).unwrap_or_else(|| similarity(memory))
+}
+
+/// Rank `memories` with `ranker`
+pub fn rank_memories(
+    ranker: &dyn MemoryRanker,
+    query_embedding: &[f32],
+    memories: Vec<MemoryNode>,
+) -> Vec<ScoredMemory> {
+    ranker.rank(
+        query_embedding,
+        memories.into_iter().map(ScoredMemory::new).collect(),
+    )
+}
@@ -0,0 +1,62 @@
+//! Second-stage reranking of search hits
+//!
+//! Vector search ranks by embedding similarity alone. [`rerank_memories`]
+//! rescores the top hits with a cross-encoder that reads the query and each
+//! memory together, and reorders them by that score. The score is kept in
+//! `metadata.custom` under [`RERANK_SCORE_KEY`], next to the similarity.
+
+use crate::capability::traits::RerankCapable;
+use crate::domain::memory::primitives::node::MemoryNode;
+
+/// Vector hits fetched for the reranker to choose from
+pub const RERANK_CANDIDATES: usize = 50;
+
+/// Metadata key of the reranker's relevance score
+pub const RERANK_SCORE_KEY: &str = "rerank_score";
+
+/// Reranker relevance of a reranked memory
+pub fn rerank_score(memory: &MemoryNode) -> Option<f32> {
+    memory
+        .metadata
+        .custom
+        .get(RERANK_SCORE_KEY)
+        .and_then(|v| v.as_f64())
+        .map(|score| score as f32)
+}
+
+/// Reorder `memories` by their relevance to `query`, keeping the best `limit`
+///
+/// # Errors
+///
+/// Returns the reranker's error when the model cannot be loaded or run.
+pub async fn rerank_memories(
+    reranker: &impl RerankCapable,
+    query: &str,
+    memories: Vec<MemoryNode>,
+    limit: usize,
+) -> Result<Vec<MemoryNode>, Box<dyn std::error::Error + Send + Sync>> {
+    let documents: Vec<String> = memories
+        .iter()
+        .map(|memory| memory.content().to_string())
+        .collect();
+    let scores = reranker.rerank(query, &documents).await?;
+    if scores.len() != memories.len() {
+        return Err(format!(
+            "Reranker returned {} scores for {} memories",
+            scores.len(),
+            memories.len()
+        )
+        .into());
+    }
+
+    let mut scored: Vec<(f32, MemoryNode)> = scores.into_iter().zip(memories).collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(scored
+        .into_iter()
+        .take(limit)
+        .map(|(score, mut memory)| {
+            memory.set_custom_metadata(RERANK_SCORE_KEY, serde_json::Value::from(score));
+            memory
+        })
+        .collect())
+}
@@ -0,0 +1,66 @@
+//! In-process similarity and boost scoring
+//!
+//! Vector search ranks memories inside SurrealDB, but several paths re-score
+//! candidates in Rust afterwards: recall boosts, tool selection, citation and
+//! any re-ranking of related or fused results. They all go through this
+//! module so they share one SIMD-backed cosine implementation and one
+//! definition of a boosted score. `benches/similarity.rs` compares it with
+//! [`cosine_scalar`] on 1024-dimensional vectors.
+
+use kodegen_simd::similarity::cosine_similarity;
+
+/// Cosine similarity, 0.0 for mismatched, empty or zero-norm vectors
+///
+/// Uses the `kodegen_simd` kernels for the current CPU.
+#[inline]
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let similarity = cosine_similarity(a, b);
+    if similarity.is_finite() { similarity } else { 0.0 }
+}
+
+/// Scalar cosine similarity, the reference for [`cosine`]
+pub fn cosine_scalar(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    let denom = norm_a.sqrt() * norm_b.sqrt();
+    if denom == 0.0 { 0.0 } else { dot / denom }
+}
+
+/// Cosine similarity of `query` to each candidate, in candidate order
+pub fn cosine_batch<C: AsRef<[f32]>>(query: &[f32], candidates: &[C]) -> Vec<f32> {
+    candidates
+        .iter()
+        .map(|candidate| cosine(query, candidate.as_ref()))
+        .collect()
+}
+
+/// Indices and similarities of the `k` candidates closest to `query`, best first
+pub fn top_k_by_similarity<C: AsRef<[f32]>>(
+    query: &[f32],
+    candidates: &[C],
+    k: usize,
+) -> Vec<(usize, f32)> {
+    let mut scored: Vec<(usize, f32)> = cosine_batch(query, candidates)
+        .into_iter()
+        .enumerate()
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(k);
+    scored
+}
+
+/// Ranking score of a memory: similarity × importance
+#[inline]
+pub fn boosted_score(similarity: f32, importance: f32) -> f32 {
+    similarity * importance
+}
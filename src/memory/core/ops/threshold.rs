@@ -0,0 +1,41 @@
+//! Minimum relevance for recall results
+//!
+//! Vector search always returns its nearest neighbours, however far away
+//! they are. [`apply_min_score`] drops hits below a floor and keeps the best
+//! rejected score, so an empty result can say how close the library came.
+
+/// Hits that reached the floor, and what was left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thresholded<T> {
+    /// Hits scoring at least the floor, in their original order
+    pub kept: Vec<T>,
+    /// Number of hits below the floor
+    pub rejected: usize,
+    /// Highest score among the rejected hits
+    pub best_rejected: Option<f32>,
+}
+
+/// Split `hits` by whether `score` reaches `min_score`
+pub fn apply_min_score<T>(
+    hits: Vec<T>,
+    min_score: f32,
+    score: impl Fn(&T) -> f32,
+) -> Thresholded<T> {
+    let mut kept = Vec::with_capacity(hits.len());
+    let mut rejected = 0;
+    let mut best_rejected: Option<f32> = None;
+    for hit in hits {
+        let hit_score = score(&hit);
+        if hit_score >= min_score {
+            kept.push(hit);
+        } else {
+            rejected += 1;
+            best_rejected = Some(best_rejected.map_or(hit_score, |best| best.max(hit_score)));
+        }
+    }
+    Thresholded {
+        kept,
+        rejected,
+        best_rejected,
+    }
+}
@@ -0,0 +1,141 @@
+//! Source-anchored memory chunks
+//!
+//! A chunk is a span of a source document (usually a file) stored as its own
+//! memory. Its ID is derived from the source and the span, so memorizing the
+//! same file again addresses the same records: unchanged chunks are kept,
+//! edited ones are re-embedded in place, and recall hits carry the path and
+//! byte/line offsets needed to jump back to the exact location.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Custom metadata key holding the chunk's source path or URL
+pub const CHUNK_SOURCE_KEY: &str = "source_path";
+/// Custom metadata key holding the first byte of the span
+pub const CHUNK_BYTE_START_KEY: &str = "byte_start";
+/// Custom metadata key holding the byte after the span
+pub const CHUNK_BYTE_END_KEY: &str = "byte_end";
+/// Custom metadata key holding the first line of the span (1-based)
+pub const CHUNK_LINE_START_KEY: &str = "line_start";
+/// Custom metadata key holding the last line of the span (1-based, inclusive)
+pub const CHUNK_LINE_END_KEY: &str = "line_end";
+
+/// Namespace for chunk IDs (UUID v5)
+const CHUNK_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6b6f_6465_6765_6e2d_6368_756e_6b2d_6964);
+
+/// Location of a chunk within its source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceSpan {
+    /// First byte of the span
+    pub byte_start: usize,
+    /// Byte after the span
+    pub byte_end: usize,
+    /// First line of the span (1-based)
+    pub line_start: usize,
+    /// Last line of the span (1-based, inclusive)
+    pub line_end: usize,
+}
+
+impl SourceSpan {
+    /// Span of `bytes` within `text`, with line numbers computed from `text`
+    ///
+    /// The range is clamped to `text` and moved to the nearest character
+    /// boundaries.
+    pub fn of(text: &str, bytes: Range<usize>) -> Self {
+        let byte_start = text.floor_char_boundary(bytes.start);
+        let byte_end = text.floor_char_boundary(bytes.end).max(byte_start);
+
+        let line_start = 1 + text.as_bytes()[..byte_start]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        let line_end = line_start
+            + text.as_bytes()[byte_start..byte_end]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count()
+            - usize::from(text[byte_start..byte_end].ends_with('\n'));
+
+        Self {
+            byte_start,
+            byte_end,
+            line_start,
+            line_end: line_end.max(line_start),
+        }
+    }
+
+    /// Length of the span in bytes
+    pub fn len(&self) -> usize {
+        self.byte_end - self.byte_start
+    }
+
+    /// Whether the span is empty
+    pub fn is_empty(&self) -> bool {
+        self.byte_end == self.byte_start
+    }
+}
+
+/// Deterministic memory ID for a chunk of `source`
+pub fn chunk_id(source: &str, span: &SourceSpan) -> Uuid {
+    let name = format!("{source}#{}..{}", span.byte_start, span.byte_end);
+    Uuid::new_v5(&CHUNK_ID_NAMESPACE, name.as_bytes())
+}
+
+/// A span of a source document to be stored as one memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryChunk {
+    /// Source path or URL
+    pub source: String,
+    /// Location within the source
+    pub span: SourceSpan,
+    /// Text of the span
+    pub content: String,
+}
+
+impl MemoryChunk {
+    /// Chunk covering `bytes` of `text`, read from `source`
+    pub fn new(source: impl Into<String>, text: &str, bytes: Range<usize>) -> Self {
+        let span = SourceSpan::of(text, bytes);
+        Self {
+            source: source.into(),
+            content: text[span.byte_start..span.byte_end].to_string(),
+            span,
+        }
+    }
+
+    /// Chunk covering the whole of `text`
+    pub fn whole(source: impl Into<String>, text: &str) -> Self {
+        Self::new(source, text, 0..text.len())
+    }
+
+    /// Deterministic memory ID of this chunk
+    pub fn id(&self) -> Uuid {
+        chunk_id(&self.source, &self.span)
+    }
+
+    /// Source and span as custom metadata fields
+    pub fn custom_metadata(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut custom = serde_json::Map::new();
+        custom.insert(CHUNK_SOURCE_KEY.into(), self.source.clone().into());
+        custom.insert(CHUNK_BYTE_START_KEY.into(), self.span.byte_start.into());
+        custom.insert(CHUNK_BYTE_END_KEY.into(), self.span.byte_end.into());
+        custom.insert(CHUNK_LINE_START_KEY.into(), self.span.line_start.into());
+        custom.insert(CHUNK_LINE_END_KEY.into(), self.span.line_end.into());
+        custom
+    }
+}
+
+/// Read a chunk's source and span back from custom metadata
+pub fn chunk_location(custom: &serde_json::Value) -> Option<(String, SourceSpan)> {
+    let field = |key: &str| custom.get(key)?.as_u64().map(|v| v as usize);
+    let source = custom.get(CHUNK_SOURCE_KEY)?.as_str()?.to_string();
+    let span = SourceSpan {
+        byte_start: field(CHUNK_BYTE_START_KEY)?,
+        byte_end: field(CHUNK_BYTE_END_KEY)?,
+        line_start: field(CHUNK_LINE_START_KEY)?,
+        line_end: field(CHUNK_LINE_END_KEY)?,
+    };
+    Some((source, span))
+}
@@ -1,10 +1,12 @@
 //! Core memory primitives
 
+pub mod chunk;
 pub mod metadata;
 pub mod node;
 pub mod relationship;
 pub mod types;
 
+pub use chunk::*;
 pub use metadata::*;
 pub use node::*;
 pub use relationship::*;
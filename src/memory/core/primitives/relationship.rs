@@ -6,7 +6,7 @@ use std::fmt;
 use cyrup_sugars::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use surrealdb::types::SurrealValue;
+use surrealdb_types::SurrealValue;
 
 /// Represents the direction of a relationship
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -10,7 +10,7 @@ use base64::Engine;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use surrealdb::types::{Datetime as SurrealDatetime, SurrealValue};
+use surrealdb_types::{Datetime as SurrealDatetime, SurrealValue};
 use surrealdb_types;
 
 use crate::memory::graph::entity::BaseEntity;
@@ -19,12 +19,13 @@ use crate::memory::utils::Result;
 use crate::memory::utils::error::Error;
 
 /// Convert serde_json::Value to surrealdb::Value
-fn json_to_surreal_value(json: serde_json::Value) -> surrealdb::types::Value {
+fn json_to_surreal_value(json: serde_json::Value) -> surrealdb_types::Value {
     SurrealValue::into_value(json)
 }
 
 /// Memory type enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum MemoryTypeEnum {
     /// Semantic memory (knowledge graph)
@@ -80,12 +81,12 @@ impl SurrealValue for MemoryTypeEnum {
         surrealdb_types::Kind::String
     }
 
-    fn into_value(self) -> surrealdb::types::Value {
+    fn into_value(self) -> surrealdb_types::Value {
         // Serialize as a string using Display trait
         SurrealValue::into_value(self.to_string())
     }
 
-    fn from_value(value: surrealdb::types::Value) -> anyhow::Result<Self> {
+    fn from_value(value: surrealdb_types::Value) -> anyhow::Result<Self> {
         // Deserialize from string using FromStr
         let s = String::from_value(value)?;
         s.parse().map_err(|e: Error| anyhow::anyhow!("{}", e))
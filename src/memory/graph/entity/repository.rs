@@ -2,7 +2,7 @@
 //!
 //! Provides thread-safe interface for entity persistence operations.
 
-use surrealdb::types::Value;
+use surrealdb_types::Value;
 
 use super::futures::{
     PendingEntity, PendingEntityCount, PendingEntityList, PendingEntityOption, PendingUnit,
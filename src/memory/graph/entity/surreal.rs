@@ -3,7 +3,7 @@
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use surrealdb::types::Value;
+use surrealdb_types::Value;
 use tokio::sync::oneshot;
 
 use super::futures::{
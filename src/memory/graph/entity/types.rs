@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
-use surrealdb::types::{Value, SurrealValue};
+use surrealdb_types::{Value, SurrealValue};
 
 use crate::memory::graph::graph_db::{GraphError, Node, Result};
 
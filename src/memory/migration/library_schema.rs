@@ -0,0 +1,473 @@
+//! Per-library schema versions
+//!
+//! Every library database carries a `schema_version:library` record: the
+//! version of [`LibraryMigrations`] applied to it, the dimension its vector
+//! index was built for and, once a memory is written, the registry key of
+//! the embedding model its memories are embedded with.
+//! [`LibrarySchema::ensure`] consults it when a coordinator opens the
+//! library:
+//!
+//! - if the embedding model's dimension differs from the recorded one, an
+//!   [`EmbeddingDimensionMigration`] rebuilds the MTREE index and sets the
+//!   old embeddings aside (they are restored if the dimension changes back);
+//! - migrations newer than the recorded version are applied in order, and the
+//!   record is updated after each one, so a failure leaves it accurate.
+//!
+//! Libraries created before versioning have no record; they are treated as
+//! version 0 with the [`LEGACY_EMBEDDING_DIMENSION`] index. A library with
+//! no record and no memories is new, and starts at the requested dimension.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use surrealdb::{Surreal, engine::any::Any};
+use tokio::sync::oneshot;
+
+use crate::memory::migration::{Migration, MigrationError, PendingMigration, Result};
+
+/// Version reached once every library migration is applied
+pub const LIBRARY_SCHEMA_VERSION: u32 = 3;
+
+/// Vector index dimension of libraries created before versioning
+pub const LEGACY_EMBEDDING_DIMENSION: usize = 1024;
+
+/// Record holding a library's schema version
+const SCHEMA_VERSION_RECORD: &str = "schema_version:library";
+
+/// Name of the vector index on memory embeddings
+const EMBEDDING_INDEX: &str = "memory_embedding_mtree";
+
+/// Schema state recorded in a library
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibrarySchemaVersion {
+    /// Last library migration applied
+    pub version: u32,
+    /// Dimension the vector index was built for
+    pub embedding_dimension: usize,
+}
+
+impl Default for LibrarySchemaVersion {
+    fn default() -> Self {
+        Self {
+            version: 0,
+            embedding_dimension: LEGACY_EMBEDDING_DIMENSION,
+        }
+    }
+}
+
+/// Run `statements` in order, stopping at the first failure
+fn run_statements(db: Arc<Surreal<Any>>, statements: Vec<String>) -> PendingMigration {
+    let (tx, rx) = oneshot::channel();
+
+    tokio::spawn(async move {
+        for statement in statements {
+            let result = match db.query(statement.as_str()).await {
+                Ok(response) => response.check().map(|_| ()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                let _ = tx.send(Err(MigrationError::DatabaseError(format!(
+                    "Failed to run '{}': {:?}",
+                    statement, e
+                ))));
+                return;
+            }
+        }
+        let _ = tx.send(Ok(()));
+    });
+
+    PendingMigration::new(rx)
+}
+
+/// Versioned migrations of library databases
+pub struct LibraryMigrations;
+
+impl LibraryMigrations {
+    /// Every library migration, oldest first
+    pub fn all() -> Vec<Box<dyn Migration>> {
+        vec![
+            Box::new(L1SecondaryEmbeddings),
+            Box::new(L2AccessCounters),
+            Box::new(L3ToolCallAudit),
+        ]
+    }
+}
+
+/// L1: Secondary embedding per memory (e.g. a second task or model)
+struct L1SecondaryEmbeddings;
+
+impl L1SecondaryEmbeddings {
+    fn up_statements() -> Vec<String> {
+        vec![
+            "DEFINE FIELD IF NOT EXISTS metadata.secondary_embedding ON memory TYPE option<array<float>>".to_string(),
+            "DEFINE FIELD IF NOT EXISTS metadata.secondary_embedding_task ON memory TYPE option<string>".to_string(),
+        ]
+    }
+}
+
+impl Migration for L1SecondaryEmbeddings {
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn name(&self) -> &str {
+        "secondary_embeddings"
+    }
+
+    fn content(&self) -> String {
+        Self::up_statements().join(";\n")
+    }
+
+    fn up(&self, db: Arc<Surreal<Any>>) -> PendingMigration {
+        run_statements(db, Self::up_statements())
+    }
+
+    fn down(&self, db: Arc<Surreal<Any>>) -> PendingMigration {
+        run_statements(
+            db,
+            vec![
+                "UPDATE memory UNSET metadata.secondary_embedding, metadata.secondary_embedding_task".to_string(),
+                "REMOVE FIELD IF EXISTS metadata.secondary_embedding_task ON memory".to_string(),
+                "REMOVE FIELD IF EXISTS metadata.secondary_embedding ON memory".to_string(),
+            ],
+        )
+    }
+}
+
+/// L2: Per-memory access counter, indexed for least-used queries
+struct L2AccessCounters;
+
+impl L2AccessCounters {
+    fn up_statements() -> Vec<String> {
+        vec![
+            "DEFINE FIELD IF NOT EXISTS metadata.access_count ON memory TYPE option<int>".to_string(),
+            "DEFINE INDEX IF NOT EXISTS memory_access_count_idx ON memory FIELDS metadata.access_count".to_string(),
+        ]
+    }
+}
+
+impl Migration for L2AccessCounters {
+    fn version(&self) -> u32 {
+        2
+    }
+
+    fn name(&self) -> &str {
+        "access_counters"
+    }
+
+    fn content(&self) -> String {
+        Self::up_statements().join(";\n")
+    }
+
+    fn up(&self, db: Arc<Surreal<Any>>) -> PendingMigration {
+        run_statements(db, Self::up_statements())
+    }
+
+    fn down(&self, db: Arc<Surreal<Any>>) -> PendingMigration {
+        run_statements(
+            db,
+            vec![
+                "REMOVE INDEX IF EXISTS memory_access_count_idx ON memory".to_string(),
+                "UPDATE memory UNSET metadata.access_count".to_string(),
+                "REMOVE FIELD IF EXISTS metadata.access_count ON memory".to_string(),
+            ],
+        )
+    }
+}
+
+/// L3: Tool-call audit log, indexed by session and tool
+struct L3ToolCallAudit;
+
+impl L3ToolCallAudit {
+    fn up_statements() -> Vec<String> {
+        vec![
+            "DEFINE TABLE IF NOT EXISTS tool_call SCHEMALESS".to_string(),
+            "DEFINE INDEX IF NOT EXISTS tool_call_session_idx ON tool_call FIELDS session_id, turn_id".to_string(),
+            "DEFINE INDEX IF NOT EXISTS tool_call_tool_idx ON tool_call FIELDS tool".to_string(),
+            "DEFINE INDEX IF NOT EXISTS tool_call_called_at_idx ON tool_call FIELDS called_at".to_string(),
+        ]
+    }
+}
+
+impl Migration for L3ToolCallAudit {
+    fn version(&self) -> u32 {
+        3
+    }
+
+    fn name(&self) -> &str {
+        "tool_call_audit"
+    }
+
+    fn content(&self) -> String {
+        Self::up_statements().join(";\n")
+    }
+
+    fn up(&self, db: Arc<Surreal<Any>>) -> PendingMigration {
+        run_statements(db, Self::up_statements())
+    }
+
+    fn down(&self, db: Arc<Surreal<Any>>) -> PendingMigration {
+        // The audit records are kept; only the indexes go
+        run_statements(
+            db,
+            vec![
+                "REMOVE INDEX IF EXISTS tool_call_called_at_idx ON tool_call".to_string(),
+                "REMOVE INDEX IF EXISTS tool_call_tool_idx ON tool_call".to_string(),
+                "REMOVE INDEX IF EXISTS tool_call_session_idx ON tool_call".to_string(),
+            ],
+        )
+    }
+}
+
+/// Move a library's vector index from one embedding dimension to another
+///
+/// Embeddings of another dimension cannot be searched with the new index.
+/// Instead of being dropped, they are swapped into
+/// `metadata.previous_embedding`, and a set-aside embedding is swapped back
+/// only if it has the target dimension, so `down` restores the library.
+/// Memories left without an embedding must be re-memorized to be found by
+/// vector search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddingDimensionMigration {
+    pub from: usize,
+    pub to: usize,
+}
+
+impl EmbeddingDimensionMigration {
+    /// Statements that make `dimension` the library's embedding dimension
+    pub fn statements(dimension: usize) -> Vec<String> {
+        vec![
+            format!(
+                "UPDATE memory SET metadata.swap_embedding = metadata.embedding, metadata.swap_pending = true \
+                 WHERE array::len(metadata.embedding ?? []) != {dimension} \
+                 AND (metadata.embedding ?? metadata.previous_embedding) != NONE"
+            ),
+            format!(
+                "UPDATE memory SET metadata.embedding = metadata.previous_embedding, \
+                 metadata.previous_embedding = metadata.swap_embedding, \
+                 metadata.swap_embedding = NONE, metadata.swap_pending = NONE \
+                 WHERE metadata.swap_pending = true \
+                 AND array::len(metadata.previous_embedding ?? []) = {dimension}"
+            ),
+            // A set-aside embedding of a third dimension stays set aside
+            "UPDATE memory SET metadata.embedding = NONE, \
+             metadata.previous_embedding = metadata.swap_embedding ?? metadata.previous_embedding, \
+             metadata.swap_embedding = NONE, metadata.swap_pending = NONE \
+             WHERE metadata.swap_pending = true"
+                .to_string(),
+            format!("REMOVE INDEX IF EXISTS {EMBEDDING_INDEX} ON memory"),
+            Self::index_statement(dimension),
+        ]
+    }
+
+    fn index_statement(dimension: usize) -> String {
+        format!(
+            "DEFINE INDEX {EMBEDDING_INDEX} ON memory FIELDS metadata.embedding \
+             MTREE DIMENSION {dimension} DIST COSINE TYPE F32"
+        )
+    }
+
+    /// Switch the library to `to`
+    pub fn up(&self, db: Arc<Surreal<Any>>) -> PendingMigration {
+        Self::switch_to(db, self.to)
+    }
+
+    /// Switch the library back to `from`
+    pub fn down(&self, db: Arc<Surreal<Any>>) -> PendingMigration {
+        Self::switch_to(db, self.from)
+    }
+
+    fn switch_to(db: Arc<Surreal<Any>>, dimension: usize) -> PendingMigration {
+        let mut statements = Self::statements(dimension);
+        let index = statements
+            .pop()
+            .unwrap_or_else(|| Self::index_statement(dimension));
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            if let Err(e) = run_statements(Arc::clone(&db), statements).await {
+                let _ = tx.send(Err(e));
+                return;
+            }
+            // The index is an optimization and MTREE syntax differs across
+            // SurrealDB versions; search still works without it
+            if let Err(e) = run_statements(db, vec![index]).await {
+                log::warn!(
+                    "Embedding index not rebuilt for dimension {}: {}",
+                    dimension,
+                    e
+                );
+            }
+            let _ = tx.send(Ok(()));
+        });
+
+        PendingMigration::new(rx)
+    }
+}
+
+/// Schema version tracking of one library database
+pub struct LibrarySchema {
+    db: Arc<Surreal<Any>>,
+}
+
+impl LibrarySchema {
+    /// Tracker for `db`, defining the `schema_version` table if needed
+    pub async fn new(db: Arc<Surreal<Any>>) -> Result<Self> {
+        run_statements(
+            Arc::clone(&db),
+            vec![
+                "DEFINE TABLE IF NOT EXISTS schema_version SCHEMAFULL".to_string(),
+                "DEFINE FIELD IF NOT EXISTS version ON schema_version TYPE int".to_string(),
+                "DEFINE FIELD IF NOT EXISTS embedding_dimension ON schema_version TYPE int"
+                    .to_string(),
+                "DEFINE FIELD IF NOT EXISTS embedding_model ON schema_version TYPE option<string>"
+                    .to_string(),
+                "DEFINE FIELD IF NOT EXISTS updated_at ON schema_version TYPE datetime".to_string(),
+            ],
+        )
+        .await?;
+        Ok(Self { db })
+    }
+
+    /// The recorded schema state, or the pre-versioning defaults
+    pub async fn current(&self) -> Result<LibrarySchemaVersion> {
+        Ok(self.recorded().await?.unwrap_or_default())
+    }
+
+    /// Registry key of the embedding model the library's memories use,
+    /// `None` until one is recorded
+    pub async fn embedding_model(&self) -> Result<Option<String>> {
+        let rows = self
+            .select(&format!(
+                "SELECT VALUE embedding_model FROM {SCHEMA_VERSION_RECORD}"
+            ))
+            .await?;
+        Ok(rows
+            .into_iter()
+            .next()
+            .and_then(|key| key.as_str().map(str::to_string)))
+    }
+
+    /// Record `registry_key` as the library's embedding model
+    ///
+    /// Call after [`Self::ensure`], which creates the record.
+    pub async fn set_embedding_model(&self, registry_key: &str) -> Result<()> {
+        run_statements(
+            Arc::clone(&self.db),
+            vec![format!(
+                "UPDATE {SCHEMA_VERSION_RECORD} SET embedding_model = {}, updated_at = time::now()",
+                serde_json::Value::from(registry_key)
+            )],
+        )
+        .await
+    }
+
+    /// Bring the library to the latest version and to `embedding_dimension`
+    ///
+    /// Returns the resulting schema state.
+    pub async fn ensure(&self, embedding_dimension: usize) -> Result<LibrarySchemaVersion> {
+        let mut state = match self.recorded().await? {
+            Some(state) => state,
+            // A new library's index is built for the model's dimension already
+            None if self.is_empty().await? => LibrarySchemaVersion {
+                version: 0,
+                embedding_dimension,
+            },
+            None => LibrarySchemaVersion::default(),
+        };
+
+        if state.embedding_dimension != embedding_dimension {
+            log::info!(
+                "Migrating embedding dimension {} -> {}",
+                state.embedding_dimension,
+                embedding_dimension
+            );
+            EmbeddingDimensionMigration {
+                from: state.embedding_dimension,
+                to: embedding_dimension,
+            }
+            .up(Arc::clone(&self.db))
+            .await?;
+            state.embedding_dimension = embedding_dimension;
+            self.save(state).await?;
+        }
+
+        let mut migrations = LibraryMigrations::all();
+        migrations.sort_by_key(|m| m.version());
+        let applied = state.version;
+        for migration in migrations.iter().filter(|m| m.version() > applied) {
+            log::info!(
+                "Applying library migration v{}: {}",
+                migration.version(),
+                migration.name()
+            );
+            migration.up(Arc::clone(&self.db)).await?;
+            state.version = migration.version();
+            self.save(state).await?;
+        }
+
+        Ok(state)
+    }
+
+    /// Undo library migrations newer than `target_version`, newest first
+    ///
+    /// The embedding dimension is left as is.
+    pub async fn rollback_to(&self, target_version: u32) -> Result<LibrarySchemaVersion> {
+        let mut state = self.current().await?;
+
+        let mut migrations = LibraryMigrations::all();
+        migrations.sort_by_key(|m| std::cmp::Reverse(m.version()));
+        let applied = state.version;
+        for migration in migrations
+            .iter()
+            .filter(|m| m.version() > target_version && m.version() <= applied)
+        {
+            log::info!(
+                "Rolling back library migration v{}: {}",
+                migration.version(),
+                migration.name()
+            );
+            migration.down(Arc::clone(&self.db)).await?;
+            state.version = migration.version() - 1;
+            self.save(state).await?;
+        }
+
+        Ok(state)
+    }
+
+    async fn recorded(&self) -> Result<Option<LibrarySchemaVersion>> {
+        let rows = self
+            .select(&format!(
+                "SELECT version, embedding_dimension FROM {SCHEMA_VERSION_RECORD}"
+            ))
+            .await?;
+        Ok(match rows.into_iter().next() {
+            Some(row) => Some(serde_json::from_value(row)?),
+            None => None,
+        })
+    }
+
+    /// Whether the library holds no memories yet
+    async fn is_empty(&self) -> Result<bool> {
+        let rows = self.select("SELECT VALUE meta::id(id) FROM memory LIMIT 1").await?;
+        Ok(rows.is_empty())
+    }
+
+    async fn select(&self, query: &str) -> Result<Vec<serde_json::Value>> {
+        let mut response = self.db.query(query).await.map_err(|e| {
+            MigrationError::DatabaseError(format!("Failed to read schema version: {:?}", e))
+        })?;
+        Ok(response.take(0).unwrap_or_default())
+    }
+
+    async fn save(&self, state: LibrarySchemaVersion) -> Result<()> {
+        run_statements(
+            Arc::clone(&self.db),
+            vec![format!(
+                "UPSERT {SCHEMA_VERSION_RECORD} SET version = {}, embedding_dimension = {}, updated_at = time::now()",
+                state.version, state.embedding_dimension
+            )],
+        )
+        .await
+    }
+}
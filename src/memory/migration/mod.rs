@@ -6,6 +6,7 @@
 pub mod converter;
 pub mod exporter;
 pub mod importer;
+pub mod library_schema;
 pub mod schema_migrations;
 pub mod validator;
 
@@ -18,6 +19,7 @@ use std::task::{Context, Poll};
 pub use converter::*;
 pub use exporter::*;
 pub use importer::*;
+pub use library_schema::*;
 pub use schema_migrations::*;
 use sha2::{Digest, Sha256};
 use surrealdb::Surreal;
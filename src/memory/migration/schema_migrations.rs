@@ -6,7 +6,7 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::{Surreal, engine::any::Any};
-use surrealdb::types::SurrealValue;
+use surrealdb_types::SurrealValue;
 
 use crate::memory::migration::{Migration, MigrationError, PendingMigration};
 
@@ -3,14 +3,19 @@
 //! emergent agent evolution, and self-modifying capabilities.
 
 pub mod api;
+#[cfg(feature = "memory")]
+pub mod builder;
 pub mod cognitive;
 pub mod constants;
 pub mod core;
 pub mod graph;
+#[cfg(feature = "memory")]
 pub mod migration;
 pub mod monitoring;
+#[cfg(feature = "memory")]
 pub mod query;
 pub mod schema;
+#[cfg(feature = "memory")]
 pub mod transaction;
 pub mod utils;
 pub mod vector;
@@ -20,13 +25,19 @@ pub mod vector;
 #[cfg(feature = "api")]
 pub use api::APIServer;
 
+// Standalone library entry point
+#[cfg(feature = "memory")]
+pub use builder::{Memory, MemoryBuilder};
+
 // Re-export core memory submodules for backward compatibility
+#[cfg(feature = "memory")]
 pub use self::core::SurrealDBMemoryManager as SurrealMemoryManager;
 pub use self::core::{
-    MemoryMetadata, MemoryNode, MemoryRelationship, SurrealDBMemoryManager, filter,
-    manager::{MemoryManager, coordinator::MemoryCoordinator},
-    ops, primitives, repository, storage,
+    MemoryMetadata, MemoryNode, MemoryRelationship, filter, manager::MemoryManager, ops,
+    primitives, repository, storage,
 };
+#[cfg(feature = "memory")]
+pub use self::core::{SurrealDBMemoryManager, manager::coordinator::MemoryCoordinator};
 
 // Re-export manager module for compatibility
 pub use self::core::manager;
@@ -39,6 +50,7 @@ pub use utils::error::Error;
 
 /// Initialize the traditional memory system with SurrealDB using a configuration object.
 /// This is a more robust approach than just a DB URL.
+#[cfg(feature = "memory")]
 pub async fn initialize(config: &MemoryConfig) -> Result<SurrealMemoryManager, Error> {
     use surrealdb::engine::any::connect;
     // use surrealdb::opt::auth::Root; // Root auth might not always be needed or desired, depends on config
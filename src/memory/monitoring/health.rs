@@ -8,8 +8,8 @@ use std::task::{Context, Poll};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use surrealdb::Surreal;
-use surrealdb::engine::any::Any;
+#[cfg(feature = "memory")]
+use surrealdb::{Surreal, engine::any::Any};
 use tokio::sync::{RwLock, oneshot};
 
 use crate::memory::vector::vector_store::{IndexStats, VectorStore};
@@ -152,10 +152,12 @@ pub trait ComponentChecker: Send + Sync {
 }
 
 /// Database health checker with actual SurrealDB access
+#[cfg(feature = "memory")]
 pub struct DatabaseHealthChecker {
     database: Arc<Surreal<Any>>,
 }
 
+#[cfg(feature = "memory")]
 impl DatabaseHealthChecker {
     /// Create new health checker with SurrealDB instance
     pub fn new(database: Arc<Surreal<Any>>) -> Self {
@@ -163,6 +165,7 @@ impl DatabaseHealthChecker {
     }
 }
 
+#[cfg(feature = "memory")]
 impl ComponentChecker for DatabaseHealthChecker {
     fn name(&self) -> &str {
         "database"
@@ -9,6 +9,7 @@ pub mod metrics;
 pub mod monitor;
 pub mod operations;
 pub mod performance;
+pub mod recall_log;
 
 // Internal fallback logic (not exported publicly)
 pub(crate) mod fallback;
@@ -20,3 +21,4 @@ pub use metrics::*;
 pub use monitor::*;
 pub use operations::*;
 pub use performance::*;
+pub use recall_log::*;
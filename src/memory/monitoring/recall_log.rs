@@ -0,0 +1,62 @@
+//! Recent recall activity
+//!
+//! A bounded, process-wide log of the most recent memory recalls (library,
+//! query, result count, latency) for operator-facing views such as the CLI
+//! dashboard. Older entries are dropped once the log is full.
+
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of recalls retained
+pub const RECALL_LOG_CAPACITY: usize = 64;
+
+/// Global recall log
+static RECALL_LOG: LazyLock<Mutex<VecDeque<RecallRecord>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::with_capacity(RECALL_LOG_CAPACITY)));
+
+/// A single completed recall
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecallRecord {
+    /// Library searched
+    pub library: String,
+    /// Query text
+    pub query: String,
+    /// Number of memories returned
+    pub results: usize,
+    /// End-to-end search latency in milliseconds
+    pub latency_ms: f64,
+    /// Completion time
+    pub at: DateTime<Utc>,
+}
+
+/// Record a completed recall
+pub fn record_recall(
+    library: impl Into<String>,
+    query: impl Into<String>,
+    results: usize,
+    latency: Duration,
+) {
+    let record = RecallRecord {
+        library: library.into(),
+        query: query.into(),
+        results,
+        latency_ms: latency.as_secs_f64() * 1000.0,
+        at: Utc::now(),
+    };
+
+    let mut log = RECALL_LOG.lock();
+    if log.len() == RECALL_LOG_CAPACITY {
+        log.pop_front();
+    }
+    log.push_back(record);
+}
+
+/// Most recent recalls, newest first
+pub fn recent_recalls(limit: usize) -> Vec<RecallRecord> {
+    RECALL_LOG.lock().iter().rev().take(limit).cloned().collect()
+}
@@ -1,7 +1,7 @@
 //! Database schema for memory nodes
 
 use serde::{Deserialize, Serialize};
-use surrealdb::types::{Datetime, RecordId, SurrealValue};
+use surrealdb_types::{Datetime, RecordId, SurrealValue};
 use uuid::Uuid;
 
 use crate::memory::core::primitives::types::MemoryTypeEnum;
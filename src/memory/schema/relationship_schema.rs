@@ -5,7 +5,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use surrealdb::types::{RecordId, SurrealValue};
+use surrealdb_types::{RecordId, SurrealValue};
 
 use crate::memory::utils;
 
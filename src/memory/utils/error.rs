@@ -162,6 +162,7 @@ impl axum::response::IntoResponse for Error {
     }
 }
 
+#[cfg(feature = "memory")]
 impl From<surrealdb::Error> for Error {
     fn from(err: surrealdb::Error) -> Self {
         Error::Database(err.to_string())
@@ -4,7 +4,7 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use kodegen_simd::cosine_similarity;
-use surrealdb::types::Value;
+use surrealdb_types::Value;
 
 use super::vector_store::{VectorMetadata, VectorSearchResult, VectorStore};
 use crate::memory::constants::ERROR_VECTOR_NOT_FOUND;
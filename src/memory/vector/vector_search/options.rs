@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
-use surrealdb::types::Value;
+use surrealdb_types::Value;
 
 use super::types::RequestInfoCallback;
 use crate::memory::utils::error::Result;
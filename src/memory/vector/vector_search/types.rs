@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
-use surrealdb::types::Value;
+use surrealdb_types::Value;
 
 use crate::memory::utils::error::Result;
 
@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use surrealdb::types::Value;
+use surrealdb_types::Value;
 
 use crate::memory::utils::error::Result;
 
@@ -0,0 +1,362 @@
+//! Crash reports for panics in long-running deployments
+//!
+//! [`install`] adds a panic hook that writes a [`CrashDump`] to
+//! `$KODEGEN_CRASH_DIR` (default: `crashes/` under the kodegen data
+//! directory) before the previous hook runs. The dump records the panic with
+//! a backtrace and what the process was doing: chat sessions and generations
+//! in flight, the models loaded in the worker pools and the most recent log
+//! lines. When `KODEGEN_CRASH_WEBHOOK` is set, the dump is also POSTed there
+//! as JSON.
+//!
+//! Sessions and generations register themselves with [`track_session`] and
+//! [`track_generation`] for as long as they run. Recent log lines come from
+//! [`RecentLogLayer`] and from errors passed to [`report_error`]. [`install`]
+//! sets up a global subscriber with the layer that also picks up `log`
+//! records, and prints to stderr when `RUST_LOG` is set; a host that installs
+//! its own subscriber first should add the layer to it.
+//!
+//! The server entry points (`main`, [`crate::run_stdio_server`],
+//! [`crate::start_server`], the CLI runner and the memory API server) all
+//! call [`install`], and report the error that ends them with
+//! [`report_error`].
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::{self, Write as _};
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Once};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, fmt as tracing_fmt};
+
+use super::request_id::current_request_id;
+use super::supervisor::panic_message;
+use crate::capability::registry::pool::{ModelResidency, all_model_status};
+
+/// Directory crash dumps are written to
+pub const CRASH_DIR_ENV: &str = "KODEGEN_CRASH_DIR";
+
+/// URL crash dumps are POSTed to, if set
+pub const CRASH_WEBHOOK_ENV: &str = "KODEGEN_CRASH_WEBHOOK";
+
+/// Log lines kept for the next crash dump
+pub const RECENT_LOG_LINES: usize = 200;
+
+/// How long a panicking thread waits for the webhook
+///
+/// Short, so an unreachable endpoint barely delays the crash.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(1);
+
+static INSTALL: Once = Once::new();
+/// Set once a [`RecentLogLayer`] is part of a subscriber
+static LAYER_ACTIVE: AtomicBool = AtomicBool::new(false);
+static IN_FLIGHT: LazyLock<Mutex<InFlightRegistry>> = LazyLock::new(Mutex::default);
+static RECENT_LOGS: LazyLock<Mutex<VecDeque<String>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::with_capacity(RECENT_LOG_LINES)));
+
+/// What an in-flight entry stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InFlightKind {
+    Session,
+    Generation,
+}
+
+#[derive(Debug, Default)]
+struct InFlightRegistry {
+    next_key: u64,
+    entries: BTreeMap<u64, (InFlightKind, String)>,
+}
+
+/// Registration of a running session or generation, removed on drop
+#[derive(Debug)]
+#[must_use = "the registration ends when the guard is dropped"]
+pub struct InFlight {
+    key: u64,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        IN_FLIGHT.lock().entries.remove(&self.key);
+    }
+}
+
+fn track(kind: InFlightKind, id: String) -> InFlight {
+    let mut registry = IN_FLIGHT.lock();
+    let key = registry.next_key;
+    registry.next_key += 1;
+    registry.entries.insert(key, (kind, id));
+    InFlight { key }
+}
+
+/// Record chat session `id` as active until the guard is dropped
+pub fn track_session(id: impl Into<String>) -> InFlight {
+    track(InFlightKind::Session, id.into())
+}
+
+/// Record generation `id` (a chat turn) as in flight until the guard is dropped
+pub fn track_generation(id: impl Into<String>) -> InFlight {
+    track(InFlightKind::Generation, id.into())
+}
+
+/// Keep `line` for the next crash dump, dropping the oldest past
+/// [`RECENT_LOG_LINES`]
+pub fn record_log(line: impl Into<String>) {
+    let mut logs = RECENT_LOGS.lock();
+    if logs.len() == RECENT_LOG_LINES {
+        logs.pop_front();
+    }
+    logs.push_back(line.into());
+}
+
+/// Log an error that did not crash the process and keep it for crash dumps
+pub fn report_error(context: &str, error: &dyn fmt::Display) {
+    log::error!("{}: {}", context, error);
+    // With the layer in a subscriber, the log record above is already kept
+    if LAYER_ACTIVE.load(Ordering::Relaxed) {
+        return;
+    }
+    record_log(format!(
+        "{} ERROR {}: {}",
+        chrono::Utc::now().to_rfc3339(),
+        context,
+        error
+    ));
+}
+
+/// Tracing layer that keeps recent events for crash dumps
+///
+/// [`install`] adds it to the global subscriber. Hosts with their own
+/// subscriber add it there, e.g.
+/// `tracing_subscriber::registry().with(RecentLogLayer).with(fmt::layer())`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecentLogLayer;
+
+impl<S: tracing::Subscriber> Layer<S> for RecentLogLayer {
+    fn on_layer(&mut self, _subscriber: &mut S) {
+        LAYER_ACTIVE.store(true, Ordering::Relaxed);
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = format!(
+            "{} {} {}:",
+            chrono::Utc::now().to_rfc3339(),
+            metadata.level(),
+            metadata.target()
+        );
+        event.record(&mut LineVisitor(&mut line));
+        record_log(line);
+    }
+}
+
+/// Appends event fields to a log line, the message first
+struct LineVisitor<'a>(&'a mut String);
+
+impl Visit for LineVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, " {value:?}");
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
+
+/// State of the process when it panicked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashDump {
+    /// When the panic happened (RFC 3339)
+    pub crashed_at: String,
+    /// Crate version
+    pub version: String,
+    pub pid: u32,
+    /// Name of the panicking thread
+    pub thread: Option<String>,
+    /// Panic message
+    pub message: String,
+    /// `file:line:column` of the panic
+    pub location: Option<String>,
+    /// Request being served by the panicking task
+    pub request_id: Option<String>,
+    pub backtrace: String,
+    /// Chat sessions with a registration in flight
+    pub active_sessions: Vec<String>,
+    /// Generations (chat turns) in flight
+    pub in_flight_generations: Vec<String>,
+    /// Registry keys of models loaded in the worker pools
+    pub loaded_models: Vec<String>,
+    /// Most recent log lines, oldest first
+    pub recent_logs: Vec<String>,
+}
+
+impl CrashDump {
+    /// Capture the current process state for a panic with `message`
+    ///
+    /// Registries locked by the panicking thread are reported empty rather
+    /// than waited on.
+    pub fn capture(message: impl Into<String>, location: Option<String>) -> Self {
+        let mut active_sessions = BTreeSet::new();
+        let mut in_flight_generations = BTreeSet::new();
+        if let Some(registry) = IN_FLIGHT.try_lock() {
+            for (kind, id) in registry.entries.values() {
+                match kind {
+                    InFlightKind::Session => active_sessions.insert(id.clone()),
+                    InFlightKind::Generation => in_flight_generations.insert(id.clone()),
+                };
+            }
+        }
+        let recent_logs = RECENT_LOGS
+            .try_lock()
+            .map(|logs| logs.iter().cloned().collect())
+            .unwrap_or_default();
+        let loaded_models = all_model_status()
+            .into_iter()
+            .filter(|model| model.state == ModelResidency::Loaded)
+            .map(|model| model.registry_key)
+            .collect();
+
+        Self {
+            crashed_at: chrono::Utc::now().to_rfc3339(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            pid: std::process::id(),
+            thread: std::thread::current().name().map(str::to_string),
+            message: message.into(),
+            location,
+            request_id: current_request_id().map(|id| id.to_string()),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            active_sessions: active_sessions.into_iter().collect(),
+            in_flight_generations: in_flight_generations.into_iter().collect(),
+            loaded_models,
+            recent_logs,
+        }
+    }
+
+    /// Write the dump as JSON into `dir`, returning the file's path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created or written.
+    pub fn write_to(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "crash-{}-{}.json",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+            self.pid
+        ));
+        let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+}
+
+/// Directory crash dumps are written to, from [`CRASH_DIR_ENV`]
+pub fn crash_dir() -> PathBuf {
+    match std::env::var_os(CRASH_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => kodegen_config::KodegenConfig::data_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join("crashes"),
+    }
+}
+
+/// Install the crash-reporting panic hook and the recent-log subscriber
+///
+/// The previous hook still runs afterwards, so panics are printed as
+/// before. Calling this more than once has no further effect.
+pub fn install() {
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            report_panic(info);
+            previous(info);
+        }));
+        install_subscriber();
+    });
+}
+
+/// Make [`RecentLogLayer`] part of the global subscriber
+///
+/// Leaves a subscriber or logger the host already installed in place.
+fn install_subscriber() {
+    // Printing is left to the host unless RUST_LOG asks for it
+    let printer = EnvFilter::try_from_default_env().ok().map(|filter| {
+        tracing_fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(filter)
+    });
+    let installed = tracing_subscriber::registry()
+        .with(RecentLogLayer.with_filter(tracing::level_filters::LevelFilter::INFO))
+        .with(printer)
+        .try_init();
+    if let Err(e) = installed {
+        LAYER_ACTIVE.store(false, Ordering::Relaxed);
+        eprintln!("Crash reports will only include reported errors, not recent logs: {e}");
+    }
+}
+
+fn report_panic(info: &PanicHookInfo<'_>) {
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+    let dump = CrashDump::capture(panic_message(info.payload()), location);
+
+    // The logger may be what panicked, so report on stderr
+    match dump.write_to(&crash_dir()) {
+        Ok(path) => eprintln!("Crash dump written to {}", path.display()),
+        Err(e) => eprintln!("Failed to write crash dump: {e}"),
+    }
+
+    if let Ok(url) = std::env::var(CRASH_WEBHOOK_ENV)
+        && !url.trim().is_empty()
+    {
+        notify_webhook(url, dump);
+    }
+}
+
+/// POST `dump` to `url`, waiting at most [`WEBHOOK_TIMEOUT`]
+///
+/// Runs on its own thread and runtime, since the panicking thread may be a
+/// runtime worker that can no longer make progress.
+fn notify_webhook(url: String, dump: CrashDump) {
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name("crash-webhook".to_string())
+        .spawn(move || {
+            let result = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| e.to_string())
+                .and_then(|runtime| {
+                    runtime
+                        .block_on(async {
+                            reqwest::Client::builder()
+                                .timeout(WEBHOOK_TIMEOUT)
+                                .build()?
+                                .post(&url)
+                                .json(&dump)
+                                .send()
+                                .await?
+                                .error_for_status()
+                                .map(drop)
+                        })
+                        .map_err(|e| e.to_string())
+                });
+            let _ = done_tx.send(result);
+        });
+    if let Err(e) = spawned {
+        eprintln!("Failed to notify crash webhook: {e}");
+        return;
+    }
+
+    match done_rx.recv_timeout(WEBHOOK_TIMEOUT) {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("Failed to notify crash webhook: {e}"),
+        Err(_) => eprintln!("Crash webhook did not answer within {WEBHOOK_TIMEOUT:?}"),
+    }
+}
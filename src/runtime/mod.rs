@@ -1,10 +1,20 @@
-//! DEPRECATED: Shared Tokio runtime module
+//! Runtime helpers
 //!
-//! This module is no longer needed since the application uses `#[tokio::main]`
-//! which provides a runtime from the start. All code should use `tokio::spawn()`
-//! directly instead of creating a separate runtime.
-//!
-//! This file is kept for backward compatibility but will be removed in a future version.
+//! Hosts the [`TaskSupervisor`] that owns background tasks, the request ID
+//! scope used to correlate logs across components and the [`crash`] reporter
+//! that dumps process state when something panics. The legacy shared
+//! runtime accessor is kept for backward compatibility: the application uses
+//! `#[tokio::main]`, so no separate runtime is ever created.
+
+pub mod crash;
+pub mod request_id;
+pub mod supervisor;
+
+pub use request_id::{
+    RequestId, annotate_query, current_request_id, ensure_request_id, spawn_with_request_id,
+    with_optional_request_id, with_request_id,
+};
+pub use supervisor::{TaskSupervisor, supervisor};
 
 #[deprecated(
     since = "0.1.0",
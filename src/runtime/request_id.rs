@@ -0,0 +1,120 @@
+//! Request IDs for correlating work across components
+//!
+//! The HTTP edge assigns each request an ID (or adopts the client's
+//! `x-request-id`) and runs the handler inside [`with_request_id`]. Anything
+//! awaited under that scope, including tool execution and database queries,
+//! can read it back with [`current_request_id`]. [`TaskSupervisor::spawn`]
+//! carries the ID into background tasks, so a memorize session logs under the
+//! request that started it; streams built with [`spawn_stream`] and chat
+//! WebSocket turns keep it the same way.
+//!
+//! MCP tool calls do not pass through that middleware; tools run under
+//! [`ensure_request_id`] instead. The memory manager spawns its queries with
+//! [`spawn_with_request_id`] and prefixes them with [`annotate_query`], and
+//! embedding pool requests carry the ID to their worker, so the database's
+//! slow-query log and the worker logs can be matched to one tool call.
+//!
+//! [`TaskSupervisor::spawn`]: super::TaskSupervisor::spawn
+//! [`spawn_stream`]: crate::async_stream::spawn_stream
+
+use std::fmt;
+use std::future::Future;
+
+/// Header carrying the request ID in requests and responses
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied request ID that is adopted as-is
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Start of the SurrealQL comment added by [`annotate_query`]
+pub const QUERY_COMMENT_PREFIX: &str = "-- request_id: ";
+
+tokio::task_local! {
+    static REQUEST_ID: RequestId;
+}
+
+/// Identifier of one inbound request
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// Generate a fresh ID
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+
+    /// Adopt a client-supplied ID if it is short and printable, else generate one
+    pub fn from_header(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(id)
+                if !id.is_empty()
+                    && id.len() <= MAX_REQUEST_ID_LEN
+                    && id.chars().all(|c| c.is_ascii_graphic()) =>
+            {
+                Self(id.to_string())
+            }
+            _ => Self::new(),
+        }
+    }
+
+    /// The ID as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for RequestId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Run `future` with `id` as the current request ID
+pub async fn with_request_id<F: Future>(id: RequestId, future: F) -> F::Output {
+    REQUEST_ID.scope(id, future).await
+}
+
+/// Request ID of the current task, if it runs under [`with_request_id`]
+pub fn current_request_id() -> Option<RequestId> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Run `future` under `id`, or without a request ID when `id` is `None`
+pub async fn with_optional_request_id<F: Future>(id: Option<RequestId>, future: F) -> F::Output {
+    match id {
+        Some(id) => with_request_id(id, future).await,
+        None => future.await,
+    }
+}
+
+/// Run `future` under the current request ID, or a fresh one outside any request
+pub async fn ensure_request_id<F: Future>(future: F) -> F::Output {
+    with_request_id(current_request_id().unwrap_or_default(), future).await
+}
+
+/// `tokio::spawn` that carries the current request ID into the new task
+pub fn spawn_with_request_id<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(with_optional_request_id(current_request_id(), future))
+}
+
+/// Prefix `query` with a SurrealQL comment naming the current request ID
+///
+/// Returns the query unchanged outside a request. IDs are printable ASCII
+/// without whitespace, so the line comment cannot swallow the query.
+pub fn annotate_query(query: impl AsRef<str>) -> String {
+    let query = query.as_ref();
+    match current_request_id() {
+        Some(id) => format!("{QUERY_COMMENT_PREFIX}{id}\n{query}"),
+        None => query.to_string(),
+    }
+}
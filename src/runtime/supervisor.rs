@@ -0,0 +1,172 @@
+//! Structured concurrency for background tasks
+//!
+//! Background work (memorize sessions, conversation memory writes, cleanup
+//! loops) used to be spawned fire-and-forget with `tokio::spawn`: panics
+//! vanished and shutdown had no way to wait for in-flight writes.
+//!
+//! [`TaskSupervisor`] owns those tasks instead. Every task is tracked, panics
+//! are caught and logged with the task name, long-running loops observe a
+//! shared cancellation token, and [`TaskSupervisor::shutdown`] drains the set.
+
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use futures::FutureExt;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::Instrument;
+
+use super::request_id::{current_request_id, with_request_id};
+
+/// Process-wide supervisor used by library code
+static GLOBAL_SUPERVISOR: LazyLock<TaskSupervisor> = LazyLock::new(TaskSupervisor::new);
+
+/// Get the process-wide task supervisor
+#[inline]
+pub fn supervisor() -> &'static TaskSupervisor {
+    &GLOBAL_SUPERVISOR
+}
+
+/// Owner of spawned background tasks
+#[derive(Debug, Clone)]
+pub struct TaskSupervisor {
+    tracker: TaskTracker,
+    cancel: CancellationToken,
+    panics: std::sync::Arc<AtomicU64>,
+}
+
+impl Default for TaskSupervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskSupervisor {
+    /// Create an empty supervisor
+    pub fn new() -> Self {
+        Self {
+            tracker: TaskTracker::new(),
+            cancel: CancellationToken::new(),
+            panics: std::sync::Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Spawn a tracked task
+    ///
+    /// A panic inside `future` is caught and logged with `name` rather than
+    /// silently dropped with the `JoinHandle`. The spawning task's tracing span
+    /// and request ID carry over to the new task.
+    pub fn spawn<F>(&self, name: impl Into<String>, future: F) -> JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        let panics = self.panics.clone();
+        let request_id = current_request_id();
+
+        self.tracker.spawn(
+            async move {
+                let guarded = AssertUnwindSafe(future).catch_unwind();
+                let result = match request_id {
+                    Some(id) => with_request_id(id, guarded).await,
+                    None => guarded.await,
+                };
+                if let Err(payload) = result {
+                    panics.fetch_add(1, Ordering::Relaxed);
+                    log::error!(
+                        "Background task '{}' panicked: {}",
+                        name,
+                        panic_message(payload.as_ref())
+                    );
+                }
+            }
+            .instrument(tracing::Span::current()),
+        )
+    }
+
+    /// Spawn a tracked task that is dropped as soon as shutdown begins
+    ///
+    /// Intended for open-ended loops (cleanup, maintenance) that have no
+    /// natural end and must not hold up [`Self::shutdown`].
+    pub fn spawn_cancellable<F>(&self, name: impl Into<String>, future: F) -> JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        let cancel = self.cancel.clone();
+        let label = name.clone();
+
+        self.spawn(name, async move {
+            tokio::select! {
+                () = cancel.cancelled() => {
+                    log::debug!("Background task '{}' cancelled for shutdown", label);
+                }
+                () = future => {}
+            }
+        })
+    }
+
+    /// Token cancelled when shutdown begins
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// Number of tasks still running
+    pub fn active_tasks(&self) -> usize {
+        self.tracker.len()
+    }
+
+    /// Number of tasks that ended in a panic
+    pub fn panic_count(&self) -> u64 {
+        self.panics.load(Ordering::Relaxed)
+    }
+
+    /// Whether shutdown has begun
+    pub fn is_shutting_down(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Cancel loops and wait for remaining tasks to finish
+    ///
+    /// Returns `true` if every task finished within `timeout`. Tasks spawned
+    /// after shutdown begins are still tracked and awaited.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        log::info!(
+            "Shutting down task supervisor ({} active tasks)",
+            self.tracker.len()
+        );
+
+        self.cancel.cancel();
+        self.tracker.close();
+
+        match tokio::time::timeout(timeout, self.tracker.wait()).await {
+            Ok(()) => {
+                log::info!("All background tasks finished");
+                true
+            }
+            Err(_) => {
+                log::warn!(
+                    "Timed out after {:?} waiting for {} background tasks",
+                    timeout,
+                    self.tracker.len()
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Extract a readable message from a panic payload
+pub(super) fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
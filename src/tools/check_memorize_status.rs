@@ -37,27 +37,42 @@ impl Tool for CheckMemorizeStatusTool {
         "Check the status of an async memorize operation started with memorize().\n\n\
          Returns current status, progress information, and memory_id when complete.\n\n\
          Status values:\n\
-         - IN_PROGRESS: Task is still running (loading content, generating embeddings, storing)\n\
+         - IN_PROGRESS: Task is queued or running (queued, loading content, generating embeddings, storing)\n\
          - COMPLETED: Task finished successfully (memory_id available)\n\
          - FAILED: Task failed (error message available)\n\n\
          Poll this repeatedly (with delays) until status is COMPLETED or FAILED.\n\
-         Progress includes current stage (Loading content, Generating embeddings, Storing in database)\n\
-         and file counts for multi-file operations."
+         Progress includes current stage (Queued (position N), Loading content, Generating embeddings,\n\
+         Storing in database)\n\
+         and file counts for multi-file operations. Files or URLs that failed to load while the\n\
+         rest was memorized are listed under Warnings."
     }
 
     fn read_only() -> bool {
         true
     }
 
-    async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl CheckMemorizeStatusTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: CheckMemorizeStatusArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<CheckMemorizeStatusOutput>, McpError> {
         let response = self
             .manager
             .get_status(&args.session_id)
             .await
             .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to get status: {}", e)))?;
 
+        // A queued session reports its place in line as its stage
+        let stage = match response.queue_position {
+            Some(position) => format!("Queued (position {position})"),
+            None => response.progress.stage.clone(),
+        };
+
         // Terminal summary based on status
-        let summary = match response.status {
+        let mut summary = match response.status {
             MemorizeStatus::InProgress => {
                 format!(
                     "⏳ Memorization in progress\n\n\
@@ -68,7 +83,7 @@ impl Tool for CheckMemorizeStatusTool {
                      Runtime: {:.1}s",
                     response.session_id,
                     response.library,
-                    response.progress.stage,
+                    stage,
                     response.progress.files_loaded,
                     response.runtime_ms as f64 / 1000.0
                 )
@@ -101,6 +116,17 @@ impl Tool for CheckMemorizeStatusTool {
             },
         };
 
+        // Partial loads name what was left out
+        if !response.warnings.is_empty() {
+            summary.push_str(&format!(
+                "\n\nWarnings ({} source(s) failed to load):",
+                response.warnings.len()
+            ));
+            for warning in &response.warnings {
+                summary.push_str(&format!("\n- {warning}"));
+            }
+        }
+
         // Convert internal status to string
         let status_str = match response.status {
             MemorizeStatus::InProgress => "IN_PROGRESS",
@@ -114,7 +140,7 @@ impl Tool for CheckMemorizeStatusTool {
             memory_id: response.memory_id,
             library: response.library,
             progress: MemorizeProgress {
-                stage: response.progress.stage,
+                stage,
                 files_loaded: response.progress.files_loaded,
                 total_size_bytes: response.progress.total_size_bytes,
             },
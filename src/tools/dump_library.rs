@@ -0,0 +1,105 @@
+//! Dump Library Tool - Stream a whole library as NDJSON chunks
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{DumpChunk, DumpLibraryArgs, DumpLibraryPrompts, MEMORY_DUMP_LIBRARY};
+use std::sync::Arc;
+
+use super::dump_manager::{DEFAULT_DUMP_CHUNK_BYTES, DumpSessionManager, DumpStatus, MAX_DUMP_CHUNK_BYTES};
+
+#[derive(Clone)]
+pub struct DumpLibraryTool {
+    manager: Arc<DumpSessionManager>,
+}
+
+impl DumpLibraryTool {
+    pub fn new(manager: Arc<DumpSessionManager>) -> Self {
+        Self { manager }
+    }
+}
+
+impl Tool for DumpLibraryTool {
+    type Args = DumpLibraryArgs;
+    type Prompts = DumpLibraryPrompts;
+
+    fn name() -> &'static str {
+        MEMORY_DUMP_LIBRARY
+    }
+
+    fn description() -> &'static str {
+        "Dump an entire memory library as NDJSON (one JSON object per line: all memories, then \
+         all relationships, each tagged with \"kind\"). Call with library to start; the response \
+         carries the first chunk and a dump_id. Call again with dump_id and cursor = next_cursor \
+         until done is true, concatenating the ndjson fields. Chunks may be empty while the dump \
+         is still IN_PROGRESS; the dump waits for the reader, and requesting a cursor releases \
+         the lines before it, so only the latest chunk can be read again. Embeddings are \
+         omitted unless include_embeddings is true. Unlike \
+         the file-based export, nothing is written to the server's filesystem."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl DumpLibraryTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: DumpLibraryArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<DumpChunk>, McpError> {
+        let max_bytes = args
+            .max_bytes
+            .unwrap_or(DEFAULT_DUMP_CHUNK_BYTES)
+            .clamp(1, MAX_DUMP_CHUNK_BYTES);
+
+        let (dump_id, cursor) = match (args.dump_id, args.library) {
+            (Some(dump_id), _) => (dump_id, args.cursor),
+            (None, Some(library)) => (
+                self.manager.start_dump(library, args.include_embeddings).await,
+                0,
+            ),
+            (None, None) => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Either library (to start a dump) or dump_id (to continue one) is required"
+                )));
+            }
+        };
+
+        let chunk = self
+            .manager
+            .read_chunk(&dump_id, cursor, max_bytes)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to read dump: {}", e)))?;
+
+        let summary = match chunk.status {
+            DumpStatus::Failed => format!(
+                "✗ Dump failed\n\n\
+                 Dump: {}\n\
+                 Library: {}\n\
+                 Error: {}",
+                chunk.dump_id,
+                chunk.library,
+                chunk.error.as_deref().unwrap_or("Unknown error")
+            ),
+            _ => format!(
+                "{} Dump {}\n\n\
+                 Dump: {}\n\
+                 Library: {}\n\
+                 Lines: {} (next cursor {})\n\
+                 Memories: {}\n\
+                 Relationships: {}",
+                if chunk.done { "✓" } else { "⏳" },
+                if chunk.done { "complete" } else { "in progress" },
+                chunk.dump_id,
+                chunk.library,
+                chunk.lines,
+                chunk.next_cursor,
+                chunk.memories,
+                chunk.relationships
+            ),
+        };
+
+        Ok(ToolResponse::new(summary, chunk))
+    }
+}
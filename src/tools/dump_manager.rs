@@ -0,0 +1,450 @@
+//! Dump Session Manager - Async session pattern for streaming library dumps
+//!
+//! Same lifecycle as memorize sessions:
+//! 1. Client calls dump_library(library) → spawns background task → returns dump_id
+//! 2. Background task pages through the library, writing one NDJSON line per
+//!    memory and then one per relationship. It stays at most
+//!    `DUMP_BUFFER_BYTES` ahead of the reader, so a dump never holds the
+//!    whole library
+//! 3. Client calls dump_library(dump_id, cursor) to read the next chunk of
+//!    lines until `done`. Requesting a cursor releases the lines before it:
+//!    a chunk can be read again until the next one is requested
+//! 4. Cleanup task removes old sessions (60s interval), stopping dumps whose
+//!    reader went away
+//!
+//! Each line is a JSON object tagged with `"kind"` (`"memory"` or
+//! `"relationship"`), so the concatenated chunks can be piped straight into
+//! `jq` or imported elsewhere without access to the server's filesystem.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Notify, RwLock};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::memory::core::manager::coordinator::MemoryCoordinator;
+use crate::memory::core::manager::pool::CoordinatorPool;
+use crate::memory::core::manager::surreal::trait_def::MemoryManager;
+
+// ============================================================================
+// CONFIGURATION CONSTANTS
+// ============================================================================
+
+/// Memories fetched per database page
+const DUMP_PAGE_SIZE: usize = 500;
+
+/// Default chunk size returned per read, in bytes
+pub const DEFAULT_DUMP_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Largest chunk size a client may request, in bytes
+pub const MAX_DUMP_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Unread lines a dump buffers before waiting for its reader, in bytes
+const DUMP_BUFFER_BYTES: usize = 2 * MAX_DUMP_CHUNK_BYTES;
+
+/// Cleanup interval in seconds
+const CLEANUP_INTERVAL_SECS: u64 = 60;
+
+/// Session retention time in seconds since the last read; unfinished dumps
+/// are stopped
+const SESSION_RETENTION_SECS: u64 = 300;
+
+fn unix_timestamp_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// NDJSON RECORDS
+// ============================================================================
+
+/// Kind of record on a dump line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpRecordKind {
+    Memory,
+    Relationship,
+}
+
+impl DumpRecordKind {
+    /// Value of the `"kind"` field
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Memory => "memory",
+            Self::Relationship => "relationship",
+        }
+    }
+}
+
+/// One NDJSON line (without the trailing newline) for a serialized record
+///
+/// Object records get a `"kind"` field; anything else is wrapped as
+/// `{"kind": ..., "value": ...}`.
+pub fn ndjson_line(kind: DumpRecordKind, record: serde_json::Value) -> String {
+    let object = match record {
+        serde_json::Value::Object(mut fields) => {
+            fields.insert("kind".to_string(), kind.as_str().into());
+            fields
+        }
+        value => {
+            let mut fields = serde_json::Map::new();
+            fields.insert("kind".to_string(), kind.as_str().into());
+            fields.insert("value".to_string(), value);
+            fields
+        }
+    };
+    serde_json::Value::Object(object).to_string()
+}
+
+/// Join lines from `cursor` into one NDJSON chunk of at most `max_bytes`
+///
+/// At least one line is returned when any remain, even if it alone exceeds
+/// `max_bytes`. Returns the chunk and the cursor of the first line not taken.
+pub fn take_lines(lines: &[String], cursor: usize, max_bytes: usize) -> (String, usize) {
+    let mut chunk = String::new();
+    let mut next = cursor.min(lines.len());
+    for line in &lines[next..] {
+        if !chunk.is_empty() && chunk.len() + line.len() + 1 > max_bytes {
+            break;
+        }
+        chunk.push_str(line);
+        chunk.push('\n');
+        next += 1;
+    }
+    (chunk, next)
+}
+
+// ============================================================================
+// SESSION TYPES
+// ============================================================================
+
+pub use kodegen_mcp_schema::memory::{DumpChunk, DumpStatus};
+
+/// Lines written but not yet released by the reader
+#[derive(Default)]
+struct DumpBuffer {
+    /// Lines from cursor `start` on
+    lines: VecDeque<String>,
+    /// Cursor of the first buffered line
+    start: usize,
+    /// Bytes of the buffered lines, newlines included
+    bytes: usize,
+}
+
+impl DumpBuffer {
+    /// Drop the lines before `cursor`
+    fn release(&mut self, cursor: usize) {
+        while self.start < cursor {
+            let Some(line) = self.lines.pop_front() else {
+                break;
+            };
+            self.bytes -= line.len() + 1;
+            self.start += 1;
+        }
+    }
+}
+
+/// Active dump session
+pub struct DumpSession {
+    /// Unique dump ID (UUID v4)
+    pub id: String,
+    /// Library being dumped
+    pub library: String,
+    /// Whether memory lines keep their embedding vectors
+    pub include_embeddings: bool,
+    /// Current status
+    pub status: Arc<RwLock<DumpStatus>>,
+    /// NDJSON lines not yet released by the reader
+    buffer: Mutex<DumpBuffer>,
+    /// Signalled when the reader releases lines
+    released: Notify,
+    /// Set when the reader went away; the dump stops
+    abandoned: AtomicBool,
+    /// Memories written so far
+    pub memories: Arc<AtomicU64>,
+    /// Relationships written so far
+    pub relationships: Arc<AtomicU64>,
+    /// Error message (when failed)
+    pub error: Arc<RwLock<Option<String>>>,
+    /// Session start time
+    pub start_time: Instant,
+    /// Last read time (for cleanup)
+    pub last_read_time: Arc<AtomicU64>,
+}
+
+impl DumpSession {
+    /// Create new session
+    pub fn new(id: String, library: String, include_embeddings: bool) -> Self {
+        Self {
+            id,
+            library,
+            include_embeddings,
+            status: Arc::new(RwLock::new(DumpStatus::InProgress)),
+            buffer: Mutex::new(DumpBuffer::default()),
+            released: Notify::new(),
+            abandoned: AtomicBool::new(false),
+            memories: Arc::new(AtomicU64::new(0)),
+            relationships: Arc::new(AtomicU64::new(0)),
+            error: Arc::new(RwLock::new(None)),
+            start_time: Instant::now(),
+            last_read_time: Arc::new(AtomicU64::new(unix_timestamp_now())),
+        }
+    }
+
+    /// Append lines to the dump, first waiting for the reader to release
+    /// enough of the buffer
+    ///
+    /// # Errors
+    ///
+    /// Fails once the dump has been abandoned by its reader.
+    async fn push(&self, kind: DumpRecordKind, lines: Vec<String>) -> anyhow::Result<()> {
+        let count = lines.len() as u64;
+        let bytes: usize = lines.iter().map(|line| line.len() + 1).sum();
+        loop {
+            {
+                let mut buffer = self.buffer.lock().await;
+                if buffer.bytes == 0 || buffer.bytes + bytes <= DUMP_BUFFER_BYTES {
+                    buffer.bytes += bytes;
+                    buffer.lines.extend(lines);
+                    break;
+                }
+            }
+            if self.abandoned.load(Ordering::Relaxed) {
+                anyhow::bail!("Dump {} was abandoned by its reader", self.id);
+            }
+            self.released.notified().await;
+        }
+        let counter = match kind {
+            DumpRecordKind::Memory => &self.memories,
+            DumpRecordKind::Relationship => &self.relationships,
+        };
+        counter.fetch_add(count, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Stop the dump: its reader went away
+    fn abandon(&self) {
+        self.abandoned.store(true, Ordering::Relaxed);
+        self.released.notify_one();
+    }
+
+    /// Update last read time (for cleanup tracking)
+    pub fn touch(&self) {
+        self.last_read_time.store(unix_timestamp_now(), Ordering::Relaxed);
+    }
+}
+
+// ============================================================================
+// SESSION MANAGER
+// ============================================================================
+
+/// Manager for dump sessions
+#[derive(Clone)]
+pub struct DumpSessionManager {
+    sessions: Arc<RwLock<HashMap<String, Arc<DumpSession>>>>,
+    pool: Arc<CoordinatorPool>,
+}
+
+impl DumpSessionManager {
+    /// Create new session manager
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self {
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            pool,
+        }
+    }
+
+    /// Start a dump of `library` (returns dump_id immediately)
+    pub async fn start_dump(&self, library: String, include_embeddings: bool) -> String {
+        let dump_id = Uuid::new_v4().to_string();
+        let session = Arc::new(DumpSession::new(dump_id.clone(), library, include_embeddings));
+
+        self.sessions
+            .write()
+            .await
+            .insert(dump_id.clone(), session.clone());
+
+        self.spawn_dump_task(session);
+        dump_id
+    }
+
+    /// Read the chunk starting at `cursor`, releasing the lines before it
+    ///
+    /// While the dump is in progress a chunk may be empty; `done` is set only
+    /// once the dump has finished and the cursor has reached the end.
+    ///
+    /// # Errors
+    ///
+    /// Fails for unknown dumps and for cursors before the last one requested,
+    /// whose lines have been released.
+    pub async fn read_chunk(
+        &self,
+        dump_id: &str,
+        cursor: usize,
+        max_bytes: usize,
+    ) -> anyhow::Result<DumpChunk> {
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(dump_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Dump not found: {}", dump_id))?;
+
+        session.touch();
+
+        // Read status before lines so a completed status implies all lines are visible
+        let status = session.status.read().await.clone();
+        let (ndjson, next_cursor, total) = {
+            let mut buffer = session.buffer.lock().await;
+            if cursor < buffer.start {
+                anyhow::bail!(
+                    "Cursor {} of dump {} was already released; continue from {}",
+                    cursor,
+                    dump_id,
+                    buffer.start
+                );
+            }
+            buffer.release(cursor);
+            let start = buffer.start;
+            let (ndjson, next) =
+                take_lines(buffer.lines.make_contiguous(), cursor - start, max_bytes);
+            (ndjson, start + next, start + buffer.lines.len())
+        };
+        session.released.notify_one();
+
+        Ok(DumpChunk {
+            dump_id: session.id.clone(),
+            library: session.library.clone(),
+            done: status != DumpStatus::InProgress && next_cursor >= total,
+            status,
+            lines: next_cursor - cursor.min(next_cursor),
+            ndjson,
+            next_cursor,
+            memories: session.memories.load(Ordering::Relaxed),
+            relationships: session.relationships.load(Ordering::Relaxed),
+            runtime_ms: session.start_time.elapsed().as_millis() as u64,
+            error: session.error.read().await.clone(),
+        })
+    }
+
+    /// Spawn background task that writes the dump
+    fn spawn_dump_task(&self, session: Arc<DumpSession>) {
+        let pool = self.pool.clone();
+        let task_name = format!("dump session {}", session.id);
+
+        crate::runtime::supervisor().spawn(task_name, async move {
+            log::info!(
+                "Dump task started for session {} (library: {})",
+                session.id,
+                session.library
+            );
+
+            let result = match pool.get_coordinator(&session.library).await {
+                Ok(coordinator) => Self::write_dump(&coordinator, &session).await,
+                Err(e) => Err(anyhow::anyhow!("Failed to get coordinator: {}", e)),
+            };
+
+            match result {
+                Ok(()) => {
+                    log::info!(
+                        "Dump task completed for session {}: {} memories, {} relationships",
+                        session.id,
+                        session.memories.load(Ordering::Relaxed),
+                        session.relationships.load(Ordering::Relaxed)
+                    );
+                    *session.status.write().await = DumpStatus::Completed;
+                }
+                Err(e) => {
+                    log::error!("Dump failed for session {}: {}", session.id, e);
+                    *session.error.write().await = Some(e.to_string());
+                    *session.status.write().await = DumpStatus::Failed;
+                }
+            }
+        });
+    }
+
+    /// Write every memory, then every relationship, as NDJSON lines
+    async fn write_dump(coordinator: &MemoryCoordinator, session: &DumpSession) -> anyhow::Result<()> {
+        let mut memory_ids = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let mut stream = coordinator.list_all_memories(DUMP_PAGE_SIZE, offset);
+            let mut lines = Vec::new();
+            while let Some(memory) = stream.next().await {
+                let mut memory = memory?;
+                if !session.include_embeddings {
+                    memory.embedding = None;
+                }
+                memory_ids.push(memory.id.clone());
+                lines.push(ndjson_line(DumpRecordKind::Memory, serde_json::to_value(&memory)?));
+            }
+
+            let fetched = lines.len();
+            session.push(DumpRecordKind::Memory, lines).await?;
+            if fetched < DUMP_PAGE_SIZE {
+                break;
+            }
+            offset += fetched;
+        }
+
+        // Relationships are listed per memory, so each appears once per endpoint
+        let mut seen = HashSet::new();
+        for memory_id in &memory_ids {
+            let mut lines = Vec::new();
+            for relationship in coordinator.get_relationships(memory_id).await? {
+                if seen.insert(relationship.id.clone()) {
+                    lines.push(ndjson_line(
+                        DumpRecordKind::Relationship,
+                        serde_json::to_value(&relationship)?,
+                    ));
+                }
+            }
+            if !lines.is_empty() {
+                session.push(DumpRecordKind::Relationship, lines).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cleanup sessions that have not been read recently, stopping their
+    /// dumps if unfinished
+    async fn cleanup_sessions(&self) {
+        let now = unix_timestamp_now();
+
+        let mut sessions = self.sessions.write().await;
+        let mut to_remove = Vec::new();
+
+        for (dump_id, session) in sessions.iter() {
+            let age_secs = now.saturating_sub(session.last_read_time.load(Ordering::Relaxed));
+            if age_secs >= SESSION_RETENTION_SECS {
+                to_remove.push(dump_id.clone());
+            }
+        }
+
+        for dump_id in to_remove {
+            log::debug!("Cleaning up dump session: {}", dump_id);
+            if let Some(session) = sessions.remove(&dump_id) {
+                session.abandon();
+            }
+        }
+    }
+
+    /// Start cleanup task (call after all tools registered)
+    ///
+    /// The loop is owned by the task supervisor and stops when shutdown begins.
+    pub fn start_cleanup_task(self: Arc<Self>) {
+        crate::runtime::supervisor().spawn_cancellable("dump session cleanup", async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CLEANUP_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                self.cleanup_sessions().await;
+            }
+        });
+    }
+}
@@ -0,0 +1,126 @@
+//! Embed Tool - Return raw embedding vectors from the server's embedding model
+//!
+//! Lets MCP clients build their own vector stores with the same model the
+//! memory libraries use, so their vectors are comparable with recall's. With
+//! `library` set, the model is the one that library is embedded with.
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::candle::{EmbedArgs, EmbedOutput, EmbedPrompts, CANDLE_EMBED};
+use std::sync::Arc;
+
+use crate::capability::traits::TextEmbeddingCapable;
+use crate::domain::embedding::matryoshka;
+use crate::domain::model::traits::CandleModel;
+use crate::memory::core::manager::pool::CoordinatorPool;
+
+/// Most texts embedded per call
+pub const MAX_EMBED_TEXTS: usize = 256;
+
+#[derive(Clone)]
+pub struct EmbedTool {
+    pool: Arc<CoordinatorPool>,
+}
+
+impl EmbedTool {
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl Tool for EmbedTool {
+    type Args = EmbedArgs;
+    type Prompts = EmbedPrompts;
+
+    fn name() -> &'static str {
+        CANDLE_EMBED
+    }
+
+    fn description() -> &'static str {
+        "Embed one or many texts with the server's embedding model, or with the model a memory \
+         library is embedded with when library is given, and return the raw vectors with their \
+         dimension. Takes an optional task \
+         (\"search_query\", \"search_document\", \"code:rust\", \"instruct:<instruction>\", ...) \
+         and an optional Matryoshka dimension. Use this to build your own vector store."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl EmbedTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: EmbedArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<EmbedOutput>, McpError> {
+        let texts = args.inputs();
+        if texts.is_empty() {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Invalid arguments: provide text or texts"
+            )));
+        }
+        if texts.len() > MAX_EMBED_TEXTS {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Invalid arguments: {} texts exceeds the limit of {} per call",
+                texts.len(),
+                MAX_EMBED_TEXTS
+            )));
+        }
+
+        // A library's vectors only compare with those of the model it records
+        let model = match &args.library {
+            Some(library) => self
+                .pool
+                .get_coordinator(library)
+                .await
+                .map_err(|e| {
+                    McpError::Other(anyhow::anyhow!(
+                        "Failed to open library '{}': {}",
+                        library,
+                        e
+                    ))
+                })?
+                .embedding_model()
+                .clone(),
+            None => self.pool.embedding_model().clone(),
+        };
+        if let Some(dimension) = args.dimension {
+            model
+                .validate_dimension_request(dimension)
+                .map_err(|e| McpError::Other(anyhow::anyhow!("{}", e)))?;
+        }
+
+        let mut embeddings = model
+            .batch_embed(&texts, args.task)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to embed texts: {}", e)))?;
+        if let Some(dimension) = args.dimension {
+            embeddings = embeddings
+                .iter()
+                .map(|embedding| matryoshka::truncate(embedding, dimension))
+                .collect();
+        }
+
+        let dimension = args.dimension.unwrap_or_else(|| model.embedding_dimension());
+        let count = embeddings.len();
+        let summary = format!(
+            "✓ Embedded {} text{} with {} ({} dimensions)",
+            count,
+            if count == 1 { "" } else { "s" },
+            model.name(),
+            dimension
+        );
+
+        Ok(ToolResponse::new(
+            summary,
+            EmbedOutput {
+                model: model.name().to_string(),
+                dimension,
+                embeddings,
+                count,
+            },
+        ))
+    }
+}
@@ -0,0 +1,246 @@
+//! Find Duplicates Tool - Report near-duplicate memories across libraries
+//!
+//! Embeddings are bucketed with random-hyperplane LSH (SimHash bands), so
+//! only memories sharing a bucket are compared. Pairs at or above the
+//! threshold are joined into merge groups (see
+//! [`crate::memory::core::ops::duplicates`]). Each library is grouped on its
+//! own, so a group never spans libraries.
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{
+    DuplicateGroup, DuplicateMember, FindDuplicatesArgs, FindDuplicatesOutput,
+    FindDuplicatesPrompts, MEMORY_FIND_DUPLICATES,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+use crate::memory::core::manager::pool::CoordinatorPool;
+use crate::memory::core::manager::surreal::trait_def::MemoryManager;
+
+pub use crate::memory::core::ops::duplicates::{
+    DuplicateScan, LSH_BANDS, LSH_ROWS, SimHashIndex, duplicate_groups,
+};
+
+/// Similarity at or above which two memories are duplicates
+pub const DEFAULT_DUPLICATE_THRESHOLD: f32 = 0.92;
+
+/// Groups returned when max_groups is not set
+pub const DEFAULT_MAX_GROUPS: usize = 100;
+
+/// Memories listed per page while scanning
+const SCAN_PAGE_SIZE: usize = 500;
+
+/// Characters of content shown per member
+const PREVIEW_CHARS: usize = 160;
+
+#[derive(Clone)]
+pub struct FindDuplicatesTool {
+    pool: Arc<CoordinatorPool>,
+}
+
+impl FindDuplicatesTool {
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self { pool }
+    }
+
+    /// Every memory with an embedding in `libraries`, with its embedding
+    async fn scan(&self, libraries: &[String]) -> Result<Vec<(DuplicateMember, Vec<f32>)>, McpError> {
+        let mut scanned = Vec::new();
+        for library in libraries {
+            let coordinator = self.pool.get_coordinator(library).await.map_err(|e| {
+                McpError::Other(anyhow::anyhow!("Failed to get coordinator for library '{}': {}", library, e))
+            })?;
+
+            let mut offset = 0;
+            loop {
+                let mut stream = coordinator.list_all_memories(SCAN_PAGE_SIZE, offset);
+                let mut fetched = 0;
+                while let Some(memory) = stream.next().await {
+                    let memory = memory.map_err(|e| {
+                        McpError::Other(anyhow::anyhow!("Failed to list library '{}': {}", library, e))
+                    })?;
+                    fetched += 1;
+                    let Some(embedding) = memory.embedding.or(memory.metadata.embedding) else {
+                        continue;
+                    };
+                    scanned.push((
+                        DuplicateMember {
+                            library: library.clone(),
+                            id: memory.id,
+                            preview: memory.content.text.chars().take(PREVIEW_CHARS).collect(),
+                            importance: memory.metadata.importance,
+                            tags: memory.metadata.tags,
+                        },
+                        embedding,
+                    ));
+                }
+                if fetched < SCAN_PAGE_SIZE {
+                    break;
+                }
+                offset += fetched;
+            }
+        }
+        Ok(scanned)
+    }
+
+    /// Keep the first member with every member's tags and delete the rest
+    ///
+    /// Returns the number of memories deleted.
+    async fn merge(&self, group: &DuplicateGroup) -> Result<usize, McpError> {
+        let Some((keep, rest)) = group.members.split_first() else {
+            return Ok(0);
+        };
+
+        let coordinator = self.pool.get_coordinator(&keep.library).await.map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to get coordinator for library '{}': {}", keep.library, e))
+        })?;
+        let mut node = coordinator
+            .get_memory(&keep.id)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to load memory '{}': {}", keep.id, e)))?
+            .ok_or_else(|| McpError::Other(anyhow::anyhow!("Memory '{}' no longer exists", keep.id)))?;
+
+        let mut tags: HashSet<String> = node.metadata.tags.iter().map(|t| t.to_string()).collect();
+        for tag in rest.iter().flat_map(|member| &member.tags) {
+            if tags.insert(tag.clone()) {
+                node.metadata.add_tag(tag.as_str());
+            }
+        }
+        coordinator
+            .update_memory(node)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to update memory '{}': {}", keep.id, e)))?;
+
+        for member in rest {
+            let coordinator = self.pool.get_coordinator(&member.library).await.map_err(|e| {
+                McpError::Other(anyhow::anyhow!("Failed to get coordinator for library '{}': {}", member.library, e))
+            })?;
+            coordinator.delete_memory(&member.id).await.map_err(|e| {
+                McpError::Other(anyhow::anyhow!("Failed to delete memory '{}': {}", member.id, e))
+            })?;
+        }
+        Ok(rest.len())
+    }
+}
+
+impl Tool for FindDuplicatesTool {
+    type Args = FindDuplicatesArgs;
+    type Prompts = FindDuplicatesPrompts;
+
+    fn name() -> &'static str {
+        MEMORY_FIND_DUPLICATES
+    }
+
+    fn description() -> &'static str {
+        "Find near-duplicate memories in one or more libraries by embedding similarity. \
+         Memories are bucketed with locality-sensitive hashing, so only likely matches are \
+         compared. Returns groups of duplicates (highest importance first) for review. With \
+         auto_merge, each group keeps its highest-importance memory, gains every member's \
+         tags, and the other members are deleted."
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl FindDuplicatesTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: FindDuplicatesArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<FindDuplicatesOutput>, McpError> {
+        let threshold = args.threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD);
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "threshold must be between 0.0 and 1.0, got {}",
+                threshold
+            )));
+        }
+
+        let libraries = if args.libraries.is_empty() {
+            self.pool
+                .list_libraries()
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to list libraries: {}", e)))?
+        } else {
+            args.libraries
+        };
+
+        let scanned = self.scan(&libraries).await?;
+
+        // Memories are only grouped with others of their own library
+        let mut found: Vec<(Vec<usize>, f32)> = Vec::new();
+        let mut comparisons = 0;
+        for library in &libraries {
+            let in_library: Vec<usize> = (0..scanned.len())
+                .filter(|&i| &scanned[i].0.library == library)
+                .collect();
+            let embeddings: Vec<&[f32]> =
+                in_library.iter().map(|&i| scanned[i].1.as_slice()).collect();
+            let scan = duplicate_groups(&embeddings, threshold);
+            comparisons += scan.comparisons;
+            found.extend(scan.groups.into_iter().map(|(indices, min_similarity)| {
+                (indices.into_iter().map(|i| in_library[i]).collect(), min_similarity)
+            }));
+        }
+        found.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+        let mut groups: Vec<DuplicateGroup> = found
+            .iter()
+            .take(args.max_groups.unwrap_or(DEFAULT_MAX_GROUPS))
+            .map(|(indices, min_similarity)| {
+                let mut members: Vec<DuplicateMember> =
+                    indices.iter().map(|&i| scanned[i].0.clone()).collect();
+                members.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+                DuplicateGroup {
+                    members,
+                    min_similarity: *min_similarity,
+                    merged: false,
+                }
+            })
+            .collect();
+
+        let mut removed = 0;
+        if args.auto_merge {
+            for group in &mut groups {
+                removed += self.merge(group).await?;
+                group.merged = true;
+            }
+        }
+
+        let summary = if groups.is_empty() {
+            format!(
+                "✓ No duplicates at similarity ≥ {:.2} among {} memories in {} libraries",
+                threshold,
+                scanned.len(),
+                libraries.len()
+            )
+        } else if args.auto_merge {
+            format!(
+                "✓ Merged {} duplicate groups, removed {} memories ({} scanned, {} comparisons)",
+                groups.len(),
+                removed,
+                scanned.len(),
+                comparisons
+            )
+        } else {
+            format!(
+                "✓ Found {} duplicate groups at similarity ≥ {:.2} ({} scanned, {} comparisons)",
+                groups.len(),
+                threshold,
+                scanned.len(),
+                comparisons
+            )
+        };
+
+        Ok(ToolResponse::new(
+            summary,
+            FindDuplicatesOutput {
+                libraries,
+                scanned: scanned.len(),
+                comparisons,
+                groups,
+                removed,
+            },
+        ))
+    }
+}
@@ -0,0 +1,467 @@
+//! History Index Manager - Chat search over stored conversations
+//!
+//! Chat sessions persist their turns in the chat memory database (`agent.db`,
+//! see [`chat_memory_coordinator`]) as memories with category `conversation`,
+//! a `message_type.*` role tag, and `session_id`/`turn_id` in custom metadata.
+//! That database is searched unless a library is named, for conversations
+//! imported into one. The index lives in memory: the chat history is indexed
+//! when the server starts ([`HistoryIndexManager::start_indexing`]) and later
+//! searches only index turns stored since, so the index follows the store
+//! without re-tokenizing every message.
+//!
+//! [`HistoryIndexManager::redact_message`] scrubs one message from its store,
+//! the index, and every memory derived from it. Derived memories point back
+//! at their source messages with [`SOURCE_MESSAGE_KEY`].
+
+use dashmap::DashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_stream::StreamExt;
+
+use crate::domain::chat::message::{CandleMessage, CandleMessageRole};
+use crate::domain::chat::message::CandleSearchChatMessage as SearchChatMessage;
+use crate::domain::chat::search::{ChatSearchIndex, ChatSearcher, SearchQuery};
+use crate::builders::agent_role::chat_memory_coordinator;
+use crate::domain::chat::session::{SESSION_ID_KEY, TURN_ID_KEY};
+use crate::memory::core::manager::coordinator::MemoryCoordinator;
+use crate::memory::core::manager::pool::CoordinatorPool;
+use crate::memory::core::manager::surreal::trait_def::MemoryManager;
+use crate::memory::core::primitives::node::MemoryNode;
+
+/// Category of memories holding chat messages
+pub const CONVERSATION_CATEGORY: &str = "conversation";
+
+/// Prefix of the tag carrying a stored message's role
+const ROLE_TAG_PREFIX: &str = "message_type.";
+
+/// Memories fetched per database page while indexing
+const HISTORY_PAGE_SIZE: usize = 500;
+
+/// Longest snippet returned per hit, in characters
+pub const DEFAULT_SNIPPET_CHARS: usize = 200;
+
+/// Custom metadata key on derived memories naming their source message
+///
+/// The value is a message memory ID or an array of them.
+pub const SOURCE_MESSAGE_KEY: &str = "source_message_id";
+
+/// Content left in place of a masked message
+pub const REDACTED_PLACEHOLDER: &str = "[redacted]";
+
+/// Memory tag on a masked message or derived memory
+pub const REDACTED_TAG: &str = "message.redacted";
+
+pub use kodegen_mcp_schema::memory::{HistoryHit, RedactionMode, RedactionReport};
+
+/// Whether `memory` names `message_id` under [`SOURCE_MESSAGE_KEY`]
+pub fn derived_from(memory: &MemoryNode, message_id: &str) -> bool {
+    match memory.metadata.custom.get(SOURCE_MESSAGE_KEY) {
+        Some(serde_json::Value::String(source)) => source == message_id,
+        Some(serde_json::Value::Array(sources)) => sources.iter().any(|s| s.as_str() == Some(message_id)),
+        _ => false,
+    }
+}
+
+/// Where an indexed message came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryOrigin {
+    /// Memory holding the message
+    pub memory_id: String,
+    /// Conversation session, if recorded
+    pub session_id: Option<String>,
+    /// Conversation turn, if recorded
+    pub turn_id: Option<String>,
+    /// Tags other than the role tag
+    pub tags: Vec<String>,
+}
+
+/// Search message for a stored conversation memory
+///
+/// Returns `None` for memories that are not chat messages. The message ID is
+/// `{session_id}/{memory_id}` so session filters match on it.
+pub fn history_message(memory: &MemoryNode) -> Option<(SearchChatMessage, HistoryOrigin)> {
+    if memory.metadata.category != CONVERSATION_CATEGORY {
+        return None;
+    }
+
+    let role = memory
+        .metadata
+        .tags
+        .iter()
+        .find_map(|tag| match tag.strip_prefix(ROLE_TAG_PREFIX)? {
+            "user" => Some(CandleMessageRole::User),
+            "assistant" => Some(CandleMessageRole::Assistant),
+            "system" => Some(CandleMessageRole::System),
+            _ => None,
+        })?;
+
+    let custom_str = |key: &str| {
+        memory
+            .metadata
+            .custom
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let origin = HistoryOrigin {
+        memory_id: memory.id.clone(),
+        session_id: custom_str(SESSION_ID_KEY),
+        turn_id: custom_str(TURN_ID_KEY),
+        tags: memory
+            .metadata
+            .tags
+            .iter()
+            .filter(|tag| !tag.starts_with(ROLE_TAG_PREFIX))
+            .cloned()
+            .collect(),
+    };
+
+    let id = match &origin.session_id {
+        Some(session_id) => format!("{session_id}/{}", memory.id),
+        None => memory.id.clone(),
+    };
+    let timestamp = u64::try_from(memory.created_at.into_inner().timestamp()).ok();
+
+    let message = SearchChatMessage {
+        message: CandleMessage {
+            role,
+            content: memory.content.text.clone(),
+            id: Some(id),
+            timestamp,
+        },
+        relevance_score: 0.0,
+        highlights: Vec::new(),
+    };
+    Some((message, origin))
+}
+
+/// Excerpt of `content` around the first occurrence of any term
+///
+/// Matching is case-insensitive. Without a match the excerpt starts at the
+/// beginning; cut ends are marked with `…`.
+pub fn snippet(content: &str, terms: &[String], max_chars: usize) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    if chars.len() <= max_chars {
+        return content.to_string();
+    }
+
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let first_match = terms
+        .iter()
+        .filter_map(|term| {
+            let needle: Vec<char> = term.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect();
+            if needle.is_empty() || needle.len() > lower.len() {
+                return None;
+            }
+            lower.windows(needle.len()).position(|window| window == needle.as_slice())
+        })
+        .min();
+
+    // Center the window on the match, clamped to the content
+    let start = first_match
+        .map_or(0, |pos| pos.saturating_sub(max_chars / 3))
+        .min(chars.len() - max_chars);
+    let end = start + max_chars;
+
+    let mut excerpt = String::new();
+    if start > 0 {
+        excerpt.push('…');
+    }
+    excerpt.extend(chars[start..end].iter());
+    if end < chars.len() {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+/// Indexed conversations of one store
+struct LibraryHistory {
+    searcher: ChatSearcher,
+    index: Arc<ChatSearchIndex>,
+    /// Origins keyed by memory ID
+    origins: DashMap<String, HistoryOrigin>,
+    /// Held while new memories are indexed
+    sync_lock: Mutex<()>,
+}
+
+impl LibraryHistory {
+    fn new() -> Self {
+        let index = Arc::new(ChatSearchIndex::new());
+        Self {
+            searcher: ChatSearcher::new(index.clone()),
+            index,
+            origins: DashMap::new(),
+            sync_lock: Mutex::new(()),
+        }
+    }
+}
+
+/// Chat history indexes, keyed by library; `None` is the chat memory database
+pub struct HistoryIndexManager {
+    pool: Arc<CoordinatorPool>,
+    libraries: DashMap<Option<String>, Arc<LibraryHistory>>,
+}
+
+impl HistoryIndexManager {
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self {
+            pool,
+            libraries: DashMap::new(),
+        }
+    }
+
+    /// Index the chat history in the background
+    ///
+    /// Called once at startup so the first search after a restart does not
+    /// wait for the whole history to be read back.
+    pub fn start_indexing(self: &Arc<Self>) {
+        let manager = self.clone();
+        crate::runtime::supervisor().spawn("chat history indexing", async move {
+            let history = manager.history(None);
+            if let Err(e) = manager.sync(None, &history).await {
+                log::warn!("Failed to index chat history: {e}");
+            }
+        });
+    }
+
+    /// Number of messages indexed for a library, or the chat history
+    pub fn indexed(&self, library: Option<&str>) -> usize {
+        self.libraries
+            .get(&library.map(str::to_string))
+            .map_or(0, |history| history.origins.len())
+    }
+
+    /// Index of a library, or of the chat history
+    fn history(&self, library: Option<&str>) -> Arc<LibraryHistory> {
+        self.libraries
+            .entry(library.map(str::to_string))
+            .or_insert_with(|| Arc::new(LibraryHistory::new()))
+            .clone()
+    }
+
+    /// Store holding the conversations: a library, or the chat memory database
+    async fn coordinator(&self, library: Option<&str>) -> anyhow::Result<Arc<MemoryCoordinator>> {
+        match library {
+            Some(library) => self
+                .pool
+                .get_coordinator(library)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to open library '{library}': {e}")),
+            None => chat_memory_coordinator(self.pool.embedding_model())
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to open chat memory: {e}")),
+        }
+    }
+
+    /// Search stored conversations, indexing new turns first
+    ///
+    /// Without a library the chat history is searched. Tag filters are
+    /// matched against the stored memory tags; every other part of the query
+    /// is evaluated by the chat searcher.
+    pub async fn search(
+        &self,
+        library: Option<&str>,
+        mut query: SearchQuery,
+    ) -> anyhow::Result<Vec<HistoryHit>> {
+        let history = self.history(library);
+        self.sync(library, &history).await?;
+
+        let terms = match &query.expression {
+            Some(expression) => expression.positive_terms(),
+            None => query.terms.clone(),
+        };
+
+        // Tags live on the memories, so filter and paginate after the search
+        let tag_filter = query.tag_filter.take().filter(|tags| !tags.is_empty());
+        let (offset, max_results) = (query.offset, query.max_results);
+        if tag_filter.is_some() {
+            query.offset = 0;
+            query.max_results = usize::MAX;
+        }
+
+        let results = history.searcher.search(query).await?;
+
+        let hits = results.into_iter().filter_map(|result| {
+            let message = &result.message.message;
+            let id = message.id.as_deref()?;
+            let memory_id = id.rsplit('/').next().unwrap_or(id);
+            let origin = history.origins.get(memory_id)?.clone();
+            if let Some(tags) = &tag_filter
+                && !origin.tags.iter().any(|tag| tags.contains(tag))
+            {
+                return None;
+            }
+            Some(HistoryHit {
+                memory_id: origin.memory_id,
+                session_id: origin.session_id,
+                turn_id: origin.turn_id,
+                role: message.role.to_string(),
+                score: result.relevance_score,
+                snippet: snippet(&message.content, &terms, DEFAULT_SNIPPET_CHARS),
+                timestamp: message.timestamp,
+                tags: origin.tags,
+            })
+        });
+
+        Ok(match tag_filter {
+            Some(_) => hits.skip(offset).take(max_results).collect(),
+            None => hits.collect(),
+        })
+    }
+
+    /// Index conversation memories not yet seen
+    async fn sync(&self, library: Option<&str>, history: &LibraryHistory) -> anyhow::Result<()> {
+        let _guard = history.sync_lock.lock().await;
+        let coordinator = self.coordinator(library).await?;
+
+        let mut offset = 0;
+        let mut added = 0;
+        loop {
+            let mut stream = coordinator.list_all_memories(HISTORY_PAGE_SIZE, offset);
+            let mut fetched = 0;
+            while let Some(memory) = stream.next().await {
+                let memory = memory?;
+                fetched += 1;
+                if history.origins.contains_key(&memory.id) {
+                    continue;
+                }
+                if let Some((message, origin)) = history_message(&memory) {
+                    history.index.add_message(message).await?;
+                    history.origins.insert(origin.memory_id.clone(), origin);
+                    added += 1;
+                }
+            }
+
+            if fetched < HISTORY_PAGE_SIZE {
+                break;
+            }
+            offset += fetched;
+        }
+
+        if added > 0 {
+            log::debug!(
+                "Indexed {added} conversation messages from {}",
+                store_name(library)
+            );
+        }
+        Ok(())
+    }
+
+    /// Scrub a message from its store, the search index and derived memories
+    ///
+    /// `message_id` is the memory ID of a stored chat message, as returned in
+    /// [`HistoryHit::memory_id`]; without a library it is looked up in the
+    /// chat history. Derived memories are found through [`SOURCE_MESSAGE_KEY`]
+    /// in the message's store and every pool library, since summaries are
+    /// memorized wherever they were asked for, and are scrubbed the same way
+    /// as the message.
+    pub async fn redact_message(
+        &self,
+        library: Option<&str>,
+        message_id: &str,
+        mode: RedactionMode,
+    ) -> anyhow::Result<RedactionReport> {
+        let history = self.history(library);
+        // Keep a concurrent sync from re-indexing the message mid-redaction
+        let _guard = history.sync_lock.lock().await;
+        let coordinator = self.coordinator(library).await?;
+
+        let mut report = RedactionReport {
+            message_id: message_id.to_string(),
+            mode,
+            message_scrubbed: false,
+            postings_removed: 0,
+            derived_memories: Vec::new(),
+        };
+
+        // Remove the message's postings; a masked message is re-indexed with
+        // the placeholder on the next sync
+        if let Some((_, origin)) = history.origins.remove(message_id) {
+            let doc_id = match &origin.session_id {
+                Some(session_id) => format!("{session_id}/{message_id}"),
+                None => message_id.to_string(),
+            };
+            report.postings_removed += history.index.remove_message(&doc_id).unwrap_or(0);
+        }
+        report.message_scrubbed = scrub(&coordinator, message_id, mode).await?;
+
+        let mut stores = vec![coordinator];
+        for other in self.pool.list_libraries().await? {
+            if library != Some(other.as_str()) {
+                stores.push(self.pool.get_coordinator(&other).await?);
+            }
+        }
+        for store in &stores {
+            for memory_id in derived_memory_ids(store, message_id).await? {
+                if scrub(store, &memory_id, mode).await? {
+                    report.derived_memories.push(memory_id);
+                }
+            }
+        }
+
+        log::info!(
+            "Redacted message {message_id} in {}: {} postings, {} derived memories",
+            store_name(library),
+            report.postings_removed,
+            report.derived_memories.len()
+        );
+        Ok(report)
+    }
+}
+
+/// Name of a store in log lines
+fn store_name(library: Option<&str>) -> String {
+    match library {
+        Some(library) => format!("library '{library}'"),
+        None => "the chat history".to_string(),
+    }
+}
+
+/// IDs of the memories in `coordinator` derived from `message_id`
+async fn derived_memory_ids(
+    coordinator: &MemoryCoordinator,
+    message_id: &str,
+) -> anyhow::Result<Vec<String>> {
+    let mut ids = Vec::new();
+    let mut offset = 0;
+    loop {
+        let mut stream = coordinator.list_all_memories(HISTORY_PAGE_SIZE, offset);
+        let mut fetched = 0;
+        while let Some(memory) = stream.next().await {
+            let memory = memory?;
+            fetched += 1;
+            if memory.id != message_id && derived_from(&memory, message_id) {
+                ids.push(memory.id.clone());
+            }
+        }
+        if fetched < HISTORY_PAGE_SIZE {
+            break;
+        }
+        offset += fetched;
+    }
+    Ok(ids)
+}
+
+/// Mask or remove one memory; whether it existed
+async fn scrub(
+    coordinator: &MemoryCoordinator,
+    memory_id: &str,
+    mode: RedactionMode,
+) -> anyhow::Result<bool> {
+    Ok(match mode {
+        RedactionMode::Mask => {
+            coordinator
+                .redact_memory(memory_id, REDACTED_PLACEHOLDER, REDACTED_TAG)
+                .await?
+        }
+        RedactionMode::Remove => {
+            let exists = MemoryManager::get_memory(coordinator, memory_id)
+                .await?
+                .is_some();
+            if exists {
+                coordinator.delete_memory(memory_id).await?;
+            }
+            exists
+        }
+    })
+}
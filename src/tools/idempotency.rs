@@ -0,0 +1,140 @@
+//! Idempotency keys for mutating MCP tools
+//!
+//! A client that retries a request after a network timeout passes the same
+//! `idempotency_key` again. The server remembers recent keys together with the
+//! outcome of the first request, so the retry returns that outcome instead of
+//! repeating the work. Reusing a key with different arguments is rejected.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+/// How long a key is remembered after its first use (10 minutes)
+pub const IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(600);
+
+/// Remembered outcome of a keyed request
+struct IdempotencyEntry<T> {
+    fingerprint: u64,
+    value: T,
+    created_at: Instant,
+}
+
+/// Result of a keyed request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Idempotent<T> {
+    /// Outcome of the first request made with the key
+    pub value: T,
+    /// Whether the outcome was replayed rather than produced by this call
+    pub replayed: bool,
+}
+
+/// Key reused with arguments that differ from its first use
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("idempotency_key '{key}' was already used with different arguments")]
+pub struct IdempotencyConflict {
+    /// The conflicting key
+    pub key: String,
+}
+
+/// Recent idempotency keys and their outcomes
+///
+/// Keyed requests are serialized, so two concurrent retries with the same key
+/// cannot both run the operation.
+pub struct IdempotencyCache<T> {
+    entries: Mutex<HashMap<String, IdempotencyEntry<T>>>,
+    ttl: Duration,
+}
+
+impl<T: Clone> Default for IdempotencyCache<T> {
+    fn default() -> Self {
+        Self::new(IDEMPOTENCY_KEY_TTL)
+    }
+}
+
+impl<T: Clone> IdempotencyCache<T> {
+    /// Create a cache that remembers keys for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Return the remembered outcome for `key`, or run `operation` and remember its result
+    ///
+    /// `fingerprint` identifies the request arguments (see [`fingerprint`]).
+    /// Failed operations are not remembered, so a retry after an error runs
+    /// the operation again.
+    pub async fn get_or_try_insert_with<F, Fut, E>(
+        &self,
+        key: &str,
+        fingerprint: u64,
+        operation: F,
+    ) -> Result<Idempotent<T>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<IdempotencyConflict>,
+    {
+        let mut entries = self.entries.lock().await;
+
+        if let Some(entry) = entries.get(key)
+            && entry.created_at.elapsed() < self.ttl
+        {
+            if entry.fingerprint != fingerprint {
+                return Err(IdempotencyConflict {
+                    key: key.to_string(),
+                }
+                .into());
+            }
+            return Ok(Idempotent {
+                value: entry.value.clone(),
+                replayed: true,
+            });
+        }
+
+        let value = operation().await?;
+        entries.insert(
+            key.to_string(),
+            IdempotencyEntry {
+                fingerprint,
+                value: value.clone(),
+                created_at: Instant::now(),
+            },
+        );
+
+        Ok(Idempotent {
+            value,
+            replayed: false,
+        })
+    }
+
+    /// Forget keys older than the TTL
+    pub async fn purge_expired(&self) {
+        let ttl = self.ttl;
+        self.entries
+            .lock()
+            .await
+            .retain(|_, entry| entry.created_at.elapsed() < ttl);
+    }
+
+    /// Number of remembered keys (including expired ones not yet purged)
+    pub async fn len(&self) -> usize {
+        self.entries.lock().await.len()
+    }
+
+    /// Whether no keys are remembered
+    pub async fn is_empty(&self) -> bool {
+        self.entries.lock().await.is_empty()
+    }
+}
+
+/// Fingerprint of a request's arguments, used to detect key reuse
+pub fn fingerprint(parts: &[&str]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    parts.hash(&mut hasher);
+    hasher.finish()
+}
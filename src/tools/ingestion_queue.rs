@@ -0,0 +1,271 @@
+//! Ingestion queue for memorize sessions
+//!
+//! Every memorize call used to start its embedding work right away, so a
+//! burst of calls ran as many embedding jobs at once. Sessions now wait in a
+//! FIFO queue and at most `concurrency` of them load and embed content at a
+//! time, by default one per text embedding worker
+//! (`KODEGEN_MEMORIZE_CONCURRENCY` overrides it). Sessions start in the order
+//! they were submitted: [`IngestionQueue::enqueue`] hands out a
+//! [`QueueTicket`] with a sequence number, and only the ticket at the head of
+//! the queue may take a free slot, however its task gets scheduled.
+//! `check_memorize_status` reports a waiting session's position. Once `capacity` sessions are waiting
+//! (`KODEGEN_MEMORIZE_QUEUE_CAPACITY`, default [`DEFAULT_QUEUE_CAPACITY`]),
+//! new sessions are rejected with [`QueueFull`], which carries a Retry-After
+//! estimate.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+use crate::capability::registry::pool::text_embedding_pool;
+
+/// Sessions running at once
+pub const MEMORIZE_CONCURRENCY_ENV: &str = "KODEGEN_MEMORIZE_CONCURRENCY";
+
+/// Sessions allowed to wait
+pub const MEMORIZE_QUEUE_CAPACITY_ENV: &str = "KODEGEN_MEMORIZE_QUEUE_CAPACITY";
+
+/// Sessions allowed to wait when nothing is configured
+pub const DEFAULT_QUEUE_CAPACITY: usize = 64;
+
+/// Assumed session duration until one has finished (10 seconds)
+const INITIAL_SESSION_MS: u64 = 10_000;
+
+/// Bounds of the Retry-After estimate
+const MIN_RETRY_AFTER: Duration = Duration::from_secs(1);
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// The queue is full; retry after `retry_after`
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "Memorize queue is full ({queued} sessions waiting), Retry-After: {}s",
+    retry_after.as_secs()
+)]
+pub struct QueueFull {
+    /// Sessions waiting when the request was rejected
+    pub queued: usize,
+    /// Estimated time until a slot frees up
+    pub retry_after: Duration,
+}
+
+/// The queue was closed; the session will not start
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Memorize queue is closed")]
+pub struct QueueClosed;
+
+/// Bounded FIFO queue limiting how many memorize sessions run at once
+pub struct IngestionQueue {
+    permits: Arc<Semaphore>,
+    concurrency: usize,
+    capacity: usize,
+    /// Waiting sessions as (ticket, session ID), in the order they will run
+    waiting: Mutex<VecDeque<(u64, String)>>,
+    /// Ticket handed to the next enqueued session
+    next_ticket: AtomicU64,
+    /// Signalled when the head of the queue changes
+    head_changed: Notify,
+    /// Moving average of session run time, in milliseconds
+    average_ms: AtomicU64,
+}
+
+impl IngestionQueue {
+    /// Queue running `concurrency` sessions at once with `capacity` waiting
+    pub fn new(concurrency: usize, capacity: usize) -> Self {
+        let concurrency = concurrency.max(1);
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency)),
+            concurrency,
+            capacity,
+            waiting: Mutex::new(VecDeque::new()),
+            next_ticket: AtomicU64::new(0),
+            head_changed: Notify::new(),
+            average_ms: AtomicU64::new(INITIAL_SESSION_MS),
+        }
+    }
+
+    /// Queue configured from the environment
+    ///
+    /// Concurrency defaults to the text embedding pool's worker limit.
+    pub fn from_env() -> Self {
+        let concurrency = env_usize(MEMORIZE_CONCURRENCY_ENV)
+            .unwrap_or_else(|| text_embedding_pool().config().max_workers_per_model);
+        let capacity = env_usize(MEMORIZE_QUEUE_CAPACITY_ENV).unwrap_or(DEFAULT_QUEUE_CAPACITY);
+        Self::new(concurrency, capacity)
+    }
+
+    /// Sessions running at once
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Sessions allowed to wait
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sessions waiting for a slot
+    pub fn queued(&self) -> usize {
+        self.waiting.lock().len()
+    }
+
+    /// Sessions currently running
+    pub fn running(&self) -> usize {
+        self.concurrency - self.permits.available_permits()
+    }
+
+    /// 1-based position of a waiting session, `None` once it is running
+    pub fn position(&self, session_id: &str) -> Option<usize> {
+        self.waiting
+            .lock()
+            .iter()
+            .position(|(_, id)| id == session_id)
+            .map(|i| i + 1)
+    }
+
+    /// Add `session_id` to the back of the queue
+    ///
+    /// The returned ticket holds the session's place; dropping it without
+    /// [`acquire`](QueueTicket::acquire)ing leaves the queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueFull`] when `capacity` sessions are already waiting.
+    pub fn enqueue(self: &Arc<Self>, session_id: &str) -> Result<QueueTicket, QueueFull> {
+        let mut waiting = self.waiting.lock();
+        if waiting.len() >= self.capacity {
+            return Err(QueueFull {
+                queued: waiting.len(),
+                retry_after: self.retry_after_for(waiting.len()),
+            });
+        }
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        waiting.push_back((ticket, session_id.to_string()));
+        Ok(QueueTicket {
+            queue: Arc::clone(self),
+            ticket,
+        })
+    }
+
+    /// Stop starting sessions; waiting ones fail with [`QueueClosed`]
+    pub fn close(&self) {
+        self.permits.close();
+        self.head_changed.notify_waiters();
+    }
+
+    /// Remove a ticket that is leaving the queue
+    fn leave(&self, ticket: u64) {
+        let mut waiting = self.waiting.lock();
+        let was_head = waiting.front().map(|(t, _)| *t) == Some(ticket);
+        waiting.retain(|(t, _)| *t != ticket);
+        drop(waiting);
+        if was_head {
+            self.head_changed.notify_waiters();
+        }
+    }
+
+    /// Estimated wait before a newly queued session would start
+    pub fn retry_after(&self) -> Duration {
+        self.retry_after_for(self.queued())
+    }
+
+    fn retry_after_for(&self, queued: usize) -> Duration {
+        let average = Duration::from_millis(self.average_ms.load(Ordering::Relaxed));
+        let rounds = queued / self.concurrency + 1;
+        (average * rounds as u32).clamp(MIN_RETRY_AFTER, MAX_RETRY_AFTER)
+    }
+
+    fn record_run(&self, elapsed: Duration) {
+        let elapsed_ms = elapsed.as_millis().min(u64::MAX as u128) as u64;
+        // Weight the latest run by a quarter
+        let _ = self
+            .average_ms
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |average| {
+                Some((average * 3 + elapsed_ms) / 4)
+            });
+    }
+}
+
+/// A waiting session's place in the [`IngestionQueue`]
+///
+/// Dropped once the session starts, or if it never will.
+pub struct QueueTicket {
+    queue: Arc<IngestionQueue>,
+    ticket: u64,
+}
+
+impl QueueTicket {
+    /// Sequence number assigned when the session was enqueued
+    pub fn number(&self) -> u64 {
+        self.ticket
+    }
+
+    /// Wait for this session's turn and take a slot
+    ///
+    /// Only the head of the queue waits for a slot, so sessions start in the
+    /// order they were enqueued. The slot is released when the returned
+    /// permit is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueClosed`] if the queue is closed before the session starts.
+    pub async fn acquire(self) -> Result<IngestionPermit, QueueClosed> {
+        let queue = &self.queue;
+        loop {
+            let head_changed = queue.head_changed.notified();
+            tokio::pin!(head_changed);
+            head_changed.as_mut().enable();
+
+            if queue.permits.is_closed() {
+                return Err(QueueClosed);
+            }
+            let at_head = queue.waiting.lock().front().map(|(t, _)| *t) == Some(self.ticket);
+            if at_head {
+                let permit = Arc::clone(&queue.permits)
+                    .acquire_owned()
+                    .await
+                    .map_err(|_| QueueClosed)?;
+                // Dropping the ticket on return hands the head to the next session
+                return Ok(IngestionPermit {
+                    queue: Arc::clone(queue),
+                    started: Instant::now(),
+                    _permit: permit,
+                });
+            }
+            head_changed.await;
+        }
+    }
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        self.queue.leave(self.ticket);
+    }
+}
+
+/// A running session's slot in the [`IngestionQueue`]
+pub struct IngestionPermit {
+    queue: Arc<IngestionQueue>,
+    started: Instant,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for IngestionPermit {
+    fn drop(&mut self) {
+        self.queue.record_run(self.started.elapsed());
+    }
+}
+
+fn env_usize(name: &str) -> Option<usize> {
+    let value = std::env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(n) => Some(n),
+        Err(_) => {
+            log::warn!("Invalid {name}={value:?}, using the default");
+            None
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Inline content pushed by memorize clients
+//!
+//! Remote MCP clients often cannot share a filesystem with the server, so
+//! `memorize` also accepts the file itself: `content` holds base64 data,
+//! `content_encoding` is `"base64"` and `mime_type` names the format. The
+//! bytes are decoded and routed to a loader by MIME type; when `mime_type` is
+//! missing or generic it is sniffed from the leading bytes.
+
+use base64::Engine;
+
+/// `content_encoding` value for base64 payloads
+pub const CONTENT_ENCODING_BASE64: &str = "base64";
+
+/// Largest decoded payload accepted (64 MiB)
+pub const MAX_INLINE_CONTENT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Inline content errors
+#[derive(Debug, thiserror::Error)]
+pub enum InlineContentError {
+    #[error("Unsupported content_encoding '{0}' (expected \"base64\")")]
+    UnsupportedEncoding(String),
+    #[error("Invalid base64 content: {0}")]
+    InvalidBase64(#[from] base64::DecodeError),
+    #[error("Inline content is {size} bytes (max {max})")]
+    TooLarge { size: usize, max: usize },
+    #[error("Content of type {0} is not valid UTF-8 text")]
+    InvalidText(String),
+    #[error("Failed to extract text from PDF: {0}")]
+    Pdf(String),
+    #[error("No loader for content of type {0}")]
+    UnsupportedMimeType(String),
+}
+
+/// Decoded bytes with their MIME type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineContent {
+    bytes: Vec<u8>,
+    mime_type: String,
+}
+
+impl InlineContent {
+    /// Decode `content` according to `encoding`
+    ///
+    /// `mime_type` is used as given unless it is absent or
+    /// `application/octet-stream`, in which case it is detected.
+    pub fn decode(
+        content: &str,
+        encoding: &str,
+        mime_type: Option<&str>,
+    ) -> Result<Self, InlineContentError> {
+        if !encoding.eq_ignore_ascii_case(CONTENT_ENCODING_BASE64) {
+            return Err(InlineContentError::UnsupportedEncoding(encoding.to_string()));
+        }
+
+        let compact: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+        let bytes = base64::engine::general_purpose::STANDARD.decode(compact)?;
+        Self::from_bytes(bytes, mime_type)
+    }
+
+    /// Wrap raw bytes, detecting the MIME type if not given
+    pub fn from_bytes(bytes: Vec<u8>, mime_type: Option<&str>) -> Result<Self, InlineContentError> {
+        if bytes.len() > MAX_INLINE_CONTENT_BYTES {
+            return Err(InlineContentError::TooLarge {
+                size: bytes.len(),
+                max: MAX_INLINE_CONTENT_BYTES,
+            });
+        }
+
+        let mime_type = match mime_type.map(|m| m.trim().to_ascii_lowercase()) {
+            Some(m) if !m.is_empty() && m != "application/octet-stream" => m,
+            _ => detect_mime_type(&bytes).to_string(),
+        };
+        Ok(Self { bytes, mime_type })
+    }
+
+    /// Decoded bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// MIME type used to pick a loader (lowercase, possibly with parameters)
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// Short description for session listings, e.g. `<inline application/pdf, 2048 bytes>`
+    pub fn describe(&self) -> String {
+        format!("<inline {}, {} bytes>", self.mime_type, self.bytes.len())
+    }
+
+    /// Extract text with the loader for this MIME type
+    pub fn extract_text(&self) -> Result<String, InlineContentError> {
+        let essence = self
+            .mime_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim();
+
+        if is_text_mime(essence) {
+            return String::from_utf8(self.bytes.clone())
+                .map_err(|_| InlineContentError::InvalidText(essence.to_string()));
+        }
+
+        match essence {
+            "application/pdf" => pdf_extract::extract_text_from_mem(&self.bytes)
+                .map_err(|e| InlineContentError::Pdf(e.to_string())),
+            // Image OCR is not available yet
+            _ => Err(InlineContentError::UnsupportedMimeType(essence.to_string())),
+        }
+    }
+}
+
+/// Whether a MIME type is loaded as UTF-8 text
+fn is_text_mime(essence: &str) -> bool {
+    essence.starts_with("text/")
+        || matches!(
+            essence,
+            "application/json"
+                | "application/xml"
+                | "application/x-yaml"
+                | "application/yaml"
+                | "application/toml"
+                | "application/javascript"
+                | "application/x-sh"
+        )
+        || essence.ends_with("+json")
+        || essence.ends_with("+xml")
+}
+
+/// Sniff a MIME type from magic bytes, falling back to text for valid UTF-8
+pub fn detect_mime_type(bytes: &[u8]) -> &'static str {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"%PDF-", "application/pdf"),
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"PK\x03\x04", "application/zip"),
+    ];
+
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return mime;
+    }
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return "text/plain";
+    }
+    "application/octet-stream"
+}
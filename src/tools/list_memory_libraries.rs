@@ -36,7 +36,14 @@ impl Tool for ListMemoryLibrariesTool {
         true
     }
 
-    async fn execute(&self, _args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl ListMemoryLibrariesTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, _args: ListMemoryLibrariesArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<ListMemoryLibrariesOutput>, McpError> {
         // Use pool's list_libraries() which scans filesystem
         let libraries = self.pool.list_libraries()
             .await
@@ -0,0 +1,72 @@
+//! List Models Tool - Describe the models this server can run
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::candle::{ListModelsArgs, ListModelsOutput, ListModelsPrompts, CANDLE_LIST_MODELS};
+
+use crate::capability::registry::{self, DownloadStatus, ModelDescriptor};
+
+#[derive(Clone, Default)]
+pub struct ListModelsTool;
+
+impl ListModelsTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tool for ListModelsTool {
+    type Args = ListModelsArgs;
+    type Prompts = ListModelsPrompts;
+
+    fn name() -> &'static str {
+        CANDLE_LIST_MODELS
+    }
+
+    fn description() -> &'static str {
+        "List the models registered with this server: registry key, capability (text_to_text, \
+         text_embedding, image_embedding, text_to_image, vision), quantization, context length, \
+         embedding dimension, whether the weights are already cached locally, and estimated \
+         memory per loaded worker. Use this to discover what the server can do before configuring agents."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl ListModelsTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: ListModelsArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<ListModelsOutput>, McpError> {
+        let models: Vec<ModelDescriptor> = registry::models()
+            .filter(|m| args.capability.is_none_or(|c| m.capability == c))
+            .collect();
+        let count = models.len();
+
+        let summary = if models.is_empty() {
+            "✓ No models registered".to_string()
+        } else {
+            let model_list = models
+                .iter()
+                .map(|m| {
+                    format!(
+                        "  • {} ({:?}, {}, ~{} MB){}",
+                        m.registry_key,
+                        m.capability,
+                        m.quantization,
+                        m.est_memory_mb,
+                        if m.download_status == DownloadStatus::Cached { "" } else { " [not cached]" }
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            format!("✓ Registered models ({})\n\n{}", count, model_list)
+        };
+
+        Ok(ToolResponse::new(summary, ListModelsOutput { models, count }))
+    }
+}
@@ -1,10 +1,11 @@
 //! Memorize Tool - Store content in a named memory library (async session-based)
 
-use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::memory::{MemorizeArgs, MemorizeOutput, MEMORY_MEMORIZE, MemorizePrompts};
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{MemorizeArgs, MemorizeOutput, MemorizePrompts, MEMORY_MEMORIZE};
 use std::sync::Arc;
 
-use super::memorize_manager::MemorizeSessionManager;
+use super::inline_content::InlineContent;
+use super::memorize_manager::{MemorizeContent, MemorizeSessionManager, MemorizeStatus};
 
 #[derive(Clone)]
 pub struct MemorizeTool {
@@ -15,6 +16,40 @@ impl MemorizeTool {
     pub fn new(manager: Arc<MemorizeSessionManager>) -> Self {
         Self { manager }
     }
+
+    /// `library_min_score`: `Some(None)` for "none", `Some(Some(score))` for a number
+    pub fn min_score_arg(args: &MemorizeArgs) -> Result<Option<Option<f32>>, McpError> {
+        match &args.library_min_score {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(serde_json::Value::String(s)) if s.eq_ignore_ascii_case("none") => Ok(Some(None)),
+            Some(value) => value
+                .as_f64()
+                .map(|score| Some(Some(score as f32)))
+                .ok_or_else(|| {
+                    McpError::Other(anyhow::anyhow!(
+                        "library_min_score must be a number between 0 and 1 or \"none\", got {}",
+                        value
+                    ))
+                }),
+        }
+    }
+
+    /// Content to memorize, decoding it if `content_encoding` is set
+    pub fn content(args: &MemorizeArgs) -> Result<MemorizeContent, McpError> {
+        match non_empty(&args.content_encoding) {
+            Some(encoding) => {
+                InlineContent::decode(&args.content, &encoding, non_empty(&args.mime_type).as_deref())
+                    .map(MemorizeContent::Inline)
+                    .map_err(|e| McpError::Other(anyhow::anyhow!("Invalid inline content: {}", e)))
+            }
+            None => Ok(MemorizeContent::Reference(args.content.clone())),
+        }
+    }
+}
+
+/// An optional string argument, treating "" as unset
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.clone().filter(|value| !value.is_empty())
 }
 
 impl Tool for MemorizeTool {
@@ -28,12 +63,27 @@ impl Tool for MemorizeTool {
     fn description() -> &'static str {
         "Start async memorization of content in a named memory library (returns immediately with session_id). \
          The content field intelligently detects and loads from: single file paths, directories (recursive), \
-         glob patterns (*.rs, **/*.md), HTTP/HTTPS URLs, GitHub repos (github.com/user/repo with or without https://), \
+         glob patterns (*.rs, **/*.md), HTTP/HTTPS URLs (several whitespace-separated URLs are fetched in parallel), GitHub repos (github.com/user/repo with or without https://), \
          or literal text (fallback). Non-existent paths are treated as literal text. \
+         Source files (.rs, .py, .ts, .go, ...) are split at function/class boundaries and stored as chunks \
+         tagged with their path and line span; memorizing the same files again updates those chunks in place. \
+         Clients without access to the server's filesystem can push a file directly: put base64 data in content, \
+         set content_encoding to \"base64\" and mime_type (e.g. application/pdf, text/markdown; detected if omitted). \
          For large operations (full repos, directories), this returns immediately and runs in background. \
          Use check_memorize_status(session_id) to monitor progress. When complete, memory_id is available. \
          Each library is a separate .db file for organizing memories by context. \
-         Memories can be retrieved later using recall() by specifying the same library name."
+         Memories can be retrieved later using recall() by specifying the same library name. \
+         Pass an idempotency_key to make retries safe: repeating a key returns the original session_id and status \
+         instead of starting a second session. \
+         Sessions wait in a bounded queue and only a few run at once; check_memorize_status shows a waiting \
+         session's queue position. When the queue is full the call fails with a Retry-After estimate in seconds. \
+         Embeddings use the library's embedding task (default s2p: plain documents, search-instruction queries). \
+         Set library_embedding_task to change it for this and every later memorize and recall on the library \
+         (s2p, s2s, code:<language> or instruct:<instruction>); it can only change while the library is empty. \
+         Set embedding_task to override it for this call only; \
+         recall with the same task so queries match the stored embeddings. \
+         Set library_min_score (0-1) to make recall on the library leave out hits less relevant than that \
+         by default, or to \"none\" to remove the floor."
     }
 
     fn read_only() -> bool {
@@ -41,31 +91,76 @@ impl Tool for MemorizeTool {
     }
 
     fn idempotent() -> bool {
-        false // Creates new memories each time
+        false // Creates new memories each time unless an idempotency_key is supplied
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
     }
+}
+
+impl MemorizeTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: MemorizeArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<MemorizeOutput>, McpError> {
+        // Start async memorize session (returns immediately); a repeated
+        // idempotency key returns the original session instead
+        let idempotency_key = non_empty(&args.idempotency_key);
+        let content = Self::content(&args)?;
 
-    async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        // Start async memorize session (returns immediately)
-        let session_id = self
+        // Persist the library's embedding task before its content is embedded
+        if let Some(task) = non_empty(&args.library_embedding_task) {
+            self.manager
+                .set_library_embedding_task(&args.library, &task)
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to set library embedding task: {}", e)))?;
+        }
+
+        // Recall's default relevance floor for the library
+        if let Some(min_score) = Self::min_score_arg(&args)? {
+            self.manager
+                .set_library_min_score(&args.library, min_score)
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to set library min_score: {}", e)))?;
+        }
+
+        let embedding_task = non_empty(&args.embedding_task);
+        let start = self
             .manager
-            .start_memorize_session(args.library.clone(), args.content.clone())
+            .start_memorize_session_idempotent(args.library.clone(), content, idempotency_key, embedding_task)
             .await
             .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to start memorize session: {}", e)))?;
 
+        let status = match start.status {
+            MemorizeStatus::InProgress => "IN_PROGRESS",
+            MemorizeStatus::Completed => "COMPLETED",
+            MemorizeStatus::Failed => "FAILED",
+        };
+        let (headline, message) = if start.replayed {
+            (
+                "✓ Memorization already started for this idempotency key",
+                "Returning the original session for this idempotency key. Use check_memorize_status to monitor progress.",
+            )
+        } else {
+            (
+                "✓ Memorization started",
+                "Memorization started in background. Use check_memorize_status to monitor progress.",
+            )
+        };
+
         let summary = format!(
-            "✓ Memorization started\n\n\
+            "{}\n\n\
              Session: {}\n\
              Library: {}\n\
-             Status: IN_PROGRESS\n\n\
+             Status: {}\n\n\
              Use check_memorize_status to monitor progress",
-            session_id, args.library
+            headline, start.session_id, args.library, status
         );
 
         Ok(ToolResponse::new(summary, MemorizeOutput {
-            session_id,
-            status: "IN_PROGRESS".to_string(),
+            session_id: start.session_id,
+            status: status.to_string(),
             library: args.library,
-            message: "Memorization started in background. Use check_memorize_status to monitor progress.".to_string(),
+            message: message.to_string(),
         }))
     }
 
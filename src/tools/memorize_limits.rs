@@ -0,0 +1,352 @@
+//! Limits on what memorize sessions may read
+//!
+//! Memorize resolves its input to files, directories, globs and URLs with
+//! the server's own permissions. [`MemorizeLimits`] bounds that:
+//!
+//! - `KODEGEN_MEMORIZE_MAX_SOURCE_BYTES`: largest file or URL body
+//!   (default 10 MiB)
+//! - `KODEGEN_MEMORIZE_MAX_FILES`: most files one directory or glob may
+//!   match (default 1000)
+//! - `KODEGEN_MEMORIZE_ALLOWED_ROOTS`: directories paths must resolve into,
+//!   separated like `PATH` (default: anywhere). Roots that don't resolve
+//!   allow nothing, so a typo narrows the sandbox instead of lifting it
+//! - `KODEGEN_MEMORIZE_URL_SCHEMES`: comma-separated URL schemes
+//!   (default `https,http`)
+//! - `KODEGEN_MEMORIZE_ALLOWED_DOMAINS`: comma-separated hosts; subdomains
+//!   match too (default: any)
+//!
+//! Paths are checked after resolving symlinks, so a link inside an allowed
+//! root cannot point out of it. Files of directories and globs are checked
+//! against the roots and size limit before they are read. URL checks apply
+//! to every redirect hop, and bodies stop downloading once past the size
+//! limit. Sources must be text; binary files are rejected instead of being
+//! embedded as garbage.
+
+use std::path::{Path, PathBuf};
+
+/// Largest file or URL body, in bytes
+pub const MEMORIZE_MAX_SOURCE_BYTES_ENV: &str = "KODEGEN_MEMORIZE_MAX_SOURCE_BYTES";
+
+/// Most files one directory or glob may match
+pub const MEMORIZE_MAX_FILES_ENV: &str = "KODEGEN_MEMORIZE_MAX_FILES";
+
+/// Directories paths must resolve into
+pub const MEMORIZE_ALLOWED_ROOTS_ENV: &str = "KODEGEN_MEMORIZE_ALLOWED_ROOTS";
+
+/// URL schemes that may be fetched
+pub const MEMORIZE_URL_SCHEMES_ENV: &str = "KODEGEN_MEMORIZE_URL_SCHEMES";
+
+/// Hosts URLs may point to
+pub const MEMORIZE_ALLOWED_DOMAINS_ENV: &str = "KODEGEN_MEMORIZE_ALLOWED_DOMAINS";
+
+/// Default largest source (10 MiB)
+pub const DEFAULT_MAX_SOURCE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Default most files per directory or glob
+pub const DEFAULT_MAX_FILES: usize = 1000;
+
+/// An input memorize refused to read
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MemorizeLimitError {
+    #[error("{input} is {size} bytes, over the limit of {limit} bytes per source")]
+    TooLarge {
+        input: String,
+        size: usize,
+        limit: usize,
+    },
+    #[error("{pattern} matches more than {limit} files")]
+    TooManyFiles { pattern: String, limit: usize },
+    #[error("{path} is outside the allowed roots ({roots})")]
+    PathNotAllowed { path: String, roots: String },
+    #[error("URL scheme '{scheme}' is not allowed ({url})")]
+    SchemeNotAllowed { url: String, scheme: String },
+    #[error("Domain '{domain}' is not allowed ({url})")]
+    DomainNotAllowed { url: String, domain: String },
+    #[error("Invalid URL {url}: {reason}")]
+    InvalidUrl { url: String, reason: String },
+    #[error("{input} is not text")]
+    NotText { input: String },
+}
+
+/// Bounds on the sources of a memorize session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorizeLimits {
+    max_source_bytes: usize,
+    max_files: usize,
+    /// Canonical roots; `None` allows any path, an empty list none
+    allowed_roots: Option<Vec<PathBuf>>,
+    url_schemes: Vec<String>,
+    /// Lowercase hosts; empty allows any
+    allowed_domains: Vec<String>,
+}
+
+impl Default for MemorizeLimits {
+    fn default() -> Self {
+        Self {
+            max_source_bytes: DEFAULT_MAX_SOURCE_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+            allowed_roots: None,
+            url_schemes: vec!["https".to_string(), "http".to_string()],
+            allowed_domains: Vec::new(),
+        }
+    }
+}
+
+impl MemorizeLimits {
+    /// Default limits: 10 MiB per source, 1000 files, any path, http(s) URLs
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits configured from the environment
+    ///
+    /// Unset or invalid variables keep their defaults.
+    pub fn from_env() -> Self {
+        let mut limits = Self::default();
+        if let Some(bytes) = env_usize(MEMORIZE_MAX_SOURCE_BYTES_ENV) {
+            limits = limits.with_max_source_bytes(bytes);
+        }
+        if let Some(files) = env_usize(MEMORIZE_MAX_FILES_ENV) {
+            limits = limits.with_max_files(files);
+        }
+        if let Some(roots) = std::env::var_os(MEMORIZE_ALLOWED_ROOTS_ENV) {
+            limits = limits.with_allowed_roots(std::env::split_paths(&roots));
+        }
+        if let Ok(schemes) = std::env::var(MEMORIZE_URL_SCHEMES_ENV) {
+            limits = limits.with_url_schemes(comma_list(&schemes));
+        }
+        if let Ok(domains) = std::env::var(MEMORIZE_ALLOWED_DOMAINS_ENV) {
+            limits = limits.with_allowed_domains(comma_list(&domains));
+        }
+        limits
+    }
+
+    /// Largest file or URL body, in bytes
+    #[must_use]
+    pub fn with_max_source_bytes(mut self, bytes: usize) -> Self {
+        self.max_source_bytes = bytes;
+        self
+    }
+
+    /// Most files one directory or glob may match
+    #[must_use]
+    pub fn with_max_files(mut self, files: usize) -> Self {
+        self.max_files = files;
+        self
+    }
+
+    /// Directories paths must resolve into; none allows any path
+    ///
+    /// Roots that cannot be resolved are dropped with a warning, and allow
+    /// nothing: if none resolve, every path is refused.
+    #[must_use]
+    pub fn with_allowed_roots(mut self, roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        let roots: Vec<PathBuf> = roots
+            .into_iter()
+            .filter(|root| !root.as_os_str().is_empty())
+            .collect();
+        if roots.is_empty() {
+            self.allowed_roots = None;
+            return self;
+        }
+        let resolved: Vec<PathBuf> = roots
+            .into_iter()
+            .filter_map(|root| match root.canonicalize() {
+                Ok(root) => Some(root),
+                Err(e) => {
+                    log::warn!("Ignoring memorize root {}: {}", root.display(), e);
+                    None
+                }
+            })
+            .collect();
+        if resolved.is_empty() {
+            log::warn!("No memorize root could be resolved; memorize will refuse every path");
+        }
+        self.allowed_roots = Some(resolved);
+        self
+    }
+
+    /// URL schemes that may be fetched
+    #[must_use]
+    pub fn with_url_schemes(mut self, schemes: impl IntoIterator<Item = String>) -> Self {
+        self.url_schemes = schemes
+            .into_iter()
+            .map(|s| s.to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    /// Hosts URLs may point to (subdomains included); none allows any
+    #[must_use]
+    pub fn with_allowed_domains(mut self, domains: impl IntoIterator<Item = String>) -> Self {
+        self.allowed_domains = domains
+            .into_iter()
+            .map(|d| d.trim_start_matches('.').to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    /// Largest file or URL body, in bytes
+    pub fn max_source_bytes(&self) -> usize {
+        self.max_source_bytes
+    }
+
+    /// Most files one directory or glob may match
+    pub fn max_files(&self) -> usize {
+        self.max_files
+    }
+
+    /// Check that `path` resolves into an allowed root, returning its
+    /// canonical form
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemorizeLimitError::PathNotAllowed`] for paths outside the
+    /// roots, or that cannot be resolved while roots are set.
+    pub fn check_path(&self, path: &Path) -> Result<PathBuf, MemorizeLimitError> {
+        let Some(roots) = &self.allowed_roots else {
+            return Ok(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+        };
+        match path.canonicalize() {
+            Ok(canonical) if roots.iter().any(|root| canonical.starts_with(root)) => Ok(canonical),
+            _ => Err(MemorizeLimitError::PathNotAllowed {
+                path: path.display().to_string(),
+                roots: if roots.is_empty() {
+                    "none could be resolved".to_string()
+                } else {
+                    roots
+                        .iter()
+                        .map(|root| root.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            }),
+        }
+    }
+
+    /// Check that the directory a glob pattern starts in is allowed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemorizeLimitError::PathNotAllowed`] when it is not.
+    pub fn check_glob(&self, pattern: &str) -> Result<(), MemorizeLimitError> {
+        if self.allowed_roots.is_none() {
+            return Ok(());
+        }
+        let literal = pattern
+            .find(['*', '?', '[', '{'])
+            .map_or(pattern, |wildcard| &pattern[..wildcard]);
+        let base = match literal.rfind('/') {
+            Some(0) => "/",
+            Some(slash) => &literal[..slash],
+            None => ".",
+        };
+        self.check_path(Path::new(base)).map(drop)
+    }
+
+    /// Check a URL's scheme and host
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unparsable URLs and disallowed schemes or hosts.
+    pub fn check_url(&self, url: &str) -> Result<(), MemorizeLimitError> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| MemorizeLimitError::InvalidUrl {
+            url: url.to_string(),
+            reason: e.to_string(),
+        })?;
+        if !self
+            .url_schemes
+            .iter()
+            .any(|scheme| scheme == parsed.scheme())
+        {
+            return Err(MemorizeLimitError::SchemeNotAllowed {
+                url: url.to_string(),
+                scheme: parsed.scheme().to_string(),
+            });
+        }
+        if self.allowed_domains.is_empty() {
+            return Ok(());
+        }
+        let host = parsed.host_str().unwrap_or_default().to_ascii_lowercase();
+        let allowed = self.allowed_domains.iter().any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        });
+        if allowed {
+            Ok(())
+        } else {
+            Err(MemorizeLimitError::DomainNotAllowed {
+                url: url.to_string(),
+                domain: host,
+            })
+        }
+    }
+
+    /// Check the size of `source`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemorizeLimitError::TooLarge`] past the per-source limit.
+    pub fn check_size(&self, source: &str, size: usize) -> Result<(), MemorizeLimitError> {
+        if size > self.max_source_bytes {
+            return Err(MemorizeLimitError::TooLarge {
+                input: source.to_string(),
+                size,
+                limit: self.max_source_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that a directory or glob has not matched too many files
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemorizeLimitError::TooManyFiles`] once `files` exceeds the limit.
+    pub fn check_file_count(&self, pattern: &str, files: usize) -> Result<(), MemorizeLimitError> {
+        if files > self.max_files {
+            return Err(MemorizeLimitError::TooManyFiles {
+                pattern: pattern.to_string(),
+                limit: self.max_files,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check a loaded source's size and that it is text
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemorizeLimitError::TooLarge`] or [`MemorizeLimitError::NotText`].
+    pub fn check_content(&self, source: &str, data: &str) -> Result<(), MemorizeLimitError> {
+        self.check_size(source, data.len())?;
+        if data.contains('\0') {
+            return Err(MemorizeLimitError::NotText {
+                input: source.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn comma_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn env_usize(name: &str) -> Option<usize> {
+    let value = std::env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(n) => Some(n),
+        Err(_) => {
+            log::warn!("Invalid {name}={value:?}, using the default");
+            None
+        }
+    }
+}
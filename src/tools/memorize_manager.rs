@@ -1,9 +1,14 @@
 //! Memorize Session Manager - Async session pattern for long-running memorize operations
 //!
 //! Based on filesystem search pattern (one-shot async task lifecycle):
-//! 1. Client calls memorize() → spawns background task → returns session_id
-//! 2. Background task: resolve_content → generate_embedding → store_in_db
-//! 3. Client polls check_memorize_status(session_id) to monitor progress
+//! 1. Client calls memorize() → queues a background task → returns session_id
+//! 2. Background task waits for an ingestion slot (see [`super::ingestion_queue`]),
+//!    then: resolve_content → generate_embedding → store_in_db. Source files
+//!    and (when `KODEGEN_CHUNK_STRATEGY` is set) prose files are split into
+//!    chunks stored as separate memories linked by their source path. What
+//!    the resolver may read is bounded by [`super::memorize_limits`]
+//! 3. Client polls check_memorize_status(session_id) to monitor progress, or
+//!    subscribes to its stage transitions (see [`MemorizeSessionManager::subscribe`])
 //! 4. Cleanup task removes old sessions (60s interval)
 
 use serde::{Deserialize, Serialize};
@@ -12,21 +17,33 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
-use crate::builders::document::DocumentBuilder;
+use tokio::sync::{Notify, RwLock, broadcast};
+use crate::builders::document::DocumentBatch;
 use uuid::Uuid;
 
+use super::idempotency::{self, IdempotencyCache};
+use super::ingestion_queue::{IngestionQueue, QueueFull, QueueTicket};
+use super::memorize_limits::{MemorizeLimitError, MemorizeLimits};
+use super::inline_content::InlineContent;
+use crate::core::tokenizer::shared_tokenizer_cache;
+use crate::domain::model::traits::CandleModel;
+use crate::memory::core::chunking::{CodeLanguage, CodeSplitter, TextSplitter};
+use crate::memory::core::manager::coordinator::{EmbeddingTasks, MemoryCoordinator};
 use crate::memory::core::manager::pool::CoordinatorPool;
+use crate::memory::core::primitives::chunk::MemoryChunk;
 use crate::memory::core::primitives::metadata::MemoryMetadata;
 use crate::domain::memory::primitives::types::MemoryTypeEnum;
-use crate::domain::context::provider::{CandleContext, CandleFile, CandleFiles};
 use crate::domain::context::CandleDocument as Document;
+use crate::domain::event::{AgentEvent, SessionState};
 use tokio_stream::StreamExt;
 
 // ============================================================================
 // CONFIGURATION CONSTANTS
 // ============================================================================
 
+/// Stage transitions buffered per session for subscribers that fall behind
+const PROGRESS_EVENT_CAPACITY: usize = 32;
+
 /// Helper function to get current Unix timestamp
 /// Returns 0 if system clock is before UNIX epoch (defensive fallback)
 fn unix_timestamp_now() -> u64 {
@@ -45,6 +62,9 @@ const COMPLETED_SESSION_RETENTION_SECS: u64 = 30;
 /// Failed session retention time in seconds (5 minutes for debugging)
 const FAILED_SESSION_RETENTION_SECS: u64 = 300;
 
+/// Longest a caller may wait for a session to finish
+pub const MAX_SESSION_WAIT: Duration = Duration::from_secs(300);
+
 // ============================================================================
 // SESSION STATUS TYPES
 // ============================================================================
@@ -92,20 +112,30 @@ pub struct MemorizeSession {
     pub id: String,
     /// Library name for storage
     pub library: String,
-    /// Original content input
+    /// Original content input (a description for inline content)
     pub content_input: String,
+    /// Decoded content pushed by the client, loaded instead of `content_input`
+    pub inline_content: Option<Arc<InlineContent>>,
+    /// Embedding task overriding the library's for this session
+    pub embedding_task: Option<String>,
     /// Current status
     pub status: Arc<RwLock<MemorizeStatus>>,
     /// Created memory ID (when completed)
     pub memory_id: Arc<RwLock<Option<String>>>,
     /// Error message (when failed)
     pub error: Arc<RwLock<Option<String>>>,
+    /// Sources that failed to load while the rest was memorized
+    pub warnings: Arc<RwLock<Vec<String>>>,
     /// Session start time
     pub start_time: Instant,
     /// Progress tracking
     pub progress: Arc<RwLock<MemorizeProgress>>,
     /// Last status check time (for cleanup)
     pub last_read_time: Arc<AtomicU64>,
+    /// Notified when the session completes or fails
+    pub finished: Arc<Notify>,
+    /// Every stage change, with the status at the time
+    pub updates: broadcast::Sender<(MemorizeStatus, MemorizeProgress)>,
 }
 
 impl MemorizeSession {
@@ -115,21 +145,51 @@ impl MemorizeSession {
             id,
             library,
             content_input,
+            inline_content: None,
+            embedding_task: None,
             status: Arc::new(RwLock::new(MemorizeStatus::InProgress)),
             memory_id: Arc::new(RwLock::new(None)),
             error: Arc::new(RwLock::new(None)),
+            warnings: Arc::new(RwLock::new(Vec::new())),
             start_time: Instant::now(),
             progress: Arc::new(RwLock::new(MemorizeProgress::default())),
             last_read_time: Arc::new(AtomicU64::new(unix_timestamp_now())),
+            finished: Arc::new(Notify::new()),
+            updates: broadcast::channel(PROGRESS_EVENT_CAPACITY).0,
         }
     }
 
+    /// Load from decoded inline content instead of resolving `content_input`
+    #[must_use]
+    pub fn with_inline_content(mut self, content: InlineContent) -> Self {
+        self.content_input = content.describe();
+        self.inline_content = Some(Arc::new(content));
+        self
+    }
+
+    /// Embed this session's content with `task` instead of the library's task
+    #[must_use]
+    pub fn with_embedding_task(mut self, task: Option<String>) -> Self {
+        self.embedding_task = task;
+        self
+    }
+
     /// Update progress stage
     pub async fn update_progress(&self, stage: &str, files_loaded: usize, total_size_bytes: usize) {
         let mut progress = self.progress.write().await;
         progress.stage = stage.to_string();
         progress.files_loaded = files_loaded;
         progress.total_size_bytes = total_size_bytes;
+        drop(progress);
+        self.publish().await;
+    }
+
+    /// Send the current status and progress to subscribers
+    async fn publish(&self) {
+        let status = self.status.read().await.clone();
+        let progress = self.progress.read().await.clone();
+        // No subscribers is not an error
+        let _ = self.updates.send((status, progress));
     }
 
     /// Mark session as completed
@@ -137,12 +197,15 @@ impl MemorizeSession {
         *self.status.write().await = MemorizeStatus::Completed;
         *self.memory_id.write().await = Some(memory_id);
         self.update_progress("Completed", 0, 0).await;
+        self.finished.notify_waiters();
     }
 
     /// Mark session as failed
     pub async fn fail(&self, error_msg: String) {
         *self.status.write().await = MemorizeStatus::Failed;
         *self.error.write().await = Some(error_msg);
+        self.publish().await;
+        self.finished.notify_waiters();
     }
 
     /// Update last read time (for cleanup tracking)
@@ -151,6 +214,103 @@ impl MemorizeSession {
     }
 }
 
+/// Content to memorize
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemorizeContent {
+    /// Path, glob, URL, GitHub reference or literal text, resolved by the server
+    Reference(String),
+    /// Bytes pushed by the client
+    Inline(InlineContent),
+}
+
+impl MemorizeContent {
+    /// Fingerprint for idempotency checks
+    fn fingerprint(&self, library: &str) -> u64 {
+        match self {
+            Self::Reference(input) => idempotency::fingerprint(&[library, input]),
+            Self::Inline(inline) => {
+                use std::hash::{DefaultHasher, Hash, Hasher};
+                let mut hasher = DefaultHasher::new();
+                inline.bytes().hash(&mut hasher);
+                let digest = hasher.finish().to_string();
+                idempotency::fingerprint(&[library, inline.mime_type(), &digest])
+            }
+        }
+    }
+}
+
+impl From<String> for MemorizeContent {
+    fn from(input: String) -> Self {
+        Self::Reference(input)
+    }
+}
+
+/// Content resolved for a session
+#[derive(Debug, Default)]
+struct LoadedContent {
+    /// Prose sections, stored together as one memory
+    sections: Vec<String>,
+    /// Chunks of source files and, with a text splitter, of prose files
+    chunks: Vec<MemoryChunk>,
+    /// Splits prose files into chunks; without one they become sections
+    text_splitter: Option<TextSplitter>,
+    /// Number of files read
+    files: usize,
+    /// Sources that failed to load, one line each
+    failures: Vec<String>,
+}
+
+impl LoadedContent {
+    fn new(text_splitter: Option<TextSplitter>) -> Self {
+        Self {
+            text_splitter,
+            ..Self::default()
+        }
+    }
+
+    fn from_text(text: String) -> Self {
+        Self {
+            sections: vec![text],
+            ..Self::default()
+        }
+    }
+
+    /// Add a loaded file: source files are split into chunks, and so is
+    /// prose when there is a text splitter; otherwise it is appended to the
+    /// prose (under a `=== path ===` header if `header`)
+    fn push_file(&mut self, path: &str, data: String, header: bool) {
+        self.files += 1;
+        if let Some(language) = CodeLanguage::from_path(path) {
+            self.chunks
+                .extend(CodeSplitter::default().split(path, &data, language));
+        } else if let Some(splitter) = &self.text_splitter {
+            self.chunks.extend(splitter.split(path, &data));
+        } else if header {
+            self.sections.push(format!("=== {} ===\n{}", path, data));
+        } else {
+            self.sections.push(data);
+        }
+    }
+
+    fn text(&self) -> String {
+        self.sections.join("\n\n")
+    }
+
+    fn size_bytes(&self) -> usize {
+        self.sections.iter().map(String::len).sum::<usize>()
+            + self.chunks.iter().map(|c| c.content.len()).sum::<usize>()
+    }
+}
+
+/// Several whitespace-separated URLs, when that is all `input` contains
+fn url_list(input: &str) -> Option<Vec<&str>> {
+    let urls: Vec<&str> = input.split_whitespace().collect();
+    let all_urls = urls
+        .iter()
+        .all(|url| url.starts_with("http://") || url.starts_with("https://"));
+    (urls.len() > 1 && all_urls).then_some(urls)
+}
+
 // ============================================================================
 // STATUS RESPONSE (for check_memorize_status tool)
 // ============================================================================
@@ -174,6 +334,35 @@ pub struct MemorizeStatusResponse {
     /// Error message (when failed)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Sources that failed to load while the rest was memorized
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// 1-based position in the ingestion queue while waiting to start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+}
+
+impl From<MemorizeStatus> for SessionState {
+    fn from(status: MemorizeStatus) -> Self {
+        match status {
+            MemorizeStatus::InProgress => SessionState::InProgress,
+            MemorizeStatus::Completed => SessionState::Completed,
+            MemorizeStatus::Failed => SessionState::Failed,
+        }
+    }
+}
+
+impl From<MemorizeStatusResponse> for AgentEvent {
+    fn from(response: MemorizeStatusResponse) -> Self {
+        AgentEvent::Session {
+            session_id: response.session_id,
+            target: Some(response.library),
+            state: response.status.into(),
+            stage: Some(response.progress.stage),
+            result: response.memory_id,
+            error: response.error,
+        }
+    }
 }
 
 // ============================================================================
@@ -185,6 +374,25 @@ pub struct MemorizeStatusResponse {
 pub struct MemorizeSessionManager {
     sessions: Arc<RwLock<HashMap<String, Arc<MemorizeSession>>>>,
     pool: Arc<CoordinatorPool>,
+    /// Sessions started with an idempotency key; entries outlive session cleanup
+    idempotency: Arc<IdempotencyCache<Arc<MemorizeSession>>>,
+    /// Limits how many sessions load and embed content at once
+    queue: Arc<IngestionQueue>,
+    /// Splits prose files into chunks; `None` stores them as one memory
+    text_splitter: Option<TextSplitter>,
+    /// Bounds on the files and URLs sessions may read
+    limits: Arc<MemorizeLimits>,
+}
+
+/// Result of starting (or replaying) a memorize session
+#[derive(Debug, Clone)]
+pub struct MemorizeStart {
+    /// Session ID
+    pub session_id: String,
+    /// Current session status
+    pub status: MemorizeStatus,
+    /// Whether an earlier session was returned for a repeated idempotency key
+    pub replayed: bool,
 }
 
 impl MemorizeSessionManager {
@@ -193,24 +401,151 @@ impl MemorizeSessionManager {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             pool,
+            idempotency: Arc::new(IdempotencyCache::default()),
+            queue: Arc::new(IngestionQueue::from_env()),
+            text_splitter: TextSplitter::from_env(),
+            limits: Arc::new(MemorizeLimits::from_env()),
         }
     }
 
+    /// Split prose files with `splitter` instead of the one configured from
+    /// the environment; `None` stores each session's prose as one memory
+    ///
+    /// Unless `splitter` already has a tokenizer, chunk sizes are counted
+    /// with the library's embedding model tokenizer.
+    #[must_use]
+    pub fn with_text_chunking(mut self, splitter: Option<TextSplitter>) -> Self {
+        self.text_splitter = splitter;
+        self
+    }
+
+    /// Enforce `limits` instead of the ones configured from the environment
+    #[must_use]
+    pub fn with_limits(mut self, limits: MemorizeLimits) -> Self {
+        self.limits = Arc::new(limits);
+        self
+    }
+
+    /// Use `queue` instead of the one configured from the environment
+    #[must_use]
+    pub fn with_ingestion_queue(mut self, queue: IngestionQueue) -> Self {
+        self.queue = Arc::new(queue);
+        self
+    }
+
+    /// The ingestion queue sessions wait in
+    pub fn ingestion_queue(&self) -> &IngestionQueue {
+        &self.queue
+    }
+
+    /// Set the embedding task used for `library` from now on
+    ///
+    /// Applies to every later memorize and recall on the library; see
+    /// [`EmbeddingTasks::for_task`] for accepted tasks. Fails once the
+    /// library has memories, unless `task` is the one already in effect.
+    pub async fn set_library_embedding_task(&self, library: &str, task: &str) -> anyhow::Result<()> {
+        let coordinator = self.pool.get_coordinator(library).await?;
+        coordinator
+            .set_embedding_tasks(EmbeddingTasks::for_task(task))
+            .await?;
+        Ok(())
+    }
+
+    /// Set the minimum recall relevance for `library`; `None` removes it
+    pub async fn set_library_min_score(&self, library: &str, min_score: Option<f32>) -> anyhow::Result<()> {
+        let coordinator = self.pool.get_coordinator(library).await?;
+        coordinator.set_recall_min_score(min_score).await?;
+        Ok(())
+    }
+
     /// Start new memorize session (returns session_id immediately)
+    ///
+    /// Fails with [`QueueFull`] when the ingestion queue is full.
     pub async fn start_memorize_session(
         &self,
         library: String,
         content: String,
     ) -> anyhow::Result<String> {
+        Ok(self
+            .create_session(library, MemorizeContent::Reference(content), None)
+            .await?
+            .id
+            .clone())
+    }
+
+    /// Start a memorize session, deduplicated by `idempotency_key`
+    ///
+    /// A key seen within the last [`idempotency::IDEMPOTENCY_KEY_TTL`] returns
+    /// the original session and its current status instead of starting a new
+    /// one. Reusing a key with a different library or content is an error, as
+    /// is starting a session while the ingestion queue is full ([`QueueFull`]).
+    /// `embedding_task` overrides the library's embedding task for this
+    /// session's content.
+    pub async fn start_memorize_session_idempotent(
+        &self,
+        library: String,
+        content: MemorizeContent,
+        idempotency_key: Option<String>,
+        embedding_task: Option<String>,
+    ) -> anyhow::Result<MemorizeStart> {
+        let Some(key) = idempotency_key else {
+            let session = self.create_session(library, content, embedding_task).await?;
+            return Ok(MemorizeStart {
+                session_id: session.id.clone(),
+                status: MemorizeStatus::InProgress,
+                replayed: false,
+            });
+        };
+
+        let fingerprint = content.fingerprint(&library);
+        let started = self
+            .idempotency
+            .get_or_try_insert_with(&key, fingerprint, || async {
+                Ok::<_, anyhow::Error>(self.create_session(library, content, embedding_task).await?)
+            })
+            .await?;
+
+        if started.replayed {
+            log::debug!(
+                "Replaying memorize session {} for idempotency key {}",
+                started.value.id,
+                key
+            );
+            started.value.touch();
+        }
+
+        Ok(MemorizeStart {
+            session_id: started.value.id.clone(),
+            status: started.value.status.read().await.clone(),
+            replayed: started.replayed,
+        })
+    }
+
+    /// Queue a new session and spawn its background task
+    async fn create_session(
+        &self,
+        library: String,
+        content: MemorizeContent,
+        embedding_task: Option<String>,
+    ) -> Result<Arc<MemorizeSession>, QueueFull> {
         // Generate unique session ID using UUID v4
         let session_id = Uuid::new_v4().to_string();
 
         // Create session
-        let session = Arc::new(MemorizeSession::new(
-            session_id.clone(),
-            library.clone(),
-            content.clone(),
-        ));
+        let session = match content {
+            MemorizeContent::Reference(input) => {
+                MemorizeSession::new(session_id.clone(), library, input)
+            }
+            MemorizeContent::Inline(inline) => {
+                MemorizeSession::new(session_id.clone(), library, String::new())
+                    .with_inline_content(inline)
+            }
+        };
+        let session = Arc::new(session.with_embedding_task(embedding_task));
+
+        // Reject before anything is stored when the queue is full
+        let ticket = self.queue.enqueue(&session_id)?;
+        session.update_progress("Queued", 0, 0).await;
 
         // Store session
         self.sessions
@@ -219,9 +554,9 @@ impl MemorizeSessionManager {
             .insert(session_id.clone(), session.clone());
 
         // Spawn background task
-        self.spawn_memorize_task(session.clone());
+        self.spawn_memorize_task(session.clone(), ticket);
 
-        Ok(session_id)
+        Ok(session)
     }
 
     /// Get status for session
@@ -234,14 +569,131 @@ impl MemorizeSessionManager {
         // Update last read time
         session.touch();
 
-        // Build response
+        Ok(self.status_of(session).await)
+    }
+
+    /// Wait up to `timeout` (capped at [`MAX_SESSION_WAIT`]) for a session to
+    /// complete or fail, then return its status
+    ///
+    /// A session still running at the deadline is returned as `IN_PROGRESS`.
+    pub async fn wait_for_session(
+        &self,
+        session_id: &str,
+        timeout: Duration,
+    ) -> anyhow::Result<MemorizeStatusResponse> {
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        session.touch();
+
+        let finished = async {
+            loop {
+                let notified = session.finished.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if *session.status.read().await != MemorizeStatus::InProgress {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        if tokio::time::timeout(timeout.min(MAX_SESSION_WAIT), finished).await.is_err() {
+            log::debug!("Timed out waiting for memorize session {}", session_id);
+        }
+
+        Ok(self.status_of(&session).await)
+    }
+
+    /// Stage transitions of a session as they happen
+    ///
+    /// The stream opens with the session's current status, then yields one
+    /// status per stage change ("Loading content", "Generating embeddings",
+    /// "Storing in database", ...) and ends once the session completes or
+    /// fails. Each item counts as a status read.
+    pub async fn subscribe(
+        &self,
+        session_id: &str,
+    ) -> anyhow::Result<impl futures::Stream<Item = MemorizeStatusResponse> + Send + use<>> {
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        session.touch();
+
+        // Subscribe before reading the current status so no change is missed
+        let mut updates = session.updates.subscribe();
+        let manager = self.clone();
+        Ok(crate::async_stream::spawn_stream(move |tx| async move {
+            let current = manager.status_of(&session).await;
+            let mut last = (current.status.clone(), current.progress.stage.clone());
+            if tx.send(current).is_err() || last.0 != MemorizeStatus::InProgress {
+                return;
+            }
+
+            loop {
+                let (status, progress) = match updates.recv().await {
+                    Ok(update) => update,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::debug!(
+                            "Memorize subscriber for session {} skipped {} updates",
+                            session.id,
+                            skipped
+                        );
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                };
+                // The opening status may already include this change
+                if (status.clone(), progress.stage.clone()) == last {
+                    continue;
+                }
+                last = (status.clone(), progress.stage.clone());
+
+                session.touch();
+                let mut response = manager.status_of(&session).await;
+                response.status = status;
+                response.progress = progress;
+                let finished = response.status != MemorizeStatus::InProgress;
+                if tx.send(response).is_err() || finished {
+                    return;
+                }
+            }
+        }))
+    }
+
+    /// Status of every tracked session, oldest first
+    ///
+    /// Unlike [`Self::get_status`] this does not count as a read, so
+    /// observers (dashboards, metrics) do not delay session cleanup.
+    pub async fn list_sessions(&self) -> Vec<MemorizeStatusResponse> {
+        let sessions = self.sessions.read().await;
+        let mut ordered: Vec<_> = sessions.values().cloned().collect();
+        ordered.sort_by_key(|s| s.start_time);
+
+        let mut statuses = Vec::with_capacity(ordered.len());
+        for session in &ordered {
+            statuses.push(self.status_of(session).await);
+        }
+        statuses
+    }
+
+    /// Build a status response for a session
+    async fn status_of(&self, session: &MemorizeSession) -> MemorizeStatusResponse {
         let status = session.status.read().await.clone();
         let memory_id = session.memory_id.read().await.clone();
         let error = session.error.read().await.clone();
+        let warnings = session.warnings.read().await.clone();
         let progress = session.progress.read().await.clone();
         let runtime_ms = session.start_time.elapsed().as_millis() as u64;
 
-        Ok(MemorizeStatusResponse {
+        MemorizeStatusResponse {
             session_id: session.id.clone(),
             status,
             memory_id,
@@ -249,14 +701,27 @@ impl MemorizeSessionManager {
             progress,
             runtime_ms,
             error,
-        })
+            warnings,
+            queue_position: self.queue.position(&session.id),
+        }
     }
 
     /// Spawn background task to execute memorize operation
-    fn spawn_memorize_task(&self, session: Arc<MemorizeSession>) {
+    fn spawn_memorize_task(&self, session: Arc<MemorizeSession>, ticket: QueueTicket) {
         let pool = self.pool.clone();
-
-        tokio::spawn(async move {
+        let text_splitter = self.text_splitter.clone();
+        let limits = self.limits.clone();
+        let task_name = format!("memorize session {}", session.id);
+
+        crate::runtime::supervisor().spawn(task_name, async move {
+            // Held until the session completes or fails
+            let _slot = match ticket.acquire().await {
+                Ok(slot) => slot,
+                Err(e) => {
+                    session.fail(e.to_string()).await;
+                    return;
+                }
+            };
             log::info!(
                 "Memorize task started for session {} (library: {})",
                 session.id,
@@ -266,41 +731,52 @@ impl MemorizeSessionManager {
             // Stage 1: Loading content
             session.update_progress("Loading content", 0, 0).await;
 
-            match Self::resolve_content(&session.content_input).await {
-                Ok(resolved_content) => {
-                    let content_size = resolved_content.len();
+            let text_splitter = match text_splitter {
+                Some(splitter) => Some(Self::count_with_model(&pool, &session, splitter).await),
+                None => None,
+            };
+            match Self::load_content(&session, text_splitter, &limits).await {
+                Ok(loaded) => {
+                    if !loaded.failures.is_empty() {
+                        log::warn!(
+                            "Session {}: {} source(s) failed to load",
+                            session.id,
+                            loaded.failures.len()
+                        );
+                        *session.warnings.write().await = loaded.failures.clone();
+                    }
+                    let content_size = loaded.size_bytes();
                     log::debug!(
-                        "Content loaded for session {}: {} bytes",
+                        "Content loaded for session {}: {} bytes from {} files",
                         session.id,
-                        content_size
+                        content_size,
+                        loaded.files
                     );
 
                     // Stage 2: Generating embeddings
                     session
-                        .update_progress("Generating embeddings", 1, content_size)
+                        .update_progress("Generating embeddings", loaded.files, content_size)
                         .await;
 
                     // Get coordinator for library
                     match pool.get_coordinator(&session.library).await {
                         Ok(coordinator) => {
+                            let coordinator = coordinator
+                                .with_embedding_task(session.embedding_task.as_deref());
+
                             // Stage 3: Storing in database
                             session
-                                .update_progress("Storing in database", 1, content_size)
+                                .update_progress("Storing in database", loaded.files, content_size)
                                 .await;
 
-                            // Store memory
-                            let metadata = MemoryMetadata::default();
-                            match coordinator
-                                .add_memory(resolved_content, MemoryTypeEnum::LongTerm, Some(metadata))
-                                .await
-                            {
-                                Ok(created) => {
+                            match Self::store_content(&coordinator, loaded).await {
+                                Ok(memory_id) => {
                                     log::info!(
                                         "Memorize task completed for session {}: memory_id = {}",
                                         session.id,
-                                        created.id()
+                                        memory_id
                                     );
-                                    session.complete(created.id().to_string()).await;
+                                    session.complete(memory_id).await;
                                 }
                                 Err(e) => {
                                     log::error!(
@@ -336,12 +812,217 @@ impl MemorizeSessionManager {
         });
     }
 
+    /// Store loaded content and return the ID reported for the session
+    ///
+    /// Prose is stored as one memory; file chunks are upserted under their
+    /// deterministic IDs. The prose memory's ID is reported when there
+    /// is one, otherwise the first chunk's.
+    async fn store_content(
+        coordinator: &MemoryCoordinator,
+        loaded: LoadedContent,
+    ) -> anyhow::Result<String> {
+        // Recalls taken while this runs do not see its partial writes
+        let generation = coordinator.begin_write();
+        let mut metadata = MemoryMetadata::default();
+        generation.stamp(&mut metadata);
+
+        let text = loaded.text();
+        let mut memory_id = None;
+
+        if !text.is_empty() {
+            let created = coordinator
+                .add_memory(text, MemoryTypeEnum::LongTerm, Some(metadata.clone()))
+                .await?;
+            memory_id = Some(created.id().to_string());
+        }
+
+        if !loaded.chunks.is_empty() {
+            let upsert = coordinator
+                .upsert_chunks(loaded.chunks, MemoryTypeEnum::LongTerm, Some(metadata))
+                .await?;
+            log::debug!(
+                "Chunks stored: {} created, {} updated, {} unchanged, {} removed",
+                upsert.created,
+                upsert.updated,
+                upsert.unchanged,
+                upsert.removed
+            );
+            if memory_id.is_none() {
+                memory_id = upsert.memories.first().map(|m| m.id().to_string());
+            }
+        }
+
+        memory_id.ok_or_else(|| anyhow::anyhow!("No content to store"))
+    }
+
+    /// `splitter` counting tokens with the embedding model of the session's
+    /// library, or unchanged (counting words) when its tokenizer can't be loaded
+    async fn count_with_model(
+        pool: &CoordinatorPool,
+        session: &MemorizeSession,
+        splitter: TextSplitter,
+    ) -> TextSplitter {
+        if splitter.has_tokenizer() {
+            return splitter;
+        }
+        let model = match pool.get_coordinator(&session.library).await {
+            Ok(coordinator) => coordinator.embedding_model().clone(),
+            Err(_) => pool.embedding_model().clone(),
+        };
+        let registry_key = model.info().registry_key;
+        let tokenizer = match model.huggingface_file(registry_key, "tokenizer.json").await {
+            Ok(path) => shared_tokenizer_cache()
+                .get_or_load(&path)
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match tokenizer {
+            Ok(tokenizer) => splitter.with_tokenizer(tokenizer),
+            Err(e) => {
+                log::warn!(
+                    "Session {}: counting chunk sizes in words, no tokenizer for {}: {}",
+                    session.id,
+                    registry_key,
+                    e
+                );
+                splitter
+            }
+        }
+    }
+
+    /// Load session content: decoded inline bytes, or a resolved reference
+    async fn load_content(
+        session: &MemorizeSession,
+        text_splitter: Option<TextSplitter>,
+        limits: &Arc<MemorizeLimits>,
+    ) -> anyhow::Result<LoadedContent> {
+        match &session.inline_content {
+            Some(inline) => {
+                // PDF extraction is CPU-bound; keep it off the async workers
+                let inline = Arc::clone(inline);
+                let text = tokio::task::spawn_blocking(move || inline.extract_text()).await??;
+                Ok(LoadedContent::from_text(text))
+            }
+            None => match url_list(&session.content_input) {
+                Some(urls) => Self::load_urls(session, &urls, text_splitter, limits).await,
+                None => {
+                    Self::resolve_content(&session.content_input, text_splitter, limits).await
+                }
+            },
+        }
+    }
+
+    /// Load several URLs in parallel, reporting progress per URL
+    ///
+    /// URLs that fail are logged, skipped and reported as warnings; the
+    /// session fails only when none could be loaded. Sections keep the order the URLs were given in.
+    /// The whole session fails if any URL is not allowed by `limits`; a URL
+    /// that redirects somewhere not allowed fails on its own.
+    async fn load_urls(
+        session: &MemorizeSession,
+        urls: &[&str],
+        text_splitter: Option<TextSplitter>,
+        limits: &Arc<MemorizeLimits>,
+    ) -> anyhow::Result<LoadedContent> {
+        for url in urls {
+            limits.check_url(url)?;
+        }
+        let mut stream = std::pin::pin!(Self::url_batch(urls.iter().copied(), limits).stream());
+        let mut documents = HashMap::with_capacity(urls.len());
+        let mut failures = Vec::new();
+        let mut loaded_bytes = 0;
+
+        while let Some((source, result)) = stream.next().await {
+            let result = result.map_err(anyhow::Error::from).and_then(|doc| {
+                limits.check_content(&source.to_string(), &doc.data)?;
+                Ok(doc)
+            });
+            match result {
+                Ok(doc) => {
+                    loaded_bytes += doc.data.len();
+                    documents.insert(source.to_string(), doc.data);
+                }
+                Err(e) => {
+                    log::warn!("Session {}: failed to load {}: {}", session.id, source, e);
+                    failures.push(format!("{}: {}", source, e));
+                }
+            }
+            let done = documents.len() + failures.len();
+            session
+                .update_progress(
+                    &format!("Loading URLs ({}/{})", done, urls.len()),
+                    documents.len(),
+                    loaded_bytes,
+                )
+                .await;
+        }
+
+        if documents.is_empty() {
+            return Err(anyhow::anyhow!("No URLs could be loaded: {}", failures.join("; ")));
+        }
+
+        let mut loaded = LoadedContent {
+            failures,
+            ..LoadedContent::new(text_splitter)
+        };
+        for url in urls {
+            if let Some(data) = documents.remove(*url) {
+                loaded.push_file(url, data, true);
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Batch loader for `urls` that stops reading bodies past the size limit
+    /// and checks every redirect hop against `limits`
+    fn url_batch<'a>(
+        urls: impl IntoIterator<Item = &'a str>,
+        limits: &Arc<MemorizeLimits>,
+    ) -> DocumentBatch {
+        let check = Arc::clone(limits);
+        Document::batch(urls)
+            .max_size(limits.max_source_bytes())
+            .check_urls(move |url| check.check_url(url).map_err(|e| e.to_string()))
+    }
+
+    /// Load one URL as `source`, checked like [`Self::url_batch`]
+    async fn load_url(
+        url: &str,
+        source: &str,
+        text_splitter: Option<TextSplitter>,
+        limits: &Arc<MemorizeLimits>,
+    ) -> anyhow::Result<LoadedContent> {
+        limits.check_url(url)?;
+        let (_, result) = Self::url_batch([url], limits)
+            .load_all()
+            .await
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Failed to load URL: {}", url))?;
+        let doc = result.map_err(|e| anyhow::anyhow!("Failed to load URL {}: {}", url, e))?;
+        limits.check_content(source, &doc.data)?;
+        let mut loaded = LoadedContent::new(text_splitter);
+        loaded.push_file(source, doc.data, false);
+        Ok(loaded)
+    }
+
     /// Smart content resolver (same as memorize.rs)
-    async fn resolve_content(input: &str) -> anyhow::Result<String> {
+    ///
+    /// Files with a recognized source extension are split at definition
+    /// boundaries, and other files and URLs by `text_splitter`, instead of
+    /// being stored whole. Literal text is always one memory.
+    ///
+    /// Paths, URLs (and the URLs they redirect to) and their sizes are
+    /// checked against `limits`; a violation fails the session, except for
+    /// single files of a directory or glob, which are skipped and reported as
+    /// warnings.
+    async fn resolve_content(
+        input: &str,
+        text_splitter: Option<TextSplitter>,
+        limits: &Arc<MemorizeLimits>,
+    ) -> anyhow::Result<LoadedContent> {
         // 1. HTTP/HTTPS URL
         if input.starts_with("http://") || input.starts_with("https://") {
-            let doc = Document::from_url(input).load_async().await;
-            return Ok(doc.data);
+            return Self::load_url(input, input, text_splitter, limits).await;
         }
 
         // 2. GitHub URL/pattern
@@ -362,8 +1043,10 @@ impl MemorizeSessionManager {
                     "README.md".to_string()
                 };
 
-                let doc = Document::from_github(&repo, &path).load_async().await;
-                return Ok(doc.data);
+                // Fetched from the raw host, which is what the limits must allow
+                let source = format!("github.com/{repo}/{path}");
+                let url = format!("https://raw.githubusercontent.com/{repo}/main/{path}");
+                return Self::load_url(&url, &source, text_splitter, limits).await;
             }
 
             return Err(anyhow::anyhow!("Invalid GitHub URL format: {}", input));
@@ -372,68 +1055,125 @@ impl MemorizeSessionManager {
         // 3. File/directory path (check before glob to avoid false positives)
         let path = std::path::Path::new(input);
         if path.exists() {
+            limits.check_path(path)?;
             if path.is_dir() {
                 // Directory: glob all files
                 let glob_pattern = format!("{}/**/*", input.trim_end_matches('/'));
-                let context = CandleContext::<CandleFiles>::glob(&glob_pattern);
-                let mut doc_stream = context.load();
-                let mut all_content = Vec::new();
-
-                while let Some(doc) = doc_stream.next().await {
-                    let file_path = doc
-                        .additional_props
-                        .get("path")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown");
-
-                    all_content.push(format!("=== {} ===\n{}", file_path, doc.data));
-                }
+                let loaded = Self::load_glob(&glob_pattern, text_splitter, limits).await?;
 
-                if all_content.is_empty() {
-                    return Err(anyhow::anyhow!("No files found in directory: {}", input));
+                if loaded.files == 0 {
+                    if loaded.failures.is_empty() {
+                        return Err(anyhow::anyhow!("No files found in directory: {}", input));
+                    }
+                    return Err(anyhow::anyhow!(
+                        "No files could be loaded from directory {}: {}",
+                        input,
+                        loaded.failures.join("; ")
+                    ));
                 }
 
-                return Ok(all_content.join("\n\n"));
+                return Ok(loaded);
             }
 
             if path.is_file() {
-                // Single file
-                let context = CandleContext::<CandleFile>::of(path).await;
-                let mut doc_stream = context.load();
-
-                if let Some(doc) = doc_stream.next().await {
-                    return Ok(doc.data);
-                }
-
-                return Err(anyhow::anyhow!("Failed to load file: {}", input));
+                // Single file, checked before it is read
+                let data = Self::read_file(path, input, limits)
+                    .await
+                    .map_err(|e| anyhow::anyhow!(e))?;
+                let mut loaded = LoadedContent::new(text_splitter);
+                loaded.push_file(input, data, false);
+                return Ok(loaded);
             }
         }
 
         // 4. Glob pattern (only if contains wildcards and path doesn't exist)
         if input.contains('*') || input.contains('?') {
-            let context = CandleContext::<CandleFiles>::glob(input);
-            let mut doc_stream = context.load();
-            let mut all_content = Vec::new();
-
-            while let Some(doc) = doc_stream.next().await {
-                let file_path = doc
-                    .additional_props
-                    .get("path")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown");
-
-                all_content.push(format!("=== {} ===\n{}", file_path, doc.data));
-            }
-
-            if !all_content.is_empty() {
-                return Ok(all_content.join("\n\n"));
+            limits.check_glob(input)?;
+            let loaded = Self::load_glob(input, text_splitter, limits).await?;
+            if loaded.files > 0 {
+                return Ok(loaded);
             }
 
             // Glob matched nothing, fall through to literal text
         }
 
         // 5. Literal text (fallback - includes text with wildcards that don't match files)
-        Ok(input.to_string())
+        Ok(LoadedContent::from_text(input.to_string()))
+    }
+
+    /// Load the files matching `pattern`, failing once it matches more than
+    /// the limit allows
+    ///
+    /// Each file's path and size are checked before it is read; files that
+    /// fail the checks or cannot be read are recorded as failures.
+    async fn load_glob(
+        pattern: &str,
+        text_splitter: Option<TextSplitter>,
+        limits: &MemorizeLimits,
+    ) -> anyhow::Result<LoadedContent> {
+        let paths = glob::glob(pattern)
+            .map_err(|e| anyhow::anyhow!("Glob pattern error for '{}': {}", pattern, e))?;
+        let mut loaded = LoadedContent::new(text_splitter);
+
+        for entry in paths {
+            let path = match entry {
+                Ok(path) if path.is_dir() => continue,
+                Ok(path) => path,
+                Err(e) => {
+                    loaded.failures.push(e.to_string());
+                    continue;
+                }
+            };
+            limits.check_file_count(pattern, loaded.files + loaded.failures.len() + 1)?;
+            let source = path.to_string_lossy().to_string();
+            match Self::read_file(&path, &source, limits).await {
+                Ok(data) => loaded.push_file(&source, data, true),
+                Err(e) => loaded.failures.push(e),
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Read a file of a directory or glob
+    ///
+    /// The path must be in an allowed root and the file's size within the
+    /// limit before any of it is read, and no more than the limit is read
+    /// should it grow meanwhile. Binary files are rejected.
+    async fn read_file(
+        path: &std::path::Path,
+        source: &str,
+        limits: &MemorizeLimits,
+    ) -> Result<String, String> {
+        use tokio::io::AsyncReadExt;
+
+        let read_error = |e: std::io::Error| format!("Failed to read {source}: {e}");
+        limits.check_path(path).map_err(|e| e.to_string())?;
+        let size = tokio::fs::metadata(path).await.map_err(read_error)?.len();
+        limits
+            .check_size(source, usize::try_from(size).unwrap_or(usize::MAX))
+            .map_err(|e| e.to_string())?;
+
+        let max = limits.max_source_bytes();
+        let file = tokio::fs::File::open(path).await.map_err(read_error)?;
+        let mut bytes = Vec::with_capacity(usize::try_from(size).unwrap_or(max).min(max));
+        file.take(u64::try_from(max).unwrap_or(u64::MAX).saturating_add(1))
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(read_error)?;
+        limits
+            .check_size(source, bytes.len())
+            .map_err(|e| e.to_string())?;
+
+        let data = String::from_utf8(bytes).map_err(|_| {
+            MemorizeLimitError::NotText {
+                input: source.to_string(),
+            }
+            .to_string()
+        })?;
+        limits
+            .check_content(source, &data)
+            .map_err(|e| e.to_string())?;
+        Ok(data)
     }
 
     /// Cleanup old sessions
@@ -464,11 +1204,16 @@ impl MemorizeSessionManager {
             log::debug!("Cleaning up memorize session: {}", session_id);
             sessions.remove(&session_id);
         }
+        drop(sessions);
+
+        self.idempotency.purge_expired().await;
     }
 
     /// Start cleanup task (call after all tools registered)
+    ///
+    /// The loop is owned by the task supervisor and stops when shutdown begins.
     pub fn start_cleanup_task(self: Arc<Self>) {
-        tokio::spawn(async move {
+        crate::runtime::supervisor().spawn_cancellable("memorize session cleanup", async move {
             let mut interval = tokio::time::interval(Duration::from_secs(CLEANUP_INTERVAL_SECS));
             loop {
                 interval.tick().await;
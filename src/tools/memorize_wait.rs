@@ -0,0 +1,178 @@
+//! Memorize Wait Tool - Memorize and wait for the result in one call
+//!
+//! Instead of polling check_memorize_status, this tool subscribes to a
+//! session's stage transitions (see [`MemorizeSessionManager::subscribe`])
+//! and returns once the session completes or fails, with every stage it
+//! passed through and when. Nothing is sent before it returns; the stages
+//! come back together in the result. A session still running at the timeout
+//! is returned as IN_PROGRESS; calling again with its session_id resumes the
+//! wait. HTTP clients that want each stage pushed as it happens follow the
+//! session over server-sent events at
+//! `/api/v1/memorize/{session_id}/events` instead.
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{
+    MEMORY_MEMORIZE_WAIT, MemorizeStageEvent, MemorizeWaitArgs, MemorizeWaitOutput,
+    MemorizeWaitPrompts,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_stream::StreamExt;
+
+use super::memorize_manager::{MAX_SESSION_WAIT, MemorizeContent, MemorizeSessionManager, MemorizeStatus};
+
+/// How long to wait for a session unless timeout_ms is given
+pub const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Clone)]
+pub struct MemorizeWaitTool {
+    manager: Arc<MemorizeSessionManager>,
+}
+
+impl MemorizeWaitTool {
+    pub fn new(manager: Arc<MemorizeSessionManager>) -> Self {
+        Self { manager }
+    }
+}
+
+fn status_str(status: &MemorizeStatus) -> &'static str {
+    match status {
+        MemorizeStatus::InProgress => "IN_PROGRESS",
+        MemorizeStatus::Completed => "COMPLETED",
+        MemorizeStatus::Failed => "FAILED",
+    }
+}
+
+impl Tool for MemorizeWaitTool {
+    type Args = MemorizeWaitArgs;
+    type Prompts = MemorizeWaitPrompts;
+
+    fn name() -> &'static str {
+        MEMORY_MEMORIZE_WAIT
+    }
+
+    fn description() -> &'static str {
+        "Memorize content and wait until the session completes or fails, instead of polling \
+         check_memorize_status. Takes the same library and content as memorize (paths, \
+         directories, globs, URLs, GitHub repos or literal text), or a session_id to wait for a \
+         session already started. Sends nothing until it returns; the result lists every stage \
+         observed (Queued, Loading content, Generating embeddings, Storing in database, \
+         Completed) with the session runtime at which it began, plus memory_id or the error. If \
+         the session is still running after timeout_ms (default 120000, max 300000) it returns \
+         IN_PROGRESS; call again with session_id to keep waiting."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        false // Starts a new session unless session_id is given
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl MemorizeWaitTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: MemorizeWaitArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<MemorizeWaitOutput>, McpError> {
+        let session_id = match args.session_id {
+            Some(session_id) => session_id,
+            None => {
+                if args.library.is_empty() || args.content.is_empty() {
+                    return Err(McpError::Other(anyhow::anyhow!(
+                        "library and content are required unless session_id is set"
+                    )));
+                }
+                self.manager
+                    .start_memorize_session_idempotent(
+                        args.library,
+                        MemorizeContent::Reference(args.content),
+                        None,
+                        None,
+                    )
+                    .await
+                    .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to start memorize session: {}", e)))?
+                    .session_id
+            }
+        };
+
+        let updates = self
+            .manager
+            .subscribe(&session_id)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to wait for session: {}", e)))?;
+        tokio::pin!(updates);
+
+        let timeout = args
+            .timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_WAIT_TIMEOUT)
+            .min(MAX_SESSION_WAIT);
+        let deadline = Instant::now() + timeout;
+
+        let mut stages = Vec::new();
+        let mut last = None;
+        while let Ok(Some(update)) =
+            tokio::time::timeout(deadline.saturating_duration_since(Instant::now()), updates.next()).await
+        {
+            stages.push(MemorizeStageEvent {
+                stage: update.progress.stage.clone(),
+                status: status_str(&update.status).to_string(),
+                runtime_ms: update.runtime_ms,
+                files_loaded: update.progress.files_loaded,
+                total_size_bytes: update.progress.total_size_bytes,
+            });
+            last = Some(update);
+        }
+        let last = last.ok_or_else(|| McpError::Other(anyhow::anyhow!("Session not found: {}", session_id)))?;
+
+        let status = status_str(&last.status);
+        let mut summary = match last.status {
+            MemorizeStatus::InProgress => format!(
+                "⏳ Memorization still running after {:.1}s\n\nSession: {}\nLibrary: {}",
+                timeout.as_secs_f64(),
+                last.session_id,
+                last.library
+            ),
+            MemorizeStatus::Completed => format!(
+                "✓ Memorization completed\n\nSession: {}\nLibrary: {}\nMemory ID: {}",
+                last.session_id,
+                last.library,
+                last.memory_id.as_deref().unwrap_or("unknown")
+            ),
+            MemorizeStatus::Failed => format!(
+                "✗ Memorization failed\n\nSession: {}\nLibrary: {}\nError: {}",
+                last.session_id,
+                last.library,
+                last.error.as_deref().unwrap_or("Unknown error")
+            ),
+        };
+        summary.push_str("\n\nStages:");
+        for stage in &stages {
+            summary.push_str(&format!(
+                "\n- {:>7.1}s {}",
+                stage.runtime_ms as f64 / 1000.0,
+                stage.stage
+            ));
+        }
+        for warning in &last.warnings {
+            summary.push_str(&format!("\nWarning: {warning}"));
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            MemorizeWaitOutput {
+                session_id: last.session_id,
+                library: last.library,
+                status: status.to_string(),
+                stages,
+                memory_id: last.memory_id,
+                error: last.error,
+                warnings: last.warnings,
+            },
+        ))
+    }
+}
@@ -1,13 +1,66 @@
 //! Memory tools for candle-agent MCP server
 
+pub mod idempotency;
+pub mod ingestion_queue;
+pub mod inline_content;
 pub mod memorize;
+pub mod memorize_limits;
 pub mod memorize_manager;
+pub mod memorize_wait;
 pub mod check_memorize_status;
+pub mod dump_library;
+pub mod dump_manager;
+pub mod embed;
+pub mod find_duplicates;
+pub mod history_index;
 pub mod recall;
+pub mod redact_message;
+pub mod search_history;
+pub mod stdio_server;
+pub mod summarize_manager;
+pub mod summarize_session;
+pub mod tool_audit;
+pub mod unused_memories;
+pub mod update_memory;
 pub mod list_memory_libraries;
+pub mod list_models;
+pub mod pool_status;
+pub mod workflow_run;
 
 pub use memorize::MemorizeTool;
-pub use memorize_manager::MemorizeSessionManager;
+pub use idempotency::{IdempotencyCache, IdempotencyConflict, Idempotent};
+pub use ingestion_queue::{IngestionQueue, QueueClosed, QueueFull, QueueTicket};
+pub use inline_content::{InlineContent, InlineContentError};
+pub use memorize_limits::{MemorizeLimitError, MemorizeLimits};
+pub use memorize_manager::{MemorizeContent, MemorizeSessionManager, MemorizeStart};
+pub use memorize_wait::MemorizeWaitTool;
 pub use check_memorize_status::CheckMemorizeStatusTool;
+pub use dump_library::DumpLibraryTool;
+pub use dump_manager::DumpSessionManager;
+pub use embed::EmbedTool;
+pub use find_duplicates::FindDuplicatesTool;
+pub use history_index::HistoryIndexManager;
 pub use recall::RecallTool;
+pub use redact_message::RedactMessageTool;
+pub use search_history::SearchHistoryTool;
+pub use stdio_server::{StdioServer, register_all_tools, served_memorize_manager};
+pub use summarize_manager::SummarizeSessionManager;
+pub use summarize_session::SummarizeSessionTool;
+pub use tool_audit::ToolAuditTool;
+pub use unused_memories::UnusedMemoriesTool;
+pub use update_memory::UpdateMemoryTool;
 pub use list_memory_libraries::ListMemoryLibrariesTool;
+pub use list_models::ListModelsTool;
+pub use pool_status::PoolStatusTool;
+pub use workflow_run::WorkflowRunTool;
+
+/// Run a tool's `execute` body under a request ID
+///
+/// MCP calls arrive without one, so a fresh ID is assigned unless the call
+/// is already inside a request scope. Queries and embedding requests made by
+/// the tool are tagged with it (see [`crate::runtime::request_id`]).
+pub(crate) async fn with_tool_request_id<T>(
+    body: impl std::future::Future<Output = Result<T, kodegen_mcp_schema::McpError>>,
+) -> Result<T, kodegen_mcp_schema::McpError> {
+    crate::runtime::ensure_request_id(body).await
+}
@@ -0,0 +1,102 @@
+//! Pool Status Tool - Report what each model worker is doing
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::candle::{PoolStatusArgs, PoolStatusOutput, PoolStatusPrompts, CANDLE_POOL_STATUS};
+
+use crate::capability::registry::pool::{
+    ModelLoadStatus, ModelResidency, PoolWorkerStatus, WorkerActivity, all_model_status,
+    all_worker_status,
+};
+
+#[derive(Clone, Default)]
+pub struct PoolStatusTool;
+
+impl PoolStatusTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tool for PoolStatusTool {
+    type Args = PoolStatusArgs;
+    type Prompts = PoolStatusPrompts;
+
+    fn name() -> &'static str {
+        CANDLE_POOL_STATUS
+    }
+
+    fn description() -> &'static str {
+        "Report the status of every loaded model worker: pool capability, registry key, state \
+         (loading, idle, busy, stopped, failed), requests in flight, requests served and failed, \
+         average latency, last error (including model load failures), last activity and \
+         reserved memory, plus each model's residency (loaded, loading, or unloaded after its \
+         keep-alive expired). Use this to diagnose slow or failing model requests."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl PoolStatusTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: PoolStatusArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<PoolStatusOutput>, McpError> {
+        let workers: Vec<PoolWorkerStatus> = all_worker_status()
+            .into_iter()
+            .filter(|w| args.capability.is_none_or(|c| w.capability == c))
+            .collect();
+        let count = workers.len();
+        let models: Vec<ModelLoadStatus> = all_model_status()
+            .into_iter()
+            .filter(|m| args.capability.is_none_or(|c| m.capability == c))
+            .collect();
+        let unloaded: Vec<&str> = models
+            .iter()
+            .filter(|m| m.state == ModelResidency::Unloaded)
+            .map(|m| m.registry_key.as_str())
+            .collect();
+        let unloaded_note = if unloaded.is_empty() {
+            String::new()
+        } else {
+            format!("\n\nUnloaded (keep-alive expired): {}", unloaded.join(", "))
+        };
+
+        let summary = if workers.is_empty() {
+            format!("✓ No model workers loaded{unloaded_note}")
+        } else {
+            let worker_list = workers
+                .iter()
+                .map(|w| {
+                    format!(
+                        "  {} {} #{} ({}): {:?}, {} served, {} failed{}{}",
+                        if w.worker.state == WorkerActivity::Failed { "✗" } else { "•" },
+                        w.worker.registry_key,
+                        w.worker.worker_id,
+                        w.capability.as_str(),
+                        w.worker.state,
+                        w.worker.requests_served,
+                        w.worker.requests_failed,
+                        w.worker
+                            .avg_latency_ms
+                            .map(|ms| format!(", avg {ms:.0} ms"))
+                            .unwrap_or_default(),
+                        w.worker
+                            .last_error
+                            .as_deref()
+                            .map(|e| format!("\n      last error: {e}"))
+                            .unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            format!("✓ Model workers ({})\n\n{}{}", count, worker_list, unloaded_note)
+        };
+
+        Ok(ToolResponse::new(summary, PoolStatusOutput { workers, count, models }))
+    }
+}
@@ -1,21 +1,43 @@
 //! Recall Tool - Retrieve relevant memories from a library using semantic search
 
-use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::memory::{RecallArgs, RecallOutput, RecalledMemory, MEMORY_RECALL, MemoryRecallPrompts};
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{MemoryRecallPrompts, RecallArgs, RecallOutput, RecalledMemory, MEMORY_RECALL};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
 
+use super::memorize_manager::{MemorizeSessionManager, MemorizeStatus};
+
+use crate::capability::rerank::reranker;
+use crate::memory::core::manager::coordinator::DEFAULT_FAST_SEARCH_BUDGET;
 use crate::memory::core::manager::pool::CoordinatorPool;
+use crate::domain::memory::primitives::node::MemoryNode;
 use crate::memory::core::ops::filter::MemoryFilter;
+use crate::memory::core::ops::grouping::{group_by_source, memory_source};
+use crate::memory::core::ops::ranking::{default_ranker, rank_memories, relevance, similarity};
+use crate::memory::core::ops::rerank::{RERANK_CANDIDATES, rerank_memories};
+use crate::memory::core::ops::threshold::apply_min_score;
+use crate::memory::core::primitives::chunk::chunk_location;
+use crate::memory::monitoring::record_recall;
+
+/// Candidates searched per requested result when grouping by source
+const GROUP_CANDIDATE_FACTOR: usize = 5;
+
+/// Upper bound on candidates searched when grouping by source
+const MAX_GROUP_CANDIDATES: usize = 200;
+
+/// How long `wait_for_session` waits unless `wait_timeout_ms` is given
+const DEFAULT_SESSION_WAIT: Duration = Duration::from_secs(30);
 
 #[derive(Clone)]
 pub struct RecallTool {
     pool: Arc<CoordinatorPool>,
+    sessions: Arc<MemorizeSessionManager>,
 }
 
 impl RecallTool {
-    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
-        Self { pool }
+    pub fn new(pool: Arc<CoordinatorPool>, sessions: Arc<MemorizeSessionManager>) -> Self {
+        Self { pool, sessions }
     }
 }
 
@@ -30,47 +52,211 @@ impl Tool for RecallTool {
     fn description() -> &'static str {
         "Retrieve relevant memories from a library using semantic search. \
          Searches for content similar to the provided context and returns the most relevant results. \
-         Uses vector similarity (cosine) to find semantically related memories."
+         Uses vector similarity (cosine) to find semantically related memories. \
+         Set group_by_source to true to return one result per source document or file: its best-matching chunk, \
+         scored by combining all hits from that source. \
+         Memories from memorize sessions still in progress are excluded; set wait_for_session to a \
+         memorize session_id to wait (up to wait_timeout_ms, default 30000) for it to finish first. \
+         Set fast to true for latency-bound callers such as autocomplete: fewer candidates are searched, \
+         importance boosting and related-memory expansion are skipped, and the call returns within budget_ms \
+         (default 50). Results cut short by the budget are marked approximate in the summary. \
+         Set embedding_task to override the library's embedding task for this query (s2p, s2s, \
+         code:<language> or instruct:<instruction>); it should match the task the memories were stored with. \
+         Set rerank to true to rescore the top 50 vector hits with a cross-encoder that reads the query and \
+         each memory together, returning the best by that score: slower, but more precise (ignored with fast). \
+         Set min_score (0-1) to leave out hits whose relevance (similarity, or rerank score when reranking) is \
+         below it; libraries can carry a default, set with memorize's library_min_score. When no hit reaches it, \
+         the result is empty and the summary reports no relevant memories; best_rejected gives the best \
+         rejected score. Treat that as the library having nothing on the topic. \
+         Hits are ordered by the library's ranker: relevance × importance unless the library registered its own \
+         (fast recalls always use relevance × importance)."
     }
 
     fn read_only() -> bool {
         true
     }
 
-    async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl RecallTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: RecallArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<RecallOutput>, McpError> {
         let start = Instant::now();
 
+        if let Some(min_score) = args.min_score
+            && !(0.0..=1.0).contains(&min_score)
+        {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "min_score must be between 0 and 1, got {}",
+                min_score
+            )));
+        }
+
         // Get coordinator for specified library
         let coordinator = self.pool.get_coordinator(&args.library)
             .await
             .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to get coordinator for library '{}': {}", args.library, e)))?;
 
+        // A per-call embedding task replaces the library's for this query
+        let coordinator = match args.embedding_task.as_deref().filter(|task| !task.is_empty()) {
+            Some(task) => Arc::new(coordinator.with_embedding_task(Some(task))),
+            None => coordinator,
+        };
+
+        // Optionally let a memorize session finish so its content is included
+        if let Some(session_id) = &args.wait_for_session {
+            let timeout = args
+                .wait_timeout_ms
+                .map_or(DEFAULT_SESSION_WAIT, Duration::from_millis);
+            let status = self
+                .sessions
+                .wait_for_session(session_id, timeout)
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to wait for session: {}", e)))?;
+            if status.status == MemorizeStatus::InProgress {
+                log::info!(
+                    "Memorize session {} still running after {}ms; recalling without it",
+                    session_id,
+                    timeout.as_millis()
+                );
+            }
+        }
+
+        // Memories written by sessions still in progress are left out, so
+        // the ranking reflects one consistent version of the library
+        let snapshot = coordinator.read_snapshot();
+
         // Create filter WITHOUT library tag (library already selected via coordinator)
         let filter = MemoryFilter::new();
 
-        // Search using coordinator's public API
-        let results = coordinator
-            .search_memories(&args.context, args.limit, Some(filter))
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Search failed: {}", e)))?;
+        // Grouping needs more candidates than results to fill distinct sources
+        let grouped = args.group_by_source;
+        let top_k = if grouped {
+            args.limit
+                .saturating_mul(GROUP_CANDIDATE_FACTOR)
+                .min(MAX_GROUP_CANDIDATES)
+                .max(args.limit)
+        } else {
+            args.limit
+        };
+
+        // Reranking picks those from a wider set of vector hits; fast
+        // recalls have no time for it
+        let fast = args.fast;
+        let rerank = args.rerank && !fast;
+        let candidates = if rerank { top_k.max(RERANK_CANDIDATES) } else { top_k };
+
+        // Search using coordinator's public API; the span ties database
+        // queries to the originating request
+        let span = tracing::info_span!(
+            "recall",
+            library = %args.library,
+            request_id = crate::runtime::current_request_id().as_ref().map(|id| id.as_str()),
+        );
+        let (results, approximate) = if fast {
+            let budget = args
+                .budget_ms
+                .map_or(DEFAULT_FAST_SEARCH_BUDGET, Duration::from_millis);
+            let found = coordinator
+                .search_memories_fast(&args.context, candidates, Some(filter), &snapshot, budget)
+                .instrument(span)
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Search failed: {}", e)))?;
+            (found.memories, found.approximate)
+        } else {
+            let found = coordinator
+                .search_memories_at(&args.context, candidates, Some(filter), &snapshot)
+                .instrument(span)
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Search failed: {}", e)))?;
+            (found, false)
+        };
+
+        // Second stage: cross-encoder relevance decides the final top_k
+        let results = if rerank {
+            rerank_memories(reranker(), &args.context, results, top_k)
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Rerank failed: {}", e)))?
+        } else {
+            results
+        };
+
+        // The library's ranker scores and orders the hits; fast recalls
+        // have no time to embed the query again, so they use the default
+        let ranker = if fast { default_ranker() } else { coordinator.ranker() };
+        let query_embedding = if ranker.uses_query_embedding() {
+            coordinator.query_embedding(&args.context)
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Query embedding failed: {}", e)))?
+        } else {
+            Vec::new()
+        };
+        let ranked = rank_memories(&*ranker, &query_embedding, results);
+
+        // (hit, score, hits from the same source); ungrouped hits stand alone
+        let hits: Vec<(MemoryNode, f32, usize)> = if grouped {
+            group_by_source(ranked, args.limit, |hit| memory_source(&hit.memory), |hit| hit.score)
+                .into_iter()
+                .filter_map(|group| {
+                    let count = group.hits.len();
+                    let best = group.hits.into_iter().next()?;
+                    Some((best.memory, group.score, count))
+                })
+                .collect()
+        } else {
+            ranked
+                .into_iter()
+                .map(|hit| (hit.memory, hit.score, 1))
+                .collect()
+        };
+
+        // Hits below the relevance floor are left out; for groups, the
+        // best hit decides
+        let min_score = args.min_score.or_else(|| coordinator.recall_min_score());
+        let (hits, floor) = match min_score {
+            Some(min_score) => {
+                let thresholded =
+                    apply_min_score(hits, min_score, |(memory, _, _)| relevance(memory));
+                let floor = (min_score, thresholded.rejected, thresholded.best_rejected);
+                (thresholded.kept, Some(floor))
+            }
+            None => (hits, None),
+        };
+
+        // Source locations of chunked hits, for the summary
+        let locations: Vec<Option<String>> = hits
+            .iter()
+            .map(|(memory, _, source_hits)| {
+                let custom = serde_json::Value::Object(
+                    memory.metadata.custom
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), (**v).clone()))
+                        .collect(),
+                );
+                let location = chunk_location(&custom).map(|(source, span)| {
+                    format!("{}:{}-{}", source, span.line_start, span.line_end)
+                });
+                match (location, *source_hits) {
+                    (Some(location), n) if n > 1 => Some(format!("{}, {} hits in source", location, n)),
+                    (location, _) => location,
+                }
+            })
+            .collect();
 
         // Convert to typed RecalledMemory structs
-        let memories: Vec<RecalledMemory> = results
+        let memories: Vec<RecalledMemory> = hits
             .into_iter()
             .enumerate()
-            .map(|(index, memory)| {
+            .map(|(index, (memory, score, _))| {
                 // Extract similarity (raw cosine) from metadata.custom
-                let similarity = memory.metadata.custom
-                    .get("similarity")
-                    .and_then(|v| v.as_f64())
-                    .unwrap_or(0.0) as f32;
+                let similarity = similarity(&memory);
 
                 // Get importance (boosted by entanglement/quality in coordinator)
                 let importance = memory.importance();
 
-                // Calculate score = similarity × importance
-                let score = similarity * importance;
-
                 // Rank is 1-indexed position in already-sorted results
                 let rank = index + 1;
 
@@ -87,16 +273,36 @@ impl Tool for RecallTool {
             .collect();
 
         let count = memories.len();
-        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        let elapsed = start.elapsed();
+        let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+
+        record_recall(args.library.clone(), args.context.clone(), count, elapsed);
 
         // Terminal summary
-        let summary = if memories.is_empty() {
+        let mode = match (fast, approximate) {
+            (false, _) if rerank => "\nMode: reranked",
+            (false, _) => "",
+            (true, false) => "\nMode: fast",
+            (true, true) => "\nMode: fast (approximate, search budget exhausted)",
+        };
+        let summary = if let Some((min_score, rejected, Some(best))) = floor
+            && memories.is_empty()
+        {
+            format!(
+                "✓ No relevant memories\n\n\
+                 Library: {}\n\
+                 Query: {}\n\
+                 Best rejected score: {:.2} (min_score {:.2}, {} below)\n\
+                 Search time: {:.0}ms{}",
+                args.library, args.context, best, min_score, rejected, elapsed_ms, mode
+            )
+        } else if memories.is_empty() {
             format!(
                 "✓ No memories found\n\n\
                  Library: {}\n\
                  Query: {}\n\
-                 Search time: {:.0}ms",
-                args.library, args.context, elapsed_ms
+                 Search time: {:.0}ms{}",
+                args.library, args.context, elapsed_ms, mode
             )
         } else {
             let top_results = memories.iter()
@@ -109,25 +315,43 @@ impl Tool for RecallTool {
                     } else {
                         m.content.clone()
                     };
-                    format!("  {}. [{:.2}] {}", i + 1, m.similarity, truncated)
+                    match &locations[i] {
+                        Some(location) => format!("  {}. [{:.2}] {} ({})", i + 1, m.similarity, truncated, location),
+                        None => format!("  {}. [{:.2}] {}", i + 1, m.similarity, truncated),
+                    }
                 })
                 .collect::<Vec<_>>()
                 .join("\n");
 
+            let omitted = match floor {
+                Some((min_score, rejected, _)) if rejected > 0 => {
+                    format!("\nOmitted below min_score {:.2}: {}", min_score, rejected)
+                }
+                _ => String::new(),
+            };
             format!(
                 "✓ Memories recalled ({} results)\n\n\
                  Library: {}\n\
-                 Search time: {:.0}ms\n\n\
+                 Search time: {:.0}ms{}{}\n\n\
                  Top results:\n{}",
-                count, args.library, elapsed_ms, top_results
+                count, args.library, elapsed_ms, mode, omitted, top_results
             )
         };
 
+        let (min_score, rejected, best_rejected) = match floor {
+            Some((min_score, rejected, best_rejected)) => {
+                (Some(min_score), rejected, best_rejected)
+            }
+            None => (None, 0, None),
+        };
         Ok(ToolResponse::new(summary, RecallOutput {
             memories,
             library: args.library,
             count,
             elapsed_ms,
+            min_score,
+            rejected,
+            best_rejected,
         }))
     }
 
@@ -0,0 +1,68 @@
+//! Redact Message Tool - Scrub a chat message everywhere it was propagated
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{RedactMessageArgs, RedactMessagePrompts, RedactionReport, MEMORY_REDACT_MESSAGE};
+use std::sync::Arc;
+
+use super::history_index::HistoryIndexManager;
+
+#[derive(Clone)]
+pub struct RedactMessageTool {
+    manager: Arc<HistoryIndexManager>,
+}
+
+impl RedactMessageTool {
+    pub fn new(manager: Arc<HistoryIndexManager>) -> Self {
+        Self { manager }
+    }
+}
+
+impl Tool for RedactMessageTool {
+    type Args = RedactMessageArgs;
+    type Prompts = RedactMessagePrompts;
+
+    fn name() -> &'static str {
+        MEMORY_REDACT_MESSAGE
+    }
+
+    fn description() -> &'static str {
+        "Redact a stored chat message for compliance requests. The message is masked (content \
+         replaced with \"[redacted]\", embedding dropped) or removed, its postings are removed \
+         from the chat search index, and memories derived from it (those naming it in \
+         source_message_id metadata) are scrubbed the same way. Returns a report of what was \
+         scrubbed."
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl RedactMessageTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: RedactMessageArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<RedactionReport>, McpError> {
+        let report = self
+            .manager
+            .redact_message(args.library.as_deref(), &args.message_id, args.mode)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Redaction failed: {}", e)))?;
+
+        let store = match &args.library {
+            Some(library) => format!("library '{library}'"),
+            None => "the chat history".to_string(),
+        };
+        let summary = if report.message_scrubbed || !report.derived_memories.is_empty() {
+            format!(
+                "✓ Redacted message {} in {}: {} index postings, {} derived memories",
+                report.message_id,
+                store,
+                report.postings_removed,
+                report.derived_memories.len()
+            )
+        } else {
+            format!("✗ Message {} not found in {}", report.message_id, store)
+        };
+
+        Ok(ToolResponse::new(summary, report))
+    }
+}
@@ -0,0 +1,142 @@
+//! Search History Tool - Ranked search over stored chat conversations
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{SearchHistoryArgs, SearchHistoryOutput, SearchHistoryPrompts, MEMORY_SEARCH_HISTORY};
+use std::sync::Arc;
+
+use super::history_index::HistoryIndexManager;
+use crate::domain::chat::search::{QueryExpr, SearchQuery, parse_query};
+
+/// Build the search query from the query language and structured fields
+///
+/// Structured fields narrow a parsed query: `terms` are ANDed with its
+/// expression, `tags` extend its tag filter, and `role`, `session_id`,
+/// `limit` and `offset` replace the corresponding filters.
+pub fn history_query(args: &SearchHistoryArgs) -> Result<SearchQuery, String> {
+    let mut query = match args.query.as_deref().map(str::trim) {
+        Some(text) if !text.is_empty() => parse_query(text)
+            .map_err(|e| format!("{e}\n{}", e.pointer(text)))?,
+        _ => SearchQuery {
+            expression: Some(QueryExpr::And(Vec::new())),
+            ..SearchQuery::default()
+        },
+    };
+
+    let terms: Vec<QueryExpr> = args
+        .terms
+        .iter()
+        .map(|term| term.trim().to_lowercase())
+        .filter(|term| !term.is_empty())
+        .map(QueryExpr::Term)
+        .collect();
+    if !terms.is_empty() {
+        let structured = if args.match_any {
+            QueryExpr::Or(terms)
+        } else {
+            QueryExpr::And(terms)
+        };
+        query.expression = Some(match query.expression.take() {
+            Some(QueryExpr::And(exprs)) if exprs.is_empty() => structured,
+            Some(expression) => QueryExpr::And(vec![expression, structured]),
+            None => structured,
+        });
+        if let Some(expression) = &query.expression {
+            query.terms = expression.positive_terms();
+        }
+    }
+
+    if !args.tags.is_empty() {
+        query.tag_filter.get_or_insert_with(Vec::new).extend(args.tags.iter().cloned());
+    }
+    if let Some(role) = &args.role {
+        query.user_filter = Some(role.to_lowercase());
+    }
+    if let Some(session_id) = &args.session_id {
+        query.session_filter = Some(session_id.clone());
+    }
+    if let Some(limit) = args.limit {
+        if limit == 0 {
+            return Err("limit must be greater than 0".to_string());
+        }
+        query.max_results = limit;
+    }
+    if let Some(offset) = args.offset {
+        query.offset = offset;
+    }
+
+    Ok(query)
+}
+
+#[derive(Clone)]
+pub struct SearchHistoryTool {
+    manager: Arc<HistoryIndexManager>,
+}
+
+impl SearchHistoryTool {
+    pub fn new(manager: Arc<HistoryIndexManager>) -> Self {
+        Self { manager }
+    }
+}
+
+impl Tool for SearchHistoryTool {
+    type Args = SearchHistoryArgs;
+    type Prompts = SearchHistoryPrompts;
+
+    fn name() -> &'static str {
+        MEMORY_SEARCH_HISTORY
+    }
+
+    fn description() -> &'static str {
+        "Search past chat conversations: the chat history agents store, or a memory library \
+         given as library. Pass query in the search \
+         language (words, \"exact phrases\", AND/OR/NOT, -word, and filters tag:, user:, \
+         session:, after:YYYY-MM-DD, before:YYYY-MM-DD, sort:, limit:, offset:) or the structured \
+         fields terms, match_any, tags, role, session_id, limit and offset. Returns ranked messages \
+         with a snippet, score, role, timestamp and the session_id/turn_id of the conversation."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl SearchHistoryTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: SearchHistoryArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<SearchHistoryOutput>, McpError> {
+        let query = history_query(&args)
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Invalid history query: {}", e)))?;
+
+        let hits = self
+            .manager
+            .search(args.library.as_deref(), query)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("History search failed: {}", e)))?;
+
+        let mut summary = match &args.library {
+            Some(library) => format!("✓ Found {} message(s) in library '{library}'", hits.len()),
+            None => format!("✓ Found {} message(s) in the chat history", hits.len()),
+        };
+        for hit in &hits {
+            summary.push_str(&format!(
+                "\n\n[{:.2}] {} (session {})\n{}",
+                hit.score,
+                hit.role,
+                hit.session_id.as_deref().unwrap_or("unknown"),
+                hit.snippet
+            ));
+        }
+
+        let output = SearchHistoryOutput {
+            indexed: self.manager.indexed(args.library.as_deref()),
+            count: hits.len(),
+            library: args.library,
+            hits,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
@@ -0,0 +1,226 @@
+//! MCP stdio transport
+//!
+//! The HTTP server suits long-running daemons, but many editors only speak
+//! MCP over a child process's stdin/stdout. [`StdioServer`] serves the same
+//! tool and prompt routers as the HTTP server (both are built by
+//! [`register_all_tools`]), so every tool behaves identically on either
+//! transport.
+//!
+//! Stdout carries protocol frames only; logs go to stderr.
+
+use std::sync::{Arc, Weak};
+
+use rmcp::handler::server::prompt::PromptContext;
+use rmcp::handler::server::router::{prompt::PromptRouter, tool::ToolRouter};
+use rmcp::handler::server::tool::ToolCallContext;
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, GetPromptRequestParam, GetPromptResult, Implementation,
+    ListPromptsResult, ListToolsResult, PaginatedRequestParam, ServerCapabilities, ServerInfo,
+};
+use rmcp::service::{RequestContext, RoleServer};
+use rmcp::{ErrorData, ServerHandler, ServiceExt};
+
+use crate::memory::core::manager::pool::CoordinatorPool;
+
+/// Memorize session manager of the tools registered last in this process
+static SERVED_MEMORIZE_MANAGER: parking_lot::RwLock<Weak<super::MemorizeSessionManager>> =
+    parking_lot::RwLock::new(Weak::new());
+
+/// Memorize session manager behind the tools this process serves, if any
+///
+/// Lets in-process views such as the dashboard list the server's sessions.
+pub fn served_memorize_manager() -> Option<Arc<super::MemorizeSessionManager>> {
+    SERVED_MEMORIZE_MANAGER.read().upgrade()
+}
+
+/// Register every candle-agent tool on the given routers
+///
+/// Session managers are created here and their cleanup tasks started, so
+/// call this once per server.
+pub fn register_all_tools<S>(
+    pool: Arc<CoordinatorPool>,
+    mut tool_router: ToolRouter<S>,
+    mut prompt_router: PromptRouter<S>,
+) -> (ToolRouter<S>, PromptRouter<S>)
+where
+    S: Send + Sync + 'static,
+{
+    use kodegen_server_http::register_tool;
+
+    let memorize_manager = Arc::new(super::MemorizeSessionManager::new(pool.clone()));
+    *SERVED_MEMORIZE_MANAGER.write() = Arc::downgrade(&memorize_manager);
+    let dump_manager = Arc::new(super::DumpSessionManager::new(pool.clone()));
+    let history_manager = Arc::new(super::HistoryIndexManager::new(pool.clone()));
+    history_manager.start_indexing();
+    let summarize_manager = Arc::new(super::SummarizeSessionManager::new(pool.clone()));
+
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::MemorizeTool::new(memorize_manager.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::CheckMemorizeStatusTool::new(memorize_manager.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::MemorizeWaitTool::new(memorize_manager.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::RecallTool::new(pool.clone(), memorize_manager.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::ListMemoryLibrariesTool::new(pool.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::DumpLibraryTool::new(dump_manager.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::SearchHistoryTool::new(history_manager.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::RedactMessageTool::new(history_manager.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::SummarizeSessionTool::new(summarize_manager.clone()),
+    );
+    (tool_router, prompt_router) =
+        register_tool(tool_router, prompt_router, super::ListModelsTool::new());
+    (tool_router, prompt_router) =
+        register_tool(tool_router, prompt_router, super::PoolStatusTool::new());
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::EmbedTool::new(pool.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::WorkflowRunTool::new(pool.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::FindDuplicatesTool::new(pool.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::UnusedMemoriesTool::new(pool.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::UpdateMemoryTool::new(pool.clone()),
+    );
+    (tool_router, prompt_router) = register_tool(
+        tool_router,
+        prompt_router,
+        super::ToolAuditTool::new(pool.clone()),
+    );
+
+    memorize_manager.start_cleanup_task();
+    dump_manager.start_cleanup_task();
+    summarize_manager.start_cleanup_task();
+
+    (tool_router, prompt_router)
+}
+
+/// MCP server handler for the stdio transport
+pub struct StdioServer {
+    tool_router: ToolRouter<Self>,
+    prompt_router: PromptRouter<Self>,
+}
+
+impl StdioServer {
+    /// Server with every candle-agent tool registered
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        let (tool_router, prompt_router) =
+            register_all_tools(pool, ToolRouter::new(), PromptRouter::new());
+        Self {
+            tool_router,
+            prompt_router,
+        }
+    }
+
+    /// Serve MCP on stdin/stdout until the client disconnects
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the MCP handshake fails or the service task panics.
+    pub async fn serve_stdio(self) -> anyhow::Result<()> {
+        let service = self.serve(rmcp::transport::stdio()).await?;
+        let reason = service.waiting().await?;
+        log::info!("stdio MCP session ended: {reason:?}");
+        Ok(())
+    }
+}
+
+impl ServerHandler for StdioServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_prompts()
+                .build(),
+            server_info: Implementation {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                ..Implementation::from_build_env()
+            },
+            ..ServerInfo::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        Ok(ListToolsResult::with_all_items(self.tool_router.list_all()))
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tool_router
+            .call(ToolCallContext::new(self, request, context))
+            .await
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, ErrorData> {
+        Ok(ListPromptsResult::with_all_items(self.prompt_router.list_all()))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        let GetPromptRequestParam { name, arguments } = request;
+        self.prompt_router
+            .get_prompt(PromptContext::new(self, name, arguments, context))
+            .await
+    }
+}
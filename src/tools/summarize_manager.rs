@@ -0,0 +1,423 @@
+//! Summarize Session Manager - Async session pattern for conversation summaries
+//!
+//! Same lifecycle as memorize and dump sessions:
+//! 1. Client calls summarize_session(library, session_id | transcript) →
+//!    spawns background task → returns summary_id
+//! 2. Background task loads the conversation, asks the local model for a
+//!    structured summary (decisions, action items, facts) and memorizes each
+//!    item into the target library
+//! 3. Client calls summarize_session(summary_id) to poll until finished
+//! 4. Cleanup task removes old sessions (60s interval)
+//!
+//! Items summarized from a stored session carry the source message IDs under
+//! [`SOURCE_MESSAGE_KEY`], so redacting a message also scrubs its summary.
+
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use super::history_index::{SOURCE_MESSAGE_KEY, history_message};
+use crate::builders::agent_role::chat_memory_coordinator;
+use crate::capability::registry::{self, TextToTextModel};
+use crate::capability::traits::TextToTextCapable;
+use crate::domain::chat::message::CandleMessageRole;
+use crate::domain::chat::session::SESSION_ID_KEY;
+use crate::domain::completion::{CandleCompletionChunk, CandleCompletionParams};
+use crate::domain::memory::primitives::types::MemoryTypeEnum;
+use crate::domain::prompt::CandlePrompt;
+use crate::memory::core::manager::coordinator::{MemoryCoordinator, NewMemory};
+use crate::memory::core::manager::pool::CoordinatorPool;
+use crate::memory::core::manager::surreal::trait_def::MemoryManager;
+use crate::memory::core::primitives::metadata::MemoryMetadata;
+
+// ============================================================================
+// CONFIGURATION CONSTANTS
+// ============================================================================
+
+/// Model used when the caller does not name one
+pub const DEFAULT_SUMMARY_MODEL: &str = "Qwen/Qwen2.5-Coder-3B-Instruct-GGUF";
+
+/// Category of memorized summary items
+pub const SUMMARY_CATEGORY: &str = "session_summary";
+
+/// Longest transcript sent to the model, in characters; older turns are dropped
+pub const MAX_TRANSCRIPT_CHARS: usize = 24_000;
+
+/// Tokens the model may spend on the summary
+const SUMMARY_MAX_TOKENS: u64 = 1024;
+
+/// Memories fetched per database page while loading a session
+const TRANSCRIPT_PAGE_SIZE: usize = 500;
+
+/// Cleanup interval in seconds
+const CLEANUP_INTERVAL_SECS: u64 = 60;
+
+/// Finished session retention time in seconds since the last read
+const FINISHED_SESSION_RETENTION_SECS: u64 = 300;
+
+fn unix_timestamp_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// SUMMARY FORMAT
+// ============================================================================
+
+pub use kodegen_mcp_schema::memory::{
+    SessionSummary, SummarizeReport, SummarizeStatus, SummaryItemKind,
+};
+
+/// Prompt asking the model to summarize `transcript` as JSON
+pub fn summary_prompt(transcript: &str) -> String {
+    format!(
+        "Summarize the conversation below. Reply with only a JSON object of the form\n\
+         {{\"decisions\": [...], \"action_items\": [...], \"facts\": [...]}}\n\
+         where each entry is one short, self-contained sentence. Use an empty list when \
+         there is nothing of that kind.\n\n\
+         Conversation:\n{transcript}\n\nJSON:"
+    )
+}
+
+/// Parse the model's reply into a summary
+///
+/// Text around the outermost JSON object (such as a code fence) is ignored,
+/// and blank or duplicate items are dropped.
+pub fn parse_summary(output: &str) -> Result<SessionSummary, String> {
+    let (Some(start), Some(end)) = (output.find('{'), output.rfind('}')) else {
+        return Err("model reply contains no JSON object".to_string());
+    };
+    if end < start {
+        return Err("model reply contains no JSON object".to_string());
+    }
+
+    let mut summary: SessionSummary = serde_json::from_str(&output[start..=end])
+        .map_err(|e| format!("model reply is not a valid summary: {e}"))?;
+    for items in [&mut summary.decisions, &mut summary.action_items, &mut summary.facts] {
+        let mut seen = std::collections::HashSet::new();
+        items.retain_mut(|item| {
+            *item = item.trim().to_string();
+            !item.is_empty() && seen.insert(item.clone())
+        });
+    }
+    Ok(summary)
+}
+
+/// Keep the end of `transcript` within [`MAX_TRANSCRIPT_CHARS`]
+pub fn clip_transcript(transcript: &str) -> &str {
+    match transcript.char_indices().rev().nth(MAX_TRANSCRIPT_CHARS - 1) {
+        Some((start, _)) if start > 0 => &transcript[start..],
+        _ => transcript,
+    }
+}
+
+// ============================================================================
+// SESSION TYPES
+// ============================================================================
+
+/// Conversation to summarize
+#[derive(Debug, Clone, PartialEq)]
+pub enum SummarySource {
+    /// Chat session stored in a library, or in the chat history when `None`
+    Session { library: Option<String>, session_id: String },
+    /// Transcript text supplied by the caller
+    Transcript(String),
+}
+
+/// Active summarize session
+pub struct SummarizeSession {
+    /// Unique summary ID (UUID v4)
+    pub id: String,
+    /// Library the items are memorized into
+    pub library: String,
+    /// Conversation being summarized
+    pub source: SummarySource,
+    /// Registry key of the model writing the summary
+    pub model: String,
+    /// Current status
+    pub status: Arc<RwLock<SummarizeStatus>>,
+    /// Current stage: "Loading transcript", "Summarizing", "Storing items"
+    pub stage: Arc<RwLock<String>>,
+    /// Generated summary (once the model has answered)
+    pub summary: Arc<RwLock<Option<SessionSummary>>>,
+    /// Memories created for the summary items
+    pub memory_ids: Arc<RwLock<Vec<String>>>,
+    /// Error message (when failed)
+    pub error: Arc<RwLock<Option<String>>>,
+    /// Session start time
+    pub start_time: Instant,
+    /// Last read time (for cleanup)
+    pub last_read_time: Arc<AtomicU64>,
+}
+
+impl SummarizeSession {
+    /// Create new session
+    pub fn new(id: String, library: String, source: SummarySource, model: String) -> Self {
+        Self {
+            id,
+            library,
+            source,
+            model,
+            status: Arc::new(RwLock::new(SummarizeStatus::InProgress)),
+            stage: Arc::new(RwLock::new("Initializing".to_string())),
+            summary: Arc::new(RwLock::new(None)),
+            memory_ids: Arc::new(RwLock::new(Vec::new())),
+            error: Arc::new(RwLock::new(None)),
+            start_time: Instant::now(),
+            last_read_time: Arc::new(AtomicU64::new(unix_timestamp_now())),
+        }
+    }
+
+    async fn set_stage(&self, stage: &str) {
+        *self.stage.write().await = stage.to_string();
+    }
+
+    /// Update last read time (for cleanup tracking)
+    pub fn touch(&self) {
+        self.last_read_time.store(unix_timestamp_now(), Ordering::Relaxed);
+    }
+}
+
+// ============================================================================
+// SESSION MANAGER
+// ============================================================================
+
+/// Manager for summarize sessions
+#[derive(Clone)]
+pub struct SummarizeSessionManager {
+    sessions: Arc<RwLock<HashMap<String, Arc<SummarizeSession>>>>,
+    pool: Arc<CoordinatorPool>,
+}
+
+impl SummarizeSessionManager {
+    /// Create new session manager
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self {
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            pool,
+        }
+    }
+
+    /// Start summarizing `source` into `library` (returns summary_id immediately)
+    pub async fn start_summary(&self, library: String, source: SummarySource, model: String) -> String {
+        let summary_id = Uuid::new_v4().to_string();
+        let session = Arc::new(SummarizeSession::new(summary_id.clone(), library, source, model));
+
+        self.sessions
+            .write()
+            .await
+            .insert(summary_id.clone(), session.clone());
+
+        self.spawn_summary_task(session);
+        summary_id
+    }
+
+    /// Current state of a summary
+    pub async fn report(&self, summary_id: &str) -> anyhow::Result<SummarizeReport> {
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(summary_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Summary not found: {}", summary_id))?;
+
+        session.touch();
+
+        Ok(SummarizeReport {
+            summary_id: session.id.clone(),
+            library: session.library.clone(),
+            status: session.status.read().await.clone(),
+            stage: session.stage.read().await.clone(),
+            summary: session.summary.read().await.clone(),
+            memory_ids: session.memory_ids.read().await.clone(),
+            runtime_ms: session.start_time.elapsed().as_millis() as u64,
+            error: session.error.read().await.clone(),
+        })
+    }
+
+    /// Spawn background task that writes the summary
+    fn spawn_summary_task(&self, session: Arc<SummarizeSession>) {
+        let pool = self.pool.clone();
+        let task_name = format!("summarize session {}", session.id);
+
+        crate::runtime::supervisor().spawn(task_name, async move {
+            log::info!(
+                "Summarize task started for session {} (library: {})",
+                session.id,
+                session.library
+            );
+
+            match Self::summarize(&pool, &session).await {
+                Ok(()) => {
+                    log::info!(
+                        "Summarize task completed for session {}: {} items",
+                        session.id,
+                        session.memory_ids.read().await.len()
+                    );
+                    *session.status.write().await = SummarizeStatus::Completed;
+                }
+                Err(e) => {
+                    log::error!("Summarize failed for session {}: {}", session.id, e);
+                    *session.error.write().await = Some(e.to_string());
+                    *session.status.write().await = SummarizeStatus::Failed;
+                }
+            }
+        });
+    }
+
+    /// Load the transcript, generate the summary and memorize its items
+    async fn summarize(pool: &CoordinatorPool, session: &SummarizeSession) -> anyhow::Result<()> {
+        session.set_stage("Loading transcript").await;
+        let (transcript, source_ids, session_id) = match &session.source {
+            SummarySource::Session { library, session_id } => {
+                let coordinator = match library {
+                    Some(library) => pool.get_coordinator(library).await?,
+                    None => chat_memory_coordinator(pool.embedding_model())
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to open chat memory: {e}"))?,
+                };
+                let (transcript, ids) = Self::load_transcript(&coordinator, session_id).await?;
+                (transcript, ids, Some(session_id.clone()))
+            }
+            SummarySource::Transcript(text) => (text.clone(), Vec::new(), None),
+        };
+        if transcript.trim().is_empty() {
+            anyhow::bail!("Nothing to summarize: the conversation is empty");
+        }
+
+        session.set_stage("Summarizing").await;
+        let model = registry::get::<TextToTextModel>(&session.model)
+            .ok_or_else(|| anyhow::anyhow!("Model '{}' not found in registry", session.model))?;
+        let params = CandleCompletionParams {
+            max_tokens: NonZeroU64::new(SUMMARY_MAX_TOKENS),
+            ..Default::default()
+        };
+        let mut stream = model.prompt(CandlePrompt::new(summary_prompt(clip_transcript(&transcript))), &params);
+        let mut reply = String::new();
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                CandleCompletionChunk::Text(text) => reply.push_str(&text),
+                CandleCompletionChunk::Complete { text, .. } => reply.push_str(&text),
+                CandleCompletionChunk::Error(e) => anyhow::bail!("Model failed: {}", e),
+                _ => {}
+            }
+        }
+        let summary = parse_summary(&reply).map_err(|e| anyhow::anyhow!("{}", e))?;
+        *session.summary.write().await = Some(summary.clone());
+
+        session.set_stage("Storing items").await;
+        let memories: Vec<NewMemory> = summary
+            .items()
+            .map(|(kind, item)| {
+                let mut custom = serde_json::json!({ "summary_id": session.id });
+                if let Some(session_id) = &session_id {
+                    custom[SESSION_ID_KEY] = session_id.clone().into();
+                }
+                if !source_ids.is_empty() {
+                    custom[SOURCE_MESSAGE_KEY] = source_ids.clone().into();
+                }
+                let metadata = MemoryMetadata {
+                    context: "summary".to_string(),
+                    category: SUMMARY_CATEGORY.to_string(),
+                    tags: vec!["summary".to_string(), kind.tag().to_string()],
+                    importance: 0.7,
+                    source: Some("summarize_session".to_string()),
+                    custom,
+                    ..MemoryMetadata::new()
+                };
+                NewMemory::new(item, MemoryTypeEnum::Semantic, Some(metadata))
+            })
+            .collect();
+
+        if !memories.is_empty() {
+            let coordinator = pool.get_coordinator(&session.library).await?;
+            let stored = coordinator.add_memories(memories).await?;
+            *session.memory_ids.write().await = stored.iter().map(|m| m.id().to_string()).collect();
+        }
+        session.set_stage("Done").await;
+        Ok(())
+    }
+
+    /// Messages of `session_id` in stored order, and their memory IDs
+    async fn load_transcript(
+        coordinator: &MemoryCoordinator,
+        session_id: &str,
+    ) -> anyhow::Result<(String, Vec<String>)> {
+        let mut messages = Vec::new();
+        let mut offset = 0;
+        loop {
+            let mut stream = coordinator.list_all_memories(TRANSCRIPT_PAGE_SIZE, offset);
+            let mut fetched = 0;
+            while let Some(memory) = stream.next().await {
+                let memory = memory?;
+                fetched += 1;
+                if let Some((message, origin)) = history_message(&memory)
+                    && origin.session_id.as_deref() == Some(session_id)
+                {
+                    messages.push((memory.created_at.into_inner(), message.message, origin.memory_id));
+                }
+            }
+            if fetched < TRANSCRIPT_PAGE_SIZE {
+                break;
+            }
+            offset += fetched;
+        }
+
+        messages.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut transcript = String::new();
+        let mut ids = Vec::with_capacity(messages.len());
+        for (_, message, memory_id) in messages {
+            let speaker = match message.role {
+                CandleMessageRole::User => "User",
+                CandleMessageRole::Assistant => "Assistant",
+                CandleMessageRole::System => "System",
+                CandleMessageRole::Tool => "Tool",
+            };
+            transcript.push_str(&format!("{speaker}: {}\n\n", message.content));
+            ids.push(memory_id);
+        }
+        Ok((transcript, ids))
+    }
+
+    /// Cleanup finished sessions that have not been read recently
+    async fn cleanup_sessions(&self) {
+        let now = unix_timestamp_now();
+
+        let mut sessions = self.sessions.write().await;
+        let mut to_remove = Vec::new();
+
+        for (summary_id, session) in sessions.iter() {
+            let age_secs = now.saturating_sub(session.last_read_time.load(Ordering::Relaxed));
+            let finished = *session.status.read().await != SummarizeStatus::InProgress;
+            if finished && age_secs >= FINISHED_SESSION_RETENTION_SECS {
+                to_remove.push(summary_id.clone());
+            }
+        }
+
+        for summary_id in to_remove {
+            log::debug!("Cleaning up summarize session: {}", summary_id);
+            sessions.remove(&summary_id);
+        }
+    }
+
+    /// Start cleanup task (call after all tools registered)
+    ///
+    /// The loop is owned by the task supervisor and stops when shutdown begins.
+    pub fn start_cleanup_task(self: Arc<Self>) {
+        crate::runtime::supervisor().spawn_cancellable("summarize session cleanup", async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CLEANUP_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                self.cleanup_sessions().await;
+            }
+        });
+    }
+}
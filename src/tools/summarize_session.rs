@@ -0,0 +1,113 @@
+//! Summarize Session Tool - Turn a conversation into long-term memories
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{
+    SummarizeReport, SummarizeSessionArgs, SummarizeSessionPrompts, MEMORY_SUMMARIZE_SESSION,
+};
+use std::sync::Arc;
+
+use super::summarize_manager::{
+    DEFAULT_SUMMARY_MODEL, SummarizeSessionManager, SummarizeStatus, SummarySource,
+};
+
+#[derive(Clone)]
+pub struct SummarizeSessionTool {
+    manager: Arc<SummarizeSessionManager>,
+}
+
+impl SummarizeSessionTool {
+    pub fn new(manager: Arc<SummarizeSessionManager>) -> Self {
+        Self { manager }
+    }
+}
+
+impl Tool for SummarizeSessionTool {
+    type Args = SummarizeSessionArgs;
+    type Prompts = SummarizeSessionPrompts;
+
+    fn name() -> &'static str {
+        MEMORY_SUMMARIZE_SESSION
+    }
+
+    fn description() -> &'static str {
+        "Summarize a conversation with the local model and memorize the result. Pass library \
+         and either session_id (a chat session from the chat history, or from source_library) or a \
+         raw transcript. The model extracts decisions, action items and facts; each is stored \
+         as a memory tagged summary.decision, summary.action_item or summary.fact. Runs in the \
+         background: the first call returns a summary_id, and calling again with summary_id \
+         reports the status, the summary and the created memory IDs."
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl SummarizeSessionTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: SummarizeSessionArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<SummarizeReport>, McpError> {
+        let summary_id = match (args.summary_id, args.library) {
+            (Some(summary_id), _) => summary_id,
+            (None, Some(library)) => {
+                let source = match (args.session_id, args.transcript) {
+                    (Some(session_id), None) => SummarySource::Session {
+                        library: args.source_library,
+                        session_id,
+                    },
+                    (None, Some(transcript)) => SummarySource::Transcript(transcript),
+                    _ => {
+                        return Err(McpError::Other(anyhow::anyhow!(
+                            "Exactly one of session_id or transcript is required"
+                        )));
+                    }
+                };
+                let model = args.model.unwrap_or_else(|| DEFAULT_SUMMARY_MODEL.to_string());
+                self.manager.start_summary(library, source, model).await
+            }
+            (None, None) => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Either library (to start a summary) or summary_id (to check one) is required"
+                )));
+            }
+        };
+
+        let report = self
+            .manager
+            .report(&summary_id)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to read summary: {}", e)))?;
+
+        let summary = match report.status {
+            SummarizeStatus::InProgress => format!(
+                "⏳ Summary in progress ({})\n\n\
+                 Summary: {}\n\
+                 Library: {}",
+                report.stage, report.summary_id, report.library
+            ),
+            SummarizeStatus::Completed => {
+                let (decisions, action_items, facts) = report
+                    .summary
+                    .as_ref()
+                    .map_or((0, 0, 0), |s| (s.decisions.len(), s.action_items.len(), s.facts.len()));
+                format!(
+                    "✓ Summary memorized\n\n\
+                     Summary: {}\n\
+                     Library: {}\n\
+                     Decisions: {}, action items: {}, facts: {}",
+                    report.summary_id, report.library, decisions, action_items, facts
+                )
+            }
+            SummarizeStatus::Failed => format!(
+                "✗ Summary failed\n\n\
+                 Summary: {}\n\
+                 Library: {}\n\
+                 Error: {}",
+                report.summary_id,
+                report.library,
+                report.error.as_deref().unwrap_or("Unknown error")
+            ),
+        };
+
+        Ok(ToolResponse::new(summary, report))
+    }
+}
@@ -0,0 +1,106 @@
+//! Tool Audit Tool - Query the persisted tool-call audit log
+//!
+//! Chat sessions write every tool call they execute to the `tool_call` table
+//! of the chat memory database, `agent.db` (see the coordinator's tool
+//! audit). This tool reads it back from there, filtered by session, turn,
+//! tool or failures, newest first.
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{
+    MEMORY_TOOL_AUDIT, ToolAuditArgs, ToolAuditOutput, ToolAuditPrompts, ToolAuditRecord,
+};
+use std::sync::Arc;
+
+use crate::builders::agent_role::chat_memory_coordinator;
+use crate::memory::core::manager::coordinator::{DEFAULT_TOOL_AUDIT_LIMIT, ToolAuditQuery};
+use crate::memory::core::manager::pool::CoordinatorPool;
+
+#[derive(Clone)]
+pub struct ToolAuditTool {
+    pool: Arc<CoordinatorPool>,
+}
+
+impl ToolAuditTool {
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl Tool for ToolAuditTool {
+    type Args = ToolAuditArgs;
+    type Prompts = ToolAuditPrompts;
+
+    fn name() -> &'static str {
+        MEMORY_TOOL_AUDIT
+    }
+
+    fn description() -> &'static str {
+        "Query the persisted audit log of tool calls made by chat sessions. Filter \
+         by session_id, turn_id, tool or failures_only. Each entry has the tool name, a hash of \
+         its arguments, duration, success, a preview of the result or error, and the session \
+         and turn that made it, newest first (default limit 100). Read-only."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl ToolAuditTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: ToolAuditArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<ToolAuditOutput>, McpError> {
+        // Chat sessions audit to the chat memory database, not a pool library
+        let coordinator = chat_memory_coordinator(self.pool.embedding_model())
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to open chat memory: {}", e)))?;
+
+        let query = ToolAuditQuery {
+            session_id: args.session_id,
+            turn_id: args.turn_id,
+            tool: args.tool,
+            failures_only: args.failures_only,
+            limit: Some(args.limit.unwrap_or(DEFAULT_TOOL_AUDIT_LIMIT)),
+        };
+        let calls: Vec<ToolAuditRecord> = coordinator
+            .tool_calls(&query)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to read tool audit log: {}", e)))?
+            .into_iter()
+            .map(|entry| ToolAuditRecord {
+                id: entry.id,
+                called_at: entry.called_at,
+                tool: entry.call.tool,
+                args_hash: entry.call.args_hash,
+                duration_ms: entry.call.duration_ms,
+                success: entry.call.success,
+                result_preview: entry.call.result_preview,
+                session_id: entry.call.session_id,
+                turn_id: entry.call.turn_id,
+            })
+            .collect();
+        let failures = calls.iter().filter(|call| !call.success).count();
+
+        let mut summary = format!("✓ {} tool calls ({} failed)", calls.len(), failures);
+        for call in calls.iter().take(20) {
+            summary.push_str(&format!(
+                "\n  {} {} {} ({} ms)",
+                call.called_at,
+                if call.success { "✓" } else { "✗" },
+                call.tool,
+                call.duration_ms
+            ));
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            ToolAuditOutput {
+                calls,
+                failures,
+            },
+        ))
+    }
+}
@@ -0,0 +1,140 @@
+//! Unused Memories Tool - Report memories no recall has returned lately
+//!
+//! Recall hits are tracked per memory (see the coordinator's recall
+//! tracking). This tool lists memories that no search has returned in the
+//! last `days` days, largest first, with the storage they take, so pruning
+//! can start where it frees the most space.
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{
+    LibraryUnusedReport, MEMORY_UNUSED_REPORT, UnusedMemoriesArgs, UnusedMemoriesOutput,
+    UnusedMemoriesPrompts, UnusedMemoryEntry,
+};
+use std::sync::Arc;
+
+use crate::memory::core::manager::coordinator::DEFAULT_UNUSED_DAYS;
+use crate::memory::core::manager::pool::CoordinatorPool;
+
+/// Memories listed per library when limit is not set
+pub const DEFAULT_UNUSED_LIMIT: usize = 50;
+
+#[derive(Clone)]
+pub struct UnusedMemoriesTool {
+    pool: Arc<CoordinatorPool>,
+}
+
+impl UnusedMemoriesTool {
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self { pool }
+    }
+}
+
+/// Human-readable byte count
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f64 / (1 << 10) as f64),
+        b => format!("{b} B"),
+    }
+}
+
+impl Tool for UnusedMemoriesTool {
+    type Args = UnusedMemoriesArgs;
+    type Prompts = UnusedMemoriesPrompts;
+
+    fn name() -> &'static str {
+        MEMORY_UNUSED_REPORT
+    }
+
+    fn description() -> &'static str {
+        "Report memories that no recall has returned in the last N days (default 30), based on \
+         per-memory recall hit tracking. Lists each library's unused memories largest first, \
+         with their size, recall count and last recall time, plus how much space they take in \
+         total. Read-only; use it to decide what to prune."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl UnusedMemoriesTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: UnusedMemoriesArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<UnusedMemoriesOutput>, McpError> {
+        let days = args.days.unwrap_or(DEFAULT_UNUSED_DAYS);
+        let limit = args.limit.unwrap_or(DEFAULT_UNUSED_LIMIT);
+
+        let libraries = if args.libraries.is_empty() {
+            self.pool
+                .list_libraries()
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to list libraries: {}", e)))?
+        } else {
+            args.libraries
+        };
+
+        let mut reports = Vec::with_capacity(libraries.len());
+        for library in libraries {
+            let coordinator = self.pool.get_coordinator(&library).await.map_err(|e| {
+                McpError::Other(anyhow::anyhow!("Failed to get coordinator for library '{}': {}", library, e))
+            })?;
+            let report = coordinator.unused_memories(days, limit).await.map_err(|e| {
+                McpError::Other(anyhow::anyhow!("Failed to report on library '{}': {}", library, e))
+            })?;
+            reports.push(LibraryUnusedReport {
+                library,
+                memories_checked: report.memories_checked,
+                total_bytes: report.total_bytes,
+                unused_count: report.unused_count,
+                unused_bytes: report.unused_bytes,
+                memories: report
+                    .memories
+                    .into_iter()
+                    .map(|memory| UnusedMemoryEntry {
+                        id: memory.id,
+                        preview: memory.preview,
+                        created_at: memory.created_at,
+                        last_recalled_at: memory.last_recalled_at,
+                        recall_count: memory.recall_count,
+                        bytes: memory.bytes,
+                    })
+                    .collect(),
+            });
+        }
+
+        let unused_count = reports.iter().map(|r| r.unused_count).sum();
+        let unused_bytes = reports.iter().map(|r| r.unused_bytes).sum();
+        let checked: usize = reports.iter().map(|r| r.memories_checked).sum();
+
+        let mut summary = format!(
+            "✓ {} of {} memories not recalled in {} days ({})",
+            unused_count,
+            checked,
+            days,
+            format_bytes(unused_bytes)
+        );
+        for report in &reports {
+            summary.push_str(&format!(
+                "\n  {}: {} unused, {} of {}",
+                report.library,
+                report.unused_count,
+                format_bytes(report.unused_bytes),
+                format_bytes(report.total_bytes)
+            ));
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            UnusedMemoriesOutput {
+                days,
+                libraries: reports,
+                unused_count,
+                unused_bytes,
+            },
+        ))
+    }
+}
@@ -0,0 +1,102 @@
+//! Update Memory Tool - Edit a memory's content in place
+//!
+//! The memory keeps its ID, metadata and relationships. Its content hash and
+//! embedding are recomputed from the new content (see
+//! [`MemoryCoordinator::edit_memory`](crate::memory::core::manager::coordinator::MemoryCoordinator::edit_memory)),
+//! so recall matches what the memory now says.
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::memory::{
+    MEMORY_UPDATE, UpdateMemoryArgs, UpdateMemoryOutput, UpdateMemoryPrompts, UpdateMode,
+};
+use std::sync::Arc;
+
+use crate::memory::core::manager::coordinator::ContentEdit;
+use crate::memory::core::manager::pool::CoordinatorPool;
+
+#[derive(Clone)]
+pub struct UpdateMemoryTool {
+    pool: Arc<CoordinatorPool>,
+}
+
+impl UpdateMemoryTool {
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl Tool for UpdateMemoryTool {
+    type Args = UpdateMemoryArgs;
+    type Prompts = UpdateMemoryPrompts;
+
+    fn name() -> &'static str {
+        MEMORY_UPDATE
+    }
+
+    fn description() -> &'static str {
+        "Edit an existing memory by ID within a library. mode \"replace\" (default) overwrites its \
+         content; \"append\" adds the content on a new line. The memory keeps its ID, tags and \
+         relationships; its content hash and embedding are recomputed and updated_at is bumped, \
+         so recall finds it by what it now says."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        false // Appending twice appends twice
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl UpdateMemoryTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: UpdateMemoryArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<UpdateMemoryOutput>, McpError> {
+        if args.content.is_empty() {
+            return Err(McpError::Other(anyhow::anyhow!("content must not be empty")));
+        }
+
+        let coordinator = self.pool.get_coordinator(&args.library).await.map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to get coordinator for library '{}': {}", args.library, e))
+        })?;
+
+        let edit = match args.mode {
+            UpdateMode::Replace => ContentEdit::Replace(args.content),
+            UpdateMode::Append => ContentEdit::Append(args.content),
+        };
+        let memory = coordinator
+            .edit_memory(&args.memory_id, &edit)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to update memory: {}", e)))?
+            .ok_or_else(|| {
+                McpError::Other(anyhow::anyhow!(
+                    "Memory '{}' not found in library '{}'",
+                    args.memory_id,
+                    args.library
+                ))
+            })?;
+
+        let content_chars = memory.content().to_string().chars().count();
+        let embedding_dimension = memory.embedding.as_ref().map_or(0, |e| e.data.len());
+        let updated_at = memory.base_memory.updated_at.to_string();
+
+        Ok(ToolResponse::new(
+            format!(
+                "✓ Updated memory {} in library '{}' ({} chars, re-embedded)",
+                args.memory_id, args.library, content_chars
+            ),
+            UpdateMemoryOutput {
+                library: args.library,
+                memory_id: args.memory_id,
+                mode: args.mode,
+                content_chars,
+                embedding_dimension,
+                updated_at,
+            },
+        ))
+    }
+}
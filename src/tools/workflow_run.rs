@@ -0,0 +1,145 @@
+//! Workflow Run Tool - Execute a declarative YAML/JSON workflow
+
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::candle::{
+    CANDLE_WORKFLOW_RUN, WorkflowRunArgs, WorkflowRunOutput, WorkflowRunPrompts,
+};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio_stream::StreamExt;
+
+use crate::domain::context::WorkflowDataChunk;
+use crate::memory::core::manager::pool::CoordinatorPool;
+use crate::workflow::WorkflowSteps;
+use crate::workflow::definition::WorkflowDefinition;
+
+#[derive(Clone)]
+pub struct WorkflowRunTool {
+    pool: Arc<CoordinatorPool>,
+}
+
+impl WorkflowRunTool {
+    pub fn new(pool: Arc<CoordinatorPool>) -> Self {
+        Self { pool }
+    }
+
+    /// Definition text: the file contents when `definition` names a file
+    ///
+    /// Files are read only from the workspace, the server's working
+    /// directory.
+    fn definition_source(definition: &str) -> Result<String, McpError> {
+        let root = std::env::current_dir().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to resolve the workspace root: {}", e))
+        })?;
+        workspace_definition(&root, definition)
+    }
+}
+
+/// Definition text: the contents of the file under `root` when `definition`
+/// names a .yaml, .yml or .json file, otherwise `definition` itself
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or, once symlinks and `..`
+/// are resolved, lies outside `root`.
+pub fn workspace_definition(root: &Path, definition: &str) -> Result<String, McpError> {
+    let trimmed = definition.trim();
+    let looks_like_path = !trimmed.contains('\n')
+        && [".yaml", ".yml", ".json"].iter().any(|ext| trimmed.ends_with(ext));
+    if !looks_like_path {
+        return Ok(definition.to_string());
+    }
+
+    let read_error = |e: std::io::Error| {
+        McpError::Other(anyhow::anyhow!("Failed to read workflow file '{}': {}", trimmed, e))
+    };
+    let root = root.canonicalize().map_err(read_error)?;
+    let path = root.join(trimmed).canonicalize().map_err(read_error)?;
+    if !path.starts_with(&root) {
+        return Err(McpError::Other(anyhow::anyhow!(
+            "Workflow file '{}' is outside the workspace {}",
+            trimmed,
+            root.display()
+        )));
+    }
+    std::fs::read_to_string(&path).map_err(read_error)
+}
+
+impl Tool for WorkflowRunTool {
+    type Args = WorkflowRunArgs;
+    type Prompts = WorkflowRunPrompts;
+
+    fn name() -> &'static str {
+        CANDLE_WORKFLOW_RUN
+    }
+
+    fn description() -> &'static str {
+        "Run a declarative workflow defined in YAML or JSON (inline, or a path to a file in \
+         the workspace). Steps are recall (search a memory library), memorize (store a prior \
+         step's output), generate (prompt a registry model) and tool (call a kodegen tool); \
+         templates bind inputs and earlier step outputs with {{name}} or {{name.field}}. The definition is validated before running: \
+         unknown step types, missing variables and dependency cycles are rejected. Returns every \
+         step's output keyed by step name, or the error and the step that failed."
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        super::with_tool_request_id(self.run(args, ctx)).await
+    }
+}
+
+impl WorkflowRunTool {
+    /// Execute the tool; [`Tool::execute`] runs this under a request ID
+    async fn run(&self, args: WorkflowRunArgs, _ctx: ToolExecutionContext) -> Result<ToolResponse<WorkflowRunOutput>, McpError> {
+        let start = Instant::now();
+
+        let source = Self::definition_source(&args.definition)?;
+        let definition = WorkflowDefinition::parse(&source)
+            .map_err(|e| McpError::Other(anyhow::anyhow!("{}", e)))?;
+        let mut steps = WorkflowSteps::new().with_memory(self.pool.clone());
+        if definition.uses_tools() {
+            steps = steps.with_kodegen_tools().await;
+        }
+        let workflow = definition
+            .compile(&steps)
+            .map_err(|e| McpError::Other(anyhow::anyhow!("{}", e)))?;
+
+        let input = args.input.unwrap_or(serde_json::Value::Null);
+        let mut stream = workflow.execute(WorkflowDataChunk::from(input));
+        let mut result = WorkflowDataChunk::default();
+        while let Some(chunk) = stream.next().await {
+            result = chunk;
+        }
+
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        let summary = match &result.error_message {
+            Some(error) => format!(
+                "✗ Workflow '{}' failed at {}: {}",
+                definition.name,
+                result.step_name.as_deref().unwrap_or("start"),
+                error
+            ),
+            None => format!(
+                "✓ Workflow '{}' completed {} steps in {}ms",
+                definition.name,
+                definition.steps.len(),
+                elapsed_ms
+            ),
+        };
+
+        Ok(ToolResponse::new(
+            summary,
+            WorkflowRunOutput {
+                workflow: definition.name,
+                output: if result.error_message.is_some() {
+                    serde_json::Value::Null
+                } else {
+                    result.data
+                },
+                error: result.error_message,
+                step: result.step_name,
+                elapsed_ms,
+            },
+        ))
+    }
+}
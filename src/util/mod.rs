@@ -3,3 +3,5 @@
 pub mod input_resolver;
 pub mod json_util;
 pub mod output;
+
+pub use cyrup_sugars::ZeroOneOrMany;
@@ -109,6 +109,14 @@ impl<S> CandleExecutableWorkflow<S>
 where
     S: CandleWorkflowStep<WorkflowDataChunk, WorkflowDataChunk>,
 {
+    /// Workflow that runs `step`
+    pub fn new(step: S) -> Self {
+        Self {
+            step,
+            _phantom: PhantomData,
+        }
+    }
+
     /// Execute the workflow with streaming output
     ///
     /// Takes input and produces a stream of outputs using streams-only architecture.
@@ -149,6 +157,7 @@ where
 
 /// Sequential composition step that chains two steps together
 /// This replaces the Arc<dyn> pattern with concrete generic composition
+#[derive(Clone)]
 pub struct CandleSequentialStep<A, B>
 where
     A: CandleWorkflowStep<WorkflowDataChunk, WorkflowDataChunk> + Clone,
@@ -0,0 +1,588 @@
+//! Declarative workflow definitions in YAML or JSON
+//!
+//! A definition lists built-in steps (see [`super::steps`]) with their
+//! dependencies, templates, a budget and error policies:
+//!
+//! ```yaml
+//! name: answer-from-notes
+//! inputs: [question]
+//! budget:
+//!   timeout_ms: 120000
+//!   max_tokens: 1024
+//! steps:
+//!   - name: notes
+//!     type: recall
+//!     library: notes
+//!     query: "{{question}}"
+//!   - name: answer
+//!     type: generate
+//!     model: Qwen/Qwen2.5-Coder-3B-Instruct-GGUF
+//!     prompt: "Notes: {{notes}}\n\nQuestion: {{question}}"
+//!     on_error: { retry: 2 }
+//!   - type: memorize
+//!     library: answers
+//!     content_from: answer
+//! ```
+//!
+//! Definitions are validated when loaded: unknown step types, duplicate
+//! names, unknown or cyclic dependencies and template variables that are
+//! neither inputs nor earlier steps are all rejected before anything runs.
+//! A template reference to another step is an implicit dependency; steps run
+//! one at a time in dependency order, ties keeping their declared order.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::NonZeroU64;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use cyrup_sugars::prelude::MessageChunk;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_stream::{Stream, StreamExt};
+
+use super::core::{CandleExecutableWorkflow, CandleWorkflowStep};
+use super::steps::{
+    GenerateStep, MemorizeStep, RecallStep, ToolStep, WorkflowSteps, bindings, generate,
+    template_variables,
+};
+use crate::domain::completion::CandleCompletionParams;
+use crate::domain::context::WorkflowDataChunk;
+
+/// Step types a definition may use
+pub const STEP_TYPES: [&str; 4] = ["recall", "memorize", "generate", "tool"];
+
+/// Errors found while loading a workflow definition
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WorkflowDefinitionError {
+    #[error("Failed to read workflow file: {0}")]
+    Io(String),
+
+    #[error("Invalid workflow definition: {0}")]
+    Parse(String),
+
+    #[error("Workflow has no steps")]
+    Empty,
+
+    #[error("Step {index} has unknown type '{kind}' (expected one of: recall, memorize, generate, tool)")]
+    UnknownStepType { index: usize, kind: String },
+
+    #[error("Step name '{0}' is used more than once; give the steps distinct names")]
+    DuplicateStep(String),
+
+    #[error("Step '{step}' depends on unknown step '{dependency}'")]
+    UnknownDependency { step: String, dependency: String },
+
+    #[error("Step '{step}' references '{variable}', which is neither an input nor another step")]
+    UnboundVariable { step: String, variable: String },
+
+    #[error("Steps form a dependency cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
+
+/// What a step does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepAction {
+    /// Search a memory library
+    Recall {
+        library: String,
+        /// Query template
+        query: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<usize>,
+    },
+    /// Store a prior step's output in a memory library
+    Memorize {
+        library: String,
+        /// Binding path of the content, usually a step name
+        content_from: String,
+    },
+    /// Generate text with a registry model
+    Generate {
+        model: String,
+        /// Prompt template
+        prompt: String,
+    },
+    /// Call a tool
+    Tool {
+        tool: String,
+        /// Argument template; strings may contain `{{...}}` references
+        #[serde(default)]
+        args: Value,
+    },
+}
+
+impl StepAction {
+    /// Name used when the step does not set one
+    fn default_name(&self) -> &str {
+        match self {
+            Self::Recall { .. } => "recall",
+            Self::Memorize { .. } => "memorize",
+            Self::Generate { .. } => "generate",
+            Self::Tool { tool, .. } => tool,
+        }
+    }
+
+    /// Root binding names referenced by the step's templates
+    fn references(&self) -> Vec<String> {
+        let paths = match self {
+            Self::Recall { query, .. } => template_variables(query),
+            Self::Memorize { content_from, .. } => vec![content_from.clone()],
+            Self::Generate { prompt, .. } => template_variables(prompt),
+            Self::Tool { args, .. } => {
+                let mut paths = Vec::new();
+                collect_value_variables(args, &mut paths);
+                paths
+            }
+        };
+        paths
+            .into_iter()
+            .map(|path| path.split('.').next().unwrap_or_default().to_string())
+            .collect()
+    }
+}
+
+fn collect_value_variables(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.extend(template_variables(s)),
+        Value::Array(items) => items.iter().for_each(|v| collect_value_variables(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_value_variables(v, out)),
+        _ => {}
+    }
+}
+
+/// What happens when a step fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorPolicy {
+    /// Stop the workflow with the error
+    #[default]
+    Fail,
+    /// Bind `{"error": message}` for the step and carry on
+    Continue,
+    /// Run the step up to this many more times, then fail
+    Retry(u32),
+}
+
+/// Limits on a whole workflow run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowBudget {
+    /// Wall-clock limit for the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Most step executions, retries included
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_steps: Option<usize>,
+    /// Token limit for each generate step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+}
+
+/// One declared step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StepDefinition {
+    /// Name its output is bound under; defaults to the step type (or tool name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub action: StepAction,
+    /// Steps that must run first, in addition to those referenced by templates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Overrides the workflow's error policy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<ErrorPolicy>,
+    /// Time limit for one attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl StepDefinition {
+    /// Name the step's output is bound under
+    pub fn name(&self) -> &str {
+        self.name
+            .as_deref()
+            .unwrap_or_else(|| self.action.default_name())
+    }
+}
+
+/// A declarative workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowDefinition {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Variables the caller must supply
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    #[serde(default)]
+    pub budget: WorkflowBudget,
+    /// Policy for steps that do not set `on_error`
+    #[serde(default)]
+    pub on_error: ErrorPolicy,
+    pub steps: Vec<StepDefinition>,
+}
+
+impl WorkflowDefinition {
+    /// Parse and validate a YAML or JSON definition
+    ///
+    /// # Errors
+    /// Returns the first parse or validation problem found.
+    pub fn parse(source: &str) -> Result<Self, WorkflowDefinitionError> {
+        // YAML is a superset of JSON, so one parser handles both
+        let raw: Value = serde_yaml::from_str(source)
+            .map_err(|e| WorkflowDefinitionError::Parse(e.to_string()))?;
+
+        if let Some(steps) = raw.get("steps").and_then(Value::as_array) {
+            for (index, step) in steps.iter().enumerate() {
+                let kind = step.get("type").and_then(Value::as_str).unwrap_or_default();
+                if !STEP_TYPES.contains(&kind) {
+                    return Err(WorkflowDefinitionError::UnknownStepType {
+                        index,
+                        kind: kind.to_string(),
+                    });
+                }
+            }
+        }
+
+        let definition: Self = serde_json::from_value(raw)
+            .map_err(|e| WorkflowDefinitionError::Parse(e.to_string()))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Read, parse and validate a definition file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is invalid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WorkflowDefinitionError> {
+        let source = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| WorkflowDefinitionError::Io(format!("{}: {}", path.as_ref().display(), e)))?;
+        Self::parse(&source)
+    }
+
+    /// Check names, dependencies and template variables
+    ///
+    /// # Errors
+    /// Returns the first problem found.
+    pub fn validate(&self) -> Result<(), WorkflowDefinitionError> {
+        self.execution_order().map(|_| ())
+    }
+
+    /// Whether any step calls a tool
+    pub fn uses_tools(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| matches!(step.action, StepAction::Tool { .. }))
+    }
+
+    /// Step indices in the order they run
+    fn execution_order(&self) -> Result<Vec<usize>, WorkflowDefinitionError> {
+        if self.steps.is_empty() {
+            return Err(WorkflowDefinitionError::Empty);
+        }
+
+        let mut index_of = HashMap::with_capacity(self.steps.len());
+        for (i, step) in self.steps.iter().enumerate() {
+            if index_of.insert(step.name(), i).is_some() {
+                return Err(WorkflowDefinitionError::DuplicateStep(step.name().to_string()));
+            }
+        }
+
+        // Explicit dependencies plus steps referenced by templates
+        let mut deps: Vec<Vec<usize>> = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            let mut step_deps = Vec::new();
+            for dependency in &step.depends_on {
+                match index_of.get(dependency.as_str()) {
+                    Some(&i) => step_deps.push(i),
+                    None => {
+                        return Err(WorkflowDefinitionError::UnknownDependency {
+                            step: step.name().to_string(),
+                            dependency: dependency.clone(),
+                        });
+                    }
+                }
+            }
+            for variable in step.action.references() {
+                if let Some(&i) = index_of.get(variable.as_str())
+                    && variable != step.name()
+                {
+                    step_deps.push(i);
+                } else if !self.inputs.contains(&variable) {
+                    return Err(WorkflowDefinitionError::UnboundVariable {
+                        step: step.name().to_string(),
+                        variable,
+                    });
+                }
+            }
+            deps.push(step_deps);
+        }
+
+        // Kahn's algorithm, always taking the earliest declared ready step
+        let mut order = Vec::with_capacity(self.steps.len());
+        let mut done = HashSet::with_capacity(self.steps.len());
+        while order.len() < self.steps.len() {
+            let next = (0..self.steps.len())
+                .find(|i| !done.contains(i) && deps[*i].iter().all(|d| done.contains(d)));
+            match next {
+                Some(i) => {
+                    done.insert(i);
+                    order.push(i);
+                }
+                None => {
+                    let cycle = (0..self.steps.len())
+                        .filter(|i| !done.contains(i))
+                        .map(|i| self.steps[i].name().to_string())
+                        .collect();
+                    return Err(WorkflowDefinitionError::Cycle(cycle));
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Build an executable workflow using `resources` for memory and tools
+    ///
+    /// # Errors
+    /// Returns an error if the definition is invalid.
+    pub fn compile(
+        &self,
+        resources: &WorkflowSteps,
+    ) -> Result<CandleExecutableWorkflow<DefinedWorkflow>, WorkflowDefinitionError> {
+        let order = self.execution_order()?;
+        let steps = order
+            .into_iter()
+            .map(|i| {
+                let step = &self.steps[i];
+                CompiledStep {
+                    name: step.name().to_string(),
+                    runner: StepRunner::new(step, resources, &self.budget),
+                    on_error: step.on_error.unwrap_or(self.on_error),
+                    timeout: step.timeout_ms.map(Duration::from_millis),
+                }
+            })
+            .collect();
+
+        Ok(CandleExecutableWorkflow::new(DefinedWorkflow {
+            name: self.name.clone(),
+            inputs: self.inputs.clone(),
+            budget: self.budget.clone(),
+            steps: Arc::new(steps),
+        }))
+    }
+}
+
+#[derive(Clone)]
+enum StepRunner {
+    Recall(RecallStep),
+    Memorize(MemorizeStep),
+    Generate(GenerateStep),
+    Tool(ToolStep),
+}
+
+impl StepRunner {
+    fn new(step: &StepDefinition, resources: &WorkflowSteps, budget: &WorkflowBudget) -> Self {
+        let name = step.name();
+        match &step.action {
+            StepAction::Recall {
+                library,
+                query,
+                limit,
+            } => {
+                let recall = resources.recall(library, query).named(name);
+                Self::Recall(match limit {
+                    Some(limit) => recall.limit(*limit),
+                    None => recall,
+                })
+            }
+            StepAction::Memorize {
+                library,
+                content_from,
+            } => Self::Memorize(resources.memorize(library, content_from).named(name)),
+            StepAction::Generate { model, prompt } => {
+                let params = CandleCompletionParams::default()
+                    .with_max_tokens(budget.max_tokens.and_then(NonZeroU64::new));
+                Self::Generate(generate(model, prompt).named(name).params(params))
+            }
+            StepAction::Tool { tool, args } => {
+                Self::Tool(resources.tool(tool, args.clone()).named(name))
+            }
+        }
+    }
+
+    fn execute(&self, input: WorkflowDataChunk) -> Pin<Box<dyn Stream<Item = WorkflowDataChunk> + Send>> {
+        match self {
+            Self::Recall(step) => step.execute(input),
+            Self::Memorize(step) => step.execute(input),
+            Self::Generate(step) => step.execute(input),
+            Self::Tool(step) => step.execute(input),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct CompiledStep {
+    name: String,
+    runner: StepRunner,
+    on_error: ErrorPolicy,
+    timeout: Option<Duration>,
+}
+
+impl CompiledStep {
+    /// One attempt: the step's last chunk, or an error chunk on timeout
+    async fn attempt(&self, input: WorkflowDataChunk, limit: Option<Duration>) -> WorkflowDataChunk {
+        let run = async {
+            let mut stream = self.runner.execute(input);
+            let mut last = None;
+            while let Some(chunk) = stream.next().await {
+                last = Some(chunk);
+            }
+            last.unwrap_or_else(|| WorkflowDataChunk::bad_chunk(format!("{}: produced no output", self.name)))
+        };
+        match limit {
+            Some(limit) => tokio::time::timeout(limit, run).await.unwrap_or_else(|_| {
+                WorkflowDataChunk::bad_chunk(format!("{}: timed out after {}ms", self.name, limit.as_millis()))
+            }),
+            None => run.await,
+        }
+    }
+}
+
+/// Workflow step compiled from a [`WorkflowDefinition`]
+#[derive(Clone)]
+pub struct DefinedWorkflow {
+    name: String,
+    inputs: Vec<String>,
+    budget: WorkflowBudget,
+    steps: Arc<Vec<CompiledStep>>,
+}
+
+impl DefinedWorkflow {
+    /// Workflow name from the definition
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Step names in execution order
+    pub fn step_names(&self) -> Vec<&str> {
+        self.steps.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    async fn run(self, input: WorkflowDataChunk) -> WorkflowDataChunk {
+        let mut vars = bindings(&input);
+        let missing: Vec<&str> = self
+            .inputs
+            .iter()
+            .filter(|name| !vars.contains_key(name.as_str()))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return WorkflowDataChunk::bad_chunk(format!("Missing workflow inputs: {}", missing.join(", ")));
+        }
+
+        let started = Instant::now();
+        let deadline = self.budget.timeout_ms.map(Duration::from_millis);
+        let mut executions = 0usize;
+        let mut last_step = None;
+
+        for step in self.steps.iter() {
+            let mut retries_left = match step.on_error {
+                ErrorPolicy::Retry(n) => n,
+                _ => 0,
+            };
+            loop {
+                if self.budget.max_steps.is_some_and(|max| executions >= max) {
+                    return WorkflowDataChunk::bad_chunk(format!(
+                        "Workflow step budget of {} executions exhausted before '{}'",
+                        executions, step.name
+                    ));
+                }
+                let remaining = match deadline {
+                    Some(deadline) => match deadline.checked_sub(started.elapsed()) {
+                        Some(remaining) => Some(remaining),
+                        None => {
+                            return WorkflowDataChunk::bad_chunk(format!(
+                                "Workflow timed out after {}ms before '{}'",
+                                deadline.as_millis(),
+                                step.name
+                            ));
+                        }
+                    },
+                    None => None,
+                };
+                let limit = match (step.timeout, remaining) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+
+                executions += 1;
+                let chunk = step
+                    .attempt(WorkflowDataChunk::from(Value::Object(vars.clone())), limit)
+                    .await;
+                let Some(error) = chunk.error().map(str::to_string) else {
+                    vars = bindings(&chunk);
+                    break;
+                };
+
+                if retries_left > 0 {
+                    retries_left -= 1;
+                    log::warn!("Workflow step '{}' failed, retrying: {}", step.name, error);
+                    continue;
+                }
+                match step.on_error {
+                    ErrorPolicy::Continue => {
+                        log::warn!("Workflow step '{}' failed, continuing: {}", step.name, error);
+                        vars.insert(step.name.clone(), serde_json::json!({ "error": error }));
+                        break;
+                    }
+                    ErrorPolicy::Fail | ErrorPolicy::Retry(_) => {
+                        let mut chunk = WorkflowDataChunk::bad_chunk(error);
+                        chunk.step_name = Some(step.name.clone());
+                        return chunk;
+                    }
+                }
+            }
+            last_step = Some(step.name.clone());
+        }
+
+        let mut output = WorkflowDataChunk::from(Value::Object(vars));
+        output.step_name = last_step;
+        output
+    }
+}
+
+impl CandleWorkflowStep<WorkflowDataChunk, WorkflowDataChunk> for DefinedWorkflow {
+    fn execute(&self, input: WorkflowDataChunk) -> Pin<Box<dyn Stream<Item = WorkflowDataChunk> + Send>> {
+        let workflow = self.clone();
+        Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
+            let _ = tx.send(workflow.run(input).await);
+        }))
+    }
+}
+
+/// Parse `input` as workflow bindings: a JSON object, or `key=value` pairs
+///
+/// Used by the CLI and MCP tool so both accept the same input forms.
+pub fn parse_inputs<'a>(input: impl IntoIterator<Item = &'a str>) -> Result<Value, String> {
+    let mut map = BTreeMap::new();
+    for item in input {
+        let item = item.trim();
+        if item.starts_with('{') {
+            let Value::Object(object) = serde_json::from_str::<Value>(item).map_err(|e| e.to_string())? else {
+                return Err("workflow input must be a JSON object".to_string());
+            };
+            map.extend(object);
+        } else {
+            let (key, value) = item
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value or a JSON object, got '{item}'"))?;
+            map.insert(key.trim().to_string(), Value::String(value.to_string()));
+        }
+    }
+    Ok(Value::Object(map.into_iter().collect()))
+}
@@ -9,6 +9,8 @@
 //! - **ops**: Zero-cost operation combinators and transformations
 //! - **parallel**: Thread-based parallel execution combinators  
 //! - **macros**: Compile-time variadic parallel execution macros
+//! - **definition**: YAML/JSON workflow definitions with load-time validation
+//! - **steps**: Built-in recall, memorize, generate and tool steps with templates
 //!
 //! ## Architecture Principles
 //! - Zero-allocation with PhantomData for type safety
@@ -19,9 +21,11 @@
 //! - Lock-free design for maximum throughput
 
 pub mod core;
+pub mod definition;
 pub mod macros;
 pub mod ops;
 pub mod parallel;
+pub mod steps;
 
 // Re-export candle core types for ergonomic imports
 pub use core::{CandleExecutableWorkflow, CandleWorkflowStep, candle_workflow};
@@ -30,3 +34,15 @@ pub use core::{CandleExecutableWorkflow, CandleWorkflowStep, candle_workflow};
 pub use macros::parallel;
 pub use ops::{DynOp, Op, map, passthrough, then};
 pub use parallel::{ParallelBuilder, ParallelN};
+
+// Re-export declarative definitions
+pub use definition::{
+    DefinedWorkflow, ErrorPolicy, StepAction, StepDefinition, WorkflowBudget, WorkflowDefinition,
+    WorkflowDefinitionError,
+};
+
+// Re-export built-in steps
+pub use steps::{
+    GenerateStep, MemorizeStep, RecallStep, ToolStep, WorkflowSteps, generate, render_template,
+    render_value,
+};
@@ -0,0 +1,549 @@
+//! Built-in workflow steps for memory, model and tool calls
+//!
+//! Declarative steps for the operations most workflows need, so they can be
+//! chained without writing a custom [`CandleWorkflowStep`] for each one:
+//!
+//! ```rust,no_run
+//! use kodegen_candle_agent::workflow::{WorkflowSteps, candle_workflow, generate};
+//!
+//! # fn build(steps: WorkflowSteps) {
+//! let workflow = candle_workflow()
+//!     .then(steps.recall("notes", "{{question}}"))
+//!     .then(generate(
+//!         "Qwen/Qwen2.5-Coder-3B-Instruct-GGUF",
+//!         "Notes: {{recall}}\n\nAnswer: {{question}}",
+//!     ))
+//!     .then(steps.memorize("answers", "generate"));
+//! # }
+//! ```
+//!
+//! ## Bindings
+//!
+//! Every built-in step reads its input chunk's `data` as a JSON object of
+//! bindings and passes the same object on with its own output added under
+//! its name (`recall`, `memorize`, `generate`, or the tool name; override
+//! with `.named(..)`). A non-object input is bound as `input`.
+//!
+//! Templates reference bindings as `{{name}}` or `{{name.field.0}}`. Strings
+//! are inserted as-is and other values as JSON. In tool argument templates, a
+//! string that is exactly one `{{...}}` reference is replaced by the bound
+//! value itself, keeping its JSON type. Unbound references end the step with
+//! an error chunk.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use cyrup_sugars::prelude::MessageChunk;
+use serde_json::{Map, Value};
+use tokio_stream::{Stream, StreamExt};
+
+use super::core::CandleWorkflowStep;
+use crate::capability::registry::{self, TextToTextModel};
+use crate::capability::traits::TextToTextCapable;
+use crate::domain::completion::{CandleCompletionChunk, CandleCompletionParams};
+use crate::domain::context::WorkflowDataChunk;
+#[cfg(feature = "memory")]
+use crate::domain::memory::primitives::types::MemoryTypeEnum;
+use crate::domain::prompt::CandlePrompt;
+use crate::domain::tool::router::CandleToolRouter;
+#[cfg(feature = "mcp")]
+use crate::domain::tool::safety::SafetyPolicy;
+#[cfg(feature = "memory")]
+use crate::memory::core::manager::pool::CoordinatorPool;
+
+/// Builds without the `memory` feature have no libraries to recall from
+#[cfg(not(feature = "memory"))]
+type CoordinatorPool = std::convert::Infallible;
+
+/// Results returned by a recall step unless configured otherwise
+pub const DEFAULT_WORKFLOW_RECALL_LIMIT: usize = 5;
+
+/// Bindings carried by a chunk: its data object, or `{"input": data}`
+pub fn bindings(chunk: &WorkflowDataChunk) -> Map<String, Value> {
+    match &chunk.data {
+        Value::Object(map) => map.clone(),
+        Value::Null => Map::new(),
+        other => Map::from_iter([("input".to_string(), other.clone())]),
+    }
+}
+
+/// Look up a dotted binding path such as `recall.0.content`
+pub fn lookup<'a>(bindings: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut value = bindings.get(parts.next()?)?;
+    for part in parts {
+        value = match value {
+            Value::Object(map) => map.get(part)?,
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Paths referenced by `{{...}}` in `template`, in order of appearance
+pub fn template_variables(template: &str) -> Vec<String> {
+    let mut variables = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        variables.push(after[..end].trim().to_string());
+        rest = &after[end + 2..];
+    }
+    variables
+}
+
+/// Substitute every `{{path}}` in `template`
+///
+/// # Errors
+/// Returns the first unbound path or unterminated reference.
+pub fn render_template(template: &str, bindings: &Map<String, Value>) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("Unterminated template reference in '{template}'"))?;
+        let path = after[..end].trim();
+        match lookup(bindings, path) {
+            Some(Value::String(s)) => out.push_str(s),
+            Some(value) => out.push_str(&value.to_string()),
+            None => return Err(format!("Unbound template variable '{path}'")),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Render every string inside a JSON template
+///
+/// A string that is exactly one `{{path}}` reference becomes the bound
+/// value itself.
+///
+/// # Errors
+/// Returns the first unbound path or unterminated reference.
+pub fn render_value(template: &Value, bindings: &Map<String, Value>) -> Result<Value, String> {
+    match template {
+        Value::String(s) => {
+            let trimmed = s.trim();
+            if let Some(path) = trimmed
+                .strip_prefix("{{")
+                .and_then(|p| p.strip_suffix("}}"))
+                .filter(|p| !p.contains("{{") && !p.contains("}}"))
+            {
+                let path = path.trim();
+                return lookup(bindings, path)
+                    .cloned()
+                    .ok_or_else(|| format!("Unbound template variable '{path}'"));
+            }
+            render_template(s, bindings).map(Value::String)
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| render_value(item, bindings))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| render_value(v, bindings).map(|v| (k.clone(), v)))
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+/// Chunk carrying `bindings` plus `output` under `name`
+fn bound_output(
+    mut bindings: Map<String, Value>,
+    name: &str,
+    output: Value,
+) -> WorkflowDataChunk {
+    bindings.insert(name.to_string(), output);
+    let mut chunk = WorkflowDataChunk::from(Value::Object(bindings));
+    chunk.step_name = Some(name.to_string());
+    chunk
+}
+
+/// Error chunk attributed to step `name`
+fn step_error(name: &str, error: impl std::fmt::Display) -> WorkflowDataChunk {
+    let mut chunk = WorkflowDataChunk::bad_chunk(format!("{name}: {error}"));
+    chunk.step_name = Some(name.to_string());
+    chunk
+}
+
+/// Run `body` for each input, forwarding error chunks untouched
+fn run_step<F, Fut>(
+    name: String,
+    input: WorkflowDataChunk,
+    body: F,
+) -> Pin<Box<dyn Stream<Item = WorkflowDataChunk> + Send>>
+where
+    F: FnOnce(Map<String, Value>) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<Value, String>> + Send,
+{
+    Box::pin(crate::async_stream::spawn_stream(move |tx| async move {
+        if input.error().is_some() {
+            let _ = tx.send(input);
+            return;
+        }
+        let bindings = bindings(&input);
+        let chunk = match body(bindings.clone()).await {
+            Ok(output) => bound_output(bindings, &name, output),
+            Err(e) => step_error(&name, e),
+        };
+        let _ = tx.send(chunk);
+    }))
+}
+
+/// Resources the memory and tool steps run against
+#[derive(Clone, Default)]
+pub struct WorkflowSteps {
+    memory: Option<Arc<CoordinatorPool>>,
+    tools: Option<CandleToolRouter>,
+}
+
+impl WorkflowSteps {
+    /// Steps without memory or tools; add them with the `with_*` methods
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Memory libraries used by [`Self::recall`] and [`Self::memorize`]
+    #[cfg(feature = "memory")]
+    #[must_use]
+    pub fn with_memory(mut self, pool: Arc<CoordinatorPool>) -> Self {
+        self.memory = Some(pool);
+        self
+    }
+
+    /// Tool router used by [`Self::tool`]
+    #[must_use]
+    pub fn with_tools(mut self, router: CandleToolRouter) -> Self {
+        self.tools = Some(router);
+        self
+    }
+
+    /// Tools of a spawned local `kodegen` process, behind the default
+    /// [`SafetyPolicy`]
+    ///
+    /// If kodegen cannot be spawned the steps are returned without tools,
+    /// and tool steps fail when run.
+    #[cfg(feature = "mcp")]
+    pub async fn with_kodegen_tools(self) -> Self {
+        match kodegen_mcp_client::create_stdio_client("kodegen", &[]).await {
+            Ok((client, _connection)) => self.with_tools(
+                CandleToolRouter::new(Some(client)).with_safety_policy(SafetyPolicy::default()),
+            ),
+            Err(e) => {
+                log::warn!("Failed to spawn kodegen: {e} - workflow tool steps will fail");
+                self
+            }
+        }
+    }
+
+    /// Without the `mcp` feature there is no kodegen to spawn
+    #[cfg(not(feature = "mcp"))]
+    pub async fn with_kodegen_tools(self) -> Self {
+        log::warn!("Workflow tool steps need the `mcp` feature");
+        self
+    }
+
+    /// Search `library` for the rendered `query_template`
+    ///
+    /// Binds an array of `{id, content, similarity}` objects.
+    pub fn recall(&self, library: impl Into<String>, query_template: impl Into<String>) -> RecallStep {
+        RecallStep {
+            name: "recall".to_string(),
+            memory: self.memory.clone(),
+            library: library.into(),
+            query_template: query_template.into(),
+            limit: DEFAULT_WORKFLOW_RECALL_LIMIT,
+        }
+    }
+
+    /// Store the binding at `content_from_step` in `library`
+    ///
+    /// `content_from_step` is a binding path, usually a prior step's name.
+    /// Binds the new memory's ID.
+    pub fn memorize(
+        &self,
+        library: impl Into<String>,
+        content_from_step: impl Into<String>,
+    ) -> MemorizeStep {
+        MemorizeStep {
+            name: "memorize".to_string(),
+            memory: self.memory.clone(),
+            library: library.into(),
+            content_from: content_from_step.into(),
+        }
+    }
+
+    /// Call tool `name` with the rendered `args_template`
+    ///
+    /// Binds the tool's JSON result under the tool name.
+    pub fn tool(&self, name: impl Into<String>, args_template: Value) -> ToolStep {
+        let tool = name.into();
+        ToolStep {
+            name: tool.clone(),
+            router: self.tools.clone(),
+            tool,
+            args_template,
+        }
+    }
+}
+
+/// Generate text with registry model `model` from the rendered `prompt_template`
+///
+/// Binds the complete response text.
+pub fn generate(model: impl Into<String>, prompt_template: impl Into<String>) -> GenerateStep {
+    GenerateStep {
+        name: "generate".to_string(),
+        model: model.into(),
+        prompt_template: prompt_template.into(),
+        params: CandleCompletionParams::default(),
+    }
+}
+
+/// Step created by [`WorkflowSteps::recall`]
+#[derive(Clone)]
+pub struct RecallStep {
+    name: String,
+    memory: Option<Arc<CoordinatorPool>>,
+    library: String,
+    query_template: String,
+    limit: usize,
+}
+
+impl RecallStep {
+    /// Name the output is bound under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Bind the output under `name` instead of `recall`
+    #[must_use]
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Most memories returned
+    #[must_use]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
+impl CandleWorkflowStep<WorkflowDataChunk, WorkflowDataChunk> for RecallStep {
+    fn execute(&self, input: WorkflowDataChunk) -> Pin<Box<dyn Stream<Item = WorkflowDataChunk> + Send>> {
+        let step = self.clone();
+        run_step(self.name.clone(), input, move |bindings| async move {
+            let pool = step.memory.ok_or("no memory configured; use WorkflowSteps::with_memory")?;
+            let query = render_template(&step.query_template, &bindings)?;
+            recall_memories(&pool, &step.library, &query, step.limit).await
+        })
+    }
+}
+
+/// Search `library` for `query`, as `{id, content, similarity}` objects
+#[cfg(feature = "memory")]
+async fn recall_memories(
+    pool: &CoordinatorPool,
+    library: &str,
+    query: &str,
+    limit: usize,
+) -> Result<Value, String> {
+    let coordinator = pool
+        .get_coordinator(library)
+        .await
+        .map_err(|e| e.to_string())?;
+    let memories = coordinator
+        .search_memories(query, limit, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Value::Array(
+        memories
+            .iter()
+            .map(|memory| {
+                serde_json::json!({
+                    "id": memory.id().to_string(),
+                    "content": memory.content().to_string(),
+                    "similarity": memory.metadata.custom.get("similarity").map(|v| (**v).clone()),
+                })
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(not(feature = "memory"))]
+async fn recall_memories(
+    pool: &CoordinatorPool,
+    _library: &str,
+    _query: &str,
+    _limit: usize,
+) -> Result<Value, String> {
+    match *pool {}
+}
+
+/// Step created by [`WorkflowSteps::memorize`]
+#[derive(Clone)]
+pub struct MemorizeStep {
+    name: String,
+    memory: Option<Arc<CoordinatorPool>>,
+    library: String,
+    content_from: String,
+}
+
+impl MemorizeStep {
+    /// Name the output is bound under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Bind the output under `name` instead of `memorize`
+    #[must_use]
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+impl CandleWorkflowStep<WorkflowDataChunk, WorkflowDataChunk> for MemorizeStep {
+    fn execute(&self, input: WorkflowDataChunk) -> Pin<Box<dyn Stream<Item = WorkflowDataChunk> + Send>> {
+        let step = self.clone();
+        run_step(self.name.clone(), input, move |bindings| async move {
+            let pool = step.memory.ok_or("no memory configured; use WorkflowSteps::with_memory")?;
+            let content = match lookup(&bindings, &step.content_from) {
+                Some(Value::String(s)) => s.clone(),
+                Some(value) => value.to_string(),
+                None => return Err(format!("Unbound template variable '{}'", step.content_from)),
+            };
+            store_memory(&pool, &step.library, content).await
+        })
+    }
+}
+
+/// Store `content` in `library`, returning the new memory's ID
+#[cfg(feature = "memory")]
+async fn store_memory(
+    pool: &CoordinatorPool,
+    library: &str,
+    content: String,
+) -> Result<Value, String> {
+    let coordinator = pool
+        .get_coordinator(library)
+        .await
+        .map_err(|e| e.to_string())?;
+    let memory = coordinator
+        .add_memory(content, MemoryTypeEnum::LongTerm, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Value::String(memory.id().to_string()))
+}
+
+#[cfg(not(feature = "memory"))]
+async fn store_memory(
+    pool: &CoordinatorPool,
+    _library: &str,
+    _content: String,
+) -> Result<Value, String> {
+    match *pool {}
+}
+
+/// Step created by [`generate`]
+#[derive(Clone)]
+pub struct GenerateStep {
+    name: String,
+    model: String,
+    prompt_template: String,
+    params: CandleCompletionParams,
+}
+
+impl GenerateStep {
+    /// Name the output is bound under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Bind the output under `name` instead of `generate`
+    #[must_use]
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Completion parameters (temperature, max tokens, ...)
+    #[must_use]
+    pub fn params(mut self, params: CandleCompletionParams) -> Self {
+        self.params = params;
+        self
+    }
+}
+
+impl CandleWorkflowStep<WorkflowDataChunk, WorkflowDataChunk> for GenerateStep {
+    fn execute(&self, input: WorkflowDataChunk) -> Pin<Box<dyn Stream<Item = WorkflowDataChunk> + Send>> {
+        let step = self.clone();
+        run_step(self.name.clone(), input, move |bindings| async move {
+            let prompt = render_template(&step.prompt_template, &bindings)?;
+            let model: TextToTextModel = registry::get(&step.model)
+                .ok_or_else(|| format!("Model '{}' not found in registry", step.model))?;
+
+            let mut stream = model.prompt(CandlePrompt::new(prompt), &step.params);
+            let mut text = String::new();
+            while let Some(chunk) = stream.next().await {
+                match chunk {
+                    CandleCompletionChunk::Text(t) => text.push_str(&t),
+                    CandleCompletionChunk::Complete { text: t, .. } => text.push_str(&t),
+                    CandleCompletionChunk::Error(e) => return Err(e),
+                    _ => {}
+                }
+            }
+            Ok(Value::String(text))
+        })
+    }
+}
+
+/// Step created by [`WorkflowSteps::tool`]
+#[derive(Clone)]
+pub struct ToolStep {
+    name: String,
+    router: Option<CandleToolRouter>,
+    tool: String,
+    args_template: Value,
+}
+
+impl ToolStep {
+    /// Name the output is bound under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Bind the output under `name` instead of the tool name
+    #[must_use]
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+impl CandleWorkflowStep<WorkflowDataChunk, WorkflowDataChunk> for ToolStep {
+    fn execute(&self, input: WorkflowDataChunk) -> Pin<Box<dyn Stream<Item = WorkflowDataChunk> + Send>> {
+        let step = self.clone();
+        run_step(self.name.clone(), input, move |bindings| async move {
+            let router = step.router.ok_or("no tools configured; use WorkflowSteps::with_tools")?;
+            let args = render_value(&step.args_template, &bindings)?;
+            router
+                .call_tool(&step.tool, args, None)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+}
@@ -1,6 +1,13 @@
 // Integration tests for builder operations
 
 mod builders {
+    mod agent_role {
+        mod test_conversation_history;
+    }
+    mod document {
+        mod test_batch;
+    }
     mod test_embedding;
+    mod test_experiment;
     mod test_vision;
 }
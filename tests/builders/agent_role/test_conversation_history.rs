@@ -0,0 +1,68 @@
+// Tests for src/builders/agent_role/conversation_history.rs
+
+use cyrup_sugars::ZeroOneOrMany;
+use kodegen_candle_agent::builders::{ConversationHistory, ConversationHistoryArgs};
+use kodegen_candle_agent::conversation_history;
+use kodegen_candle_agent::prelude::CandleMessageRole;
+
+fn contents(history: &ConversationHistory) -> Vec<&str> {
+    history
+        .iter()
+        .map(|(_, content)| content.as_str())
+        .collect()
+}
+
+#[test]
+fn tuples_concatenate_in_order() {
+    let history = (
+        (CandleMessageRole::System, "a"),
+        (CandleMessageRole::User, "b".to_string()),
+        (
+            (CandleMessageRole::Assistant, "c"),
+            (CandleMessageRole::User, "d"),
+        ),
+        (CandleMessageRole::Assistant, "e"),
+    )
+        .into_history();
+
+    assert_eq!(contents(&history), ["a", "b", "c", "d", "e"]);
+    assert_eq!(history.messages()[0].0, CandleMessageRole::System);
+}
+
+#[test]
+fn macro_push_and_extend_agree() {
+    let from_macro = conversation_history![
+        CandleMessageRole::User => "hi",
+        CandleMessageRole::Assistant => "hello",
+    ];
+
+    let mut built = ConversationHistory::new();
+    built.push(CandleMessageRole::User, "hi");
+    built.extend([(CandleMessageRole::Assistant, "hello")]);
+
+    assert_eq!(from_macro, built);
+    assert_eq!(
+        vec![
+            (CandleMessageRole::User, "hi"),
+            (CandleMessageRole::Assistant, "hello")
+        ]
+        .into_history(),
+        built
+    );
+    assert!(conversation_history![].is_empty());
+}
+
+#[test]
+fn converts_to_and_from_zero_one_or_many() {
+    let single: ZeroOneOrMany<_> = (CandleMessageRole::User, "only").into_history().into();
+    assert_eq!(single.len(), 1);
+    assert!(
+        matches!(single.first(), Some((CandleMessageRole::User, text)) if text == "only")
+    );
+
+    let empty: ZeroOneOrMany<(CandleMessageRole, String)> = ConversationHistory::new().into();
+    assert!(matches!(empty, ZeroOneOrMany::None));
+
+    let back = ConversationHistory::from(single);
+    assert_eq!(contents(&back), ["only"]);
+}
@@ -0,0 +1,156 @@
+// Tests for src/builders/document/batch.rs
+
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use kodegen_candle_agent::builders::document::{
+    BatchProgress, DocumentBatch, DocumentLoadError, DocumentSource,
+};
+
+#[test]
+fn test_sources_are_classified() {
+    assert_eq!(
+        DocumentSource::parse("https://example.com/a.md"),
+        DocumentSource::Url("https://example.com/a.md".to_string())
+    );
+    assert!(matches!(DocumentSource::parse("docs/a.md"), DocumentSource::File(_)));
+}
+
+#[test]
+fn test_only_transient_failures_are_retried() {
+    assert!(DocumentLoadError::Status { status: 503 }.is_transient());
+    assert!(DocumentLoadError::Status { status: 429 }.is_transient());
+    assert!(!DocumentLoadError::Status { status: 404 }.is_transient());
+    assert!(!DocumentLoadError::Io("missing".to_string()).is_transient());
+}
+
+#[tokio::test]
+async fn test_batch_reports_each_source_and_progress() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let good = dir.path().join("notes.md");
+    std::fs::write(&good, "# Notes").expect("write");
+    let missing = dir.path().join("missing.md");
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let results = DocumentBatch::new([good.clone(), missing.clone()])
+        .concurrency(2)
+        .on_progress(move |p| seen.lock().unwrap().push(p))
+        .load_all()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    for (source, result) in results {
+        if source == DocumentSource::File(good.clone()) {
+            assert_eq!(result.expect("loaded").data, "# Notes");
+        } else {
+            assert!(matches!(result, Err(DocumentLoadError::Io(_))));
+        }
+    }
+
+    let progress = progress.lock().unwrap();
+    assert_eq!(
+        progress.last(),
+        Some(&BatchProgress {
+            completed: 2,
+            failed: 1,
+            total: 2
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_oversized_file_is_refused() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let large = dir.path().join("large.md");
+    std::fs::write(&large, "a".repeat(100)).expect("write");
+
+    let (_, result) = DocumentBatch::new([large])
+        .max_size(10)
+        .load_all()
+        .await
+        .pop()
+        .expect("one result");
+    assert_eq!(
+        result.err(),
+        Some(DocumentLoadError::TooLarge { size: 100, max: 10 })
+    );
+}
+
+/// Answer every connection on a local port with `response`, returning its URL
+async fn serve(response: String) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let response = response.clone();
+            tokio::spawn(async move {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    format!("http://{addr}/doc")
+}
+
+#[tokio::test]
+async fn test_redirect_targets_are_checked() {
+    let url = serve(
+        "HTTP/1.1 302 Found\r\nLocation: http://blocked.invalid/doc\r\n\
+         Content-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    )
+    .await;
+    let check = |url: &str| {
+        if url.contains("blocked") {
+            Err(format!("{url} is blocked"))
+        } else {
+            Ok(())
+        }
+    };
+
+    let (_, result) = DocumentBatch::new([url.as_str()])
+        .retries(0)
+        .check_urls(check)
+        .load_all()
+        .await
+        .pop()
+        .expect("one result");
+    match result {
+        Err(DocumentLoadError::NotAllowed(reason)) => assert!(reason.contains("blocked")),
+        other => panic!("expected the redirect to be refused, got {other:?}"),
+    }
+
+    let (_, result) = DocumentBatch::new(["http://blocked.invalid/doc"])
+        .check_urls(check)
+        .load_all()
+        .await
+        .pop()
+        .expect("one result");
+    assert!(matches!(result, Err(DocumentLoadError::NotAllowed(_))));
+}
+
+#[tokio::test]
+async fn test_oversized_content_length_is_refused() {
+    let body = "a".repeat(100);
+    let url = serve(format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    ))
+    .await;
+
+    let (_, result) = DocumentBatch::new([url.as_str()])
+        .max_size(10)
+        .load_all()
+        .await
+        .pop()
+        .expect("one result");
+    assert_eq!(
+        result.err(),
+        Some(DocumentLoadError::TooLarge { size: 100, max: 10 })
+    );
+}
@@ -0,0 +1,96 @@
+// Tests for src/builders/experiment.rs
+
+use kodegen_candle_agent::builders::ConversationHistory;
+use kodegen_candle_agent::builders::experiment::{
+    Arm, ExperimentReport, TurnComparison, TurnOutcome, replay_turns,
+};
+use kodegen_candle_agent::prelude::{CandleMessageChunk, CandleMessageRole};
+
+fn complete(text: &str, token_count: u32) -> CandleMessageChunk {
+    CandleMessageChunk::Complete {
+        text: text.to_string(),
+        finish_reason: Some("Stop".to_string()),
+        usage: None,
+        token_count: Some(token_count),
+        elapsed_secs: None,
+        tokens_per_sec: None,
+        citations: Vec::new(),
+        confidence: None,
+    }
+}
+
+fn outcome(reply: &str, token_count: u32) -> TurnOutcome {
+    TurnOutcome {
+        reply: reply.to_string(),
+        token_count: Some(token_count),
+        latency_secs: 1.0,
+        ..TurnOutcome::default()
+    }
+}
+
+#[test]
+fn replay_turns_pairs_user_messages_with_prior_history() {
+    let conversation = ConversationHistory::new()
+        .with_message(CandleMessageRole::System, "be brief")
+        .with_message(CandleMessageRole::User, "hi")
+        .with_message(CandleMessageRole::Assistant, "hello")
+        .with_message(CandleMessageRole::User, "bye");
+
+    let turns = replay_turns(&conversation);
+    assert_eq!(turns.len(), 2);
+    assert_eq!(turns[0].0.len(), 1);
+    assert_eq!(turns[0].1, "hi");
+    assert_eq!(turns[1].0.len(), 3);
+    assert_eq!(turns[1].0.messages()[2].1, "hello");
+    assert_eq!(turns[1].1, "bye");
+}
+
+#[tokio::test]
+async fn collect_joins_text_and_records_usage() {
+    let stream = kodegen_candle_agent::from_iter(vec![
+        CandleMessageChunk::ToolCallStart {
+            id: "1".to_string(),
+            name: "search".to_string(),
+        },
+        CandleMessageChunk::Text("Hel".to_string()),
+        complete("lo", 7),
+    ]);
+
+    let outcome = TurnOutcome::collect(stream).await;
+    assert_eq!(outcome.reply, "Hello");
+    assert_eq!(outcome.tool_calls, ["search"]);
+    assert_eq!(outcome.token_count, Some(7));
+    assert!(outcome.first_token_secs.is_some());
+    assert!(!outcome.failed());
+}
+
+#[test]
+fn report_summarizes_and_diffs_by_arm() {
+    let report = ExperimentReport {
+        a_label: "qwen".to_string(),
+        b_label: "llama".to_string(),
+        turns: vec![
+            TurnComparison {
+                turn: 1,
+                user_message: "hi".to_string(),
+                a: outcome("hello", 3),
+                b: outcome("hello ", 4),
+            },
+            TurnComparison {
+                turn: 2,
+                user_message: "bye".to_string(),
+                a: outcome("goodbye", 5),
+                b: outcome("see you", 6),
+            },
+        ],
+    };
+
+    assert_eq!(report.differing_turns().count(), 1);
+    assert_eq!(report.summary(Arm::A).total_tokens, 8);
+    assert_eq!(report.summary(Arm::B).total_tokens, 10);
+    assert_eq!(
+        report.transcript(Arm::B),
+        "## Turn 1\n> hi\n\nhello\n\n## Turn 2\n> bye\n\nsee you\n\n"
+    );
+    assert!(report.to_string().contains("1 of 2 turn(s) differ"));
+}
@@ -1,6 +1,14 @@
 // Integration tests for capability operations
 
 mod capability {
+    mod test_adaptive_batch;
+    mod test_chat_template;
+    mod test_encoder_embedding;
+    mod test_keep_alive;
+    mod test_lora;
+    mod test_pool_status;
+    mod test_prefix_cache;
+    mod test_quantization;
     mod test_registry;
     mod test_stella_instruction;
 }
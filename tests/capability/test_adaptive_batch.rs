@@ -0,0 +1,60 @@
+// Tests for src/capability/text_embedding/adaptive_batch.rs
+
+use std::time::Duration;
+
+use kodegen_candle_agent::capability::registry::pool::core::MemoryPressure;
+use kodegen_candle_agent::capability::text_embedding::adaptive_batch::{
+    AdaptiveBatchSizer, MAX_BATCH_LATENCY,
+};
+
+/// Feed a full batch that runs at `per_text` regardless of size
+fn record(sizer: &AdaptiveBatchSizer, per_text: Duration) {
+    let size = sizer.batch_size();
+    sizer.record(size, per_text * size as u32, MemoryPressure::Low);
+}
+
+#[test]
+fn test_sizer_grows_while_throughput_improves() {
+    let sizer = AdaptiveBatchSizer::new(16, 1, 64);
+    // Larger batches amortize a fixed overhead, so throughput keeps rising
+    for _ in 0..20 {
+        let size = sizer.batch_size();
+        let elapsed = Duration::from_millis(100) + Duration::from_millis(5) * size as u32;
+        sizer.record(size, elapsed, MemoryPressure::Low);
+    }
+    assert!(sizer.batch_size() >= 48);
+    assert!(sizer.batch_size() <= 64);
+}
+
+#[test]
+fn test_sizer_turns_around_when_throughput_drops() {
+    let sizer = AdaptiveBatchSizer::new(16, 1, 64);
+    record(&sizer, Duration::from_millis(10));
+    let grown = sizer.batch_size();
+    assert!(grown > 16);
+
+    record(&sizer, Duration::from_millis(20));
+    assert!(sizer.batch_size() < grown);
+}
+
+#[test]
+fn test_sizer_shrinks_under_pressure_and_latency() {
+    let sizer = AdaptiveBatchSizer::new(32, 1, 64);
+    sizer.record(32, Duration::from_millis(50), MemoryPressure::Critical);
+    assert_eq!(sizer.batch_size(), 16);
+
+    sizer.record(16, MAX_BATCH_LATENCY * 2, MemoryPressure::Low);
+    assert_eq!(sizer.batch_size(), 12);
+
+    for _ in 0..20 {
+        sizer.record(1, Duration::from_millis(1), MemoryPressure::Critical);
+    }
+    assert_eq!(sizer.batch_size(), 1);
+}
+
+#[test]
+fn test_sizer_ignores_partial_batches_for_throughput() {
+    let sizer = AdaptiveBatchSizer::new(16, 1, 64);
+    sizer.record(3, Duration::from_millis(1), MemoryPressure::Low);
+    assert_eq!(sizer.batch_size(), 16);
+}
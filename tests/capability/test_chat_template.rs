@@ -0,0 +1,44 @@
+// Tests for src/capability/text_to_text/template_goldens.rs
+
+use std::path::Path;
+
+use kodegen_candle_agent::capability::text_to_text::template_goldens::check_goldens;
+use kodegen_candle_agent::capability::text_to_text::{
+    ChatConversation, ChatTemplateFamily, ChatTurn,
+};
+
+#[test]
+fn test_renders_match_goldens() {
+    let dir = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/goldens/chat_templates"));
+    let mismatches = check_goldens(dir);
+    let report: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+    assert!(
+        mismatches.is_empty(),
+        "chat template renders changed; if intended, regenerate with \
+         `templates goldens tests/goldens/chat_templates`:\n{}",
+        report.join("\n")
+    );
+}
+
+#[test]
+fn test_chatml_single_user_turn() {
+    let conversation = ChatConversation::new(vec![ChatTurn::user("hi")]);
+    assert_eq!(
+        ChatTemplateFamily::ChatMl.render(&conversation),
+        "<|im_start|>user\nhi<|im_end|>\n<|im_start|>assistant\n"
+    );
+}
+
+#[test]
+fn test_mistral_system_goes_into_last_user_turn() {
+    let conversation = ChatConversation::new(vec![
+        ChatTurn::system("Be brief."),
+        ChatTurn::user("hi"),
+        ChatTurn::assistant("hello"),
+        ChatTurn::user("bye"),
+    ]);
+    assert_eq!(
+        ChatTemplateFamily::Mistral.render(&conversation),
+        "[INST] hi[/INST] hello</s>[INST] Be brief.\n\nbye[/INST]"
+    );
+}
@@ -0,0 +1,60 @@
+// Tests for src/capability/text_embedding/encoder/
+
+use kodegen_candle_agent::capability::registry::{FromRegistry, TextEmbeddingModel};
+use kodegen_candle_agent::capability::text_embedding::encoder::instruction::{
+    format_with_prefix, task_prefix,
+};
+use kodegen_candle_agent::capability::text_embedding::encoder::{NO_PREFIXES, NOMIC_EMBED_TEXT};
+use kodegen_candle_agent::capability::traits::TextEmbeddingCapable;
+use kodegen_candle_agent::domain::model::traits::CandleModel;
+
+#[test]
+fn test_nomic_task_prefixes() {
+    let prefixes = &NOMIC_EMBED_TEXT.prefixes;
+    assert_eq!(task_prefix(prefixes, Some("document")), "search_document: ");
+    assert_eq!(
+        task_prefix(prefixes, Some("search_document")),
+        "search_document: "
+    );
+    assert_eq!(
+        task_prefix(prefixes, Some("search_query")),
+        "search_query: "
+    );
+    assert_eq!(task_prefix(prefixes, Some("code:rust")), "search_query: ");
+    assert_eq!(task_prefix(prefixes, None), "search_query: ");
+    assert_eq!(task_prefix(prefixes, Some("s2s")), "clustering: ");
+    assert_eq!(
+        task_prefix(prefixes, Some("classification")),
+        "classification: "
+    );
+}
+
+#[test]
+fn test_models_without_prefixes_keep_text() {
+    let texts = vec!["What is Rust?".to_string()];
+    assert_eq!(
+        format_with_prefix(&NO_PREFIXES, &texts, Some("search_query")),
+        texts
+    );
+}
+
+#[test]
+fn test_encoder_models_are_registered() {
+    for (key, dimension) in [
+        ("BAAI/bge-m3", 1024),
+        ("thenlper/gte-large", 1024),
+        ("nomic-ai/nomic-embed-text-v1.5", 768),
+    ] {
+        let model = TextEmbeddingModel::from_registry(key)
+            .unwrap_or_else(|| panic!("{key} should be registered"));
+        assert!(matches!(model, TextEmbeddingModel::Encoder(_)));
+        assert_eq!(model.info().registry_key, key);
+        assert_eq!(model.embedding_dimension(), dimension);
+        assert!(model.supports_dimension(dimension));
+    }
+
+    let nomic = TextEmbeddingModel::from_registry("nomic-ai/nomic-embed-text-v1.5").unwrap();
+    assert!(nomic.supports_dimension(256));
+    let bge = TextEmbeddingModel::from_registry("BAAI/bge-m3").unwrap();
+    assert!(!bge.supports_dimension(256));
+}
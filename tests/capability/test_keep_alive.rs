@@ -0,0 +1,51 @@
+// Tests for src/capability/registry/pool/keep_alive.rs
+
+use std::time::Duration;
+
+use kodegen_candle_agent::capability::registry::ModelCapability;
+use kodegen_candle_agent::capability::registry::pool::{
+    KeepAlive, ModelLoadStatus, ModelResidency, keep_alive, prometheus_model_status,
+    set_keep_alive,
+};
+
+#[test]
+fn test_parse_ollama_durations() {
+    let idle = |secs| KeepAlive::Idle(Duration::from_secs(secs));
+    assert_eq!("5m".parse::<KeepAlive>().unwrap(), idle(300));
+    assert_eq!("30s".parse::<KeepAlive>().unwrap(), idle(30));
+    assert_eq!("1h".parse::<KeepAlive>().unwrap(), idle(3600));
+    assert_eq!("90".parse::<KeepAlive>().unwrap(), idle(90));
+    assert_eq!("0".parse::<KeepAlive>().unwrap(), idle(0));
+    assert_eq!("-1".parse::<KeepAlive>().unwrap(), KeepAlive::Forever);
+    assert_eq!("-5m".parse::<KeepAlive>().unwrap(), KeepAlive::Forever);
+
+    for invalid in ["", "soon", "5d", "-"] {
+        assert!(invalid.parse::<KeepAlive>().is_err(), "{invalid:?} should not parse");
+    }
+}
+
+#[test]
+fn test_set_keep_alive_overrides_pool() {
+    set_keep_alive(ModelCapability::TextToImage, KeepAlive::Idle(Duration::from_secs(60)));
+    assert_eq!(keep_alive(ModelCapability::TextToImage).idle_secs(), Some(60));
+
+    set_keep_alive(ModelCapability::TextToImage, KeepAlive::Forever);
+    assert_eq!(keep_alive(ModelCapability::TextToImage).idle_secs(), None);
+}
+
+#[test]
+fn test_prometheus_model_status() {
+    let models = vec![ModelLoadStatus {
+        capability: ModelCapability::TextToText,
+        registry_key: "unsloth/Qwen3-1.7B-GGUF".to_string(),
+        state: ModelResidency::Unloaded,
+        workers: 0,
+        keep_alive_secs: Some(300),
+        unloaded_at: Some(1_700_000_000),
+    }];
+
+    let output = prometheus_model_status(&models);
+    let labels = "capability=\"text_to_text\",model=\"unsloth/Qwen3-1.7B-GGUF\"";
+    assert!(output.contains(&format!("pool_model_state{{{labels},state=\"unloaded\"}} 1\n")));
+    assert!(output.contains(&format!("pool_model_state{{{labels},state=\"loaded\"}} 0\n")));
+}
@@ -0,0 +1,127 @@
+// Tests for src/capability/lora.rs
+
+use std::collections::HashMap;
+
+use candle_core::quantized::{GgmlDType, QTensor, gguf_file};
+use candle_core::{Device, Tensor};
+use kodegen_candle_agent::capability::lora::*;
+use kodegen_candle_agent::capability::registry::{
+    register_lora_adapter, resolve_lora_adapters, unregister_lora_adapter,
+};
+
+#[test]
+fn test_peft_modules_map_to_gguf_tensors() {
+    assert_eq!(
+        gguf_tensor_name("base_model.model.model.layers.3.self_attn.q_proj").as_deref(),
+        Some("blk.3.attn_q.weight")
+    );
+    assert_eq!(
+        gguf_tensor_name("base_model.model.model.layers.12.mlp.down_proj").as_deref(),
+        Some("blk.12.ffn_down.weight")
+    );
+    assert_eq!(gguf_tensor_name("base_model.model.lm_head"), None);
+}
+
+#[test]
+fn test_requested_adapters_accepts_name_or_list() {
+    let single = serde_json::json!({ "lora_adapter": "sql" });
+    let list = serde_json::json!({ "lora_adapter": ["sql", "style"] });
+
+    assert_eq!(requested_adapters(Some(&single)), vec!["sql"]);
+    assert_eq!(requested_adapters(Some(&list)), vec!["sql", "style"]);
+    assert!(requested_adapters(None).is_empty());
+}
+
+#[test]
+fn test_merge_applies_scaled_low_rank_update() -> Result<(), Box<dyn std::error::Error>> {
+    let device = Device::Cpu;
+    let base = Tensor::zeros((4, 8), candle_core::DType::F32, &device)?;
+    let untouched = Tensor::ones((4, 8), candle_core::DType::F32, &device)?;
+
+    // Write a two-tensor GGUF in memory
+    let q = QTensor::quantize(&base, GgmlDType::F32)?;
+    let v = QTensor::quantize(&untouched, GgmlDType::F32)?;
+    let mut file = std::io::Cursor::new(Vec::new());
+    gguf_file::write(
+        &mut file,
+        &[],
+        &[("blk.0.attn_q.weight", &q), ("blk.0.attn_v.weight", &v)],
+    )?;
+    file.set_position(0);
+    let content = gguf_file::Content::read(&mut file)?;
+
+    // Rank-1 update: B·A is all ones, scaled by 0.5
+    let prefix = "base_model.model.model.layers.0.self_attn.q_proj";
+    let mut tensors = HashMap::new();
+    tensors.insert(format!("{prefix}.lora_A.weight"), Tensor::ones((1, 8), candle_core::DType::F32, &device)?);
+    tensors.insert(format!("{prefix}.lora_B.weight"), Tensor::ones((4, 1), candle_core::DType::F32, &device)?);
+    let adapter = LoraWeights::from_tensors("test", tensors, 0.5)?;
+    assert_eq!(adapter.target_count(), 1);
+
+    let mut merged = merge_into_gguf(&content, &mut file, &[adapter])?;
+    let merged_content = gguf_file::Content::read(&mut merged)?;
+
+    let q = merged_content
+        .tensor(&mut merged, "blk.0.attn_q.weight", &device)?
+        .dequantize(&device)?;
+    assert!(q.flatten_all()?.to_vec1::<f32>()?.iter().all(|&x| (x - 0.5).abs() < 1e-6));
+
+    let v = merged_content
+        .tensor(&mut merged, "blk.0.attn_v.weight", &device)?
+        .dequantize(&device)?;
+    assert!(v.flatten_all()?.to_vec1::<f32>()?.iter().all(|&x| (x - 1.0).abs() < 1e-6));
+
+    Ok(())
+}
+
+#[test]
+fn test_adapters_resolve_by_name_per_model() {
+    let model = format!("test-lora-{}", uuid::Uuid::new_v4());
+    register_lora_adapter(&model, LoraAdapter::local("sql", "/adapters/sql.safetensors"));
+    register_lora_adapter(&model, LoraAdapter::huggingface("style", "org/style-lora", "adapter_model.safetensors").with_scale(2.0));
+
+    let resolved = resolve_lora_adapters(&model, &["style".to_string(), "sql".to_string()]).unwrap();
+    assert_eq!(resolved[0].name, "style");
+    assert_eq!(resolved[0].scale, Some(2.0));
+    assert_eq!(resolved[1].name, "sql");
+
+    assert!(matches!(
+        resolve_lora_adapters(&model, &["missing".to_string()]),
+        Err(LoraError::UnknownAdapter { .. })
+    ));
+
+    assert!(unregister_lora_adapter(&model, "sql").is_some());
+    assert!(resolve_lora_adapters(&model, &["sql".to_string()]).is_err());
+}
+
+#[test]
+fn test_load_takes_rank_and_alpha_from_adapter_config() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let weights_path = dir.path().join("adapter_model.safetensors");
+    let config_path = dir.path().join(ADAPTER_CONFIG_FILE);
+
+    let device = Device::Cpu;
+    let prefix = "base_model.model.model.layers.0.self_attn.q_proj";
+    let mut tensors = HashMap::new();
+    tensors.insert(format!("{prefix}.lora_A.weight"), Tensor::ones((2, 8), candle_core::DType::F32, &device)?);
+    tensors.insert(format!("{prefix}.lora_B.weight"), Tensor::ones((4, 2), candle_core::DType::F32, &device)?);
+    candle_core::safetensors::save(&tensors, &weights_path)?;
+    let adapter = LoraAdapter::local("sql", &weights_path);
+
+    // No config: the scale is not guessed
+    assert!(matches!(LoraWeights::load(&adapter, &weights_path), Err(LoraError::Config { .. })));
+
+    std::fs::write(&config_path, r#"{"r": 2, "lora_alpha": 16, "target_modules": ["q_proj"]}"#)?;
+    assert_eq!(LoraWeights::load(&adapter, &weights_path)?.scale(), 8.0);
+    assert_eq!(LoraWeights::load(&adapter.clone().with_scale(0.5), &weights_path)?.scale(), 0.5);
+
+    std::fs::write(&config_path, r#"{"r": 4, "lora_alpha": 16, "use_rslora": true}"#)?;
+    assert!(matches!(
+        LoraWeights::load(&adapter, &weights_path),
+        Err(LoraError::ShapeMismatch { .. })
+    ));
+
+    assert_eq!(adapter_config_file("sql/adapter_model.safetensors"), "sql/adapter_config.json");
+    assert_eq!(adapter_config_file("adapter_model.safetensors"), ADAPTER_CONFIG_FILE);
+    Ok(())
+}
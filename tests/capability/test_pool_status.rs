@@ -0,0 +1,67 @@
+// Tests for src/capability/registry/pool/status.rs
+
+use std::time::Duration;
+
+use kodegen_candle_agent::capability::registry::ModelCapability;
+use kodegen_candle_agent::capability::registry::pool::core::WorkerStats;
+use kodegen_candle_agent::capability::registry::pool::{
+    PoolWorkerStatus, WorkerActivity, WorkerState, WorkerStatus, prometheus_worker_status,
+    worker_activity,
+};
+
+#[test]
+fn test_worker_activity() {
+    assert_eq!(worker_activity(WorkerState::Spawning, 0), WorkerActivity::Loading);
+    assert_eq!(worker_activity(WorkerState::Loading, 0), WorkerActivity::Loading);
+    assert_eq!(worker_activity(WorkerState::Ready, 0), WorkerActivity::Idle);
+    assert_eq!(worker_activity(WorkerState::Ready, 2), WorkerActivity::Busy);
+    assert_eq!(worker_activity(WorkerState::Idle, 0), WorkerActivity::Idle);
+    assert_eq!(worker_activity(WorkerState::Evicting, 0), WorkerActivity::Stopped);
+    assert_eq!(worker_activity(WorkerState::Failed, 0), WorkerActivity::Failed);
+}
+
+#[test]
+fn test_stats_track_outcomes_and_last_error() {
+    let stats = WorkerStats::default();
+    assert_eq!(stats.avg_latency_ms(), None);
+    assert_eq!(stats.last_error(), None);
+
+    stats.record(Duration::from_millis(100), &Ok::<(), String>(()));
+    stats.record(Duration::from_millis(300), &Err::<(), _>("out of memory"));
+
+    assert_eq!(stats.requests_served.load(std::sync::atomic::Ordering::Relaxed), 1);
+    assert_eq!(stats.requests_failed.load(std::sync::atomic::Ordering::Relaxed), 1);
+    assert_eq!(stats.avg_latency_ms(), Some(200.0));
+    assert_eq!(stats.last_error().as_deref(), Some("out of memory"));
+
+    stats.set_last_error("model load failed");
+    assert_eq!(stats.last_error().as_deref(), Some("model load failed"));
+}
+
+#[test]
+fn test_prometheus_worker_status() {
+    let workers = vec![PoolWorkerStatus {
+        capability: ModelCapability::TextEmbedding,
+        worker: WorkerStatus {
+            registry_key: "dunzhang/stella_en_400M_v5".to_string(),
+            worker_id: 3,
+            state: WorkerActivity::Busy,
+            pending_requests: 1,
+            requests_served: 42,
+            requests_failed: 2,
+            avg_latency_ms: Some(12.5),
+            last_error: Some("timeout".to_string()),
+            last_used: 0,
+            memory_mb: 1024,
+        },
+    }];
+
+    let output = prometheus_worker_status(&workers);
+    let labels = "capability=\"text_embedding\",model=\"dunzhang/stella_en_400M_v5\",worker=\"3\"";
+
+    assert!(output.contains(&format!("pool_worker_state{{{labels},state=\"busy\"}} 1\n")));
+    assert!(output.contains(&format!("pool_worker_state{{{labels},state=\"idle\"}} 0\n")));
+    assert!(output.contains(&format!("pool_worker_requests_served_total{{{labels}}} 42\n")));
+    assert!(output.contains(&format!("pool_worker_requests_failed_total{{{labels}}} 2\n")));
+    assert!(output.contains(&format!("pool_worker_latency_avg_ms{{{labels}}} 12.50\n")));
+}
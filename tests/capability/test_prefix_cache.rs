@@ -0,0 +1,78 @@
+// Tests for src/capability/text_to_text/prefix_cache.rs
+
+use kodegen_candle_agent::capability::text_to_text::prefix_cache::{
+    MIN_PREFIX_TOKENS, PrefixCache,
+};
+
+/// `shared` common tokens followed by `tail`
+fn prompt(shared: usize, tail: &[u32]) -> Vec<u32> {
+    (0..shared as u32).chain(tail.iter().copied()).collect()
+}
+
+#[test]
+fn test_checkpoint_at_divergence_from_previous_prompt() {
+    let mut cache: PrefixCache<&str> = PrefixCache::new(4);
+    let shared = MIN_PREFIX_TOKENS + 8;
+
+    // Nothing to compare the first prompt with
+    assert_eq!(cache.checkpoint_at(&prompt(shared, &[1000, 1001]), 0), None);
+    // The second diverges after the shared part
+    let second = prompt(shared, &[2000]);
+    assert_eq!(cache.checkpoint_at(&second, 0), Some(shared));
+    cache.insert(second[..shared].to_vec(), "after shared prefix");
+
+    // A third prompt with the same start reuses the snapshot
+    let third = prompt(shared, &[3000, 3001]);
+    assert_eq!(cache.lookup(&third), Some((shared, "after shared prefix")));
+    assert_eq!(cache.checkpoint_at(&third, shared), None);
+}
+
+#[test]
+fn test_lookup_leaves_a_token_to_run_and_prefers_longest_prefix() {
+    let mut cache = PrefixCache::new(4);
+    let long = prompt(MIN_PREFIX_TOKENS * 2, &[]);
+    cache.insert(long[..MIN_PREFIX_TOKENS].to_vec(), 1);
+    cache.insert(long.clone(), 2);
+
+    assert_eq!(cache.lookup(&long), Some((MIN_PREFIX_TOKENS, 1)));
+    assert_eq!(
+        cache.lookup(&prompt(MIN_PREFIX_TOKENS * 2, &[7])),
+        Some((long.len(), 2))
+    );
+    assert_eq!(cache.lookup(&[9, 9, 9]), None);
+}
+
+#[test]
+fn test_capacity_evicts_least_recently_used() {
+    let mut cache = PrefixCache::new(2);
+    cache.insert(vec![1; MIN_PREFIX_TOKENS], "a");
+    cache.insert(vec![2; MIN_PREFIX_TOKENS], "b");
+    assert!(cache.lookup(&[1; MIN_PREFIX_TOKENS + 1]).is_some());
+    cache.insert(vec![3; MIN_PREFIX_TOKENS], "c");
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.lookup(&[2; MIN_PREFIX_TOKENS + 1]).is_none());
+    assert!(cache.lookup(&[1; MIN_PREFIX_TOKENS + 1]).is_some());
+
+    let mut disabled: PrefixCache<&str> = PrefixCache::new(0);
+    disabled.insert(vec![1; MIN_PREFIX_TOKENS], "a");
+    assert!(disabled.is_empty());
+}
+
+#[test]
+fn test_token_budget_evicts_least_recently_used() {
+    let mut cache = PrefixCache::new(4).with_max_tokens(MIN_PREFIX_TOKENS * 2);
+    cache.insert(vec![1; MIN_PREFIX_TOKENS], "a");
+    cache.insert(vec![2; MIN_PREFIX_TOKENS], "b");
+    assert_eq!(cache.tokens(), MIN_PREFIX_TOKENS * 2);
+
+    cache.insert(vec![3; MIN_PREFIX_TOKENS], "c");
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.tokens(), MIN_PREFIX_TOKENS * 2);
+    assert!(cache.lookup(&[1; MIN_PREFIX_TOKENS + 1]).is_none());
+
+    // A prefix over the whole budget is not kept
+    cache.insert(vec![4; MIN_PREFIX_TOKENS * 3], "d");
+    assert_eq!(cache.len(), 2);
+    assert!(cache.lookup(&[4; MIN_PREFIX_TOKENS * 3 + 1]).is_none());
+}
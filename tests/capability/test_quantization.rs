@@ -0,0 +1,55 @@
+// Tests for src/capability/registry/quantization.rs
+
+use kodegen_candle_agent::capability::registry::*;
+use kodegen_candle_agent::domain::model::traits::CandleModel;
+
+#[test]
+fn split_variant_recognizes_known_suffixes() {
+    assert_eq!(
+        split_variant("unsloth/Qwen3-1.7B-GGUF:Q8_0"),
+        ("unsloth/Qwen3-1.7B-GGUF", Some(QuantVariant::Q8_0))
+    );
+    assert_eq!(
+        split_variant("unsloth/Qwen3-1.7B-GGUF:q5_k_m"),
+        ("unsloth/Qwen3-1.7B-GGUF", Some(QuantVariant::Q5_K_M))
+    );
+    assert_eq!(split_variant("phi4:latest"), ("phi4:latest", None));
+    assert_eq!(split_variant("unsloth/Qwen3-1.7B-GGUF"), ("unsloth/Qwen3-1.7B-GGUF", None));
+}
+
+#[test]
+fn variant_keys_resolve_to_their_own_model_info() {
+    let base: TextToTextModel = get("Qwen/Qwen2.5-Coder-3B-Instruct-GGUF").expect("base model");
+    let q8: TextToTextModel = get("unsloth/Qwen3-1.7B-GGUF:Q8_0").expect("Q8_0 variant");
+
+    assert_eq!(q8.info().quantization, "Q8_0");
+    assert_eq!(q8.info().registry_key, "unsloth/Qwen3-1.7B-GGUF:Q8_0");
+    assert!(q8.info().est_memory_allocation_mb > base.info().est_memory_allocation_mb);
+
+    // The default variant and the bare repository are the base model itself
+    for key in ["unsloth/Qwen3-1.7B-GGUF:Q4_K_M", "unsloth/Qwen3-1.7B-GGUF"] {
+        let q4: TextToTextModel = get(key).expect("default variant");
+        assert_eq!(q4.info().registry_key, base.info().registry_key);
+    }
+
+    // A variant of the base registry key lands on the same registry entry
+    let aliased: TextToTextModel =
+        get("Qwen/Qwen2.5-Coder-3B-Instruct-GGUF:Q8_0").expect("Q8_0 via base key");
+    assert_eq!(aliased.info().registry_key, "unsloth/Qwen3-1.7B-GGUF:Q8_0");
+}
+
+#[test]
+fn models_lists_every_variant_once() {
+    let keys: Vec<String> = models()
+        .filter(|m| m.capability == ModelCapability::TextToText)
+        .map(|m| m.registry_key)
+        .collect();
+
+    for variant in [QuantVariant::Q5_K_M, QuantVariant::Q8_0] {
+        let key = format!("unsloth/Qwen3-1.7B-GGUF:{variant}");
+        assert!(keys.contains(&key), "{key} not listed: {keys:?}");
+    }
+    let mut unique = keys.clone();
+    unique.dedup();
+    assert_eq!(unique, keys);
+}
@@ -355,3 +355,56 @@ async fn test_cannot_overwrite_static_models() {
         _ => panic!("Expected KeyAlreadyExists error"),
     }
 }
+
+/// Loaded-model handles start empty and never trigger a load on lookup
+#[test]
+fn test_loaded_model_handle_is_lazy() {
+    let model = TextToTextModel::Qwen3Quantized(Arc::new(CandleQwen3QuantizedModel::default()));
+
+    let first = loaded_model_handle(&model);
+    let second = loaded_model_handle(&model);
+
+    assert!(!first.is_loaded(), "Handle lookup must not load the model");
+    assert!(!second.is_loaded());
+    assert_eq!(format!("{first:?}"), "LoadedModelHandle { loaded: false }");
+}
+
+/// `models()` describes every registered model with its capability and limits
+#[test]
+fn test_models_describes_registered_models() {
+    let described: Vec<ModelDescriptor> = models().collect();
+
+    // Other tests register models concurrently, so only check for duplicates
+    let mut keys: Vec<&str> = described.iter().map(|m| m.registry_key.as_str()).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    assert_eq!(keys.len(), described.len(), "Each model should be described once");
+
+    let stella = described
+        .iter()
+        .find(|m| m.registry_key == "dunzhang/stella_en_400M_v5")
+        .expect("Stella is registered statically");
+    assert_eq!(stella.capability, ModelCapability::TextEmbedding);
+    assert!(stella.embedding_dimension.is_some());
+    assert!(stella.est_memory_mb > 0);
+}
+
+/// GGUF chat models resolve by their short name as well as their key
+#[test]
+fn test_text_to_text_lookup_by_name() {
+    use kodegen_candle_agent::capability::text_to_text::ChatTemplateFamily;
+    use kodegen_candle_agent::domain::model::traits::CandleModel;
+
+    let llama: TextToTextModel = get("llama-3.2-3b").expect("Llama 3.2 resolves by name");
+    assert_eq!(llama.info().registry_key, "unsloth/Llama-3.2-3B-Instruct-GGUF");
+    assert_eq!(llama.chat_template(), Some(ChatTemplateFamily::Llama3));
+
+    let mistral = "MISTRAL-7B-INSTRUCT"
+        .into_text_to_text_model()
+        .expect("Mistral resolves by name");
+    assert_eq!(mistral.chat_template(), Some(ChatTemplateFamily::Mistral));
+
+    assert!(get::<TextToTextModel>("no-such-model").is_none());
+    let unknown = "no-such-model".into_text_to_text_model().unwrap_err();
+    assert!(unknown.to_string().contains("llama-3.2-3b"));
+}
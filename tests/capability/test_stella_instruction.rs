@@ -108,3 +108,13 @@ fn test_instruction_mapping() {
         assert!(result[0].contains("Retrieve semantically similar text"));
     }
 }
+
+#[test]
+fn test_code_tasks_use_language_instruction() {
+    let result = format_with_instruction(&["fn main() {}"], Some("code:rust"));
+    assert!(result[0].starts_with("Instruct: Given a question about Rust code"));
+    assert!(result[0].ends_with("Query: fn main() {}"));
+
+    assert!(code_instruction("code:python").is_some());
+    assert!(code_instruction("code:cobol").is_none());
+}
@@ -2,4 +2,5 @@
 
 mod cli {
     mod test_handler;
+    mod test_render;
 }
@@ -46,3 +46,43 @@ fn test_handle_temperature_command() {
         _ => panic!("Expected ConfigChanged result"),
     }
 }
+
+#[test]
+fn test_handle_search_command_uses_query_language() {
+    let mut handler = InputHandler::new(CliConfig::new());
+    handler.handle("connection timeout on upload");
+    handler.handle("retry after error");
+    handler.handle("draft notes");
+
+    match handler.handle("/search (timeout OR retry) -draft") {
+        InputHandlerResult::Command(CommandResult::SearchResults(results)) => {
+            assert_eq!(results.len(), 2);
+        }
+        _ => panic!("Expected SearchResults result"),
+    }
+
+    match handler.handle("/search error AND") {
+        InputHandlerResult::Command(CommandResult::Error(message)) => {
+            assert!(message.contains("AND needs a term after it"));
+        }
+        _ => panic!("Expected Error result"),
+    }
+}
+
+#[test]
+fn test_handle_regenerate_command() {
+    let mut handler = InputHandler::new(CliConfig::new());
+
+    match handler.handle("/regenerate 1.1") {
+        InputHandlerResult::Regenerate(options) => assert_eq!(options.temperature, Some(1.1)),
+        _ => panic!("Expected Regenerate result"),
+    }
+    match handler.handle("/regenerate") {
+        InputHandlerResult::Regenerate(options) => assert_eq!(options.temperature, None),
+        _ => panic!("Expected Regenerate result"),
+    }
+    assert!(matches!(
+        handler.handle("/regenerate 3"),
+        InputHandlerResult::Command(CommandResult::Error(_))
+    ));
+}
@@ -0,0 +1,69 @@
+// Tests for src/cli/render.rs
+
+use kodegen_candle_agent::cli::MarkdownStreamRenderer;
+use kodegen_candle_agent::cli::render::format_turn_stats;
+use std::time::Duration;
+use termcolor::Buffer;
+
+fn render_plain(tokens: &[&str]) -> String {
+    let mut out = Buffer::no_color();
+    let mut renderer = MarkdownStreamRenderer::new();
+    for token in tokens {
+        renderer.push(&mut out, token).expect("push");
+    }
+    renderer.finish(&mut out).expect("finish");
+    String::from_utf8(out.into_inner()).expect("utf8")
+}
+
+#[test]
+fn test_plain_text_passes_through() {
+    assert_eq!(render_plain(&["Hello", ", ", "world"]), "Hello, world");
+}
+
+#[test]
+fn test_bold_markers_split_across_tokens() {
+    assert_eq!(render_plain(&["a *", "*bold*", "* b"]), "a bold b");
+}
+
+#[test]
+fn test_single_asterisk_is_literal() {
+    assert_eq!(render_plain(&["2 * 3"]), "2 * 3");
+}
+
+#[test]
+fn test_heading_marker_is_stripped() {
+    assert_eq!(render_plain(&["#", "# Title\nbody"]), "Title\nbody");
+}
+
+#[test]
+fn test_code_fence_toggles_code_block() {
+    let mut out = Buffer::no_color();
+    let mut renderer = MarkdownStreamRenderer::new();
+
+    renderer.push(&mut out, "``").expect("push");
+    assert!(!renderer.in_code_block());
+    renderer.push(&mut out, "`rust\nlet x = **y**;\n").expect("push");
+    assert!(renderer.in_code_block());
+    renderer.push(&mut out, "```\n").expect("push");
+    assert!(!renderer.in_code_block());
+
+    let text = String::from_utf8(out.into_inner()).expect("utf8");
+    assert_eq!(text, "```rust\nlet x = **y**;\n```\n");
+}
+
+#[test]
+fn test_finish_resets_unterminated_state() {
+    let mut out = Buffer::no_color();
+    let mut renderer = MarkdownStreamRenderer::new();
+    renderer.push(&mut out, "```\ncode").expect("push");
+    renderer.finish(&mut out).expect("finish");
+    assert!(!renderer.in_code_block());
+}
+
+#[test]
+fn test_format_turn_stats() {
+    let stats = format_turn_stats(Some(42), Some(12.345), Some(Duration::from_millis(250)))
+        .expect("stats");
+    assert_eq!(stats, "42 tokens · 12.3 tok/s · TTFT 250 ms");
+    assert!(format_turn_stats(None, None, None).is_none());
+}
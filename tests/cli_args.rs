@@ -91,3 +91,73 @@ fn test_validate_memory_timeout() {
     args.memory_read_timeout = 5000;
     assert!(args.validate().is_ok());
 }
+
+#[test]
+fn test_parse_dashboard() {
+    assert!(!CliArgs::default().dashboard);
+
+    let args = vec!["program".to_string(), "--dashboard".to_string()];
+    let cli_args = CliArgs::from_args(&args);
+    assert!(cli_args.dashboard);
+    assert!(cli_args.documents.is_empty());
+}
+
+#[test]
+fn test_parse_trace() {
+    assert!(!CliArgs::default().trace);
+
+    let args = vec!["program".to_string(), "--trace".to_string()];
+    assert!(CliArgs::from_args(&args).trace);
+}
+
+#[test]
+fn test_parse_workflow_run() {
+    let args: Vec<String> = ["program", "workflow", "run", "flow.yaml", "--input", "question=why"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let cli_args = CliArgs::from_args(&args);
+    assert_eq!(cli_args.workflow, Some(std::path::PathBuf::from("flow.yaml")));
+    assert_eq!(cli_args.workflow_inputs, ["question=why"]);
+    assert!(!cli_args.interactive);
+    assert!(cli_args.documents.is_empty());
+}
+
+#[test]
+fn test_parse_template_goldens() {
+    let args: Vec<String> = ["program", "templates", "goldens", "tests/goldens/chat_templates"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let cli_args = CliArgs::from_args(&args);
+    assert_eq!(
+        cli_args.template_goldens,
+        Some(std::path::PathBuf::from("tests/goldens/chat_templates"))
+    );
+    assert!(!cli_args.interactive);
+    assert!(cli_args.documents.is_empty());
+}
+
+#[test]
+fn test_template_goldens_requires_a_directory() {
+    let args: Vec<String> = ["program", "templates", "goldens"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let cli_args = CliArgs::from_args(&args);
+    assert!(!cli_args.interactive);
+    assert!(cli_args.validate().is_err());
+}
+
+#[test]
+fn test_parse_memory_fsck() {
+    let args: Vec<String> = ["program", "memory", "fsck", "notes", "--repair"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let cli_args = CliArgs::from_args(&args);
+    assert_eq!(cli_args.fsck_library.as_deref(), Some("notes"));
+    assert!(cli_args.fsck_repair);
+    assert!(!cli_args.interactive);
+    assert!(cli_args.documents.is_empty());
+}
@@ -6,8 +6,12 @@ mod core {
         mod test_stats;
         mod test_tokens;
         mod test_config;
+        mod test_constraints;
+        mod test_token_output_stream;
     }
+    mod test_device_util;
     mod test_model_config;
+    mod test_model_manager;
     mod test_simd_adapters;
     mod tokenizer {
         mod test_core;
@@ -0,0 +1,24 @@
+// Tests for src/core/generation/constraints.rs
+
+use kodegen_candle_agent::constraints::{
+    ConstraintError, choice_pattern, validate_regex, validate_schema,
+};
+use serde_json::json;
+
+#[test]
+fn test_validate_schema_accepts_objects_and_booleans_only() {
+    assert!(validate_schema(&json!({"type": "object"})).is_ok());
+    assert!(validate_schema(&json!(true)).is_ok());
+    assert!(matches!(
+        validate_schema(&json!("object")),
+        Err(ConstraintError::InvalidSchema(_))
+    ));
+}
+
+#[test]
+fn test_choice_pattern_escapes_literals() {
+    let pattern = choice_pattern(&["yes", "no", "a+b"]);
+    assert_eq!(pattern, r"(yes|no|a\+b)");
+    assert!(validate_regex(&pattern).is_ok());
+    assert!(matches!(validate_regex("(unclosed"), Err(ConstraintError::InvalidRegex(_))));
+}
@@ -59,3 +59,16 @@ fn test_efficiency_summary() {
     assert!(summary.contains("90.0%")); // Cache hit rate
     assert!(summary.contains("1.0MB")); // Memory usage
 }
+
+#[test]
+fn test_flash_attention_survives_reset() {
+    let mut stats = GenerationStatistics::new();
+    assert!(!stats.flash_attention_active);
+
+    stats.set_flash_attention_active(true);
+    stats.total_tokens = 10;
+    stats.reset();
+
+    assert!(stats.flash_attention_active);
+    assert_eq!(stats.total_tokens, 0);
+}
@@ -0,0 +1,66 @@
+// Tests for src/core/generation/token_output_stream.rs
+
+use std::collections::HashMap;
+
+use kodegen_candle_agent::core::generation::TokenOutputStream;
+use tokenizers::Tokenizer;
+use tokenizers::models::wordlevel::WordLevel;
+
+/// Word-level tokenizer over `words`; decoding joins tokens with spaces
+fn tokenizer(words: &[&str]) -> Tokenizer {
+    let vocab: HashMap<String, u32> = words
+        .iter()
+        .enumerate()
+        .map(|(id, word)| (word.to_string(), id as u32))
+        .collect();
+    let model = WordLevel::builder()
+        .vocab(vocab.into_iter().collect())
+        .unk_token("<unk>".to_string())
+        .build()
+        .expect("word-level model");
+    Tokenizer::new(model)
+}
+
+fn run(tos: &mut TokenOutputStream, tokens: &[u32]) -> String {
+    let mut text = String::new();
+    for &token in tokens {
+        if tos.stopped() {
+            break;
+        }
+        text.extend(tos.next_token(token).expect("decode"));
+    }
+    text.extend(tos.decode_rest().expect("decode"));
+    text
+}
+
+#[test]
+fn test_stream_without_stops_emits_everything() {
+    let mut tos = TokenOutputStream::new(tokenizer(&["<unk>", "hello", "world", "END"]));
+    assert_eq!(run(&mut tos, &[1, 2, 3, 1]), "hello world END hello");
+    assert!(!tos.stopped());
+}
+
+#[test]
+fn test_stream_stops_before_a_sequence_split_across_tokens() {
+    let words = ["<unk>", "hello", "world", "END", "MARK", "after"];
+    let mut tos =
+        TokenOutputStream::new(tokenizer(&words)).with_stop_sequences(vec!["END MARK".to_string()]);
+
+    // "END" alone could start the stop sequence, so it is held back
+    assert_eq!(tos.next_token(1).expect("decode").as_deref(), Some("hello"));
+    assert_eq!(tos.next_token(3).expect("decode").as_deref(), Some(" "));
+    assert_eq!(tos.next_token(4).expect("decode"), None);
+    assert!(tos.stopped());
+    assert_eq!(tos.next_token(5).expect("decode"), None);
+    assert_eq!(tos.decode_rest().expect("decode"), None);
+}
+
+#[test]
+fn test_held_text_is_released_when_the_sequence_does_not_complete() {
+    let words = ["<unk>", "hello", "world", "END", "MARK", "after"];
+    let mut tos =
+        TokenOutputStream::new(tokenizer(&words)).with_stop_sequences(vec!["END MARK".to_string()]);
+
+    assert_eq!(run(&mut tos, &[1, 3, 5, 3]), "hello END after END");
+    assert!(!tos.stopped());
+}
@@ -0,0 +1,32 @@
+// Tests for src/core/device_util.rs
+
+use candle_core::Device;
+use kodegen_candle_agent::core::device_util::{
+    DevicePreference, resolve_flash_attention, select_device,
+};
+
+#[test]
+fn test_parse_device_preference() {
+    assert_eq!("auto".parse(), Ok(DevicePreference::Auto));
+    assert_eq!(" CPU ".parse(), Ok(DevicePreference::Cpu));
+    assert_eq!("cuda".parse(), Ok(DevicePreference::Cuda(0)));
+    assert_eq!("cuda:1".parse(), Ok(DevicePreference::Cuda(1)));
+    assert_eq!("metal:0".parse(), Ok(DevicePreference::Metal(0)));
+    assert!("tpu".parse::<DevicePreference>().is_err());
+    assert!("cuda:x".parse::<DevicePreference>().is_err());
+}
+
+#[test]
+fn test_cpu_preference_selects_cpu() {
+    let device = select_device(DevicePreference::Cpu).expect("cpu device");
+    assert!(matches!(device, Device::Cpu));
+}
+
+#[test]
+fn test_flash_attention_falls_back_without_kernels() {
+    let cpu = Device::Cpu;
+    assert!(!resolve_flash_attention("model", false, true, &cpu));
+    // No flash-attn kernels run on the CPU, whatever the architecture
+    assert!(!resolve_flash_attention("model", true, true, &cpu));
+    assert!(!resolve_flash_attention("model", true, false, &cpu));
+}
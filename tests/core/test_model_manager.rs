@@ -0,0 +1,97 @@
+// Tests for src/core/model_manager.rs
+
+use std::sync::Arc;
+
+use kodegen_candle_agent::capability::registry::{AnyModel, TextToTextModel};
+use kodegen_candle_agent::capability::text_to_text::CandleQwen3QuantizedModel;
+use kodegen_candle_agent::core::{EngineError, ModelManager, ModelRole};
+use kodegen_candle_agent::domain::model::traits::CandleModel;
+
+fn chat_model() -> AnyModel {
+    AnyModel::TextToText(TextToTextModel::Qwen3Quantized(Arc::new(
+        CandleQwen3QuantizedModel::default(),
+    )))
+}
+
+#[test]
+fn test_routes_by_key_then_role_default() {
+    let manager = ModelManager::new();
+    manager
+        .insert("chat", chat_model(), ModelRole::Chat)
+        .unwrap();
+    manager
+        .insert("draft", chat_model(), ModelRole::Draft)
+        .unwrap();
+
+    assert_eq!(
+        manager
+            .route(Some("draft"), ModelRole::Chat)
+            .unwrap()
+            .registry_key(),
+        "draft"
+    );
+    assert_eq!(
+        manager.route(None, ModelRole::Chat).unwrap().registry_key(),
+        "chat"
+    );
+    assert!(matches!(
+        manager.route(None, ModelRole::Reranker),
+        Err(EngineError::ModelNotFound)
+    ));
+
+    assert!(manager.unload("chat"));
+    assert!(manager.route(None, ModelRole::Chat).is_err());
+    assert!(!manager.unload("chat"));
+}
+
+#[test]
+fn test_memory_budget_covers_all_models() {
+    let per_model = chat_model().info().est_memory_allocation_mb;
+    let manager = ModelManager::new().with_memory_budget_mb(per_model);
+
+    manager
+        .insert("chat", chat_model(), ModelRole::Chat)
+        .unwrap();
+    assert_eq!(manager.memory_used_mb(), per_model);
+    assert!(matches!(
+        manager.insert("draft", chat_model(), ModelRole::Draft),
+        Err(EngineError::MemoryBudgetExceeded {
+            available_mb: 0,
+            ..
+        })
+    ));
+
+    // Reloading a held model charges nothing
+    manager
+        .insert("chat", chat_model(), ModelRole::Chat)
+        .unwrap();
+    assert_eq!(manager.stats().models.len(), 1);
+}
+
+#[test]
+fn test_role_requires_capability() {
+    let manager = ModelManager::new();
+    assert!(matches!(
+        manager.insert("chat", chat_model(), ModelRole::Vision),
+        Err(EngineError::ConfigurationError(_))
+    ));
+    assert!(!manager.is_loaded("chat"));
+}
+
+#[tokio::test]
+async fn test_route_model_prefers_held_chat_models() {
+    let manager = ModelManager::new();
+    manager
+        .insert("chat", chat_model(), ModelRole::Chat)
+        .unwrap();
+
+    // Held models are returned without touching the registry
+    let routed = manager.route_model(None, "unregistered").await.unwrap();
+    assert_eq!(routed.registry_key(), "chat");
+    assert!(routed.text_to_text().is_some());
+
+    assert!(matches!(
+        manager.route_model(Some("unregistered"), "chat").await,
+        Err(EngineError::ModelNotFound)
+    ));
+}
@@ -1,21 +1,65 @@
 // Integration tests for domain operations
 
 mod domain {
+    mod agent {
+        mod test_prompt_guard;
+        mod test_untrusted_content;
+    }
     mod chat {
+        mod test_citations;
+        mod test_confidence;
+        mod test_handoff;
+        mod test_injection;
+        mod test_interrupt;
         mod test_loop;
         mod message {
             mod test_message_processing;
             mod test_mod;
         }
         mod test_orchestration;
+        mod test_profile;
+        mod test_recall;
+        mod test_regenerate;
+        mod test_trace;
+        mod search {
+            mod test_dsl;
+            mod test_index;
+        }
         mod templates {
             mod parser {
                 mod test_mod;
             }
+            mod test_model_template;
+        }
+    }
+    mod completion {
+        mod test_response_format;
+        mod test_types;
+    }
+    mod context {
+        mod test_builder;
+        mod extraction {
+            mod test_validation;
+        }
+        mod provider {
+            mod test_context_impl;
+            mod test_file_tree;
+            mod test_sql;
         }
     }
+    mod embedding {
+        mod test_matryoshka;
+    }
+    mod test_event;
     mod model {
+        mod test_defaults;
         mod test_error;
+        mod test_hf_cache;
+    }
+    mod tool {
+        mod test_analytics;
+        mod test_safety;
+        mod test_selector;
     }
     mod util {
         mod test_json_util;
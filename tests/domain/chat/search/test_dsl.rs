@@ -0,0 +1,97 @@
+// Tests for src/domain/chat/search/dsl.rs
+
+use kodegen_candle_agent::domain::chat::search::{
+    QueryExpr, QueryOperator, SortOrder, parse_query,
+};
+
+#[test]
+fn test_parses_boolean_expression_and_filters() {
+    let query = parse_query("error AND (timeout OR retry) tag:bug after:2024-06-01 user:assistant")
+        .expect("valid query");
+
+    assert_eq!(
+        query.expression,
+        Some(QueryExpr::And(vec![
+            QueryExpr::Term("error".into()),
+            QueryExpr::Or(vec![
+                QueryExpr::Term("timeout".into()),
+                QueryExpr::Term("retry".into()),
+            ]),
+        ]))
+    );
+    assert_eq!(query.terms, vec!["error", "timeout", "retry"]);
+    assert!(matches!(query.operator, QueryOperator::And));
+    assert_eq!(query.tag_filter, Some(vec!["bug".to_string()]));
+    assert_eq!(query.user_filter.as_deref(), Some("assistant"));
+
+    let range = query.date_range.expect("date range");
+    assert_eq!(range.start, 1_717_200_000);
+    assert_eq!(range.end, u64::MAX);
+}
+
+#[test]
+fn test_precedence_negation_and_phrases() {
+    let query = parse_query("a b OR \"exact phrase\" -draft sort:newest limit:5").expect("valid query");
+    let expression = query.expression.expect("expression");
+
+    assert_eq!(
+        expression,
+        QueryExpr::Or(vec![
+            QueryExpr::And(vec![QueryExpr::Term("a".into()), QueryExpr::Term("b".into())]),
+            QueryExpr::And(vec![
+                QueryExpr::Phrase("exact phrase".into()),
+                QueryExpr::Not(Box::new(QueryExpr::Term("draft".into()))),
+            ]),
+        ])
+    );
+    assert!(matches!(query.sort_order, SortOrder::DateDescending));
+    assert_eq!(query.max_results, 5);
+
+    assert!(expression.matches("A and B"));
+    assert!(expression.matches("an Exact Phrase here"));
+    assert!(!expression.matches("an exact phrase in a draft"));
+    assert!(!expression.matches("only a"));
+}
+
+#[test]
+fn test_filter_only_query_matches_everything() {
+    let query = parse_query("tag:bug,ui tag:urgent").expect("valid query");
+
+    assert_eq!(query.expression, Some(QueryExpr::And(Vec::new())));
+    assert!(query.terms.is_empty());
+    assert_eq!(
+        query.tag_filter,
+        Some(vec!["bug".to_string(), "ui".to_string(), "urgent".to_string()])
+    );
+    assert!(query.expression.expect("expression").matches("anything"));
+}
+
+#[test]
+fn test_syntax_errors_report_column_and_hint() {
+    let cases = [
+        ("", 1, "Empty query"),
+        ("error AND (timeout OR retry", 11, "Unclosed '('"),
+        ("error)", 6, "Unexpected ')'"),
+        ("error AND", 7, "AND needs a term after it"),
+        ("OR retry", 1, "OR needs a term before it"),
+        ("\"unterminated", 1, "Unclosed quote"),
+        ("tga:bug", 1, "did you mean 'tag:'"),
+        ("(error tag:bug)", 8, "outside the parentheses"),
+        ("after:June", 1, "expected YYYY-MM-DD"),
+        ("user:robot", 1, "Unknown role"),
+        ("limit:0", 1, "positive number"),
+        ("after:2024-06-02 before:2024-06-01", 18, "later than"),
+    ];
+
+    for (input, column, hint) in cases {
+        let error = parse_query(input).expect_err(input);
+        assert_eq!(error.column, column, "column for {input:?}: {error}");
+        assert!(error.message.contains(hint), "hint for {input:?}: {error}");
+    }
+}
+
+#[test]
+fn test_urls_and_unknown_prefixes_are_terms() {
+    let query = parse_query("https://example.com note:later").expect("valid query");
+    assert_eq!(query.terms, vec!["https://example.com", "note:later"]);
+}
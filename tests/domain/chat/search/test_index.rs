@@ -0,0 +1,35 @@
+// Tests for src/domain/chat/search/index.rs
+
+use kodegen_candle_agent::domain::chat::message::CandleSearchChatMessage;
+use kodegen_candle_agent::domain::chat::message::{CandleMessage, CandleMessageRole};
+use kodegen_candle_agent::domain::chat::search::ChatSearchIndex;
+
+fn message(id: &str, content: &str) -> CandleSearchChatMessage {
+    CandleSearchChatMessage {
+        message: CandleMessage {
+            role: CandleMessageRole::User,
+            content: content.to_string(),
+            id: Some(id.to_string()),
+            timestamp: None,
+        },
+        relevance_score: 0.0,
+        highlights: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_remove_message_drops_its_postings_only() {
+    let index = ChatSearchIndex::new();
+    index.add_message(message("s1/a", "my card number is 4111")).await.unwrap();
+    index.add_message(message("s1/b", "my order shipped")).await.unwrap();
+
+    assert_eq!(index.remove_message("s1/a"), Some(5));
+
+    assert!(index.document_store().get("s1/a").is_none());
+    assert!(index.inverted_index().get("4111").is_none());
+    let shared = index.inverted_index().get("my").unwrap();
+    assert_eq!(shared.value().len(), 1);
+    assert_eq!(shared.value()[0].doc_id, "s1/b");
+
+    assert_eq!(index.remove_message("s1/a"), None);
+}
@@ -0,0 +1,74 @@
+// Tests for src/domain/chat/templates/model_template.rs
+
+use kodegen_candle_agent::capability::text_to_text::{
+    ChatConversation, ChatTemplateFamily, ChatTurn,
+};
+use kodegen_candle_agent::domain::chat::templates::{
+    JinjaChatTemplate, ModelChatTemplate, TemplatePreference,
+};
+use serde_json::json;
+
+const CHATML_JINJA: &str = "{{ bos_token }}{% for message in messages %}\
+<|im_start|>{{ message.role }}\n{{ message.content | trim }}<|im_end|>\n\
+{% endfor %}{% if add_generation_prompt %}<|im_start|>assistant\n{% endif %}";
+
+fn conversation() -> ChatConversation {
+    ChatConversation::new(vec![ChatTurn::system("Be brief."), ChatTurn::user(" hi ")])
+}
+
+#[test]
+fn test_jinja_template_from_tokenizer_config() -> Result<(), Box<dyn std::error::Error>> {
+    let config = json!({
+        "chat_template": CHATML_JINJA,
+        "bos_token": { "content": "<s>", "lstrip": false },
+        "eos_token": "<|im_end|>",
+    });
+    let template =
+        ModelChatTemplate::Jinja(JinjaChatTemplate::from_tokenizer_config_value(&config)?.into());
+
+    // The tokenizer adds the BOS token, so the template's is dropped
+    assert_eq!(
+        template.render(&conversation())?,
+        "<|im_start|>system\nBe brief.<|im_end|>\n<|im_start|>user\nhi<|im_end|>\n<|im_start|>assistant\n"
+    );
+    assert_eq!(template.end_of_turn(), "<|im_end|>");
+    Ok(())
+}
+
+#[test]
+fn test_named_templates_pick_default() -> Result<(), Box<dyn std::error::Error>> {
+    let config = json!({
+        "chat_template": [
+            { "name": "tool_use", "template": "tools" },
+            { "name": "default", "template": "{{ messages | length }} turns" },
+        ],
+    });
+    let template = JinjaChatTemplate::from_tokenizer_config_value(&config)?;
+    assert_eq!(template.render(&conversation())?, "2 turns");
+    Ok(())
+}
+
+#[test]
+fn test_raise_exception_is_a_render_error() -> Result<(), Box<dyn std::error::Error>> {
+    let template = JinjaChatTemplate::new("{{ raise_exception('no system role') }}", "", "")?;
+    let error = template
+        .render(&conversation())
+        .expect_err("raise_exception fails the render");
+    assert!(error.to_string().contains("no system role"));
+    Ok(())
+}
+
+#[test]
+fn test_preset_without_model_template() -> Result<(), Box<dyn std::error::Error>> {
+    let template = ModelChatTemplate::resolve(
+        Some(ChatTemplateFamily::ChatMl),
+        None,
+        TemplatePreference::Model,
+    )?;
+    assert_eq!(
+        template.render(&conversation())?,
+        ChatTemplateFamily::ChatMl.render(&conversation())
+    );
+    assert!(ModelChatTemplate::resolve(None, None, TemplatePreference::Preset).is_err());
+    Ok(())
+}
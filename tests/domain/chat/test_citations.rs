@@ -0,0 +1,30 @@
+// Tests for src/domain/chat/citations.rs
+
+use kodegen_candle_agent::domain::chat::citations::{CITATION_THRESHOLD, rank_citations};
+use kodegen_candle_agent::domain::context::builder::{ContextItem, ContextSourceKind};
+
+#[test]
+fn test_rank_citations_keeps_similar_memories_best_first() {
+    let close = ContextItem::new(ContextSourceKind::Memory, "notes.md", "deploys run at noon")
+        .with_memory_id("m1");
+    let closer = ContextItem::new(ContextSourceKind::Memory, "team_notes:ops.md", "noon deploys")
+        .with_memory_id("m2");
+    let unrelated = ContextItem::new(ContextSourceKind::Memory, "misc.md", "lunch menu")
+        .with_memory_id("m3");
+    let file = ContextItem::new(ContextSourceKind::File, "src/lib.rs", "fn deploy()");
+
+    let answer = [1.0, 0.0];
+    let entries = vec![
+        (&close, vec![0.8, 0.6]),
+        (&closer, vec![1.0, 0.1]),
+        (&unrelated, vec![0.0, 1.0]),
+        (&file, vec![1.0, 0.0]),
+    ];
+
+    let citations = rank_citations(&answer, &entries, CITATION_THRESHOLD);
+    assert_eq!(citations.len(), 2);
+    assert_eq!(citations[0].memory_id.as_deref(), Some("m2"));
+    assert_eq!(citations[0].source, "team_notes:ops.md");
+    assert_eq!(citations[1].memory_id.as_deref(), Some("m1"));
+    assert!(citations[0].similarity >= citations[1].similarity);
+}
@@ -0,0 +1,56 @@
+// Tests for src/domain/chat/confidence.rs
+
+use kodegen_candle_agent::domain::chat::confidence::{
+    ConfidenceEstimator, LowConfidenceAction, combine_confidence, hedging_count, hedging_score,
+    logprob_score, parse_self_eval,
+};
+
+#[test]
+fn test_hedging_lowers_score() {
+    assert_eq!(hedging_count("The capital of France is Paris."), 0);
+    assert!((hedging_score("The capital of France is Paris.") - 1.0).abs() < f32::EPSILON);
+
+    let hedged = "I think it is probably Lyon, but I’m not sure.";
+    assert_eq!(hedging_count(hedged), 3);
+    assert!(hedging_score(hedged) < 0.5);
+}
+
+#[test]
+fn test_combine_uses_available_signals() {
+    // Hedging alone
+    assert!((combine_confidence(None, "It is Paris.", None) - 1.0).abs() < 1e-6);
+
+    // A near-certain model with a plain answer scores high
+    let high = combine_confidence(Some(-0.05), "It is Paris.", Some(0.9));
+    assert!(high > 0.9, "{high}");
+
+    // Low token probabilities and a poor self-rating pull the score down
+    let low = combine_confidence(Some(-2.5), "Perhaps Lyon.", Some(0.2));
+    assert!(low < 0.3, "{low}");
+
+    // Non-finite logprobs are ignored
+    assert_eq!(
+        combine_confidence(Some(f64::NEG_INFINITY), "It is Paris.", None),
+        combine_confidence(None, "It is Paris.", None)
+    );
+    assert!((logprob_score(0.0) - 1.0).abs() < f32::EPSILON);
+}
+
+#[test]
+fn test_parse_self_eval() {
+    assert_eq!(parse_self_eval("8"), Some(0.8));
+    assert_eq!(parse_self_eval(" Rating: 7.5/10"), Some(0.75));
+    assert_eq!(parse_self_eval("10."), Some(1.0));
+    assert_eq!(parse_self_eval("42"), None);
+    assert_eq!(parse_self_eval("no idea"), None);
+}
+
+#[test]
+fn test_threshold_action() {
+    let estimator = ConfidenceEstimator::new();
+    assert_eq!(estimator.action_for(0.0), None);
+
+    let estimator = estimator.with_threshold(0.6, LowConfidenceAction::RecallMemory);
+    assert_eq!(estimator.action_for(0.4), Some(LowConfidenceAction::RecallMemory));
+    assert_eq!(estimator.action_for(0.6), None);
+}
@@ -0,0 +1,97 @@
+// Tests for src/domain/chat/handoff.rs
+
+use kodegen_candle_agent::builders::ConversationHistory;
+use kodegen_candle_agent::domain::chat::handoff::{
+    HANDOFF_MESSAGE_CHARS, HANDOFF_SUMMARY_MESSAGES, HANDOFF_TOOL_OUTPUT_CHARS, HandoffDocument,
+};
+use kodegen_candle_agent::prelude::{CandleMessageChunk, CandleMessageRole};
+
+fn history() -> ConversationHistory {
+    ConversationHistory::new()
+        .with_message(CandleMessageRole::User, "Find how the parser handles tabs")
+        .with_message(CandleMessageRole::Tool, "lexer.rs: tabs expand to 4 spaces")
+        .with_message(CandleMessageRole::Assistant, "Tabs expand to four spaces.")
+        .with_message(
+            CandleMessageRole::User,
+            "  Make the tab width configurable ",
+        )
+}
+
+#[test]
+fn test_from_history_packages_summary_tools_and_task() {
+    let document = HandoffDocument::from_history("researcher", &history());
+
+    assert_eq!(document.from, "researcher");
+    assert_eq!(document.pending_task, "Make the tab width configurable");
+    assert_eq!(document.tool_outputs.len(), 1);
+    assert_eq!(
+        document.tool_outputs[0].output,
+        "lexer.rs: tabs expand to 4 spaces"
+    );
+    assert_eq!(
+        document.summary,
+        "user: Find how the parser handles tabs\n\
+         assistant: Tabs expand to four spaces.\n\
+         user: Make the tab width configurable"
+    );
+}
+
+#[test]
+fn test_brief_ends_with_task_and_skips_empty_sections() {
+    let brief = HandoffDocument::new("reviewer")
+        .with_memory("Style guide: no tabs")
+        .with_pending_task("Address the review comments")
+        .brief();
+
+    assert!(brief.starts_with("You are taking over this conversation from reviewer."));
+    assert!(brief.contains("## Relevant memories\n- Style guide: no tabs\n"));
+    assert!(!brief.contains("## Tool outputs"));
+    assert!(brief.ends_with("## Your task\nAddress the review comments"));
+}
+
+#[test]
+fn test_long_messages_are_clipped_and_chunk_round_trips() -> Result<(), serde_json::Error> {
+    let long = "x".repeat(HANDOFF_MESSAGE_CHARS + 10);
+    let history = ConversationHistory::new().with_message(CandleMessageRole::Assistant, &long);
+    let document = HandoffDocument::from_history("coder", &history);
+    assert_eq!(
+        document.summary.chars().count(),
+        "assistant: ".len() + HANDOFF_MESSAGE_CHARS + 1
+    );
+
+    let chunk = CandleMessageChunk::Handoff(document.clone());
+    let json = serde_json::to_string(&chunk)?;
+    match serde_json::from_str::<CandleMessageChunk>(&json)? {
+        CandleMessageChunk::Handoff(parsed) => assert_eq!(parsed, document),
+        other => panic!("unexpected chunk {other:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_long_history_keeps_opening_and_latest_messages() {
+    let mut history = ConversationHistory::new()
+        .with_message(CandleMessageRole::User, "Port the parser to the new lexer")
+        .with_message(CandleMessageRole::Tool, "y".repeat(HANDOFF_TOOL_OUTPUT_CHARS * 2));
+    for i in 0..HANDOFF_SUMMARY_MESSAGES * 2 {
+        history = history.with_message(CandleMessageRole::Assistant, &format!("step {i}"));
+    }
+    let document = HandoffDocument::from_history("coder", &history);
+
+    let lines: Vec<&str> = document.summary.lines().collect();
+    assert_eq!(lines.len(), HANDOFF_SUMMARY_MESSAGES + 1);
+    assert_eq!(lines[0], "user: Port the parser to the new lexer");
+    assert_eq!(
+        lines[1],
+        format!("[{} earlier messages omitted]", HANDOFF_SUMMARY_MESSAGES + 1)
+    );
+    assert_eq!(
+        lines.last(),
+        Some(&format!("assistant: step {}", HANDOFF_SUMMARY_MESSAGES * 2 - 1).as_str())
+    );
+
+    assert_eq!(
+        document.tool_outputs[0].output.chars().count(),
+        HANDOFF_TOOL_OUTPUT_CHARS + 1
+    );
+}
@@ -0,0 +1,61 @@
+// Tests for src/domain/chat/injection.rs
+
+use kodegen_candle_agent::domain::chat::injection::{
+    BACKGROUND_CONTEXT_HEADING, ContextPlacement, MemoryInjection,
+};
+
+const CONTEXT: &str = "## Relevant Context\n\n- [memory: notes.md]: deploys run at noon\n";
+
+#[test]
+fn test_default_places_context_before_question() {
+    let prompt = MemoryInjection::default().assemble("Be brief.", "", CONTEXT, "When do deploys run?");
+    assert_eq!(
+        prompt,
+        "Be brief.\n\n## Relevant Context\n\n- [memory: notes.md]: deploys run at noon\n\nUser: When do deploys run?"
+    );
+}
+
+#[test]
+fn test_after_question_and_tool_message_placements() {
+    let after = MemoryInjection::new(ContextPlacement::AfterQuestion).assemble("Be brief.", "", CONTEXT, "When?");
+    assert!(after.starts_with("Be brief.\n\nUser: When?\n\n## Relevant Context"));
+
+    let tool = MemoryInjection::from(ContextPlacement::ToolMessage).assemble("Be brief.", "", CONTEXT, "When?");
+    assert!(tool.contains("Tool (memory_recall):\n## Relevant Context"));
+    assert!(tool.ends_with("User: When?"));
+}
+
+#[test]
+fn test_system_prompt_placement_precedes_transcript() {
+    let transcript = "User: Hi\nAssistant: Hello";
+
+    let system = MemoryInjection::new(ContextPlacement::SystemPrompt).assemble("Be brief.", transcript, CONTEXT, "When?");
+    assert!(system.starts_with("Be brief.\n\n## Relevant Context"));
+    assert!(system.ends_with("Assistant: Hello\n\nUser: When?"));
+
+    let before = MemoryInjection::new(ContextPlacement::BeforeQuestion).assemble("Be brief.", transcript, CONTEXT, "When?");
+    assert!(before.starts_with("Be brief.\n\nUser: Hi\nAssistant: Hello\n\n## Relevant Context"));
+    assert!(before.ends_with("deploys run at noon\n\nUser: When?"));
+}
+
+#[test]
+fn test_empty_context_is_omitted() {
+    let prompt = MemoryInjection::new(ContextPlacement::AfterQuestion).assemble("Be brief.", "", "", "When?");
+    assert_eq!(prompt, "Be brief.\n\nUser: When?");
+}
+
+#[test]
+fn test_context_builder_uses_placement_heading_and_cutoff() {
+    use kodegen_candle_agent::domain::context::builder::{ContextItem, ContextSourceKind};
+
+    let built = MemoryInjection::new(ContextPlacement::SystemPrompt)
+        .with_min_score(0.5)
+        .context_builder(500)
+        .add(ContextItem::new(ContextSourceKind::Memory, "a", "kept").with_score(0.9))
+        .add(ContextItem::new(ContextSourceKind::Memory, "b", "dropped").with_score(0.2))
+        .build();
+
+    assert!(built.text.starts_with(BACKGROUND_CONTEXT_HEADING));
+    assert_eq!(built.included.len(), 1);
+    assert_eq!(built.below_cutoff, 1);
+}
@@ -0,0 +1,29 @@
+// Tests for src/domain/chat/interrupt.rs
+
+use std::time::Duration;
+
+use kodegen_candle_agent::domain::chat::TurnInterrupt;
+
+#[test]
+fn test_clones_share_interruption() {
+    let interrupt = TurnInterrupt::new();
+    let held_by_agent = interrupt.clone();
+    assert!(!held_by_agent.is_interrupted());
+
+    interrupt.interrupt();
+    interrupt.interrupt();
+    assert!(held_by_agent.is_interrupted());
+}
+
+#[tokio::test]
+async fn test_interrupted_resolves_after_interrupt() {
+    let interrupt = TurnInterrupt::new();
+    let waiter = interrupt.clone();
+    let task = tokio::spawn(async move { waiter.interrupted().await });
+
+    interrupt.interrupt();
+    tokio::time::timeout(Duration::from_secs(1), task)
+        .await
+        .expect("interrupted() should resolve")
+        .unwrap();
+}
@@ -0,0 +1,66 @@
+// Tests for src/domain/chat/profile.rs
+
+use kodegen_candle_agent::domain::chat::profile::{
+    PROFILE_HEADING, ProfileFact, ProfileKey, extract_profile_facts, render_profile,
+};
+
+#[test]
+fn test_extract_profile_facts_reads_first_person_statements() {
+    let facts = extract_profile_facts(
+        "Call me Sam. My timezone is Europe/Berlin, and I prefer to write in Rust. Please use 4-space indentation.",
+    );
+    assert_eq!(
+        facts,
+        vec![
+            ProfileFact::new(ProfileKey::Name, "Sam"),
+            ProfileFact::new(ProfileKey::Timezone, "Europe/Berlin"),
+            ProfileFact::new(ProfileKey::ProgrammingLanguage, "Rust"),
+            ProfileFact::new(ProfileKey::Indentation, "4-space"),
+        ]
+    );
+
+    assert!(extract_profile_facts("What is Ana's timezone?").is_empty());
+}
+
+#[test]
+fn test_render_profile_respects_budget() {
+    let facts = vec![
+        ProfileFact::new(ProfileKey::ProgrammingLanguage, "Rust"),
+        ProfileFact::new(ProfileKey::Timezone, "Europe/Berlin"),
+    ];
+
+    let block = render_profile(&facts, 150);
+    assert!(block.starts_with(PROFILE_HEADING));
+    assert!(block.contains("- preferred programming language is Rust"));
+    assert!(block.contains("- timezone is Europe/Berlin"));
+
+    let tight = render_profile(&facts, 20);
+    assert!(tight.contains("Rust"));
+    assert!(!tight.contains("Berlin"));
+
+    assert!(render_profile(&facts, 4).is_empty());
+    assert!(render_profile(&[], 150).is_empty());
+}
+
+#[test]
+fn test_extract_profile_facts_ignores_loose_matches() {
+    for message in [
+        "Can you call me back later",
+        "Don't call me Sam.",
+        "My time zone is wrong",
+        "I prefer to write in the morning",
+        "Answer in French",
+        "I use 2 spaces after a period",
+        "Should I use snake_case?",
+    ] {
+        assert!(extract_profile_facts(message).is_empty(), "{message}");
+    }
+
+    assert_eq!(
+        extract_profile_facts("My favourite language is c++. From now on, reply in german"),
+        vec![
+            ProfileFact::new(ProfileKey::ProgrammingLanguage, "C++"),
+            ProfileFact::new(ProfileKey::ResponseLanguage, "German"),
+        ]
+    );
+}
@@ -0,0 +1,25 @@
+// Tests for src/domain/chat/recall.rs
+
+use kodegen_candle_agent::domain::chat::recall::{DEFAULT_RECALL_WEIGHT, RecallLibrary};
+
+#[test]
+fn test_names_get_default_weight() {
+    let libraries: Vec<RecallLibrary> = ["docs", "team_notes"].into_iter().map(Into::into).collect();
+    assert_eq!(libraries[0], RecallLibrary::new("docs"));
+    assert_eq!(libraries[1].weight, DEFAULT_RECALL_WEIGHT);
+}
+
+#[test]
+fn test_weighted_tuples() {
+    let library = RecallLibrary::from(("team_notes", 0.5));
+    assert_eq!(library.name, "team_notes");
+    assert_eq!(library.weight, 0.5);
+
+    let owned = RecallLibrary::from(("docs".to_string(), 2.0));
+    assert_eq!(owned.weight, 2.0);
+}
+
+#[test]
+fn test_negative_weight_clamped() {
+    assert_eq!(RecallLibrary::new("docs").with_weight(-1.0).weight, 0.0);
+}
@@ -0,0 +1,65 @@
+// Tests for src/domain/chat/regenerate.rs
+
+use kodegen_candle_agent::domain::chat::regenerate::{
+    DEFAULT_TEMPERATURE_STEP, LastTurn, MAX_REGENERATE_TEMPERATURE, RegenerateOptions, last_turn,
+    record_last_turn, set_assistant_memory,
+};
+
+#[test]
+fn test_default_options_raise_temperature() {
+    let options = RegenerateOptions::new();
+    assert_eq!(options.resolve_temperature(0.5), 0.5 + DEFAULT_TEMPERATURE_STEP);
+    assert_eq!(options.resolve_temperature(1.95), MAX_REGENERATE_TEMPERATURE);
+
+    let fixed = RegenerateOptions::new().with_temperature(0.3).with_seed(7);
+    assert_eq!(fixed.resolve_temperature(1.0), 0.3);
+    assert_eq!(fixed.resolve_seed(), 7);
+}
+
+#[test]
+fn test_assistant_memory_attaches_to_latest_turn_only() {
+    let key = "test-regenerate-session";
+    let turn = LastTurn {
+        user_message: "What is a monad?".to_string(),
+        temperature: 0.7,
+        session_id: key.to_string(),
+        turn_id: "turn-2".to_string(),
+        assistant_memory_id: None,
+    };
+    record_last_turn(key, turn.clone());
+
+    // A late write from an earlier turn must not overwrite the latest one
+    set_assistant_memory(key, "turn-1", "memory-old".to_string());
+    assert_eq!(last_turn(key), Some(turn));
+
+    set_assistant_memory(key, "turn-2", "memory-new".to_string());
+    assert_eq!(
+        last_turn(key).and_then(|t| t.assistant_memory_id),
+        Some("memory-new".to_string())
+    );
+    assert!(last_turn("test-regenerate-unknown").is_none());
+}
+
+#[test]
+fn test_turns_are_kept_per_session_and_need_one() {
+    let turn = |session: &str, message: &str| LastTurn {
+        user_message: message.to_string(),
+        temperature: 0.7,
+        session_id: session.to_string(),
+        turn_id: format!("{session}-turn"),
+        assistant_memory_id: None,
+    };
+    record_last_turn("test-regenerate-a", turn("test-regenerate-a", "first"));
+    record_last_turn("test-regenerate-b", turn("test-regenerate-b", "second"));
+    record_last_turn("", turn("anonymous", "third"));
+
+    assert_eq!(
+        last_turn("test-regenerate-a").map(|t| t.user_message),
+        Some("first".to_string())
+    );
+    assert_eq!(
+        last_turn("test-regenerate-b").map(|t| t.user_message),
+        Some("second".to_string())
+    );
+    assert!(last_turn("").is_none());
+}
@@ -0,0 +1,54 @@
+// Tests for src/domain/chat/trace.rs
+
+use kodegen_candle_agent::domain::chat::trace::{TurnTrace, Verbosity};
+use kodegen_candle_agent::domain::completion::CandleCompletionParams;
+use kodegen_candle_agent::domain::context::builder::{BuiltContext, ContextItem, ContextSourceKind};
+
+fn context() -> BuiltContext {
+    BuiltContext {
+        text: "## Relevant Context\n...".to_string(),
+        included: vec![
+            ContextItem::new(ContextSourceKind::Memory, "notes.md", "deploys run at noon")
+                .with_memory_id("m1")
+                .with_score(0.82),
+            ContextItem::new(ContextSourceKind::File, "src/lib.rs", "fn deploy()"),
+        ],
+        tokens: 12,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_capture_respects_verbosity() {
+    let mut params = CandleCompletionParams::default();
+    params.temperature = 0.3;
+    let tools = vec!["fs_read_file".to_string()];
+
+    assert!(TurnTrace::capture(Verbosity::Off, "prompt", &params, &tools, &context()).is_none());
+
+    let summary = TurnTrace::capture(Verbosity::Summary, "prompt", &params, &tools, &context()).unwrap();
+    assert!(summary.prompt.is_none());
+    assert_eq!(summary.tools, tools);
+    assert_eq!(summary.memory_hits.len(), 1);
+    assert_eq!(summary.memory_hits[0].memory_id.as_deref(), Some("m1"));
+    assert_eq!(summary.memory_hits[0].score, 0.82);
+    assert_eq!(summary.context_tokens, 12);
+    assert_eq!(summary.sampling.temperature, 0.3);
+
+    let full = TurnTrace::capture(Verbosity::Full, "the whole prompt", &params, &tools, &context()).unwrap();
+    assert_eq!(full.prompt.as_deref(), Some("the whole prompt"));
+    assert!(full.to_string().contains("the whole prompt"));
+}
+
+#[test]
+fn test_assistant_prefix_counts_toward_prompt() {
+    let plain = CandleCompletionParams::default();
+    let prefilled = CandleCompletionParams::default().with_assistant_prefix(Some("```json\n".to_string()));
+    assert_eq!(CandleCompletionParams::default().with_assistant_prefix(Some(String::new())).assistant_prefix, None);
+
+    let without = TurnTrace::capture(Verbosity::Summary, "prompt", &plain, &[], &context()).unwrap();
+    let with = TurnTrace::capture(Verbosity::Summary, "prompt", &prefilled, &[], &context()).unwrap();
+    assert_eq!(with.prompt_tokens, without.prompt_tokens + 2);
+    assert_eq!(with.assistant_prefix.as_deref(), Some("```json\n"));
+    assert!(with.to_string().contains("assistant prefix: \"```json\\n\""));
+}
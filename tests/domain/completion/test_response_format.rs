@@ -0,0 +1,53 @@
+// Tests for src/domain/completion/response_format.rs
+
+use kodegen_candle_agent::domain::completion::{CandleCompletionParams, ResponseFormat};
+use serde_json::json;
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[allow(dead_code)]
+struct Verdict {
+    approved: bool,
+    reason: String,
+}
+
+#[test]
+fn test_format_for_type_describes_its_fields() {
+    let format = ResponseFormat::for_type::<Verdict>();
+    assert_eq!(format.name, "Verdict");
+    assert!(format.schema["properties"]["approved"].is_object());
+    assert!(format.schema["properties"]["reason"].is_object());
+    assert!(format.instructions().contains("Verdict"));
+
+    let params = CandleCompletionParams::new().with_response_format(Some(format.clone()));
+    assert_eq!(params.response_format, Some(format));
+}
+
+#[test]
+fn test_json_schema_must_be_object_or_bool() {
+    assert!(ResponseFormat::json_schema("Answer", json!({"type": "string"})).is_ok());
+    assert!(ResponseFormat::json_schema("Answer", json!(true)).is_ok());
+    assert!(ResponseFormat::json_schema("Answer", json!("string")).is_err());
+}
+
+#[test]
+fn test_response_format_rejected_with_tools() {
+    let tool = kodegen_candle_agent::ToolInfo {
+        name: "search".into(),
+        title: None,
+        description: None,
+        input_schema: std::sync::Arc::new(serde_json::Map::new()),
+        output_schema: None,
+        annotations: None,
+        icons: None,
+        meta: None,
+    };
+    let mut params = CandleCompletionParams::new()
+        .with_response_format(Some(ResponseFormat::for_type::<Verdict>()));
+    assert!(params.validate_response_format().is_ok());
+
+    params.tools = Some(cyrup_sugars::ZeroOneOrMany::from(vec![tool]));
+    assert!(params.validate_response_format().is_err());
+
+    params.response_format = None;
+    assert!(params.validate_response_format().is_ok());
+}
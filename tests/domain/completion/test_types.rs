@@ -0,0 +1,70 @@
+// Tests for src/domain/completion/types.rs
+
+use kodegen_candle_agent::domain::completion::{CandleCompletionParams, SamplingSettings};
+use kodegen_candle_agent::domain::model::{ValidationError, ValidationSeverity};
+use serde_json::json;
+
+#[test]
+fn test_sampling_settings_read_from_additional_params() {
+    let params = CandleCompletionParams::new()
+        .with_temperature(0.7)
+        .expect("valid temperature")
+        .with_additional_params(Some(json!({"top_k": 40, "top_p": 0.9, "repeat_last_n": 64})));
+
+    let settings = params.sampling_settings();
+    assert_eq!(settings.top_k, Some(40));
+    assert_eq!(settings.top_p, Some(0.9));
+    assert_eq!(settings.repeat_last_n, Some(64));
+    assert_eq!(settings.repeat_penalty, None);
+    assert!(settings.validate(Some(2048)).expect("valid").is_empty());
+}
+
+#[test]
+fn test_greedy_with_top_p_warns() {
+    let settings = SamplingSettings {
+        temperature: 0.0,
+        top_p: Some(0.9),
+        ..Default::default()
+    };
+
+    let warnings = settings.validate(None).expect("warning only");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, ValidationSeverity::Warning);
+    assert_eq!(warnings[0].field.as_deref(), Some("top_p"));
+}
+
+#[test]
+fn test_invalid_sampling_rejected() {
+    let top_k_zero = SamplingSettings {
+        temperature: 0.7,
+        top_k: Some(0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        top_k_zero.validate(None),
+        Err(ValidationError::InvalidRange { ref field, .. }) if field == "top_k"
+    ));
+
+    let long_window = SamplingSettings {
+        temperature: 0.7,
+        repeat_last_n: Some(4096),
+        ..Default::default()
+    };
+    assert!(matches!(
+        long_window.validate(Some(2048)),
+        Err(ValidationError::InconsistentData { .. })
+    ));
+    // Without a known context length the window cannot be checked
+    assert!(long_window.validate(None).is_ok());
+}
+
+#[test]
+fn test_stop_sequences_merge_field_and_additional_params() {
+    let params = CandleCompletionParams::new()
+        .with_stop(vec!["</answer>".to_string(), String::new()])
+        .with_additional_params(Some(json!({"stop": ["\nUser:", "</answer>"]})));
+    assert_eq!(params.stop_sequences(), ["</answer>", "\nUser:"]);
+
+    let single = CandleCompletionParams::new().with_additional_params(Some(json!({"stop": "###"})));
+    assert_eq!(single.stop_sequences(), ["###"]);
+}
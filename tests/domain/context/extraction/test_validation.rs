@@ -0,0 +1,53 @@
+// Tests for src/domain/context/extraction/validation.rs
+
+use kodegen_candle_agent::domain::context::extraction::{
+    DEFAULT_MAX_REPAIRS, ExtractionAttempt, ExtractionError, ExtractionResult, repair_prompt,
+};
+
+fn attempt(number: usize, errors: &[&str]) -> ExtractionAttempt {
+    ExtractionAttempt {
+        number,
+        response: format!("{{\"attempt\": {number}}}"),
+        errors: errors.iter().map(|e| e.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_repair_prompt_lists_previous_answer_and_errors() {
+    let errors = vec![
+        "end_date 2024-01-01 is before start_date 2024-03-01".to_string(),
+        "status must be one of open, closed".to_string(),
+    ];
+    let prompt = repair_prompt("Extract the booking.", "{\"status\": \"pending\"}", &errors);
+
+    assert!(prompt.starts_with("Extract the booking."));
+    assert!(prompt.contains("{\"status\": \"pending\"}"));
+    assert!(prompt.contains("- end_date 2024-01-01 is before start_date 2024-03-01\n"));
+    assert!(prompt.contains("- status must be one of open, closed"));
+}
+
+#[test]
+fn test_result_records_attempts() {
+    let result = ExtractionResult::new(7u32, "7".to_string(), 12)
+        .with_attempts(vec![attempt(1, &["value too small"]), attempt(2, &[])]);
+
+    assert_eq!(result.repairs(), 1);
+    assert!(!result.attempts[0].succeeded());
+    assert!(result.attempts[1].succeeded());
+}
+
+#[test]
+fn test_exhausted_error_reports_last_errors() {
+    let err = ExtractionError::RepairsExhausted {
+        attempts: vec![attempt(1, &["bad date"]), attempt(2, &["bad status", "bad id"])],
+    };
+    assert_eq!(
+        err.to_string(),
+        "Extraction failed after 2 attempts: bad status; bad id"
+    );
+}
+
+#[test]
+fn test_repair_is_opt_in() {
+    assert_eq!(DEFAULT_MAX_REPAIRS, 0);
+}
@@ -0,0 +1,41 @@
+// Tests for src/domain/context/provider/context_impl.rs
+
+use kodegen_candle_agent::domain::context::provider::{
+    CandleContext, CandleContextError, CandleContextLoadReport, CandleFiles,
+};
+use tokio_stream::StreamExt;
+
+#[tokio::test]
+async fn test_unreadable_files_are_reported() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    std::fs::write(dir.path().join("notes.txt"), "deploys run at noon").expect("write");
+    std::fs::write(dir.path().join("blob.bin"), [0xff, 0xfe, 0x00, 0x81]).expect("write");
+
+    let pattern = format!("{}/*", dir.path().display());
+    let mut stream = CandleContext::<CandleFiles>::glob(pattern).load();
+    let mut report = CandleContextLoadReport::new("context_files");
+    let mut documents = Vec::new();
+    while let Some(item) = stream.next().await {
+        documents.extend(report.record(item));
+    }
+
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].data, "deploys run at noon");
+    assert_eq!(report.loaded, 1);
+    assert!(report.has_failures());
+    assert!(matches!(report.failures[0], CandleContextError::IoError(_)));
+
+    let warnings = report.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("context_files: "));
+    assert!(warnings[0].contains("blob.bin"));
+    assert_eq!(report.to_string(), "context_files: 1 of 2 loaded");
+}
+
+#[tokio::test]
+async fn test_bad_glob_pattern_is_reported() {
+    let mut stream = CandleContext::<CandleFiles>::glob("[unclosed").load();
+    let item = stream.next().await.expect("one item");
+    assert!(matches!(item, Err(CandleContextError::PatternError(_))));
+    assert!(stream.next().await.is_none());
+}
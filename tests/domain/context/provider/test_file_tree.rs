@@ -0,0 +1,48 @@
+// Tests for src/domain/context/provider/file_tree.rs
+
+use kodegen_candle_agent::domain::context::provider::{
+    CandleContext, CandleDirectory, FileTreeEntry, render_file_tree,
+};
+use tokio_stream::StreamExt;
+
+fn entry(path: &str, size_bytes: u64) -> FileTreeEntry {
+    FileTreeEntry {
+        path: path.to_string(),
+        size_bytes,
+    }
+}
+
+#[test]
+fn test_render_groups_files_under_directories() {
+    let tree = render_file_tree(
+        "/repo",
+        &[
+            entry("src/main.rs", 2048),
+            entry("README.md", 100),
+            entry("src/util/mod.rs", 10),
+        ],
+    );
+
+    assert!(tree.starts_with("File tree of /repo (3 files, 2.1 KiB)\nLanguages: Rust 2\n"));
+    assert!(tree.contains(
+        "README.md (100 B)\nsrc/\n  main.rs (2.0 KiB, Rust)\n  util/\n    mod.rs (10 B, Rust)\n"
+    ));
+}
+
+#[tokio::test]
+async fn test_tree_only_lists_files_without_reading_them() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    std::fs::create_dir(dir.path().join("src")).expect("mkdir");
+    std::fs::write(dir.path().join("src/lib.rs"), "pub fn a() {}").expect("write");
+    std::fs::write(dir.path().join("notes.txt"), "hello").expect("write");
+
+    let mut stream = CandleContext::<CandleDirectory>::of(dir.path())
+        .file_tree_only()
+        .load();
+    let tree = stream.next().await.expect("tree").expect("loaded");
+    assert!(stream.next().await.is_none());
+
+    assert_eq!(tree.additional_props["kind"], "file_tree");
+    assert!(tree.data.contains("notes.txt (5 B)"));
+    assert!(tree.data.contains("src/\n  lib.rs (13 B, Rust)"));
+}
@@ -0,0 +1,122 @@
+// Tests for src/domain/context/builder.rs
+
+use std::collections::HashMap;
+
+use kodegen_candle_agent::domain::context::CandleDocument;
+use kodegen_candle_agent::domain::context::builder::{
+    ContextBuilder, ContextItem, ContextSourceKind,
+};
+
+fn item(source: &str, content: &str, score: f32) -> ContextItem {
+    ContextItem::new(ContextSourceKind::File, source, content).with_score(score)
+}
+
+#[test]
+fn test_entries_ranked_with_provenance() {
+    let context = ContextBuilder::new(500)
+        .add(item("low.rs", "low relevance", 0.2))
+        .add(item("high.rs", "high relevance", 0.9))
+        .build();
+
+    assert_eq!(
+        context.text,
+        "## Relevant Context\n\n- [file: high.rs]: high relevance\n- [file: low.rs]: low relevance\n"
+    );
+    assert_eq!(context.included.len(), 2);
+    assert_eq!(context.dropped, 0);
+}
+
+#[test]
+fn test_duplicates_keep_best_score() {
+    let context = ContextBuilder::new(500)
+        .add(item("a.md", "Same   content", 0.3))
+        .add(
+            ContextItem::new(ContextSourceKind::Memory, "mem-1", "same content").with_score(0.8),
+        )
+        .build();
+
+    assert_eq!(context.duplicates, 1);
+    assert_eq!(context.included.len(), 1);
+    assert_eq!(context.included[0].kind, ContextSourceKind::Memory);
+}
+
+#[test]
+fn test_budget_drops_and_truncates() {
+    let long = "word ".repeat(200);
+    let context = ContextBuilder::new(60)
+        .add(item("short.rs", "short", 0.9))
+        .add(item("long.rs", &long, 0.5))
+        .add(item("tiny.rs", "tiny", 0.1))
+        .build();
+
+    assert!(context.tokens <= 60);
+    assert_eq!(context.included[0].source, "short.rs");
+    // The long item is cut to fit the remaining budget
+    assert!(context.included[1].content.ends_with('…'));
+    assert!(context.included[1].content.len() < long.len());
+    assert_eq!(context.dropped, 1);
+}
+
+#[test]
+fn test_empty_context_has_no_heading() {
+    let context = ContextBuilder::default().build();
+    assert!(context.is_empty());
+    assert!(context.text.is_empty());
+}
+
+#[test]
+fn test_document_provenance() {
+    let document = |key: &str, value: &str| CandleDocument {
+        data: "body".to_string(),
+        format: None,
+        media_type: None,
+        additional_props: HashMap::from([(key.to_string(), value.into())]),
+    };
+
+    let web = ContextItem::from_document(&document("url", "https://example.com/page"));
+    assert_eq!(web.kind, ContextSourceKind::Web);
+    assert_eq!(web.source, "https://example.com/page");
+
+    let file = ContextItem::from_document(&document("path", "docs/guide.md"));
+    assert_eq!(file.kind, ContextSourceKind::File);
+}
+
+#[test]
+fn test_library_memories_weighted_and_attributed() {
+    use kodegen_candle_agent::domain::memory::primitives::node::MemoryNode;
+    use kodegen_candle_agent::domain::memory::primitives::types::{MemoryContent, MemoryTypeEnum};
+
+    let memory = |text: &str, similarity: f64| {
+        let mut node = MemoryNode::new(MemoryTypeEnum::Semantic, MemoryContent::text(text));
+        node.set_importance(1.0).unwrap();
+        node.set_custom_metadata("source", serde_json::json!("notes.md"));
+        node.set_custom_metadata("similarity", serde_json::json!(similarity));
+        node
+    };
+    let own = [memory("own hit", 0.6)];
+    let shared = [memory("shared hit", 0.9)];
+
+    let context = ContextBuilder::new(500)
+        .add_memories(&own)
+        .add_library_memories("team_notes", 0.5, &shared)
+        .build();
+
+    assert_eq!(context.included.len(), 2);
+    assert_eq!(context.included[0].content, "own hit");
+    assert_eq!(context.included[1].source, "team_notes:notes.md");
+    assert!((context.included[1].score - 0.45).abs() < 1e-6);
+}
+
+#[test]
+fn test_min_score_drops_weak_matches() {
+    let context = ContextBuilder::new(500)
+        .with_min_score(0.3)
+        .add(item("a", "strong match", 0.8))
+        .add(item("b", "weak match", 0.1))
+        .build();
+
+    assert_eq!(context.included.len(), 1);
+    assert_eq!(context.included[0].source, "a");
+    assert_eq!(context.below_cutoff, 1);
+    assert_eq!(context.dropped, 0);
+}
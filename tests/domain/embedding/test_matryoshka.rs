@@ -0,0 +1,28 @@
+// Tests for src/domain/embedding/matryoshka.rs
+
+use kodegen_candle_agent::domain::embedding::matryoshka::truncate;
+
+#[test]
+fn test_truncate_renormalizes_prefix() {
+    let embedding = [0.6, 0.0, 0.8, 0.0];
+    let truncated = truncate(&embedding, 2);
+
+    assert_eq!(truncated, vec![1.0, 0.0]);
+}
+
+#[test]
+fn test_truncate_keeps_unit_length() {
+    let embedding: Vec<f32> = (1..=8).map(|i| i as f32).collect();
+    let truncated = truncate(&embedding, 3);
+
+    assert_eq!(truncated.len(), 3);
+    let norm = truncated.iter().map(|x| x * x).sum::<f32>().sqrt();
+    assert!((norm - 1.0).abs() < 1e-6);
+    assert!(truncated.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_truncate_beyond_length_and_zero_prefix() {
+    assert_eq!(truncate(&[3.0, 4.0], 8), vec![0.6, 0.8]);
+    assert_eq!(truncate(&[0.0, 0.0, 1.0], 2), vec![0.0, 0.0]);
+}
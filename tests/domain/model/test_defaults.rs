@@ -0,0 +1,87 @@
+// Tests for src/domain/model/defaults.rs
+
+use std::num::NonZeroU32;
+
+use kodegen_candle_agent::capability::text_to_text::qwen3_quantized::QWEN3_QUANTIZED_MODEL_INFO;
+use kodegen_candle_agent::domain::model::CandleModelInfo;
+use kodegen_candle_agent::domain::model::defaults::{
+    FALLBACK_MAX_TOKENS, FALLBACK_TEMPERATURE, GenerationOverrides, ModelDefaultsError,
+    apply_system_prompt_prefix, resolve_generation,
+};
+
+fn model_info() -> CandleModelInfo {
+    let mut info = QWEN3_QUANTIZED_MODEL_INFO.clone();
+    info.default_temperature = Some(0.6);
+    info.default_top_k = Some(20);
+    info.default_top_p = Some(0.95);
+    info.max_output_tokens = NonZeroU32::new(4096);
+    info.requires_max_tokens = false;
+    info.system_prompt_prefix = None;
+    info
+}
+
+#[test]
+fn test_unset_params_use_model_defaults() {
+    let resolved = resolve_generation(&model_info(), &GenerationOverrides::default()).unwrap();
+
+    assert_eq!(resolved.temperature, 0.6);
+    assert_eq!(resolved.max_tokens, 4096);
+    assert_eq!(resolved.top_k, Some(20));
+    assert_eq!(resolved.top_p, Some(0.95));
+}
+
+#[test]
+fn test_overrides_win() {
+    let overrides = GenerationOverrides {
+        temperature: Some(0.0),
+        max_tokens: Some(128),
+        top_k: Some(1),
+        top_p: None,
+    };
+    let resolved = resolve_generation(&model_info(), &overrides).unwrap();
+
+    assert_eq!(resolved.temperature, 0.0);
+    assert_eq!(resolved.max_tokens, 128);
+    assert_eq!(resolved.top_k, Some(1));
+    assert_eq!(resolved.top_p, Some(0.95));
+}
+
+#[test]
+fn test_fallbacks_without_model_defaults() {
+    let mut info = model_info();
+    info.default_temperature = None;
+    info.max_output_tokens = None;
+
+    let resolved = resolve_generation(&info, &GenerationOverrides::default()).unwrap();
+    assert_eq!(resolved.temperature, FALLBACK_TEMPERATURE);
+    assert_eq!(resolved.max_tokens, FALLBACK_MAX_TOKENS);
+}
+
+#[test]
+fn test_required_max_tokens() {
+    let mut info = model_info();
+    info.requires_max_tokens = true;
+
+    let err = resolve_generation(&info, &GenerationOverrides::default()).unwrap_err();
+    assert!(matches!(err, ModelDefaultsError::MaxTokensRequired { .. }));
+
+    let overrides = GenerationOverrides {
+        max_tokens: Some(256),
+        ..Default::default()
+    };
+    assert_eq!(resolve_generation(&info, &overrides).unwrap().max_tokens, 256);
+}
+
+#[test]
+fn test_system_prompt_prefix() {
+    let mut info = model_info();
+    assert_eq!(apply_system_prompt_prefix(&info, ""), None);
+    assert_eq!(apply_system_prompt_prefix(&info, "Be brief.").as_deref(), Some("Be brief."));
+
+    info.system_prompt_prefix = Some("You are Qwen.".to_string());
+    let prefixed = apply_system_prompt_prefix(&info, "Be brief.").unwrap();
+    assert_eq!(prefixed, "You are Qwen.\n\nBe brief.");
+    // Applying twice leaves the prompt unchanged
+    assert_eq!(apply_system_prompt_prefix(&info, &prefixed).unwrap(), prefixed);
+    assert_eq!(apply_system_prompt_prefix(&info, "").as_deref(), Some("You are Qwen."));
+}
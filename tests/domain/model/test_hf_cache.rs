@@ -0,0 +1,86 @@
+// Tests for src/domain/model/hf_cache.rs
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use kodegen_candle_agent::domain::model::hf_cache::{lease_repo_at, parse_size, prune_to, scan};
+
+/// A repo directory holding one blob of `size` bytes last used `age` ago
+fn cached_repo(root: &Path, repo: &str, size: usize, age: Duration) {
+    let blobs = root
+        .join(format!("models--{}", repo.replace('/', "--")))
+        .join("blobs");
+    std::fs::create_dir_all(&blobs).unwrap();
+    let blob = blobs.join("blob");
+    std::fs::write(&blob, vec![0u8; size]).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&blob)
+        .unwrap()
+        .set_modified(SystemTime::now() - age)
+        .unwrap();
+}
+
+#[test]
+fn test_parse_size_suffixes() {
+    assert_eq!(parse_size("1024"), Some(1024));
+    assert_eq!(parse_size("2K"), Some(2048));
+    assert_eq!(parse_size("3 MiB"), Some(3 << 20));
+    assert_eq!(parse_size("50GB"), Some(50 << 30));
+    assert_eq!(parse_size("lots"), None);
+}
+
+#[test]
+fn test_scan_reports_repos_most_recent_first() {
+    let root = tempfile::tempdir().unwrap();
+    cached_repo(root.path(), "org/old", 100, Duration::from_secs(3600));
+    cached_repo(root.path(), "org/new", 50, Duration::from_secs(60));
+
+    let report = scan(root.path()).unwrap();
+    assert_eq!(report.total_bytes, 150);
+    let repos: Vec<_> = report.repos.iter().map(|r| r.repo.as_str()).collect();
+    assert_eq!(repos, ["org/new", "org/old"]);
+}
+
+#[test]
+fn test_prune_removes_least_recently_used_and_spares_protected() {
+    let root = tempfile::tempdir().unwrap();
+    cached_repo(
+        root.path(),
+        "org/oldest",
+        100,
+        Duration::from_secs(3 * 3600),
+    );
+    cached_repo(root.path(), "org/older", 100, Duration::from_secs(2 * 3600));
+    cached_repo(root.path(), "org/recent", 100, Duration::from_secs(60));
+    let protected: HashSet<String> = ["org/oldest".to_string()].into();
+
+    let report = prune_to(root.path(), 150, &protected).unwrap();
+
+    assert_eq!(report.skipped_in_use, ["org/oldest"]);
+    let removed: Vec<_> = report.removed.iter().map(|r| r.repo.as_str()).collect();
+    assert_eq!(removed, ["org/older", "org/recent"]);
+    assert_eq!(report.freed_bytes, 200);
+    assert_eq!(report.remaining_bytes, 100);
+    assert!(root.path().join("models--org--oldest").exists());
+}
+
+#[test]
+fn test_prune_spares_leased_repos() {
+    let root = tempfile::tempdir().unwrap();
+    cached_repo(root.path(), "org/leased", 100, Duration::from_secs(3600));
+    cached_repo(root.path(), "org/free", 100, Duration::from_secs(60));
+    let lease = lease_repo_at(root.path(), "org/leased").unwrap();
+
+    let report = prune_to(root.path(), 0, &HashSet::new()).unwrap();
+
+    assert_eq!(report.skipped_in_use, ["org/leased"]);
+    let removed: Vec<_> = report.removed.iter().map(|r| r.repo.as_str()).collect();
+    assert_eq!(removed, ["org/free"]);
+
+    drop(lease);
+    let report = prune_to(root.path(), 0, &HashSet::new()).unwrap();
+    assert_eq!(report.remaining_bytes, 0);
+    assert!(!root.path().join("models--org--leased").exists());
+}
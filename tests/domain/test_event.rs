@@ -0,0 +1,111 @@
+// Tests for src/domain/event.rs
+
+use kodegen_candle_agent::StreamExt;
+use kodegen_candle_agent::domain::context::chunks::{
+    CandleCompletionChunk, CandleJsonChunk, FinishReason, WorkflowDataChunk,
+};
+use kodegen_candle_agent::domain::event::{AgentEvent, agent_events};
+use kodegen_candle_agent::domain::model::CandleUsage;
+use kodegen_candle_agent::prelude::CandleMessageChunk;
+use serde_json::json;
+
+#[test]
+fn test_message_and_completion_chunks_map_to_the_same_events() {
+    let from_chat = AgentEvent::from(CandleMessageChunk::ToolCall {
+        id: "1".to_string(),
+        name: "search".to_string(),
+        partial_input: "{\"q\":".to_string(),
+    });
+    let from_completion = AgentEvent::from(CandleCompletionChunk::ToolCall {
+        id: "1".to_string(),
+        name: "search".to_string(),
+        partial_input: "{\"q\":".to_string(),
+    });
+    assert_eq!(from_chat, from_completion);
+
+    let complete = AgentEvent::from(CandleCompletionChunk::Complete {
+        text: String::new(),
+        finish_reason: Some(FinishReason::Stop),
+        usage: Some(CandleUsage::new(3, 4)),
+        token_count: Some(4),
+        elapsed_secs: None,
+        tokens_per_sec: None,
+        mean_logprob: None,
+        flash_attention: None,
+    });
+    assert!(complete.is_complete());
+    assert!(matches!(
+        complete,
+        AgentEvent::Complete { finish_reason: Some(ref reason), usage: Some(_), .. } if reason == "Stop"
+    ));
+}
+
+#[test]
+fn test_workflow_errors_keep_their_step() {
+    let step = AgentEvent::from(WorkflowDataChunk {
+        data: json!({"answer": 42}),
+        step_name: Some("generate".to_string()),
+        timestamp: None,
+        error_message: None,
+    });
+    assert_eq!(
+        step,
+        AgentEvent::WorkflowStep {
+            step: Some("generate".to_string()),
+            data: json!({"answer": 42}),
+        }
+    );
+
+    let failed = AgentEvent::from(WorkflowDataChunk {
+        step_name: Some("recall".to_string()),
+        error_message: Some("library missing".to_string()),
+        ..WorkflowDataChunk::default()
+    });
+    assert!(failed.is_error());
+    assert_eq!(
+        failed,
+        AgentEvent::Error {
+            message: "library missing".to_string(),
+            source: Some("recall".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_events_serialize_with_a_type_tag() {
+    let text = serde_json::to_value(AgentEvent::Text {
+        text: "Hel".to_string(),
+    })
+    .unwrap();
+    assert_eq!(text, json!({"type": "text", "text": "Hel"}));
+
+    let value = serde_json::to_value(AgentEvent::from(CandleJsonChunk(json!([1, 2])))).unwrap();
+    assert_eq!(value, json!({"type": "json", "value": [1, 2]}));
+    let back: AgentEvent = serde_json::from_value(value).unwrap();
+    assert_eq!(
+        back,
+        AgentEvent::Json {
+            value: json!([1, 2])
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_agent_events_adapts_a_chunk_stream() {
+    let events: Vec<AgentEvent> = agent_events(kodegen_candle_agent::from_iter(vec![
+        CandleMessageChunk::Text("hi".to_string()),
+        CandleMessageChunk::Error("boom".to_string()),
+    ]))
+    .collect()
+    .await;
+
+    assert_eq!(
+        events,
+        [
+            AgentEvent::Text {
+                text: "hi".to_string()
+            },
+            AgentEvent::error("boom"),
+        ]
+    );
+}
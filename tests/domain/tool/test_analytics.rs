@@ -0,0 +1,72 @@
+// Tests for src/domain/tool/analytics.rs
+
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+
+use kodegen_candle_agent::domain::tool::{ToolAnalytics, ToolInfo, ToolSelector, call_succeeded};
+use rmcp::model::{CallToolResult, Content};
+
+#[test]
+fn test_success_rate_and_percentiles() {
+    let analytics = ToolAnalytics::new();
+    for ms in 1..=100 {
+        analytics.record("fs_read", Duration::from_millis(ms), ms % 4 != 0);
+    }
+
+    let stats = analytics.snapshot("fs_read").expect("stats recorded");
+    assert_eq!(stats.calls, 100);
+    assert_eq!(stats.failures, 25);
+    assert!((stats.success_rate - 0.75).abs() < 1e-9);
+    assert!((stats.p50_ms - 50.0).abs() < 1e-9);
+    assert!((stats.p95_ms - 95.0).abs() < 1e-9);
+    assert!((stats.p99_ms - 99.0).abs() < 1e-9);
+
+    assert!(analytics.snapshot("unknown").is_none());
+}
+
+#[test]
+fn test_snapshots_sorted_and_reset() {
+    let analytics = ToolAnalytics::new();
+    analytics.record("b_tool", Duration::from_millis(1), true);
+    analytics.record("a_tool", Duration::from_millis(1), false);
+
+    let names: Vec<_> = analytics.snapshots().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, vec!["a_tool", "b_tool"]);
+
+    analytics.reset();
+    assert!(analytics.snapshots().is_empty());
+}
+
+#[test]
+fn test_error_results_count_as_failures() {
+    let ok: Result<CallToolResult, String> = Ok(CallToolResult::success(vec![Content::text("done")]));
+    let flagged: Result<CallToolResult, String> =
+        Ok(CallToolResult::error(vec![Content::text("no such file")]));
+    let failed: Result<CallToolResult, String> = Err("connection closed".to_string());
+
+    assert!(call_succeeded(&ok));
+    assert!(!call_succeeded(&flagged));
+    assert!(!call_succeeded(&failed));
+}
+
+#[test]
+fn test_selector_list_includes_hints_after_enough_calls() {
+    let tools = vec![
+        ToolInfo::new(Cow::Borrowed("flaky"), "Sometimes works", Arc::new(serde_json::Map::new())),
+        ToolInfo::new(Cow::Borrowed("fresh"), "Never called", Arc::new(serde_json::Map::new())),
+    ];
+
+    let analytics = ToolAnalytics::new();
+    for i in 0..4 {
+        analytics.record("flaky", Duration::from_millis(10), i == 0);
+    }
+    analytics.record("fresh", Duration::from_millis(10), true);
+
+    let list = ToolSelector::create_abbreviated_list(&tools, Some(&analytics));
+    assert!(list.contains("- flaky: Sometimes works [25% success over 4 calls, p50 10ms]"));
+    assert!(list.contains("- fresh: Never called\n") || list.ends_with("- fresh: Never called"));
+
+    let plain = ToolSelector::create_abbreviated_list(&tools, None);
+    assert!(!plain.contains('['));
+}
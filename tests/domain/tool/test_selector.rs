@@ -0,0 +1,57 @@
+// Tests for src/domain/tool/selector.rs
+
+use std::sync::Arc;
+
+use kodegen_candle_agent::capability::registry::{cache_tool_embedding, get_tool_embedding};
+use kodegen_candle_agent::domain::tool::ToolSelector;
+
+#[test]
+fn test_rank_by_similarity_orders_by_cosine() {
+    let query = [1.0f32, 0.0];
+    let orthogonal = [0.0f32, 1.0];
+    let aligned = [2.0f32, 0.0];
+    let diagonal = [1.0f32, 1.0];
+    let candidates: Vec<&[f32]> = vec![&orthogonal, &aligned, &diagonal];
+
+    assert_eq!(
+        ToolSelector::rank_by_similarity(&query, &candidates, 10),
+        vec![1, 2, 0]
+    );
+    assert_eq!(ToolSelector::rank_by_similarity(&query, &candidates, 2), vec![1, 2]);
+}
+
+#[test]
+fn test_rank_by_similarity_mismatched_dimensions_rank_last() {
+    let query = [1.0f32, 0.0];
+    let short = [1.0f32];
+    let aligned = [0.5f32, 0.0];
+    let candidates: Vec<&[f32]> = vec![&short, &aligned];
+
+    assert_eq!(ToolSelector::rank_by_similarity(&query, &candidates, 2), vec![1, 0]);
+}
+
+#[test]
+fn test_rank_embedded_keeps_original_indices_when_tools_are_skipped() {
+    let query = [1.0f32, 0.0];
+    let orthogonal = [0.0f32, 1.0];
+    let aligned = [2.0f32, 0.0];
+    let embeddings: Vec<Option<&[f32]>> = vec![None, Some(&orthogonal), None, Some(&aligned)];
+
+    assert_eq!(ToolSelector::rank_embedded(&query, &embeddings, 10), vec![3, 1]);
+    assert_eq!(ToolSelector::rank_embedded(&query, &embeddings, 1), vec![3]);
+}
+
+#[test]
+fn test_tool_embedding_cache_keyed_by_description() {
+    let embedding: Arc<[f32]> = vec![0.25f32, 0.75].into();
+    cache_tool_embedding("test/model", "test_selector_tool", "reads files", embedding.clone());
+
+    assert_eq!(
+        get_tool_embedding("test/model", "test_selector_tool", "reads files").as_deref(),
+        Some(&*embedding)
+    );
+    // A changed description misses the cache
+    assert!(get_tool_embedding("test/model", "test_selector_tool", "writes files").is_none());
+    // So does a different embedding model
+    assert!(get_tool_embedding("other/model", "test_selector_tool", "reads files").is_none());
+}
@@ -100,6 +100,8 @@ async fn test_stream_conversion() {
                 tokens_generated: 2,
                 elapsed_secs: 0.01,
                 tokens_per_sec: 200.0,
+                mean_logprob: None,
+                flash_attention: false,
             }));
         })
     });
@@ -146,10 +148,7 @@ async fn test_stream_conversion() {
 #[tokio::test]
 async fn test_text_generator_with_mock_model() {
     use candle_core::{Device, Tensor};
-    use kodegen_candle_agent::prelude::{
-        CandleModel, GenerationStatistics, SamplingConfig, SimdMetrics, SpecialTokens,
-        TextGenerator, TokenHistory,
-    };
+    use kodegen_candle_agent::prelude::{CandleModel, SamplingConfig, SpecialTokens, TextGenerator};
     use tokenizers::Tokenizer;
 
     // Mock model implementation
@@ -242,21 +241,7 @@ async fn test_text_generator_with_mock_model() {
     };
 
     let config = SamplingConfig::default();
-    let token_history = TokenHistory::new(100);
-    let stats = GenerationStatistics::new();
-    let simd_metrics = SimdMetrics::default();
-
-    let generator = TextGenerator {
-        model: Box::new(model),
-        tokenizer,
-        device,
-        config,
-        token_history,
-        stats,
-        simd_metrics,
-        constraint: None,
-        constraint_state: None,
-    };
+    let generator = TextGenerator::new(Box::new(model), tokenizer, device, config);
 
     let special_tokens = SpecialTokens {
         bos_token_id: Some(1),
@@ -381,3 +366,87 @@ async fn test_memory_integration_compatibility() {
         println!("  (Full memory integration can be tested with SURREAL_TEST_URL set)");
     }
 }
+
+/// Test 8: Completion Cancellation
+///
+/// Verifies that cancelling a coordinated completion ends the stream and
+/// closes the provider's channel, so its token loop can stop.
+#[tokio::test]
+async fn test_completion_cancellation() {
+    use std::time::Duration;
+    use tokio_util::sync::CancellationToken;
+
+    let config = EngineConfig::new("test-model", "test-provider");
+    let engine = match Engine::new(config) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Skipping: Failed to create engine: {:?}", e);
+            return;
+        }
+    };
+
+    let cancellation = CancellationToken::new();
+    let (stopped_tx, stopped_rx) = tokio::sync::oneshot::channel();
+    let mut stream = Box::pin(engine.coordinate_completion(cancellation.clone(), move || {
+        kodegen_candle_agent::async_stream::spawn_stream(|sender| async move {
+            // Emit tokens until nobody is listening
+            while !sender.is_closed() {
+                let _ = sender.send(CandleCompletionChunk::Text("token".to_string()));
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            let _ = stopped_tx.send(());
+        })
+    }));
+
+    assert!(matches!(stream.next().await, Some(CandleCompletionChunk::Text(_))));
+    cancellation.cancel();
+
+    let drained = tokio::time::timeout(Duration::from_secs(1), async {
+        while stream.next().await.is_some() {}
+    })
+    .await;
+    assert!(drained.is_ok(), "Stream should end once cancelled");
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), stopped_rx).await.is_ok(),
+        "Provider should see its channel closed"
+    );
+}
+
+/// Completion streams always end with a `Complete` chunk
+///
+/// A provider stream that stops without one gets a final chunk from the
+/// engine; one that errors does not.
+#[tokio::test]
+async fn test_completion_ends_with_complete_chunk() {
+    use tokio_util::sync::CancellationToken;
+
+    let config = EngineConfig::new("test-model", "test-provider");
+    let engine = match Engine::new(config) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Skipping: Failed to create engine: {:?}", e);
+            return;
+        }
+    };
+
+    let chunks: Vec<CandleCompletionChunk> = engine
+        .coordinate_completion(CancellationToken::new(), move || {
+            kodegen_candle_agent::async_stream::spawn_stream(|sender| async move {
+                let _ = sender.send(CandleCompletionChunk::Text("hi".to_string()));
+            })
+        })
+        .collect()
+        .await;
+    assert_eq!(chunks.len(), 2);
+    assert!(matches!(chunks[1], CandleCompletionChunk::Complete { .. }));
+
+    let chunks: Vec<CandleCompletionChunk> = engine
+        .coordinate_completion(CancellationToken::new(), move || {
+            kodegen_candle_agent::async_stream::spawn_stream(|sender| async move {
+                let _ = sender.send(CandleCompletionChunk::Error("boom".to_string()));
+            })
+        })
+        .collect()
+        .await;
+    assert_eq!(chunks.len(), 1);
+}
@@ -1,16 +1,48 @@
 // Integration tests for memory operations
 
 mod memory {
+    mod api {
+        mod test_memorize_stream;
+        mod test_openai;
+        mod test_openapi;
+        mod test_replay;
+        mod test_server;
+        mod test_ws;
+    }
+    mod test_builder;
+    mod chunking {
+        mod test_code;
+        mod test_text;
+    }
     mod core {
+        mod test_chunk;
+        mod test_consolidation;
+        mod test_content_edit;
+        mod test_embedding_tasks;
+        mod test_fsck;
+        mod test_pool;
+        mod test_recall_stats;
         mod test_schema;
+        mod test_snapshot;
+        mod test_tool_audit;
+        mod test_transaction;
     }
     mod migration {
         mod test_converter;
+        mod test_library_schema;
     }
     mod monitoring {
         mod test_metrics;
         mod test_metrics_test;
         mod test_metrics_tests;
+        mod test_recall_log;
+    }
+    mod ops {
+        mod test_grouping;
+        mod test_ranking;
+        mod test_rerank;
+        mod test_similarity;
+        mod test_threshold;
     }
     mod schema {
         mod test_relationship_schema;
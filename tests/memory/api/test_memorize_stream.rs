@@ -0,0 +1,112 @@
+// Tests for src/memory/api/memorize_stream.rs
+
+#![cfg(all(feature = "api", feature = "tools"))]
+
+use std::sync::Arc;
+
+use axum::response::IntoResponse;
+use futures::StreamExt;
+use kodegen_candle_agent::capability::registry::{FromRegistry, TextEmbeddingModel};
+use kodegen_candle_agent::memory::api::memorize_stream::follow_session;
+use kodegen_candle_agent::memory::core::manager::pool::{CoordinatorPool, DEFAULT_EMBEDDING_MODEL};
+use kodegen_candle_agent::tools::MemorizeSessionManager;
+use kodegen_candle_agent::tools::memorize_limits::MemorizeLimits;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Reads SSE frames as (event name, stage)
+struct Events<S> {
+    body: S,
+    buffer: String,
+}
+
+impl<S> Events<S>
+where
+    S: futures::Stream<Item = Result<axum::body::Bytes, axum::Error>> + Unpin,
+{
+    async fn next(&mut self) -> (String, String) {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let frame: String = self.buffer.drain(..end + 2).collect();
+                let field = |name: &str| {
+                    frame
+                        .lines()
+                        .find_map(|line| line.strip_prefix(name))
+                        .map(str::to_string)
+                };
+                // Skip keep-alive comments
+                let (Some(event), Some(data)) = (field("event: "), field("data: ")) else {
+                    continue;
+                };
+                let status: serde_json::Value = serde_json::from_str(&data).unwrap();
+                return (event, status["progress"]["stage"].as_str().unwrap().to_string());
+            }
+            let chunk = self.body.next().await.expect("stream ended early").unwrap();
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_stage_events_arrive_before_completion() {
+    // Document server that answers only once released
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/notes.txt", listener.local_addr().unwrap());
+    let (release, released) = tokio::sync::oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 1024];
+        let _ = socket.read(&mut request).await;
+        let _ = released.await;
+        // A missing document fails the session before anything is embedded
+        let _ = socket
+            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await;
+    });
+
+    let model = TextEmbeddingModel::from_registry(DEFAULT_EMBEDDING_MODEL)
+        .expect("default embedding model should be registered");
+    let manager = Arc::new(
+        MemorizeSessionManager::new(Arc::new(CoordinatorPool::new(model)))
+            .with_text_chunking(None)
+            .with_limits(MemorizeLimits::new()),
+    );
+    let session_id = manager
+        .start_memorize_session("notes".to_string(), url)
+        .await
+        .unwrap();
+
+    let sse = follow_session(&manager, &session_id).await.unwrap();
+    let mut events = Events {
+        body: sse.into_response().into_body().into_data_stream(),
+        buffer: String::new(),
+    };
+
+    // Stages arrive while the session is still blocked on the download
+    let mut stages = Vec::new();
+    loop {
+        let (event, stage) = events.next().await;
+        assert_eq!(event, "stage");
+        stages.push(stage);
+        if stages.last().map(String::as_str) == Some("Loading content") {
+            break;
+        }
+    }
+    assert!(stages.iter().all(|s| s == "Queued" || s == "Loading content"));
+    assert!(stages.windows(2).all(|w| w[0] == "Queued" && w[1] == "Loading content"));
+
+    release.send(()).unwrap();
+    let (event, _) = events.next().await;
+    assert_eq!(event, "failed");
+}
+
+#[tokio::test]
+async fn test_unknown_session_is_not_found() {
+    let model = TextEmbeddingModel::from_registry(DEFAULT_EMBEDDING_MODEL)
+        .expect("default embedding model should be registered");
+    let manager = Arc::new(MemorizeSessionManager::new(Arc::new(CoordinatorPool::new(model))));
+
+    let Err((status, _)) = follow_session(&manager, "missing").await else {
+        panic!("missing session was followed");
+    };
+    assert_eq!(status, axum::http::StatusCode::NOT_FOUND);
+}
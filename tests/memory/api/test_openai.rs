@@ -0,0 +1,130 @@
+// Tests for src/memory/api/openai.rs
+
+#![cfg(feature = "api")]
+
+use base64::Engine;
+use kodegen_candle_agent::capability::text_to_text::ChatRole;
+use kodegen_candle_agent::memory::api::openai::{
+    ChatCompletionRequest, ChatMessage, EmbeddingRequest, EmbeddingVector, EncodingFormat,
+    ToolChoice, ToolChoiceMode, conversation_turns, resolve_tool_choice,
+};
+use serde_json::json;
+
+fn request_with_tools(tool_choice: serde_json::Value) -> ChatCompletionRequest {
+    serde_json::from_value(json!({
+        "model": "Qwen/Qwen3-4B",
+        "messages": [{"role": "user", "content": "Weather in Paris?"}],
+        "tools": [
+            {"type": "function", "function": {"name": "get_weather", "parameters": {"type": "object"}}},
+            {"type": "function", "function": {"name": "get_time"}}
+        ],
+        "tool_choice": tool_choice
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_tool_choice_forms_parse() {
+    let auto = request_with_tools(json!("auto"));
+    assert_eq!(auto.tool_choice, Some(ToolChoice::Mode(ToolChoiceMode::Auto)));
+
+    let named = request_with_tools(json!({"type": "function", "function": {"name": "get_time"}}));
+    assert!(matches!(
+        named.tool_choice,
+        Some(ToolChoice::Function { ref function, .. }) if function.name == "get_time"
+    ));
+    assert!(!named.stream);
+}
+
+#[test]
+fn test_tool_choice_selects_offered_tools() {
+    let request = request_with_tools(json!("none"));
+    let (tools, instruction) =
+        resolve_tool_choice(&request.tools, request.tool_choice.as_ref()).unwrap();
+    assert!(tools.is_empty());
+    assert!(instruction.is_none());
+
+    let request = request_with_tools(json!("required"));
+    let (tools, instruction) =
+        resolve_tool_choice(&request.tools, request.tool_choice.as_ref()).unwrap();
+    assert_eq!(tools.len(), 2);
+    assert!(instruction.is_some());
+
+    let request = request_with_tools(json!({"type": "function", "function": {"name": "get_weather"}}));
+    let (tools, instruction) =
+        resolve_tool_choice(&request.tools, request.tool_choice.as_ref()).unwrap();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].name, "get_weather");
+    assert!(instruction.unwrap().contains("get_weather"));
+
+    let request = request_with_tools(json!({"type": "function", "function": {"name": "missing"}}));
+    let (status, _) =
+        resolve_tool_choice(&request.tools, request.tool_choice.as_ref()).unwrap_err();
+    assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_conversation_turns_keep_roles() {
+    let messages: Vec<ChatMessage> = serde_json::from_value(json!([
+        {"role": "system", "content": "Be brief."},
+        {"role": "user", "content": [{"type": "text", "text": "Weather in Paris?"}]},
+        {"role": "assistant", "content": null, "tool_calls": [{
+            "id": "call_1",
+            "type": "function",
+            "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}
+        }]},
+        {"role": "tool", "tool_call_id": "call_1", "content": "18C"}
+    ]))
+    .unwrap();
+
+    let turns = conversation_turns(&messages, Some("You must call a tool."));
+    let roles: Vec<ChatRole> = turns.iter().map(|turn| turn.role).collect();
+    assert_eq!(
+        roles,
+        vec![
+            ChatRole::System,
+            ChatRole::User,
+            ChatRole::Assistant,
+            ChatRole::Tool,
+            ChatRole::System
+        ]
+    );
+    assert_eq!(turns[1].content, "Weather in Paris?");
+    assert!(turns[2].content.contains("\"name\": \"get_weather\""));
+    assert_eq!(turns[3].content, "18C");
+}
+
+#[test]
+fn test_embedding_request_accepts_string_or_array() {
+    let one: EmbeddingRequest =
+        serde_json::from_value(json!({"model": "BAAI/bge-small-en-v1.5", "input": "hello"}))
+            .unwrap();
+    assert_eq!(one.input.into_texts(), vec!["hello".to_string()]);
+    assert_eq!(one.encoding_format, EncodingFormat::Float);
+
+    let many: EmbeddingRequest =
+        serde_json::from_value(json!({"input": ["a", "b"], "encoding_format": "base64"})).unwrap();
+    assert_eq!(many.input.into_texts().len(), 2);
+    assert_eq!(many.encoding_format, EncodingFormat::Base64);
+}
+
+#[test]
+fn test_base64_embedding_is_little_endian_f32() {
+    let EmbeddingVector::Base64(encoded) =
+        EmbeddingVector::encode(vec![1.0, -2.5], EncodingFormat::Base64)
+    else {
+        panic!("expected base64");
+    };
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .unwrap();
+    let floats: Vec<f32> = bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    assert_eq!(floats, vec![1.0, -2.5]);
+
+    let float =
+        serde_json::to_value(EmbeddingVector::encode(vec![0.5], EncodingFormat::Float)).unwrap();
+    assert_eq!(float, json!([0.5]));
+}
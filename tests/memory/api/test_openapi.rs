@@ -0,0 +1,38 @@
+// Tests for src/memory/api/openapi.rs
+
+#![cfg(feature = "api")]
+
+use kodegen_candle_agent::memory::api::openapi::ApiDoc;
+use utoipa::OpenApi;
+
+#[test]
+fn test_spec_covers_versioned_endpoints() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let paths = spec["paths"].as_object().unwrap();
+
+    for path in [
+        "/v1/memories",
+        "/v1/memories/{id}",
+        "/v1/memories/search",
+        "/v1/health",
+        "/v1/metrics",
+    ] {
+        assert!(paths.contains_key(path), "missing {path}");
+    }
+    assert!(paths.keys().all(|path| path.starts_with("/v1/")));
+
+    let item = &paths["/v1/memories/{id}"];
+    assert!(item.get("get").is_some());
+    assert!(item.get("put").is_some());
+    assert!(item.get("delete").is_some());
+}
+
+#[test]
+fn test_spec_includes_request_and_response_schemas() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+
+    for schema in ["CreateMemoryRequest", "MemoryResponse", "SearchRequest", "MemoryTypeEnum"] {
+        assert!(schemas.contains_key(schema), "missing {schema}");
+    }
+}
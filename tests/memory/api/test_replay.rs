@@ -0,0 +1,41 @@
+// Tests for src/memory/api/replay.rs
+
+#![cfg(feature = "api")]
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use kodegen_candle_agent::memory::api::replay::{ReplayBuffer, ReplayGap};
+
+#[test]
+fn test_offsets_increase_and_old_events_are_dropped() {
+    let buffer = ReplayBuffer::with_capacity(2);
+    assert_eq!(buffer.push("a"), 1);
+    assert_eq!(buffer.push("b"), 2);
+    assert_eq!(buffer.push("c"), 3);
+
+    assert_eq!(buffer.events_after(1).unwrap(), vec![(2, "b"), (3, "c")]);
+    assert_eq!(buffer.events_after(3).unwrap(), vec![]);
+    assert_eq!(
+        buffer.events_after(0),
+        Err(ReplayGap {
+            requested: 0,
+            oldest: 2
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_follow_replays_missed_events_then_live_ones() {
+    let buffer = Arc::new(ReplayBuffer::new());
+    buffer.push("a".to_string());
+    buffer.push("b".to_string());
+
+    let events = Arc::clone(&buffer).follow(1).unwrap();
+    buffer.push("c".to_string());
+    buffer.finish();
+    assert_eq!(buffer.push("late".to_string()), 3);
+
+    let received: Vec<_> = events.collect().await;
+    assert_eq!(received, vec![(2, "b".to_string()), (3, "c".to_string())]);
+}
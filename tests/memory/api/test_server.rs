@@ -0,0 +1,52 @@
+// Tests for src/memory/api/mod.rs
+
+#![cfg(feature = "api")]
+
+use std::sync::Arc;
+
+use kodegen_candle_agent::capability::registry::{FromRegistry, TextEmbeddingModel};
+use kodegen_candle_agent::memory::api::{API_ADDR_ENV, PoolApiServer};
+use kodegen_candle_agent::memory::core::manager::pool::{CoordinatorPool, DEFAULT_EMBEDDING_MODEL};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn test_pool_api_serves_next_to_mcp() {
+    let model = TextEmbeddingModel::from_registry(DEFAULT_EMBEDDING_MODEL)
+        .expect("default embedding model should be registered");
+    let pool = Arc::new(CoordinatorPool::new(model));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server = PoolApiServer::with_listener(listener, pool).unwrap();
+
+    let addr = server.local_addr();
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /api/openapi.json HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.contains("/v1/memories"));
+
+    server.shutdown().await;
+    assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+}
+
+#[tokio::test]
+async fn test_pool_api_reports_taken_port() {
+    let model = TextEmbeddingModel::from_registry(DEFAULT_EMBEDDING_MODEL)
+        .expect("default embedding model should be registered");
+    let pool = Arc::new(CoordinatorPool::new(model));
+    let taken = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+    let error = PoolApiServer::start(taken.local_addr().unwrap(), pool)
+        .await
+        .err()
+        .expect("binding a taken port should fail");
+
+    let message = error.to_string();
+    assert!(message.contains("already in use"), "{message}");
+    assert!(message.contains(API_ADDR_ENV), "{message}");
+}
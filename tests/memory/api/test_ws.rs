@@ -0,0 +1,92 @@
+// Tests for src/memory/api/ws.rs
+
+#![cfg(feature = "api")]
+
+use kodegen_candle_agent::domain::event::AgentEvent;
+use kodegen_candle_agent::memory::api::ws::{
+    ChatSessions, ClientFrame, MAX_HISTORY_TURNS, ServerFrame,
+};
+use serde_json::json;
+
+#[test]
+fn test_client_frames_parse() {
+    let hello: ClientFrame =
+        serde_json::from_value(json!({"type": "hello", "session_id": "abc"})).unwrap();
+    assert_eq!(
+        hello,
+        ClientFrame::Hello {
+            session_id: Some("abc".to_string()),
+            model: None
+        }
+    );
+
+    let cancel: ClientFrame = serde_json::from_value(json!({"type": "cancel"})).unwrap();
+    assert_eq!(cancel, ClientFrame::Cancel { turn: None });
+
+    assert!(serde_json::from_value::<ClientFrame>(json!({"type": "message"})).is_err());
+}
+
+#[test]
+fn test_chunk_frame_nests_agent_event() {
+    let frame = ServerFrame::Chunk {
+        turn: 2,
+        chunk: AgentEvent::Text {
+            text: "hi".to_string(),
+        },
+    };
+    let value = serde_json::to_value(&frame).unwrap();
+    assert_eq!(value["type"], "chunk");
+    assert_eq!(value["turn"], 2);
+    assert_eq!(value["chunk"]["type"], "text");
+    assert_eq!(value["chunk"]["text"], "hi");
+}
+
+#[test]
+fn test_sessions_resume_by_id() {
+    let sessions = ChatSessions::new();
+    let (id, resumed, turns) = sessions.open(None, None);
+    assert!(!resumed);
+    assert_eq!(turns, 0);
+
+    sessions.record_turn(&id, "hello", "hi there", false);
+    let (again, resumed, _) = sessions.open(Some(&id), None);
+    assert_eq!(again, id);
+    assert!(resumed);
+    assert_eq!(sessions.history(&id), vec!["hello", "hi there"]);
+
+    let (other, resumed, _) = sessions.open(Some("unknown"), None);
+    assert_ne!(other, id);
+    assert!(!resumed);
+    assert_eq!(sessions.len(), 2);
+
+    // Nothing is streaming, so there is nothing to interrupt
+    assert!(!sessions.interrupt(&id, None));
+}
+
+#[test]
+fn test_interrupted_turn_is_recorded_as_interrupted() {
+    let sessions = ChatSessions::new();
+    let (id, _, _) = sessions.open(None, None);
+
+    sessions.record_turn(&id, "first", "done", false);
+    sessions.record_turn(&id, "second", "partial", true);
+
+    assert_eq!(sessions.history(&id), vec!["first", "done", "second", "partial"]);
+    assert_eq!(sessions.interrupted_turns(&id).len(), 1);
+}
+
+#[test]
+fn test_history_keeps_latest_turns() {
+    let sessions = ChatSessions::new();
+    let (id, _, _) = sessions.open(None, None);
+
+    for i in 0..MAX_HISTORY_TURNS + 3 {
+        sessions.record_turn(&id, &format!("q{i}"), &format!("a{i}"), false);
+    }
+
+    let history = sessions.history(&id);
+    assert_eq!(history.len(), MAX_HISTORY_TURNS * 2);
+    assert_eq!(history[0], "q3");
+    let last = format!("a{}", MAX_HISTORY_TURNS + 2);
+    assert_eq!(history.last(), Some(&last));
+}
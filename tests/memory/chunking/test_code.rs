@@ -0,0 +1,97 @@
+// Tests for src/memory/core/chunking/code.rs
+
+use kodegen_candle_agent::capability::text_embedding::stella::instruction::code_instruction;
+use kodegen_candle_agent::memory::core::chunking::{CodeLanguage, CodeSplitter};
+
+const RUST_SOURCE: &str = r#"use std::fmt;
+
+/// Adds numbers
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+}
+
+impl Point {
+    fn new() -> Self { Self { x: 0 } }
+}
+"#;
+
+#[test]
+fn test_language_from_path() {
+    assert_eq!(CodeLanguage::from_path("src/lib.rs"), Some(CodeLanguage::Rust));
+    assert_eq!(CodeLanguage::from_path("web/App.TSX"), Some(CodeLanguage::TypeScript));
+    assert_eq!(CodeLanguage::from_path("include/vec.hpp"), Some(CodeLanguage::Cpp));
+    assert_eq!(CodeLanguage::from_path("README.md"), None);
+    assert_eq!(CodeLanguage::from_path("Makefile"), None);
+}
+
+#[test]
+fn test_every_language_has_instruction() {
+    for language in CodeLanguage::ALL {
+        assert!(
+            code_instruction(language.embedding_task()).is_some(),
+            "no instruction for {}",
+            language.name()
+        );
+    }
+}
+
+#[test]
+fn test_split_at_top_level_definitions() {
+    let chunks = CodeSplitter::new()
+        .with_min_bytes(0)
+        .split("src/point.rs", RUST_SOURCE, CodeLanguage::Rust);
+
+    assert_eq!(chunks.len(), 4);
+    assert!(chunks[0].content.starts_with("use std::fmt;"));
+    // Doc comments and attributes stay with their definition
+    assert!(chunks[1].content.starts_with("/// Adds numbers\npub fn add"));
+    assert_eq!(chunks[1].span.line_start, 3);
+    assert!(chunks[2].content.starts_with("#[derive(Debug)]\npub struct Point"));
+    assert!(chunks[3].content.starts_with("impl Point"));
+    assert_eq!(chunks[3].span.line_end, 15);
+
+    for chunk in &chunks {
+        assert_eq!(chunk.source, "src/point.rs");
+    }
+}
+
+#[test]
+fn test_small_definitions_are_merged() {
+    let chunks = CodeSplitter::new().split("src/point.rs", RUST_SOURCE, CodeLanguage::Rust);
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].content, RUST_SOURCE);
+}
+
+#[test]
+fn test_oversized_definition_split_at_nested_definitions() {
+    let source = "class Store:\n    def get(self, key):\n        return self.data[key]\n\n    def put(self, key, value):\n        self.data[key] = value\n";
+    let chunks = CodeSplitter::new()
+        .with_max_bytes(80)
+        .with_min_bytes(0)
+        .split("store.py", source, CodeLanguage::Python);
+
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].content, "class Store:\n");
+    assert!(chunks[1].content.trim_start().starts_with("def get"));
+    assert!(chunks[2].content.trim_start().starts_with("def put"));
+    assert!(chunks.iter().all(|c| c.content.len() <= 80));
+}
+
+#[test]
+fn test_code_without_definitions_split_into_windows() {
+    let source = "x = 1\n".repeat(10);
+    let chunks = CodeSplitter::new()
+        .with_max_bytes(20)
+        .with_min_bytes(0)
+        .split("script.py", &source, CodeLanguage::Python);
+
+    assert!(chunks.len() > 1);
+    assert!(chunks.iter().all(|c| c.content.len() <= 20));
+    let joined: String = chunks.iter().map(|c| c.content.as_str()).collect();
+    assert_eq!(joined, source);
+}
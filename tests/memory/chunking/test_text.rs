@@ -0,0 +1,125 @@
+// Tests for src/memory/core/chunking/text.rs
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use kodegen_candle_agent::memory::core::chunking::{ChunkStrategy, TextSplitter};
+use tokenizers::Tokenizer;
+use tokenizers::models::wordlevel::WordLevel;
+use tokenizers::pre_tokenizers::whitespace::Whitespace;
+
+fn sentences(count: usize) -> String {
+    (0..count)
+        .map(|i| format!("Sentence number {i} has six words."))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn test_short_text_is_one_chunk() {
+    let chunks = TextSplitter::new().split("notes.md", "  A short note.\n");
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].content, "A short note.");
+    assert_eq!(chunks[0].source, "notes.md");
+    assert!(TextSplitter::new().split("empty.md", " \n ").is_empty());
+}
+
+#[test]
+fn test_sentence_chunks_respect_budget_and_overlap() {
+    let text = sentences(10);
+    let chunks = TextSplitter::new()
+        .with_max_tokens(20)
+        .with_overlap_tokens(6)
+        .split("doc.md", &text);
+
+    assert!(chunks.len() > 1);
+    for chunk in &chunks {
+        assert!(chunk.content.split_whitespace().count() <= 20);
+        assert!(chunk.content.starts_with("Sentence"));
+        assert!(chunk.content.ends_with("words."));
+        assert_eq!(chunk.source, "doc.md");
+    }
+    // Each chunk repeats the last sentence of the previous one
+    for pair in chunks.windows(2) {
+        let last_sentence = pair[0].content.rsplit("Sentence").next().unwrap();
+        assert!(
+            pair[1]
+                .content
+                .starts_with(&format!("Sentence{last_sentence}"))
+        );
+    }
+    assert!(
+        chunks
+            .last()
+            .unwrap()
+            .content
+            .ends_with("Sentence number 9 has six words.")
+    );
+}
+
+#[test]
+fn test_token_windows_overlap() {
+    let text = (0..25)
+        .map(|i| format!("w{i}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let chunks = TextSplitter::new()
+        .with_strategy(ChunkStrategy::Tokens)
+        .with_max_tokens(10)
+        .with_overlap_tokens(2)
+        .split("words.txt", &text);
+
+    let contents: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
+    assert_eq!(
+        contents,
+        [
+            "w0 w1 w2 w3 w4 w5 w6 w7 w8 w9",
+            "w8 w9 w10 w11 w12 w13 w14 w15 w16 w17",
+            "w16 w17 w18 w19 w20 w21 w22 w23 w24",
+        ]
+    );
+    // Overlapping spans still get distinct IDs
+    assert_ne!(chunks[0].id(), chunks[1].id());
+}
+
+#[test]
+fn test_overlap_is_kept_below_budget() {
+    let splitter = TextSplitter::new()
+        .with_max_tokens(4)
+        .with_overlap_tokens(10);
+    assert_eq!(splitter.overlap_tokens(), 3);
+    assert_eq!("Tokens".parse(), Ok(ChunkStrategy::Tokens));
+    assert!("paragraphs".parse::<ChunkStrategy>().is_err());
+}
+
+/// Tokenizer splitting punctuation from words, so it counts more tokens than words
+fn punctuation_tokenizer() -> Arc<Tokenizer> {
+    let vocab: HashMap<String, u32> = HashMap::from([("<unk>".to_string(), 0)]);
+    let model = WordLevel::builder()
+        .vocab(vocab.into_iter().collect())
+        .unk_token("<unk>".to_string())
+        .build()
+        .expect("word-level model");
+    let mut tokenizer = Tokenizer::new(model);
+    tokenizer.with_pre_tokenizer(Some(Whitespace::default()));
+    Arc::new(tokenizer)
+}
+
+#[test]
+fn test_tokenizer_counts_chunk_sizes() {
+    let text = "One, two, three, four.";
+    let splitter = TextSplitter::new()
+        .with_strategy(ChunkStrategy::Tokens)
+        .with_max_tokens(4)
+        .with_overlap_tokens(0);
+    assert_eq!(splitter.split("list.txt", text).len(), 1);
+
+    let splitter = splitter.with_tokenizer(punctuation_tokenizer());
+    assert!(splitter.has_tokenizer());
+    let contents: Vec<String> = splitter
+        .split("list.txt", text)
+        .into_iter()
+        .map(|c| c.content)
+        .collect();
+    assert_eq!(contents, ["One, two,", "three, four."]);
+}
@@ -0,0 +1,63 @@
+// Tests for src/memory/core/primitives/chunk.rs
+
+use kodegen_candle_agent::memory::core::primitives::chunk::{
+    MemoryChunk, SourceSpan, chunk_id, chunk_location,
+};
+
+const TEXT: &str = "fn a() {}\n\nfn b() {\n    1\n}\n";
+
+#[test]
+fn test_span_line_numbers() {
+    let start = TEXT.find("fn b").unwrap();
+    let span = SourceSpan::of(TEXT, start..TEXT.len());
+
+    assert_eq!(span.line_start, 3);
+    // Trailing newline does not open another line
+    assert_eq!(span.line_end, 5);
+    assert_eq!(span.len(), TEXT.len() - start);
+}
+
+#[test]
+fn test_span_first_line() {
+    let span = SourceSpan::of(TEXT, 0..9);
+    assert_eq!((span.line_start, span.line_end), (1, 1));
+}
+
+#[test]
+fn test_span_clamps_to_char_boundaries() {
+    let text = "héllo";
+    // Byte 2 is inside 'é'
+    let span = SourceSpan::of(text, 2..100);
+    assert_eq!(span.byte_start, 1);
+    assert_eq!(span.byte_end, text.len());
+}
+
+#[test]
+fn test_chunk_id_is_deterministic() {
+    let first = MemoryChunk::new("src/lib.rs", TEXT, 0..9);
+    let second = MemoryChunk::new("src/lib.rs", TEXT, 0..9);
+    assert_eq!(first.id(), second.id());
+    assert_eq!(first.id(), chunk_id("src/lib.rs", &first.span));
+}
+
+#[test]
+fn test_chunk_id_depends_on_source_and_span() {
+    let chunk = MemoryChunk::new("src/lib.rs", TEXT, 0..9);
+    assert_ne!(chunk.id(), MemoryChunk::new("src/main.rs", TEXT, 0..9).id());
+    assert_ne!(chunk.id(), MemoryChunk::new("src/lib.rs", TEXT, 0..10).id());
+}
+
+#[test]
+fn test_location_round_trip() {
+    let chunk = MemoryChunk::new("docs/guide.md", TEXT, 11..TEXT.len());
+    let custom = serde_json::Value::Object(chunk.custom_metadata());
+
+    let (source, span) = chunk_location(&custom).expect("location");
+    assert_eq!(source, "docs/guide.md");
+    assert_eq!(span, chunk.span);
+}
+
+#[test]
+fn test_location_missing_fields() {
+    assert!(chunk_location(&serde_json::json!({ "source_path": "a.rs" })).is_none());
+}
@@ -0,0 +1,41 @@
+// Tests for src/memory/core/consolidation/mod.rs
+
+use std::time::Duration;
+
+use kodegen_candle_agent::memory::core::consolidation::{
+    ConsolidationConfig, MIN_IMPORTANCE, decayed_importance,
+};
+
+#[test]
+fn test_importance_halves_every_half_life() {
+    assert_eq!(decayed_importance(0.8, 0.0, 30.0), 0.8);
+    assert!((decayed_importance(0.8, 30.0, 30.0) - 0.4).abs() < 1e-6);
+    assert!((decayed_importance(0.8, 60.0, 30.0) - 0.2).abs() < 1e-6);
+}
+
+#[test]
+fn test_importance_stops_at_the_floor() {
+    assert_eq!(decayed_importance(0.5, 10_000.0, 1.0), MIN_IMPORTANCE);
+    assert_eq!(decayed_importance(0.005, 30.0, 1.0), 0.005);
+}
+
+#[test]
+fn test_config_validation_and_schedule() {
+    let config = ConsolidationConfig::default();
+    assert!(config.validate().is_ok());
+    assert_eq!(config.interval(), Some(Duration::from_secs(6 * 3600)));
+    assert_eq!(ConsolidationConfig::disabled().interval(), None);
+    assert_eq!(config.merge_threshold, None);
+
+    let bad_threshold = ConsolidationConfig {
+        merge_threshold: Some(1.5),
+        ..ConsolidationConfig::default()
+    };
+    assert!(bad_threshold.validate().is_err());
+
+    let bad_half_life = ConsolidationConfig {
+        decay_half_life_days: Some(0.0),
+        ..ConsolidationConfig::default()
+    };
+    assert!(bad_half_life.validate().is_err());
+}
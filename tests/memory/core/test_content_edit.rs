@@ -0,0 +1,24 @@
+// Tests for src/memory/core/manager/coordinator/types.rs
+
+#![cfg(feature = "memory")]
+
+use kodegen_candle_agent::memory::core::manager::coordinator::ContentEdit;
+
+#[test]
+fn test_replace_discards_old_content() {
+    let edit = ContentEdit::Replace("Deploys run on Fridays".to_string());
+    assert_eq!(
+        edit.apply("Deploys run on Mondays"),
+        "Deploys run on Fridays"
+    );
+}
+
+#[test]
+fn test_append_adds_a_line() {
+    let edit = ContentEdit::Append("Rollbacks need approval".to_string());
+    assert_eq!(
+        edit.apply("Deploys run on Fridays"),
+        "Deploys run on Fridays\nRollbacks need approval"
+    );
+    assert_eq!(edit.apply(""), "Rollbacks need approval");
+}
@@ -0,0 +1,53 @@
+// Tests for src/memory/core/manager/coordinator/embedding_tasks.rs
+
+#![cfg(feature = "memory")]
+
+use kodegen_candle_agent::capability::text_embedding::stella::instruction::{
+    custom_instruction, format_single_with_instruction,
+};
+use kodegen_candle_agent::memory::core::manager::coordinator::{
+    DOCUMENT_TASK, EmbeddingTasks, QUERY_TASK,
+};
+
+#[test]
+fn test_search_tasks_keep_asymmetric_default() {
+    for task in ["", "s2p", "search_query", DOCUMENT_TASK] {
+        assert_eq!(EmbeddingTasks::for_task(task), EmbeddingTasks::default());
+    }
+    assert_eq!(EmbeddingTasks::default().document, DOCUMENT_TASK);
+    assert_eq!(EmbeddingTasks::default().query, QUERY_TASK);
+}
+
+#[test]
+fn test_similarity_and_custom_tasks_are_symmetric() {
+    assert_eq!(
+        EmbeddingTasks::for_task("s2s"),
+        EmbeddingTasks::symmetric("s2s")
+    );
+
+    let custom = EmbeddingTasks::with_instruction(" Retrieve matching tickets. ");
+    assert_eq!(custom.document, "instruct:Retrieve matching tickets.");
+    assert_eq!(custom.document, custom.query);
+    assert_eq!(EmbeddingTasks::for_task(&custom.document), custom);
+}
+
+#[test]
+fn test_code_tasks_instruct_queries_only() {
+    let tasks = EmbeddingTasks::for_task("code:rust");
+    assert_eq!(tasks.document, DOCUMENT_TASK);
+    assert_eq!(tasks.query, "code:rust");
+}
+
+#[test]
+fn test_custom_instruction_prefixes_text() {
+    assert_eq!(
+        custom_instruction("instruct: Find bugs "),
+        Some("Find bugs")
+    );
+    assert_eq!(custom_instruction("instruct:   "), None);
+    assert_eq!(custom_instruction("s2s"), None);
+    assert_eq!(
+        format_single_with_instruction("text", Some("instruct:Find bugs")),
+        "Instruct: Find bugs\nQuery: text"
+    );
+}
@@ -0,0 +1,38 @@
+// Tests for src/memory/core/manager/coordinator/fsck.rs
+
+#![cfg(feature = "memory")]
+
+use kodegen_candle_agent::memory::core::manager::coordinator::{
+    FsckIssue, FsckIssueKind, FsckReport,
+};
+
+#[test]
+fn test_report_counts_unrepaired_issues() {
+    let report = FsckReport {
+        memories_checked: 3,
+        issues: vec![
+            FsckIssue {
+                kind: FsckIssueKind::EmbeddingDimension {
+                    expected: 1024,
+                    actual: 768,
+                },
+                record: "memory:a".to_string(),
+                repaired: true,
+                repair_error: None,
+            },
+            FsckIssue {
+                kind: FsckIssueKind::OrphanedEdge,
+                record: "entangled:e1".to_string(),
+                repaired: false,
+                repair_error: Some("Database error: locked".to_string()),
+            },
+        ],
+    };
+
+    assert!(!report.is_clean());
+    assert_eq!(report.unrepaired(), 1);
+    let text = report.to_string();
+    assert!(text.starts_with("3 memories checked, 2 issue(s), 1 unrepaired"));
+    assert!(text.contains("memory:a: embedding has 768 dimensions, expected 1024 [repaired]"));
+    assert!(text.contains("entangled:e1: orphaned graph edge [repair failed: Database error: locked]"));
+}
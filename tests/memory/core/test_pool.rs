@@ -0,0 +1,52 @@
+// Tests for src/memory/core/manager/pool.rs
+
+#![cfg(feature = "memory")]
+
+use kodegen_candle_agent::capability::registry::{FromRegistry, TextEmbeddingModel};
+use kodegen_candle_agent::domain::model::traits::CandleModel;
+use kodegen_candle_agent::memory::core::manager::pool::{
+    CoordinatorPool, DEFAULT_EMBEDDING_MODEL, parse_library_models,
+};
+
+const LARGE_MODEL: &str = "dunzhang/stella_en_1.5B_v5";
+
+#[test]
+fn test_parse_library_models() {
+    let models = parse_library_models(" research = dunzhang/stella_en_1.5B_v5 ,, scratch=a/b ")
+        .expect("well-formed spec should parse");
+    assert_eq!(
+        models,
+        vec![
+            ("research".to_string(), LARGE_MODEL.to_string()),
+            ("scratch".to_string(), "a/b".to_string()),
+        ]
+    );
+
+    assert!(parse_library_models("").unwrap().is_empty());
+    assert!(parse_library_models("research").is_err());
+    assert!(parse_library_models("=a/b").is_err());
+    assert!(parse_library_models("research=").is_err());
+}
+
+#[test]
+fn test_library_embedding_model_override() {
+    let default = TextEmbeddingModel::from_registry(DEFAULT_EMBEDDING_MODEL)
+        .expect("default embedding model should be registered");
+    let large =
+        TextEmbeddingModel::from_registry(LARGE_MODEL).expect("Stella 1.5B should be registered");
+
+    let pool = CoordinatorPool::new(default).with_library_embedding_model("research", large);
+
+    assert_eq!(
+        pool.library_embedding_model("research").info().registry_key,
+        LARGE_MODEL
+    );
+    assert_eq!(
+        pool.library_embedding_model("notes").info().registry_key,
+        DEFAULT_EMBEDDING_MODEL
+    );
+    assert_eq!(
+        pool.embedding_model().info().registry_key,
+        DEFAULT_EMBEDDING_MODEL
+    );
+}
@@ -0,0 +1,31 @@
+// Tests for src/memory/core/manager/coordinator/recall_stats.rs
+
+#![cfg(feature = "memory")]
+
+use kodegen_candle_agent::memory::core::manager::coordinator::RecallHits;
+
+#[test]
+fn test_hits_accumulate_per_memory() {
+    let hits = RecallHits::default();
+    assert_eq!(hits.record(["a".to_string(), "b".to_string()]), 2);
+    assert_eq!(hits.record(["a".to_string()]), 2);
+
+    let pending = hits.take();
+    assert_eq!(pending["a"], 2);
+    assert_eq!(pending["b"], 1);
+    assert_eq!(hits.pending(), 0);
+}
+
+#[test]
+fn test_restored_hits_merge_with_new_ones() {
+    let hits = RecallHits::default();
+    hits.record(["a".to_string()]);
+    let failed = hits.take();
+
+    hits.record(["a".to_string(), "c".to_string()]);
+    hits.restore(failed);
+
+    let pending = hits.take();
+    assert_eq!(pending["a"], 2);
+    assert_eq!(pending["c"], 1);
+}
@@ -0,0 +1,52 @@
+// Tests for src/memory/core/manager/coordinator/snapshot.rs
+
+#![cfg(feature = "memory")]
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use kodegen_candle_agent::memory::core::{ReadSnapshot, WriteGenerations};
+
+#[test]
+fn test_snapshot_excludes_pending_and_later_generations() {
+    let snapshot = ReadSnapshot::new(100, [90]);
+
+    // Memories written outside any generation are always visible
+    assert!(snapshot.includes(None));
+    assert!(snapshot.includes(Some(80)));
+    assert!(snapshot.includes(Some(100)));
+
+    // Still being written when the snapshot was taken
+    assert!(!snapshot.includes(Some(90)));
+    // Started after the snapshot
+    assert!(!snapshot.includes(Some(101)));
+}
+
+#[test]
+fn test_default_snapshot_includes_everything() {
+    let snapshot = ReadSnapshot::default();
+    assert!(snapshot.pending().is_empty());
+    assert!(snapshot.includes(Some(u64::MAX - 1)));
+    assert!(snapshot.includes(None));
+}
+
+#[test]
+fn test_snapshot_after_restart_includes_earlier_generations() {
+    // Stamped by a previous process a minute ago
+    let earlier = SystemTime::now()
+        .checked_sub(Duration::from_secs(60))
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| u64::try_from(d.as_micros()).unwrap())
+        .unwrap();
+
+    // A fresh process has opened no generation yet
+    let generations = Arc::new(WriteGenerations::default());
+    let snapshot = generations.snapshot();
+    assert!(snapshot.includes(Some(earlier)));
+
+    // Writes started after the snapshot stay out of it
+    let write = generations.begin_write();
+    assert!(write.id() > snapshot.generation());
+    assert!(!snapshot.includes(Some(write.id())));
+    assert!(!generations.snapshot().includes(Some(write.id())));
+}
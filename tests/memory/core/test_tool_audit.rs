@@ -0,0 +1,62 @@
+// Tests for src/memory/core/manager/coordinator/tool_audit.rs
+
+#![cfg(feature = "memory")]
+
+use std::time::Duration;
+
+use kodegen_candle_agent::memory::core::manager::coordinator::{
+    TOOL_AUDIT_PREVIEW_CHARS, ToolAuditQuery, ToolCallRecord,
+};
+
+#[test]
+fn test_record_hashes_args_and_truncates_result() {
+    let long_result = "x".repeat(TOOL_AUDIT_PREVIEW_CHARS + 50);
+    let record = ToolCallRecord::new(
+        "fs_read_file",
+        r#"{"path":"/etc/hosts"}"#,
+        Duration::from_millis(42),
+        Ok(&long_result),
+    )
+    .in_turn("session-1", "turn-1");
+
+    assert!(record.success);
+    assert_eq!(record.duration_ms, 42);
+    assert_eq!(record.args_hash.len(), 16);
+    assert!(!record.args_hash.contains("hosts"));
+    assert_eq!(record.result_preview.len(), TOOL_AUDIT_PREVIEW_CHARS);
+    assert_eq!(record.session_id.as_deref(), Some("session-1"));
+
+    let same_args = ToolCallRecord::new(
+        "fs_read_file",
+        r#"{"path":"/etc/hosts"}"#,
+        Duration::ZERO,
+        Err("denied"),
+    );
+    assert_eq!(same_args.args_hash, record.args_hash);
+    assert!(!same_args.success);
+    assert_eq!(same_args.result_preview, "denied");
+}
+
+#[test]
+fn test_query_filters_with_bound_values() {
+    let query = ToolAuditQuery {
+        session_id: Some("s'1\"".to_string()),
+        failures_only: true,
+        limit: Some(5),
+        ..ToolAuditQuery::default()
+    }
+    .to_query();
+
+    assert!(query.contains("session_id = $session_id"));
+    assert!(!query.contains("s'1"));
+    assert!(query.contains("success = false"));
+    assert!(!query.contains("turn_id ="));
+    assert!(query.ends_with("ORDER BY called_at DESC LIMIT $limit"));
+}
+
+#[test]
+fn test_args_hash_is_sha256_prefix() {
+    let record = ToolCallRecord::new("t", "abc", Duration::ZERO, Ok(""));
+    // SHA-256("abc") = ba7816bf8f01cfea...
+    assert_eq!(record.args_hash, "ba7816bf8f01cfea");
+}
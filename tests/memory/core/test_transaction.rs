@@ -0,0 +1,40 @@
+// Tests for src/memory/core/manager/coordinator/transaction.rs
+
+#![cfg(feature = "memory")]
+
+use kodegen_candle_agent::domain::memory::primitives::types::MemoryTypeEnum;
+use kodegen_candle_agent::memory::core::MemoryTransaction;
+use kodegen_candle_agent::memory::core::manager::surreal::MetadataUpdate;
+
+#[test]
+fn test_transaction_buffers_operations_in_order() {
+    let mut tx = MemoryTransaction::default();
+    assert!(tx.is_empty());
+
+    let first = tx.add_memory("first fact", MemoryTypeEnum::Semantic, None);
+    let second = tx.add_memory("second fact", MemoryTypeEnum::Semantic, None);
+    tx.add_relationship(&first, &second, "related_to", None);
+
+    // IDs are returned up front in the stored (hyphen-free) form
+    assert_ne!(first, second);
+    assert_eq!(first.len(), 32);
+    assert!(!first.contains('-'));
+    assert_eq!(tx.len(), 3);
+}
+
+#[test]
+fn test_empty_metadata_update_is_skipped() {
+    let mut tx = MemoryTransaction::default();
+    tx.update_metadata("missing", MetadataUpdate::default());
+    assert!(tx.is_empty());
+
+    tx.update_metadata(
+        "some_memory",
+        MetadataUpdate {
+            importance: Some(0.9),
+            ..Default::default()
+        },
+    );
+    assert_eq!(tx.len(), 1);
+    tx.rollback();
+}
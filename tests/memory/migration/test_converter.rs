@@ -1,5 +1,7 @@
 // Tests extracted from src/memory/migration/converter.rs
 
+#![cfg(feature = "memory")]
+
 use kodegen_candle_agent::memory::migration::converter::{DataConverter, ImportMetadata, ImportData};
 
 #[test]
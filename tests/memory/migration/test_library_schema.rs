@@ -0,0 +1,37 @@
+// Tests for src/memory/migration/library_schema.rs
+
+#![cfg(feature = "memory")]
+
+use kodegen_candle_agent::memory::migration::{
+    EmbeddingDimensionMigration, LEGACY_EMBEDDING_DIMENSION, LIBRARY_SCHEMA_VERSION,
+    LibraryMigrations, LibrarySchemaVersion,
+};
+
+#[test]
+fn test_library_migrations_are_sequential() {
+    let versions: Vec<u32> = LibraryMigrations::all()
+        .iter()
+        .map(|m| m.version())
+        .collect();
+    let expected: Vec<u32> = (1..=LIBRARY_SCHEMA_VERSION).collect();
+    assert_eq!(versions, expected);
+}
+
+#[test]
+fn test_unversioned_library_defaults_to_legacy_index() {
+    let state = LibrarySchemaVersion::default();
+    assert_eq!(state.version, 0);
+    assert_eq!(state.embedding_dimension, LEGACY_EMBEDDING_DIMENSION);
+}
+
+#[test]
+fn test_dimension_statements_set_aside_mismatched_embeddings() {
+    let statements = EmbeddingDimensionMigration::statements(768);
+
+    assert!(statements[0].contains("!= 768"));
+    assert!(statements[1].contains("metadata.previous_embedding = metadata.swap_embedding"));
+    // Only set-aside embeddings of the new dimension are restored
+    assert!(statements[1].contains("array::len(metadata.previous_embedding ?? []) = 768"));
+    assert!(statements[2].contains("metadata.embedding = NONE"));
+    assert!(statements.last().unwrap().contains("MTREE DIMENSION 768"));
+}
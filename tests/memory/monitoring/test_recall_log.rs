@@ -0,0 +1,21 @@
+// Tests for src/memory/monitoring/recall_log.rs
+
+use std::time::Duration;
+
+use kodegen_candle_agent::memory::monitoring::{RECALL_LOG_CAPACITY, record_recall, recent_recalls};
+
+#[test]
+fn test_recall_log_is_bounded_and_newest_first() {
+    let library = format!("recall_log_{}", uuid::Uuid::new_v4().simple());
+
+    for i in 0..RECALL_LOG_CAPACITY + 5 {
+        record_recall(&library, format!("query {i}"), i, Duration::from_millis(5));
+    }
+
+    let recalls = recent_recalls(RECALL_LOG_CAPACITY * 2);
+    assert!(recalls.len() <= RECALL_LOG_CAPACITY);
+
+    let ours: Vec<_> = recalls.iter().filter(|r| r.library == library).collect();
+    assert_eq!(ours[0].query, format!("query {}", RECALL_LOG_CAPACITY + 4));
+    assert!((ours[0].latency_ms - 5.0).abs() < f64::EPSILON);
+}
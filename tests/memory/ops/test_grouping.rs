@@ -0,0 +1,46 @@
+// Tests for src/memory/core/ops/grouping.rs
+
+use kodegen_candle_agent::memory::core::ops::grouping::group_by_source;
+
+fn hits() -> Vec<(&'static str, &'static str, f32)> {
+    vec![
+        ("a.rs", "a1", 0.9),
+        ("a.rs", "a2", 0.8),
+        ("b.rs", "b1", 0.85),
+        ("a.rs", "a3", 0.7),
+        ("c.md", "c1", 0.5),
+    ]
+}
+
+#[test]
+fn test_one_group_per_source() {
+    let groups = group_by_source(hits(), 10, |h| h.0.to_string(), |h| h.2);
+
+    let sources: Vec<&str> = groups.iter().map(|g| g.source.as_str()).collect();
+    assert_eq!(sources, ["a.rs", "b.rs", "c.md"]);
+    assert_eq!(groups[0].hits.len(), 3);
+    assert_eq!(groups[0].best().1, "a1");
+}
+
+#[test]
+fn test_combined_score_decays() {
+    let groups = group_by_source(hits(), 10, |h| h.0.to_string(), |h| h.2);
+
+    // 0.9 + 0.8 / 2 + 0.7 / 4
+    assert!((groups[0].score - 1.475).abs() < 1e-6);
+    assert!((groups[1].score - 0.85).abs() < 1e-6);
+}
+
+#[test]
+fn test_best_hit_is_highest_scoring() {
+    let hits = vec![("a.rs", "low", 0.2), ("a.rs", "high", 0.6)];
+    let groups = group_by_source(hits, 10, |h| h.0.to_string(), |h| h.2);
+    assert_eq!(groups[0].best().1, "high");
+}
+
+#[test]
+fn test_limit_counts_sources() {
+    let groups = group_by_source(hits(), 2, |h| h.0.to_string(), |h| h.2);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[1].source, "b.rs");
+}
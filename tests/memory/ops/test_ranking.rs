@@ -0,0 +1,53 @@
+// Tests for src/memory/core/ops/ranking.rs
+
+use kodegen_candle_agent::domain::memory::primitives::node::MemoryNode;
+use kodegen_candle_agent::domain::memory::primitives::types::{MemoryContent, MemoryTypeEnum};
+use kodegen_candle_agent::memory::core::ops::ranking::{
+    MemoryRanker, ScoredMemory, SimilarityImportanceRanker, rank_memories,
+};
+
+fn memory(text: &str, similarity: f64, importance: f32) -> MemoryNode {
+    let mut memory = MemoryNode::new(MemoryTypeEnum::Semantic, MemoryContent::text(text));
+    memory.set_custom_metadata("similarity", serde_json::Value::from(similarity));
+    memory.set_importance(importance).unwrap();
+    memory
+}
+
+/// Keeps only candidates whose content mentions a keyword, in input order
+struct Mentions(&'static str);
+
+impl MemoryRanker for Mentions {
+    fn rank(&self, query_embedding: &[f32], candidates: Vec<ScoredMemory>) -> Vec<ScoredMemory> {
+        assert_eq!(query_embedding, [1.0, 0.0]);
+        candidates
+            .into_iter()
+            .filter(|c| c.memory.content().to_string().contains(self.0))
+            .collect()
+    }
+}
+
+#[test]
+fn test_default_ranks_by_similarity_times_importance() {
+    let memories = vec![
+        memory("close but minor", 0.9, 0.2),
+        memory("further but important", 0.6, 1.0),
+    ];
+
+    let ranked = rank_memories(&SimilarityImportanceRanker, &[], memories);
+    assert_eq!(
+        ranked[0].memory.content().to_string(),
+        "further but important"
+    );
+    assert!((ranked[0].score - 0.6).abs() < 1e-6);
+    assert!((ranked[1].score - 0.18).abs() < 1e-6);
+    assert!(!SimilarityImportanceRanker.uses_query_embedding());
+}
+
+#[test]
+fn test_custom_ranker_sees_embedding_and_relevance() {
+    let memories = vec![memory("deploy notes", 0.4, 1.0), memory("lunch", 0.9, 1.0)];
+
+    let ranked = rank_memories(&Mentions("deploy"), &[1.0, 0.0], memories);
+    assert_eq!(ranked.len(), 1);
+    assert!((ranked[0].score - 0.4).abs() < 1e-6);
+}
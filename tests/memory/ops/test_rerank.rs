@@ -0,0 +1,67 @@
+// Tests for src/memory/core/ops/rerank.rs
+
+use kodegen_candle_agent::capability::rerank::CrossEncoderReranker;
+use kodegen_candle_agent::capability::traits::{RerankCapable, RerankFuture};
+use kodegen_candle_agent::domain::memory::primitives::node::MemoryNode;
+use kodegen_candle_agent::domain::memory::primitives::types::{MemoryContent, MemoryTypeEnum};
+use kodegen_candle_agent::domain::model::CandleModelInfo;
+use kodegen_candle_agent::domain::model::traits::CandleModel;
+use kodegen_candle_agent::memory::core::ops::rerank::{rerank_memories, rerank_score};
+
+/// Scores documents by how many query words they contain
+#[derive(Debug)]
+struct WordOverlap;
+
+impl CandleModel for WordOverlap {
+    fn info(&self) -> &'static CandleModelInfo {
+        CrossEncoderReranker::new().info()
+    }
+}
+
+impl RerankCapable for WordOverlap {
+    fn rerank(&self, query: &str, documents: &[String]) -> RerankFuture<'_> {
+        let scores = documents
+            .iter()
+            .map(|document| {
+                query
+                    .split_whitespace()
+                    .filter(|word| document.contains(word))
+                    .count() as f32
+            })
+            .collect();
+        Box::pin(async move { Ok(scores) })
+    }
+}
+
+fn memory(text: &str) -> MemoryNode {
+    MemoryNode::new(MemoryTypeEnum::Semantic, MemoryContent::text(text))
+}
+
+#[tokio::test]
+async fn test_reorders_by_relevance_and_keeps_limit() {
+    let memories = vec![
+        memory("deploys run on fridays"),
+        memory("rollback the deploy with the rollback script"),
+        memory("lunch is at noon"),
+    ];
+
+    let reranked = rerank_memories(&WordOverlap, "rollback deploy script", memories, 2)
+        .await
+        .unwrap();
+
+    let contents: Vec<String> = reranked.iter().map(|m| m.content().to_string()).collect();
+    assert_eq!(
+        contents,
+        [
+            "rollback the deploy with the rollback script",
+            "deploys run on fridays"
+        ]
+    );
+    assert_eq!(rerank_score(&reranked[0]), Some(3.0));
+    assert_eq!(rerank_score(&reranked[1]), Some(1.0));
+}
+
+#[test]
+fn test_unreranked_memory_has_no_score() {
+    assert_eq!(rerank_score(&memory("plain hit")), None);
+}
@@ -0,0 +1,35 @@
+// Tests for src/memory/core/ops/similarity.rs
+
+use kodegen_candle_agent::memory::core::ops::similarity::{
+    boosted_score, cosine, cosine_scalar, top_k_by_similarity,
+};
+
+fn vector(seed: usize) -> Vec<f32> {
+    (0..1024).map(|i| ((i * 31 + seed * 17) % 97) as f32 - 48.0).collect()
+}
+
+#[test]
+fn simd_cosine_matches_scalar() {
+    for seed in 1..8 {
+        let (a, b) = (vector(0), vector(seed));
+        assert!((cosine(&a, &b) - cosine_scalar(&a, &b)).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn degenerate_vectors_score_zero() {
+    assert_eq!(cosine(&[1.0, 2.0], &[1.0]), 0.0);
+    assert_eq!(cosine(&[], &[]), 0.0);
+    assert_eq!(cosine(&[0.0; 4], &[1.0; 4]), 0.0);
+}
+
+#[test]
+fn top_k_orders_best_first() {
+    let query = [1.0, 0.0];
+    let candidates = [vec![0.0, 1.0], vec![1.0, 0.1], vec![1.0, 1.0]];
+
+    let ranked = top_k_by_similarity(&query, &candidates, 2);
+    let indices: Vec<usize> = ranked.iter().map(|(i, _)| *i).collect();
+    assert_eq!(indices, [1, 2]);
+    assert_eq!(boosted_score(0.5, 0.8), 0.4);
+}
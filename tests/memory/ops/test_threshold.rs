@@ -0,0 +1,22 @@
+// Tests for src/memory/core/ops/threshold.rs
+
+use kodegen_candle_agent::memory::core::ops::threshold::apply_min_score;
+
+#[test]
+fn test_hits_below_floor_are_rejected_in_order() {
+    let hits = vec![("a", 0.9), ("b", 0.4), ("c", 0.7), ("d", 0.55)];
+    let result = apply_min_score(hits, 0.6, |(_, score)| *score);
+
+    assert_eq!(result.kept, vec![("a", 0.9), ("c", 0.7)]);
+    assert_eq!(result.rejected, 2);
+    assert_eq!(result.best_rejected, Some(0.55));
+}
+
+#[test]
+fn test_nothing_rejected_has_no_best_rejected_score() {
+    let result = apply_min_score(vec![0.8_f32, 0.6], 0.6, |score| *score);
+
+    assert_eq!(result.kept.len(), 2);
+    assert_eq!(result.rejected, 0);
+    assert_eq!(result.best_rejected, None);
+}
@@ -0,0 +1,20 @@
+// Tests for src/memory/builder.rs
+
+#![cfg(feature = "memory")]
+
+use kodegen_candle_agent::memory::{Error, Memory};
+
+#[tokio::test]
+async fn test_unregistered_embedding_model_is_rejected() {
+    let result = Memory::library("notes")
+        .embedding_model("nobody/no-such-model")
+        .at_path(std::env::temp_dir().join("kodegen-builder-test.db"))
+        .build()
+        .await;
+
+    match result {
+        Err(Error::Embedding(message)) => assert!(message.contains("nobody/no-such-model")),
+        Err(other) => panic!("expected an embedding error, got {other:?}"),
+        Ok(_) => panic!("expected an embedding error"),
+    }
+}
@@ -0,0 +1,7 @@
+// Integration tests for runtime helpers
+
+mod runtime {
+    mod test_crash;
+    mod test_request_id;
+    mod test_supervisor;
+}
@@ -0,0 +1,70 @@
+// Tests for src/runtime/crash.rs
+
+use kodegen_candle_agent::runtime::crash::{
+    CrashDump, RecentLogLayer, record_log, track_generation, track_session,
+};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[test]
+fn test_dump_lists_in_flight_work_until_dropped() {
+    let session = track_session("crash-test-session");
+    let generation = track_generation("crash-test-turn");
+    record_log("crash-test log line");
+
+    let dump = CrashDump::capture("boom", Some("src/lib.rs:1:1".to_string()));
+    assert_eq!(dump.message, "boom");
+    assert!(
+        dump.active_sessions
+            .contains(&"crash-test-session".to_string())
+    );
+    assert!(
+        dump.in_flight_generations
+            .contains(&"crash-test-turn".to_string())
+    );
+    assert!(
+        dump.recent_logs
+            .iter()
+            .any(|line| line == "crash-test log line")
+    );
+
+    drop(generation);
+    drop(session);
+    let dump = CrashDump::capture("boom", None);
+    assert!(
+        !dump
+            .active_sessions
+            .contains(&"crash-test-session".to_string())
+    );
+    assert!(
+        !dump
+            .in_flight_generations
+            .contains(&"crash-test-turn".to_string())
+    );
+}
+
+#[test]
+fn test_dump_round_trips_through_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let dump = CrashDump::capture("written", None);
+
+    let path = dump.write_to(dir.path()).unwrap();
+    assert!(path.starts_with(dir.path()));
+
+    let read: CrashDump = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert_eq!(read, dump);
+}
+
+#[test]
+fn test_recent_log_layer_feeds_dumps() {
+    let subscriber = tracing_subscriber::registry().with(RecentLogLayer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(library = "notes", "crash-test traced line");
+    });
+
+    let dump = CrashDump::capture("boom", None);
+    assert!(dump.recent_logs.iter().any(|line| {
+        line.contains("WARN")
+            && line.contains("crash-test traced line")
+            && line.contains("library=\"notes\"")
+    }));
+}
@@ -0,0 +1,77 @@
+// Tests for src/runtime/request_id.rs
+
+use kodegen_candle_agent::runtime::{
+    RequestId, TaskSupervisor, annotate_query, current_request_id, ensure_request_id,
+    spawn_with_request_id, with_request_id,
+};
+
+#[test]
+fn test_from_header_adopts_valid_ids_only() {
+    assert_eq!(RequestId::from_header(Some("abc-123")).as_str(), "abc-123");
+
+    let too_long = "x".repeat(200);
+    for invalid in [None, Some(""), Some("has space"), Some(too_long.as_str())] {
+        let id = RequestId::from_header(invalid);
+        assert_eq!(id.as_str().len(), 32, "expected a generated id for {invalid:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_request_id_is_scoped_and_follows_supervised_tasks() {
+    assert!(current_request_id().is_none());
+
+    let id = RequestId::from_header(Some("req-1"));
+    let supervisor = TaskSupervisor::new();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    with_request_id(id.clone(), async {
+        assert_eq!(current_request_id(), Some(id.clone()));
+        supervisor.spawn("background write", async move {
+            let _ = tx.send(current_request_id());
+        });
+    })
+    .await;
+
+    assert!(current_request_id().is_none());
+    assert_eq!(rx.await.unwrap(), Some(id));
+}
+
+#[tokio::test]
+async fn test_queries_are_annotated_in_spawned_tasks() {
+    assert_eq!(annotate_query("SELECT * FROM memory"), "SELECT * FROM memory");
+
+    let id = RequestId::from_header(Some("tool-7"));
+    let query = with_request_id(id, async {
+        spawn_with_request_id(async { annotate_query("SELECT * FROM memory") })
+            .await
+            .unwrap()
+    })
+    .await;
+    assert_eq!(query, "-- request_id: tool-7\nSELECT * FROM memory");
+}
+
+#[tokio::test]
+async fn test_ensure_request_id_keeps_or_assigns() {
+    let assigned = ensure_request_id(async { current_request_id() }).await;
+    assert!(assigned.is_some());
+
+    let id = RequestId::from_header(Some("outer"));
+    let kept = with_request_id(id.clone(), ensure_request_id(async { current_request_id() })).await;
+    assert_eq!(kept, Some(id));
+}
+
+#[tokio::test]
+async fn test_spawned_streams_keep_the_request_id() {
+    use kodegen_candle_agent::async_stream::{StreamExt, spawn_stream};
+
+    let id = RequestId::from_header(Some("stream-3"));
+    let stream = with_request_id(id.clone(), async {
+        spawn_stream(|tx| async move {
+            let _ = tx.send(current_request_id());
+        })
+    })
+    .await;
+
+    let seen: Vec<_> = stream.collect().await;
+    assert_eq!(seen, vec![Some(id)]);
+}
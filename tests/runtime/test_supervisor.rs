@@ -0,0 +1,50 @@
+// Tests for src/runtime/supervisor.rs
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use kodegen_candle_agent::runtime::TaskSupervisor;
+
+#[tokio::test]
+async fn test_panic_is_contained_and_counted() {
+    let supervisor = TaskSupervisor::new();
+
+    let handle = supervisor.spawn("panicking task", async {
+        panic!("boom");
+    });
+
+    // The supervisor catches the panic, so the JoinHandle resolves cleanly
+    assert!(handle.await.is_ok());
+    assert_eq!(supervisor.panic_count(), 1);
+}
+
+#[tokio::test]
+async fn test_shutdown_waits_for_tracked_tasks() {
+    let supervisor = TaskSupervisor::new();
+    let finished = Arc::new(AtomicBool::new(false));
+
+    let flag = finished.clone();
+    supervisor.spawn("slow write", async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        flag.store(true, Ordering::SeqCst);
+    });
+
+    assert!(supervisor.shutdown(Duration::from_secs(5)).await);
+    assert!(finished.load(Ordering::SeqCst));
+    assert_eq!(supervisor.active_tasks(), 0);
+}
+
+#[tokio::test]
+async fn test_cancellable_loop_stops_on_shutdown() {
+    let supervisor = TaskSupervisor::new();
+
+    supervisor.spawn_cancellable("endless loop", async {
+        loop {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+
+    assert!(supervisor.shutdown(Duration::from_secs(5)).await);
+    assert!(supervisor.is_shutting_down());
+}
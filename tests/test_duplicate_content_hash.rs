@@ -1,3 +1,5 @@
+#![cfg(feature = "memory")]
+
 /// Test for duplicate content_hash upsert behavior
 /// Verifies that re-ingesting content with the same hash resets importance to 1.0
 
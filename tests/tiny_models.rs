@@ -0,0 +1,241 @@
+// End-to-end pipeline test using tiny checkpoints
+//
+// Runs memorize → recall → chat → tool-call against real (tiny) models.
+// Skipped unless tiny mode is active so regular `cargo test` stays offline:
+//
+//     KODEGEN_TINY_MODELS=1 cargo test --test tiny_models
+#![cfg(feature = "memory")]
+
+use std::sync::Arc;
+
+use cyrup_sugars::ZeroOneOrMany;
+use kodegen_candle_agent::capability::registry::{FromRegistry, TextEmbeddingModel, TextToTextModel};
+use kodegen_candle_agent::capability::text_to_text::CandleQwen3QuantizedModel;
+use kodegen_candle_agent::capability::text_to_text::qwen3_quantized::LoadedQwen3QuantizedModel;
+use kodegen_candle_agent::capability::tiny::{
+    GgufCheckpoint, TINY_EMBEDDING_MODEL, tiny_models_enabled, write_tiny_qwen3_gguf,
+};
+use kodegen_candle_agent::capability::traits::TextToTextCapable;
+use kodegen_candle_agent::domain::completion::{CandleCompletionChunk, CandleCompletionParams};
+use kodegen_candle_agent::domain::memory::primitives::types::MemoryTypeEnum;
+use kodegen_candle_agent::domain::prompt::CandlePrompt;
+use kodegen_candle_agent::memory::core::manager::coordinator::{ContentEdit, EmbeddingTasks};
+use kodegen_candle_agent::memory::core::manager::pool::CoordinatorPool;
+use tokio_stream::StreamExt;
+
+#[test]
+fn test_tiny_checkpoint_differs_from_default() {
+    let tiny = GgufCheckpoint::qwen3_tiny();
+    let default = GgufCheckpoint::qwen3_default();
+
+    assert_ne!(tiny.repo, default.repo);
+    assert!(tiny.file.ends_with(".gguf"));
+    assert!(!tiny.tokenizer_repo.is_empty());
+    assert!(tiny.generated.is_some());
+    assert!(default.generated.is_none());
+}
+
+#[test]
+fn test_generated_tiny_checkpoint_is_small_and_loads() -> anyhow::Result<()> {
+    use candle_core::quantized::gguf_file;
+    use candle_core::{Device, Tensor};
+    use candle_transformers::models::quantized_qwen3::ModelWeights;
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("tiny.gguf");
+    write_tiny_qwen3_gguf(&path)?;
+    assert!(std::fs::metadata(&path)?.len() < 16 * 1024 * 1024);
+
+    let mut file = std::fs::File::open(&path)?;
+    let content = gguf_file::Content::read(&mut file)?;
+    let mut model = ModelWeights::from_gguf(content, &mut file, &Device::Cpu)?;
+
+    let input = Tensor::new(&[[9707u32, 11]], &Device::Cpu)?;
+    let logits = model.forward(&input, 0)?;
+    assert_eq!(logits.dims().last(), Some(&151_936));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tiny_end_to_end_pipeline() -> anyhow::Result<()> {
+    if !tiny_models_enabled() {
+        eprintln!("tiny model mode disabled; set KODEGEN_TINY_MODELS=1 to run");
+        return Ok(());
+    }
+
+    // Memorize → recall
+    let emb_model = TextEmbeddingModel::from_registry(TINY_EMBEDDING_MODEL)
+        .ok_or_else(|| anyhow::anyhow!("embedding model missing from registry"))?;
+    let pool = Arc::new(CoordinatorPool::new(emb_model));
+    let library = format!("tiny_e2e_{}", uuid::Uuid::new_v4().simple());
+    let coordinator = pool.get_coordinator(&library).await?;
+
+    coordinator
+        .add_memory(
+            "The deploy script lives in scripts/deploy.sh".to_string(),
+            MemoryTypeEnum::Fact,
+            None,
+        )
+        .await?;
+
+    let recalled = coordinator.search_memories("where is the deploy script", 3, None).await?;
+    assert!(!recalled.is_empty(), "recall returned no memories");
+
+    // Chat
+    let text_model = TextToTextModel::from_registry("Qwen/Qwen2.5-Coder-3B-Instruct-GGUF")
+        .ok_or_else(|| anyhow::anyhow!("text model missing from registry"))?;
+    let params = CandleCompletionParams::new()
+        .with_max_tokens(std::num::NonZeroU64::new(16));
+    let mut stream = text_model.prompt(CandlePrompt::new("Say hello."), &params);
+    let mut produced = false;
+    let mut last = None;
+    while let Some(chunk) = stream.next().await {
+        match &chunk {
+            CandleCompletionChunk::Error(e) => anyhow::bail!("chat failed: {}", e),
+            CandleCompletionChunk::Text(_) => produced = true,
+            _ => {}
+        }
+        last = Some(chunk);
+    }
+    assert!(produced, "chat produced no text");
+
+    // The provider ends the reply with its real usage and finish reason
+    match last {
+        Some(CandleCompletionChunk::Complete {
+            usage: Some(usage),
+            finish_reason: Some(_),
+            ..
+        }) => {
+            assert!(usage.input_tokens > 0, "prompt tokens not counted");
+            assert!(usage.output_tokens > 0, "reply tokens not counted");
+        }
+        other => anyhow::bail!("reply did not end with usage: {:?}", other),
+    }
+
+    // Tool call: tiny models may answer in prose, so only require a clean stream
+    let tool = rmcp::model::Tool::new(
+        "get_time",
+        "Return the current time",
+        Arc::new(serde_json::Map::new()),
+    );
+    let mut params = CandleCompletionParams::new()
+        .with_max_tokens(std::num::NonZeroU64::new(32));
+    params.tools = Some(ZeroOneOrMany::One(tool));
+    let mut stream = text_model.prompt(CandlePrompt::new("What time is it?"), &params);
+    while let Some(chunk) = stream.next().await {
+        if let CandleCompletionChunk::Error(e) = chunk {
+            anyhow::bail!("tool-call turn failed: {}", e);
+        }
+    }
+
+    pool.shutdown_all().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tiny_edited_memory_recalls_new_content() -> anyhow::Result<()> {
+    if !tiny_models_enabled() {
+        eprintln!("tiny model mode disabled; set KODEGEN_TINY_MODELS=1 to run");
+        return Ok(());
+    }
+
+    let emb_model = TextEmbeddingModel::from_registry(TINY_EMBEDDING_MODEL)
+        .ok_or_else(|| anyhow::anyhow!("embedding model missing from registry"))?;
+    let pool = Arc::new(CoordinatorPool::new(emb_model));
+    let library = format!("tiny_edit_{}", uuid::Uuid::new_v4().simple());
+    let coordinator = pool.get_coordinator(&library).await?;
+
+    let memory = coordinator
+        .add_memory("Deploys run on Mondays".to_string(), MemoryTypeEnum::Fact, None)
+        .await?;
+    coordinator
+        .add_memory("Rollbacks need approval".to_string(), MemoryTypeEnum::Fact, None)
+        .await?;
+    let memory_id = memory.id().to_string();
+
+    let edit = ContentEdit::Replace("Deploys run on Fridays".to_string());
+    coordinator
+        .edit_memory(&memory_id, &edit)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("edited memory not found"))?;
+
+    let recalled = coordinator.search_memories("which day do deploys run", 1, None).await?;
+    let top = recalled
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("recall returned no memories"))?;
+    assert_eq!(top.id(), memory.id());
+    assert_eq!(top.content().to_string(), "Deploys run on Fridays");
+
+    // Another memory's content is refused rather than duplicated
+    let clash = ContentEdit::Replace("Rollbacks need approval".to_string());
+    assert!(coordinator.edit_memory(&memory_id, &clash).await.is_err());
+
+    pool.shutdown_all().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tiny_embedding_task_is_fixed_once_memories_exist() -> anyhow::Result<()> {
+    if !tiny_models_enabled() {
+        eprintln!("tiny model mode disabled; set KODEGEN_TINY_MODELS=1 to run");
+        return Ok(());
+    }
+
+    let emb_model = TextEmbeddingModel::from_registry(TINY_EMBEDDING_MODEL)
+        .ok_or_else(|| anyhow::anyhow!("embedding model missing from registry"))?;
+    let pool = Arc::new(CoordinatorPool::new(emb_model));
+    let library = format!("tiny_task_{}", uuid::Uuid::new_v4().simple());
+    let coordinator = pool.get_coordinator(&library).await?;
+
+    coordinator.set_embedding_tasks(EmbeddingTasks::for_task("s2s")).await?;
+    coordinator
+        .add_memory("Deploys run on Mondays".to_string(), MemoryTypeEnum::Fact, None)
+        .await?;
+
+    coordinator.set_embedding_tasks(EmbeddingTasks::for_task("s2s")).await?;
+    assert!(
+        coordinator
+            .set_embedding_tasks(EmbeddingTasks::for_task("s2p"))
+            .await
+            .is_err()
+    );
+
+    pool.shutdown_all().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tiny_concurrent_prompts_all_complete() -> anyhow::Result<()> {
+    if !tiny_models_enabled() {
+        eprintln!("tiny model mode disabled; set KODEGEN_TINY_MODELS=1 to run");
+        return Ok(());
+    }
+
+    let base = CandleQwen3QuantizedModel::new().map_err(|e| anyhow::anyhow!("{e}"))?;
+    let model = LoadedQwen3QuantizedModel::load(&base)
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    // Prompts of different lengths, in flight on the same model at once
+    let params = CandleCompletionParams::new()
+        .with_max_tokens(std::num::NonZeroU64::new(8));
+    let prompts = ["Say hello.", "Name three colors of the rainbow, briefly."];
+    let streams = prompts.map(|prompt| model.prompt(CandlePrompt::new(prompt), &params));
+
+    for (prompt, mut stream) in prompts.into_iter().zip(streams) {
+        let mut text = String::new();
+        let mut usage = None;
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                CandleCompletionChunk::Error(e) => anyhow::bail!("{prompt:?} failed: {e}"),
+                CandleCompletionChunk::Text(t) => text.push_str(&t),
+                CandleCompletionChunk::Complete { usage: u, .. } => usage = u,
+                _ => {}
+            }
+        }
+        assert!(!text.is_empty(), "{prompt:?} produced no text");
+        let usage = usage.ok_or_else(|| anyhow::anyhow!("{prompt:?} reported no usage"))?;
+        assert!(usage.output_tokens > 0, "{prompt:?} usage {usage:?}");
+    }
+    Ok(())
+}
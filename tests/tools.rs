@@ -0,0 +1,18 @@
+// Integration tests for MCP tool helpers
+#![cfg(feature = "tools")]
+
+mod tools {
+    mod test_dump_manager;
+    mod test_embed;
+    mod test_find_duplicates;
+    mod test_history_index;
+    mod test_idempotency;
+    mod test_ingestion_queue;
+    mod test_inline_content;
+    mod test_memorize;
+    mod test_memorize_limits;
+    mod test_memorize_manager;
+    mod test_recall;
+    mod test_summarize_manager;
+    mod test_workflow_run;
+}
@@ -0,0 +1,53 @@
+// Tests for src/tools/dump_manager.rs
+
+use kodegen_candle_agent::tools::dump_manager::{DumpRecordKind, ndjson_line, take_lines};
+
+#[test]
+fn test_ndjson_line_tags_objects_with_kind() {
+    let line = ndjson_line(
+        DumpRecordKind::Relationship,
+        serde_json::json!({"id": "r1", "source_id": "a", "target_id": "b"}),
+    );
+
+    assert!(!line.contains('\n'));
+    let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(parsed["kind"], "relationship");
+    assert_eq!(parsed["id"], "r1");
+    assert_eq!(parsed["target_id"], "b");
+}
+
+#[test]
+fn test_ndjson_line_wraps_non_objects() {
+    let line = ndjson_line(DumpRecordKind::Memory, serde_json::json!("text"));
+    let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(parsed["kind"], "memory");
+    assert_eq!(parsed["value"], "text");
+}
+
+#[test]
+fn test_take_lines_respects_byte_budget() {
+    let lines: Vec<String> = ["aaaa", "bbbb", "cccc"].iter().map(|s| s.to_string()).collect();
+
+    let (chunk, next) = take_lines(&lines, 0, 10);
+    assert_eq!(chunk, "aaaa\nbbbb\n");
+    assert_eq!(next, 2);
+
+    let (chunk, next) = take_lines(&lines, next, 10);
+    assert_eq!(chunk, "cccc\n");
+    assert_eq!(next, 3);
+}
+
+#[test]
+fn test_take_lines_returns_oversized_line_alone() {
+    let lines = vec!["x".repeat(50), "y".to_string()];
+    let (chunk, next) = take_lines(&lines, 0, 10);
+    assert_eq!(chunk.len(), 51);
+    assert_eq!(next, 1);
+}
+
+#[test]
+fn test_take_lines_past_end_is_empty() {
+    let lines = vec!["a".to_string()];
+    assert_eq!(take_lines(&lines, 1, 10), (String::new(), 1));
+    assert_eq!(take_lines(&lines, 5, 10), (String::new(), 1));
+}
@@ -0,0 +1,26 @@
+// Tests for src/tools/embed.rs
+
+use kodegen_mcp_schema::candle::EmbedArgs;
+use serde_json::json;
+
+#[test]
+fn test_inputs_put_text_before_texts() {
+    let args: EmbedArgs = serde_json::from_value(json!({
+        "text": "first",
+        "texts": ["second", "third"],
+        "task": "search_document",
+        "library": "research"
+    }))
+    .unwrap();
+
+    assert_eq!(args.inputs(), ["first", "second", "third"]);
+    assert_eq!(args.task.as_deref(), Some("search_document"));
+    assert_eq!(args.dimension, None);
+    assert_eq!(args.library.as_deref(), Some("research"));
+}
+
+#[test]
+fn test_inputs_are_empty_without_text() {
+    let args: EmbedArgs = serde_json::from_value(json!({"dimension": 256})).unwrap();
+    assert!(args.inputs().is_empty());
+}
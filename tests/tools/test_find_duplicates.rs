@@ -0,0 +1,51 @@
+// Tests for src/tools/find_duplicates.rs
+
+use kodegen_candle_agent::tools::find_duplicates::{SimHashIndex, duplicate_groups};
+
+fn unit(angle: f32) -> Vec<f32> {
+    vec![angle.cos(), angle.sin(), 0.0, 0.0]
+}
+
+#[test]
+fn test_duplicate_groups_joins_near_identical_embeddings() {
+    let embeddings = vec![
+        unit(0.0),
+        vec![0.0, 0.0, 1.0, 0.0],
+        unit(0.01),
+        unit(0.02),
+        vec![0.0, 0.0, 0.0, 1.0],
+    ];
+
+    let scan = duplicate_groups(&embeddings, 0.99);
+
+    assert_eq!(scan.groups.len(), 1);
+    assert_eq!(scan.groups[0].0, vec![0, 2, 3]);
+    assert!(scan.groups[0].1 >= 0.99);
+    assert!(scan.comparisons < embeddings.len() * (embeddings.len() - 1) / 2);
+}
+
+#[test]
+fn test_duplicate_groups_do_not_chain() {
+    // 0 matches 1 and 1 matches 2, but 0 and 2 are too far apart
+    let embeddings = vec![unit(0.0), unit(0.1), unit(0.2)];
+
+    let scan = duplicate_groups(&embeddings, 0.99);
+
+    assert_eq!(scan.groups.len(), 1);
+    assert_eq!(scan.groups[0].0.len(), 2);
+    assert!(scan.groups[0].1 >= 0.99);
+}
+
+#[test]
+fn test_duplicate_groups_ignores_mismatched_dimensions() {
+    let embeddings = vec![vec![1.0, 0.0], vec![1.0, 0.0, 0.0], vec![]];
+    let scan = duplicate_groups(&embeddings, 0.5);
+    assert!(scan.groups.is_empty());
+}
+
+#[test]
+fn test_simhash_band_keys_are_deterministic() {
+    let index = SimHashIndex::new(4);
+    let embedding = unit(0.3);
+    assert_eq!(index.band_keys(&embedding), SimHashIndex::new(4).band_keys(&embedding));
+}
@@ -0,0 +1,69 @@
+// Tests for src/tools/history_index.rs
+
+use kodegen_candle_agent::domain::chat::message::CandleMessageRole;
+use kodegen_candle_agent::memory::core::primitives::node::MemoryNode;
+use kodegen_candle_agent::memory::core::primitives::types::{MemoryContent, MemoryTypeEnum};
+use kodegen_candle_agent::tools::history_index::{derived_from, history_message, snippet};
+
+fn conversation_memory(text: &str, tags: &[&str]) -> MemoryNode {
+    let mut memory = MemoryNode::with_id(
+        "mem1".to_string(),
+        MemoryTypeEnum::Episodic,
+        MemoryContent::new(text),
+    );
+    memory.metadata.category = "conversation".to_string();
+    memory.metadata.tags = tags.iter().map(|t| t.to_string()).collect();
+    memory.metadata.custom = serde_json::json!({"session_id": "s1", "turn_id": "t1"});
+    memory
+}
+
+#[test]
+fn test_history_message_reads_role_session_and_turn() {
+    let memory = conversation_memory("retry the deploy", &["message_type.assistant", "ops"]);
+
+    let (message, origin) = history_message(&memory).unwrap();
+    assert_eq!(message.message.role, CandleMessageRole::Assistant);
+    assert_eq!(message.message.content, "retry the deploy");
+    assert_eq!(message.message.id.as_deref(), Some("s1/mem1"));
+    assert_eq!(origin.session_id.as_deref(), Some("s1"));
+    assert_eq!(origin.turn_id.as_deref(), Some("t1"));
+    assert_eq!(origin.tags, vec!["ops".to_string()]);
+}
+
+#[test]
+fn test_history_message_skips_other_memories() {
+    let mut memory = conversation_memory("notes", &["message_type.user"]);
+    memory.metadata.category = "context".to_string();
+    assert!(history_message(&memory).is_none());
+
+    let untagged = conversation_memory("notes", &["ops"]);
+    assert!(history_message(&untagged).is_none());
+}
+
+#[test]
+fn test_snippet_centers_on_first_match() {
+    let content = format!("{} Timeout while deploying {}", "a".repeat(100), "b".repeat(100));
+
+    let excerpt = snippet(&content, &["timeout".to_string()], 40);
+    assert!(excerpt.starts_with('…'));
+    assert!(excerpt.ends_with('…'));
+    assert!(excerpt.contains("Timeout"));
+
+    let short = snippet("short message", &["missing".to_string()], 40);
+    assert_eq!(short, "short message");
+}
+
+#[test]
+fn test_derived_from_matches_source_message_back_references() {
+    let mut summary = conversation_memory("summary", &[]);
+    summary.metadata.custom = serde_json::json!({"source_message_id": "mem1"});
+    assert!(derived_from(&summary, "mem1"));
+    assert!(!derived_from(&summary, "mem2"));
+
+    summary.metadata.custom = serde_json::json!({"source_message_id": ["mem2", "mem3"]});
+    assert!(derived_from(&summary, "mem3"));
+    assert!(!derived_from(&summary, "mem1"));
+
+    let plain = conversation_memory("notes", &["message_type.user"]);
+    assert!(!derived_from(&plain, "mem1"));
+}
@@ -0,0 +1,81 @@
+// Tests for src/tools/idempotency.rs
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use kodegen_candle_agent::tools::idempotency::{
+    IdempotencyCache, IdempotencyConflict, fingerprint,
+};
+
+#[tokio::test]
+async fn test_repeated_key_replays_first_outcome() {
+    let cache = IdempotencyCache::<String>::default();
+    let runs = AtomicUsize::new(0);
+    let args = fingerprint(&["docs", "hello"]);
+
+    let first = cache
+        .get_or_try_insert_with("key-1", args, || async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, IdempotencyConflict>("session-a".to_string())
+        })
+        .await
+        .unwrap();
+    let retry = cache
+        .get_or_try_insert_with("key-1", args, || async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, IdempotencyConflict>("session-b".to_string())
+        })
+        .await
+        .unwrap();
+
+    assert!(!first.replayed);
+    assert!(retry.replayed);
+    assert_eq!(retry.value, "session-a");
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_key_reuse_with_different_arguments_is_rejected() {
+    let cache = IdempotencyCache::<String>::default();
+    cache
+        .get_or_try_insert_with("key-1", fingerprint(&["docs", "hello"]), || async {
+            Ok::<_, IdempotencyConflict>("session-a".to_string())
+        })
+        .await
+        .unwrap();
+
+    let conflict = cache
+        .get_or_try_insert_with("key-1", fingerprint(&["docs", "goodbye"]), || async {
+            Ok::<_, IdempotencyConflict>("session-b".to_string())
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(conflict.key, "key-1");
+}
+
+#[tokio::test]
+async fn test_failures_are_not_remembered_and_keys_expire() {
+    let cache = IdempotencyCache::<String>::new(Duration::from_millis(20));
+    let args = fingerprint(&["docs", "hello"]);
+
+    let failed = cache
+        .get_or_try_insert_with("key-1", args, || async {
+            Err::<String, _>(IdempotencyConflict {
+                key: "transient".to_string(),
+            })
+        })
+        .await;
+    assert!(failed.is_err());
+    assert!(cache.is_empty().await);
+
+    cache
+        .get_or_try_insert_with("key-1", args, || async {
+            Ok::<_, IdempotencyConflict>("session-a".to_string())
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    cache.purge_expired().await;
+    assert!(cache.is_empty().await);
+}
@@ -0,0 +1,88 @@
+// Tests for src/tools/ingestion_queue.rs
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use kodegen_candle_agent::tools::ingestion_queue::{IngestionQueue, QueueClosed, QueueFull};
+
+#[test]
+fn test_full_queue_rejects_with_retry_after() {
+    let queue = Arc::new(IngestionQueue::new(1, 2));
+    let a = queue.enqueue("a").unwrap();
+    let b = queue.enqueue("b").unwrap();
+    assert!(a.number() < b.number());
+
+    let Err(QueueFull { queued, retry_after }) = queue.enqueue("c") else {
+        panic!("third session was queued");
+    };
+    assert_eq!(queued, 2);
+    assert!(retry_after >= Duration::from_secs(1));
+    assert_eq!(queue.position("a"), Some(1));
+    assert_eq!(queue.position("b"), Some(2));
+    assert_eq!(queue.position("c"), None);
+
+    // A dropped ticket gives up its place
+    drop(a);
+    assert_eq!(queue.position("b"), Some(1));
+}
+
+#[tokio::test]
+async fn test_concurrency_limit_and_positions() {
+    let queue = Arc::new(IngestionQueue::new(1, 8));
+    let first = queue.enqueue("first").unwrap();
+    let second = queue.enqueue("second").unwrap();
+
+    let first = first.acquire().await.unwrap();
+    assert_eq!(queue.running(), 1);
+    assert_eq!(queue.position("first"), None);
+    assert_eq!(queue.position("second"), Some(1));
+
+    // The second session waits until the first releases its slot
+    let waiting = tokio::spawn(async move {
+        let _slot = second.acquire().await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!waiting.is_finished());
+
+    drop(first);
+    waiting.await.unwrap();
+    assert_eq!(queue.queued(), 0);
+    assert_eq!(queue.running(), 0);
+}
+
+#[tokio::test]
+async fn test_sessions_start_in_submit_order() {
+    let queue = Arc::new(IngestionQueue::new(1, 8));
+    let first = queue.enqueue("first").unwrap();
+    let second = queue.enqueue("second").unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    // The later session's task asks for a slot first
+    let later = tokio::spawn({
+        let tx = tx.clone();
+        async move {
+            let _slot = second.acquire().await.unwrap();
+            tx.send("second").unwrap();
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(queue.running(), 0);
+
+    let earlier = tokio::spawn(async move {
+        let _slot = first.acquire().await.unwrap();
+        tx.send("first").unwrap();
+    });
+    earlier.await.unwrap();
+    later.await.unwrap();
+    assert_eq!(rx.recv().await, Some("first"));
+    assert_eq!(rx.recv().await, Some("second"));
+}
+
+#[tokio::test]
+async fn test_closed_queue_fails_waiting_sessions() {
+    let queue = Arc::new(IngestionQueue::new(1, 8));
+    let ticket = queue.enqueue("a").unwrap();
+    queue.close();
+    assert!(matches!(ticket.acquire().await, Err(QueueClosed)));
+    assert_eq!(queue.queued(), 0);
+}
@@ -0,0 +1,59 @@
+// Tests for src/tools/inline_content.rs
+
+use base64::Engine;
+use kodegen_candle_agent::tools::{InlineContent, InlineContentError};
+use kodegen_candle_agent::tools::inline_content::detect_mime_type;
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+#[test]
+fn test_decode_text_with_declared_mime_type() {
+    let encoded = encode("# Notes\n\nInline markdown".as_bytes());
+    let content = InlineContent::decode(&encoded, "base64", Some("text/markdown")).unwrap();
+
+    assert_eq!(content.mime_type(), "text/markdown");
+    assert_eq!(content.extract_text().unwrap(), "# Notes\n\nInline markdown");
+}
+
+#[test]
+fn test_decode_ignores_line_breaks() {
+    let encoded = encode(b"{\"key\": \"value\"}");
+    let wrapped = format!("{}\n{}", &encoded[..8], &encoded[8..]);
+    let content = InlineContent::decode(&wrapped, "BASE64", Some("application/json")).unwrap();
+
+    assert_eq!(content.extract_text().unwrap(), "{\"key\": \"value\"}");
+}
+
+#[test]
+fn test_mime_type_detected_when_missing_or_generic() {
+    assert_eq!(detect_mime_type(b"%PDF-1.7\n..."), "application/pdf");
+    assert_eq!(detect_mime_type(b"\x89PNG\r\n\x1a\n...."), "image/png");
+    assert_eq!(detect_mime_type(b"plain words"), "text/plain");
+    assert_eq!(detect_mime_type(&[0xff, 0x00, 0xfe]), "application/octet-stream");
+
+    let content =
+        InlineContent::decode(&encode(b"hello"), "base64", Some("application/octet-stream"))
+            .unwrap();
+    assert_eq!(content.mime_type(), "text/plain");
+}
+
+#[test]
+fn test_rejects_bad_input() {
+    assert!(matches!(
+        InlineContent::decode("aGVsbG8=", "gzip", None),
+        Err(InlineContentError::UnsupportedEncoding(_))
+    ));
+    assert!(matches!(
+        InlineContent::decode("not base64!", "base64", None),
+        Err(InlineContentError::InvalidBase64(_))
+    ));
+
+    let image = InlineContent::decode(&encode(b"\xff\xd8\xff\xe0"), "base64", None).unwrap();
+    assert_eq!(image.mime_type(), "image/jpeg");
+    assert!(matches!(
+        image.extract_text(),
+        Err(InlineContentError::UnsupportedMimeType(m)) if m == "image/jpeg"
+    ));
+}
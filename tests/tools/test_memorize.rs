@@ -0,0 +1,62 @@
+// Tests for src/tools/memorize.rs
+
+use kodegen_candle_agent::tools::memorize::MemorizeTool;
+use kodegen_mcp_schema::memory::MemorizeArgs;
+use kodegen_candle_agent::tools::memorize_manager::MemorizeContent;
+use serde_json::json;
+
+#[test]
+fn test_every_option_survives_the_json_entry_point() {
+    let args: MemorizeArgs = serde_json::from_value(json!({
+        "library": "docs",
+        "content": "aGVsbG8=",
+        "content_encoding": "base64",
+        "mime_type": "text/plain",
+        "idempotency_key": "retry-1",
+        "library_embedding_task": "s2s",
+        "library_min_score": 0.4,
+        "embedding_task": "code:rust"
+    }))
+    .unwrap();
+
+    assert_eq!(args.idempotency_key.as_deref(), Some("retry-1"));
+    assert_eq!(args.library_embedding_task.as_deref(), Some("s2s"));
+    assert_eq!(args.embedding_task.as_deref(), Some("code:rust"));
+    assert_eq!(MemorizeTool::min_score_arg(&args).unwrap(), Some(Some(0.4)));
+    assert!(matches!(
+        MemorizeTool::content(&args).unwrap(),
+        MemorizeContent::Inline(_)
+    ));
+}
+
+#[test]
+fn test_min_score_accepts_none_and_rejects_text() {
+    let mut args: MemorizeArgs =
+        serde_json::from_value(json!({"library": "docs", "content": "text"})).unwrap();
+    assert_eq!(MemorizeTool::min_score_arg(&args).unwrap(), None);
+    assert_eq!(
+        MemorizeTool::content(&args).unwrap(),
+        MemorizeContent::Reference("text".to_string())
+    );
+
+    args.library_min_score = Some(json!("none"));
+    assert_eq!(MemorizeTool::min_score_arg(&args).unwrap(), Some(None));
+    args.library_min_score = Some(json!("high"));
+    assert!(MemorizeTool::min_score_arg(&args).is_err());
+}
+
+#[test]
+fn test_schema_advertises_every_option() {
+    let schema = serde_json::to_value(schemars::schema_for!(MemorizeArgs)).unwrap();
+    let properties = schema["properties"].as_object().unwrap();
+    for field in [
+        "content_encoding",
+        "mime_type",
+        "idempotency_key",
+        "library_embedding_task",
+        "library_min_score",
+        "embedding_task",
+    ] {
+        assert!(properties.contains_key(field), "missing {field}");
+    }
+}
@@ -0,0 +1,88 @@
+// Tests for src/tools/memorize_limits.rs
+
+use kodegen_candle_agent::tools::memorize_limits::{MemorizeLimitError, MemorizeLimits};
+
+#[test]
+fn test_paths_outside_allowed_roots_are_rejected() {
+    let root = tempfile::tempdir().unwrap();
+    let other = tempfile::tempdir().unwrap();
+    let inside = root.path().join("notes.md");
+    let outside = other.path().join("secret.txt");
+    std::fs::write(&inside, "notes").unwrap();
+    std::fs::write(&outside, "secret").unwrap();
+
+    let limits = MemorizeLimits::new().with_allowed_roots([root.path().to_path_buf()]);
+    assert!(limits.check_path(&inside).is_ok());
+    assert!(matches!(
+        limits.check_path(&outside),
+        Err(MemorizeLimitError::PathNotAllowed { .. })
+    ));
+    assert!(
+        limits
+            .check_glob(&format!("{}/**/*.md", root.path().display()))
+            .is_ok()
+    );
+    assert!(
+        limits
+            .check_glob(&format!("{}/*", other.path().display()))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_unresolvable_roots_refuse_every_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("notes.md");
+    std::fs::write(&file, "notes").unwrap();
+
+    let limits = MemorizeLimits::new().with_allowed_roots([dir.path().join("missing")]);
+    assert!(matches!(
+        limits.check_path(&file),
+        Err(MemorizeLimitError::PathNotAllowed { .. })
+    ));
+    assert!(
+        limits
+            .check_glob(&format!("{}/*.md", dir.path().display()))
+            .is_err()
+    );
+
+    // No roots configured at all still allows any path
+    assert!(MemorizeLimits::new().with_allowed_roots([]).check_path(&file).is_ok());
+}
+
+#[test]
+fn test_url_schemes_and_domains() {
+    let limits = MemorizeLimits::new().with_allowed_domains(["example.com".to_string()]);
+    assert!(limits.check_url("https://example.com/a").is_ok());
+    assert!(limits.check_url("https://docs.example.com/a").is_ok());
+    assert!(matches!(
+        limits.check_url("https://badexample.com/a"),
+        Err(MemorizeLimitError::DomainNotAllowed { .. })
+    ));
+    assert!(matches!(
+        limits.check_url("file:///etc/passwd"),
+        Err(MemorizeLimitError::SchemeNotAllowed { .. })
+    ));
+}
+
+#[test]
+fn test_size_count_and_text_checks() {
+    let limits = MemorizeLimits::new()
+        .with_max_source_bytes(4)
+        .with_max_files(2);
+    assert!(limits.check_content("a.txt", "abcd").is_ok());
+    assert!(matches!(
+        limits.check_content("a.txt", "abcde"),
+        Err(MemorizeLimitError::TooLarge {
+            size: 5,
+            limit: 4,
+            ..
+        })
+    ));
+    assert!(matches!(
+        limits.check_content("a.bin", "a\0b"),
+        Err(MemorizeLimitError::NotText { .. })
+    ));
+    assert!(limits.check_file_count("*.md", 2).is_ok());
+    assert!(limits.check_file_count("*.md", 3).is_err());
+}
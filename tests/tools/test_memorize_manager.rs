@@ -0,0 +1,39 @@
+// Tests for src/tools/memorize_manager.rs
+
+use kodegen_candle_agent::tools::memorize_manager::{MemorizeSession, MemorizeStatus};
+
+#[tokio::test]
+async fn test_session_publishes_stage_transitions() {
+    let session = MemorizeSession::new("s1".to_string(), "lib".to_string(), "text".to_string());
+    let mut updates = session.updates.subscribe();
+
+    session.update_progress("Loading content", 0, 0).await;
+    session
+        .update_progress("Generating embeddings", 2, 128)
+        .await;
+    session.complete("memory:1".to_string()).await;
+
+    let (status, progress) = updates.recv().await.unwrap();
+    assert_eq!(status, MemorizeStatus::InProgress);
+    assert_eq!(progress.stage, "Loading content");
+
+    let (_, progress) = updates.recv().await.unwrap();
+    assert_eq!(progress.stage, "Generating embeddings");
+    assert_eq!(progress.files_loaded, 2);
+    assert_eq!(progress.total_size_bytes, 128);
+
+    let (status, progress) = updates.recv().await.unwrap();
+    assert_eq!(status, MemorizeStatus::Completed);
+    assert_eq!(progress.stage, "Completed");
+}
+
+#[tokio::test]
+async fn test_failed_session_publishes_failure() {
+    let session = MemorizeSession::new("s2".to_string(), "lib".to_string(), "text".to_string());
+    let mut updates = session.updates.subscribe();
+
+    session.fail("boom".to_string()).await;
+
+    let (status, _) = updates.recv().await.unwrap();
+    assert_eq!(status, MemorizeStatus::Failed);
+}
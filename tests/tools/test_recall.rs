@@ -0,0 +1,61 @@
+// Tests for src/tools/recall.rs
+
+use kodegen_mcp_schema::memory::{DEFAULT_RECALL_LIMIT, RecallArgs};
+use serde_json::json;
+
+#[test]
+fn test_every_option_survives_the_json_entry_point() {
+    let args: RecallArgs = serde_json::from_value(json!({
+        "library": "docs",
+        "context": "release process",
+        "limit": 3,
+        "group_by_source": true,
+        "wait_for_session": "session-1",
+        "wait_timeout_ms": 5000,
+        "fast": true,
+        "budget_ms": 20,
+        "embedding_task": "s2s",
+        "rerank": true,
+        "min_score": 0.5
+    }))
+    .unwrap();
+
+    assert_eq!(args.limit, 3);
+    assert!(args.group_by_source);
+    assert_eq!(args.wait_for_session.as_deref(), Some("session-1"));
+    assert_eq!(args.wait_timeout_ms, Some(5000));
+    assert!(args.fast);
+    assert_eq!(args.budget_ms, Some(20));
+    assert_eq!(args.embedding_task.as_deref(), Some("s2s"));
+    assert!(args.rerank);
+    assert_eq!(args.min_score, Some(0.5));
+}
+
+#[test]
+fn test_options_default_to_off() {
+    let args: RecallArgs =
+        serde_json::from_value(json!({"library": "docs", "context": "release"})).unwrap();
+
+    assert_eq!(args.limit, DEFAULT_RECALL_LIMIT);
+    assert!(!args.group_by_source && !args.fast && !args.rerank);
+    assert_eq!(args.wait_for_session, None);
+    assert_eq!(args.min_score, None);
+}
+
+#[test]
+fn test_schema_advertises_every_option() {
+    let schema = serde_json::to_value(schemars::schema_for!(RecallArgs)).unwrap();
+    let properties = schema["properties"].as_object().unwrap();
+    for field in [
+        "group_by_source",
+        "wait_for_session",
+        "wait_timeout_ms",
+        "fast",
+        "budget_ms",
+        "embedding_task",
+        "rerank",
+        "min_score",
+    ] {
+        assert!(properties.contains_key(field), "missing {field}");
+    }
+}
@@ -0,0 +1,36 @@
+// Tests for src/tools/summarize_manager.rs
+
+use kodegen_candle_agent::tools::summarize_manager::{
+    MAX_TRANSCRIPT_CHARS, SummaryItemKind, clip_transcript, parse_summary,
+};
+
+#[test]
+fn test_parse_summary_reads_fenced_json_and_drops_blank_items() {
+    let reply = "Here you go:\n```json\n{\"decisions\": [\"Ship on Friday\", \"  \"], \
+                 \"action_items\": [\" Ana writes the changelog \", \"Ana writes the changelog\"]}\n```";
+
+    let summary = parse_summary(reply).unwrap();
+    assert_eq!(summary.decisions, vec!["Ship on Friday".to_string()]);
+    assert_eq!(summary.action_items, vec!["Ana writes the changelog".to_string()]);
+    assert!(summary.facts.is_empty());
+
+    let kinds: Vec<SummaryItemKind> = summary.items().map(|(kind, _)| kind).collect();
+    assert_eq!(kinds, vec![SummaryItemKind::Decision, SummaryItemKind::ActionItem]);
+    assert_eq!(SummaryItemKind::ActionItem.tag(), "summary.action_item");
+}
+
+#[test]
+fn test_parse_summary_rejects_replies_without_json() {
+    assert!(parse_summary("Nothing was decided.").is_err());
+    assert!(parse_summary("} backwards {").is_err());
+}
+
+#[test]
+fn test_clip_transcript_keeps_the_end() {
+    let transcript = format!("{}{}", "a".repeat(10), "b".repeat(MAX_TRANSCRIPT_CHARS));
+    let clipped = clip_transcript(&transcript);
+    assert_eq!(clipped.chars().count(), MAX_TRANSCRIPT_CHARS);
+    assert!(clipped.chars().all(|c| c == 'b'));
+
+    assert_eq!(clip_transcript("short"), "short");
+}
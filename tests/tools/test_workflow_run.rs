@@ -0,0 +1,42 @@
+// Tests for src/tools/workflow_run.rs
+
+use kodegen_candle_agent::tools::workflow_run::workspace_definition;
+
+#[test]
+fn test_inline_definitions_are_returned_as_is() {
+    let root = tempfile::tempdir().unwrap();
+    let inline = "name: inline\nsteps: []\n";
+    assert_eq!(workspace_definition(root.path(), inline).unwrap(), inline);
+}
+
+#[test]
+fn test_definition_files_are_read_from_the_workspace() {
+    let root = tempfile::tempdir().unwrap();
+    std::fs::create_dir(root.path().join("flows")).unwrap();
+    std::fs::write(root.path().join("flows/answer.yaml"), "name: answer\n").unwrap();
+
+    let source = workspace_definition(root.path(), "flows/answer.yaml").unwrap();
+    assert_eq!(source, "name: answer\n");
+}
+
+#[test]
+fn test_definition_files_outside_the_workspace_are_refused() {
+    let outside = tempfile::tempdir().unwrap();
+    std::fs::write(outside.path().join("secret.json"), "{}").unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let escape = format!(
+        "../{}/secret.json",
+        outside.path().file_name().unwrap().to_string_lossy()
+    );
+
+    for definition in [
+        escape,
+        outside.path().join("secret.json").display().to_string(),
+    ] {
+        let error = workspace_definition(root.path(), &definition).unwrap_err();
+        assert!(
+            error.to_string().contains("outside the workspace"),
+            "{error}"
+        );
+    }
+}
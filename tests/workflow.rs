@@ -1,5 +1,7 @@
 // Integration tests for workflow operations
 
 mod workflow {
+    mod test_definition;
     mod test_parallel;
+    mod test_steps;
 }
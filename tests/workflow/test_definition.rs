@@ -0,0 +1,81 @@
+// Tests for src/workflow/definition.rs
+
+use kodegen_candle_agent::workflow::definition::parse_inputs;
+use kodegen_candle_agent::workflow::{
+    ErrorPolicy, WorkflowDefinition, WorkflowDefinitionError, WorkflowSteps,
+};
+use serde_json::json;
+
+const ANSWER: &str = r#"
+name: answer
+inputs: [question]
+steps:
+  - type: memorize
+    library: answers
+    content_from: answer
+  - name: answer
+    type: generate
+    model: Qwen/Qwen2.5-Coder-3B-Instruct-GGUF
+    prompt: "Notes: {{notes}} Q: {{question}}"
+    on_error: { retry: 2 }
+  - name: notes
+    type: recall
+    library: notes
+    query: "{{question}}"
+"#;
+
+#[test]
+fn test_yaml_definition_parses_and_orders_by_references() {
+    let definition = WorkflowDefinition::parse(ANSWER).expect("valid definition");
+    assert_eq!(definition.steps[1].on_error, Some(ErrorPolicy::Retry(2)));
+
+    // `memorize` is declared first but needs `answer`, which needs `notes`
+    assert!(definition.compile(&WorkflowSteps::new()).is_ok());
+}
+
+#[test]
+fn test_json_definition_parses() {
+    let json = r#"{"steps": [{"type": "tool", "tool": "echo", "args": {"text": "hi"}}]}"#;
+    let definition = WorkflowDefinition::parse(json).expect("valid definition");
+    assert_eq!(definition.steps[0].name(), "echo");
+}
+
+#[test]
+fn test_validation_errors() {
+    let unknown = "steps:\n  - type: summarize\n";
+    assert!(matches!(
+        WorkflowDefinition::parse(unknown),
+        Err(WorkflowDefinitionError::UnknownStepType { index: 0, .. })
+    ));
+
+    let unbound = "steps:\n  - type: recall\n    library: notes\n    query: \"{{topic}}\"\n";
+    assert_eq!(
+        WorkflowDefinition::parse(unbound),
+        Err(WorkflowDefinitionError::UnboundVariable {
+            step: "recall".to_string(),
+            variable: "topic".to_string(),
+        })
+    );
+
+    let cycle = r#"
+steps:
+  - name: a
+    type: generate
+    model: m
+    prompt: "{{b}}"
+  - name: b
+    type: generate
+    model: m
+    prompt: "{{a}}"
+"#;
+    assert!(matches!(
+        WorkflowDefinition::parse(cycle),
+        Err(WorkflowDefinitionError::Cycle(_))
+    ));
+}
+
+#[test]
+fn test_inputs_accept_pairs_and_json() {
+    let input = parse_inputs(["question=why", r#"{"limit": 3}"#]).unwrap();
+    assert_eq!(input, json!({"question": "why", "limit": 3}));
+}
@@ -0,0 +1,34 @@
+// Tests for src/workflow/steps.rs
+
+use kodegen_candle_agent::domain::context::WorkflowDataChunk;
+use kodegen_candle_agent::workflow::steps::{bindings, lookup, render_template, render_value};
+use serde_json::json;
+
+#[test]
+fn test_templates_bind_prior_step_outputs() {
+    let chunk = WorkflowDataChunk::from(json!({
+        "question": "why?",
+        "recall": [{"content": "because"}],
+    }));
+    let vars = bindings(&chunk);
+
+    assert_eq!(
+        render_template("Q: {{question}} A: {{ recall.0.content }}", &vars).unwrap(),
+        "Q: why? A: because"
+    );
+    assert_eq!(lookup(&vars, "recall.0.content"), Some(&json!("because")));
+    assert!(render_template("{{missing}}", &vars).unwrap_err().contains("missing"));
+}
+
+#[test]
+fn test_non_object_input_is_bound_as_input() {
+    let vars = bindings(&WorkflowDataChunk::from(json!("hello")));
+    assert_eq!(render_template("{{input}}!", &vars).unwrap(), "hello!");
+}
+
+#[test]
+fn test_whole_value_references_keep_their_type() {
+    let vars = bindings(&WorkflowDataChunk::from(json!({"ids": [1, 2], "name": "x"})));
+    let args = render_value(&json!({"ids": "{{ids}}", "label": "item {{name}}"}), &vars).unwrap();
+    assert_eq!(args, json!({"ids": [1, 2], "label": "item x"}));
+}